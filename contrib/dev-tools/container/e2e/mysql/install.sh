@@ -16,6 +16,10 @@ MYSQL_DATABASE=$TORRUST_INDEX_DATABASE
 echo "Creating MySQL database '$MYSQL_DATABASE' for for E2E testing ..."
 MYSQL_PWD=$MYSQL_PASSWORD mysql -h $MYSQL_HOST -u $MYSQL_USER -e "CREATE DATABASE IF NOT EXISTS $MYSQL_DATABASE;"
 
+# Create the MySQL database for the driver parity tests.
+echo "Creating MySQL database '$TORRUST_INDEX_DRIVERS_DATABASE' for the driver parity tests ..."
+MYSQL_PWD=$MYSQL_PASSWORD mysql -h $MYSQL_HOST -u $MYSQL_USER -e "CREATE DATABASE IF NOT EXISTS $TORRUST_INDEX_DRIVERS_DATABASE;"
+
 ## Tracker
 
 # Generate the Tracker sqlite database directory and file if it does not exist
@@ -0,0 +1,2 @@
+DROP TABLE IF EXISTS torrust_domain_event_consumers;
+DROP TABLE IF EXISTS torrust_domain_events;
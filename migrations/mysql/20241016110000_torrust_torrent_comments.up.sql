@@ -0,0 +1,12 @@
+CREATE TABLE IF NOT EXISTS torrust_torrent_comments (
+    comment_id INTEGER NOT NULL PRIMARY KEY AUTO_INCREMENT,
+    torrent_id INTEGER NOT NULL,
+    author_id INTEGER NOT NULL,
+    text TEXT NOT NULL,
+    hidden BOOL NOT NULL DEFAULT FALSE,
+    date_created DATETIME NOT NULL,
+    date_updated DATETIME DEFAULT NULL,
+    FOREIGN KEY (torrent_id) REFERENCES torrust_torrents(torrent_id) ON DELETE CASCADE,
+    FOREIGN KEY (author_id) REFERENCES torrust_users(user_id) ON DELETE CASCADE,
+    INDEX (torrent_id)
+);
@@ -0,0 +1,2 @@
+DROP INDEX torrust_torrents_status_idx ON torrust_torrents;
+ALTER TABLE torrust_torrents DROP COLUMN status;
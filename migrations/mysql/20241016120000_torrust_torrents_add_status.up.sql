@@ -0,0 +1,3 @@
+ALTER TABLE torrust_torrents ADD COLUMN status VARCHAR(16) NOT NULL DEFAULT 'approved';
+
+CREATE INDEX torrust_torrents_status_idx ON torrust_torrents (status);
@@ -0,0 +1,16 @@
+CREATE TABLE IF NOT EXISTS torrust_torrent_reports (
+    report_id INTEGER NOT NULL PRIMARY KEY AUTO_INCREMENT,
+    torrent_id INTEGER NOT NULL,
+    reporter_id INTEGER NOT NULL,
+    reason VARCHAR(32) NOT NULL,
+    details TEXT DEFAULT NULL,
+    resolved BOOL NOT NULL DEFAULT FALSE,
+    resolution VARCHAR(32) DEFAULT NULL,
+    resolved_by INTEGER DEFAULT NULL,
+    date_created DATETIME NOT NULL,
+    date_resolved DATETIME DEFAULT NULL,
+    FOREIGN KEY (torrent_id) REFERENCES torrust_torrents(torrent_id) ON DELETE CASCADE,
+    FOREIGN KEY (reporter_id) REFERENCES torrust_users(user_id) ON DELETE CASCADE,
+    FOREIGN KEY (resolved_by) REFERENCES torrust_users(user_id) ON DELETE SET NULL,
+    INDEX (resolved)
+);
@@ -0,0 +1,12 @@
+ALTER TABLE torrust_torrent_announce_urls ADD COLUMN tier INTEGER NOT NULL DEFAULT 0;
+
+-- Existing trackers were listed in their own tier
+UPDATE torrust_torrent_announce_urls
+JOIN (
+    SELECT current.announce_url_id, COUNT(previous.announce_url_id) AS tier
+    FROM torrust_torrent_announce_urls AS current
+    LEFT JOIN torrust_torrent_announce_urls AS previous
+    ON previous.torrent_id = current.torrent_id AND previous.announce_url_id < current.announce_url_id
+    GROUP BY current.announce_url_id
+) AS tiers ON tiers.announce_url_id = torrust_torrent_announce_urls.announce_url_id
+SET torrust_torrent_announce_urls.tier = tiers.tier;
@@ -0,0 +1 @@
+DROP TABLE IF EXISTS torrust_download_client_stats;
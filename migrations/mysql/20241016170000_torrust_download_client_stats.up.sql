@@ -0,0 +1,7 @@
+CREATE TABLE IF NOT EXISTS torrust_download_client_stats (
+    date DATE NOT NULL,
+    client_family VARCHAR(64) NOT NULL,
+    user_agent VARCHAR(255) NOT NULL DEFAULT '',
+    downloads INTEGER NOT NULL DEFAULT 0,
+    PRIMARY KEY (date, client_family, user_agent)
+);
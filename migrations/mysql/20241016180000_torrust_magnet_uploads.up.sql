@@ -0,0 +1,20 @@
+CREATE TABLE IF NOT EXISTS torrust_magnet_uploads (
+    magnet_upload_id INTEGER NOT NULL PRIMARY KEY AUTO_INCREMENT,
+    user_id INTEGER NOT NULL,
+    info_hash CHAR(40) NOT NULL,
+    magnet_uri TEXT NOT NULL,
+    title VARCHAR(256) NOT NULL,
+    description TEXT NOT NULL,
+    category_id INTEGER NOT NULL,
+    tag_ids TEXT NOT NULL,
+    status VARCHAR(16) NOT NULL DEFAULT 'resolving',
+    torrent_id INTEGER NULL,
+    attempts INTEGER NOT NULL DEFAULT 0,
+    error TEXT NULL,
+    date_created DATETIME NOT NULL,
+    date_updated DATETIME NOT NULL,
+    INDEX (status),
+    FOREIGN KEY (user_id) REFERENCES torrust_users(user_id) ON DELETE CASCADE,
+    FOREIGN KEY (category_id) REFERENCES torrust_categories(category_id) ON DELETE CASCADE,
+    FOREIGN KEY (torrent_id) REFERENCES torrust_torrents(torrent_id) ON DELETE SET NULL
+);
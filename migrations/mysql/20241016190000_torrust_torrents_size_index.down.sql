@@ -0,0 +1 @@
+DROP INDEX torrust_torrents_size_idx ON torrust_torrents;
@@ -0,0 +1 @@
+CREATE INDEX torrust_torrents_size_idx ON torrust_torrents (size);
@@ -0,0 +1,3 @@
+ALTER TABLE torrust_users DROP COLUMN date_last_login;
+DROP TABLE IF EXISTS torrust_user_email_unsubscribes;
+DROP TABLE IF EXISTS torrust_email_queue;
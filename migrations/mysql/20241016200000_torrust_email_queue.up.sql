@@ -0,0 +1,16 @@
+CREATE TABLE IF NOT EXISTS torrust_email_queue (
+    email_id INTEGER NOT NULL PRIMARY KEY AUTO_INCREMENT,
+    recipient VARCHAR(320) NOT NULL,
+    subject TEXT NOT NULL,
+    body MEDIUMTEXT NOT NULL,
+    attempts INTEGER NOT NULL DEFAULT 0,
+    date_created DATETIME NOT NULL
+);
+
+CREATE TABLE IF NOT EXISTS torrust_user_email_unsubscribes (
+    user_id INTEGER NOT NULL PRIMARY KEY,
+    date_created DATETIME NOT NULL,
+    FOREIGN KEY (user_id) REFERENCES torrust_users(user_id) ON DELETE CASCADE
+);
+
+ALTER TABLE torrust_users ADD COLUMN date_last_login DATETIME DEFAULT NULL;
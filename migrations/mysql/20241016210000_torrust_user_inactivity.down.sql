@@ -0,0 +1,3 @@
+DROP TABLE IF EXISTS torrust_user_inactivity_exemptions;
+ALTER TABLE torrust_users DROP COLUMN date_disabled;
+ALTER TABLE torrust_users DROP COLUMN date_inactivity_warning;
@@ -0,0 +1,9 @@
+ALTER TABLE torrust_users ADD COLUMN date_inactivity_warning DATETIME DEFAULT NULL;
+
+ALTER TABLE torrust_users ADD COLUMN date_disabled DATETIME DEFAULT NULL;
+
+CREATE TABLE IF NOT EXISTS torrust_user_inactivity_exemptions (
+    user_id INTEGER NOT NULL PRIMARY KEY,
+    date_created DATETIME NOT NULL,
+    FOREIGN KEY (user_id) REFERENCES torrust_users(user_id) ON DELETE CASCADE
+);
@@ -0,0 +1,11 @@
+CREATE TABLE IF NOT EXISTS torrust_images (
+    image_id INTEGER NOT NULL PRIMARY KEY AUTO_INCREMENT,
+    user_id INTEGER NOT NULL,
+    kind VARCHAR(32) NOT NULL,
+    object_key VARCHAR(255) NOT NULL UNIQUE,
+    url TEXT NOT NULL,
+    content_type VARCHAR(64) NOT NULL,
+    size BIGINT NOT NULL,
+    date_created DATETIME NOT NULL,
+    FOREIGN KEY (user_id) REFERENCES torrust_users(user_id) ON DELETE CASCADE
+);
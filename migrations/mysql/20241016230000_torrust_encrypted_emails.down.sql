@@ -0,0 +1,4 @@
+-- Emails must be decrypted before reverting, encrypted emails don't fit in
+-- the plaintext column sizes.
+ALTER TABLE torrust_email_queue MODIFY recipient VARCHAR(320) NOT NULL;
+ALTER TABLE torrust_user_profiles MODIFY email VARCHAR(320);
@@ -0,0 +1,3 @@
+-- Encrypted emails are longer than the plaintext ones.
+ALTER TABLE torrust_user_profiles MODIFY email VARCHAR(512);
+ALTER TABLE torrust_email_queue MODIFY recipient VARCHAR(512) NOT NULL;
@@ -0,0 +1,17 @@
+CREATE TABLE IF NOT EXISTS torrust_collections (
+    collection_id INTEGER NOT NULL PRIMARY KEY AUTO_INCREMENT,
+    user_id INTEGER NOT NULL,
+    name VARCHAR(100) NOT NULL,
+    description TEXT DEFAULT NULL,
+    date_created DATETIME NOT NULL,
+    FOREIGN KEY (user_id) REFERENCES torrust_users(user_id) ON DELETE CASCADE
+);
+
+CREATE TABLE IF NOT EXISTS torrust_collection_items (
+    collection_id INTEGER NOT NULL,
+    torrent_id INTEGER NOT NULL,
+    date_added DATETIME NOT NULL,
+    PRIMARY KEY (collection_id, torrent_id),
+    FOREIGN KEY (collection_id) REFERENCES torrust_collections(collection_id) ON DELETE CASCADE,
+    FOREIGN KEY (torrent_id) REFERENCES torrust_torrents(torrent_id) ON DELETE CASCADE
+);
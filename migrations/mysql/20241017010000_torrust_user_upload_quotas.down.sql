@@ -0,0 +1,5 @@
+-- MySQL may have dropped the index it created for the `uploader_id` foreign
+-- key when the up migration added `torrust_torrents_uploader_id_idx`, so the
+-- foreign key gets another index before dropping this one.
+ALTER TABLE torrust_torrents ADD INDEX torrust_torrents_uploader_id_fk_idx (uploader_id), DROP INDEX torrust_torrents_uploader_id_idx;
+DROP TABLE IF EXISTS torrust_user_upload_quotas;
@@ -0,0 +1,9 @@
+CREATE TABLE IF NOT EXISTS torrust_user_upload_quotas (
+    user_id INTEGER NOT NULL PRIMARY KEY,
+    max_uploads_per_day INTEGER NULL,
+    max_total_upload_size BIGINT NULL,
+    date_updated DATETIME NOT NULL,
+    FOREIGN KEY (user_id) REFERENCES torrust_users(user_id) ON DELETE CASCADE
+);
+
+CREATE INDEX torrust_torrents_uploader_id_idx ON torrust_torrents (uploader_id);
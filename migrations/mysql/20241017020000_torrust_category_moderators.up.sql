@@ -0,0 +1,8 @@
+CREATE TABLE IF NOT EXISTS torrust_category_moderators (
+    category_id INTEGER NOT NULL,
+    user_id INTEGER NOT NULL,
+    date_created DATETIME NOT NULL,
+    PRIMARY KEY (category_id, user_id),
+    FOREIGN KEY (category_id) REFERENCES torrust_categories(category_id) ON DELETE CASCADE,
+    FOREIGN KEY (user_id) REFERENCES torrust_users(user_id) ON DELETE CASCADE
+);
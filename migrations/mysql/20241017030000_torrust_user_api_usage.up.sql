@@ -0,0 +1,11 @@
+CREATE TABLE IF NOT EXISTS torrust_user_api_usage (
+    user_id INTEGER NOT NULL,
+    credential VARCHAR(64) NOT NULL,
+    date DATE NOT NULL,
+    requests BIGINT NOT NULL DEFAULT 0,
+    rate_limit_hits BIGINT NOT NULL DEFAULT 0,
+    last_ip VARCHAR(45),
+    last_used DATETIME NOT NULL,
+    PRIMARY KEY (user_id, credential, date),
+    FOREIGN KEY (user_id) REFERENCES torrust_users(user_id) ON DELETE CASCADE
+);
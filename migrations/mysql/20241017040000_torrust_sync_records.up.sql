@@ -0,0 +1,9 @@
+CREATE TABLE IF NOT EXISTS torrust_sync_records (
+    torrent_id INTEGER NOT NULL PRIMARY KEY,
+    partition_id INTEGER NOT NULL,
+    record_hash VARCHAR(64) NOT NULL,
+    sequence BIGINT NOT NULL,
+    deleted BOOLEAN NOT NULL DEFAULT FALSE
+);
+
+CREATE INDEX torrust_sync_records_partition_idx ON torrust_sync_records (partition_id, sequence);
@@ -0,0 +1,2 @@
+DROP TABLE IF EXISTS torrust_smart_collection_filters;
+ALTER TABLE torrust_collections DROP COLUMN smart;
@@ -0,0 +1,11 @@
+CREATE TABLE IF NOT EXISTS torrust_audit_log (
+    audit_id INTEGER NOT NULL PRIMARY KEY AUTO_INCREMENT,
+    actor_id INTEGER DEFAULT NULL,
+    action VARCHAR(64) NOT NULL,
+    target VARCHAR(255) NOT NULL,
+    details TEXT DEFAULT NULL,
+    date_created DATETIME NOT NULL,
+    FOREIGN KEY (actor_id) REFERENCES torrust_users(user_id) ON DELETE SET NULL,
+    INDEX (action),
+    INDEX (date_created)
+);
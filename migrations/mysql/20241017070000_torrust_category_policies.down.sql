@@ -0,0 +1,2 @@
+DROP TABLE IF EXISTS torrust_torrent_approvals;
+DROP TABLE IF EXISTS torrust_category_policies;
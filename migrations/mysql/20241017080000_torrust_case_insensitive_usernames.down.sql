@@ -0,0 +1,2 @@
+-- Usernames renamed by the up migration keep their new name.
+ALTER TABLE torrust_user_profiles MODIFY username VARCHAR(24) NOT NULL;
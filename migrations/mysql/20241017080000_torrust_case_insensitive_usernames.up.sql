@@ -0,0 +1,19 @@
+-- Usernames are unique regardless of case, whatever the server default
+-- collation. The collation is accent-sensitive, like `NOCASE` in SQLite, so
+-- `jose` and `josé` are different usernames in both drivers.
+--
+-- Existing usernames differing only by case would make the unique key fail,
+-- so they are renamed first: the oldest account keeps the username and the
+-- other ones get their user ID appended, for example `alice` is renamed to
+-- `alice_42`. Usernames are up to 24 characters long.
+UPDATE torrust_user_profiles profile
+INNER JOIN (
+    SELECT DISTINCT newer.user_id
+    FROM torrust_user_profiles newer
+    INNER JOIN torrust_user_profiles older
+        ON CONVERT(older.username USING utf8mb4) COLLATE utf8mb4_0900_as_ci = CONVERT(newer.username USING utf8mb4) COLLATE utf8mb4_0900_as_ci
+        AND older.user_id < newer.user_id
+) duplicates ON duplicates.user_id = profile.user_id
+SET profile.username = CONCAT(LEFT(profile.username, 23 - CHAR_LENGTH(profile.user_id)), '_', profile.user_id);
+
+ALTER TABLE torrust_user_profiles MODIFY username VARCHAR(24) CHARACTER SET utf8mb4 NOT NULL COLLATE utf8mb4_0900_as_ci;
@@ -0,0 +1,12 @@
+CREATE TABLE IF NOT EXISTS torrust_torrent_previews (
+    preview_id INTEGER NOT NULL PRIMARY KEY AUTO_INCREMENT,
+    torrent_id INTEGER NOT NULL,
+    file_index INTEGER NOT NULL,
+    path TEXT NOT NULL,
+    content_type VARCHAR(64) NOT NULL,
+    size BIGINT NOT NULL,
+    content LONGBLOB NOT NULL,
+    date_created DATETIME NOT NULL,
+    UNIQUE (torrent_id, file_index),
+    FOREIGN KEY (torrent_id) REFERENCES torrust_torrents(torrent_id) ON DELETE CASCADE
+);
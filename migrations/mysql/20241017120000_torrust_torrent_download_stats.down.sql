@@ -0,0 +1 @@
+DROP TABLE IF EXISTS torrust_torrent_download_stats;
@@ -0,0 +1,7 @@
+CREATE TABLE IF NOT EXISTS torrust_torrent_download_stats (
+    torrent_id INTEGER NOT NULL,
+    date DATE NOT NULL,
+    downloads INTEGER NOT NULL DEFAULT 0,
+    PRIMARY KEY (torrent_id, date),
+    FOREIGN KEY (torrent_id) REFERENCES torrust_torrents(torrent_id) ON DELETE CASCADE
+);
@@ -0,0 +1,7 @@
+CREATE TABLE IF NOT EXISTS torrust_torrent_rule_applications (
+    torrent_id INTEGER NOT NULL,
+    rule VARCHAR(255) NOT NULL,
+    date_applied DATETIME NOT NULL,
+    PRIMARY KEY (torrent_id, rule),
+    FOREIGN KEY (torrent_id) REFERENCES torrust_torrents(torrent_id) ON DELETE CASCADE
+);
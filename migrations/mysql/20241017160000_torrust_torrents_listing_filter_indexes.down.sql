@@ -0,0 +1 @@
+DROP INDEX torrust_torrents_date_uploaded_idx ON torrust_torrents;
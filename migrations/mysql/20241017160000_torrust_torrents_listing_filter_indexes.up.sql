@@ -0,0 +1 @@
+CREATE INDEX torrust_torrents_date_uploaded_idx ON torrust_torrents (date_uploaded);
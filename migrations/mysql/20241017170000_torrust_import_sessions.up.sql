@@ -0,0 +1,24 @@
+CREATE TABLE IF NOT EXISTS torrust_import_sessions (
+    import_id INTEGER NOT NULL PRIMARY KEY AUTO_INCREMENT,
+    user_id INTEGER NOT NULL,
+    source VARCHAR(100) NOT NULL,
+    conflict_policy VARCHAR(20) NOT NULL,
+    mapping MEDIUMTEXT DEFAULT NULL,
+    status VARCHAR(20) NOT NULL DEFAULT 'open',
+    date_created DATETIME NOT NULL,
+    date_completed DATETIME DEFAULT NULL,
+    FOREIGN KEY (user_id) REFERENCES torrust_users(user_id) ON DELETE CASCADE
+);
+
+CREATE TABLE IF NOT EXISTS torrust_import_batches (
+    import_id INTEGER NOT NULL,
+    batch_id VARCHAR(64) NOT NULL,
+    imported INTEGER NOT NULL,
+    updated INTEGER NOT NULL,
+    duplicates INTEGER NOT NULL,
+    failures INTEGER NOT NULL,
+    summary MEDIUMTEXT NOT NULL,
+    date_processed DATETIME NOT NULL,
+    PRIMARY KEY (import_id, batch_id),
+    FOREIGN KEY (import_id) REFERENCES torrust_import_sessions(import_id) ON DELETE CASCADE
+);
@@ -0,0 +1,16 @@
+CREATE TABLE IF NOT EXISTS torrust_user_points (
+    user_id INTEGER NOT NULL PRIMARY KEY,
+    balance BIGINT NOT NULL DEFAULT 0,
+    FOREIGN KEY (user_id) REFERENCES torrust_users(user_id) ON DELETE CASCADE
+);
+
+CREATE TABLE IF NOT EXISTS torrust_user_point_adjustments (
+    adjustment_id INTEGER NOT NULL PRIMARY KEY AUTO_INCREMENT,
+    user_id INTEGER NOT NULL,
+    amount BIGINT NOT NULL,
+    reason VARCHAR(200) NOT NULL,
+    reference VARCHAR(100) DEFAULT NULL,
+    date_created DATETIME NOT NULL,
+    UNIQUE (user_id, reference),
+    FOREIGN KEY (user_id) REFERENCES torrust_users(user_id) ON DELETE CASCADE
+);
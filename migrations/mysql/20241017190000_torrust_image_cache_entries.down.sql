@@ -0,0 +1 @@
+DROP TABLE IF EXISTS torrust_image_cache_entries;
@@ -0,0 +1,6 @@
+CREATE TABLE IF NOT EXISTS torrust_image_cache_entries (
+    cache_key CHAR(64) NOT NULL PRIMARY KEY,
+    size BIGINT NOT NULL,
+    last_accessed DATETIME NOT NULL,
+    INDEX (last_accessed)
+);
@@ -0,0 +1,13 @@
+CREATE TABLE IF NOT EXISTS torrust_torrent_description_images (
+    image_id INTEGER NOT NULL PRIMARY KEY AUTO_INCREMENT,
+    torrent_id INTEGER NOT NULL,
+    uploader_id INTEGER NOT NULL,
+    cache_key VARCHAR(128) NOT NULL,
+    file_name VARCHAR(255) NOT NULL,
+    content_type VARCHAR(64) NOT NULL,
+    size BIGINT NOT NULL,
+    date_created DATETIME NOT NULL,
+    UNIQUE (torrent_id, cache_key),
+    FOREIGN KEY (torrent_id) REFERENCES torrust_torrents(torrent_id) ON DELETE CASCADE,
+    FOREIGN KEY (uploader_id) REFERENCES torrust_users(user_id) ON DELETE CASCADE
+);
@@ -0,0 +1,2 @@
+DROP INDEX torrust_torrent_files_length_idx ON torrust_torrent_files;
+DROP TABLE IF EXISTS torrust_torrent_duplicates;
@@ -0,0 +1,9 @@
+CREATE TABLE IF NOT EXISTS torrust_torrent_trash (
+    torrent_id INTEGER NOT NULL PRIMARY KEY,
+    previous_status VARCHAR(16) NOT NULL,
+    deleted_by INTEGER NULL,
+    date_deleted DATETIME NOT NULL,
+    INDEX (date_deleted),
+    FOREIGN KEY (torrent_id) REFERENCES torrust_torrents(torrent_id) ON DELETE CASCADE,
+    FOREIGN KEY (deleted_by) REFERENCES torrust_users(user_id) ON DELETE SET NULL
+);
@@ -0,0 +1,7 @@
+CREATE TABLE IF NOT EXISTS torrust_torrent_schedules (
+    torrent_id INTEGER NOT NULL PRIMARY KEY,
+    publish_at DATETIME NOT NULL,
+    status_on_publish VARCHAR(16) NOT NULL,
+    INDEX (publish_at),
+    FOREIGN KEY (torrent_id) REFERENCES torrust_torrents(torrent_id) ON DELETE CASCADE
+);
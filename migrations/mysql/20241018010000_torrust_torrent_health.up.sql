@@ -0,0 +1,9 @@
+CREATE TABLE IF NOT EXISTS torrust_torrent_health (
+    torrent_id INTEGER NOT NULL PRIMARY KEY,
+    health_score DOUBLE NOT NULL,
+    zero_seeders_since DATETIME NULL,
+    date_flagged DATETIME NULL,
+    date_updated DATETIME NOT NULL,
+    INDEX (zero_seeders_since),
+    FOREIGN KEY (torrent_id) REFERENCES torrust_torrents(torrent_id) ON DELETE CASCADE
+);
@@ -0,0 +1,8 @@
+CREATE TABLE IF NOT EXISTS torrust_antispam_patterns (
+    pattern_id INTEGER NOT NULL PRIMARY KEY AUTO_INCREMENT,
+    pattern VARCHAR(255) NOT NULL UNIQUE,
+    action VARCHAR(16) NOT NULL,
+    created_by INTEGER NULL,
+    date_created DATETIME NOT NULL,
+    FOREIGN KEY (created_by) REFERENCES torrust_users(user_id) ON DELETE SET NULL
+);
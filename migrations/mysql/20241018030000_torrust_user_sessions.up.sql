@@ -0,0 +1,11 @@
+CREATE TABLE IF NOT EXISTS torrust_user_sessions (
+    session_id VARCHAR(32) NOT NULL PRIMARY KEY,
+    user_id INTEGER NOT NULL,
+    user_agent VARCHAR(255) NULL,
+    ip VARCHAR(45) NULL,
+    date_created DATETIME NOT NULL,
+    last_seen DATETIME NOT NULL,
+    expires_at DATETIME NOT NULL,
+    INDEX torrust_user_sessions_user_id_idx (user_id),
+    FOREIGN KEY (user_id) REFERENCES torrust_users(user_id) ON DELETE CASCADE
+);
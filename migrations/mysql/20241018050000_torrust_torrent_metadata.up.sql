@@ -0,0 +1,13 @@
+CREATE TABLE IF NOT EXISTS torrust_torrent_metadata (
+    torrent_id INTEGER NOT NULL PRIMARY KEY,
+    provider VARCHAR(32) NOT NULL,
+    external_id VARCHAR(128) NOT NULL,
+    title VARCHAR(512) NOT NULL,
+    year INTEGER NULL,
+    synopsis TEXT NULL,
+    poster_url VARCHAR(1024) NULL,
+    external_url VARCHAR(1024) NULL,
+    date_updated DATETIME NOT NULL,
+    INDEX (external_id),
+    FOREIGN KEY (torrent_id) REFERENCES torrust_torrents(torrent_id) ON DELETE CASCADE
+);
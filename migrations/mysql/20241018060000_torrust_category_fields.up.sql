@@ -0,0 +1,22 @@
+CREATE TABLE IF NOT EXISTS torrust_category_fields (
+    field_id INTEGER NOT NULL PRIMARY KEY AUTO_INCREMENT,
+    category_id INTEGER NOT NULL,
+    name VARCHAR(64) NOT NULL,
+    label VARCHAR(128) NOT NULL,
+    field_type VARCHAR(16) NOT NULL,
+    required BOOL NOT NULL DEFAULT FALSE,
+    options TEXT NOT NULL,
+    date_created DATETIME NOT NULL,
+    UNIQUE (category_id, name),
+    FOREIGN KEY (category_id) REFERENCES torrust_categories(category_id) ON DELETE CASCADE
+);
+
+CREATE TABLE IF NOT EXISTS torrust_torrent_field_values (
+    torrent_id INTEGER NOT NULL,
+    field_id INTEGER NOT NULL,
+    value VARCHAR(255) NOT NULL,
+    PRIMARY KEY (torrent_id, field_id),
+    INDEX (field_id, value),
+    FOREIGN KEY (torrent_id) REFERENCES torrust_torrents(torrent_id) ON DELETE CASCADE,
+    FOREIGN KEY (field_id) REFERENCES torrust_category_fields(field_id) ON DELETE CASCADE
+);
@@ -0,0 +1,8 @@
+CREATE TABLE IF NOT EXISTS torrust_user_transfer_stats (
+    tracker_key CHAR(32) NOT NULL PRIMARY KEY,
+    user_id INTEGER NOT NULL,
+    uploaded BIGINT NOT NULL DEFAULT 0,
+    downloaded BIGINT NOT NULL DEFAULT 0,
+    date_updated DATETIME NOT NULL,
+    FOREIGN KEY (user_id) REFERENCES torrust_users(user_id) ON DELETE CASCADE
+);
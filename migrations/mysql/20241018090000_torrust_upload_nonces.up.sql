@@ -0,0 +1,4 @@
+CREATE TABLE IF NOT EXISTS torrust_upload_nonces (
+    nonce VARCHAR(64) NOT NULL PRIMARY KEY,
+    date_expiry BIGINT NOT NULL
+);
@@ -0,0 +1,11 @@
+CREATE TABLE IF NOT EXISTS torrust_domain_events (
+    event_id INTEGER NOT NULL PRIMARY KEY AUTOINCREMENT,
+    event_type VARCHAR(64) NOT NULL,
+    payload TEXT NOT NULL,
+    date_created TEXT NOT NULL
+);
+
+CREATE TABLE IF NOT EXISTS torrust_domain_event_consumers (
+    consumer VARCHAR(64) NOT NULL PRIMARY KEY,
+    last_event_id INTEGER NOT NULL
+);
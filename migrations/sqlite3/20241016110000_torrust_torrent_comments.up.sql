@@ -0,0 +1,13 @@
+CREATE TABLE IF NOT EXISTS torrust_torrent_comments (
+    comment_id INTEGER NOT NULL PRIMARY KEY AUTOINCREMENT,
+    torrent_id INTEGER NOT NULL,
+    author_id INTEGER NOT NULL,
+    text TEXT NOT NULL,
+    hidden BOOL NOT NULL DEFAULT FALSE,
+    date_created TEXT NOT NULL,
+    date_updated TEXT DEFAULT NULL,
+    FOREIGN KEY (torrent_id) REFERENCES torrust_torrents(torrent_id) ON DELETE CASCADE,
+    FOREIGN KEY (author_id) REFERENCES torrust_users(user_id) ON DELETE CASCADE
+);
+
+CREATE INDEX IF NOT EXISTS torrust_torrent_comments_torrent_id_idx ON torrust_torrent_comments (torrent_id);
@@ -0,0 +1,2 @@
+DROP INDEX IF EXISTS torrust_torrents_status_idx;
+ALTER TABLE torrust_torrents DROP COLUMN status;
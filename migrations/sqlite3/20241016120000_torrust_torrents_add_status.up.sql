@@ -0,0 +1,3 @@
+ALTER TABLE torrust_torrents ADD COLUMN status TEXT NOT NULL DEFAULT 'approved';
+
+CREATE INDEX IF NOT EXISTS torrust_torrents_status_idx ON torrust_torrents (status);
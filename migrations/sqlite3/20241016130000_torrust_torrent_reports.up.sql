@@ -0,0 +1,17 @@
+CREATE TABLE IF NOT EXISTS torrust_torrent_reports (
+    report_id INTEGER NOT NULL PRIMARY KEY AUTOINCREMENT,
+    torrent_id INTEGER NOT NULL,
+    reporter_id INTEGER NOT NULL,
+    reason TEXT NOT NULL,
+    details TEXT DEFAULT NULL,
+    resolved BOOL NOT NULL DEFAULT FALSE,
+    resolution TEXT DEFAULT NULL,
+    resolved_by INTEGER DEFAULT NULL,
+    date_created TEXT NOT NULL,
+    date_resolved TEXT DEFAULT NULL,
+    FOREIGN KEY (torrent_id) REFERENCES torrust_torrents(torrent_id) ON DELETE CASCADE,
+    FOREIGN KEY (reporter_id) REFERENCES torrust_users(user_id) ON DELETE CASCADE,
+    FOREIGN KEY (resolved_by) REFERENCES torrust_users(user_id) ON DELETE SET NULL
+);
+
+CREATE INDEX IF NOT EXISTS torrust_torrent_reports_resolved_idx ON torrust_torrent_reports (resolved);
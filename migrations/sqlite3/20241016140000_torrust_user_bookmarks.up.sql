@@ -0,0 +1,8 @@
+CREATE TABLE IF NOT EXISTS torrust_user_bookmarks (
+    user_id INTEGER NOT NULL,
+    torrent_id INTEGER NOT NULL,
+    date_created TEXT NOT NULL,
+    PRIMARY KEY (user_id, torrent_id),
+    FOREIGN KEY (user_id) REFERENCES torrust_users(user_id) ON DELETE CASCADE,
+    FOREIGN KEY (torrent_id) REFERENCES torrust_torrents(torrent_id) ON DELETE CASCADE
+);
@@ -0,0 +1,10 @@
+CREATE TABLE IF NOT EXISTS torrust_user_subscriptions (
+    subscription_id INTEGER NOT NULL PRIMARY KEY AUTOINCREMENT,
+    user_id INTEGER NOT NULL,
+    category_id INTEGER DEFAULT NULL,
+    tag_id INTEGER DEFAULT NULL,
+    date_created TEXT NOT NULL,
+    FOREIGN KEY (user_id) REFERENCES torrust_users(user_id) ON DELETE CASCADE,
+    FOREIGN KEY (category_id) REFERENCES torrust_categories(category_id) ON DELETE CASCADE,
+    FOREIGN KEY (tag_id) REFERENCES torrust_torrent_tags(tag_id) ON DELETE CASCADE
+);
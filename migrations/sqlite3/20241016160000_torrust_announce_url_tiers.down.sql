@@ -0,0 +1 @@
+ALTER TABLE torrust_torrent_announce_urls DROP COLUMN tier;
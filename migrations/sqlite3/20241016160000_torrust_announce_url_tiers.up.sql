@@ -0,0 +1,10 @@
+ALTER TABLE torrust_torrent_announce_urls ADD COLUMN tier INTEGER NOT NULL DEFAULT 0;
+
+-- Existing trackers were listed in their own tier
+UPDATE torrust_torrent_announce_urls
+SET tier = (
+    SELECT COUNT(*)
+    FROM torrust_torrent_announce_urls AS previous
+    WHERE previous.torrent_id = torrust_torrent_announce_urls.torrent_id
+    AND previous.announce_url_id < torrust_torrent_announce_urls.announce_url_id
+);
@@ -0,0 +1,7 @@
+CREATE TABLE IF NOT EXISTS torrust_download_client_stats (
+    date TEXT NOT NULL,
+    client_family TEXT NOT NULL,
+    user_agent TEXT NOT NULL DEFAULT '',
+    downloads INTEGER NOT NULL DEFAULT 0,
+    PRIMARY KEY (date, client_family, user_agent)
+);
@@ -0,0 +1,21 @@
+CREATE TABLE IF NOT EXISTS torrust_magnet_uploads (
+    magnet_upload_id INTEGER NOT NULL PRIMARY KEY AUTOINCREMENT,
+    user_id INTEGER NOT NULL,
+    info_hash TEXT NOT NULL,
+    magnet_uri TEXT NOT NULL,
+    title TEXT NOT NULL,
+    description TEXT NOT NULL,
+    category_id INTEGER NOT NULL,
+    tag_ids TEXT NOT NULL DEFAULT '',
+    status TEXT NOT NULL DEFAULT 'resolving',
+    torrent_id INTEGER NULL,
+    attempts INTEGER NOT NULL DEFAULT 0,
+    error TEXT NULL,
+    date_created TEXT NOT NULL,
+    date_updated TEXT NOT NULL,
+    FOREIGN KEY (user_id) REFERENCES torrust_users(user_id) ON DELETE CASCADE,
+    FOREIGN KEY (category_id) REFERENCES torrust_categories(category_id) ON DELETE CASCADE,
+    FOREIGN KEY (torrent_id) REFERENCES torrust_torrents(torrent_id) ON DELETE SET NULL
+);
+
+CREATE INDEX IF NOT EXISTS torrust_magnet_uploads_status_idx ON torrust_magnet_uploads (status);
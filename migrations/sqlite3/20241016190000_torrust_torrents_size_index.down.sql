@@ -0,0 +1 @@
+DROP INDEX IF EXISTS torrust_torrents_size_idx;
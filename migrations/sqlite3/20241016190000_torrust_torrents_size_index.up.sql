@@ -0,0 +1 @@
+CREATE INDEX IF NOT EXISTS torrust_torrents_size_idx ON torrust_torrents (size);
@@ -0,0 +1,16 @@
+CREATE TABLE IF NOT EXISTS torrust_email_queue (
+    email_id INTEGER NOT NULL PRIMARY KEY AUTOINCREMENT,
+    recipient TEXT NOT NULL,
+    subject TEXT NOT NULL,
+    body TEXT NOT NULL,
+    attempts INTEGER NOT NULL DEFAULT 0,
+    date_created TEXT NOT NULL
+);
+
+CREATE TABLE IF NOT EXISTS torrust_user_email_unsubscribes (
+    user_id INTEGER NOT NULL PRIMARY KEY,
+    date_created TEXT NOT NULL,
+    FOREIGN KEY (user_id) REFERENCES torrust_users(user_id) ON DELETE CASCADE
+);
+
+ALTER TABLE torrust_users ADD COLUMN date_last_login TEXT DEFAULT NULL;
@@ -0,0 +1,9 @@
+ALTER TABLE torrust_users ADD COLUMN date_inactivity_warning TEXT DEFAULT NULL;
+
+ALTER TABLE torrust_users ADD COLUMN date_disabled TEXT DEFAULT NULL;
+
+CREATE TABLE IF NOT EXISTS torrust_user_inactivity_exemptions (
+    user_id INTEGER NOT NULL PRIMARY KEY,
+    date_created TEXT NOT NULL,
+    FOREIGN KEY (user_id) REFERENCES torrust_users(user_id) ON DELETE CASCADE
+);
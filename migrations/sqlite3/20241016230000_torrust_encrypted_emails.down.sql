@@ -0,0 +1,2 @@
+-- Nothing to revert, the up migration doesn't change the schema.
+SELECT 1;
@@ -0,0 +1,2 @@
+-- SQLite TEXT columns are big enough for the encrypted emails.
+SELECT 1;
@@ -0,0 +1,2 @@
+DROP TABLE IF EXISTS torrust_collection_items;
+DROP TABLE IF EXISTS torrust_collections;
@@ -0,0 +1,2 @@
+DROP INDEX IF EXISTS torrust_torrents_uploader_id_idx;
+DROP TABLE IF EXISTS torrust_user_upload_quotas;
@@ -0,0 +1,11 @@
+CREATE TABLE IF NOT EXISTS torrust_user_api_usage (
+    user_id INTEGER NOT NULL,
+    credential TEXT NOT NULL,
+    date TEXT NOT NULL,
+    requests INTEGER NOT NULL DEFAULT 0,
+    rate_limit_hits INTEGER NOT NULL DEFAULT 0,
+    last_ip TEXT,
+    last_used TEXT NOT NULL,
+    PRIMARY KEY (user_id, credential, date),
+    FOREIGN KEY (user_id) REFERENCES torrust_users(user_id) ON DELETE CASCADE
+);
@@ -0,0 +1 @@
+DROP TABLE IF EXISTS torrust_sync_records;
@@ -0,0 +1,12 @@
+ALTER TABLE torrust_collections ADD COLUMN smart BOOLEAN NOT NULL DEFAULT FALSE;
+
+CREATE TABLE IF NOT EXISTS torrust_smart_collection_filters (
+    collection_id INTEGER NOT NULL PRIMARY KEY,
+    category TEXT DEFAULT NULL,
+    tags TEXT DEFAULT NULL,
+    uploaded_after TEXT DEFAULT NULL,
+    uploaded_before TEXT DEFAULT NULL,
+    max_age_days INTEGER DEFAULT NULL,
+    date_materialized TEXT DEFAULT NULL,
+    FOREIGN KEY (collection_id) REFERENCES torrust_collections(collection_id) ON DELETE CASCADE
+);
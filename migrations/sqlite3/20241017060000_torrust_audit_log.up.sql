@@ -0,0 +1,12 @@
+CREATE TABLE IF NOT EXISTS torrust_audit_log (
+    audit_id INTEGER NOT NULL PRIMARY KEY AUTOINCREMENT,
+    actor_id INTEGER DEFAULT NULL,
+    action TEXT NOT NULL,
+    target TEXT NOT NULL,
+    details TEXT DEFAULT NULL,
+    date_created TEXT NOT NULL,
+    FOREIGN KEY (actor_id) REFERENCES torrust_users(user_id) ON DELETE SET NULL
+);
+
+CREATE INDEX IF NOT EXISTS torrust_audit_log_action_idx ON torrust_audit_log (action);
+CREATE INDEX IF NOT EXISTS torrust_audit_log_date_created_idx ON torrust_audit_log (date_created);
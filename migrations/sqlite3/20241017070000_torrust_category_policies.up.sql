@@ -0,0 +1,14 @@
+CREATE TABLE IF NOT EXISTS torrust_category_policies (
+    category_id INTEGER NOT NULL PRIMARY KEY,
+    co_signing BOOL NOT NULL DEFAULT FALSE,
+    FOREIGN KEY (category_id) REFERENCES torrust_categories(category_id) ON DELETE CASCADE
+);
+
+CREATE TABLE IF NOT EXISTS torrust_torrent_approvals (
+    torrent_id INTEGER NOT NULL,
+    user_id INTEGER NOT NULL,
+    date_created TEXT NOT NULL,
+    PRIMARY KEY (torrent_id, user_id),
+    FOREIGN KEY (torrent_id) REFERENCES torrust_torrents(torrent_id) ON DELETE CASCADE,
+    FOREIGN KEY (user_id) REFERENCES torrust_users(user_id) ON DELETE CASCADE
+);
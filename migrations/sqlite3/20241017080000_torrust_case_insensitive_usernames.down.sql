@@ -0,0 +1,2 @@
+-- Usernames renamed by the up migration keep their new name.
+DROP INDEX IF EXISTS torrust_user_profiles_username_nocase_index;
@@ -0,0 +1,16 @@
+-- Usernames are unique regardless of case, like in MySQL. Accents are
+-- significant in both drivers.
+--
+-- Existing usernames differing only by case would make the unique index
+-- fail, so they are renamed first: the oldest account keeps the username and
+-- the other ones get their user ID appended, for example `alice` is renamed
+-- to `alice_42`. Usernames are up to 24 characters long.
+UPDATE torrust_user_profiles
+SET username = SUBSTR(username, 1, 23 - LENGTH(CAST(user_id AS TEXT))) || '_' || user_id
+WHERE EXISTS (
+    SELECT 1 FROM torrust_user_profiles older
+    WHERE older.username = torrust_user_profiles.username COLLATE NOCASE
+    AND older.user_id < torrust_user_profiles.user_id
+);
+
+CREATE UNIQUE INDEX IF NOT EXISTS torrust_user_profiles_username_nocase_index ON torrust_user_profiles (username COLLATE NOCASE);
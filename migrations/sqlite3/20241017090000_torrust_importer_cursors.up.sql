@@ -0,0 +1,4 @@
+CREATE TABLE IF NOT EXISTS torrust_importer_cursors (
+    importer VARCHAR(64) NOT NULL PRIMARY KEY,
+    last_torrent_id INTEGER NOT NULL
+);
@@ -0,0 +1,12 @@
+CREATE TABLE IF NOT EXISTS torrust_torrent_previews (
+    preview_id INTEGER NOT NULL PRIMARY KEY AUTOINCREMENT,
+    torrent_id INTEGER NOT NULL,
+    file_index INTEGER NOT NULL,
+    path TEXT NOT NULL,
+    content_type TEXT NOT NULL,
+    size INTEGER NOT NULL,
+    content BLOB NOT NULL,
+    date_created TEXT NOT NULL,
+    UNIQUE (torrent_id, file_index),
+    FOREIGN KEY (torrent_id) REFERENCES torrust_torrents(torrent_id) ON DELETE CASCADE
+);
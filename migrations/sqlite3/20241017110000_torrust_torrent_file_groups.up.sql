@@ -0,0 +1,9 @@
+CREATE TABLE IF NOT EXISTS torrust_torrent_file_groups (
+    group_id INTEGER NOT NULL PRIMARY KEY AUTOINCREMENT,
+    torrent_id INTEGER NOT NULL,
+    position INTEGER NOT NULL,
+    name TEXT NOT NULL,
+    path TEXT NOT NULL,
+    UNIQUE (torrent_id, name),
+    FOREIGN KEY (torrent_id) REFERENCES torrust_torrents(torrent_id) ON DELETE CASCADE
+);
@@ -0,0 +1,5 @@
+CREATE TABLE IF NOT EXISTS torrust_torrent_tag_synonyms (
+    name TEXT NOT NULL PRIMARY KEY,
+    tag_id INTEGER NOT NULL,
+    FOREIGN KEY (tag_id) REFERENCES torrust_torrent_tags(tag_id) ON DELETE CASCADE
+);
@@ -0,0 +1,2 @@
+DROP TABLE IF EXISTS torrust_user_saved_search_results;
+DROP TABLE IF EXISTS torrust_user_saved_searches;
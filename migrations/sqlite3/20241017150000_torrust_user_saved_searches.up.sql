@@ -0,0 +1,20 @@
+CREATE TABLE IF NOT EXISTS torrust_user_saved_searches (
+    saved_search_id INTEGER NOT NULL PRIMARY KEY AUTOINCREMENT,
+    user_id INTEGER NOT NULL,
+    name TEXT NOT NULL,
+    query TEXT NOT NULL,
+    category TEXT DEFAULT NULL,
+    tags TEXT DEFAULT NULL,
+    email_alerts BOOLEAN NOT NULL DEFAULT TRUE,
+    date_created TEXT NOT NULL,
+    FOREIGN KEY (user_id) REFERENCES torrust_users(user_id) ON DELETE CASCADE
+);
+
+CREATE TABLE IF NOT EXISTS torrust_user_saved_search_results (
+    saved_search_id INTEGER NOT NULL,
+    torrent_id INTEGER NOT NULL,
+    date_matched TEXT NOT NULL,
+    PRIMARY KEY (saved_search_id, torrent_id),
+    FOREIGN KEY (saved_search_id) REFERENCES torrust_user_saved_searches(saved_search_id) ON DELETE CASCADE,
+    FOREIGN KEY (torrent_id) REFERENCES torrust_torrents(torrent_id) ON DELETE CASCADE
+);
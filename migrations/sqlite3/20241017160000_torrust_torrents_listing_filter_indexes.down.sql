@@ -0,0 +1,2 @@
+DROP INDEX IF EXISTS torrust_torrent_files_torrent_id_idx;
+DROP INDEX IF EXISTS torrust_torrents_date_uploaded_idx;
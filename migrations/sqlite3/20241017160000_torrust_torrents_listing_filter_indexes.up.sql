@@ -0,0 +1,2 @@
+CREATE INDEX IF NOT EXISTS torrust_torrents_date_uploaded_idx ON torrust_torrents (date_uploaded);
+CREATE INDEX IF NOT EXISTS torrust_torrent_files_torrent_id_idx ON torrust_torrent_files (torrent_id);
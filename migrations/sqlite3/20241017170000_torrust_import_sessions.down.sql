@@ -0,0 +1,2 @@
+DROP TABLE IF EXISTS torrust_import_batches;
+DROP TABLE IF EXISTS torrust_import_sessions;
@@ -0,0 +1,24 @@
+CREATE TABLE IF NOT EXISTS torrust_import_sessions (
+    import_id INTEGER NOT NULL PRIMARY KEY AUTOINCREMENT,
+    user_id INTEGER NOT NULL,
+    source TEXT NOT NULL,
+    conflict_policy TEXT NOT NULL,
+    mapping TEXT NULL DEFAULT NULL,
+    status TEXT NOT NULL DEFAULT 'open',
+    date_created TEXT NOT NULL,
+    date_completed TEXT NULL DEFAULT NULL,
+    FOREIGN KEY(user_id) REFERENCES torrust_users(user_id) ON DELETE CASCADE
+);
+
+CREATE TABLE IF NOT EXISTS torrust_import_batches (
+    import_id INTEGER NOT NULL,
+    batch_id TEXT NOT NULL,
+    imported INTEGER NOT NULL,
+    updated INTEGER NOT NULL,
+    duplicates INTEGER NOT NULL,
+    failures INTEGER NOT NULL,
+    summary TEXT NOT NULL,
+    date_processed TEXT NOT NULL,
+    PRIMARY KEY(import_id, batch_id),
+    FOREIGN KEY(import_id) REFERENCES torrust_import_sessions(import_id) ON DELETE CASCADE
+);
@@ -0,0 +1,2 @@
+DROP TABLE IF EXISTS torrust_user_point_adjustments;
+DROP TABLE IF EXISTS torrust_user_points;
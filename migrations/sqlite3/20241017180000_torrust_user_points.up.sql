@@ -0,0 +1,16 @@
+CREATE TABLE IF NOT EXISTS torrust_user_points (
+    user_id INTEGER NOT NULL PRIMARY KEY,
+    balance INTEGER NOT NULL DEFAULT 0,
+    FOREIGN KEY(user_id) REFERENCES torrust_users(user_id) ON DELETE CASCADE
+);
+
+CREATE TABLE IF NOT EXISTS torrust_user_point_adjustments (
+    adjustment_id INTEGER NOT NULL PRIMARY KEY AUTOINCREMENT,
+    user_id INTEGER NOT NULL,
+    amount INTEGER NOT NULL,
+    reason TEXT NOT NULL,
+    reference TEXT NULL DEFAULT NULL,
+    date_created TEXT NOT NULL,
+    UNIQUE(user_id, reference),
+    FOREIGN KEY(user_id) REFERENCES torrust_users(user_id) ON DELETE CASCADE
+);
@@ -0,0 +1,7 @@
+CREATE TABLE IF NOT EXISTS torrust_image_cache_entries (
+    cache_key TEXT NOT NULL PRIMARY KEY,
+    size INTEGER NOT NULL,
+    last_accessed TEXT NOT NULL
+);
+
+CREATE INDEX IF NOT EXISTS torrust_image_cache_entries_last_accessed ON torrust_image_cache_entries (last_accessed);
@@ -0,0 +1,13 @@
+CREATE TABLE IF NOT EXISTS torrust_torrent_description_images (
+    image_id INTEGER NOT NULL PRIMARY KEY AUTOINCREMENT,
+    torrent_id INTEGER NOT NULL,
+    uploader_id INTEGER NOT NULL,
+    cache_key TEXT NOT NULL,
+    file_name TEXT NOT NULL,
+    content_type TEXT NOT NULL,
+    size INTEGER NOT NULL,
+    date_created TEXT NOT NULL,
+    UNIQUE (torrent_id, cache_key),
+    FOREIGN KEY (torrent_id) REFERENCES torrust_torrents(torrent_id) ON DELETE CASCADE,
+    FOREIGN KEY (uploader_id) REFERENCES torrust_users(user_id) ON DELETE CASCADE
+);
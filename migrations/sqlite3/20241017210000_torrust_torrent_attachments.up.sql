@@ -0,0 +1,15 @@
+CREATE TABLE IF NOT EXISTS torrust_torrent_attachments (
+    attachment_id INTEGER NOT NULL PRIMARY KEY AUTOINCREMENT,
+    torrent_id INTEGER NOT NULL,
+    uploader_id INTEGER NOT NULL,
+    kind TEXT NOT NULL,
+    file_name TEXT NOT NULL,
+    content_type TEXT NOT NULL,
+    size INTEGER NOT NULL,
+    content BLOB NOT NULL,
+    date_created TEXT NOT NULL,
+    FOREIGN KEY (torrent_id) REFERENCES torrust_torrents(torrent_id) ON DELETE CASCADE,
+    FOREIGN KEY (uploader_id) REFERENCES torrust_users(user_id) ON DELETE CASCADE
+);
+
+CREATE INDEX IF NOT EXISTS torrust_torrent_attachments_torrent_id ON torrust_torrent_attachments (torrent_id);
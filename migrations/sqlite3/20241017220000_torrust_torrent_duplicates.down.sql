@@ -0,0 +1,2 @@
+DROP INDEX IF EXISTS torrust_torrent_files_length_idx;
+DROP TABLE IF EXISTS torrust_torrent_duplicates;
@@ -0,0 +1,11 @@
+CREATE TABLE IF NOT EXISTS torrust_torrent_duplicates (
+    torrent_id INTEGER NOT NULL,
+    duplicate_of INTEGER NOT NULL,
+    overlap REAL NOT NULL,
+    date_detected TEXT NOT NULL,
+    PRIMARY KEY (torrent_id, duplicate_of),
+    FOREIGN KEY (torrent_id) REFERENCES torrust_torrents(torrent_id) ON DELETE CASCADE,
+    FOREIGN KEY (duplicate_of) REFERENCES torrust_torrents(torrent_id) ON DELETE CASCADE
+);
+
+CREATE INDEX IF NOT EXISTS torrust_torrent_files_length_idx ON torrust_torrent_files (length);
@@ -0,0 +1,10 @@
+CREATE TABLE IF NOT EXISTS torrust_torrent_trash (
+    torrent_id INTEGER NOT NULL PRIMARY KEY,
+    previous_status TEXT NOT NULL,
+    deleted_by INTEGER NULL,
+    date_deleted TEXT NOT NULL,
+    FOREIGN KEY (torrent_id) REFERENCES torrust_torrents(torrent_id) ON DELETE CASCADE,
+    FOREIGN KEY (deleted_by) REFERENCES torrust_users(user_id) ON DELETE SET NULL
+);
+
+CREATE INDEX IF NOT EXISTS torrust_torrent_trash_date_deleted ON torrust_torrent_trash (date_deleted);
@@ -0,0 +1,8 @@
+CREATE TABLE IF NOT EXISTS torrust_torrent_schedules (
+    torrent_id INTEGER NOT NULL PRIMARY KEY,
+    publish_at TEXT NOT NULL,
+    status_on_publish TEXT NOT NULL,
+    FOREIGN KEY (torrent_id) REFERENCES torrust_torrents(torrent_id) ON DELETE CASCADE
+);
+
+CREATE INDEX IF NOT EXISTS torrust_torrent_schedules_publish_at ON torrust_torrent_schedules (publish_at);
@@ -0,0 +1,10 @@
+CREATE TABLE IF NOT EXISTS torrust_torrent_health (
+    torrent_id INTEGER NOT NULL PRIMARY KEY,
+    health_score REAL NOT NULL,
+    zero_seeders_since TEXT NULL,
+    date_flagged TEXT NULL,
+    date_updated TEXT NOT NULL,
+    FOREIGN KEY (torrent_id) REFERENCES torrust_torrents(torrent_id) ON DELETE CASCADE
+);
+
+CREATE INDEX IF NOT EXISTS torrust_torrent_health_zero_seeders_since ON torrust_torrent_health (zero_seeders_since);
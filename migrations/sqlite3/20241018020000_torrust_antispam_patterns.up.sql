@@ -0,0 +1,8 @@
+CREATE TABLE IF NOT EXISTS torrust_antispam_patterns (
+    pattern_id INTEGER NOT NULL PRIMARY KEY AUTOINCREMENT,
+    pattern TEXT NOT NULL UNIQUE,
+    action TEXT NOT NULL,
+    created_by INTEGER NULL,
+    date_created TEXT NOT NULL,
+    FOREIGN KEY (created_by) REFERENCES torrust_users(user_id) ON DELETE SET NULL
+);
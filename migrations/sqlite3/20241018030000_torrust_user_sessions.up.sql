@@ -0,0 +1,12 @@
+CREATE TABLE IF NOT EXISTS torrust_user_sessions (
+    session_id TEXT NOT NULL PRIMARY KEY,
+    user_id INTEGER NOT NULL,
+    user_agent TEXT NULL,
+    ip TEXT NULL,
+    date_created TEXT NOT NULL,
+    last_seen TEXT NOT NULL,
+    expires_at TEXT NOT NULL,
+    FOREIGN KEY (user_id) REFERENCES torrust_users(user_id) ON DELETE CASCADE
+);
+
+CREATE INDEX IF NOT EXISTS torrust_user_sessions_user_id_idx ON torrust_user_sessions (user_id);
@@ -0,0 +1,10 @@
+CREATE TABLE IF NOT EXISTS torrust_torrent_web_seeds (
+    web_seed_id INTEGER NOT NULL PRIMARY KEY AUTOINCREMENT,
+    torrent_id INTEGER NOT NULL,
+    url TEXT NOT NULL,
+    added_by INTEGER NOT NULL,
+    date_created TEXT NOT NULL,
+    UNIQUE (torrent_id, url),
+    FOREIGN KEY (torrent_id) REFERENCES torrust_torrents(torrent_id) ON DELETE CASCADE,
+    FOREIGN KEY (added_by) REFERENCES torrust_users(user_id) ON DELETE CASCADE
+);
@@ -0,0 +1,14 @@
+CREATE TABLE IF NOT EXISTS torrust_torrent_metadata (
+    torrent_id INTEGER NOT NULL PRIMARY KEY,
+    provider TEXT NOT NULL,
+    external_id TEXT NOT NULL,
+    title TEXT NOT NULL,
+    year INTEGER NULL,
+    synopsis TEXT NULL,
+    poster_url TEXT NULL,
+    external_url TEXT NULL,
+    date_updated TEXT NOT NULL,
+    FOREIGN KEY (torrent_id) REFERENCES torrust_torrents(torrent_id) ON DELETE CASCADE
+);
+
+CREATE INDEX IF NOT EXISTS torrust_torrent_metadata_external_id ON torrust_torrent_metadata (external_id);
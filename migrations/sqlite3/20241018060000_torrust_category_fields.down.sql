@@ -0,0 +1,2 @@
+DROP TABLE IF EXISTS torrust_torrent_field_values;
+DROP TABLE IF EXISTS torrust_category_fields;
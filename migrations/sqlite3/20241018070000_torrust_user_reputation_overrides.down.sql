@@ -0,0 +1 @@
+DROP TABLE IF EXISTS torrust_user_reputation_overrides;
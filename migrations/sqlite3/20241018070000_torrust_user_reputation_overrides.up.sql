@@ -0,0 +1,6 @@
+CREATE TABLE IF NOT EXISTS torrust_user_reputation_overrides (
+    user_id INTEGER NOT NULL PRIMARY KEY,
+    trusted BOOL NOT NULL,
+    date_updated TEXT NOT NULL,
+    FOREIGN KEY (user_id) REFERENCES torrust_users(user_id) ON DELETE CASCADE
+);
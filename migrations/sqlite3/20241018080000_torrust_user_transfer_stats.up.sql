@@ -0,0 +1,10 @@
+CREATE TABLE IF NOT EXISTS torrust_user_transfer_stats (
+    tracker_key TEXT NOT NULL PRIMARY KEY,
+    user_id INTEGER NOT NULL,
+    uploaded INTEGER NOT NULL DEFAULT 0,
+    downloaded INTEGER NOT NULL DEFAULT 0,
+    date_updated TEXT NOT NULL,
+    FOREIGN KEY (user_id) REFERENCES torrust_users(user_id) ON DELETE CASCADE
+);
+
+CREATE INDEX IF NOT EXISTS torrust_user_transfer_stats_user_id_idx ON torrust_user_transfer_stats (user_id);
@@ -0,0 +1,4 @@
+CREATE TABLE IF NOT EXISTS torrust_upload_nonces (
+    nonce TEXT NOT NULL PRIMARY KEY,
+    date_expiry INTEGER NOT NULL
+);
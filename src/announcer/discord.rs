@@ -0,0 +1,27 @@
+//! Discord webhook target.
+use serde_json::json;
+use url::Url;
+
+use super::Error;
+use crate::utils::trace_context::PropagateTraceContext;
+
+/// It posts the message using a Discord channel webhook.
+///
+/// # Errors
+///
+/// It returns an error if the request fails or the webhook rejects it.
+pub async fn send(client: &reqwest::Client, webhook_url: &Url, message: &str) -> Result<(), Error> {
+    let response = client
+        .post(webhook_url.clone())
+        .with_trace_context()
+        .json(&json!({ "content": message }))
+        .send()
+        .await
+        .map_err(|e| Error::Request { error: e.to_string() })?;
+
+    if !response.status().is_success() {
+        return Err(Error::UnexpectedResponse);
+    }
+
+    Ok(())
+}
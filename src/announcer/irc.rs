@@ -0,0 +1,142 @@
+//! IRC channel target.
+//!
+//! The announcer does not keep a permanent connection. For each announcement
+//! it connects to the server, registers, joins the channel, sends the message
+//! and quits.
+use std::time::Duration;
+
+use tokio::io::{AsyncBufReadExt, AsyncWriteExt, BufReader};
+use tokio::net::TcpStream;
+use tokio::time::timeout;
+
+use super::Error;
+
+/// Maximum time to wait for the server to accept the registration.
+const REGISTRATION_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// Maximum length of an IRC message in bytes, including the command and the
+/// trailing `\r\n`.
+const MAX_MESSAGE_LENGTH: usize = 512;
+
+/// It sends the message to the IRC channel.
+///
+/// # Errors
+///
+/// It returns an error if it can't connect to the server or the server does
+/// not accept the registration.
+pub async fn send(server: &str, port: u16, nickname: &str, channel: &str, message: &str) -> Result<(), Error> {
+    let stream = TcpStream::connect((server, port)).await.map_err(request_error)?;
+
+    let (reader, mut writer) = stream.into_split();
+    let mut lines = BufReader::new(reader).lines();
+
+    writer
+        .write_all(format!("NICK {nickname}\r\nUSER {nickname} 0 * :{nickname}\r\n").as_bytes())
+        .await
+        .map_err(request_error)?;
+
+    // Wait for the welcome reply (`001`) answering the server pings.
+    timeout(REGISTRATION_TIMEOUT, async {
+        while let Some(line) = lines.next_line().await.map_err(request_error)? {
+            if let Some(token) = line.strip_prefix("PING ") {
+                writer
+                    .write_all(format!("PONG {token}\r\n").as_bytes())
+                    .await
+                    .map_err(request_error)?;
+            } else if line.split(' ').nth(1) == Some("001") {
+                return Ok(());
+            }
+        }
+
+        Err(Error::UnexpectedResponse)
+    })
+    .await
+    .map_err(|_| Error::UnexpectedResponse)??;
+
+    let mut commands = format!("JOIN {channel}\r\n");
+
+    for privmsg in privmsg_commands(channel, message) {
+        commands.push_str(&privmsg);
+    }
+
+    commands.push_str("QUIT\r\n");
+
+    writer.write_all(commands.as_bytes()).await.map_err(request_error)?;
+    writer.flush().await.map_err(request_error)?;
+
+    Ok(())
+}
+
+/// It builds one `PRIVMSG` command for each line of the message.
+///
+/// The message includes user content, like the torrent title. IRC commands
+/// are separated by line breaks, so any `\r`, `\n` or `\0` splits the
+/// message into lines instead of starting a new command. Lines are
+/// truncated so the commands don't exceed the IRC message length.
+fn privmsg_commands(channel: &str, message: &str) -> Vec<String> {
+    let prefix = format!("PRIVMSG {channel} :");
+    let max_line_length = MAX_MESSAGE_LENGTH.saturating_sub(prefix.len() + 2);
+
+    message
+        .split(['\r', '\n', '\0'])
+        .filter(|line| !line.is_empty())
+        .map(|line| format!("{prefix}{}\r\n", truncate(line, max_line_length)))
+        .collect()
+}
+
+/// It truncates the text to the maximum number of bytes, without splitting a
+/// character.
+fn truncate(text: &str, max_length: usize) -> &str {
+    if text.len() <= max_length {
+        return text;
+    }
+
+    let mut end = max_length;
+
+    while !text.is_char_boundary(end) {
+        end -= 1;
+    }
+
+    &text[..end]
+}
+
+#[allow(clippy::needless_pass_by_value)]
+fn request_error(e: std::io::Error) -> Error {
+    Error::Request { error: e.to_string() }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{privmsg_commands, MAX_MESSAGE_LENGTH};
+
+    #[test]
+    fn it_should_send_one_message_for_each_line() {
+        assert_eq!(
+            privmsg_commands("#torrents", "New torrent\n\nUbuntu 24.04"),
+            vec!["PRIVMSG #torrents :New torrent\r\n", "PRIVMSG #torrents :Ubuntu 24.04\r\n"]
+        );
+    }
+
+    #[test]
+    fn it_should_not_allow_injecting_commands_in_the_message() {
+        let commands = privmsg_commands("#torrents", "Title\rJOIN #other\0PRIVMSG NickServ :hi");
+
+        assert_eq!(
+            commands,
+            vec![
+                "PRIVMSG #torrents :Title\r\n",
+                "PRIVMSG #torrents :JOIN #other\r\n",
+                "PRIVMSG #torrents :PRIVMSG NickServ :hi\r\n"
+            ]
+        );
+    }
+
+    #[test]
+    fn it_should_truncate_the_lines_exceeding_the_message_length() {
+        let commands = privmsg_commands("#torrents", &"\u{e9}".repeat(MAX_MESSAGE_LENGTH));
+
+        assert_eq!(commands.len(), 1);
+        assert!(commands[0].len() <= MAX_MESSAGE_LENGTH);
+        assert!(commands[0].ends_with("\u{e9}\r\n"));
+    }
+}
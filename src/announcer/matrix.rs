@@ -0,0 +1,47 @@
+//! Matrix room target.
+//!
+//! It uses the [client-server API](https://spec.matrix.org/latest/client-server-api/)
+//! with the access token of an account already joined to the room.
+use serde_json::json;
+use url::Url;
+
+use super::Error;
+use crate::utils::clock;
+use crate::utils::trace_context::PropagateTraceContext;
+
+/// It sends a text message to the Matrix room.
+///
+/// # Errors
+///
+/// It returns an error if the request fails or the homeserver rejects it.
+pub async fn send(
+    client: &reqwest::Client,
+    homeserver_url: &Url,
+    access_token: &str,
+    room_id: &str,
+    message: &str,
+) -> Result<(), Error> {
+    // The transaction ID makes the request idempotent for the homeserver.
+    let transaction_id = format!("torrust-{}-{}", clock::now(), rand::random::<u32>());
+
+    let url = format!(
+        "{}/_matrix/client/v3/rooms/{}/send/m.room.message/{transaction_id}",
+        homeserver_url.as_str().trim_end_matches('/'),
+        urlencoding::encode(room_id)
+    );
+
+    let response = client
+        .put(url)
+        .with_trace_context()
+        .bearer_auth(access_token)
+        .json(&json!({ "msgtype": "m.text", "body": message }))
+        .send()
+        .await
+        .map_err(|e| Error::Request { error: e.to_string() })?;
+
+    if !response.status().is_success() {
+        return Err(Error::UnexpectedResponse);
+    }
+
+    Ok(())
+}
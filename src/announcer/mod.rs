@@ -0,0 +1,143 @@
+//! New-torrent announcer.
+//!
+//! It posts a message to external chat channels every time a new torrent is
+//! uploaded. Supported targets:
+//!
+//! - [`irc`]: an IRC channel.
+//! - [`matrix`]: a Matrix room using the client-server API.
+//! - [`discord`]: a Discord channel webhook.
+//!
+//! Channels are configured in the `[announcer]` section. Each channel has its
+//! own message template and can be restricted to some categories.
+//!
+//! The announcer is driven by the domain event outbox. See the
+//! [`announcer` cronjob](crate::console::cronjobs::announcer).
+pub mod discord;
+pub mod irc;
+pub mod matrix;
+
+use derive_more::{Display, Error};
+use tera::{Context, Tera};
+use tracing::error;
+
+use crate::config::{AnnouncerChannel, AnnouncerTarget};
+use crate::models::domain_event::TorrentUploaded;
+
+#[derive(Debug, Display, PartialEq, Eq, Error)]
+pub enum Error {
+    #[display("Invalid announcement template: {error}.")]
+    Template { error: String },
+
+    #[display("Announcement request error: {error}.")]
+    Request { error: String },
+
+    #[display("Unexpected announcement target response.")]
+    UnexpectedResponse,
+}
+
+pub struct Announcer {
+    client: reqwest::Client,
+    channels: Vec<AnnouncerChannel>,
+}
+
+impl Announcer {
+    #[must_use]
+    pub fn new(channels: Vec<AnnouncerChannel>) -> Self {
+        Self {
+            client: reqwest::Client::new(),
+            channels,
+        }
+    }
+
+    /// It announces the new torrent in all the channels accepting the torrent
+    /// category.
+    ///
+    /// Errors are only logged. A failing channel does not prevent the
+    /// announcement in the other channels.
+    pub async fn announce(&self, torrent: &TorrentUploaded) {
+        for channel in self.channels.iter().filter(|c| c.accepts_category(&torrent.category)) {
+            if let Err(e) = self.announce_in(channel, torrent).await {
+                error!(target: "announcer", "Failed to announce torrent #{}: {e}", torrent.torrent_id);
+            }
+        }
+    }
+
+    async fn announce_in(&self, channel: &AnnouncerChannel, torrent: &TorrentUploaded) -> Result<(), Error> {
+        let message = render(&channel.template, torrent)?;
+
+        match &channel.target {
+            AnnouncerTarget::Irc {
+                server,
+                port,
+                nickname,
+                channel,
+            } => irc::send(server, *port, nickname, channel, &message).await,
+            AnnouncerTarget::Matrix {
+                homeserver_url,
+                access_token,
+                room_id,
+            } => matrix::send(&self.client, homeserver_url, access_token, room_id, &message).await,
+            AnnouncerTarget::Discord { webhook_url } => discord::send(&self.client, webhook_url, &message).await,
+        }
+    }
+}
+
+/// It renders the announcement message for the torrent.
+///
+/// # Errors
+///
+/// It returns an error if the template is not valid.
+pub fn render(template: &str, torrent: &TorrentUploaded) -> Result<String, Error> {
+    let mut context = Context::new();
+
+    context.insert("torrent_id", &torrent.torrent_id);
+    context.insert("info_hash", &torrent.info_hash);
+    context.insert("title", &torrent.title);
+    context.insert("category", &torrent.category);
+    context.insert("uploader", &torrent.uploader);
+
+    Tera::one_off(template, &context, false).map_err(|e| Error::Template { error: e.to_string() })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::render;
+    use crate::config::{AnnouncerChannel, AnnouncerTarget};
+    use crate::models::domain_event::TorrentUploaded;
+
+    fn torrent_uploaded() -> TorrentUploaded {
+        TorrentUploaded {
+            torrent_id: 1,
+            info_hash: "9e0217d0fa71c87332cd8bf9dbeabcb2c2cf3c4d".to_string(),
+            title: "Ubuntu".to_string(),
+            category: "software".to_string(),
+            uploader: "admin".to_string(),
+        }
+    }
+
+    #[test]
+    fn it_should_render_the_announcement_template() {
+        assert_eq!(
+            render(
+                "New torrent: {{ title }} [{{ category }}] uploaded by {{ uploader }}",
+                &torrent_uploaded()
+            )
+            .unwrap(),
+            "New torrent: Ubuntu [software] uploaded by admin"
+        );
+    }
+
+    #[test]
+    fn it_should_route_announcements_by_category() {
+        let channel = AnnouncerChannel {
+            target: AnnouncerTarget::Discord {
+                webhook_url: "https://discord.com/api/webhooks/1/token".parse().unwrap(),
+            },
+            categories: vec!["software".to_string()],
+            template: String::new(),
+        };
+
+        assert!(channel.accepts_category("software"));
+        assert!(!channel.accepts_category("movies"));
+    }
+}
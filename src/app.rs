@@ -1,28 +1,79 @@
 use std::net::SocketAddr;
 use std::sync::Arc;
+use std::time::Duration;
 
+use derive_more::{Display, Error};
 use tokio::task::JoinHandle;
-use tracing::info;
+use tracing::{error, info};
 
 use crate::bootstrap::logging;
 use crate::cache::image::manager::ImageCacheService;
+use crate::cache::query::QueryCache;
 use crate::common::AppData;
-use crate::config::validator::Validator;
+use crate::config::validator::{check_services, ValidationError, Validator};
 use crate::config::Configuration;
-use crate::databases::database;
+use crate::databases::database::{self, Driver, PoolSettings};
+use crate::databases::encryption::Cipher;
+use crate::mailer::digest::Digester;
+use crate::services::antispam::{self, DbBannedPatternRepository};
+use crate::services::api_usage::{self, DbApiUsageRepository};
+use crate::services::audit::{self, DbAuditLogRepository};
 use crate::services::authentication::{DbUserAuthenticationRepository, JsonWebToken, Service};
 use crate::services::authorization::{CasbinConfiguration, CasbinEnforcer};
-use crate::services::category::{self, DbCategoryRepository};
+use crate::services::bookmark::{self, DbBookmarkRepository};
+use crate::services::bulk_import::session::DbImportSessionRepository;
+use crate::services::bulk_mail::DbEmailQueueRepository;
+use crate::services::category::{self, DbCategoryModeratorRepository, DbCategoryRepository};
+use crate::services::category_field::{self, DbCategoryFieldRepository};
+use crate::services::collection::{self, DbCollectionRepository};
+use crate::services::comment::{self, DbCommentRepository};
+use crate::services::domain_events::DbDomainEventRepository;
+use crate::services::download_statistics::{self, DbDownloadStatisticsRepository};
+use crate::services::enrichment::{self, DbTorrentMetadataRepository};
+use crate::services::image_upload::{self, DbImageRepository};
+use crate::services::inactivity::{self, DbInactivityRepository};
+use crate::services::live_events::{self, Broadcaster};
+use crate::services::ratio::{self, DbTransferStatsRepository};
+use crate::services::report::{self, DbReportRepository};
+use crate::services::reputation::{self, DbReputationRepository};
+use crate::services::saved_search::{self, DbSavedSearchRepository};
+use crate::services::seeding_rewards::{self, DbSeedingRewardsRepository};
+use crate::services::session::{self, DbUserSessionRepository};
+use crate::services::signed_upload::DbUploadNonceRepository;
+use crate::services::subscription::{self, DbSubscriptionRepository};
+use crate::services::sync::{self, DbSyncRepository};
 use crate::services::tag::{self, DbTagRepository};
+use crate::services::torrent::attachment::{self, DbTorrentAttachmentRepository};
+use crate::services::torrent::deduplication::{self, DbTorrentDuplicateRepository};
+use crate::services::torrent::description_image::{self, DbDescriptionImageRepository};
+use crate::services::torrent::file_group::{self, DbTorrentFileGroupRepository};
+use crate::services::torrent::health::{self, DbTorrentHealthRepository};
+use crate::services::torrent::preview::{self, DbTorrentPreviewRepository};
+use crate::services::torrent::quota::{self, DbUploadQuotaRepository};
+use crate::services::torrent::trash::{self, DbTorrentTrashRepository};
+use crate::services::torrent::web_seed::{self, DbTorrentWebSeedRepository};
 use crate::services::torrent::{
-    DbCanonicalInfoHashGroupRepository, DbTorrentAnnounceUrlRepository, DbTorrentFileRepository, DbTorrentInfoRepository,
-    DbTorrentListingGenerator, DbTorrentRepository, DbTorrentTagRepository,
+    DbCanonicalInfoHashGroupRepository, DbMagnetUploadRepository, DbTorrentAnnounceUrlRepository, DbTorrentFileRepository,
+    DbTorrentInfoRepository, DbTorrentListingGenerator, DbTorrentRepository, DbTorrentTagRepository,
 };
-use crate::services::user::{self, DbBannedUserList, DbUserProfileRepository, DbUserRepository, Repository};
-use crate::services::{about, authorization, proxy, settings, torrent};
+use crate::services::torrent_rules::{self, DbTorrentRuleRepository};
+use crate::services::trending_tags::DbTagActivityRepository;
+use crate::services::user::{
+    self, CachedUserRepository, DbBannedUserList, DbUserProfileRepository, DbUserRepository, Repository,
+};
+use crate::services::{
+    about, announce_key, authorization, bulk_import, bulk_mail, health, listing_snapshot, proxy, retention, search, settings,
+    signed_upload, statistics_importer, torrent, trending_tags, widget,
+};
+use crate::storage::s3::Bucket;
+use crate::storage::torrent_file;
+use crate::tracker::alerts::Alerter;
 use crate::tracker::statistics_importer::StatisticsImporter;
+use crate::web::api::server::rate_limit::RateLimiter;
 use crate::web::api::server::signals::Halted;
 use crate::web::api::server::v1::auth::Authentication;
+use crate::web::api::server::v1::deprecation::Deprecations;
+use crate::web::api::server::v1::etag::ModificationTimes;
 use crate::web::api::Version;
 use crate::{console, mailer, tracker, web};
 
@@ -31,18 +82,47 @@ pub struct Running {
     pub api_server: JoinHandle<std::result::Result<(), std::io::Error>>,
     pub api_server_halt_task: tokio::sync::oneshot::Sender<Halted>,
     pub tracker_data_importer_handle: tokio::task::JoinHandle<()>,
+    /// The background jobs to stop after the API server. Refer to
+    /// [`shutdown`](crate::console::shutdown).
+    pub background_jobs: console::shutdown::Coordinator,
+}
+
+/// Errors that prevent the application from starting.
+#[derive(Debug, Display, Error)]
+pub enum Error {
+    #[display(
+        "There are {pending} pending database migrations. Apply them with `cargo run --bin migrate up` or enable \
+         `database.auto_migrate`."
+    )]
+    PendingMigrations { pending: usize },
+
+    #[display("Invalid settings: {source}")]
+    InvalidSettings { source: ValidationError },
+
+    #[display("The `{service}` service failed the active checks, disable `config.active_checks` to skip them: {source}")]
+    UnavailableService { service: String, source: ValidationError },
 }
 
 /// Runs the application.
 ///
+/// # Errors
+///
+/// It returns an error if:
+///
+/// - The settings are invalid.
+/// - A service fails the active checks, when `config.active_checks` is
+///   enabled.
+/// - There are pending database migrations and the `database.auto_migrate`
+///   option is not enabled.
+///
 /// # Panics
 ///
 /// It panics if there is an error connecting to the database.
 #[allow(clippy::too_many_lines)]
-pub async fn run(configuration: Configuration, api_version: &Version) -> Running {
-    let threshold = configuration.settings.read().await.logging.threshold.clone();
+pub async fn run(configuration: Configuration, api_version: &Version) -> Result<Running, Error> {
+    let logging = configuration.settings.read().await.logging.clone();
 
-    logging::setup(&threshold);
+    logging::setup(&logging);
 
     log_configuration(&configuration).await;
 
@@ -53,18 +133,77 @@ pub async fn run(configuration: Configuration, api_version: &Version) -> Running
 
     let settings = configuration.settings.read().await;
 
-    settings.validate().expect("invalid settings");
+    settings.validate().map_err(|source| Error::InvalidSettings { source })?;
+
+    if settings.config.active_checks {
+        check_services(&settings).await.map_err(|source| Error::UnavailableService {
+            service: source.service().unwrap_or_default().to_string(),
+            source,
+        })?;
+    }
 
     // From [database] config
     let database_connect_url = settings.database.connect_url.clone().to_string();
+    let opt_database_encryption = settings.database.encryption.clone();
+    let database_auto_migrate = settings.database.auto_migrate;
+    let database_replica_urls: Vec<String> = settings.database.replica_urls.iter().map(ToString::to_string).collect();
+    let database_max_replica_lag = Duration::from_secs(settings.database.max_replica_lag_seconds);
+    let database_pool_settings = PoolSettings::from(&settings.database);
+    let database_maintenance_interval_seconds = settings.database.sqlite.maintenance_interval_seconds;
     // From [importer] config
     let importer_torrent_info_update_interval = settings.tracker_statistics_importer.torrent_info_update_interval;
     let importer_port = settings.tracker_statistics_importer.port;
+    let importer_alerts = settings.tracker_statistics_importer.alerts.clone();
     // From [net] config
     let config_bind_address = settings.net.bind_address;
     let opt_net_tsl = settings.net.tsl.clone();
+    let opt_rate_limits = settings.net.rate_limits.clone();
+    let shutdown_timeout = Duration::from_secs(settings.net.shutdown_timeout_seconds);
     // Unstable config
     let unstable = settings.unstable.clone();
+    // From [announcer] config
+    let opt_announcer = settings.announcer.clone();
+    // From [image_cache] config
+    let image_cache_eviction_interval_seconds = settings.image_cache.eviction_interval_seconds;
+    // From [listing_snapshots] config
+    let opt_listing_snapshots = settings.listing_snapshots.clone();
+    // From [cache] config
+    let opt_cache = settings.cache.clone();
+    let opt_mirror_sync = settings.mirror_sync.clone();
+    // From [trending_tags] config
+    let opt_trending_tags = settings.trending_tags.clone();
+    // From [torrent.trash] config
+    let trash_purge_interval_seconds = settings
+        .torrent
+        .as_ref()
+        .map(|torrent| torrent.trash.clone())
+        .unwrap_or_default()
+        .purge_interval_seconds;
+    // From [torrent.magnet_uploads] config
+    let opt_magnet_uploads = settings.torrent.as_ref().and_then(|torrent| torrent.magnet_uploads.clone());
+    // From [torrent.health] config
+    let opt_torrent_health = settings.torrent.as_ref().and_then(|torrent| torrent.health.clone());
+    // From [integrations.seeding_rewards] config
+    let opt_seeding_rewards = settings
+        .integrations
+        .as_ref()
+        .and_then(|integrations| integrations.seeding_rewards.clone());
+    // From [mail.digest] config
+    let opt_mail_digest = settings.mail.digest.clone();
+    // From [mail.queue] config
+    let mail_queue = settings.mail.queue.clone();
+    // From [inactivity] config
+    let opt_inactivity = settings.inactivity.clone();
+    // From [retention] config
+    let opt_retention = settings.retention.clone();
+    // From [torrent_rules] config
+    let opt_torrent_rules = settings.torrent_rules.clone();
+    // From [ratio] config
+    let opt_ratio = settings.ratio.clone();
+    // From [storage.s3] config
+    let opt_s3 = settings.storage.as_ref().and_then(|storage| storage.s3.clone());
+    // From [storage.torrent_files] config
+    let storage = settings.storage.clone().unwrap_or_default();
 
     // IMPORTANT: drop settings before starting server to avoid read locks that
     // leads to requests hanging.
@@ -72,24 +211,91 @@ pub async fn run(configuration: Configuration, api_version: &Version) -> Running
 
     // Build app dependencies
 
-    let database = Arc::new(database::connect(&database_connect_url).await.expect("Database error."));
+    let cipher = opt_database_encryption
+        .map(|encryption| Arc::new(Cipher::from_config(&encryption).expect("invalid database encryption key")));
+    let database = Arc::new(
+        database::connect_with_replicas(
+            &database_connect_url,
+            &database_replica_urls,
+            database_max_replica_lag,
+            &database_pool_settings,
+            cipher,
+        )
+        .await
+        .expect("Database error."),
+    );
+
+    let pending_migrations = database
+        .get_migrations()
+        .await
+        .expect("Database error.")
+        .iter()
+        .filter(|migration| !migration.applied)
+        .count();
+
+    if pending_migrations > 0 {
+        if !database_auto_migrate {
+            return Err(Error::PendingMigrations {
+                pending: pending_migrations,
+            });
+        }
+
+        database.run_migrations().await.expect("Could not run database migrations.");
+
+        info!("Applied {pending_migrations} pending database migrations.");
+    }
     let json_web_token = Arc::new(JsonWebToken::new(configuration.clone()));
-    let auth = Arc::new(Authentication::new(json_web_token.clone()));
+    let rate_limiter = Arc::new(RateLimiter::new(opt_rate_limits));
+    let deprecations = Arc::new(Deprecations::default());
+    let live_events = Arc::new(Broadcaster::default());
+    let query_cache = Arc::new(QueryCache::new(opt_cache.as_ref()));
 
     // Repositories
-    let category_repository = Arc::new(DbCategoryRepository::new(database.clone()));
-    let tag_repository = Arc::new(DbTagRepository::new(database.clone()));
-    let user_repository: Arc<Box<dyn Repository>> = Arc::new(Box::new(DbUserRepository::new(database.clone())));
+    let category_repository = Arc::new(DbCategoryRepository::new(database.clone(), query_cache.clone()));
+    let category_moderator_repository = Arc::new(DbCategoryModeratorRepository::new(database.clone()));
+    let tag_repository = Arc::new(DbTagRepository::new(database.clone(), query_cache.clone()));
+    let user_repository: Arc<Box<dyn Repository>> = Arc::new(Box::new(CachedUserRepository::new(Box::new(
+        DbUserRepository::new(database.clone()),
+    ))));
     let user_authentication_repository = Arc::new(DbUserAuthenticationRepository::new(database.clone()));
     let user_profile_repository = Arc::new(DbUserProfileRepository::new(database.clone()));
-    let torrent_repository = Arc::new(DbTorrentRepository::new(database.clone()));
+    let torrent_file_store = torrent_file::from_config(&storage, database.clone());
+    let torrent_repository = Arc::new(DbTorrentRepository::new(database.clone(), torrent_file_store.clone()));
     let canonical_info_hash_group_repository = Arc::new(DbCanonicalInfoHashGroupRepository::new(database.clone()));
     let torrent_info_repository = Arc::new(DbTorrentInfoRepository::new(database.clone()));
     let torrent_file_repository = Arc::new(DbTorrentFileRepository::new(database.clone()));
     let torrent_announce_url_repository = Arc::new(DbTorrentAnnounceUrlRepository::new(database.clone()));
     let torrent_tag_repository = Arc::new(DbTorrentTagRepository::new(database.clone()));
-    let torrent_listing_generator = Arc::new(DbTorrentListingGenerator::new(database.clone()));
+    let torrent_listing_generator = Arc::new(DbTorrentListingGenerator::new(database.clone(), query_cache));
     let banned_user_list = Arc::new(DbBannedUserList::new(database.clone()));
+    let domain_event_repository = Arc::new(DbDomainEventRepository::new(database.clone()));
+    let comment_repository = Arc::new(DbCommentRepository::new(database.clone()));
+    let report_repository = Arc::new(DbReportRepository::new(database.clone()));
+    let bookmark_repository = Arc::new(DbBookmarkRepository::new(database.clone()));
+    let saved_search_repository = Arc::new(DbSavedSearchRepository::new(database.clone()));
+    let subscription_repository = Arc::new(DbSubscriptionRepository::new(database.clone()));
+    let download_statistics_repository = Arc::new(DbDownloadStatisticsRepository::new(database.clone()));
+    let magnet_upload_repository = Arc::new(DbMagnetUploadRepository::new(database.clone()));
+    let email_queue_repository = Arc::new(DbEmailQueueRepository::new(database.clone()));
+    let inactivity_repository = Arc::new(DbInactivityRepository::new(database.clone()));
+    let image_repository = Arc::new(DbImageRepository::new(database.clone()));
+    let collection_repository = Arc::new(DbCollectionRepository::new(database.clone()));
+    let upload_quota_repository = Arc::new(DbUploadQuotaRepository::new(database.clone()));
+    let reputation_repository = Arc::new(DbReputationRepository::new(database.clone()));
+    let transfer_stats_repository = Arc::new(DbTransferStatsRepository::new(database.clone()));
+    let api_usage_repository = Arc::new(DbApiUsageRepository::new(database.clone()));
+    let sync_repository = Arc::new(DbSyncRepository::new(database.clone()));
+    let audit_log_repository = Arc::new(DbAuditLogRepository::new(database.clone()));
+    let torrent_preview_repository = Arc::new(DbTorrentPreviewRepository::new(database.clone()));
+    let torrent_file_group_repository = Arc::new(DbTorrentFileGroupRepository::new(database.clone()));
+    let description_image_repository = Arc::new(DbDescriptionImageRepository::new(database.clone()));
+    let torrent_attachment_repository = Arc::new(DbTorrentAttachmentRepository::new(database.clone()));
+    let torrent_web_seed_repository = Arc::new(DbTorrentWebSeedRepository::new(database.clone()));
+    let torrent_metadata_repository = Arc::new(DbTorrentMetadataRepository::new(database.clone()));
+    let category_field_repository = Arc::new(DbCategoryFieldRepository::new(database.clone()));
+    let torrent_duplicate_repository = Arc::new(DbTorrentDuplicateRepository::new(database.clone(), torrent_file_store));
+    let torrent_trash_repository = Arc::new(DbTorrentTrashRepository::new(database.clone()));
+    let torrent_health_repository = Arc::new(DbTorrentHealthRepository::new(database.clone()));
     let casbin_enforcer = Arc::new(
         if let Some(casbin) = unstable
             .as_ref()
@@ -103,22 +309,119 @@ pub async fn run(configuration: Configuration, api_version: &Version) -> Running
     );
 
     // Services
-    let authorization_service = Arc::new(authorization::Service::new(user_repository.clone(), casbin_enforcer.clone()));
+    let authorization_service = Arc::new(authorization::Service::new(
+        user_repository.clone(),
+        category_moderator_repository.clone(),
+        casbin_enforcer.clone(),
+    ));
+    let audit_service = Arc::new(audit::Service::new(audit_log_repository, authorization_service.clone()));
+    let session_service = Arc::new(session::Service::new(
+        Arc::new(DbUserSessionRepository::new(database.clone())),
+        authorization_service.clone(),
+    ));
+    let auth = Arc::new(Authentication::new(json_web_token.clone(), session_service.clone()));
     let tracker_service = Arc::new(tracker::service::Service::new(configuration.clone(), database.clone()).await);
     let tracker_statistics_importer =
         Arc::new(StatisticsImporter::new(configuration.clone(), tracker_service.clone(), database.clone()).await);
     let mailer_service = Arc::new(mailer::Service::new(configuration.clone()).await);
-    let image_cache_service: Arc<ImageCacheService> = Arc::new(ImageCacheService::new(configuration.clone()).await);
+    let image_cache_service: Arc<ImageCacheService> =
+        Arc::new(ImageCacheService::new(configuration.clone(), database.clone()).await);
     let category_service = Arc::new(category::Service::new(
         category_repository.clone(),
+        category_moderator_repository.clone(),
+        authorization_service.clone(),
+        audit_service.clone(),
+    ));
+    let category_field_service = Arc::new(category_field::Service::new(
+        category_repository.clone(),
+        category_field_repository.clone(),
         authorization_service.clone(),
+        audit_service.clone(),
+    ));
+    let tag_service = Arc::new(tag::Service::new(
+        tag_repository.clone(),
+        torrent_tag_repository.clone(),
+        authorization_service.clone(),
+        audit_service.clone(),
     ));
-    let tag_service = Arc::new(tag::Service::new(tag_repository.clone(), authorization_service.clone()));
     let proxy_service = Arc::new(proxy::Service::new(
         image_cache_service.clone(),
         authorization_service.clone(),
     ));
-    let settings_service = Arc::new(settings::Service::new(configuration.clone(), authorization_service.clone()));
+    let settings_service = Arc::new(settings::Service::new(
+        configuration.clone(),
+        authorization_service.clone(),
+        audit_service.clone(),
+    ));
+    let search_service = Arc::new(search::Service::new(configuration.clone(), database.clone()).await);
+    let upload_quota_service = Arc::new(quota::Service::new(
+        configuration.clone(),
+        upload_quota_repository.clone(),
+        authorization_service.clone(),
+    ));
+    let reputation_service = Arc::new(reputation::Service::new(
+        configuration.clone(),
+        reputation_repository,
+        authorization_service.clone(),
+        audit_service.clone(),
+    ));
+    let ratio_service = Arc::new(ratio::Service::new(
+        configuration.clone(),
+        tracker_service.clone(),
+        transfer_stats_repository,
+    ));
+    let announce_key_service = Arc::new(announce_key::Service::new(
+        configuration.clone(),
+        tracker_service.clone(),
+        authorization_service.clone(),
+    ));
+    let torrent_preview_service = Arc::new(preview::Service::new(
+        configuration.clone(),
+        user_repository.clone(),
+        torrent_repository.clone(),
+        torrent_listing_generator.clone(),
+        torrent_preview_repository.clone(),
+        authorization_service.clone(),
+    ));
+    let description_image_service = Arc::new(description_image::Service::new(
+        configuration.clone(),
+        user_repository.clone(),
+        torrent_listing_generator.clone(),
+        description_image_repository,
+        image_cache_service.clone(),
+        authorization_service.clone(),
+    ));
+    let torrent_attachment_service = Arc::new(attachment::Service::new(
+        configuration.clone(),
+        user_repository.clone(),
+        torrent_listing_generator.clone(),
+        torrent_attachment_repository,
+        authorization_service.clone(),
+    ));
+    let torrent_web_seed_service = Arc::new(web_seed::Service::new(
+        user_repository.clone(),
+        torrent_listing_generator.clone(),
+        torrent_web_seed_repository,
+        authorization_service.clone(),
+    ));
+    let torrent_file_group_service = Arc::new(file_group::Service::new(
+        user_repository.clone(),
+        torrent_listing_generator.clone(),
+        torrent_file_repository.clone(),
+        torrent_file_group_repository.clone(),
+        authorization_service.clone(),
+    ));
+    let torrent_duplicate_service = Arc::new(deduplication::Service::new(
+        configuration.clone(),
+        torrent_duplicate_repository,
+        authorization_service.clone(),
+    ));
+    let antispam_service = Arc::new(antispam::Service::new(
+        configuration.clone(),
+        Arc::new(DbBannedPatternRepository::new(database.clone())),
+        authorization_service.clone(),
+        audit_service.clone(),
+    ));
     let torrent_index = Arc::new(torrent::Index::new(
         configuration.clone(),
         tracker_statistics_importer.clone(),
@@ -133,6 +436,48 @@ pub async fn run(configuration: Configuration, api_version: &Version) -> Running
         torrent_tag_repository.clone(),
         torrent_listing_generator.clone(),
         authorization_service.clone(),
+        search_service.clone(),
+        domain_event_repository.clone(),
+        bookmark_repository.clone(),
+        magnet_upload_repository,
+        torrent_preview_repository.clone(),
+        torrent_file_group_repository.clone(),
+        upload_quota_service.clone(),
+        live_events.clone(),
+        audit_service.clone(),
+        torrent_duplicate_service.clone(),
+        torrent_trash_repository.clone(),
+        antispam_service.clone(),
+        torrent_metadata_repository.clone(),
+        category_field_repository,
+        reputation_service.clone(),
+        ratio_service.clone(),
+    ));
+    let torrent_metadata_service = Arc::new(enrichment::Service::new(
+        configuration.clone(),
+        user_repository.clone(),
+        torrent_listing_generator.clone(),
+        torrent_metadata_repository,
+        search_service.clone(),
+        authorization_service.clone(),
+    ));
+    let torrent_trash_service = Arc::new(trash::Service::new(
+        configuration.clone(),
+        torrent_trash_repository,
+        torrent_repository.clone(),
+        torrent_listing_generator.clone(),
+        tracker_service.clone(),
+        search_service.clone(),
+        authorization_service.clone(),
+        audit_service.clone(),
+    ));
+    let torrent_health_service = Arc::new(health::Service::new(
+        configuration.clone(),
+        torrent_health_repository,
+        torrent_repository.clone(),
+        torrent_listing_generator.clone(),
+        authorization_service.clone(),
+        audit_service.clone(),
     ));
     let registration_service = Arc::new(user::RegistrationService::new(
         configuration.clone(),
@@ -142,13 +487,21 @@ pub async fn run(configuration: Configuration, api_version: &Version) -> Running
     ));
     let profile_service = Arc::new(user::ProfileService::new(
         configuration.clone(),
+        mailer_service.clone(),
+        user_repository.clone(),
         user_authentication_repository.clone(),
+        user_profile_repository.clone(),
+        torrent_listing_generator.clone(),
         authorization_service.clone(),
+        reputation_service.clone(),
+        ratio_service.clone(),
     ));
     let ban_service = Arc::new(user::BanService::new(
+        user_repository.clone(),
         user_profile_repository.clone(),
         banned_user_list.clone(),
         authorization_service.clone(),
+        audit_service.clone(),
     ));
     let authentication_service = Arc::new(Service::new(
         configuration.clone(),
@@ -156,10 +509,163 @@ pub async fn run(configuration: Configuration, api_version: &Version) -> Running
         user_repository.clone(),
         user_profile_repository.clone(),
         user_authentication_repository.clone(),
+        session_service.clone(),
     ));
 
     let about_service = Arc::new(about::Service::new(authorization_service.clone()));
 
+    let signed_upload_service = Arc::new(signed_upload::Service::new(
+        configuration.clone(),
+        Arc::new(DbUploadNonceRepository::new(database.clone())),
+    ));
+
+    let comment_service = Arc::new(comment::Service::new(
+        comment_repository.clone(),
+        torrent_listing_generator.clone(),
+        authorization_service.clone(),
+        audit_service.clone(),
+    ));
+
+    let report_service = Arc::new(report::Service::new(
+        report_repository.clone(),
+        torrent_listing_generator.clone(),
+        torrent_index.clone(),
+        ban_service.clone(),
+        authorization_service.clone(),
+        live_events.clone(),
+        audit_service.clone(),
+    ));
+
+    let statistics_importer_service = Arc::new(statistics_importer::Service::new(
+        tracker_statistics_importer.clone(),
+        authorization_service.clone(),
+    ));
+
+    let bookmark_service = Arc::new(bookmark::Service::new(
+        configuration.clone(),
+        bookmark_repository.clone(),
+        saved_search_repository.clone(),
+        torrent_listing_generator.clone(),
+        authorization_service.clone(),
+    ));
+
+    let subscription_service = Arc::new(subscription::Service::new(
+        subscription_repository.clone(),
+        category_repository.clone(),
+        tag_repository.clone(),
+        authorization_service.clone(),
+    ));
+
+    let download_statistics_service = Arc::new(download_statistics::Service::new(
+        configuration.clone(),
+        download_statistics_repository.clone(),
+        authorization_service.clone(),
+    ));
+
+    let bulk_import_service = Arc::new(bulk_import::Service::new(
+        torrent_index.clone(),
+        category_repository.clone(),
+        tag_repository.clone(),
+        authorization_service.clone(),
+    ));
+
+    let seeding_rewards_service = Arc::new(seeding_rewards::Service::new(
+        Arc::new(DbSeedingRewardsRepository::new(database.clone())),
+        domain_event_repository.clone(),
+        authorization_service.clone(),
+    ));
+
+    let import_session_service = Arc::new(bulk_import::session::Service::new(
+        Arc::new(DbImportSessionRepository::new(database.clone())),
+        torrent_index.clone(),
+        category_repository.clone(),
+        tag_repository.clone(),
+        authorization_service.clone(),
+    ));
+
+    let bulk_mail_service = Arc::new(bulk_mail::Service::new(
+        configuration.clone(),
+        mailer_service.clone(),
+        email_queue_repository.clone(),
+        authorization_service.clone(),
+    ));
+
+    let inactivity_service = Arc::new(inactivity::Service::new(
+        configuration.clone(),
+        inactivity_repository,
+        email_queue_repository.clone(),
+        authorization_service.clone(),
+    ));
+
+    let live_events_service = Arc::new(live_events::Service::new(live_events, authorization_service.clone()));
+
+    let image_upload_service = Arc::new(image_upload::Service::new(
+        opt_s3.as_ref().map(Bucket::new),
+        image_repository,
+        authorization_service.clone(),
+    ));
+
+    let collection_service = Arc::new(collection::Service::new(
+        configuration.clone(),
+        collection_repository.clone(),
+        torrent_listing_generator.clone(),
+        authorization_service.clone(),
+    ));
+
+    let retention_service = Arc::new(retention::Service::new(configuration.clone(), database.clone()));
+
+    let api_usage_service = Arc::new(api_usage::Service::new(
+        rate_limiter.clone(),
+        api_usage_repository,
+        authorization_service.clone(),
+    ));
+
+    let widget_service = Arc::new(widget::Service::new(
+        configuration.clone(),
+        torrent_listing_generator.clone(),
+        authorization_service.clone(),
+    ));
+
+    let sync_service = Arc::new(sync::Service::new(
+        configuration.clone(),
+        sync_repository,
+        authorization_service.clone(),
+    ));
+
+    let listing_snapshot_service =
+        Arc::new(listing_snapshot::Service::new(configuration.clone(), torrent_index.clone(), category_repository.clone()).await);
+    let trending_tags_service = Arc::new(
+        trending_tags::Service::new(
+            configuration.clone(),
+            Arc::new(DbTagActivityRepository::new(database.clone())),
+            authorization_service.clone(),
+        )
+        .await,
+    );
+
+    let torrent_rules_service = Arc::new(torrent_rules::Service::new(
+        configuration.clone(),
+        Arc::new(DbTorrentRuleRepository::new(database.clone())),
+        torrent_repository.clone(),
+        torrent_listing_generator.clone(),
+        tag_repository.clone(),
+        email_queue_repository.clone(),
+        audit_service.clone(),
+        authorization_service.clone(),
+    ));
+
+    let saved_search_service = Arc::new(saved_search::Service::new(
+        configuration.clone(),
+        saved_search_repository.clone(),
+        torrent_tag_repository.clone(),
+        torrent_listing_generator.clone(),
+        user_profile_repository.clone(),
+        email_queue_repository.clone(),
+        authorization_service.clone(),
+    ));
+
+    let health_service = Arc::new(health::Service::new(configuration.clone(), database.clone()));
+
     // Build app container
 
     let app_data = Arc::new(AppData::new(
@@ -170,50 +676,278 @@ pub async fn run(configuration: Configuration, api_version: &Version) -> Running
         authentication_service,
         tracker_service.clone(),
         tracker_statistics_importer.clone(),
-        mailer_service,
-        image_cache_service,
+        mailer_service.clone(),
+        image_cache_service.clone(),
         category_repository,
         tag_repository,
         user_repository,
         user_authentication_repository,
-        user_profile_repository,
+        user_profile_repository.clone(),
         torrent_repository,
         canonical_info_hash_group_repository,
         torrent_info_repository,
         torrent_file_repository,
         torrent_announce_url_repository,
-        torrent_tag_repository,
-        torrent_listing_generator,
+        torrent_tag_repository.clone(),
+        torrent_listing_generator.clone(),
         banned_user_list,
         category_service,
         tag_service,
         proxy_service,
-        settings_service,
-        torrent_index,
+        settings_service.clone(),
+        torrent_index.clone(),
         registration_service,
         profile_service,
         ban_service,
         about_service,
+        signed_upload_service,
+        domain_event_repository.clone(),
+        comment_repository,
+        comment_service,
+        report_repository,
+        report_service,
+        statistics_importer_service,
+        listing_snapshot_service.clone(),
+        bookmark_repository,
+        bookmark_service,
+        subscription_repository.clone(),
+        subscription_service,
+        download_statistics_repository,
+        download_statistics_service,
+        rate_limiter,
+        deprecations,
+        bulk_import_service,
+        bulk_mail_service,
+        inactivity_service.clone(),
+        image_upload_service,
+        live_events_service,
+        collection_repository,
+        collection_service.clone(),
+        retention_service.clone(),
+        upload_quota_repository,
+        upload_quota_service,
+        category_moderator_repository,
+        api_usage_service.clone(),
+        widget_service,
+        sync_service.clone(),
+        audit_service,
+        torrent_preview_repository,
+        torrent_preview_service,
+        announce_key_service,
+        torrent_file_group_repository,
+        torrent_file_group_service,
+        trending_tags_service.clone(),
+        torrent_rules_service.clone(),
+        saved_search_service.clone(),
+        import_session_service,
+        Arc::new(ModificationTimes::default()),
+        seeding_rewards_service.clone(),
+        description_image_service,
+        torrent_attachment_service,
+        torrent_duplicate_service,
+        torrent_trash_service.clone(),
+        torrent_health_service.clone(),
+        antispam_service,
+        session_service,
+        health_service,
+        torrent_web_seed_service,
+        torrent_metadata_service,
+        category_field_service,
+        reputation_service,
     ));
 
+    // Rebuild the search index in the background.
+    if search_service.is_enabled() {
+        tokio::spawn(async move {
+            if let Err(e) = search_service.rebuild().await {
+                error!("Failed to rebuild the search index: {e}");
+            }
+        });
+    }
+
+    // Stop the background jobs on SIGTERM.
+    let background_jobs = console::shutdown::Coordinator::new(shutdown_timeout);
+
+    background_jobs.request_on_signal();
+
     // Start cronjob to import tracker torrent data and updating
     // seeders and leechers info.
     let tracker_statistics_importer_handle = console::cronjobs::tracker_statistics_importer::start(
         importer_port,
         importer_torrent_info_update_interval,
         &tracker_statistics_importer,
+        importer_alerts.map(|alerts| Alerter::new(alerts, mailer_service.clone())),
+        background_jobs.handle(),
     );
 
+    // Start cronjob to post new-torrent announcements.
+    if let Some(announcer) = &opt_announcer {
+        drop(console::cronjobs::announcer::start(
+            announcer,
+            &domain_event_repository,
+            background_jobs.handle(),
+        ));
+    }
+
+    // Start cronjob to publish the seeding summaries.
+    if let Some(seeding_rewards) = &opt_seeding_rewards {
+        drop(console::cronjobs::seeding_rewards::start(
+            seeding_rewards,
+            &seeding_rewards_service,
+            &domain_event_repository,
+        ));
+    }
+
+    // Start cronjob to import the users' transfer stats from the tracker.
+    if let Some(ratio) = &opt_ratio {
+        drop(console::cronjobs::transfer_stats::start(
+            ratio.import_interval_seconds,
+            &ratio_service,
+        ));
+    }
+
+    // Start cronjob to refresh the listing snapshots.
+    if let Some(listing_snapshots) = &opt_listing_snapshots {
+        drop(console::cronjobs::listing_snapshots::start(
+            listing_snapshots.refresh_interval_seconds,
+            &listing_snapshot_service,
+        ));
+    }
+
+    // Start cronjob to rank the trending tags.
+    if let Some(trending_tags) = &opt_trending_tags {
+        drop(console::cronjobs::trending_tags::start(
+            trending_tags.refresh_interval_seconds,
+            &trending_tags_service,
+        ));
+    }
+
+    // Start cronjob to detect the torrent changes for the mirrors.
+    if let Some(mirror_sync) = &opt_mirror_sync {
+        drop(console::cronjobs::mirror_sync::start(
+            mirror_sync.refresh_interval_seconds,
+            &sync_service,
+        ));
+    }
+
+    // Start cronjob to resolve the torrents uploaded as magnet links.
+    if let Some(magnet_uploads) = &opt_magnet_uploads {
+        drop(console::cronjobs::magnet_resolver::start(
+            magnet_uploads.resolve_interval_seconds,
+            &torrent_index,
+        ));
+    }
+
+    // Start cronjob to release the scheduled torrents.
+    drop(console::cronjobs::scheduled_releases::start(&torrent_index));
+
+    // Start cronjob to send the emails in the outbound email queue.
+    drop(console::cronjobs::mail_queue::start(
+        &mail_queue,
+        mailer_service.clone(),
+        &email_queue_repository,
+        background_jobs.handle(),
+    ));
+
+    // Start cronjob to apply the account inactivity policy.
+    if let Some(inactivity) = &opt_inactivity {
+        drop(console::cronjobs::inactivity::start(
+            inactivity.check_interval_seconds,
+            &inactivity_service,
+        ));
+    }
+
+    // Start cronjob to apply the torrent rules.
+    if let Some(torrent_rules) = &opt_torrent_rules {
+        drop(console::cronjobs::torrent_rules::start(
+            torrent_rules.check_interval_seconds,
+            &torrent_rules_service,
+        ));
+    }
+
+    // Start cronjob to evict images from the image cache.
+    drop(console::cronjobs::image_cache_eviction::start(
+        image_cache_eviction_interval_seconds,
+        &image_cache_service,
+    ));
+
+    // Start cronjob to purge the expired torrents from the trash bin.
+    drop(console::cronjobs::trash_purge::start(
+        trash_purge_interval_seconds,
+        &torrent_trash_service,
+    ));
+
+    // Start cronjob to update the health of the torrents.
+    if let Some(torrent_health) = &opt_torrent_health {
+        drop(console::cronjobs::torrent_health::start(
+            torrent_health.check_interval_seconds,
+            &torrent_health_service,
+        ));
+    }
+
+    // Start cronjob to compact the SQLite database.
+    if database.get_database_driver() == Driver::Sqlite3 && database_maintenance_interval_seconds > 0 {
+        drop(console::cronjobs::database_maintenance::start(
+            database_maintenance_interval_seconds,
+            &database,
+        ));
+    }
+
+    // Start cronjob to delete the data older than the retention periods.
+    if let Some(retention) = &opt_retention {
+        drop(console::cronjobs::gc::start(
+            retention.gc_interval_seconds,
+            &retention_service,
+        ));
+    }
+
+    // Start cronjob to store the API usage counters.
+    drop(console::cronjobs::api_usage::start(&api_usage_service));
+
+    // Start cronjob to update the torrents of the smart collections.
+    drop(console::cronjobs::smart_collections::start(&collection_service));
+
+    // Start cronjob to match the new torrents against the saved searches.
+    drop(console::cronjobs::saved_searches::start(
+        &saved_search_service,
+        &domain_event_repository,
+    ));
+
+    // Start cronjob to send the new-torrent digest emails.
+    if let Some(mail_digest) = &opt_mail_digest {
+        let digester = Digester::new(
+            mailer_service.clone(),
+            subscription_repository,
+            torrent_tag_repository,
+            user_profile_repository,
+            mail_digest.max_torrents,
+        );
+
+        drop(console::cronjobs::mail_digest::start(
+            mail_digest,
+            digester,
+            &domain_event_repository,
+        ));
+    }
+
+    // Reload the settings on SIGHUP.
+    drop(console::reload::start(
+        &configuration,
+        &settings_service,
+        &torrent_listing_generator,
+    ));
+
     // Start API server
     let running_api = web::api::start(app_data, config_bind_address, opt_net_tsl, api_version).await;
 
     // Full running application
-    Running {
+    Ok(Running {
         api_socket_addr: running_api.socket_addr,
         api_server: running_api.task,
         api_server_halt_task: running_api.halt_task,
         tracker_data_importer_handle: tracker_statistics_importer_handle,
-    }
+        background_jobs,
+    })
 }
 
 /// It logs the final configuration removing secrets.
@@ -222,3 +956,46 @@ async fn log_configuration(configuration: &Configuration) {
     setting.remove_secrets();
     info!("Configuration:\n{}", setting.to_json());
 }
+
+#[cfg(test)]
+mod tests {
+    use tokio::net::TcpListener;
+    use url::Url;
+
+    use super::{run, Error};
+    use crate::config::{Configuration, Settings, Threshold};
+    use crate::web::api::Version;
+
+    fn settings() -> Settings {
+        let mut settings = Settings::default();
+
+        settings.logging.threshold = Threshold::Off;
+
+        settings
+    }
+
+    #[tokio::test]
+    async fn it_should_not_start_with_invalid_settings() {
+        let mut settings = settings();
+        settings.database.max_connections = 0;
+
+        let result = run(Configuration::new(settings), &Version::V1).await;
+
+        assert!(matches!(result, Err(Error::InvalidSettings { .. })));
+    }
+
+    #[tokio::test]
+    async fn it_should_not_start_when_a_service_fails_the_active_checks() {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let port = listener.local_addr().unwrap().port();
+        drop(listener);
+
+        let mut settings = settings();
+        settings.config.active_checks = true;
+        settings.tracker.api_url = Url::parse(&format!("http://127.0.0.1:{port}")).unwrap();
+
+        let result = run(Configuration::new(settings), &Version::V1).await;
+
+        assert!(matches!(result, Err(Error::UnavailableService { service, .. }) if service == "tracker.api_url"));
+    }
+}
@@ -0,0 +1,12 @@
+//! Backup command.
+//!
+//! It writes a consistent snapshot of the whole index: the database tables
+//! and the images in the filesystem image cache.
+//!
+//! You can execute it with: `cargo run --bin backup`
+use torrust_index::console::commands::backup::app::run;
+
+#[tokio::main]
+async fn main() -> anyhow::Result<()> {
+    run().await
+}
@@ -46,6 +46,7 @@ fn main() {
         nodes: Some(vec![("99.236.6.144".to_string(), 6881), ("91.109.195.156".to_string(), 1996)]),
         encoding: None,
         httpseeds: Some(vec!["https://seeder.torrust-demo.com/seed".to_string()]),
+        url_list: None,
         announce_list: Some(vec![vec!["https://tracker.torrust-demo.com/announce".to_string()]]),
         creation_date: None,
         comment: None,
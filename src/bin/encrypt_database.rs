@@ -0,0 +1,12 @@
+//! Encrypt Database command.
+//!
+//! It encrypts the sensitive columns written before enabling the database
+//! encryption.
+//!
+//! You can execute it with: `cargo run --bin encrypt_database`
+use torrust_index::console::commands::encrypt_database::app::run;
+
+#[tokio::main]
+async fn main() -> anyhow::Result<()> {
+    run().await
+}
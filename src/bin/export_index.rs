@@ -0,0 +1,12 @@
+//! Export Index command.
+//!
+//! It exports the whole index in a portable format. It's used to migrate the
+//! index to a different database backend.
+//!
+//! You can execute it with: `cargo run --bin export_index -- --help`
+use torrust_index::console::commands::export::app::run;
+
+#[tokio::main]
+async fn main() -> anyhow::Result<()> {
+    run().await
+}
@@ -0,0 +1,11 @@
+//! Import Index command.
+//!
+//! It imports an index exported with the `export_index` command.
+//!
+//! You can execute it with: `cargo run --bin import_index -- --help`
+use torrust_index::console::commands::export::import::run;
+
+#[tokio::main]
+async fn main() -> anyhow::Result<()> {
+    run().await
+}
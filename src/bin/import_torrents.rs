@@ -0,0 +1,12 @@
+//! Import Torrents command.
+//!
+//! It imports all the torrent files in a directory. It's used to migrate
+//! torrents from another site.
+//!
+//! You can execute it with: `cargo run --bin import_torrents -- --help`
+use torrust_index::console::commands::import::app::run;
+
+#[tokio::main]
+async fn main() -> anyhow::Result<()> {
+    run().await
+}
@@ -0,0 +1,11 @@
+//! Migrate command.
+//!
+//! It applies, lists or redoes the database migrations.
+//!
+//! You can execute it with: `cargo run --bin migrate [up|status|redo]`
+use torrust_index::console::commands::migrate::app::run;
+
+#[tokio::main]
+async fn main() -> anyhow::Result<()> {
+    run().await
+}
@@ -0,0 +1,12 @@
+//! Move Torrent Files command.
+//!
+//! It moves the pieces of the existing torrents from one torrent file
+//! storage backend to another.
+//!
+//! You can execute it with: `cargo run --bin move_torrent_files -- <FROM> <TO>`
+use torrust_index::console::commands::move_torrent_files::app::run;
+
+#[tokio::main]
+async fn main() -> anyhow::Result<()> {
+    run().await
+}
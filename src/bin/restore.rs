@@ -0,0 +1,12 @@
+//! Restore command.
+//!
+//! It replaces the database tables and the images in the filesystem image
+//! cache with a backup.
+//!
+//! You can execute it with: `cargo run --bin restore`
+use torrust_index::console::commands::backup::restore::run;
+
+#[tokio::main]
+async fn main() -> anyhow::Result<()> {
+    run().await
+}
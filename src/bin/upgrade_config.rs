@@ -0,0 +1,10 @@
+//! Upgrade Config command.
+//!
+//! It upgrades a configuration file to the latest schema version.
+//!
+//! You can execute it with: `cargo run --bin upgrade_config [CONFIG_TOML_PATH]`
+use torrust_index::console::commands::upgrade_config::app::run;
+
+fn main() -> anyhow::Result<()> {
+    run()
+}
@@ -6,38 +6,57 @@
 //! - `Info`
 //! - `Debug`
 //! - `Trace`
+//!
+//! Spans can also be exported to an OpenTelemetry collector. Refer to the
+//! [`otlp`](crate::bootstrap::otlp) module.
 use std::sync::Once;
 
 use tracing::info;
 use tracing::level_filters::LevelFilter;
+use tracing_subscriber::layer::SubscriberExt;
+use tracing_subscriber::util::SubscriberInitExt;
+use tracing_subscriber::{Layer, Registry};
 
-use crate::config::Threshold;
+use super::otlp;
+use crate::config::{Logging, Tracing};
 
 static INIT: Once = Once::new();
 
-pub fn setup(threshold: &Threshold) {
-    let tracing_level_filter: LevelFilter = threshold.clone().into();
+pub fn setup(logging: &Logging) {
+    let tracing_level_filter: LevelFilter = logging.threshold.clone().into();
 
     if tracing_level_filter == LevelFilter::OFF {
         return;
     }
 
     INIT.call_once(|| {
-        tracing_stdout_init(tracing_level_filter, &TraceStyle::Default);
+        tracing_stdout_init(tracing_level_filter, &TraceStyle::Default, logging.tracing.as_ref());
     });
 }
 
-fn tracing_stdout_init(filter: LevelFilter, style: &TraceStyle) {
-    let builder = tracing_subscriber::fmt().with_max_level(filter);
+fn tracing_stdout_init(filter: LevelFilter, style: &TraceStyle, opt_tracing: Option<&Tracing>) {
+    let layer = tracing_subscriber::fmt::layer();
 
-    let () = match style {
-        TraceStyle::Default => builder.init(),
-        TraceStyle::Pretty(display_filename) => builder.pretty().with_file(*display_filename).init(),
-        TraceStyle::Compact => builder.compact().init(),
-        TraceStyle::Json => builder.json().init(),
+    let fmt_layer: Box<dyn Layer<Registry> + Send + Sync> = match style {
+        TraceStyle::Default => layer.boxed(),
+        TraceStyle::Pretty(display_filename) => layer.pretty().with_file(*display_filename).boxed(),
+        TraceStyle::Compact => layer.compact().boxed(),
+        TraceStyle::Json => layer.json().boxed(),
     };
 
+    let otlp_layer = opt_tracing.map(otlp::start);
+
+    tracing_subscriber::registry()
+        .with(fmt_layer)
+        .with(otlp_layer)
+        .with(filter)
+        .init();
+
     info!("Logging initialized");
+
+    if let Some(tracing) = opt_tracing {
+        info!("Exporting spans to {}", tracing.otlp_endpoint);
+    }
 }
 
 #[derive(Debug)]
@@ -0,0 +1,464 @@
+//! Export of the tracing spans to an OpenTelemetry collector.
+//!
+//! When the `[logging.tracing]` section is present, spans are sent in batches
+//! to the collector using OTLP over HTTP with the JSON encoding:
+//!
+//! ```toml
+//! [logging.tracing]
+//! otlp_endpoint = "http://localhost:4318/v1/traces"
+//! service_name = "torrust-index"
+//! ```
+//!
+//! API request spans continue the W3C trace context of the request (refer to
+//! [`trace_context`](crate::utils::trace_context)), so the index spans are
+//! shown in the same trace as the caller and the services called by the
+//! index. Child spans inherit the trace from their parent. Events emitted
+//! inside a span are exported as span events.
+//!
+//! Spans are dropped, instead of blocking the application, when the
+//! collector can't keep up.
+use std::fmt::{self, Write};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use serde_json::{json, Value};
+use tokio::sync::mpsc;
+use tracing::field::{Field, Visit};
+use tracing::span::{Attributes, Id, Record};
+use tracing::{Event, Subscriber};
+use tracing_subscriber::layer::Context;
+use tracing_subscriber::registry::LookupSpan;
+use tracing_subscriber::Layer;
+
+use crate::config::Tracing;
+use crate::utils::trace_context::random_id;
+
+/// Max number of spans waiting to be exported.
+const MAX_QUEUED_SPANS: usize = 4096;
+
+/// Max number of spans in each export request.
+const MAX_BATCH_SIZE: usize = 512;
+
+const EXPORT_INTERVAL: Duration = Duration::from_secs(5);
+
+/// Max number of events exported for each span.
+const MAX_EVENTS_PER_SPAN: usize = 128;
+
+/// Spans from the HTTP libraries are not exported. Otherwise, the export
+/// requests would generate new spans to export.
+const IGNORED_TARGETS: &[&str] = &["hyper", "reqwest", "h2", "tower", "rustls"];
+
+/// Fields used to continue the trace of the caller instead of starting a new
+/// one. They are not exported as attributes.
+const TRACE_ID_FIELD: &str = "trace_id";
+const SPAN_ID_FIELD: &str = "span_id";
+const PARENT_SPAN_ID_FIELD: &str = "parent_span_id";
+
+const SPAN_KIND_INTERNAL: u8 = 1;
+const SPAN_KIND_SERVER: u8 = 2;
+
+/// A finished span ready to be exported.
+#[derive(Debug, Clone, PartialEq)]
+struct FinishedSpan {
+    trace_id: [u8; 16],
+    span_id: [u8; 8],
+    parent_span_id: Option<[u8; 8]>,
+    name: String,
+    target: String,
+    kind: u8,
+    start: SystemTime,
+    end: SystemTime,
+    attributes: Vec<(String, String)>,
+    events: Vec<SpanEvent>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+struct SpanEvent {
+    time: SystemTime,
+    name: String,
+    attributes: Vec<(String, String)>,
+}
+
+/// Span data kept in the span extensions until the span is closed.
+struct SpanData {
+    trace_id: [u8; 16],
+    span_id: [u8; 8],
+    parent_span_id: Option<[u8; 8]>,
+    kind: u8,
+    start: SystemTime,
+    attributes: Vec<(String, String)>,
+    events: Vec<SpanEvent>,
+}
+
+/// Layer that sends the closed spans to the exporter task.
+pub struct OtlpLayer {
+    sender: mpsc::Sender<FinishedSpan>,
+}
+
+/// Starts the exporter task and returns the layer feeding it.
+///
+/// It must be called from a Tokio runtime.
+#[must_use]
+pub fn start(config: &Tracing) -> OtlpLayer {
+    let (sender, receiver) = mpsc::channel(MAX_QUEUED_SPANS);
+
+    tokio::spawn(export(config.clone(), receiver));
+
+    OtlpLayer { sender }
+}
+
+impl<S> Layer<S> for OtlpLayer
+where
+    S: Subscriber + for<'a> LookupSpan<'a>,
+{
+    fn on_new_span(&self, attrs: &Attributes<'_>, id: &Id, ctx: Context<'_, S>) {
+        let Some(span) = ctx.span(id) else {
+            return;
+        };
+
+        if is_ignored(span.metadata().target()) {
+            return;
+        }
+
+        let mut visitor = AttributeVisitor::default();
+        attrs.record(&mut visitor);
+
+        let parent = span.scope().skip(1).find_map(|ancestor| {
+            ancestor
+                .extensions()
+                .get::<SpanData>()
+                .map(|data| (data.trace_id, data.span_id))
+        });
+
+        let (trace_id, span_id, parent_span_id, kind) = match (visitor.trace_id(), parent) {
+            // The span continues a trace context, like the API request spans.
+            (Some(trace_id), _) => (
+                trace_id,
+                visitor.span_id().unwrap_or_else(random_id),
+                visitor.parent_span_id(),
+                SPAN_KIND_SERVER,
+            ),
+            (None, Some((trace_id, parent_span_id))) => (trace_id, random_id(), Some(parent_span_id), SPAN_KIND_INTERNAL),
+            (None, None) => (random_id(), random_id(), None, SPAN_KIND_INTERNAL),
+        };
+
+        span.extensions_mut().insert(SpanData {
+            trace_id,
+            span_id,
+            parent_span_id,
+            kind,
+            start: SystemTime::now(),
+            attributes: visitor.attributes,
+            events: vec![],
+        });
+    }
+
+    fn on_record(&self, id: &Id, values: &Record<'_>, ctx: Context<'_, S>) {
+        let Some(span) = ctx.span(id) else {
+            return;
+        };
+
+        let mut extensions = span.extensions_mut();
+
+        if let Some(data) = extensions.get_mut::<SpanData>() {
+            let mut visitor = AttributeVisitor::default();
+            values.record(&mut visitor);
+            data.attributes.extend(visitor.attributes);
+        }
+    }
+
+    fn on_event(&self, event: &Event<'_>, ctx: Context<'_, S>) {
+        let Some(span) = ctx.event_span(event) else {
+            return;
+        };
+
+        let mut extensions = span.extensions_mut();
+
+        let Some(data) = extensions.get_mut::<SpanData>() else {
+            return;
+        };
+
+        if data.events.len() >= MAX_EVENTS_PER_SPAN {
+            return;
+        }
+
+        let mut visitor = AttributeVisitor::default();
+        event.record(&mut visitor);
+
+        let name = visitor.attributes.iter().position(|(key, _)| key == "message").map_or_else(
+            || event.metadata().name().to_string(),
+            |index| visitor.attributes.remove(index).1,
+        );
+
+        visitor
+            .attributes
+            .push(("level".to_string(), event.metadata().level().to_string()));
+
+        data.events.push(SpanEvent {
+            time: SystemTime::now(),
+            name,
+            attributes: visitor.attributes,
+        });
+    }
+
+    fn on_close(&self, id: Id, ctx: Context<'_, S>) {
+        let Some(span) = ctx.span(&id) else {
+            return;
+        };
+
+        let Some(data) = span.extensions_mut().remove::<SpanData>() else {
+            return;
+        };
+
+        let finished_span = FinishedSpan {
+            trace_id: data.trace_id,
+            span_id: data.span_id,
+            parent_span_id: data.parent_span_id,
+            name: span.name().to_string(),
+            target: span.metadata().target().to_string(),
+            kind: data.kind,
+            start: data.start,
+            end: SystemTime::now(),
+            attributes: data.attributes,
+            events: data.events,
+        };
+
+        // The span is dropped if the queue is full.
+        drop(self.sender.try_send(finished_span));
+    }
+}
+
+fn is_ignored(target: &str) -> bool {
+    IGNORED_TARGETS
+        .iter()
+        .any(|ignored| target == *ignored || target.starts_with(&format!("{ignored}::")))
+}
+
+#[derive(Default)]
+struct AttributeVisitor {
+    attributes: Vec<(String, String)>,
+    trace_id: Option<String>,
+    span_id: Option<String>,
+    parent_span_id: Option<String>,
+}
+
+impl AttributeVisitor {
+    fn trace_id(&self) -> Option<[u8; 16]> {
+        decode_id(self.trace_id.as_deref()?)
+    }
+
+    fn span_id(&self) -> Option<[u8; 8]> {
+        decode_id(self.span_id.as_deref()?)
+    }
+
+    fn parent_span_id(&self) -> Option<[u8; 8]> {
+        decode_id(self.parent_span_id.as_deref()?)
+    }
+}
+
+impl Visit for AttributeVisitor {
+    fn record_str(&mut self, field: &Field, value: &str) {
+        match field.name() {
+            TRACE_ID_FIELD => self.trace_id = Some(value.to_string()),
+            SPAN_ID_FIELD => self.span_id = Some(value.to_string()),
+            PARENT_SPAN_ID_FIELD => self.parent_span_id = Some(value.to_string()),
+            name => self.attributes.push((name.to_string(), value.to_string())),
+        }
+    }
+
+    fn record_debug(&mut self, field: &Field, value: &dyn fmt::Debug) {
+        let mut formatted = String::new();
+        let _ = write!(formatted, "{value:?}");
+        self.record_str(field, &formatted);
+    }
+}
+
+fn decode_id<const N: usize>(hex_id: &str) -> Option<[u8; N]> {
+    hex::decode(hex_id)
+        .ok()?
+        .try_into()
+        .ok()
+        .filter(|id: &[u8; N]| *id != [0u8; N])
+}
+
+async fn export(config: Tracing, mut receiver: mpsc::Receiver<FinishedSpan>) {
+    let client = reqwest::Client::new();
+
+    let mut interval = tokio::time::interval(EXPORT_INTERVAL);
+
+    let mut batch = vec![];
+
+    loop {
+        let closed = tokio::select! {
+            maybe_span = receiver.recv() => match maybe_span {
+                Some(span) => {
+                    batch.push(span);
+                    if batch.len() < MAX_BATCH_SIZE {
+                        continue;
+                    }
+                    false
+                }
+                None => true,
+            },
+            _ = interval.tick() => false,
+        };
+
+        if !batch.is_empty() {
+            let body = request_body(&config.service_name, &std::mem::take(&mut batch));
+
+            let result = client
+                .post(config.otlp_endpoint.clone())
+                .json(&body)
+                .send()
+                .await
+                .and_then(reqwest::Response::error_for_status);
+
+            if let Err(e) = result {
+                tracing::warn!(target: "otlp", "Failed to export spans to {}: {e}", config.otlp_endpoint);
+            }
+        }
+
+        if closed {
+            break;
+        }
+    }
+}
+
+/// The OTLP/HTTP JSON export request.
+fn request_body(service_name: &str, spans: &[FinishedSpan]) -> Value {
+    json!({
+        "resourceSpans": [{
+            "resource": {
+                "attributes": [attribute("service.name", service_name)]
+            },
+            "scopeSpans": [{
+                "scope": { "name": "torrust-index", "version": env!("CARGO_PKG_VERSION") },
+                "spans": spans.iter().map(span_json).collect::<Vec<Value>>()
+            }]
+        }]
+    })
+}
+
+fn span_json(span: &FinishedSpan) -> Value {
+    let mut attributes = vec![attribute("code.namespace", &span.target)];
+    attributes.extend(span.attributes.iter().map(|(key, value)| attribute(key, value)));
+
+    json!({
+        "traceId": hex::encode(span.trace_id),
+        "spanId": hex::encode(span.span_id),
+        "parentSpanId": span.parent_span_id.map(hex::encode).unwrap_or_default(),
+        "name": span.name,
+        "kind": span.kind,
+        "startTimeUnixNano": unix_nanos(span.start),
+        "endTimeUnixNano": unix_nanos(span.end),
+        "attributes": attributes,
+        "events": span.events.iter().map(|event| json!({
+            "timeUnixNano": unix_nanos(event.time),
+            "name": event.name,
+            "attributes": event.attributes.iter().map(|(key, value)| attribute(key, value)).collect::<Vec<Value>>(),
+        })).collect::<Vec<Value>>(),
+    })
+}
+
+fn attribute(key: &str, value: &str) -> Value {
+    json!({ "key": key, "value": { "stringValue": value } })
+}
+
+/// OTLP JSON encodes 64-bit integers as strings.
+fn unix_nanos(time: SystemTime) -> String {
+    time.duration_since(UNIX_EPOCH).unwrap_or_default().as_nanos().to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use tokio::sync::mpsc;
+    use tracing_subscriber::layer::SubscriberExt;
+
+    use super::{request_body, FinishedSpan, OtlpLayer, SPAN_KIND_INTERNAL, SPAN_KIND_SERVER};
+
+    fn record_spans(f: impl FnOnce()) -> Vec<FinishedSpan> {
+        let (sender, mut receiver) = mpsc::channel(100);
+
+        let subscriber = tracing_subscriber::registry().with(OtlpLayer { sender });
+
+        tracing::subscriber::with_default(subscriber, f);
+
+        let mut spans = vec![];
+        while let Ok(span) = receiver.try_recv() {
+            spans.push(span);
+        }
+        spans
+    }
+
+    #[test]
+    fn it_should_continue_the_trace_of_the_request_span() {
+        let spans = record_spans(|| {
+            let request = tracing::info_span!(
+                "request",
+                method = "GET",
+                trace_id = "4bf92f3577b34da6a3ce929d0e0e4736",
+                span_id = "1111111111111111",
+                parent_span_id = "00f067aa0ba902b7"
+            );
+            let _request = request.enter();
+
+            let child = tracing::info_span!("child");
+            let _child = child.enter();
+
+            tracing::info!(user_id = 1, "Email sent");
+        });
+
+        let [child, request] = spans.as_slice() else {
+            panic!("two spans should be exported: {spans:?}");
+        };
+
+        assert_eq!(hex::encode(request.trace_id), "4bf92f3577b34da6a3ce929d0e0e4736");
+        assert_eq!(hex::encode(request.span_id), "1111111111111111");
+        assert_eq!(request.parent_span_id.map(hex::encode), Some("00f067aa0ba902b7".to_string()));
+        assert_eq!(request.kind, SPAN_KIND_SERVER);
+        assert_eq!(request.attributes, vec![("method".to_string(), "GET".to_string())]);
+
+        assert_eq!(child.trace_id, request.trace_id);
+        assert_eq!(child.parent_span_id, Some(request.span_id));
+        assert_eq!(child.kind, SPAN_KIND_INTERNAL);
+        assert_eq!(child.events.len(), 1);
+        assert_eq!(child.events[0].name, "Email sent");
+    }
+
+    #[test]
+    fn it_should_start_a_new_trace_for_root_spans_without_trace_context() {
+        let spans = record_spans(|| {
+            let _span = tracing::info_span!("cronjob").entered();
+        });
+
+        assert_eq!(spans.len(), 1);
+        assert_eq!(spans[0].parent_span_id, None);
+    }
+
+    #[test]
+    fn it_should_not_export_spans_from_http_libraries() {
+        let spans = record_spans(|| {
+            let _span = tracing::info_span!(target: "hyper::client", "connect").entered();
+        });
+
+        assert!(spans.is_empty());
+    }
+
+    #[test]
+    fn it_should_build_the_otlp_json_export_request() {
+        let spans = record_spans(|| {
+            let _span = tracing::info_span!("cronjob").entered();
+        });
+
+        let body = request_body("torrust-index", &spans);
+
+        assert_eq!(
+            body["resourceSpans"][0]["resource"]["attributes"][0]["value"]["stringValue"],
+            "torrust-index"
+        );
+        assert_eq!(
+            body["resourceSpans"][0]["scopeSpans"][0]["spans"][0]["traceId"],
+            hex::encode(spans[0].trace_id)
+        );
+        assert_eq!(body["resourceSpans"][0]["scopeSpans"][0]["spans"][0]["name"], "cronjob");
+    }
+}
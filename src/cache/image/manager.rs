@@ -1,13 +1,22 @@
-use std::collections::HashMap;
-use std::sync::Arc;
+use std::collections::{HashMap, HashSet};
+use std::sync::{Arc, Mutex};
 use std::time::{Duration, SystemTime};
 
 use bytes::Bytes;
+use sha2::{Digest, Sha256};
 use tokio::sync::RwLock;
+use tracing::error;
 
+use super::store::{self, FilesystemStore, ImageStore, MemoryStore, S3Store};
+use super::thumbnail::{Variant, VariantRequest};
 use crate::cache::BytesCache;
-use crate::config::Configuration;
+use crate::config::{Configuration, ImageCacheBackend};
+use crate::databases::database::{self, Database};
 use crate::models::user::UserId;
+use crate::storage::s3::Bucket;
+
+/// Maximum number of images removed at once by the eviction job.
+const EVICTION_BATCH_SIZE: u32 = 100;
 
 pub enum Error {
     UrlIsUnreachable,
@@ -60,7 +69,7 @@ impl ImageCacheQuota {
     /// This function will return a `Error::UserQuotaMet` if user quota has been met.
     pub fn add_usage(&mut self, amount: usize) -> Result<(), Error> {
         // Check if quota needs to be reset.
-        if now_in_secs() - self.date_start_secs > self.period_secs {
+        if self.is_expired() {
             self.reset();
         }
 
@@ -78,6 +87,11 @@ impl ImageCacheQuota {
         self.date_start_secs = now_in_secs();
     }
 
+    #[must_use]
+    pub fn is_expired(&self) -> bool {
+        now_in_secs() - self.date_start_secs > self.period_secs
+    }
+
     #[must_use]
     pub fn is_reached(&self) -> bool {
         self.usage >= self.max_usage
@@ -85,7 +99,11 @@ impl ImageCacheQuota {
 }
 
 pub struct ImageCacheService {
-    image_cache: RwLock<BytesCache>,
+    store: Box<dyn ImageStore>,
+    database: Arc<Box<dyn Database>>,
+    /// Keys of the images read from a persistent store since the last
+    /// eviction.
+    accessed: Mutex<HashSet<String>>,
     user_quotas: RwLock<UserQuotas>,
     reqwest_client: reqwest::Client,
     cfg: Arc<Configuration>,
@@ -97,12 +115,29 @@ impl ImageCacheService {
     /// # Panics
     ///
     /// This function will panic if the image cache could not be created.
-    pub async fn new(cfg: Arc<Configuration>) -> Self {
+    pub async fn new(cfg: Arc<Configuration>, database: Arc<Box<dyn Database>>) -> Self {
         let settings = cfg.settings.read().await;
 
-        let image_cache =
-            BytesCache::with_capacity_and_entry_size_limit(settings.image_cache.capacity, settings.image_cache.entry_size_limit)
-                .expect("Could not create image cache.");
+        let store: Box<dyn ImageStore> = match settings.image_cache.backend {
+            ImageCacheBackend::Memory => Box::new(MemoryStore::new(
+                BytesCache::with_capacity_and_entry_size_limit(
+                    settings.image_cache.capacity,
+                    settings.image_cache.entry_size_limit,
+                )
+                .expect("Could not create image cache."),
+            )),
+            ImageCacheBackend::Filesystem => Box::new(FilesystemStore::new(&settings.image_cache.path)),
+            ImageCacheBackend::S3 => Box::new(S3Store::new(
+                Bucket::new(
+                    settings
+                        .storage
+                        .as_ref()
+                        .and_then(|storage| storage.s3.as_ref())
+                        .expect("the image cache bucket should be validated"),
+                ),
+                &settings.image_cache.s3_key_prefix,
+            )),
+        };
 
         let reqwest_client = reqwest::Client::builder()
             .timeout(Duration::from_millis(settings.image_cache.max_request_timeout_ms))
@@ -112,7 +147,9 @@ impl ImageCacheService {
         drop(settings);
 
         Self {
-            image_cache: RwLock::new(image_cache),
+            store,
+            database,
+            accessed: Mutex::new(HashSet::new()),
             user_quotas: RwLock::new(HashMap::new()),
             reqwest_client,
             cfg,
@@ -126,9 +163,12 @@ impl ImageCacheService {
     ///
     /// Return a `Error::Unauthenticated` if the user has not been authenticated.
     pub async fn get_image_by_url(&self, url: &str, user_id: UserId) -> Result<Bytes, Error> {
-        if let Some(entry) = self.image_cache.read().await.get(url).await {
-            return Ok(entry.bytes);
+        let cache_key = cache_key(url);
+
+        if let Some(bytes) = self.get_cached_image(&cache_key).await {
+            return Ok(bytes);
         }
+
         self.check_user_quota(&user_id).await?;
 
         let image_bytes = self.get_image_from_url_as_bytes(url).await?;
@@ -140,13 +180,150 @@ impl ImageCacheService {
         // This can be problematic if a task is spawned after every user request.
         // Since these functions execute very fast, I don't see a reason to further optimize this.
         // For now.
-        self.update_image_cache(url, &image_bytes).await?;
+        self.update_image_cache(&cache_key, &image_bytes).await?;
 
         self.update_user_quota(&user_id, image_bytes.len()).await?;
 
         Ok(image_bytes)
     }
 
+    /// Get a resized or converted variant of the image from the url. The
+    /// variant is rendered from the original image the first time, and
+    /// stored alongside it. It returns the image and its content type.
+    ///
+    /// # Errors
+    ///
+    /// Return the same errors as [`get_image_by_url`](Self::get_image_by_url),
+    /// and `Error::UrlIsNotAnImage` if the image can't be decoded.
+    pub async fn get_image_variant(
+        &self,
+        url: &str,
+        user_id: UserId,
+        request: &VariantRequest,
+    ) -> Result<(Bytes, &'static str), Error> {
+        let variant = request.variant(&self.cfg.settings.read().await.image_cache.thumbnail_widths);
+
+        let variant_cache_key = variant.cache_key(&cache_key(url));
+
+        if let Some(bytes) = self.get_cached_image(&variant_cache_key).await {
+            return Ok((bytes, variant.format.content_type()));
+        }
+
+        let original = self.get_image_by_url(url, user_id).await?;
+
+        let bytes = render(variant, original).await?;
+
+        self.update_image_cache(&variant_cache_key, &bytes).await?;
+
+        Ok((bytes, variant.format.content_type()))
+    }
+
+    /// Whether the images are kept after restarts and are only removed by
+    /// the eviction job.
+    #[must_use]
+    pub fn is_persistent(&self) -> bool {
+        self.store.is_persistent()
+    }
+
+    /// It stores an image uploaded by a user. Uploaded images are not
+    /// tracked for eviction, so they are kept until they are removed from
+    /// the store.
+    ///
+    /// # Errors
+    ///
+    /// It returns an error if the store fails.
+    pub async fn put_uploaded_image(&self, cache_key: &str, bytes: Bytes) -> Result<(), store::Error> {
+        self.store.put(cache_key, bytes).await
+    }
+
+    /// It returns an image uploaded by a user.
+    ///
+    /// # Errors
+    ///
+    /// It returns an error if the store fails.
+    pub async fn get_uploaded_image(&self, cache_key: &str) -> Result<Option<Bytes>, store::Error> {
+        self.store.get(cache_key).await
+    }
+
+    /// It removes the expired user quotas and, for persistent stores, the
+    /// least recently used images until the cache is not bigger than its
+    /// capacity. It returns the number of removed images.
+    ///
+    /// # Errors
+    ///
+    /// It returns an error if there is a database error.
+    pub async fn evict(&self) -> Result<usize, database::Error> {
+        self.user_quotas.write().await.retain(|_, quota| !quota.is_expired());
+
+        if !self.store.is_persistent() {
+            return Ok(0);
+        }
+
+        let accessed: Vec<String> = self
+            .accessed
+            .lock()
+            .expect("image cache lock should not be poisoned")
+            .drain()
+            .collect();
+
+        self.database.touch_image_cache_entries(&accessed).await?;
+
+        let capacity = i64::try_from(self.cfg.settings.read().await.image_cache.capacity).unwrap_or(i64::MAX);
+
+        let mut size = self.database.get_image_cache_size().await?;
+        let mut evicted = 0;
+
+        while size > capacity {
+            let entries = self
+                .database
+                .get_least_recently_accessed_image_cache_entries(EVICTION_BATCH_SIZE)
+                .await?;
+
+            if entries.is_empty() {
+                break;
+            }
+
+            for (cache_key, image_size) in entries {
+                if let Err(err) = self.store.delete(&cache_key).await {
+                    // Keep the entry, so the image is removed in the next execution
+                    error!(target: "image cache", "Failed to remove image {cache_key}: {err:?}");
+                    return Ok(evicted);
+                }
+
+                self.database.delete_image_cache_entry(&cache_key).await?;
+
+                size -= image_size;
+                evicted += 1;
+
+                if size <= capacity {
+                    break;
+                }
+            }
+        }
+
+        Ok(evicted)
+    }
+
+    async fn get_cached_image(&self, cache_key: &str) -> Option<Bytes> {
+        match self.store.get(cache_key).await {
+            Ok(Some(bytes)) => {
+                if self.store.is_persistent() {
+                    self.accessed
+                        .lock()
+                        .expect("image cache lock should not be poisoned")
+                        .insert(cache_key.to_string());
+                }
+
+                Some(bytes)
+            }
+            Ok(None) => None,
+            Err(err) => {
+                error!(target: "image cache", "Failed to read image {cache_key}: {err:?}");
+                None
+            }
+        }
+    }
+
     async fn get_image_from_url_as_bytes(&self, url: &str) -> Result<Bytes, Error> {
         let res = self
             .reqwest_client
@@ -189,16 +366,23 @@ impl ImageCacheService {
         Ok(())
     }
 
-    async fn update_image_cache(&self, url: &str, image_bytes: &Bytes) -> Result<(), Error> {
-        if self
-            .image_cache
-            .write()
-            .await
-            .set(url.to_string(), image_bytes.clone())
-            .await
-            .is_err()
-        {
-            return Err(Error::ImageTooBig);
+    async fn update_image_cache(&self, cache_key: &str, image_bytes: &Bytes) -> Result<(), Error> {
+        match self.store.put(cache_key, image_bytes.clone()).await {
+            Ok(()) => {}
+            Err(store::Error::ImageTooBig) => return Err(Error::ImageTooBig),
+            Err(store::Error::StorageFailed(err)) => {
+                // The image is still returned, it's only not cached
+                error!(target: "image cache", "Failed to store image {cache_key}: {err}");
+                return Ok(());
+            }
+        }
+
+        if self.store.is_persistent() {
+            let size = i64::try_from(image_bytes.len()).unwrap_or(i64::MAX);
+
+            if let Err(err) = self.database.upsert_image_cache_entry(cache_key, size).await {
+                error!(target: "image cache", "Failed to track image {cache_key}: {err:?}");
+            }
         }
 
         Ok(())
@@ -226,3 +410,18 @@ impl ImageCacheService {
         Ok(())
     }
 }
+
+/// Decoding and encoding images is CPU-bound, so it runs outside of the async
+/// runtime workers.
+async fn render(variant: Variant, original: Bytes) -> Result<Bytes, Error> {
+    tokio::task::spawn_blocking(move || variant.render(&original))
+        .await
+        .map_err(|_| Error::UrlIsNotAnImage)?
+        .map_err(|_| Error::UrlIsNotAnImage)
+}
+
+/// The key of the cached image. URLs can be long and contain any character,
+/// so images are stored by the SHA-256 of the URL.
+fn cache_key(url: &str) -> String {
+    hex::encode(Sha256::digest(url.as_bytes()))
+}
@@ -1 +1,3 @@
 pub mod manager;
+pub mod store;
+pub mod thumbnail;
@@ -0,0 +1,229 @@
+//! Storage backends for the image cache.
+//!
+//! - [`MemoryStore`]: the images are kept in memory, up to the cache
+//!   capacity. They are lost when the application restarts.
+//! - [`FilesystemStore`]: the images are stored as files in a local
+//!   directory.
+//! - [`S3Store`]: the images are stored as objects in an S3-compatible
+//!   bucket.
+//!
+//! The filesystem and S3 stores are persistent. Their images are tracked in
+//! the database, so the eviction job can remove the least recently used ones
+//! when the cache is bigger than its capacity.
+use async_trait::async_trait;
+use bytes::Bytes;
+use camino::{Utf8Path, Utf8PathBuf};
+use tokio::sync::RwLock;
+
+use crate::cache::BytesCache;
+use crate::storage::s3::Bucket;
+
+#[derive(Debug, PartialEq, Eq)]
+pub enum Error {
+    /// The image does not fit in the cache.
+    ImageTooBig,
+    /// The storage failed.
+    StorageFailed(String),
+}
+
+/// An image cache storage backend. Images are stored by cache key.
+#[async_trait]
+pub trait ImageStore: Sync + Send {
+    /// Whether the images are kept after restarts.
+    fn is_persistent(&self) -> bool;
+
+    /// It returns the image, if it's stored.
+    async fn get(&self, key: &str) -> Result<Option<Bytes>, Error>;
+
+    /// It stores an image.
+    async fn put(&self, key: &str, bytes: Bytes) -> Result<(), Error>;
+
+    /// It removes an image. Removing an image that is not stored is not an
+    /// error.
+    async fn delete(&self, key: &str) -> Result<(), Error>;
+}
+
+pub struct MemoryStore {
+    cache: RwLock<BytesCache>,
+}
+
+impl MemoryStore {
+    #[must_use]
+    pub fn new(cache: BytesCache) -> Self {
+        Self {
+            cache: RwLock::new(cache),
+        }
+    }
+}
+
+#[async_trait]
+impl ImageStore for MemoryStore {
+    fn is_persistent(&self) -> bool {
+        false
+    }
+
+    async fn get(&self, key: &str) -> Result<Option<Bytes>, Error> {
+        Ok(self.cache.read().await.get(key).await.map(|entry| entry.bytes))
+    }
+
+    async fn put(&self, key: &str, bytes: Bytes) -> Result<(), Error> {
+        self.cache
+            .write()
+            .await
+            .set(key.to_string(), bytes)
+            .await
+            .map(|_| ())
+            .map_err(|_| Error::ImageTooBig)
+    }
+
+    async fn delete(&self, _key: &str) -> Result<(), Error> {
+        // The bytes cache evicts the oldest images on its own
+        Ok(())
+    }
+}
+
+pub struct FilesystemStore {
+    path: Utf8PathBuf,
+}
+
+impl FilesystemStore {
+    #[must_use]
+    pub fn new(path: &Utf8Path) -> Self {
+        Self { path: path.to_owned() }
+    }
+
+    /// Images are grouped in subdirectories by the first two characters of
+    /// the key, to avoid directories with too many files.
+    fn image_path(&self, key: &str) -> Utf8PathBuf {
+        self.path.join(key.get(..2).unwrap_or(key)).join(key)
+    }
+}
+
+#[async_trait]
+impl ImageStore for FilesystemStore {
+    fn is_persistent(&self) -> bool {
+        true
+    }
+
+    async fn get(&self, key: &str) -> Result<Option<Bytes>, Error> {
+        match tokio::fs::read(self.image_path(key)).await {
+            Ok(bytes) => Ok(Some(Bytes::from(bytes))),
+            Err(err) if err.kind() == std::io::ErrorKind::NotFound => Ok(None),
+            Err(err) => Err(Error::StorageFailed(err.to_string())),
+        }
+    }
+
+    async fn put(&self, key: &str, bytes: Bytes) -> Result<(), Error> {
+        let image_path = self.image_path(key);
+
+        if let Some(directory) = image_path.parent() {
+            tokio::fs::create_dir_all(directory)
+                .await
+                .map_err(|err| Error::StorageFailed(err.to_string()))?;
+        }
+
+        // Write to a temporary file first, so readers never get a partial image
+        let temporary_path = image_path.with_extension("tmp");
+
+        tokio::fs::write(&temporary_path, &bytes)
+            .await
+            .map_err(|err| Error::StorageFailed(err.to_string()))?;
+
+        tokio::fs::rename(&temporary_path, &image_path)
+            .await
+            .map_err(|err| Error::StorageFailed(err.to_string()))
+    }
+
+    async fn delete(&self, key: &str) -> Result<(), Error> {
+        match tokio::fs::remove_file(self.image_path(key)).await {
+            Ok(()) => Ok(()),
+            Err(err) if err.kind() == std::io::ErrorKind::NotFound => Ok(()),
+            Err(err) => Err(Error::StorageFailed(err.to_string())),
+        }
+    }
+}
+
+pub struct S3Store {
+    bucket: Bucket,
+    key_prefix: String,
+}
+
+impl S3Store {
+    #[must_use]
+    pub fn new(bucket: Bucket, key_prefix: &str) -> Self {
+        Self {
+            bucket,
+            key_prefix: key_prefix.to_string(),
+        }
+    }
+
+    fn object_key(&self, key: &str) -> String {
+        format!("{}{key}", self.key_prefix)
+    }
+}
+
+#[async_trait]
+impl ImageStore for S3Store {
+    fn is_persistent(&self) -> bool {
+        true
+    }
+
+    async fn get(&self, key: &str) -> Result<Option<Bytes>, Error> {
+        self.bucket
+            .get(&self.object_key(key))
+            .await
+            .map_err(|err| Error::StorageFailed(format!("{err:?}")))
+    }
+
+    async fn put(&self, key: &str, bytes: Bytes) -> Result<(), Error> {
+        self.bucket
+            .put(&self.object_key(key), content_type(&bytes), bytes)
+            .await
+            .map_err(|err| Error::StorageFailed(format!("{err:?}")))
+    }
+
+    async fn delete(&self, key: &str) -> Result<(), Error> {
+        self.bucket
+            .delete(&self.object_key(key))
+            .await
+            .map_err(|err| Error::StorageFailed(format!("{err:?}")))
+    }
+}
+
+/// The content type of the cached image. Only PNG and JPEG images are
+/// cached.
+fn content_type(bytes: &[u8]) -> &'static str {
+    if bytes.starts_with(b"\x89PNG") {
+        "image/png"
+    } else {
+        "image/jpeg"
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use bytes::Bytes;
+    use camino::Utf8PathBuf;
+
+    use super::{FilesystemStore, ImageStore};
+
+    #[tokio::test]
+    async fn the_filesystem_store_should_store_and_remove_images() {
+        let directory = tempfile::tempdir().unwrap();
+        let store = FilesystemStore::new(&Utf8PathBuf::from_path_buf(directory.path().to_path_buf()).unwrap());
+
+        let key = "5452869be36f9f3350ccee6b4544e7e76caaadab";
+
+        assert_eq!(store.get(key).await, Ok(None));
+
+        store.put(key, Bytes::from_static(b"image")).await.unwrap();
+
+        assert_eq!(store.get(key).await, Ok(Some(Bytes::from_static(b"image"))));
+        assert!(directory.path().join("54").join(key).exists());
+
+        store.delete(key).await.unwrap();
+
+        assert_eq!(store.get(key).await, Ok(None));
+        assert_eq!(store.delete(key).await, Ok(()));
+    }
+}
@@ -0,0 +1,185 @@
+//! Resized and converted variants of the cached images.
+//!
+//! Clients can ask the image proxy for a smaller variant of an image, and
+//! for a more efficient format:
+//!
+//! ```text
+//! /v1/proxy/image/{url}?width=320&format=webp
+//! ```
+//!
+//! The requested width is rounded up to the closest width in the
+//! `image_cache.thumbnail_widths` configuration option, so the number of
+//! variants of each image is bounded. Images are never enlarged. Resized
+//! images without a format are encoded as PNG.
+use std::io::Cursor;
+
+use bytes::Bytes;
+use image::imageops::FilterType;
+use image::{DynamicImage, ImageFormat};
+use serde::Deserialize;
+use utoipa::{IntoParams, ToSchema};
+
+/// Output format of an image variant.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize, ToSchema)]
+#[serde(rename_all = "lowercase")]
+pub enum Format {
+    Png,
+    Webp,
+    Avif,
+}
+
+impl Format {
+    #[must_use]
+    pub fn content_type(self) -> &'static str {
+        match self {
+            Format::Png => "image/png",
+            Format::Webp => "image/webp",
+            Format::Avif => "image/avif",
+        }
+    }
+
+    fn extension(self) -> &'static str {
+        match self {
+            Format::Png => "png",
+            Format::Webp => "webp",
+            Format::Avif => "avif",
+        }
+    }
+
+    fn image_format(self) -> ImageFormat {
+        match self {
+            Format::Png => ImageFormat::Png,
+            Format::Webp => ImageFormat::WebP,
+            Format::Avif => ImageFormat::Avif,
+        }
+    }
+}
+
+/// The variant of the image requested by the client.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
+pub struct VariantRequest {
+    /// Maximum width in pixels. The height keeps the aspect ratio.
+    pub width: Option<u32>,
+    /// Output format: `png`, `webp` or `avif`.
+    pub format: Option<Format>,
+}
+
+impl VariantRequest {
+    /// Whether the original image was requested.
+    #[must_use]
+    pub fn is_original(&self) -> bool {
+        self.width.is_none() && self.format.is_none()
+    }
+
+    /// The variant to render, with the width rounded up to one of the
+    /// allowed widths. Widths bigger than all the allowed ones are rounded
+    /// down to the biggest one.
+    #[must_use]
+    pub fn variant(&self, allowed_widths: &[u32]) -> Variant {
+        let width = self.width.and_then(|width| {
+            allowed_widths
+                .iter()
+                .filter(|allowed| **allowed >= width)
+                .min()
+                .or_else(|| allowed_widths.iter().max())
+                .copied()
+        });
+
+        Variant {
+            width,
+            format: self.format.unwrap_or(Format::Png),
+        }
+    }
+}
+
+/// An image variant.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Variant {
+    pub width: Option<u32>,
+    pub format: Format,
+}
+
+impl Variant {
+    /// The cache key of the variant, from the cache key of the original
+    /// image. Variants are stored alongside the originals.
+    #[must_use]
+    pub fn cache_key(&self, original_cache_key: &str) -> String {
+        match self.width {
+            Some(width) => format!("{original_cache_key}-w{width}.{}", self.format.extension()),
+            None => format!("{original_cache_key}.{}", self.format.extension()),
+        }
+    }
+
+    /// It renders the variant of the original image.
+    ///
+    /// # Errors
+    ///
+    /// It returns an error if the original image can't be decoded or the
+    /// variant can't be encoded.
+    pub fn render(&self, original: &[u8]) -> Result<Bytes, image::ImageError> {
+        let image = image::load_from_memory(original)?;
+
+        let image = match self.width {
+            Some(width) if width < image.width() => image.resize(width, u32::MAX, FilterType::Lanczos3),
+            _ => image,
+        };
+
+        // Not all the encoders support every color type
+        let image = DynamicImage::ImageRgba8(image.to_rgba8());
+
+        let mut bytes = Vec::new();
+
+        image.write_to(&mut Cursor::new(&mut bytes), self.format.image_format())?;
+
+        Ok(Bytes::from(bytes))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Format, Variant, VariantRequest};
+
+    const ALLOWED_WIDTHS: &[u32] = &[160, 320, 640];
+
+    #[test]
+    fn it_should_round_the_requested_width_to_an_allowed_width() {
+        let request = |width| VariantRequest {
+            width: Some(width),
+            format: None,
+        };
+
+        assert_eq!(request(100).variant(ALLOWED_WIDTHS).width, Some(160));
+        assert_eq!(request(320).variant(ALLOWED_WIDTHS).width, Some(320));
+        assert_eq!(request(321).variant(ALLOWED_WIDTHS).width, Some(640));
+        assert_eq!(request(5000).variant(ALLOWED_WIDTHS).width, Some(640));
+    }
+
+    #[test]
+    fn it_should_store_the_variants_alongside_the_original_image() {
+        let variant = Variant {
+            width: Some(320),
+            format: Format::Webp,
+        };
+
+        assert_eq!(variant.cache_key("abc"), "abc-w320.webp");
+    }
+
+    #[test]
+    fn it_should_resize_images_keeping_the_aspect_ratio() {
+        let original = image::DynamicImage::new_rgba8(640, 480);
+        let mut png = Vec::new();
+        original
+            .write_to(&mut std::io::Cursor::new(&mut png), image::ImageFormat::Png)
+            .unwrap();
+
+        let variant = Variant {
+            width: Some(320),
+            format: Format::Png,
+        };
+
+        let resized = image::load_from_memory(&variant.render(&png).unwrap()).unwrap();
+
+        assert_eq!((resized.width(), resized.height()), (320, 240));
+    }
+}
@@ -1,4 +1,5 @@
 pub mod image;
+pub mod query;
 
 use bytes::Bytes;
 use indexmap::IndexMap;
@@ -0,0 +1,118 @@
+//! In-memory LRU cache backend.
+use std::sync::Mutex;
+use std::time::Duration;
+
+use async_trait::async_trait;
+use indexmap::IndexMap;
+use tokio::time::Instant;
+
+use super::Backend;
+
+struct Entry {
+    value: Vec<u8>,
+    expires_at: Instant,
+}
+
+/// The entries ordered from the least to the most recently used.
+type Entries = IndexMap<(String, String), Entry>;
+
+pub struct MemoryBackend {
+    entries: Mutex<Entries>,
+    capacity: usize,
+}
+
+impl MemoryBackend {
+    #[must_use]
+    pub fn new(capacity: usize) -> Self {
+        Self {
+            entries: Mutex::new(IndexMap::new()),
+            capacity,
+        }
+    }
+
+    fn entries(&self) -> std::sync::MutexGuard<'_, Entries> {
+        self.entries.lock().expect("query cache lock should not be poisoned")
+    }
+}
+
+#[async_trait]
+impl Backend for MemoryBackend {
+    async fn get(&self, namespace: &str, key: &str) -> Option<Vec<u8>> {
+        let mut entries = self.entries();
+
+        let entry = entries.shift_remove(&(namespace.to_string(), key.to_string()))?;
+
+        if entry.expires_at <= Instant::now() {
+            return None;
+        }
+
+        let value = entry.value.clone();
+
+        // Move the entry to the end, as the most recently used.
+        entries.insert((namespace.to_string(), key.to_string()), entry);
+
+        Some(value)
+    }
+
+    async fn set(&self, namespace: &str, key: &str, value: Vec<u8>, ttl: Duration) {
+        if self.capacity == 0 {
+            return;
+        }
+
+        let mut entries = self.entries();
+
+        drop(entries.shift_remove(&(namespace.to_string(), key.to_string())));
+
+        while entries.len() >= self.capacity {
+            drop(entries.shift_remove_index(0));
+        }
+
+        entries.insert(
+            (namespace.to_string(), key.to_string()),
+            Entry {
+                value,
+                expires_at: Instant::now() + ttl,
+            },
+        );
+    }
+
+    async fn invalidate(&self, namespace: &str) {
+        self.entries().retain(|(entry_namespace, _), _| entry_namespace != namespace);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use super::MemoryBackend;
+    use crate::cache::query::Backend;
+
+    const TTL: Duration = Duration::from_secs(30);
+
+    #[tokio::test]
+    async fn it_should_evict_the_least_recently_used_entries() {
+        let backend = MemoryBackend::new(2);
+
+        backend.set("tags", "a", b"a".to_vec(), TTL).await;
+        backend.set("tags", "b", b"b".to_vec(), TTL).await;
+
+        // "a" is now the most recently used entry
+        assert_eq!(backend.get("tags", "a").await, Some(b"a".to_vec()));
+
+        backend.set("tags", "c", b"c".to_vec(), TTL).await;
+
+        assert_eq!(backend.get("tags", "a").await, Some(b"a".to_vec()));
+        assert_eq!(backend.get("tags", "b").await, None);
+        assert_eq!(backend.get("tags", "c").await, Some(b"c".to_vec()));
+    }
+
+    #[tokio::test]
+    async fn it_should_not_return_expired_entries() {
+        let backend = MemoryBackend::new(2);
+
+        backend.set("tags", "a", b"a".to_vec(), Duration::ZERO).await;
+
+        assert_eq!(backend.get("tags", "a").await, None);
+    }
+}
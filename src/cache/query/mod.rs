@@ -0,0 +1,183 @@
+//! Cache for hot queries.
+//!
+//! Some queries, like the categories, the tags or the first pages of the
+//! torrent listing, are executed on almost every page view. When the
+//! `[cache]` configuration section is present, their results are cached for
+//! `ttl_seconds`.
+//!
+//! There are two backends:
+//!
+//! - [`memory`]: an in-memory LRU cache for each instance.
+//! - [`redis`]: a Redis server shared by all the instances.
+//!
+//! Results are cached in namespaces. The write paths invalidate the whole
+//! namespace of the results they change, for example adding a category
+//! invalidates the [`CATEGORIES`] namespace.
+pub mod memory;
+pub mod redis;
+
+use std::future::Future;
+use std::time::Duration;
+
+use async_trait::async_trait;
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+use tracing::{error, warn};
+
+use crate::config::{Cache, CacheBackend};
+
+/// Namespace for the list of categories.
+pub const CATEGORIES: &str = "categories";
+
+/// Namespace for the list of tags.
+pub const TAGS: &str = "tags";
+
+/// Namespace for the torrent listing pages.
+pub const TORRENT_LISTINGS: &str = "torrent_listings";
+
+/// A cache backend.
+///
+/// Backends only store bytes. Errors are handled by the backends, which log
+/// them and behave like an empty cache, so a cache outage never breaks the
+/// queries.
+#[async_trait]
+pub trait Backend: Sync + Send {
+    /// It returns the cached value, if any.
+    async fn get(&self, namespace: &str, key: &str) -> Option<Vec<u8>>;
+
+    /// It caches a value for `ttl`.
+    async fn set(&self, namespace: &str, key: &str, value: Vec<u8>, ttl: Duration);
+
+    /// It removes all the values in the namespace.
+    async fn invalidate(&self, namespace: &str);
+}
+
+pub struct QueryCache {
+    backend: Option<Box<dyn Backend>>,
+    ttl: Duration,
+}
+
+impl QueryCache {
+    /// It builds the cache from the `[cache]` configuration section. Queries
+    /// are not cached when there is no configuration.
+    ///
+    /// # Panics
+    ///
+    /// Will panic if the Redis client can't be created.
+    #[must_use]
+    pub fn new(config: Option<&Cache>) -> Self {
+        let Some(config) = config else {
+            return Self::disabled();
+        };
+
+        let backend: Box<dyn Backend> = match config.backend {
+            CacheBackend::Memory => Box::new(memory::MemoryBackend::new(config.capacity)),
+            CacheBackend::Redis => Box::new(
+                redis::RedisBackend::new(config.redis.as_ref().expect("the Redis configuration should be validated"))
+                    .expect("the Redis client should be created"),
+            ),
+        };
+
+        Self {
+            backend: Some(backend),
+            ttl: Duration::from_secs(config.ttl_seconds),
+        }
+    }
+
+    /// A cache that does not cache anything.
+    #[must_use]
+    pub fn disabled() -> Self {
+        Self {
+            backend: None,
+            ttl: Duration::ZERO,
+        }
+    }
+
+    /// It returns the cached result for the key, or loads and caches it.
+    /// Failed loads are not cached.
+    ///
+    /// # Errors
+    ///
+    /// It returns the error of the `load` function.
+    pub async fn get_or_load<T, E, F, Fut>(&self, namespace: &str, key: &str, load: F) -> Result<T, E>
+    where
+        T: Serialize + DeserializeOwned,
+        F: FnOnce() -> Fut,
+        Fut: Future<Output = Result<T, E>>,
+    {
+        let Some(backend) = &self.backend else {
+            return load().await;
+        };
+
+        if let Some(bytes) = backend.get(namespace, key).await {
+            match serde_json::from_slice(&bytes) {
+                Ok(value) => return Ok(value),
+                Err(err) => warn!(target: "query cache", "Ignoring invalid cached value for {namespace}/{key}: {err}"),
+            }
+        }
+
+        let value = load().await?;
+
+        match serde_json::to_vec(&value) {
+            Ok(bytes) => backend.set(namespace, key, bytes, self.ttl).await,
+            Err(err) => error!(target: "query cache", "Failed to serialize the value for {namespace}/{key}: {err}"),
+        }
+
+        Ok(value)
+    }
+
+    /// It removes all the cached results in the namespace.
+    pub async fn invalidate(&self, namespace: &str) {
+        if let Some(backend) = &self.backend {
+            backend.invalidate(namespace).await;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::atomic::{AtomicU32, Ordering};
+
+    use super::{QueryCache, CATEGORIES, TAGS};
+    use crate::config::Cache;
+
+    #[allow(clippy::unused_async)]
+    async fn load(loads: &AtomicU32) -> Result<Vec<String>, ()> {
+        loads.fetch_add(1, Ordering::Relaxed);
+        Ok(vec!["Linux".to_string()])
+    }
+
+    #[tokio::test]
+    async fn it_should_load_the_results_once_until_the_namespace_is_invalidated() {
+        let cache = QueryCache::new(Some(&Cache::default()));
+        let loads = AtomicU32::new(0);
+
+        for _ in 0..2 {
+            assert_eq!(
+                cache.get_or_load(CATEGORIES, "all", || load(&loads)).await,
+                Ok(vec!["Linux".to_string()])
+            );
+        }
+        assert_eq!(loads.load(Ordering::Relaxed), 1);
+
+        cache.invalidate(TAGS).await;
+        let _ = cache.get_or_load(CATEGORIES, "all", || load(&loads)).await;
+        assert_eq!(loads.load(Ordering::Relaxed), 1);
+
+        cache.invalidate(CATEGORIES).await;
+        let _ = cache.get_or_load(CATEGORIES, "all", || load(&loads)).await;
+        assert_eq!(loads.load(Ordering::Relaxed), 2);
+    }
+
+    #[tokio::test]
+    async fn it_should_always_load_the_results_when_it_is_disabled() {
+        let cache = QueryCache::disabled();
+        let loads = AtomicU32::new(0);
+
+        for _ in 0..2 {
+            let _ = cache.get_or_load(CATEGORIES, "all", || load(&loads)).await;
+        }
+
+        assert_eq!(loads.load(Ordering::Relaxed), 2);
+    }
+}
@@ -0,0 +1,108 @@
+//! Redis cache backend.
+//!
+//! Values are stored with `SET ... EX` under the key
+//! `{key_prefix}{namespace}:{generation}:{key}`. Invalidating a namespace
+//! increments its generation, so the previous values are no longer read and
+//! expire on their own.
+use std::time::Duration;
+
+use async_trait::async_trait;
+use redis::aio::ConnectionManager;
+use redis::{AsyncCommands, Client, RedisError};
+use tokio::sync::OnceCell;
+use tracing::error;
+
+use super::Backend;
+use crate::config::Redis;
+
+pub struct RedisBackend {
+    client: Client,
+    /// The connection is opened with the first query, and reconnects on its
+    /// own when the server is restarted.
+    connection: OnceCell<ConnectionManager>,
+    key_prefix: String,
+}
+
+impl RedisBackend {
+    /// # Errors
+    ///
+    /// It returns an error if the Redis URL is not valid.
+    pub fn new(config: &Redis) -> Result<Self, RedisError> {
+        Ok(Self {
+            client: Client::open(config.url.as_str())?,
+            connection: OnceCell::new(),
+            key_prefix: config.key_prefix.clone(),
+        })
+    }
+
+    async fn connection(&self) -> Option<ConnectionManager> {
+        match self.connection.get_or_try_init(|| self.client.get_connection_manager()).await {
+            Ok(connection) => Some(connection.clone()),
+            Err(err) => {
+                error!(target: "query cache", "Failed to connect to Redis: {err}");
+                None
+            }
+        }
+    }
+
+    fn generation_key(&self, namespace: &str) -> String {
+        format!("{}{namespace}:generation", self.key_prefix)
+    }
+
+    /// It returns the key of the value in the current generation of the
+    /// namespace.
+    async fn value_key(&self, connection: &mut ConnectionManager, namespace: &str, key: &str) -> Option<String> {
+        match connection.get::<_, Option<u64>>(self.generation_key(namespace)).await {
+            Ok(generation) => Some(format!(
+                "{}{namespace}:{}:{key}",
+                self.key_prefix,
+                generation.unwrap_or_default()
+            )),
+            Err(err) => {
+                error!(target: "query cache", "Failed to get the {namespace} generation: {err}");
+                None
+            }
+        }
+    }
+}
+
+#[async_trait]
+impl Backend for RedisBackend {
+    async fn get(&self, namespace: &str, key: &str) -> Option<Vec<u8>> {
+        let mut connection = self.connection().await?;
+
+        let value_key = self.value_key(&mut connection, namespace, key).await?;
+
+        match connection.get::<_, Option<Vec<u8>>>(value_key).await {
+            Ok(value) => value,
+            Err(err) => {
+                error!(target: "query cache", "Failed to get {namespace}/{key}: {err}");
+                None
+            }
+        }
+    }
+
+    async fn set(&self, namespace: &str, key: &str, value: Vec<u8>, ttl: Duration) {
+        let Some(mut connection) = self.connection().await else {
+            return;
+        };
+
+        let Some(value_key) = self.value_key(&mut connection, namespace, key).await else {
+            return;
+        };
+
+        if let Err(err) = connection.set_ex::<_, _, ()>(value_key, value, ttl.as_secs().max(1)).await {
+            error!(target: "query cache", "Failed to set {namespace}/{key}: {err}");
+        }
+    }
+
+    async fn invalidate(&self, namespace: &str) {
+        let Some(mut connection) = self.connection().await else {
+            return;
+        };
+
+        if let Err(err) = connection.incr::<_, _, u64>(self.generation_key(namespace), 1).await {
+            error!(target: "query cache", "Failed to invalidate {namespace}: {err}");
+        }
+    }
+}
@@ -4,16 +4,39 @@ use crate::cache::image::manager::ImageCacheService;
 use crate::config::Configuration;
 use crate::databases::database::Database;
 use crate::services::authentication::{DbUserAuthenticationRepository, JsonWebToken, Service};
-use crate::services::category::{self, DbCategoryRepository};
+use crate::services::bookmark::{self, DbBookmarkRepository};
+use crate::services::category::{self, DbCategoryModeratorRepository, DbCategoryRepository};
+use crate::services::collection::{self, DbCollectionRepository};
+use crate::services::comment::{self, DbCommentRepository};
+use crate::services::domain_events::DbDomainEventRepository;
+use crate::services::download_statistics::{self, DbDownloadStatisticsRepository};
+use crate::services::listing_snapshot;
+use crate::services::report::{self, DbReportRepository};
+use crate::services::saved_search;
+use crate::services::seeding_rewards;
+use crate::services::subscription::{self, DbSubscriptionRepository};
 use crate::services::tag::{self, DbTagRepository};
+use crate::services::torrent::file_group::{self, DbTorrentFileGroupRepository};
+use crate::services::torrent::preview::{self, DbTorrentPreviewRepository};
+use crate::services::torrent::quota::{self, DbUploadQuotaRepository};
+use crate::services::torrent::{attachment, deduplication, description_image, health, trash, web_seed};
 use crate::services::torrent::{
     DbCanonicalInfoHashGroupRepository, DbTorrentAnnounceUrlRepository, DbTorrentFileRepository, DbTorrentInfoRepository,
     DbTorrentListingGenerator, DbTorrentRepository, DbTorrentTagRepository,
 };
+use crate::services::torrent_rules;
+use crate::services::trending_tags;
 use crate::services::user::{self, DbBannedUserList, DbUserProfileRepository, Repository};
-use crate::services::{about, proxy, settings, torrent};
+use crate::services::{
+    about, announce_key, antispam, api_usage, audit, bulk_import, bulk_mail, category_field, enrichment, health, image_upload,
+    inactivity, live_events, proxy, reputation, retention, session, settings, signed_upload, statistics_importer, sync, torrent,
+    widget,
+};
 use crate::tracker::statistics_importer::StatisticsImporter;
+use crate::web::api::server::rate_limit::RateLimiter;
 use crate::web::api::server::v1::auth::Authentication;
+use crate::web::api::server::v1::deprecation::Deprecations;
+use crate::web::api::server::v1::etag::ModificationTimes;
 use crate::{mailer, tracker};
 
 pub type Username = String;
@@ -52,6 +75,60 @@ pub struct AppData {
     pub profile_service: Arc<user::ProfileService>,
     pub ban_service: Arc<user::BanService>,
     pub about_service: Arc<about::Service>,
+    pub signed_upload_service: Arc<signed_upload::Service>,
+    pub domain_event_repository: Arc<DbDomainEventRepository>,
+    pub comment_repository: Arc<DbCommentRepository>,
+    pub comment_service: Arc<comment::Service>,
+    pub report_repository: Arc<DbReportRepository>,
+    pub report_service: Arc<report::Service>,
+    pub statistics_importer_service: Arc<statistics_importer::Service>,
+    pub listing_snapshot_service: Arc<listing_snapshot::Service>,
+    pub bookmark_repository: Arc<DbBookmarkRepository>,
+    pub bookmark_service: Arc<bookmark::Service>,
+    pub subscription_repository: Arc<DbSubscriptionRepository>,
+    pub subscription_service: Arc<subscription::Service>,
+    pub download_statistics_repository: Arc<DbDownloadStatisticsRepository>,
+    pub download_statistics_service: Arc<download_statistics::Service>,
+    pub rate_limiter: Arc<RateLimiter>,
+    pub deprecations: Arc<Deprecations>,
+    pub bulk_import_service: Arc<bulk_import::Service>,
+    pub bulk_mail_service: Arc<bulk_mail::Service>,
+    pub inactivity_service: Arc<inactivity::Service>,
+    pub image_upload_service: Arc<image_upload::Service>,
+    pub live_events_service: Arc<live_events::Service>,
+    pub collection_repository: Arc<DbCollectionRepository>,
+    pub collection_service: Arc<collection::Service>,
+    pub retention_service: Arc<retention::Service>,
+    pub upload_quota_repository: Arc<DbUploadQuotaRepository>,
+    pub upload_quota_service: Arc<quota::Service>,
+    pub category_moderator_repository: Arc<DbCategoryModeratorRepository>,
+    pub api_usage_service: Arc<api_usage::Service>,
+    pub widget_service: Arc<widget::Service>,
+    pub sync_service: Arc<sync::Service>,
+    pub audit_service: Arc<audit::Service>,
+    pub torrent_preview_repository: Arc<DbTorrentPreviewRepository>,
+    pub torrent_preview_service: Arc<preview::Service>,
+    pub announce_key_service: Arc<announce_key::Service>,
+    pub torrent_file_group_repository: Arc<DbTorrentFileGroupRepository>,
+    pub torrent_file_group_service: Arc<file_group::Service>,
+    pub trending_tags_service: Arc<trending_tags::Service>,
+    pub torrent_rules_service: Arc<torrent_rules::Service>,
+    pub saved_search_service: Arc<saved_search::Service>,
+    pub import_session_service: Arc<bulk_import::session::Service>,
+    pub modification_times: Arc<ModificationTimes>,
+    pub seeding_rewards_service: Arc<seeding_rewards::Service>,
+    pub description_image_service: Arc<description_image::Service>,
+    pub torrent_attachment_service: Arc<attachment::Service>,
+    pub torrent_duplicate_service: Arc<deduplication::Service>,
+    pub torrent_trash_service: Arc<trash::Service>,
+    pub torrent_health_service: Arc<health::Service>,
+    pub antispam_service: Arc<antispam::Service>,
+    pub session_service: Arc<session::Service>,
+    pub health_service: Arc<health::Service>,
+    pub torrent_web_seed_service: Arc<web_seed::Service>,
+    pub torrent_metadata_service: Arc<enrichment::Service>,
+    pub category_field_service: Arc<category_field::Service>,
+    pub reputation_service: Arc<reputation::Service>,
 }
 
 impl AppData {
@@ -90,6 +167,60 @@ impl AppData {
         profile_service: Arc<user::ProfileService>,
         ban_service: Arc<user::BanService>,
         about_service: Arc<about::Service>,
+        signed_upload_service: Arc<signed_upload::Service>,
+        domain_event_repository: Arc<DbDomainEventRepository>,
+        comment_repository: Arc<DbCommentRepository>,
+        comment_service: Arc<comment::Service>,
+        report_repository: Arc<DbReportRepository>,
+        report_service: Arc<report::Service>,
+        statistics_importer_service: Arc<statistics_importer::Service>,
+        listing_snapshot_service: Arc<listing_snapshot::Service>,
+        bookmark_repository: Arc<DbBookmarkRepository>,
+        bookmark_service: Arc<bookmark::Service>,
+        subscription_repository: Arc<DbSubscriptionRepository>,
+        subscription_service: Arc<subscription::Service>,
+        download_statistics_repository: Arc<DbDownloadStatisticsRepository>,
+        download_statistics_service: Arc<download_statistics::Service>,
+        rate_limiter: Arc<RateLimiter>,
+        deprecations: Arc<Deprecations>,
+        bulk_import_service: Arc<bulk_import::Service>,
+        bulk_mail_service: Arc<bulk_mail::Service>,
+        inactivity_service: Arc<inactivity::Service>,
+        image_upload_service: Arc<image_upload::Service>,
+        live_events_service: Arc<live_events::Service>,
+        collection_repository: Arc<DbCollectionRepository>,
+        collection_service: Arc<collection::Service>,
+        retention_service: Arc<retention::Service>,
+        upload_quota_repository: Arc<DbUploadQuotaRepository>,
+        upload_quota_service: Arc<quota::Service>,
+        category_moderator_repository: Arc<DbCategoryModeratorRepository>,
+        api_usage_service: Arc<api_usage::Service>,
+        widget_service: Arc<widget::Service>,
+        sync_service: Arc<sync::Service>,
+        audit_service: Arc<audit::Service>,
+        torrent_preview_repository: Arc<DbTorrentPreviewRepository>,
+        torrent_preview_service: Arc<preview::Service>,
+        announce_key_service: Arc<announce_key::Service>,
+        torrent_file_group_repository: Arc<DbTorrentFileGroupRepository>,
+        torrent_file_group_service: Arc<file_group::Service>,
+        trending_tags_service: Arc<trending_tags::Service>,
+        torrent_rules_service: Arc<torrent_rules::Service>,
+        saved_search_service: Arc<saved_search::Service>,
+        import_session_service: Arc<bulk_import::session::Service>,
+        modification_times: Arc<ModificationTimes>,
+        seeding_rewards_service: Arc<seeding_rewards::Service>,
+        description_image_service: Arc<description_image::Service>,
+        torrent_attachment_service: Arc<attachment::Service>,
+        torrent_duplicate_service: Arc<deduplication::Service>,
+        torrent_trash_service: Arc<trash::Service>,
+        torrent_health_service: Arc<health::Service>,
+        antispam_service: Arc<antispam::Service>,
+        session_service: Arc<session::Service>,
+        health_service: Arc<health::Service>,
+        torrent_web_seed_service: Arc<web_seed::Service>,
+        torrent_metadata_service: Arc<enrichment::Service>,
+        category_field_service: Arc<category_field::Service>,
+        reputation_service: Arc<reputation::Service>,
     ) -> AppData {
         AppData {
             cfg,
@@ -125,6 +256,60 @@ impl AppData {
             profile_service,
             ban_service,
             about_service,
+            signed_upload_service,
+            domain_event_repository,
+            comment_repository,
+            comment_service,
+            report_repository,
+            report_service,
+            statistics_importer_service,
+            listing_snapshot_service,
+            bookmark_repository,
+            bookmark_service,
+            subscription_repository,
+            subscription_service,
+            download_statistics_repository,
+            download_statistics_service,
+            rate_limiter,
+            deprecations,
+            bulk_import_service,
+            bulk_mail_service,
+            inactivity_service,
+            image_upload_service,
+            live_events_service,
+            collection_repository,
+            collection_service,
+            retention_service,
+            upload_quota_repository,
+            upload_quota_service,
+            category_moderator_repository,
+            api_usage_service,
+            widget_service,
+            sync_service,
+            audit_service,
+            torrent_preview_repository,
+            torrent_preview_service,
+            announce_key_service,
+            torrent_file_group_repository,
+            torrent_file_group_service,
+            trending_tags_service,
+            torrent_rules_service,
+            saved_search_service,
+            import_session_service,
+            modification_times,
+            seeding_rewards_service,
+            description_image_service,
+            torrent_attachment_service,
+            torrent_duplicate_service,
+            torrent_trash_service,
+            torrent_health_service,
+            antispam_service,
+            session_service,
+            health_service,
+            torrent_web_seed_service,
+            torrent_metadata_service,
+            category_field_service,
+            reputation_service,
         }
     }
 }
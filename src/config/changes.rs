@@ -0,0 +1,176 @@
+//! Changes in the settings since the application started.
+//!
+//! Most options are read every time they are used, so changing them takes
+//! effect immediately. Other options are only read when the application
+//! starts, like the bind address or the database connection. Changing them
+//! requires a restart. They are listed in [`RESTART_REQUIRED_OPTIONS`].
+use serde::Serialize;
+use serde_json::Value;
+
+use super::Settings;
+
+/// Options that are only read when the application starts. An option requires
+/// a restart if it's in the list or it belongs to a section in the list.
+pub const RESTART_REQUIRED_OPTIONS: &[&str] = &[
+    "metadata",
+    "logging",
+    "api.compression",
+    "net.bind_address",
+    "net.tsl",
+    "net.rate_limits",
+    "net.shutdown_timeout_seconds",
+    "tracker.api_url",
+    "tracker.token",
+    "tracker.url",
+    "tracker.backups",
+    "database",
+    "mail.smtp",
+    "mail.digest",
+    "mail.queue",
+    "image_cache.backend",
+    "image_cache.capacity",
+    "image_cache.entry_size_limit",
+    "image_cache.path",
+    "image_cache.s3_key_prefix",
+    "image_cache.eviction_interval_seconds",
+    "tracker_statistics_importer",
+    "search",
+    "announcer",
+    "listing_snapshots",
+    "cache",
+    "mirror_sync",
+    "torrent.magnet_uploads",
+    "torrent.trash.purge_interval_seconds",
+    "torrent.health.check_interval_seconds",
+    "inactivity",
+    "retention",
+    "storage",
+    "torrent_rules",
+    "trending_tags",
+    "unstable",
+];
+
+/// It checks whether changing the option requires a restart. Options are
+/// written like in the TOML file, for example: `net.bind_address`.
+#[must_use]
+pub fn requires_restart(option: &str) -> bool {
+    RESTART_REQUIRED_OPTIONS.iter().any(|restart_required_option| {
+        option == *restart_required_option
+            || option
+                .strip_prefix(restart_required_option)
+                .is_some_and(|rest| rest.starts_with('.'))
+            || restart_required_option
+                .strip_prefix(option)
+                .is_some_and(|rest| rest.starts_with('.'))
+    })
+}
+
+/// The options that changed, grouped by whether they took effect.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize)]
+pub struct SettingsChanges {
+    /// Options that took effect immediately.
+    pub applied: Vec<String>,
+    /// Options that only take effect after restarting the application.
+    pub pending_restart: Vec<String>,
+}
+
+impl SettingsChanges {
+    /// It returns the options that are different in the `new` settings.
+    ///
+    /// # Panics
+    ///
+    /// Will panic if the settings can't be serialized.
+    #[must_use]
+    pub fn between(running: &Settings, new: &Settings) -> Self {
+        let running = serde_json::to_value(running).expect("settings should be serializable");
+        let new = serde_json::to_value(new).expect("settings should be serializable");
+
+        let mut options = vec![];
+
+        changed_options("", &running, &new, &mut options);
+
+        let (pending_restart, applied) = options.into_iter().partition(|option| requires_restart(option));
+
+        Self {
+            applied,
+            pending_restart,
+        }
+    }
+
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.applied.is_empty() && self.pending_restart.is_empty()
+    }
+}
+
+/// It adds the options that changed to `options`. Sections are compared
+/// option by option, other values, like lists, are compared as a whole.
+fn changed_options(prefix: &str, running: &Value, new: &Value, options: &mut Vec<String>) {
+    match (running, new) {
+        (Value::Object(running), Value::Object(new)) => {
+            let mut keys: Vec<&String> = running.keys().chain(new.keys()).collect();
+            keys.sort();
+            keys.dedup();
+
+            for key in keys {
+                let option = if prefix.is_empty() {
+                    key.clone()
+                } else {
+                    format!("{prefix}.{key}")
+                };
+
+                changed_options(
+                    &option,
+                    running.get(key).unwrap_or(&Value::Null),
+                    new.get(key).unwrap_or(&Value::Null),
+                    options,
+                );
+            }
+        }
+        _ => {
+            if running != new {
+                options.push(prefix.to_string());
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::net::SocketAddr;
+
+    use super::{requires_restart, SettingsChanges};
+    use crate::config::Settings;
+
+    #[test]
+    fn it_should_know_which_options_require_a_restart() {
+        assert!(requires_restart("net.bind_address"));
+        assert!(requires_restart("database.connect_url"));
+        assert!(requires_restart("net"));
+        assert!(requires_restart("api.compression.br"));
+
+        assert!(!requires_restart("net.base_url"));
+        assert!(!requires_restart("website.name"));
+        assert!(!requires_restart("databases"));
+        assert!(!requires_restart("api.cache_control"));
+    }
+
+    #[test]
+    fn it_should_list_the_changed_options_by_whether_they_took_effect() {
+        let running = Settings::default();
+
+        let mut new = Settings::default();
+        new.website.name = "Another name".to_string();
+        new.net.bind_address = "0.0.0.0:3002".parse::<SocketAddr>().unwrap();
+
+        assert_eq!(
+            SettingsChanges::between(&running, &new),
+            SettingsChanges {
+                applied: vec!["website.name".to_string()],
+                pending_restart: vec!["net.bind_address".to_string()],
+            }
+        );
+
+        assert!(SettingsChanges::between(&running, &running).is_empty());
+    }
+}
@@ -0,0 +1,188 @@
+//! Migration of the configuration from older schema versions.
+//!
+//! Configurations written for an older schema version are upgraded in
+//! memory when they are loaded, so breaking changes in the schema don't
+//! force users to rewrite them by hand. The upgraded configuration can be
+//! written back to the file with:
+//!
+//! ```text
+//! cargo run --bin upgrade_config ./index.toml
+//! ```
+//!
+//! Every breaking change in the schema must come with a migration step that
+//! rewrites the options of the previous version.
+//!
+//! Supported upgrades:
+//!
+//! - `2.0.0` to `3.0.0`: version `2.0.0` applied the pending database
+//!   migrations at every start. From version `3.0.0` they are only applied
+//!   when `database.auto_migrate` is enabled, so the upgrade enables it
+//!   unless it's already set.
+use toml::{Table, Value};
+
+use super::{VERSION_2, VERSION_3};
+
+/// A step that upgrades the configuration from one schema version to the
+/// next one.
+struct Migration {
+    from: &'static str,
+    to: &'static str,
+    apply: fn(&mut Table),
+}
+
+/// The migration steps, from the oldest to the latest schema version.
+const MIGRATIONS: &[Migration] = &[Migration {
+    from: VERSION_2,
+    to: VERSION_3,
+    apply: from_v2_to_v3,
+}];
+
+/// A configuration upgraded to the latest schema version.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Upgraded {
+    /// The schema version of the original configuration.
+    pub from_version: String,
+    /// The schema version of the upgraded configuration.
+    pub to_version: String,
+    /// The upgraded configuration in TOML format.
+    pub config_toml: String,
+}
+
+/// It upgrades a configuration in TOML format to the latest schema version.
+///
+/// It returns `None` when there is nothing to upgrade: the configuration is
+/// already in the latest version, the version is unknown or it's not valid
+/// TOML. Loading the original configuration reports the error in those
+/// cases.
+///
+/// Comments and formatting are not kept in the upgraded configuration.
+#[must_use]
+pub fn upgrade(config_toml: &str) -> Option<Upgraded> {
+    let mut table = config_toml.parse::<Table>().ok()?;
+
+    let from_version = schema_version(&table)?;
+
+    let mut version = from_version.clone();
+    let mut upgraded = false;
+
+    for migration in MIGRATIONS {
+        if version == migration.from {
+            (migration.apply)(&mut table);
+            set_schema_version(&mut table, migration.to);
+
+            version = migration.to.to_string();
+            upgraded = true;
+        }
+    }
+
+    if !upgraded {
+        return None;
+    }
+
+    Some(Upgraded {
+        from_version,
+        to_version: version,
+        config_toml: toml::to_string(&table).ok()?,
+    })
+}
+
+/// It enables `database.auto_migrate` to keep applying the database
+/// migrations at startup like version `2.0.0` did.
+fn from_v2_to_v3(table: &mut Table) {
+    let database = table.entry("database").or_insert_with(|| Value::Table(Table::new()));
+
+    if let Value::Table(database) = database {
+        database.entry("auto_migrate").or_insert(Value::Boolean(true));
+    }
+}
+
+fn schema_version(table: &Table) -> Option<String> {
+    table
+        .get("metadata")?
+        .get("schema_version")?
+        .as_str()
+        .map(ToString::to_string)
+}
+
+fn set_schema_version(table: &mut Table, version: &str) {
+    if let Some(Value::Table(metadata)) = table.get_mut("metadata") {
+        metadata.insert("schema_version".to_string(), Value::String(version.to_string()));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use toml::Table;
+
+    use super::upgrade;
+
+    #[test]
+    fn it_should_upgrade_a_v2_configuration_to_the_latest_version() {
+        let upgraded = upgrade(
+            r#"
+            [metadata]
+            schema_version = "2.0.0"
+
+            [website]
+            name = "My Index"
+            "#,
+        )
+        .unwrap();
+
+        assert_eq!(upgraded.from_version, "2.0.0");
+        assert_eq!(upgraded.to_version, "3.0.0");
+
+        let table = upgraded.config_toml.parse::<Table>().unwrap();
+
+        assert_eq!(table["metadata"]["schema_version"].as_str(), Some("3.0.0"));
+        assert_eq!(table["website"]["name"].as_str(), Some("My Index"));
+    }
+
+    #[test]
+    fn it_should_not_upgrade_a_configuration_in_the_latest_version() {
+        assert_eq!(upgrade("[metadata]\nschema_version = \"3.0.0\"\n"), None);
+    }
+
+    #[test]
+    fn it_should_not_upgrade_a_configuration_with_an_unknown_version() {
+        assert_eq!(upgrade("[metadata]\nschema_version = \"1.0.0\"\n"), None);
+        assert_eq!(upgrade("[website]\nname = \"My Index\"\n"), None);
+    }
+
+    #[test]
+    fn it_should_enable_the_database_auto_migration_when_upgrading_from_v2() {
+        let upgraded = upgrade(
+            r#"
+            [metadata]
+            schema_version = "2.0.0"
+
+            [database]
+            connect_url = "sqlite://data.db?mode=rwc"
+            "#,
+        )
+        .unwrap();
+
+        let table = upgraded.config_toml.parse::<Table>().unwrap();
+
+        assert_eq!(table["database"]["auto_migrate"].as_bool(), Some(true));
+        assert_eq!(table["database"]["connect_url"].as_str(), Some("sqlite://data.db?mode=rwc"));
+    }
+
+    #[test]
+    fn it_should_enable_the_database_auto_migration_when_upgrading_from_v2_without_a_database_section() {
+        let upgraded = upgrade("[metadata]\nschema_version = \"2.0.0\"\n").unwrap();
+
+        let table = upgraded.config_toml.parse::<Table>().unwrap();
+
+        assert_eq!(table["database"]["auto_migrate"].as_bool(), Some(true));
+    }
+
+    #[test]
+    fn it_should_keep_the_database_auto_migration_option_when_it_is_already_set() {
+        let upgraded = upgrade("[metadata]\nschema_version = \"2.0.0\"\n\n[database]\nauto_migrate = false\n").unwrap();
+
+        let table = upgraded.config_toml.parse::<Table>().unwrap();
+
+        assert_eq!(table["database"]["auto_migrate"].as_bool(), Some(false));
+    }
+}
@@ -1,60 +1,151 @@
 //! Configuration for the application.
-pub mod v2;
+pub mod changes;
+pub mod migration;
+pub mod v3;
 pub mod validator;
 
-use std::env;
 use std::sync::Arc;
+use std::{env, fs};
 
 use camino::Utf8PathBuf;
 use derive_more::Display;
-use figment::providers::{Env, Format, Serialized, Toml};
+use figment::providers::{Data, Env, Format, Serialized, Toml};
 use figment::Figment;
 use serde::{Deserialize, Serialize};
 use serde_with::{serde_as, NoneAsEmptyString};
 use thiserror::Error;
-use tokio::sync::RwLock;
+use tokio::sync::{watch, RwLock};
 use torrust_index_located_error::LocatedError;
 
+use self::changes::SettingsChanges;
+use self::validator::{ValidationError, Validator};
 use crate::web::api::server::DynError;
 
-pub type Settings = v2::Settings;
-
-pub type Api = v2::api::Api;
-
-pub type Registration = v2::registration::Registration;
-pub type Email = v2::registration::Email;
-
-pub type Auth = v2::auth::Auth;
-pub type SecretKey = v2::auth::ClaimTokenPepper;
-pub type PasswordConstraints = v2::auth::PasswordConstraints;
-
-pub type Database = v2::database::Database;
-
-pub type ImageCache = v2::image_cache::ImageCache;
-
-pub type Mail = v2::mail::Mail;
-pub type Smtp = v2::mail::Smtp;
-pub type Credentials = v2::mail::Credentials;
-
-pub type Network = v2::net::Network;
-
-pub type TrackerStatisticsImporter = v2::tracker_statistics_importer::TrackerStatisticsImporter;
-
-pub type Tracker = v2::tracker::Tracker;
-pub type ApiToken = v2::tracker::ApiToken;
-
-pub type Logging = v2::logging::Logging;
-pub type Threshold = v2::logging::Threshold;
-
-pub type Website = v2::website::Website;
-pub type Demo = v2::website::Demo;
-pub type Terms = v2::website::Terms;
-pub type TermsPage = v2::website::TermsPage;
-pub type TermsUpload = v2::website::TermsUpload;
-pub type Markdown = v2::website::Markdown;
-
-/// Configuration version
+pub type Settings = v3::Settings;
+
+pub type Announcer = v3::announcer::Announcer;
+pub type AnnouncerChannel = v3::announcer::Channel;
+pub type AnnouncerTarget = v3::announcer::Target;
+
+pub type Api = v3::api::Api;
+pub type CacheControlRule = v3::api::CacheControlRule;
+pub type Compression = v3::api::Compression;
+pub type ResponseFormat = v3::api::ResponseFormat;
+pub type FieldNaming = v3::api::FieldNaming;
+
+pub type Registration = v3::registration::Registration;
+pub type Email = v3::registration::Email;
+
+pub type Cache = v3::cache::Cache;
+pub type CacheBackend = v3::cache::Backend;
+pub type Redis = v3::cache::Redis;
+
+pub type ConfigChecks = v3::config::Config;
+
+pub type Auth = v3::auth::Auth;
+pub type SecretKey = v3::auth::ClaimTokenPepper;
+pub type PasswordConstraints = v3::auth::PasswordConstraints;
+pub type JwtExtraClaims = v3::auth::ExtraClaims;
+
+pub type Database = v3::database::Database;
+pub type DatabaseEncryption = v3::database::Encryption;
+pub type DatabaseEncryptionKey = v3::database::EncryptionKey;
+pub type DatabaseSqlite = v3::database::Sqlite;
+pub type SqliteJournalMode = v3::database::JournalMode;
+pub type SqliteSynchronous = v3::database::Synchronous;
+
+pub type DownloadStatistics = v3::download_statistics::DownloadStatistics;
+
+pub type ImageCache = v3::image_cache::ImageCache;
+pub type ImageCacheBackend = v3::image_cache::Backend;
+
+pub type Inactivity = v3::inactivity::Inactivity;
+pub type InactivityAction = v3::inactivity::Action;
+
+pub type Integrations = v3::integrations::Integrations;
+pub type SeedingRewards = v3::integrations::SeedingRewards;
+pub type SignedUploads = v3::integrations::SignedUploads;
+pub type SigningSecret = v3::integrations::SigningSecret;
+
+pub type Limits = v3::limits::Limits;
+
+pub type ListingSnapshots = v3::listing_snapshots::ListingSnapshots;
+
+pub type Mail = v3::mail::Mail;
+pub type Smtp = v3::mail::Smtp;
+pub type Credentials = v3::mail::Credentials;
+pub type MailDigest = v3::mail::Digest;
+pub type MailQueue = v3::mail::Queue;
+
+pub type MirrorSync = v3::mirror_sync::MirrorSync;
+
+pub type Network = v3::net::Network;
+pub type RateLimits = v3::net::RateLimits;
+pub type RateLimit = v3::net::RateLimit;
+
+pub type Retention = v3::retention::Retention;
+pub type RetentionDataKind = v3::retention::DataKind;
+
+pub type Search = v3::search::Search;
+pub type SearchBackend = v3::search::Backend;
+pub type Tantivy = v3::search::Tantivy;
+pub type Meilisearch = v3::search::Meilisearch;
+
+pub type Storage = v3::storage::Storage;
+pub type S3 = v3::storage::S3;
+pub type S3SecretAccessKey = v3::storage::SecretAccessKey;
+pub type TorrentFiles = v3::storage::TorrentFiles;
+pub type TorrentFilesBackend = v3::storage::TorrentFilesBackend;
+
+pub type TorrentStructure = v3::torrent::Structure;
+pub type MagnetUploads = v3::torrent::MagnetUploads;
+pub type SimilarTitles = v3::torrent::SimilarTitles;
+pub type TorrentPreviews = v3::torrent::Previews;
+pub type TorrentDescriptionImages = v3::torrent::DescriptionImages;
+pub type TorrentAttachments = v3::torrent::Attachments;
+pub type TorrentDeduplication = v3::torrent::Deduplication;
+pub type DuplicateAction = v3::torrent::DuplicateAction;
+pub type TorrentTrash = v3::torrent::Trash;
+pub type TorrentHealth = v3::torrent::Health;
+pub type DeadTorrentAction = v3::torrent::DeadTorrentAction;
+pub type TorrentDownload = v3::torrent::Download;
+pub type TorrentAnnounceUrls = v3::torrent::AnnounceUrls;
+
+pub type TorrentRules = v3::torrent_rules::TorrentRules;
+pub type TorrentRule = v3::torrent_rules::Rule;
+pub type TorrentRuleAction = v3::torrent_rules::Action;
+
+pub type TrackerStatisticsImporter = v3::tracker_statistics_importer::TrackerStatisticsImporter;
+pub type ImporterAlerts = v3::tracker_statistics_importer::Alerts;
+
+pub type TrendingTags = v3::trending_tags::TrendingTags;
+pub type Antispam = v3::antispam::Antispam;
+pub type UploadPolicy = v3::upload_policy::UploadPolicy;
+pub type Reputation = v3::reputation::Reputation;
+pub type Ratio = v3::ratio::Ratio;
+
+pub type Enrichment = v3::enrichment::Enrichment;
+pub type TmdbProvider = v3::enrichment::Tmdb;
+pub type MusicBrainzProvider = v3::enrichment::MusicBrainz;
+
+pub type Tracker = v3::tracker::Tracker;
+pub type ApiToken = v3::tracker::ApiToken;
+pub type BackupTracker = v3::tracker::BackupTracker;
+
+pub type Logging = v3::logging::Logging;
+pub type Threshold = v3::logging::Threshold;
+pub type Tracing = v3::logging::Tracing;
+
+pub type Website = v3::website::Website;
+pub type Demo = v3::website::Demo;
+pub type Terms = v3::website::Terms;
+pub type TermsPage = v3::website::TermsPage;
+pub type TermsUpload = v3::website::TermsUpload;
+pub type Markdown = v3::website::Markdown;
+
+/// Configuration versions
 const VERSION_2: &str = "2.0.0";
+const VERSION_3: &str = "3.0.0";
 
 /// Prefix for env vars that overwrite configuration options.
 const CONFIG_OVERRIDE_PREFIX: &str = "TORRUST_INDEX_CONFIG_OVERRIDE_";
@@ -69,7 +160,7 @@ pub const ENV_VAR_CONFIG_TOML: &str = "TORRUST_INDEX_CONFIG_TOML";
 /// The `index.toml` file location.
 pub const ENV_VAR_CONFIG_TOML_PATH: &str = "TORRUST_INDEX_CONFIG_TOML_PATH";
 
-pub const LATEST_VERSION: &str = "2.0.0";
+pub const LATEST_VERSION: &str = VERSION_3;
 
 /// Info about the configuration specification.
 #[derive(Serialize, Deserialize, PartialEq, Eq, Debug, Display, Clone)]
@@ -179,7 +270,7 @@ impl Info {
         let env_var_config_toml_path = ENV_VAR_CONFIG_TOML_PATH.to_string();
 
         let config_toml = if let Ok(config_toml) = env::var(env_var_config_toml) {
-            println!("Loading extra configuration from environment variable {config_toml} ...");
+            println!("Loading extra configuration from environment variable {ENV_VAR_CONFIG_TOML} ...");
             Some(config_toml)
         } else {
             None
@@ -238,6 +329,12 @@ pub enum Error {
 
     #[error("Missing mandatory configuration option. Option path: {path}")]
     MissingMandatoryOption { path: String },
+
+    #[error("Invalid configuration: {source}")]
+    InvalidSettings { source: ValidationError },
+
+    #[error("The configuration can't be reloaded because it was not loaded from a file or an environment variable")]
+    NotReloadable,
 }
 
 impl From<figment::Error> for Error {
@@ -265,6 +362,11 @@ pub struct Tsl {
     #[serde_as(as = "NoneAsEmptyString")]
     #[serde(default = "Tsl::default_ssl_key_path")]
     pub ssl_key_path: Option<Utf8PathBuf>,
+    /// How often the certificate and key files are checked for changes, in
+    /// seconds. Renewed certificates are loaded without restarting the
+    /// server. `0` disables the checks.
+    #[serde(default = "Tsl::default_cert_reload_interval_seconds")]
+    pub cert_reload_interval_seconds: u64,
 }
 
 impl Tsl {
@@ -277,6 +379,10 @@ impl Tsl {
     fn default_ssl_key_path() -> Option<Utf8PathBuf> {
         Some(Utf8PathBuf::new())
     }
+
+    fn default_cert_reload_interval_seconds() -> u64 {
+        60
+    }
 }
 
 /// The configuration service.
@@ -284,17 +390,34 @@ impl Tsl {
 pub struct Configuration {
     /// The state of the configuration.
     pub settings: RwLock<Settings>,
+    /// The settings when the application started. Options that require a
+    /// restart are only read from them.
+    startup_settings: Settings,
+    /// Where the settings were loaded from. `None` when they were not loaded
+    /// from a file or an environment variable, so they can't be reloaded.
+    info: Option<Info>,
+    /// The number of times the settings were reloaded. Services that cache
+    /// values derived from the settings subscribe to it.
+    reloads: watch::Sender<u64>,
 }
 
 impl Default for Configuration {
     fn default() -> Configuration {
-        Configuration {
-            settings: RwLock::new(Settings::default()),
-        }
+        Configuration::new(Settings::default())
     }
 }
 
 impl Configuration {
+    #[must_use]
+    pub fn new(settings: Settings) -> Configuration {
+        Configuration {
+            startup_settings: settings.clone(),
+            settings: RwLock::new(settings),
+            info: None,
+            reloads: watch::Sender::new(0),
+        }
+    }
+
     /// Loads the configuration from the `Info` struct.
     ///
     /// # Errors
@@ -304,15 +427,61 @@ impl Configuration {
         let settings = Self::load_settings(info)?;
 
         Ok(Configuration {
-            settings: RwLock::new(settings),
+            info: Some(info.clone()),
+            ..Configuration::new(settings)
         })
     }
 
+    /// It loads the settings again from the same sources, and it replaces the
+    /// running settings if they are valid. The subscribers are notified after
+    /// the settings are replaced. Options that require a restart are
+    /// replaced too, but they don't take effect until the application is
+    /// restarted.
+    ///
+    /// It returns the options that changed.
+    ///
+    /// # Errors
+    ///
+    /// Will return `Err` if the settings were not loaded from a file or an
+    /// environment variable, or if the new settings can't be loaded or they
+    /// are not valid. The running settings are not changed in that case.
+    pub async fn reload(&self) -> Result<SettingsChanges, Error> {
+        let Some(info) = &self.info else {
+            return Err(Error::NotReloadable);
+        };
+
+        let new_settings = Self::load_settings(info)?;
+
+        new_settings.validate().map_err(|source| Error::InvalidSettings { source })?;
+
+        let mut settings_lock = self.settings.write().await;
+
+        let changes = SettingsChanges::between(&settings_lock, &new_settings);
+
+        *settings_lock = new_settings;
+
+        drop(settings_lock);
+
+        self.reloads.send_modify(|reloads| *reloads += 1);
+
+        Ok(changes)
+    }
+
+    /// It returns a receiver that is notified every time the settings are
+    /// reloaded.
+    #[must_use]
+    pub fn subscribe(&self) -> watch::Receiver<u64> {
+        self.reloads.subscribe()
+    }
+
     /// Loads the settings from the `Info` struct. The whole
     /// configuration in toml format is included in the `info.index_toml` string.
     ///
     /// Configuration provided via env var has priority over config file path.
     ///
+    /// Configurations in an older schema version are upgraded in memory.
+    /// Refer to the [`migration`] module.
+    ///
     /// # Errors
     ///
     /// Will return `Err` if the environment variable does not exist or has a bad configuration.
@@ -320,10 +489,16 @@ impl Configuration {
         // Load configuration provided by the user, prioritizing env vars
         let figment = if let Some(config_toml) = &info.config_toml {
             // Config in env var has priority over config file path
-            Figment::from(Toml::string(config_toml)).merge(Env::prefixed(CONFIG_OVERRIDE_PREFIX).split(CONFIG_OVERRIDE_SEPARATOR))
+            let toml = Self::upgrade(config_toml, ENV_VAR_CONFIG_TOML).unwrap_or_else(|| Toml::string(config_toml));
+
+            Figment::from(toml).merge(Env::prefixed(CONFIG_OVERRIDE_PREFIX).split(CONFIG_OVERRIDE_SEPARATOR))
         } else {
-            Figment::from(Toml::file(&info.config_toml_path))
-                .merge(Env::prefixed(CONFIG_OVERRIDE_PREFIX).split(CONFIG_OVERRIDE_SEPARATOR))
+            let toml = fs::read_to_string(&info.config_toml_path)
+                .ok()
+                .and_then(|config_toml| Self::upgrade(&config_toml, &info.config_toml_path))
+                .unwrap_or_else(|| Toml::file(&info.config_toml_path));
+
+            Figment::from(toml).merge(Env::prefixed(CONFIG_OVERRIDE_PREFIX).split(CONFIG_OVERRIDE_SEPARATOR))
         };
 
         // Make sure user has provided the mandatory options.
@@ -335,7 +510,7 @@ impl Configuration {
         // Build final configuration.
         let settings: Settings = figment.extract()?;
 
-        if settings.metadata.schema_version != Version::new(VERSION_2) {
+        if settings.metadata.schema_version != Version::new(VERSION_3) {
             return Err(Error::UnsupportedVersion {
                 version: settings.metadata.schema_version,
             });
@@ -344,6 +519,19 @@ impl Configuration {
         Ok(settings)
     }
 
+    /// It returns the configuration upgraded to the latest schema version, or
+    /// `None` if there is nothing to upgrade.
+    fn upgrade(config_toml: &str, source: &str) -> Option<Data<Toml>> {
+        let upgraded = migration::upgrade(config_toml)?;
+
+        println!(
+            "Upgrading configuration from `{source}` from schema version {} to {} in memory. Run `cargo run --bin upgrade_config` to upgrade the file ...",
+            upgraded.from_version, upgraded.to_version
+        );
+
+        Some(Toml::string(&upgraded.config_toml))
+    }
+
     /// Some configuration options are mandatory. The tracker will panic if
     /// the user doesn't provide an explicit value for them from one of the
     /// configuration sources: TOML or ENV VARS.
@@ -387,6 +575,13 @@ impl Configuration {
         let settings_lock = self.settings.read().await;
         settings_lock.net.base_url.as_ref().map(std::string::ToString::to_string)
     }
+
+    /// It returns the options that changed since the application started.
+    pub async fn get_changes_since_startup(&self) -> SettingsChanges {
+        let settings_lock = self.settings.read().await;
+
+        SettingsChanges::between(&self.startup_settings, &settings_lock)
+    }
 }
 
 #[cfg(test)]
@@ -394,7 +589,7 @@ mod tests {
 
     use url::Url;
 
-    use crate::config::{ApiToken, Configuration, Info, SecretKey, Settings};
+    use crate::config::{ApiToken, Configuration, Error, Info, SecretKey, Settings};
 
     #[cfg(test)]
     fn default_config_toml() -> String {
@@ -456,6 +651,13 @@ mod tests {
         assert_eq!(configuration.get_api_base_url().await, Some("http://localhost/".to_string()));
     }
 
+    #[tokio::test]
+    async fn configuration_should_not_be_reloaded_when_it_was_not_loaded_from_a_file_or_an_env_var() {
+        let configuration = Configuration::default();
+
+        assert!(matches!(configuration.reload().await, Err(Error::NotReloadable)));
+    }
+
     #[tokio::test]
     async fn configuration_could_be_loaded_from_a_toml_string() {
         figment::Jail::expect_with(|jail| {
@@ -591,7 +793,7 @@ mod tests {
         use url::Url;
 
         use crate::config::validator::Validator;
-        use crate::config::Configuration;
+        use crate::config::{ApiToken, BackupTracker, Configuration};
 
         #[tokio::test]
         async fn udp_trackers_in_private_mode_are_not_supported() {
@@ -603,5 +805,21 @@ mod tests {
 
             assert!(settings_lock.validate().is_err());
         }
+
+        #[tokio::test]
+        async fn backup_trackers_in_private_mode_are_not_supported() {
+            let configuration = Configuration::default();
+
+            let mut settings_lock = configuration.settings.write().await;
+            settings_lock.tracker.private = true;
+            settings_lock.tracker.url = Url::parse("http://localhost:7070").unwrap();
+            settings_lock.tracker.backups = vec![BackupTracker {
+                api_url: Url::parse("http://backup:1212/").unwrap(),
+                token: ApiToken::new("MyAccessToken"),
+                url: Url::parse("http://backup:7070").unwrap(),
+            }];
+
+            assert!(settings_lock.validate().is_err());
+        }
     }
 }
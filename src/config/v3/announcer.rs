@@ -0,0 +1,92 @@
+use serde::{Deserialize, Serialize};
+use url::Url;
+
+/// Configuration for the announcer that posts new-torrent announcements to
+/// external chat channels.
+///
+/// When this section is not present, no announcements are sent.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct Announcer {
+    /// Interval in seconds between checks for new events. Default to `10`.
+    #[serde(default = "Announcer::default_poll_interval_seconds")]
+    pub poll_interval_seconds: u64,
+
+    /// The channels where announcements are posted.
+    #[serde(default = "Announcer::default_channels")]
+    pub channels: Vec<Channel>,
+}
+
+impl Default for Announcer {
+    fn default() -> Self {
+        Self {
+            poll_interval_seconds: Self::default_poll_interval_seconds(),
+            channels: Self::default_channels(),
+        }
+    }
+}
+
+impl Announcer {
+    fn default_poll_interval_seconds() -> u64 {
+        10
+    }
+
+    fn default_channels() -> Vec<Channel> {
+        Vec::new()
+    }
+}
+
+/// A channel where announcements are posted.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct Channel {
+    /// Where the announcement is sent.
+    pub target: Target,
+
+    /// Only torrents in these categories are announced. If it's empty
+    /// torrents in all categories are announced.
+    #[serde(default = "Channel::default_categories")]
+    pub categories: Vec<String>,
+
+    /// The message template. It uses the [Tera](https://keats.github.io/tera/)
+    /// syntax. Available variables: `torrent_id`, `info_hash`, `title`,
+    /// `category` and `uploader`.
+    #[serde(default = "Channel::default_template")]
+    pub template: String,
+}
+
+impl Channel {
+    fn default_categories() -> Vec<String> {
+        Vec::new()
+    }
+
+    fn default_template() -> String {
+        "New torrent: {{ title }} [{{ category }}] uploaded by {{ uploader }}".to_string()
+    }
+
+    /// Whether torrents in the category are announced in this channel.
+    #[must_use]
+    pub fn accepts_category(&self, category: &str) -> bool {
+        self.categories.is_empty() || self.categories.iter().any(|c| c == category)
+    }
+}
+
+/// The announcement target.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(tag = "kind", rename_all = "lowercase")]
+pub enum Target {
+    /// An IRC channel. The announcer connects, joins the channel, sends the
+    /// message and quits.
+    Irc {
+        server: String,
+        port: u16,
+        nickname: String,
+        channel: String,
+    },
+    /// A Matrix room using the client-server API.
+    Matrix {
+        homeserver_url: Url,
+        access_token: String,
+        room_id: String,
+    },
+    /// A Discord channel webhook.
+    Discord { webhook_url: Url },
+}
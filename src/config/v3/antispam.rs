@@ -0,0 +1,50 @@
+use serde::{Deserialize, Serialize};
+
+use crate::config::validator::{ValidationError, Validator};
+
+/// Configuration for the anti-spam heuristics.
+///
+/// Uploads whose description has too many links are held for moderation.
+/// The banned patterns are managed by the administrators through the API and
+/// they are always checked. When this section is not present, only the
+/// banned patterns are checked.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct Antispam {
+    /// Max number of links in the description. Default to `10`.
+    #[serde(default = "Antispam::default_max_links")]
+    pub max_links: usize,
+
+    /// Max ratio of links to words in the description, from `0.0` to `1.0`.
+    /// Default to `0.3`.
+    #[serde(default = "Antispam::default_max_link_density")]
+    pub max_link_density: f64,
+}
+
+impl Default for Antispam {
+    fn default() -> Self {
+        Self {
+            max_links: Self::default_max_links(),
+            max_link_density: Self::default_max_link_density(),
+        }
+    }
+}
+
+impl Validator for Antispam {
+    fn validate(&self) -> Result<(), ValidationError> {
+        if self.max_link_density <= 0.0 || self.max_link_density > 1.0 {
+            return Err(ValidationError::InvalidLinkDensity);
+        }
+
+        Ok(())
+    }
+}
+
+impl Antispam {
+    fn default_max_links() -> usize {
+        10
+    }
+
+    fn default_max_link_density() -> f64 {
+        0.3
+    }
+}
@@ -0,0 +1,221 @@
+use serde::{Deserialize, Serialize};
+
+/// Core configuration for the API
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct Api {
+    /// The default page size for torrent lists.
+    #[serde(default = "Api::default_default_torrent_page_size")]
+    pub default_torrent_page_size: u8,
+
+    /// The maximum page size for torrent lists.
+    #[serde(default = "Api::default_max_torrent_page_size")]
+    pub max_torrent_page_size: u8,
+
+    /// The default shape of the JSON responses. Clients can ask for a
+    /// different one with the `format` query parameter or the
+    /// `Accept-Profile` header.
+    #[serde(default = "Api::default_response_format")]
+    pub response_format: ResponseFormat,
+
+    /// Serve the `OpenAPI` specification at `/v1/openapi.json` and the
+    /// Swagger UI at `/v1/docs`. Default to `false`.
+    #[serde(default = "Api::default_openapi")]
+    pub openapi: bool,
+
+    /// Serve the embeddable widgets with the latest torrents at
+    /// `/v1/widget/torrents`. Disable them for private instances. Default to
+    /// `true`.
+    #[serde(default = "Api::default_widgets")]
+    pub widgets: bool,
+
+    /// Compression of the responses. Changing it requires a restart.
+    #[serde(default = "Api::default_compression")]
+    pub compression: Compression,
+
+    /// The `Cache-Control` header of the successful `GET` responses, by
+    /// route. The first rule matching the request path is used. Responses
+    /// whose handler already sets the header are not changed.
+    #[serde(default = "Api::default_cache_control")]
+    pub cache_control: Vec<CacheControlRule>,
+}
+
+impl Default for Api {
+    fn default() -> Self {
+        Self {
+            default_torrent_page_size: Api::default_default_torrent_page_size(),
+            max_torrent_page_size: Api::default_max_torrent_page_size(),
+            response_format: Api::default_response_format(),
+            openapi: Api::default_openapi(),
+            widgets: Api::default_widgets(),
+            compression: Api::default_compression(),
+            cache_control: Api::default_cache_control(),
+        }
+    }
+}
+
+impl Api {
+    fn default_default_torrent_page_size() -> u8 {
+        10
+    }
+
+    fn default_max_torrent_page_size() -> u8 {
+        30
+    }
+
+    fn default_response_format() -> ResponseFormat {
+        ResponseFormat::default()
+    }
+
+    fn default_openapi() -> bool {
+        false
+    }
+
+    fn default_widgets() -> bool {
+        true
+    }
+
+    fn default_compression() -> Compression {
+        Compression::default()
+    }
+
+    fn default_cache_control() -> Vec<CacheControlRule> {
+        vec![
+            CacheControlRule {
+                path: "/v1/proxy/image/:url".to_string(),
+                value: "public, max-age=86400".to_string(),
+            },
+            CacheControlRule {
+                path: "/v1/torrents".to_string(),
+                value: "public, max-age=60".to_string(),
+            },
+        ]
+    }
+}
+
+/// Compression of the responses. Clients choose the algorithm with the
+/// `Accept-Encoding` header among the enabled ones.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct Compression {
+    /// Compress the responses. Default to `true`.
+    #[serde(default = "Compression::default_enabled")]
+    pub enabled: bool,
+
+    /// Enable the `gzip` encoding. Default to `true`.
+    #[serde(default = "Compression::default_gzip")]
+    pub gzip: bool,
+
+    /// Enable the `br` (Brotli) encoding. Default to `true`.
+    #[serde(default = "Compression::default_br")]
+    pub br: bool,
+
+    /// Enable the `deflate` encoding. Default to `true`.
+    #[serde(default = "Compression::default_deflate")]
+    pub deflate: bool,
+
+    /// Enable the `zstd` encoding. Default to `true`.
+    #[serde(default = "Compression::default_zstd")]
+    pub zstd: bool,
+
+    /// Responses smaller than this size, in bytes, are not compressed.
+    /// Images and event streams are never compressed. Default to `32`.
+    #[serde(default = "Compression::default_min_size_bytes")]
+    pub min_size_bytes: u16,
+}
+
+impl Default for Compression {
+    fn default() -> Self {
+        Self {
+            enabled: Self::default_enabled(),
+            gzip: Self::default_gzip(),
+            br: Self::default_br(),
+            deflate: Self::default_deflate(),
+            zstd: Self::default_zstd(),
+            min_size_bytes: Self::default_min_size_bytes(),
+        }
+    }
+}
+
+impl Compression {
+    fn default_enabled() -> bool {
+        true
+    }
+
+    fn default_gzip() -> bool {
+        true
+    }
+
+    fn default_br() -> bool {
+        true
+    }
+
+    fn default_deflate() -> bool {
+        true
+    }
+
+    fn default_zstd() -> bool {
+        true
+    }
+
+    fn default_min_size_bytes() -> u16 {
+        32
+    }
+}
+
+/// The `Cache-Control` header for the routes matching a path pattern.
+///
+/// For example, to cache the proxied images for a day in a CDN:
+///
+/// ```toml
+/// [[api.cache_control]]
+/// path = "/v1/proxy/image/:url"
+/// value = "public, max-age=86400"
+/// ```
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct CacheControlRule {
+    /// Path pattern using the router syntax. Segments starting with `:`
+    /// match any value and a trailing `*` matches any remaining segments,
+    /// for example `/v1/torrent/:info_hash` or `/v1/category/*`.
+    pub path: String,
+    /// Value of the `Cache-Control` header, for example
+    /// `public, max-age=60`.
+    pub value: String,
+}
+
+/// Shape of the JSON responses.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+pub struct ResponseFormat {
+    /// Wrap the successful responses in a `{"data": ...}` envelope. Default
+    /// to `true`.
+    #[serde(default = "ResponseFormat::default_envelope")]
+    pub envelope: bool,
+
+    /// The naming convention of the fields. Default to `snake_case`.
+    #[serde(default = "ResponseFormat::default_field_naming")]
+    pub field_naming: FieldNaming,
+}
+
+impl Default for ResponseFormat {
+    fn default() -> Self {
+        Self {
+            envelope: Self::default_envelope(),
+            field_naming: Self::default_field_naming(),
+        }
+    }
+}
+
+impl ResponseFormat {
+    fn default_envelope() -> bool {
+        true
+    }
+
+    fn default_field_naming() -> FieldNaming {
+        FieldNaming::SnakeCase
+    }
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum FieldNaming {
+    SnakeCase,
+    CamelCase,
+}
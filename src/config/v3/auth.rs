@@ -0,0 +1,226 @@
+use std::fmt;
+
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+
+use crate::config::validator::{ValidationError, Validator};
+
+/// Registered JWT claim names and the claims set by the index. Extra claims
+/// can't use them.
+const RESERVED_CLAIMS: &[&str] = &["user", "exp", "iat", "nbf", "iss", "sub", "aud", "jti"];
+
+/// Authentication options.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct Auth {
+    /// The secret key used to sign JWT tokens.
+    #[serde(default = "Auth::default_user_claim_token_pepper")]
+    pub user_claim_token_pepper: ClaimTokenPepper,
+
+    /// The password constraints
+    #[serde(default = "Auth::default_password_constraints")]
+    pub password_constraints: PasswordConstraints,
+
+    /// The JSON Web Token options.
+    #[serde(default = "Auth::default_jwt")]
+    pub jwt: Option<Jwt>,
+}
+
+impl Default for Auth {
+    fn default() -> Self {
+        Self {
+            password_constraints: Self::default_password_constraints(),
+            user_claim_token_pepper: Self::default_user_claim_token_pepper(),
+            jwt: Self::default_jwt(),
+        }
+    }
+}
+
+impl Auth {
+    pub fn override_user_claim_token_pepper(&mut self, user_claim_token_pepper: &str) {
+        self.user_claim_token_pepper = ClaimTokenPepper::new(user_claim_token_pepper);
+    }
+
+    fn default_user_claim_token_pepper() -> ClaimTokenPepper {
+        ClaimTokenPepper::new("MaxVerstappenWC2021")
+    }
+
+    fn default_password_constraints() -> PasswordConstraints {
+        PasswordConstraints::default()
+    }
+
+    fn default_jwt() -> Option<Jwt> {
+        None
+    }
+}
+
+impl Validator for Auth {
+    fn validate(&self) -> Result<(), ValidationError> {
+        if let Some(jwt) = &self.jwt {
+            jwt.extra_claims.validate()?;
+        }
+
+        Ok(())
+    }
+}
+
+/// JSON Web Token options.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Default)]
+pub struct Jwt {
+    /// Additional claims embedded into the issued tokens, so that frontends
+    /// can enable features without extra API calls.
+    #[serde(default)]
+    pub extra_claims: ExtraClaims,
+}
+
+/// Additional claims embedded into the issued tokens.
+///
+/// ```toml
+/// [auth.jwt.extra_claims.all]
+/// features = ["comments", "requests"]
+///
+/// [auth.jwt.extra_claims.admin]
+/// features = ["comments", "requests", "moderation"]
+/// ```
+///
+/// The claims for the user's role replace the claims for all users with the
+/// same name.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Default)]
+pub struct ExtraClaims {
+    /// Claims for all users.
+    #[serde(default)]
+    pub all: Map<String, Value>,
+    /// Claims for administrators.
+    #[serde(default)]
+    pub admin: Map<String, Value>,
+    /// Claims for registered users who are not administrators.
+    #[serde(default)]
+    pub registered: Map<String, Value>,
+}
+
+impl ExtraClaims {
+    /// It returns the extra claims for a user.
+    #[must_use]
+    pub fn for_user(&self, administrator: bool) -> Map<String, Value> {
+        let mut claims = self.all.clone();
+
+        let role_claims = if administrator { &self.admin } else { &self.registered };
+
+        claims.extend(role_claims.clone());
+
+        claims
+    }
+}
+
+impl Validator for ExtraClaims {
+    fn validate(&self) -> Result<(), ValidationError> {
+        for (name, value) in self.all.iter().chain(&self.admin).chain(&self.registered) {
+            if name.trim().is_empty() || RESERVED_CLAIMS.contains(&name.as_str()) || value.is_null() {
+                return Err(ValidationError::InvalidJwtExtraClaim(name.clone()));
+            }
+        }
+
+        Ok(())
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct ClaimTokenPepper(String);
+
+impl ClaimTokenPepper {
+    /// # Panics
+    ///
+    /// Will panic if the key if empty.
+    #[must_use]
+    pub fn new(key: &str) -> Self {
+        assert!(!key.is_empty(), "secret key cannot be empty");
+
+        Self(key.to_owned())
+    }
+
+    #[must_use]
+    pub fn as_bytes(&self) -> &[u8] {
+        self.0.as_bytes()
+    }
+}
+
+impl fmt::Display for ClaimTokenPepper {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.0)
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct PasswordConstraints {
+    /// The maximum password length.
+    #[serde(default = "PasswordConstraints::default_max_password_length")]
+    pub max_password_length: usize,
+    /// The minimum password length.
+    #[serde(default = "PasswordConstraints::default_min_password_length")]
+    pub min_password_length: usize,
+}
+
+impl Default for PasswordConstraints {
+    fn default() -> Self {
+        Self {
+            max_password_length: Self::default_max_password_length(),
+            min_password_length: Self::default_min_password_length(),
+        }
+    }
+}
+
+impl PasswordConstraints {
+    fn default_min_password_length() -> usize {
+        6
+    }
+
+    fn default_max_password_length() -> usize {
+        64
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::{json, Map, Value};
+
+    use super::{ClaimTokenPepper, ExtraClaims};
+    use crate::config::validator::Validator;
+
+    fn claims(value: Value) -> Map<String, Value> {
+        value.as_object().unwrap().clone()
+    }
+
+    #[test]
+    #[should_panic(expected = "secret key cannot be empty")]
+    fn secret_key_can_not_be_empty() {
+        drop(ClaimTokenPepper::new(""));
+    }
+
+    #[test]
+    fn role_extra_claims_should_replace_the_claims_for_all_users() {
+        let extra_claims = ExtraClaims {
+            all: claims(json!({"features": ["comments"], "theme": "dark"})),
+            admin: claims(json!({"features": ["comments", "moderation"]})),
+            registered: Map::new(),
+        };
+
+        assert_eq!(
+            Value::Object(extra_claims.for_user(true)),
+            json!({"features": ["comments", "moderation"], "theme": "dark"})
+        );
+        assert_eq!(
+            Value::Object(extra_claims.for_user(false)),
+            json!({"features": ["comments"], "theme": "dark"})
+        );
+    }
+
+    #[test]
+    fn extra_claims_should_not_replace_the_index_claims() {
+        let extra_claims = ExtraClaims {
+            all: Map::new(),
+            admin: Map::new(),
+            registered: claims(json!({"exp": 0})),
+        };
+
+        assert!(extra_claims.validate().is_err());
+    }
+}
@@ -0,0 +1,98 @@
+use serde::{Deserialize, Serialize};
+use url::Url;
+
+use super::{ValidationError, Validator};
+
+/// Configuration for the cache of hot queries, like the categories, the tags
+/// or the torrent listings.
+///
+/// Cached results are invalidated when the application changes them. They
+/// can also be changed by other instances or background jobs, so results may
+/// be outdated for up to `ttl_seconds`. When this section is not present,
+/// queries are not cached.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct Cache {
+    /// The cache backend. Default to `memory`.
+    #[serde(default = "Cache::default_backend")]
+    pub backend: Backend,
+
+    /// Time in seconds the cached results are used. Default to `60`.
+    #[serde(default = "Cache::default_ttl_seconds")]
+    pub ttl_seconds: u64,
+
+    /// Maximum number of cached results in the `memory` backend. The least
+    /// recently used results are evicted first. Default to `1000`.
+    #[serde(default = "Cache::default_capacity")]
+    pub capacity: usize,
+
+    /// The Redis configuration. Required if the backend is `redis`.
+    #[serde(default = "Cache::default_redis")]
+    pub redis: Option<Redis>,
+}
+
+impl Validator for Cache {
+    fn validate(&self) -> Result<(), ValidationError> {
+        if self.backend == Backend::Redis && self.redis.is_none() {
+            return Err(ValidationError::MissingRedisConfiguration);
+        }
+
+        Ok(())
+    }
+}
+
+impl Default for Cache {
+    fn default() -> Self {
+        Self {
+            backend: Self::default_backend(),
+            ttl_seconds: Self::default_ttl_seconds(),
+            capacity: Self::default_capacity(),
+            redis: Self::default_redis(),
+        }
+    }
+}
+
+impl Cache {
+    fn default_backend() -> Backend {
+        Backend::Memory
+    }
+
+    fn default_ttl_seconds() -> u64 {
+        60
+    }
+
+    fn default_capacity() -> usize {
+        1000
+    }
+
+    fn default_redis() -> Option<Redis> {
+        None
+    }
+}
+
+/// The cache backend.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum Backend {
+    /// In-memory LRU cache. Each instance has its own cache.
+    Memory,
+    /// Redis server shared by all the instances.
+    Redis,
+}
+
+/// The Redis client configuration.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct Redis {
+    /// The Redis server URL, for example `redis://127.0.0.1:6379/0`.
+    pub url: Url,
+
+    /// Prefix for the keys, so several indexes can share a Redis server.
+    /// Default to `torrust-index:`.
+    #[serde(default = "Redis::default_key_prefix")]
+    pub key_prefix: String,
+}
+
+impl Redis {
+    fn default_key_prefix() -> String {
+        "torrust-index:".to_string()
+    }
+}
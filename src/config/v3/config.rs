@@ -0,0 +1,24 @@
+use serde::{Deserialize, Serialize};
+
+/// Options about how the configuration itself is checked.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct Config {
+    /// Whether the services in the configuration (tracker, SMTP server,
+    /// etc.) are probed at startup. Refer to [`check_services`](crate::config::validator::check_services).
+    #[serde(default = "Config::default_active_checks")]
+    pub active_checks: bool,
+}
+
+impl Default for Config {
+    fn default() -> Self {
+        Self {
+            active_checks: Self::default_active_checks(),
+        }
+    }
+}
+
+impl Config {
+    fn default_active_checks() -> bool {
+        false
+    }
+}
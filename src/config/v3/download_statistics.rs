@@ -0,0 +1,30 @@
+use serde::{Deserialize, Serialize};
+
+/// Configuration for the `.torrent` download statistics.
+///
+/// When this section is present, the index counts downloads by client family
+/// (qBittorrent, Transmission, curl, ...), derived from the `User-Agent`
+/// header. Only daily counters are stored, never the requests themselves.
+/// When this section is not present, downloads are not recorded.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct DownloadStatistics {
+    /// Whether to also count downloads by raw `User-Agent` string. Raw
+    /// values can identify unusual setups, so they are not stored unless
+    /// enabled. Default to `false`.
+    #[serde(default = "DownloadStatistics::default_store_raw_user_agents")]
+    pub store_raw_user_agents: bool,
+}
+
+impl Default for DownloadStatistics {
+    fn default() -> Self {
+        Self {
+            store_raw_user_agents: Self::default_store_raw_user_agents(),
+        }
+    }
+}
+
+impl DownloadStatistics {
+    fn default_store_raw_user_agents() -> bool {
+        false
+    }
+}
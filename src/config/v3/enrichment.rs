@@ -0,0 +1,117 @@
+use serde::{Deserialize, Serialize};
+use url::Url;
+
+use crate::config::validator::{ValidationError, Validator};
+
+/// Configuration for the metadata enrichment providers.
+///
+/// Uploaders can fetch the poster, the synopsis and the canonical metadata
+/// of their torrents from these providers, using an external ID or the
+/// title. When this section is not present, the metadata can't be fetched.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct Enrichment {
+    /// The TMDB provider, for movies and TV shows. It also resolves IMDb
+    /// IDs.
+    #[serde(default = "Enrichment::default_tmdb")]
+    pub tmdb: Option<Tmdb>,
+
+    /// The `MusicBrainz` provider, for music releases.
+    #[serde(default = "Enrichment::default_musicbrainz")]
+    pub musicbrainz: Option<MusicBrainz>,
+}
+
+impl Validator for Enrichment {
+    fn validate(&self) -> Result<(), ValidationError> {
+        if self.tmdb.is_none() && self.musicbrainz.is_none() {
+            return Err(ValidationError::MissingMetadataProviders);
+        }
+
+        Ok(())
+    }
+}
+
+impl Enrichment {
+    fn default_tmdb() -> Option<Tmdb> {
+        None
+    }
+
+    fn default_musicbrainz() -> Option<MusicBrainz> {
+        None
+    }
+}
+
+/// TMDB (The Movie Database) API configuration.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct Tmdb {
+    /// The API key.
+    pub api_key: String,
+
+    /// The API URL. Default to `https://api.themoviedb.org/3/`.
+    #[serde(default = "Tmdb::default_api_url")]
+    pub api_url: Url,
+
+    /// The language of the titles and synopses. Default to `en-US`.
+    #[serde(default = "Tmdb::default_language")]
+    pub language: String,
+
+    /// The base URL of the posters. Default to
+    /// `https://image.tmdb.org/t/p/w500/`.
+    #[serde(default = "Tmdb::default_image_url")]
+    pub image_url: Url,
+}
+
+impl Tmdb {
+    fn default_api_url() -> Url {
+        Url::parse("https://api.themoviedb.org/3/").expect("the TMDB API URL should be valid")
+    }
+
+    fn default_language() -> String {
+        "en-US".to_string()
+    }
+
+    fn default_image_url() -> Url {
+        Url::parse("https://image.tmdb.org/t/p/w500/").expect("the TMDB image URL should be valid")
+    }
+}
+
+/// `MusicBrainz` API configuration. It does not need an API key.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct MusicBrainz {
+    /// The API URL. Default to `https://musicbrainz.org/ws/2/`.
+    #[serde(default = "MusicBrainz::default_api_url")]
+    pub api_url: Url,
+
+    /// The Cover Art Archive URL, for the release covers. Default to
+    /// `https://coverartarchive.org/`.
+    #[serde(default = "MusicBrainz::default_cover_art_url")]
+    pub cover_art_url: Url,
+
+    /// The `User-Agent` sent to the API. `MusicBrainz` requires one
+    /// identifying the application and a contact.
+    #[serde(default = "MusicBrainz::default_user_agent")]
+    pub user_agent: String,
+}
+
+impl Default for MusicBrainz {
+    fn default() -> Self {
+        Self {
+            api_url: Self::default_api_url(),
+            cover_art_url: Self::default_cover_art_url(),
+            user_agent: Self::default_user_agent(),
+        }
+    }
+}
+
+impl MusicBrainz {
+    fn default_api_url() -> Url {
+        Url::parse("https://musicbrainz.org/ws/2/").expect("the MusicBrainz API URL should be valid")
+    }
+
+    fn default_cover_art_url() -> Url {
+        Url::parse("https://coverartarchive.org/").expect("the Cover Art Archive URL should be valid")
+    }
+
+    fn default_user_agent() -> String {
+        "torrust-index ( https://github.com/torrust/torrust-index )".to_string()
+    }
+}
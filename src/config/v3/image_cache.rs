@@ -0,0 +1,138 @@
+use camino::Utf8PathBuf;
+use serde::{Deserialize, Serialize};
+
+/// Configuration for the image proxy cache.
+///
+/// Users have a cache quota per period. For example: 100MB per day.
+/// When users are navigating the site, they will be downloading images that are
+/// embedded in the torrent description. These images will be cached in the
+/// proxy. The proxy will not download new images if the user has reached the
+/// quota.
+///
+/// Images are stored in memory by default. They can also be stored in a
+/// local directory or in the S3-compatible bucket of the `[storage.s3]`
+/// section, so they are kept after restarts. In those cases a background job
+/// removes the least recently used images when the cache is bigger than the
+/// `capacity`.
+#[allow(clippy::module_name_repetitions)]
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct ImageCache {
+    /// The storage backend. Default to `memory`.
+    #[serde(default = "ImageCache::default_backend")]
+    pub backend: Backend,
+
+    /// Cache size in bytes.
+    #[serde(default = "ImageCache::default_capacity")]
+    pub capacity: usize,
+
+    /// Maximum size in bytes for a single image.
+    #[serde(default = "ImageCache::default_entry_size_limit")]
+    pub entry_size_limit: usize,
+
+    /// Maximum time in seconds to wait for downloading the image form the original source.
+    #[serde(default = "ImageCache::default_max_request_timeout_ms")]
+    pub max_request_timeout_ms: u64,
+
+    /// Users have a cache quota per period. For example: 100MB per day.
+    /// This is the maximum size in bytes (100MB in bytes).    
+    #[serde(default = "ImageCache::default_user_quota_bytes")]
+    pub user_quota_bytes: usize,
+
+    /// Users have a cache quota per period. For example: 100MB per day.
+    /// This is the period in seconds (1 day in seconds).
+    #[serde(default = "ImageCache::default_user_quota_period_seconds")]
+    pub user_quota_period_seconds: u64,
+
+    /// The directory where the images are stored with the `filesystem`
+    /// backend. Default to `./storage/index/lib/image_cache`.
+    #[serde(default = "ImageCache::default_path")]
+    pub path: Utf8PathBuf,
+
+    /// Prefix for the object keys with the `s3` backend. Default to
+    /// `image-cache/`.
+    #[serde(default = "ImageCache::default_s3_key_prefix")]
+    pub s3_key_prefix: String,
+
+    /// Interval in seconds between executions of the eviction job. It
+    /// removes the least recently used images from the `filesystem` and `s3`
+    /// backends, and the expired user quotas. Default to `300`.
+    #[serde(default = "ImageCache::default_eviction_interval_seconds")]
+    pub eviction_interval_seconds: u64,
+
+    /// Widths in pixels of the resized variants served by the image proxy
+    /// with the `width` query parameter. Requested widths are rounded up to
+    /// one of these. Default to `[160, 320, 640, 1280]`.
+    #[serde(default = "ImageCache::default_thumbnail_widths")]
+    pub thumbnail_widths: Vec<u32>,
+}
+
+impl Default for ImageCache {
+    fn default() -> Self {
+        Self {
+            backend: Self::default_backend(),
+            max_request_timeout_ms: Self::default_max_request_timeout_ms(),
+            capacity: Self::default_capacity(),
+            entry_size_limit: Self::default_entry_size_limit(),
+            user_quota_period_seconds: Self::default_user_quota_period_seconds(),
+            user_quota_bytes: Self::default_user_quota_bytes(),
+            path: Self::default_path(),
+            s3_key_prefix: Self::default_s3_key_prefix(),
+            eviction_interval_seconds: Self::default_eviction_interval_seconds(),
+            thumbnail_widths: Self::default_thumbnail_widths(),
+        }
+    }
+}
+
+impl ImageCache {
+    fn default_max_request_timeout_ms() -> u64 {
+        1000
+    }
+
+    fn default_capacity() -> usize {
+        128_000_000
+    }
+
+    fn default_entry_size_limit() -> usize {
+        4_000_000
+    }
+
+    fn default_user_quota_period_seconds() -> u64 {
+        3600
+    }
+
+    fn default_user_quota_bytes() -> usize {
+        64_000_000
+    }
+
+    fn default_backend() -> Backend {
+        Backend::Memory
+    }
+
+    fn default_path() -> Utf8PathBuf {
+        Utf8PathBuf::from("./storage/index/lib/image_cache")
+    }
+
+    fn default_s3_key_prefix() -> String {
+        "image-cache/".to_string()
+    }
+
+    fn default_eviction_interval_seconds() -> u64 {
+        300
+    }
+
+    fn default_thumbnail_widths() -> Vec<u32> {
+        vec![160, 320, 640, 1280]
+    }
+}
+
+/// The image cache storage backend.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum Backend {
+    /// In memory. Images are lost when the application restarts.
+    Memory,
+    /// Files in a local directory.
+    Filesystem,
+    /// Objects in the `[storage.s3]` bucket.
+    S3,
+}
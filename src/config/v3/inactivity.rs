@@ -0,0 +1,89 @@
+use serde::{Deserialize, Serialize};
+
+use crate::config::validator::{ValidationError, Validator};
+
+/// Configuration for the account inactivity policy.
+///
+/// Users who have not logged in for `warn_after_months` months are warned by
+/// email. If they don't log in after `action_after_months` months, their
+/// accounts are disabled or deleted. A month is 30 days. Administrators and
+/// exempted users are never affected. When this section is not present,
+/// inactive accounts are kept.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct Inactivity {
+    /// Months without logging in before the user is warned. Default to `11`.
+    #[serde(default = "Inactivity::default_warn_after_months")]
+    pub warn_after_months: u32,
+
+    /// Months without logging in before the account is disabled or deleted.
+    /// It must be greater than `warn_after_months`. Default to `12`.
+    #[serde(default = "Inactivity::default_action_after_months")]
+    pub action_after_months: u32,
+
+    /// What to do with the inactive accounts. Default to `disable`.
+    #[serde(default = "Inactivity::default_action")]
+    pub action: Action,
+
+    /// Whether to keep the torrents uploaded by deleted users. Deleting a
+    /// user deletes their torrents, so accounts with uploads are disabled
+    /// instead of deleted. Default to `true`.
+    #[serde(default = "Inactivity::default_preserve_uploads")]
+    pub preserve_uploads: bool,
+
+    /// Interval in seconds between checks. Default to `86400` (one day).
+    #[serde(default = "Inactivity::default_check_interval_seconds")]
+    pub check_interval_seconds: u64,
+}
+
+impl Default for Inactivity {
+    fn default() -> Self {
+        Self {
+            warn_after_months: Self::default_warn_after_months(),
+            action_after_months: Self::default_action_after_months(),
+            action: Self::default_action(),
+            preserve_uploads: Self::default_preserve_uploads(),
+            check_interval_seconds: Self::default_check_interval_seconds(),
+        }
+    }
+}
+
+impl Inactivity {
+    fn default_warn_after_months() -> u32 {
+        11
+    }
+
+    fn default_action_after_months() -> u32 {
+        12
+    }
+
+    fn default_action() -> Action {
+        Action::Disable
+    }
+
+    fn default_preserve_uploads() -> bool {
+        true
+    }
+
+    fn default_check_interval_seconds() -> u64 {
+        86_400
+    }
+}
+
+impl Validator for Inactivity {
+    fn validate(&self) -> Result<(), ValidationError> {
+        if self.action_after_months <= self.warn_after_months {
+            return Err(ValidationError::InvalidInactivityPeriods);
+        }
+
+        Ok(())
+    }
+}
+
+/// What to do with the inactive accounts.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum Action {
+    /// Disabled users can't log in until an administrator exempts them.
+    Disable,
+    Delete,
+}
@@ -0,0 +1,196 @@
+use std::fmt;
+
+use serde::{Deserialize, Serialize};
+use url::Url;
+
+use crate::config::validator::{ValidationError, Validator};
+
+/// Integrations with external systems.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct Integrations {
+    /// Delegated uploads using HMAC-signed server-to-server requests.
+    #[serde(default = "Integrations::default_signed_uploads")]
+    pub signed_uploads: Option<SignedUploads>,
+
+    /// Seeding summaries for external bonus systems.
+    #[serde(default = "Integrations::default_seeding_rewards")]
+    pub seeding_rewards: Option<SeedingRewards>,
+}
+
+impl Default for Integrations {
+    fn default() -> Self {
+        Self {
+            signed_uploads: Self::default_signed_uploads(),
+            seeding_rewards: Self::default_seeding_rewards(),
+        }
+    }
+}
+
+impl Integrations {
+    fn default_signed_uploads() -> Option<SignedUploads> {
+        None
+    }
+
+    fn default_seeding_rewards() -> Option<SeedingRewards> {
+        None
+    }
+}
+
+impl Validator for Integrations {
+    fn validate(&self) -> Result<(), ValidationError> {
+        if let Some(signed_uploads) = &self.signed_uploads {
+            if signed_uploads.secret.is_empty() {
+                return Err(ValidationError::EmptySigningSecret(
+                    "integrations.signed_uploads.secret".to_string(),
+                ));
+            }
+        }
+
+        if let Some(seeding_rewards) = &self.seeding_rewards {
+            if seeding_rewards.webhook_secret.as_ref().is_some_and(SigningSecret::is_empty) {
+                return Err(ValidationError::EmptySigningSecret(
+                    "integrations.seeding_rewards.webhook_secret".to_string(),
+                ));
+            }
+        }
+
+        Ok(())
+    }
+}
+
+/// Signed uploads configuration.
+///
+/// Trusted external systems (for example, a release pipeline) can upload
+/// torrents on behalf of a user by signing the request with a shared secret
+/// instead of using the user's session token.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct SignedUploads {
+    /// The shared secret used to sign the requests with HMAC-SHA256.
+    pub secret: SigningSecret,
+
+    /// The maximum difference in seconds allowed between the request
+    /// timestamp and the server clock. Default to `300`.
+    #[serde(default = "SignedUploads::default_max_clock_skew_seconds")]
+    pub max_clock_skew_seconds: u64,
+}
+
+impl SignedUploads {
+    fn default_max_clock_skew_seconds() -> u64 {
+        300
+    }
+}
+
+/// Seeding rewards configuration.
+///
+/// The index periodically publishes a seeding summary for each user with
+/// seeded uploads. External bonus systems consume them with the API or
+/// receive them in a webhook, and write back their adjustments to the users'
+/// points balance with the API.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct SeedingRewards {
+    /// Interval in seconds between seeding summaries. Default to `86400`
+    /// (one day).
+    #[serde(default = "SeedingRewards::default_summary_interval_seconds")]
+    pub summary_interval_seconds: u64,
+
+    /// Interval in seconds between checks for summaries pending delivery to
+    /// the webhook. Default to `60`.
+    #[serde(default = "SeedingRewards::default_poll_interval_seconds")]
+    pub poll_interval_seconds: u64,
+
+    /// The webhook receiving the summaries. When it's not set, the summaries
+    /// are only available in the API.
+    #[serde(default = "SeedingRewards::default_webhook_url")]
+    pub webhook_url: Option<Url>,
+
+    /// The shared secret used to sign the webhook requests with
+    /// HMAC-SHA256. When it's not set, the requests are not signed.
+    #[serde(default = "SeedingRewards::default_webhook_secret")]
+    pub webhook_secret: Option<SigningSecret>,
+}
+
+impl Default for SeedingRewards {
+    fn default() -> Self {
+        Self {
+            summary_interval_seconds: Self::default_summary_interval_seconds(),
+            poll_interval_seconds: Self::default_poll_interval_seconds(),
+            webhook_url: Self::default_webhook_url(),
+            webhook_secret: Self::default_webhook_secret(),
+        }
+    }
+}
+
+impl SeedingRewards {
+    fn default_summary_interval_seconds() -> u64 {
+        86_400
+    }
+
+    fn default_poll_interval_seconds() -> u64 {
+        60
+    }
+
+    fn default_webhook_url() -> Option<Url> {
+        None
+    }
+
+    fn default_webhook_secret() -> Option<SigningSecret> {
+        None
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct SigningSecret(String);
+
+impl SigningSecret {
+    /// # Panics
+    ///
+    /// Will panic if the secret is empty.
+    #[must_use]
+    pub fn new(secret: &str) -> Self {
+        assert!(!secret.is_empty(), "signing secret cannot be empty");
+
+        Self(secret.to_owned())
+    }
+
+    #[must_use]
+    pub fn as_bytes(&self) -> &[u8] {
+        self.0.as_bytes()
+    }
+
+    /// Secrets loaded from the configuration file are not checked until the
+    /// settings are validated.
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+}
+
+impl fmt::Display for SigningSecret {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.0)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Integrations;
+    use crate::config::validator::{ValidationError, Validator};
+
+    #[test]
+    fn it_should_not_allow_an_empty_signing_secret() {
+        let integrations: Integrations = serde_json::from_str(r#"{ "signed_uploads": { "secret": "" } }"#).unwrap();
+
+        assert!(matches!(
+            integrations.validate(),
+            Err(ValidationError::EmptySigningSecret(option)) if option == "integrations.signed_uploads.secret"
+        ));
+
+        let integrations: Integrations = serde_json::from_str(r#"{ "seeding_rewards": { "webhook_secret": "" } }"#).unwrap();
+
+        assert!(integrations.validate().is_err());
+
+        let integrations: Integrations = serde_json::from_str(r#"{ "signed_uploads": { "secret": "secret" } }"#).unwrap();
+
+        assert!(integrations.validate().is_ok());
+    }
+}
@@ -0,0 +1,45 @@
+use serde::{Deserialize, Serialize};
+
+/// Configuration for the per-user upload quotas.
+///
+/// The limits apply to every user except administrators. They can be
+/// overridden for a given user from the admin API. When this section is not
+/// present, uploads are unlimited unless the user has an override.
+///
+/// ```toml
+/// [limits]
+/// max_uploads_per_day = 20
+/// max_total_upload_size = 1099511627776
+/// ```
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct Limits {
+    /// Max number of torrents a user can upload in the last 24 hours. Magnet
+    /// uploads waiting for their metadata are also counted. Unlimited by
+    /// default.
+    #[serde(default = "Limits::default_max_uploads_per_day")]
+    pub max_uploads_per_day: Option<u32>,
+
+    /// Max total size in bytes of the contents of all the torrents uploaded
+    /// by a user. Unlimited by default.
+    #[serde(default = "Limits::default_max_total_upload_size")]
+    pub max_total_upload_size: Option<u64>,
+}
+
+impl Default for Limits {
+    fn default() -> Self {
+        Self {
+            max_uploads_per_day: Self::default_max_uploads_per_day(),
+            max_total_upload_size: Self::default_max_total_upload_size(),
+        }
+    }
+}
+
+impl Limits {
+    fn default_max_uploads_per_day() -> Option<u32> {
+        None
+    }
+
+    fn default_max_total_upload_size() -> Option<u64> {
+        None
+    }
+}
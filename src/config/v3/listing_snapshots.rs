@@ -0,0 +1,49 @@
+use serde::{Deserialize, Serialize};
+
+/// Configuration for the torrent listing snapshots.
+///
+/// Snapshots are pre-rendered JSON responses for the first pages of the
+/// default torrent listing and the top categories. They are refreshed in the
+/// background and used to serve anonymous requests, so public instances can
+/// survive traffic spikes. When this section is not present, all requests
+/// are served from the database.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct ListingSnapshots {
+    /// Interval in seconds between snapshot refreshes. Default to `30`.
+    #[serde(default = "ListingSnapshots::default_refresh_interval_seconds")]
+    pub refresh_interval_seconds: u64,
+
+    /// Number of pages cached for each listing, starting at the first page.
+    /// Default to `3`.
+    #[serde(default = "ListingSnapshots::default_pages")]
+    pub pages: u32,
+
+    /// Number of categories, with the most torrents, cached in addition to
+    /// the listing with all categories. Default to `10`.
+    #[serde(default = "ListingSnapshots::default_top_categories")]
+    pub top_categories: usize,
+}
+
+impl Default for ListingSnapshots {
+    fn default() -> Self {
+        Self {
+            refresh_interval_seconds: Self::default_refresh_interval_seconds(),
+            pages: Self::default_pages(),
+            top_categories: Self::default_top_categories(),
+        }
+    }
+}
+
+impl ListingSnapshots {
+    fn default_refresh_interval_seconds() -> u64 {
+        30
+    }
+
+    fn default_pages() -> u32 {
+        3
+    }
+
+    fn default_top_categories() -> usize {
+        10
+    }
+}
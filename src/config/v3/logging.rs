@@ -2,6 +2,7 @@ use std::fmt;
 
 use serde::{Deserialize, Serialize};
 use tracing::level_filters::LevelFilter;
+use url::Url;
 
 /// Core configuration for the API
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
@@ -9,12 +10,18 @@ pub struct Logging {
     /// Logging level. Possible values are: `Off`, `Error`, `Warn`, `Info`, `Debug`, `Trace`.
     #[serde(default = "Logging::default_threshold")]
     pub threshold: Threshold,
+
+    /// Export of the tracing spans to an OpenTelemetry collector. When this
+    /// section is not present, spans are only logged.
+    #[serde(default = "Logging::default_tracing")]
+    pub tracing: Option<Tracing>,
 }
 
 impl Default for Logging {
     fn default() -> Self {
         Self {
             threshold: Logging::default_threshold(),
+            tracing: Logging::default_tracing(),
         }
     }
 }
@@ -23,6 +30,45 @@ impl Logging {
     fn default_threshold() -> Threshold {
         Threshold::Info
     }
+
+    fn default_tracing() -> Option<Tracing> {
+        None
+    }
+}
+
+/// OTLP exporter configuration, for operators running Jaeger, Tempo or any
+/// other OpenTelemetry collector.
+///
+/// Spans are exported using OTLP over HTTP with the JSON encoding.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct Tracing {
+    /// The OTLP/HTTP traces endpoint of the collector. Default to
+    /// `http://localhost:4318/v1/traces`.
+    #[serde(default = "Tracing::default_otlp_endpoint")]
+    pub otlp_endpoint: Url,
+
+    /// The `service.name` resource attribute. Default to `torrust-index`.
+    #[serde(default = "Tracing::default_service_name")]
+    pub service_name: String,
+}
+
+impl Default for Tracing {
+    fn default() -> Self {
+        Self {
+            otlp_endpoint: Tracing::default_otlp_endpoint(),
+            service_name: Tracing::default_service_name(),
+        }
+    }
+}
+
+impl Tracing {
+    fn default_otlp_endpoint() -> Url {
+        Url::parse("http://localhost:4318/v1/traces").expect("the default OTLP endpoint should be a valid URL")
+    }
+
+    fn default_service_name() -> String {
+        "torrust-index".to_string()
+    }
 }
 
 #[derive(Serialize, Deserialize, PartialEq, Eq, PartialOrd, Ord, Debug, Hash, Clone)]
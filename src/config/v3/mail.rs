@@ -15,6 +15,16 @@ pub struct Mail {
     /// The SMTP server configuration.
     #[serde(default = "Mail::default_smtp")]
     pub smtp: Smtp,
+
+    /// The new-torrent digest emails configuration. Digests are disabled
+    /// when this section is not present.
+    #[serde(default = "Mail::default_digest")]
+    pub digest: Option<Digest>,
+
+    /// The outbound email queue configuration. Bulk emails are sent through
+    /// the queue.
+    #[serde(default = "Mail::default_queue")]
+    pub queue: Queue,
 }
 
 impl Default for Mail {
@@ -23,6 +33,8 @@ impl Default for Mail {
             from: Self::default_from(),
             reply_to: Self::default_reply_to(),
             smtp: Self::default_smtp(),
+            digest: Self::default_digest(),
+            queue: Self::default_queue(),
         }
     }
 }
@@ -39,6 +51,88 @@ impl Mail {
     fn default_smtp() -> Smtp {
         Smtp::default()
     }
+
+    fn default_digest() -> Option<Digest> {
+        None
+    }
+
+    fn default_queue() -> Queue {
+        Queue::default()
+    }
+}
+
+/// Digest emails with the new torrents in the categories and tags users are
+/// subscribed to.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct Digest {
+    /// Interval in seconds between digests. Default to `86400` (one day).
+    #[serde(default = "Digest::default_interval_seconds")]
+    pub interval_seconds: u64,
+
+    /// Maximum number of torrents in one digest email. Default to `50`.
+    #[serde(default = "Digest::default_max_torrents")]
+    pub max_torrents: usize,
+}
+
+impl Default for Digest {
+    fn default() -> Self {
+        Self {
+            interval_seconds: Self::default_interval_seconds(),
+            max_torrents: Self::default_max_torrents(),
+        }
+    }
+}
+
+impl Digest {
+    fn default_interval_seconds() -> u64 {
+        86_400
+    }
+
+    fn default_max_torrents() -> usize {
+        50
+    }
+}
+
+/// The outbound email queue. Emails are sent in batches to throttle the
+/// delivery rate.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct Queue {
+    /// Interval in seconds between batches. Default to `10`.
+    #[serde(default = "Queue::default_interval_seconds")]
+    pub interval_seconds: u64,
+
+    /// Maximum number of emails sent in one batch. Default to `20`.
+    #[serde(default = "Queue::default_batch_size")]
+    pub batch_size: u32,
+
+    /// Number of failed delivery attempts before an email is discarded.
+    /// Default to `3`.
+    #[serde(default = "Queue::default_max_attempts")]
+    pub max_attempts: u32,
+}
+
+impl Default for Queue {
+    fn default() -> Self {
+        Self {
+            interval_seconds: Self::default_interval_seconds(),
+            batch_size: Self::default_batch_size(),
+            max_attempts: Self::default_max_attempts(),
+        }
+    }
+}
+
+impl Queue {
+    fn default_interval_seconds() -> u64 {
+        10
+    }
+
+    fn default_batch_size() -> u32 {
+        20
+    }
+
+    fn default_max_attempts() -> u32 {
+        3
+    }
 }
 
 /// SMTP configuration.
@@ -0,0 +1,29 @@
+use serde::{Deserialize, Serialize};
+
+/// Configuration for the delta sync for mirrors.
+///
+/// The torrents are grouped in partitions of consecutive IDs. A background
+/// job detects the torrents added, changed or removed since the last run, so
+/// mirrors only download the changed partitions and records. When this
+/// section is not present, the sync endpoints are disabled.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct MirrorSync {
+    /// Interval in seconds between two runs of the job detecting the
+    /// changes. Default to `300`.
+    #[serde(default = "MirrorSync::default_refresh_interval_seconds")]
+    pub refresh_interval_seconds: u64,
+}
+
+impl Default for MirrorSync {
+    fn default() -> Self {
+        Self {
+            refresh_interval_seconds: Self::default_refresh_interval_seconds(),
+        }
+    }
+}
+
+impl MirrorSync {
+    fn default_refresh_interval_seconds() -> u64 {
+        300
+    }
+}
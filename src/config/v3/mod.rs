@@ -0,0 +1,542 @@
+pub mod announcer;
+pub mod antispam;
+pub mod api;
+pub mod auth;
+pub mod cache;
+pub mod config;
+pub mod database;
+pub mod download_statistics;
+pub mod enrichment;
+pub mod image_cache;
+pub mod inactivity;
+pub mod integrations;
+pub mod limits;
+pub mod listing_snapshots;
+pub mod logging;
+pub mod mail;
+pub mod mirror_sync;
+pub mod net;
+pub mod ratio;
+pub mod registration;
+pub mod reputation;
+pub mod retention;
+pub mod search;
+pub mod storage;
+pub mod torrent;
+pub mod torrent_rules;
+pub mod tracker;
+pub mod tracker_statistics_importer;
+pub mod trending_tags;
+pub mod unstable;
+pub mod upload_policy;
+pub mod website;
+
+use logging::Logging;
+use registration::Registration;
+use serde::{Deserialize, Serialize};
+use unstable::Unstable;
+
+use self::announcer::{Announcer, Target};
+use self::antispam::Antispam;
+use self::api::Api;
+use self::auth::{Auth, ClaimTokenPepper};
+use self::cache::Cache;
+use self::config::Config;
+use self::database::{Database, EncryptionKey};
+use self::download_statistics::DownloadStatistics;
+use self::enrichment::Enrichment;
+use self::image_cache::{Backend as ImageCacheBackend, ImageCache};
+use self::inactivity::Inactivity;
+use self::integrations::{Integrations, SigningSecret};
+use self::limits::Limits;
+use self::listing_snapshots::ListingSnapshots;
+use self::mail::Mail;
+use self::mirror_sync::MirrorSync;
+use self::net::Network;
+use self::ratio::Ratio;
+use self::reputation::Reputation;
+use self::retention::Retention;
+use self::search::Search;
+use self::storage::{SecretAccessKey, Storage, TorrentFilesBackend};
+use self::torrent::Torrent;
+use self::torrent_rules::TorrentRules;
+use self::tracker::{ApiToken, Tracker};
+use self::tracker_statistics_importer::TrackerStatisticsImporter;
+use self::trending_tags::TrendingTags;
+use self::upload_policy::UploadPolicy;
+use self::website::Website;
+use super::validator::{ValidationError, Validator};
+use super::Metadata;
+
+/// The whole configuration for the index.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct Settings {
+    /// Configuration metadata.
+    #[serde(default = "Settings::default_metadata")]
+    pub metadata: Metadata,
+
+    /// The configuration checks.
+    #[serde(default = "Settings::default_config")]
+    pub config: Config,
+
+    /// The logging configuration.
+    #[serde(default = "Settings::default_logging")]
+    pub logging: Logging,
+
+    /// The website customizable values.
+    #[serde(default = "Settings::default_website")]
+    pub website: Website,
+
+    /// The tracker configuration.
+    #[serde(default = "Settings::default_tracker")]
+    pub tracker: Tracker,
+
+    /// The network configuration.
+    #[serde(default = "Settings::default_network")]
+    pub net: Network,
+
+    /// The authentication configuration.
+    #[serde(default = "Settings::default_auth")]
+    pub auth: Auth,
+
+    /// The database configuration.
+    #[serde(default = "Settings::default_database")]
+    pub database: Database,
+
+    /// The SMTP configuration.
+    #[serde(default = "Settings::default_mail")]
+    pub mail: Mail,
+
+    /// The image proxy cache configuration.
+    #[serde(default = "Settings::default_image_cache")]
+    pub image_cache: ImageCache,
+
+    /// The API configuration.
+    #[serde(default = "Settings::default_api")]
+    pub api: Api,
+
+    /// The registration configuration.
+    #[serde(default = "Settings::default_registration")]
+    pub registration: Option<Registration>,
+
+    /// The tracker statistics importer job configuration.
+    #[serde(default = "Settings::default_tracker_statistics_importer")]
+    pub tracker_statistics_importer: TrackerStatisticsImporter,
+
+    /// The full-text search configuration.
+    #[serde(default = "Settings::default_search")]
+    pub search: Option<Search>,
+
+    /// The integrations with external systems configuration.
+    #[serde(default = "Settings::default_integrations")]
+    pub integrations: Option<Integrations>,
+
+    /// The new-torrent announcer configuration.
+    #[serde(default = "Settings::default_announcer")]
+    pub announcer: Option<Announcer>,
+
+    /// The torrent listing snapshots configuration.
+    #[serde(default = "Settings::default_listing_snapshots")]
+    pub listing_snapshots: Option<ListingSnapshots>,
+
+    /// The cache of hot queries configuration.
+    #[serde(default = "Settings::default_cache")]
+    pub cache: Option<Cache>,
+
+    /// The delta sync for mirrors configuration.
+    #[serde(default = "Settings::default_mirror_sync")]
+    pub mirror_sync: Option<MirrorSync>,
+
+    /// The uploaded torrents configuration.
+    #[serde(default = "Settings::default_torrent")]
+    pub torrent: Option<Torrent>,
+
+    /// The `.torrent` download statistics configuration.
+    #[serde(default = "Settings::default_download_statistics")]
+    pub download_statistics: Option<DownloadStatistics>,
+
+    /// The account inactivity policy configuration.
+    #[serde(default = "Settings::default_inactivity")]
+    pub inactivity: Option<Inactivity>,
+
+    /// The per-user upload quotas configuration.
+    #[serde(default = "Settings::default_limits")]
+    pub limits: Option<Limits>,
+
+    /// The data retention policy configuration.
+    #[serde(default = "Settings::default_retention")]
+    pub retention: Option<Retention>,
+
+    /// The external storage backends configuration.
+    #[serde(default = "Settings::default_storage")]
+    pub storage: Option<Storage>,
+
+    /// The torrent rules configuration.
+    #[serde(default = "Settings::default_torrent_rules")]
+    pub torrent_rules: Option<TorrentRules>,
+
+    /// The trending tags configuration.
+    #[serde(default = "Settings::default_trending_tags")]
+    pub trending_tags: Option<TrendingTags>,
+
+    /// The anti-spam heuristics configuration.
+    #[serde(default = "Settings::default_antispam")]
+    pub antispam: Option<Antispam>,
+
+    /// The metadata enrichment providers configuration.
+    #[serde(default = "Settings::default_enrichment")]
+    pub enrichment: Option<Enrichment>,
+
+    /// The content policies of the uploaded torrents.
+    #[serde(default = "Settings::default_upload_policy")]
+    pub upload_policy: Option<UploadPolicy>,
+
+    /// The trusted-uploader thresholds.
+    #[serde(default = "Settings::default_reputation")]
+    pub reputation: Option<Reputation>,
+
+    /// The ratio accounting in private mode.
+    #[serde(default = "Settings::default_ratio")]
+    pub ratio: Option<Ratio>,
+
+    /// The unstable configuration.
+    #[serde(default = "Settings::default_unstable")]
+    pub unstable: Option<Unstable>,
+}
+
+impl Default for Settings {
+    fn default() -> Self {
+        Self {
+            metadata: Self::default_metadata(),
+            config: Self::default_config(),
+            logging: Self::default_logging(),
+            website: Self::default_website(),
+            tracker: Self::default_tracker(),
+            net: Self::default_network(),
+            auth: Self::default_auth(),
+            database: Self::default_database(),
+            mail: Self::default_mail(),
+            image_cache: Self::default_image_cache(),
+            api: Self::default_api(),
+            registration: Self::default_registration(),
+            tracker_statistics_importer: Self::default_tracker_statistics_importer(),
+            search: Self::default_search(),
+            integrations: Self::default_integrations(),
+            announcer: Self::default_announcer(),
+            listing_snapshots: Self::default_listing_snapshots(),
+            cache: Self::default_cache(),
+            mirror_sync: Self::default_mirror_sync(),
+            torrent: Self::default_torrent(),
+            download_statistics: Self::default_download_statistics(),
+            inactivity: Self::default_inactivity(),
+            limits: Self::default_limits(),
+            retention: Self::default_retention(),
+            storage: Self::default_storage(),
+            torrent_rules: Self::default_torrent_rules(),
+            trending_tags: Self::default_trending_tags(),
+            antispam: Self::default_antispam(),
+            enrichment: Self::default_enrichment(),
+            upload_policy: Self::default_upload_policy(),
+            reputation: Self::default_reputation(),
+            ratio: Self::default_ratio(),
+            unstable: Self::default_unstable(),
+        }
+    }
+}
+
+impl Settings {
+    pub fn remove_secrets(&mut self) {
+        self.tracker.token = ApiToken::new("***");
+        if let Some(_password) = self.database.connect_url.password() {
+            let _ = self.database.connect_url.set_password(Some("***"));
+        }
+        for replica_url in &mut self.database.replica_urls {
+            if replica_url.password().is_some() {
+                let _ = replica_url.set_password(Some("***"));
+            }
+        }
+        if let Some(key) = self.database.encryption.as_mut().and_then(|e| e.key.as_mut()) {
+            *key = EncryptionKey::new("***");
+        }
+        "***".clone_into(&mut self.mail.smtp.credentials.password);
+        self.auth.user_claim_token_pepper = ClaimTokenPepper::new("***");
+        if let Some(meilisearch) = self.search.as_mut().and_then(|s| s.meilisearch.as_mut()) {
+            if meilisearch.api_key.is_some() {
+                meilisearch.api_key = Some("***".to_string());
+            }
+        }
+        if let Some(signed_uploads) = self.integrations.as_mut().and_then(|i| i.signed_uploads.as_mut()) {
+            signed_uploads.secret = SigningSecret::new("***");
+        }
+        if let Some(seeding_rewards) = self.integrations.as_mut().and_then(|i| i.seeding_rewards.as_mut()) {
+            if let Some(webhook_url) = seeding_rewards.webhook_url.as_mut() {
+                webhook_url.set_path("***");
+            }
+            if seeding_rewards.webhook_secret.is_some() {
+                seeding_rewards.webhook_secret = Some(SigningSecret::new("***"));
+            }
+        }
+        if let Some(redis) = self.cache.as_mut().and_then(|c| c.redis.as_mut()) {
+            if redis.url.password().is_some() {
+                let _ = redis.url.set_password(Some("***"));
+            }
+        }
+        if let Some(s3) = self.storage.as_mut().and_then(|s| s.s3.as_mut()) {
+            s3.secret_access_key = SecretAccessKey::new("***");
+        }
+        if let Some(announcer) = self.announcer.as_mut() {
+            for channel in &mut announcer.channels {
+                match &mut channel.target {
+                    Target::Matrix { access_token, .. } => "***".clone_into(access_token),
+                    Target::Discord { webhook_url } => {
+                        webhook_url.set_path("***");
+                    }
+                    Target::Irc { .. } => {}
+                }
+            }
+        }
+        if let Some(tmdb) = self.enrichment.as_mut().and_then(|e| e.tmdb.as_mut()) {
+            "***".clone_into(&mut tmdb.api_key);
+        }
+        if let Some(webhook_url) = self
+            .tracker_statistics_importer
+            .alerts
+            .as_mut()
+            .and_then(|alerts| alerts.webhook_url.as_mut())
+        {
+            webhook_url.set_path("***");
+        }
+    }
+
+    /// Encodes the configuration to TOML.
+    ///
+    /// # Panics
+    ///
+    /// Will panic if it can't be converted to TOML.
+    #[must_use]
+    pub fn to_toml(&self) -> String {
+        toml::to_string(self).expect("Could not encode TOML value")
+    }
+
+    /// Encodes the configuration to JSON.
+    ///
+    /// # Panics
+    ///
+    /// Will panic if it can't be converted to JSON.
+    #[must_use]
+    pub fn to_json(&self) -> String {
+        serde_json::to_string_pretty(self).expect("Could not encode JSON value")
+    }
+
+    fn default_metadata() -> Metadata {
+        Metadata::default()
+    }
+
+    fn default_config() -> Config {
+        Config::default()
+    }
+
+    fn default_logging() -> Logging {
+        Logging::default()
+    }
+
+    fn default_website() -> Website {
+        Website::default()
+    }
+
+    fn default_tracker() -> Tracker {
+        Tracker::default()
+    }
+
+    fn default_network() -> Network {
+        Network::default()
+    }
+
+    fn default_auth() -> Auth {
+        Auth::default()
+    }
+
+    fn default_database() -> Database {
+        Database::default()
+    }
+
+    fn default_mail() -> Mail {
+        Mail::default()
+    }
+
+    fn default_image_cache() -> ImageCache {
+        ImageCache::default()
+    }
+
+    fn default_api() -> Api {
+        Api::default()
+    }
+
+    fn default_registration() -> Option<Registration> {
+        None
+    }
+
+    fn default_tracker_statistics_importer() -> TrackerStatisticsImporter {
+        TrackerStatisticsImporter::default()
+    }
+
+    fn default_search() -> Option<Search> {
+        None
+    }
+
+    fn default_integrations() -> Option<Integrations> {
+        None
+    }
+
+    fn default_announcer() -> Option<Announcer> {
+        None
+    }
+
+    fn default_listing_snapshots() -> Option<ListingSnapshots> {
+        None
+    }
+
+    fn default_cache() -> Option<Cache> {
+        None
+    }
+
+    fn default_mirror_sync() -> Option<MirrorSync> {
+        None
+    }
+
+    fn default_torrent() -> Option<Torrent> {
+        None
+    }
+
+    fn default_download_statistics() -> Option<DownloadStatistics> {
+        None
+    }
+
+    fn default_inactivity() -> Option<Inactivity> {
+        None
+    }
+
+    fn default_limits() -> Option<Limits> {
+        None
+    }
+
+    fn default_retention() -> Option<Retention> {
+        None
+    }
+
+    fn default_storage() -> Option<Storage> {
+        None
+    }
+
+    fn default_torrent_rules() -> Option<TorrentRules> {
+        None
+    }
+
+    fn default_trending_tags() -> Option<TrendingTags> {
+        None
+    }
+
+    fn default_antispam() -> Option<Antispam> {
+        None
+    }
+
+    fn default_enrichment() -> Option<Enrichment> {
+        None
+    }
+
+    fn default_upload_policy() -> Option<UploadPolicy> {
+        None
+    }
+
+    fn default_reputation() -> Option<Reputation> {
+        None
+    }
+
+    fn default_ratio() -> Option<Ratio> {
+        None
+    }
+
+    fn default_unstable() -> Option<Unstable> {
+        None
+    }
+}
+
+impl Validator for Settings {
+    fn validate(&self) -> Result<(), ValidationError> {
+        self.tracker.validate()?;
+
+        self.auth.validate()?;
+
+        self.database.validate()?;
+
+        if let Some(search) = &self.search {
+            search.validate()?;
+        }
+
+        if let Some(cache) = &self.cache {
+            cache.validate()?;
+        }
+
+        if self.image_cache.backend == ImageCacheBackend::S3 && self.storage.as_ref().and_then(|s| s.s3.as_ref()).is_none() {
+            return Err(ValidationError::MissingImageCacheBucket);
+        }
+
+        if let Some(storage) = &self.storage {
+            if storage.torrent_files.backend == TorrentFilesBackend::S3 && storage.s3.is_none() {
+                return Err(ValidationError::MissingTorrentFilesBucket);
+            }
+        }
+
+        if self.image_cache.backend == ImageCacheBackend::Memory
+            && self.torrent.as_ref().and_then(|t| t.description_images.as_ref()).is_some()
+        {
+            return Err(ValidationError::DescriptionImagesInMemory);
+        }
+
+        if let Some(torrent) = &self.torrent {
+            torrent.validate()?;
+        }
+
+        if let Some(inactivity) = &self.inactivity {
+            inactivity.validate()?;
+        }
+
+        if let Some(retention) = &self.retention {
+            retention.validate()?;
+        }
+
+        if let Some(torrent_rules) = &self.torrent_rules {
+            torrent_rules.validate()?;
+        }
+
+        if let Some(antispam) = &self.antispam {
+            antispam.validate()?;
+        }
+
+        if let Some(enrichment) = &self.enrichment {
+            enrichment.validate()?;
+        }
+
+        if let Some(upload_policy) = &self.upload_policy {
+            upload_policy.validate()?;
+        }
+
+        if let Some(reputation) = &self.reputation {
+            reputation.validate()?;
+        }
+
+        if let Some(integrations) = &self.integrations {
+            integrations.validate()?;
+        }
+
+        if let Some(ratio) = &self.ratio {
+            if !self.tracker.private {
+                return Err(ValidationError::RatioRequiresPrivateMode);
+            }
+
+            ratio.validate()?;
+        }
+
+        Ok(())
+    }
+}
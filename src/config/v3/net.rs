@@ -0,0 +1,181 @@
+use std::net::{IpAddr, Ipv4Addr, SocketAddr};
+
+use serde::{Deserialize, Serialize};
+use url::Url;
+
+use crate::config::Tsl;
+
+/// The the base URL for the API.
+///
+/// NOTICE: that `port` and por in `base_url` does not necessarily match because
+/// the application migth be running behind a proxy. The local socket could be
+/// bound to, for example, port 80 but the application could be exposed publicly
+/// via port 443, which is a very common setup.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct Network {
+    /// The base URL for the API. For example: `http://localhost`.
+    /// If not set, the base URL will be inferred from the request.
+    #[serde(default = "Network::default_base_url")]
+    pub base_url: Option<Url>,
+
+    /// The address the tracker will bind to.
+    /// The format is `ip:port`, for example `0.0.0.0:6969`. If you want to
+    /// listen to all interfaces, use `0.0.0.0`. If you want the operating
+    /// system to choose a random port, use port `0`.
+    #[serde(default = "Network::default_bind_address")]
+    pub bind_address: SocketAddr,
+
+    /// TSL configuration.
+    #[serde(default = "Network::default_tsl")]
+    pub tsl: Option<Tsl>,
+
+    /// Rate limits for the most abused endpoints. When this section is not
+    /// present, requests are not rate limited.
+    #[serde(default = "Network::default_rate_limits")]
+    pub rate_limits: Option<RateLimits>,
+
+    /// Maximum number of seconds to wait on shutdown for the in-flight
+    /// requests and the background jobs to finish. The connections still
+    /// open after the timeout are closed.
+    #[serde(default = "Network::default_shutdown_timeout_seconds")]
+    pub shutdown_timeout_seconds: u64,
+}
+
+impl Default for Network {
+    fn default() -> Self {
+        Self {
+            bind_address: Self::default_bind_address(),
+            base_url: Self::default_base_url(),
+            tsl: Self::default_tsl(),
+            rate_limits: Self::default_rate_limits(),
+            shutdown_timeout_seconds: Self::default_shutdown_timeout_seconds(),
+        }
+    }
+}
+
+impl Network {
+    fn default_bind_address() -> SocketAddr {
+        SocketAddr::new(Self::default_ip(), Self::default_port())
+    }
+
+    fn default_ip() -> IpAddr {
+        IpAddr::V4(Ipv4Addr::new(0, 0, 0, 0))
+    }
+
+    fn default_port() -> u16 {
+        3001
+    }
+
+    fn default_base_url() -> Option<Url> {
+        None
+    }
+
+    fn default_tsl() -> Option<Tsl> {
+        None
+    }
+
+    fn default_rate_limits() -> Option<RateLimits> {
+        None
+    }
+
+    fn default_shutdown_timeout_seconds() -> u64 {
+        90
+    }
+}
+
+/// Rate limits by endpoint group.
+///
+/// Each client gets its own token bucket per group. Logged-in users are
+/// identified by their user ID and guests by their IP address.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct RateLimits {
+    /// Limit for the login endpoint. Default to 5 requests with a refill
+    /// of 10 requests per minute.
+    #[serde(default = "RateLimits::default_login")]
+    pub login: RateLimit,
+
+    /// Limit for the registration endpoint. Default to 3 requests with a
+    /// refill of 2 requests per minute.
+    #[serde(default = "RateLimits::default_registration")]
+    pub registration: RateLimit,
+
+    /// Limit for the endpoints sending emails: resending the verification
+    /// email and changing the email. Default to 3 requests with a refill of
+    /// 1 request per minute.
+    #[serde(default = "RateLimits::default_email")]
+    pub email: RateLimit,
+
+    /// Limit for the torrent upload endpoint. Default to 10 requests with a
+    /// refill of 10 requests per minute.
+    #[serde(default = "RateLimits::default_upload")]
+    pub upload: RateLimit,
+
+    /// Limit for the torrent listing when it contains a search. Default to
+    /// 30 requests with a refill of 120 requests per minute.
+    #[serde(default = "RateLimits::default_search")]
+    pub search: RateLimit,
+
+    /// Soft threshold, as a percentage of the burst. When a client has used
+    /// this percentage of its bucket, responses include a
+    /// `X-RateLimit-Warning` header and a warning is logged, before requests
+    /// are rejected. Default to 80. Use 100 to only warn on the last request
+    /// accepted.
+    #[serde(default = "RateLimits::default_soft_threshold")]
+    pub soft_threshold: u8,
+}
+
+impl Default for RateLimits {
+    fn default() -> Self {
+        Self {
+            login: Self::default_login(),
+            registration: Self::default_registration(),
+            email: Self::default_email(),
+            upload: Self::default_upload(),
+            search: Self::default_search(),
+            soft_threshold: Self::default_soft_threshold(),
+        }
+    }
+}
+
+impl RateLimits {
+    fn default_login() -> RateLimit {
+        RateLimit::new(5, 10)
+    }
+
+    fn default_registration() -> RateLimit {
+        RateLimit::new(3, 2)
+    }
+
+    fn default_email() -> RateLimit {
+        RateLimit::new(3, 1)
+    }
+
+    fn default_upload() -> RateLimit {
+        RateLimit::new(10, 10)
+    }
+
+    fn default_search() -> RateLimit {
+        RateLimit::new(30, 120)
+    }
+
+    fn default_soft_threshold() -> u8 {
+        80
+    }
+}
+
+/// A token bucket configuration.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+pub struct RateLimit {
+    /// Max number of requests accepted in a burst.
+    pub burst: u32,
+
+    /// Number of requests added back to the bucket every minute.
+    pub per_minute: u32,
+}
+
+impl RateLimit {
+    #[must_use]
+    pub fn new(burst: u32, per_minute: u32) -> Self {
+        Self { burst, per_minute }
+    }
+}
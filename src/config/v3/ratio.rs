@@ -0,0 +1,77 @@
+use serde::{Deserialize, Serialize};
+
+use crate::config::validator::{ValidationError, Validator};
+
+/// Configuration for the ratio accounting in private mode.
+///
+/// The index imports the bytes uploaded and downloaded with each user's keys
+/// from the tracker, and shows the upload ratio in the user profiles. It can
+/// also block the torrent file downloads of the users below a minimum ratio.
+/// It requires the tracker to be running in private mode.
+///
+/// ```toml
+/// [ratio]
+/// import_interval_seconds = 3600
+/// min_download_ratio = 0.5
+/// min_downloaded_bytes = 5368709120
+/// ```
+///
+/// Refer to the [`ratio`](crate::services::ratio) service for the details.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct Ratio {
+    /// Interval in seconds between imports of the transfer stats from the
+    /// tracker. Default to `3600` (one hour).
+    #[serde(default = "Ratio::default_import_interval_seconds")]
+    pub import_interval_seconds: u64,
+
+    /// Minimum upload ratio to download torrent files. When it's not set,
+    /// the ratio is only informative.
+    #[serde(default = "Ratio::default_min_download_ratio")]
+    pub min_download_ratio: Option<f64>,
+
+    /// The minimum ratio is only enforced once the user has downloaded this
+    /// number of bytes, so new users can start downloading. Default to
+    /// `5368709120` (5 GiB).
+    #[serde(default = "Ratio::default_min_downloaded_bytes")]
+    pub min_downloaded_bytes: u64,
+}
+
+impl Default for Ratio {
+    fn default() -> Self {
+        Self {
+            import_interval_seconds: Self::default_import_interval_seconds(),
+            min_download_ratio: Self::default_min_download_ratio(),
+            min_downloaded_bytes: Self::default_min_downloaded_bytes(),
+        }
+    }
+}
+
+impl Ratio {
+    fn default_import_interval_seconds() -> u64 {
+        3600
+    }
+
+    fn default_min_download_ratio() -> Option<f64> {
+        None
+    }
+
+    fn default_min_downloaded_bytes() -> u64 {
+        5_368_709_120
+    }
+}
+
+impl Validator for Ratio {
+    fn validate(&self) -> Result<(), ValidationError> {
+        if self.import_interval_seconds == 0 {
+            return Err(ValidationError::InvalidRatioImportInterval);
+        }
+
+        if let Some(min_download_ratio) = self.min_download_ratio {
+            if !min_download_ratio.is_finite() || min_download_ratio < 0.0 {
+                return Err(ValidationError::InvalidMinDownloadRatio);
+            }
+        }
+
+        Ok(())
+    }
+}
@@ -0,0 +1,71 @@
+use serde::{Deserialize, Serialize};
+
+use crate::config::validator::{ValidationError, Validator};
+
+/// Configuration for the trusted-uploader status.
+///
+/// Users meeting all the thresholds are trusted uploaders: their uploads skip
+/// the moderation queue and they have a badge in the torrent details and
+/// their profile. Administrators can override the status of any user. When
+/// this section is not present, only the users trusted by an administrator
+/// are trusted uploaders.
+///
+/// ```toml
+/// [reputation]
+/// min_approved_uploads = 10
+/// min_approved_ratio = 0.9
+/// max_upheld_reports = 0
+/// ```
+///
+/// Refer to the [`reputation`](crate::services::reputation) service for the
+/// details.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct Reputation {
+    /// Minimum number of approved uploads. Default to `10`.
+    #[serde(default = "Reputation::default_min_approved_uploads")]
+    pub min_approved_uploads: u64,
+
+    /// Minimum ratio of approved uploads out of the moderated ones, from
+    /// `0.0` to `1.0`. Default to `0.9`.
+    #[serde(default = "Reputation::default_min_approved_ratio")]
+    pub min_approved_ratio: f64,
+
+    /// Maximum number of upheld reports against the user's torrents: reports
+    /// resolved with an action other than dismissing them. Default to `0`.
+    #[serde(default = "Reputation::default_max_upheld_reports")]
+    pub max_upheld_reports: u64,
+}
+
+impl Default for Reputation {
+    fn default() -> Self {
+        Self {
+            min_approved_uploads: Self::default_min_approved_uploads(),
+            min_approved_ratio: Self::default_min_approved_ratio(),
+            max_upheld_reports: Self::default_max_upheld_reports(),
+        }
+    }
+}
+
+impl Reputation {
+    fn default_min_approved_uploads() -> u64 {
+        10
+    }
+
+    fn default_min_approved_ratio() -> f64 {
+        0.9
+    }
+
+    fn default_max_upheld_reports() -> u64 {
+        0
+    }
+}
+
+impl Validator for Reputation {
+    fn validate(&self) -> Result<(), ValidationError> {
+        if !(0.0..=1.0).contains(&self.min_approved_ratio) {
+            return Err(ValidationError::InvalidApprovedRatio);
+        }
+
+        Ok(())
+    }
+}
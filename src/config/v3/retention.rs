@@ -0,0 +1,176 @@
+use std::collections::BTreeMap;
+
+use serde::{Deserialize, Serialize};
+
+use crate::config::validator::{ValidationError, Validator};
+use crate::services::retention::RETAINED_TABLES;
+
+/// Configuration for the data retention policy.
+///
+/// The garbage collection job deletes the rows older than the retention
+/// period of their kind of data. Periods are in days. Kinds of data without a
+/// period are kept forever. When this section is not present, nothing is
+/// deleted.
+///
+/// ```toml
+/// [retention]
+/// download_history_days = 365
+/// stats_days = 90
+///
+/// [retention.tables]
+/// torrust_domain_events = 30
+/// ```
+///
+/// Refer to the [`retention`](crate::services::retention) service for the
+/// tables in each kind of data.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct Retention {
+    /// Days to keep the API access logs.
+    #[serde(default = "Retention::default_days")]
+    pub access_logs_days: Option<u32>,
+
+    /// Days to keep the security events, like the audit log.
+    #[serde(default = "Retention::default_days")]
+    pub security_events_days: Option<u32>,
+
+    /// Days to keep the `.torrent` download history.
+    #[serde(default = "Retention::default_days")]
+    pub download_history_days: Option<u32>,
+
+    /// Days to keep the time-series statistics.
+    #[serde(default = "Retention::default_days")]
+    pub stats_days: Option<u32>,
+
+    /// Per-table retention periods in days. They override the period of the
+    /// kind of data of the table.
+    #[serde(default = "Retention::default_tables")]
+    pub tables: BTreeMap<String, u32>,
+
+    /// Interval in seconds between garbage collection runs. Default to
+    /// `86400` (one day).
+    #[serde(default = "Retention::default_gc_interval_seconds")]
+    pub gc_interval_seconds: u64,
+}
+
+impl Default for Retention {
+    fn default() -> Self {
+        Self {
+            access_logs_days: Self::default_days(),
+            security_events_days: Self::default_days(),
+            download_history_days: Self::default_days(),
+            stats_days: Self::default_days(),
+            tables: Self::default_tables(),
+            gc_interval_seconds: Self::default_gc_interval_seconds(),
+        }
+    }
+}
+
+impl Retention {
+    fn default_days() -> Option<u32> {
+        None
+    }
+
+    fn default_tables() -> BTreeMap<String, u32> {
+        BTreeMap::new()
+    }
+
+    fn default_gc_interval_seconds() -> u64 {
+        86_400
+    }
+
+    /// The retention period in days for a table, if any. The per-table
+    /// override takes precedence over the period of the kind of data.
+    #[must_use]
+    pub fn days_for(&self, table: &str, kind: Option<DataKind>) -> Option<u32> {
+        if let Some(days) = self.tables.get(table) {
+            return Some(*days);
+        }
+
+        match kind? {
+            DataKind::AccessLogs => self.access_logs_days,
+            DataKind::SecurityEvents => self.security_events_days,
+            DataKind::DownloadHistory => self.download_history_days,
+            DataKind::Stats => self.stats_days,
+        }
+    }
+}
+
+impl Validator for Retention {
+    fn validate(&self) -> Result<(), ValidationError> {
+        if let Some(table) = self
+            .tables
+            .keys()
+            .find(|table| !RETAINED_TABLES.iter().any(|retained| retained.table == table.as_str()))
+        {
+            return Err(ValidationError::UnknownRetentionTable(table.clone()));
+        }
+
+        let periods = [
+            self.access_logs_days,
+            self.security_events_days,
+            self.download_history_days,
+            self.stats_days,
+        ];
+
+        if periods.iter().flatten().chain(self.tables.values()).any(|days| *days == 0) {
+            return Err(ValidationError::InvalidRetentionPeriod);
+        }
+
+        Ok(())
+    }
+}
+
+/// The kinds of data with a retention period.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DataKind {
+    AccessLogs,
+    SecurityEvents,
+    DownloadHistory,
+    Stats,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{DataKind, Retention};
+    use crate::config::validator::Validator;
+
+    #[test]
+    fn the_per_table_period_should_override_the_period_of_the_kind_of_data() {
+        let mut retention = Retention {
+            stats_days: Some(90),
+            ..Retention::default()
+        };
+
+        assert_eq!(
+            retention.days_for("torrust_torrent_tracker_stats", Some(DataKind::Stats)),
+            Some(90)
+        );
+
+        retention.tables.insert("torrust_torrent_tracker_stats".to_string(), 7);
+
+        assert_eq!(
+            retention.days_for("torrust_torrent_tracker_stats", Some(DataKind::Stats)),
+            Some(7)
+        );
+        assert_eq!(retention.days_for("torrust_domain_events", None), None);
+    }
+
+    #[test]
+    fn it_should_not_allow_overrides_for_unknown_tables() {
+        let mut retention = Retention::default();
+
+        retention.tables.insert("torrust_users".to_string(), 30);
+
+        assert!(retention.validate().is_err());
+    }
+
+    #[test]
+    fn it_should_not_allow_empty_retention_periods() {
+        let retention = Retention {
+            download_history_days: Some(0),
+            ..Retention::default()
+        };
+
+        assert!(retention.validate().is_err());
+    }
+}
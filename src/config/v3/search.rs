@@ -0,0 +1,128 @@
+use camino::Utf8PathBuf;
+use serde::{Deserialize, Serialize};
+use url::Url;
+
+use super::{ValidationError, Validator};
+
+/// Full-text search configuration.
+///
+/// When this section is not present, torrents are searched using a simple
+/// SQL `LIKE` on the torrent title.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct Search {
+    /// The search backend. Default to `tantivy`.
+    #[serde(default = "Search::default_backend")]
+    pub backend: Backend,
+
+    /// The maximum number of ranked results taken from the search index.
+    /// Default to `1000`.
+    #[serde(default = "Search::default_max_results")]
+    pub max_results: usize,
+
+    /// The built-in Tantivy index configuration.
+    #[serde(default = "Search::default_tantivy")]
+    pub tantivy: Tantivy,
+
+    /// The Meilisearch client configuration. Required if the backend is
+    /// `meilisearch`.
+    #[serde(default = "Search::default_meilisearch")]
+    pub meilisearch: Option<Meilisearch>,
+}
+
+impl Validator for Search {
+    fn validate(&self) -> Result<(), ValidationError> {
+        if self.backend == Backend::Meilisearch && self.meilisearch.is_none() {
+            return Err(ValidationError::MissingMeilisearchConfiguration);
+        }
+
+        Ok(())
+    }
+}
+
+impl Default for Search {
+    fn default() -> Self {
+        Self {
+            backend: Self::default_backend(),
+            max_results: Self::default_max_results(),
+            tantivy: Self::default_tantivy(),
+            meilisearch: Self::default_meilisearch(),
+        }
+    }
+}
+
+impl Search {
+    fn default_backend() -> Backend {
+        Backend::Tantivy
+    }
+
+    fn default_max_results() -> usize {
+        1000
+    }
+
+    fn default_tantivy() -> Tantivy {
+        Tantivy::default()
+    }
+
+    fn default_meilisearch() -> Option<Meilisearch> {
+        None
+    }
+}
+
+/// The search backend.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum Backend {
+    /// Built-in full-text index.
+    Tantivy,
+    /// External Meilisearch server.
+    Meilisearch,
+}
+
+/// Built-in Tantivy index configuration.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct Tantivy {
+    /// The directory where the index is stored. If it's not set the index is
+    /// kept in memory. In both cases the index is rebuilt from the database
+    /// when the application starts.
+    #[serde(default = "Tantivy::default_index_path")]
+    pub index_path: Option<Utf8PathBuf>,
+}
+
+impl Default for Tantivy {
+    fn default() -> Self {
+        Self {
+            index_path: Self::default_index_path(),
+        }
+    }
+}
+
+impl Tantivy {
+    fn default_index_path() -> Option<Utf8PathBuf> {
+        None
+    }
+}
+
+/// Meilisearch client configuration.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct Meilisearch {
+    /// The Meilisearch server URL. For example: `http://localhost:7700`.
+    pub url: Url,
+
+    /// The API key.
+    #[serde(default = "Meilisearch::default_api_key")]
+    pub api_key: Option<String>,
+
+    /// The index UID. Default to `torrents`.
+    #[serde(default = "Meilisearch::default_index")]
+    pub index: String,
+}
+
+impl Meilisearch {
+    fn default_api_key() -> Option<String> {
+        None
+    }
+
+    fn default_index() -> String {
+        "torrents".to_string()
+    }
+}
@@ -0,0 +1,217 @@
+use std::fmt;
+
+use camino::Utf8PathBuf;
+use serde::{Deserialize, Serialize};
+use url::Url;
+
+/// External storage backends.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct Storage {
+    /// S3-compatible object storage (AWS S3, `MinIO`, Garage, ...).
+    #[serde(default = "Storage::default_s3")]
+    pub s3: Option<S3>,
+
+    /// Where the pieces of the uploaded torrents are stored.
+    #[serde(default = "Storage::default_torrent_files")]
+    pub torrent_files: TorrentFiles,
+}
+
+impl Default for Storage {
+    fn default() -> Self {
+        Self {
+            s3: Self::default_s3(),
+            torrent_files: Self::default_torrent_files(),
+        }
+    }
+}
+
+impl Storage {
+    fn default_s3() -> Option<S3> {
+        None
+    }
+
+    fn default_torrent_files() -> TorrentFiles {
+        TorrentFiles::default()
+    }
+}
+
+/// Storage of the torrent files.
+///
+/// The piece hashes are most of the size of a torrent file. They are stored
+/// in the database by default. They can be stored in a local directory or
+/// in the `[storage.s3]` bucket instead, so the database and its backups
+/// stay small. The existing torrents are moved to the new backend with:
+///
+/// ```text
+/// cargo run --bin move_torrent_files database filesystem
+/// ```
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct TorrentFiles {
+    /// The storage backend. Default to `database`.
+    #[serde(default = "TorrentFiles::default_backend")]
+    pub backend: TorrentFilesBackend,
+
+    /// The directory where the torrent files are stored with the
+    /// `filesystem` backend. Default to `./storage/index/lib/torrent_files`.
+    #[serde(default = "TorrentFiles::default_path")]
+    pub path: Utf8PathBuf,
+
+    /// Prefix for the object keys with the `s3` backend. Default to
+    /// `torrent-files/`.
+    #[serde(default = "TorrentFiles::default_s3_key_prefix")]
+    pub s3_key_prefix: String,
+}
+
+impl Default for TorrentFiles {
+    fn default() -> Self {
+        Self {
+            backend: Self::default_backend(),
+            path: Self::default_path(),
+            s3_key_prefix: Self::default_s3_key_prefix(),
+        }
+    }
+}
+
+impl TorrentFiles {
+    fn default_backend() -> TorrentFilesBackend {
+        TorrentFilesBackend::Database
+    }
+
+    fn default_path() -> Utf8PathBuf {
+        Utf8PathBuf::from("./storage/index/lib/torrent_files")
+    }
+
+    fn default_s3_key_prefix() -> String {
+        "torrent-files/".to_string()
+    }
+}
+
+/// The torrent files storage backend.
+#[allow(clippy::module_name_repetitions)]
+#[derive(Debug, Default, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum TorrentFilesBackend {
+    /// The `torrust_torrents` table.
+    #[default]
+    Database,
+    /// Files in a local directory.
+    Filesystem,
+    /// Objects in the `[storage.s3]` bucket.
+    S3,
+}
+
+impl fmt::Display for TorrentFilesBackend {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            TorrentFilesBackend::Database => write!(f, "database"),
+            TorrentFilesBackend::Filesystem => write!(f, "filesystem"),
+            TorrentFilesBackend::S3 => write!(f, "s3"),
+        }
+    }
+}
+
+impl std::str::FromStr for TorrentFilesBackend {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "database" => Ok(TorrentFilesBackend::Database),
+            "filesystem" => Ok(TorrentFilesBackend::Filesystem),
+            "s3" => Ok(TorrentFilesBackend::S3),
+            _ => Err(format!(
+                "unknown torrent files backend `{s}`, expected `database`, `filesystem` or `s3`"
+            )),
+        }
+    }
+}
+
+/// S3 storage backend configuration.
+///
+/// When enabled, users upload images directly to the bucket with pre-signed
+/// URLs issued by the index, so the image bytes never go through the index.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct S3 {
+    /// The S3 endpoint. For example: `https://s3.eu-west-1.amazonaws.com`.
+    pub endpoint: Url,
+
+    /// The region used to sign the requests. Default to `us-east-1`.
+    #[serde(default = "S3::default_region")]
+    pub region: String,
+
+    /// The bucket where the images are stored.
+    pub bucket: String,
+
+    /// The access key ID.
+    pub access_key_id: String,
+
+    /// The secret access key.
+    pub secret_access_key: SecretAccessKey,
+
+    /// Use path-style URLs (`https://endpoint/bucket/key`) instead of
+    /// virtual-hosted-style URLs (`https://bucket.endpoint/key`). Most
+    /// self-hosted servers require it. Default to `true`.
+    #[serde(default = "S3::default_path_style")]
+    pub path_style: bool,
+
+    /// The base URL used to serve the uploaded images, for example a CDN in
+    /// front of the bucket. If not set, the bucket URL is used.
+    #[serde(default = "S3::default_public_url")]
+    pub public_url: Option<Url>,
+
+    /// How long the pre-signed URLs are valid in seconds. Default to `900`.
+    #[serde(default = "S3::default_presigned_url_expiration_seconds")]
+    pub presigned_url_expiration_seconds: u64,
+
+    /// The maximum size of an uploaded image in bytes. Default to `10485760`
+    /// (10 MiB).
+    #[serde(default = "S3::default_max_image_size")]
+    pub max_image_size: u64,
+}
+
+impl S3 {
+    fn default_region() -> String {
+        "us-east-1".to_string()
+    }
+
+    fn default_path_style() -> bool {
+        true
+    }
+
+    fn default_public_url() -> Option<Url> {
+        None
+    }
+
+    fn default_presigned_url_expiration_seconds() -> u64 {
+        900
+    }
+
+    fn default_max_image_size() -> u64 {
+        10 * 1024 * 1024
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct SecretAccessKey(String);
+
+impl SecretAccessKey {
+    /// # Panics
+    ///
+    /// Will panic if the key is empty.
+    #[must_use]
+    pub fn new(key: &str) -> Self {
+        assert!(!key.is_empty(), "secret access key cannot be empty");
+
+        Self(key.to_owned())
+    }
+
+    #[must_use]
+    pub fn as_bytes(&self) -> &[u8] {
+        self.0.as_bytes()
+    }
+}
+
+impl fmt::Display for SecretAccessKey {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.0)
+    }
+}
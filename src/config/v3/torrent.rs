@@ -0,0 +1,726 @@
+use serde::{Deserialize, Serialize};
+
+use crate::config::validator::{ValidationError, Validator};
+
+/// Configuration for the uploaded torrents.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Default)]
+pub struct Torrent {
+    /// Policies on the metainfo structure. When this section is not present,
+    /// any valid torrent file can be uploaded.
+    #[serde(default = "Torrent::default_structure")]
+    pub structure: Option<Structure>,
+
+    /// Uploads from magnet links. When this section is not present, only
+    /// torrent files can be uploaded.
+    #[serde(default = "Torrent::default_magnet_uploads")]
+    pub magnet_uploads: Option<MagnetUploads>,
+
+    /// Warnings about existing torrents with a similar title and size. When
+    /// this section is not present, only torrents with the same info-hash
+    /// are detected as duplicates.
+    #[serde(default = "Torrent::default_similar_titles")]
+    pub similar_titles: Option<SimilarTitles>,
+
+    /// Previews of the images and text files in the torrents. When this
+    /// section is not present, previews can't be attached.
+    #[serde(default = "Torrent::default_previews")]
+    pub previews: Option<Previews>,
+
+    /// Images uploaded to be embedded in the torrent descriptions. When this
+    /// section is not present, only remote images can be used, through the
+    /// image proxy.
+    #[serde(default = "Torrent::default_description_images")]
+    pub description_images: Option<DescriptionImages>,
+
+    /// Auxiliary files attached to the torrents, like the NFO and sample
+    /// screenshots. When this section is not present, files can't be
+    /// attached.
+    #[serde(default = "Torrent::default_attachments")]
+    pub attachments: Option<Attachments>,
+
+    /// Detection of uploads whose contents overlap with existing torrents.
+    /// When this section is not present, torrents are not compared by
+    /// content.
+    #[serde(default = "Torrent::default_deduplication")]
+    pub deduplication: Option<Deduplication>,
+
+    /// Trash bin for the deleted torrents.
+    #[serde(default = "Torrent::default_trash")]
+    pub trash: Trash,
+
+    /// Health score of the torrents and cleanup of the dead ones. When this
+    /// section is not present, the health of the torrents is not tracked.
+    #[serde(default = "Torrent::default_health")]
+    pub health: Option<Health>,
+
+    /// Changes made to the torrent files when they are downloaded. When this
+    /// section is not present, the files are served as they were uploaded.
+    #[serde(default = "Torrent::default_download")]
+    pub download: Option<Download>,
+
+    /// Trackers of the uploaded torrents.
+    #[serde(default = "Torrent::default_announce_urls")]
+    pub announce_urls: AnnounceUrls,
+}
+
+impl Torrent {
+    fn default_structure() -> Option<Structure> {
+        None
+    }
+
+    fn default_magnet_uploads() -> Option<MagnetUploads> {
+        None
+    }
+
+    fn default_similar_titles() -> Option<SimilarTitles> {
+        None
+    }
+
+    fn default_previews() -> Option<Previews> {
+        None
+    }
+
+    fn default_description_images() -> Option<DescriptionImages> {
+        None
+    }
+
+    fn default_attachments() -> Option<Attachments> {
+        None
+    }
+
+    fn default_deduplication() -> Option<Deduplication> {
+        None
+    }
+
+    fn default_trash() -> Trash {
+        Trash::default()
+    }
+
+    fn default_health() -> Option<Health> {
+        None
+    }
+
+    fn default_download() -> Option<Download> {
+        None
+    }
+
+    fn default_announce_urls() -> AnnounceUrls {
+        AnnounceUrls::default()
+    }
+}
+
+impl Validator for Torrent {
+    fn validate(&self) -> Result<(), ValidationError> {
+        if let Some(structure) = &self.structure {
+            structure.validate()?;
+        }
+
+        if let Some(similar_titles) = &self.similar_titles {
+            similar_titles.validate()?;
+        }
+
+        if let Some(deduplication) = &self.deduplication {
+            deduplication.validate()?;
+        }
+
+        if let Some(health) = &self.health {
+            health.validate()?;
+        }
+
+        if let Some(download) = &self.download {
+            download.validate()?;
+        }
+
+        Ok(())
+    }
+}
+
+/// Upload policies on the metainfo structure.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct Structure {
+    /// Minimum piece length in bytes. Default to `16384` (16 KiB).
+    #[serde(default = "Structure::default_min_piece_length")]
+    pub min_piece_length: u64,
+
+    /// Maximum piece length in bytes. Default to `67108864` (64 MiB).
+    #[serde(default = "Structure::default_max_piece_length")]
+    pub max_piece_length: u64,
+
+    /// Reject torrents with padding files that are not used to align files
+    /// to pieces (BEP 47). Default to `true`.
+    #[serde(default = "Structure::default_reject_padding_abuse")]
+    pub reject_padding_abuse: bool,
+
+    /// Names of the categories that only accept multi-file torrents (packs).
+    #[serde(default = "Structure::default_pack_only_categories")]
+    pub pack_only_categories: Vec<String>,
+
+    /// Maximum number of components in a file path, including the torrent
+    /// name. Default to `16`.
+    #[serde(default = "Structure::default_max_path_depth")]
+    pub max_path_depth: usize,
+
+    /// Maximum length in bytes of a file path, including the torrent name.
+    /// Default to `1024`.
+    #[serde(default = "Structure::default_max_path_length")]
+    pub max_path_length: usize,
+}
+
+impl Default for Structure {
+    fn default() -> Self {
+        Self {
+            min_piece_length: Self::default_min_piece_length(),
+            max_piece_length: Self::default_max_piece_length(),
+            reject_padding_abuse: Self::default_reject_padding_abuse(),
+            pack_only_categories: Self::default_pack_only_categories(),
+            max_path_depth: Self::default_max_path_depth(),
+            max_path_length: Self::default_max_path_length(),
+        }
+    }
+}
+
+impl Validator for Structure {
+    fn validate(&self) -> Result<(), ValidationError> {
+        if self.min_piece_length > self.max_piece_length {
+            return Err(ValidationError::InvalidPieceLengthRange);
+        }
+
+        Ok(())
+    }
+}
+
+impl Structure {
+    fn default_min_piece_length() -> u64 {
+        16 * 1024
+    }
+
+    fn default_max_piece_length() -> u64 {
+        64 * 1024 * 1024
+    }
+
+    fn default_reject_padding_abuse() -> bool {
+        true
+    }
+
+    fn default_pack_only_categories() -> Vec<String> {
+        Vec::new()
+    }
+
+    fn default_max_path_depth() -> usize {
+        16
+    }
+
+    fn default_max_path_length() -> usize {
+        1024
+    }
+}
+
+/// Configuration for the torrents uploaded as magnet links.
+///
+/// The index fetches the info dictionary from the peers returned by the
+/// trackers in the magnet link, and by the index tracker, using the metadata
+/// extension (BEP 9).
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct MagnetUploads {
+    /// Interval in seconds between attempts to resolve the pending magnet
+    /// uploads. Default to `60`.
+    #[serde(default = "MagnetUploads::default_resolve_interval_seconds")]
+    pub resolve_interval_seconds: u64,
+
+    /// Number of attempts before giving up on a magnet link. Default to `10`.
+    #[serde(default = "MagnetUploads::default_max_attempts")]
+    pub max_attempts: u32,
+
+    /// Max number of peers contacted in each attempt. Default to `20`.
+    #[serde(default = "MagnetUploads::default_max_peers")]
+    pub max_peers: usize,
+
+    /// Timeout in seconds for each tracker and peer connection. Default to
+    /// `10`.
+    #[serde(default = "MagnetUploads::default_timeout_seconds")]
+    pub timeout_seconds: u64,
+
+    /// Max size in bytes of the info dictionary. Default to `10485760`
+    /// (10 MiB), the same limit as the torrent file uploads.
+    #[serde(default = "MagnetUploads::default_max_metadata_size")]
+    pub max_metadata_size: usize,
+}
+
+impl Default for MagnetUploads {
+    fn default() -> Self {
+        Self {
+            resolve_interval_seconds: Self::default_resolve_interval_seconds(),
+            max_attempts: Self::default_max_attempts(),
+            max_peers: Self::default_max_peers(),
+            timeout_seconds: Self::default_timeout_seconds(),
+            max_metadata_size: Self::default_max_metadata_size(),
+        }
+    }
+}
+
+impl MagnetUploads {
+    fn default_resolve_interval_seconds() -> u64 {
+        60
+    }
+
+    fn default_max_attempts() -> u32 {
+        10
+    }
+
+    fn default_max_peers() -> usize {
+        20
+    }
+
+    fn default_timeout_seconds() -> u64 {
+        10
+    }
+
+    fn default_max_metadata_size() -> usize {
+        10 * 1024 * 1024
+    }
+}
+
+/// Detection of possible duplicate uploads.
+///
+/// Torrents with a different info-hash can contain the same release, for
+/// example when the uploader creates the torrent again. When a torrent is
+/// uploaded, the index looks for existing torrents with a similar size and a
+/// similar normalized title (ignoring case, punctuation and separators) and
+/// returns them in the upload response. The upload is not rejected.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct SimilarTitles {
+    /// Max distance between the normalized titles, from `0.0` (identical) to
+    /// `1.0` (completely different). It's the edit distance divided by the
+    /// length of the longest title. Default to `0.2`.
+    #[serde(default = "SimilarTitles::default_max_title_distance")]
+    pub max_title_distance: f64,
+
+    /// Max relative difference between the torrent sizes. Default to `0.01`
+    /// (1%).
+    #[serde(default = "SimilarTitles::default_size_tolerance")]
+    pub size_tolerance: f64,
+
+    /// Max number of similar torrents returned. Default to `5`.
+    #[serde(default = "SimilarTitles::default_max_candidates")]
+    pub max_candidates: usize,
+}
+
+impl Default for SimilarTitles {
+    fn default() -> Self {
+        Self {
+            max_title_distance: Self::default_max_title_distance(),
+            size_tolerance: Self::default_size_tolerance(),
+            max_candidates: Self::default_max_candidates(),
+        }
+    }
+}
+
+impl Validator for SimilarTitles {
+    fn validate(&self) -> Result<(), ValidationError> {
+        if !(0.0..=1.0).contains(&self.max_title_distance) || !(0.0..=1.0).contains(&self.size_tolerance) {
+            return Err(ValidationError::InvalidSimilarTitlesThreshold);
+        }
+
+        Ok(())
+    }
+}
+
+impl SimilarTitles {
+    fn default_max_title_distance() -> f64 {
+        0.2
+    }
+
+    fn default_size_tolerance() -> f64 {
+        0.01
+    }
+
+    fn default_max_candidates() -> usize {
+        5
+    }
+}
+
+/// Previews of the torrent contents.
+///
+/// Uploaders can attach images and text files in the torrent as previews.
+/// The client sends the pieces containing the file and the index verifies
+/// them against the piece hashes in the torrent, so previews always match
+/// the torrent contents.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct Previews {
+    /// Max size in bytes of a file used as a preview. Default to `1048576`
+    /// (1 MiB).
+    #[serde(default = "Previews::default_max_file_size")]
+    pub max_file_size: u64,
+
+    /// Max number of previews in a torrent. Default to `5`.
+    #[serde(default = "Previews::default_max_previews_per_torrent")]
+    pub max_previews_per_torrent: usize,
+}
+
+impl Default for Previews {
+    fn default() -> Self {
+        Self {
+            max_file_size: Self::default_max_file_size(),
+            max_previews_per_torrent: Self::default_max_previews_per_torrent(),
+        }
+    }
+}
+
+impl Previews {
+    fn default_max_file_size() -> u64 {
+        1024 * 1024
+    }
+
+    fn default_max_previews_per_torrent() -> usize {
+        5
+    }
+}
+
+/// Images uploaded to be embedded in the torrent descriptions.
+///
+/// The images are stored with the `[image_cache]` backend, so it must be
+/// `filesystem` or `s3`. They are not counted in the image cache capacity
+/// and they are never evicted.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct DescriptionImages {
+    /// Max size in bytes of an uploaded image. Default to `2097152` (2 MiB).
+    #[serde(default = "DescriptionImages::default_max_image_size")]
+    pub max_image_size: u64,
+
+    /// Max number of images uploaded for a torrent. Default to `20`.
+    #[serde(default = "DescriptionImages::default_max_images_per_torrent")]
+    pub max_images_per_torrent: usize,
+}
+
+impl Default for DescriptionImages {
+    fn default() -> Self {
+        Self {
+            max_image_size: Self::default_max_image_size(),
+            max_images_per_torrent: Self::default_max_images_per_torrent(),
+        }
+    }
+}
+
+impl DescriptionImages {
+    fn default_max_image_size() -> u64 {
+        2 * 1024 * 1024
+    }
+
+    fn default_max_images_per_torrent() -> usize {
+        20
+    }
+}
+
+/// Auxiliary files attached to the torrents.
+///
+/// A torrent can have one NFO, the text file with the release information,
+/// and some sample screenshots. Unlike previews, they don't need to be in
+/// the torrent contents.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct Attachments {
+    /// Max size in bytes of an NFO file. Default to `65536` (64 KiB).
+    #[serde(default = "Attachments::default_max_nfo_size")]
+    pub max_nfo_size: u64,
+
+    /// Max size in bytes of a screenshot. Default to `2097152` (2 MiB).
+    #[serde(default = "Attachments::default_max_screenshot_size")]
+    pub max_screenshot_size: u64,
+
+    /// Max number of screenshots in a torrent. Default to `10`.
+    #[serde(default = "Attachments::default_max_screenshots_per_torrent")]
+    pub max_screenshots_per_torrent: usize,
+}
+
+impl Default for Attachments {
+    fn default() -> Self {
+        Self {
+            max_nfo_size: Self::default_max_nfo_size(),
+            max_screenshot_size: Self::default_max_screenshot_size(),
+            max_screenshots_per_torrent: Self::default_max_screenshots_per_torrent(),
+        }
+    }
+}
+
+impl Attachments {
+    fn default_max_nfo_size() -> u64 {
+        64 * 1024
+    }
+
+    fn default_max_screenshot_size() -> u64 {
+        2 * 1024 * 1024
+    }
+
+    fn default_max_screenshots_per_torrent() -> usize {
+        10
+    }
+}
+
+/// Trash bin for the deleted torrents.
+///
+/// Deleted torrents are hidden but they are kept, and whitelisted in the
+/// tracker, until they are purged. Administrators can restore them or purge
+/// them before the retention period ends.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct Trash {
+    /// Days a deleted torrent is kept before it's purged. Default to `30`.
+    #[serde(default = "Trash::default_retention_days")]
+    pub retention_days: u32,
+
+    /// Interval in seconds between the runs of the job purging the expired
+    /// torrents. Default to `3600` (1 hour).
+    #[serde(default = "Trash::default_purge_interval_seconds")]
+    pub purge_interval_seconds: u64,
+}
+
+impl Default for Trash {
+    fn default() -> Self {
+        Self {
+            retention_days: Self::default_retention_days(),
+            purge_interval_seconds: Self::default_purge_interval_seconds(),
+        }
+    }
+}
+
+impl Trash {
+    fn default_retention_days() -> u32 {
+        30
+    }
+
+    fn default_purge_interval_seconds() -> u64 {
+        3600
+    }
+}
+
+/// Detection of uploads whose contents overlap with existing torrents.
+///
+/// The uploaded torrent is compared with the existing torrents that have
+/// files with the same size. The overlap is the fraction of the contents of
+/// the smallest torrent that is also in the other one, comparing the file
+/// sizes and, when both torrents have the same piece length, the piece
+/// hashes.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct Deduplication {
+    /// Min overlap, from `0.0` to `1.0`, to consider a torrent a duplicate.
+    /// Default to `0.9`.
+    #[serde(default = "Deduplication::default_threshold")]
+    pub threshold: f64,
+
+    /// What to do with the duplicate uploads. Default to `flag`.
+    #[serde(default = "Deduplication::default_action")]
+    pub action: DuplicateAction,
+
+    /// Min size in bytes of the files compared by size, so small files like
+    /// NFOs or samples are not enough to match. Default to `1048576` (1 MiB).
+    #[serde(default = "Deduplication::default_min_file_size")]
+    pub min_file_size: u64,
+
+    /// Max number of existing torrents compared with an upload. Default to
+    /// `50`.
+    #[serde(default = "Deduplication::default_max_candidates")]
+    pub max_candidates: u32,
+}
+
+impl Default for Deduplication {
+    fn default() -> Self {
+        Self {
+            threshold: Self::default_threshold(),
+            action: Self::default_action(),
+            min_file_size: Self::default_min_file_size(),
+            max_candidates: Self::default_max_candidates(),
+        }
+    }
+}
+
+impl Validator for Deduplication {
+    fn validate(&self) -> Result<(), ValidationError> {
+        if self.threshold <= 0.0 || self.threshold > 1.0 {
+            return Err(ValidationError::InvalidDeduplicationThreshold);
+        }
+
+        Ok(())
+    }
+}
+
+impl Deduplication {
+    fn default_threshold() -> f64 {
+        0.9
+    }
+
+    fn default_action() -> DuplicateAction {
+        DuplicateAction::Flag
+    }
+
+    fn default_min_file_size() -> u64 {
+        1024 * 1024
+    }
+
+    fn default_max_candidates() -> u32 {
+        50
+    }
+}
+
+/// What to do with an upload whose contents overlap with an existing torrent.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum DuplicateAction {
+    /// The upload is rejected.
+    Reject,
+    /// The upload is accepted and listed with its duplicates for the
+    /// administrators.
+    Flag,
+}
+
+/// Health score of the torrents and cleanup of the dead ones.
+///
+/// The health score is a moving average of the seeders imported from the
+/// tracker, from `0.0` (no seeders) to `1.0` (`healthy_seeders` or more).
+/// Torrents without seeders for `dead_after_days` are dead.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct Health {
+    /// Interval in seconds between the runs of the job updating the health
+    /// scores. Default to `3600` (1 hour).
+    #[serde(default = "Health::default_check_interval_seconds")]
+    pub check_interval_seconds: u64,
+
+    /// Number of seeders for a torrent to be completely healthy. Default to
+    /// `10`.
+    #[serde(default = "Health::default_healthy_seeders")]
+    pub healthy_seeders: u32,
+
+    /// Days without seeders for a torrent to be dead. Default to `30`.
+    #[serde(default = "Health::default_dead_after_days")]
+    pub dead_after_days: u32,
+
+    /// What to do with the dead torrents. Default to `flag`.
+    #[serde(default = "Health::default_action")]
+    pub action: DeadTorrentAction,
+}
+
+impl Default for Health {
+    fn default() -> Self {
+        Self {
+            check_interval_seconds: Self::default_check_interval_seconds(),
+            healthy_seeders: Self::default_healthy_seeders(),
+            dead_after_days: Self::default_dead_after_days(),
+            action: Self::default_action(),
+        }
+    }
+}
+
+impl Validator for Health {
+    fn validate(&self) -> Result<(), ValidationError> {
+        if self.healthy_seeders == 0 {
+            return Err(ValidationError::InvalidHealthySeeders);
+        }
+
+        Ok(())
+    }
+}
+
+impl Health {
+    fn default_check_interval_seconds() -> u64 {
+        3600
+    }
+
+    fn default_healthy_seeders() -> u32 {
+        10
+    }
+
+    fn default_dead_after_days() -> u32 {
+        30
+    }
+
+    fn default_action() -> DeadTorrentAction {
+        DeadTorrentAction::Flag
+    }
+}
+
+/// What to do with a torrent without seeders for too long.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum DeadTorrentAction {
+    /// The torrent is flagged for the administrators, who decide what to do
+    /// with it.
+    Flag,
+    /// The torrent is archived, so it's removed from the public listing.
+    Archive,
+}
+
+/// Changes made to the torrent files when they are downloaded.
+///
+/// Setting the private flag or the source changes the info-hash of the
+/// downloaded torrent. The new info-hash is added to the group of the
+/// uploaded torrent and whitelisted in the tracker the first time the
+/// torrent is downloaded, but its peers are a different swarm than the peers
+/// of the torrent with the original info-hash.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Default)]
+pub struct Download {
+    /// Set the private flag (BEP 27), so clients only get peers from the
+    /// tracker. Default to `false`: the flag is kept as it was uploaded.
+    #[serde(default = "Download::default_set_private")]
+    pub set_private: bool,
+
+    /// Value of the `source` field of the info dictionary. Private trackers
+    /// use it to give their torrents a different info-hash. Default to no
+    /// source.
+    #[serde(default = "Download::default_source")]
+    pub source: Option<String>,
+
+    /// Remove the web seeds: the BEP 17 HTTP seeds and the BEP 19 web seeds
+    /// added to the torrent listing. Default to `false`.
+    #[serde(default = "Download::default_strip_web_seeds")]
+    pub strip_web_seeds: bool,
+}
+
+impl Validator for Download {
+    fn validate(&self) -> Result<(), ValidationError> {
+        if self.source.as_ref().is_some_and(|source| source.trim().is_empty()) {
+            return Err(ValidationError::EmptyTorrentSource);
+        }
+
+        Ok(())
+    }
+}
+
+impl Download {
+    fn default_set_private() -> bool {
+        false
+    }
+
+    fn default_source() -> Option<String> {
+        None
+    }
+
+    fn default_strip_web_seeds() -> bool {
+        false
+    }
+}
+
+/// Trackers of the uploaded torrents.
+///
+/// The index tracker is always the main tracker of the downloaded torrents.
+/// By default, the original `announce` URL is replaced, and the other
+/// trackers are removed from private torrents.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct AnnounceUrls {
+    /// Keep all the original trackers as `announce-list` tiers after the
+    /// index tracker, also for private torrents, so users can cross-seed the
+    /// torrents to other trackers. Uploaders can choose it for each upload.
+    /// Default to `false`.
+    #[serde(default = "AnnounceUrls::default_preserve")]
+    pub preserve: bool,
+}
+
+impl Default for AnnounceUrls {
+    fn default() -> Self {
+        Self {
+            preserve: Self::default_preserve(),
+        }
+    }
+}
+
+impl AnnounceUrls {
+    fn default_preserve() -> bool {
+        false
+    }
+}
@@ -0,0 +1,130 @@
+use std::collections::HashSet;
+
+use serde::{Deserialize, Serialize};
+
+use crate::config::validator::{ValidationError, Validator};
+
+/// Configuration for the torrent rules.
+///
+/// Rules archive, delist or notify the uploader of the approved torrents
+/// matching all their conditions. They are evaluated in order by a scheduled
+/// job and each rule is applied only once to each torrent. When this section
+/// is not present, no rules are applied.
+///
+/// ```toml
+/// [torrent_rules]
+/// check_interval_seconds = 86400
+///
+/// [[torrent_rules.rules]]
+/// name = "archive-dead-torrents"
+/// min_age_days = 365
+/// max_seeders = 0
+/// action = "archive"
+///
+/// [[torrent_rules.rules]]
+/// name = "reseed-request"
+/// category = "software"
+/// tags = ["linux"]
+/// min_age_days = 30
+/// max_seeders = 1
+/// action = "notify"
+/// message = "Please, keep seeding your torrent."
+/// ```
+///
+/// Refer to the [`torrent_rules`](crate::services::torrent_rules) service for
+/// the details.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct TorrentRules {
+    /// The rules, in evaluation order.
+    #[serde(default = "TorrentRules::default_rules")]
+    pub rules: Vec<Rule>,
+
+    /// Interval in seconds between evaluations. Default to `86400` (one day).
+    #[serde(default = "TorrentRules::default_check_interval_seconds")]
+    pub check_interval_seconds: u64,
+}
+
+impl Default for TorrentRules {
+    fn default() -> Self {
+        Self {
+            rules: Self::default_rules(),
+            check_interval_seconds: Self::default_check_interval_seconds(),
+        }
+    }
+}
+
+impl TorrentRules {
+    fn default_rules() -> Vec<Rule> {
+        vec![]
+    }
+
+    fn default_check_interval_seconds() -> u64 {
+        86_400
+    }
+}
+
+impl Validator for TorrentRules {
+    fn validate(&self) -> Result<(), ValidationError> {
+        let mut names = HashSet::new();
+
+        for rule in &self.rules {
+            let name = rule.name.trim();
+
+            if name.is_empty() || !names.insert(name) {
+                return Err(ValidationError::InvalidTorrentRule(rule.name.clone()));
+            }
+
+            // Rules without conditions would match all the torrents
+            if rule.category.is_none() && rule.tags.is_empty() && rule.min_age_days.is_none() && rule.max_seeders.is_none() {
+                return Err(ValidationError::InvalidTorrentRule(rule.name.clone()));
+            }
+        }
+
+        Ok(())
+    }
+}
+
+/// A torrent rule. Torrents must match all the conditions.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct Rule {
+    /// Unique name of the rule. It's used to remember the torrents the rule
+    /// was applied to, so renaming a rule applies it again.
+    pub name: String,
+
+    /// Name of the category of the torrent.
+    #[serde(default)]
+    pub category: Option<String>,
+
+    /// Names of the tags the torrent must have, all of them.
+    #[serde(default)]
+    pub tags: Vec<String>,
+
+    /// Minimum days since the torrent was uploaded.
+    #[serde(default)]
+    pub min_age_days: Option<u32>,
+
+    /// Maximum number of seeders, according to the tracker statistics.
+    #[serde(default)]
+    pub max_seeders: Option<i64>,
+
+    /// What to do with the matching torrents.
+    pub action: Action,
+
+    /// Message for the uploader in the `notify` action.
+    #[serde(default)]
+    pub message: Option<String>,
+}
+
+/// What to do with the torrents matching a rule.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum Action {
+    /// The torrent is removed from the listing and kept as archived.
+    /// Moderators can approve it again.
+    Archive,
+    /// The torrent is removed from the listing. Moderators can approve it
+    /// again.
+    Delist,
+    /// The uploader is notified by email.
+    Notify,
+}
@@ -4,6 +4,7 @@ use serde::{Deserialize, Serialize};
 use url::Url;
 
 use super::{ValidationError, Validator};
+use crate::utils::redaction::REDACTED;
 
 /// Configuration for the associated tracker.
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
@@ -31,6 +32,32 @@ pub struct Tracker {
     /// Connection string for the tracker. For example: `udp://TRACKER_IP:6969`.
     #[serde(default = "Tracker::default_url")]
     pub url: Url,
+
+    /// Backup trackers. Uploaded torrents are whitelisted in all of them and
+    /// they are added to the torrent files after the main tracker, so that
+    /// clients can fall back to them.
+    ///
+    /// ```toml
+    /// [[tracker.backups]]
+    /// api_url = "http://backup.tracker:1212/"
+    /// token = "MyAccessToken"
+    /// url = "udp://backup.tracker:6969"
+    /// ```
+    #[serde(default = "Tracker::default_backups")]
+    pub backups: Vec<BackupTracker>,
+}
+
+/// Configuration for a backup tracker.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct BackupTracker {
+    /// The url of the tracker API. For example: `http://localhost:1212/`.
+    pub api_url: Url,
+
+    /// The token used to authenticate with the tracker API.
+    pub token: ApiToken,
+
+    /// Connection string for the tracker. For example: `udp://TRACKER_IP:6969`.
+    pub url: Url,
 }
 
 impl Validator for Tracker {
@@ -39,6 +66,11 @@ impl Validator for Tracker {
             return Err(ValidationError::UdpTrackersInPrivateModeNotSupported);
         }
 
+        // Users' keys are only issued by the main tracker
+        if self.private && !self.backups.is_empty() {
+            return Err(ValidationError::BackupTrackersInPrivateModeNotSupported);
+        }
+
         Ok(())
     }
 }
@@ -52,6 +84,7 @@ impl Default for Tracker {
             api_url: Self::default_api_url(),
             token: Self::default_token(),
             token_valid_seconds: Self::default_token_valid_seconds(),
+            backups: Self::default_backups(),
         }
     }
 }
@@ -84,9 +117,15 @@ impl Tracker {
     fn default_token_valid_seconds() -> u64 {
         7_257_600
     }
+
+    fn default_backups() -> Vec<BackupTracker> {
+        vec![]
+    }
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+/// The token is never shown in debug output, so that it does not leak in
+/// the logs.
+#[derive(Clone, Serialize, Deserialize, PartialEq)]
 pub struct ApiToken(String);
 
 impl ApiToken {
@@ -106,6 +145,12 @@ impl ApiToken {
     }
 }
 
+impl fmt::Debug for ApiToken {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_tuple("ApiToken").field(&REDACTED).finish()
+    }
+}
+
 impl fmt::Display for ApiToken {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         write!(f, "{}", self.0)
@@ -121,4 +166,9 @@ mod tests {
     fn apai_token_can_not_be_empty() {
         drop(ApiToken::new(""));
     }
+
+    #[test]
+    fn api_token_should_not_be_shown_in_debug_output() {
+        assert_eq!(format!("{:?}", ApiToken::new("MyAccessToken")), "ApiToken(\"***\")");
+    }
 }
@@ -0,0 +1,115 @@
+use serde::{Deserialize, Serialize};
+use url::Url;
+
+/// Configuration for the tracker statistics importer.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct TrackerStatisticsImporter {
+    /// The port the Importer API is listening on. Default to `3002`.
+    #[serde(default = "TrackerStatisticsImporter::default_port")]
+    pub port: u16,
+
+    /// The interval in seconds to get statistics from the tracker.
+    #[serde(default = "TrackerStatisticsImporter::default_torrent_info_update_interval")]
+    pub torrent_info_update_interval: u64,
+
+    /// Number of torrents requested to the tracker API at once. Default to
+    /// `50`.
+    #[serde(default = "TrackerStatisticsImporter::default_batch_size")]
+    pub batch_size: u32,
+
+    /// Number of batches requested to the tracker API at the same time.
+    /// Default to `1`.
+    #[serde(default = "TrackerStatisticsImporter::default_concurrency")]
+    pub concurrency: u8,
+
+    /// Seconds after which the seeders and leechers shown in the torrent
+    /// details are scraped again from the trackers, instead of waiting for
+    /// the next importer run. On-demand scrape is disabled when it's not set.
+    #[serde(default = "TrackerStatisticsImporter::default_on_demand_scrape_threshold")]
+    pub on_demand_scrape_threshold: Option<u64>,
+
+    /// Alerts sent when the importer keeps failing. Alerts are disabled when
+    /// this section is not present.
+    #[serde(default = "TrackerStatisticsImporter::default_alerts")]
+    pub alerts: Option<Alerts>,
+}
+
+impl Default for TrackerStatisticsImporter {
+    fn default() -> Self {
+        Self {
+            torrent_info_update_interval: Self::default_torrent_info_update_interval(),
+            batch_size: Self::default_batch_size(),
+            concurrency: Self::default_concurrency(),
+            on_demand_scrape_threshold: Self::default_on_demand_scrape_threshold(),
+            port: Self::default_port(),
+            alerts: Self::default_alerts(),
+        }
+    }
+}
+
+impl TrackerStatisticsImporter {
+    fn default_torrent_info_update_interval() -> u64 {
+        3600
+    }
+
+    fn default_batch_size() -> u32 {
+        50
+    }
+
+    fn default_concurrency() -> u8 {
+        1
+    }
+
+    fn default_on_demand_scrape_threshold() -> Option<u64> {
+        None
+    }
+
+    fn default_port() -> u16 {
+        3002
+    }
+
+    fn default_alerts() -> Option<Alerts> {
+        None
+    }
+}
+
+/// Alerts sent when several consecutive importer runs fail.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct Alerts {
+    /// Number of consecutive failed runs before the alert is sent. Default to
+    /// `3`. Only one alert is sent until the importer succeeds again.
+    #[serde(default = "Alerts::default_consecutive_failures")]
+    pub consecutive_failures: u32,
+
+    /// The email address the alert is sent to.
+    #[serde(default = "Alerts::default_email")]
+    pub email: Option<String>,
+
+    /// The URL the alert is posted to as a JSON document.
+    #[serde(default = "Alerts::default_webhook_url")]
+    pub webhook_url: Option<Url>,
+}
+
+impl Default for Alerts {
+    fn default() -> Self {
+        Self {
+            consecutive_failures: Self::default_consecutive_failures(),
+            email: Self::default_email(),
+            webhook_url: Self::default_webhook_url(),
+        }
+    }
+}
+
+impl Alerts {
+    fn default_consecutive_failures() -> u32 {
+        3
+    }
+
+    fn default_email() -> Option<String> {
+        None
+    }
+
+    fn default_webhook_url() -> Option<Url> {
+        None
+    }
+}
@@ -0,0 +1,47 @@
+use serde::{Deserialize, Serialize};
+
+/// Configuration for the trending tags.
+///
+/// Tags are ranked by the growth of their activity, uploads plus `.torrent`
+/// downloads, in the last window compared to the previous one. The ranking is
+/// refreshed in the background. When this section is not present, the
+/// downloads per torrent are not recorded and the trending tags endpoint is
+/// disabled.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct TrendingTags {
+    /// Length of the activity window in days. Default to `7`.
+    #[serde(default = "TrendingTags::default_window_days")]
+    pub window_days: u32,
+
+    /// Interval in seconds between ranking refreshes. Default to `3600`.
+    #[serde(default = "TrendingTags::default_refresh_interval_seconds")]
+    pub refresh_interval_seconds: u64,
+
+    /// Maximum number of tags in the ranking. Default to `20`.
+    #[serde(default = "TrendingTags::default_max_tags")]
+    pub max_tags: usize,
+}
+
+impl Default for TrendingTags {
+    fn default() -> Self {
+        Self {
+            window_days: Self::default_window_days(),
+            refresh_interval_seconds: Self::default_refresh_interval_seconds(),
+            max_tags: Self::default_max_tags(),
+        }
+    }
+}
+
+impl TrendingTags {
+    fn default_window_days() -> u32 {
+        7
+    }
+
+    fn default_refresh_interval_seconds() -> u64 {
+        3600
+    }
+
+    fn default_max_tags() -> usize {
+        20
+    }
+}
@@ -0,0 +1,107 @@
+use serde::{Deserialize, Serialize};
+
+use crate::config::validator::{ValidationError, Validator};
+
+/// Configuration for the content policies of the uploaded torrents.
+///
+/// Torrents containing files with a banned extension, or whose total size or
+/// piece length are outside the bounds, are rejected when they are uploaded.
+/// When this section is not present, only the `[torrent.structure]` policies
+/// are applied.
+///
+/// ```toml
+/// [upload_policy]
+/// banned_extensions = ["exe", "scr", "lnk"]
+/// min_total_size = 1048576
+/// max_total_size = 107374182400
+/// min_piece_length = 262144
+/// max_piece_length = 16777216
+/// ```
+///
+/// Refer to the [`upload_policy`](crate::services::upload_policy) service for
+/// the details.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct UploadPolicy {
+    /// File extensions not allowed in the torrents, without the dot. They are
+    /// compared case-insensitively. Default to none.
+    #[serde(default = "UploadPolicy::default_banned_extensions")]
+    pub banned_extensions: Vec<String>,
+
+    /// Minimum total size in bytes of the torrent contents. Unlimited by
+    /// default.
+    #[serde(default = "UploadPolicy::default_min_total_size")]
+    pub min_total_size: Option<u64>,
+
+    /// Maximum total size in bytes of the torrent contents. Unlimited by
+    /// default.
+    #[serde(default = "UploadPolicy::default_max_total_size")]
+    pub max_total_size: Option<u64>,
+
+    /// Minimum piece length in bytes. Unlimited by default.
+    #[serde(default = "UploadPolicy::default_min_piece_length")]
+    pub min_piece_length: Option<u64>,
+
+    /// Maximum piece length in bytes. Unlimited by default.
+    #[serde(default = "UploadPolicy::default_max_piece_length")]
+    pub max_piece_length: Option<u64>,
+}
+
+impl Default for UploadPolicy {
+    fn default() -> Self {
+        Self {
+            banned_extensions: Self::default_banned_extensions(),
+            min_total_size: Self::default_min_total_size(),
+            max_total_size: Self::default_max_total_size(),
+            min_piece_length: Self::default_min_piece_length(),
+            max_piece_length: Self::default_max_piece_length(),
+        }
+    }
+}
+
+impl UploadPolicy {
+    fn default_banned_extensions() -> Vec<String> {
+        vec![]
+    }
+
+    fn default_min_total_size() -> Option<u64> {
+        None
+    }
+
+    fn default_max_total_size() -> Option<u64> {
+        None
+    }
+
+    fn default_min_piece_length() -> Option<u64> {
+        None
+    }
+
+    fn default_max_piece_length() -> Option<u64> {
+        None
+    }
+}
+
+impl Validator for UploadPolicy {
+    fn validate(&self) -> Result<(), ValidationError> {
+        if let Some(extension) = self
+            .banned_extensions
+            .iter()
+            .find(|extension| extension.trim_start_matches('.').trim().is_empty())
+        {
+            return Err(ValidationError::InvalidBannedExtension(extension.clone()));
+        }
+
+        if let (Some(min), Some(max)) = (self.min_total_size, self.max_total_size) {
+            if min > max {
+                return Err(ValidationError::InvalidUploadSizeRange);
+            }
+        }
+
+        if let (Some(min), Some(max)) = (self.min_piece_length, self.max_piece_length) {
+            if min > max {
+                return Err(ValidationError::InvalidUploadPieceLengthRange);
+            }
+        }
+
+        Ok(())
+    }
+}
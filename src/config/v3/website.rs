@@ -14,6 +14,20 @@ pub struct Website {
     /// The legal information.
     #[serde(default = "Website::default_terms")]
     pub terms: Terms,
+
+    /// Whether new uploads must be approved by a moderator before they are
+    /// listed.
+    #[serde(default = "Website::default_moderated_uploads")]
+    pub moderated_uploads: bool,
+
+    /// The URL of the torrent page in the web application, with an
+    /// `{info_hash}` placeholder. For example:
+    /// `https://index.torrust.com/torrent/{info_hash}`.
+    ///
+    /// The short torrent URLs (`/t/{short_info_hash}`) redirect to it. When
+    /// it's not set, they redirect to the torrent details in the API.
+    #[serde(default = "Website::default_torrent_page_url")]
+    pub torrent_page_url: Option<String>,
 }
 
 impl Default for Website {
@@ -22,6 +36,8 @@ impl Default for Website {
             name: Self::default_name(),
             demo: Self::default_demo(),
             terms: Self::default_terms(),
+            moderated_uploads: Self::default_moderated_uploads(),
+            torrent_page_url: Self::default_torrent_page_url(),
         }
     }
 }
@@ -38,6 +54,14 @@ impl Website {
     fn default_terms() -> Terms {
         Terms::default()
     }
+
+    fn default_moderated_uploads() -> bool {
+        false
+    }
+
+    fn default_torrent_page_url() -> Option<String> {
+        None
+    }
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
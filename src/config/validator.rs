@@ -1,11 +1,141 @@
 //! Trait to validate the whole settings of sections of the settings.
+//!
+//! Besides the validation of the values, there are optional [active checks](check_services)
+//! that probe the services in the configuration at startup. They are enabled
+//! with the `config.active_checks` option:
+//!
+//! ```toml
+//! [config]
+//! active_checks = true
+//! ```
+use std::net::SocketAddr;
+use std::time::Duration;
+
 use thiserror::Error;
+use tokio::net::{lookup_host, TcpStream};
+use tokio::time::timeout;
+use url::Url;
+
+use super::Settings;
+
+/// Max time to resolve or connect to each service in the active checks.
+const ACTIVE_CHECK_TIMEOUT: Duration = Duration::from_secs(5);
 
 /// Errors that can occur validating the configuration.
 #[derive(Error, Debug)]
 pub enum ValidationError {
     #[error("UDP private trackers are not supported. URL schemes for private tracker URLs must be HTTP ot HTTPS")]
     UdpTrackersInPrivateModeNotSupported,
+
+    #[error("Backup trackers are not supported in private mode because users' keys are only issued by the main tracker")]
+    BackupTrackersInPrivateModeNotSupported,
+
+    #[error("The Meilisearch search backend requires the `[search.meilisearch]` configuration")]
+    MissingMeilisearchConfiguration,
+
+    #[error("The `s3` image cache backend requires the `[storage.s3]` configuration")]
+    MissingImageCacheBucket,
+
+    #[error("The `s3` torrent files backend requires the `[storage.s3]` configuration")]
+    MissingTorrentFilesBucket,
+
+    #[error("The `[torrent.description_images]` uploads require the `filesystem` or `s3` image cache backend")]
+    DescriptionImagesInMemory,
+
+    #[error("The Redis cache backend requires the `[cache.redis]` configuration")]
+    MissingRedisConfiguration,
+
+    #[error("The `[torrent.structure]` minimum piece length can't be greater than the maximum piece length")]
+    InvalidPieceLengthRange,
+
+    #[error("The `[torrent.similar_titles]` title distance and size tolerance must be between 0.0 and 1.0")]
+    InvalidSimilarTitlesThreshold,
+
+    #[error("The `[torrent.deduplication]` threshold must be greater than 0.0 and not greater than 1.0")]
+    InvalidDeduplicationThreshold,
+
+    #[error("The `[torrent.health]` healthy seeders must be greater than 0")]
+    InvalidHealthySeeders,
+
+    #[error("The `[torrent.download]` source can't be empty")]
+    EmptyTorrentSource,
+
+    #[error("The `[upload_policy]` banned extension `{0}` is empty")]
+    InvalidBannedExtension(String),
+
+    #[error("The `[upload_policy]` minimum total size can't be greater than the maximum total size")]
+    InvalidUploadSizeRange,
+
+    #[error("The `[upload_policy]` minimum piece length can't be greater than the maximum piece length")]
+    InvalidUploadPieceLengthRange,
+
+    #[error("The `[reputation]` min approved ratio must be between 0.0 and 1.0")]
+    InvalidApprovedRatio,
+
+    #[error("The `[ratio]` section requires the tracker to be running in private mode")]
+    RatioRequiresPrivateMode,
+
+    #[error("The `[ratio]` import interval must be greater than 0")]
+    InvalidRatioImportInterval,
+
+    #[error("The `[ratio]` min download ratio must be a non-negative number")]
+    InvalidMinDownloadRatio,
+
+    #[error("The `[antispam]` max link density must be greater than 0.0 and not greater than 1.0")]
+    InvalidLinkDensity,
+
+    #[error("The `[enrichment]` section requires at least one metadata provider")]
+    MissingMetadataProviders,
+
+    #[error("The `[inactivity]` action period must be longer than the warning period")]
+    InvalidInactivityPeriods,
+
+    #[error("The `[database.encryption]` section requires either a `key` of 64 hexadecimal characters or a `key_path`")]
+    InvalidDatabaseEncryptionKey,
+
+    #[error("The `[database]` max connections must be greater than 0")]
+    InvalidDatabasePoolSize,
+
+    #[error("The `[database]` read replicas are only supported with `MySQL` databases")]
+    UnsupportedDatabaseReplicas,
+
+    #[error("The `[retention.tables]` section has an override for the `{0}` table, which has no retention policy")]
+    UnknownRetentionTable(String),
+
+    #[error("The `[torrent_rules]` rule `{0}` has no conditions or its name is empty or duplicated")]
+    InvalidTorrentRule(String),
+
+    #[error("The `[retention]` periods must be at least one day")]
+    InvalidRetentionPeriod,
+
+    #[error("The `[auth.jwt.extra_claims]` claim `{0}` is reserved or has no value")]
+    InvalidJwtExtraClaim(String),
+
+    #[error("The `{0}` option can't be empty")]
+    EmptySigningSecret(String),
+
+    #[error("The `{option}` host `{host}` can't be resolved: {error}")]
+    UnresolvableHost { option: String, host: String, error: String },
+
+    #[error("The `{option}` service at `{host}:{port}` is not reachable: {error}")]
+    UnreachableService {
+        option: String,
+        host: String,
+        port: u16,
+        error: String,
+    },
+}
+
+impl ValidationError {
+    /// The option of the service that failed the [active checks](check_services),
+    /// if the error comes from them.
+    #[must_use]
+    pub fn service(&self) -> Option<&str> {
+        match self {
+            Self::UnresolvableHost { option, .. } | Self::UnreachableService { option, .. } => Some(option),
+            _ => None,
+        }
+    }
 }
 
 pub trait Validator {
@@ -14,3 +144,204 @@ pub trait Validator {
     /// Will return an error if the configuration is invalid.
     fn validate(&self) -> Result<(), ValidationError>;
 }
+
+/// It checks that the services in the configuration are reachable, so that
+/// common misconfigurations, like a wrong port, fail at startup instead of
+/// when the services are used:
+///
+/// - The tracker API (`tracker.api_url`) must accept connections.
+/// - The tracker (`tracker.url`) must accept connections, or only be resolved
+///   for UDP trackers.
+/// - The same for the API and the URL of every backup tracker
+///   (`tracker.backups`).
+/// - The SMTP server (`mail.smtp`), if any, must accept connections.
+/// - The base URL (`net.base_url`), if any, must be resolved. It can point to
+///   this application, which is not listening yet.
+///
+/// # Errors
+///
+/// Will return an error if a host can't be resolved or a service does not
+/// accept connections.
+pub async fn check_services(settings: &Settings) -> Result<(), ValidationError> {
+    check_tracker_api(settings).await?;
+
+    check_tracker_url("tracker.url", &settings.tracker.url).await?;
+
+    for (index, backup) in settings.tracker.backups.iter().enumerate() {
+        check_url(&format!("tracker.backups[{index}].api_url"), &backup.api_url, true).await?;
+
+        check_tracker_url(&format!("tracker.backups[{index}].url"), &backup.url).await?;
+    }
+
+    if !settings.mail.smtp.server.is_empty() {
+        check_smtp_server(settings).await?;
+    }
+
+    if let Some(base_url) = &settings.net.base_url {
+        check_url("net.base_url", base_url, false).await?;
+    }
+
+    Ok(())
+}
+
+/// It checks that the tracker API (`tracker.api_url`) accepts connections.
+///
+/// # Errors
+///
+/// Will return an error if the host can't be resolved or the tracker API
+/// does not accept connections.
+pub async fn check_tracker_api(settings: &Settings) -> Result<(), ValidationError> {
+    check_url("tracker.api_url", &settings.tracker.api_url, true).await
+}
+
+/// It checks that the SMTP server (`mail.smtp`) accepts connections.
+///
+/// # Errors
+///
+/// Will return an error if the host can't be resolved or the SMTP server
+/// does not accept connections.
+pub async fn check_smtp_server(settings: &Settings) -> Result<(), ValidationError> {
+    let addresses = resolve("mail.smtp.server", &settings.mail.smtp.server, settings.mail.smtp.port).await?;
+
+    connect(
+        "mail.smtp.server",
+        &settings.mail.smtp.server,
+        settings.mail.smtp.port,
+        &addresses,
+    )
+    .await
+}
+
+/// It checks a tracker URL. UDP trackers are only resolved.
+async fn check_tracker_url(option: &str, url: &Url) -> Result<(), ValidationError> {
+    let connect_to_tracker = url.scheme() != "udp";
+
+    check_url(option, url, connect_to_tracker).await
+}
+
+/// It resolves the URL host and, optionally, connects to it.
+async fn check_url(option: &str, url: &Url, connect_to_host: bool) -> Result<(), ValidationError> {
+    let Some(host) = url.host_str() else {
+        return Err(ValidationError::UnresolvableHost {
+            option: option.to_string(),
+            host: url.to_string(),
+            error: "the URL has no host".to_string(),
+        });
+    };
+
+    // IPv6 hosts are enclosed in brackets in URLs.
+    let host = host.trim_start_matches('[').trim_end_matches(']');
+
+    let port = url.port_or_known_default().unwrap_or_default();
+
+    let addresses = resolve(option, host, port).await?;
+
+    if connect_to_host {
+        connect(option, host, port, &addresses).await?;
+    }
+
+    Ok(())
+}
+
+async fn resolve(option: &str, host: &str, port: u16) -> Result<Vec<SocketAddr>, ValidationError> {
+    let unresolvable_host = |error: String| ValidationError::UnresolvableHost {
+        option: option.to_string(),
+        host: host.to_string(),
+        error,
+    };
+
+    let addresses: Vec<SocketAddr> = timeout(ACTIVE_CHECK_TIMEOUT, lookup_host((host, port)))
+        .await
+        .map_err(|_| unresolvable_host("timeout".to_string()))?
+        .map_err(|err| unresolvable_host(err.to_string()))?
+        .collect();
+
+    if addresses.is_empty() {
+        return Err(unresolvable_host("no addresses found".to_string()));
+    }
+
+    Ok(addresses)
+}
+
+async fn connect(option: &str, host: &str, port: u16, addresses: &[SocketAddr]) -> Result<(), ValidationError> {
+    let unreachable_service = |error: String| ValidationError::UnreachableService {
+        option: option.to_string(),
+        host: host.to_string(),
+        port,
+        error,
+    };
+
+    timeout(ACTIVE_CHECK_TIMEOUT, TcpStream::connect(addresses))
+        .await
+        .map_err(|_| unreachable_service("timeout".to_string()))?
+        .map_err(|err| unreachable_service(err.to_string()))?;
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use tokio::net::TcpListener;
+    use url::Url;
+
+    use super::{check_services, check_url, ValidationError};
+    use crate::config::{ApiToken, BackupTracker, Settings};
+
+    #[tokio::test]
+    async fn it_should_accept_reachable_services() {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let port = listener.local_addr().unwrap().port();
+
+        let url = Url::parse(&format!("http://127.0.0.1:{port}/api")).unwrap();
+
+        assert!(check_url("tracker.api_url", &url, true).await.is_ok());
+    }
+
+    #[tokio::test]
+    async fn it_should_reject_services_not_accepting_connections() {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let port = listener.local_addr().unwrap().port();
+        drop(listener);
+
+        let url = Url::parse(&format!("http://127.0.0.1:{port}/api")).unwrap();
+
+        assert!(matches!(
+            check_url("tracker.api_url", &url, true).await,
+            Err(ValidationError::UnreachableService { .. })
+        ));
+    }
+
+    #[tokio::test]
+    async fn it_should_only_resolve_the_host_when_it_does_not_have_to_connect() {
+        let url = Url::parse("udp://127.0.0.1:1/announce").unwrap();
+
+        assert!(check_url("tracker.url", &url, false).await.is_ok());
+    }
+
+    #[tokio::test]
+    async fn it_should_check_the_backup_trackers() {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let port = listener.local_addr().unwrap().port();
+
+        let closed_listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let closed_port = closed_listener.local_addr().unwrap().port();
+        drop(closed_listener);
+
+        let mut settings = Settings::default();
+        settings.tracker.api_url = Url::parse(&format!("http://127.0.0.1:{port}")).unwrap();
+        settings.tracker.url = Url::parse("udp://127.0.0.1:6969").unwrap();
+
+        assert!(check_services(&settings).await.is_ok());
+
+        settings.tracker.backups = vec![BackupTracker {
+            api_url: Url::parse(&format!("http://127.0.0.1:{closed_port}")).unwrap(),
+            token: ApiToken::new("MyAccessToken"),
+            url: Url::parse("udp://127.0.0.1:6969").unwrap(),
+        }];
+
+        assert!(matches!(
+            check_services(&settings).await,
+            Err(ValidationError::UnreachableService { option, .. }) if option == "tracker.backups[0].api_url"
+        ));
+    }
+}
@@ -0,0 +1,254 @@
+//! Console app to back up the whole index.
+//!
+//! Run with:
+//!
+//! ```text
+//! cargo run --bin backup -- [--output <OUTPUT>] [--s3-key <S3_KEY>]
+//! ```
+//!
+//! For example:
+//!
+//! ```text
+//! cargo run --bin backup > ./storage/index/backup.ndjson
+//! cargo run --bin backup -- --output ./storage/index/backup.ndjson
+//! cargo run --bin backup -- --s3-key backups/2024-10-18.ndjson
+//! ```
+//!
+//! The backup is written to the standard output by default, so it can be
+//! compressed or uploaded with other tools. With `--s3-key`, it's uploaded
+//! to the `[storage.s3]` bucket. The whole backup is kept in memory before
+//! uploading it, so big indexes should be streamed to the standard output
+//! instead. Progress messages are written to the standard error.
+//!
+//! The database rows are read in a single transaction, so the index doesn't
+//! need to be stopped. The backup can be restored with the
+//! [`restore`](super::restore) command.
+//!
+//! Torrent files in the `filesystem` or `s3` torrent file backends are not
+//! included. Back up that directory or bucket with other tools.
+//!
+//! Refer to the [`format`](super::format) module for the backup format.
+use anyhow::Context;
+use base64::engine::general_purpose::STANDARD;
+use base64::Engine;
+use bytes::Bytes;
+use camino::{Utf8Path, Utf8PathBuf};
+use clap::Parser;
+use text_colorizer::Colorize;
+use tokio::io::{AsyncWrite, AsyncWriteExt, BufWriter};
+use tokio::sync::mpsc;
+
+use super::format::{self, is_valid_image_key, Header, Image, Record, Row};
+use crate::bootstrap::config::initialize_configuration;
+use crate::bootstrap::logging;
+use crate::cache::image::store::{FilesystemStore, ImageStore};
+use crate::config::ImageCacheBackend;
+use crate::databases::database::{self, Database};
+use crate::storage::s3::Bucket;
+
+/// Number of rows read from the database ahead of the writer.
+const ROWS_BUFFER: usize = 1000;
+
+#[derive(Parser, Debug)]
+#[clap(author, version, about, long_about = None)]
+struct Args {
+    /// File where the backup is written. Default to the standard output.
+    #[arg(short, long)]
+    output: Option<Utf8PathBuf>,
+
+    /// Key of the object where the backup is uploaded, in the `[storage.s3]`
+    /// bucket.
+    #[arg(long, conflicts_with = "output")]
+    s3_key: Option<String>,
+}
+
+#[derive(Debug, Default)]
+struct BackupSummary {
+    rows: u64,
+    images: usize,
+}
+
+/// # Errors
+///
+/// It returns an error if the backup can't be written or uploaded, or there
+/// is a database error.
+///
+/// # Panics
+///
+/// Panics if it can't connect to the database.
+pub async fn run() -> anyhow::Result<()> {
+    let args = Args::parse();
+
+    let configuration = initialize_configuration();
+
+    let settings = configuration.get_all().await;
+
+    // Logs are written to the standard output, like the backup
+    if args.output.is_some() || args.s3_key.is_some() {
+        logging::setup(&settings.logging);
+    }
+
+    let database = database::connect_without_migrating(settings.database.connect_url.as_ref(), None)
+        .await
+        .expect("unable to connect to db");
+
+    // Images in other backends are not stored by the index
+    let image_cache_path = match settings.image_cache.backend {
+        ImageCacheBackend::Filesystem => Some(settings.image_cache.path.as_path()),
+        ImageCacheBackend::Memory | ImageCacheBackend::S3 => None,
+    };
+
+    let summary = if let Some(s3_key) = &args.s3_key {
+        let s3 = settings
+            .storage
+            .as_ref()
+            .and_then(|storage| storage.s3.as_ref())
+            .context("the `[storage.s3]` section is not configured")?;
+
+        let mut buffer = Vec::new();
+
+        let summary = backup(database.as_ref(), image_cache_path, &mut buffer).await?;
+
+        eprintln!("Uploading the backup to {} ...", s3_key.yellow());
+
+        Bucket::new(s3)
+            .put(s3_key, "application/x-ndjson", Bytes::from(buffer))
+            .await
+            .map_err(|e| anyhow::anyhow!("failed to upload the backup: {e:?}"))?;
+
+        summary
+    } else if let Some(output) = &args.output {
+        let file = tokio::fs::File::create(output)
+            .await
+            .with_context(|| format!("failed to create the backup file {output}"))?;
+
+        backup(database.as_ref(), image_cache_path, file).await?
+    } else {
+        backup(database.as_ref(), image_cache_path, tokio::io::stdout()).await?
+    };
+
+    eprintln!("Rows: {}", summary.rows.to_string().green());
+    eprintln!("Images: {}", summary.images.to_string().green());
+
+    Ok(())
+}
+
+async fn backup<W: AsyncWrite + Unpin>(
+    database: &dyn Database,
+    image_cache_path: Option<&Utf8Path>,
+    writer: W,
+) -> anyhow::Result<BackupSummary> {
+    let mut writer = BufWriter::new(writer);
+
+    let migration = database
+        .get_migrations()
+        .await
+        .map_err(db_error)?
+        .iter()
+        .filter(|migration| migration.applied)
+        .map(|migration| migration.version)
+        .max()
+        .context("the database has no migrations applied")?;
+
+    write_record(
+        &mut writer,
+        &Record::Header(Header {
+            format: format::FORMAT.to_string(),
+            version: format::VERSION,
+            created_at: chrono::Utc::now().to_rfc3339(),
+            migration,
+        }),
+    )
+    .await?;
+
+    // Database rows
+
+    let (sender, mut receiver) = mpsc::channel(ROWS_BUFFER);
+
+    let write_rows = async {
+        while let Some(row) = receiver.recv().await {
+            write_record(&mut writer, &Record::Row(Row::from(row))).await?;
+        }
+
+        anyhow::Ok(())
+    };
+
+    let (rows, ()) = tokio::try_join!(async { database.dump_tables(sender).await.map_err(db_error) }, write_rows)?;
+
+    // Images
+
+    let mut images = 0;
+
+    if let Some(path) = image_cache_path {
+        let store = FilesystemStore::new(path);
+
+        for key in get_image_keys(path)? {
+            // The image could have been evicted in the meantime
+            let Some(bytes) = store
+                .get(&key)
+                .await
+                .map_err(|e| anyhow::anyhow!("failed to read the image {key}: {e:?}"))?
+            else {
+                continue;
+            };
+
+            write_record(
+                &mut writer,
+                &Record::Image(Image {
+                    key,
+                    data: STANDARD.encode(bytes),
+                }),
+            )
+            .await?;
+
+            images += 1;
+        }
+    }
+
+    writer.flush().await.context("failed to write the backup")?;
+
+    Ok(BackupSummary { rows, images })
+}
+
+/// The keys of the images in the filesystem image cache. Images are grouped
+/// in subdirectories.
+fn get_image_keys(path: &Utf8Path) -> anyhow::Result<Vec<String>> {
+    let mut keys = vec![];
+
+    if !path.exists() {
+        return Ok(keys);
+    }
+
+    for directory in path.read_dir_utf8().context("failed to read the image cache")? {
+        let directory = directory?;
+
+        if !directory.file_type()?.is_dir() {
+            continue;
+        }
+
+        for file in directory.path().read_dir_utf8()? {
+            let file = file?;
+
+            // Temporary files are skipped
+            if file.file_type()?.is_file() && is_valid_image_key(file.file_name()) {
+                keys.push(file.file_name().to_string());
+            }
+        }
+    }
+
+    keys.sort();
+
+    Ok(keys)
+}
+
+async fn write_record<W: AsyncWrite + Unpin>(writer: &mut BufWriter<W>, record: &Record) -> anyhow::Result<()> {
+    let mut line = serde_json::to_vec(record)?;
+
+    line.push(b'\n');
+
+    writer.write_all(&line).await.context("failed to write the backup")
+}
+
+fn db_error(error: database::Error) -> anyhow::Error {
+    anyhow::anyhow!("database error: {error:?}")
+}
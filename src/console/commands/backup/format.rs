@@ -0,0 +1,175 @@
+//! Backup format.
+//!
+//! A backup is a single NDJSON stream, so it can be written to the standard
+//! output and piped to other tools. The first record is always the header,
+//! with the format version and the last database migration applied. Then,
+//! the rows of all the database tables and the images stored in the
+//! filesystem image cache:
+//!
+//! ```text
+//! {"type":"header","format":"torrust-index-backup","version":1,"created_at":"2024-10-18T10:00:00+00:00","migration":20241018030000}
+//! {"type":"row","table":"torrust_categories","columns":["category_id","name"],"values":[{"integer":1},{"text":"software"}]}
+//! {"type":"image","key":"5f0c...","data":"iVBORw0KGgo..."}
+//! ```
+//!
+//! Blobs and images are encoded in base64. The stored torrent files are
+//! rows of the database tables. Encrypted columns are copied as they are,
+//! so the same encryption key is needed after restoring the backup.
+use base64::engine::general_purpose::STANDARD;
+use base64::Engine;
+use serde::{Deserialize, Serialize};
+
+use crate::databases::database::{ColumnValue, TableRow};
+
+/// Name of the format in the header.
+pub const FORMAT: &str = "torrust-index-backup";
+
+/// Current version of the format. Newer versions are rejected.
+pub const VERSION: u32 = 1;
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum Record {
+    Header(Header),
+    Row(Row),
+    Image(Image),
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Header {
+    pub format: String,
+    pub version: u32,
+    pub created_at: String,
+    /// The version of the last database migration applied.
+    pub migration: i64,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Row {
+    pub table: String,
+    pub columns: Vec<String>,
+    pub values: Vec<Value>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Value {
+    Null,
+    Integer(i64),
+    Real(f64),
+    Text(String),
+    /// Base64 encoded bytes.
+    Blob(String),
+}
+
+/// An image of the filesystem image cache.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Image {
+    /// The cache key.
+    pub key: String,
+    /// Base64 encoded image.
+    pub data: String,
+}
+
+impl From<TableRow> for Row {
+    fn from(row: TableRow) -> Self {
+        Self {
+            table: row.table,
+            columns: row.columns,
+            values: row
+                .values
+                .into_iter()
+                .map(|value| match value {
+                    ColumnValue::Null => Value::Null,
+                    ColumnValue::Integer(value) => Value::Integer(value),
+                    ColumnValue::Real(value) => Value::Real(value),
+                    ColumnValue::Text(value) => Value::Text(value),
+                    ColumnValue::Blob(value) => Value::Blob(STANDARD.encode(value)),
+                })
+                .collect(),
+        }
+    }
+}
+
+impl TryFrom<Row> for TableRow {
+    type Error = base64::DecodeError;
+
+    fn try_from(row: Row) -> Result<Self, Self::Error> {
+        Ok(Self {
+            table: row.table,
+            columns: row.columns,
+            values: row
+                .values
+                .into_iter()
+                .map(|value| {
+                    Ok(match value {
+                        Value::Null => ColumnValue::Null,
+                        Value::Integer(value) => ColumnValue::Integer(value),
+                        Value::Real(value) => ColumnValue::Real(value),
+                        Value::Text(value) => ColumnValue::Text(value),
+                        Value::Blob(value) => ColumnValue::Blob(STANDARD.decode(value)?),
+                    })
+                })
+                .collect::<Result<_, _>>()?,
+        })
+    }
+}
+
+/// It checks the header is the first record and the version is supported.
+///
+/// # Errors
+///
+/// It returns an error if the record is not a supported header.
+pub fn check_header(record: Option<&Record>) -> anyhow::Result<&Header> {
+    match record {
+        Some(Record::Header(header)) if header.format != FORMAT => {
+            anyhow::bail!("unknown backup format `{}`", header.format)
+        }
+        Some(Record::Header(header)) if header.version > VERSION => anyhow::bail!(
+            "unsupported backup version {}, the latest supported version is {VERSION}",
+            header.version
+        ),
+        Some(Record::Header(header)) => Ok(header),
+        _ => anyhow::bail!("the first record must be the header"),
+    }
+}
+
+/// Whether the key of an image can be used as a file name.
+#[must_use]
+pub fn is_valid_image_key(key: &str) -> bool {
+    !key.is_empty() && key.chars().all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_')
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Record, Row, Value};
+    use crate::databases::database::{ColumnValue, TableRow};
+
+    #[test]
+    fn it_should_keep_the_column_values_in_a_round_trip() {
+        let table_row = TableRow {
+            table: "torrust_torrent_info".to_string(),
+            columns: vec![
+                "torrent_id".to_string(),
+                "title".to_string(),
+                "pieces".to_string(),
+                "comment".to_string(),
+            ],
+            values: vec![
+                ColumnValue::Integer(1),
+                ColumnValue::Text("MandelbrotSet".to_string()),
+                ColumnValue::Blob(vec![0, 159, 255]),
+                ColumnValue::Null,
+            ],
+        };
+
+        let json = serde_json::to_string(&Record::Row(Row::from(table_row.clone()))).unwrap();
+
+        let Record::Row(row) = serde_json::from_str::<Record>(&json).unwrap() else {
+            panic!("expected a row record");
+        };
+
+        assert_eq!(row.values[2], Value::Blob("AJ//".to_string()));
+        assert_eq!(TableRow::try_from(row).unwrap(), table_row);
+    }
+}
@@ -0,0 +1,11 @@
+//! Commands to back up the whole index while it's running and restore it,
+//! with any of the database drivers.
+//!
+//! Unlike the [`export`](super::export) commands, a backup is a copy of all
+//! the database tables, so it can only be restored with the same database
+//! migrations.
+//!
+//! Refer to the [`format`] module for a description of the backup format.
+pub mod app;
+pub mod format;
+pub mod restore;
@@ -0,0 +1,238 @@
+//! Console app to restore a backup of the whole index.
+//!
+//! Run with:
+//!
+//! ```text
+//! cargo run --bin restore -- [--input <INPUT>] [--s3-key <S3_KEY>]
+//! ```
+//!
+//! For example:
+//!
+//! ```text
+//! cargo run --bin restore < ./storage/index/backup.ndjson
+//! cargo run --bin restore -- --input ./storage/index/backup.ndjson
+//! cargo run --bin restore -- --s3-key backups/2024-10-18.ndjson
+//! ```
+//!
+//! The backup is read from the standard input by default. With `--s3-key`,
+//! it's downloaded from the `[storage.s3]` bucket.
+//!
+//! All the rows of the database tables are replaced in a single transaction,
+//! so the database is not changed when the restore fails. The database must
+//! have the same migrations applied as the backup: create it with the
+//! [`migrate`](crate::console::commands::migrate) command of the same
+//! version of the index. The index should be stopped while the backup is
+//! restored. The search index is rebuilt when it starts again.
+//!
+//! Images are only restored when the image cache is stored in the
+//! filesystem.
+use anyhow::Context;
+use base64::engine::general_purpose::STANDARD;
+use base64::Engine;
+use bytes::Bytes;
+use camino::Utf8PathBuf;
+use clap::Parser;
+use text_colorizer::Colorize;
+use tokio::io::{AsyncBufRead, AsyncBufReadExt, BufReader};
+use tokio::sync::mpsc;
+
+use super::format::{self, is_valid_image_key, Record};
+use crate::bootstrap::config::initialize_configuration;
+use crate::bootstrap::logging;
+use crate::cache::image::store::{FilesystemStore, ImageStore};
+use crate::config::ImageCacheBackend;
+use crate::databases::database::{self, Database, TableRow};
+use crate::storage::s3::Bucket;
+
+/// Number of rows read from the backup ahead of the database.
+const ROWS_BUFFER: usize = 1000;
+
+#[derive(Parser, Debug)]
+#[clap(author, version, about, long_about = None)]
+struct Args {
+    /// File with the backup. Default to the standard input.
+    #[arg(short, long)]
+    input: Option<Utf8PathBuf>,
+
+    /// Key of the object with the backup, in the `[storage.s3]` bucket.
+    #[arg(long, conflicts_with = "input")]
+    s3_key: Option<String>,
+}
+
+#[derive(Debug, Default)]
+struct RestoreSummary {
+    rows: u64,
+    images: usize,
+    skipped_images: usize,
+}
+
+/// # Errors
+///
+/// It returns an error if the backup can't be read or it's not valid, or
+/// there is a database error.
+///
+/// # Panics
+///
+/// Panics if it can't connect to the database.
+pub async fn run() -> anyhow::Result<()> {
+    let args = Args::parse();
+
+    let configuration = initialize_configuration();
+
+    let settings = configuration.get_all().await;
+
+    logging::setup(&settings.logging);
+
+    let database = database::connect_without_migrating(settings.database.connect_url.as_ref(), None)
+        .await
+        .expect("unable to connect to db");
+
+    let image_store = match settings.image_cache.backend {
+        ImageCacheBackend::Filesystem => Some(FilesystemStore::new(&settings.image_cache.path)),
+        ImageCacheBackend::Memory | ImageCacheBackend::S3 => None,
+    };
+
+    let summary = if let Some(s3_key) = &args.s3_key {
+        let s3 = settings
+            .storage
+            .as_ref()
+            .and_then(|storage| storage.s3.as_ref())
+            .context("the `[storage.s3]` section is not configured")?;
+
+        println!("Downloading the backup from {} ...", s3_key.yellow());
+
+        let backup = Bucket::new(s3)
+            .get(s3_key)
+            .await
+            .map_err(|e| anyhow::anyhow!("failed to download the backup: {e:?}"))?
+            .with_context(|| format!("the backup {s3_key} does not exist"))?;
+
+        restore(database.as_ref(), image_store.as_ref(), &backup[..]).await?
+    } else if let Some(input) = &args.input {
+        let file = tokio::fs::File::open(input)
+            .await
+            .with_context(|| format!("failed to open the backup file {input}"))?;
+
+        restore(database.as_ref(), image_store.as_ref(), BufReader::new(file)).await?
+    } else {
+        restore(database.as_ref(), image_store.as_ref(), BufReader::new(tokio::io::stdin())).await?
+    };
+
+    println!("Rows: {}", summary.rows.to_string().green());
+    println!("Images: {}", summary.images.to_string().green());
+
+    if summary.skipped_images > 0 {
+        println!(
+            "Skipped images: {} (the image cache is not stored in the filesystem)",
+            summary.skipped_images.to_string().yellow()
+        );
+    }
+
+    Ok(())
+}
+
+async fn restore<R: AsyncBufRead + Unpin>(
+    database: &dyn Database,
+    image_store: Option<&FilesystemStore>,
+    reader: R,
+) -> anyhow::Result<RestoreSummary> {
+    let mut lines = reader.lines();
+
+    let first_record = match lines.next_line().await.context("failed to read the backup")? {
+        Some(line) => Some(serde_json::from_str::<Record>(&line).context("invalid record in line 1")?),
+        None => None,
+    };
+
+    let header = format::check_header(first_record.as_ref())?;
+
+    let migration = database
+        .get_migrations()
+        .await
+        .map_err(db_error)?
+        .iter()
+        .filter(|migration| migration.applied)
+        .map(|migration| migration.version)
+        .max()
+        .unwrap_or_default();
+
+    if migration != header.migration {
+        anyhow::bail!(
+            "the backup needs the database migration {}, but the last migration applied is {migration}. \
+             Apply the migrations with the `migrate` command of the same version of the index",
+            header.migration
+        );
+    }
+
+    println!("Restoring the backup created at {} ...", header.created_at.yellow());
+
+    let (sender, receiver) = mpsc::channel(ROWS_BUFFER);
+
+    // When this future fails, the restore is dropped and the transaction is
+    // rolled back
+    let read_records = async move {
+        let mut images = 0;
+        let mut skipped_images = 0;
+        let mut line_number = 1;
+
+        while let Some(line) = lines.next_line().await.context("failed to read the backup")? {
+            line_number += 1;
+
+            if line.trim().is_empty() {
+                continue;
+            }
+
+            let record: Record =
+                serde_json::from_str(&line).map_err(|e| anyhow::anyhow!("invalid record in line {line_number}: {e}"))?;
+
+            match record {
+                Record::Header(_) => anyhow::bail!("unexpected header in line {line_number}"),
+                Record::Row(row) => {
+                    let row = TableRow::try_from(row).map_err(|e| anyhow::anyhow!("invalid blob in line {line_number}: {e}"))?;
+
+                    // The restore failed, its error is returned
+                    if sender.send(row).await.is_err() {
+                        break;
+                    }
+                }
+                Record::Image(image) => {
+                    let Some(store) = image_store else {
+                        skipped_images += 1;
+                        continue;
+                    };
+
+                    if !is_valid_image_key(&image.key) {
+                        anyhow::bail!("invalid image key in line {line_number}");
+                    }
+
+                    let bytes = STANDARD
+                        .decode(&image.data)
+                        .map_err(|e| anyhow::anyhow!("invalid image in line {line_number}: {e}"))?;
+
+                    store
+                        .put(&image.key, Bytes::from(bytes))
+                        .await
+                        .map_err(|e| anyhow::anyhow!("failed to write the image {}: {e:?}", image.key))?;
+
+                    images += 1;
+                }
+            }
+        }
+
+        anyhow::Ok((images, skipped_images))
+    };
+
+    let (rows, (images, skipped_images)) = tokio::try_join!(
+        async { database.restore_tables(receiver).await.map_err(db_error) },
+        read_records
+    )?;
+
+    Ok(RestoreSummary {
+        rows,
+        images,
+        skipped_images,
+    })
+}
+
+fn db_error(error: database::Error) -> anyhow::Error {
+    anyhow::anyhow!("database error: {error:?}")
+}
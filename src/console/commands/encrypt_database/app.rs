@@ -0,0 +1,100 @@
+//! Console app to encrypt the sensitive columns of an existing database.
+//!
+//! Run with:
+//!
+//! ```text
+//! cargo run --bin encrypt_database
+//! ```
+//!
+//! It requires the `[database.encryption]` section in the configuration.
+//! Once the encryption is enabled, the index encrypts new values, but the
+//! values written before stay in plaintext until this command rewrites them.
+//!
+//! Currently, it encrypts the users' emails. Values that are already
+//! encrypted are rewritten unchanged, so it can be run more than once.
+//! Emails waiting in the outbound queue are not rewritten: they are removed
+//! from the queue once they are sent.
+//!
+//! Refer to the [`encryption`](crate::databases::encryption) module for the
+//! encryption details.
+use std::sync::Arc;
+
+use anyhow::Context;
+use text_colorizer::Colorize;
+
+use crate::bootstrap::config::initialize_configuration;
+use crate::bootstrap::logging;
+use crate::databases::database::{self, Database};
+use crate::databases::encryption::Cipher;
+
+/// Number of users read from the database in each query.
+const USERS_PAGE_SIZE: u32 = 1000;
+
+/// # Errors
+///
+/// It returns an error if the encryption is not configured or there is a
+/// database error.
+///
+/// # Panics
+///
+/// Panics if it can't connect to the database.
+pub async fn run() -> anyhow::Result<()> {
+    let configuration = initialize_configuration();
+
+    let logging = configuration.settings.read().await.logging.clone();
+
+    logging::setup(&logging);
+
+    let database_settings = configuration.settings.read().await.database.clone();
+
+    let encryption = database_settings
+        .encryption
+        .context("the `[database.encryption]` section is not configured")?;
+
+    let cipher = Cipher::from_config(&encryption).context("invalid database encryption key")?;
+
+    let database = database::connect_with_cipher(database_settings.connect_url.as_ref(), Some(Arc::new(cipher)))
+        .await
+        .expect("unable to connect to db");
+
+    println!("Encrypting the sensitive columns ...");
+
+    let emails = encrypt_user_emails(database.as_ref()).await?;
+
+    println!("User emails: {}", emails.to_string().green());
+
+    Ok(())
+}
+
+/// It rewrites the users' emails. The database driver encrypts them.
+async fn encrypt_user_emails(database: &dyn Database) -> anyhow::Result<usize> {
+    let mut offset = 0;
+    let mut emails = 0;
+
+    loop {
+        let users = database
+            .get_users_paginated(offset, USERS_PAGE_SIZE)
+            .await
+            .map_err(db_error)?;
+
+        if users.is_empty() {
+            break;
+        }
+
+        offset += users.len() as u64;
+
+        for user in users.into_iter().filter(|user| !user.email.is_empty()) {
+            database
+                .update_user_email(user.user_id, &user.email)
+                .await
+                .map_err(db_error)?;
+            emails += 1;
+        }
+    }
+
+    Ok(emails)
+}
+
+fn db_error(error: database::Error) -> anyhow::Error {
+    anyhow::anyhow!("database error: {error:?}")
+}
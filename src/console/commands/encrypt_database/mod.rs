@@ -0,0 +1,2 @@
+//! Command to encrypt the sensitive columns of an existing database.
+pub mod app;
@@ -0,0 +1,283 @@
+//! Console app to export the whole index in a portable format.
+//!
+//! Run with:
+//!
+//! ```text
+//! cargo run --bin export_index -- --output <OUTPUT> [--format <FORMAT>]
+//! ```
+//!
+//! For example:
+//!
+//! ```text
+//! cargo run --bin export_index -- --output ./storage/export
+//! ```
+//!
+//! It exports the categories, tags, users (without passwords) and torrents,
+//! including the torrent files, from the database in the configuration. The
+//! output directory is created if it does not exist. The export can be
+//! imported with the [`import_index`](super::import) command, using the same
+//! or a different database backend.
+//!
+//! Refer to the [`format`](super::format) module for the export format.
+use std::collections::{HashMap, HashSet};
+use std::str::FromStr;
+use std::sync::Arc;
+
+use anyhow::Context;
+use bittorrent_primitives::info_hash::InfoHash;
+use camino::{Utf8Path, Utf8PathBuf};
+use clap::Parser;
+use text_colorizer::Colorize;
+
+use super::format::{self, IndexFormat, Record, Writer};
+use crate::bootstrap::config::initialize_configuration;
+use crate::bootstrap::logging;
+use crate::databases::database::{self, Database};
+use crate::databases::encryption::Cipher;
+use crate::models::torrent::TorrentStatus;
+use crate::storage::torrent_file::{self, TorrentFileStore};
+use crate::utils::parse_torrent::encode_torrent;
+
+/// Number of users read from the database in each query.
+const USERS_PAGE_SIZE: u32 = 1000;
+
+#[derive(Parser, Debug)]
+#[clap(author, version, about, long_about = None)]
+struct Args {
+    /// Directory where the export is written.
+    #[arg(short, long)]
+    output: Utf8PathBuf,
+
+    /// Format of the index file.
+    #[arg(short, long, value_enum, default_value_t = IndexFormat::Ndjson)]
+    format: IndexFormat,
+}
+
+#[derive(Debug, Default)]
+struct ExportSummary {
+    categories: usize,
+    tags: usize,
+    users: usize,
+    torrents: usize,
+}
+
+/// # Errors
+///
+/// It returns an error if the export can't be written or there is a
+/// database error.
+///
+/// # Panics
+///
+/// Panics if it can't connect to the database.
+pub async fn run() -> anyhow::Result<()> {
+    let args = Args::parse();
+
+    let configuration = initialize_configuration();
+
+    let logging = configuration.settings.read().await.logging.clone();
+
+    logging::setup(&logging);
+
+    let database_settings = configuration.settings.read().await.database.clone();
+    let storage = configuration.settings.read().await.storage.clone().unwrap_or_default();
+
+    let cipher = database_settings
+        .encryption
+        .as_ref()
+        .map(|encryption| Cipher::from_config(encryption).map(Arc::new))
+        .transpose()
+        .context("invalid database encryption key")?;
+
+    let database = Arc::new(
+        database::connect_with_cipher(database_settings.connect_url.as_ref(), cipher)
+            .await
+            .expect("unable to connect to db"),
+    );
+
+    let torrent_file_store = torrent_file::from_config(&storage, database.clone());
+
+    println!("Exporting the index to {} ...", args.output.to_string().yellow());
+
+    std::fs::create_dir_all(args.output.join(format::TORRENTS_DIR)).context("failed to create the output directory")?;
+
+    let mut writer = Writer::create(&args.output, args.format).context("failed to create the index file")?;
+
+    let summary = export(
+        database.as_ref().as_ref(),
+        torrent_file_store.as_ref(),
+        &args.output,
+        &mut writer,
+    )
+    .await?;
+
+    writer.finish().context("failed to write the index file")?;
+
+    println!("Categories: {}", summary.categories.to_string().green());
+    println!("Tags: {}", summary.tags.to_string().green());
+    println!("Users: {}", summary.users.to_string().green());
+    println!("Torrents: {}", summary.torrents.to_string().green());
+
+    Ok(())
+}
+
+async fn export(
+    database: &dyn Database,
+    torrent_file_store: &dyn TorrentFileStore,
+    output: &Utf8Path,
+    writer: &mut Writer,
+) -> anyhow::Result<ExportSummary> {
+    let mut summary = ExportSummary::default();
+
+    writer.write(&Record::Header(format::Header {
+        format: format::FORMAT.to_string(),
+        version: format::VERSION,
+        exported_at: chrono::Utc::now().to_rfc3339(),
+    }))?;
+
+    // Categories
+
+    let categories = database.get_categories().await.map_err(db_error)?;
+
+    let category_names: HashMap<i64, String> = categories
+        .iter()
+        .map(|category| (category.id, category.name.clone()))
+        .collect();
+
+    for category in categories {
+        writer.write(&Record::Category(format::Category { name: category.name }))?;
+        summary.categories += 1;
+    }
+
+    // Tags
+
+    for tag in database.get_tags().await.map_err(db_error)? {
+        writer.write(&Record::Tag(format::Tag { name: tag.name }))?;
+        summary.tags += 1;
+    }
+
+    // Users
+
+    let mut offset = 0;
+
+    loop {
+        let users = database
+            .get_users_paginated(offset, USERS_PAGE_SIZE)
+            .await
+            .map_err(db_error)?;
+
+        if users.is_empty() {
+            break;
+        }
+
+        offset += users.len() as u64;
+
+        for user in users {
+            writer.write(&Record::User(format::User {
+                username: user.username,
+                email: Some(user.email).filter(|email| !email.is_empty()),
+                email_verified: user.email_verified,
+                administrator: user.administrator,
+            }))?;
+            summary.users += 1;
+        }
+    }
+
+    // Torrents
+
+    let mut torrents = database.get_all_torrents_compact().await.map_err(db_error)?;
+
+    torrents.sort_by_key(|torrent| torrent.torrent_id);
+
+    // Torrents in the trash bin are not exported
+    let trashed_torrent_ids: HashSet<i64> = database
+        .get_trashed_torrents()
+        .await
+        .map_err(db_error)?
+        .into_iter()
+        .map(|trashed_torrent| trashed_torrent.torrent_id)
+        .collect();
+
+    for torrent_compact in torrents {
+        if trashed_torrent_ids.contains(&torrent_compact.torrent_id) {
+            continue;
+        }
+
+        let record = export_torrent(
+            database,
+            torrent_file_store,
+            torrent_compact.torrent_id,
+            &category_names,
+            output,
+        )
+        .await
+        .with_context(|| format!("failed to export torrent {}", torrent_compact.info_hash))?;
+
+        writer.write(&Record::Torrent(record))?;
+        summary.torrents += 1;
+    }
+
+    Ok(summary)
+}
+
+async fn export_torrent(
+    database: &dyn Database,
+    torrent_file_store: &dyn TorrentFileStore,
+    torrent_id: i64,
+    category_names: &HashMap<i64, String>,
+    output: &Utf8Path,
+) -> anyhow::Result<format::Torrent> {
+    let listing = database.get_torrent_listing_from_id(torrent_id).await.map_err(db_error)?;
+    let mut torrent = database.get_torrent_from_id(torrent_id).await.map_err(db_error)?;
+
+    let info_hash = InfoHash::from_str(&listing.info_hash).context("invalid info-hash")?;
+
+    torrent_file::load_pieces(torrent_file_store, &info_hash, &mut torrent)
+        .await
+        .map_err(|e| anyhow::anyhow!("failed to load the pieces: {e:?}"))?;
+    let tags = database.get_tags_for_torrent_id(torrent_id).await.map_err(db_error)?;
+
+    let canonical_info_hash = torrent.canonical_info_hash();
+
+    let original_info_hashes = database
+        .get_torrent_canonical_info_hash_group(&canonical_info_hash)
+        .await
+        .map_err(db_error)?
+        .original_info_hashes
+        .iter()
+        .map(|info_hash| info_hash.to_hex_string())
+        .collect();
+
+    let file = format!("{}/{}.torrent", format::TORRENTS_DIR, torrent.canonical_info_hash_hex());
+
+    let bytes = encode_torrent(&torrent).map_err(|e| anyhow::anyhow!("{e}"))?;
+
+    std::fs::write(output.join(&file), bytes)?;
+
+    Ok(format::Torrent {
+        info_hash: torrent.canonical_info_hash_hex(),
+        original_info_hashes,
+        title: listing.title,
+        description: listing.description,
+        category: listing.category_id.and_then(|id| category_names.get(&id).cloned()),
+        tags: tags.into_iter().map(|tag| tag.name).collect(),
+        uploader: listing.uploader,
+        date_uploaded: listing.date_uploaded,
+        status: parse_status(&listing.status),
+        file,
+    })
+}
+
+fn parse_status(status: &str) -> TorrentStatus {
+    match status {
+        "pending" => TorrentStatus::Pending,
+        "rejected" => TorrentStatus::Rejected,
+        "archived" => TorrentStatus::Archived,
+        "delisted" => TorrentStatus::Delisted,
+        _ => TorrentStatus::Approved,
+    }
+}
+
+#[allow(clippy::needless_pass_by_value)]
+fn db_error(error: database::Error) -> anyhow::Error {
+    anyhow::anyhow!("database error: {error:?}")
+}
@@ -0,0 +1,272 @@
+//! Portable export format.
+//!
+//! An export is a directory with the index records and the torrent files:
+//!
+//! ```text
+//! export/
+//! ├── index.ndjson
+//! └── torrents/
+//!     ├── 5452869be36f9f3350ccee6b4544e7e76caaadab.torrent
+//!     └── ...
+//! ```
+//!
+//! The index file contains one JSON record per line (NDJSON). The same
+//! records can be exported as a JSON array in an `index.json` file instead.
+//! The first record is always the header, with the format version. The other
+//! records are sorted by type, so the records they depend on are imported
+//! first: categories, tags, users and torrents.
+//!
+//! ```text
+//! {"type":"header","format":"torrust-index","version":1,"exported_at":"2024-10-16T10:00:00+00:00"}
+//! {"type":"category","name":"software"}
+//! {"type":"tag","name":"linux"}
+//! {"type":"user","username":"indexadmin","email":"admin@example.com","email_verified":true,"administrator":true}
+//! {"type":"torrent","info_hash":"5452869be36f9f3350ccee6b4544e7e76caaadab","original_info_hashes":["5452869be36f9f3350ccee6b4544e7e76caaadab"],"title":"MandelbrotSet","description":"MandelbrotSet image","category":"software","tags":["linux"],"uploader":"indexadmin","date_uploaded":"2023-05-25 11:33:02","status":"approved","file":"torrents/5452869be36f9f3350ccee6b4544e7e76caaadab.torrent"}
+//! ```
+//!
+//! Secrets are not exported: neither password hashes nor tracker keys.
+use std::fs::File;
+use std::io::{BufRead, BufReader, Write};
+
+use camino::Utf8Path;
+use serde::{Deserialize, Serialize};
+
+use crate::models::torrent::TorrentStatus;
+
+/// Name of the format in the header.
+pub const FORMAT: &str = "torrust-index";
+
+/// Current version of the format. Importers reject newer versions.
+pub const VERSION: u32 = 1;
+
+pub const NDJSON_INDEX_FILE: &str = "index.ndjson";
+pub const JSON_INDEX_FILE: &str = "index.json";
+pub const TORRENTS_DIR: &str = "torrents";
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum Record {
+    Header(Header),
+    Category(Category),
+    Tag(Tag),
+    User(User),
+    Torrent(Torrent),
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Header {
+    pub format: String,
+    pub version: u32,
+    pub exported_at: String,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Category {
+    pub name: String,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Tag {
+    pub name: String,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct User {
+    pub username: String,
+    pub email: Option<String>,
+    pub email_verified: bool,
+    pub administrator: bool,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Torrent {
+    /// The canonical info-hash.
+    pub info_hash: String,
+    /// The info-hashes of the torrent files uploaded for this torrent.
+    pub original_info_hashes: Vec<String>,
+    pub title: String,
+    pub description: Option<String>,
+    pub category: Option<String>,
+    pub tags: Vec<String>,
+    /// Username of the uploader.
+    pub uploader: String,
+    pub date_uploaded: String,
+    pub status: TorrentStatus,
+    /// Path of the torrent file, relative to the export directory.
+    pub file: String,
+}
+
+/// Format of the index file.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, clap::ValueEnum)]
+pub enum IndexFormat {
+    /// One JSON record per line.
+    #[default]
+    Ndjson,
+    /// A JSON array of records.
+    Json,
+}
+
+impl IndexFormat {
+    #[must_use]
+    pub fn file_name(&self) -> &'static str {
+        match self {
+            IndexFormat::Ndjson => NDJSON_INDEX_FILE,
+            IndexFormat::Json => JSON_INDEX_FILE,
+        }
+    }
+}
+
+/// It writes the records to the index file.
+pub struct Writer {
+    file: std::io::BufWriter<File>,
+    format: IndexFormat,
+    records: usize,
+}
+
+impl Writer {
+    /// It creates the index file in the export directory.
+    ///
+    /// # Errors
+    ///
+    /// It returns an error if the index file already exists or can't be
+    /// created.
+    pub fn create(directory: &Utf8Path, format: IndexFormat) -> std::io::Result<Self> {
+        let file = File::options()
+            .write(true)
+            .create_new(true)
+            .open(directory.join(format.file_name()))?;
+
+        Ok(Self {
+            file: std::io::BufWriter::new(file),
+            format,
+            records: 0,
+        })
+    }
+
+    /// # Errors
+    ///
+    /// It returns an error if the record can't be written.
+    pub fn write(&mut self, record: &Record) -> std::io::Result<()> {
+        let json = serde_json::to_string(record)?;
+
+        match self.format {
+            IndexFormat::Ndjson => writeln!(self.file, "{json}")?,
+            IndexFormat::Json => {
+                let separator = if self.records == 0 { "[\n" } else { ",\n" };
+                write!(self.file, "{separator}{json}")?;
+            }
+        }
+
+        self.records += 1;
+
+        Ok(())
+    }
+
+    /// # Errors
+    ///
+    /// It returns an error if the index file can't be written.
+    pub fn finish(mut self) -> std::io::Result<()> {
+        if self.format == IndexFormat::Json {
+            let end = if self.records == 0 { "[]\n" } else { "\n]\n" };
+            write!(self.file, "{end}")?;
+        }
+
+        self.file.flush()
+    }
+}
+
+/// It reads the records from the index file in the export directory. The
+/// format is detected from the file name.
+///
+/// # Errors
+///
+/// It returns an error if there is no index file or the JSON index file is
+/// not valid.
+pub fn read(directory: &Utf8Path) -> anyhow::Result<Box<dyn Iterator<Item = anyhow::Result<Record>>>> {
+    let ndjson_path = directory.join(NDJSON_INDEX_FILE);
+
+    if ndjson_path.exists() {
+        let lines = BufReader::new(File::open(ndjson_path)?).lines();
+
+        return Ok(Box::new(
+            lines
+                .enumerate()
+                .filter(|(_, line)| !matches!(line, Ok(line) if line.trim().is_empty()))
+                .map(|(index, line)| {
+                    let record =
+                        serde_json::from_str(&line?).map_err(|e| anyhow::anyhow!("invalid record in line {}: {e}", index + 1))?;
+                    Ok(record)
+                }),
+        ));
+    }
+
+    let json_path = directory.join(JSON_INDEX_FILE);
+
+    if json_path.exists() {
+        let records: Vec<Record> = serde_json::from_reader(BufReader::new(File::open(json_path)?))?;
+
+        return Ok(Box::new(records.into_iter().map(Ok)));
+    }
+
+    anyhow::bail!("no `{NDJSON_INDEX_FILE}` or `{JSON_INDEX_FILE}` file in {directory}")
+}
+
+/// It checks the header is the first record and the version is supported.
+///
+/// # Errors
+///
+/// It returns an error if the record is not a supported header.
+pub fn check_header(record: Option<&Record>) -> anyhow::Result<&Header> {
+    match record {
+        Some(Record::Header(header)) if header.format != FORMAT => {
+            anyhow::bail!("unknown export format `{}`", header.format)
+        }
+        Some(Record::Header(header)) if header.version > VERSION => anyhow::bail!(
+            "unsupported export version {}, the latest supported version is {VERSION}",
+            header.version
+        ),
+        Some(Record::Header(header)) => Ok(header),
+        _ => anyhow::bail!("the first record must be the header"),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{check_header, Category, Header, Record, FORMAT, VERSION};
+
+    fn header(version: u32) -> Record {
+        Record::Header(Header {
+            format: FORMAT.to_string(),
+            version,
+            exported_at: "2024-10-16T10:00:00+00:00".to_string(),
+        })
+    }
+
+    #[test]
+    fn it_should_tag_records_with_their_type() {
+        let record = Record::Category(Category {
+            name: "software".to_string(),
+        });
+
+        let json = serde_json::to_string(&record).unwrap();
+
+        assert_eq!(json, r#"{"type":"category","name":"software"}"#);
+        assert_eq!(serde_json::from_str::<Record>(&json).unwrap(), record);
+    }
+
+    #[test]
+    fn it_should_accept_exports_with_a_supported_version() {
+        assert!(check_header(Some(&header(VERSION))).is_ok());
+        assert!(check_header(Some(&header(VERSION + 1))).is_err());
+    }
+
+    #[test]
+    fn it_should_require_the_header_as_the_first_record() {
+        let category = Record::Category(Category {
+            name: "software".to_string(),
+        });
+
+        assert!(check_header(Some(&category)).is_err());
+        assert!(check_header(None).is_err());
+    }
+}
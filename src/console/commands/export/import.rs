@@ -0,0 +1,337 @@
+//! Console app to import an index export.
+//!
+//! Run with:
+//!
+//! ```text
+//! cargo run --bin import_index -- --input <INPUT>
+//! ```
+//!
+//! For example:
+//!
+//! ```text
+//! cargo run --bin import_index -- --input ./storage/export
+//! ```
+//!
+//! It imports an export generated with the [`export_index`](super::app)
+//! command into the database in the configuration. The database can be
+//! empty or not:
+//!
+//! - Existing categories, tags and users (with the same name) are reused.
+//! - Torrents already in the index (with the same info-hash) are skipped.
+//!
+//! Passwords are not included in the export, so imported users get a random
+//! password. Administrators have to give users a new password, or users have
+//! to reset it, before they can log in again.
+use std::collections::HashMap;
+use std::sync::Arc;
+
+use anyhow::Context;
+use bittorrent_primitives::info_hash::InfoHash;
+use camino::{Utf8Path, Utf8PathBuf};
+use clap::Parser;
+use rand::distributions::Alphanumeric;
+use rand::Rng;
+use text_colorizer::Colorize;
+
+use super::format::{self, Record};
+use crate::bootstrap::config::initialize_configuration;
+use crate::bootstrap::logging;
+use crate::databases::database::{self, Database};
+use crate::databases::encryption::Cipher;
+use crate::models::torrent::Metadata;
+use crate::models::torrent_tag::TagId;
+use crate::models::user::UserId;
+use crate::services::authentication::hash_password;
+use crate::utils::parse_torrent::decode_and_validate_torrent_file;
+
+#[derive(Parser, Debug)]
+#[clap(author, version, about, long_about = None)]
+struct Args {
+    /// Directory with the export.
+    #[arg(short, long)]
+    input: Utf8PathBuf,
+}
+
+#[derive(Debug, Default)]
+struct ImportSummary {
+    categories: Counter,
+    tags: Counter,
+    users: Counter,
+    torrents: Counter,
+    failures: Vec<String>,
+}
+
+#[derive(Debug, Default)]
+struct Counter {
+    imported: usize,
+    existing: usize,
+}
+
+/// # Errors
+///
+/// It returns an error if the export can't be read or there is a database
+/// error.
+///
+/// # Panics
+///
+/// Panics if it can't connect to the database.
+pub async fn run() -> anyhow::Result<()> {
+    let args = Args::parse();
+
+    let configuration = initialize_configuration();
+
+    let logging = configuration.settings.read().await.logging.clone();
+
+    logging::setup(&logging);
+
+    let database_settings = configuration.settings.read().await.database.clone();
+
+    let cipher = database_settings
+        .encryption
+        .as_ref()
+        .map(|encryption| Cipher::from_config(encryption).map(Arc::new))
+        .transpose()
+        .context("invalid database encryption key")?;
+
+    let database = database::connect_with_cipher(database_settings.connect_url.as_ref(), cipher)
+        .await
+        .expect("unable to connect to db");
+
+    println!("Importing the index from {} ...", args.input.to_string().yellow());
+
+    let mut records = format::read(&args.input)?;
+
+    let first = records.next().transpose()?;
+    let header = format::check_header(first.as_ref())?;
+
+    println!("Export version {} from {}", header.version, header.exported_at);
+
+    let mut importer = Importer::new(database.as_ref(), &args.input)?;
+
+    for record in records {
+        importer.import(record?).await?;
+    }
+
+    print_summary(&importer.summary);
+
+    Ok(())
+}
+
+struct Importer<'a> {
+    database: &'a dyn Database,
+    input: &'a Utf8Path,
+    /// Password hash for all the imported users. The password is random and
+    /// it's discarded.
+    password_hash: String,
+    user_ids: HashMap<String, UserId>,
+    tag_ids: HashMap<String, TagId>,
+    summary: ImportSummary,
+}
+
+impl<'a> Importer<'a> {
+    fn new(database: &'a dyn Database, input: &'a Utf8Path) -> anyhow::Result<Self> {
+        let password: String = rand::thread_rng()
+            .sample_iter(&Alphanumeric)
+            .take(32)
+            .map(char::from)
+            .collect();
+
+        let password_hash = hash_password(&password).map_err(|e| anyhow::anyhow!("{e}"))?;
+
+        Ok(Self {
+            database,
+            input,
+            password_hash,
+            user_ids: HashMap::new(),
+            tag_ids: HashMap::new(),
+            summary: ImportSummary::default(),
+        })
+    }
+
+    /// It imports one record. Torrents that can't be imported are added to
+    /// the failures. Any other error aborts the import.
+    async fn import(&mut self, record: Record) -> anyhow::Result<()> {
+        match record {
+            Record::Header(_) => anyhow::bail!("unexpected header record"),
+            Record::Category(category) => self.import_category(&category.name).await,
+            Record::Tag(tag) => self.import_tag(&tag.name).await,
+            Record::User(user) => self
+                .import_user(&user)
+                .await
+                .with_context(|| format!("failed to import user {}", user.username)),
+            Record::Torrent(torrent) => {
+                if let Err(error) = self.import_torrent(&torrent).await {
+                    self.summary.failures.push(format!("{}: {error}", torrent.info_hash));
+                }
+                Ok(())
+            }
+        }
+    }
+
+    async fn import_category(&mut self, name: &str) -> anyhow::Result<()> {
+        match self.database.get_category_from_name(name).await {
+            Ok(_) => self.summary.categories.existing += 1,
+            Err(database::Error::CategoryNotFound) => {
+                self.database.insert_category_and_get_id(name).await.map_err(db_error)?;
+                self.summary.categories.imported += 1;
+            }
+            Err(error) => return Err(db_error(error)),
+        }
+
+        Ok(())
+    }
+
+    async fn import_tag(&mut self, name: &str) -> anyhow::Result<()> {
+        let tag_id = match self.database.get_tag_from_name(name).await {
+            Ok(tag) => {
+                self.summary.tags.existing += 1;
+                tag.tag_id
+            }
+            Err(database::Error::TagNotFound) => {
+                let tag_id = self.database.insert_tag_and_get_id(name).await.map_err(db_error)?;
+                self.summary.tags.imported += 1;
+                tag_id
+            }
+            Err(error) => return Err(db_error(error)),
+        };
+
+        self.tag_ids.insert(name.to_string(), tag_id);
+
+        Ok(())
+    }
+
+    async fn import_user(&mut self, user: &format::User) -> anyhow::Result<()> {
+        let user_id = match self.database.get_user_profile_from_username(&user.username).await {
+            Ok(profile) => {
+                self.summary.users.existing += 1;
+                profile.user_id
+            }
+            Err(database::Error::UserNotFound) => {
+                let user_id = self
+                    .database
+                    .insert_user_and_get_id(&user.username, user.email.as_deref().unwrap_or_default(), &self.password_hash)
+                    .await
+                    .map_err(db_error)?;
+
+                if user.email_verified {
+                    self.database.verify_email(user_id).await.map_err(db_error)?;
+                }
+
+                if user.administrator {
+                    self.database.grant_admin_role(user_id).await.map_err(db_error)?;
+                }
+
+                self.summary.users.imported += 1;
+                user_id
+            }
+            Err(error) => return Err(db_error(error)),
+        };
+
+        self.user_ids.insert(user.username.clone(), user_id);
+
+        Ok(())
+    }
+
+    async fn import_torrent(&mut self, record: &format::Torrent) -> anyhow::Result<()> {
+        let Some(uploader_id) = self.user_ids.get(&record.uploader).copied() else {
+            anyhow::bail!("unknown uploader `{}`", record.uploader);
+        };
+
+        let category_id = match &record.category {
+            Some(category) => self.database.get_category_from_name(category).await.map_err(db_error)?.id,
+            None => anyhow::bail!("missing category"),
+        };
+
+        let tag_ids: Vec<TagId> = record.tags.iter().filter_map(|tag| self.tag_ids.get(tag).copied()).collect();
+
+        let bytes = std::fs::read(self.input.join(&record.file)).with_context(|| format!("can't read {}", record.file))?;
+
+        let (torrent, _) = decode_and_validate_torrent_file(&bytes).map_err(|e| anyhow::anyhow!("{e}"))?;
+
+        if torrent.canonical_info_hash_hex() != record.info_hash {
+            anyhow::bail!("the torrent file info-hash is {}", torrent.canonical_info_hash_hex());
+        }
+
+        let canonical_info_hash = torrent.canonical_info_hash();
+
+        let mut original_info_hashes: Vec<InfoHash> = vec![];
+        for original_info_hash in &record.original_info_hashes {
+            original_info_hashes.push(
+                original_info_hash
+                    .parse()
+                    .map_err(|_| anyhow::anyhow!("invalid original info-hash {original_info_hash}"))?,
+            );
+        }
+        if original_info_hashes.is_empty() {
+            original_info_hashes.push(canonical_info_hash);
+        }
+
+        let metadata = Metadata::new(
+            &record.title,
+            record.description.as_deref().unwrap_or_default(),
+            category_id,
+            &tag_ids,
+        )
+        .map_err(|e| anyhow::anyhow!("{e}"))?;
+
+        let torrent_id = match self
+            .database
+            .insert_torrent_and_get_id(&original_info_hashes[0], &torrent, uploader_id, &metadata, &record.status)
+            .await
+        {
+            Ok(torrent_id) => torrent_id,
+            Err(database::Error::TorrentAlreadyExists) => {
+                self.summary.torrents.existing += 1;
+                return Ok(());
+            }
+            Err(error) => return Err(db_error(error)),
+        };
+
+        for original_info_hash in &original_info_hashes[1..] {
+            self.database
+                .add_info_hash_to_canonical_info_hash_group(original_info_hash, &canonical_info_hash)
+                .await
+                .map_err(db_error)?;
+        }
+
+        self.database
+            .update_torrent_date_uploaded(torrent_id, &record.date_uploaded)
+            .await
+            .map_err(db_error)?;
+
+        self.summary.torrents.imported += 1;
+
+        Ok(())
+    }
+}
+
+fn print_summary(summary: &ImportSummary) {
+    for (label, counter) in [
+        ("Categories", &summary.categories),
+        ("Tags", &summary.tags),
+        ("Users", &summary.users),
+        ("Torrents", &summary.torrents),
+    ] {
+        println!(
+            "{label}: {} imported, {} already in the index",
+            counter.imported.to_string().green(),
+            counter.existing
+        );
+    }
+
+    if !summary.failures.is_empty() {
+        println!(
+            "{} {} torrents could not be imported:",
+            "Failed".red(),
+            summary.failures.len()
+        );
+        for failure in &summary.failures {
+            println!("  {failure}");
+        }
+    }
+}
+
+#[allow(clippy::needless_pass_by_value)]
+fn db_error(error: database::Error) -> anyhow::Error {
+    anyhow::anyhow!("database error: {error:?}")
+}
@@ -0,0 +1,7 @@
+//! Commands to export the whole index in a portable format and import it
+//! again, for example, to migrate from `SQLite` to `MySQL`.
+//!
+//! Refer to the [`format`] module for a description of the export format.
+pub mod app;
+pub mod format;
+pub mod import;
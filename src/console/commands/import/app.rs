@@ -0,0 +1,300 @@
+//! Console app to import torrent files from a directory.
+//!
+//! Run with:
+//!
+//! ```text
+//! cargo run --bin import_torrents -- \
+//!   --directory <DIRECTORY> \
+//!   --uploader <UPLOADER> \
+//!   [--manifest <MANIFEST>] \
+//!   [--mapping <MAPPING>]
+//! ```
+//!
+//! For example:
+//!
+//! ```text
+//! cargo run --bin import_torrents -- \
+//!   --directory ./storage/import \
+//!   --manifest ./storage/import.csv \
+//!   --mapping ./storage/mapping.toml \
+//!   --uploader admin
+//! ```
+//!
+//! The torrents are uploaded by the `uploader` user, which must be an
+//! administrator. The command uses the same configuration as the Index, so it
+//! imports the torrents directly into the Index database. It's not necessary
+//! to run the Index API.
+//!
+//! Refer to the [`bulk_import`](crate::services::bulk_import) service for the
+//! format of the manifest and mapping files.
+//!
+//! After running it you will see a summary like this:
+//!
+//! ```text
+//! Torrent files found: 4
+//! Imported: 2
+//! Duplicates: 1
+//!   debian.torrent: A torrent with the same original infohash already exists in our database.
+//! Failures: 1
+//!   fedora.torrent: Selected category does not exist.
+//! ```
+use std::sync::Arc;
+
+use anyhow::Context;
+use camino::Utf8PathBuf;
+use clap::Parser;
+use text_colorizer::Colorize;
+
+use crate::bootstrap::config::initialize_configuration;
+use crate::bootstrap::logging;
+use crate::cache::query::QueryCache;
+use crate::databases::database;
+use crate::services::antispam::{self, DbBannedPatternRepository};
+use crate::services::audit::{self, DbAuditLogRepository};
+use crate::services::authorization::{self, CasbinConfiguration, CasbinEnforcer};
+use crate::services::bookmark::DbBookmarkRepository;
+use crate::services::bulk_import::{self, ImportRequest, ImportSummary, SkippedTorrent};
+use crate::services::category::{DbCategoryModeratorRepository, DbCategoryRepository};
+use crate::services::category_field::DbCategoryFieldRepository;
+use crate::services::domain_events::DbDomainEventRepository;
+use crate::services::enrichment::DbTorrentMetadataRepository;
+use crate::services::live_events::Broadcaster;
+use crate::services::ratio::{self, DbTransferStatsRepository};
+use crate::services::reputation::{self, DbReputationRepository};
+use crate::services::search;
+use crate::services::tag::DbTagRepository;
+use crate::services::torrent::deduplication::{self, DbTorrentDuplicateRepository};
+use crate::services::torrent::file_group::DbTorrentFileGroupRepository;
+use crate::services::torrent::preview::DbTorrentPreviewRepository;
+use crate::services::torrent::quota::{self, DbUploadQuotaRepository};
+use crate::services::torrent::trash::DbTorrentTrashRepository;
+use crate::services::torrent::{
+    self, DbCanonicalInfoHashGroupRepository, DbMagnetUploadRepository, DbTorrentAnnounceUrlRepository, DbTorrentFileRepository,
+    DbTorrentInfoRepository, DbTorrentListingGenerator, DbTorrentRepository, DbTorrentTagRepository,
+};
+use crate::services::user::{DbUserProfileRepository, DbUserRepository, Repository};
+use crate::storage::torrent_file;
+use crate::tracker;
+use crate::tracker::statistics_importer::StatisticsImporter;
+
+#[derive(Parser, Debug)]
+#[clap(author, version, about, long_about = None)]
+struct Args {
+    /// Directory containing the `.torrent` files.
+    #[arg(short, long)]
+    directory: Utf8PathBuf,
+
+    /// CSV file with the title, description, category and tags of the torrents.
+    #[arg(long)]
+    manifest: Option<Utf8PathBuf>,
+
+    /// TOML file with the categories and tags mapping.
+    #[arg(long)]
+    mapping: Option<Utf8PathBuf>,
+
+    /// Username of the administrator uploading the torrents.
+    #[arg(short, long)]
+    uploader: String,
+}
+
+/// # Errors
+///
+/// It returns an error if the uploader does not exist or the import can't be
+/// started.
+///
+/// # Panics
+///
+/// Panics if it can't connect to the database.
+pub async fn run() -> anyhow::Result<()> {
+    let args = Args::parse();
+
+    let configuration = initialize_configuration();
+
+    let logging = configuration.settings.read().await.logging.clone();
+
+    logging::setup(&logging);
+
+    let configuration = Arc::new(configuration);
+
+    let settings = configuration.settings.read().await;
+    let database_connect_url = settings.database.connect_url.clone().to_string();
+    let unstable = settings.unstable.clone();
+    let cache = settings.cache.clone();
+    let storage = settings.storage.clone().unwrap_or_default();
+    drop(settings);
+
+    let database = Arc::new(
+        database::connect(&database_connect_url)
+            .await
+            .expect("unable to connect to db"),
+    );
+
+    // Use the same cache as the running index, so imported categories and
+    // tags invalidate the results cached in Redis.
+    let query_cache = Arc::new(QueryCache::new(cache.as_ref()));
+
+    let torrent_file_store = torrent_file::from_config(&storage, database.clone());
+
+    let category_repository = Arc::new(DbCategoryRepository::new(database.clone(), query_cache.clone()));
+    let tag_repository = Arc::new(DbTagRepository::new(database.clone(), query_cache.clone()));
+    let user_repository: Arc<Box<dyn Repository>> = Arc::new(Box::new(DbUserRepository::new(database.clone())));
+    let user_profile_repository = Arc::new(DbUserProfileRepository::new(database.clone()));
+
+    let casbin_enforcer = Arc::new(
+        if let Some(casbin) = unstable
+            .as_ref()
+            .and_then(|u| u.auth.as_ref())
+            .and_then(|auth| auth.casbin.as_ref())
+        {
+            CasbinEnforcer::with_configuration(CasbinConfiguration::new(&casbin.model, &casbin.policy)).await
+        } else {
+            CasbinEnforcer::with_default_configuration().await
+        },
+    );
+
+    let authorization_service = Arc::new(authorization::Service::new(
+        user_repository.clone(),
+        Arc::new(DbCategoryModeratorRepository::new(database.clone())),
+        casbin_enforcer,
+    ));
+    let tracker_service = Arc::new(tracker::service::Service::new(configuration.clone(), database.clone()).await);
+    let tracker_statistics_importer =
+        Arc::new(StatisticsImporter::new(configuration.clone(), tracker_service.clone(), database.clone()).await);
+    let search_service = Arc::new(search::Service::new(configuration.clone(), database.clone()).await);
+    let upload_quota_service = Arc::new(quota::Service::new(
+        configuration.clone(),
+        Arc::new(DbUploadQuotaRepository::new(database.clone())),
+        authorization_service.clone(),
+    ));
+
+    let audit_service = Arc::new(audit::Service::new(
+        Arc::new(DbAuditLogRepository::new(database.clone())),
+        authorization_service.clone(),
+    ));
+    let antispam_service = Arc::new(antispam::Service::new(
+        configuration.clone(),
+        Arc::new(DbBannedPatternRepository::new(database.clone())),
+        authorization_service.clone(),
+        audit_service.clone(),
+    ));
+    let reputation_service = Arc::new(reputation::Service::new(
+        configuration.clone(),
+        Arc::new(DbReputationRepository::new(database.clone())),
+        authorization_service.clone(),
+        audit_service.clone(),
+    ));
+    let ratio_service = Arc::new(ratio::Service::new(
+        configuration.clone(),
+        tracker_service.clone(),
+        Arc::new(DbTransferStatsRepository::new(database.clone())),
+    ));
+
+    let torrent_index = Arc::new(torrent::Index::new(
+        configuration.clone(),
+        tracker_statistics_importer,
+        tracker_service,
+        user_repository,
+        category_repository.clone(),
+        Arc::new(DbTorrentRepository::new(database.clone(), torrent_file_store.clone())),
+        Arc::new(DbCanonicalInfoHashGroupRepository::new(database.clone())),
+        Arc::new(DbTorrentInfoRepository::new(database.clone())),
+        Arc::new(DbTorrentFileRepository::new(database.clone())),
+        Arc::new(DbTorrentAnnounceUrlRepository::new(database.clone())),
+        Arc::new(DbTorrentTagRepository::new(database.clone())),
+        Arc::new(DbTorrentListingGenerator::new(database.clone(), query_cache)),
+        authorization_service.clone(),
+        search_service,
+        Arc::new(DbDomainEventRepository::new(database.clone())),
+        Arc::new(DbBookmarkRepository::new(database.clone())),
+        Arc::new(DbMagnetUploadRepository::new(database.clone())),
+        Arc::new(DbTorrentPreviewRepository::new(database.clone())),
+        Arc::new(DbTorrentFileGroupRepository::new(database.clone())),
+        upload_quota_service,
+        Arc::new(Broadcaster::default()),
+        audit_service,
+        Arc::new(deduplication::Service::new(
+            configuration.clone(),
+            Arc::new(DbTorrentDuplicateRepository::new(database.clone(), torrent_file_store)),
+            authorization_service.clone(),
+        )),
+        Arc::new(DbTorrentTrashRepository::new(database.clone())),
+        antispam_service,
+        Arc::new(DbTorrentMetadataRepository::new(database.clone())),
+        Arc::new(DbCategoryFieldRepository::new(database.clone())),
+        reputation_service,
+        ratio_service,
+    ));
+
+    let bulk_import_service =
+        bulk_import::Service::new(torrent_index, category_repository, tag_repository, authorization_service);
+
+    let uploader = user_profile_repository
+        .get_user_profile_from_username(&args.uploader)
+        .await
+        .map_err(|_| anyhow::anyhow!("user `{}` not found", args.uploader))?;
+
+    println!(
+        "Importing torrents from {} as {} ...",
+        args.directory.to_string().yellow(),
+        uploader.username.yellow()
+    );
+
+    let request = ImportRequest {
+        directory: args.directory,
+        manifest: args.manifest,
+        mapping: args.mapping,
+    };
+
+    let summary = bulk_import_service
+        .import(&request, Some(uploader.user_id))
+        .await
+        .map_err(|error| anyhow::anyhow!("{error}"))
+        .context("failed to import torrents")?;
+
+    print_summary(&summary);
+
+    Ok(())
+}
+
+fn print_summary(summary: &ImportSummary) {
+    println!("Torrent files found: {}", summary.total);
+    println!("Imported: {}", summary.imported.len().to_string().green());
+    print_skipped("Duplicates", &summary.duplicates);
+    print_skipped("Failures", &summary.failures);
+
+    let mapping = &summary.mapping;
+
+    if !mapping.unmapped_categories.is_empty() {
+        println!("Unmapped categories:");
+        for (category, torrents) in &mapping.unmapped_categories {
+            println!("  {category}: {torrents} torrents");
+        }
+    }
+
+    if !mapping.unmapped_tags.is_empty() {
+        println!("Unmapped tags:");
+        for (tag, torrents) in &mapping.unmapped_tags {
+            println!("  {tag}: {torrents} torrents");
+        }
+    }
+
+    for category in &mapping.missing_categories {
+        println!("{} category `{category}` does not exist", "Missing".red());
+    }
+
+    for tag in &mapping.missing_tags {
+        println!("{} tag `{tag}` does not exist", "Missing".red());
+    }
+}
+
+fn print_skipped(label: &str, skipped: &[SkippedTorrent]) {
+    if skipped.is_empty() {
+        println!("{label}: 0");
+        return;
+    }
+
+    println!("{label}: {}", skipped.len().to_string().yellow());
+    for torrent in skipped {
+        println!("  {}: {}", torrent.file, torrent.reason);
+    }
+}
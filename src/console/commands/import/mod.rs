@@ -0,0 +1,2 @@
+//! Command to import torrent files from a directory.
+pub mod app;
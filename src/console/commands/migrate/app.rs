@@ -0,0 +1,104 @@
+//! Console app to manage the database migrations.
+//!
+//! Run with:
+//!
+//! ```text
+//! cargo run --bin migrate [up|status|redo]
+//! ```
+//!
+//! - `up` (default): it applies the pending migrations.
+//! - `status`: it lists the migrations and whether they were applied.
+//! - `redo`: it reverts the last applied migration and applies it again.
+//!   Only migrations with a down script can be redone: the ones from version
+//!   `20241016100000` on have a `.down.sql` script next to their `.up.sql`
+//!   script, the older ones can't be reverted.
+//!
+//! The same migrations are shipped for every database driver (`SQLite` and
+//! `MySQL`), in the `migrations` directory. There is no `PostgreSQL` driver
+//! yet, so there are no `PostgreSQL` migrations either.
+//!
+//! The index refuses to start when there are pending migrations, unless the
+//! `database.auto_migrate` option is enabled.
+use std::env;
+
+use anyhow::bail;
+use text_colorizer::Colorize;
+
+use crate::bootstrap::config::initialize_configuration;
+use crate::bootstrap::logging;
+use crate::databases::database::{self, Database};
+
+/// # Errors
+///
+/// It returns an error if the subcommand is unknown or there is a database
+/// error.
+///
+/// # Panics
+///
+/// Panics if it can't connect to the database.
+pub async fn run() -> anyhow::Result<()> {
+    let subcommand = env::args().nth(1).unwrap_or_else(|| "up".to_string());
+
+    let configuration = initialize_configuration();
+
+    let logging = configuration.settings.read().await.logging.clone();
+
+    logging::setup(&logging);
+
+    let database_connect_url = configuration.settings.read().await.database.connect_url.to_string();
+
+    let database = database::connect_without_migrating(&database_connect_url, None)
+        .await
+        .expect("unable to connect to db");
+
+    match subcommand.as_str() {
+        "up" => up(database.as_ref()).await,
+        "status" => status(database.as_ref()).await,
+        "redo" => redo(database.as_ref()).await,
+        _ => bail!("unknown subcommand `{subcommand}`, expected `up`, `status` or `redo`"),
+    }
+}
+
+async fn up(database: &dyn Database) -> anyhow::Result<()> {
+    let applied = database.run_migrations().await.map_err(db_error)?;
+
+    println!("Applied migrations: {}", applied.to_string().green());
+
+    Ok(())
+}
+
+async fn status(database: &dyn Database) -> anyhow::Result<()> {
+    let migrations = database.get_migrations().await.map_err(db_error)?;
+
+    for migration in &migrations {
+        let state = if migration.applied {
+            "applied".green()
+        } else {
+            "pending".yellow()
+        };
+
+        println!("{} [{state}] {}", migration.version, migration.description);
+    }
+
+    let pending = migrations.iter().filter(|migration| !migration.applied).count();
+
+    println!("Pending migrations: {pending}");
+
+    Ok(())
+}
+
+async fn redo(database: &dyn Database) -> anyhow::Result<()> {
+    match database.redo_last_migration().await {
+        Ok(version) => {
+            println!("Migration {} redone.", version.to_string().green());
+            Ok(())
+        }
+        Err(database::Error::MigrationNotFound) => bail!("no migration has been applied yet"),
+        Err(database::Error::MigrationNotReversible) => bail!("the last applied migration can't be reverted"),
+        Err(error) => Err(db_error(error)),
+    }
+}
+
+fn db_error(error: database::Error) -> anyhow::Error {
+    anyhow::anyhow!("database error: {error:?}")
+}
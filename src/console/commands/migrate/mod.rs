@@ -0,0 +1,2 @@
+//! Command to manage the database migrations.
+pub mod app;
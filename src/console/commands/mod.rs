@@ -1,3 +1,10 @@
 //! Console commands that can be run manually.
+pub mod backup;
+pub mod encrypt_database;
+pub mod export;
+pub mod import;
+pub mod migrate;
+pub mod move_torrent_files;
 pub mod seeder;
 pub mod tracker_statistics_importer;
+pub mod upgrade_config;
@@ -0,0 +1,171 @@
+//! Console app to move the torrent files between storage backends.
+//!
+//! Run with:
+//!
+//! ```text
+//! cargo run --bin move_torrent_files -- <FROM> <TO>
+//! ```
+//!
+//! For example, to move the pieces of the existing torrents out of the
+//! database:
+//!
+//! ```text
+//! cargo run --bin move_torrent_files -- database filesystem
+//! ```
+//!
+//! The backends are `database`, `filesystem` and `s3`, with the options in
+//! the `[storage]` section of the configuration. The pieces of each torrent
+//! are copied to the new backend before they are removed from the old one,
+//! so the command can be stopped and run again at any time.
+//!
+//! Set `storage.torrent_files.backend` to the new backend once the torrents
+//! are moved. Torrents whose pieces are still in the database are read from
+//! it with any backend, so the index can run while they are moved out of it.
+use std::str::FromStr;
+use std::sync::Arc;
+
+use anyhow::{bail, Context};
+use bittorrent_primitives::info_hash::InfoHash;
+use clap::Parser;
+use text_colorizer::Colorize;
+use tracing::error;
+
+use crate::bootstrap::config::initialize_configuration;
+use crate::bootstrap::logging;
+use crate::config::TorrentFilesBackend;
+use crate::databases::database::{self, Database};
+use crate::databases::encryption::Cipher;
+use crate::storage::torrent_file::{self, TorrentFileStore};
+
+#[derive(Parser, Debug)]
+#[clap(author, version, about, long_about = None)]
+struct Args {
+    /// The backend where the torrent files are now: `database`,
+    /// `filesystem` or `s3`.
+    from: TorrentFilesBackend,
+
+    /// The backend where the torrent files are moved to: `database`,
+    /// `filesystem` or `s3`.
+    to: TorrentFilesBackend,
+}
+
+#[derive(Debug, Default)]
+struct MoveSummary {
+    moved: usize,
+    skipped: usize,
+    failed: usize,
+}
+
+/// # Errors
+///
+/// It returns an error if the backends are the same, the `s3` backend is
+/// not configured or there is a database error.
+///
+/// # Panics
+///
+/// Panics if it can't connect to the database.
+pub async fn run() -> anyhow::Result<()> {
+    let args = Args::parse();
+
+    if args.from == args.to {
+        bail!("the torrent files are already in the `{}` backend", args.to);
+    }
+
+    let configuration = initialize_configuration();
+
+    let logging = configuration.settings.read().await.logging.clone();
+
+    logging::setup(&logging);
+
+    let database_settings = configuration.settings.read().await.database.clone();
+    let storage = configuration.settings.read().await.storage.clone().unwrap_or_default();
+
+    if (args.from == TorrentFilesBackend::S3 || args.to == TorrentFilesBackend::S3) && storage.s3.is_none() {
+        bail!("the `s3` backend requires the `[storage.s3]` configuration");
+    }
+
+    let cipher = database_settings
+        .encryption
+        .as_ref()
+        .map(|encryption| Cipher::from_config(encryption).map(Arc::new))
+        .transpose()
+        .context("invalid database encryption key")?;
+
+    let database = Arc::new(
+        database::connect_with_cipher(database_settings.connect_url.as_ref(), cipher)
+            .await
+            .expect("unable to connect to db"),
+    );
+
+    let from = torrent_file::build(args.from, &storage, database.clone());
+    let to = torrent_file::build(args.to, &storage, database.clone());
+
+    println!(
+        "Moving the torrent files from {} to {} ...",
+        args.from.to_string().yellow(),
+        args.to.to_string().yellow()
+    );
+
+    let summary = move_torrent_files(database.as_ref().as_ref(), from.as_ref(), to.as_ref()).await?;
+
+    println!("Moved: {}", summary.moved.to_string().green());
+    println!("Skipped: {}", summary.skipped.to_string().yellow());
+    println!("Failed: {}", summary.failed.to_string().red());
+
+    if summary.failed > 0 {
+        bail!("{} torrent files could not be moved, run the command again", summary.failed);
+    }
+
+    Ok(())
+}
+
+/// It moves the pieces of all the torrents. Torrents without pieces in the
+/// old backend are skipped: they are BEP 30 torrents or they were already
+/// moved.
+async fn move_torrent_files(
+    database: &dyn Database,
+    from: &dyn TorrentFileStore,
+    to: &dyn TorrentFileStore,
+) -> anyhow::Result<MoveSummary> {
+    let mut summary = MoveSummary::default();
+
+    let torrents = database
+        .get_all_torrents_compact()
+        .await
+        .map_err(|e| anyhow::anyhow!("database error: {e:?}"))?;
+
+    for torrent in torrents {
+        let Ok(info_hash) = InfoHash::from_str(&torrent.info_hash) else {
+            error!(target: "torrent_files", "Invalid info-hash for torrent #{}: {}", torrent.torrent_id, torrent.info_hash);
+            summary.failed += 1;
+            continue;
+        };
+
+        match move_one(&info_hash, from, to).await {
+            Ok(true) => summary.moved += 1,
+            Ok(false) => summary.skipped += 1,
+            Err(e) => {
+                error!(target: "torrent_files", "Failed to move the pieces of torrent {info_hash}: {e:?}");
+                summary.failed += 1;
+            }
+        }
+    }
+
+    Ok(summary)
+}
+
+async fn move_one(
+    info_hash: &InfoHash,
+    from: &dyn TorrentFileStore,
+    to: &dyn TorrentFileStore,
+) -> Result<bool, torrent_file::Error> {
+    let Some(pieces) = from.get(info_hash).await? else {
+        return Ok(false);
+    };
+
+    to.put(info_hash, pieces).await?;
+
+    from.delete(info_hash).await?;
+
+    Ok(true)
+}
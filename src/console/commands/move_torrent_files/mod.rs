@@ -0,0 +1,2 @@
+//! Command to move the torrent files between storage backends.
+pub mod app;
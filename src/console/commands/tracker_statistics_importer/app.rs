@@ -90,9 +90,9 @@ pub async fn import() {
 
     let configuration = initialize_configuration();
 
-    let threshold = configuration.settings.read().await.logging.threshold.clone();
+    let logging = configuration.settings.read().await.logging.clone();
 
-    logging::setup(&threshold);
+    logging::setup(&logging);
 
     let cfg = Arc::new(configuration);
 
@@ -0,0 +1,59 @@
+//! Console app to upgrade a configuration file to the latest schema version.
+//!
+//! Run with:
+//!
+//! ```text
+//! cargo run --bin upgrade_config [CONFIG_TOML_PATH]
+//! ```
+//!
+//! When the path is not given, it upgrades the file in the
+//! `TORRUST_INDEX_CONFIG_TOML_PATH` environment variable, or the default
+//! configuration file.
+//!
+//! The index upgrades older configurations in memory every time it starts,
+//! so upgrading the file is optional. The original file is kept with the
+//! `.bak` extension, because comments are not kept in the upgraded file.
+//!
+//! Refer to the [`migration`](crate::config::migration) module for the
+//! supported upgrades.
+use std::{env, fs};
+
+use anyhow::Context;
+use text_colorizer::Colorize;
+
+use crate::bootstrap::config::DEFAULT_PATH_CONFIG;
+use crate::config::{migration, ENV_VAR_CONFIG_TOML_PATH};
+
+/// # Errors
+///
+/// It returns an error if the file can't be read or written.
+pub fn run() -> anyhow::Result<()> {
+    let config_toml_path = env::args()
+        .nth(1)
+        .or_else(|| env::var(ENV_VAR_CONFIG_TOML_PATH).ok())
+        .unwrap_or_else(|| DEFAULT_PATH_CONFIG.to_string());
+
+    let config_toml =
+        fs::read_to_string(&config_toml_path).with_context(|| format!("unable to read the file `{config_toml_path}`"))?;
+
+    let Some(upgraded) = migration::upgrade(&config_toml) else {
+        println!("Nothing to upgrade in `{config_toml_path}`.");
+        return Ok(());
+    };
+
+    let backup_path = format!("{config_toml_path}.bak");
+
+    fs::write(&backup_path, &config_toml).with_context(|| format!("unable to write the file `{backup_path}`"))?;
+
+    fs::write(&config_toml_path, &upgraded.config_toml)
+        .with_context(|| format!("unable to write the file `{config_toml_path}`"))?;
+
+    println!(
+        "Configuration `{config_toml_path}` upgraded from schema version {} to {}. The original file was saved in `{}`.",
+        upgraded.from_version,
+        upgraded.to_version.green(),
+        backup_path
+    );
+
+    Ok(())
+}
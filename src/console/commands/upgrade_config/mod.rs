@@ -0,0 +1,2 @@
+//! Command to upgrade the configuration file to the latest schema version.
+pub mod app;
@@ -0,0 +1,98 @@
+//! Cronjob to post new-torrent announcements.
+//!
+//! It reads the domain events from the outbox and announces the uploaded
+//! torrents in the configured channels. The last processed event is saved as
+//! the `announcer` consumer cursor, so announcements are resumed after a
+//! restart.
+//!
+//! Events are processed at least once. If the application stops after
+//! announcing a torrent but before saving the cursor, the torrent could be
+//! announced twice. On a graceful shutdown, the cursor is saved after the
+//! announcement in progress and the remaining events are left for the next
+//! run.
+use std::sync::Arc;
+use std::time::Duration;
+
+use tokio::task::JoinHandle;
+use tracing::{debug, error, info};
+
+use crate::announcer::Announcer;
+use crate::config::Announcer as AnnouncerConfig;
+use crate::console::shutdown::Shutdown;
+use crate::models::domain_event::{DbDomainEvent, DomainEvent};
+use crate::services::domain_events::DbDomainEventRepository;
+
+/// The outbox consumer name.
+const CONSUMER: &str = "announcer";
+
+/// Maximum number of events processed in one execution.
+const BATCH_SIZE: u32 = 50;
+
+#[must_use]
+pub fn start(
+    config: &AnnouncerConfig,
+    domain_event_repository: &Arc<DbDomainEventRepository>,
+    mut shutdown: Shutdown,
+) -> JoinHandle<()> {
+    let weak_domain_event_repository = Arc::downgrade(domain_event_repository);
+    let announcer = Announcer::new(config.channels.clone());
+    let poll_interval_seconds = config.poll_interval_seconds;
+
+    tokio::spawn(async move {
+        let mut execution_interval = tokio::time::interval(Duration::from_secs(poll_interval_seconds));
+
+        info!("Running announcer every {poll_interval_seconds} seconds ...");
+
+        loop {
+            tokio::select! {
+                _ = execution_interval.tick() => {}
+                () = shutdown.requested() => break,
+            }
+
+            let Some(domain_event_repository) = weak_domain_event_repository.upgrade() else {
+                break;
+            };
+
+            if let Err(e) = announce_pending_events(&announcer, &domain_event_repository, &shutdown).await {
+                error!(target: "announcer", "Failed to process domain events: {e:?}");
+            }
+
+            drop(domain_event_repository);
+        }
+
+        info!("Announcer stopped");
+    })
+}
+
+async fn announce_pending_events(
+    announcer: &Announcer,
+    domain_event_repository: &DbDomainEventRepository,
+    shutdown: &Shutdown,
+) -> Result<(), crate::databases::database::Error> {
+    let cursor = domain_event_repository.get_cursor(CONSUMER).await?;
+
+    let events = domain_event_repository.fetch_after(cursor, BATCH_SIZE).await?;
+
+    for event in events {
+        if shutdown.is_requested() {
+            break;
+        }
+
+        announce_event(announcer, &event).await;
+
+        domain_event_repository.save_cursor(CONSUMER, event.event_id).await?;
+    }
+
+    Ok(())
+}
+
+async fn announce_event(announcer: &Announcer, event: &DbDomainEvent) {
+    match serde_json::from_str::<DomainEvent>(&event.payload) {
+        Ok(DomainEvent::TorrentUploaded(torrent)) => {
+            debug!(target: "announcer", "Announcing torrent #{} ...", torrent.torrent_id);
+            announcer.announce(&torrent).await;
+        }
+        Ok(_) => {}
+        Err(e) => error!(target: "announcer", "Skipping invalid domain event #{}: {e}", event.event_id),
+    }
+}
@@ -0,0 +1,41 @@
+//! Cronjob to store the API usage counters.
+//!
+//! The rate limiter counts the requests made by logged-in users in memory.
+//! This job stores the counters every minute. Refer to the
+//! [`api_usage`](crate::services::api_usage) service.
+use std::sync::Arc;
+use std::time::Duration;
+
+use tokio::task::JoinHandle;
+use tracing::{debug, error, info};
+
+use crate::services::api_usage;
+
+/// Interval in seconds between two runs.
+const FLUSH_INTERVAL_SECONDS: u64 = 60;
+
+#[must_use]
+pub fn start(api_usage_service: &Arc<api_usage::Service>) -> JoinHandle<()> {
+    let weak_api_usage_service = Arc::downgrade(api_usage_service);
+
+    tokio::spawn(async move {
+        let mut execution_interval = tokio::time::interval(Duration::from_secs(FLUSH_INTERVAL_SECONDS));
+
+        info!("Storing API usage counters every {FLUSH_INTERVAL_SECONDS} seconds ...");
+
+        loop {
+            execution_interval.tick().await;
+
+            let Some(api_usage_service) = weak_api_usage_service.upgrade() else {
+                break;
+            };
+
+            match api_usage_service.flush().await {
+                Ok(stored) => debug!(target: "api_usage", "API usage counters stored: {stored}"),
+                Err(e) => error!(target: "api_usage", "Failed to store the API usage counters: {e:?}"),
+            }
+
+            drop(api_usage_service);
+        }
+    })
+}
@@ -0,0 +1,48 @@
+//! Cronjob to run the maintenance of the `SQLite` database.
+//!
+//! It compacts the database (`VACUUM`) and updates the statistics used by
+//! the query planner (`ANALYZE`) while the index is running. Refer to the
+//! `[database.sqlite]` section in the configuration.
+use std::sync::Arc;
+use std::time::Duration;
+
+use tokio::task::JoinHandle;
+use tokio::time::Instant;
+use tracing::{error, info};
+
+use crate::databases::database::Database;
+
+#[must_use]
+pub fn start(maintenance_interval_seconds: u64, database: &Arc<Box<dyn Database>>) -> JoinHandle<()> {
+    let weak_database = Arc::downgrade(database);
+
+    tokio::spawn(async move {
+        let period = Duration::from_secs(maintenance_interval_seconds);
+
+        // The first maintenance is not run when the index starts
+        let mut execution_interval = tokio::time::interval_at(Instant::now() + period, period);
+
+        info!("Running the database maintenance every {maintenance_interval_seconds} seconds ...");
+
+        loop {
+            execution_interval.tick().await;
+
+            let Some(database) = weak_database.upgrade() else {
+                break;
+            };
+
+            let start = Instant::now();
+
+            match database.run_maintenance().await {
+                Ok(()) => info!(
+                    target: "database_maintenance",
+                    "Database maintenance finished in {} ms",
+                    start.elapsed().as_millis()
+                ),
+                Err(e) => error!(target: "database_maintenance", "Failed to run the database maintenance: {e:?}"),
+            }
+
+            drop(database);
+        }
+    })
+}
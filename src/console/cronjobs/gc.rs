@@ -0,0 +1,41 @@
+//! Garbage collection cronjob.
+//!
+//! It deletes the data older than the periods in the `[retention]` section.
+//! Refer to the [`retention`](crate::services::retention) service.
+use std::sync::Arc;
+use std::time::Duration;
+
+use tokio::task::JoinHandle;
+use tracing::{error, info};
+
+use crate::services::retention;
+
+#[must_use]
+pub fn start(gc_interval_seconds: u64, retention_service: &Arc<retention::Service>) -> JoinHandle<()> {
+    let weak_retention_service = Arc::downgrade(retention_service);
+
+    tokio::spawn(async move {
+        let mut execution_interval = tokio::time::interval(Duration::from_secs(gc_interval_seconds));
+
+        info!("Collecting expired data every {gc_interval_seconds} seconds ...");
+
+        loop {
+            execution_interval.tick().await;
+
+            let Some(retention_service) = weak_retention_service.upgrade() else {
+                break;
+            };
+
+            match retention_service.collect_garbage().await {
+                Ok(purges) => info!(
+                    target: "retention",
+                    "Expired rows deleted: {}",
+                    purges.iter().map(|purge| purge.deleted).sum::<u64>()
+                ),
+                Err(e) => error!(target: "retention", "Failed to delete the expired data: {e:?}"),
+            }
+
+            drop(retention_service);
+        }
+    })
+}
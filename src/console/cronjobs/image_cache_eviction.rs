@@ -0,0 +1,39 @@
+//! Image cache eviction cronjob.
+//!
+//! It removes the expired user quotas of the image proxy and, when the images
+//! are stored in a local directory or in a bucket, the least recently used
+//! images until the cache is not bigger than its capacity. Refer to the
+//! [`ImageCacheService`](crate::cache::image::manager::ImageCacheService).
+use std::sync::Arc;
+use std::time::Duration;
+
+use tokio::task::JoinHandle;
+use tracing::{debug, error, info};
+
+use crate::cache::image::manager::ImageCacheService;
+
+#[must_use]
+pub fn start(eviction_interval_seconds: u64, image_cache_service: &Arc<ImageCacheService>) -> JoinHandle<()> {
+    let weak_image_cache_service = Arc::downgrade(image_cache_service);
+
+    tokio::spawn(async move {
+        let mut execution_interval = tokio::time::interval(Duration::from_secs(eviction_interval_seconds));
+
+        info!("Evicting images from the image cache every {eviction_interval_seconds} seconds ...");
+
+        loop {
+            execution_interval.tick().await;
+
+            let Some(image_cache_service) = weak_image_cache_service.upgrade() else {
+                break;
+            };
+
+            match image_cache_service.evict().await {
+                Ok(evicted) => debug!(target: "image cache", "Images evicted: {evicted}"),
+                Err(e) => error!(target: "image cache", "Failed to evict images: {e:?}"),
+            }
+
+            drop(image_cache_service);
+        }
+    })
+}
@@ -0,0 +1,42 @@
+//! Cronjob to apply the account inactivity policy.
+//!
+//! It warns inactive users and disables or deletes the accounts of the users
+//! who were warned and didn't log in. Refer to the
+//! [`inactivity`](crate::services::inactivity) service.
+use std::sync::Arc;
+use std::time::Duration;
+
+use tokio::task::JoinHandle;
+use tracing::{error, info};
+
+use crate::services::inactivity;
+
+#[must_use]
+pub fn start(check_interval_seconds: u64, inactivity_service: &Arc<inactivity::Service>) -> JoinHandle<()> {
+    let weak_inactivity_service = Arc::downgrade(inactivity_service);
+
+    tokio::spawn(async move {
+        let mut execution_interval = tokio::time::interval(Duration::from_secs(check_interval_seconds));
+
+        info!("Checking inactive accounts every {check_interval_seconds} seconds ...");
+
+        loop {
+            execution_interval.tick().await;
+
+            let Some(inactivity_service) = weak_inactivity_service.upgrade() else {
+                break;
+            };
+
+            match inactivity_service.check().await {
+                Ok(summary) => info!(
+                    target: "inactivity",
+                    "Inactive accounts: {} warned, {} disabled, {} deleted",
+                    summary.warned, summary.disabled, summary.deleted
+                ),
+                Err(e) => error!(target: "inactivity", "Failed to check the inactive accounts: {e:?}"),
+            }
+
+            drop(inactivity_service);
+        }
+    })
+}
@@ -0,0 +1,34 @@
+//! Cronjob to refresh the torrent listing snapshots.
+//!
+//! Refer to the [`listing_snapshot`](crate::services::listing_snapshot)
+//! service for more information.
+use std::sync::Arc;
+use std::time::Duration;
+
+use tokio::task::JoinHandle;
+use tracing::info;
+
+use crate::services::listing_snapshot;
+
+#[must_use]
+pub fn start(refresh_interval_seconds: u64, listing_snapshot_service: &Arc<listing_snapshot::Service>) -> JoinHandle<()> {
+    let weak_listing_snapshot_service = Arc::downgrade(listing_snapshot_service);
+
+    tokio::spawn(async move {
+        let mut execution_interval = tokio::time::interval(Duration::from_secs(refresh_interval_seconds));
+
+        info!("Refreshing listing snapshots every {refresh_interval_seconds} seconds ...");
+
+        loop {
+            execution_interval.tick().await; // first tick is immediate...
+
+            let Some(listing_snapshot_service) = weak_listing_snapshot_service.upgrade() else {
+                break;
+            };
+
+            listing_snapshot_service.refresh().await;
+
+            drop(listing_snapshot_service);
+        }
+    })
+}
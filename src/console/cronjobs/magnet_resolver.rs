@@ -0,0 +1,34 @@
+//! Cronjob to resolve the torrents uploaded as magnet links.
+//!
+//! Refer to [`Index::resolve_magnet_uploads`](crate::services::torrent::Index::resolve_magnet_uploads)
+//! for more information.
+use std::sync::Arc;
+use std::time::Duration;
+
+use tokio::task::JoinHandle;
+use tracing::info;
+
+use crate::services::torrent;
+
+#[must_use]
+pub fn start(resolve_interval_seconds: u64, torrent_index: &Arc<torrent::Index>) -> JoinHandle<()> {
+    let weak_torrent_index = Arc::downgrade(torrent_index);
+
+    tokio::spawn(async move {
+        let mut execution_interval = tokio::time::interval(Duration::from_secs(resolve_interval_seconds));
+
+        info!("Resolving magnet uploads every {resolve_interval_seconds} seconds ...");
+
+        loop {
+            execution_interval.tick().await; // first tick is immediate...
+
+            let Some(torrent_index) = weak_torrent_index.upgrade() else {
+                break;
+            };
+
+            torrent_index.resolve_magnet_uploads().await;
+
+            drop(torrent_index);
+        }
+    })
+}
@@ -0,0 +1,96 @@
+//! Cronjob to send the new-torrent digest emails.
+//!
+//! It reads the domain events from the outbox and sends the digest emails
+//! with the torrents uploaded since the previous execution. The last
+//! processed event is saved as the `mail_digest` consumer cursor.
+//!
+//! When the digest runs for the first time, the existing events are skipped
+//! so users don't receive a digest with all the torrents in the index.
+use std::sync::Arc;
+use std::time::Duration;
+
+use tokio::task::JoinHandle;
+use tracing::{error, info};
+
+use crate::config::MailDigest;
+use crate::databases::database;
+use crate::mailer::digest::Digester;
+use crate::models::domain_event::DomainEvent;
+use crate::services::domain_events::DbDomainEventRepository;
+
+/// The outbox consumer name.
+const CONSUMER: &str = "mail_digest";
+
+/// Number of events fetched at once.
+const BATCH_SIZE: u32 = 50;
+
+#[must_use]
+pub fn start(config: &MailDigest, digester: Digester, domain_event_repository: &Arc<DbDomainEventRepository>) -> JoinHandle<()> {
+    let weak_domain_event_repository = Arc::downgrade(domain_event_repository);
+    let interval_seconds = config.interval_seconds;
+
+    tokio::spawn(async move {
+        let mut execution_interval = tokio::time::interval(Duration::from_secs(interval_seconds));
+
+        info!("Sending new-torrent digests every {interval_seconds} seconds ...");
+
+        let mut first_execution = true;
+
+        loop {
+            execution_interval.tick().await;
+
+            let Some(domain_event_repository) = weak_domain_event_repository.upgrade() else {
+                break;
+            };
+
+            if let Err(e) = send_digest(&digester, &domain_event_repository, first_execution).await {
+                error!(target: "digest", "Failed to send the new-torrent digest: {e:?}");
+            } else {
+                first_execution = false;
+            }
+
+            drop(domain_event_repository);
+        }
+    })
+}
+
+async fn send_digest(
+    digester: &Digester,
+    domain_event_repository: &DbDomainEventRepository,
+    first_execution: bool,
+) -> Result<(), database::Error> {
+    let initial_cursor = domain_event_repository.get_cursor(CONSUMER).await?;
+
+    let mut cursor = initial_cursor;
+    let mut torrents = Vec::new();
+
+    loop {
+        let events = domain_event_repository.fetch_after(cursor, BATCH_SIZE).await?;
+
+        let Some(last_event) = events.last() else {
+            break;
+        };
+
+        cursor = last_event.event_id;
+
+        for event in events {
+            match serde_json::from_str::<DomainEvent>(&event.payload) {
+                Ok(DomainEvent::TorrentUploaded(torrent)) => torrents.push(torrent),
+                Ok(_) => {}
+                Err(e) => error!(target: "digest", "Skipping invalid domain event #{}: {e}", event.event_id),
+            }
+        }
+    }
+
+    if cursor == initial_cursor {
+        return Ok(());
+    }
+
+    if first_execution && initial_cursor == 0 {
+        info!(target: "digest", "Skipping {} torrents uploaded before the digest was enabled", torrents.len());
+    } else {
+        digester.send(&torrents).await?;
+    }
+
+    domain_event_repository.save_cursor(CONSUMER, cursor).await
+}
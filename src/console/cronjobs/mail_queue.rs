@@ -0,0 +1,91 @@
+//! Cronjob to send the emails in the outbound email queue.
+//!
+//! Emails are sent in batches to throttle the delivery rate: at most
+//! `batch_size` emails every `interval_seconds` seconds. Emails that can't be
+//! delivered stay in the queue and are retried in the next batches, until
+//! they reach the maximum number of attempts.
+//!
+//! On shutdown, the email being sent is removed from the queue before the
+//! cronjob stops, so it's not sent twice after a restart.
+use std::sync::Arc;
+use std::time::Duration;
+
+use tokio::task::JoinHandle;
+use tracing::{debug, error, info, warn};
+
+use crate::config::MailQueue;
+use crate::console::shutdown::Shutdown;
+use crate::databases::database;
+use crate::mailer;
+use crate::services::bulk_mail::DbEmailQueueRepository;
+
+#[must_use]
+pub fn start(
+    config: &MailQueue,
+    mailer: Arc<mailer::Service>,
+    email_queue_repository: &Arc<DbEmailQueueRepository>,
+    mut shutdown: Shutdown,
+) -> JoinHandle<()> {
+    let weak_email_queue_repository = Arc::downgrade(email_queue_repository);
+    let config = config.clone();
+
+    tokio::spawn(async move {
+        let mut execution_interval = tokio::time::interval(Duration::from_secs(config.interval_seconds));
+
+        info!(
+            "Sending up to {} queued emails every {} seconds ...",
+            config.batch_size, config.interval_seconds
+        );
+
+        loop {
+            tokio::select! {
+                _ = execution_interval.tick() => {}
+                () = shutdown.requested() => break,
+            }
+
+            let Some(email_queue_repository) = weak_email_queue_repository.upgrade() else {
+                break;
+            };
+
+            if let Err(e) = send_batch(&config, &mailer, &email_queue_repository, &shutdown).await {
+                error!(target: "mailer", "Failed to send the queued emails: {e:?}");
+            }
+
+            drop(email_queue_repository);
+        }
+
+        info!("Email queue cronjob stopped");
+    })
+}
+
+async fn send_batch(
+    config: &MailQueue,
+    mailer: &mailer::Service,
+    email_queue_repository: &DbEmailQueueRepository,
+    shutdown: &Shutdown,
+) -> Result<(), database::Error> {
+    let emails = email_queue_repository.get_batch(config.batch_size).await?;
+
+    if emails.is_empty() {
+        return Ok(());
+    }
+
+    debug!(target: "mailer", "Sending {} queued emails ...", emails.len());
+
+    for email in emails {
+        if shutdown.is_requested() {
+            break;
+        }
+
+        match mailer.send_plain_mail(&email.recipient, &email.subject, &email.body).await {
+            Ok(()) => email_queue_repository.delete(email.email_id).await?,
+            Err(e) if email.attempts + 1 >= i64::from(config.max_attempts) => {
+                warn!(target: "mailer", "Discarding queued email #{} after {} attempts: {e}", email.email_id, email.attempts + 1);
+                email_queue_repository.delete(email.email_id).await?;
+            }
+            Err(_) => email_queue_repository.increase_attempts(email.email_id).await?,
+        }
+    }
+
+    Ok(())
+}
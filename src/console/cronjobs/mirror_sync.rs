@@ -0,0 +1,37 @@
+//! Cronjob to detect the torrent changes for the mirrors.
+//!
+//! Refer to the [`sync`](crate::services::sync) service for more
+//! information.
+use std::sync::Arc;
+use std::time::Duration;
+
+use tokio::task::JoinHandle;
+use tracing::{debug, error, info};
+
+use crate::services::sync;
+
+#[must_use]
+pub fn start(refresh_interval_seconds: u64, sync_service: &Arc<sync::Service>) -> JoinHandle<()> {
+    let weak_sync_service = Arc::downgrade(sync_service);
+
+    tokio::spawn(async move {
+        let mut execution_interval = tokio::time::interval(Duration::from_secs(refresh_interval_seconds));
+
+        info!("Detecting torrent changes for mirrors every {refresh_interval_seconds} seconds ...");
+
+        loop {
+            execution_interval.tick().await; // first tick is immediate...
+
+            let Some(sync_service) = weak_sync_service.upgrade() else {
+                break;
+            };
+
+            match sync_service.refresh().await {
+                Ok(changes) => debug!(target: "mirror_sync", "Torrent changes detected: {changes}"),
+                Err(e) => error!(target: "mirror_sync", "Failed to detect the torrent changes: {e:?}"),
+            }
+
+            drop(sync_service);
+        }
+    })
+}
@@ -1,2 +1,22 @@
 //! Cronjobs that are executed automatically.
+pub mod announcer;
+pub mod api_usage;
+pub mod database_maintenance;
+pub mod gc;
+pub mod image_cache_eviction;
+pub mod inactivity;
+pub mod listing_snapshots;
+pub mod magnet_resolver;
+pub mod mail_digest;
+pub mod mail_queue;
+pub mod mirror_sync;
+pub mod saved_searches;
+pub mod scheduled_releases;
+pub mod seeding_rewards;
+pub mod smart_collections;
+pub mod torrent_health;
+pub mod torrent_rules;
 pub mod tracker_statistics_importer;
+pub mod transfer_stats;
+pub mod trash_purge;
+pub mod trending_tags;
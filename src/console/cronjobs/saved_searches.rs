@@ -0,0 +1,104 @@
+//! Cronjob to match the new torrents against the saved searches.
+//!
+//! It reads the domain events from the outbox and adds the torrents uploaded
+//! since the previous execution to the results of the matching saved
+//! searches. The last processed event is saved as the `saved_searches`
+//! consumer cursor. Refer to the [`saved_search`](crate::services::saved_search)
+//! service.
+//!
+//! When the job runs for the first time, the existing events are skipped so
+//! the torrents uploaded before the saved searches were available are not
+//! matched.
+use std::sync::Arc;
+use std::time::Duration;
+
+use tokio::task::JoinHandle;
+use tracing::{debug, error, info};
+
+use crate::databases::database;
+use crate::models::domain_event::DomainEvent;
+use crate::services::domain_events::DbDomainEventRepository;
+use crate::services::saved_search;
+
+/// The outbox consumer name.
+const CONSUMER: &str = "saved_searches";
+
+/// Number of events fetched at once.
+const BATCH_SIZE: u32 = 50;
+
+/// Interval in seconds between two runs.
+const MATCHING_INTERVAL_SECONDS: u64 = 300;
+
+#[must_use]
+pub fn start(
+    saved_search_service: &Arc<saved_search::Service>,
+    domain_event_repository: &Arc<DbDomainEventRepository>,
+) -> JoinHandle<()> {
+    let weak_saved_search_service = Arc::downgrade(saved_search_service);
+    let weak_domain_event_repository = Arc::downgrade(domain_event_repository);
+
+    tokio::spawn(async move {
+        let mut execution_interval = tokio::time::interval(Duration::from_secs(MATCHING_INTERVAL_SECONDS));
+
+        info!("Matching new torrents against saved searches every {MATCHING_INTERVAL_SECONDS} seconds ...");
+
+        loop {
+            execution_interval.tick().await;
+
+            let (Some(saved_search_service), Some(domain_event_repository)) =
+                (weak_saved_search_service.upgrade(), weak_domain_event_repository.upgrade())
+            else {
+                break;
+            };
+
+            if let Err(e) = match_new_torrents(&saved_search_service, &domain_event_repository).await {
+                error!(target: "saved_searches", "Failed to match the new torrents against the saved searches: {e:?}");
+            }
+
+            drop(saved_search_service);
+            drop(domain_event_repository);
+        }
+    })
+}
+
+async fn match_new_torrents(
+    saved_search_service: &saved_search::Service,
+    domain_event_repository: &DbDomainEventRepository,
+) -> Result<(), database::Error> {
+    let initial_cursor = domain_event_repository.get_cursor(CONSUMER).await?;
+
+    let mut cursor = initial_cursor;
+    let mut torrents = Vec::new();
+
+    loop {
+        let events = domain_event_repository.fetch_after(cursor, BATCH_SIZE).await?;
+
+        let Some(last_event) = events.last() else {
+            break;
+        };
+
+        cursor = last_event.event_id;
+
+        for event in events {
+            match serde_json::from_str::<DomainEvent>(&event.payload) {
+                Ok(DomainEvent::TorrentUploaded(torrent)) => torrents.push(torrent),
+                Ok(_) => {}
+                Err(e) => error!(target: "saved_searches", "Skipping invalid domain event #{}: {e}", event.event_id),
+            }
+        }
+    }
+
+    if cursor == initial_cursor {
+        return Ok(());
+    }
+
+    if initial_cursor == 0 {
+        info!(target: "saved_searches", "Skipping {} torrents uploaded before the saved searches were enabled", torrents.len());
+    } else {
+        let matches = saved_search_service.match_new_torrents(&torrents).await?;
+
+        debug!(target: "saved_searches", "{} new torrents, {matches} saved search matches", torrents.len());
+    }
+
+    domain_event_repository.save_cursor(CONSUMER, cursor).await
+}
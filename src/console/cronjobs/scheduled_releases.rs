@@ -0,0 +1,38 @@
+//! Cronjob to release the torrents scheduled with a publish date.
+//!
+//! Refer to [`Index::publish_scheduled_torrents`](crate::services::torrent::Index::publish_scheduled_torrents)
+//! for more information.
+use std::sync::Arc;
+use std::time::Duration;
+
+use tokio::task::JoinHandle;
+use tracing::info;
+
+use crate::services::torrent;
+
+/// Scheduled torrents are released at most this number of seconds after
+/// their publish date.
+const RELEASE_INTERVAL_SECONDS: u64 = 60;
+
+#[must_use]
+pub fn start(torrent_index: &Arc<torrent::Index>) -> JoinHandle<()> {
+    let weak_torrent_index = Arc::downgrade(torrent_index);
+
+    tokio::spawn(async move {
+        let mut execution_interval = tokio::time::interval(Duration::from_secs(RELEASE_INTERVAL_SECONDS));
+
+        info!("Releasing scheduled torrents every {RELEASE_INTERVAL_SECONDS} seconds ...");
+
+        loop {
+            execution_interval.tick().await; // first tick is immediate...
+
+            let Some(torrent_index) = weak_torrent_index.upgrade() else {
+                break;
+            };
+
+            torrent_index.publish_scheduled_torrents().await;
+
+            drop(torrent_index);
+        }
+    })
+}
@@ -0,0 +1,110 @@
+//! Cronjob to publish the seeding summaries for external bonus systems.
+//!
+//! It publishes the seeding summaries every `summary_interval_seconds`. The
+//! first summaries are published one interval after the application starts,
+//! so restarts do not publish extra summaries.
+//!
+//! When a webhook is configured, it also posts the pending summaries to the
+//! webhook every `poll_interval_seconds`. The last delivered summary is saved
+//! as the `seeding_rewards` consumer cursor of the domain event outbox. A
+//! failed delivery is retried in the next execution, so summaries are
+//! delivered at least once and in order.
+use std::sync::Arc;
+use std::time::Duration;
+
+use tokio::task::JoinHandle;
+use tokio::time::Instant;
+use tracing::{debug, error, info};
+
+use crate::config::SeedingRewards;
+use crate::services::domain_events::DbDomainEventRepository;
+use crate::services::seeding_rewards::{self, Webhook};
+
+/// The outbox consumer name.
+const CONSUMER: &str = "seeding_rewards";
+
+/// Maximum number of summaries delivered in one execution.
+const BATCH_SIZE: u32 = 50;
+
+#[must_use]
+pub fn start(
+    config: &SeedingRewards,
+    seeding_rewards_service: &Arc<seeding_rewards::Service>,
+    domain_event_repository: &Arc<DbDomainEventRepository>,
+) -> JoinHandle<()> {
+    let weak_seeding_rewards_service = Arc::downgrade(seeding_rewards_service);
+    let weak_domain_event_repository = Arc::downgrade(domain_event_repository);
+    let webhook = config
+        .webhook_url
+        .clone()
+        .map(|url| Webhook::new(url, config.webhook_secret.clone()));
+    let summary_interval = Duration::from_secs(config.summary_interval_seconds);
+    let poll_interval_seconds = config.poll_interval_seconds;
+
+    tokio::spawn(async move {
+        let mut execution_interval = tokio::time::interval(Duration::from_secs(poll_interval_seconds));
+        let mut next_summaries = Instant::now() + summary_interval;
+
+        info!(
+            "Publishing seeding summaries every {} seconds ...",
+            summary_interval.as_secs()
+        );
+
+        loop {
+            execution_interval.tick().await;
+
+            let (Some(seeding_rewards_service), Some(domain_event_repository)) =
+                (weak_seeding_rewards_service.upgrade(), weak_domain_event_repository.upgrade())
+            else {
+                break;
+            };
+
+            if Instant::now() >= next_summaries {
+                next_summaries += summary_interval;
+
+                match seeding_rewards_service.publish_summaries(summary_interval.as_secs()).await {
+                    Ok(published) => info!(target: "seeding rewards", "Published {published} seeding summaries"),
+                    Err(e) => error!(target: "seeding rewards", "Failed to publish the seeding summaries: {e:?}"),
+                }
+            }
+
+            if let Some(webhook) = &webhook {
+                deliver_pending_summaries(webhook, &domain_event_repository).await;
+            }
+
+            drop(seeding_rewards_service);
+            drop(domain_event_repository);
+        }
+    })
+}
+
+async fn deliver_pending_summaries(webhook: &Webhook, domain_event_repository: &DbDomainEventRepository) {
+    let events = match domain_event_repository.get_cursor(CONSUMER).await {
+        Ok(cursor) => domain_event_repository.fetch_after(cursor, BATCH_SIZE).await,
+        Err(e) => Err(e),
+    };
+
+    let events = match events {
+        Ok(events) => events,
+        Err(e) => {
+            error!(target: "seeding rewards", "Failed to read the domain events: {e:?}");
+            return;
+        }
+    };
+
+    for event in events {
+        if let Some(summary) = seeding_rewards::seeding_summary_event(&event) {
+            debug!(target: "seeding rewards", "Delivering seeding summary #{} ...", event.event_id);
+
+            if let Err(e) = webhook.send(&summary).await {
+                error!(target: "seeding rewards", "Failed to deliver seeding summary #{}: {e}", event.event_id);
+                return;
+            }
+        }
+
+        if let Err(e) = domain_event_repository.save_cursor(CONSUMER, event.event_id).await {
+            error!(target: "seeding rewards", "Failed to save the consumer cursor: {e:?}");
+            return;
+        }
+    }
+}
@@ -0,0 +1,42 @@
+//! Cronjob to update the torrents of the smart collections.
+//!
+//! Smart collections select their torrents with a filter. This job
+//! materializes the matching torrents periodically so new uploads are added
+//! to the collections. Refer to the [`collection`](crate::services::collection)
+//! service.
+use std::sync::Arc;
+use std::time::Duration;
+
+use tokio::task::JoinHandle;
+use tracing::{debug, error, info};
+
+use crate::services::collection;
+
+/// Interval in seconds between two runs.
+const MATERIALIZATION_INTERVAL_SECONDS: u64 = 600;
+
+#[must_use]
+pub fn start(collection_service: &Arc<collection::Service>) -> JoinHandle<()> {
+    let weak_collection_service = Arc::downgrade(collection_service);
+
+    tokio::spawn(async move {
+        let mut execution_interval = tokio::time::interval(Duration::from_secs(MATERIALIZATION_INTERVAL_SECONDS));
+
+        info!("Updating smart collections every {MATERIALIZATION_INTERVAL_SECONDS} seconds ...");
+
+        loop {
+            execution_interval.tick().await;
+
+            let Some(collection_service) = weak_collection_service.upgrade() else {
+                break;
+            };
+
+            match collection_service.materialize_smart_collections().await {
+                Ok(updated) => debug!(target: "smart_collections", "Smart collections updated: {updated}"),
+                Err(e) => error!(target: "smart_collections", "Failed to update the smart collections: {e:?}"),
+            }
+
+            drop(collection_service);
+        }
+    })
+}
@@ -0,0 +1,42 @@
+//! Cronjob to update the health of the torrents.
+//!
+//! It updates the health score of the torrents and flags or archives the
+//! dead ones. Refer to the [`health`](crate::services::torrent::health)
+//! service.
+use std::sync::Arc;
+use std::time::Duration;
+
+use tokio::task::JoinHandle;
+use tracing::{error, info};
+
+use crate::services::torrent::health;
+
+#[must_use]
+pub fn start(check_interval_seconds: u64, torrent_health_service: &Arc<health::Service>) -> JoinHandle<()> {
+    let weak_torrent_health_service = Arc::downgrade(torrent_health_service);
+
+    tokio::spawn(async move {
+        let mut execution_interval = tokio::time::interval(Duration::from_secs(check_interval_seconds));
+
+        info!("Updating the health of the torrents every {check_interval_seconds} seconds ...");
+
+        loop {
+            execution_interval.tick().await;
+
+            let Some(torrent_health_service) = weak_torrent_health_service.upgrade() else {
+                break;
+            };
+
+            match torrent_health_service.check().await {
+                Ok(summary) => info!(
+                    target: "torrent_health",
+                    "Torrent health: {} updated, {} flagged, {} archived",
+                    summary.updated, summary.flagged, summary.archived
+                ),
+                Err(e) => error!(target: "torrent_health", "Failed to update the health of the torrents: {e:?}"),
+            }
+
+            drop(torrent_health_service);
+        }
+    })
+}
@@ -0,0 +1,42 @@
+//! Cronjob to apply the torrent rules.
+//!
+//! It archives, delists or notifies the uploaders of the torrents matching
+//! the rules. Refer to the [`torrent_rules`](crate::services::torrent_rules)
+//! service.
+use std::sync::Arc;
+use std::time::Duration;
+
+use tokio::task::JoinHandle;
+use tracing::{error, info};
+
+use crate::services::torrent_rules;
+
+#[must_use]
+pub fn start(check_interval_seconds: u64, torrent_rules_service: &Arc<torrent_rules::Service>) -> JoinHandle<()> {
+    let weak_torrent_rules_service = Arc::downgrade(torrent_rules_service);
+
+    tokio::spawn(async move {
+        let mut execution_interval = tokio::time::interval(Duration::from_secs(check_interval_seconds));
+
+        info!("Applying the torrent rules every {check_interval_seconds} seconds ...");
+
+        loop {
+            execution_interval.tick().await;
+
+            let Some(torrent_rules_service) = weak_torrent_rules_service.upgrade() else {
+                break;
+            };
+
+            match torrent_rules_service.check().await {
+                Ok(summary) => info!(
+                    target: "torrent_rules",
+                    "Torrent rules: {} archived, {} delisted, {} notified",
+                    summary.archived, summary.delisted, summary.notified
+                ),
+                Err(e) => error!(target: "torrent_rules", "Failed to apply the torrent rules: {e:?}"),
+            }
+
+            drop(torrent_rules_service);
+        }
+    })
+}
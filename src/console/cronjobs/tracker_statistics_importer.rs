@@ -10,6 +10,9 @@
 //! The last heartbeat signal time is used to determine whether the cronjob was
 //! executed successfully or not. The API has a `health_check` endpoint which is
 //! used when the application is running in containers.
+//!
+//! On shutdown, the cronjob finishes the run in progress, so the cursor is
+//! saved and the next run continues from there.
 use std::net::SocketAddr;
 use std::sync::{Arc, Mutex};
 
@@ -18,11 +21,12 @@ use axum::routing::{get, post};
 use axum::{Json, Router};
 use chrono::{DateTime, Utc};
 use serde_json::{json, Value};
-use text_colorizer::Colorize;
 use tokio::net::TcpListener;
 use tokio::task::JoinHandle;
-use tracing::{debug, error, info};
+use tracing::{error, info};
 
+use crate::console::shutdown::Shutdown;
+use crate::tracker::alerts::Alerter;
 use crate::tracker::statistics_importer::StatisticsImporter;
 use crate::utils::clock::seconds_ago_utc;
 
@@ -45,6 +49,8 @@ pub fn start(
     importer_port: u16,
     torrent_stats_update_interval: u64,
     tracker_statistics_importer: &Arc<StatisticsImporter>,
+    alerter: Option<Alerter>,
+    mut shutdown: Shutdown,
 ) -> JoinHandle<()> {
     let weak_tracker_statistics_importer = Arc::downgrade(tracker_statistics_importer);
 
@@ -89,9 +95,10 @@ pub fn start(
         // database. If we remove the interval we would be constantly queering
         // if there are torrent stats pending to update, unless there are
         // torrents to update. Maybe we should only sleep for 100 milliseconds
-        // if we did not update any torrents in the latest execution. With this
-        // current limit we can only import 50 torrent stats every 2000 seconds,
-        // which is 500 torrents per second (1800000 torrents per hour).
+        // if we did not update any torrents in the latest execution. Each
+        // execution imports up to `batch_size * concurrency` torrent stats.
+        // With the default options (batches of 50 torrents, one at a time) we
+        // can only import 50 torrent stats every 2000 milliseconds.
         //
         // | Interval (secs) | Number of torrents imported per hour |
         // ------------------|--------------------------------------|
@@ -101,6 +108,9 @@ pub fn start(
         // |           4 sec |               50 * (3600/4) =  45000 |
         // |           5 sec |               50 * (3600/5) =  36000 |
         //
+        // Increase the `batch_size` or the `concurrency` options to import
+        // more torrent stats per execution.
+        //
         // The `execution_interval_in_milliseconds` could be a config option in
         // the future.
 
@@ -123,29 +133,28 @@ pub fn start(
                         .try_into()
                         .expect("update interval should be a positive integer"),
                 );
-                let limit = 50;
-
-                debug!(
-                    "Importing torrents statistics not updated since {} limited to a maximum of {} torrents ...",
-                    one_interval_ago.to_string().yellow(),
-                    limit.to_string().yellow()
-                );
 
-                match statistics_importer
-                    .import_torrents_statistics_not_updated_since(one_interval_ago, limit)
-                    .await
-                {
+                match statistics_importer.import_next_batches(one_interval_ago).await {
                     Ok(()) => {}
                     Err(e) => error!("Failed to import statistics: {:?}", e),
                 }
 
+                if let Some(alerter) = &alerter {
+                    alerter.check(&statistics_importer.status()).await;
+                }
+
                 drop(statistics_importer);
             } else {
                 break;
             }
 
-            execution_interval.tick().await;
+            tokio::select! {
+                _ = execution_interval.tick() => {}
+                () = shutdown.requested() => break,
+            }
         }
+
+        info!("Tracker statistics importer cronjob stopped");
     })
 }
 
@@ -0,0 +1,37 @@
+//! Cronjob to import the users' transfer stats from the tracker.
+//!
+//! Refer to the [`ratio`](crate::services::ratio) service for more
+//! information.
+use std::sync::Arc;
+use std::time::Duration;
+
+use tokio::task::JoinHandle;
+use tracing::{error, info};
+
+use crate::services::ratio;
+
+#[must_use]
+pub fn start(import_interval_seconds: u64, ratio_service: &Arc<ratio::Service>) -> JoinHandle<()> {
+    let weak_ratio_service = Arc::downgrade(ratio_service);
+
+    tokio::spawn(async move {
+        let mut execution_interval = tokio::time::interval(Duration::from_secs(import_interval_seconds));
+
+        info!("Importing transfer stats from the tracker every {import_interval_seconds} seconds ...");
+
+        loop {
+            execution_interval.tick().await; // first tick is immediate...
+
+            let Some(ratio_service) = weak_ratio_service.upgrade() else {
+                break;
+            };
+
+            match ratio_service.import_transfer_stats().await {
+                Ok(imported) => info!(target: "ratio", "Imported transfer stats of {imported} keys"),
+                Err(e) => error!(target: "ratio", "Failed to import the transfer stats: {e:?}"),
+            }
+
+            drop(ratio_service);
+        }
+    })
+}
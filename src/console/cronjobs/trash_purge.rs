@@ -0,0 +1,36 @@
+//! Cronjob to purge the torrents in the trash bin after the retention period.
+//!
+//! Refer to the [`trash`](crate::services::torrent::trash) service.
+use std::sync::Arc;
+use std::time::Duration;
+
+use tokio::task::JoinHandle;
+use tracing::{debug, error, info};
+
+use crate::services::torrent::trash;
+
+#[must_use]
+pub fn start(purge_interval_seconds: u64, trash_service: &Arc<trash::Service>) -> JoinHandle<()> {
+    let weak_trash_service = Arc::downgrade(trash_service);
+
+    tokio::spawn(async move {
+        let mut execution_interval = tokio::time::interval(Duration::from_secs(purge_interval_seconds));
+
+        info!("Purging expired torrents from the trash bin every {purge_interval_seconds} seconds ...");
+
+        loop {
+            execution_interval.tick().await;
+
+            let Some(trash_service) = weak_trash_service.upgrade() else {
+                break;
+            };
+
+            match trash_service.purge_expired_torrents().await {
+                Ok(purged) => debug!(target: "trash", "Torrents purged: {purged}"),
+                Err(e) => error!(target: "trash", "Failed to purge the expired torrents: {e:?}"),
+            }
+
+            drop(trash_service);
+        }
+    })
+}
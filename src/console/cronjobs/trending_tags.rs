@@ -0,0 +1,34 @@
+//! Cronjob to rank the trending tags.
+//!
+//! Refer to the [`trending_tags`](crate::services::trending_tags) service for
+//! more information.
+use std::sync::Arc;
+use std::time::Duration;
+
+use tokio::task::JoinHandle;
+use tracing::info;
+
+use crate::services::trending_tags;
+
+#[must_use]
+pub fn start(refresh_interval_seconds: u64, trending_tags_service: &Arc<trending_tags::Service>) -> JoinHandle<()> {
+    let weak_trending_tags_service = Arc::downgrade(trending_tags_service);
+
+    tokio::spawn(async move {
+        let mut execution_interval = tokio::time::interval(Duration::from_secs(refresh_interval_seconds));
+
+        info!("Ranking trending tags every {refresh_interval_seconds} seconds ...");
+
+        loop {
+            execution_interval.tick().await; // first tick is immediate...
+
+            let Some(trending_tags_service) = weak_trending_tags_service.upgrade() else {
+                break;
+            };
+
+            trending_tags_service.refresh().await;
+
+            drop(trending_tags_service);
+        }
+    })
+}
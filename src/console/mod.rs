@@ -1,3 +1,5 @@
 //! Console modules.
 pub mod commands;
 pub mod cronjobs;
+pub mod reload;
+pub mod shutdown;
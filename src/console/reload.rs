@@ -0,0 +1,78 @@
+//! Reload of the settings without restarting the application.
+//!
+//! The settings are reloaded when the process receives a `SIGHUP` signal or
+//! when an administrator calls the
+//! [reload endpoint](crate::web::api::server::v1::contexts::admin#reload-the-settings).
+//! After every successful reload, the values derived from the old settings
+//! are discarded.
+//!
+//! Refer to [`Configuration::reload`](crate::config::Configuration::reload).
+use std::sync::Arc;
+
+use tokio::task::JoinHandle;
+use tracing::info;
+
+use crate::config::Configuration;
+use crate::services::settings;
+use crate::services::torrent::DbTorrentListingGenerator;
+
+#[must_use]
+pub fn start(
+    configuration: &Arc<Configuration>,
+    settings_service: &Arc<settings::Service>,
+    torrent_listing_generator: &Arc<DbTorrentListingGenerator>,
+) -> JoinHandle<()> {
+    #[cfg(unix)]
+    drop(listen_for_hangup(settings_service));
+
+    #[cfg(not(unix))]
+    let _ = settings_service;
+
+    let mut reloads = configuration.subscribe();
+    let weak_torrent_listing_generator = Arc::downgrade(torrent_listing_generator);
+
+    tokio::spawn(async move {
+        while reloads.changed().await.is_ok() {
+            let Some(torrent_listing_generator) = weak_torrent_listing_generator.upgrade() else {
+                break;
+            };
+
+            // Cached listings may have been built with the old settings.
+            torrent_listing_generator.invalidate();
+
+            drop(torrent_listing_generator);
+        }
+    })
+}
+
+#[cfg(unix)]
+fn listen_for_hangup(settings_service: &Arc<settings::Service>) -> JoinHandle<()> {
+    use tokio::signal::unix::{signal, SignalKind};
+    use tracing::error;
+
+    let weak_settings_service = Arc::downgrade(settings_service);
+
+    tokio::spawn(async move {
+        let mut hangup = match signal(SignalKind::hangup()) {
+            Ok(hangup) => hangup,
+            Err(e) => {
+                error!("Failed to listen for the SIGHUP signal: {e}");
+                return;
+            }
+        };
+
+        info!("Reloading the settings on SIGHUP ...");
+
+        while hangup.recv().await.is_some() {
+            let Some(settings_service) = weak_settings_service.upgrade() else {
+                break;
+            };
+
+            info!("SIGHUP received, reloading the settings ...");
+
+            settings_service.reload_on_signal().await;
+
+            drop(settings_service);
+        }
+    })
+}
@@ -0,0 +1,132 @@
+//! Coordinated shutdown of the background jobs.
+//!
+//! When the application receives the `SIGTERM` signal or `Ctrl+C`, the API
+//! server stops accepting new connections and waits for the in-flight
+//! requests, and the background jobs are asked to stop at the same time.
+//!
+//! Jobs holding a [`Shutdown`] handle finish the unit of work in progress,
+//! like the email being sent or the batch of torrents being imported, and
+//! save their cursor before stopping, so they continue where they stopped
+//! after a restart. The application waits for them up to
+//! `net.shutdown_timeout_seconds`. The other jobs only do work that is safe
+//! to repeat, so they are stopped when the process exits.
+use std::sync::Arc;
+use std::time::Duration;
+
+use tokio::sync::{mpsc, watch};
+use tracing::{info, warn};
+
+use crate::web::api::server::signals::global_shutdown_signal;
+
+/// It asks the background jobs to stop and waits for them.
+pub struct Coordinator {
+    requested: Arc<watch::Sender<bool>>,
+    running_tx: mpsc::Sender<()>,
+    running_rx: mpsc::Receiver<()>,
+    timeout: Duration,
+}
+
+impl Coordinator {
+    #[must_use]
+    pub fn new(timeout: Duration) -> Self {
+        let (running_tx, running_rx) = mpsc::channel(1);
+
+        Self {
+            requested: Arc::new(watch::Sender::new(false)),
+            running_tx,
+            running_rx,
+            timeout,
+        }
+    }
+
+    /// It returns a handle for a background job. The coordinator waits until
+    /// all the handles are dropped.
+    #[must_use]
+    pub fn handle(&self) -> Shutdown {
+        Shutdown {
+            requested: self.requested.subscribe(),
+            _running: self.running_tx.clone(),
+        }
+    }
+
+    /// It asks the background jobs to stop when the application receives the
+    /// `SIGTERM` signal or `Ctrl+C`.
+    pub fn request_on_signal(&self) {
+        let requested = self.requested.clone();
+
+        drop(tokio::spawn(async move {
+            global_shutdown_signal().await;
+
+            info!("Stopping the background jobs ...");
+
+            requested.send_replace(true);
+        }));
+    }
+
+    /// It asks the background jobs to stop, if they were not asked yet, and
+    /// waits for them to finish the work in progress, up to the shutdown
+    /// timeout.
+    pub async fn shutdown(self) {
+        let Self {
+            requested,
+            running_tx,
+            mut running_rx,
+            timeout,
+        } = self;
+
+        requested.send_replace(true);
+
+        drop(running_tx);
+
+        // `recv` returns when all the handles have been dropped.
+        match tokio::time::timeout(timeout, running_rx.recv()).await {
+            Ok(_) => info!("Background jobs stopped"),
+            Err(_) => warn!("Background jobs still running after {} seconds", timeout.as_secs()),
+        }
+    }
+}
+
+/// A handle for a background job to know when it has to stop.
+#[derive(Clone)]
+pub struct Shutdown {
+    requested: watch::Receiver<bool>,
+    /// Keeps the coordinator waiting while the job is running.
+    _running: mpsc::Sender<()>,
+}
+
+impl Shutdown {
+    /// It resolves when the job has to stop.
+    pub async fn requested(&mut self) {
+        // The coordinator was dropped if it fails, so the job has to stop too.
+        drop(self.requested.wait_for(|requested| *requested).await);
+    }
+
+    /// Whether the job has to stop or not.
+    #[must_use]
+    pub fn is_requested(&self) -> bool {
+        *self.requested.borrow()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use super::Coordinator;
+
+    #[tokio::test]
+    async fn it_should_ask_the_jobs_to_stop_and_wait_for_them() {
+        let coordinator = Coordinator::new(Duration::from_secs(5));
+
+        let mut shutdown = coordinator.handle();
+
+        let job = tokio::spawn(async move {
+            shutdown.requested().await;
+            shutdown.is_requested()
+        });
+
+        coordinator.shutdown().await;
+
+        assert!(job.await.unwrap());
+    }
+}
@@ -1,18 +1,58 @@
+use std::sync::Arc;
+use std::time::Duration;
+
 use async_trait::async_trait;
 use bittorrent_primitives::info_hash::InfoHash;
 use chrono::{DateTime, NaiveDateTime, Utc};
 use serde::{Deserialize, Serialize};
+use tokio::sync::mpsc;
 use url::Url;
+use utoipa::ToSchema;
 
+use crate::config::{self, SqliteJournalMode, SqliteSynchronous};
+use crate::databases::encryption::Cipher;
 use crate::databases::mysql::Mysql;
+use crate::databases::replicas::{self, Replicas};
 use crate::databases::sqlite::Sqlite;
-use crate::models::category::CategoryId;
-use crate::models::response::TorrentsResponse;
-use crate::models::torrent::{Metadata, TorrentListing};
+use crate::models::antispam::{BannedPattern, PatternAction, PatternId};
+use crate::models::api_usage::ApiUsageRecord;
+use crate::models::audit::{AuditAction, AuditEntryId, AuditLogFilter};
+use crate::models::bookmark::Bookmark;
+use crate::models::category::{CategoryId, CategoryPolicy};
+use crate::models::category_field::{CategoryField, CategoryFieldId, FieldType, TorrentFieldValue};
+use crate::models::collection::{Collection, CollectionId, SmartFilter};
+use crate::models::comment::{Comment, CommentId};
+use crate::models::domain_event::{DbDomainEvent, EventId};
+use crate::models::email::{EmailId, QueuedEmail, Recipient, Segment};
+use crate::models::image::{DescriptionImage, ImageId, ImageKind};
+use crate::models::import_session::{ImportBatch, ImportSession, ImportSessionId};
+use crate::models::magnet_upload::{MagnetUpload, MagnetUploadId, MagnetUploadStatus};
+use crate::models::points::{PointsAdjustment, UploaderSeedingStats};
+use crate::models::report::{Report, ReportAction, ReportId, ReportReason};
+use crate::models::reputation::ReputationStats;
+use crate::models::response::{AuditLogResponse, CommentsResponse, ReportsResponse, TorrentsResponse};
+use crate::models::saved_search::{SavedSearch, SavedSearchId};
+use crate::models::subscription::{Subscription, SubscriptionId};
+use crate::models::sync::{PartitionId, Sequence, SyncPartitionSummary, SyncState, SyncTorrent};
+use crate::models::torrent::{
+    BulkAction, ListingFilter, Metadata, ScheduledRelease, SimilarTorrent, TorrentId, TorrentListing, TorrentStatus,
+    TrashedTorrent,
+};
+use crate::models::torrent_attachment::{AttachmentId, AttachmentKind, TorrentAttachment};
+use crate::models::torrent_duplicate::DuplicatePair;
 use crate::models::torrent_file::{DbTorrent, Torrent, TorrentFile};
-use crate::models::torrent_tag::{TagId, TorrentTag};
+use crate::models::torrent_file_group::TorrentFileGroup;
+use crate::models::torrent_health::{DeadTorrent, TorrentHealthSample};
+use crate::models::torrent_metadata::TorrentMetadata;
+use crate::models::torrent_preview::{PreviewId, TorrentPreview};
+use crate::models::torrent_rule::TorrentRuleCandidate;
+use crate::models::torrent_tag::{TagId, TagSynonym, TorrentTag};
+use crate::models::torrent_web_seed::{TorrentWebSeed, WebSeedId};
 use crate::models::tracker_key::TrackerKey;
-use crate::models::user::{User, UserAuthentication, UserCompact, UserId, UserProfile};
+use crate::models::transfer_stats::TransferStats;
+use crate::models::upload_quota::{UploadLimits, UploadUsage};
+use crate::models::user::{InactiveUser, PublicProfile, User, UserAuthentication, UserCompact, UserFull, UserId, UserProfile};
+use crate::models::user_session::UserSession;
 use crate::services::torrent::CanonicalInfoHashGroup;
 
 /// Database tables to be truncated when upgrading from v1.0.0 to v2.0.0.
@@ -34,6 +74,56 @@ pub const TABLES_TO_TRUNCATE: &[&str] = &[
     "torrust_users",
     "torrust_categories",
     "torrust_torrent_tags",
+    "torrust_domain_events",
+    "torrust_domain_event_consumers",
+    "torrust_torrent_comments",
+    "torrust_torrent_reports",
+    "torrust_user_bookmarks",
+    "torrust_user_subscriptions",
+    "torrust_download_client_stats",
+    "torrust_magnet_uploads",
+    "torrust_email_queue",
+    "torrust_user_email_unsubscribes",
+    "torrust_user_inactivity_exemptions",
+    "torrust_images",
+    "torrust_smart_collection_filters",
+    "torrust_collection_items",
+    "torrust_collections",
+    "torrust_user_upload_quotas",
+    "torrust_user_reputation_overrides",
+    "torrust_user_transfer_stats",
+    "torrust_upload_nonces",
+    "torrust_category_moderators",
+    "torrust_user_api_usage",
+    "torrust_sync_records",
+    "torrust_audit_log",
+    "torrust_torrent_approvals",
+    "torrust_category_policies",
+    "torrust_importer_cursors",
+    "torrust_torrent_previews",
+    "torrust_torrent_file_groups",
+    "torrust_torrent_download_stats",
+    "torrust_torrent_tag_synonyms",
+    "torrust_torrent_rule_applications",
+    "torrust_user_saved_search_results",
+    "torrust_user_saved_searches",
+    "torrust_import_batches",
+    "torrust_import_sessions",
+    "torrust_user_points",
+    "torrust_user_point_adjustments",
+    "torrust_image_cache_entries",
+    "torrust_torrent_description_images",
+    "torrust_torrent_attachments",
+    "torrust_torrent_duplicates",
+    "torrust_torrent_trash",
+    "torrust_torrent_schedules",
+    "torrust_torrent_health",
+    "torrust_antispam_patterns",
+    "torrust_user_sessions",
+    "torrust_torrent_web_seeds",
+    "torrust_torrent_metadata",
+    "torrust_torrent_field_values",
+    "torrust_category_fields",
 ];
 
 /// Database drivers.
@@ -43,6 +133,100 @@ pub enum Driver {
     Mysql,
 }
 
+/// Settings of the database connection pool.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PoolSettings {
+    pub max_connections: u32,
+    /// Maximum time to wait for a connection of the pool.
+    pub acquire_timeout: Duration,
+    /// Maximum execution time of the statements. Only supported by `MySQL`,
+    /// for `SELECT` statements.
+    pub statement_timeout: Option<Duration>,
+    /// Statements slower than this are logged as warnings, with the name of
+    /// the database method that runs them.
+    pub slow_query_threshold: Option<Duration>,
+    pub sqlite: SqliteSettings,
+}
+
+/// Settings of the `SQLite` connections. Other drivers ignore them.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SqliteSettings {
+    pub journal_mode: SqliteJournalMode,
+    pub synchronous: SqliteSynchronous,
+    /// Maximum time to wait for a locked database.
+    pub busy_timeout: Duration,
+}
+
+impl Default for SqliteSettings {
+    fn default() -> Self {
+        Self {
+            journal_mode: SqliteJournalMode::Wal,
+            synchronous: SqliteSynchronous::Normal,
+            busy_timeout: Duration::from_secs(5),
+        }
+    }
+}
+
+impl Default for PoolSettings {
+    fn default() -> Self {
+        Self {
+            max_connections: 10,
+            acquire_timeout: Duration::from_secs(30),
+            statement_timeout: None,
+            slow_query_threshold: Some(Duration::from_secs(1)),
+            sqlite: SqliteSettings::default(),
+        }
+    }
+}
+
+impl From<&config::Database> for PoolSettings {
+    /// The pool settings in the `[database]` section. Timeouts and thresholds
+    /// set to `0` are disabled.
+    fn from(database: &config::Database) -> Self {
+        Self {
+            max_connections: database.max_connections,
+            acquire_timeout: Duration::from_secs(database.acquire_timeout_seconds),
+            statement_timeout: Some(database.statement_timeout_ms)
+                .filter(|timeout| *timeout > 0)
+                .map(Duration::from_millis),
+            slow_query_threshold: Some(database.slow_query_threshold_ms)
+                .filter(|threshold| *threshold > 0)
+                .map(Duration::from_millis),
+            sqlite: SqliteSettings {
+                journal_mode: database.sqlite.journal_mode,
+                synchronous: database.sqlite.synchronous,
+                busy_timeout: Duration::from_millis(database.sqlite.busy_timeout_ms),
+            },
+        }
+    }
+}
+
+/// A database migration and whether it was applied.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct MigrationStatus {
+    pub version: i64,
+    pub description: String,
+    pub applied: bool,
+}
+
+/// A row of a table in a backup.
+#[derive(Debug, Clone, PartialEq)]
+pub struct TableRow {
+    pub table: String,
+    pub columns: Vec<String>,
+    pub values: Vec<ColumnValue>,
+}
+
+/// The value of a column in a backup. Dates are stored as text.
+#[derive(Debug, Clone, PartialEq)]
+pub enum ColumnValue {
+    Null,
+    Integer(i64),
+    Real(f64),
+    Text(String),
+    Blob(Vec<u8>),
+}
+
 /// Compact representation of torrent.
 #[derive(Debug, Serialize, sqlx::FromRow)]
 pub struct TorrentCompact {
@@ -50,6 +234,23 @@ pub struct TorrentCompact {
     pub info_hash: String,
 }
 
+/// Number of `.torrent` downloads for a client family or user agent.
+#[derive(Debug, Serialize, Deserialize, PartialEq, Eq, sqlx::FromRow)]
+pub struct DownloadCount {
+    pub name: String,
+    pub downloads: i64,
+}
+
+/// Number of approved uploads and `.torrent` downloads of the torrents with a
+/// tag in a period.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq, sqlx::FromRow)]
+pub struct TagActivity {
+    pub tag_id: TagId,
+    pub name: String,
+    pub uploads: i64,
+    pub downloads: i64,
+}
+
 /// Torrent category.
 #[derive(Debug, Serialize, Deserialize, sqlx::FromRow)]
 pub struct Category {
@@ -59,7 +260,7 @@ pub struct Category {
 }
 
 /// Sorting options for torrents.
-#[derive(Clone, Copy, Debug, Deserialize)]
+#[derive(Clone, Copy, Debug, Deserialize, ToSchema)]
 pub enum Sorting {
     UploadedAsc,
     UploadedDesc,
@@ -71,6 +272,8 @@ pub enum Sorting {
     NameDesc,
     SizeAsc,
     SizeDesc,
+    /// Only available when the search is backed by a search index.
+    Relevance,
 }
 
 /// Database errors.
@@ -89,6 +292,77 @@ pub enum Error {
     TorrentAlreadyExists, // when uploading an already uploaded info_hash
     TorrentTitleAlreadyExists,
     TorrentInfoHashNotFound,
+    CommentNotFound,
+    ReportNotFound,
+    BookmarkNotFound,
+    SubscriptionNotFound,
+    SavedSearchNotFound,
+    ImportSessionNotFound,
+    CollectionNotFound,
+    CollectionItemNotFound,
+    MagnetUploadNotFound,
+    ImageAlreadyExists,
+    TorrentPreviewAlreadyExists,
+    TorrentPreviewNotFound,
+    TagSynonymNotFound,
+    DescriptionImageNotFound,
+    TorrentAttachmentNotFound,
+    TorrentWebSeedAlreadyExists,
+    TorrentWebSeedNotFound,
+    TorrentMetadataNotFound,
+    CategoryFieldAlreadyExists,
+    CategoryFieldNotFound,
+    BannedPatternAlreadyExists,
+    BannedPatternNotFound,
+    UserSessionNotFound,
+    MigrationError,
+    MigrationNotFound,
+    MigrationNotReversible,
+    InvalidBackupRow,
+    InvalidReplica,
+}
+
+/// The level of the slow statements log. It's disabled without threshold.
+#[must_use]
+pub fn slow_statements_level(pool_settings: &PoolSettings) -> log::LevelFilter {
+    if pool_settings.slow_query_threshold.is_some() {
+        log::LevelFilter::Warn
+    } else {
+        log::LevelFilter::Off
+    }
+}
+
+/// The threshold of the slow statements log.
+#[must_use]
+pub fn slow_statements_threshold(pool_settings: &PoolSettings) -> Duration {
+    pool_settings.slow_query_threshold.unwrap_or(Duration::MAX)
+}
+
+/// It returns the statement to insert a row of a backup. Rows of unknown
+/// tables or with invalid column names are rejected, because the names are
+/// not bound as parameters.
+///
+/// # Errors
+///
+/// This function will return an `Error::InvalidBackupRow` if the row can't be
+/// restored in the given tables.
+pub fn insert_row_statement(row: &TableRow, tables: &[String]) -> Result<String, Error> {
+    let is_identifier = |name: &str| !name.is_empty() && name.chars().all(|c| c.is_ascii_alphanumeric() || c == '_');
+
+    if !tables.contains(&row.table)
+        || row.columns.is_empty()
+        || row.columns.len() != row.values.len()
+        || !row.columns.iter().all(|column| is_identifier(column))
+    {
+        return Err(Error::InvalidBackupRow);
+    }
+
+    Ok(format!(
+        "INSERT INTO {} ({}) VALUES ({})",
+        row.table,
+        row.columns.join(", "),
+        vec!["?"; row.columns.len()].join(", ")
+    ))
 }
 
 /// Get the Driver of the Database from the Connection String
@@ -104,27 +378,134 @@ pub fn get_driver(db_path: &str) -> Result<Driver, Error> {
     }
 }
 
-/// Connect to a database.
+/// Connect to a database. The pending migrations are applied.
 ///
 /// # Errors
 ///
-/// This function will return an `Error::UnrecognizedDatabaseDriver` if unable to match database type.
+/// This function will return an `Error::UnrecognizedDatabaseDriver` if unable to match database type,
+/// or an `Error::MigrationError` if the pending migrations can't be applied.
 pub async fn connect(db_path: &str) -> Result<Box<dyn Database>, Error> {
+    connect_with_cipher(db_path, None).await
+}
+
+/// Connect to a database encrypting the sensitive columns with the `cipher`.
+/// The pending migrations are applied.
+///
+/// # Errors
+///
+/// This function will return an `Error::UnrecognizedDatabaseDriver` if unable to match database type,
+/// or an `Error::MigrationError` if the pending migrations can't be applied.
+pub async fn connect_with_cipher(db_path: &str, cipher: Option<Arc<Cipher>>) -> Result<Box<dyn Database>, Error> {
+    let database = connect_without_migrating(db_path, cipher).await?;
+
+    database.run_migrations().await?;
+
+    Ok(database)
+}
+
+/// Connect to a database without applying the pending migrations. Refer to
+/// the [`migrate`](crate::console::commands::migrate) command.
+///
+/// # Errors
+///
+/// This function will return an `Error::UnrecognizedDatabaseDriver` if unable to match database type.
+pub async fn connect_without_migrating(db_path: &str, cipher: Option<Arc<Cipher>>) -> Result<Box<dyn Database>, Error> {
+    connect_with_pool_settings(db_path, &PoolSettings::default(), cipher).await
+}
+
+/// Connect to a database with the given connection pool settings, without
+/// applying the pending migrations.
+///
+/// # Errors
+///
+/// This function will return an `Error::UnrecognizedDatabaseDriver` if unable to match database type.
+pub async fn connect_with_pool_settings(
+    db_path: &str,
+    pool_settings: &PoolSettings,
+    cipher: Option<Arc<Cipher>>,
+) -> Result<Box<dyn Database>, Error> {
     let db_driver = self::get_driver(db_path)?;
 
     Ok(match db_driver {
-        self::Driver::Sqlite3 => Box::new(Sqlite::new(db_path).await),
-        self::Driver::Mysql => Box::new(Mysql::new(db_path).await),
+        self::Driver::Sqlite3 => Box::new(Sqlite::new(db_path, pool_settings).await.with_cipher(cipher)),
+        self::Driver::Mysql => Box::new(Mysql::new(db_path, pool_settings).await.with_cipher(cipher)),
     })
 }
 
+/// Connect to a database with read replicas, without applying the pending
+/// migrations. Refer to the [`replicas`](crate::databases::replicas) module.
+///
+/// # Errors
+///
+/// This function will return an `Error::UnrecognizedDatabaseDriver` if unable to match database type,
+/// or an `Error::InvalidReplica` if there are replicas and the database or
+/// the replicas are not `MySQL` databases.
+pub async fn connect_with_replicas(
+    db_path: &str,
+    replica_urls: &[String],
+    max_replica_lag: Duration,
+    pool_settings: &PoolSettings,
+    cipher: Option<Arc<Cipher>>,
+) -> Result<Box<dyn Database>, Error> {
+    if replica_urls.is_empty() {
+        return connect_with_pool_settings(db_path, pool_settings, cipher).await;
+    }
+
+    if self::get_driver(db_path)? != self::Driver::Mysql {
+        return Err(Error::InvalidReplica);
+    }
+
+    let replicas = Arc::new(Replicas::connect(replica_urls, pool_settings)?);
+
+    drop(replicas::start_lag_monitor(&replicas, max_replica_lag));
+
+    Ok(Box::new(
+        Mysql::new(db_path, pool_settings)
+            .await
+            .with_cipher(cipher)
+            .with_replicas(replicas),
+    ))
+}
+
 /// Trait for database implementations.
 #[async_trait]
 pub trait Database: Sync + Send {
     /// Return current database driver.
     fn get_database_driver(&self) -> Driver;
 
-    async fn new(db_path: &str) -> Self
+    /// It checks that the database accepts queries.
+    async fn ping(&self) -> Result<(), Error>;
+
+    /// It returns the migrations of the database driver, from the oldest, and
+    /// whether they were applied.
+    async fn get_migrations(&self) -> Result<Vec<MigrationStatus>, Error>;
+
+    /// It applies the pending migrations. It returns the number of migrations
+    /// applied.
+    async fn run_migrations(&self) -> Result<usize, Error>;
+
+    /// It reverts the last applied migration and applies the pending
+    /// migrations again. Only reversible migrations can be redone. It returns
+    /// the version of the migration.
+    async fn redo_last_migration(&self) -> Result<i64, Error>;
+
+    /// It sends the rows of all the tables, except the migrations table, to
+    /// the channel. The rows are read in a single transaction, so they are a
+    /// consistent snapshot even when the index is running. It returns the
+    /// number of rows.
+    async fn dump_tables(&self, rows: mpsc::Sender<TableRow>) -> Result<u64, Error>;
+
+    /// It replaces the rows of all the tables, except the migrations table,
+    /// with the rows received from the channel, in a single transaction. The
+    /// foreign keys are not checked until all the rows are inserted. It
+    /// returns the number of rows.
+    async fn restore_tables(&self, rows: mpsc::Receiver<TableRow>) -> Result<u64, Error>;
+
+    /// It compacts the database and updates the statistics used by the query
+    /// planner. Only `SQLite` databases need it, other drivers do nothing.
+    async fn run_maintenance(&self) -> Result<(), Error>;
+
+    async fn new(db_path: &str, pool_settings: &PoolSettings) -> Self
     where
         Self: Sized;
 
@@ -164,6 +545,9 @@ pub trait Database: Sync + Send {
     /// Link a `TrackerKey` to a certain user with `user_id`.
     async fn add_tracker_key(&self, user_id: i64, tracker_key: &TrackerKey) -> Result<(), Error>;
 
+    /// Delete all the `TrackerKey`s of a user, for example, when they are revoked.
+    async fn delete_user_tracker_keys(&self, user_id: i64) -> Result<(), Error>;
+
     /// Delete user and all related user data with `user_id`.
     async fn delete_user(&self, user_id: i64) -> Result<(), Error>;
 
@@ -183,12 +567,22 @@ pub trait Database: Sync + Send {
     async fn delete_category(&self, category_name: &str) -> Result<(), Error>;
 
     /// Get results of a torrent search in a paginated and sorted form as `TorrentsResponse` from `search`, `categories`, `sort`, `offset` and `page_size`.
+    ///
+    /// If `torrent_ids` is provided, only those torrents are included in the
+    /// results and `Sorting::Relevance` keeps the same order.
+    ///
+    /// Only torrents with the given moderation `status` are included.
+    #[allow(clippy::too_many_arguments)]
     async fn get_torrents_search_sorted_paginated(
         &self,
         search: &Option<String>,
         categories: &Option<Vec<String>>,
         tags: &Option<Vec<String>>,
+        torrent_ids: &Option<Vec<i64>>,
+        status: &TorrentStatus,
+        filter: &ListingFilter,
         sort: &Sorting,
+        then_sort: &Option<Sorting>,
         offset: u64,
         page_size: u8,
     ) -> Result<TorrentsResponse, Error>;
@@ -200,6 +594,7 @@ pub trait Database: Sync + Send {
         torrent: &Torrent,
         uploader_id: UserId,
         metadata: &Metadata,
+        status: &TorrentStatus,
     ) -> Result<i64, Error>;
 
     /// Get `Torrent` from `InfoHash`.
@@ -214,13 +609,19 @@ pub trait Database: Sync + Send {
 
         let torrent_nodes = self.get_torrent_nodes_from_id(db_torrent.torrent_id).await?;
 
-        Ok(Torrent::from_database(
+        let torrent_web_seeds = self.get_torrent_web_seeds(db_torrent.torrent_id).await?;
+
+        let mut torrent = Torrent::from_database(
             &db_torrent,
             &torrent_files,
             torrent_announce_urls,
             torrent_http_seed_urls,
             torrent_nodes,
-        ))
+        );
+
+        torrent.set_url_list(torrent_web_seeds.into_iter().map(|web_seed| web_seed.url).collect());
+
+        Ok(torrent)
     }
 
     /// Get `Torrent` from `torrent_id`.
@@ -235,13 +636,19 @@ pub trait Database: Sync + Send {
 
         let torrent_nodes = self.get_torrent_nodes_from_id(db_torrent.torrent_id).await?;
 
-        Ok(Torrent::from_database(
+        let torrent_web_seeds = self.get_torrent_web_seeds(db_torrent.torrent_id).await?;
+
+        let mut torrent = Torrent::from_database(
             &db_torrent,
             &torrent_files,
             torrent_announce_urls,
             torrent_http_seed_urls,
             torrent_nodes,
-        ))
+        );
+
+        torrent.set_url_list(torrent_web_seeds.into_iter().map(|web_seed| web_seed.url).collect());
+
+        Ok(torrent)
     }
 
     /// It returns the list of all infohashes producing the same canonical
@@ -272,7 +679,8 @@ pub trait Database: Sync + Send {
     /// Get torrent's info as `DbTorrentInfo` from `torrent_id`.
     async fn get_torrent_info_from_id(&self, torrent_id: i64) -> Result<DbTorrent, Error>;
 
-    /// Get torrent's info as `DbTorrentInfo` from torrent `InfoHash`.
+    /// Get torrent's info as `DbTorrentInfo` from torrent `InfoHash`. Torrents
+    /// in the trash bin are not found.
     async fn get_torrent_info_from_info_hash(&self, info_hash: &InfoHash) -> Result<DbTorrent, Error>;
 
     /// Get all torrent's files as `Vec<TorrentFile>` from `torrent_id`.
@@ -287,18 +695,21 @@ pub trait Database: Sync + Send {
     /// Get all torrent's nodes as `Vec<(String, i64)>` from `torrent_id`.
     async fn get_torrent_nodes_from_id(&self, torrent_id: i64) -> Result<Vec<(String, i64)>, Error>;
 
-    /// Get `TorrentListing` from `torrent_id`.
+    /// Get `TorrentListing` from `torrent_id`. Torrents in the trash bin are
+    /// not found.
     async fn get_torrent_listing_from_id(&self, torrent_id: i64) -> Result<TorrentListing, Error>;
 
-    /// Get `TorrentListing` from `InfoHash`.
+    /// Get `TorrentListing` from `InfoHash`. Torrents in the trash bin are not
+    /// found.
     async fn get_torrent_listing_from_info_hash(&self, info_hash: &InfoHash) -> Result<TorrentListing, Error>;
 
     /// Get all torrents as `Vec<TorrentCompact>`.
     async fn get_all_torrents_compact(&self) -> Result<Vec<TorrentCompact>, Error>;
 
-    /// Get torrents whose stats have not been imported from the tracker at least since a given datetime.
+    /// Get torrents after `after_torrent_id`, ordered by `torrent_id`, whose stats have not been imported from the tracker at least since a given datetime.
     async fn get_torrents_with_stats_not_updated_since(
         &self,
+        after_torrent_id: TorrentId,
         datetime: DateTime<Utc>,
         limit: i64,
     ) -> Result<Vec<TorrentCompact>, Error>;
@@ -345,6 +756,808 @@ pub trait Database: Sync + Send {
     /// Delete a torrent with `torrent_id`.
     async fn delete_torrent(&self, torrent_id: i64) -> Result<(), Error>;
 
+    /// Add a new event to the domain event outbox and return its `event_id`.
+    async fn insert_domain_event(&self, event_type: &str, payload: &str) -> Result<EventId, Error>;
+
+    /// Get up to `limit` domain events with an `event_id` greater than `event_id`, in order.
+    async fn get_domain_events_after(&self, event_id: EventId, limit: u32) -> Result<Vec<DbDomainEvent>, Error>;
+
+    /// Get the last event processed by the domain event `consumer`. It's `0` if it has not processed any event yet.
+    async fn get_domain_event_consumer_cursor(&self, consumer: &str) -> Result<EventId, Error>;
+
+    /// Save the last event processed by the domain event `consumer`.
+    async fn update_domain_event_consumer_cursor(&self, consumer: &str, event_id: EventId) -> Result<(), Error>;
+
+    /// Add a new comment to a torrent and return its `comment_id`.
+    async fn insert_comment(&self, torrent_id: TorrentId, author_id: UserId, text: &str) -> Result<CommentId, Error>;
+
+    /// Get a comment from its `comment_id`.
+    async fn get_comment(&self, comment_id: CommentId) -> Result<Comment, Error>;
+
+    /// Get a page of the torrent comments, ordered from the oldest to the newest.
+    async fn get_torrent_comments_paginated(
+        &self,
+        torrent_id: TorrentId,
+        include_hidden: bool,
+        offset: u64,
+        limit: u8,
+    ) -> Result<CommentsResponse, Error>;
+
+    /// Update the text of a comment.
+    async fn update_comment_text(&self, comment_id: CommentId, text: &str) -> Result<(), Error>;
+
+    /// Hide or show a comment.
+    async fn update_comment_hidden(&self, comment_id: CommentId, hidden: bool) -> Result<(), Error>;
+
+    /// Delete a comment.
+    async fn delete_comment(&self, comment_id: CommentId) -> Result<(), Error>;
+
+    /// Update the moderation status of a torrent.
+    async fn update_torrent_status(&self, torrent_id: TorrentId, status: &TorrentStatus) -> Result<(), Error>;
+
+    /// Add a new report about a torrent and return its ID.
+    async fn insert_report(
+        &self,
+        torrent_id: TorrentId,
+        reporter_id: UserId,
+        reason: &ReportReason,
+        details: Option<&str>,
+    ) -> Result<ReportId, Error>;
+
+    /// Get a report.
+    async fn get_report(&self, report_id: ReportId) -> Result<Report, Error>;
+
+    /// Get a page of reports, from the oldest to the newest, optionally
+    /// filtered by whether they are resolved or not.
+    async fn get_reports_paginated(&self, resolved: Option<bool>, offset: u64, limit: u8) -> Result<ReportsResponse, Error>;
+
+    /// Mark a report as resolved.
+    async fn resolve_report(&self, report_id: ReportId, resolved_by: UserId, resolution: &ReportAction) -> Result<(), Error>;
+
+    /// Bookmark a torrent for a user. Bookmarking a torrent twice has no effect.
+    async fn insert_bookmark(&self, user_id: UserId, torrent_id: TorrentId) -> Result<(), Error>;
+
+    /// Remove a torrent from the user bookmarks.
+    async fn delete_bookmark(&self, user_id: UserId, torrent_id: TorrentId) -> Result<(), Error>;
+
+    /// Whether the user has bookmarked the torrent or not.
+    async fn is_torrent_bookmarked(&self, user_id: UserId, torrent_id: TorrentId) -> Result<bool, Error>;
+
+    /// Get the IDs of the torrents bookmarked by a user, from the newest
+    /// bookmark to the oldest.
+    async fn get_user_bookmarked_torrent_ids(&self, user_id: UserId) -> Result<Vec<TorrentId>, Error>;
+
+    /// Subscribe a user to the new torrents in a category or with a tag and
+    /// return the `subscription_id`.
+    async fn insert_subscription(
+        &self,
+        user_id: UserId,
+        category_id: Option<CategoryId>,
+        tag_id: Option<TagId>,
+    ) -> Result<SubscriptionId, Error>;
+
+    /// Get a subscription from its `subscription_id`.
+    async fn get_subscription(&self, subscription_id: SubscriptionId) -> Result<Subscription, Error>;
+
+    /// Get all the subscriptions of a user.
+    async fn get_user_subscriptions(&self, user_id: UserId) -> Result<Vec<Subscription>, Error>;
+
+    /// Get the subscriptions of all users.
+    async fn get_all_subscriptions(&self) -> Result<Vec<Subscription>, Error>;
+
+    /// Delete a subscription.
+    async fn delete_subscription(&self, subscription_id: SubscriptionId) -> Result<(), Error>;
+
+    /// Get a cheap value that changes when torrents are added or removed or
+    /// when the tracker stats are updated.
+    async fn get_torrent_listing_version(&self) -> Result<String, Error>;
+    /// Replace the torrent's announce urls keeping the tier structure.
+    async fn update_torrent_announce_urls(&self, torrent_id: i64, tiers: &[Vec<String>]) -> Result<(), Error>;
+    /// Add one download to the daily counter for the client family and user
+    /// agent. The user agent is empty when raw user agents are not stored.
+    async fn increment_download_client_stats(&self, date: &str, client_family: &str, user_agent: &str) -> Result<(), Error>;
+
+    /// Get the number of downloads by client family since the date
+    /// (inclusive), from the most used family to the least used one.
+    async fn get_download_client_family_stats(&self, since: &str) -> Result<Vec<DownloadCount>, Error>;
+
+    /// Get the number of downloads by raw user agent since the date
+    /// (inclusive), from the most used user agent to the least used one.
+    async fn get_download_user_agent_stats(&self, since: &str, limit: u32) -> Result<Vec<DownloadCount>, Error>;
+
+    /// Add a new torrent uploaded as a magnet link, with the `resolving`
+    /// status.
+    #[allow(clippy::too_many_arguments)]
+    async fn insert_magnet_upload(
+        &self,
+        user_id: UserId,
+        info_hash: &str,
+        magnet_uri: &str,
+        title: &str,
+        description: &str,
+        category_id: CategoryId,
+        tag_ids: &str,
+    ) -> Result<MagnetUploadId, Error>;
+
+    /// Get a torrent uploaded as a magnet link.
+    async fn get_magnet_upload(&self, magnet_upload_id: MagnetUploadId) -> Result<MagnetUpload, Error>;
+
+    /// Whether there is a magnet upload being resolved for the info-hash.
+    async fn is_magnet_upload_resolving(&self, info_hash: &str) -> Result<bool, Error>;
+
+    /// Get the oldest magnet uploads with the `resolving` status.
+    async fn get_resolving_magnet_uploads(&self, limit: u32) -> Result<Vec<MagnetUpload>, Error>;
+
+    /// Update the status of a magnet upload.
+    async fn update_magnet_upload(
+        &self,
+        magnet_upload_id: MagnetUploadId,
+        status: &MagnetUploadStatus,
+        torrent_id: Option<TorrentId>,
+        attempts: i64,
+        error: Option<&str>,
+    ) -> Result<(), Error>;
+
+    /// Get the most recent torrents whose size is in the range (inclusive).
+    async fn get_torrents_by_size_range(&self, min_size: i64, max_size: i64, limit: u32) -> Result<Vec<SimilarTorrent>, Error>;
+
+    /// Get a page of users, sorted by ID.
+    async fn get_users_paginated(&self, offset: u64, limit: u32) -> Result<Vec<UserFull>, Error>;
+
+    /// Update the upload date of a torrent. It's used to preserve the
+    /// original date when torrents are imported from another index.
+    async fn update_torrent_date_uploaded(&self, torrent_id: TorrentId, date_uploaded: &str) -> Result<(), Error>;
+
+    /// Record the date of the last login of a user. It also clears the
+    /// inactivity warning.
+    async fn update_user_last_login(&self, user_id: UserId) -> Result<(), Error>;
+
+    /// Get the users in a segment with a verified email. Users who
+    /// unsubscribed from non-essential emails are only included when
+    /// `include_unsubscribed` is `true`.
+    async fn get_email_recipients(&self, segment: &Segment, include_unsubscribed: bool) -> Result<Vec<Recipient>, Error>;
+
+    /// Unsubscribe a user from non-essential emails.
+    async fn insert_email_unsubscribe(&self, user_id: UserId) -> Result<(), Error>;
+
+    /// Add an email to the outbound email queue and return the `email_id`.
+    async fn insert_queued_email(&self, recipient: &str, subject: &str, body: &str) -> Result<EmailId, Error>;
+
+    /// Get the oldest emails in the outbound email queue.
+    async fn get_queued_emails(&self, limit: u32) -> Result<Vec<QueuedEmail>, Error>;
+
+    /// Increase the number of failed delivery attempts of a queued email.
+    async fn increase_queued_email_attempts(&self, email_id: EmailId) -> Result<(), Error>;
+
+    /// Remove an email from the outbound email queue.
+    async fn delete_queued_email(&self, email_id: EmailId) -> Result<(), Error>;
+
+    /// Get the users, excluding administrators, disabled users and exempted
+    /// users, whose last activity is older than `since`, from the least to
+    /// the most recently active.
+    async fn get_inactive_users(&self, since: &str) -> Result<Vec<InactiveUser>, Error>;
+
+    /// Record that a user was warned about the inactivity.
+    async fn update_user_inactivity_warning(&self, user_id: UserId) -> Result<(), Error>;
+
+    /// Disable or enable a user account.
+    async fn update_user_disabled(&self, user_id: UserId, disabled: bool) -> Result<(), Error>;
+
+    /// Check if a user account is disabled.
+    async fn is_user_disabled(&self, user_id: UserId) -> Result<bool, Error>;
+
+    /// Exempt a user from the inactivity policy.
+    async fn insert_inactivity_exemption(&self, user_id: UserId) -> Result<(), Error>;
+
+    /// Remove the inactivity policy exemption of a user.
+    async fn delete_inactivity_exemption(&self, user_id: UserId) -> Result<(), Error>;
+
+    /// Get the usernames of the users exempted from the inactivity policy.
+    async fn get_inactivity_exemptions(&self) -> Result<Vec<String>, Error>;
+
+    /// Register an image uploaded to the external storage.
+    async fn insert_image(
+        &self,
+        user_id: UserId,
+        kind: ImageKind,
+        object_key: &str,
+        url: &str,
+        content_type: &str,
+        size: u64,
+    ) -> Result<ImageId, Error>;
+
+    /// Change the avatar URL of a user.
+    async fn update_user_avatar(&self, user_id: UserId, avatar: &str) -> Result<(), Error>;
+
+    /// Change the email of a user. It's encrypted if the encryption is
+    /// enabled.
+    async fn update_user_email(&self, user_id: UserId, email: &str) -> Result<(), Error>;
+
+    /// Get the torrents bookmarked by a user, from the newest bookmark to the
+    /// oldest.
+    async fn get_user_bookmarks(&self, user_id: UserId) -> Result<Vec<Bookmark>, Error>;
+
+    /// Bookmark a torrent for a user with the given date. It returns `false`
+    /// if the torrent was already bookmarked.
+    async fn insert_bookmark_with_date(&self, user_id: UserId, torrent_id: TorrentId, date_created: &str) -> Result<bool, Error>;
+
+    /// Add a new collection and return its ID.
+    async fn insert_collection(&self, user_id: UserId, name: &str, description: Option<&str>) -> Result<CollectionId, Error>;
+
+    /// Get a collection with the aggregate stats of its approved torrents.
+    async fn get_collection(&self, collection_id: CollectionId) -> Result<Collection, Error>;
+
+    /// Get the collections of a user.
+    async fn get_user_collections(&self, user_id: UserId) -> Result<Vec<Collection>, Error>;
+
+    /// Change the collection name and description.
+    async fn update_collection(&self, collection_id: CollectionId, name: &str, description: Option<&str>) -> Result<(), Error>;
+
+    /// Delete a collection and its items.
+    async fn delete_collection(&self, collection_id: CollectionId) -> Result<(), Error>;
+
+    /// Add a torrent to a collection. Adding a torrent twice has no effect.
+    async fn insert_collection_item(&self, collection_id: CollectionId, torrent_id: TorrentId) -> Result<(), Error>;
+
+    /// Remove a torrent from a collection.
+    async fn delete_collection_item(&self, collection_id: CollectionId, torrent_id: TorrentId) -> Result<(), Error>;
+
+    /// Get the IDs of the torrents in a collection, from the newest item to
+    /// the oldest.
+    async fn get_collection_torrent_ids(&self, collection_id: CollectionId) -> Result<Vec<TorrentId>, Error>;
+
+    /// Delete the rows of a table with a date older than the cutoff date and
+    /// return the number of deleted rows.
+    ///
+    /// The table and column names are not escaped. They must not come from
+    /// user input.
+    async fn delete_rows_older_than(&self, table: &str, date_column: &str, cutoff: &str) -> Result<u64, Error>;
+
+    /// Get the number of uploads of a user since a date and the total size
+    /// of the contents of all their torrents.
+    async fn get_user_upload_usage(&self, user_id: UserId, since: &str) -> Result<UploadUsage, Error>;
+
+    /// Get the upload limits set for a user by an administrator, if any.
+    async fn get_user_upload_limits(&self, user_id: UserId) -> Result<Option<UploadLimits>, Error>;
+
+    /// Set the upload limits of a user, replacing the previous ones.
+    async fn upsert_user_upload_limits(&self, user_id: UserId, limits: &UploadLimits) -> Result<(), Error>;
+
+    /// Remove the upload limits set for a user.
+    async fn delete_user_upload_limits(&self, user_id: UserId) -> Result<(), Error>;
+
+    /// Make a user a moderator of a category.
+    async fn insert_category_moderator(&self, category_id: CategoryId, user_id: UserId) -> Result<(), Error>;
+
+    /// Remove a moderator from a category.
+    async fn delete_category_moderator(&self, category_id: CategoryId, user_id: UserId) -> Result<(), Error>;
+
+    /// Get the usernames of the moderators of a category.
+    async fn get_category_moderators(&self, category_id: CategoryId) -> Result<Vec<String>, Error>;
+
+    /// Get the categories moderated by a user.
+    async fn get_moderated_categories(&self, user_id: UserId) -> Result<Vec<Category>, Error>;
+
+    /// Check if a user is a moderator of a category.
+    async fn is_category_moderator(&self, category_id: CategoryId, user_id: UserId) -> Result<bool, Error>;
+
+    /// Get the public profile of a user from the username.
+    async fn get_public_user_profile(&self, username: &str) -> Result<PublicProfile, Error>;
+
+    /// Update the user's profile fields. `None` values clear the fields.
+    async fn update_user_profile(&self, user_id: UserId, bio: Option<&str>, avatar: Option<&str>) -> Result<(), Error>;
+
+    /// Get the IDs of the approved torrents uploaded by a user, from the
+    /// newest to the oldest.
+    async fn get_user_uploaded_torrent_ids(&self, user_id: UserId) -> Result<Vec<TorrentId>, Error>;
+
+    /// Add the API usage counters of a user's credential in a day to the
+    /// stored ones.
+    async fn add_user_api_usage(&self, record: &ApiUsageRecord) -> Result<(), Error>;
+
+    /// Get the API usage of a user since a day (`YYYY-MM-DD`), from the
+    /// oldest day.
+    async fn get_user_api_usage(&self, user_id: UserId, since: &str) -> Result<Vec<ApiUsageRecord>, Error>;
+
+    /// Get up to `limit` approved torrents with an ID greater than
+    /// `after_torrent_id`, ordered by ID, with the data mirrors sync.
+    async fn get_sync_torrents(&self, after_torrent_id: TorrentId, limit: u32) -> Result<Vec<SyncTorrent>, Error>;
+
+    /// Get the last synced state of all torrents.
+    async fn get_sync_states(&self) -> Result<Vec<SyncState>, Error>;
+
+    /// Get the sequence of the last synced change, or `0` if there are none.
+    async fn get_last_sync_sequence(&self) -> Result<Sequence, Error>;
+
+    /// Insert or update the synced state of the torrents.
+    async fn upsert_sync_states(&self, states: &[SyncState]) -> Result<(), Error>;
+
+    /// Get the summary of all the partitions.
+    async fn get_sync_partitions(&self) -> Result<Vec<SyncPartitionSummary>, Error>;
+
+    /// Get the synced states of a partition changed after a sequence.
+    async fn get_sync_partition_changes(&self, partition_id: PartitionId, since: Sequence) -> Result<Vec<SyncState>, Error>;
+
+    /// Set the filter of a smart collection, marking the collection as smart.
+    async fn upsert_smart_collection_filter(&self, collection_id: CollectionId, filter: &SmartFilter) -> Result<(), Error>;
+
+    /// Get the filter of a smart collection, if it's a smart collection.
+    async fn get_smart_collection_filter(&self, collection_id: CollectionId) -> Result<Option<SmartFilter>, Error>;
+
+    /// Get the IDs and filters of all the smart collections.
+    async fn get_smart_collection_filters(&self) -> Result<Vec<(CollectionId, SmartFilter)>, Error>;
+
+    /// Replace the items of a smart collection with the newest approved
+    /// torrents matching the filter, up to `max_items`. `uploaded_since`
+    /// replaces `uploaded_after` when it's later. It returns the number of
+    /// items.
+    async fn materialize_smart_collection(
+        &self,
+        collection_id: CollectionId,
+        filter: &SmartFilter,
+        uploaded_since: Option<&str>,
+        max_items: u32,
+    ) -> Result<u64, Error>;
+
+    /// Add an entry to the audit log and return its ID.
+    async fn insert_audit_entry(
+        &self,
+        actor_id: Option<UserId>,
+        action: &AuditAction,
+        target: &str,
+        details: Option<&str>,
+    ) -> Result<AuditEntryId, Error>;
+
+    /// Get a page of the audit log, from the newest to the oldest entry.
+    async fn get_audit_log_paginated(&self, filter: &AuditLogFilter, offset: u64, limit: u8) -> Result<AuditLogResponse, Error>;
+
+    /// Get the moderation policy of a category. Categories without a policy
+    /// have the default one.
+    async fn get_category_policy(&self, category_id: CategoryId) -> Result<CategoryPolicy, Error>;
+
+    /// Insert or update the moderation policy of a category.
+    async fn upsert_category_policy(&self, category_id: CategoryId, policy: &CategoryPolicy) -> Result<(), Error>;
+
+    /// Add the approval of a staff member to a torrent. It returns `false` if
+    /// the user had already approved the torrent.
+    async fn insert_torrent_approval(&self, torrent_id: TorrentId, user_id: UserId) -> Result<bool, Error>;
+
+    /// Get the number of staff members who approved a torrent.
+    async fn count_torrent_approvals(&self, torrent_id: TorrentId) -> Result<u64, Error>;
+
+    /// Delete all the approvals of a torrent.
+    async fn delete_torrent_approvals(&self, torrent_id: TorrentId) -> Result<(), Error>;
+
+    /// Get the last torrent processed by the `importer` in the current pass. It's `0` if a new pass has to start.
+    async fn get_importer_cursor(&self, importer: &str) -> Result<TorrentId, Error>;
+
+    /// Save the last torrent processed by the `importer`.
+    async fn update_importer_cursor(&self, importer: &str, torrent_id: TorrentId) -> Result<(), Error>;
+
+    /// Add a preview of a file in a torrent.
+    async fn insert_torrent_preview(
+        &self,
+        torrent_id: TorrentId,
+        file_index: i64,
+        path: &str,
+        content_type: &str,
+        content: &[u8],
+    ) -> Result<PreviewId, Error>;
+
+    /// Get the previews of a torrent, without their contents.
+    async fn get_torrent_previews(&self, torrent_id: TorrentId) -> Result<Vec<TorrentPreview>, Error>;
+
+    /// Get the content type and the contents of a torrent preview.
+    async fn get_torrent_preview_content(&self, torrent_id: TorrentId, preview_id: PreviewId)
+        -> Result<(String, Vec<u8>), Error>;
+
+    /// Replace the file groups of a torrent.
+    async fn update_torrent_file_groups(&self, torrent_id: TorrentId, groups: &[TorrentFileGroup]) -> Result<(), Error>;
+
+    /// Get the file groups of a torrent in the order they were declared.
+    async fn get_torrent_file_groups(&self, torrent_id: TorrentId) -> Result<Vec<TorrentFileGroup>, Error>;
+    /// Increment the `.torrent` downloads of a torrent for a date.
+    async fn increment_torrent_download_stats(&self, info_hash: &str, date: &str) -> Result<(), Error>;
+
+    /// Get the uploads and downloads of the torrents with each tag from the
+    /// `since` date (included) to the `until` date (excluded).
+    async fn get_tag_activity(&self, since: &str, until: &str) -> Result<Vec<TagActivity>, Error>;
+
+    /// Merge the `source` tag into the `target` tag in a single transaction.
+    /// The torrents with the `source` tag get the `target` tag, the `source`
+    /// synonyms are moved to the `target` tag, and the `source` tag is
+    /// deleted and its name kept as a synonym of the `target` tag.
+    async fn merge_tags(&self, source_tag_id: TagId, target_tag_id: TagId) -> Result<(), Error>;
+
+    /// Add a synonym for a tag. It fails with `TagNotFound` if the tag does
+    /// not exist.
+    async fn insert_tag_synonym(&self, name: &str, tag_id: TagId) -> Result<(), Error>;
+
+    /// Delete a tag synonym.
+    async fn delete_tag_synonym(&self, name: &str) -> Result<(), Error>;
+
+    /// Get all the tag synonyms.
+    async fn get_tag_synonyms(&self) -> Result<Vec<TagSynonym>, Error>;
+
+    /// Get the tag a synonym points to.
+    async fn get_tag_from_synonym(&self, name: &str) -> Result<TorrentTag, Error>;
+
+    /// Get the approved torrents uploaded before the date that the torrent
+    /// rule has not been applied to.
+    async fn get_torrent_rule_candidates(&self, rule: &str, uploaded_before: &str) -> Result<Vec<TorrentRuleCandidate>, Error>;
+
+    /// Record that a torrent rule has been applied to a torrent.
+    async fn insert_torrent_rule_application(&self, torrent_id: TorrentId, rule: &str) -> Result<(), Error>;
+
+    /// Add a new saved search and return its `saved_search_id`.
+    async fn insert_saved_search(
+        &self,
+        user_id: UserId,
+        name: &str,
+        query: &str,
+        category: Option<&str>,
+        tags: &[String],
+        email_alerts: bool,
+    ) -> Result<SavedSearchId, Error>;
+
+    /// Get a saved search from its `saved_search_id`.
+    async fn get_saved_search(&self, saved_search_id: SavedSearchId) -> Result<SavedSearch, Error>;
+
+    /// Get all the saved searches of a user.
+    async fn get_user_saved_searches(&self, user_id: UserId) -> Result<Vec<SavedSearch>, Error>;
+
+    /// Get the saved searches of all users.
+    async fn get_all_saved_searches(&self) -> Result<Vec<SavedSearch>, Error>;
+
+    /// Delete a saved search and its results.
+    async fn delete_saved_search(&self, saved_search_id: SavedSearchId) -> Result<(), Error>;
+
+    /// Add a torrent to the results of a saved search. It does nothing if the
+    /// torrent is already in the results.
+    async fn insert_saved_search_result(&self, saved_search_id: SavedSearchId, torrent_id: TorrentId) -> Result<(), Error>;
+
+    /// Get the torrents in the results of a saved search, the newest match
+    /// first.
+    async fn get_saved_search_result_ids(&self, saved_search_id: SavedSearchId) -> Result<Vec<TorrentId>, Error>;
+
+    /// Add a new import session and return its ID.
+    async fn insert_import_session(
+        &self,
+        user_id: UserId,
+        source: &str,
+        conflict_policy: &str,
+        mapping: Option<&str>,
+    ) -> Result<ImportSessionId, Error>;
+
+    /// Get an import session with the totals of its processed batches.
+    async fn get_import_session(&self, import_id: ImportSessionId) -> Result<ImportSession, Error>;
+
+    /// Mark an import session as completed.
+    async fn complete_import_session(&self, import_id: ImportSessionId) -> Result<(), Error>;
+
+    /// Get the IDs of the processed batches of an import session, in
+    /// processing order.
+    async fn get_import_batch_ids(&self, import_id: ImportSessionId) -> Result<Vec<String>, Error>;
+
+    /// Get the summary of a batch, if it was already processed.
+    async fn get_import_batch_summary(&self, import_id: ImportSessionId, batch_id: &str) -> Result<Option<String>, Error>;
+
+    /// Add a processed batch. It returns `false` if the batch was already
+    /// processed.
+    async fn insert_import_batch(&self, import_id: ImportSessionId, batch: &ImportBatch) -> Result<bool, Error>;
+
+    /// Get the domain events of a type published after the event `event_id`.
+    async fn get_domain_events_of_type_after(
+        &self,
+        event_type: &str,
+        event_id: EventId,
+        limit: u32,
+    ) -> Result<Vec<DbDomainEvent>, Error>;
+
+    /// Get the seeding stats of the approved torrents of each uploader with
+    /// at least one seeded torrent.
+    async fn get_uploader_seeding_stats(&self) -> Result<Vec<UploaderSeedingStats>, Error>;
+
+    /// Add a points adjustment and update the user balance. It returns
+    /// `false` if the user already has an adjustment with the same reference.
+    async fn insert_points_adjustment(
+        &self,
+        user_id: UserId,
+        amount: i64,
+        reason: &str,
+        reference: Option<&str>,
+    ) -> Result<bool, Error>;
+
+    /// Get the points balance of a user.
+    async fn get_points_balance(&self, user_id: UserId) -> Result<i64, Error>;
+
+    /// Get the latest points adjustments of a user, newest first.
+    async fn get_points_adjustments(&self, user_id: UserId, limit: u32) -> Result<Vec<PointsAdjustment>, Error>;
+
+    /// Add an image stored in the image cache, or update its size.
+    async fn upsert_image_cache_entry(&self, cache_key: &str, size: i64) -> Result<(), Error>;
+
+    /// Set the last access time of the images in the image cache to now.
+    async fn touch_image_cache_entries(&self, cache_keys: &[String]) -> Result<(), Error>;
+
+    /// Get the total size of the images in the image cache.
+    async fn get_image_cache_size(&self) -> Result<i64, Error>;
+
+    /// Get the key and size of the least recently accessed images in the
+    /// image cache.
+    async fn get_least_recently_accessed_image_cache_entries(&self, limit: u32) -> Result<Vec<(String, i64)>, Error>;
+
+    /// Remove an image from the image cache entries.
+    async fn delete_image_cache_entry(&self, cache_key: &str) -> Result<(), Error>;
+
+    /// Add an image to be embedded in the description of a torrent.
+    async fn insert_torrent_description_image(
+        &self,
+        torrent_id: TorrentId,
+        uploader_id: UserId,
+        cache_key: &str,
+        file_name: &str,
+        content_type: &str,
+        size: i64,
+    ) -> Result<ImageId, Error>;
+
+    /// Get the images uploaded for the description of a torrent.
+    async fn get_torrent_description_images(&self, torrent_id: TorrentId) -> Result<Vec<DescriptionImage>, Error>;
+
+    /// Get the content type and the image cache key of an image uploaded for
+    /// the description of a torrent.
+    async fn get_torrent_description_image_key(
+        &self,
+        torrent_id: TorrentId,
+        image_id: ImageId,
+    ) -> Result<(String, String), Error>;
+
+    /// Attach an auxiliary file to a torrent.
+    async fn insert_torrent_attachment(
+        &self,
+        torrent_id: TorrentId,
+        uploader_id: UserId,
+        kind: AttachmentKind,
+        file_name: &str,
+        content_type: &str,
+        content: &[u8],
+    ) -> Result<AttachmentId, Error>;
+
+    /// Get the files attached to a torrent, without their contents.
+    async fn get_torrent_attachments(&self, torrent_id: TorrentId) -> Result<Vec<TorrentAttachment>, Error>;
+
+    /// Get the content type and the contents of a file attached to a torrent.
+    async fn get_torrent_attachment_content(
+        &self,
+        torrent_id: TorrentId,
+        attachment_id: AttachmentId,
+    ) -> Result<(String, Vec<u8>), Error>;
+
+    /// Remove a file attached to a torrent.
+    async fn delete_torrent_attachment(&self, torrent_id: TorrentId, attachment_id: AttachmentId) -> Result<(), Error>;
+
+    /// Get the torrents with files of the given sizes, from the one with
+    /// the most matching files.
+    async fn get_torrents_with_file_lengths(&self, lengths: &[i64], limit: u32) -> Result<Vec<TorrentId>, Error>;
+
+    /// Record that the contents of an uploaded torrent overlap with an
+    /// existing torrent.
+    async fn insert_torrent_duplicate(&self, torrent_id: TorrentId, duplicate_of: TorrentId, overlap: f64) -> Result<(), Error>;
+
+    /// Get all the detected duplicates, from the most recent to the oldest.
+    async fn get_torrent_duplicates(&self) -> Result<Vec<DuplicatePair>, Error>;
+
+    /// Get the torrents in any detected duplicate.
+    async fn get_duplicated_torrents(&self) -> Result<Vec<SimilarTorrent>, Error>;
+
+    /// Move a torrent to the trash bin. The torrent keeps its previous
+    /// moderation status in the trash bin so that it can be restored.
+    async fn move_torrent_to_trash(&self, torrent_id: TorrentId, deleted_by: Option<UserId>) -> Result<(), Error>;
+
+    /// Restore a torrent from the trash bin with its previous moderation
+    /// status.
+    async fn restore_torrent_from_trash(&self, torrent_id: TorrentId) -> Result<(), Error>;
+
+    /// Get a torrent in the trash bin.
+    async fn get_trashed_torrent(&self, torrent_id: TorrentId) -> Result<TrashedTorrent, Error>;
+
+    /// Get the torrents in the trash bin, from the most recently deleted.
+    async fn get_trashed_torrents(&self) -> Result<Vec<TrashedTorrent>, Error>;
+
+    /// Get the torrents moved to the trash bin before the given date, from
+    /// the oldest.
+    async fn get_torrents_trashed_before(&self, date: &str, limit: u32) -> Result<Vec<TrashedTorrent>, Error>;
+
+    /// Schedule the release of a torrent. The torrent gets the given status
+    /// when it's released.
+    async fn insert_torrent_schedule(
+        &self,
+        torrent_id: TorrentId,
+        publish_at: &str,
+        status_on_publish: &TorrentStatus,
+    ) -> Result<(), Error>;
+
+    /// Get the scheduled torrents whose release date is before the given
+    /// date, from the oldest.
+    async fn get_due_torrent_schedules(&self, date: &str, limit: u32) -> Result<Vec<ScheduledRelease>, Error>;
+
+    /// Release a scheduled torrent. It gets the status it was scheduled with
+    /// and its upload date is set to the release date.
+    async fn publish_scheduled_torrent(&self, torrent_id: TorrentId) -> Result<(), Error>;
+
+    /// Get the current seeders and the health of all the approved torrents.
+    async fn get_torrent_health_samples(&self) -> Result<Vec<TorrentHealthSample>, Error>;
+
+    /// Save the health of a torrent. The flag is cleared when the torrent is
+    /// seeded again.
+    async fn upsert_torrent_health(
+        &self,
+        torrent_id: TorrentId,
+        health_score: f64,
+        zero_seeders_since: Option<&str>,
+    ) -> Result<(), Error>;
+
+    /// Get the approved torrents without seeders since before the given
+    /// date, from the oldest.
+    async fn get_dead_torrents(&self, zero_seeders_before: &str) -> Result<Vec<DeadTorrent>, Error>;
+
+    /// Flag a torrent as dead.
+    async fn flag_dead_torrent(&self, torrent_id: TorrentId) -> Result<(), Error>;
+
+    /// Delete the health of a torrent.
+    async fn delete_torrent_health(&self, torrent_id: TorrentId) -> Result<(), Error>;
+
+    /// Apply a moderation action to many torrents in a single transaction.
+    /// No torrent is changed if it fails for any of them.
+    async fn apply_bulk_action(&self, torrent_ids: &[TorrentId], action: &BulkAction) -> Result<(), Error>;
+
+    /// Add a banned pattern.
+    async fn insert_banned_pattern(&self, pattern: &str, action: &PatternAction, created_by: UserId) -> Result<PatternId, Error>;
+
+    /// Delete a banned pattern.
+    async fn delete_banned_pattern(&self, pattern_id: PatternId) -> Result<(), Error>;
+
+    /// Get all the banned patterns, from the oldest.
+    async fn get_banned_patterns(&self) -> Result<Vec<BannedPattern>, Error>;
+
+    /// Add a session of a user.
+    async fn insert_user_session(
+        &self,
+        session_id: &str,
+        user_id: UserId,
+        user_agent: Option<&str>,
+        ip: Option<&str>,
+        expires_at: &str,
+    ) -> Result<(), Error>;
+
+    /// Get a session by its ID.
+    async fn get_user_session(&self, session_id: &str) -> Result<UserSession, Error>;
+
+    /// Get the sessions of a user which expire after the given date, from the
+    /// most recently used.
+    async fn get_user_sessions(&self, user_id: UserId, expires_after: &str) -> Result<Vec<UserSession>, Error>;
+
+    /// Update the last time a session was used.
+    async fn update_user_session_last_seen(&self, session_id: &str, last_seen: &str) -> Result<(), Error>;
+
+    /// Update the expiration date of a session, when its token is renewed.
+    async fn update_user_session_expiration(&self, session_id: &str, expires_at: &str) -> Result<(), Error>;
+
+    /// Delete a session of a user.
+    async fn delete_user_session(&self, session_id: &str, user_id: UserId) -> Result<(), Error>;
+
+    /// Delete the sessions which expired before the given date.
+    async fn delete_expired_user_sessions(&self, expires_before: &str) -> Result<u64, Error>;
+
+    /// Get the pieces of a torrent, in hex. They are empty when they are
+    /// kept in another torrent file store.
+    async fn get_torrent_pieces(&self, info_hash: &InfoHash) -> Result<Option<String>, Error>;
+
+    /// Update the pieces of a torrent, in hex.
+    async fn update_torrent_pieces(&self, info_hash: &InfoHash, pieces: &str) -> Result<(), Error>;
+
+    /// Add an HTTP web seed to a torrent.
+    async fn insert_torrent_web_seed(&self, torrent_id: TorrentId, url: &str, added_by: UserId) -> Result<WebSeedId, Error>;
+
+    /// Get the HTTP web seeds of a torrent, in the order they were added.
+    async fn get_torrent_web_seeds(&self, torrent_id: TorrentId) -> Result<Vec<TorrentWebSeed>, Error>;
+
+    /// Remove an HTTP web seed from a torrent.
+    async fn delete_torrent_web_seed(&self, torrent_id: TorrentId, web_seed_id: WebSeedId) -> Result<(), Error>;
+
+    /// Add or replace the metadata of a torrent fetched from an external
+    /// provider.
+    async fn upsert_torrent_metadata(&self, metadata: &TorrentMetadata) -> Result<(), Error>;
+
+    /// Get the metadata of a torrent fetched from an external provider.
+    async fn get_torrent_metadata(&self, torrent_id: TorrentId) -> Result<Option<TorrentMetadata>, Error>;
+
+    /// Remove the metadata of a torrent.
+    async fn delete_torrent_metadata(&self, torrent_id: TorrentId) -> Result<(), Error>;
+
+    /// Add a structured field to the torrents of a category.
+    async fn insert_category_field(
+        &self,
+        category_id: CategoryId,
+        name: &str,
+        label: &str,
+        field_type: FieldType,
+        required: bool,
+        options: &[String],
+    ) -> Result<CategoryFieldId, Error>;
+
+    /// Get the structured fields of a category.
+    async fn get_category_fields(&self, category_id: CategoryId) -> Result<Vec<CategoryField>, Error>;
+
+    /// Remove a structured field from a category, with its values.
+    async fn delete_category_field(&self, category_id: CategoryId, name: &str) -> Result<(), Error>;
+
+    /// Replace the values of the category fields of a torrent.
+    async fn set_torrent_field_values(&self, torrent_id: TorrentId, values: &[(CategoryFieldId, String)]) -> Result<(), Error>;
+
+    /// Get the values of the fields of the current category of a torrent.
+    async fn get_torrent_field_values(&self, torrent_id: TorrentId) -> Result<Vec<TorrentFieldValue>, Error>;
+
+    /// Get the uploads of a user and the reports against them.
+    async fn get_user_reputation_stats(&self, user_id: UserId) -> Result<ReputationStats, Error>;
+
+    /// Get the trusted status set for a user by an administrator, if any.
+    async fn get_user_trusted_override(&self, user_id: UserId) -> Result<Option<bool>, Error>;
+
+    /// Set the trusted status of a user, replacing the previous one.
+    async fn upsert_user_trusted_override(&self, user_id: UserId, trusted: bool) -> Result<(), Error>;
+
+    /// Remove the trusted status set for a user.
+    async fn delete_user_trusted_override(&self, user_id: UserId) -> Result<(), Error>;
+
+    /// Save the bytes uploaded and downloaded with a tracker key, reported by
+    /// the tracker. It returns `false` when the key was not issued by the
+    /// index.
+    async fn import_key_transfer_stats(&self, tracker_key: &str, uploaded: u64, downloaded: u64) -> Result<bool, Error>;
+
+    /// Get the bytes uploaded and downloaded with all the keys of a user.
+    async fn get_user_transfer_stats(&self, user_id: UserId) -> Result<TransferStats, Error>;
+
+    /// Save a signed upload nonce until it expires. It returns `false` when
+    /// the nonce was already saved and has not expired yet.
+    async fn add_upload_nonce(&self, nonce: &str, date_expiry: u64) -> Result<bool, Error>;
+
+    /// Delete the signed upload nonces expired before the given time.
+    async fn delete_expired_upload_nonces(&self, now: u64) -> Result<(), Error>;
+
     /// DELETES ALL DATABASE ROWS, ONLY CALL THIS IF YOU KNOW WHAT YOU'RE DOING!
     async fn delete_all_database_rows(&self) -> Result<(), Error>;
 }
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use super::{slow_statements_level, slow_statements_threshold, PoolSettings};
+    use crate::config;
+
+    fn pool_settings(slow_query_threshold_ms: u64) -> PoolSettings {
+        PoolSettings::from(&config::Database {
+            slow_query_threshold_ms,
+            ..config::Database::default()
+        })
+    }
+
+    #[test]
+    fn it_should_log_the_statements_slower_than_the_threshold_as_warnings() {
+        let pool_settings = pool_settings(250);
+
+        assert_eq!(pool_settings.slow_query_threshold, Some(Duration::from_millis(250)));
+        assert_eq!(slow_statements_level(&pool_settings), log::LevelFilter::Warn);
+        assert_eq!(slow_statements_threshold(&pool_settings), Duration::from_millis(250));
+    }
+
+    #[test]
+    fn it_should_not_log_the_slow_statements_when_the_threshold_is_zero() {
+        let pool_settings = pool_settings(0);
+
+        assert_eq!(pool_settings.slow_query_threshold, None);
+        assert_eq!(slow_statements_level(&pool_settings), log::LevelFilter::Off);
+        assert_eq!(slow_statements_threshold(&pool_settings), Duration::MAX);
+    }
+}
@@ -0,0 +1,261 @@
+//! Application-level encryption of sensitive columns.
+//!
+//! When the `[database.encryption]` section is configured, the database
+//! drivers encrypt the sensitive columns before writing them and decrypt them
+//! after reading them, so the rest of the application only sees plaintext.
+//!
+//! Values are encrypted with AES-256-GCM and stored as:
+//!
+//! ```text
+//! enc:v1:<base64url(nonce || ciphertext || tag)>
+//! ```
+//!
+//! The nonce is derived from the key, the column and the plaintext (like in
+//! the SIV construction), so the same value always has the same ciphertext.
+//! That keeps the `UNIQUE` constraints (for example, on users' emails)
+//! working. The price is that it leaks which rows share a value.
+//!
+//! Values without the prefix are returned unchanged. That allows enabling
+//! the encryption on an existing database and encrypting the old rows later
+//! with the `encrypt_database` command.
+use std::fs;
+
+use base64::engine::general_purpose::URL_SAFE_NO_PAD;
+use base64::Engine;
+use ring::aead::{Aad, LessSafeKey, Nonce, UnboundKey, AES_256_GCM, NONCE_LEN};
+use ring::hmac;
+use thiserror::Error;
+
+use crate::config::DatabaseEncryption;
+
+/// Prefix of the encrypted values.
+pub const PREFIX: &str = "enc:v1:";
+
+#[derive(Error, Debug, PartialEq, Eq)]
+pub enum Error {
+    #[error("the encryption key must be 64 hexadecimal characters")]
+    InvalidKey,
+
+    #[error("unable to read the encryption key file: {0}")]
+    KeyFile(String),
+
+    #[error("the value is encrypted but there is no encryption key")]
+    MissingKey,
+
+    #[error("unable to decrypt the value")]
+    Decryption,
+}
+
+/// The encrypted columns. The column is authenticated with the value, so a
+/// value can't be copied to a different column.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Column {
+    UserEmail,
+    QueuedEmailRecipient,
+}
+
+impl Column {
+    #[must_use]
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Column::UserEmail => "torrust_user_profiles.email",
+            Column::QueuedEmailRecipient => "torrust_email_queue.recipient",
+        }
+    }
+}
+
+pub struct Cipher {
+    key: LessSafeKey,
+    nonce_key: hmac::Key,
+}
+
+impl Cipher {
+    /// # Panics
+    ///
+    /// Will panic if the key can't be used by the AEAD algorithm, which
+    /// can't happen with a 32-byte key.
+    #[must_use]
+    pub fn new(key: &[u8; 32]) -> Self {
+        let master_key = hmac::Key::new(hmac::HMAC_SHA256, key);
+
+        let encryption_key = hmac::sign(&master_key, b"torrust-index column encryption key");
+        let nonce_key = hmac::sign(&master_key, b"torrust-index column nonce key");
+
+        Self {
+            key: LessSafeKey::new(
+                UnboundKey::new(&AES_256_GCM, encryption_key.as_ref()).expect("AES-256 keys are 32 bytes long"),
+            ),
+            nonce_key: hmac::Key::new(hmac::HMAC_SHA256, nonce_key.as_ref()),
+        }
+    }
+
+    /// It builds the cipher with the key in the configuration or in the key
+    /// file.
+    ///
+    /// # Errors
+    ///
+    /// It returns an error if the key file can't be read or the key is not
+    /// valid.
+    pub fn from_config(encryption: &DatabaseEncryption) -> Result<Self, Error> {
+        let key = match (&encryption.key, &encryption.key_path) {
+            (Some(key), _) => key.to_bytes(),
+            (None, Some(key_path)) => {
+                let contents = fs::read_to_string(key_path).map_err(|e| Error::KeyFile(e.to_string()))?;
+
+                let mut key = [0u8; 32];
+
+                hex::decode_to_slice(contents.trim(), &mut key).ok().map(|()| key)
+            }
+            (None, None) => None,
+        }
+        .ok_or(Error::InvalidKey)?;
+
+        Ok(Self::new(&key))
+    }
+
+    /// It encrypts a value. Empty values are not encrypted.
+    ///
+    /// # Panics
+    ///
+    /// Will panic if the AEAD algorithm fails, which only happens with
+    /// values bigger than 64 GiB.
+    #[must_use]
+    pub fn encrypt(&self, column: Column, value: &str) -> String {
+        if value.is_empty() {
+            return String::new();
+        }
+
+        let mut nonce_context = hmac::Context::with_key(&self.nonce_key);
+        nonce_context.update(column.as_str().as_bytes());
+        nonce_context.update(&[0]);
+        nonce_context.update(value.as_bytes());
+
+        let mut nonce = [0u8; NONCE_LEN];
+        nonce.copy_from_slice(&nonce_context.sign().as_ref()[..NONCE_LEN]);
+
+        let mut in_out = value.as_bytes().to_vec();
+
+        self.key
+            .seal_in_place_append_tag(
+                Nonce::assume_unique_for_key(nonce),
+                Aad::from(column.as_str().as_bytes()),
+                &mut in_out,
+            )
+            .expect("values should be small enough to be encrypted");
+
+        let mut sealed = nonce.to_vec();
+        sealed.extend_from_slice(&in_out);
+
+        format!("{PREFIX}{}", URL_SAFE_NO_PAD.encode(sealed))
+    }
+
+    /// It decrypts a value. Values without the prefix are not encrypted and
+    /// they are returned unchanged.
+    ///
+    /// # Errors
+    ///
+    /// It returns an error if the value was not encrypted with this key for
+    /// this column.
+    pub fn decrypt(&self, column: Column, value: String) -> Result<String, Error> {
+        let Some(encoded) = value.strip_prefix(PREFIX) else {
+            return Ok(value);
+        };
+
+        let mut sealed = URL_SAFE_NO_PAD.decode(encoded).map_err(|_| Error::Decryption)?;
+
+        if sealed.len() < NONCE_LEN {
+            return Err(Error::Decryption);
+        }
+
+        let mut in_out = sealed.split_off(NONCE_LEN);
+
+        let nonce = Nonce::try_assume_unique_for_key(&sealed).map_err(|_| Error::Decryption)?;
+
+        let plaintext = self
+            .key
+            .open_in_place(nonce, Aad::from(column.as_str().as_bytes()), &mut in_out)
+            .map_err(|_| Error::Decryption)?;
+
+        String::from_utf8(plaintext.to_vec()).map_err(|_| Error::Decryption)
+    }
+}
+
+/// It encrypts a value if the encryption is enabled.
+#[must_use]
+pub fn encrypt(cipher: Option<&Cipher>, column: Column, value: &str) -> String {
+    match cipher {
+        Some(cipher) => cipher.encrypt(column, value),
+        None => value.to_string(),
+    }
+}
+
+/// It decrypts a value if it's encrypted.
+///
+/// # Errors
+///
+/// It returns an error if the value is encrypted and it can't be decrypted,
+/// for example, because the encryption is not enabled.
+pub fn decrypt(cipher: Option<&Cipher>, column: Column, value: String) -> Result<String, Error> {
+    match cipher {
+        Some(cipher) => cipher.decrypt(column, value),
+        None if value.starts_with(PREFIX) => Err(Error::MissingKey),
+        None => Ok(value),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{decrypt, Cipher, Column, Error, PREFIX};
+
+    fn cipher() -> Cipher {
+        Cipher::new(&[7u8; 32])
+    }
+
+    #[test]
+    fn it_should_decrypt_the_encrypted_values() {
+        let encrypted = cipher().encrypt(Column::UserEmail, "indexadmin@torrust.com");
+
+        assert!(encrypted.starts_with(PREFIX));
+        assert!(!encrypted.contains("indexadmin"));
+        assert_eq!(
+            cipher().decrypt(Column::UserEmail, encrypted),
+            Ok("indexadmin@torrust.com".to_string())
+        );
+    }
+
+    #[test]
+    fn it_should_always_encrypt_a_value_the_same_way_to_keep_unique_constraints_working() {
+        assert_eq!(
+            cipher().encrypt(Column::UserEmail, "indexadmin@torrust.com"),
+            cipher().encrypt(Column::UserEmail, "indexadmin@torrust.com")
+        );
+        assert_ne!(
+            cipher().encrypt(Column::UserEmail, "indexadmin@torrust.com"),
+            cipher().encrypt(Column::QueuedEmailRecipient, "indexadmin@torrust.com")
+        );
+    }
+
+    #[test]
+    fn it_should_return_plaintext_values_unchanged() {
+        assert_eq!(
+            cipher().decrypt(Column::UserEmail, "indexadmin@torrust.com".to_string()),
+            Ok("indexadmin@torrust.com".to_string())
+        );
+        assert_eq!(cipher().encrypt(Column::UserEmail, ""), String::new());
+    }
+
+    #[test]
+    fn it_should_fail_decrypting_values_from_other_columns_or_keys() {
+        let encrypted = cipher().encrypt(Column::UserEmail, "indexadmin@torrust.com");
+
+        assert_eq!(
+            cipher().decrypt(Column::QueuedEmailRecipient, encrypted.clone()),
+            Err(Error::Decryption)
+        );
+        assert_eq!(
+            Cipher::new(&[8u8; 32]).decrypt(Column::UserEmail, encrypted.clone()),
+            Err(Error::Decryption)
+        );
+        assert_eq!(decrypt(None, Column::UserEmail, encrypted), Err(Error::MissingKey));
+    }
+}
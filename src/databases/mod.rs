@@ -1,3 +1,7 @@
 pub mod database;
+pub mod encryption;
 pub mod mysql;
+pub mod replicas;
 pub mod sqlite;
+#[cfg(test)]
+pub mod testing;
@@ -1,31 +1,148 @@
 use std::str::FromStr;
-use std::time::Duration;
+use std::sync::Arc;
 
 use async_trait::async_trait;
 use bittorrent_primitives::info_hash::InfoHash;
 use chrono::{DateTime, NaiveDateTime, Utc};
-use sqlx::sqlite::{SqliteConnectOptions, SqlitePoolOptions};
-use sqlx::{query, query_as, Acquire, ConnectOptions, SqlitePool};
+use futures::TryStreamExt;
+use sqlx::migrate::{Migrate, MigrateError, Migrator};
+use sqlx::sqlite::{SqliteConnectOptions, SqliteConnection, SqliteJournalMode, SqlitePoolOptions, SqliteRow, SqliteSynchronous};
+use sqlx::{query, query_as, Acquire, Column as _, ConnectOptions, Row as _, SqlitePool, TypeInfo as _, ValueRef as _};
+use tokio::sync::mpsc;
 use url::Url;
 
 use super::database::TABLES_TO_TRUNCATE;
+use crate::config;
 use crate::databases::database;
-use crate::databases::database::{Category, Database, Driver, Sorting, TorrentCompact};
-use crate::models::category::CategoryId;
-use crate::models::response::TorrentsResponse;
-use crate::models::torrent::{Metadata, TorrentListing};
+use crate::databases::database::{
+    insert_row_statement, slow_statements_level, slow_statements_threshold, Category, ColumnValue, Database, DownloadCount,
+    Driver, MigrationStatus, PoolSettings, Sorting, TableRow, TagActivity, TorrentCompact,
+};
+use crate::databases::encryption::{self, Cipher, Column};
+use crate::models::antispam::{BannedPattern, PatternAction, PatternId};
+use crate::models::api_usage::ApiUsageRecord;
+use crate::models::audit::{AuditAction, AuditEntry, AuditEntryId, AuditLogFilter};
+use crate::models::bookmark::Bookmark;
+use crate::models::category::{CategoryId, CategoryPolicy};
+use crate::models::category_field::{CategoryField, CategoryFieldId, FieldType, TorrentFieldValue};
+use crate::models::collection::{Collection, CollectionId, SmartFilter, SmartFilterRow};
+use crate::models::comment::{Comment, CommentId};
+use crate::models::domain_event::{DbDomainEvent, EventId};
+use crate::models::email::{EmailId, QueuedEmail, Recipient, Role, Segment};
+use crate::models::image::{DescriptionImage, ImageId, ImageKind};
+use crate::models::import_session::{ImportBatch, ImportSession, ImportSessionId};
+use crate::models::magnet_upload::{MagnetUpload, MagnetUploadId, MagnetUploadStatus};
+use crate::models::points::{PointsAdjustment, UploaderSeedingStats};
+use crate::models::report::{Report, ReportAction, ReportId, ReportReason};
+use crate::models::reputation::ReputationStats;
+use crate::models::response::{AuditLogResponse, CommentsResponse, ReportsResponse, TorrentsResponse};
+use crate::models::saved_search::{SavedSearch, SavedSearchId, SavedSearchRow};
+use crate::models::subscription::{Subscription, SubscriptionId};
+use crate::models::sync::{PartitionId, Sequence, SyncPartitionSummary, SyncState, SyncTorrent};
+use crate::models::torrent::{
+    BulkAction, ListingFilter, Metadata, ScheduledRelease, SimilarTorrent, TorrentId, TorrentListing, TorrentStatus,
+    TrashedTorrent,
+};
+use crate::models::torrent_attachment::{AttachmentId, AttachmentKind, TorrentAttachment};
+use crate::models::torrent_duplicate::DuplicatePair;
 use crate::models::torrent_file::{
     DbTorrent, DbTorrentAnnounceUrl, DbTorrentFile, DbTorrentHttpSeedUrl, DbTorrentNode, Torrent, TorrentFile,
 };
-use crate::models::torrent_tag::{TagId, TorrentTag};
+use crate::models::torrent_file_group::TorrentFileGroup;
+use crate::models::torrent_health::{DeadTorrent, TorrentHealthSample};
+use crate::models::torrent_metadata::TorrentMetadata;
+use crate::models::torrent_preview::{PreviewId, TorrentPreview};
+use crate::models::torrent_rule::TorrentRuleCandidate;
+use crate::models::torrent_tag::{TagId, TagSynonym, TorrentTag};
+use crate::models::torrent_web_seed::{TorrentWebSeed, WebSeedId};
 use crate::models::tracker_key::TrackerKey;
-use crate::models::user::{User, UserAuthentication, UserCompact, UserId, UserProfile};
+use crate::models::transfer_stats::TransferStats;
+use crate::models::upload_quota::{UploadLimits, UploadUsage};
+use crate::models::user::{InactiveUser, PublicProfile, User, UserAuthentication, UserCompact, UserFull, UserId, UserProfile};
+use crate::models::user_session::UserSession;
 use crate::services::torrent::{CanonicalInfoHashGroup, DbTorrentInfoHash};
 use crate::utils::clock::{self, datetime_now, DATETIME_FORMAT};
 use crate::utils::hex::from_bytes;
 
+static MIGRATOR: Migrator = sqlx::migrate!("migrations/sqlite3");
+
+fn migration_error(err: MigrateError) -> database::Error {
+    tracing::error!("Unable to run the database migrations: {err}");
+    database::Error::MigrationError
+}
+
+fn journal_mode(journal_mode: config::SqliteJournalMode) -> SqliteJournalMode {
+    match journal_mode {
+        config::SqliteJournalMode::Delete => SqliteJournalMode::Delete,
+        config::SqliteJournalMode::Truncate => SqliteJournalMode::Truncate,
+        config::SqliteJournalMode::Persist => SqliteJournalMode::Persist,
+        config::SqliteJournalMode::Memory => SqliteJournalMode::Memory,
+        config::SqliteJournalMode::Wal => SqliteJournalMode::Wal,
+        config::SqliteJournalMode::Off => SqliteJournalMode::Off,
+    }
+}
+
+fn synchronous(synchronous: config::SqliteSynchronous) -> SqliteSynchronous {
+    match synchronous {
+        config::SqliteSynchronous::Off => SqliteSynchronous::Off,
+        config::SqliteSynchronous::Normal => SqliteSynchronous::Normal,
+        config::SqliteSynchronous::Full => SqliteSynchronous::Full,
+        config::SqliteSynchronous::Extra => SqliteSynchronous::Extra,
+    }
+}
+
+/// The tables of the index, without the migrations table.
+async fn get_table_names(conn: &mut SqliteConnection) -> Result<Vec<String>, database::Error> {
+    query_as::<_, (String,)>(
+        "SELECT name FROM sqlite_master WHERE type = 'table' AND name NOT LIKE 'sqlite_%' AND name <> '_sqlx_migrations' ORDER BY name",
+    )
+    .fetch_all(conn)
+    .await
+    .map(|tables| tables.into_iter().map(|(table,)| table).collect())
+    .map_err(|_| database::Error::Error)
+}
+
+/// The value of a column, by its storage class.
+fn get_column_value(row: &SqliteRow, index: usize) -> Result<ColumnValue, database::Error> {
+    let value = row.try_get_raw(index).map_err(|_| database::Error::Error)?;
+
+    if value.is_null() {
+        return Ok(ColumnValue::Null);
+    }
+
+    let column_value = match value.type_info().name() {
+        "INTEGER" | "BOOLEAN" => row.try_get(index).map(ColumnValue::Integer),
+        "REAL" => row.try_get(index).map(ColumnValue::Real),
+        "BLOB" => row.try_get(index).map(ColumnValue::Blob),
+        _ => row.try_get(index).map(ColumnValue::Text),
+    };
+
+    column_value.map_err(|_| database::Error::Error)
+}
+
 pub struct Sqlite {
     pub pool: SqlitePool,
+    cipher: Option<Arc<Cipher>>,
+}
+
+impl Sqlite {
+    /// It sets the cipher used to encrypt the sensitive columns.
+    #[must_use]
+    pub fn with_cipher(mut self, cipher: Option<Arc<Cipher>>) -> Self {
+        self.cipher = cipher;
+        self
+    }
+
+    fn encrypt(&self, column: Column, value: &str) -> String {
+        encryption::encrypt(self.cipher.as_deref(), column, value)
+    }
+
+    fn decrypt(&self, column: Column, value: String) -> Result<String, database::Error> {
+        encryption::decrypt(self.cipher.as_deref(), column, value).map_err(|err| {
+            tracing::error!("Unable to decrypt the {} column: {err}", column.as_str());
+            database::Error::Error
+        })
+    }
 }
 
 #[async_trait]
@@ -34,25 +151,198 @@ impl Database for Sqlite {
         Driver::Sqlite3
     }
 
-    async fn new(database_url: &str) -> Self {
+    #[tracing::instrument(level = "warn", skip_all)]
+    async fn ping(&self) -> Result<(), database::Error> {
+        query("SELECT 1")
+            .execute(&self.pool)
+            .await
+            .map(|_| ())
+            .map_err(|_| database::Error::Error)
+    }
+
+    #[tracing::instrument(level = "warn", skip_all)]
+    async fn get_migrations(&self) -> Result<Vec<MigrationStatus>, database::Error> {
+        let mut conn = self.pool.acquire().await.map_err(|_| database::Error::Error)?;
+
+        conn.ensure_migrations_table().await.map_err(migration_error)?;
+
+        let applied_migrations = conn.list_applied_migrations().await.map_err(migration_error)?;
+
+        Ok(MIGRATOR
+            .iter()
+            .filter(|migration| !migration.migration_type.is_down_migration())
+            .map(|migration| MigrationStatus {
+                version: migration.version,
+                description: migration.description.to_string(),
+                applied: applied_migrations
+                    .iter()
+                    .any(|applied_migration| applied_migration.version == migration.version),
+            })
+            .collect())
+    }
+
+    #[tracing::instrument(level = "warn", skip_all)]
+    async fn run_migrations(&self) -> Result<usize, database::Error> {
+        let pending = self
+            .get_migrations()
+            .await?
+            .iter()
+            .filter(|migration| !migration.applied)
+            .count();
+
+        MIGRATOR.run(&self.pool).await.map_err(migration_error)?;
+
+        Ok(pending)
+    }
+
+    #[tracing::instrument(level = "warn", skip_all)]
+    async fn redo_last_migration(&self) -> Result<i64, database::Error> {
+        let applied_versions: Vec<i64> = self
+            .get_migrations()
+            .await?
+            .iter()
+            .filter(|migration| migration.applied)
+            .map(|migration| migration.version)
+            .collect();
+
+        let Some(&last_version) = applied_versions.last() else {
+            return Err(database::Error::MigrationNotFound);
+        };
+
+        if !MIGRATOR
+            .iter()
+            .any(|migration| migration.version == last_version && migration.migration_type.is_down_migration())
+        {
+            return Err(database::Error::MigrationNotReversible);
+        }
+
+        let previous_version = applied_versions.iter().rev().nth(1).copied().unwrap_or(0);
+
+        MIGRATOR.undo(&self.pool, previous_version).await.map_err(migration_error)?;
+
+        MIGRATOR.run(&self.pool).await.map_err(migration_error)?;
+
+        Ok(last_version)
+    }
+
+    #[tracing::instrument(level = "warn", skip_all)]
+    async fn dump_tables(&self, rows: mpsc::Sender<TableRow>) -> Result<u64, database::Error> {
+        // A read transaction sees the same snapshot until it ends
+        let mut tx = self.pool.begin().await.map_err(|_| database::Error::Error)?;
+
+        let mut count = 0;
+
+        for table in get_table_names(&mut tx).await? {
+            let sql = format!("SELECT * FROM {table}");
+
+            let mut table_rows = query(&sql).fetch(&mut *tx);
+
+            while let Some(row) = table_rows.try_next().await.map_err(|_| database::Error::Error)? {
+                let table_row = TableRow {
+                    table: table.clone(),
+                    columns: row.columns().iter().map(|column| column.name().to_string()).collect(),
+                    values: (0..row.len())
+                        .map(|index| get_column_value(&row, index))
+                        .collect::<Result<_, _>>()?,
+                };
+
+                rows.send(table_row).await.map_err(|_| database::Error::Error)?;
+
+                count += 1;
+            }
+        }
+
+        tx.rollback().await.map_err(|_| database::Error::Error)?;
+
+        Ok(count)
+    }
+
+    #[tracing::instrument(level = "warn", skip_all)]
+    async fn restore_tables(&self, mut rows: mpsc::Receiver<TableRow>) -> Result<u64, database::Error> {
+        let mut tx = self.pool.begin().await.map_err(|_| database::Error::Error)?;
+
+        // Rows are inserted in any order, so references are checked on commit
+        query("PRAGMA defer_foreign_keys = ON")
+            .execute(&mut *tx)
+            .await
+            .map_err(|_| database::Error::Error)?;
+
+        let tables = get_table_names(&mut tx).await?;
+
+        for table in &tables {
+            query(&format!("DELETE FROM {table}"))
+                .execute(&mut *tx)
+                .await
+                .map_err(|_| database::Error::Error)?;
+        }
+
+        let mut count = 0;
+
+        while let Some(row) = rows.recv().await {
+            let sql = insert_row_statement(&row, &tables)?;
+
+            let mut insert = query(&sql);
+
+            for value in row.values {
+                insert = match value {
+                    ColumnValue::Null => insert.bind(None::<i64>),
+                    ColumnValue::Integer(value) => insert.bind(value),
+                    ColumnValue::Real(value) => insert.bind(value),
+                    ColumnValue::Text(value) => insert.bind(value),
+                    ColumnValue::Blob(value) => insert.bind(value),
+                };
+            }
+
+            insert.execute(&mut *tx).await.map_err(|err| {
+                tracing::error!("Unable to restore a row of the {} table: {err}", row.table);
+                database::Error::Error
+            })?;
+
+            count += 1;
+        }
+
+        tx.commit().await.map_err(|err| {
+            tracing::error!("Unable to restore the tables: {err}");
+            database::Error::Error
+        })?;
+
+        Ok(count)
+    }
+
+    #[tracing::instrument(level = "warn", skip_all)]
+    async fn run_maintenance(&self) -> Result<(), database::Error> {
+        // `VACUUM` can't run in a transaction. The checkpoint truncates the
+        // WAL file after it.
+        for statement in ["VACUUM", "ANALYZE", "PRAGMA wal_checkpoint(TRUNCATE)"] {
+            query(statement).execute(&self.pool).await.map_err(|err| {
+                tracing::error!("Unable to run `{statement}`: {err}");
+                database::Error::Error
+            })?;
+        }
+
+        Ok(())
+    }
+
+    async fn new(database_url: &str, pool_settings: &PoolSettings) -> Self {
         let connection_options = SqliteConnectOptions::from_str(database_url)
             .expect("Unable to create connection options.")
             .log_statements(log::LevelFilter::Debug)
-            .log_slow_statements(log::LevelFilter::Info, Duration::from_secs(1));
+            .log_slow_statements(slow_statements_level(pool_settings), slow_statements_threshold(pool_settings))
+            .journal_mode(journal_mode(pool_settings.sqlite.journal_mode))
+            .synchronous(synchronous(pool_settings.sqlite.synchronous))
+            .busy_timeout(pool_settings.sqlite.busy_timeout);
 
         let db = SqlitePoolOptions::new()
+            .max_connections(pool_settings.max_connections)
+            .acquire_timeout(pool_settings.acquire_timeout)
             .connect_with(connection_options)
             .await
             .expect("Unable to create database pool.");
 
-        sqlx::migrate!("migrations/sqlite3")
-            .run(&db)
-            .await
-            .expect("Could not run database migrations.");
-
-        Self { pool: db }
+        Self { pool: db, cipher: None }
     }
 
+    #[tracing::instrument(level = "warn", skip_all)]
     async fn insert_user_and_get_id(&self, username: &str, email: &str, password_hash: &str) -> Result<i64, database::Error> {
         // open pool connection
         let mut conn = self.pool.acquire().await.map_err(|_| database::Error::Error)?;
@@ -86,7 +376,7 @@ impl Database for Sqlite {
         let insert_user_profile_result = query(r#"INSERT INTO torrust_user_profiles (user_id, username, email, email_verified, bio, avatar) VALUES (?, ?, NULLIF(?, ""), 0, NULL, NULL)"#)
             .bind(user_id)
             .bind(username)
-            .bind(email)
+            .bind(self.encrypt(Column::UserEmail, email))
             .execute(&mut *tx)
             .await
             .map_err(|e| match e {
@@ -116,6 +406,7 @@ impl Database for Sqlite {
     }
 
     /// Change user's password.
+    #[tracing::instrument(level = "warn", skip_all)]
     async fn change_user_password(&self, user_id: i64, new_password: &str) -> Result<(), database::Error> {
         query("UPDATE torrust_user_authentication SET password_hash = ? WHERE user_id = ?")
             .bind(new_password)
@@ -132,6 +423,7 @@ impl Database for Sqlite {
             })
     }
 
+    #[tracing::instrument(level = "warn", skip_all)]
     async fn get_user_from_id(&self, user_id: i64) -> Result<User, database::Error> {
         query_as::<_, User>("SELECT * FROM torrust_users WHERE user_id = ?")
             .bind(user_id)
@@ -140,6 +432,7 @@ impl Database for Sqlite {
             .map_err(|_| database::Error::UserNotFound)
     }
 
+    #[tracing::instrument(level = "warn", skip_all)]
     async fn get_user_authentication_from_id(&self, user_id: UserId) -> Result<UserAuthentication, database::Error> {
         query_as::<_, UserAuthentication>("SELECT * FROM torrust_user_authentication WHERE user_id = ?")
             .bind(user_id)
@@ -148,14 +441,20 @@ impl Database for Sqlite {
             .map_err(|_| database::Error::UserNotFound)
     }
 
+    #[tracing::instrument(level = "warn", skip_all)]
     async fn get_user_profile_from_username(&self, username: &str) -> Result<UserProfile, database::Error> {
-        query_as::<_, UserProfile>("SELECT * FROM torrust_user_profiles WHERE username = ?")
+        let mut profile = query_as::<_, UserProfile>("SELECT * FROM torrust_user_profiles WHERE username = ? COLLATE NOCASE")
             .bind(username)
             .fetch_one(&self.pool)
             .await
-            .map_err(|_| database::Error::UserNotFound)
+            .map_err(|_| database::Error::UserNotFound)?;
+
+        profile.email = self.decrypt(Column::UserEmail, profile.email)?;
+
+        Ok(profile)
     }
 
+    #[tracing::instrument(level = "warn", skip_all)]
     async fn get_user_compact_from_id(&self, user_id: i64) -> Result<UserCompact, database::Error> {
         query_as::<_, UserCompact>("SELECT tu.user_id, tp.username, tu.administrator FROM torrust_users tu INNER JOIN torrust_user_profiles tp ON tu.user_id = tp.user_id WHERE tu.user_id = ?")
             .bind(user_id)
@@ -164,6 +463,7 @@ impl Database for Sqlite {
             .map_err(|_| database::Error::UserNotFound)
     }
 
+    #[tracing::instrument(level = "warn", skip_all)]
     async fn get_user_tracker_key(&self, user_id: i64) -> Option<TrackerKey> {
         const HOUR_IN_SECONDS: i64 = 3600;
 
@@ -179,6 +479,7 @@ impl Database for Sqlite {
             .ok()
     }
 
+    #[tracing::instrument(level = "warn", skip_all)]
     async fn count_users(&self) -> Result<i64, database::Error> {
         query_as("SELECT COUNT(*) FROM torrust_users")
             .fetch_one(&self.pool)
@@ -187,6 +488,7 @@ impl Database for Sqlite {
             .map_err(|_| database::Error::Error)
     }
 
+    #[tracing::instrument(level = "warn", skip_all)]
     async fn ban_user(&self, user_id: i64, reason: &str, date_expiry: NaiveDateTime) -> Result<(), database::Error> {
         // date needs to be in ISO 8601 format
         let date_expiry_string = date_expiry.format("%Y-%m-%d %H:%M:%S").to_string();
@@ -201,6 +503,7 @@ impl Database for Sqlite {
             .map_err(|_| database::Error::Error)
     }
 
+    #[tracing::instrument(level = "warn", skip_all)]
     async fn grant_admin_role(&self, user_id: i64) -> Result<(), database::Error> {
         query("UPDATE torrust_users SET administrator = TRUE WHERE user_id = ?")
             .bind(user_id)
@@ -216,6 +519,7 @@ impl Database for Sqlite {
             })
     }
 
+    #[tracing::instrument(level = "warn", skip_all)]
     async fn verify_email(&self, user_id: i64) -> Result<(), database::Error> {
         query("UPDATE torrust_user_profiles SET email_verified = TRUE WHERE user_id = ?")
             .bind(user_id)
@@ -225,6 +529,7 @@ impl Database for Sqlite {
             .map_err(|_| database::Error::Error)
     }
 
+    #[tracing::instrument(level = "warn", skip_all)]
     async fn add_tracker_key(&self, user_id: i64, tracker_key: &TrackerKey) -> Result<(), database::Error> {
         let key = tracker_key.key.clone();
 
@@ -238,6 +543,17 @@ impl Database for Sqlite {
             .map_err(|_| database::Error::Error)
     }
 
+    #[tracing::instrument(level = "warn", skip_all)]
+    async fn delete_user_tracker_keys(&self, user_id: i64) -> Result<(), database::Error> {
+        query("DELETE FROM torrust_tracker_keys WHERE user_id = ?")
+            .bind(user_id)
+            .execute(&self.pool)
+            .await
+            .map(|_| ())
+            .map_err(|_| database::Error::Error)
+    }
+
+    #[tracing::instrument(level = "warn", skip_all)]
     async fn delete_user(&self, user_id: i64) -> Result<(), database::Error> {
         query("DELETE FROM torrust_users WHERE user_id = ?")
             .bind(user_id)
@@ -253,6 +569,7 @@ impl Database for Sqlite {
             })
     }
 
+    #[tracing::instrument(level = "warn", skip_all)]
     async fn insert_category_and_get_id(&self, category_name: &str) -> Result<i64, database::Error> {
         query("INSERT INTO torrust_categories (name) VALUES (?)")
             .bind(category_name)
@@ -262,6 +579,7 @@ impl Database for Sqlite {
             .map_err(|_| database::Error::Error)
     }
 
+    #[tracing::instrument(level = "warn", skip_all)]
     async fn get_category_from_id(&self, category_id: i64) -> Result<Category, database::Error> {
         query_as::<_, Category>("SELECT category_id, name, (SELECT COUNT(*) FROM torrust_torrents WHERE torrust_torrents.category_id = torrust_categories.category_id) AS num_torrents FROM torrust_categories WHERE category_id = ?")
             .bind(category_id)
@@ -270,6 +588,7 @@ impl Database for Sqlite {
             .map_err(|_| database::Error::CategoryNotFound)
     }
 
+    #[tracing::instrument(level = "warn", skip_all)]
     async fn get_category_from_name(&self, category_name: &str) -> Result<Category, database::Error> {
         query_as::<_, Category>("SELECT category_id, name, (SELECT COUNT(*) FROM torrust_torrents WHERE torrust_torrents.category_id = torrust_categories.category_id) AS num_torrents FROM torrust_categories WHERE name = ?")
             .bind(category_name)
@@ -278,6 +597,7 @@ impl Database for Sqlite {
             .map_err(|_| database::Error::CategoryNotFound)
     }
 
+    #[tracing::instrument(level = "warn", skip_all)]
     async fn get_categories(&self) -> Result<Vec<Category>, database::Error> {
         query_as::<_, Category>("SELECT tc.category_id, tc.name, COUNT(tt.category_id) as num_torrents FROM torrust_categories tc LEFT JOIN torrust_torrents tt on tc.category_id = tt.category_id GROUP BY tc.name;")
             .fetch_all(&self.pool)
@@ -285,6 +605,7 @@ impl Database for Sqlite {
             .map_err(|_| database::Error::Error)
     }
 
+    #[tracing::instrument(level = "warn", skip_all)]
     async fn delete_category(&self, category_name: &str) -> Result<(), database::Error> {
         query("DELETE FROM torrust_categories WHERE name = ?")
             .bind(category_name)
@@ -301,13 +622,18 @@ impl Database for Sqlite {
     }
 
     // todo: refactor this
-    #[allow(clippy::too_many_lines)]
+    #[allow(clippy::too_many_lines, clippy::too_many_arguments)]
+    #[tracing::instrument(level = "warn", skip_all)]
     async fn get_torrents_search_sorted_paginated(
         &self,
         search: &Option<String>,
         categories: &Option<Vec<String>>,
         tags: &Option<Vec<String>>,
+        torrent_ids: &Option<Vec<i64>>,
+        status: &TorrentStatus,
+        filter: &ListingFilter,
         sort: &Sorting,
+        then_sort: &Option<Sorting>,
         offset: u64,
         limit: u8,
     ) -> Result<TorrentsResponse, database::Error> {
@@ -316,17 +642,44 @@ impl Database for Sqlite {
             Some(v) => format!("%{v}%"),
         };
 
-        let sort_query: String = match sort {
-            Sorting::UploadedAsc => "date_uploaded ASC".to_string(),
-            Sorting::UploadedDesc => "date_uploaded DESC".to_string(),
-            Sorting::SeedersAsc => "seeders ASC".to_string(),
-            Sorting::SeedersDesc => "seeders DESC".to_string(),
-            Sorting::LeechersAsc => "leechers ASC".to_string(),
-            Sorting::LeechersDesc => "leechers DESC".to_string(),
-            Sorting::NameAsc => "title ASC".to_string(),
-            Sorting::NameDesc => "title DESC".to_string(),
-            Sorting::SizeAsc => "size ASC".to_string(),
-            Sorting::SizeDesc => "size DESC".to_string(),
+        // Torrent IDs come from the search index ordered by relevance
+        let (torrent_ids_filter_query, relevance_sort_query) = match torrent_ids {
+            Some(ids) if ids.is_empty() => ("AND 1 = 0".to_string(), "date_uploaded DESC".to_string()),
+            Some(ids) => {
+                let ids_csv = ids.iter().map(ToString::to_string).collect::<Vec<String>>().join(",");
+                let positions: String = ids
+                    .iter()
+                    .enumerate()
+                    .map(|(position, id)| format!("WHEN {id} THEN {position} "))
+                    .collect();
+                (
+                    format!("AND tt.torrent_id IN ({ids_csv})"),
+                    format!("CASE tt.torrent_id {positions}END"),
+                )
+            }
+            None => (String::new(), "date_uploaded DESC".to_string()),
+        };
+
+        let sort_column = |sort: &Sorting| -> String {
+            match sort {
+                Sorting::UploadedAsc => "date_uploaded ASC".to_string(),
+                Sorting::UploadedDesc => "date_uploaded DESC".to_string(),
+                Sorting::SeedersAsc => "seeders ASC".to_string(),
+                Sorting::SeedersDesc => "seeders DESC".to_string(),
+                Sorting::LeechersAsc => "leechers ASC".to_string(),
+                Sorting::LeechersDesc => "leechers DESC".to_string(),
+                Sorting::NameAsc => "title ASC".to_string(),
+                Sorting::NameDesc => "title DESC".to_string(),
+                Sorting::SizeAsc => "size ASC".to_string(),
+                Sorting::SizeDesc => "size DESC".to_string(),
+                Sorting::Relevance => relevance_sort_query.clone(),
+            }
+        };
+
+        // The secondary sorting orders the torrents with the same value
+        let sort_query = match then_sort {
+            Some(then_sort) => format!("{}, {}", sort_column(sort), sort_column(then_sort)),
+            None => sort_column(sort),
         };
 
         let category_filter_query = if let Some(c) = categories {
@@ -375,6 +728,52 @@ impl Database for Sqlite {
             String::new()
         };
 
+        // Numbers are formatted in the query, text values are bound
+        let mut filter_query = String::new();
+        let mut filter_values = vec![];
+
+        if let Some(min_size) = filter.min_size {
+            filter_query.push_str(&format!(" AND tt.size >= {min_size}"));
+        }
+
+        if let Some(max_size) = filter.max_size {
+            filter_query.push_str(&format!(" AND tt.size <= {max_size}"));
+        }
+
+        if let Some(since) = &filter.uploaded_since {
+            filter_query.push_str(" AND tt.date_uploaded >= ?");
+            filter_values.push(since.clone());
+        }
+
+        if let Some(until) = &filter.uploaded_until {
+            filter_query.push_str(" AND tt.date_uploaded < ?");
+            filter_values.push(until.clone());
+        }
+
+        if let Some(extension) = &filter.file_extension {
+            // Single-file torrents only have the name
+            filter_query.push_str(
+                " AND (tt.name LIKE ? OR EXISTS (SELECT 1 FROM torrust_torrent_files tf WHERE tf.torrent_id = tt.torrent_id AND tf.path LIKE ?))",
+            );
+            filter_values.push(format!("%.{extension}"));
+            filter_values.push(format!("%.{extension}"));
+        }
+
+        // Values of the fields of another category are kept when torrents
+        // are moved, but they are ignored
+        for (name, value) in &filter.fields {
+            filter_query.push_str(
+                " AND EXISTS (SELECT 1 FROM torrust_torrent_field_values fv INNER JOIN torrust_category_fields cf ON cf.field_id = fv.field_id WHERE fv.torrent_id = tt.torrent_id AND cf.category_id = tt.category_id AND cf.name = ? AND fv.value = ?)",
+            );
+            filter_values.push(name.clone());
+            filter_values.push(value.clone());
+        }
+
+        let having_query = match filter.min_seeders {
+            Some(min_seeders) => format!("HAVING seeders >= {min_seeders}"),
+            None => String::new(),
+        };
+
         let mut query_string = format!(
             "SELECT
             tt.torrent_id,
@@ -390,6 +789,7 @@ impl Database for Sqlite {
             tt.creation_date,
             tt.created_by,
             tt.`encoding`,
+            tt.status,
             CAST(COALESCE(sum(ts.seeders),0) as signed) as seeders,
             CAST(COALESCE(sum(ts.leechers),0) as signed) as leechers
             FROM torrust_torrents tt
@@ -398,14 +798,24 @@ impl Database for Sqlite {
             INNER JOIN torrust_user_profiles tp ON tt.uploader_id = tp.user_id
             INNER JOIN torrust_torrent_info ti ON tt.torrent_id = ti.torrent_id
             LEFT JOIN torrust_torrent_tracker_stats ts ON tt.torrent_id = ts.torrent_id
-            WHERE title LIKE ?
-            GROUP BY tt.torrent_id"
+            WHERE (ti.title LIKE ? OR EXISTS (SELECT 1 FROM torrust_torrent_metadata tm WHERE tm.torrent_id = tt.torrent_id AND tm.title LIKE ?))
+            AND tt.status = ? {torrent_ids_filter_query}{filter_query}
+            GROUP BY tt.torrent_id
+            {having_query}"
         );
 
         let count_query = format!("SELECT COUNT(*) as count FROM ({query_string}) AS count_table");
 
-        let count_result: Result<i64, database::Error> = query_as(&count_query)
+        let mut count = query_as::<_, (i64,)>(&count_query)
             .bind(title.clone())
+            .bind(title.clone())
+            .bind(status.to_string());
+
+        for value in &filter_values {
+            count = count.bind(value.as_str());
+        }
+
+        let count_result: Result<i64, database::Error> = count
             .fetch_one(&self.pool)
             .await
             .map(|(v,)| v)
@@ -415,8 +825,16 @@ impl Database for Sqlite {
 
         query_string = format!("{query_string} ORDER BY {sort_query} LIMIT ?, ?");
 
-        let res: Vec<TorrentListing> = sqlx::query_as::<_, TorrentListing>(&query_string)
+        let mut results = sqlx::query_as::<_, TorrentListing>(&query_string)
+            .bind(title.clone())
             .bind(title)
+            .bind(status.to_string());
+
+        for value in &filter_values {
+            results = results.bind(value.as_str());
+        }
+
+        let res: Vec<TorrentListing> = results
             .bind(i64::saturating_add_unsigned(0, offset))
             .bind(limit)
             .fetch_all(&self.pool)
@@ -430,12 +848,14 @@ impl Database for Sqlite {
     }
 
     #[allow(clippy::too_many_lines)]
+    #[tracing::instrument(level = "warn", skip_all)]
     async fn insert_torrent_and_get_id(
         &self,
         original_info_hash: &InfoHash,
         torrent: &Torrent,
         uploader_id: UserId,
         metadata: &Metadata,
+        status: &TorrentStatus,
     ) -> Result<i64, database::Error> {
         let info_hash = torrent.canonical_info_hash_hex();
         let canonical_info_hash = torrent.canonical_info_hash();
@@ -476,8 +896,9 @@ impl Database for Sqlite {
             date_uploaded,
             creation_date,
             created_by,
-            `encoding`
-        ) VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, strftime('%Y-%m-%d %H:%M:%S',DATETIME('now', 'utc')), ?, ?, ?)",
+            `encoding`,
+            status
+        ) VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, strftime('%Y-%m-%d %H:%M:%S',DATETIME('now', 'utc')), ?, ?, ?, ?)",
         )
         .bind(uploader_id)
         .bind(metadata.category_id)
@@ -494,6 +915,7 @@ impl Database for Sqlite {
         .bind(torrent.creation_date)
         .bind(torrent.created_by.clone())
         .bind(torrent.encoding.clone())
+        .bind(status.to_string())
         .execute(&mut *tx)
         .await
         .map(|v| v.last_insert_rowid())
@@ -570,17 +992,18 @@ impl Database for Sqlite {
 
         let insert_torrent_announce_urls_result: Result<(), database::Error> = if let Some(announce_urls) = &torrent.announce_list
         {
-            // flatten the nested vec (this will however remove the)
-            let announce_urls = announce_urls.iter().flatten().collect::<Vec<&String>>();
-
-            for tracker_url in &announce_urls {
-                let () = query("INSERT INTO torrust_torrent_announce_urls (torrent_id, tracker_url) VALUES (?, ?)")
-                    .bind(torrent_id)
-                    .bind(tracker_url)
-                    .execute(&mut *tx)
-                    .await
-                    .map(|_| ())
-                    .map_err(|_| database::Error::Error)?;
+            // the tier of each tracker is stored to preserve the list structure
+            for (tier, tracker_urls) in announce_urls.iter().enumerate() {
+                for tracker_url in tracker_urls {
+                    let () = query("INSERT INTO torrust_torrent_announce_urls (torrent_id, tracker_url, tier) VALUES (?, ?, ?)")
+                        .bind(torrent_id)
+                        .bind(tracker_url)
+                        .bind(u32::try_from(tier).unwrap_or(u32::MAX))
+                        .execute(&mut *tx)
+                        .await
+                        .map(|_| ())
+                        .map_err(|_| database::Error::Error)?;
+                }
             }
 
             Ok(())
@@ -700,6 +1123,7 @@ impl Database for Sqlite {
         }
     }
 
+    #[tracing::instrument(level = "warn", skip_all)]
     async fn get_torrent_canonical_info_hash_group(
         &self,
         canonical: &InfoHash,
@@ -726,6 +1150,7 @@ impl Database for Sqlite {
         })
     }
 
+    #[tracing::instrument(level = "warn", skip_all)]
     async fn find_canonical_info_hash_for(&self, info_hash: &InfoHash) -> Result<Option<InfoHash>, database::Error> {
         let maybe_db_torrent_info_hash = query_as::<_, DbTorrentInfoHash>(
             "SELECT info_hash, canonical_info_hash, original_is_known FROM torrust_torrent_info_hashes WHERE info_hash = ?",
@@ -744,6 +1169,7 @@ impl Database for Sqlite {
         }
     }
 
+    #[tracing::instrument(level = "warn", skip_all)]
     async fn add_info_hash_to_canonical_info_hash_group(
         &self,
         original: &InfoHash,
@@ -759,6 +1185,7 @@ impl Database for Sqlite {
             .map_err(|err| database::Error::ErrorWithText(err.to_string()))
     }
 
+    #[tracing::instrument(level = "warn", skip_all)]
     async fn get_torrent_info_from_id(&self, torrent_id: i64) -> Result<DbTorrent, database::Error> {
         query_as::<_, DbTorrent>("SELECT * FROM torrust_torrents WHERE torrent_id = ?")
             .bind(torrent_id)
@@ -767,14 +1194,16 @@ impl Database for Sqlite {
             .map_err(|_| database::Error::TorrentNotFound)
     }
 
+    #[tracing::instrument(level = "warn", skip_all)]
     async fn get_torrent_info_from_info_hash(&self, info_hash: &InfoHash) -> Result<DbTorrent, database::Error> {
-        query_as::<_, DbTorrent>("SELECT * FROM torrust_torrents WHERE info_hash = ?")
+        query_as::<_, DbTorrent>("SELECT * FROM torrust_torrents WHERE info_hash = ? AND status <> 'deleted'")
             .bind(info_hash.to_hex_string().to_lowercase())
             .fetch_one(&self.pool)
             .await
             .map_err(|_| database::Error::TorrentNotFound)
     }
 
+    #[tracing::instrument(level = "warn", skip_all)]
     async fn get_torrent_files_from_id(&self, torrent_id: i64) -> Result<Vec<TorrentFile>, database::Error> {
         let db_torrent_files =
             query_as::<_, DbTorrentFile>("SELECT md5sum, length, path FROM torrust_torrent_files WHERE torrent_id = ?")
@@ -800,15 +1229,19 @@ impl Database for Sqlite {
         Ok(torrent_files)
     }
 
+    #[tracing::instrument(level = "warn", skip_all)]
     async fn get_torrent_announce_urls_from_id(&self, torrent_id: i64) -> Result<Vec<Vec<String>>, database::Error> {
-        query_as::<_, DbTorrentAnnounceUrl>("SELECT tracker_url FROM torrust_torrent_announce_urls WHERE torrent_id = ?")
-            .bind(torrent_id)
-            .fetch_all(&self.pool)
-            .await
-            .map(|v| v.iter().map(|a| vec![a.tracker_url.to_string()]).collect())
-            .map_err(|_| database::Error::TorrentNotFound)
+        query_as::<_, DbTorrentAnnounceUrl>(
+            "SELECT tracker_url, tier FROM torrust_torrent_announce_urls WHERE torrent_id = ? ORDER BY tier, announce_url_id",
+        )
+        .bind(torrent_id)
+        .fetch_all(&self.pool)
+        .await
+        .map(|v| DbTorrentAnnounceUrl::group_by_tier(&v))
+        .map_err(|_| database::Error::TorrentNotFound)
     }
 
+    #[tracing::instrument(level = "warn", skip_all)]
     async fn get_torrent_http_seed_urls_from_id(&self, torrent_id: i64) -> Result<Vec<String>, database::Error> {
         query_as::<_, DbTorrentHttpSeedUrl>("SELECT seed_url FROM torrust_torrent_http_seeds WHERE torrent_id = ?")
             .bind(torrent_id)
@@ -818,6 +1251,7 @@ impl Database for Sqlite {
             .map_err(|_| database::Error::TorrentNotFound)
     }
 
+    #[tracing::instrument(level = "warn", skip_all)]
     async fn get_torrent_nodes_from_id(&self, torrent_id: i64) -> Result<Vec<(String, i64)>, database::Error> {
         query_as::<_, DbTorrentNode>("SELECT node_ip, node_port FROM torrust_torrent_nodes WHERE torrent_id = ?")
             .bind(torrent_id)
@@ -827,6 +1261,7 @@ impl Database for Sqlite {
             .map_err(|_| database::Error::TorrentNotFound)
     }
 
+    #[tracing::instrument(level = "warn", skip_all)]
     async fn get_torrent_listing_from_id(&self, torrent_id: i64) -> Result<TorrentListing, database::Error> {
         query_as::<_, TorrentListing>(
             "SELECT 
@@ -842,13 +1277,14 @@ impl Database for Sqlite {
             tt.creation_date,
             tt.created_by,
             tt.`encoding`,
+            tt.status,
             CAST(COALESCE(sum(ts.seeders),0) as signed) as seeders,
             CAST(COALESCE(sum(ts.leechers),0) as signed) as leechers
             FROM torrust_torrents tt
             INNER JOIN torrust_user_profiles tp ON tt.uploader_id = tp.user_id
             INNER JOIN torrust_torrent_info ti ON tt.torrent_id = ti.torrent_id
             LEFT JOIN torrust_torrent_tracker_stats ts ON tt.torrent_id = ts.torrent_id
-            WHERE tt.torrent_id = ?
+            WHERE tt.torrent_id = ? AND tt.status <> 'deleted'
             GROUP BY ts.torrent_id",
         )
         .bind(torrent_id)
@@ -857,6 +1293,7 @@ impl Database for Sqlite {
         .map_err(|_| database::Error::TorrentNotFound)
     }
 
+    #[tracing::instrument(level = "warn", skip_all)]
     async fn get_torrent_listing_from_info_hash(&self, info_hash: &InfoHash) -> Result<TorrentListing, database::Error> {
         query_as::<_, TorrentListing>(
             "SELECT
@@ -872,13 +1309,14 @@ impl Database for Sqlite {
             tt.creation_date,
             tt.created_by,
             tt.`encoding`,
+            tt.status,
             CAST(COALESCE(sum(ts.seeders),0) as signed) as seeders,
             CAST(COALESCE(sum(ts.leechers),0) as signed) as leechers
             FROM torrust_torrents tt
             INNER JOIN torrust_user_profiles tp ON tt.uploader_id = tp.user_id
             INNER JOIN torrust_torrent_info ti ON tt.torrent_id = ti.torrent_id
             LEFT JOIN torrust_torrent_tracker_stats ts ON tt.torrent_id = ts.torrent_id
-            WHERE tt.info_hash = ?
+            WHERE tt.info_hash = ? AND tt.status <> 'deleted'
             GROUP BY ts.torrent_id",
         )
         .bind(info_hash.to_string().to_lowercase())
@@ -887,6 +1325,7 @@ impl Database for Sqlite {
         .map_err(|_| database::Error::TorrentNotFound)
     }
 
+    #[tracing::instrument(level = "warn", skip_all)]
     async fn get_all_torrents_compact(&self) -> Result<Vec<TorrentCompact>, database::Error> {
         query_as::<_, TorrentCompact>("SELECT torrent_id, info_hash FROM torrust_torrents")
             .fetch_all(&self.pool)
@@ -894,8 +1333,10 @@ impl Database for Sqlite {
             .map_err(|_| database::Error::Error)
     }
 
+    #[tracing::instrument(level = "warn", skip_all)]
     async fn get_torrents_with_stats_not_updated_since(
         &self,
+        after_torrent_id: TorrentId,
         datetime: DateTime<Utc>,
         limit: i64,
     ) -> Result<Vec<TorrentCompact>, database::Error> {
@@ -903,11 +1344,12 @@ impl Database for Sqlite {
             "SELECT tt.torrent_id, tt.info_hash
              FROM torrust_torrents tt
              LEFT JOIN torrust_torrent_tracker_stats tts ON tt.torrent_id = tts.torrent_id
-             WHERE tts.updated_at < ? OR tts.updated_at IS NULL
-             ORDER BY tts.updated_at ASC
+             WHERE tt.torrent_id > ? AND (tts.updated_at < ? OR tts.updated_at IS NULL)
+             ORDER BY tt.torrent_id ASC
              LIMIT ?
         ",
         )
+        .bind(after_torrent_id)
         .bind(datetime.format(DATETIME_FORMAT).to_string())
         .bind(limit)
         .fetch_all(&self.pool)
@@ -915,6 +1357,7 @@ impl Database for Sqlite {
         .map_err(|_| database::Error::Error)
     }
 
+    #[tracing::instrument(level = "warn", skip_all)]
     async fn update_torrent_title(&self, torrent_id: i64, title: &str) -> Result<(), database::Error> {
         query("UPDATE torrust_torrent_info SET title = $1 WHERE torrent_id = $2")
             .bind(title)
@@ -941,6 +1384,7 @@ impl Database for Sqlite {
             })
     }
 
+    #[tracing::instrument(level = "warn", skip_all)]
     async fn update_torrent_description(&self, torrent_id: i64, description: &str) -> Result<(), database::Error> {
         query("UPDATE torrust_torrent_info SET description = $1 WHERE torrent_id = $2")
             .bind(description)
@@ -957,6 +1401,7 @@ impl Database for Sqlite {
             })
     }
 
+    #[tracing::instrument(level = "warn", skip_all)]
     async fn update_torrent_category(&self, torrent_id: i64, category_id: CategoryId) -> Result<(), database::Error> {
         query("UPDATE torrust_torrents SET category_id = $1 WHERE torrent_id = $2")
             .bind(category_id)
@@ -973,6 +1418,7 @@ impl Database for Sqlite {
             })
     }
 
+    #[tracing::instrument(level = "warn", skip_all)]
     async fn insert_tag_and_get_id(&self, tag_name: &str) -> Result<i64, database::Error> {
         query("INSERT INTO torrust_torrent_tags (name) VALUES (?)")
             .bind(tag_name)
@@ -992,6 +1438,7 @@ impl Database for Sqlite {
             })
     }
 
+    #[tracing::instrument(level = "warn", skip_all)]
     async fn delete_tag(&self, tag_id: TagId) -> Result<(), database::Error> {
         query("DELETE FROM torrust_torrent_tags WHERE tag_id = ?")
             .bind(tag_id)
@@ -1001,6 +1448,7 @@ impl Database for Sqlite {
             .map_err(|err| database::Error::ErrorWithText(err.to_string()))
     }
 
+    #[tracing::instrument(level = "warn", skip_all)]
     async fn add_torrent_tag_link(&self, torrent_id: i64, tag_id: TagId) -> Result<(), database::Error> {
         query("INSERT INTO torrust_torrent_tag_links (torrent_id, tag_id) VALUES (?, ?)")
             .bind(torrent_id)
@@ -1011,6 +1459,7 @@ impl Database for Sqlite {
             .map_err(|_| database::Error::Error)
     }
 
+    #[tracing::instrument(level = "warn", skip_all)]
     async fn add_torrent_tag_links(&self, torrent_id: i64, tag_ids: &[TagId]) -> Result<(), database::Error> {
         let mut tx = self
             .pool
@@ -1032,6 +1481,7 @@ impl Database for Sqlite {
             .map_err(|err| database::Error::ErrorWithText(err.to_string()))
     }
 
+    #[tracing::instrument(level = "warn", skip_all)]
     async fn delete_torrent_tag_link(&self, torrent_id: i64, tag_id: TagId) -> Result<(), database::Error> {
         query("DELETE FROM torrust_torrent_tag_links WHERE torrent_id = ? AND tag_id = ?")
             .bind(torrent_id)
@@ -1042,6 +1492,7 @@ impl Database for Sqlite {
             .map_err(|_| database::Error::Error)
     }
 
+    #[tracing::instrument(level = "warn", skip_all)]
     async fn delete_all_torrent_tag_links(&self, torrent_id: i64) -> Result<(), database::Error> {
         query("DELETE FROM torrust_torrent_tag_links WHERE torrent_id = ?")
             .bind(torrent_id)
@@ -1051,6 +1502,7 @@ impl Database for Sqlite {
             .map_err(|err| database::Error::ErrorWithText(err.to_string()))
     }
 
+    #[tracing::instrument(level = "warn", skip_all)]
     async fn get_tag_from_name(&self, name: &str) -> Result<TorrentTag, database::Error> {
         query_as::<_, TorrentTag>("SELECT tag_id, name FROM torrust_torrent_tags WHERE name = ?")
             .bind(name)
@@ -1059,6 +1511,7 @@ impl Database for Sqlite {
             .map_err(|_| database::Error::TagNotFound)
     }
 
+    #[tracing::instrument(level = "warn", skip_all)]
     async fn get_tags(&self) -> Result<Vec<TorrentTag>, database::Error> {
         query_as::<_, TorrentTag>("SELECT tag_id, name FROM torrust_torrent_tags")
             .fetch_all(&self.pool)
@@ -1066,6 +1519,7 @@ impl Database for Sqlite {
             .map_err(|_| database::Error::Error)
     }
 
+    #[tracing::instrument(level = "warn", skip_all)]
     async fn get_tags_for_torrent_id(&self, torrent_id: i64) -> Result<Vec<TorrentTag>, database::Error> {
         query_as::<_, TorrentTag>(
             "SELECT torrust_torrent_tags.tag_id, torrust_torrent_tags.name
@@ -1079,6 +1533,7 @@ impl Database for Sqlite {
         .map_err(|_| database::Error::Error)
     }
 
+    #[tracing::instrument(level = "warn", skip_all)]
     async fn update_tracker_info(
         &self,
         torrent_id: i64,
@@ -1098,6 +1553,7 @@ impl Database for Sqlite {
             .map_err(|_| database::Error::TorrentNotFound)
     }
 
+    #[tracing::instrument(level = "warn", skip_all)]
     async fn delete_torrent(&self, torrent_id: i64) -> Result<(), database::Error> {
         query("DELETE FROM torrust_torrents WHERE torrent_id = ?")
             .bind(torrent_id)
@@ -1113,14 +1569,3745 @@ impl Database for Sqlite {
             })
     }
 
-    async fn delete_all_database_rows(&self) -> Result<(), database::Error> {
-        for table in TABLES_TO_TRUNCATE {
-            query(&format!("DELETE FROM {table};"))
-                .execute(&self.pool)
-                .await
-                .map_err(|_| database::Error::Error)?;
-        }
+    #[tracing::instrument(level = "warn", skip_all)]
+    async fn insert_domain_event(&self, event_type: &str, payload: &str) -> Result<EventId, database::Error> {
+        query("INSERT INTO torrust_domain_events (event_type, payload, date_created) VALUES (?, ?, ?)")
+            .bind(event_type)
+            .bind(payload)
+            .bind(datetime_now())
+            .execute(&self.pool)
+            .await
+            .map(|v| v.last_insert_rowid())
+            .map_err(|_| database::Error::Error)
+    }
 
-        Ok(())
+    #[tracing::instrument(level = "warn", skip_all)]
+    async fn get_domain_events_after(&self, event_id: EventId, limit: u32) -> Result<Vec<DbDomainEvent>, database::Error> {
+        query_as::<_, DbDomainEvent>(
+            "SELECT event_id, event_type, payload, date_created FROM torrust_domain_events WHERE event_id > ? ORDER BY event_id ASC LIMIT ?",
+        )
+        .bind(event_id)
+        .bind(limit)
+        .fetch_all(&self.pool)
+        .await
+        .map_err(|_| database::Error::Error)
+    }
+
+    #[tracing::instrument(level = "warn", skip_all)]
+    async fn get_domain_event_consumer_cursor(&self, consumer: &str) -> Result<EventId, database::Error> {
+        query_as::<_, (EventId,)>("SELECT last_event_id FROM torrust_domain_event_consumers WHERE consumer = ?")
+            .bind(consumer)
+            .fetch_optional(&self.pool)
+            .await
+            .map(|v| v.map_or(0, |(last_event_id,)| last_event_id))
+            .map_err(|_| database::Error::Error)
+    }
+
+    #[tracing::instrument(level = "warn", skip_all)]
+    async fn update_domain_event_consumer_cursor(&self, consumer: &str, event_id: EventId) -> Result<(), database::Error> {
+        query("REPLACE INTO torrust_domain_event_consumers (consumer, last_event_id) VALUES (?, ?)")
+            .bind(consumer)
+            .bind(event_id)
+            .execute(&self.pool)
+            .await
+            .map(|_| ())
+            .map_err(|_| database::Error::Error)
+    }
+
+    #[tracing::instrument(level = "warn", skip_all)]
+    async fn insert_comment(&self, torrent_id: TorrentId, author_id: UserId, text: &str) -> Result<CommentId, database::Error> {
+        query("INSERT INTO torrust_torrent_comments (torrent_id, author_id, text, date_created) VALUES (?, ?, ?, ?)")
+            .bind(torrent_id)
+            .bind(author_id)
+            .bind(text)
+            .bind(datetime_now())
+            .execute(&self.pool)
+            .await
+            .map(|v| v.last_insert_rowid())
+            .map_err(|_| database::Error::Error)
+    }
+
+    #[tracing::instrument(level = "warn", skip_all)]
+    async fn get_comment(&self, comment_id: CommentId) -> Result<Comment, database::Error> {
+        query_as::<_, Comment>(
+            "SELECT tc.comment_id, tc.torrent_id, tc.author_id, tp.username AS author, tc.text, tc.hidden, tc.date_created, tc.date_updated
+            FROM torrust_torrent_comments tc
+            INNER JOIN torrust_user_profiles tp ON tc.author_id = tp.user_id
+            WHERE tc.comment_id = ?",
+        )
+        .bind(comment_id)
+        .fetch_one(&self.pool)
+        .await
+        .map_err(|_| database::Error::CommentNotFound)
+    }
+
+    #[tracing::instrument(level = "warn", skip_all)]
+    async fn get_torrent_comments_paginated(
+        &self,
+        torrent_id: TorrentId,
+        include_hidden: bool,
+        offset: u64,
+        limit: u8,
+    ) -> Result<CommentsResponse, database::Error> {
+        let hidden_filter = if include_hidden { "" } else { " AND tc.hidden = FALSE" };
+
+        let count_query = format!("SELECT COUNT(*) FROM torrust_torrent_comments tc WHERE tc.torrent_id = ?{hidden_filter}");
+
+        let count: i64 = query_as::<_, (i64,)>(&count_query)
+            .bind(torrent_id)
+            .fetch_one(&self.pool)
+            .await
+            .map(|(v,)| v)
+            .map_err(|_| database::Error::Error)?;
+
+        let query_string = format!(
+            "SELECT tc.comment_id, tc.torrent_id, tc.author_id, tp.username AS author, tc.text, tc.hidden, tc.date_created, tc.date_updated
+            FROM torrust_torrent_comments tc
+            INNER JOIN torrust_user_profiles tp ON tc.author_id = tp.user_id
+            WHERE tc.torrent_id = ?{hidden_filter}
+            ORDER BY tc.comment_id ASC
+            LIMIT ?, ?"
+        );
+
+        let results = query_as::<_, Comment>(&query_string)
+            .bind(torrent_id)
+            .bind(i64::saturating_add_unsigned(0, offset))
+            .bind(limit)
+            .fetch_all(&self.pool)
+            .await
+            .map_err(|_| database::Error::Error)?;
+
+        Ok(CommentsResponse {
+            total: u32::try_from(count).expect("variable `count` is larger than u32"),
+            results,
+        })
+    }
+
+    #[tracing::instrument(level = "warn", skip_all)]
+    async fn update_comment_text(&self, comment_id: CommentId, text: &str) -> Result<(), database::Error> {
+        query("UPDATE torrust_torrent_comments SET text = ?, date_updated = ? WHERE comment_id = ?")
+            .bind(text)
+            .bind(datetime_now())
+            .bind(comment_id)
+            .execute(&self.pool)
+            .await
+            .map_err(|_| database::Error::Error)
+            .and_then(|v| {
+                if v.rows_affected() > 0 {
+                    Ok(())
+                } else {
+                    Err(database::Error::CommentNotFound)
+                }
+            })
+    }
+
+    #[tracing::instrument(level = "warn", skip_all)]
+    async fn update_comment_hidden(&self, comment_id: CommentId, hidden: bool) -> Result<(), database::Error> {
+        query("UPDATE torrust_torrent_comments SET hidden = ? WHERE comment_id = ?")
+            .bind(hidden)
+            .bind(comment_id)
+            .execute(&self.pool)
+            .await
+            .map_err(|_| database::Error::Error)
+            .and_then(|v| {
+                if v.rows_affected() > 0 {
+                    Ok(())
+                } else {
+                    Err(database::Error::CommentNotFound)
+                }
+            })
+    }
+
+    #[tracing::instrument(level = "warn", skip_all)]
+    async fn delete_comment(&self, comment_id: CommentId) -> Result<(), database::Error> {
+        query("DELETE FROM torrust_torrent_comments WHERE comment_id = ?")
+            .bind(comment_id)
+            .execute(&self.pool)
+            .await
+            .map_err(|_| database::Error::Error)
+            .and_then(|v| {
+                if v.rows_affected() > 0 {
+                    Ok(())
+                } else {
+                    Err(database::Error::CommentNotFound)
+                }
+            })
+    }
+
+    #[tracing::instrument(level = "warn", skip_all)]
+    async fn update_torrent_status(&self, torrent_id: TorrentId, status: &TorrentStatus) -> Result<(), database::Error> {
+        query("UPDATE torrust_torrents SET status = ? WHERE torrent_id = ?")
+            .bind(status.to_string())
+            .bind(torrent_id)
+            .execute(&self.pool)
+            .await
+            .map_err(|_| database::Error::Error)
+            .and_then(|v| {
+                if v.rows_affected() > 0 {
+                    Ok(())
+                } else {
+                    Err(database::Error::TorrentNotFound)
+                }
+            })
+    }
+
+    #[tracing::instrument(level = "warn", skip_all)]
+    async fn insert_report(
+        &self,
+        torrent_id: TorrentId,
+        reporter_id: UserId,
+        reason: &ReportReason,
+        details: Option<&str>,
+    ) -> Result<ReportId, database::Error> {
+        query(
+            "INSERT INTO torrust_torrent_reports (torrent_id, reporter_id, reason, details, date_created) VALUES (?, ?, ?, ?, ?)",
+        )
+        .bind(torrent_id)
+        .bind(reporter_id)
+        .bind(reason.to_string())
+        .bind(details)
+        .bind(datetime_now())
+        .execute(&self.pool)
+        .await
+        .map(|v| v.last_insert_rowid())
+        .map_err(|_| database::Error::Error)
+    }
+
+    #[tracing::instrument(level = "warn", skip_all)]
+    async fn get_report(&self, report_id: ReportId) -> Result<Report, database::Error> {
+        query_as::<_, Report>(
+            "SELECT tr.report_id, tr.torrent_id, tt.info_hash, ti.title, tu.username AS uploader, tr.reporter_id, tp.username AS reporter, tr.reason, tr.details, tr.resolved, tr.resolution, tr.date_created, tr.date_resolved
+            FROM torrust_torrent_reports tr
+            INNER JOIN torrust_torrents tt ON tr.torrent_id = tt.torrent_id
+            INNER JOIN torrust_torrent_info ti ON tr.torrent_id = ti.torrent_id
+            INNER JOIN torrust_user_profiles tu ON tt.uploader_id = tu.user_id
+            INNER JOIN torrust_user_profiles tp ON tr.reporter_id = tp.user_id
+            WHERE tr.report_id = ?",
+        )
+        .bind(report_id)
+        .fetch_one(&self.pool)
+        .await
+        .map_err(|_| database::Error::ReportNotFound)
+    }
+
+    #[tracing::instrument(level = "warn", skip_all)]
+    async fn get_reports_paginated(
+        &self,
+        resolved: Option<bool>,
+        offset: u64,
+        limit: u8,
+    ) -> Result<ReportsResponse, database::Error> {
+        let resolved_filter = match resolved {
+            Some(true) => " WHERE tr.resolved = TRUE",
+            Some(false) => " WHERE tr.resolved = FALSE",
+            None => "",
+        };
+
+        let count_query = format!("SELECT COUNT(*) FROM torrust_torrent_reports tr{resolved_filter}");
+
+        let count: i64 = query_as::<_, (i64,)>(&count_query)
+            .fetch_one(&self.pool)
+            .await
+            .map(|(v,)| v)
+            .map_err(|_| database::Error::Error)?;
+
+        let query_string = format!(
+            "SELECT tr.report_id, tr.torrent_id, tt.info_hash, ti.title, tu.username AS uploader, tr.reporter_id, tp.username AS reporter, tr.reason, tr.details, tr.resolved, tr.resolution, tr.date_created, tr.date_resolved
+            FROM torrust_torrent_reports tr
+            INNER JOIN torrust_torrents tt ON tr.torrent_id = tt.torrent_id
+            INNER JOIN torrust_torrent_info ti ON tr.torrent_id = ti.torrent_id
+            INNER JOIN torrust_user_profiles tu ON tt.uploader_id = tu.user_id
+            INNER JOIN torrust_user_profiles tp ON tr.reporter_id = tp.user_id{resolved_filter}
+            ORDER BY tr.report_id ASC
+            LIMIT ?, ?"
+        );
+
+        let results = query_as::<_, Report>(&query_string)
+            .bind(i64::saturating_add_unsigned(0, offset))
+            .bind(limit)
+            .fetch_all(&self.pool)
+            .await
+            .map_err(|_| database::Error::Error)?;
+
+        Ok(ReportsResponse {
+            total: u32::try_from(count).expect("variable `count` is larger than u32"),
+            results,
+        })
+    }
+
+    #[tracing::instrument(level = "warn", skip_all)]
+    async fn resolve_report(
+        &self,
+        report_id: ReportId,
+        resolved_by: UserId,
+        resolution: &ReportAction,
+    ) -> Result<(), database::Error> {
+        query("UPDATE torrust_torrent_reports SET resolved = TRUE, resolution = ?, resolved_by = ?, date_resolved = ? WHERE report_id = ?")
+            .bind(resolution.to_string())
+            .bind(resolved_by)
+            .bind(datetime_now())
+            .bind(report_id)
+            .execute(&self.pool)
+            .await
+            .map_err(|_| database::Error::Error)
+            .and_then(|v| {
+                if v.rows_affected() > 0 {
+                    Ok(())
+                } else {
+                    Err(database::Error::ReportNotFound)
+                }
+            })
+    }
+
+    #[tracing::instrument(level = "warn", skip_all)]
+    async fn insert_bookmark(&self, user_id: UserId, torrent_id: TorrentId) -> Result<(), database::Error> {
+        query("INSERT OR IGNORE INTO torrust_user_bookmarks (user_id, torrent_id, date_created) VALUES (?, ?, ?)")
+            .bind(user_id)
+            .bind(torrent_id)
+            .bind(datetime_now())
+            .execute(&self.pool)
+            .await
+            .map(|_| ())
+            .map_err(|_| database::Error::Error)
+    }
+
+    #[tracing::instrument(level = "warn", skip_all)]
+    async fn delete_bookmark(&self, user_id: UserId, torrent_id: TorrentId) -> Result<(), database::Error> {
+        query("DELETE FROM torrust_user_bookmarks WHERE user_id = ? AND torrent_id = ?")
+            .bind(user_id)
+            .bind(torrent_id)
+            .execute(&self.pool)
+            .await
+            .map_err(|_| database::Error::Error)
+            .and_then(|v| {
+                if v.rows_affected() > 0 {
+                    Ok(())
+                } else {
+                    Err(database::Error::BookmarkNotFound)
+                }
+            })
+    }
+
+    #[tracing::instrument(level = "warn", skip_all)]
+    async fn is_torrent_bookmarked(&self, user_id: UserId, torrent_id: TorrentId) -> Result<bool, database::Error> {
+        query_as::<_, (i64,)>("SELECT COUNT(*) FROM torrust_user_bookmarks WHERE user_id = ? AND torrent_id = ?")
+            .bind(user_id)
+            .bind(torrent_id)
+            .fetch_one(&self.pool)
+            .await
+            .map(|(count,)| count > 0)
+            .map_err(|_| database::Error::Error)
+    }
+
+    #[tracing::instrument(level = "warn", skip_all)]
+    async fn get_user_bookmarked_torrent_ids(&self, user_id: UserId) -> Result<Vec<TorrentId>, database::Error> {
+        query_as::<_, (TorrentId,)>(
+            "SELECT torrent_id FROM torrust_user_bookmarks WHERE user_id = ? ORDER BY date_created DESC, torrent_id DESC",
+        )
+        .bind(user_id)
+        .fetch_all(&self.pool)
+        .await
+        .map(|v| v.into_iter().map(|(torrent_id,)| torrent_id).collect())
+        .map_err(|_| database::Error::Error)
+    }
+
+    #[tracing::instrument(level = "warn", skip_all)]
+    async fn insert_subscription(
+        &self,
+        user_id: UserId,
+        category_id: Option<CategoryId>,
+        tag_id: Option<TagId>,
+    ) -> Result<SubscriptionId, database::Error> {
+        query("INSERT INTO torrust_user_subscriptions (user_id, category_id, tag_id, date_created) VALUES (?, ?, ?, ?)")
+            .bind(user_id)
+            .bind(category_id)
+            .bind(tag_id)
+            .bind(datetime_now())
+            .execute(&self.pool)
+            .await
+            .map(|v| v.last_insert_rowid())
+            .map_err(|_| database::Error::Error)
+    }
+
+    #[tracing::instrument(level = "warn", skip_all)]
+    async fn get_subscription(&self, subscription_id: SubscriptionId) -> Result<Subscription, database::Error> {
+        query_as::<_, Subscription>(
+            "SELECT ts.subscription_id, ts.user_id, tp.username, ts.category_id, tc.name AS category, ts.tag_id, tt.name AS tag, ts.date_created
+            FROM torrust_user_subscriptions ts
+            INNER JOIN torrust_user_profiles tp ON ts.user_id = tp.user_id
+            LEFT JOIN torrust_categories tc ON ts.category_id = tc.category_id
+            LEFT JOIN torrust_torrent_tags tt ON ts.tag_id = tt.tag_id
+            WHERE ts.subscription_id = ?",
+        )
+        .bind(subscription_id)
+        .fetch_one(&self.pool)
+        .await
+        .map_err(|_| database::Error::SubscriptionNotFound)
+    }
+
+    #[tracing::instrument(level = "warn", skip_all)]
+    async fn get_user_subscriptions(&self, user_id: UserId) -> Result<Vec<Subscription>, database::Error> {
+        query_as::<_, Subscription>(
+            "SELECT ts.subscription_id, ts.user_id, tp.username, ts.category_id, tc.name AS category, ts.tag_id, tt.name AS tag, ts.date_created
+            FROM torrust_user_subscriptions ts
+            INNER JOIN torrust_user_profiles tp ON ts.user_id = tp.user_id
+            LEFT JOIN torrust_categories tc ON ts.category_id = tc.category_id
+            LEFT JOIN torrust_torrent_tags tt ON ts.tag_id = tt.tag_id
+            WHERE ts.user_id = ?
+            ORDER BY ts.subscription_id ASC",
+        )
+        .bind(user_id)
+        .fetch_all(&self.pool)
+        .await
+        .map_err(|_| database::Error::Error)
+    }
+
+    #[tracing::instrument(level = "warn", skip_all)]
+    async fn get_all_subscriptions(&self) -> Result<Vec<Subscription>, database::Error> {
+        query_as::<_, Subscription>(
+            "SELECT ts.subscription_id, ts.user_id, tp.username, ts.category_id, tc.name AS category, ts.tag_id, tt.name AS tag, ts.date_created
+            FROM torrust_user_subscriptions ts
+            INNER JOIN torrust_user_profiles tp ON ts.user_id = tp.user_id
+            LEFT JOIN torrust_categories tc ON ts.category_id = tc.category_id
+            LEFT JOIN torrust_torrent_tags tt ON ts.tag_id = tt.tag_id
+            ORDER BY ts.subscription_id ASC",
+        )
+        .fetch_all(&self.pool)
+        .await
+        .map_err(|_| database::Error::Error)
+    }
+
+    #[tracing::instrument(level = "warn", skip_all)]
+    async fn delete_subscription(&self, subscription_id: SubscriptionId) -> Result<(), database::Error> {
+        query("DELETE FROM torrust_user_subscriptions WHERE subscription_id = ?")
+            .bind(subscription_id)
+            .execute(&self.pool)
+            .await
+            .map_err(|_| database::Error::Error)
+            .and_then(|v| {
+                if v.rows_affected() > 0 {
+                    Ok(())
+                } else {
+                    Err(database::Error::SubscriptionNotFound)
+                }
+            })
+    }
+
+    #[tracing::instrument(level = "warn", skip_all)]
+    async fn get_torrent_listing_version(&self) -> Result<String, database::Error> {
+        query_as::<_, (i64, Option<String>, Option<String>)>(
+            "SELECT
+                (SELECT COUNT(*) FROM torrust_torrents),
+                (SELECT MAX(date_uploaded) FROM torrust_torrents),
+                (SELECT MAX(updated_at) FROM torrust_torrent_tracker_stats)",
+        )
+        .fetch_one(&self.pool)
+        .await
+        .map(|(count, last_uploaded, last_stats_update)| {
+            format!(
+                "{count}-{}-{}",
+                last_uploaded.unwrap_or_default(),
+                last_stats_update.unwrap_or_default()
+            )
+        })
+        .map_err(|_| database::Error::Error)
+    }
+    #[tracing::instrument(level = "warn", skip_all)]
+    async fn update_torrent_announce_urls(&self, torrent_id: i64, tiers: &[Vec<String>]) -> Result<(), database::Error> {
+        let mut tx = self
+            .pool
+            .begin()
+            .await
+            .map_err(|err| database::Error::ErrorWithText(err.to_string()))?;
+
+        query("DELETE FROM torrust_torrent_announce_urls WHERE torrent_id = ?")
+            .bind(torrent_id)
+            .execute(&mut *tx)
+            .await
+            .map_err(|err| database::Error::ErrorWithText(err.to_string()))?;
+
+        for (tier, tracker_urls) in tiers.iter().enumerate() {
+            for tracker_url in tracker_urls {
+                query("INSERT INTO torrust_torrent_announce_urls (torrent_id, tracker_url, tier) VALUES (?, ?, ?)")
+                    .bind(torrent_id)
+                    .bind(tracker_url)
+                    .bind(u32::try_from(tier).unwrap_or(u32::MAX))
+                    .execute(&mut *tx)
+                    .await
+                    .map_err(|err| database::Error::ErrorWithText(err.to_string()))?;
+            }
+        }
+
+        tx.commit()
+            .await
+            .map_err(|err| database::Error::ErrorWithText(err.to_string()))
+    }
+    #[tracing::instrument(level = "warn", skip_all)]
+    async fn increment_download_client_stats(
+        &self,
+        date: &str,
+        client_family: &str,
+        user_agent: &str,
+    ) -> Result<(), database::Error> {
+        query(
+            "INSERT INTO torrust_download_client_stats (date, client_family, user_agent, downloads) VALUES (?, ?, ?, 1)
+            ON CONFLICT (date, client_family, user_agent) DO UPDATE SET downloads = downloads + 1",
+        )
+        .bind(date)
+        .bind(client_family)
+        .bind(user_agent)
+        .execute(&self.pool)
+        .await
+        .map(|_| ())
+        .map_err(|_| database::Error::Error)
+    }
+
+    #[tracing::instrument(level = "warn", skip_all)]
+    async fn get_download_client_family_stats(&self, since: &str) -> Result<Vec<DownloadCount>, database::Error> {
+        query_as::<_, DownloadCount>(
+            "SELECT client_family AS name, SUM(downloads) AS downloads
+            FROM torrust_download_client_stats
+            WHERE date >= ?
+            GROUP BY client_family
+            ORDER BY downloads DESC, name ASC",
+        )
+        .bind(since)
+        .fetch_all(&self.pool)
+        .await
+        .map_err(|_| database::Error::Error)
+    }
+
+    #[tracing::instrument(level = "warn", skip_all)]
+    async fn get_download_user_agent_stats(&self, since: &str, limit: u32) -> Result<Vec<DownloadCount>, database::Error> {
+        query_as::<_, DownloadCount>(
+            "SELECT user_agent AS name, SUM(downloads) AS downloads
+            FROM torrust_download_client_stats
+            WHERE date >= ? AND user_agent <> ''
+            GROUP BY user_agent
+            ORDER BY downloads DESC, name ASC
+            LIMIT ?",
+        )
+        .bind(since)
+        .bind(limit)
+        .fetch_all(&self.pool)
+        .await
+        .map_err(|_| database::Error::Error)
+    }
+
+    #[tracing::instrument(level = "warn", skip_all)]
+    async fn insert_magnet_upload(
+        &self,
+        user_id: UserId,
+        info_hash: &str,
+        magnet_uri: &str,
+        title: &str,
+        description: &str,
+        category_id: CategoryId,
+        tag_ids: &str,
+    ) -> Result<MagnetUploadId, database::Error> {
+        let now = datetime_now();
+
+        query(
+            "INSERT INTO torrust_magnet_uploads (user_id, info_hash, magnet_uri, title, description, category_id, tag_ids, status, date_created, date_updated) VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?)",
+        )
+        .bind(user_id)
+        .bind(info_hash)
+        .bind(magnet_uri)
+        .bind(title)
+        .bind(description)
+        .bind(category_id)
+        .bind(tag_ids)
+        .bind(MagnetUploadStatus::Resolving.to_string())
+        .bind(&now)
+        .bind(&now)
+        .execute(&self.pool)
+        .await
+        .map(|v| v.last_insert_rowid())
+        .map_err(|_| database::Error::Error)
+    }
+
+    #[tracing::instrument(level = "warn", skip_all)]
+    async fn get_magnet_upload(&self, magnet_upload_id: MagnetUploadId) -> Result<MagnetUpload, database::Error> {
+        query_as::<_, MagnetUpload>("SELECT magnet_upload_id, user_id, info_hash, magnet_uri, title, description, category_id, tag_ids, status, torrent_id, attempts, error, date_created, date_updated FROM torrust_magnet_uploads WHERE magnet_upload_id = ?")
+            .bind(magnet_upload_id)
+            .fetch_one(&self.pool)
+            .await
+            .map_err(|_| database::Error::MagnetUploadNotFound)
+    }
+
+    #[tracing::instrument(level = "warn", skip_all)]
+    async fn is_magnet_upload_resolving(&self, info_hash: &str) -> Result<bool, database::Error> {
+        query_as::<_, (i64,)>("SELECT COUNT(*) FROM torrust_magnet_uploads WHERE info_hash = ? AND status = ?")
+            .bind(info_hash)
+            .bind(MagnetUploadStatus::Resolving.to_string())
+            .fetch_one(&self.pool)
+            .await
+            .map(|(count,)| count > 0)
+            .map_err(|_| database::Error::Error)
+    }
+
+    #[tracing::instrument(level = "warn", skip_all)]
+    async fn get_resolving_magnet_uploads(&self, limit: u32) -> Result<Vec<MagnetUpload>, database::Error> {
+        query_as::<_, MagnetUpload>("SELECT magnet_upload_id, user_id, info_hash, magnet_uri, title, description, category_id, tag_ids, status, torrent_id, attempts, error, date_created, date_updated FROM torrust_magnet_uploads WHERE status = ? ORDER BY date_updated ASC, magnet_upload_id ASC LIMIT ?")
+            .bind(MagnetUploadStatus::Resolving.to_string())
+            .bind(limit)
+            .fetch_all(&self.pool)
+            .await
+            .map_err(|_| database::Error::Error)
+    }
+
+    #[tracing::instrument(level = "warn", skip_all)]
+    async fn update_magnet_upload(
+        &self,
+        magnet_upload_id: MagnetUploadId,
+        status: &MagnetUploadStatus,
+        torrent_id: Option<TorrentId>,
+        attempts: i64,
+        error: Option<&str>,
+    ) -> Result<(), database::Error> {
+        query(
+            "UPDATE torrust_magnet_uploads SET status = ?, torrent_id = ?, attempts = ?, error = ?, date_updated = ? WHERE magnet_upload_id = ?",
+        )
+        .bind(status.to_string())
+        .bind(torrent_id)
+        .bind(attempts)
+        .bind(error)
+        .bind(datetime_now())
+        .bind(magnet_upload_id)
+        .execute(&self.pool)
+        .await
+        .map_err(|_| database::Error::Error)
+        .and_then(|v| {
+            if v.rows_affected() > 0 {
+                Ok(())
+            } else {
+                Err(database::Error::MagnetUploadNotFound)
+            }
+        })
+    }
+
+    #[tracing::instrument(level = "warn", skip_all)]
+    async fn get_torrents_by_size_range(
+        &self,
+        min_size: i64,
+        max_size: i64,
+        limit: u32,
+    ) -> Result<Vec<SimilarTorrent>, database::Error> {
+        query_as::<_, SimilarTorrent>(
+            "SELECT tt.torrent_id, tt.info_hash, ti.title, tt.size AS file_size
+            FROM torrust_torrents tt
+            INNER JOIN torrust_torrent_info ti ON tt.torrent_id = ti.torrent_id
+            WHERE tt.size BETWEEN ? AND ?
+            ORDER BY tt.date_uploaded DESC
+            LIMIT ?",
+        )
+        .bind(min_size)
+        .bind(max_size)
+        .bind(limit)
+        .fetch_all(&self.pool)
+        .await
+        .map_err(|_| database::Error::Error)
+    }
+
+    #[tracing::instrument(level = "warn", skip_all)]
+    async fn get_users_paginated(&self, offset: u64, limit: u32) -> Result<Vec<UserFull>, database::Error> {
+        query_as::<_, UserFull>(
+            r#"SELECT tu.user_id, tu.date_registered, tu.date_imported, tu.administrator, tp.username, COALESCE(tp.email, "") AS email, tp.email_verified, COALESCE(tp.bio, "") AS bio, COALESCE(tp.avatar, "") AS avatar
+            FROM torrust_users tu
+            INNER JOIN torrust_user_profiles tp ON tu.user_id = tp.user_id
+            ORDER BY tu.user_id
+            LIMIT ? OFFSET ?"#,
+        )
+        .bind(limit)
+        .bind(i64::try_from(offset).unwrap_or(i64::MAX))
+        .fetch_all(&self.pool)
+        .await
+        .map_err(|_| database::Error::Error)?
+        .into_iter()
+        .map(|mut user| {
+            user.email = self.decrypt(Column::UserEmail, user.email)?;
+            Ok(user)
+        })
+        .collect()
+    }
+
+    #[tracing::instrument(level = "warn", skip_all)]
+    async fn update_torrent_date_uploaded(&self, torrent_id: TorrentId, date_uploaded: &str) -> Result<(), database::Error> {
+        query("UPDATE torrust_torrents SET date_uploaded = ? WHERE torrent_id = ?")
+            .bind(date_uploaded)
+            .bind(torrent_id)
+            .execute(&self.pool)
+            .await
+            .map_err(|_| database::Error::Error)
+            .and_then(|v| {
+                if v.rows_affected() > 0 {
+                    Ok(())
+                } else {
+                    Err(database::Error::TorrentNotFound)
+                }
+            })
+    }
+
+    #[tracing::instrument(level = "warn", skip_all)]
+    async fn update_user_last_login(&self, user_id: UserId) -> Result<(), database::Error> {
+        query("UPDATE torrust_users SET date_last_login = ?, date_inactivity_warning = NULL WHERE user_id = ?")
+            .bind(datetime_now())
+            .bind(user_id)
+            .execute(&self.pool)
+            .await
+            .map_err(|_| database::Error::Error)
+            .and_then(|v| {
+                if v.rows_affected() > 0 {
+                    Ok(())
+                } else {
+                    Err(database::Error::UserNotFound)
+                }
+            })
+    }
+
+    #[tracing::instrument(level = "warn", skip_all)]
+    async fn get_email_recipients(
+        &self,
+        segment: &Segment,
+        include_unsubscribed: bool,
+    ) -> Result<Vec<Recipient>, database::Error> {
+        let mut sql = String::from(
+            "SELECT tu.user_id, tp.username, tp.email
+            FROM torrust_users tu
+            INNER JOIN torrust_user_profiles tp ON tu.user_id = tp.user_id
+            WHERE tp.email IS NOT NULL AND tp.email <> '' AND tp.email_verified = TRUE",
+        );
+
+        match segment {
+            Segment::All => {}
+            Segment::Active { .. } => sql.push_str(" AND tu.date_last_login >= ?"),
+            Segment::Role {
+                role: Role::Administrator,
+            } => sql.push_str(" AND tu.administrator = TRUE"),
+            Segment::Role { role: Role::User } => sql.push_str(" AND tu.administrator = FALSE"),
+        }
+
+        if !include_unsubscribed {
+            sql.push_str(" AND tu.user_id NOT IN (SELECT user_id FROM torrust_user_email_unsubscribes)");
+        }
+
+        sql.push_str(" ORDER BY tu.user_id ASC");
+
+        let mut query = query_as::<_, Recipient>(&sql);
+
+        if let Segment::Active { days } = segment {
+            let since = clock::seconds_ago_utc(i64::from(*days) * 86_400);
+            query = query.bind(since.format(DATETIME_FORMAT).to_string());
+        }
+
+        query
+            .fetch_all(&self.pool)
+            .await
+            .map_err(|_| database::Error::Error)?
+            .into_iter()
+            .map(|mut recipient| {
+                recipient.email = self.decrypt(Column::UserEmail, recipient.email)?;
+                Ok(recipient)
+            })
+            .collect()
+    }
+
+    #[tracing::instrument(level = "warn", skip_all)]
+    async fn insert_email_unsubscribe(&self, user_id: UserId) -> Result<(), database::Error> {
+        query("INSERT OR IGNORE INTO torrust_user_email_unsubscribes (user_id, date_created) VALUES (?, ?)")
+            .bind(user_id)
+            .bind(datetime_now())
+            .execute(&self.pool)
+            .await
+            .map(|_| ())
+            .map_err(|_| database::Error::Error)
+    }
+
+    #[tracing::instrument(level = "warn", skip_all)]
+    async fn insert_queued_email(&self, recipient: &str, subject: &str, body: &str) -> Result<EmailId, database::Error> {
+        query("INSERT INTO torrust_email_queue (recipient, subject, body, date_created) VALUES (?, ?, ?, ?)")
+            .bind(self.encrypt(Column::QueuedEmailRecipient, recipient))
+            .bind(subject)
+            .bind(body)
+            .bind(datetime_now())
+            .execute(&self.pool)
+            .await
+            .map(|v| v.last_insert_rowid())
+            .map_err(|_| database::Error::Error)
+    }
+
+    #[tracing::instrument(level = "warn", skip_all)]
+    async fn get_queued_emails(&self, limit: u32) -> Result<Vec<QueuedEmail>, database::Error> {
+        query_as::<_, QueuedEmail>(
+            "SELECT email_id, recipient, subject, body, attempts FROM torrust_email_queue ORDER BY email_id ASC LIMIT ?",
+        )
+        .bind(limit)
+        .fetch_all(&self.pool)
+        .await
+        .map_err(|_| database::Error::Error)?
+        .into_iter()
+        .map(|mut email| {
+            email.recipient = self.decrypt(Column::QueuedEmailRecipient, email.recipient)?;
+            Ok(email)
+        })
+        .collect()
+    }
+
+    #[tracing::instrument(level = "warn", skip_all)]
+    async fn increase_queued_email_attempts(&self, email_id: EmailId) -> Result<(), database::Error> {
+        query("UPDATE torrust_email_queue SET attempts = attempts + 1 WHERE email_id = ?")
+            .bind(email_id)
+            .execute(&self.pool)
+            .await
+            .map(|_| ())
+            .map_err(|_| database::Error::Error)
+    }
+
+    #[tracing::instrument(level = "warn", skip_all)]
+    async fn delete_queued_email(&self, email_id: EmailId) -> Result<(), database::Error> {
+        query("DELETE FROM torrust_email_queue WHERE email_id = ?")
+            .bind(email_id)
+            .execute(&self.pool)
+            .await
+            .map(|_| ())
+            .map_err(|_| database::Error::Error)
+    }
+
+    #[tracing::instrument(level = "warn", skip_all)]
+    async fn get_inactive_users(&self, since: &str) -> Result<Vec<InactiveUser>, database::Error> {
+        query_as::<_, InactiveUser>(
+            r#"SELECT tu.user_id, tp.username, COALESCE(tp.email, "") AS email, tp.email_verified,
+                COALESCE(tu.date_last_login, tu.date_registered, tu.date_imported) AS last_activity,
+                tu.date_inactivity_warning,
+                (SELECT COUNT(*) FROM torrust_torrents tt WHERE tt.uploader_id = tu.user_id) AS uploads
+            FROM torrust_users tu
+            INNER JOIN torrust_user_profiles tp ON tu.user_id = tp.user_id
+            WHERE tu.administrator = FALSE
+                AND tu.date_disabled IS NULL
+                AND tu.user_id NOT IN (SELECT user_id FROM torrust_user_inactivity_exemptions)
+                AND COALESCE(tu.date_last_login, tu.date_registered, tu.date_imported) < ?
+            ORDER BY COALESCE(tu.date_last_login, tu.date_registered, tu.date_imported) ASC, tu.user_id ASC"#,
+        )
+        .bind(since)
+        .fetch_all(&self.pool)
+        .await
+        .map_err(|_| database::Error::Error)?
+        .into_iter()
+        .map(|mut user| {
+            user.email = self.decrypt(Column::UserEmail, user.email)?;
+            Ok(user)
+        })
+        .collect()
+    }
+
+    #[tracing::instrument(level = "warn", skip_all)]
+    async fn update_user_inactivity_warning(&self, user_id: UserId) -> Result<(), database::Error> {
+        query("UPDATE torrust_users SET date_inactivity_warning = ? WHERE user_id = ?")
+            .bind(datetime_now())
+            .bind(user_id)
+            .execute(&self.pool)
+            .await
+            .map_err(|_| database::Error::Error)
+            .and_then(|v| {
+                if v.rows_affected() > 0 {
+                    Ok(())
+                } else {
+                    Err(database::Error::UserNotFound)
+                }
+            })
+    }
+
+    #[tracing::instrument(level = "warn", skip_all)]
+    async fn update_user_disabled(&self, user_id: UserId, disabled: bool) -> Result<(), database::Error> {
+        let date_disabled = if disabled { Some(datetime_now()) } else { None };
+
+        query("UPDATE torrust_users SET date_disabled = ?, date_inactivity_warning = NULL WHERE user_id = ?")
+            .bind(date_disabled)
+            .bind(user_id)
+            .execute(&self.pool)
+            .await
+            .map_err(|_| database::Error::Error)
+            .and_then(|v| {
+                if v.rows_affected() > 0 {
+                    Ok(())
+                } else {
+                    Err(database::Error::UserNotFound)
+                }
+            })
+    }
+
+    #[tracing::instrument(level = "warn", skip_all)]
+    async fn is_user_disabled(&self, user_id: UserId) -> Result<bool, database::Error> {
+        query_as::<_, (i64,)>("SELECT COUNT(*) FROM torrust_users WHERE user_id = ? AND date_disabled IS NOT NULL")
+            .bind(user_id)
+            .fetch_one(&self.pool)
+            .await
+            .map(|(count,)| count > 0)
+            .map_err(|_| database::Error::Error)
+    }
+
+    #[tracing::instrument(level = "warn", skip_all)]
+    async fn insert_inactivity_exemption(&self, user_id: UserId) -> Result<(), database::Error> {
+        query("INSERT OR IGNORE INTO torrust_user_inactivity_exemptions (user_id, date_created) VALUES (?, ?)")
+            .bind(user_id)
+            .bind(datetime_now())
+            .execute(&self.pool)
+            .await
+            .map(|_| ())
+            .map_err(|_| database::Error::Error)
+    }
+
+    #[tracing::instrument(level = "warn", skip_all)]
+    async fn delete_inactivity_exemption(&self, user_id: UserId) -> Result<(), database::Error> {
+        query("DELETE FROM torrust_user_inactivity_exemptions WHERE user_id = ?")
+            .bind(user_id)
+            .execute(&self.pool)
+            .await
+            .map(|_| ())
+            .map_err(|_| database::Error::Error)
+    }
+
+    #[tracing::instrument(level = "warn", skip_all)]
+    async fn get_inactivity_exemptions(&self) -> Result<Vec<String>, database::Error> {
+        query_as::<_, (String,)>(
+            "SELECT tp.username
+            FROM torrust_user_inactivity_exemptions te
+            INNER JOIN torrust_user_profiles tp ON te.user_id = tp.user_id
+            ORDER BY tp.username ASC",
+        )
+        .fetch_all(&self.pool)
+        .await
+        .map(|v| v.into_iter().map(|(username,)| username).collect())
+        .map_err(|_| database::Error::Error)
+    }
+
+    #[tracing::instrument(level = "warn", skip_all)]
+    async fn insert_image(
+        &self,
+        user_id: UserId,
+        kind: ImageKind,
+        object_key: &str,
+        url: &str,
+        content_type: &str,
+        size: u64,
+    ) -> Result<ImageId, database::Error> {
+        query("INSERT INTO torrust_images (user_id, kind, object_key, url, content_type, size, date_created) VALUES (?, ?, ?, ?, ?, ?, ?)")
+            .bind(user_id)
+            .bind(kind.as_str())
+            .bind(object_key)
+            .bind(url)
+            .bind(content_type)
+            .bind(i64::try_from(size).unwrap_or(i64::MAX))
+            .bind(datetime_now())
+            .execute(&self.pool)
+            .await
+            .map(|v| v.last_insert_rowid())
+            .map_err(|e| match e {
+                sqlx::Error::Database(err) if err.message().contains("UNIQUE") => {
+                    database::Error::ImageAlreadyExists
+                }
+                _ => database::Error::Error,
+            })
+    }
+
+    #[tracing::instrument(level = "warn", skip_all)]
+    async fn update_user_avatar(&self, user_id: UserId, avatar: &str) -> Result<(), database::Error> {
+        query("UPDATE torrust_user_profiles SET avatar = ? WHERE user_id = ?")
+            .bind(avatar)
+            .bind(user_id)
+            .execute(&self.pool)
+            .await
+            .map_err(|_| database::Error::Error)
+            .and_then(|v| {
+                if v.rows_affected() > 0 {
+                    Ok(())
+                } else {
+                    Err(database::Error::UserNotFound)
+                }
+            })
+    }
+
+    #[tracing::instrument(level = "warn", skip_all)]
+    async fn update_user_email(&self, user_id: UserId, email: &str) -> Result<(), database::Error> {
+        query(r#"UPDATE torrust_user_profiles SET email = NULLIF(?, "") WHERE user_id = ?"#)
+            .bind(self.encrypt(Column::UserEmail, email))
+            .bind(user_id)
+            .execute(&self.pool)
+            .await
+            .map_err(|e| match e {
+                sqlx::Error::Database(err) if err.message().contains("email") => database::Error::EmailTaken,
+                _ => database::Error::Error,
+            })
+            .and_then(|v| {
+                if v.rows_affected() > 0 {
+                    Ok(())
+                } else {
+                    Err(database::Error::UserNotFound)
+                }
+            })
+    }
+
+    #[tracing::instrument(level = "warn", skip_all)]
+    async fn get_user_bookmarks(&self, user_id: UserId) -> Result<Vec<Bookmark>, database::Error> {
+        query_as::<_, Bookmark>(
+            "SELECT tt.info_hash, ti.title, ub.date_created
+            FROM torrust_user_bookmarks ub
+            INNER JOIN torrust_torrents tt ON ub.torrent_id = tt.torrent_id
+            INNER JOIN torrust_torrent_info ti ON ub.torrent_id = ti.torrent_id
+            WHERE ub.user_id = ?
+            ORDER BY ub.date_created DESC, ub.torrent_id DESC",
+        )
+        .bind(user_id)
+        .fetch_all(&self.pool)
+        .await
+        .map_err(|_| database::Error::Error)
+    }
+
+    #[tracing::instrument(level = "warn", skip_all)]
+    async fn insert_bookmark_with_date(
+        &self,
+        user_id: UserId,
+        torrent_id: TorrentId,
+        date_created: &str,
+    ) -> Result<bool, database::Error> {
+        query("INSERT OR IGNORE INTO torrust_user_bookmarks (user_id, torrent_id, date_created) VALUES (?, ?, ?)")
+            .bind(user_id)
+            .bind(torrent_id)
+            .bind(date_created)
+            .execute(&self.pool)
+            .await
+            .map(|v| v.rows_affected() > 0)
+            .map_err(|_| database::Error::Error)
+    }
+
+    #[tracing::instrument(level = "warn", skip_all)]
+    async fn insert_collection(
+        &self,
+        user_id: UserId,
+        name: &str,
+        description: Option<&str>,
+    ) -> Result<CollectionId, database::Error> {
+        query("INSERT INTO torrust_collections (user_id, name, description, date_created) VALUES (?, ?, ?, ?)")
+            .bind(user_id)
+            .bind(name)
+            .bind(description)
+            .bind(datetime_now())
+            .execute(&self.pool)
+            .await
+            .map(|v| v.last_insert_rowid())
+            .map_err(|_| database::Error::Error)
+    }
+
+    #[tracing::instrument(level = "warn", skip_all)]
+    async fn get_collection(&self, collection_id: CollectionId) -> Result<Collection, database::Error> {
+        query_as::<_, Collection>(
+            "SELECT tc.collection_id, tc.user_id, tp.username AS owner, tc.name, tc.description, tc.date_created,
+            COUNT(tt.torrent_id) AS torrent_count,
+            CAST(COALESCE(SUM(tt.size), 0) AS signed) AS total_size,
+            CAST(COALESCE(SUM(stats.seeders), 0) AS signed) AS seeders,
+            CAST(COALESCE(SUM(stats.leechers), 0) AS signed) AS leechers,
+            tc.smart
+            FROM torrust_collections tc
+            INNER JOIN torrust_user_profiles tp ON tc.user_id = tp.user_id
+            LEFT JOIN torrust_collection_items ci ON tc.collection_id = ci.collection_id
+            LEFT JOIN torrust_torrents tt ON ci.torrent_id = tt.torrent_id AND tt.status = 'approved'
+            LEFT JOIN (
+                SELECT torrent_id, SUM(seeders) AS seeders, SUM(leechers) AS leechers
+                FROM torrust_torrent_tracker_stats
+                GROUP BY torrent_id
+            ) stats ON tt.torrent_id = stats.torrent_id
+            WHERE tc.collection_id = ?
+            GROUP BY tc.collection_id, tc.user_id, tp.username, tc.name, tc.description, tc.date_created, tc.smart",
+        )
+        .bind(collection_id)
+        .fetch_one(&self.pool)
+        .await
+        .map_err(|_| database::Error::CollectionNotFound)
+    }
+
+    #[tracing::instrument(level = "warn", skip_all)]
+    async fn get_user_collections(&self, user_id: UserId) -> Result<Vec<Collection>, database::Error> {
+        query_as::<_, Collection>(
+            "SELECT tc.collection_id, tc.user_id, tp.username AS owner, tc.name, tc.description, tc.date_created,
+            COUNT(tt.torrent_id) AS torrent_count,
+            CAST(COALESCE(SUM(tt.size), 0) AS signed) AS total_size,
+            CAST(COALESCE(SUM(stats.seeders), 0) AS signed) AS seeders,
+            CAST(COALESCE(SUM(stats.leechers), 0) AS signed) AS leechers,
+            tc.smart
+            FROM torrust_collections tc
+            INNER JOIN torrust_user_profiles tp ON tc.user_id = tp.user_id
+            LEFT JOIN torrust_collection_items ci ON tc.collection_id = ci.collection_id
+            LEFT JOIN torrust_torrents tt ON ci.torrent_id = tt.torrent_id AND tt.status = 'approved'
+            LEFT JOIN (
+                SELECT torrent_id, SUM(seeders) AS seeders, SUM(leechers) AS leechers
+                FROM torrust_torrent_tracker_stats
+                GROUP BY torrent_id
+            ) stats ON tt.torrent_id = stats.torrent_id
+            WHERE tc.user_id = ?
+            GROUP BY tc.collection_id, tc.user_id, tp.username, tc.name, tc.description, tc.date_created, tc.smart
+            ORDER BY tc.collection_id ASC",
+        )
+        .bind(user_id)
+        .fetch_all(&self.pool)
+        .await
+        .map_err(|_| database::Error::Error)
+    }
+
+    #[tracing::instrument(level = "warn", skip_all)]
+    async fn update_collection(
+        &self,
+        collection_id: CollectionId,
+        name: &str,
+        description: Option<&str>,
+    ) -> Result<(), database::Error> {
+        query("UPDATE torrust_collections SET name = ?, description = ? WHERE collection_id = ?")
+            .bind(name)
+            .bind(description)
+            .bind(collection_id)
+            .execute(&self.pool)
+            .await
+            .map(|_| ())
+            .map_err(|_| database::Error::Error)
+    }
+
+    #[tracing::instrument(level = "warn", skip_all)]
+    async fn delete_collection(&self, collection_id: CollectionId) -> Result<(), database::Error> {
+        query("DELETE FROM torrust_collections WHERE collection_id = ?")
+            .bind(collection_id)
+            .execute(&self.pool)
+            .await
+            .map_err(|_| database::Error::Error)
+            .and_then(|v| {
+                if v.rows_affected() > 0 {
+                    Ok(())
+                } else {
+                    Err(database::Error::CollectionNotFound)
+                }
+            })
+    }
+
+    #[tracing::instrument(level = "warn", skip_all)]
+    async fn insert_collection_item(&self, collection_id: CollectionId, torrent_id: TorrentId) -> Result<(), database::Error> {
+        query("INSERT OR IGNORE INTO torrust_collection_items (collection_id, torrent_id, date_added) VALUES (?, ?, ?)")
+            .bind(collection_id)
+            .bind(torrent_id)
+            .bind(datetime_now())
+            .execute(&self.pool)
+            .await
+            .map(|_| ())
+            .map_err(|_| database::Error::Error)
+    }
+
+    #[tracing::instrument(level = "warn", skip_all)]
+    async fn delete_collection_item(&self, collection_id: CollectionId, torrent_id: TorrentId) -> Result<(), database::Error> {
+        query("DELETE FROM torrust_collection_items WHERE collection_id = ? AND torrent_id = ?")
+            .bind(collection_id)
+            .bind(torrent_id)
+            .execute(&self.pool)
+            .await
+            .map_err(|_| database::Error::Error)
+            .and_then(|v| {
+                if v.rows_affected() > 0 {
+                    Ok(())
+                } else {
+                    Err(database::Error::CollectionItemNotFound)
+                }
+            })
+    }
+
+    #[tracing::instrument(level = "warn", skip_all)]
+    async fn get_collection_torrent_ids(&self, collection_id: CollectionId) -> Result<Vec<TorrentId>, database::Error> {
+        query_as::<_, (TorrentId,)>(
+            "SELECT torrent_id FROM torrust_collection_items WHERE collection_id = ? ORDER BY date_added DESC, torrent_id DESC",
+        )
+        .bind(collection_id)
+        .fetch_all(&self.pool)
+        .await
+        .map(|v| v.into_iter().map(|(torrent_id,)| torrent_id).collect())
+        .map_err(|_| database::Error::Error)
+    }
+
+    #[tracing::instrument(level = "warn", skip_all)]
+    async fn delete_rows_older_than(&self, table: &str, date_column: &str, cutoff: &str) -> Result<u64, database::Error> {
+        query(&format!("DELETE FROM {table} WHERE {date_column} < ?"))
+            .bind(cutoff)
+            .execute(&self.pool)
+            .await
+            .map(|v| v.rows_affected())
+            .map_err(|_| database::Error::Error)
+    }
+
+    #[tracing::instrument(level = "warn", skip_all)]
+    async fn get_user_upload_usage(&self, user_id: UserId, since: &str) -> Result<UploadUsage, database::Error> {
+        query_as::<_, (i64, i64)>(
+            "SELECT
+            (SELECT COUNT(*) FROM torrust_torrents WHERE uploader_id = ? AND date_uploaded >= ?)
+            + (SELECT COUNT(*) FROM torrust_magnet_uploads WHERE user_id = ? AND status = 'resolving' AND date_created >= ?),
+            (SELECT CAST(COALESCE(SUM(size), 0) AS signed) FROM torrust_torrents WHERE uploader_id = ?)",
+        )
+        .bind(user_id)
+        .bind(since)
+        .bind(user_id)
+        .bind(since)
+        .bind(user_id)
+        .fetch_one(&self.pool)
+        .await
+        .map(|(uploads_last_day, total_upload_size)| UploadUsage {
+            uploads_last_day: u64::try_from(uploads_last_day).unwrap_or_default(),
+            total_upload_size: u64::try_from(total_upload_size).unwrap_or_default(),
+        })
+        .map_err(|_| database::Error::Error)
+    }
+
+    #[tracing::instrument(level = "warn", skip_all)]
+    async fn get_user_upload_limits(&self, user_id: UserId) -> Result<Option<UploadLimits>, database::Error> {
+        query_as::<_, (Option<i64>, Option<i64>)>(
+            "SELECT max_uploads_per_day, max_total_upload_size FROM torrust_user_upload_quotas WHERE user_id = ?",
+        )
+        .bind(user_id)
+        .fetch_optional(&self.pool)
+        .await
+        .map(|row| {
+            row.map(|(max_uploads_per_day, max_total_upload_size)| UploadLimits {
+                max_uploads_per_day: max_uploads_per_day.and_then(|v| u32::try_from(v).ok()),
+                max_total_upload_size: max_total_upload_size.and_then(|v| u64::try_from(v).ok()),
+            })
+        })
+        .map_err(|_| database::Error::Error)
+    }
+
+    #[tracing::instrument(level = "warn", skip_all)]
+    async fn upsert_user_upload_limits(&self, user_id: UserId, limits: &UploadLimits) -> Result<(), database::Error> {
+        query(
+            "INSERT INTO torrust_user_upload_quotas (user_id, max_uploads_per_day, max_total_upload_size, date_updated)
+            VALUES (?, ?, ?, ?)
+            ON CONFLICT (user_id) DO UPDATE SET
+            max_uploads_per_day = excluded.max_uploads_per_day,
+            max_total_upload_size = excluded.max_total_upload_size,
+            date_updated = excluded.date_updated",
+        )
+        .bind(user_id)
+        .bind(limits.max_uploads_per_day)
+        .bind(limits.max_total_upload_size.map(|v| i64::try_from(v).unwrap_or(i64::MAX)))
+        .bind(datetime_now())
+        .execute(&self.pool)
+        .await
+        .map(|_| ())
+        .map_err(|_| database::Error::Error)
+    }
+
+    #[tracing::instrument(level = "warn", skip_all)]
+    async fn delete_user_upload_limits(&self, user_id: UserId) -> Result<(), database::Error> {
+        query("DELETE FROM torrust_user_upload_quotas WHERE user_id = ?")
+            .bind(user_id)
+            .execute(&self.pool)
+            .await
+            .map(|_| ())
+            .map_err(|_| database::Error::Error)
+    }
+
+    #[tracing::instrument(level = "warn", skip_all)]
+    async fn insert_category_moderator(&self, category_id: CategoryId, user_id: UserId) -> Result<(), database::Error> {
+        query("INSERT OR IGNORE INTO torrust_category_moderators (category_id, user_id, date_created) VALUES (?, ?, ?)")
+            .bind(category_id)
+            .bind(user_id)
+            .bind(datetime_now())
+            .execute(&self.pool)
+            .await
+            .map(|_| ())
+            .map_err(|_| database::Error::Error)
+    }
+
+    #[tracing::instrument(level = "warn", skip_all)]
+    async fn delete_category_moderator(&self, category_id: CategoryId, user_id: UserId) -> Result<(), database::Error> {
+        query("DELETE FROM torrust_category_moderators WHERE category_id = ? AND user_id = ?")
+            .bind(category_id)
+            .bind(user_id)
+            .execute(&self.pool)
+            .await
+            .map(|_| ())
+            .map_err(|_| database::Error::Error)
+    }
+
+    #[tracing::instrument(level = "warn", skip_all)]
+    async fn get_category_moderators(&self, category_id: CategoryId) -> Result<Vec<String>, database::Error> {
+        query_as::<_, (String,)>(
+            "SELECT tp.username
+            FROM torrust_category_moderators cm
+            INNER JOIN torrust_user_profiles tp ON cm.user_id = tp.user_id
+            WHERE cm.category_id = ?
+            ORDER BY tp.username ASC",
+        )
+        .bind(category_id)
+        .fetch_all(&self.pool)
+        .await
+        .map(|v| v.into_iter().map(|(username,)| username).collect())
+        .map_err(|_| database::Error::Error)
+    }
+
+    #[tracing::instrument(level = "warn", skip_all)]
+    async fn get_moderated_categories(&self, user_id: UserId) -> Result<Vec<Category>, database::Error> {
+        query_as::<_, Category>(
+            "SELECT tc.category_id, tc.name, COUNT(tt.category_id) AS num_torrents
+            FROM torrust_category_moderators cm
+            INNER JOIN torrust_categories tc ON cm.category_id = tc.category_id
+            LEFT JOIN torrust_torrents tt ON tc.category_id = tt.category_id
+            WHERE cm.user_id = ?
+            GROUP BY tc.category_id, tc.name
+            ORDER BY tc.name ASC",
+        )
+        .bind(user_id)
+        .fetch_all(&self.pool)
+        .await
+        .map_err(|_| database::Error::Error)
+    }
+
+    #[tracing::instrument(level = "warn", skip_all)]
+    async fn is_category_moderator(&self, category_id: CategoryId, user_id: UserId) -> Result<bool, database::Error> {
+        query_as::<_, (i64,)>("SELECT COUNT(*) FROM torrust_category_moderators WHERE category_id = ? AND user_id = ?")
+            .bind(category_id)
+            .bind(user_id)
+            .fetch_one(&self.pool)
+            .await
+            .map(|(count,)| count > 0)
+            .map_err(|_| database::Error::Error)
+    }
+
+    #[tracing::instrument(level = "warn", skip_all)]
+    async fn get_public_user_profile(&self, username: &str) -> Result<PublicProfile, database::Error> {
+        query_as::<_, PublicProfile>(
+            "SELECT tp.username, tu.date_registered AS date_registered, tp.bio, tp.avatar,
+                (SELECT COUNT(*) FROM torrust_torrents tt WHERE tt.uploader_id = tu.user_id AND tt.status = ?) AS uploads
+            FROM torrust_users tu
+            INNER JOIN torrust_user_profiles tp ON tu.user_id = tp.user_id
+            WHERE tp.username = ? COLLATE NOCASE",
+        )
+        .bind(TorrentStatus::Approved.to_string())
+        .bind(username)
+        .fetch_one(&self.pool)
+        .await
+        .map_err(|_| database::Error::UserNotFound)
+    }
+
+    #[tracing::instrument(level = "warn", skip_all)]
+    async fn update_user_profile(&self, user_id: UserId, bio: Option<&str>, avatar: Option<&str>) -> Result<(), database::Error> {
+        query("UPDATE torrust_user_profiles SET bio = ?, avatar = ? WHERE user_id = ?")
+            .bind(bio)
+            .bind(avatar)
+            .bind(user_id)
+            .execute(&self.pool)
+            .await
+            .map_err(|_| database::Error::Error)
+            .and_then(|v| {
+                if v.rows_affected() > 0 {
+                    Ok(())
+                } else {
+                    Err(database::Error::UserNotFound)
+                }
+            })
+    }
+
+    #[tracing::instrument(level = "warn", skip_all)]
+    async fn get_user_uploaded_torrent_ids(&self, user_id: UserId) -> Result<Vec<TorrentId>, database::Error> {
+        query_as::<_, (TorrentId,)>(
+            "SELECT torrent_id FROM torrust_torrents WHERE uploader_id = ? AND status = ? ORDER BY date_uploaded DESC, torrent_id DESC",
+        )
+        .bind(user_id)
+        .bind(TorrentStatus::Approved.to_string())
+        .fetch_all(&self.pool)
+        .await
+        .map(|v| v.into_iter().map(|(torrent_id,)| torrent_id).collect())
+        .map_err(|_| database::Error::Error)
+    }
+
+    #[tracing::instrument(level = "warn", skip_all)]
+    async fn add_user_api_usage(&self, record: &ApiUsageRecord) -> Result<(), database::Error> {
+        query(
+            "INSERT INTO torrust_user_api_usage (user_id, credential, date, requests, rate_limit_hits, last_ip, last_used)
+            VALUES (?, ?, ?, ?, ?, ?, ?)
+            ON CONFLICT (user_id, credential, date) DO UPDATE SET
+                requests = requests + excluded.requests,
+                rate_limit_hits = rate_limit_hits + excluded.rate_limit_hits,
+                last_ip = COALESCE(excluded.last_ip, last_ip),
+                last_used = excluded.last_used",
+        )
+        .bind(record.user_id)
+        .bind(&record.credential)
+        .bind(&record.date)
+        .bind(record.requests)
+        .bind(record.rate_limit_hits)
+        .bind(&record.last_ip)
+        .bind(&record.last_used)
+        .execute(&self.pool)
+        .await
+        .map(|_| ())
+        .map_err(|_| database::Error::Error)
+    }
+
+    #[tracing::instrument(level = "warn", skip_all)]
+    async fn get_user_api_usage(&self, user_id: UserId, since: &str) -> Result<Vec<ApiUsageRecord>, database::Error> {
+        query_as::<_, ApiUsageRecord>(
+            "SELECT user_id, credential, date AS date, requests, rate_limit_hits, last_ip, last_used AS last_used
+            FROM torrust_user_api_usage
+            WHERE user_id = ? AND date >= ?
+            ORDER BY date ASC, credential ASC",
+        )
+        .bind(user_id)
+        .bind(since)
+        .fetch_all(&self.pool)
+        .await
+        .map_err(|_| database::Error::Error)
+    }
+
+    #[tracing::instrument(level = "warn", skip_all)]
+    async fn get_sync_torrents(&self, after_torrent_id: TorrentId, limit: u32) -> Result<Vec<SyncTorrent>, database::Error> {
+        query_as::<_, SyncTorrent>(
+            "SELECT
+            tt.torrent_id,
+            tt.info_hash,
+            ti.title,
+            ti.description,
+            tc.name AS category,
+            (SELECT GROUP_CONCAT(tg.name) FROM torrust_torrent_tag_links tl
+                INNER JOIN torrust_torrent_tags tg ON tl.tag_id = tg.tag_id
+                WHERE tl.torrent_id = tt.torrent_id) AS tags,
+            tt.name,
+            tt.size AS file_size,
+            tt.date_uploaded AS date_uploaded,
+            tp.username AS uploader
+            FROM torrust_torrents tt
+            INNER JOIN torrust_torrent_info ti ON tt.torrent_id = ti.torrent_id
+            INNER JOIN torrust_user_profiles tp ON tt.uploader_id = tp.user_id
+            LEFT JOIN torrust_categories tc ON tt.category_id = tc.category_id
+            WHERE tt.torrent_id > ? AND tt.status = ?
+            ORDER BY tt.torrent_id ASC
+            LIMIT ?",
+        )
+        .bind(after_torrent_id)
+        .bind(TorrentStatus::Approved.to_string())
+        .bind(limit)
+        .fetch_all(&self.pool)
+        .await
+        .map_err(|_| database::Error::Error)
+    }
+
+    #[tracing::instrument(level = "warn", skip_all)]
+    async fn get_sync_states(&self) -> Result<Vec<SyncState>, database::Error> {
+        query_as::<_, SyncState>("SELECT torrent_id, partition_id, record_hash, sequence, deleted FROM torrust_sync_records")
+            .fetch_all(&self.pool)
+            .await
+            .map_err(|_| database::Error::Error)
+    }
+
+    #[tracing::instrument(level = "warn", skip_all)]
+    async fn get_last_sync_sequence(&self) -> Result<Sequence, database::Error> {
+        query_as::<_, (i64,)>("SELECT CAST(COALESCE(MAX(sequence), 0) AS SIGNED) FROM torrust_sync_records")
+            .fetch_one(&self.pool)
+            .await
+            .map(|(sequence,)| sequence)
+            .map_err(|_| database::Error::Error)
+    }
+
+    #[tracing::instrument(level = "warn", skip_all)]
+    async fn upsert_sync_states(&self, states: &[SyncState]) -> Result<(), database::Error> {
+        let mut tx = self
+            .pool
+            .begin()
+            .await
+            .map_err(|err| database::Error::ErrorWithText(err.to_string()))?;
+
+        for state in states {
+            query(
+                "INSERT INTO torrust_sync_records (torrent_id, partition_id, record_hash, sequence, deleted)
+                VALUES (?, ?, ?, ?, ?)
+                ON CONFLICT (torrent_id) DO UPDATE SET
+                partition_id = excluded.partition_id,
+                record_hash = excluded.record_hash,
+                sequence = excluded.sequence,
+                deleted = excluded.deleted",
+            )
+            .bind(state.torrent_id)
+            .bind(state.partition_id)
+            .bind(&state.record_hash)
+            .bind(state.sequence)
+            .bind(state.deleted)
+            .execute(&mut *tx)
+            .await
+            .map_err(|err| database::Error::ErrorWithText(err.to_string()))?;
+        }
+
+        tx.commit()
+            .await
+            .map_err(|err| database::Error::ErrorWithText(err.to_string()))
+    }
+
+    #[tracing::instrument(level = "warn", skip_all)]
+    async fn get_sync_partitions(&self) -> Result<Vec<SyncPartitionSummary>, database::Error> {
+        query_as::<_, SyncPartitionSummary>(
+            "SELECT
+            partition_id,
+            CAST(SUM(CASE WHEN deleted THEN 0 ELSE 1 END) AS SIGNED) AS records,
+            MAX(sequence) AS last_sequence
+            FROM torrust_sync_records
+            GROUP BY partition_id
+            ORDER BY partition_id ASC",
+        )
+        .fetch_all(&self.pool)
+        .await
+        .map_err(|_| database::Error::Error)
+    }
+
+    #[tracing::instrument(level = "warn", skip_all)]
+    async fn get_sync_partition_changes(
+        &self,
+        partition_id: PartitionId,
+        since: Sequence,
+    ) -> Result<Vec<SyncState>, database::Error> {
+        query_as::<_, SyncState>(
+            "SELECT torrent_id, partition_id, record_hash, sequence, deleted
+            FROM torrust_sync_records
+            WHERE partition_id = ? AND sequence > ?
+            ORDER BY sequence ASC",
+        )
+        .bind(partition_id)
+        .bind(since)
+        .fetch_all(&self.pool)
+        .await
+        .map_err(|_| database::Error::Error)
+    }
+
+    #[tracing::instrument(level = "warn", skip_all)]
+    async fn upsert_smart_collection_filter(
+        &self,
+        collection_id: CollectionId,
+        filter: &SmartFilter,
+    ) -> Result<(), database::Error> {
+        let mut tx = self
+            .pool
+            .begin()
+            .await
+            .map_err(|err| database::Error::ErrorWithText(err.to_string()))?;
+
+        query(
+            "INSERT INTO torrust_smart_collection_filters
+            (collection_id, category, tags, uploaded_after, uploaded_before, max_age_days)
+            VALUES (?, ?, ?, ?, ?, ?)
+            ON CONFLICT (collection_id) DO UPDATE SET
+                category = excluded.category,
+                tags = excluded.tags,
+                uploaded_after = excluded.uploaded_after,
+                uploaded_before = excluded.uploaded_before,
+                max_age_days = excluded.max_age_days",
+        )
+        .bind(collection_id)
+        .bind(&filter.category)
+        .bind(if filter.tags.is_empty() {
+            None
+        } else {
+            Some(filter.tags.join(","))
+        })
+        .bind(&filter.uploaded_after)
+        .bind(&filter.uploaded_before)
+        .bind(filter.max_age_days.map(i64::from))
+        .execute(&mut *tx)
+        .await
+        .map_err(|err| database::Error::ErrorWithText(err.to_string()))?;
+
+        query("UPDATE torrust_collections SET smart = TRUE WHERE collection_id = ?")
+            .bind(collection_id)
+            .execute(&mut *tx)
+            .await
+            .map_err(|err| database::Error::ErrorWithText(err.to_string()))?;
+
+        tx.commit()
+            .await
+            .map_err(|err| database::Error::ErrorWithText(err.to_string()))
+    }
+
+    #[tracing::instrument(level = "warn", skip_all)]
+    async fn get_smart_collection_filter(&self, collection_id: CollectionId) -> Result<Option<SmartFilter>, database::Error> {
+        query_as::<_, SmartFilterRow>(
+            "SELECT category, tags, uploaded_after, uploaded_before, max_age_days
+            FROM torrust_smart_collection_filters
+            WHERE collection_id = ?",
+        )
+        .bind(collection_id)
+        .fetch_optional(&self.pool)
+        .await
+        .map(|row| row.map(SmartFilter::from))
+        .map_err(|_| database::Error::Error)
+    }
+
+    #[tracing::instrument(level = "warn", skip_all)]
+    async fn get_smart_collection_filters(&self) -> Result<Vec<(CollectionId, SmartFilter)>, database::Error> {
+        let collection_ids = query_as::<_, (CollectionId,)>(
+            "SELECT collection_id FROM torrust_smart_collection_filters ORDER BY collection_id ASC",
+        )
+        .fetch_all(&self.pool)
+        .await
+        .map_err(|_| database::Error::Error)?;
+
+        let mut filters = vec![];
+
+        for (collection_id,) in collection_ids {
+            if let Some(filter) = self.get_smart_collection_filter(collection_id).await? {
+                filters.push((collection_id, filter));
+            }
+        }
+
+        Ok(filters)
+    }
+
+    #[tracing::instrument(level = "warn", skip_all)]
+    async fn materialize_smart_collection(
+        &self,
+        collection_id: CollectionId,
+        filter: &SmartFilter,
+        uploaded_since: Option<&str>,
+        max_items: u32,
+    ) -> Result<u64, database::Error> {
+        let uploaded_after = match (filter.uploaded_after.as_deref(), uploaded_since) {
+            (Some(uploaded_after), Some(uploaded_since)) => Some(uploaded_after.max(uploaded_since)),
+            (uploaded_after, uploaded_since) => uploaded_after.or(uploaded_since),
+        };
+
+        let tag_placeholders = vec!["?"; filter.tags.len()].join(", ");
+
+        let mut conditions = vec!["tt.status = ?".to_string()];
+
+        if filter.category.is_some() {
+            conditions.push("tc.name = ?".to_string());
+        }
+
+        if !filter.tags.is_empty() {
+            conditions.push(format!(
+                "(SELECT COUNT(DISTINCT tg.name) FROM torrust_torrent_tag_links tl
+                INNER JOIN torrust_torrent_tags tg ON tl.tag_id = tg.tag_id
+                WHERE tl.torrent_id = tt.torrent_id AND tg.name IN ({tag_placeholders})) = ?"
+            ));
+        }
+
+        if uploaded_after.is_some() {
+            conditions.push("tt.date_uploaded >= ?".to_string());
+        }
+
+        if filter.uploaded_before.is_some() {
+            conditions.push("tt.date_uploaded < ?".to_string());
+        }
+
+        let insert_query = format!(
+            "INSERT OR IGNORE INTO torrust_collection_items (collection_id, torrent_id, date_added)
+            SELECT ?, tt.torrent_id, tt.date_uploaded
+            FROM torrust_torrents tt
+            LEFT JOIN torrust_categories tc ON tt.category_id = tc.category_id
+            WHERE {}
+            ORDER BY tt.date_uploaded DESC, tt.torrent_id DESC
+            LIMIT ?",
+            conditions.join(" AND ")
+        );
+
+        let mut insert = query(&insert_query)
+            .bind(collection_id)
+            .bind(TorrentStatus::Approved.to_string());
+
+        if let Some(category) = &filter.category {
+            insert = insert.bind(category);
+        }
+
+        if !filter.tags.is_empty() {
+            for tag in &filter.tags {
+                insert = insert.bind(tag);
+            }
+
+            insert = insert.bind(i64::try_from(filter.tags.len()).expect("number of tags is larger than i64"));
+        }
+
+        if let Some(uploaded_after) = uploaded_after {
+            insert = insert.bind(uploaded_after);
+        }
+
+        if let Some(uploaded_before) = &filter.uploaded_before {
+            insert = insert.bind(uploaded_before);
+        }
+
+        insert = insert.bind(max_items);
+
+        let mut tx = self
+            .pool
+            .begin()
+            .await
+            .map_err(|err| database::Error::ErrorWithText(err.to_string()))?;
+
+        query("DELETE FROM torrust_collection_items WHERE collection_id = ?")
+            .bind(collection_id)
+            .execute(&mut *tx)
+            .await
+            .map_err(|err| database::Error::ErrorWithText(err.to_string()))?;
+
+        let items = insert
+            .execute(&mut *tx)
+            .await
+            .map(|v| v.rows_affected())
+            .map_err(|err| database::Error::ErrorWithText(err.to_string()))?;
+
+        query("UPDATE torrust_smart_collection_filters SET date_materialized = ? WHERE collection_id = ?")
+            .bind(datetime_now())
+            .bind(collection_id)
+            .execute(&mut *tx)
+            .await
+            .map_err(|err| database::Error::ErrorWithText(err.to_string()))?;
+
+        tx.commit()
+            .await
+            .map_err(|err| database::Error::ErrorWithText(err.to_string()))?;
+
+        Ok(items)
+    }
+
+    #[tracing::instrument(level = "warn", skip_all)]
+    async fn insert_audit_entry(
+        &self,
+        actor_id: Option<UserId>,
+        action: &AuditAction,
+        target: &str,
+        details: Option<&str>,
+    ) -> Result<AuditEntryId, database::Error> {
+        query("INSERT INTO torrust_audit_log (actor_id, action, target, details, date_created) VALUES (?, ?, ?, ?, ?)")
+            .bind(actor_id)
+            .bind(action.to_string())
+            .bind(target)
+            .bind(details)
+            .bind(datetime_now())
+            .execute(&self.pool)
+            .await
+            .map(|v| v.last_insert_rowid())
+            .map_err(|_| database::Error::Error)
+    }
+
+    #[tracing::instrument(level = "warn", skip_all)]
+    async fn get_audit_log_paginated(
+        &self,
+        filter: &AuditLogFilter,
+        offset: u64,
+        limit: u8,
+    ) -> Result<AuditLogResponse, database::Error> {
+        let action = filter.action.map(|action| action.to_string());
+
+        let mut conditions = vec![];
+        let mut values = vec![];
+
+        if let Some(actor) = &filter.actor {
+            conditions.push("tp.username = ? COLLATE NOCASE");
+            values.push(actor.as_str());
+        }
+
+        if let Some(action) = &action {
+            conditions.push("al.action = ?");
+            values.push(action.as_str());
+        }
+
+        if let Some(since) = &filter.since {
+            conditions.push("al.date_created >= ?");
+            values.push(since.as_str());
+        }
+
+        if let Some(until) = &filter.until {
+            conditions.push("al.date_created < ?");
+            values.push(until.as_str());
+        }
+
+        let where_clause = if conditions.is_empty() {
+            String::new()
+        } else {
+            format!(" WHERE {}", conditions.join(" AND "))
+        };
+
+        let count_query = format!(
+            "SELECT COUNT(*) FROM torrust_audit_log al
+            LEFT JOIN torrust_user_profiles tp ON al.actor_id = tp.user_id{where_clause}"
+        );
+
+        let mut count = query_as::<_, (i64,)>(&count_query);
+
+        for value in &values {
+            count = count.bind(*value);
+        }
+
+        let count = count
+            .fetch_one(&self.pool)
+            .await
+            .map(|(v,)| v)
+            .map_err(|_| database::Error::Error)?;
+
+        let query_string = format!(
+            "SELECT al.audit_id, al.actor_id, tp.username AS actor, al.action, al.target, al.details, al.date_created
+            FROM torrust_audit_log al
+            LEFT JOIN torrust_user_profiles tp ON al.actor_id = tp.user_id{where_clause}
+            ORDER BY al.audit_id DESC
+            LIMIT ?, ?"
+        );
+
+        let mut results = query_as::<_, AuditEntry>(&query_string);
+
+        for value in &values {
+            results = results.bind(*value);
+        }
+
+        let results = results
+            .bind(i64::saturating_add_unsigned(0, offset))
+            .bind(limit)
+            .fetch_all(&self.pool)
+            .await
+            .map_err(|_| database::Error::Error)?;
+
+        Ok(AuditLogResponse {
+            total: u32::try_from(count).expect("variable `count` is larger than u32"),
+            results,
+        })
+    }
+
+    #[tracing::instrument(level = "warn", skip_all)]
+    async fn get_category_policy(&self, category_id: CategoryId) -> Result<CategoryPolicy, database::Error> {
+        query_as::<_, (bool,)>("SELECT co_signing FROM torrust_category_policies WHERE category_id = ?")
+            .bind(category_id)
+            .fetch_optional(&self.pool)
+            .await
+            .map(|v| v.map(|(co_signing,)| CategoryPolicy { co_signing }).unwrap_or_default())
+            .map_err(|_| database::Error::Error)
+    }
+
+    #[tracing::instrument(level = "warn", skip_all)]
+    async fn upsert_category_policy(&self, category_id: CategoryId, policy: &CategoryPolicy) -> Result<(), database::Error> {
+        query(
+            "INSERT INTO torrust_category_policies (category_id, co_signing) VALUES (?, ?)
+            ON CONFLICT(category_id) DO UPDATE SET co_signing = excluded.co_signing",
+        )
+        .bind(category_id)
+        .bind(policy.co_signing)
+        .execute(&self.pool)
+        .await
+        .map(|_| ())
+        .map_err(|_| database::Error::Error)
+    }
+
+    #[tracing::instrument(level = "warn", skip_all)]
+    async fn insert_torrent_approval(&self, torrent_id: TorrentId, user_id: UserId) -> Result<bool, database::Error> {
+        query("INSERT OR IGNORE INTO torrust_torrent_approvals (torrent_id, user_id, date_created) VALUES (?, ?, ?)")
+            .bind(torrent_id)
+            .bind(user_id)
+            .bind(datetime_now())
+            .execute(&self.pool)
+            .await
+            .map(|v| v.rows_affected() > 0)
+            .map_err(|_| database::Error::Error)
+    }
+
+    #[tracing::instrument(level = "warn", skip_all)]
+    async fn count_torrent_approvals(&self, torrent_id: TorrentId) -> Result<u64, database::Error> {
+        query_as::<_, (i64,)>("SELECT COUNT(*) FROM torrust_torrent_approvals WHERE torrent_id = ?")
+            .bind(torrent_id)
+            .fetch_one(&self.pool)
+            .await
+            .map(|(v,)| u64::try_from(v).expect("the number of approvals is negative"))
+            .map_err(|_| database::Error::Error)
+    }
+
+    #[tracing::instrument(level = "warn", skip_all)]
+    async fn delete_torrent_approvals(&self, torrent_id: TorrentId) -> Result<(), database::Error> {
+        query("DELETE FROM torrust_torrent_approvals WHERE torrent_id = ?")
+            .bind(torrent_id)
+            .execute(&self.pool)
+            .await
+            .map(|_| ())
+            .map_err(|_| database::Error::Error)
+    }
+
+    #[tracing::instrument(level = "warn", skip_all)]
+    async fn get_importer_cursor(&self, importer: &str) -> Result<TorrentId, database::Error> {
+        query_as::<_, (TorrentId,)>("SELECT last_torrent_id FROM torrust_importer_cursors WHERE importer = ?")
+            .bind(importer)
+            .fetch_optional(&self.pool)
+            .await
+            .map(|v| v.map_or(0, |(last_torrent_id,)| last_torrent_id))
+            .map_err(|_| database::Error::Error)
+    }
+
+    #[tracing::instrument(level = "warn", skip_all)]
+    async fn update_importer_cursor(&self, importer: &str, torrent_id: TorrentId) -> Result<(), database::Error> {
+        query("REPLACE INTO torrust_importer_cursors (importer, last_torrent_id) VALUES (?, ?)")
+            .bind(importer)
+            .bind(torrent_id)
+            .execute(&self.pool)
+            .await
+            .map(|_| ())
+            .map_err(|_| database::Error::Error)
+    }
+
+    #[tracing::instrument(level = "warn", skip_all)]
+    async fn insert_torrent_preview(
+        &self,
+        torrent_id: TorrentId,
+        file_index: i64,
+        path: &str,
+        content_type: &str,
+        content: &[u8],
+    ) -> Result<PreviewId, database::Error> {
+        query(
+            "INSERT INTO torrust_torrent_previews (torrent_id, file_index, path, content_type, size, content, date_created) VALUES (?, ?, ?, ?, ?, ?, ?)",
+        )
+        .bind(torrent_id)
+        .bind(file_index)
+        .bind(path)
+        .bind(content_type)
+        .bind(i64::try_from(content.len()).unwrap_or(i64::MAX))
+        .bind(content)
+        .bind(datetime_now())
+        .execute(&self.pool)
+        .await
+        .map(|v| v.last_insert_rowid())
+        .map_err(|e| match e {
+            sqlx::Error::Database(err) if err.message().contains("UNIQUE") => database::Error::TorrentPreviewAlreadyExists,
+            _ => database::Error::Error,
+        })
+    }
+
+    #[tracing::instrument(level = "warn", skip_all)]
+    async fn get_torrent_previews(&self, torrent_id: TorrentId) -> Result<Vec<TorrentPreview>, database::Error> {
+        query_as::<_, TorrentPreview>(
+            "SELECT preview_id, torrent_id, file_index, path, content_type, size FROM torrust_torrent_previews WHERE torrent_id = ? ORDER BY file_index ASC",
+        )
+        .bind(torrent_id)
+        .fetch_all(&self.pool)
+        .await
+        .map_err(|_| database::Error::Error)
+    }
+
+    #[tracing::instrument(level = "warn", skip_all)]
+    async fn get_torrent_preview_content(
+        &self,
+        torrent_id: TorrentId,
+        preview_id: PreviewId,
+    ) -> Result<(String, Vec<u8>), database::Error> {
+        query_as::<_, (String, Vec<u8>)>(
+            "SELECT content_type, content FROM torrust_torrent_previews WHERE torrent_id = ? AND preview_id = ?",
+        )
+        .bind(torrent_id)
+        .bind(preview_id)
+        .fetch_optional(&self.pool)
+        .await
+        .map_err(|_| database::Error::Error)?
+        .ok_or(database::Error::TorrentPreviewNotFound)
+    }
+
+    #[tracing::instrument(level = "warn", skip_all)]
+    async fn update_torrent_file_groups(
+        &self,
+        torrent_id: TorrentId,
+        groups: &[TorrentFileGroup],
+    ) -> Result<(), database::Error> {
+        let mut tx = self.pool.begin().await.map_err(|_| database::Error::Error)?;
+
+        query("DELETE FROM torrust_torrent_file_groups WHERE torrent_id = ?")
+            .bind(torrent_id)
+            .execute(&mut *tx)
+            .await
+            .map_err(|_| database::Error::Error)?;
+
+        for (position, group) in groups.iter().enumerate() {
+            query("INSERT INTO torrust_torrent_file_groups (torrent_id, position, name, path) VALUES (?, ?, ?, ?)")
+                .bind(torrent_id)
+                .bind(i64::try_from(position).unwrap_or(i64::MAX))
+                .bind(&group.name)
+                .bind(&group.path)
+                .execute(&mut *tx)
+                .await
+                .map_err(|_| database::Error::Error)?;
+        }
+
+        tx.commit().await.map_err(|_| database::Error::Error)
+    }
+
+    #[tracing::instrument(level = "warn", skip_all)]
+    async fn get_torrent_file_groups(&self, torrent_id: TorrentId) -> Result<Vec<TorrentFileGroup>, database::Error> {
+        query_as::<_, TorrentFileGroup>(
+            "SELECT name, path FROM torrust_torrent_file_groups WHERE torrent_id = ? ORDER BY position ASC",
+        )
+        .bind(torrent_id)
+        .fetch_all(&self.pool)
+        .await
+        .map_err(|_| database::Error::Error)
+    }
+    #[tracing::instrument(level = "warn", skip_all)]
+    async fn increment_torrent_download_stats(&self, info_hash: &str, date: &str) -> Result<(), database::Error> {
+        query(
+            "INSERT INTO torrust_torrent_download_stats (torrent_id, date, downloads)
+            SELECT torrent_id, ?, 1 FROM torrust_torrents WHERE info_hash = ?
+            ON CONFLICT (torrent_id, date) DO UPDATE SET downloads = downloads + 1",
+        )
+        .bind(date)
+        .bind(info_hash)
+        .execute(&self.pool)
+        .await
+        .map(|_| ())
+        .map_err(|_| database::Error::Error)
+    }
+
+    #[tracing::instrument(level = "warn", skip_all)]
+    async fn get_tag_activity(&self, since: &str, until: &str) -> Result<Vec<TagActivity>, database::Error> {
+        query_as::<_, TagActivity>(
+            "SELECT t.tag_id, t.name,
+                (SELECT COUNT(*) FROM torrust_torrent_tag_links tl
+                    INNER JOIN torrust_torrents tt ON tt.torrent_id = tl.torrent_id
+                    WHERE tl.tag_id = t.tag_id AND tt.status = 'approved' AND tt.date_uploaded >= ? AND tt.date_uploaded < ?) AS uploads,
+                (SELECT COALESCE(SUM(ds.downloads), 0) FROM torrust_torrent_tag_links tl
+                    INNER JOIN torrust_torrent_download_stats ds ON ds.torrent_id = tl.torrent_id
+                    WHERE tl.tag_id = t.tag_id AND ds.date >= ? AND ds.date < ?) AS downloads
+            FROM torrust_torrent_tags t",
+        )
+        .bind(since)
+        .bind(until)
+        .bind(since)
+        .bind(until)
+        .fetch_all(&self.pool)
+        .await
+        .map_err(|_| database::Error::Error)
+    }
+
+    #[tracing::instrument(level = "warn", skip_all)]
+    async fn merge_tags(&self, source_tag_id: TagId, target_tag_id: TagId) -> Result<(), database::Error> {
+        let mut tx = self.pool.begin().await.map_err(|_| database::Error::Error)?;
+
+        let (source_name,) = query_as::<_, (String,)>("SELECT name FROM torrust_torrent_tags WHERE tag_id = ?")
+            .bind(source_tag_id)
+            .fetch_optional(&mut *tx)
+            .await
+            .map_err(|_| database::Error::Error)?
+            .ok_or(database::Error::TagNotFound)?;
+
+        query("SELECT tag_id FROM torrust_torrent_tags WHERE tag_id = ?")
+            .bind(target_tag_id)
+            .fetch_optional(&mut *tx)
+            .await
+            .map_err(|_| database::Error::Error)?
+            .ok_or(database::Error::TagNotFound)?;
+
+        query(
+            "INSERT OR IGNORE INTO torrust_torrent_tag_links (torrent_id, tag_id)
+            SELECT torrent_id, ? FROM torrust_torrent_tag_links WHERE tag_id = ?",
+        )
+        .bind(target_tag_id)
+        .bind(source_tag_id)
+        .execute(&mut *tx)
+        .await
+        .map_err(|_| database::Error::Error)?;
+
+        query("DELETE FROM torrust_torrent_tag_links WHERE tag_id = ?")
+            .bind(source_tag_id)
+            .execute(&mut *tx)
+            .await
+            .map_err(|_| database::Error::Error)?;
+
+        query("UPDATE torrust_torrent_tag_synonyms SET tag_id = ? WHERE tag_id = ?")
+            .bind(target_tag_id)
+            .bind(source_tag_id)
+            .execute(&mut *tx)
+            .await
+            .map_err(|_| database::Error::Error)?;
+
+        query("DELETE FROM torrust_torrent_tags WHERE tag_id = ?")
+            .bind(source_tag_id)
+            .execute(&mut *tx)
+            .await
+            .map_err(|_| database::Error::Error)?;
+
+        query("INSERT INTO torrust_torrent_tag_synonyms (name, tag_id) VALUES (?, ?)")
+            .bind(source_name)
+            .bind(target_tag_id)
+            .execute(&mut *tx)
+            .await
+            .map_err(|_| database::Error::Error)?;
+
+        tx.commit().await.map_err(|_| database::Error::Error)
+    }
+
+    #[tracing::instrument(level = "warn", skip_all)]
+    async fn insert_tag_synonym(&self, name: &str, tag_id: TagId) -> Result<(), database::Error> {
+        let result = query(
+            "INSERT INTO torrust_torrent_tag_synonyms (name, tag_id) SELECT ?, tag_id FROM torrust_torrent_tags WHERE tag_id = ?",
+        )
+        .bind(name)
+        .bind(tag_id)
+        .execute(&self.pool)
+        .await
+        .map_err(|e| match e {
+            sqlx::Error::Database(err) if err.message().contains("UNIQUE") => database::Error::TagAlreadyExists,
+            _ => database::Error::Error,
+        })?;
+
+        if result.rows_affected() == 0 {
+            return Err(database::Error::TagNotFound);
+        }
+
+        Ok(())
+    }
+
+    #[tracing::instrument(level = "warn", skip_all)]
+    async fn delete_tag_synonym(&self, name: &str) -> Result<(), database::Error> {
+        query("DELETE FROM torrust_torrent_tag_synonyms WHERE name = ?")
+            .bind(name)
+            .execute(&self.pool)
+            .await
+            .map_err(|_| database::Error::Error)
+            .and_then(|v| {
+                if v.rows_affected() > 0 {
+                    Ok(())
+                } else {
+                    Err(database::Error::TagSynonymNotFound)
+                }
+            })
+    }
+
+    #[tracing::instrument(level = "warn", skip_all)]
+    async fn get_tag_synonyms(&self) -> Result<Vec<TagSynonym>, database::Error> {
+        query_as::<_, TagSynonym>(
+            "SELECT s.name, s.tag_id, t.name AS tag_name
+            FROM torrust_torrent_tag_synonyms s
+            INNER JOIN torrust_torrent_tags t ON t.tag_id = s.tag_id
+            ORDER BY s.name ASC",
+        )
+        .fetch_all(&self.pool)
+        .await
+        .map_err(|_| database::Error::Error)
+    }
+
+    #[tracing::instrument(level = "warn", skip_all)]
+    async fn get_tag_from_synonym(&self, name: &str) -> Result<TorrentTag, database::Error> {
+        query_as::<_, TorrentTag>(
+            "SELECT t.tag_id, t.name
+            FROM torrust_torrent_tag_synonyms s
+            INNER JOIN torrust_torrent_tags t ON t.tag_id = s.tag_id
+            WHERE s.name = ?",
+        )
+        .bind(name)
+        .fetch_optional(&self.pool)
+        .await
+        .map_err(|_| database::Error::Error)?
+        .ok_or(database::Error::TagNotFound)
+    }
+
+    #[tracing::instrument(level = "warn", skip_all)]
+    async fn get_torrent_rule_candidates(
+        &self,
+        rule: &str,
+        uploaded_before: &str,
+    ) -> Result<Vec<TorrentRuleCandidate>, database::Error> {
+        query_as::<_, TorrentRuleCandidate>(
+            r#"SELECT tt.torrent_id, tt.info_hash, ti.title, tc.name AS category,
+                (SELECT GROUP_CONCAT(tl.tag_id) FROM torrust_torrent_tag_links tl WHERE tl.torrent_id = tt.torrent_id) AS tag_ids,
+                tt.date_uploaded AS date_uploaded,
+                (SELECT CAST(COALESCE(SUM(ts.seeders), 0) AS SIGNED) FROM torrust_torrent_tracker_stats ts WHERE ts.torrent_id = tt.torrent_id) AS seeders,
+                tp.username AS uploader, COALESCE(tp.email, "") AS uploader_email
+            FROM torrust_torrents tt
+            INNER JOIN torrust_torrent_info ti ON tt.torrent_id = ti.torrent_id
+            INNER JOIN torrust_user_profiles tp ON tt.uploader_id = tp.user_id
+            LEFT JOIN torrust_categories tc ON tt.category_id = tc.category_id
+            WHERE tt.status = ? AND tt.date_uploaded <= ?
+                AND tt.torrent_id NOT IN (SELECT torrent_id FROM torrust_torrent_rule_applications WHERE rule = ?)
+            ORDER BY tt.torrent_id ASC"#,
+        )
+        .bind(TorrentStatus::Approved.to_string())
+        .bind(uploaded_before)
+        .bind(rule)
+        .fetch_all(&self.pool)
+        .await
+        .map_err(|_| database::Error::Error)?
+        .into_iter()
+        .map(|mut candidate| {
+            candidate.uploader_email = self.decrypt(Column::UserEmail, candidate.uploader_email)?;
+            Ok(candidate)
+        })
+        .collect()
+    }
+
+    #[tracing::instrument(level = "warn", skip_all)]
+    async fn insert_torrent_rule_application(&self, torrent_id: TorrentId, rule: &str) -> Result<(), database::Error> {
+        query("INSERT OR IGNORE INTO torrust_torrent_rule_applications (torrent_id, rule, date_applied) VALUES (?, ?, ?)")
+            .bind(torrent_id)
+            .bind(rule)
+            .bind(datetime_now())
+            .execute(&self.pool)
+            .await
+            .map(|_| ())
+            .map_err(|_| database::Error::Error)
+    }
+
+    #[tracing::instrument(level = "warn", skip_all)]
+    async fn insert_saved_search(
+        &self,
+        user_id: UserId,
+        name: &str,
+        query: &str,
+        category: Option<&str>,
+        tags: &[String],
+        email_alerts: bool,
+    ) -> Result<SavedSearchId, database::Error> {
+        query(
+            "INSERT INTO torrust_user_saved_searches (user_id, name, query, category, tags, email_alerts, date_created) VALUES (?, ?, ?, ?, ?, ?, ?)",
+        )
+        .bind(user_id)
+        .bind(name)
+        .bind(query)
+        .bind(category)
+        .bind(if tags.is_empty() { None } else { Some(tags.join(",")) })
+        .bind(email_alerts)
+        .bind(datetime_now())
+        .execute(&self.pool)
+        .await
+        .map(|v| v.last_insert_rowid())
+        .map_err(|_| database::Error::Error)
+    }
+
+    #[tracing::instrument(level = "warn", skip_all)]
+    async fn get_saved_search(&self, saved_search_id: SavedSearchId) -> Result<SavedSearch, database::Error> {
+        query_as::<_, SavedSearchRow>(
+            "SELECT ss.saved_search_id, ss.user_id, tp.username, ss.name, ss.query, ss.category, ss.tags, ss.email_alerts, ss.date_created AS date_created
+            FROM torrust_user_saved_searches ss
+            INNER JOIN torrust_user_profiles tp ON ss.user_id = tp.user_id
+            WHERE ss.saved_search_id = ?",
+        )
+        .bind(saved_search_id)
+        .fetch_one(&self.pool)
+        .await
+        .map(SavedSearch::from)
+        .map_err(|_| database::Error::SavedSearchNotFound)
+    }
+
+    #[tracing::instrument(level = "warn", skip_all)]
+    async fn get_user_saved_searches(&self, user_id: UserId) -> Result<Vec<SavedSearch>, database::Error> {
+        query_as::<_, SavedSearchRow>(
+            "SELECT ss.saved_search_id, ss.user_id, tp.username, ss.name, ss.query, ss.category, ss.tags, ss.email_alerts, ss.date_created AS date_created
+            FROM torrust_user_saved_searches ss
+            INNER JOIN torrust_user_profiles tp ON ss.user_id = tp.user_id
+            WHERE ss.user_id = ?
+            ORDER BY ss.saved_search_id ASC",
+        )
+        .bind(user_id)
+        .fetch_all(&self.pool)
+        .await
+        .map(|rows| rows.into_iter().map(SavedSearch::from).collect())
+        .map_err(|_| database::Error::Error)
+    }
+
+    #[tracing::instrument(level = "warn", skip_all)]
+    async fn get_all_saved_searches(&self) -> Result<Vec<SavedSearch>, database::Error> {
+        query_as::<_, SavedSearchRow>(
+            "SELECT ss.saved_search_id, ss.user_id, tp.username, ss.name, ss.query, ss.category, ss.tags, ss.email_alerts, ss.date_created AS date_created
+            FROM torrust_user_saved_searches ss
+            INNER JOIN torrust_user_profiles tp ON ss.user_id = tp.user_id
+            ORDER BY ss.saved_search_id ASC",
+        )
+        .fetch_all(&self.pool)
+        .await
+        .map(|rows| rows.into_iter().map(SavedSearch::from).collect())
+        .map_err(|_| database::Error::Error)
+    }
+
+    #[tracing::instrument(level = "warn", skip_all)]
+    async fn delete_saved_search(&self, saved_search_id: SavedSearchId) -> Result<(), database::Error> {
+        query("DELETE FROM torrust_user_saved_searches WHERE saved_search_id = ?")
+            .bind(saved_search_id)
+            .execute(&self.pool)
+            .await
+            .map_err(|_| database::Error::Error)
+            .and_then(|v| {
+                if v.rows_affected() > 0 {
+                    Ok(())
+                } else {
+                    Err(database::Error::SavedSearchNotFound)
+                }
+            })
+    }
+
+    #[tracing::instrument(level = "warn", skip_all)]
+    async fn insert_saved_search_result(
+        &self,
+        saved_search_id: SavedSearchId,
+        torrent_id: TorrentId,
+    ) -> Result<(), database::Error> {
+        query("INSERT OR IGNORE INTO torrust_user_saved_search_results (saved_search_id, torrent_id, date_matched) VALUES (?, ?, ?)")
+            .bind(saved_search_id)
+            .bind(torrent_id)
+            .bind(datetime_now())
+            .execute(&self.pool)
+            .await
+            .map(|_| ())
+            .map_err(|_| database::Error::Error)
+    }
+
+    #[tracing::instrument(level = "warn", skip_all)]
+    async fn get_saved_search_result_ids(&self, saved_search_id: SavedSearchId) -> Result<Vec<TorrentId>, database::Error> {
+        query_as::<_, (TorrentId,)>(
+            "SELECT torrent_id FROM torrust_user_saved_search_results
+            WHERE saved_search_id = ?
+            ORDER BY date_matched DESC, torrent_id DESC",
+        )
+        .bind(saved_search_id)
+        .fetch_all(&self.pool)
+        .await
+        .map(|rows| rows.into_iter().map(|(torrent_id,)| torrent_id).collect())
+        .map_err(|_| database::Error::Error)
+    }
+
+    #[tracing::instrument(level = "warn", skip_all)]
+    async fn insert_import_session(
+        &self,
+        user_id: UserId,
+        source: &str,
+        conflict_policy: &str,
+        mapping: Option<&str>,
+    ) -> Result<ImportSessionId, database::Error> {
+        query(
+            "INSERT INTO torrust_import_sessions (user_id, source, conflict_policy, mapping, status, date_created) VALUES (?, ?, ?, ?, 'open', ?)",
+        )
+        .bind(user_id)
+        .bind(source)
+        .bind(conflict_policy)
+        .bind(mapping)
+        .bind(datetime_now())
+        .execute(&self.pool)
+        .await
+        .map(|v| v.last_insert_rowid())
+        .map_err(|_| database::Error::Error)
+    }
+
+    #[tracing::instrument(level = "warn", skip_all)]
+    async fn get_import_session(&self, import_id: ImportSessionId) -> Result<ImportSession, database::Error> {
+        query_as::<_, ImportSession>(
+            "SELECT s.import_id, s.user_id, tp.username, s.source, s.conflict_policy, s.mapping, s.status,
+            s.date_created,
+            s.date_completed,
+            COUNT(b.batch_id) AS batches,
+            CAST(COALESCE(SUM(b.imported), 0) AS INTEGER) AS imported,
+            CAST(COALESCE(SUM(b.updated), 0) AS INTEGER) AS updated,
+            CAST(COALESCE(SUM(b.duplicates), 0) AS INTEGER) AS duplicates,
+            CAST(COALESCE(SUM(b.failures), 0) AS INTEGER) AS failures
+            FROM torrust_import_sessions s
+            INNER JOIN torrust_user_profiles tp ON s.user_id = tp.user_id
+            LEFT JOIN torrust_import_batches b ON s.import_id = b.import_id
+            WHERE s.import_id = ?
+            GROUP BY s.import_id",
+        )
+        .bind(import_id)
+        .fetch_one(&self.pool)
+        .await
+        .map_err(|_| database::Error::ImportSessionNotFound)
+    }
+
+    #[tracing::instrument(level = "warn", skip_all)]
+    async fn complete_import_session(&self, import_id: ImportSessionId) -> Result<(), database::Error> {
+        query(
+            "UPDATE torrust_import_sessions SET status = 'completed', date_completed = ? WHERE import_id = ? AND status = 'open'",
+        )
+        .bind(datetime_now())
+        .bind(import_id)
+        .execute(&self.pool)
+        .await
+        .map(|_| ())
+        .map_err(|_| database::Error::Error)
+    }
+
+    #[tracing::instrument(level = "warn", skip_all)]
+    async fn get_import_batch_ids(&self, import_id: ImportSessionId) -> Result<Vec<String>, database::Error> {
+        query_as::<_, (String,)>(
+            "SELECT batch_id FROM torrust_import_batches WHERE import_id = ? ORDER BY date_processed ASC, batch_id ASC",
+        )
+        .bind(import_id)
+        .fetch_all(&self.pool)
+        .await
+        .map(|rows| rows.into_iter().map(|(batch_id,)| batch_id).collect())
+        .map_err(|_| database::Error::Error)
+    }
+
+    #[tracing::instrument(level = "warn", skip_all)]
+    async fn get_import_batch_summary(
+        &self,
+        import_id: ImportSessionId,
+        batch_id: &str,
+    ) -> Result<Option<String>, database::Error> {
+        query_as::<_, (String,)>("SELECT summary FROM torrust_import_batches WHERE import_id = ? AND batch_id = ?")
+            .bind(import_id)
+            .bind(batch_id)
+            .fetch_optional(&self.pool)
+            .await
+            .map(|row| row.map(|(summary,)| summary))
+            .map_err(|_| database::Error::Error)
+    }
+
+    #[tracing::instrument(level = "warn", skip_all)]
+    async fn insert_import_batch(&self, import_id: ImportSessionId, batch: &ImportBatch) -> Result<bool, database::Error> {
+        query(
+            "INSERT OR IGNORE INTO torrust_import_batches (import_id, batch_id, imported, updated, duplicates, failures, summary, date_processed)
+            VALUES (?, ?, ?, ?, ?, ?, ?, ?)",
+        )
+        .bind(import_id)
+        .bind(&batch.batch_id)
+        .bind(batch.imported)
+        .bind(batch.updated)
+        .bind(batch.duplicates)
+        .bind(batch.failures)
+        .bind(&batch.summary)
+        .bind(datetime_now())
+        .execute(&self.pool)
+        .await
+        .map(|v| v.rows_affected() > 0)
+        .map_err(|_| database::Error::Error)
+    }
+
+    #[tracing::instrument(level = "warn", skip_all)]
+    async fn get_domain_events_of_type_after(
+        &self,
+        event_type: &str,
+        event_id: EventId,
+        limit: u32,
+    ) -> Result<Vec<DbDomainEvent>, database::Error> {
+        query_as::<_, DbDomainEvent>(
+            "SELECT event_id, event_type, payload, date_created FROM torrust_domain_events
+            WHERE event_type = ? AND event_id > ? ORDER BY event_id ASC LIMIT ?",
+        )
+        .bind(event_type)
+        .bind(event_id)
+        .bind(limit)
+        .fetch_all(&self.pool)
+        .await
+        .map_err(|_| database::Error::Error)
+    }
+
+    #[tracing::instrument(level = "warn", skip_all)]
+    async fn get_uploader_seeding_stats(&self) -> Result<Vec<UploaderSeedingStats>, database::Error> {
+        query_as::<_, UploaderSeedingStats>(
+            "SELECT tt.uploader_id AS user_id, tp.username,
+            COUNT(tt.torrent_id) AS torrents,
+            CAST(COALESCE(SUM(CASE WHEN ts.seeders > 0 THEN 1 ELSE 0 END), 0) AS INTEGER) AS seeded_torrents,
+            CAST(COALESCE(SUM(ts.seeders), 0) AS INTEGER) AS seeders,
+            CAST(COALESCE(SUM(ts.leechers), 0) AS INTEGER) AS leechers,
+            CAST(COALESCE(SUM(CASE WHEN ts.seeders > 0 THEN tt.size ELSE 0 END), 0) AS INTEGER) AS seeded_bytes
+            FROM torrust_torrents tt
+            INNER JOIN torrust_user_profiles tp ON tt.uploader_id = tp.user_id
+            LEFT JOIN torrust_torrent_tracker_stats ts ON tt.torrent_id = ts.torrent_id
+            WHERE tt.status = ?
+            GROUP BY tt.uploader_id, tp.username
+            HAVING seeded_torrents > 0
+            ORDER BY tt.uploader_id ASC",
+        )
+        .bind(TorrentStatus::Approved.to_string())
+        .fetch_all(&self.pool)
+        .await
+        .map_err(|_| database::Error::Error)
+    }
+
+    #[tracing::instrument(level = "warn", skip_all)]
+    async fn insert_points_adjustment(
+        &self,
+        user_id: UserId,
+        amount: i64,
+        reason: &str,
+        reference: Option<&str>,
+    ) -> Result<bool, database::Error> {
+        let mut tx = self
+            .pool
+            .begin()
+            .await
+            .map_err(|err| database::Error::ErrorWithText(err.to_string()))?;
+
+        let inserted = query(
+            "INSERT OR IGNORE INTO torrust_user_point_adjustments (user_id, amount, reason, reference, date_created) VALUES (?, ?, ?, ?, ?)",
+        )
+        .bind(user_id)
+        .bind(amount)
+        .bind(reason)
+        .bind(reference)
+        .bind(datetime_now())
+        .execute(&mut *tx)
+        .await
+        .map(|v| v.rows_affected() > 0)
+        .map_err(|_| database::Error::Error)?;
+
+        if inserted {
+            query(
+                "INSERT INTO torrust_user_points (user_id, balance) VALUES (?, ?)
+            ON CONFLICT (user_id) DO UPDATE SET balance = balance + excluded.balance",
+            )
+            .bind(user_id)
+            .bind(amount)
+            .execute(&mut *tx)
+            .await
+            .map_err(|_| database::Error::Error)?;
+        }
+
+        tx.commit()
+            .await
+            .map_err(|err| database::Error::ErrorWithText(err.to_string()))?;
+
+        Ok(inserted)
+    }
+
+    #[tracing::instrument(level = "warn", skip_all)]
+    async fn get_points_balance(&self, user_id: UserId) -> Result<i64, database::Error> {
+        query_as::<_, (i64,)>("SELECT balance FROM torrust_user_points WHERE user_id = ?")
+            .bind(user_id)
+            .fetch_optional(&self.pool)
+            .await
+            .map(|v| v.map_or(0, |(balance,)| balance))
+            .map_err(|_| database::Error::Error)
+    }
+
+    #[tracing::instrument(level = "warn", skip_all)]
+    async fn get_points_adjustments(&self, user_id: UserId, limit: u32) -> Result<Vec<PointsAdjustment>, database::Error> {
+        query_as::<_, PointsAdjustment>(
+            "SELECT adjustment_id, user_id, amount, reason, reference, date_created
+            FROM torrust_user_point_adjustments WHERE user_id = ? ORDER BY adjustment_id DESC LIMIT ?",
+        )
+        .bind(user_id)
+        .bind(limit)
+        .fetch_all(&self.pool)
+        .await
+        .map_err(|_| database::Error::Error)
+    }
+
+    #[tracing::instrument(level = "warn", skip_all)]
+    async fn upsert_image_cache_entry(&self, cache_key: &str, size: i64) -> Result<(), database::Error> {
+        query(
+            "INSERT INTO torrust_image_cache_entries (cache_key, size, last_accessed) VALUES (?, ?, ?)
+            ON CONFLICT (cache_key) DO UPDATE SET size = excluded.size, last_accessed = excluded.last_accessed",
+        )
+        .bind(cache_key)
+        .bind(size)
+        .bind(datetime_now())
+        .execute(&self.pool)
+        .await
+        .map(|_| ())
+        .map_err(|_| database::Error::Error)
+    }
+
+    #[tracing::instrument(level = "warn", skip_all)]
+    async fn touch_image_cache_entries(&self, cache_keys: &[String]) -> Result<(), database::Error> {
+        let mut tx = self
+            .pool
+            .begin()
+            .await
+            .map_err(|err| database::Error::ErrorWithText(err.to_string()))?;
+
+        let now = datetime_now();
+
+        for cache_key in cache_keys {
+            query("UPDATE torrust_image_cache_entries SET last_accessed = ? WHERE cache_key = ?")
+                .bind(&now)
+                .bind(cache_key)
+                .execute(&mut *tx)
+                .await
+                .map_err(|_| database::Error::Error)?;
+        }
+
+        tx.commit()
+            .await
+            .map_err(|err| database::Error::ErrorWithText(err.to_string()))
+    }
+
+    #[tracing::instrument(level = "warn", skip_all)]
+    async fn get_image_cache_size(&self) -> Result<i64, database::Error> {
+        query_as::<_, (i64,)>("SELECT CAST(COALESCE(SUM(size), 0) AS INTEGER) FROM torrust_image_cache_entries")
+            .fetch_one(&self.pool)
+            .await
+            .map(|(size,)| size)
+            .map_err(|_| database::Error::Error)
+    }
+
+    #[tracing::instrument(level = "warn", skip_all)]
+    async fn get_least_recently_accessed_image_cache_entries(&self, limit: u32) -> Result<Vec<(String, i64)>, database::Error> {
+        query_as::<_, (String, i64)>(
+            "SELECT cache_key, size FROM torrust_image_cache_entries ORDER BY last_accessed ASC, cache_key ASC LIMIT ?",
+        )
+        .bind(limit)
+        .fetch_all(&self.pool)
+        .await
+        .map_err(|_| database::Error::Error)
+    }
+
+    #[tracing::instrument(level = "warn", skip_all)]
+    async fn delete_image_cache_entry(&self, cache_key: &str) -> Result<(), database::Error> {
+        query("DELETE FROM torrust_image_cache_entries WHERE cache_key = ?")
+            .bind(cache_key)
+            .execute(&self.pool)
+            .await
+            .map(|_| ())
+            .map_err(|_| database::Error::Error)
+    }
+
+    #[tracing::instrument(level = "warn", skip_all)]
+    async fn insert_torrent_description_image(
+        &self,
+        torrent_id: TorrentId,
+        uploader_id: UserId,
+        cache_key: &str,
+        file_name: &str,
+        content_type: &str,
+        size: i64,
+    ) -> Result<ImageId, database::Error> {
+        query(
+            "INSERT INTO torrust_torrent_description_images (torrent_id, uploader_id, cache_key, file_name, content_type, size, date_created) VALUES (?, ?, ?, ?, ?, ?, ?)",
+        )
+        .bind(torrent_id)
+        .bind(uploader_id)
+        .bind(cache_key)
+        .bind(file_name)
+        .bind(content_type)
+        .bind(size)
+        .bind(datetime_now())
+        .execute(&self.pool)
+        .await
+        .map(|v| v.last_insert_rowid())
+        .map_err(|e| match e {
+            sqlx::Error::Database(err) if err.message().contains("UNIQUE") => database::Error::ImageAlreadyExists,
+            _ => database::Error::Error,
+        })
+    }
+
+    #[tracing::instrument(level = "warn", skip_all)]
+    async fn get_torrent_description_images(&self, torrent_id: TorrentId) -> Result<Vec<DescriptionImage>, database::Error> {
+        query_as::<_, DescriptionImage>(
+            "SELECT image_id, torrent_id, file_name, content_type, size FROM torrust_torrent_description_images WHERE torrent_id = ? ORDER BY image_id ASC",
+        )
+        .bind(torrent_id)
+        .fetch_all(&self.pool)
+        .await
+        .map_err(|_| database::Error::Error)
+    }
+
+    #[tracing::instrument(level = "warn", skip_all)]
+    async fn get_torrent_description_image_key(
+        &self,
+        torrent_id: TorrentId,
+        image_id: ImageId,
+    ) -> Result<(String, String), database::Error> {
+        query_as::<_, (String, String)>(
+            "SELECT content_type, cache_key FROM torrust_torrent_description_images WHERE torrent_id = ? AND image_id = ?",
+        )
+        .bind(torrent_id)
+        .bind(image_id)
+        .fetch_optional(&self.pool)
+        .await
+        .map_err(|_| database::Error::Error)?
+        .ok_or(database::Error::DescriptionImageNotFound)
+    }
+
+    #[tracing::instrument(level = "warn", skip_all)]
+    async fn insert_torrent_attachment(
+        &self,
+        torrent_id: TorrentId,
+        uploader_id: UserId,
+        kind: AttachmentKind,
+        file_name: &str,
+        content_type: &str,
+        content: &[u8],
+    ) -> Result<AttachmentId, database::Error> {
+        query(
+            "INSERT INTO torrust_torrent_attachments (torrent_id, uploader_id, kind, file_name, content_type, size, content, date_created) VALUES (?, ?, ?, ?, ?, ?, ?, ?)",
+        )
+        .bind(torrent_id)
+        .bind(uploader_id)
+        .bind(kind.as_str())
+        .bind(file_name)
+        .bind(content_type)
+        .bind(i64::try_from(content.len()).unwrap_or(i64::MAX))
+        .bind(content)
+        .bind(datetime_now())
+        .execute(&self.pool)
+        .await
+        .map(|v| v.last_insert_rowid())
+        .map_err(|_| database::Error::Error)
+    }
+
+    #[tracing::instrument(level = "warn", skip_all)]
+    async fn get_torrent_attachments(&self, torrent_id: TorrentId) -> Result<Vec<TorrentAttachment>, database::Error> {
+        query_as::<_, TorrentAttachment>(
+            "SELECT attachment_id, torrent_id, kind, file_name, content_type, size FROM torrust_torrent_attachments WHERE torrent_id = ? ORDER BY attachment_id ASC",
+        )
+        .bind(torrent_id)
+        .fetch_all(&self.pool)
+        .await
+        .map_err(|_| database::Error::Error)
+    }
+
+    #[tracing::instrument(level = "warn", skip_all)]
+    async fn get_torrent_attachment_content(
+        &self,
+        torrent_id: TorrentId,
+        attachment_id: AttachmentId,
+    ) -> Result<(String, Vec<u8>), database::Error> {
+        query_as::<_, (String, Vec<u8>)>(
+            "SELECT content_type, content FROM torrust_torrent_attachments WHERE torrent_id = ? AND attachment_id = ?",
+        )
+        .bind(torrent_id)
+        .bind(attachment_id)
+        .fetch_optional(&self.pool)
+        .await
+        .map_err(|_| database::Error::Error)?
+        .ok_or(database::Error::TorrentAttachmentNotFound)
+    }
+
+    #[tracing::instrument(level = "warn", skip_all)]
+    async fn delete_torrent_attachment(&self, torrent_id: TorrentId, attachment_id: AttachmentId) -> Result<(), database::Error> {
+        let result = query("DELETE FROM torrust_torrent_attachments WHERE torrent_id = ? AND attachment_id = ?")
+            .bind(torrent_id)
+            .bind(attachment_id)
+            .execute(&self.pool)
+            .await
+            .map_err(|_| database::Error::Error)?;
+
+        if result.rows_affected() == 0 {
+            return Err(database::Error::TorrentAttachmentNotFound);
+        }
+
+        Ok(())
+    }
+
+    #[tracing::instrument(level = "warn", skip_all)]
+    async fn get_torrents_with_file_lengths(&self, lengths: &[i64], limit: u32) -> Result<Vec<TorrentId>, database::Error> {
+        if lengths.is_empty() {
+            return Ok(vec![]);
+        }
+
+        let lengths_csv = lengths.iter().map(ToString::to_string).collect::<Vec<String>>().join(",");
+
+        query_as::<_, (TorrentId,)>(&format!(
+            "SELECT torrent_id FROM torrust_torrent_files
+            WHERE length IN ({lengths_csv})
+            GROUP BY torrent_id
+            ORDER BY COUNT(*) DESC, torrent_id DESC
+            LIMIT ?"
+        ))
+        .bind(limit)
+        .fetch_all(&self.pool)
+        .await
+        .map(|rows| rows.into_iter().map(|(torrent_id,)| torrent_id).collect())
+        .map_err(|_| database::Error::Error)
+    }
+
+    #[tracing::instrument(level = "warn", skip_all)]
+    async fn insert_torrent_duplicate(
+        &self,
+        torrent_id: TorrentId,
+        duplicate_of: TorrentId,
+        overlap: f64,
+    ) -> Result<(), database::Error> {
+        query("INSERT INTO torrust_torrent_duplicates (torrent_id, duplicate_of, overlap, date_detected) VALUES (?, ?, ?, ?)")
+            .bind(torrent_id)
+            .bind(duplicate_of)
+            .bind(overlap)
+            .bind(datetime_now())
+            .execute(&self.pool)
+            .await
+            .map(|_| ())
+            .map_err(|_| database::Error::Error)
+    }
+
+    #[tracing::instrument(level = "warn", skip_all)]
+    async fn get_torrent_duplicates(&self) -> Result<Vec<DuplicatePair>, database::Error> {
+        query_as::<_, DuplicatePair>(
+            "SELECT torrent_id, duplicate_of, overlap, date_detected FROM torrust_torrent_duplicates ORDER BY date_detected DESC, torrent_id DESC",
+        )
+        .fetch_all(&self.pool)
+        .await
+        .map_err(|_| database::Error::Error)
+    }
+
+    #[tracing::instrument(level = "warn", skip_all)]
+    async fn get_duplicated_torrents(&self) -> Result<Vec<SimilarTorrent>, database::Error> {
+        query_as::<_, SimilarTorrent>(
+            "SELECT tt.torrent_id, tt.info_hash, ti.title, tt.size AS file_size
+            FROM torrust_torrents tt
+            INNER JOIN torrust_torrent_info ti ON tt.torrent_id = ti.torrent_id
+            WHERE tt.torrent_id IN (SELECT torrent_id FROM torrust_torrent_duplicates UNION SELECT duplicate_of FROM torrust_torrent_duplicates)",
+        )
+        .fetch_all(&self.pool)
+        .await
+        .map_err(|_| database::Error::Error)
+    }
+
+    #[tracing::instrument(level = "warn", skip_all)]
+    async fn move_torrent_to_trash(&self, torrent_id: TorrentId, deleted_by: Option<UserId>) -> Result<(), database::Error> {
+        let mut tx = self.pool.begin().await.map_err(|_| database::Error::Error)?;
+
+        let result = query(
+            "INSERT INTO torrust_torrent_trash (torrent_id, previous_status, deleted_by, date_deleted)
+            SELECT torrent_id, status, ?, ? FROM torrust_torrents WHERE torrent_id = ? AND status <> 'deleted'",
+        )
+        .bind(deleted_by)
+        .bind(datetime_now())
+        .bind(torrent_id)
+        .execute(&mut *tx)
+        .await
+        .map_err(|_| database::Error::Error)?;
+
+        if result.rows_affected() == 0 {
+            return Err(database::Error::TorrentNotFound);
+        }
+
+        query("UPDATE torrust_torrents SET status = 'deleted' WHERE torrent_id = ?")
+            .bind(torrent_id)
+            .execute(&mut *tx)
+            .await
+            .map_err(|_| database::Error::Error)?;
+
+        tx.commit().await.map_err(|_| database::Error::Error)
+    }
+
+    #[tracing::instrument(level = "warn", skip_all)]
+    async fn restore_torrent_from_trash(&self, torrent_id: TorrentId) -> Result<(), database::Error> {
+        let mut tx = self.pool.begin().await.map_err(|_| database::Error::Error)?;
+
+        let result = query(
+            "UPDATE torrust_torrents
+            SET status = (SELECT previous_status FROM torrust_torrent_trash WHERE torrent_id = ?)
+            WHERE torrent_id = ? AND status = 'deleted' AND EXISTS (SELECT 1 FROM torrust_torrent_trash WHERE torrent_id = ?)",
+        )
+        .bind(torrent_id)
+        .bind(torrent_id)
+        .bind(torrent_id)
+        .execute(&mut *tx)
+        .await
+        .map_err(|_| database::Error::Error)?;
+
+        if result.rows_affected() == 0 {
+            return Err(database::Error::TorrentNotFound);
+        }
+
+        query("DELETE FROM torrust_torrent_trash WHERE torrent_id = ?")
+            .bind(torrent_id)
+            .execute(&mut *tx)
+            .await
+            .map_err(|_| database::Error::Error)?;
+
+        tx.commit().await.map_err(|_| database::Error::Error)
+    }
+
+    #[tracing::instrument(level = "warn", skip_all)]
+    async fn get_trashed_torrent(&self, torrent_id: TorrentId) -> Result<TrashedTorrent, database::Error> {
+        query_as::<_, TrashedTorrent>(
+            "SELECT tt.torrent_id, tt.info_hash, ti.title, tr.previous_status, tp.username AS deleted_by, tr.date_deleted
+            FROM torrust_torrent_trash tr
+            INNER JOIN torrust_torrents tt ON tr.torrent_id = tt.torrent_id
+            INNER JOIN torrust_torrent_info ti ON tr.torrent_id = ti.torrent_id
+            LEFT JOIN torrust_user_profiles tp ON tr.deleted_by = tp.user_id
+            WHERE tr.torrent_id = ?",
+        )
+        .bind(torrent_id)
+        .fetch_optional(&self.pool)
+        .await
+        .map_err(|_| database::Error::Error)?
+        .ok_or(database::Error::TorrentNotFound)
+    }
+
+    #[tracing::instrument(level = "warn", skip_all)]
+    async fn get_trashed_torrents(&self) -> Result<Vec<TrashedTorrent>, database::Error> {
+        query_as::<_, TrashedTorrent>(
+            "SELECT tt.torrent_id, tt.info_hash, ti.title, tr.previous_status, tp.username AS deleted_by, tr.date_deleted
+            FROM torrust_torrent_trash tr
+            INNER JOIN torrust_torrents tt ON tr.torrent_id = tt.torrent_id
+            INNER JOIN torrust_torrent_info ti ON tr.torrent_id = ti.torrent_id
+            LEFT JOIN torrust_user_profiles tp ON tr.deleted_by = tp.user_id
+            ORDER BY tr.date_deleted DESC",
+        )
+        .fetch_all(&self.pool)
+        .await
+        .map_err(|_| database::Error::Error)
+    }
+
+    #[tracing::instrument(level = "warn", skip_all)]
+    async fn get_torrents_trashed_before(&self, date: &str, limit: u32) -> Result<Vec<TrashedTorrent>, database::Error> {
+        query_as::<_, TrashedTorrent>(
+            "SELECT tt.torrent_id, tt.info_hash, ti.title, tr.previous_status, tp.username AS deleted_by, tr.date_deleted
+            FROM torrust_torrent_trash tr
+            INNER JOIN torrust_torrents tt ON tr.torrent_id = tt.torrent_id
+            INNER JOIN torrust_torrent_info ti ON tr.torrent_id = ti.torrent_id
+            LEFT JOIN torrust_user_profiles tp ON tr.deleted_by = tp.user_id
+            WHERE tr.date_deleted < ?
+            ORDER BY tr.date_deleted ASC
+            LIMIT ?",
+        )
+        .bind(date)
+        .bind(limit)
+        .fetch_all(&self.pool)
+        .await
+        .map_err(|_| database::Error::Error)
+    }
+
+    #[tracing::instrument(level = "warn", skip_all)]
+    async fn insert_torrent_schedule(
+        &self,
+        torrent_id: TorrentId,
+        publish_at: &str,
+        status_on_publish: &TorrentStatus,
+    ) -> Result<(), database::Error> {
+        query("INSERT INTO torrust_torrent_schedules (torrent_id, publish_at, status_on_publish) VALUES (?, ?, ?)")
+            .bind(torrent_id)
+            .bind(publish_at)
+            .bind(status_on_publish.to_string())
+            .execute(&self.pool)
+            .await
+            .map(|_| ())
+            .map_err(|_| database::Error::Error)
+    }
+
+    #[tracing::instrument(level = "warn", skip_all)]
+    async fn get_due_torrent_schedules(&self, date: &str, limit: u32) -> Result<Vec<ScheduledRelease>, database::Error> {
+        query_as::<_, ScheduledRelease>(
+            "SELECT tt.torrent_id, tt.info_hash, ti.title, ts.publish_at, ts.status_on_publish
+            FROM torrust_torrent_schedules ts
+            INNER JOIN torrust_torrents tt ON ts.torrent_id = tt.torrent_id
+            INNER JOIN torrust_torrent_info ti ON ts.torrent_id = ti.torrent_id
+            WHERE ts.publish_at <= ? AND tt.status = 'scheduled'
+            ORDER BY ts.publish_at ASC
+            LIMIT ?",
+        )
+        .bind(date)
+        .bind(limit)
+        .fetch_all(&self.pool)
+        .await
+        .map_err(|_| database::Error::Error)
+    }
+
+    #[tracing::instrument(level = "warn", skip_all)]
+    async fn publish_scheduled_torrent(&self, torrent_id: TorrentId) -> Result<(), database::Error> {
+        let mut tx = self.pool.begin().await.map_err(|_| database::Error::Error)?;
+
+        let result = query(
+            "UPDATE torrust_torrents
+            SET status = (SELECT status_on_publish FROM torrust_torrent_schedules WHERE torrent_id = ?), date_uploaded = ?
+            WHERE torrent_id = ? AND status = 'scheduled' AND EXISTS (SELECT 1 FROM torrust_torrent_schedules WHERE torrent_id = ?)",
+        )
+        .bind(torrent_id)
+        .bind(datetime_now())
+        .bind(torrent_id)
+        .bind(torrent_id)
+        .execute(&mut *tx)
+        .await
+        .map_err(|_| database::Error::Error)?;
+
+        if result.rows_affected() == 0 {
+            return Err(database::Error::TorrentNotFound);
+        }
+
+        query("DELETE FROM torrust_torrent_schedules WHERE torrent_id = ?")
+            .bind(torrent_id)
+            .execute(&mut *tx)
+            .await
+            .map_err(|_| database::Error::Error)?;
+
+        tx.commit().await.map_err(|_| database::Error::Error)
+    }
+
+    #[tracing::instrument(level = "warn", skip_all)]
+    async fn get_torrent_health_samples(&self) -> Result<Vec<TorrentHealthSample>, database::Error> {
+        query_as::<_, TorrentHealthSample>(
+            "SELECT tt.torrent_id, COALESCE(ts.seeders, 0) AS seeders, th.health_score, th.zero_seeders_since
+            FROM torrust_torrents tt
+            LEFT JOIN torrust_torrent_tracker_stats ts ON tt.torrent_id = ts.torrent_id
+            LEFT JOIN torrust_torrent_health th ON tt.torrent_id = th.torrent_id
+            WHERE tt.status = 'approved'",
+        )
+        .fetch_all(&self.pool)
+        .await
+        .map_err(|_| database::Error::Error)
+    }
+
+    #[tracing::instrument(level = "warn", skip_all)]
+    async fn upsert_torrent_health(
+        &self,
+        torrent_id: TorrentId,
+        health_score: f64,
+        zero_seeders_since: Option<&str>,
+    ) -> Result<(), database::Error> {
+        query(
+            "INSERT INTO torrust_torrent_health (torrent_id, health_score, zero_seeders_since, date_updated)
+            VALUES (?, ?, ?, ?)
+            ON CONFLICT (torrent_id) DO UPDATE SET
+            health_score = excluded.health_score,
+            zero_seeders_since = excluded.zero_seeders_since,
+            date_flagged = CASE WHEN excluded.zero_seeders_since IS NULL THEN NULL ELSE torrust_torrent_health.date_flagged END,
+            date_updated = excluded.date_updated",
+        )
+        .bind(torrent_id)
+        .bind(health_score)
+        .bind(zero_seeders_since)
+        .bind(datetime_now())
+        .execute(&self.pool)
+        .await
+        .map(|_| ())
+        .map_err(|_| database::Error::Error)
+    }
+
+    #[tracing::instrument(level = "warn", skip_all)]
+    async fn get_dead_torrents(&self, zero_seeders_before: &str) -> Result<Vec<DeadTorrent>, database::Error> {
+        query_as::<_, DeadTorrent>(
+            "SELECT tt.torrent_id, tt.info_hash, ti.title, COALESCE(ts.seeders, 0) AS seeders, th.health_score, th.zero_seeders_since, th.date_flagged
+            FROM torrust_torrent_health th
+            INNER JOIN torrust_torrents tt ON th.torrent_id = tt.torrent_id
+            INNER JOIN torrust_torrent_info ti ON th.torrent_id = ti.torrent_id
+            LEFT JOIN torrust_torrent_tracker_stats ts ON th.torrent_id = ts.torrent_id
+            WHERE tt.status = 'approved' AND th.zero_seeders_since < ?
+            ORDER BY th.zero_seeders_since ASC",
+        )
+        .bind(zero_seeders_before)
+        .fetch_all(&self.pool)
+        .await
+        .map_err(|_| database::Error::Error)
+    }
+
+    #[tracing::instrument(level = "warn", skip_all)]
+    async fn flag_dead_torrent(&self, torrent_id: TorrentId) -> Result<(), database::Error> {
+        query("UPDATE torrust_torrent_health SET date_flagged = ? WHERE torrent_id = ? AND date_flagged IS NULL")
+            .bind(datetime_now())
+            .bind(torrent_id)
+            .execute(&self.pool)
+            .await
+            .map(|_| ())
+            .map_err(|_| database::Error::Error)
+    }
+
+    #[tracing::instrument(level = "warn", skip_all)]
+    async fn delete_torrent_health(&self, torrent_id: TorrentId) -> Result<(), database::Error> {
+        query("DELETE FROM torrust_torrent_health WHERE torrent_id = ?")
+            .bind(torrent_id)
+            .execute(&self.pool)
+            .await
+            .map(|_| ())
+            .map_err(|_| database::Error::Error)
+    }
+
+    #[tracing::instrument(level = "warn", skip_all)]
+    async fn apply_bulk_action(&self, torrent_ids: &[TorrentId], action: &BulkAction) -> Result<(), database::Error> {
+        let mut tx = self.pool.begin().await.map_err(|_| database::Error::Error)?;
+
+        for torrent_id in torrent_ids {
+            let result = match action {
+                BulkAction::Delete { deleted_by } => {
+                    query(
+                        "INSERT INTO torrust_torrent_trash (torrent_id, previous_status, deleted_by, date_deleted)
+                        SELECT torrent_id, status, ?, ? FROM torrust_torrents WHERE torrent_id = ? AND status <> 'deleted'",
+                    )
+                    .bind(deleted_by)
+                    .bind(datetime_now())
+                    .bind(torrent_id)
+                    .execute(&mut *tx)
+                    .await
+                    .map_err(|_| database::Error::Error)?;
+
+                    query("UPDATE torrust_torrents SET status = 'deleted' WHERE torrent_id = ?")
+                        .bind(torrent_id)
+                        .execute(&mut *tx)
+                        .await
+                }
+                BulkAction::Recategorize(category_id) => {
+                    query("UPDATE torrust_torrents SET category_id = ? WHERE torrent_id = ?")
+                        .bind(category_id)
+                        .bind(torrent_id)
+                        .execute(&mut *tx)
+                        .await
+                }
+                BulkAction::AddTag(tag_id) => {
+                    query("INSERT OR IGNORE INTO torrust_torrent_tag_links (torrent_id, tag_id) VALUES (?, ?)")
+                        .bind(torrent_id)
+                        .bind(tag_id)
+                        .execute(&mut *tx)
+                        .await
+                }
+                BulkAction::RemoveTag(tag_id) => {
+                    query("DELETE FROM torrust_torrent_tag_links WHERE torrent_id = ? AND tag_id = ?")
+                        .bind(torrent_id)
+                        .bind(tag_id)
+                        .execute(&mut *tx)
+                        .await
+                }
+                BulkAction::Approve => {
+                    query("UPDATE torrust_torrents SET status = 'approved' WHERE torrent_id = ? AND status <> 'deleted'")
+                        .bind(torrent_id)
+                        .execute(&mut *tx)
+                        .await
+                }
+            };
+
+            result.map_err(|_| database::Error::Error)?;
+        }
+
+        tx.commit().await.map_err(|_| database::Error::Error)
+    }
+
+    #[tracing::instrument(level = "warn", skip_all)]
+    async fn insert_banned_pattern(
+        &self,
+        pattern: &str,
+        action: &PatternAction,
+        created_by: UserId,
+    ) -> Result<PatternId, database::Error> {
+        query("INSERT INTO torrust_antispam_patterns (pattern, action, created_by, date_created) VALUES (?, ?, ?, ?)")
+            .bind(pattern)
+            .bind(action.to_string())
+            .bind(created_by)
+            .bind(datetime_now())
+            .execute(&self.pool)
+            .await
+            .map(|v| v.last_insert_rowid())
+            .map_err(|e| match e {
+                sqlx::Error::Database(err) if err.message().contains("UNIQUE") => database::Error::BannedPatternAlreadyExists,
+                _ => database::Error::Error,
+            })
+    }
+
+    #[tracing::instrument(level = "warn", skip_all)]
+    async fn delete_banned_pattern(&self, pattern_id: PatternId) -> Result<(), database::Error> {
+        query("DELETE FROM torrust_antispam_patterns WHERE pattern_id = ?")
+            .bind(pattern_id)
+            .execute(&self.pool)
+            .await
+            .map_err(|_| database::Error::Error)
+            .and_then(|v| {
+                if v.rows_affected() > 0 {
+                    Ok(())
+                } else {
+                    Err(database::Error::BannedPatternNotFound)
+                }
+            })
+    }
+
+    #[tracing::instrument(level = "warn", skip_all)]
+    async fn get_banned_patterns(&self) -> Result<Vec<BannedPattern>, database::Error> {
+        query_as::<_, BannedPattern>(
+            "SELECT ap.pattern_id, ap.pattern, ap.action, tp.username AS created_by, ap.date_created
+            FROM torrust_antispam_patterns ap
+            LEFT JOIN torrust_user_profiles tp ON ap.created_by = tp.user_id
+            ORDER BY ap.pattern_id ASC",
+        )
+        .fetch_all(&self.pool)
+        .await
+        .map_err(|_| database::Error::Error)
+    }
+
+    #[tracing::instrument(level = "warn", skip_all)]
+    async fn insert_user_session(
+        &self,
+        session_id: &str,
+        user_id: UserId,
+        user_agent: Option<&str>,
+        ip: Option<&str>,
+        expires_at: &str,
+    ) -> Result<(), database::Error> {
+        let now = datetime_now();
+
+        query(
+            "INSERT INTO torrust_user_sessions (session_id, user_id, user_agent, ip, date_created, last_seen, expires_at)
+            VALUES (?, ?, ?, ?, ?, ?, ?)",
+        )
+        .bind(session_id)
+        .bind(user_id)
+        .bind(user_agent)
+        .bind(ip)
+        .bind(&now)
+        .bind(&now)
+        .bind(expires_at)
+        .execute(&self.pool)
+        .await
+        .map(|_| ())
+        .map_err(|_| database::Error::Error)
+    }
+
+    #[tracing::instrument(level = "warn", skip_all)]
+    async fn get_user_session(&self, session_id: &str) -> Result<UserSession, database::Error> {
+        query_as::<_, UserSession>(
+            "SELECT session_id, user_id, user_agent, ip, date_created, last_seen, expires_at
+            FROM torrust_user_sessions WHERE session_id = ?",
+        )
+        .bind(session_id)
+        .fetch_one(&self.pool)
+        .await
+        .map_err(|_| database::Error::UserSessionNotFound)
+    }
+
+    #[tracing::instrument(level = "warn", skip_all)]
+    async fn get_user_sessions(&self, user_id: UserId, expires_after: &str) -> Result<Vec<UserSession>, database::Error> {
+        query_as::<_, UserSession>(
+            "SELECT session_id, user_id, user_agent, ip, date_created, last_seen, expires_at
+            FROM torrust_user_sessions WHERE user_id = ? AND expires_at > ?
+            ORDER BY last_seen DESC",
+        )
+        .bind(user_id)
+        .bind(expires_after)
+        .fetch_all(&self.pool)
+        .await
+        .map_err(|_| database::Error::Error)
+    }
+
+    #[tracing::instrument(level = "warn", skip_all)]
+    async fn update_user_session_last_seen(&self, session_id: &str, last_seen: &str) -> Result<(), database::Error> {
+        query("UPDATE torrust_user_sessions SET last_seen = ? WHERE session_id = ?")
+            .bind(last_seen)
+            .bind(session_id)
+            .execute(&self.pool)
+            .await
+            .map(|_| ())
+            .map_err(|_| database::Error::Error)
+    }
+
+    #[tracing::instrument(level = "warn", skip_all)]
+    async fn update_user_session_expiration(&self, session_id: &str, expires_at: &str) -> Result<(), database::Error> {
+        query("UPDATE torrust_user_sessions SET expires_at = ? WHERE session_id = ?")
+            .bind(expires_at)
+            .bind(session_id)
+            .execute(&self.pool)
+            .await
+            .map(|_| ())
+            .map_err(|_| database::Error::Error)
+    }
+
+    #[tracing::instrument(level = "warn", skip_all)]
+    async fn delete_user_session(&self, session_id: &str, user_id: UserId) -> Result<(), database::Error> {
+        query("DELETE FROM torrust_user_sessions WHERE session_id = ? AND user_id = ?")
+            .bind(session_id)
+            .bind(user_id)
+            .execute(&self.pool)
+            .await
+            .map_err(|_| database::Error::Error)
+            .and_then(|v| {
+                if v.rows_affected() > 0 {
+                    Ok(())
+                } else {
+                    Err(database::Error::UserSessionNotFound)
+                }
+            })
+    }
+
+    #[tracing::instrument(level = "warn", skip_all)]
+    async fn delete_expired_user_sessions(&self, expires_before: &str) -> Result<u64, database::Error> {
+        query("DELETE FROM torrust_user_sessions WHERE expires_at < ?")
+            .bind(expires_before)
+            .execute(&self.pool)
+            .await
+            .map(|v| v.rows_affected())
+            .map_err(|_| database::Error::Error)
+    }
+
+    #[tracing::instrument(level = "warn", skip_all)]
+    async fn get_torrent_pieces(&self, info_hash: &InfoHash) -> Result<Option<String>, database::Error> {
+        query_as::<_, (Option<String>,)>("SELECT pieces FROM torrust_torrents WHERE info_hash = ?")
+            .bind(info_hash.to_hex_string().to_lowercase())
+            .fetch_optional(&self.pool)
+            .await
+            .map_err(|_| database::Error::Error)?
+            .map(|(pieces,)| pieces)
+            .ok_or(database::Error::TorrentNotFound)
+    }
+
+    #[tracing::instrument(level = "warn", skip_all)]
+    async fn update_torrent_pieces(&self, info_hash: &InfoHash, pieces: &str) -> Result<(), database::Error> {
+        query("UPDATE torrust_torrents SET pieces = ? WHERE info_hash = ? AND is_bep_30 = 0")
+            .bind(pieces)
+            .bind(info_hash.to_hex_string().to_lowercase())
+            .execute(&self.pool)
+            .await
+            .map_err(|_| database::Error::Error)
+            .and_then(|v| {
+                if v.rows_affected() > 0 {
+                    Ok(())
+                } else {
+                    Err(database::Error::TorrentNotFound)
+                }
+            })
+    }
+
+    #[tracing::instrument(level = "warn", skip_all)]
+    async fn insert_torrent_web_seed(
+        &self,
+        torrent_id: TorrentId,
+        url: &str,
+        added_by: UserId,
+    ) -> Result<WebSeedId, database::Error> {
+        query("INSERT INTO torrust_torrent_web_seeds (torrent_id, url, added_by, date_created) VALUES (?, ?, ?, ?)")
+            .bind(torrent_id)
+            .bind(url)
+            .bind(added_by)
+            .bind(datetime_now())
+            .execute(&self.pool)
+            .await
+            .map(|v| v.last_insert_rowid())
+            .map_err(|e| match e {
+                sqlx::Error::Database(err) if err.message().contains("UNIQUE") => database::Error::TorrentWebSeedAlreadyExists,
+                _ => database::Error::Error,
+            })
+    }
+
+    #[tracing::instrument(level = "warn", skip_all)]
+    async fn get_torrent_web_seeds(&self, torrent_id: TorrentId) -> Result<Vec<TorrentWebSeed>, database::Error> {
+        query_as::<_, TorrentWebSeed>(
+            "SELECT web_seed_id, torrent_id, url, date_created FROM torrust_torrent_web_seeds WHERE torrent_id = ? ORDER BY web_seed_id ASC",
+        )
+        .bind(torrent_id)
+        .fetch_all(&self.pool)
+        .await
+        .map_err(|_| database::Error::Error)
+    }
+
+    #[tracing::instrument(level = "warn", skip_all)]
+    async fn delete_torrent_web_seed(&self, torrent_id: TorrentId, web_seed_id: WebSeedId) -> Result<(), database::Error> {
+        let result = query("DELETE FROM torrust_torrent_web_seeds WHERE torrent_id = ? AND web_seed_id = ?")
+            .bind(torrent_id)
+            .bind(web_seed_id)
+            .execute(&self.pool)
+            .await
+            .map_err(|_| database::Error::Error)?;
+
+        if result.rows_affected() == 0 {
+            return Err(database::Error::TorrentWebSeedNotFound);
+        }
+
+        Ok(())
+    }
+
+    #[tracing::instrument(level = "warn", skip_all)]
+    async fn upsert_torrent_metadata(&self, metadata: &TorrentMetadata) -> Result<(), database::Error> {
+        query(
+            "INSERT INTO torrust_torrent_metadata (torrent_id, provider, external_id, title, year, synopsis, poster_url, external_url, date_updated)
+            VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?)
+            ON CONFLICT (torrent_id) DO UPDATE SET
+            provider = excluded.provider,
+            external_id = excluded.external_id,
+            title = excluded.title,
+            year = excluded.year,
+            synopsis = excluded.synopsis,
+            poster_url = excluded.poster_url,
+            external_url = excluded.external_url,
+            date_updated = excluded.date_updated",
+        )
+        .bind(metadata.torrent_id)
+        .bind(&metadata.provider)
+        .bind(&metadata.external_id)
+        .bind(&metadata.title)
+        .bind(metadata.year)
+        .bind(&metadata.synopsis)
+        .bind(&metadata.poster_url)
+        .bind(&metadata.external_url)
+        .bind(datetime_now())
+        .execute(&self.pool)
+        .await
+        .map(|_| ())
+        .map_err(|_| database::Error::Error)
+    }
+
+    #[tracing::instrument(level = "warn", skip_all)]
+    async fn get_torrent_metadata(&self, torrent_id: TorrentId) -> Result<Option<TorrentMetadata>, database::Error> {
+        query_as::<_, TorrentMetadata>(
+            "SELECT torrent_id, provider, external_id, title, year, synopsis, poster_url, external_url, date_updated
+            FROM torrust_torrent_metadata
+            WHERE torrent_id = ?",
+        )
+        .bind(torrent_id)
+        .fetch_optional(&self.pool)
+        .await
+        .map_err(|_| database::Error::Error)
+    }
+
+    #[tracing::instrument(level = "warn", skip_all)]
+    async fn delete_torrent_metadata(&self, torrent_id: TorrentId) -> Result<(), database::Error> {
+        let result = query("DELETE FROM torrust_torrent_metadata WHERE torrent_id = ?")
+            .bind(torrent_id)
+            .execute(&self.pool)
+            .await
+            .map_err(|_| database::Error::Error)?;
+
+        if result.rows_affected() == 0 {
+            return Err(database::Error::TorrentMetadataNotFound);
+        }
+
+        Ok(())
+    }
+
+    #[tracing::instrument(level = "warn", skip_all)]
+    async fn insert_category_field(
+        &self,
+        category_id: CategoryId,
+        name: &str,
+        label: &str,
+        field_type: FieldType,
+        required: bool,
+        options: &[String],
+    ) -> Result<CategoryFieldId, database::Error> {
+        let options = serde_json::to_string(options).map_err(|_| database::Error::Error)?;
+
+        query(
+            "INSERT INTO torrust_category_fields (category_id, name, label, field_type, required, options, date_created) VALUES (?, ?, ?, ?, ?, ?, ?)",
+        )
+        .bind(category_id)
+        .bind(name)
+        .bind(label)
+        .bind(field_type.as_str())
+        .bind(required)
+        .bind(options)
+        .bind(datetime_now())
+        .execute(&self.pool)
+        .await
+        .map(|v| v.last_insert_rowid())
+        .map_err(|e| match e {
+            sqlx::Error::Database(err) if err.message().contains("UNIQUE") => database::Error::CategoryFieldAlreadyExists,
+            _ => database::Error::Error,
+        })
+    }
+
+    #[tracing::instrument(level = "warn", skip_all)]
+    async fn get_category_fields(&self, category_id: CategoryId) -> Result<Vec<CategoryField>, database::Error> {
+        let rows = query_as::<_, (CategoryFieldId, CategoryId, String, String, String, bool, String)>(
+            "SELECT field_id, category_id, name, label, field_type, required, options FROM torrust_category_fields WHERE category_id = ? ORDER BY field_id ASC",
+        )
+        .bind(category_id)
+        .fetch_all(&self.pool)
+        .await
+        .map_err(|_| database::Error::Error)?;
+
+        rows.into_iter()
+            .map(|(field_id, category_id, name, label, field_type, required, options)| {
+                Ok(CategoryField {
+                    field_id,
+                    category_id,
+                    name,
+                    label,
+                    field_type: FieldType::from_str(&field_type).map_err(|()| database::Error::Error)?,
+                    required,
+                    options: serde_json::from_str(&options).map_err(|_| database::Error::Error)?,
+                })
+            })
+            .collect()
+    }
+
+    #[tracing::instrument(level = "warn", skip_all)]
+    async fn delete_category_field(&self, category_id: CategoryId, name: &str) -> Result<(), database::Error> {
+        let result = query("DELETE FROM torrust_category_fields WHERE category_id = ? AND name = ?")
+            .bind(category_id)
+            .bind(name)
+            .execute(&self.pool)
+            .await
+            .map_err(|_| database::Error::Error)?;
+
+        if result.rows_affected() == 0 {
+            return Err(database::Error::CategoryFieldNotFound);
+        }
+
+        Ok(())
+    }
+
+    #[tracing::instrument(level = "warn", skip_all)]
+    async fn set_torrent_field_values(
+        &self,
+        torrent_id: TorrentId,
+        values: &[(CategoryFieldId, String)],
+    ) -> Result<(), database::Error> {
+        let mut tx = self.pool.begin().await.map_err(|_| database::Error::Error)?;
+
+        query("DELETE FROM torrust_torrent_field_values WHERE torrent_id = ?")
+            .bind(torrent_id)
+            .execute(&mut *tx)
+            .await
+            .map_err(|_| database::Error::Error)?;
+
+        for (field_id, value) in values {
+            query("INSERT INTO torrust_torrent_field_values (torrent_id, field_id, value) VALUES (?, ?, ?)")
+                .bind(torrent_id)
+                .bind(field_id)
+                .bind(value)
+                .execute(&mut *tx)
+                .await
+                .map_err(|_| database::Error::Error)?;
+        }
+
+        tx.commit().await.map_err(|_| database::Error::Error)
+    }
+
+    #[tracing::instrument(level = "warn", skip_all)]
+    async fn get_torrent_field_values(&self, torrent_id: TorrentId) -> Result<Vec<TorrentFieldValue>, database::Error> {
+        query_as::<_, TorrentFieldValue>(
+            "SELECT cf.name, cf.label, fv.value FROM torrust_torrent_field_values fv
+            INNER JOIN torrust_category_fields cf ON cf.field_id = fv.field_id
+            INNER JOIN torrust_torrents tt ON tt.torrent_id = fv.torrent_id AND tt.category_id = cf.category_id
+            WHERE fv.torrent_id = ?
+            ORDER BY cf.field_id ASC",
+        )
+        .bind(torrent_id)
+        .fetch_all(&self.pool)
+        .await
+        .map_err(|_| database::Error::Error)
+    }
+
+    #[tracing::instrument(level = "warn", skip_all)]
+    async fn get_user_reputation_stats(&self, user_id: UserId) -> Result<ReputationStats, database::Error> {
+        query_as::<_, (i64, i64, i64, i64, i64)>(
+            "SELECT
+            (SELECT COUNT(*) FROM torrust_torrents WHERE uploader_id = ?),
+            (SELECT COUNT(*) FROM torrust_torrents WHERE uploader_id = ? AND status = ?),
+            (SELECT COUNT(*) FROM torrust_torrents WHERE uploader_id = ? AND status = ?),
+            (SELECT COUNT(*) FROM torrust_torrent_reports tr
+                INNER JOIN torrust_torrents tt ON tt.torrent_id = tr.torrent_id
+                WHERE tt.uploader_id = ?),
+            (SELECT COUNT(*) FROM torrust_torrent_reports tr
+                INNER JOIN torrust_torrents tt ON tt.torrent_id = tr.torrent_id
+                WHERE tt.uploader_id = ? AND tr.resolved = TRUE AND tr.resolution <> ?)",
+        )
+        .bind(user_id)
+        .bind(user_id)
+        .bind(TorrentStatus::Approved.to_string())
+        .bind(user_id)
+        .bind(TorrentStatus::Rejected.to_string())
+        .bind(user_id)
+        .bind(user_id)
+        .bind(ReportAction::Dismiss.to_string())
+        .fetch_one(&self.pool)
+        .await
+        .map(
+            |(uploads, approved_uploads, rejected_uploads, reports, upheld_reports)| ReputationStats {
+                uploads: u64::try_from(uploads).unwrap_or_default(),
+                approved_uploads: u64::try_from(approved_uploads).unwrap_or_default(),
+                rejected_uploads: u64::try_from(rejected_uploads).unwrap_or_default(),
+                reports: u64::try_from(reports).unwrap_or_default(),
+                upheld_reports: u64::try_from(upheld_reports).unwrap_or_default(),
+            },
+        )
+        .map_err(|_| database::Error::Error)
+    }
+
+    #[tracing::instrument(level = "warn", skip_all)]
+    async fn get_user_trusted_override(&self, user_id: UserId) -> Result<Option<bool>, database::Error> {
+        query_as::<_, (bool,)>("SELECT trusted FROM torrust_user_reputation_overrides WHERE user_id = ?")
+            .bind(user_id)
+            .fetch_optional(&self.pool)
+            .await
+            .map(|row| row.map(|(trusted,)| trusted))
+            .map_err(|_| database::Error::Error)
+    }
+
+    #[tracing::instrument(level = "warn", skip_all)]
+    async fn upsert_user_trusted_override(&self, user_id: UserId, trusted: bool) -> Result<(), database::Error> {
+        query(
+            "INSERT INTO torrust_user_reputation_overrides (user_id, trusted, date_updated)
+            VALUES (?, ?, ?)
+            ON CONFLICT (user_id) DO UPDATE SET
+            trusted = excluded.trusted,
+            date_updated = excluded.date_updated",
+        )
+        .bind(user_id)
+        .bind(trusted)
+        .bind(datetime_now())
+        .execute(&self.pool)
+        .await
+        .map(|_| ())
+        .map_err(|_| database::Error::Error)
+    }
+
+    #[tracing::instrument(level = "warn", skip_all)]
+    async fn delete_user_trusted_override(&self, user_id: UserId) -> Result<(), database::Error> {
+        query("DELETE FROM torrust_user_reputation_overrides WHERE user_id = ?")
+            .bind(user_id)
+            .execute(&self.pool)
+            .await
+            .map(|_| ())
+            .map_err(|_| database::Error::Error)
+    }
+
+    #[tracing::instrument(level = "warn", skip_all)]
+    async fn import_key_transfer_stats(
+        &self,
+        tracker_key: &str,
+        uploaded: u64,
+        downloaded: u64,
+    ) -> Result<bool, database::Error> {
+        // Keys revoked in the index are kept, so the bytes transferred with
+        // them still count
+        query(
+            "INSERT INTO torrust_user_transfer_stats (tracker_key, user_id, uploaded, downloaded, date_updated)
+            SELECT tracker_key, user_id, ?, ?, ? FROM torrust_tracker_keys WHERE tracker_key = ? LIMIT 1
+            ON CONFLICT (tracker_key) DO UPDATE SET
+            uploaded = excluded.uploaded,
+            downloaded = excluded.downloaded,
+            date_updated = excluded.date_updated",
+        )
+        .bind(i64::try_from(uploaded).unwrap_or(i64::MAX))
+        .bind(i64::try_from(downloaded).unwrap_or(i64::MAX))
+        .bind(datetime_now())
+        .bind(tracker_key)
+        .execute(&self.pool)
+        .await
+        .map(|v| v.rows_affected() > 0)
+        .map_err(|_| database::Error::Error)
+    }
+
+    #[tracing::instrument(level = "warn", skip_all)]
+    async fn get_user_transfer_stats(&self, user_id: UserId) -> Result<TransferStats, database::Error> {
+        query_as::<_, (i64, i64)>(
+            "SELECT COALESCE(SUM(uploaded), 0), COALESCE(SUM(downloaded), 0)
+            FROM torrust_user_transfer_stats WHERE user_id = ?",
+        )
+        .bind(user_id)
+        .fetch_one(&self.pool)
+        .await
+        .map(|(uploaded, downloaded)| TransferStats {
+            uploaded: u64::try_from(uploaded).unwrap_or_default(),
+            downloaded: u64::try_from(downloaded).unwrap_or_default(),
+        })
+        .map_err(|_| database::Error::Error)
+    }
+
+    #[tracing::instrument(level = "warn", skip_all)]
+    async fn add_upload_nonce(&self, nonce: &str, date_expiry: u64) -> Result<bool, database::Error> {
+        query("INSERT OR IGNORE INTO torrust_upload_nonces (nonce, date_expiry) VALUES (?, ?)")
+            .bind(nonce)
+            .bind(i64::try_from(date_expiry).unwrap_or(i64::MAX))
+            .execute(&self.pool)
+            .await
+            .map(|v| v.rows_affected() > 0)
+            .map_err(|_| database::Error::Error)
+    }
+
+    #[tracing::instrument(level = "warn", skip_all)]
+    async fn delete_expired_upload_nonces(&self, now: u64) -> Result<(), database::Error> {
+        query("DELETE FROM torrust_upload_nonces WHERE date_expiry < ?")
+            .bind(i64::try_from(now).unwrap_or(i64::MAX))
+            .execute(&self.pool)
+            .await
+            .map(|_| ())
+            .map_err(|_| database::Error::Error)
+    }
+
+    #[tracing::instrument(level = "warn", skip_all)]
+    async fn delete_all_database_rows(&self) -> Result<(), database::Error> {
+        for table in TABLES_TO_TRUNCATE {
+            query(&format!("DELETE FROM {table};"))
+                .execute(&self.pool)
+                .await
+                .map_err(|_| database::Error::Error)?;
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use sqlx::query_as;
+    use tempfile::TempDir;
+
+    use super::{Sqlite, MIGRATOR};
+    use crate::config::{SqliteJournalMode, SqliteSynchronous};
+    use crate::databases::database::{Database, PoolSettings, SqliteSettings};
+    use crate::databases::testing::{add_category, add_torrent, add_user};
+    use crate::models::torrent::TorrentStatus;
+
+    /// Version of the first migration with a down script.
+    const FIRST_REVERSIBLE_MIGRATION: i64 = 20_241_016_100_000;
+
+    async fn migrated_database(temp_dir: &TempDir) -> Sqlite {
+        let database_url = format!("sqlite://{}/data.db?mode=rwc", temp_dir.path().display());
+
+        let database = Sqlite::new(&database_url, &PoolSettings::default()).await;
+
+        database.run_migrations().await.unwrap();
+
+        database
+    }
+
+    #[tokio::test]
+    async fn it_should_apply_the_pragmas_to_all_the_connections_of_the_pool() {
+        let temp_dir = TempDir::new().unwrap();
+        let database_url = format!("sqlite://{}/data.db?mode=rwc", temp_dir.path().display());
+
+        let pool_settings = PoolSettings {
+            max_connections: 2,
+            sqlite: SqliteSettings {
+                journal_mode: SqliteJournalMode::Truncate,
+                synchronous: SqliteSynchronous::Full,
+                busy_timeout: Duration::from_millis(1234),
+            },
+            ..PoolSettings::default()
+        };
+
+        let database = Sqlite::new(&database_url, &pool_settings).await;
+
+        // Both connections are open at the same time, so they are different
+        let mut first_conn = database.pool.acquire().await.unwrap();
+        let mut second_conn = database.pool.acquire().await.unwrap();
+
+        for conn in [&mut first_conn, &mut second_conn] {
+            let (journal_mode,): (String,) = query_as("PRAGMA journal_mode").fetch_one(&mut **conn).await.unwrap();
+            let (synchronous,): (i64,) = query_as("PRAGMA synchronous").fetch_one(&mut **conn).await.unwrap();
+            let (busy_timeout,): (i64,) = query_as("PRAGMA busy_timeout").fetch_one(&mut **conn).await.unwrap();
+
+            assert_eq!(journal_mode, "truncate");
+            // FULL
+            assert_eq!(synchronous, 2);
+            assert_eq!(busy_timeout, 1234);
+        }
+    }
+
+    #[test]
+    fn all_the_migrations_since_the_first_reversible_one_should_have_a_down_script() {
+        for migration in MIGRATOR
+            .iter()
+            .filter(|migration| !migration.migration_type.is_down_migration())
+            .filter(|migration| migration.version >= FIRST_REVERSIBLE_MIGRATION)
+        {
+            assert!(
+                MIGRATOR
+                    .iter()
+                    .any(|down| down.version == migration.version && down.migration_type.is_down_migration()),
+                "migration {} has no down script",
+                migration.version
+            );
+        }
+    }
+
+    #[tokio::test]
+    async fn it_should_redo_the_last_migration() {
+        let temp_dir = TempDir::new().unwrap();
+        let database = migrated_database(&temp_dir).await;
+
+        let last_version = MIGRATOR.iter().map(|migration| migration.version).max().unwrap();
+
+        assert_eq!(database.redo_last_migration().await.unwrap(), last_version);
+        assert!(database
+            .get_migrations()
+            .await
+            .unwrap()
+            .iter()
+            .all(|migration| migration.applied));
+    }
+
+    #[tokio::test]
+    async fn it_should_revert_and_reapply_all_the_reversible_migrations_keeping_the_existing_data() {
+        let temp_dir = TempDir::new().unwrap();
+        let database = migrated_database(&temp_dir).await;
+
+        let user_id = add_user(&database, "alice").await;
+        let category_id = add_category(&database, "movies").await;
+        let (torrent_id, _) = add_torrent(&database, user_id, category_id, &TorrentStatus::Approved).await;
+
+        MIGRATOR.undo(&database.pool, FIRST_REVERSIBLE_MIGRATION - 1).await.unwrap();
+
+        let migrations = database.get_migrations().await.unwrap();
+
+        for migration in &migrations {
+            assert_eq!(migration.applied, migration.version < FIRST_REVERSIBLE_MIGRATION);
+        }
+
+        let reverted = migrations.iter().filter(|migration| !migration.applied).count();
+
+        assert_eq!(database.run_migrations().await.unwrap(), reverted);
+        assert!(database.get_user_from_id(user_id).await.is_ok());
+        assert!(database.get_torrent_info_from_id(torrent_id).await.is_ok());
     }
 }
@@ -0,0 +1,111 @@
+//! Databases for the unit tests.
+use std::sync::Arc;
+
+use bittorrent_primitives::info_hash::InfoHash;
+use sqlx::sqlite::SqlitePoolOptions;
+use tempfile::TempDir;
+use uuid::Uuid;
+
+use super::database::{self, Database};
+use crate::models::category::CategoryId;
+use crate::models::torrent::{Metadata, TorrentId, TorrentStatus};
+use crate::models::user::UserId;
+use crate::services::torrent_file::generate_random_torrent;
+
+/// It creates a new `SQLite` database, with all the migrations applied, in a
+/// temporary directory. The directory is removed when the returned `TempDir`
+/// is dropped.
+///
+/// # Panics
+///
+/// It panics if the database can't be created.
+pub async fn new_sqlite_database() -> (TempDir, Arc<Box<dyn Database>>) {
+    let temp_dir = TempDir::new().expect("failed to create a temporary directory");
+
+    let database = database::connect(&connect_url(&temp_dir)).await.expect("Database error.");
+
+    (temp_dir, Arc::new(database))
+}
+
+/// It runs a SQL statement in a database created with
+/// [`new_sqlite_database`], to set up data the [`Database`] trait can't,
+/// like past dates.
+///
+/// # Panics
+///
+/// It panics if the statement fails.
+pub async fn execute(temp_dir: &TempDir, sql: &str) {
+    let pool = SqlitePoolOptions::new()
+        .connect(&connect_url(temp_dir))
+        .await
+        .expect("Database error.");
+
+    sqlx::query(sql).execute(&pool).await.expect("Database error.");
+}
+
+/// It adds a user and returns its ID.
+///
+/// # Panics
+///
+/// It panics if the user can't be added.
+pub async fn add_user(database: &dyn Database, username: &str) -> UserId {
+    database
+        .insert_user_and_get_id(username, &format!("{username}@torrust.com"), "password_hash")
+        .await
+        .expect("Database error.")
+}
+
+/// It adds an administrator and returns its ID.
+///
+/// # Panics
+///
+/// It panics if the user can't be added.
+pub async fn add_admin(database: &dyn Database, username: &str) -> UserId {
+    let user_id = add_user(database, username).await;
+
+    database.grant_admin_role(user_id).await.expect("Database error.");
+
+    user_id
+}
+
+/// It adds a category and returns its ID.
+///
+/// # Panics
+///
+/// It panics if the category can't be added.
+pub async fn add_category(database: &dyn Database, name: &str) -> CategoryId {
+    database.insert_category_and_get_id(name).await.expect("Database error.")
+}
+
+/// It adds a random torrent and returns its ID and info-hash.
+///
+/// # Panics
+///
+/// It panics if the torrent can't be added.
+pub async fn add_torrent(
+    database: &dyn Database,
+    uploader_id: UserId,
+    category_id: CategoryId,
+    status: &TorrentStatus,
+) -> (TorrentId, InfoHash) {
+    let torrent = generate_random_torrent(Uuid::new_v4());
+    let info_hash = torrent.canonical_info_hash();
+
+    let metadata = Metadata {
+        title: torrent.info.name.clone(),
+        description: String::new(),
+        category_id,
+        tags: vec![],
+    };
+
+    let torrent_id = database
+        .insert_torrent_and_get_id(&info_hash, &torrent, uploader_id, &metadata, status)
+        .await
+        .expect("Database error.");
+
+    (torrent_id, info_hash)
+}
+
+fn connect_url(temp_dir: &TempDir) -> String {
+    format!("sqlite://{}/data.db?mode=rwc", temp_dir.path().display())
+}
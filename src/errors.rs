@@ -6,6 +6,7 @@ use hyper::StatusCode;
 
 use crate::databases::database;
 use crate::models::torrent::MetadataError;
+use crate::services::search;
 use crate::tracker::service::TrackerAPIError;
 use crate::utils::parse_torrent::DecodeTorrentFileError;
 
@@ -74,6 +75,9 @@ pub enum ServiceError {
     #[display("Please verify your email before logging in")]
     EmailNotVerified,
 
+    #[display("The email is already verified")]
+    EmailAlreadyVerified,
+
     /// when the a token name is already taken
     /// token not found
     #[display("Token not found. Please sign in.")]
@@ -87,6 +91,13 @@ pub enum ServiceError {
     /// token invalid
     TokenInvalid,
 
+    /// the session of the token was revoked
+    #[display("Session revoked. Please sign in again.")]
+    SessionRevoked,
+
+    #[display("Session not found.")]
+    SessionNotFound,
+
     #[display("Uploaded torrent is not valid.")]
     InvalidTorrentFile,
 
@@ -132,6 +143,9 @@ pub enum ServiceError {
     #[display("Failed to send verification email.")]
     FailedToSendVerificationEmail,
 
+    #[display("Failed to send email.")]
+    FailedToSendEmail,
+
     #[display("Category already exists.")]
     CategoryAlreadyExists,
 
@@ -153,6 +167,12 @@ pub enum ServiceError {
     #[display("Tag not found.")]
     TagNotFound,
 
+    #[display("Tag synonym not found.")]
+    TagSynonymNotFound,
+
+    #[display("A tag can't be merged into itself.")]
+    CannotMergeTagIntoItself,
+
     #[display("Database error.")]
     DatabaseError,
 
@@ -175,6 +195,407 @@ pub enum ServiceError {
     #[display("Invalid tracker API token.")]
     InvalidTrackerToken,
     // End tracker errors
+    #[display("Search index error.")]
+    SearchIndexError,
+
+    // Begin signed upload errors
+    #[display("Signed uploads are not enabled.")]
+    SignedUploadsDisabled,
+
+    #[display("Invalid upload signature.")]
+    InvalidUploadSignature,
+
+    #[display("Upload signature expired. Check the request timestamp.")]
+    ExpiredUploadSignature,
+
+    #[display("Upload signature already used.")]
+    UploadSignatureAlreadyUsed,
+    // End signed upload errors
+
+    // Begin comment errors
+    #[display("Comment not found.")]
+    CommentNotFound,
+
+    #[display("Comment text must be between 1 and 10000 characters.")]
+    InvalidCommentLength,
+    // End comment errors
+
+    // Begin report errors
+    #[display("Report not found.")]
+    ReportNotFound,
+
+    #[display("Report details must not exceed 2000 characters.")]
+    InvalidReportDetailsLength,
+
+    #[display("Report already resolved.")]
+    ReportAlreadyResolved,
+    // End report errors
+
+    // Begin bookmark errors
+    #[display("Bookmark not found.")]
+    BookmarkNotFound,
+
+    #[display("Unsupported bookmarks file format or version.")]
+    UnsupportedBookmarksFile,
+    // End bookmark errors
+
+    // Begin subscription errors
+    #[display("Subscription not found.")]
+    SubscriptionNotFound,
+
+    #[display("A subscription must have either a category or a tag.")]
+    InvalidSubscription,
+
+    #[display("Already subscribed.")]
+    SubscriptionAlreadyExists,
+    // End subscription errors
+
+    // Begin saved search errors
+    #[display("Saved search not found.")]
+    SavedSearchNotFound,
+
+    #[display("A saved search must have a name and a query, a category or tags.")]
+    InvalidSavedSearch,
+
+    #[display("Too many saved searches.")]
+    TooManySavedSearches,
+    // End saved search errors
+
+    // Begin collection errors
+    #[display("Collection not found.")]
+    CollectionNotFound,
+
+    #[display("Torrent not found in the collection.")]
+    CollectionItemNotFound,
+
+    #[display("Collection name must be between 1 and 100 characters.")]
+    InvalidCollectionName,
+
+    #[display("Invalid smart collection filter.")]
+    InvalidSmartCollectionFilter,
+
+    #[display("The torrents of a smart collection can't be changed manually.")]
+    SmartCollectionNotEditable,
+    // End collection errors
+
+    // Begin torrent listing errors
+    #[display("Invalid torrent listing filter.")]
+    InvalidListingFilter,
+    // End torrent listing errors
+
+    // Begin torrent structure errors
+    #[display("Torrent piece length is outside the allowed range.")]
+    TorrentPieceLengthNotAllowed,
+
+    #[display("Torrent padding files are only allowed to align files to pieces.")]
+    TorrentPaddingFilesNotAllowed,
+
+    #[display("Only multi-file torrents are allowed in this category.")]
+    SingleFileTorrentNotAllowedInCategory,
+
+    #[display("Torrent file paths have too many directories.")]
+    TorrentPathTooDeep,
+
+    #[display("Torrent file paths are too long.")]
+    TorrentPathTooLong,
+    // End torrent structure errors
+
+    // Begin upload policy errors
+    #[display("The torrent contains files with a banned extension. Remove them and create the torrent again.")]
+    TorrentFileExtensionNotAllowed,
+
+    #[display("The torrent contents are smaller than the minimum size allowed.")]
+    TorrentTooSmall,
+
+    #[display("The torrent contents are larger than the maximum size allowed.")]
+    TorrentTooLarge,
+
+    #[display("The torrent piece length is smaller than the minimum allowed. Create the torrent with bigger pieces.")]
+    TorrentPieceLengthTooSmall,
+
+    #[display("The torrent piece length is larger than the maximum allowed. Create the torrent with smaller pieces.")]
+    TorrentPieceLengthTooLarge,
+    // End upload policy errors
+
+    // Begin torrent trackers errors
+    #[display("Tracker URLs must be valid HTTP, HTTPS or UDP URLs.")]
+    InvalidTrackerUrl,
+
+    #[display("At least one tracker is required.")]
+    MissingTrackers,
+    // End torrent trackers errors
+
+    // Begin magnet upload errors
+    #[display("Magnet uploads are not enabled.")]
+    MagnetUploadsDisabled,
+
+    #[display("Invalid magnet link. It must contain a BitTorrent v1 info-hash.")]
+    InvalidMagnetLink,
+
+    #[display("Magnet upload not found.")]
+    MagnetUploadNotFound,
+
+    #[display("This torrent is already being resolved.")]
+    MagnetUploadAlreadyResolving,
+    // End magnet upload errors
+
+    // Begin rate limit errors
+    #[display("Too many requests. Please try again later.")]
+    TooManyRequests,
+    // End rate limit errors
+
+    // Begin bulk import errors
+    #[display("The import directory can't be read.")]
+    InvalidImportDirectory,
+
+    #[display("Invalid import manifest file.")]
+    InvalidImportManifest,
+
+    #[display("Invalid import mapping file.")]
+    InvalidImportMapping,
+
+    #[display("Import session not found.")]
+    ImportSessionNotFound,
+
+    #[display("Invalid import session.")]
+    InvalidImportSession,
+
+    #[display("Invalid import batch.")]
+    InvalidImportBatch,
+
+    #[display("The import session is already completed.")]
+    ImportSessionCompleted,
+    // End bulk import errors
+
+    // Begin points errors
+    #[display("Points adjustments need a non-zero amount and a reason.")]
+    InvalidPointsAdjustment,
+    // End points errors
+
+    // Begin bulk mail errors
+    #[display("The email subject and body are required.")]
+    MissingBulkMailFields,
+
+    #[display("Invalid email template.")]
+    InvalidBulkMailTemplate,
+    // End bulk mail errors
+
+    // Begin inactivity errors
+    #[display("This account is disabled.")]
+    AccountDisabled,
+    // End inactivity errors
+
+    // Begin image upload errors
+    #[display("The image storage is not enabled.")]
+    ImageStorageDisabled,
+
+    #[display("Unsupported image type.")]
+    UnsupportedImageType,
+
+    #[display("The image is empty or too big.")]
+    InvalidImageSize,
+
+    #[display("The uploaded image is not valid.")]
+    InvalidImageUpload,
+
+    #[display("The uploaded image was not found.")]
+    ImageUploadNotFound,
+
+    #[display("The image was already registered.")]
+    ImageAlreadyRegistered,
+    // End image upload errors
+
+    // Begin upload quota errors
+    #[display("You have reached the maximum number of uploads per day. Please try again later.")]
+    DailyUploadLimitReached,
+
+    #[display("This upload would exceed your total upload size quota.")]
+    UploadSizeQuotaExceeded,
+    // End upload quota errors
+
+    // Begin user profile errors
+    #[display("The bio is too long.")]
+    BioTooLong,
+    // End user profile errors
+
+    // Begin widget errors
+    #[display("Widgets are not enabled.")]
+    WidgetsDisabled,
+
+    #[display("Invalid JSONP callback name.")]
+    InvalidJsonpCallback,
+    // End widget errors
+
+    // Begin mirror sync errors
+    #[display("The delta sync for mirrors is not enabled.")]
+    MirrorSyncDisabled,
+    // End mirror sync errors
+
+    // Begin audit log errors
+    #[display("Invalid date range. Dates must be in YYYY-MM-DD format and `from` can't be after `to`.")]
+    InvalidAuditLogDateRange,
+    // End audit log errors
+
+    // Begin co-signing errors
+    #[display("You have already approved this torrent. It must be approved by another staff member.")]
+    TorrentAlreadyCoSigned,
+
+    #[display("You can't approve your own torrent in a category that requires co-signing.")]
+    CannotCoSignOwnTorrent,
+    // End co-signing errors
+
+    // Begin torrent preview errors
+    #[display("Torrent previews are not enabled.")]
+    TorrentPreviewsDisabled,
+
+    #[display("The file is not in the torrent.")]
+    PreviewFileNotFound,
+
+    #[display("The file is empty or too big for a preview.")]
+    InvalidPreviewFileSize,
+
+    #[display("The preview does not match the torrent pieces.")]
+    InvalidPreviewPieces,
+
+    #[display("Only images and text files can be used as previews.")]
+    UnsupportedPreviewType,
+
+    #[display("The torrent has already the max number of previews.")]
+    TooManyPreviews,
+
+    #[display("There is already a preview of this file.")]
+    TorrentPreviewAlreadyExists,
+
+    #[display("Torrent preview not found.")]
+    TorrentPreviewNotFound,
+    // End torrent preview errors
+
+    // Begin description image errors
+    #[display("Description image uploads are not enabled.")]
+    DescriptionImagesDisabled,
+
+    #[display("The torrent has already the max number of description images.")]
+    TooManyDescriptionImages,
+
+    #[display("Description image not found.")]
+    DescriptionImageNotFound,
+    // End description image errors
+
+    // Begin torrent attachment errors
+    #[display("Torrent attachments are not enabled.")]
+    TorrentAttachmentsDisabled,
+
+    #[display("The attachment is empty or too big.")]
+    InvalidAttachmentSize,
+
+    #[display("NFO attachments must be text files and screenshots must be images.")]
+    UnsupportedAttachmentType,
+
+    #[display("The torrent has already the max number of screenshots.")]
+    TooManyScreenshots,
+
+    #[display("Torrent attachment not found.")]
+    TorrentAttachmentNotFound,
+    // End torrent attachment errors
+
+    // Begin torrent web seed errors
+    #[display("Web seeds must be HTTP or HTTPS URLs.")]
+    InvalidWebSeedUrl,
+
+    #[display("The torrent has already the max number of web seeds.")]
+    TooManyWebSeeds,
+
+    #[display("The torrent has already this web seed.")]
+    TorrentWebSeedAlreadyExists,
+
+    #[display("Torrent web seed not found.")]
+    TorrentWebSeedNotFound,
+    // End torrent web seed errors
+
+    // Begin torrent metadata errors
+    #[display("Metadata enrichment is not enabled.")]
+    MetadataEnrichmentDisabled,
+
+    #[display("Invalid external ID. Use `imdb:<id>`, `tmdb:movie/<id>`, `tmdb:tv/<id>` or `musicbrainz:<id>`, or a title.")]
+    InvalidExternalId,
+
+    #[display("There is no configured metadata provider for this request.")]
+    MetadataProviderNotConfigured,
+
+    #[display("The metadata provider failed. Try again later.")]
+    MetadataProviderFailed,
+
+    #[display("Torrent metadata not found.")]
+    TorrentMetadataNotFound,
+    // End torrent metadata errors
+
+    // Begin category field errors
+    #[display("Category fields must have a unique lowercase name, a label and, for `choice` fields, the options.")]
+    InvalidCategoryField,
+
+    #[display("The category has already a field with this name.")]
+    CategoryFieldAlreadyExists,
+
+    #[display("Category field not found.")]
+    CategoryFieldNotFound,
+
+    #[display("A category field value is not valid for the field type, or the field does not exist.")]
+    InvalidCategoryFieldValue,
+
+    #[display("A required category field is empty.")]
+    MissingCategoryFieldValue,
+    // End category field errors
+
+    // Begin torrent file group errors
+    #[display("File groups must have a unique name and a path matching some files in the torrent.")]
+    InvalidTorrentFileGroup,
+
+    #[display("The torrent has too many file groups.")]
+    TooManyTorrentFileGroups,
+
+    #[display("Torrent file group not found.")]
+    TorrentFileGroupNotFound,
+    // End torrent file group errors
+
+    // Begin torrent deduplication errors
+    #[display("The contents of this torrent are already in another torrent.")]
+    DuplicateTorrentContent,
+    // End torrent deduplication errors
+
+    // Begin scheduled release errors
+    #[display("The publish date must be in the future.")]
+    PublishDateInThePast,
+    // End scheduled release errors
+
+    // Begin bulk action errors
+    #[display("Bulk actions need between 1 and 1000 info-hashes, and the category or tag of the action.")]
+    InvalidBulkAction,
+    // End bulk action errors
+
+    // Begin antispam errors
+    #[display("The upload looks like spam.")]
+    SpamDetected,
+
+    #[display("The banned pattern must be a valid regular expression of up to 255 characters.")]
+    InvalidBannedPattern,
+
+    #[display("The banned pattern already exists.")]
+    BannedPatternAlreadyExists,
+
+    #[display("Banned pattern not found.")]
+    BannedPatternNotFound,
+    // End antispam errors
+
+    // Begin ratio errors
+    #[display("Your upload ratio is below the minimum required to download torrents. Seed to raise it.")]
+    RatioTooLow,
+    // End ratio errors
+    #[display("The settings could not be reloaded. The running settings were not changed.")]
+    SettingsReloadFailed,
+    #[display("Trending tags are not enabled.")]
+    TrendingTagsDisabled,
+    #[display("Announce keys are only used when the tracker is private.")]
+    AnnounceKeysNotUsed,
 }
 
 impl From<sqlx::Error> for ServiceError {
@@ -270,6 +691,13 @@ impl From<TrackerAPIError> for ServiceError {
     }
 }
 
+impl From<search::Error> for ServiceError {
+    fn from(e: search::Error) -> Self {
+        eprintln!("{e}");
+        ServiceError::SearchIndexError
+    }
+}
+
 #[must_use]
 pub fn http_status_code_for_service_error(error: &ServiceError) -> StatusCode {
     #[allow(clippy::match_same_arms)]
@@ -291,10 +719,12 @@ pub fn http_status_code_for_service_error(error: &ServiceError) -> StatusCode {
         ServiceError::UsernameTaken => StatusCode::BAD_REQUEST,
         ServiceError::UsernameInvalid => StatusCode::BAD_REQUEST,
         ServiceError::EmailTaken => StatusCode::BAD_REQUEST,
+        ServiceError::EmailAlreadyVerified => StatusCode::BAD_REQUEST,
         ServiceError::EmailNotVerified => StatusCode::FORBIDDEN,
         ServiceError::TokenNotFound => StatusCode::UNAUTHORIZED,
         ServiceError::TokenExpired => StatusCode::UNAUTHORIZED,
         ServiceError::TokenInvalid => StatusCode::UNAUTHORIZED,
+        ServiceError::SessionRevoked => StatusCode::UNAUTHORIZED,
         ServiceError::TorrentNotFound => StatusCode::NOT_FOUND,
         ServiceError::InvalidTorrentFile => StatusCode::BAD_REQUEST,
         ServiceError::InvalidTorrentPiecesLength => StatusCode::BAD_REQUEST,
@@ -317,15 +747,130 @@ pub fn http_status_code_for_service_error(error: &ServiceError) -> StatusCode {
         ServiceError::InternalServerError => StatusCode::INTERNAL_SERVER_ERROR,
         ServiceError::EmailMissing => StatusCode::NOT_FOUND,
         ServiceError::FailedToSendVerificationEmail => StatusCode::INTERNAL_SERVER_ERROR,
+        ServiceError::FailedToSendEmail => StatusCode::INTERNAL_SERVER_ERROR,
         ServiceError::WhitelistingError => StatusCode::INTERNAL_SERVER_ERROR,
         ServiceError::DatabaseError => StatusCode::INTERNAL_SERVER_ERROR,
         ServiceError::CategoryNotFound => StatusCode::NOT_FOUND,
         ServiceError::TagNotFound => StatusCode::NOT_FOUND,
+        ServiceError::TagSynonymNotFound => StatusCode::NOT_FOUND,
+        ServiceError::CannotMergeTagIntoItself => StatusCode::BAD_REQUEST,
         ServiceError::TrackerResponseError => StatusCode::INTERNAL_SERVER_ERROR,
         ServiceError::TrackerUnknownResponse => StatusCode::INTERNAL_SERVER_ERROR,
         ServiceError::TorrentNotFoundInTracker => StatusCode::NOT_FOUND,
         ServiceError::InvalidTrackerToken => StatusCode::INTERNAL_SERVER_ERROR,
         ServiceError::LoggedInUserNotFound => StatusCode::UNAUTHORIZED,
+        ServiceError::SearchIndexError => StatusCode::INTERNAL_SERVER_ERROR,
+        ServiceError::SignedUploadsDisabled => StatusCode::FORBIDDEN,
+        ServiceError::InvalidUploadSignature => StatusCode::UNAUTHORIZED,
+        ServiceError::ExpiredUploadSignature => StatusCode::UNAUTHORIZED,
+        ServiceError::UploadSignatureAlreadyUsed => StatusCode::UNAUTHORIZED,
+        ServiceError::CommentNotFound => StatusCode::NOT_FOUND,
+        ServiceError::InvalidCommentLength => StatusCode::BAD_REQUEST,
+        ServiceError::ReportNotFound => StatusCode::NOT_FOUND,
+        ServiceError::InvalidReportDetailsLength => StatusCode::BAD_REQUEST,
+        ServiceError::ReportAlreadyResolved => StatusCode::CONFLICT,
+        ServiceError::BookmarkNotFound => StatusCode::NOT_FOUND,
+        ServiceError::UnsupportedBookmarksFile => StatusCode::BAD_REQUEST,
+        ServiceError::SubscriptionNotFound => StatusCode::NOT_FOUND,
+        ServiceError::CollectionNotFound => StatusCode::NOT_FOUND,
+        ServiceError::CollectionItemNotFound => StatusCode::NOT_FOUND,
+        ServiceError::InvalidCollectionName => StatusCode::BAD_REQUEST,
+        ServiceError::InvalidSmartCollectionFilter => StatusCode::BAD_REQUEST,
+        ServiceError::SmartCollectionNotEditable => StatusCode::BAD_REQUEST,
+        ServiceError::InvalidSubscription => StatusCode::BAD_REQUEST,
+        ServiceError::SubscriptionAlreadyExists => StatusCode::BAD_REQUEST,
+        ServiceError::SavedSearchNotFound => StatusCode::NOT_FOUND,
+        ServiceError::InvalidSavedSearch => StatusCode::BAD_REQUEST,
+        ServiceError::TooManySavedSearches => StatusCode::BAD_REQUEST,
+        ServiceError::TorrentPieceLengthNotAllowed => StatusCode::BAD_REQUEST,
+        ServiceError::TorrentPaddingFilesNotAllowed => StatusCode::BAD_REQUEST,
+        ServiceError::SingleFileTorrentNotAllowedInCategory => StatusCode::BAD_REQUEST,
+        ServiceError::TorrentPathTooDeep => StatusCode::BAD_REQUEST,
+        ServiceError::TorrentPathTooLong => StatusCode::BAD_REQUEST,
+        ServiceError::TorrentFileExtensionNotAllowed => StatusCode::BAD_REQUEST,
+        ServiceError::TorrentTooSmall => StatusCode::BAD_REQUEST,
+        ServiceError::TorrentTooLarge => StatusCode::BAD_REQUEST,
+        ServiceError::TorrentPieceLengthTooSmall => StatusCode::BAD_REQUEST,
+        ServiceError::TorrentPieceLengthTooLarge => StatusCode::BAD_REQUEST,
+        ServiceError::InvalidListingFilter => StatusCode::BAD_REQUEST,
+        ServiceError::InvalidTrackerUrl => StatusCode::BAD_REQUEST,
+        ServiceError::MissingTrackers => StatusCode::BAD_REQUEST,
+        ServiceError::MagnetUploadsDisabled => StatusCode::FORBIDDEN,
+        ServiceError::InvalidMagnetLink => StatusCode::BAD_REQUEST,
+        ServiceError::MagnetUploadNotFound => StatusCode::NOT_FOUND,
+        ServiceError::MagnetUploadAlreadyResolving => StatusCode::CONFLICT,
+        ServiceError::TooManyRequests => StatusCode::TOO_MANY_REQUESTS,
+        ServiceError::InvalidImportDirectory => StatusCode::BAD_REQUEST,
+        ServiceError::InvalidImportManifest => StatusCode::BAD_REQUEST,
+        ServiceError::InvalidImportMapping => StatusCode::BAD_REQUEST,
+        ServiceError::ImportSessionNotFound => StatusCode::NOT_FOUND,
+        ServiceError::InvalidImportSession => StatusCode::BAD_REQUEST,
+        ServiceError::InvalidImportBatch => StatusCode::BAD_REQUEST,
+        ServiceError::ImportSessionCompleted => StatusCode::CONFLICT,
+        ServiceError::InvalidPointsAdjustment => StatusCode::BAD_REQUEST,
+        ServiceError::MissingBulkMailFields => StatusCode::BAD_REQUEST,
+        ServiceError::InvalidBulkMailTemplate => StatusCode::BAD_REQUEST,
+        ServiceError::AccountDisabled => StatusCode::FORBIDDEN,
+        ServiceError::ImageStorageDisabled => StatusCode::FORBIDDEN,
+        ServiceError::UnsupportedImageType => StatusCode::BAD_REQUEST,
+        ServiceError::InvalidImageSize => StatusCode::BAD_REQUEST,
+        ServiceError::InvalidImageUpload => StatusCode::BAD_REQUEST,
+        ServiceError::ImageUploadNotFound => StatusCode::NOT_FOUND,
+        ServiceError::ImageAlreadyRegistered => StatusCode::CONFLICT,
+        ServiceError::DailyUploadLimitReached => StatusCode::TOO_MANY_REQUESTS,
+        ServiceError::UploadSizeQuotaExceeded => StatusCode::FORBIDDEN,
+        ServiceError::BioTooLong => StatusCode::BAD_REQUEST,
+        ServiceError::WidgetsDisabled => StatusCode::FORBIDDEN,
+        ServiceError::InvalidJsonpCallback => StatusCode::BAD_REQUEST,
+        ServiceError::MirrorSyncDisabled => StatusCode::FORBIDDEN,
+        ServiceError::InvalidAuditLogDateRange => StatusCode::BAD_REQUEST,
+        ServiceError::TorrentAlreadyCoSigned => StatusCode::CONFLICT,
+        ServiceError::CannotCoSignOwnTorrent => StatusCode::FORBIDDEN,
+        ServiceError::TorrentPreviewsDisabled => StatusCode::FORBIDDEN,
+        ServiceError::PreviewFileNotFound => StatusCode::BAD_REQUEST,
+        ServiceError::InvalidPreviewFileSize => StatusCode::BAD_REQUEST,
+        ServiceError::InvalidPreviewPieces => StatusCode::BAD_REQUEST,
+        ServiceError::UnsupportedPreviewType => StatusCode::BAD_REQUEST,
+        ServiceError::TooManyPreviews => StatusCode::CONFLICT,
+        ServiceError::TorrentPreviewAlreadyExists => StatusCode::CONFLICT,
+        ServiceError::TorrentPreviewNotFound => StatusCode::NOT_FOUND,
+        ServiceError::DescriptionImagesDisabled => StatusCode::FORBIDDEN,
+        ServiceError::TooManyDescriptionImages => StatusCode::CONFLICT,
+        ServiceError::DescriptionImageNotFound => StatusCode::NOT_FOUND,
+        ServiceError::TorrentAttachmentsDisabled => StatusCode::FORBIDDEN,
+        ServiceError::InvalidAttachmentSize => StatusCode::BAD_REQUEST,
+        ServiceError::UnsupportedAttachmentType => StatusCode::BAD_REQUEST,
+        ServiceError::TooManyScreenshots => StatusCode::CONFLICT,
+        ServiceError::TorrentAttachmentNotFound => StatusCode::NOT_FOUND,
+        ServiceError::InvalidWebSeedUrl => StatusCode::BAD_REQUEST,
+        ServiceError::TooManyWebSeeds => StatusCode::CONFLICT,
+        ServiceError::TorrentWebSeedAlreadyExists => StatusCode::CONFLICT,
+        ServiceError::TorrentWebSeedNotFound => StatusCode::NOT_FOUND,
+        ServiceError::MetadataEnrichmentDisabled => StatusCode::FORBIDDEN,
+        ServiceError::InvalidExternalId => StatusCode::BAD_REQUEST,
+        ServiceError::MetadataProviderNotConfigured => StatusCode::BAD_REQUEST,
+        ServiceError::MetadataProviderFailed => StatusCode::SERVICE_UNAVAILABLE,
+        ServiceError::TorrentMetadataNotFound => StatusCode::NOT_FOUND,
+        ServiceError::InvalidCategoryField => StatusCode::BAD_REQUEST,
+        ServiceError::CategoryFieldAlreadyExists => StatusCode::CONFLICT,
+        ServiceError::CategoryFieldNotFound => StatusCode::NOT_FOUND,
+        ServiceError::InvalidCategoryFieldValue => StatusCode::BAD_REQUEST,
+        ServiceError::MissingCategoryFieldValue => StatusCode::BAD_REQUEST,
+        ServiceError::InvalidTorrentFileGroup => StatusCode::BAD_REQUEST,
+        ServiceError::TooManyTorrentFileGroups => StatusCode::BAD_REQUEST,
+        ServiceError::TorrentFileGroupNotFound => StatusCode::NOT_FOUND,
+        ServiceError::DuplicateTorrentContent => StatusCode::CONFLICT,
+        ServiceError::PublishDateInThePast => StatusCode::BAD_REQUEST,
+        ServiceError::InvalidBulkAction => StatusCode::BAD_REQUEST,
+        ServiceError::SpamDetected => StatusCode::BAD_REQUEST,
+        ServiceError::InvalidBannedPattern => StatusCode::BAD_REQUEST,
+        ServiceError::BannedPatternAlreadyExists => StatusCode::CONFLICT,
+        ServiceError::BannedPatternNotFound => StatusCode::NOT_FOUND,
+        ServiceError::RatioTooLow => StatusCode::FORBIDDEN,
+        ServiceError::SettingsReloadFailed => StatusCode::INTERNAL_SERVER_ERROR,
+        ServiceError::SessionNotFound => StatusCode::NOT_FOUND,
+        ServiceError::TrendingTagsDisabled => StatusCode::FORBIDDEN,
+        ServiceError::AnnounceKeysNotUsed => StatusCode::BAD_REQUEST,
     }
 }
 
@@ -346,5 +891,33 @@ pub fn map_database_error_to_service_error(error: &database::Error) -> ServiceEr
         database::Error::TorrentTitleAlreadyExists => ServiceError::TorrentTitleAlreadyExists,
         database::Error::UnrecognizedDatabaseDriver => ServiceError::InternalServerError,
         database::Error::TorrentInfoHashNotFound => ServiceError::TorrentNotFound,
+        database::Error::CommentNotFound => ServiceError::CommentNotFound,
+        database::Error::ReportNotFound => ServiceError::ReportNotFound,
+        database::Error::BookmarkNotFound => ServiceError::BookmarkNotFound,
+        database::Error::SubscriptionNotFound => ServiceError::SubscriptionNotFound,
+        database::Error::SavedSearchNotFound => ServiceError::SavedSearchNotFound,
+        database::Error::ImportSessionNotFound => ServiceError::ImportSessionNotFound,
+        database::Error::CollectionNotFound => ServiceError::CollectionNotFound,
+        database::Error::CollectionItemNotFound => ServiceError::CollectionItemNotFound,
+        database::Error::MagnetUploadNotFound => ServiceError::MagnetUploadNotFound,
+        database::Error::ImageAlreadyExists => ServiceError::ImageAlreadyRegistered,
+        database::Error::TorrentPreviewAlreadyExists => ServiceError::TorrentPreviewAlreadyExists,
+        database::Error::TorrentPreviewNotFound => ServiceError::TorrentPreviewNotFound,
+        database::Error::TagSynonymNotFound => ServiceError::TagSynonymNotFound,
+        database::Error::DescriptionImageNotFound => ServiceError::DescriptionImageNotFound,
+        database::Error::TorrentAttachmentNotFound => ServiceError::TorrentAttachmentNotFound,
+        database::Error::TorrentWebSeedAlreadyExists => ServiceError::TorrentWebSeedAlreadyExists,
+        database::Error::TorrentWebSeedNotFound => ServiceError::TorrentWebSeedNotFound,
+        database::Error::TorrentMetadataNotFound => ServiceError::TorrentMetadataNotFound,
+        database::Error::CategoryFieldAlreadyExists => ServiceError::CategoryFieldAlreadyExists,
+        database::Error::CategoryFieldNotFound => ServiceError::CategoryFieldNotFound,
+        database::Error::BannedPatternAlreadyExists => ServiceError::BannedPatternAlreadyExists,
+        database::Error::BannedPatternNotFound => ServiceError::BannedPatternNotFound,
+        database::Error::UserSessionNotFound => ServiceError::SessionNotFound,
+        database::Error::MigrationError
+        | database::Error::MigrationNotFound
+        | database::Error::MigrationNotReversible
+        | database::Error::InvalidBackupRow
+        | database::Error::InvalidReplica => ServiceError::InternalServerError,
     }
 }
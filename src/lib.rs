@@ -280,6 +280,7 @@
 //!
 //! In addition to the production code documentation you can find a lot of
 //! examples in the [tests](https://github.com/torrust/torrust-index/tree/develop/tests/e2e/contexts) directory.
+pub mod announcer;
 pub mod app;
 pub mod bootstrap;
 pub mod cache;
@@ -291,6 +292,7 @@ pub mod errors;
 pub mod mailer;
 pub mod models;
 pub mod services;
+pub mod storage;
 pub mod tracker;
 pub mod ui;
 pub mod upgrades;
@@ -0,0 +1,240 @@
+//! New-torrent digest emails.
+//!
+//! Users subscribe to categories and tags using the
+//! [`subscription`](crate::services::subscription) service. The digest
+//! cronjob collects the torrents uploaded since the previous digest and each
+//! subscriber receives one email with the new torrents matching any of their
+//! subscriptions.
+//!
+//! Only users with a verified email receive digests.
+use std::collections::BTreeMap;
+use std::fmt::Write;
+use std::sync::Arc;
+
+use tracing::{debug, error};
+
+use super::Service;
+use crate::databases::database;
+use crate::models::domain_event::TorrentUploaded;
+use crate::models::subscription::Subscription;
+use crate::models::torrent_tag::TagId;
+use crate::services::subscription::DbSubscriptionRepository;
+use crate::services::torrent::DbTorrentTagRepository;
+use crate::services::user::DbUserProfileRepository;
+
+/// A new torrent with its tags.
+struct NewTorrent<'a> {
+    torrent: &'a TorrentUploaded,
+    tag_ids: Vec<TagId>,
+}
+
+pub struct Digester {
+    mailer: Arc<Service>,
+    subscription_repository: Arc<DbSubscriptionRepository>,
+    torrent_tag_repository: Arc<DbTorrentTagRepository>,
+    user_profile_repository: Arc<DbUserProfileRepository>,
+    max_torrents: usize,
+}
+
+impl Digester {
+    #[must_use]
+    pub fn new(
+        mailer: Arc<Service>,
+        subscription_repository: Arc<DbSubscriptionRepository>,
+        torrent_tag_repository: Arc<DbTorrentTagRepository>,
+        user_profile_repository: Arc<DbUserProfileRepository>,
+        max_torrents: usize,
+    ) -> Self {
+        Self {
+            mailer,
+            subscription_repository,
+            torrent_tag_repository,
+            user_profile_repository,
+            max_torrents,
+        }
+    }
+
+    /// It sends the digest emails for the new torrents.
+    ///
+    /// Errors sending the emails are only logged.
+    ///
+    /// # Errors
+    ///
+    /// It returns an error if the subscriptions can't be loaded.
+    pub async fn send(&self, torrents: &[TorrentUploaded]) -> Result<(), database::Error> {
+        if torrents.is_empty() {
+            return Ok(());
+        }
+
+        let subscriptions = self.subscription_repository.get_all().await?;
+
+        if subscriptions.is_empty() {
+            return Ok(());
+        }
+
+        let mut new_torrents = Vec::with_capacity(torrents.len());
+
+        for torrent in torrents {
+            let tag_ids = match self.torrent_tag_repository.get_tags_for_torrent(&torrent.torrent_id).await {
+                Ok(tags) => tags.into_iter().map(|tag| tag.tag_id).collect(),
+                Err(e) => {
+                    error!(target: "digest", "Failed to get the tags for torrent #{}: {e:?}", torrent.torrent_id);
+                    Vec::new()
+                }
+            };
+
+            new_torrents.push(NewTorrent { torrent, tag_ids });
+        }
+
+        for (username, torrents) in torrents_by_subscriber(&new_torrents, &subscriptions) {
+            let user_profile = match self.user_profile_repository.get_user_profile_from_username(&username).await {
+                Ok(user_profile) => user_profile,
+                Err(e) => {
+                    error!(target: "digest", "Failed to get the profile for user {username}: {e:?}");
+                    continue;
+                }
+            };
+
+            if user_profile.email.is_empty() || !user_profile.email_verified {
+                continue;
+            }
+
+            debug!(target: "digest", "Sending digest with {} torrents to user {username} ...", torrents.len());
+
+            let body = build_body(&username, &torrents, self.max_torrents);
+
+            if let Err(e) = self
+                .mailer
+                .send_plain_mail(&user_profile.email, "Torrust - New torrents", &body)
+                .await
+            {
+                error!(target: "digest", "Failed to send digest to user {username}: {e}");
+            }
+        }
+
+        Ok(())
+    }
+}
+
+/// It returns the new torrents for each subscriber, in upload order.
+fn torrents_by_subscriber<'a>(
+    new_torrents: &[NewTorrent<'a>],
+    subscriptions: &[Subscription],
+) -> BTreeMap<String, Vec<&'a TorrentUploaded>> {
+    let mut digests: BTreeMap<String, Vec<&TorrentUploaded>> = BTreeMap::new();
+
+    for new_torrent in new_torrents {
+        for subscription in subscriptions {
+            let matches = subscription.category.as_ref() == Some(&new_torrent.torrent.category)
+                || subscription
+                    .tag_id
+                    .is_some_and(|tag_id| new_torrent.tag_ids.contains(&tag_id));
+
+            if !matches {
+                continue;
+            }
+
+            let torrents = digests.entry(subscription.username.clone()).or_default();
+
+            if !torrents
+                .iter()
+                .any(|torrent| torrent.torrent_id == new_torrent.torrent.torrent_id)
+            {
+                torrents.push(new_torrent.torrent);
+            }
+        }
+    }
+
+    digests
+}
+
+fn build_body(username: &str, torrents: &[&TorrentUploaded], max_torrents: usize) -> String {
+    let mut body = format!("Hi {username},\n\nThese are the new torrents in the categories and tags you are subscribed to:\n\n");
+
+    for torrent in torrents.iter().take(max_torrents) {
+        let _ = writeln!(
+            body,
+            "- {} [{}] uploaded by {} (info-hash: {})",
+            torrent.title, torrent.category, torrent.uploader, torrent.info_hash
+        );
+    }
+
+    if torrents.len() > max_torrents {
+        let _ = writeln!(body, "- ... and {} more", torrents.len() - max_torrents);
+    }
+
+    body
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{build_body, torrents_by_subscriber, NewTorrent};
+    use crate::models::domain_event::TorrentUploaded;
+    use crate::models::subscription::Subscription;
+
+    fn torrent(torrent_id: i64, category: &str) -> TorrentUploaded {
+        TorrentUploaded {
+            torrent_id,
+            info_hash: format!("{torrent_id:040}"),
+            title: format!("Torrent {torrent_id}"),
+            category: category.to_string(),
+            uploader: "uploader".to_string(),
+        }
+    }
+
+    fn subscription(username: &str, category: Option<&str>, tag_id: Option<i64>) -> Subscription {
+        Subscription {
+            subscription_id: 1,
+            user_id: 1,
+            username: username.to_string(),
+            category_id: category.map(|_| 1),
+            category: category.map(ToString::to_string),
+            tag_id,
+            tag: tag_id.map(|tag_id| format!("tag {tag_id}")),
+            date_created: "2024-10-16 10:00:00".to_string(),
+        }
+    }
+
+    #[test]
+    fn it_should_group_the_new_torrents_by_subscriber_without_duplicates() {
+        let movie = torrent(1, "movies");
+        let music = torrent(2, "music");
+
+        let new_torrents = vec![
+            NewTorrent {
+                torrent: &movie,
+                tag_ids: vec![7],
+            },
+            NewTorrent {
+                torrent: &music,
+                tag_ids: vec![],
+            },
+        ];
+
+        let subscriptions = vec![
+            subscription("alice", Some("movies"), None),
+            subscription("alice", None, Some(7)),
+            subscription("bob", Some("music"), None),
+            subscription("carol", Some("software"), None),
+        ];
+
+        let digests = torrents_by_subscriber(&new_torrents, &subscriptions);
+
+        assert_eq!(digests.len(), 2);
+        assert_eq!(digests["alice"], vec![&movie]);
+        assert_eq!(digests["bob"], vec![&music]);
+    }
+
+    #[test]
+    fn it_should_limit_the_number_of_torrents_in_the_email() {
+        let torrents = [torrent(1, "movies"), torrent(2, "movies"), torrent(3, "movies")];
+        let torrents: Vec<&TorrentUploaded> = torrents.iter().collect();
+
+        let body = build_body("alice", &torrents, 2);
+
+        assert!(body.contains("Torrent 1"));
+        assert!(body.contains("Torrent 2"));
+        assert!(!body.contains("Torrent 3"));
+        assert!(body.contains("and 1 more"));
+    }
+}
@@ -1,3 +1,5 @@
+pub mod digest;
+
 use std::collections::HashMap;
 use std::sync::Arc;
 
@@ -9,10 +11,12 @@ use lettre::{AsyncSmtpTransport, AsyncTransport, Message, Tokio1Executor};
 use serde::{Deserialize, Serialize};
 use serde_json::value::{to_value, Value};
 use tera::{try_get_value, Context, Tera};
+use tracing::Instrument;
 
 use crate::config::Configuration;
 use crate::errors::ServiceError;
 use crate::utils::clock;
+use crate::utils::trace_context::TraceContext;
 use crate::web::api::server::v1::routes::API_VERSION_URL_PREFIX;
 
 lazy_static! {
@@ -48,6 +52,18 @@ pub fn do_nothing_filter(value: &Value, _: &HashMap<String, Value>) -> tera::Res
     Ok(to_value(s).unwrap())
 }
 
+/// Issuer of the email verification tokens.
+pub const EMAIL_VERIFICATION_ISSUER: &str = "email-verification";
+
+/// Issuer of the tokens to unsubscribe from non-essential emails.
+pub const EMAIL_UNSUBSCRIBE_ISSUER: &str = "email-unsubscribe";
+
+/// Issuer of the tokens to confirm a new email address.
+pub const EMAIL_CHANGE_ISSUER: &str = "email-change";
+
+/// Seconds the links to confirm a new email address are valid.
+const EMAIL_CHANGE_TOKEN_TTL_SECONDS: u64 = 86_400;
+
 pub struct Service {
     cfg: Arc<Configuration>,
     mailer: Arc<Mailer>,
@@ -60,6 +76,16 @@ pub struct VerifyClaims {
     pub exp: u64,
 }
 
+/// Claims of the tokens to confirm a new email address.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ChangeEmailClaims {
+    pub iss: String,
+    pub sub: i64,
+    /// The new email address.
+    pub email: String,
+    pub exp: u64,
+}
+
 impl Service {
     pub async fn new(cfg: Arc<Configuration>) -> Service {
         let mailer = Arc::new(Self::get_mailer(&cfg).await);
@@ -111,7 +137,7 @@ impl Service {
 
         let mail = build_letter(verification_url.as_str(), username, builder)?;
 
-        match self.mailer.send(mail).await {
+        match self.deliver(mail).await {
             Ok(_res) => Ok(()),
             Err(e) => {
                 eprintln!("Failed to send email: {e}");
@@ -120,6 +146,86 @@ impl Service {
         }
     }
 
+    /// Send the email with the link to confirm a new email address. It's
+    /// sent to the new address.
+    ///
+    /// # Errors
+    ///
+    /// This function will return an error if unable to send an email.
+    pub async fn send_email_change_mail(
+        &self,
+        to: &str,
+        username: &str,
+        user_id: i64,
+        base_url: &str,
+    ) -> Result<(), ServiceError> {
+        let confirmation_url = self.get_email_change_url(user_id, to, base_url).await;
+
+        let body = format!(
+            "Hi {username},
+
+Please click the link below to confirm your new email address. The link is valid for 24 hours.
+{confirmation_url}
+
+If you didn't request this change, you can ignore this email. Your email address will not change."
+        );
+
+        self.send_plain_mail(to, "Torrust - Confirm your new email address", &body)
+            .await
+    }
+
+    /// Send a plain text email.
+    ///
+    /// # Errors
+    ///
+    /// This function will return an error if unable to send an email.
+    pub async fn send_plain_mail(&self, to: &str, subject: &str, body: &str) -> Result<(), ServiceError> {
+        let builder = self.get_builder(to).await;
+
+        let mail = builder
+            .subject(subject)
+            .header(lettre::message::header::ContentType::TEXT_PLAIN)
+            .body(body.to_string())
+            .map_err(|e| {
+                tracing::error!("{e}");
+                ServiceError::InternalServerError
+            })?;
+
+        match self.deliver(mail).await {
+            Ok(_res) => Ok(()),
+            Err(e) => {
+                tracing::error!("Failed to send email: {e}");
+                Err(ServiceError::FailedToSendEmail)
+            }
+        }
+    }
+
+    /// It sends the email in a `smtp` span. SMTP can't carry the trace
+    /// context, so the trace ID is only recorded in the span.
+    async fn deliver(
+        &self,
+        mail: Message,
+    ) -> Result<lettre::transport::smtp::response::Response, lettre::transport::smtp::Error> {
+        let trace_id = TraceContext::current()
+            .map(|trace_context| trace_context.trace_id_hex())
+            .unwrap_or_default();
+
+        let span = tracing::info_span!(target: "mailer", "smtp", trace_id = %trace_id);
+
+        async {
+            let result = self.mailer.send(mail).await;
+
+            match &result {
+                Ok(_) => tracing::info!(target: "mailer", "Email sent"),
+                Err(e) => tracing::info!(target: "mailer", error = %e, "Email not sent"),
+            }
+
+            result
+        }
+        .instrument(span)
+        .await
+    }
+
     async fn get_builder(&self, to: &str) -> MessageBuilder {
         let settings = self.cfg.settings.read().await;
 
@@ -130,26 +236,58 @@ impl Service {
     }
 
     async fn get_verification_url(&self, user_id: i64, base_url: &str) -> String {
-        let settings = self.cfg.settings.read().await;
+        let (token, base_url) = self.get_user_token(EMAIL_VERIFICATION_ISSUER, user_id, base_url).await;
 
-        // create verification JWT
-        let key = settings.auth.user_claim_token_pepper.as_bytes();
+        format!("{base_url}/{API_VERSION_URL_PREFIX}/user/email/verify/{token}")
+    }
+
+    /// It returns the URL users open to unsubscribe from non-essential
+    /// emails.
+    pub async fn get_unsubscribe_url(&self, user_id: i64, base_url: &str) -> String {
+        let (token, base_url) = self.get_user_token(EMAIL_UNSUBSCRIBE_ISSUER, user_id, base_url).await;
 
-        // Create non expiring token that is only valid for email-verification
+        format!("{base_url}/{API_VERSION_URL_PREFIX}/user/email/unsubscribe/{token}")
+    }
+
+    async fn get_email_change_url(&self, user_id: i64, email: &str, base_url: &str) -> String {
+        let claims = ChangeEmailClaims {
+            iss: String::from(EMAIL_CHANGE_ISSUER),
+            sub: user_id,
+            email: email.to_string(),
+            exp: clock::now() + EMAIL_CHANGE_TOKEN_TTL_SECONDS,
+        };
+
+        let (token, base_url) = self.sign_claims(&claims, base_url).await;
+
+        format!("{base_url}/{API_VERSION_URL_PREFIX}/user/email/change/{token}")
+    }
+
+    /// It returns a non expiring token that is only valid for the issuer, and
+    /// the base URL for the link.
+    async fn get_user_token(&self, issuer: &str, user_id: i64, base_url: &str) -> (String, String) {
         let claims = VerifyClaims {
-            iss: String::from("email-verification"),
+            iss: String::from(issuer),
             sub: user_id,
             exp: clock::now() + 315_569_260, // 10 years from now
         };
 
-        let token = encode(&Header::default(), &claims, &EncodingKey::from_secret(key)).unwrap();
+        self.sign_claims(&claims, base_url).await
+    }
+
+    /// It returns the token with the claims, and the base URL for the link.
+    async fn sign_claims<T: Serialize>(&self, claims: &T, base_url: &str) -> (String, String) {
+        let settings = self.cfg.settings.read().await;
+
+        let key = settings.auth.user_claim_token_pepper.as_bytes();
+
+        let token = encode(&Header::default(), claims, &EncodingKey::from_secret(key)).unwrap();
 
         let base_url = match &settings.net.base_url {
             Some(url) => url.to_string(),
             None => base_url.to_string(),
         };
 
-        format!("{base_url}/{API_VERSION_URL_PREFIX}/user/email/verify/{token}")
+        (token, base_url)
     }
 }
 
@@ -8,11 +8,21 @@ async fn main() -> Result<(), std::io::Error> {
 
     let api_version = Version::V1;
 
-    let app = app::run(configuration, &api_version).await;
+    let app = match app::run(configuration, &api_version).await {
+        Ok(app) => app,
+        Err(e) => {
+            eprintln!("{e}");
+            std::process::exit(1);
+        }
+    };
 
     assert!(!app.api_server_halt_task.is_closed(), "Halt channel should be open");
 
-    match api_version {
+    let result = match api_version {
         Version::V1 => app.api_server.await.expect("the API server was dropped"),
-    }
+    };
+
+    app.background_jobs.shutdown().await;
+
+    result
 }
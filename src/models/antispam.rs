@@ -0,0 +1,40 @@
+use derive_more::Display;
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+
+#[allow(clippy::module_name_repetitions)]
+pub type PatternId = i64;
+
+/// What to do with an upload matching a banned pattern.
+#[derive(Debug, Display, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "lowercase")]
+pub enum PatternAction {
+    /// The upload is held for moderation.
+    #[display("hold")]
+    Hold,
+    /// The upload is rejected.
+    #[display("reject")]
+    Reject,
+}
+
+/// A regular expression matched against the title and the description of
+/// the uploads, ignoring case.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, ToSchema, sqlx::FromRow)]
+pub struct BannedPattern {
+    pub pattern_id: PatternId,
+    pub pattern: String,
+    /// `hold` or `reject`.
+    pub action: String,
+    /// The username of the administrator who added the pattern. `None` if
+    /// the user was deleted.
+    pub created_by: Option<String>,
+    pub date_created: String,
+}
+
+impl BannedPattern {
+    /// Whether the uploads matching the pattern are rejected or not.
+    #[must_use]
+    pub fn rejects(&self) -> bool {
+        self.action == PatternAction::Reject.to_string()
+    }
+}
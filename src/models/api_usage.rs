@@ -0,0 +1,76 @@
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+
+use super::user::UserId;
+
+/// API usage of one of the user's credentials in a day. Refer to the
+/// [`api_usage`](crate::services::api_usage) service.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, sqlx::FromRow)]
+pub struct ApiUsageRecord {
+    pub user_id: UserId,
+    /// The credential ID. It never contains the credential itself.
+    pub credential: String,
+    /// The day in `YYYY-MM-DD` format.
+    pub date: String,
+    pub requests: i64,
+    /// Requests rejected by the rate limiter.
+    pub rate_limit_hits: i64,
+    pub last_ip: Option<String>,
+    pub last_used: String,
+}
+
+/// The number of requests made by the user in a day, with all the
+/// credentials.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+pub struct DailyApiUsage {
+    pub date: String,
+    pub requests: i64,
+    pub rate_limit_hits: i64,
+}
+
+/// The usage of one of the user's credentials.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+pub struct CredentialUsage {
+    /// The credential ID, for example `token-3f2a9c0b41d7`.
+    pub credential: String,
+    /// Whether it's the credential used to get the statistics.
+    pub current: bool,
+    pub requests: i64,
+    pub rate_limit_hits: i64,
+    pub first_used_date: String,
+    pub last_used: String,
+    /// The IP address of the last request made with the credential.
+    pub last_ip: Option<String>,
+}
+
+/// Summary of the user's API usage.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+pub struct ApiUsageSummary {
+    /// Number of days included in the summary, including today.
+    pub days: u32,
+    /// Requests per day, from the oldest day to today. Days without requests
+    /// are not included.
+    pub daily: Vec<DailyApiUsage>,
+    /// Credentials used in the period, from the most recently used.
+    pub credentials: Vec<CredentialUsage>,
+}
+
+/// The state of a rate limit bucket, used to debug the rate limits. Refer to
+/// the [`rate_limit`](crate::web::api::server::rate_limit) middleware.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+pub struct RateLimitCounter {
+    /// The group of endpoints, for example `login`.
+    pub group: String,
+    /// The logged-in user the bucket belongs to.
+    pub user_id: Option<UserId>,
+    /// The IP address of the guest the bucket belongs to.
+    pub ip: Option<String>,
+    /// Max number of requests accepted in a burst.
+    pub limit: u32,
+    /// Requests the client can make now.
+    pub remaining: u32,
+    /// Seconds until the bucket is full again.
+    pub reset_after_seconds: u64,
+    /// Whether the client is over the soft threshold.
+    pub soft_limited: bool,
+}
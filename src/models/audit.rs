@@ -0,0 +1,116 @@
+use derive_more::Display;
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+
+use super::user::UserId;
+
+#[allow(clippy::module_name_repetitions)]
+pub type AuditEntryId = i64;
+
+/// An administrative or moderation action recorded in the audit log.
+#[derive(Debug, Display, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum AuditAction {
+    /// A torrent is moved to the trash bin.
+    #[display("torrent_delete")]
+    TorrentDelete,
+    /// A torrent is restored from the trash bin.
+    #[display("torrent_restore")]
+    TorrentRestore,
+    /// A torrent is removed from the trash bin for good.
+    #[display("torrent_purge")]
+    TorrentPurge,
+    #[display("torrent_approve")]
+    TorrentApprove,
+    #[display("torrent_reject")]
+    TorrentReject,
+    /// A staff member approves a torrent in a category that requires
+    /// co-signing. The torrent is approved with the second approval.
+    #[display("torrent_co_sign")]
+    TorrentCoSign,
+    /// A torrent rule archives a torrent.
+    #[display("torrent_archive")]
+    TorrentArchive,
+    /// A torrent rule delists a torrent.
+    #[display("torrent_delist")]
+    TorrentDelist,
+    /// A torrent is moved to another category by a bulk action.
+    #[display("torrent_recategorize")]
+    TorrentRecategorize,
+    /// A tag is added to or removed from a torrent by a bulk action.
+    #[display("torrent_retag")]
+    TorrentRetag,
+    #[display("antispam_pattern_add")]
+    AntispamPatternAdd,
+    #[display("antispam_pattern_delete")]
+    AntispamPatternDelete,
+    #[display("user_ban")]
+    UserBan,
+    /// The trusted-uploader status of a user is set or cleared by an
+    /// administrator.
+    #[display("user_trust_change")]
+    UserTrustChange,
+    #[display("category_add")]
+    CategoryAdd,
+    #[display("category_delete")]
+    CategoryDelete,
+    #[display("category_moderator_add")]
+    CategoryModeratorAdd,
+    #[display("category_moderator_remove")]
+    CategoryModeratorRemove,
+    #[display("category_policy_change")]
+    CategoryPolicyChange,
+    #[display("category_field_add")]
+    CategoryFieldAdd,
+    #[display("category_field_delete")]
+    CategoryFieldDelete,
+    #[display("tag_add")]
+    TagAdd,
+    #[display("tag_delete")]
+    TagDelete,
+    /// A tag is merged into another tag and its name kept as a synonym.
+    #[display("tag_merge")]
+    TagMerge,
+    #[display("tag_synonym_add")]
+    TagSynonymAdd,
+    #[display("tag_synonym_delete")]
+    TagSynonymDelete,
+    /// A moderator hides, shows or deletes a comment.
+    #[display("comment_moderate")]
+    CommentModerate,
+    #[display("report_resolve")]
+    ReportResolve,
+    /// The settings are reloaded from the configuration file, by an
+    /// administrator or with the `SIGHUP` signal.
+    #[display("settings_update")]
+    SettingsUpdate,
+}
+
+/// An entry in the audit log.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, ToSchema, sqlx::FromRow)]
+pub struct AuditEntry {
+    pub audit_id: AuditEntryId,
+    /// The user who took the action. `None` if the user was deleted or the
+    /// action was not taken by a user.
+    pub actor_id: Option<UserId>,
+    /// The username of the actor.
+    pub actor: Option<String>,
+    pub action: String,
+    /// The object of the action, for example, the info-hash of a torrent or
+    /// the username of a user.
+    pub target: String,
+    pub details: Option<String>,
+    pub date_created: String,
+}
+
+/// Filters for the audit log.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct AuditLogFilter {
+    /// The username of the actor.
+    pub actor: Option<String>,
+    pub action: Option<AuditAction>,
+    /// Only entries created at or after this date and time.
+    pub since: Option<String>,
+    /// Only entries created before this date and time.
+    pub until: Option<String>,
+}
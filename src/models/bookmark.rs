@@ -0,0 +1,15 @@
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+
+/// A bookmark in the portable format used to export and import them. The
+/// torrent is identified by its info-hash, so bookmarks can be imported in
+/// other index instances.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, sqlx::FromRow, ToSchema)]
+pub struct Bookmark {
+    pub info_hash: String,
+    /// The torrent title. It's only informative: it's ignored when importing.
+    #[serde(default)]
+    pub title: String,
+    /// The date the torrent was bookmarked, in `%Y-%m-%d %H:%M:%S` format.
+    pub date_created: Option<String>,
+}
@@ -1,8 +1,10 @@
 use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
 
 use crate::databases::database::Category as DatabaseCategory;
 
-#[derive(Debug, Serialize, Deserialize, sqlx::FromRow)]
+#[derive(Debug, Serialize, Deserialize, sqlx::FromRow, ToSchema)]
+#[schema(as = TorrentCategory)]
 pub struct Category {
     pub id: i64,
     // Deprecated. Use `id`.
@@ -14,6 +16,15 @@ pub struct Category {
 #[allow(clippy::module_name_repetitions)]
 pub type CategoryId = i64;
 
+/// Moderation policy of a category.
+#[allow(clippy::module_name_repetitions)]
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+pub struct CategoryPolicy {
+    /// Whether new torrents must be approved by two different staff members
+    /// (four-eyes principle) before they are listed.
+    pub co_signing: bool,
+}
+
 impl From<DatabaseCategory> for Category {
     fn from(db_category: DatabaseCategory) -> Self {
         Category {
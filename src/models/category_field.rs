@@ -0,0 +1,76 @@
+use std::str::FromStr;
+
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+
+use super::category::CategoryId;
+
+#[allow(clippy::module_name_repetitions)]
+pub type CategoryFieldId = i64;
+
+/// The type of the values of a category field.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum FieldType {
+    /// Free text, up to 255 characters.
+    Text,
+    /// A whole number.
+    Integer,
+    /// `true` or `false`.
+    Boolean,
+    /// One of the `options` of the field.
+    Choice,
+}
+
+impl FieldType {
+    #[must_use]
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            FieldType::Text => "text",
+            FieldType::Integer => "integer",
+            FieldType::Boolean => "boolean",
+            FieldType::Choice => "choice",
+        }
+    }
+}
+
+impl FromStr for FieldType {
+    type Err = ();
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "text" => Ok(FieldType::Text),
+            "integer" => Ok(FieldType::Integer),
+            "boolean" => Ok(FieldType::Boolean),
+            "choice" => Ok(FieldType::Choice),
+            _ => Err(()),
+        }
+    }
+}
+
+/// A structured field of the torrents in a category, like the `resolution`
+/// of the videos or the `bitrate` of the music.
+#[allow(clippy::module_name_repetitions)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+pub struct CategoryField {
+    pub field_id: CategoryFieldId,
+    pub category_id: CategoryId,
+    /// The field key in uploads and listing filters, like `resolution`.
+    pub name: String,
+    /// The name shown to users, like `Resolution`.
+    pub label: String,
+    pub field_type: FieldType,
+    /// Whether uploads to the category must fill the field.
+    pub required: bool,
+    /// The allowed values of `choice` fields.
+    pub options: Vec<String>,
+}
+
+/// The value of a category field for a torrent.
+#[allow(clippy::module_name_repetitions)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, sqlx::FromRow, ToSchema)]
+pub struct TorrentFieldValue {
+    pub name: String,
+    pub label: String,
+    pub value: String,
+}
@@ -0,0 +1,72 @@
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+
+use super::user::UserId;
+
+#[allow(clippy::module_name_repetitions)]
+pub type CollectionId = i64;
+
+/// A named collection of torrents created by a user.
+///
+/// The aggregate attributes only include the approved torrents.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, sqlx::FromRow, ToSchema)]
+pub struct Collection {
+    pub collection_id: CollectionId,
+    pub user_id: UserId,
+    /// The username of the owner.
+    pub owner: String,
+    pub name: String,
+    pub description: Option<String>,
+    pub date_created: String,
+    pub torrent_count: i64,
+    /// The sum of the torrent sizes in bytes.
+    pub total_size: i64,
+    pub seeders: i64,
+    pub leechers: i64,
+    /// Whether the torrents are selected automatically by a
+    /// [`SmartFilter`].
+    pub smart: bool,
+}
+
+/// The filter of a smart collection. The collection contains the approved
+/// torrents matching all the criteria.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+pub struct SmartFilter {
+    /// Only torrents in this category.
+    pub category: Option<String>,
+    /// Only torrents with all these tags.
+    #[serde(default)]
+    pub tags: Vec<String>,
+    /// Only torrents uploaded on or after this day, in `YYYY-MM-DD` format.
+    pub uploaded_after: Option<String>,
+    /// Only torrents uploaded before this day, in `YYYY-MM-DD` format.
+    pub uploaded_before: Option<String>,
+    /// Only torrents uploaded in the last days.
+    pub max_age_days: Option<u32>,
+}
+
+/// A [`SmartFilter`] as it's stored in the database.
+#[derive(Debug, Clone, PartialEq, Eq, sqlx::FromRow)]
+pub struct SmartFilterRow {
+    pub category: Option<String>,
+    /// Comma-separated tag names.
+    pub tags: Option<String>,
+    pub uploaded_after: Option<String>,
+    pub uploaded_before: Option<String>,
+    pub max_age_days: Option<i64>,
+}
+
+impl From<SmartFilterRow> for SmartFilter {
+    fn from(row: SmartFilterRow) -> Self {
+        Self {
+            category: row.category,
+            tags: row
+                .tags
+                .map(|tags| tags.split(',').map(ToString::to_string).collect())
+                .unwrap_or_default(),
+            uploaded_after: row.uploaded_after,
+            uploaded_before: row.uploaded_before,
+            max_age_days: row.max_age_days.and_then(|days| u32::try_from(days).ok()),
+        }
+    }
+}
@@ -0,0 +1,22 @@
+use serde::{Deserialize, Serialize};
+
+use super::torrent::TorrentId;
+use super::user::UserId;
+
+#[allow(clippy::module_name_repetitions)]
+pub type CommentId = i64;
+
+/// A user comment on a torrent.
+#[derive(Debug, PartialEq, Eq, Serialize, Deserialize, sqlx::FromRow)]
+pub struct Comment {
+    pub comment_id: CommentId,
+    pub torrent_id: TorrentId,
+    pub author_id: UserId,
+    /// The author's username.
+    pub author: String,
+    pub text: String,
+    /// Hidden comments are only listed for moderators.
+    pub hidden: bool,
+    pub date_created: String,
+    pub date_updated: Option<String>,
+}
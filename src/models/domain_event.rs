@@ -0,0 +1,74 @@
+//! Domain events stored in the outbox.
+//!
+//! Events are appended to the `torrust_domain_events` table by the services
+//! and processed asynchronously by consumers (for example, the announcer).
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+
+use super::torrent::TorrentId;
+use super::user::UserId;
+
+pub type EventId = i64;
+
+/// The type of the [`SeedingSummary`] events.
+pub const SEEDING_SUMMARY_EVENT_TYPE: &str = "seeding_summary";
+
+/// A domain event as it is stored in the database.
+#[allow(clippy::module_name_repetitions)]
+#[derive(Debug, PartialEq, Eq, Serialize, Deserialize, sqlx::FromRow)]
+pub struct DbDomainEvent {
+    pub event_id: EventId,
+    pub event_type: String,
+    pub payload: String,
+    pub date_created: String,
+}
+
+#[allow(clippy::module_name_repetitions)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub enum DomainEvent {
+    TorrentUploaded(TorrentUploaded),
+    SeedingSummary(SeedingSummary),
+}
+
+impl DomainEvent {
+    #[must_use]
+    pub fn event_type(&self) -> &'static str {
+        match self {
+            DomainEvent::TorrentUploaded(_) => "torrent_uploaded",
+            DomainEvent::SeedingSummary(_) => SEEDING_SUMMARY_EVENT_TYPE,
+        }
+    }
+}
+
+/// A new torrent has been uploaded to the index.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TorrentUploaded {
+    pub torrent_id: TorrentId,
+    pub info_hash: String,
+    pub title: String,
+    pub category: String,
+    pub uploader: String,
+}
+
+/// Periodic seeding summary of the torrents uploaded by a user, built from
+/// the tracker stats. It's a snapshot of the swarms when the summary was
+/// published.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+pub struct SeedingSummary {
+    pub user_id: UserId,
+    pub username: String,
+    /// When the summary was published.
+    pub date: String,
+    /// Seconds between summaries. Consumers can use it to prorate rewards.
+    pub period_seconds: u64,
+    /// Approved torrents uploaded by the user.
+    pub torrents: i64,
+    /// Torrents with at least one seeder.
+    pub seeded_torrents: i64,
+    /// Seeders in all the torrents.
+    pub seeders: i64,
+    /// Leechers in all the torrents.
+    pub leechers: i64,
+    /// Total size in bytes of the torrents with at least one seeder.
+    pub seeded_bytes: i64,
+}
@@ -0,0 +1,45 @@
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+
+use super::user::UserId;
+
+#[allow(clippy::module_name_repetitions)]
+pub type EmailId = i64;
+
+/// An email waiting in the outbound email queue.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, sqlx::FromRow)]
+pub struct QueuedEmail {
+    pub email_id: EmailId,
+    pub recipient: String,
+    pub subject: String,
+    pub body: String,
+    /// Number of failed delivery attempts.
+    pub attempts: i64,
+}
+
+/// The users who receive a bulk email.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum Segment {
+    /// All users.
+    All,
+    /// Users who logged in during the last `days` days.
+    Active { days: u32 },
+    /// Users with a role.
+    Role { role: Role },
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum Role {
+    Administrator,
+    User,
+}
+
+/// A user who receives a bulk email.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, sqlx::FromRow)]
+pub struct Recipient {
+    pub user_id: UserId,
+    pub username: String,
+    pub email: String,
+}
@@ -0,0 +1,50 @@
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+
+use super::torrent::TorrentId;
+
+#[allow(clippy::module_name_repetitions)]
+pub type ImageId = i64;
+
+/// What an uploaded image is used for.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum ImageKind {
+    /// The user's profile picture.
+    Avatar,
+    /// A screenshot of the torrent contents, linked from the description.
+    Screenshot,
+}
+
+impl ImageKind {
+    #[must_use]
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            ImageKind::Avatar => "avatar",
+            ImageKind::Screenshot => "screenshot",
+        }
+    }
+}
+
+/// An image uploaded to the external storage and registered in the index.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct UploadedImage {
+    pub image_id: ImageId,
+    pub kind: ImageKind,
+    /// Public URL of the image.
+    pub url: String,
+    pub content_type: String,
+    pub size: u64,
+}
+
+/// An image uploaded to be embedded in a torrent description. It's stored
+/// with the image cache backend.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, sqlx::FromRow, ToSchema)]
+pub struct DescriptionImage {
+    pub image_id: ImageId,
+    pub torrent_id: TorrentId,
+    /// The name of the uploaded file.
+    pub file_name: String,
+    pub content_type: String,
+    pub size: i64,
+}
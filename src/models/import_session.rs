@@ -0,0 +1,86 @@
+use derive_more::Display;
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+
+use super::user::UserId;
+
+#[allow(clippy::module_name_repetitions)]
+pub type ImportSessionId = i64;
+
+/// What to do with the imported torrents that are already in the index.
+#[derive(Debug, Display, Default, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum ConflictPolicy {
+    /// The torrent is skipped and reported as a duplicate.
+    #[default]
+    #[display("skip")]
+    Skip,
+    /// The title, description, category and tags of the torrent in the index
+    /// are replaced with the imported ones.
+    #[display("update")]
+    Update,
+}
+
+/// Status of an import session.
+#[derive(Debug, Display, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum ImportSessionStatus {
+    /// The session accepts new batches.
+    #[display("open")]
+    Open,
+    /// The peer finished the import. New batches are rejected.
+    #[display("completed")]
+    Completed,
+}
+
+/// An import session used by a remote peer to push torrents in batches.
+#[allow(clippy::module_name_repetitions)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, sqlx::FromRow, ToSchema)]
+pub struct ImportSession {
+    pub import_id: ImportSessionId,
+    pub user_id: UserId,
+    /// The user who created the session.
+    pub username: String,
+    /// Name of the source site or tool.
+    pub source: String,
+    /// One of `skip` or `update`.
+    pub conflict_policy: String,
+    /// Categories and tags mapping encoded as JSON.
+    #[serde(skip)]
+    pub mapping: Option<String>,
+    /// One of `open` or `completed`.
+    pub status: String,
+    pub date_created: String,
+    pub date_completed: Option<String>,
+    /// Number of processed batches.
+    pub batches: i64,
+    pub imported: i64,
+    pub updated: i64,
+    pub duplicates: i64,
+    pub failures: i64,
+    /// IDs of the processed batches, in processing order. Peers resume an
+    /// interrupted import by pushing the batches not included here.
+    #[sqlx(skip)]
+    pub batch_ids: Vec<String>,
+}
+
+impl ImportSession {
+    #[must_use]
+    pub fn is_open(&self) -> bool {
+        self.status == ImportSessionStatus::Open.to_string()
+    }
+}
+
+/// The result of a processed batch, as stored in the database.
+#[allow(clippy::module_name_repetitions)]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ImportBatch {
+    pub batch_id: String,
+    pub imported: i64,
+    pub updated: i64,
+    pub duplicates: i64,
+    pub failures: i64,
+    /// The batch summary encoded as JSON. It's returned again when the
+    /// batch is pushed again.
+    pub summary: String,
+}
@@ -0,0 +1,62 @@
+use derive_more::Display;
+use serde::{Deserialize, Serialize};
+
+use super::category::CategoryId;
+use super::torrent::TorrentId;
+use super::torrent_tag::TagId;
+use super::user::UserId;
+
+#[allow(clippy::module_name_repetitions)]
+pub type MagnetUploadId = i64;
+
+/// Status of a torrent uploaded as a magnet link.
+#[derive(Debug, Display, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum MagnetUploadStatus {
+    /// The index is fetching the info dictionary from peers.
+    #[display("resolving")]
+    Resolving,
+    /// The info dictionary was fetched and the torrent added to the index.
+    #[display("resolved")]
+    Resolved,
+    /// The info dictionary could not be fetched, or the torrent was rejected.
+    #[display("failed")]
+    Failed,
+}
+
+/// A torrent uploaded as a magnet link.
+///
+/// The torrent is added to the index once its info dictionary is fetched
+/// from peers.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, sqlx::FromRow)]
+pub struct MagnetUpload {
+    pub magnet_upload_id: MagnetUploadId,
+    pub user_id: UserId,
+    pub info_hash: String,
+    pub magnet_uri: String,
+    pub title: String,
+    pub description: String,
+    pub category_id: CategoryId,
+    /// Comma separated tag IDs.
+    #[serde(skip)]
+    pub tag_ids: String,
+    pub status: String,
+    /// The torrent added to the index, once resolved.
+    pub torrent_id: Option<TorrentId>,
+    /// Number of failed attempts to fetch the info dictionary.
+    pub attempts: i64,
+    /// Why the upload failed.
+    pub error: Option<String>,
+    pub date_created: String,
+    pub date_updated: String,
+}
+
+impl MagnetUpload {
+    #[must_use]
+    pub fn tags(&self) -> Vec<TagId> {
+        self.tag_ids
+            .split(',')
+            .filter_map(|tag_id| tag_id.trim().parse().ok())
+            .collect()
+    }
+}
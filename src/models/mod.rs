@@ -1,7 +1,37 @@
+pub mod antispam;
+pub mod api_usage;
+pub mod audit;
+pub mod bookmark;
 pub mod category;
+pub mod category_field;
+pub mod collection;
+pub mod comment;
+pub mod domain_event;
+pub mod email;
+pub mod image;
+pub mod import_session;
+pub mod magnet_upload;
+pub mod points;
+pub mod report;
+pub mod reputation;
 pub mod response;
+pub mod saved_search;
+pub mod subscription;
+pub mod sync;
 pub mod torrent;
+pub mod torrent_attachment;
+pub mod torrent_contents;
+pub mod torrent_duplicate;
 pub mod torrent_file;
+pub mod torrent_file_group;
+pub mod torrent_health;
+pub mod torrent_metadata;
+pub mod torrent_preview;
+pub mod torrent_rule;
 pub mod torrent_tag;
+pub mod torrent_web_seed;
 pub mod tracker_key;
+pub mod transfer_stats;
+pub mod upload_quota;
 pub mod user;
+pub mod user_session;
@@ -0,0 +1,51 @@
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+
+use super::domain_event::{EventId, SeedingSummary};
+use super::user::UserId;
+
+pub type AdjustmentId = i64;
+
+/// A change in the points balance of a user made by an external bonus
+/// system.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, sqlx::FromRow, ToSchema)]
+pub struct PointsAdjustment {
+    pub adjustment_id: AdjustmentId,
+    pub user_id: UserId,
+    /// Points added to the balance. Negative amounts subtract points.
+    pub amount: i64,
+    pub reason: String,
+    /// Unique reference of the adjustment in the external system.
+    pub reference: Option<String>,
+    pub date_created: String,
+}
+
+/// The points balance of a user with the latest adjustments.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+pub struct PointsBalance {
+    pub username: String,
+    pub balance: i64,
+    /// The latest adjustments, newest first.
+    pub adjustments: Vec<PointsAdjustment>,
+}
+
+/// Seeding stats of the torrents uploaded by a user.
+#[derive(Debug, Clone, PartialEq, Eq, sqlx::FromRow)]
+pub struct UploaderSeedingStats {
+    pub user_id: UserId,
+    pub username: String,
+    pub torrents: i64,
+    pub seeded_torrents: i64,
+    pub seeders: i64,
+    pub leechers: i64,
+    pub seeded_bytes: i64,
+}
+
+/// A published seeding summary.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+pub struct SeedingSummaryEvent {
+    /// The domain event ID. Consumers pull the summaries published after the
+    /// last one they have processed.
+    pub event_id: EventId,
+    pub summary: SeedingSummary,
+}
@@ -0,0 +1,60 @@
+use derive_more::Display;
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+
+use super::torrent::TorrentId;
+use super::user::UserId;
+
+#[allow(clippy::module_name_repetitions)]
+pub type ReportId = i64;
+
+/// Why a user reports a torrent.
+#[derive(Debug, Display, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum ReportReason {
+    /// Copyright infringement.
+    #[display("dmca")]
+    Dmca,
+    #[display("malware")]
+    Malware,
+    #[display("spam")]
+    Spam,
+    #[display("other")]
+    Other,
+}
+
+/// What a moderator does to resolve a report.
+#[derive(Debug, Display, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum ReportAction {
+    /// Close the report without any further action.
+    #[display("dismiss")]
+    Dismiss,
+    /// Remove the torrent from the public listing.
+    #[display("hide_torrent")]
+    HideTorrent,
+    /// Ban the user who uploaded the torrent.
+    #[display("ban_uploader")]
+    BanUploader,
+}
+
+/// A user report about a torrent.
+#[derive(Debug, PartialEq, Eq, Serialize, Deserialize, sqlx::FromRow)]
+pub struct Report {
+    pub report_id: ReportId,
+    pub torrent_id: TorrentId,
+    pub info_hash: String,
+    pub title: String,
+    /// The username of the torrent uploader.
+    pub uploader: String,
+    pub reporter_id: UserId,
+    /// The username of the user who reported the torrent.
+    pub reporter: String,
+    pub reason: String,
+    pub details: Option<String>,
+    pub resolved: bool,
+    /// The action taken by the moderator who resolved the report.
+    pub resolution: Option<String>,
+    pub date_created: String,
+    pub date_resolved: Option<String>,
+}
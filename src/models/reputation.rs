@@ -0,0 +1,47 @@
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+
+/// Uploads of a user and reports against them, counted by the reputation.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+pub struct ReputationStats {
+    /// All the torrents uploaded by the user, whatever their status.
+    pub uploads: u64,
+    pub approved_uploads: u64,
+    pub rejected_uploads: u64,
+    /// Reports against the torrents uploaded by the user.
+    pub reports: u64,
+    /// Reports against the torrents uploaded by the user that were resolved
+    /// with an action other than dismissing them.
+    pub upheld_reports: u64,
+}
+
+impl ReputationStats {
+    /// The approved uploads out of the moderated ones, from `0.0` to `1.0`.
+    /// Pending uploads are not counted. It's `0.0` when no uploads were
+    /// moderated.
+    #[must_use]
+    #[allow(clippy::cast_precision_loss)]
+    pub fn approved_ratio(&self) -> f64 {
+        let moderated = self.approved_uploads + self.rejected_uploads;
+
+        if moderated == 0 {
+            return 0.0;
+        }
+
+        self.approved_uploads as f64 / moderated as f64
+    }
+}
+
+/// The reputation of a user.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, ToSchema)]
+pub struct UserReputation {
+    pub username: String,
+    pub stats: ReputationStats,
+    pub approved_ratio: f64,
+    /// Whether the user is a trusted uploader. Their uploads skip the
+    /// moderation queue.
+    pub trusted: bool,
+    /// Whether the trusted status was set for the user by an administrator
+    /// instead of coming from the `[reputation]` thresholds.
+    pub overridden: bool,
+}
@@ -1,11 +1,20 @@
 use serde::{Deserialize, Serialize};
 use url::Url;
+use utoipa::ToSchema;
 
+use super::audit::AuditEntry;
 use super::category::Category;
+use super::category_field::TorrentFieldValue;
+use super::comment::Comment;
+use super::report::Report;
 use super::torrent::TorrentId;
 use crate::databases::database::Category as DatabaseCategory;
 use crate::models::torrent::TorrentListing;
-use crate::models::torrent_file::TorrentFile;
+use crate::models::torrent_contents::TorrentContents;
+use crate::models::torrent_file::{include_backup_trackers_tier, TorrentFile};
+use crate::models::torrent_file_group::TorrentFileGroupSummary;
+use crate::models::torrent_metadata::TorrentMetadata;
+use crate::models::torrent_preview::TorrentPreview;
 use crate::models::torrent_tag::TorrentTag;
 use crate::services::torrent::CanonicalInfoHashGroup;
 
@@ -26,7 +35,7 @@ pub struct ErrorResponse<T> {
 }
 
 #[allow(clippy::module_name_repetitions)]
-#[derive(Serialize, Deserialize, Debug)]
+#[derive(Serialize, Deserialize, Debug, ToSchema)]
 pub struct TokenResponse {
     pub token: String,
     pub username: String,
@@ -41,17 +50,19 @@ pub struct NewTorrentResponse {
 }
 
 #[allow(clippy::module_name_repetitions)]
-#[derive(Serialize, Deserialize, Debug)]
+#[derive(Serialize, Deserialize, Debug, ToSchema)]
 pub struct DeletedTorrentResponse {
     pub torrent_id: TorrentId,
     pub info_hash: String,
 }
 
 #[allow(clippy::module_name_repetitions)]
-#[derive(Debug, Serialize, Deserialize, sqlx::FromRow)]
+#[derive(Debug, Serialize, Deserialize, sqlx::FromRow, ToSchema)]
 pub struct TorrentResponse {
     pub torrent_id: i64,
     pub uploader: String,
+    /// Whether the uploader is a trusted uploader.
+    pub uploader_trusted: bool,
     pub info_hash: String,
     pub title: String,
     pub description: Option<String>,
@@ -61,7 +72,11 @@ pub struct TorrentResponse {
     pub seeders: i64,
     pub leechers: i64,
     pub files: Vec<TorrentFile>,
+    /// The files as a tree of folders, with their kinds and aggregate stats.
+    pub contents: TorrentContents,
     pub trackers: Vec<String>,
+    /// The trackers grouped in `announce-list` tiers.
+    pub tracker_tiers: Vec<Vec<String>>,
     pub magnet_link: String,
     pub tags: Vec<TorrentTag>,
     pub name: String,
@@ -70,6 +85,17 @@ pub struct TorrentResponse {
     pub created_by: Option<String>,
     pub encoding: Option<String>,
     pub canonical_info_hash_group: Vec<String>,
+    /// Whether the logged-in user has bookmarked the torrent or not.
+    pub bookmarked: bool,
+    /// Files in the torrent attached as previews by the uploader.
+    pub previews: Vec<TorrentPreview>,
+    /// The sub-groups of the files declared by the uploader.
+    pub file_groups: Vec<TorrentFileGroupSummary>,
+    /// The poster, synopsis and canonical metadata fetched from an external
+    /// provider.
+    pub metadata: Option<TorrentMetadata>,
+    /// The values of the custom fields of the torrent category.
+    pub fields: Vec<TorrentFieldValue>,
 }
 
 impl TorrentResponse {
@@ -82,6 +108,7 @@ impl TorrentResponse {
         TorrentResponse {
             torrent_id: torrent_listing.torrent_id,
             uploader: torrent_listing.uploader,
+            uploader_trusted: false,
             info_hash: torrent_listing.info_hash,
             title: torrent_listing.title,
             description: torrent_listing.description,
@@ -91,7 +118,9 @@ impl TorrentResponse {
             seeders: torrent_listing.seeders,
             leechers: torrent_listing.leechers,
             files: vec![],
+            contents: TorrentContents::default(),
             trackers: vec![],
+            tracker_tiers: vec![],
             magnet_link: String::new(),
             tags: vec![],
             name: torrent_listing.name,
@@ -104,22 +133,112 @@ impl TorrentResponse {
                 .iter()
                 .map(bittorrent_primitives::info_hash::InfoHash::to_hex_string)
                 .collect(),
+            bookmarked: false,
+            previews: vec![],
+            file_groups: vec![],
+            metadata: None,
+            fields: vec![],
         }
     }
 
-    /// It adds the tracker URL in the first position of the tracker list.
+    /// It adds the tracker URL in the first position of the tracker list and
+    /// in its own first tier.
     pub fn include_url_as_main_tracker(&mut self, tracker_url: &Url) {
         // Remove any existing instances of tracker_url
         self.trackers.retain(|tracker| *tracker != tracker_url.to_string());
 
         // Insert tracker_url at the first position
         self.trackers.insert(0, tracker_url.to_owned().to_string());
+
+        for tier in &mut self.tracker_tiers {
+            tier.retain(|tracker| *tracker != tracker_url.to_string());
+        }
+
+        self.tracker_tiers.retain(|tier| !tier.is_empty());
+
+        self.tracker_tiers.insert(0, vec![tracker_url.to_owned().to_string()]);
     }
+
+    /// It adds the backup tracker URLs after the main tracker in the tracker
+    /// list and in their own tier, right after the tier of the main tracker.
+    pub fn include_urls_as_backup_trackers(&mut self, tracker_urls: &[Url]) {
+        if tracker_urls.is_empty() {
+            return;
+        }
+
+        include_backup_trackers_tier(&mut self.tracker_tiers, tracker_urls);
+
+        self.trackers = self.tracker_tiers.iter().flatten().cloned().collect();
+    }
+}
+
+/// The magnet link of a torrent.
+#[allow(clippy::module_name_repetitions)]
+#[derive(Serialize, Deserialize, Debug, PartialEq, Eq, ToSchema)]
+pub struct MagnetLinkResponse {
+    /// The info-hash in the magnet link. It's not the info-hash of the
+    /// uploaded torrent when the downloaded torrents are rewritten.
+    pub info_hash: String,
+    pub magnet_uri: String,
 }
 
+/// The peers of a torrent in the tracker.
 #[allow(clippy::module_name_repetitions)]
-#[derive(Serialize, Deserialize, Debug, sqlx::FromRow)]
+#[derive(Serialize, Deserialize, Debug, ToSchema)]
+pub struct TorrentPeersResponse {
+    pub info_hash: String,
+    pub seeders: i64,
+    pub completed: i64,
+    pub leechers: i64,
+    /// Whether the peer addresses are masked. Only administrators get the
+    /// full addresses and the peer IDs.
+    pub masked: bool,
+    pub peers: Vec<TorrentPeer>,
+}
+
+#[derive(Serialize, Deserialize, Debug, PartialEq, Eq, ToSchema)]
+pub struct TorrentPeer {
+    /// The peer ID, if the addresses are not masked.
+    pub peer_id: Option<String>,
+    /// The client software, from the peer ID.
+    pub client: Option<String>,
+    /// The peer IP address and port, or only its network when the addresses
+    /// are masked. Eg: `203.0.0.0/16`.
+    pub address: Option<String>,
+    /// Time of the last announce, as returned by the tracker.
+    pub updated: Option<i64>,
+    pub uploaded: Option<i64>,
+    pub downloaded: Option<i64>,
+    pub left: Option<i64>,
+    pub event: Option<String>,
+    /// Whether the peer has the whole torrent.
+    pub seeder: bool,
+}
+
+#[allow(clippy::module_name_repetitions)]
+#[derive(Serialize, Deserialize, Debug, sqlx::FromRow, ToSchema)]
 pub struct TorrentsResponse {
     pub total: u32,
     pub results: Vec<TorrentListing>,
 }
+
+#[allow(clippy::module_name_repetitions)]
+#[derive(Serialize, Deserialize, Debug)]
+pub struct CommentsResponse {
+    pub total: u32,
+    pub results: Vec<Comment>,
+}
+
+#[allow(clippy::module_name_repetitions)]
+#[derive(Serialize, Deserialize, Debug)]
+pub struct ReportsResponse {
+    pub total: u32,
+    pub results: Vec<Report>,
+}
+
+#[allow(clippy::module_name_repetitions)]
+#[derive(Serialize, Deserialize, Debug, ToSchema)]
+pub struct AuditLogResponse {
+    pub total: u32,
+    pub results: Vec<AuditEntry>,
+}
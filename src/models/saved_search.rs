@@ -0,0 +1,61 @@
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+
+use super::user::UserId;
+
+#[allow(clippy::module_name_repetitions)]
+pub type SavedSearchId = i64;
+
+/// A search saved by a user. The new torrents matching the search are added
+/// to its results.
+#[allow(clippy::module_name_repetitions)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+pub struct SavedSearch {
+    pub saved_search_id: SavedSearchId,
+    pub user_id: UserId,
+    pub username: String,
+    pub name: String,
+    /// Words the torrent title must contain, in any order and case.
+    pub query: String,
+    /// Only torrents in this category.
+    pub category: Option<String>,
+    /// Only torrents with all these tags.
+    pub tags: Vec<String>,
+    /// Whether the user receives an email with the new matching torrents.
+    pub email_alerts: bool,
+    pub date_created: String,
+}
+
+/// A [`SavedSearch`] as it's stored in the database.
+#[derive(Debug, Clone, PartialEq, Eq, sqlx::FromRow)]
+pub struct SavedSearchRow {
+    pub saved_search_id: SavedSearchId,
+    pub user_id: UserId,
+    pub username: String,
+    pub name: String,
+    pub query: String,
+    pub category: Option<String>,
+    /// Comma-separated tag names.
+    pub tags: Option<String>,
+    pub email_alerts: bool,
+    pub date_created: String,
+}
+
+impl From<SavedSearchRow> for SavedSearch {
+    fn from(row: SavedSearchRow) -> Self {
+        Self {
+            saved_search_id: row.saved_search_id,
+            user_id: row.user_id,
+            username: row.username,
+            name: row.name,
+            query: row.query,
+            category: row.category,
+            tags: row
+                .tags
+                .map(|tags| tags.split(',').map(ToString::to_string).collect())
+                .unwrap_or_default(),
+            email_alerts: row.email_alerts,
+            date_created: row.date_created,
+        }
+    }
+}
@@ -0,0 +1,25 @@
+use serde::{Deserialize, Serialize};
+
+use super::category::CategoryId;
+use super::torrent_tag::TagId;
+use super::user::UserId;
+
+#[allow(clippy::module_name_repetitions)]
+pub type SubscriptionId = i64;
+
+/// A user subscription to the new torrents in a category or with a tag.
+///
+/// Only one of `category_id` and `tag_id` is set.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, sqlx::FromRow)]
+pub struct Subscription {
+    pub subscription_id: SubscriptionId,
+    pub user_id: UserId,
+    pub username: String,
+    pub category_id: Option<CategoryId>,
+    /// The category name.
+    pub category: Option<String>,
+    pub tag_id: Option<TagId>,
+    /// The tag name.
+    pub tag: Option<String>,
+    pub date_created: String,
+}
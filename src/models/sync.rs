@@ -0,0 +1,124 @@
+//! Records of the delta sync for mirrors.
+//!
+//! Refer to the [`sync`](crate::services::sync) service.
+use serde::Serialize;
+use utoipa::ToSchema;
+
+use super::torrent::TorrentId;
+
+pub type PartitionId = i64;
+
+/// Global sequence number of the changes. It only grows.
+pub type Sequence = i64;
+
+/// The data of a torrent as it's read from the database.
+#[derive(Debug, Clone, PartialEq, Eq, sqlx::FromRow)]
+pub struct SyncTorrent {
+    pub torrent_id: TorrentId,
+    pub info_hash: String,
+    pub title: String,
+    pub description: Option<String>,
+    pub category: Option<String>,
+    /// Comma-separated tag names.
+    pub tags: Option<String>,
+    pub name: String,
+    pub file_size: i64,
+    pub date_uploaded: String,
+    pub uploader: String,
+}
+
+/// The data of a torrent mirrors get. It does not include the tracker stats
+/// because they change too often.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, ToSchema)]
+pub struct SyncRecord {
+    pub torrent_id: TorrentId,
+    pub info_hash: String,
+    pub title: String,
+    pub description: Option<String>,
+    pub category: Option<String>,
+    /// Sorted by name.
+    pub tags: Vec<String>,
+    pub name: String,
+    pub file_size: i64,
+    pub date_uploaded: String,
+    pub uploader: String,
+}
+
+impl From<SyncTorrent> for SyncRecord {
+    fn from(torrent: SyncTorrent) -> Self {
+        let mut tags: Vec<String> = torrent
+            .tags
+            .map(|tags| tags.split(',').map(str::to_string).collect())
+            .unwrap_or_default();
+
+        tags.sort();
+
+        SyncRecord {
+            torrent_id: torrent.torrent_id,
+            info_hash: torrent.info_hash,
+            title: torrent.title,
+            description: torrent.description,
+            category: torrent.category,
+            tags,
+            name: torrent.name,
+            file_size: torrent.file_size,
+            date_uploaded: torrent.date_uploaded,
+            uploader: torrent.uploader,
+        }
+    }
+}
+
+/// The last synced state of a torrent.
+#[derive(Debug, Clone, PartialEq, Eq, sqlx::FromRow)]
+pub struct SyncState {
+    pub torrent_id: TorrentId,
+    pub partition_id: PartitionId,
+    /// Hash of the record. It's empty for deleted torrents.
+    pub record_hash: String,
+    /// Sequence of the last change.
+    pub sequence: Sequence,
+    pub deleted: bool,
+}
+
+/// Summary of a partition as it's read from the database.
+#[derive(Debug, Clone, PartialEq, Eq, sqlx::FromRow)]
+pub struct SyncPartitionSummary {
+    pub partition_id: PartitionId,
+    /// Number of torrents, not including the deleted ones.
+    pub records: i64,
+    pub last_sequence: Sequence,
+}
+
+/// A partition in the sync manifest.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, ToSchema)]
+pub struct SyncPartition {
+    pub partition_id: PartitionId,
+    /// It changes every time a torrent in the partition changes.
+    pub version: String,
+    pub records: i64,
+    /// Sequence of the last change in the partition.
+    pub last_sequence: Sequence,
+}
+
+/// The list of partitions mirrors compare with their copy.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, ToSchema)]
+pub struct SyncManifest {
+    /// Number of consecutive torrent IDs in each partition.
+    pub partition_size: i64,
+    /// Sequence of the last change.
+    pub last_sequence: Sequence,
+    pub partitions: Vec<SyncPartition>,
+}
+
+/// The changes in a partition after a sequence.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, ToSchema)]
+pub struct PartitionChanges {
+    pub partition_id: PartitionId,
+    pub version: String,
+    /// The sequence to use in the next request.
+    pub last_sequence: Sequence,
+    /// Torrents added or changed.
+    pub records: Vec<SyncRecord>,
+    /// IDs of the torrents removed from the index.
+    pub deleted: Vec<TorrentId>,
+}
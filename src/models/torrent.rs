@@ -1,8 +1,10 @@
 use derive_more::{Display, Error};
 use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
 
 use super::category::CategoryId;
 use super::torrent_tag::TagId;
+use super::user::UserId;
 
 const MIN_TORRENT_TITLE_LENGTH: usize = 3;
 
@@ -11,7 +13,7 @@ pub type TorrentId = i64;
 
 #[allow(clippy::module_name_repetitions)]
 #[allow(dead_code)]
-#[derive(Debug, PartialEq, Eq, Serialize, Deserialize, sqlx::FromRow)]
+#[derive(Debug, PartialEq, Eq, Serialize, Deserialize, sqlx::FromRow, ToSchema)]
 pub struct TorrentListing {
     pub torrent_id: TorrentId,
     pub uploader: String,
@@ -28,6 +30,101 @@ pub struct TorrentListing {
     pub creation_date: Option<i64>,
     pub created_by: Option<String>,
     pub encoding: Option<String>,
+    pub status: String,
+}
+
+/// An existing torrent that could be a duplicate of an uploaded one.
+#[allow(clippy::module_name_repetitions)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, sqlx::FromRow, ToSchema)]
+pub struct SimilarTorrent {
+    pub torrent_id: TorrentId,
+    pub info_hash: String,
+    pub title: String,
+    pub file_size: i64,
+}
+
+/// A deleted torrent in the trash bin.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, sqlx::FromRow, ToSchema)]
+pub struct TrashedTorrent {
+    pub torrent_id: TorrentId,
+    pub info_hash: String,
+    pub title: String,
+    /// The moderation status the torrent gets back when it's restored.
+    pub previous_status: String,
+    /// The username of the user who deleted the torrent. `None` if the user
+    /// was deleted.
+    pub deleted_by: Option<String>,
+    pub date_deleted: String,
+}
+
+/// A torrent waiting for its scheduled release date.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, sqlx::FromRow)]
+pub struct ScheduledRelease {
+    pub torrent_id: TorrentId,
+    pub info_hash: String,
+    pub title: String,
+    pub publish_at: String,
+    /// The moderation status the torrent gets when it's released.
+    pub status_on_publish: String,
+}
+
+/// A moderation action applied to many torrents in a single transaction.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BulkAction {
+    /// Move the torrents to the trash bin.
+    Delete {
+        deleted_by: Option<UserId>,
+    },
+    Recategorize(CategoryId),
+    AddTag(TagId),
+    RemoveTag(TagId),
+    Approve,
+}
+
+/// Filters for the torrent listing. Torrents must match all of them.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ListingFilter {
+    /// Minimum total size in bytes.
+    pub min_size: Option<i64>,
+    /// Maximum total size in bytes.
+    pub max_size: Option<i64>,
+    /// Only torrents uploaded at or after this date and time.
+    pub uploaded_since: Option<String>,
+    /// Only torrents uploaded before this date and time.
+    pub uploaded_until: Option<String>,
+    /// Minimum number of seeders, according to the tracker statistics.
+    pub min_seeders: Option<i64>,
+    /// Lowercase extension, without the dot, of at least one of the files.
+    pub file_extension: Option<String>,
+    /// Values of the category fields, by field name.
+    pub fields: Vec<(String, String)>,
+}
+
+/// Moderation status of a torrent.
+///
+/// Only approved torrents are included in the public torrent listing.
+#[derive(Debug, Display, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum TorrentStatus {
+    /// Waiting for a moderator to review the upload.
+    #[display("pending")]
+    Pending,
+    #[display("approved")]
+    Approved,
+    #[display("rejected")]
+    Rejected,
+    /// Removed from the listing and kept as archived by a torrent rule.
+    #[display("archived")]
+    Archived,
+    /// Removed from the listing by a torrent rule.
+    #[display("delisted")]
+    Delisted,
+    /// In the trash bin. It can be restored until it's purged.
+    #[display("deleted")]
+    Deleted,
+    /// Hidden until its scheduled release date.
+    #[display("scheduled")]
+    Scheduled,
 }
 
 #[derive(Debug, Display, PartialEq, Eq, Error)]
@@ -0,0 +1,42 @@
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+
+use super::torrent::TorrentId;
+
+#[allow(clippy::module_name_repetitions)]
+pub type AttachmentId = i64;
+
+/// The type of an auxiliary file attached to a torrent.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum AttachmentKind {
+    /// The release information text file. A torrent has at most one.
+    Nfo,
+    /// A sample screenshot of the torrent contents.
+    Screenshot,
+}
+
+impl AttachmentKind {
+    #[must_use]
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            AttachmentKind::Nfo => "nfo",
+            AttachmentKind::Screenshot => "screenshot",
+        }
+    }
+}
+
+/// An auxiliary file attached to a torrent listing, like the NFO or a
+/// screenshot.
+#[allow(clippy::module_name_repetitions)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, sqlx::FromRow, ToSchema)]
+pub struct TorrentAttachment {
+    pub attachment_id: AttachmentId,
+    pub torrent_id: TorrentId,
+    /// `nfo` or `screenshot`.
+    pub kind: String,
+    /// The name of the uploaded file.
+    pub file_name: String,
+    pub content_type: String,
+    pub size: i64,
+}
@@ -0,0 +1,60 @@
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+
+/// The kind of a file in a torrent, guessed from its extension.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum FileKind {
+    Video,
+    Audio,
+    Image,
+    Subtitle,
+    Document,
+    Archive,
+    DiskImage,
+    Executable,
+    Other,
+}
+
+/// A file or a folder in the file tree of a torrent.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+pub struct FileNode {
+    pub name: String,
+    /// The size of the file, or the total size of the files in the folder.
+    pub length: i64,
+    /// Only for files.
+    pub kind: Option<FileKind>,
+    /// Only for files with a known extension.
+    pub mime_type: Option<String>,
+    /// The files and folders in the folder. Folders go first and both are
+    /// sorted by name. Empty for files.
+    pub children: Vec<FileNode>,
+}
+
+impl FileNode {
+    #[must_use]
+    pub fn is_folder(&self) -> bool {
+        self.kind.is_none()
+    }
+}
+
+/// Aggregate stats of the files in a torrent.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+pub struct FileStats {
+    pub file_count: u64,
+    pub video_count: u64,
+    pub audio_count: u64,
+    pub image_count: u64,
+    pub archive_count: u64,
+    pub video_size: i64,
+    pub audio_size: i64,
+    pub archive_size: i64,
+}
+
+/// The files of a torrent as a tree, with their kinds and aggregate stats.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+pub struct TorrentContents {
+    /// The files and folders in the torrent root folder.
+    pub tree: Vec<FileNode>,
+    pub stats: FileStats,
+}
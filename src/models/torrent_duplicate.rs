@@ -0,0 +1,27 @@
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+
+use super::torrent::{SimilarTorrent, TorrentId};
+
+/// An uploaded torrent whose contents overlap with an existing torrent.
+#[allow(clippy::module_name_repetitions)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, sqlx::FromRow, ToSchema)]
+pub struct DuplicatePair {
+    /// The uploaded torrent.
+    pub torrent_id: TorrentId,
+    /// The existing torrent.
+    pub duplicate_of: TorrentId,
+    /// Fraction of the contents of the smallest torrent that is also in the
+    /// other one, from `0.0` to `1.0`.
+    pub overlap: f64,
+    pub date_detected: String,
+}
+
+/// A group of torrents connected by overlapping contents.
+#[allow(clippy::module_name_repetitions)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, ToSchema)]
+pub struct DuplicateCluster {
+    pub torrents: Vec<SimilarTorrent>,
+    /// The detected duplicates, from the most recent to the oldest.
+    pub pairs: Vec<DuplicatePair>,
+}
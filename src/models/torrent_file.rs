@@ -5,6 +5,7 @@ use serde_bytes::ByteBuf;
 use sha1::{Digest, Sha1};
 use tracing::error;
 use url::Url;
+use utoipa::ToSchema;
 
 use crate::utils::hex::{from_bytes, into_bytes};
 
@@ -19,6 +20,11 @@ pub struct Torrent {
     pub encoding: Option<String>,
     #[serde(default)]
     pub httpseeds: Option<Vec<String>>,
+    /// The HTTP web seeds (BEP 19) added to the torrent listing. The
+    /// `url-list` of uploaded torrents is not kept.
+    #[serde(default, skip_deserializing)]
+    #[serde(rename = "url-list")]
+    pub url_list: Option<Vec<String>>,
     #[serde(default)]
     #[serde(rename = "announce-list")]
     pub announce_list: Option<Vec<Vec<String>>>,
@@ -56,7 +62,7 @@ pub struct TorrentInfoDictionary {
     pub source: Option<String>,
 }
 
-#[derive(PartialEq, Eq, Debug, Clone, Serialize, Deserialize)]
+#[derive(PartialEq, Eq, Debug, Clone, Serialize, Deserialize, ToSchema)]
 pub struct TorrentFile {
     pub path: Vec<String>,
     pub length: i64,
@@ -115,6 +121,7 @@ impl Torrent {
             } else {
                 Some(torrent_http_seed_urls)
             },
+            url_list: None,
             announce_list: Some(torrent_announce_urls),
             creation_date: db_torrent.creation_date,
             comment: db_torrent.comment.clone(),
@@ -122,6 +129,12 @@ impl Torrent {
         }
     }
 
+    /// Sets the HTTP web seeds (BEP 19) of the torrent. The `url-list` key is
+    /// omitted when there are none.
+    pub fn set_url_list(&mut self, web_seed_urls: Vec<String>) {
+        self.url_list = if web_seed_urls.is_empty() { None } else { Some(web_seed_urls) };
+    }
+
     /// Includes the tracker URL a the main tracker in the torrent.
     ///
     /// It will be the URL in the `announce` field and also the first URL in the
@@ -161,6 +174,49 @@ impl Torrent {
         }
     }
 
+    /// Adds the backup tracker URLs in their own tier, right after the tier of
+    /// the main tracker, and removes them from the other tiers.
+    pub fn include_urls_as_backup_trackers(&mut self, tracker_urls: &[Url]) {
+        if tracker_urls.is_empty() {
+            return;
+        }
+
+        let announce = self.announce.clone();
+
+        let list = self
+            .announce_list
+            .get_or_insert_with(|| announce.map(|announce| vec![vec![announce]]).unwrap_or_default());
+
+        include_backup_trackers_tier(list, tracker_urls);
+    }
+
+    /// Keeps the original trackers as the `announce_list` tiers, and sets the
+    /// announce URL to the tracker URL. The original `announce` URL is the
+    /// only tier when the torrent does not have an `announce_list`.
+    ///
+    /// The tracker URL is removed from the tiers. It's added again as the
+    /// main tracker when the torrent is downloaded, so the original trackers
+    /// are always after it and the torrent can be cross-seeded.
+    pub fn preserve_original_trackers(&mut self, tracker_url: &Url) {
+        if self.announce_list.as_ref().map_or(true, Vec::is_empty) {
+            self.announce_list = self.announce.take().map(|announce| vec![vec![announce]]);
+        }
+
+        if let Some(list) = &mut self.announce_list {
+            for inner_list in list.iter_mut() {
+                inner_list.retain(|url| *url != tracker_url.to_string());
+            }
+
+            list.retain(|inner_list| !inner_list.is_empty());
+
+            if list.is_empty() {
+                self.announce_list = None;
+            }
+        }
+
+        self.set_announce_to(tracker_url);
+    }
+
     /// Removes all other trackers if the torrent is private.
     pub fn reset_announce_list_if_private(&mut self) {
         if self.is_private() {
@@ -380,6 +436,30 @@ pub struct DbTorrentFile {
 #[derive(PartialEq, Eq, Debug, Clone, Serialize, Deserialize, sqlx::FromRow)]
 pub struct DbTorrentAnnounceUrl {
     pub tracker_url: String,
+    pub tier: i64,
+}
+
+impl DbTorrentAnnounceUrl {
+    /// It builds the `announce-list` tiers from the announce urls ordered by
+    /// tier.
+    #[must_use]
+    pub fn group_by_tier(announce_urls: &[DbTorrentAnnounceUrl]) -> Vec<Vec<String>> {
+        let mut tiers: Vec<Vec<String>> = vec![];
+        let mut current_tier = None;
+
+        for announce_url in announce_urls {
+            if current_tier != Some(announce_url.tier) {
+                tiers.push(vec![]);
+                current_tier = Some(announce_url.tier);
+            }
+
+            if let Some(tier) = tiers.last_mut() {
+                tier.push(announce_url.tracker_url.clone());
+            }
+        }
+
+        tiers
+    }
 }
 
 #[derive(PartialEq, Eq, Debug, Clone, Serialize, Deserialize, sqlx::FromRow)]
@@ -393,6 +473,24 @@ pub struct DbTorrentNode {
     pub node_port: i64,
 }
 
+/// It adds the backup tracker URLs in their own tier, right after the tier of
+/// the main tracker, and removes them from the other tiers.
+pub fn include_backup_trackers_tier(tiers: &mut Vec<Vec<String>>, tracker_urls: &[Url]) {
+    if tracker_urls.is_empty() {
+        return;
+    }
+
+    let tracker_urls: Vec<String> = tracker_urls.iter().map(ToString::to_string).collect();
+
+    for tier in tiers.iter_mut().skip(1) {
+        tier.retain(|tracker| !tracker_urls.contains(tracker));
+    }
+
+    tiers.retain(|tier| !tier.is_empty());
+
+    tiers.insert(tiers.len().min(1), tracker_urls);
+}
+
 #[cfg(test)]
 mod tests {
 
@@ -463,6 +561,7 @@ mod tests {
                 nodes: None,
                 encoding: None,
                 httpseeds: None,
+                url_list: None,
             };
 
             assert_eq!(torrent.canonical_info_hash_hex(), "79fa9e4a2927804fe4feab488a76c8c2d3d1cdca");
@@ -504,6 +603,7 @@ mod tests {
                     nodes: None,
                     encoding: None,
                     httpseeds: None,
+                    url_list: None,
                 };
 
                 assert_eq!(torrent.canonical_info_hash_hex(), "79fa9e4a2927804fe4feab488a76c8c2d3d1cdca");
@@ -543,6 +643,7 @@ mod tests {
                     nodes: None,
                     encoding: None,
                     httpseeds: None,
+                    url_list: None,
                 };
 
                 assert_eq!(torrent.canonical_info_hash_hex(), "aa2aca91ab650c4d249c475ca3fa604f2ccb0d2a");
@@ -578,6 +679,7 @@ mod tests {
                     nodes: None,
                     encoding: None,
                     httpseeds: None,
+                    url_list: None,
                 };
 
                 assert_eq!(torrent.canonical_info_hash_hex(), "ccc1cf4feb59f3fa85c96c9be1ebbafcfe8a9cc8");
@@ -613,10 +715,128 @@ mod tests {
                     nodes: None,
                     encoding: None,
                     httpseeds: None,
+                    url_list: None,
                 };
 
                 assert_eq!(torrent.canonical_info_hash_hex(), "d3a558d0a19aaa23ba6f9f430f40924d10fefa86");
             }
         }
     }
+
+    mod announce_list_tiers {
+        use crate::models::torrent_file::DbTorrentAnnounceUrl;
+
+        fn announce_url(tracker_url: &str, tier: i64) -> DbTorrentAnnounceUrl {
+            DbTorrentAnnounceUrl {
+                tracker_url: tracker_url.to_string(),
+                tier,
+            }
+        }
+
+        #[test]
+        fn it_should_group_the_announce_urls_by_tier() {
+            let announce_urls = vec![
+                announce_url("udp://tracker-a:6969", 0),
+                announce_url("udp://tracker-b:6969", 0),
+                announce_url("udp://tracker-c:6969", 1),
+                announce_url("udp://tracker-d:6969", 3),
+            ];
+
+            assert_eq!(
+                DbTorrentAnnounceUrl::group_by_tier(&announce_urls),
+                vec![
+                    vec!["udp://tracker-a:6969".to_string(), "udp://tracker-b:6969".to_string()],
+                    vec!["udp://tracker-c:6969".to_string()],
+                    vec!["udp://tracker-d:6969".to_string()],
+                ]
+            );
+        }
+    }
+
+    mod backup_trackers {
+        use url::Url;
+
+        use crate::models::torrent_file::{include_backup_trackers_tier, Torrent, TorrentInfoDictionary};
+
+        fn torrent_with_announce_list(announce_list: Option<Vec<Vec<String>>>) -> Torrent {
+            Torrent {
+                info: TorrentInfoDictionary {
+                    name: "sample.txt".to_string(),
+                    pieces: None,
+                    piece_length: 16384,
+                    md5sum: None,
+                    length: Some(11),
+                    files: None,
+                    private: None,
+                    path: None,
+                    root_hash: None,
+                    source: None,
+                },
+                announce: Some("udp://main:6969".to_string()),
+                announce_list,
+                creation_date: None,
+                comment: None,
+                created_by: None,
+                nodes: None,
+                encoding: None,
+                httpseeds: None,
+                url_list: None,
+            }
+        }
+
+        #[test]
+        fn it_should_add_the_backup_trackers_in_their_own_tier_after_the_main_tracker() {
+            let mut tiers = vec![
+                vec!["udp://main:6969".to_string()],
+                vec!["udp://backup:6969".to_string(), "udp://other:6969".to_string()],
+                vec!["udp://backup:6969".to_string()],
+            ];
+
+            include_backup_trackers_tier(&mut tiers, &[Url::parse("udp://backup:6969").unwrap()]);
+
+            assert_eq!(
+                tiers,
+                vec![
+                    vec!["udp://main:6969".to_string()],
+                    vec!["udp://backup:6969".to_string()],
+                    vec!["udp://other:6969".to_string()],
+                ]
+            );
+        }
+
+        #[test]
+        fn it_should_preserve_the_original_trackers_after_the_index_tracker() {
+            let mut torrent = torrent_with_announce_list(None);
+
+            torrent.preserve_original_trackers(&Url::parse("udp://index:6969").unwrap());
+
+            assert_eq!(torrent.announce, Some("udp://index:6969".to_string()));
+            assert_eq!(torrent.announce_list, Some(vec![vec!["udp://main:6969".to_string()]]));
+
+            torrent.include_url_as_main_tracker(&Url::parse("udp://index:6969").unwrap());
+
+            assert_eq!(
+                torrent.announce_list,
+                Some(vec![
+                    vec!["udp://index:6969".to_string()],
+                    vec!["udp://main:6969".to_string()]
+                ])
+            );
+        }
+
+        #[test]
+        fn it_should_create_the_announce_list_when_the_torrent_does_not_have_one() {
+            let mut torrent = torrent_with_announce_list(None);
+
+            torrent.include_urls_as_backup_trackers(&[Url::parse("udp://backup:6969").unwrap()]);
+
+            assert_eq!(
+                torrent.announce_list,
+                Some(vec![
+                    vec!["udp://main:6969".to_string()],
+                    vec!["udp://backup:6969".to_string()]
+                ])
+            );
+        }
+    }
 }
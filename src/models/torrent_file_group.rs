@@ -0,0 +1,33 @@
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+
+/// A logical sub-group of the files in a torrent pack, like the folder of a
+/// season in a TV series pack.
+#[allow(clippy::module_name_repetitions)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, sqlx::FromRow, ToSchema)]
+pub struct TorrentFileGroup {
+    pub name: String,
+    /// The files whose path, relative to the torrent root folder, starts with
+    /// this prefix belong to the group. For example: `Season 1/`.
+    pub path: String,
+}
+
+/// A file group with the number of files in it and their total size.
+#[allow(clippy::module_name_repetitions)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+pub struct TorrentFileGroupSummary {
+    pub name: String,
+    pub path: String,
+    pub files: u64,
+    pub size: i64,
+}
+
+/// A file in a torrent pack with the group it belongs to, if any.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+pub struct PackFile {
+    /// The file path relative to the torrent root folder, with `/` as
+    /// separator.
+    pub path: String,
+    pub length: i64,
+    pub group: Option<String>,
+}
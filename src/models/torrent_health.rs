@@ -0,0 +1,29 @@
+use serde::{Deserialize, Serialize};
+
+use super::torrent::TorrentId;
+
+/// The current seeders of an approved torrent and its health so far.
+#[allow(clippy::module_name_repetitions)]
+#[derive(Debug, Clone, PartialEq, sqlx::FromRow)]
+pub struct TorrentHealthSample {
+    pub torrent_id: TorrentId,
+    pub seeders: i64,
+    /// `None` if the health of the torrent has not been calculated yet.
+    pub health_score: Option<f64>,
+    pub zero_seeders_since: Option<String>,
+}
+
+/// An approved torrent without seeders for longer than the configured
+/// period.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, sqlx::FromRow)]
+pub struct DeadTorrent {
+    pub torrent_id: TorrentId,
+    pub info_hash: String,
+    pub title: String,
+    pub seeders: i64,
+    pub health_score: f64,
+    pub zero_seeders_since: String,
+    /// When the torrent was flagged as dead. `None` if it has not been
+    /// flagged yet.
+    pub date_flagged: Option<String>,
+}
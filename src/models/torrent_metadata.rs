@@ -0,0 +1,25 @@
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+
+use super::torrent::TorrentId;
+
+/// The metadata of a torrent listing fetched from an external provider, like
+/// TMDB or `MusicBrainz`.
+#[allow(clippy::module_name_repetitions)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, sqlx::FromRow, ToSchema)]
+pub struct TorrentMetadata {
+    pub torrent_id: TorrentId,
+    /// `tmdb` or `musicbrainz`.
+    pub provider: String,
+    /// The ID of the title in the provider, like `tmdb:movie/603`.
+    pub external_id: String,
+    /// The canonical title.
+    pub title: String,
+    /// The release year.
+    pub year: Option<i64>,
+    pub synopsis: Option<String>,
+    pub poster_url: Option<String>,
+    /// The page of the title in the provider website.
+    pub external_url: Option<String>,
+    pub date_updated: String,
+}
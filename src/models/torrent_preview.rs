@@ -0,0 +1,22 @@
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+
+use super::torrent::TorrentId;
+
+#[allow(clippy::module_name_repetitions)]
+pub type PreviewId = i64;
+
+/// A file in the torrent attached as a preview.
+#[allow(clippy::module_name_repetitions)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, sqlx::FromRow, ToSchema)]
+pub struct TorrentPreview {
+    pub preview_id: PreviewId,
+    pub torrent_id: TorrentId,
+    /// Position of the file in the torrent. It's always `0` in single-file
+    /// torrents.
+    pub file_index: i64,
+    /// The file path in the torrent, with `/` as separator.
+    pub path: String,
+    pub content_type: String,
+    pub size: i64,
+}
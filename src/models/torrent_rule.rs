@@ -0,0 +1,33 @@
+use super::torrent::TorrentId;
+use super::torrent_tag::TagId;
+
+/// An approved torrent that a torrent rule has not been applied to yet.
+#[allow(clippy::module_name_repetitions)]
+#[derive(Debug, Clone, PartialEq, Eq, sqlx::FromRow)]
+pub struct TorrentRuleCandidate {
+    pub torrent_id: TorrentId,
+    pub info_hash: String,
+    pub title: String,
+    /// Name of the category, if any.
+    pub category: Option<String>,
+    /// Comma-separated IDs of the tags of the torrent.
+    pub tag_ids: Option<String>,
+    pub date_uploaded: String,
+    pub seeders: i64,
+    pub uploader: String,
+    /// Email of the uploader. Empty if the uploader has no email.
+    pub uploader_email: String,
+}
+
+impl TorrentRuleCandidate {
+    /// It returns the IDs of the tags of the torrent.
+    #[must_use]
+    pub fn tag_ids(&self) -> Vec<TagId> {
+        self.tag_ids
+            .as_deref()
+            .unwrap_or_default()
+            .split(',')
+            .filter_map(|tag_id| tag_id.trim().parse().ok())
+            .collect()
+    }
+}
@@ -1,10 +1,35 @@
 use serde::{Deserialize, Serialize};
 use sqlx::FromRow;
+use utoipa::ToSchema;
 
 pub type TagId = i64;
 
-#[derive(Debug, Serialize, Deserialize, PartialEq, Eq, PartialOrd, Ord, FromRow)]
+#[derive(Debug, Serialize, Deserialize, PartialEq, Eq, PartialOrd, Ord, FromRow, ToSchema)]
 pub struct TorrentTag {
     pub tag_id: TagId,
     pub name: String,
 }
+
+/// A tag ranked by the growth of its activity in the last window.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq, ToSchema)]
+pub struct TrendingTag {
+    pub tag_id: TagId,
+    pub name: String,
+    /// Approved uploads with the tag in the last window.
+    pub uploads: i64,
+    /// `.torrent` downloads of the torrents with the tag in the last window.
+    pub downloads: i64,
+    /// Uploads plus downloads in the last window minus the ones in the
+    /// previous window.
+    pub growth: i64,
+}
+
+/// An alternative name for a tag. Searches for the synonym find the torrents
+/// with the tag.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq, FromRow, ToSchema)]
+pub struct TagSynonym {
+    pub name: String,
+    pub tag_id: TagId,
+    /// The name of the tag the synonym points to.
+    pub tag_name: String,
+}
@@ -0,0 +1,18 @@
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+
+use super::torrent::TorrentId;
+
+#[allow(clippy::module_name_repetitions)]
+pub type WebSeedId = i64;
+
+/// An HTTP web seed (BEP 19) of a torrent listing. Web seeds are added to the
+/// `url-list` of the downloaded torrent files and to the magnet links.
+#[allow(clippy::module_name_repetitions)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, sqlx::FromRow, ToSchema)]
+pub struct TorrentWebSeed {
+    pub web_seed_id: WebSeedId,
+    pub torrent_id: TorrentId,
+    pub url: String,
+    pub date_created: String,
+}
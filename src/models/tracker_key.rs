@@ -1,5 +1,6 @@
 use serde::{Deserialize, Serialize};
 use sqlx::FromRow;
+use utoipa::ToSchema;
 
 #[derive(Debug, Serialize, Deserialize, FromRow)]
 pub struct TrackerKey {
@@ -12,3 +13,24 @@ pub struct Duration {
     pub secs: i64,
     pub nanos: i64,
 }
+
+/// The status of a user's tracker key.
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct TrackerKeyStatus {
+    /// Whether the user has a key valid for at least one more hour. Otherwise,
+    /// a new key is issued with the next torrent download.
+    pub has_key: bool,
+    /// When the key expires, in seconds since the Unix epoch.
+    pub valid_until: Option<i64>,
+    /// Whether the tracker accepts the key, or `None` if the user has no key.
+    pub accepted_by_tracker: Option<bool>,
+}
+
+/// A user's new tracker key.
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct RegeneratedTrackerKey {
+    /// The personal announce URL with the new key.
+    pub announce_url: String,
+    /// When the key expires, in seconds since the Unix epoch.
+    pub valid_until: i64,
+}
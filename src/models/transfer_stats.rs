@@ -0,0 +1,42 @@
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+
+/// Bytes uploaded and downloaded by a user, as reported by the tracker for
+/// all the user's keys.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+pub struct TransferStats {
+    pub uploaded: u64,
+    pub downloaded: u64,
+}
+
+impl TransferStats {
+    /// The uploaded bytes divided by the downloaded bytes. It's `None` when
+    /// the user has not downloaded anything.
+    #[must_use]
+    #[allow(clippy::cast_precision_loss)]
+    pub fn ratio(&self) -> Option<f64> {
+        if self.downloaded == 0 {
+            return None;
+        }
+
+        Some(self.uploaded as f64 / self.downloaded as f64)
+    }
+}
+
+/// The transfer stats of a user in their profile.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, ToSchema)]
+pub struct UserTransferStats {
+    pub stats: TransferStats,
+    /// The upload ratio. It's `null` when the user has not downloaded
+    /// anything.
+    pub ratio: Option<f64>,
+}
+
+impl From<TransferStats> for UserTransferStats {
+    fn from(stats: TransferStats) -> Self {
+        Self {
+            ratio: stats.ratio(),
+            stats,
+        }
+    }
+}
@@ -0,0 +1,32 @@
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+
+/// Upload limits of a user. `None` means unlimited.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+pub struct UploadLimits {
+    /// Max number of torrents uploaded in the last 24 hours.
+    pub max_uploads_per_day: Option<u32>,
+    /// Max total size in bytes of the contents of the uploaded torrents.
+    pub max_total_upload_size: Option<u64>,
+}
+
+/// Uploads of a user counted by the quotas.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+pub struct UploadUsage {
+    /// Torrents and pending magnet uploads in the last 24 hours.
+    pub uploads_last_day: u64,
+    /// Total size in bytes of the contents of the uploaded torrents.
+    pub total_upload_size: u64,
+}
+
+/// The upload quota of a user.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+pub struct UploadQuota {
+    pub username: String,
+    /// The limits that apply to the user.
+    pub limits: UploadLimits,
+    /// Whether the limits were set for the user by an administrator instead
+    /// of coming from the `[limits]` configuration.
+    pub overridden: bool,
+    pub usage: UploadUsage,
+}
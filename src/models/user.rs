@@ -3,6 +3,10 @@ use std::str::FromStr;
 
 use regex::Regex;
 use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+use utoipa::ToSchema;
+
+use super::user_session::SessionId;
 
 #[allow(clippy::module_name_repetitions)]
 pub type UserId = i64;
@@ -41,6 +45,21 @@ pub struct UserCompact {
     pub administrator: bool,
 }
 
+/// The public profile of a user. It does not include private data like the
+/// email address.
+#[allow(clippy::module_name_repetitions)]
+#[derive(Debug, PartialEq, Eq, Serialize, Deserialize, Clone, sqlx::FromRow, ToSchema)]
+pub struct PublicProfile {
+    pub username: String,
+    pub date_registered: Option<String>,
+    pub bio: Option<String>,
+    /// The avatar image URL. It can be an external URL or an image uploaded
+    /// to the index storage.
+    pub avatar: Option<String>,
+    /// Number of approved torrents uploaded by the user.
+    pub uploads: i64,
+}
+
 #[allow(clippy::module_name_repetitions)]
 #[derive(Debug, Serialize, Deserialize, Clone, sqlx::FromRow)]
 pub struct UserFull {
@@ -55,11 +74,36 @@ pub struct UserFull {
     pub avatar: String,
 }
 
+/// A user who has not logged in for a while. Refer to the
+/// [`inactivity`](crate::services::inactivity) service.
+#[allow(clippy::module_name_repetitions)]
+#[derive(Debug, PartialEq, Eq, Serialize, Deserialize, Clone, sqlx::FromRow)]
+pub struct InactiveUser {
+    pub user_id: UserId,
+    pub username: String,
+    pub email: String,
+    pub email_verified: bool,
+    /// Date of the last login or, if the user never logged in, the
+    /// registration date.
+    pub last_activity: String,
+    /// Date when the user was warned about the inactivity.
+    pub date_inactivity_warning: Option<String>,
+    /// Number of torrents uploaded by the user.
+    pub uploads: i64,
+}
+
 #[allow(clippy::module_name_repetitions)]
 #[derive(Debug, Serialize, Deserialize, Clone)]
 pub struct UserClaims {
     pub user: UserCompact,
     pub exp: u64, // epoch in seconds
+    /// The ID of the session. Tokens issued before sessions were introduced
+    /// don't have it.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub jti: Option<SessionId>,
+    /// Claims configured in the `[auth.jwt.extra_claims]` section.
+    #[serde(flatten)]
+    pub extra: Map<String, Value>,
 }
 
 const MAX_USERNAME_LENGTH: usize = 20;
@@ -0,0 +1,30 @@
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+
+use super::user::UserId;
+
+/// The ID of a session. It's the `jti` claim of the tokens issued for the
+/// session.
+#[allow(clippy::module_name_repetitions)]
+pub type SessionId = String;
+
+/// A session started when the user logged in. Renewed tokens keep the
+/// session of the original token.
+#[allow(clippy::module_name_repetitions)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, ToSchema, sqlx::FromRow)]
+pub struct UserSession {
+    pub session_id: SessionId,
+    #[serde(skip)]
+    pub user_id: UserId,
+    /// The `User-Agent` of the client used to log in.
+    pub user_agent: Option<String>,
+    /// The IP address of the client used to log in.
+    pub ip: Option<String>,
+    pub date_created: String,
+    /// The last time the session was used, with a resolution of one minute.
+    pub last_seen: String,
+    pub expires_at: String,
+    /// Whether the session is the one used in the request.
+    #[sqlx(skip)]
+    pub current: bool,
+}
@@ -0,0 +1,100 @@
+//! Personal announce keys.
+//!
+//! In private mode, the announce URLs in the downloaded torrent files include
+//! a key issued by the tracker for each user. Users can check the tracker
+//! still accepts their key and regenerate it if it was compromised, for
+//! example, because they shared a torrent file.
+use std::sync::Arc;
+
+use super::authorization::{self, ACTION};
+use crate::config::Configuration;
+use crate::errors::ServiceError;
+use crate::models::tracker_key::{RegeneratedTrackerKey, TrackerKeyStatus};
+use crate::models::user::UserId;
+use crate::tracker;
+
+pub struct Service {
+    configuration: Arc<Configuration>,
+    tracker_service: Arc<tracker::service::Service>,
+    authorization_service: Arc<authorization::Service>,
+}
+
+impl Service {
+    #[must_use]
+    pub fn new(
+        configuration: Arc<Configuration>,
+        tracker_service: Arc<tracker::service::Service>,
+        authorization_service: Arc<authorization::Service>,
+    ) -> Service {
+        Service {
+            configuration,
+            tracker_service,
+            authorization_service,
+        }
+    }
+
+    /// It returns the status of the user's key, checking the tracker accepts
+    /// it.
+    ///
+    /// # Errors
+    ///
+    /// It returns an error if:
+    ///
+    /// * The user is not logged in.
+    /// * The index is not in private mode.
+    /// * The announce request to check the key fails.
+    pub async fn get_status(&self, maybe_user_id: Option<UserId>) -> Result<TrackerKeyStatus, ServiceError> {
+        let user_id = self.authorize(maybe_user_id).await?;
+
+        let Some(tracker_key) = self.tracker_service.get_user_tracker_key(user_id).await else {
+            return Ok(TrackerKeyStatus {
+                has_key: false,
+                valid_until: None,
+                accepted_by_tracker: None,
+            });
+        };
+
+        let accepted_by_tracker = self.tracker_service.is_tracker_key_accepted(&tracker_key).await?;
+
+        Ok(TrackerKeyStatus {
+            has_key: true,
+            valid_until: Some(tracker_key.valid_until),
+            accepted_by_tracker: Some(accepted_by_tracker),
+        })
+    }
+
+    /// It revokes the user's key and issues a new one. The torrent files
+    /// downloaded from now on include the new key.
+    ///
+    /// # Errors
+    ///
+    /// It returns an error if:
+    ///
+    /// * The user is not logged in.
+    /// * The index is not in private mode.
+    /// * The tracker API requests fail.
+    pub async fn regenerate(&self, maybe_user_id: Option<UserId>) -> Result<RegeneratedTrackerKey, ServiceError> {
+        let user_id = self.authorize(maybe_user_id).await?;
+
+        let tracker_key = self.tracker_service.regenerate_tracker_key(user_id).await?;
+
+        Ok(RegeneratedTrackerKey {
+            announce_url: self.tracker_service.announce_url_with_key(&tracker_key).to_string(),
+            valid_until: tracker_key.valid_until,
+        })
+    }
+
+    async fn authorize(&self, maybe_user_id: Option<UserId>) -> Result<UserId, ServiceError> {
+        self.authorization_service
+            .authorize(ACTION::ManageAnnounceKey, maybe_user_id)
+            .await?;
+
+        let user_id = maybe_user_id.ok_or(ServiceError::UnauthorizedActionForGuests)?;
+
+        if !self.configuration.settings.read().await.tracker.private {
+            return Err(ServiceError::AnnounceKeysNotUsed);
+        }
+
+        Ok(user_id)
+    }
+}
@@ -0,0 +1,314 @@
+//! Anti-spam checks for the uploads.
+//!
+//! The title and the description of each upload are checked against the
+//! banned patterns managed by the administrators. Patterns are regular
+//! expressions matched ignoring case. Uploads matching a `reject` pattern are
+//! rejected, and uploads matching a `hold` pattern are held for moderation
+//! even if the uploader could publish them directly.
+//!
+//! When the `[antispam]` section is present, descriptions with too many links
+//! are also held for moderation:
+//!
+//! ```toml
+//! [antispam]
+//! max_links = 10
+//! max_link_density = 0.3
+//! ```
+use std::sync::Arc;
+
+use lazy_static::lazy_static;
+use regex::{Regex, RegexBuilder};
+use serde::Deserialize;
+use tracing::warn;
+use utoipa::ToSchema;
+
+use super::audit;
+use super::authorization::{self, ACTION};
+use crate::config::{Antispam, Configuration};
+use crate::databases::database::{Database, Error};
+use crate::errors::ServiceError;
+use crate::models::antispam::{BannedPattern, PatternAction, PatternId};
+use crate::models::audit::AuditAction;
+use crate::models::user::UserId;
+
+/// Max length of a banned pattern.
+const MAX_PATTERN_LENGTH: usize = 255;
+
+lazy_static! {
+    static ref LINK_REGEX: Regex = Regex::new(r"(?i)\b(?:https?://|www\.)\S+").expect("link regexp should be valid");
+}
+
+/// The result of checking an upload.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Verdict {
+    Clean,
+    /// The upload must be held for moderation, for the given reason.
+    Suspicious(String),
+}
+
+#[derive(Debug, Deserialize, ToSchema)]
+pub struct AddBannedPatternRequest {
+    /// A regular expression. It's matched ignoring case.
+    pub pattern: String,
+    pub action: PatternAction,
+}
+
+pub struct Service {
+    configuration: Arc<Configuration>,
+    banned_pattern_repository: Arc<DbBannedPatternRepository>,
+    authorization_service: Arc<authorization::Service>,
+    audit_service: Arc<audit::Service>,
+}
+
+impl Service {
+    #[must_use]
+    pub fn new(
+        configuration: Arc<Configuration>,
+        banned_pattern_repository: Arc<DbBannedPatternRepository>,
+        authorization_service: Arc<authorization::Service>,
+        audit_service: Arc<audit::Service>,
+    ) -> Service {
+        Service {
+            configuration,
+            banned_pattern_repository,
+            authorization_service,
+            audit_service,
+        }
+    }
+
+    /// It checks the title and the description of an upload.
+    ///
+    /// # Errors
+    ///
+    /// It returns an error if:
+    ///
+    /// * The upload matches a banned pattern which rejects uploads.
+    /// * There is a database error.
+    pub async fn check_upload(&self, title: &str, description: &str) -> Result<Verdict, ServiceError> {
+        let mut verdict = Verdict::Clean;
+
+        for banned_pattern in self.banned_pattern_repository.get_all().await? {
+            let Ok(regex) = RegexBuilder::new(&banned_pattern.pattern).case_insensitive(true).build() else {
+                warn!(target: "antispam", "Ignoring invalid banned pattern #{}", banned_pattern.pattern_id);
+                continue;
+            };
+
+            if !regex.is_match(title) && !regex.is_match(description) {
+                continue;
+            }
+
+            if banned_pattern.rejects() {
+                return Err(ServiceError::SpamDetected);
+            }
+
+            if verdict == Verdict::Clean {
+                verdict = Verdict::Suspicious(format!("matches banned pattern #{}", banned_pattern.pattern_id));
+            }
+        }
+
+        if verdict != Verdict::Clean {
+            return Ok(verdict);
+        }
+
+        let settings = self.configuration.settings.read().await;
+
+        Ok(settings
+            .antispam
+            .as_ref()
+            .map_or(Verdict::Clean, |antispam| link_heuristics(antispam, description)))
+    }
+
+    /// It returns all the banned patterns.
+    ///
+    /// # Errors
+    ///
+    /// It returns an error if:
+    ///
+    /// * The user is not an administrator.
+    /// * There is a database error.
+    pub async fn get_patterns(&self, maybe_user_id: Option<UserId>) -> Result<Vec<BannedPattern>, ServiceError> {
+        self.authorization_service
+            .authorize(ACTION::ManageAntispam, maybe_user_id)
+            .await?;
+
+        Ok(self.banned_pattern_repository.get_all().await?)
+    }
+
+    /// It adds a banned pattern.
+    ///
+    /// # Errors
+    ///
+    /// It returns an error if:
+    ///
+    /// * The user is not an administrator.
+    /// * The pattern is empty, too long or not a valid regular expression.
+    /// * The pattern already exists.
+    /// * There is a database error.
+    pub async fn add_pattern(
+        &self,
+        request: &AddBannedPatternRequest,
+        maybe_user_id: Option<UserId>,
+    ) -> Result<PatternId, ServiceError> {
+        self.authorization_service
+            .authorize(ACTION::ManageAntispam, maybe_user_id)
+            .await?;
+
+        let Some(user_id) = maybe_user_id else {
+            return Err(ServiceError::UnauthorizedActionForGuests);
+        };
+
+        let pattern = request.pattern.trim();
+
+        if pattern.is_empty() || pattern.len() > MAX_PATTERN_LENGTH || Regex::new(pattern).is_err() {
+            return Err(ServiceError::InvalidBannedPattern);
+        }
+
+        let pattern_id = self.banned_pattern_repository.add(pattern, &request.action, user_id).await?;
+
+        self.audit_service
+            .record(
+                maybe_user_id,
+                AuditAction::AntispamPatternAdd,
+                &pattern_id.to_string(),
+                Some(&format!("{} ({})", pattern, request.action)),
+            )
+            .await;
+
+        Ok(pattern_id)
+    }
+
+    /// It deletes a banned pattern.
+    ///
+    /// # Errors
+    ///
+    /// It returns an error if:
+    ///
+    /// * The user is not an administrator.
+    /// * The pattern does not exist.
+    /// * There is a database error.
+    pub async fn delete_pattern(&self, pattern_id: PatternId, maybe_user_id: Option<UserId>) -> Result<(), ServiceError> {
+        self.authorization_service
+            .authorize(ACTION::ManageAntispam, maybe_user_id)
+            .await?;
+
+        self.banned_pattern_repository.delete(pattern_id).await?;
+
+        self.audit_service
+            .record(
+                maybe_user_id,
+                AuditAction::AntispamPatternDelete,
+                &pattern_id.to_string(),
+                None,
+            )
+            .await;
+
+        Ok(())
+    }
+}
+
+/// It holds the descriptions with too many links, or with more links than
+/// text.
+fn link_heuristics(antispam: &Antispam, description: &str) -> Verdict {
+    let links = LINK_REGEX.find_iter(description).count();
+
+    if links == 0 {
+        return Verdict::Clean;
+    }
+
+    if links > antispam.max_links {
+        return Verdict::Suspicious(format!("{links} links in the description"));
+    }
+
+    let words = description.split_whitespace().count();
+
+    #[allow(clippy::cast_precision_loss)]
+    let link_density = links as f64 / words as f64;
+
+    if link_density > antispam.max_link_density {
+        return Verdict::Suspicious(format!("link density of {link_density:.2} in the description"));
+    }
+
+    Verdict::Clean
+}
+
+pub struct DbBannedPatternRepository {
+    database: Arc<Box<dyn Database>>,
+}
+
+impl DbBannedPatternRepository {
+    #[must_use]
+    pub fn new(database: Arc<Box<dyn Database>>) -> Self {
+        Self { database }
+    }
+
+    /// It returns all the banned patterns.
+    ///
+    /// # Errors
+    ///
+    /// It returns an error if there is a database error.
+    pub async fn get_all(&self) -> Result<Vec<BannedPattern>, Error> {
+        self.database.get_banned_patterns().await
+    }
+
+    /// It adds a banned pattern.
+    ///
+    /// # Errors
+    ///
+    /// It returns an error if the pattern already exists or there is a
+    /// database error.
+    pub async fn add(&self, pattern: &str, action: &PatternAction, created_by: UserId) -> Result<PatternId, Error> {
+        self.database.insert_banned_pattern(pattern, action, created_by).await
+    }
+
+    /// It deletes a banned pattern.
+    ///
+    /// # Errors
+    ///
+    /// It returns an error if the pattern does not exist or there is a
+    /// database error.
+    pub async fn delete(&self, pattern_id: PatternId) -> Result<(), Error> {
+        self.database.delete_banned_pattern(pattern_id).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{link_heuristics, Verdict};
+    use crate::config::Antispam;
+
+    #[test]
+    fn descriptions_without_links_should_be_clean() {
+        assert_eq!(
+            link_heuristics(&Antispam::default(), "Ubuntu 24.04 desktop image"),
+            Verdict::Clean
+        );
+    }
+
+    #[test]
+    fn descriptions_with_a_few_links_in_the_text_should_be_clean() {
+        let description = "Official image downloaded from https://ubuntu.com/download with the checksums verified";
+
+        assert_eq!(link_heuristics(&Antispam::default(), description), Verdict::Clean);
+    }
+
+    #[test]
+    fn descriptions_with_too_many_links_should_be_suspicious() {
+        let antispam = Antispam {
+            max_links: 2,
+            ..Antispam::default()
+        };
+        let description = "Mirrors: http://a.example http://b.example and www.c.example for faster downloads of the image";
+
+        assert!(matches!(link_heuristics(&antispam, description), Verdict::Suspicious(_)));
+    }
+
+    #[test]
+    fn descriptions_with_mostly_links_should_be_suspicious() {
+        let description = "free https://spam.example/a https://spam.example/b";
+
+        assert!(matches!(
+            link_heuristics(&Antispam::default(), description),
+            Verdict::Suspicious(_)
+        ));
+    }
+}
@@ -0,0 +1,329 @@
+//! API usage statistics for users.
+//!
+//! The [`rate_limit`](crate::web::api::server::rate_limit) middleware counts
+//! the requests made by logged-in users with each credential: requests per
+//! day, requests rejected by the rate limiter and the IP address of the last
+//! request. The counters are stored every minute by the `api_usage` cronjob.
+//!
+//! Users can review the usage of their credentials to detect leaked ones, for
+//! example a token used from an unknown IP address.
+//!
+//! Credentials are identified by a prefix of their SHA-256 hash, so they are
+//! never stored. The stored usage counts as API access logs for the
+//! `[retention]` policy.
+//!
+//! Administrators can also inspect the current rate limit buckets of a user
+//! or an IP address, to debug the limits reported by API consumers.
+use std::collections::{BTreeMap, HashMap};
+use std::net::IpAddr;
+use std::sync::Arc;
+use std::time::Instant;
+
+use chrono::{TimeDelta, Utc};
+use serde::Deserialize;
+use sha2::{Digest, Sha256};
+use utoipa::IntoParams;
+
+use super::authorization::{self, ACTION};
+use crate::databases::database::{Database, Error};
+use crate::errors::ServiceError;
+use crate::models::api_usage::{ApiUsageRecord, ApiUsageSummary, CredentialUsage, DailyApiUsage, RateLimitCounter};
+use crate::models::user::UserId;
+use crate::web::api::server::rate_limit::RateLimiter;
+
+/// Number of days included in the usage summary, including today.
+pub const USAGE_SUMMARY_DAYS: u32 = 30;
+
+/// Number of hex characters of the credential hash used as credential ID.
+const CREDENTIAL_HASH_PREFIX_LENGTH: usize = 12;
+
+/// It returns the ID used in the usage statistics for a token.
+#[must_use]
+pub fn credential_id(token: &str) -> String {
+    let hash = hex::encode(Sha256::digest(token.as_bytes()));
+
+    format!("token-{}", &hash[..CREDENTIAL_HASH_PREFIX_LENGTH])
+}
+
+/// Filter for the rate limit counters. Without filters, it returns the
+/// counters of all the clients.
+#[derive(Debug, Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
+pub struct RateLimitCountersRequest {
+    /// Only the counters of this user. Eg: `?username=bob`
+    pub username: Option<String>,
+    /// Only the counters of this guest IP address. Eg: `?ip=203.0.113.7`
+    #[param(value_type = Option<String>)]
+    pub ip: Option<IpAddr>,
+}
+
+pub struct Service {
+    rate_limiter: Arc<RateLimiter>,
+    api_usage_repository: Arc<DbApiUsageRepository>,
+    authorization_service: Arc<authorization::Service>,
+}
+
+impl Service {
+    #[must_use]
+    pub fn new(
+        rate_limiter: Arc<RateLimiter>,
+        api_usage_repository: Arc<DbApiUsageRepository>,
+        authorization_service: Arc<authorization::Service>,
+    ) -> Service {
+        Service {
+            rate_limiter,
+            api_usage_repository,
+            authorization_service,
+        }
+    }
+
+    /// It stores the usage counters recorded by the rate limiter since the
+    /// last call. It returns the number of stored counters.
+    ///
+    /// # Errors
+    ///
+    /// It returns an error if there is a database error. The counters not
+    /// stored yet are lost.
+    pub async fn flush(&self) -> Result<usize, Error> {
+        let records = self.rate_limiter.take_usage();
+
+        for record in &records {
+            self.api_usage_repository.add(record).await?;
+        }
+
+        Ok(records.len())
+    }
+
+    /// It returns the API usage of the logged-in user in the last
+    /// [`USAGE_SUMMARY_DAYS`] days. The `current_credential` is the ID of
+    /// the credential used in the request, if any.
+    ///
+    /// # Errors
+    ///
+    /// It returns an error if:
+    ///
+    /// * The user is not logged in.
+    /// * The user does not have permissions to see the API usage.
+    /// * There is a database error.
+    pub async fn get_summary(
+        &self,
+        maybe_user_id: Option<UserId>,
+        current_credential: Option<&str>,
+    ) -> Result<ApiUsageSummary, ServiceError> {
+        let Some(user_id) = maybe_user_id else {
+            return Err(ServiceError::UnauthorizedActionForGuests);
+        };
+
+        self.authorization_service
+            .authorize(ACTION::GetApiUsage, maybe_user_id)
+            .await?;
+
+        let since = (Utc::now() - TimeDelta::days(i64::from(USAGE_SUMMARY_DAYS - 1)))
+            .format("%Y-%m-%d")
+            .to_string();
+
+        let mut records = self.api_usage_repository.get(user_id, &since).await?;
+
+        records.extend(self.rate_limiter.pending_usage(user_id));
+
+        Ok(summarize(&records, current_credential))
+    }
+
+    /// It returns the current state of the rate limit buckets. The list is
+    /// empty when rate limits are not configured.
+    ///
+    /// # Errors
+    ///
+    /// It returns an error if:
+    ///
+    /// * The user does not have permissions to inspect the rate limits.
+    /// * The user in the filter does not exist.
+    pub async fn get_rate_limit_counters(
+        &self,
+        request: &RateLimitCountersRequest,
+        maybe_user_id: Option<UserId>,
+    ) -> Result<Vec<RateLimitCounter>, ServiceError> {
+        self.authorization_service
+            .authorize(ACTION::GetRateLimitCounters, maybe_user_id)
+            .await?;
+
+        let user_id = match &request.username {
+            Some(username) => Some(self.api_usage_repository.get_user_id(username).await?),
+            None => None,
+        };
+
+        Ok(self.rate_limiter.counters(user_id, request.ip, Instant::now()))
+    }
+}
+
+/// It aggregates the usage counters by day and by credential.
+fn summarize(records: &[ApiUsageRecord], current_credential: Option<&str>) -> ApiUsageSummary {
+    let mut daily: BTreeMap<&str, DailyApiUsage> = BTreeMap::new();
+    let mut credentials: HashMap<&str, CredentialUsage> = HashMap::new();
+
+    for record in records {
+        let day = daily.entry(&record.date).or_insert_with(|| DailyApiUsage {
+            date: record.date.clone(),
+            requests: 0,
+            rate_limit_hits: 0,
+        });
+
+        day.requests += record.requests;
+        day.rate_limit_hits += record.rate_limit_hits;
+
+        let credential = credentials.entry(&record.credential).or_insert_with(|| CredentialUsage {
+            credential: record.credential.clone(),
+            current: current_credential == Some(record.credential.as_str()),
+            requests: 0,
+            rate_limit_hits: 0,
+            first_used_date: record.date.clone(),
+            last_used: record.last_used.clone(),
+            last_ip: record.last_ip.clone(),
+        });
+
+        credential.requests += record.requests;
+        credential.rate_limit_hits += record.rate_limit_hits;
+
+        if record.date < credential.first_used_date {
+            credential.first_used_date = record.date.clone();
+        }
+
+        if record.last_used >= credential.last_used {
+            credential.last_used = record.last_used.clone();
+
+            if record.last_ip.is_some() {
+                credential.last_ip = record.last_ip.clone();
+            }
+        }
+    }
+
+    let mut credentials: Vec<CredentialUsage> = credentials.into_values().collect();
+
+    credentials.sort_by(|a, b| b.last_used.cmp(&a.last_used).then_with(|| a.credential.cmp(&b.credential)));
+
+    ApiUsageSummary {
+        days: USAGE_SUMMARY_DAYS,
+        daily: daily.into_values().collect(),
+        credentials,
+    }
+}
+
+pub struct DbApiUsageRepository {
+    database: Arc<Box<dyn Database>>,
+}
+
+impl DbApiUsageRepository {
+    #[must_use]
+    pub fn new(database: Arc<Box<dyn Database>>) -> Self {
+        Self { database }
+    }
+
+    /// It adds the usage counters to the stored ones.
+    ///
+    /// # Errors
+    ///
+    /// It returns an error if there is a database error.
+    pub async fn add(&self, record: &ApiUsageRecord) -> Result<(), Error> {
+        self.database.add_user_api_usage(record).await
+    }
+
+    /// It returns the stored usage counters of a user since a day.
+    ///
+    /// # Errors
+    ///
+    /// It returns an error if there is a database error.
+    pub async fn get(&self, user_id: UserId, since: &str) -> Result<Vec<ApiUsageRecord>, Error> {
+        self.database.get_user_api_usage(user_id, since).await
+    }
+
+    /// It returns the ID of a user.
+    ///
+    /// # Errors
+    ///
+    /// It returns an error if the user does not exist.
+    pub async fn get_user_id(&self, username: &str) -> Result<UserId, Error> {
+        self.database
+            .get_user_profile_from_username(username)
+            .await
+            .map(|user_profile| user_profile.user_id)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{credential_id, summarize};
+    use crate::models::api_usage::{ApiUsageRecord, DailyApiUsage};
+
+    fn record(credential: &str, date: &str, requests: i64, last_ip: Option<&str>, last_used: &str) -> ApiUsageRecord {
+        ApiUsageRecord {
+            user_id: 1,
+            credential: credential.to_string(),
+            date: date.to_string(),
+            requests,
+            rate_limit_hits: 0,
+            last_ip: last_ip.map(ToString::to_string),
+            last_used: last_used.to_string(),
+        }
+    }
+
+    #[test]
+    fn it_should_never_include_the_token_in_the_credential_id() {
+        let id = credential_id("eyJ0eXAiOiJKV1QiLCJhbGciOiJIUzI1NiJ9.secret");
+
+        assert!(id.starts_with("token-"));
+        assert_eq!(id.len(), "token-".len() + 12);
+        assert_eq!(id, credential_id("eyJ0eXAiOiJKV1QiLCJhbGciOiJIUzI1NiJ9.secret"));
+        assert_ne!(id, credential_id("another-token"));
+    }
+
+    #[test]
+    fn it_should_aggregate_the_requests_per_day() {
+        let summary = summarize(
+            &[
+                record("token-a", "2024-10-17", 5, None, "2024-10-17 10:00:00"),
+                record("token-b", "2024-10-16", 2, None, "2024-10-16 10:00:00"),
+                record("token-a", "2024-10-17", 1, None, "2024-10-17 11:00:00"),
+            ],
+            None,
+        );
+
+        assert_eq!(
+            summary.daily,
+            vec![
+                DailyApiUsage {
+                    date: "2024-10-16".to_string(),
+                    requests: 2,
+                    rate_limit_hits: 0
+                },
+                DailyApiUsage {
+                    date: "2024-10-17".to_string(),
+                    requests: 6,
+                    rate_limit_hits: 0
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn it_should_keep_the_last_ip_of_each_credential() {
+        let summary = summarize(
+            &[
+                record("token-a", "2024-10-16", 1, Some("10.0.0.1"), "2024-10-16 10:00:00"),
+                record("token-a", "2024-10-17", 1, Some("10.0.0.2"), "2024-10-17 10:00:00"),
+                record("token-b", "2024-10-15", 1, Some("10.0.0.3"), "2024-10-15 10:00:00"),
+            ],
+            Some("token-b"),
+        );
+
+        assert_eq!(summary.credentials.len(), 2);
+
+        assert_eq!(summary.credentials[0].credential, "token-a");
+        assert_eq!(summary.credentials[0].requests, 2);
+        assert_eq!(summary.credentials[0].first_used_date, "2024-10-16");
+        assert_eq!(summary.credentials[0].last_ip, Some("10.0.0.2".to_string()));
+        assert!(!summary.credentials[0].current);
+
+        assert_eq!(summary.credentials[1].credential, "token-b");
+        assert!(summary.credentials[1].current);
+    }
+}
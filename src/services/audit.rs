@@ -0,0 +1,207 @@
+//! Audit log service.
+//!
+//! It records the actions taken by administrators and moderators, like
+//! deleting a torrent, banning a user or changing the categories, so that
+//! they can be reviewed later.
+//!
+//! Recording an action never makes the action fail. Errors are only logged.
+//!
+//! The entries are deleted after the `security_events_days` period in the
+//! `[retention]` section, if any. Refer to the [`retention`](crate::services::retention)
+//! service.
+use std::sync::Arc;
+
+use chrono::{NaiveDate, NaiveTime, TimeDelta};
+use serde_derive::Deserialize;
+use tracing::error;
+use utoipa::IntoParams;
+
+use super::authorization::{self, ACTION};
+use crate::databases::database::{Database, Error};
+use crate::errors::ServiceError;
+use crate::models::audit::{AuditAction, AuditLogFilter};
+use crate::models::response::AuditLogResponse;
+use crate::models::user::UserId;
+use crate::utils::clock::DATETIME_FORMAT;
+
+/// Default number of entries per page.
+const DEFAULT_PAGE_SIZE: u8 = 50;
+
+/// Maximum number of entries per page.
+const MAX_PAGE_SIZE: u8 = 100;
+
+/// Administrator request to get a page of the audit log.
+#[derive(Debug, Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
+pub struct AuditLogRequest {
+    pub page_size: Option<u8>,
+    pub page: Option<u32>,
+    /// Only the actions taken by this user. Eg: `?actor=admin`
+    pub actor: Option<String>,
+    /// Only this type of action. Eg: `?action=torrent_delete`
+    pub action: Option<AuditAction>,
+    /// Only the actions taken on or after this day, in `YYYY-MM-DD` format.
+    pub from: Option<String>,
+    /// Only the actions taken on or before this day, in `YYYY-MM-DD` format.
+    pub to: Option<String>,
+}
+
+pub struct Service {
+    audit_log_repository: Arc<DbAuditLogRepository>,
+    authorization_service: Arc<authorization::Service>,
+}
+
+impl Service {
+    #[must_use]
+    pub fn new(audit_log_repository: Arc<DbAuditLogRepository>, authorization_service: Arc<authorization::Service>) -> Service {
+        Service {
+            audit_log_repository,
+            authorization_service,
+        }
+    }
+
+    /// It records an action in the audit log. The caller must have already
+    /// authorized the action.
+    pub async fn record(&self, actor_id: Option<UserId>, action: AuditAction, target: &str, details: Option<&str>) {
+        if let Err(err) = self.audit_log_repository.add(actor_id, &action, target, details).await {
+            error!(target: "audit", "Failed to record action {action} on {target}: {err:?}");
+        }
+    }
+
+    /// It returns a page of the audit log, from the newest to the oldest
+    /// entry.
+    ///
+    /// # Errors
+    ///
+    /// It returns an error if:
+    ///
+    /// * The user does not have permissions to read the audit log.
+    /// * The date range is not valid.
+    /// * There is a database error.
+    pub async fn get_log(
+        &self,
+        request: &AuditLogRequest,
+        maybe_user_id: Option<UserId>,
+    ) -> Result<AuditLogResponse, ServiceError> {
+        self.authorization_service
+            .authorize(ACTION::GetAuditLog, maybe_user_id)
+            .await?;
+
+        let filter = filter_from(request)?;
+
+        let page_size = request.page_size.unwrap_or(DEFAULT_PAGE_SIZE).min(MAX_PAGE_SIZE);
+        let offset = u64::from(request.page.unwrap_or(0)) * u64::from(page_size);
+
+        Ok(self.audit_log_repository.get_page(&filter, offset, page_size).await?)
+    }
+}
+
+/// It builds the database filter from the request. The `to` day is
+/// included.
+fn filter_from(request: &AuditLogRequest) -> Result<AuditLogFilter, ServiceError> {
+    let from = parse_day(request.from.as_deref())?;
+    let to = parse_day(request.to.as_deref())?;
+
+    if let (Some(from), Some(to)) = (from, to) {
+        if from > to {
+            return Err(ServiceError::InvalidAuditLogDateRange);
+        }
+    }
+
+    let start_of = |day: NaiveDate| day.and_time(NaiveTime::MIN).format(DATETIME_FORMAT).to_string();
+
+    Ok(AuditLogFilter {
+        actor: request
+            .actor
+            .as_deref()
+            .map(str::trim)
+            .filter(|actor| !actor.is_empty())
+            .map(ToString::to_string),
+        action: request.action,
+        since: from.map(start_of),
+        until: to.map(|to| start_of(to + TimeDelta::days(1))),
+    })
+}
+
+fn parse_day(day: Option<&str>) -> Result<Option<NaiveDate>, ServiceError> {
+    day.map(|day| NaiveDate::parse_from_str(day.trim(), "%Y-%m-%d").map_err(|_| ServiceError::InvalidAuditLogDateRange))
+        .transpose()
+}
+
+pub struct DbAuditLogRepository {
+    database: Arc<Box<dyn Database>>,
+}
+
+impl DbAuditLogRepository {
+    #[must_use]
+    pub fn new(database: Arc<Box<dyn Database>>) -> Self {
+        Self { database }
+    }
+
+    /// It adds an entry to the audit log.
+    ///
+    /// # Errors
+    ///
+    /// It returns an error if there is a database error.
+    pub async fn add(
+        &self,
+        actor_id: Option<UserId>,
+        action: &AuditAction,
+        target: &str,
+        details: Option<&str>,
+    ) -> Result<(), Error> {
+        self.database
+            .insert_audit_entry(actor_id, action, target, details)
+            .await
+            .map(|_| ())
+    }
+
+    /// It returns a page of the audit log.
+    ///
+    /// # Errors
+    ///
+    /// It returns an error if there is a database error.
+    pub async fn get_page(&self, filter: &AuditLogFilter, offset: u64, page_size: u8) -> Result<AuditLogResponse, Error> {
+        self.database.get_audit_log_paginated(filter, offset, page_size).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{filter_from, AuditLogRequest};
+    use crate::errors::ServiceError;
+    use crate::models::audit::AuditAction;
+
+    fn request(from: Option<&str>, to: Option<&str>) -> AuditLogRequest {
+        AuditLogRequest {
+            page_size: None,
+            page: None,
+            actor: Some(" admin ".to_string()),
+            action: Some(AuditAction::UserBan),
+            from: from.map(ToString::to_string),
+            to: to.map(ToString::to_string),
+        }
+    }
+
+    #[test]
+    fn it_should_include_the_whole_last_day_of_the_date_range() {
+        let filter = filter_from(&request(Some("2024-10-01"), Some("2024-10-31"))).unwrap();
+
+        assert_eq!(filter.actor, Some("admin".to_string()));
+        assert_eq!(filter.action, Some(AuditAction::UserBan));
+        assert_eq!(filter.since, Some("2024-10-01 00:00:00".to_string()));
+        assert_eq!(filter.until, Some("2024-11-01 00:00:00".to_string()));
+    }
+
+    #[test]
+    fn it_should_reject_invalid_date_ranges() {
+        assert_eq!(
+            filter_from(&request(Some("2024-10-31"), Some("2024-10-01"))),
+            Err(ServiceError::InvalidAuditLogDateRange)
+        );
+        assert_eq!(
+            filter_from(&request(Some("yesterday"), None)),
+            Err(ServiceError::InvalidAuditLogDateRange)
+        );
+    }
+}
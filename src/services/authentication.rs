@@ -1,24 +1,40 @@
 //! Authentication services.
+//!
+//! Passwords are stored as [PHC strings](https://github.com/P-H-C/phc-string-format/blob/master/phc-sf-spec.md),
+//! so every hash starts with the scheme used to generate it, for example,
+//! `$argon2id$v=19$m=19456,t=2,p=1$...`. New passwords are hashed with
+//! Argon2id. Hashes generated with other schemes (`pbkdf2-sha256`, imported
+//! from old versions) or with weaker Argon2 parameters are still verified,
+//! and they are transparently re-hashed with the current hasher the next
+//! time the user logs in, so users don't have to reset their passwords.
 use std::sync::Arc;
 
-use argon2::{Argon2, PasswordHash, PasswordVerifier};
+use argon2::password_hash::SaltString;
+use argon2::{Argon2, Params, PasswordHash, PasswordHasher, PasswordVerifier};
 use jsonwebtoken::{decode, encode, Algorithm, DecodingKey, EncodingKey, Header, Validation};
+use pbkdf2::password_hash::rand_core::OsRng;
 use pbkdf2::Pbkdf2;
 
+use super::session::{self, Client};
 use super::user::DbUserProfileRepository;
 use crate::config::Configuration;
 use crate::databases::database::{Database, Error};
 use crate::errors::ServiceError;
 use crate::models::user::{UserAuthentication, UserClaims, UserCompact, UserId};
+use crate::models::user_session::SessionId;
 use crate::services::user::Repository;
 use crate::utils::clock;
 
+/// Tokens are valid for two weeks.
+const TOKEN_VALIDITY_SECONDS: u64 = 1_209_600;
+
 pub struct Service {
     configuration: Arc<Configuration>,
     json_web_token: Arc<JsonWebToken>,
     user_repository: Arc<Box<dyn Repository>>,
     user_profile_repository: Arc<DbUserProfileRepository>,
     user_authentication_repository: Arc<DbUserAuthenticationRepository>,
+    session_service: Arc<session::Service>,
 }
 
 impl Service {
@@ -28,6 +44,7 @@ impl Service {
         user_repository: Arc<Box<dyn Repository>>,
         user_profile_repository: Arc<DbUserProfileRepository>,
         user_authentication_repository: Arc<DbUserAuthenticationRepository>,
+        session_service: Arc<session::Service>,
     ) -> Self {
         Self {
             configuration,
@@ -35,11 +52,13 @@ impl Service {
             user_repository,
             user_profile_repository,
             user_authentication_repository,
+            session_service,
         }
     }
 
     /// Authenticate user with username and password.
-    /// It returns a JWT token and a compact user profile.
+    /// It returns a JWT token and a compact user profile. A new session is
+    /// started for the client.
     ///
     /// # Errors
     ///
@@ -48,9 +67,11 @@ impl Service {
     /// * A `ServiceError::WrongPasswordOrUsername` if unable to get user profile.
     /// * A `ServiceError::InternalServerError` if unable to get user authentication data from the user id.
     /// * A `ServiceError::EmailNotVerified` if the email should be, but is not verified.
+    /// * A `ServiceError::AccountDisabled` if the account was disabled for inactivity.
     /// * An error if unable to verify the password.
     /// * An error if unable to get the user data from the database.
-    pub async fn login(&self, username: &str, password: &str) -> Result<(String, UserCompact), ServiceError> {
+    /// * An error if unable to start the session.
+    pub async fn login(&self, username: &str, password: &str, client: &Client) -> Result<(String, UserCompact), ServiceError> {
         // Get the user profile from database
         let user_profile = self
             .user_profile_repository
@@ -67,6 +88,10 @@ impl Service {
 
         verify_password(password.as_bytes(), &user_authentication).map_err(|_| ServiceError::WrongPasswordOrUsername)?;
 
+        if needs_rehash(&user_authentication.password_hash) {
+            self.rehash_password(&user_profile.user_id, password).await;
+        }
+
         let settings = self.configuration.settings.read().await;
 
         // Fail login if email verification is required and this email is not verified
@@ -81,10 +106,25 @@ impl Service {
         // Drop read lock on settings
         drop(settings);
 
+        self.check_not_disabled(&user_profile.user_id).await?;
+
         let user_compact = self.user_repository.get_compact(&user_profile.user_id).await?;
 
+        if let Err(e) = self
+            .user_authentication_repository
+            .update_last_login(&user_profile.user_id)
+            .await
+        {
+            tracing::error!("Failed to record the last login of user #{}: {e:?}", user_profile.user_id);
+        }
+
+        let session_id = self
+            .session_service
+            .start(user_profile.user_id, client, clock::now() + TOKEN_VALIDITY_SECONDS)
+            .await?;
+
         // Sign JWT with compact user details as payload
-        let token = self.json_web_token.sign(user_compact.clone()).await;
+        let token = self.json_web_token.sign(user_compact.clone(), Some(session_id)).await;
 
         Ok((token, user_compact))
     }
@@ -96,6 +136,8 @@ impl Service {
     /// This function will return an error if:
     ///
     /// * Unable to verify the supplied payload as a valid jwt.
+    /// * The session of the token was revoked.
+    /// * The account was disabled for inactivity.
     /// * Unable to get user data from the database.
     pub async fn renew_token(&self, token: &str) -> Result<(String, UserCompact), ServiceError> {
         const ONE_WEEK_IN_SECONDS: u64 = 604_800;
@@ -103,16 +145,65 @@ impl Service {
         // Verify if token is valid
         let claims = self.json_web_token.verify(token).await?;
 
+        if let Some(session_id) = &claims.jti {
+            self.session_service.check(session_id).await?;
+        }
+
+        self.check_not_disabled(&claims.user.user_id).await?;
+
         let user_compact = self.user_repository.get_compact(&claims.user.user_id).await?;
 
         // Renew token if it is valid for less than one week
         let token = match claims.exp - clock::now() {
-            x if x < ONE_WEEK_IN_SECONDS => self.json_web_token.sign(user_compact.clone()).await,
+            x if x < ONE_WEEK_IN_SECONDS => {
+                if let Some(session_id) = &claims.jti {
+                    self.session_service
+                        .extend(session_id, clock::now() + TOKEN_VALIDITY_SECONDS)
+                        .await?;
+                }
+
+                self.json_web_token.sign(user_compact.clone(), claims.jti).await
+            }
             _ => token.to_string(),
         };
 
         Ok((token, user_compact))
     }
+
+    /// It replaces the user's password hash with a hash generated with the
+    /// current hasher. It only logs the errors because the user can still
+    /// log in with the old hash, and it will be tried again on the next login.
+    async fn rehash_password(&self, user_id: &UserId, password: &str) {
+        let password_hash = match hash_password(password) {
+            Ok(password_hash) => password_hash,
+            Err(e) => {
+                tracing::error!("Failed to re-hash the password of user #{user_id}: {e:?}");
+                return;
+            }
+        };
+
+        match self
+            .user_authentication_repository
+            .change_password(*user_id, &password_hash)
+            .await
+        {
+            Ok(()) => tracing::info!("Password of user #{user_id} re-hashed with Argon2id"),
+            Err(e) => tracing::error!("Failed to save the re-hashed password of user #{user_id}: {e:?}"),
+        }
+    }
+
+    async fn check_not_disabled(&self, user_id: &UserId) -> Result<(), ServiceError> {
+        if self
+            .user_authentication_repository
+            .is_disabled(user_id)
+            .await
+            .map_err(|_| ServiceError::InternalServerError)?
+        {
+            return Err(ServiceError::AccountDisabled);
+        }
+
+        Ok(())
+    }
 }
 
 pub struct JsonWebToken {
@@ -124,22 +215,34 @@ impl JsonWebToken {
         Self { cfg }
     }
 
-    /// Create Json Web Token.
+    /// Create Json Web Token. The `session_id` is the `jti` claim.
     ///
     /// # Panics
     ///
     /// This function will panic if the default encoding algorithm does not ç
     /// match the encoding key.
-    pub async fn sign(&self, user: UserCompact) -> String {
+    pub async fn sign(&self, user: UserCompact, session_id: Option<SessionId>) -> String {
         let settings = self.cfg.settings.read().await;
 
         // Create JWT that expires in two weeks
         let key = settings.auth.user_claim_token_pepper.as_bytes();
 
         // todo: create config option for setting the token validity in seconds.
-        let exp_date = clock::now() + 1_209_600; // two weeks from now
-
-        let claims = UserClaims { user, exp: exp_date };
+        let exp_date = clock::now() + TOKEN_VALIDITY_SECONDS; // two weeks from now
+
+        let extra = settings
+            .auth
+            .jwt
+            .as_ref()
+            .map(|jwt| jwt.extra_claims.for_user(user.administrator))
+            .unwrap_or_default();
+
+        let claims = UserClaims {
+            user,
+            exp: exp_date,
+            jti: session_id,
+            extra,
+        };
 
         encode(&Header::default(), &claims, &EncodingKey::from_secret(key)).expect("argument `Header` should match `EncodingKey`")
     }
@@ -188,6 +291,24 @@ impl DbUserAuthenticationRepository {
         self.database.get_user_authentication_from_id(*user_id).await
     }
 
+    /// It records the date of the last login of the user.
+    ///
+    /// # Errors
+    ///
+    /// This function will return an error if there is a database error.
+    pub async fn update_last_login(&self, user_id: &UserId) -> Result<(), Error> {
+        self.database.update_user_last_login(*user_id).await
+    }
+
+    /// It returns whether the account was disabled for inactivity.
+    ///
+    /// # Errors
+    ///
+    /// This function will return an error if there is a database error.
+    pub async fn is_disabled(&self, user_id: &UserId) -> Result<bool, Error> {
+        self.database.is_user_disabled(*user_id).await
+    }
+
     /// It changes the user's password.
     ///
     /// # Errors
@@ -198,6 +319,47 @@ impl DbUserAuthenticationRepository {
     }
 }
 
+/// It hashes a password with the default hasher: Argon2id with the default
+/// parameters.
+///
+/// # Errors
+///
+/// It returns an error if the password can't be hashed.
+pub fn hash_password(password: &str) -> Result<String, ServiceError> {
+    let salt = SaltString::generate(&mut OsRng);
+
+    // Hash password to PHC string ($argon2id$v=19$...)
+    let password_hash = Argon2::default().hash_password(password.as_bytes(), &salt)?.to_string();
+
+    Ok(password_hash)
+}
+
+/// It returns `true` if the password hash was not generated with the default
+/// hasher, that is, with a different scheme or with other Argon2 parameters.
+/// Hashes that can't be parsed are not re-hashed because the password can't
+/// be verified.
+#[must_use]
+pub fn needs_rehash(password_hash: &str) -> bool {
+    let Ok(parsed_hash) = PasswordHash::new(password_hash) else {
+        return false;
+    };
+
+    if parsed_hash.algorithm != argon2::Algorithm::Argon2id.ident() || parsed_hash.version != Some(argon2::Version::V0x13.into())
+    {
+        return true;
+    }
+
+    let Ok(params) = Params::try_from(&parsed_hash) else {
+        return true;
+    };
+
+    let default_params = Params::default();
+
+    params.m_cost() != default_params.m_cost()
+        || params.t_cost() != default_params.t_cost()
+        || params.p_cost() != default_params.p_cost()
+}
+
 /// Verify if the user supplied and the database supplied passwords match
 ///
 /// # Errors
@@ -229,8 +391,14 @@ pub fn verify_password(password: &[u8], user_authentication: &UserAuthentication
 
 #[cfg(test)]
 mod tests {
-    use super::verify_password;
-    use crate::models::user::UserAuthentication;
+    use std::sync::Arc;
+
+    use serde_json::json;
+
+    use super::{hash_password, needs_rehash, verify_password, JsonWebToken};
+    use crate::config::v3::auth::{ExtraClaims, Jwt};
+    use crate::config::{Configuration, Settings};
+    use crate::models::user::{UserAuthentication, UserCompact};
 
     #[test]
     fn password_hashed_with_pbkdf2_sha256_should_be_verified() {
@@ -259,4 +427,78 @@ mod tests {
         assert!(verify_password(password, &user_authentication).is_ok());
         assert!(verify_password("incorrect password".as_bytes(), &user_authentication).is_err());
     }
+
+    #[test]
+    fn passwords_should_be_hashed_with_argon2id() {
+        let password_hash = hash_password("12345678").unwrap();
+        let user_authentication = UserAuthentication {
+            user_id: 1i64,
+            password_hash: password_hash.clone(),
+        };
+
+        assert!(password_hash.starts_with("$argon2id$"));
+        assert!(verify_password("12345678".as_bytes(), &user_authentication).is_ok());
+    }
+
+    #[test]
+    fn only_hashes_not_generated_with_the_default_hasher_should_be_rehashed() {
+        assert!(needs_rehash(
+            "$pbkdf2-sha256$i=10000,l=32$pZIh8nilm+cg6fk5Ubf2zQ$AngLuZ+sGUragqm4bIae/W+ior0TWxYFFaTx8CulqtY"
+        ));
+        assert!(needs_rehash(
+            "$argon2id$v=19$m=4096,t=3,p=1$ycK5lJ4xmFBnaJ51M1j1eA$kU3UlNiSc3JDbl48TCj7JBDKmrT92DOUAgo4Yq0+nMw"
+        ));
+        assert!(!needs_rehash(&hash_password("12345678").unwrap()));
+        assert!(!needs_rehash("not a password hash"));
+    }
+
+    #[tokio::test]
+    async fn the_json_web_token_should_include_the_extra_claims_for_the_user_role() {
+        let mut settings = Settings::default();
+        settings.auth.jwt = Some(Jwt {
+            extra_claims: ExtraClaims {
+                all: json!({"features": ["comments"]}).as_object().unwrap().clone(),
+                admin: json!({"features": ["comments", "moderation"]}).as_object().unwrap().clone(),
+                registered: serde_json::Map::new(),
+            },
+        });
+        let json_web_token = JsonWebToken::new(Arc::new(Configuration::new(settings)));
+
+        let token = json_web_token
+            .sign(
+                UserCompact {
+                    user_id: 1,
+                    username: "indexadmin".to_string(),
+                    administrator: true,
+                },
+                None,
+            )
+            .await;
+
+        let claims = json_web_token.verify(&token).await.unwrap();
+
+        assert_eq!(claims.user.username, "indexadmin");
+        assert_eq!(claims.extra.get("features"), Some(&json!(["comments", "moderation"])));
+    }
+
+    #[tokio::test]
+    async fn the_json_web_token_should_include_the_session_id() {
+        let json_web_token = JsonWebToken::new(Arc::new(Configuration::new(Settings::default())));
+
+        let token = json_web_token
+            .sign(
+                UserCompact {
+                    user_id: 1,
+                    username: "indexadmin".to_string(),
+                    administrator: true,
+                },
+                Some("5d0b8a7d3b5b4c1c9a3a3f3c2f1e0d9c".to_string()),
+            )
+            .await;
+
+        let claims = json_web_token.verify(&token).await.unwrap();
+
+        assert_eq!(claims.jti.as_deref(), Some("5d0b8a7d3b5b4c1c9a3a3f3c2f1e0d9c"));
+        assert!(claims.extra.get("jti").is_none());
+    }
 }
@@ -6,14 +6,18 @@ use casbin::{CoreApi, DefaultModel, Enforcer, MgmtApi};
 use serde::{Deserialize, Serialize};
 use tokio::sync::RwLock;
 
+use super::category::DbCategoryModeratorRepository;
 use super::user::Repository;
 use crate::errors::ServiceError;
+use crate::models::category::CategoryId;
 use crate::models::user::{UserCompact, UserId};
 
 #[derive(Debug, PartialEq, Serialize, Deserialize, Hash)]
 #[serde(rename_all = "lowercase")]
 enum UserRole {
     Admin,
+    /// A registered user acting on a category they moderate.
+    Moderator,
     Registered,
     Guest,
 }
@@ -22,6 +26,7 @@ impl fmt::Display for UserRole {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         let role_str = match self {
             UserRole::Admin => "admin",
+            UserRole::Moderator => "moderator",
             UserRole::Registered => "registered",
             UserRole::Guest => "guest",
         };
@@ -52,18 +57,70 @@ pub enum ACTION {
     GetCanonicalInfoHash,
     ChangePassword,
     BanUser,
+    GetComments,
+    AddComment,
+    ModerateComment,
+    ModerateTorrent,
+    ReportTorrent,
+    ModerateReports,
+    GetImporterStatus,
+    BookmarkTorrent,
+    ManageSubscriptions,
+    EditTorrentTrackers,
+    GetDownloadStatistics,
+    ImportTorrents,
+    SendBulkMail,
+    ManageInactivity,
+    UploadImage,
+    GetCollection,
+    ManageCollections,
+    ModerateCollections,
+    ManageUploadQuotas,
+    EditTorrentInfo,
+    ManageCategoryModerators,
+    GetUserProfile,
+    EditUserProfile,
+    GetApiUsage,
+    ChangeEmail,
+    GetRateLimitCounters,
+    ManageSmartCollections,
+    GetAuditLog,
+    ManageCategoryPolicies,
+    ManageCategoryFields,
+    GetTorrentPeers,
+    GetPeerAddresses,
+    ManageAnnounceKey,
+    MergeTags,
+    ManageTagSynonyms,
+    ManageTorrentRules,
+    ManageSavedSearches,
+    ManagePoints,
+    GetDuplicateTorrents,
+    ManageTrash,
+    GetDeadTorrents,
+    BulkModerateTorrents,
+    ManageAntispam,
+    ManageSessions,
+    ReloadSettings,
+    ManageReputation,
 }
 
 pub struct Service {
     user_repository: Arc<Box<dyn Repository>>,
+    category_moderator_repository: Arc<DbCategoryModeratorRepository>,
     casbin_enforcer: Arc<CasbinEnforcer>,
 }
 
 impl Service {
     #[must_use]
-    pub fn new(user_repository: Arc<Box<dyn Repository>>, casbin_enforcer: Arc<CasbinEnforcer>) -> Self {
+    pub fn new(
+        user_repository: Arc<Box<dyn Repository>>,
+        category_moderator_repository: Arc<DbCategoryModeratorRepository>,
+        casbin_enforcer: Arc<CasbinEnforcer>,
+    ) -> Self {
         Self {
             user_repository,
+            category_moderator_repository,
             casbin_enforcer,
         }
     }
@@ -77,13 +134,7 @@ impl Service {
     pub async fn authorize(&self, action: ACTION, maybe_user_id: Option<UserId>) -> std::result::Result<(), ServiceError> {
         let role = self.get_role(maybe_user_id).await;
 
-        let enforcer = self.casbin_enforcer.enforcer.read().await;
-
-        let authorize = enforcer
-            .enforce((&role, action))
-            .map_err(|_| ServiceError::UnauthorizedAction)?;
-
-        if authorize {
+        if self.enforce(&role, &action).await? {
             Ok(())
         } else if role == UserRole::Guest {
             Err(ServiceError::UnauthorizedActionForGuests)
@@ -92,6 +143,76 @@ impl Service {
         }
     }
 
+    /// Allows or denies an user to perform an action on a resource (a
+    /// torrent, for example) that belongs to a category.
+    ///
+    /// Besides the users allowed by their role, the moderators of the
+    /// category can perform the actions of the `moderator` role.
+    ///
+    /// # Errors
+    ///
+    /// Will return an error if:
+    /// - The user is not authorized to perform the action.
+    pub async fn authorize_in_category(
+        &self,
+        action: ACTION,
+        maybe_user_id: Option<UserId>,
+        category_id: Option<CategoryId>,
+    ) -> std::result::Result<(), ServiceError> {
+        let result = self.authorize(action.clone(), maybe_user_id).await;
+
+        if result.is_err() {
+            if let (Some(user_id), Some(category_id)) = (maybe_user_id, category_id) {
+                if self.enforce(&UserRole::Moderator, &action).await?
+                    && self
+                        .category_moderator_repository
+                        .is_moderator(category_id, user_id)
+                        .await
+                        .unwrap_or(false)
+                {
+                    return Ok(());
+                }
+            }
+        }
+
+        result
+    }
+
+    /// It returns the names of the categories where the user can perform an
+    /// action, or `None` if the user can perform it in all the categories.
+    ///
+    /// # Errors
+    ///
+    /// Will return an error if:
+    /// - The user is not authorized to perform the action in any category.
+    pub async fn authorized_categories(
+        &self,
+        action: ACTION,
+        maybe_user_id: Option<UserId>,
+    ) -> std::result::Result<Option<Vec<String>>, ServiceError> {
+        let result = self.authorize(action.clone(), maybe_user_id).await;
+
+        if result.is_err() {
+            if let Some(user_id) = maybe_user_id {
+                if self.enforce(&UserRole::Moderator, &action).await? {
+                    let categories = self.category_moderator_repository.get_moderated_categories(user_id).await?;
+
+                    if !categories.is_empty() {
+                        return Ok(Some(categories.into_iter().map(|category| category.name).collect()));
+                    }
+                }
+            }
+        }
+
+        result.map(|()| None)
+    }
+
+    async fn enforce(&self, role: &UserRole, action: &ACTION) -> std::result::Result<bool, ServiceError> {
+        let enforcer = self.casbin_enforcer.enforcer.read().await;
+
+        enforcer.enforce((role, action)).map_err(|_| ServiceError::UnauthorizedAction)
+    }
+
     /// It returns the compact user.
     ///
     /// # Errors
@@ -248,6 +369,52 @@ impl Default for CasbinConfiguration {
                 admin, GetCanonicalInfoHash
                 admin, ChangePassword
                 admin, BanUser
+                admin, GetComments
+                admin, AddComment
+                admin, ModerateComment
+                admin, ModerateTorrent
+                admin, ReportTorrent
+                admin, ModerateReports
+                admin, GetImporterStatus
+                admin, BookmarkTorrent
+                admin, ManageSubscriptions
+                admin, EditTorrentTrackers
+                admin, GetDownloadStatistics
+                admin, ImportTorrents
+                admin, SendBulkMail
+                admin, ManageInactivity
+                admin, UploadImage
+                admin, GetCollection
+                admin, ManageCollections
+                admin, ModerateCollections
+                admin, ManageUploadQuotas
+                admin, EditTorrentInfo
+                admin, ManageCategoryModerators
+                admin, GetUserProfile
+                admin, EditUserProfile
+                admin, GetApiUsage
+                admin, ChangeEmail
+                admin, GetRateLimitCounters
+                admin, ManageSmartCollections
+                admin, GetAuditLog
+                admin, ManageCategoryPolicies
+                admin, ManageCategoryFields
+                admin, GetTorrentPeers
+                admin, GetPeerAddresses
+                admin, ManageAnnounceKey
+                admin, MergeTags
+                admin, ManageTagSynonyms
+                admin, ManageTorrentRules
+                admin, ManageSavedSearches
+                admin, ManagePoints
+                admin, GetDuplicateTorrents
+                admin, ManageTrash
+                admin, GetDeadTorrents
+                admin, BulkModerateTorrents
+                admin, ManageAntispam
+                admin, ManageSessions
+                admin, ReloadSettings
+                admin, ManageReputation
                 registered, GetAboutPage
                 registered, GetLicensePage
                 registered, GetCategories
@@ -261,6 +428,26 @@ impl Default for CasbinConfiguration {
                 registered, GenerateTorrentInfoListing
                 registered, GetCanonicalInfoHash
                 registered, ChangePassword
+                registered, GetComments
+                registered, AddComment
+                registered, ReportTorrent
+                registered, BookmarkTorrent
+                registered, ManageSubscriptions
+                registered, ManageSavedSearches
+                registered, UploadImage
+                registered, GetCollection
+                registered, ManageCollections
+                registered, GetUserProfile
+                registered, EditUserProfile
+                registered, GetApiUsage
+                registered, ManageSessions
+                registered, ChangeEmail
+                registered, ManageAnnounceKey
+                moderator, ModerateTorrent
+                moderator, DeleteTorrent
+                moderator, EditTorrentInfo
+                moderator, EditTorrentTrackers
+                moderator, GetTorrentPeers
                 guest, GetAboutPage
                 guest, GetLicensePage
                 guest, GetCategories
@@ -271,6 +458,9 @@ impl Default for CasbinConfiguration {
                 guest, GetTorrentInfo
                 guest, GenerateTorrentInfoListing
                 guest, GetCanonicalInfoHash
+                guest, GetComments
+                guest, GetCollection
+                guest, GetUserProfile
                 ",
             ),
         }
@@ -0,0 +1,509 @@
+//! User bookmarks service.
+//!
+//! Logged-in users can bookmark torrents to find them later.
+//!
+//! Users can also export their bookmarks and their
+//! [saved searches](crate::services::saved_search) to a portable JSON file
+//! and import them back, in the same or another index instance. Torrents are
+//! identified by their info-hash in the file. Saved searches are imported
+//! without their results, so only the torrents uploaded after the import are
+//! matched.
+use std::str::FromStr;
+use std::sync::Arc;
+
+use bittorrent_primitives::info_hash::InfoHash;
+use chrono::NaiveDateTime;
+use serde_derive::{Deserialize, Serialize};
+use utoipa::{IntoParams, ToSchema};
+
+use super::authorization::{self, ACTION};
+use super::saved_search::{self, AddSavedSearchRequest, DbSavedSearchRepository, MAX_SAVED_SEARCHES_PER_USER};
+use super::torrent::{DbTorrentListingGenerator, ListingSpecification};
+use crate::config::Configuration;
+use crate::databases::database::{Database, Error, Sorting};
+use crate::errors::ServiceError;
+use crate::models::bookmark::Bookmark;
+use crate::models::response::TorrentsResponse;
+use crate::models::torrent::{ListingFilter, TorrentId, TorrentStatus};
+use crate::models::user::UserId;
+use crate::utils::clock::{datetime_now, DATETIME_FORMAT};
+
+/// The `format` attribute of the bookmarks files.
+pub const BOOKMARKS_FILE_FORMAT: &str = "torrust-index-bookmarks";
+
+/// The current version of the bookmarks file format. Version `2` added the
+/// saved searches.
+pub const BOOKMARKS_FILE_VERSION: u32 = 2;
+
+/// User request to get a page of bookmarked torrents.
+#[derive(Debug, Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
+pub struct ListingRequest {
+    pub page_size: Option<u8>,
+    pub page: Option<u32>,
+}
+
+/// The portable file with the user bookmarks.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+pub struct BookmarksFile {
+    /// It's always `torrust-index-bookmarks`.
+    pub format: String,
+    pub version: u32,
+    /// The export date, in `%Y-%m-%d %H:%M:%S` format.
+    #[serde(default)]
+    pub exported_at: String,
+    pub bookmarks: Vec<Bookmark>,
+    /// The saved searches, without their results. Files of version `1`
+    /// don't have them.
+    #[serde(default)]
+    pub saved_searches: Vec<AddSavedSearchRequest>,
+}
+
+/// The result of importing a bookmarks file.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+pub struct ImportSummary {
+    /// Number of new bookmarks.
+    pub imported: usize,
+    /// Number of bookmarks the user already had.
+    pub skipped: usize,
+    /// Info-hashes of the torrents that are not in this index or are not
+    /// valid.
+    pub not_found: Vec<String>,
+    /// Number of new saved searches.
+    pub saved_searches_imported: usize,
+    /// Number of saved searches that are not valid, that the user already
+    /// had with the same name, or that exceed the maximum per user.
+    pub saved_searches_skipped: usize,
+}
+
+pub struct Service {
+    configuration: Arc<Configuration>,
+    bookmark_repository: Arc<DbBookmarkRepository>,
+    saved_search_repository: Arc<DbSavedSearchRepository>,
+    torrent_listing_generator: Arc<DbTorrentListingGenerator>,
+    authorization_service: Arc<authorization::Service>,
+}
+
+impl Service {
+    #[must_use]
+    pub fn new(
+        configuration: Arc<Configuration>,
+        bookmark_repository: Arc<DbBookmarkRepository>,
+        saved_search_repository: Arc<DbSavedSearchRepository>,
+        torrent_listing_generator: Arc<DbTorrentListingGenerator>,
+        authorization_service: Arc<authorization::Service>,
+    ) -> Service {
+        Service {
+            configuration,
+            bookmark_repository,
+            saved_search_repository,
+            torrent_listing_generator,
+            authorization_service,
+        }
+    }
+
+    /// Bookmarks a torrent. Bookmarking a torrent twice has no effect.
+    ///
+    /// # Errors
+    ///
+    /// It returns an error if:
+    ///
+    /// * The user does not have the required permissions.
+    /// * The torrent does not exist.
+    /// * There is a database error.
+    pub async fn add_bookmark(&self, info_hash: &InfoHash, maybe_user_id: Option<UserId>) -> Result<TorrentId, ServiceError> {
+        let user_id = self.authorize(maybe_user_id).await?;
+
+        let torrent_id = self.torrent_id(info_hash).await?;
+
+        self.bookmark_repository.add(user_id, torrent_id).await?;
+
+        Ok(torrent_id)
+    }
+
+    /// Removes a torrent from the user bookmarks.
+    ///
+    /// # Errors
+    ///
+    /// It returns an error if:
+    ///
+    /// * The user does not have the required permissions.
+    /// * The torrent does not exist or it's not bookmarked.
+    /// * There is a database error.
+    pub async fn delete_bookmark(&self, info_hash: &InfoHash, maybe_user_id: Option<UserId>) -> Result<TorrentId, ServiceError> {
+        let user_id = self.authorize(maybe_user_id).await?;
+
+        let torrent_id = self.torrent_id(info_hash).await?;
+
+        self.bookmark_repository.delete(user_id, torrent_id).await?;
+
+        Ok(torrent_id)
+    }
+
+    /// Returns a page of the torrents bookmarked by the user, from the newest
+    /// bookmark to the oldest.
+    ///
+    /// # Errors
+    ///
+    /// It returns an error if:
+    ///
+    /// * The user does not have the required permissions.
+    /// * There is a database error.
+    pub async fn get_bookmarks(
+        &self,
+        request: &ListingRequest,
+        maybe_user_id: Option<UserId>,
+    ) -> Result<TorrentsResponse, ServiceError> {
+        let user_id = self.authorize(maybe_user_id).await?;
+
+        let settings = self.configuration.settings.read().await;
+        let default_torrent_page_size = settings.api.default_torrent_page_size;
+        let max_torrent_page_size = settings.api.max_torrent_page_size;
+        drop(settings);
+
+        let page_size = request
+            .page_size
+            .unwrap_or(default_torrent_page_size)
+            .min(max_torrent_page_size);
+        let offset = u64::from(request.page.unwrap_or(0)) * u64::from(page_size);
+
+        let torrent_ids = self.bookmark_repository.get_torrent_ids(user_id).await?;
+
+        let specification = ListingSpecification {
+            search: None,
+            categories: None,
+            tags: None,
+            torrent_ids: Some(torrent_ids),
+            status: TorrentStatus::Approved,
+            filter: ListingFilter::default(),
+            sort: Sorting::Relevance,
+            then_sort: None,
+            offset,
+            page_size,
+        };
+
+        Ok(self.torrent_listing_generator.generate_listing(&specification).await?)
+    }
+
+    /// Returns all the user bookmarks and saved searches in the portable
+    /// bookmarks file format.
+    ///
+    /// # Errors
+    ///
+    /// It returns an error if:
+    ///
+    /// * The user does not have the required permissions.
+    /// * There is a database error.
+    pub async fn export_bookmarks(&self, maybe_user_id: Option<UserId>) -> Result<BookmarksFile, ServiceError> {
+        let user_id = self.authorize(maybe_user_id).await?;
+
+        let bookmarks = self.bookmark_repository.get_all(user_id).await?;
+
+        let saved_searches = self
+            .saved_search_repository
+            .get_by_user(user_id)
+            .await?
+            .into_iter()
+            .map(|saved_search| AddSavedSearchRequest {
+                name: saved_search.name,
+                query: saved_search.query,
+                category: saved_search.category,
+                tags: saved_search.tags,
+                email_alerts: Some(saved_search.email_alerts),
+            })
+            .collect();
+
+        Ok(BookmarksFile {
+            format: BOOKMARKS_FILE_FORMAT.to_string(),
+            version: BOOKMARKS_FILE_VERSION,
+            exported_at: datetime_now(),
+            bookmarks,
+            saved_searches,
+        })
+    }
+
+    /// Imports the bookmarks and saved searches in a bookmarks file. Torrents
+    /// that are already bookmarked are skipped and torrents that are not in
+    /// this index are reported in the summary. The original bookmark dates
+    /// are kept. Saved searches with the name of an existing one are skipped.
+    ///
+    /// # Errors
+    ///
+    /// It returns an error if:
+    ///
+    /// * The user does not have the required permissions.
+    /// * The file format or version is not supported.
+    /// * There is a database error.
+    pub async fn import_bookmarks(
+        &self,
+        bookmarks_file: &BookmarksFile,
+        maybe_user_id: Option<UserId>,
+    ) -> Result<ImportSummary, ServiceError> {
+        let user_id = self.authorize(maybe_user_id).await?;
+
+        if bookmarks_file.format != BOOKMARKS_FILE_FORMAT || bookmarks_file.version > BOOKMARKS_FILE_VERSION {
+            return Err(ServiceError::UnsupportedBookmarksFile);
+        }
+
+        let mut summary = ImportSummary {
+            imported: 0,
+            skipped: 0,
+            not_found: vec![],
+            saved_searches_imported: 0,
+            saved_searches_skipped: 0,
+        };
+
+        for bookmark in &bookmarks_file.bookmarks {
+            let Ok(info_hash) = InfoHash::from_str(&bookmark.info_hash.to_lowercase()) else {
+                summary.not_found.push(bookmark.info_hash.clone());
+                continue;
+            };
+
+            let torrent_id = match self.torrent_id(&info_hash).await {
+                Ok(torrent_id) => torrent_id,
+                Err(ServiceError::TorrentNotFound) => {
+                    summary.not_found.push(bookmark.info_hash.clone());
+                    continue;
+                }
+                Err(e) => return Err(e),
+            };
+
+            let date_created = bookmark
+                .date_created
+                .as_deref()
+                .filter(|date| NaiveDateTime::parse_from_str(date, DATETIME_FORMAT).is_ok())
+                .map_or_else(datetime_now, ToString::to_string);
+
+            if self
+                .bookmark_repository
+                .add_with_date(user_id, torrent_id, &date_created)
+                .await?
+            {
+                summary.imported += 1;
+            } else {
+                summary.skipped += 1;
+            }
+        }
+
+        let mut saved_search_names: Vec<String> = self
+            .saved_search_repository
+            .get_by_user(user_id)
+            .await?
+            .into_iter()
+            .map(|saved_search| saved_search.name)
+            .collect();
+
+        for saved_search in &bookmarks_file.saved_searches {
+            let Ok(saved_search) = saved_search::normalize(saved_search) else {
+                summary.saved_searches_skipped += 1;
+                continue;
+            };
+
+            if saved_search_names.contains(&saved_search.name) || saved_search_names.len() >= MAX_SAVED_SEARCHES_PER_USER {
+                summary.saved_searches_skipped += 1;
+                continue;
+            }
+
+            self.saved_search_repository.add(user_id, &saved_search).await?;
+
+            saved_search_names.push(saved_search.name);
+            summary.saved_searches_imported += 1;
+        }
+
+        Ok(summary)
+    }
+
+    async fn authorize(&self, maybe_user_id: Option<UserId>) -> Result<UserId, ServiceError> {
+        self.authorization_service
+            .authorize(ACTION::BookmarkTorrent, maybe_user_id)
+            .await?;
+
+        maybe_user_id.ok_or(ServiceError::UnauthorizedActionForGuests)
+    }
+
+    async fn torrent_id(&self, info_hash: &InfoHash) -> Result<TorrentId, ServiceError> {
+        let torrent_listing = self.torrent_listing_generator.one_torrent_by_info_hash(info_hash).await?;
+
+        Ok(torrent_listing.torrent_id)
+    }
+}
+
+pub struct DbBookmarkRepository {
+    database: Arc<Box<dyn Database>>,
+}
+
+impl DbBookmarkRepository {
+    #[must_use]
+    pub fn new(database: Arc<Box<dyn Database>>) -> Self {
+        Self { database }
+    }
+
+    /// It bookmarks the torrent for the user.
+    ///
+    /// # Errors
+    ///
+    /// It returns an error if there is a database error.
+    pub async fn add(&self, user_id: UserId, torrent_id: TorrentId) -> Result<(), Error> {
+        self.database.insert_bookmark(user_id, torrent_id).await
+    }
+
+    /// It removes the torrent from the user bookmarks.
+    ///
+    /// # Errors
+    ///
+    /// It returns an error if the bookmark does not exist or there is a
+    /// database error.
+    pub async fn delete(&self, user_id: UserId, torrent_id: TorrentId) -> Result<(), Error> {
+        self.database.delete_bookmark(user_id, torrent_id).await
+    }
+
+    /// Whether the user has bookmarked the torrent or not.
+    ///
+    /// # Errors
+    ///
+    /// It returns an error if there is a database error.
+    pub async fn is_bookmarked(&self, user_id: UserId, torrent_id: TorrentId) -> Result<bool, Error> {
+        self.database.is_torrent_bookmarked(user_id, torrent_id).await
+    }
+
+    /// It returns the IDs of the torrents bookmarked by the user, from the
+    /// newest bookmark to the oldest.
+    ///
+    /// # Errors
+    ///
+    /// It returns an error if there is a database error.
+    pub async fn get_torrent_ids(&self, user_id: UserId) -> Result<Vec<TorrentId>, Error> {
+        self.database.get_user_bookmarked_torrent_ids(user_id).await
+    }
+
+    /// It bookmarks the torrent for the user with the given date.
+    ///
+    /// It returns `false` if the torrent was already bookmarked.
+    ///
+    /// # Errors
+    ///
+    /// It returns an error if there is a database error.
+    pub async fn add_with_date(&self, user_id: UserId, torrent_id: TorrentId, date_created: &str) -> Result<bool, Error> {
+        self.database
+            .insert_bookmark_with_date(user_id, torrent_id, date_created)
+            .await
+    }
+
+    /// It returns all the user bookmarks, from the newest to the oldest.
+    ///
+    /// # Errors
+    ///
+    /// It returns an error if there is a database error.
+    pub async fn get_all(&self, user_id: UserId) -> Result<Vec<Bookmark>, Error> {
+        self.database.get_user_bookmarks(user_id).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use super::{BookmarksFile, BOOKMARKS_FILE_VERSION};
+    use crate::config::Configuration;
+    use crate::databases::testing::{add_category, add_torrent, add_user, new_sqlite_database};
+    use crate::models::torrent::TorrentStatus;
+    use crate::services::saved_search::AddSavedSearchRequest;
+    use crate::services::testing::services;
+
+    fn saved_search_request(name: &str) -> AddSavedSearchRequest {
+        AddSavedSearchRequest {
+            name: name.to_string(),
+            query: "ubuntu".to_string(),
+            category: Some("software".to_string()),
+            tags: vec!["iso".to_string()],
+            email_alerts: Some(false),
+        }
+    }
+
+    #[tokio::test]
+    async fn it_should_export_and_import_the_bookmarks_and_the_saved_searches() {
+        let (_temp_dir, database) = new_sqlite_database().await;
+        let services = services(Arc::new(Configuration::default()), database.clone()).await;
+
+        let user_id = add_user(database.as_ref(), "alice").await;
+        let another_user_id = add_user(database.as_ref(), "bob").await;
+        let category_id = add_category(database.as_ref(), "bookmarks").await;
+        let (_torrent_id, info_hash) = add_torrent(database.as_ref(), user_id, category_id, &TorrentStatus::Approved).await;
+
+        services
+            .bookmark_service
+            .add_bookmark(&info_hash, Some(user_id))
+            .await
+            .unwrap();
+        database
+            .insert_saved_search(
+                user_id,
+                "Ubuntu ISOs",
+                "ubuntu",
+                Some("software"),
+                &["iso".to_string()],
+                false,
+            )
+            .await
+            .unwrap();
+
+        let bookmarks_file = services.bookmark_service.export_bookmarks(Some(user_id)).await.unwrap();
+
+        assert_eq!(bookmarks_file.version, BOOKMARKS_FILE_VERSION);
+        assert_eq!(bookmarks_file.bookmarks.len(), 1);
+        assert_eq!(bookmarks_file.saved_searches, vec![saved_search_request("Ubuntu ISOs")]);
+
+        // The file is imported by another user after a round trip through JSON
+        let bookmarks_file: BookmarksFile = serde_json::from_str(&serde_json::to_string(&bookmarks_file).unwrap()).unwrap();
+
+        let summary = services
+            .bookmark_service
+            .import_bookmarks(&bookmarks_file, Some(another_user_id))
+            .await
+            .unwrap();
+
+        assert_eq!(summary.imported, 1);
+        assert_eq!(summary.saved_searches_imported, 1);
+
+        let exported_again = services
+            .bookmark_service
+            .export_bookmarks(Some(another_user_id))
+            .await
+            .unwrap();
+
+        assert_eq!(exported_again.bookmarks, bookmarks_file.bookmarks);
+        assert_eq!(exported_again.saved_searches, bookmarks_file.saved_searches);
+
+        let summary = services
+            .bookmark_service
+            .import_bookmarks(&bookmarks_file, Some(another_user_id))
+            .await
+            .unwrap();
+
+        assert_eq!(summary.skipped, 1);
+        assert_eq!(summary.saved_searches_imported, 0);
+        assert_eq!(summary.saved_searches_skipped, 1);
+    }
+
+    #[tokio::test]
+    async fn it_should_import_the_bookmarks_files_without_saved_searches() {
+        let (_temp_dir, database) = new_sqlite_database().await;
+        let services = services(Arc::new(Configuration::default()), database.clone()).await;
+
+        let user_id = add_user(database.as_ref(), "alice").await;
+
+        let bookmarks_file: BookmarksFile = serde_json::from_str(
+            r#"{ "format": "torrust-index-bookmarks", "version": 1, "exported_at": "2024-10-17 10:00:00", "bookmarks": [] }"#,
+        )
+        .unwrap();
+
+        let summary = services
+            .bookmark_service
+            .import_bookmarks(&bookmarks_file, Some(user_id))
+            .await
+            .unwrap();
+
+        assert_eq!(summary.imported, 0);
+        assert_eq!(summary.saved_searches_imported, 0);
+    }
+}
@@ -0,0 +1,265 @@
+//! CSV manifest for bulk imports.
+//!
+//! The manifest contains the metadata of the imported torrents. It's a CSV
+//! file with a header row like this:
+//!
+//! ```text
+//! file,title,description,category,tags
+//! ubuntu/ubuntu-24.04-desktop-amd64.iso.torrent,Ubuntu 24.04,Desktop image,Apps,linux;ubuntu
+//! debian-12.7.0-amd64-netinst.iso.torrent,"Debian 12.7, netinst",,Apps,linux
+//! ```
+//!
+//! Only the `file` column is required. It contains the path of the torrent
+//! file relative to the import directory, or only the file name. Tags are
+//! separated by `;`. The category and tags are the ones used in the source
+//! site. They are translated with the [`mapping`](super::mapping) file.
+//!
+//! Torrent files without a manifest entry use the file name (without the
+//! `.torrent` extension) as title.
+use std::collections::HashMap;
+
+use camino::Utf8Path;
+use derive_more::{Display, Error};
+
+#[derive(Debug, Display, PartialEq, Eq, Error)]
+pub enum Error {
+    #[display("Can't read the manifest file: {error}.")]
+    CannotReadFile { error: String },
+
+    #[display("Invalid manifest file: {error}.")]
+    InvalidFile { error: String },
+}
+
+/// The metadata for one torrent file.
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct ManifestEntry {
+    pub file: String,
+    pub title: Option<String>,
+    pub description: Option<String>,
+    pub category: Option<String>,
+    pub tags: Vec<String>,
+}
+
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct Manifest {
+    entries: Vec<ManifestEntry>,
+    by_file: HashMap<String, usize>,
+}
+
+impl Manifest {
+    /// It loads the manifest from a CSV file.
+    ///
+    /// # Errors
+    ///
+    /// It returns an error if the file can't be read or it's not valid.
+    pub fn load_from_file(path: &Utf8Path) -> Result<Self, Error> {
+        let contents = std::fs::read_to_string(path).map_err(|e| Error::CannotReadFile { error: e.to_string() })?;
+
+        Self::parse(&contents)
+    }
+
+    /// It parses the manifest from a CSV string.
+    ///
+    /// # Errors
+    ///
+    /// It returns an error if the CSV is not valid, the `file` column is
+    /// missing or a file is listed more than once.
+    pub fn parse(csv: &str) -> Result<Self, Error> {
+        let mut records = parse_csv(csv.trim_start_matches('\u{feff}'))?.into_iter();
+
+        let header: Vec<String> = records
+            .next()
+            .ok_or_else(|| Error::InvalidFile {
+                error: "missing header row".to_string(),
+            })?
+            .iter()
+            .map(|column| column.trim().to_lowercase())
+            .collect();
+
+        let column = |name: &str| header.iter().position(|column| column == name);
+
+        let file_column = column("file").ok_or_else(|| Error::InvalidFile {
+            error: "missing `file` column".to_string(),
+        })?;
+        let title_column = column("title");
+        let description_column = column("description");
+        let category_column = column("category");
+        let tags_column = column("tags");
+
+        let mut manifest = Manifest::default();
+
+        for (index, record) in records.enumerate() {
+            // Skip blank lines
+            if record.iter().all(|value| value.trim().is_empty()) {
+                continue;
+            }
+
+            let value = |column: Option<usize>| {
+                column
+                    .and_then(|column| record.get(column))
+                    .map(|value| value.trim().to_string())
+                    .filter(|value| !value.is_empty())
+            };
+
+            let line = index + 2;
+
+            let file = value(Some(file_column)).ok_or_else(|| Error::InvalidFile {
+                error: format!("empty `file` value in row {line}"),
+            })?;
+
+            let tags = value(tags_column)
+                .map(|tags| {
+                    tags.split(';')
+                        .map(str::trim)
+                        .filter(|tag| !tag.is_empty())
+                        .map(ToString::to_string)
+                        .collect()
+                })
+                .unwrap_or_default();
+
+            let entry = ManifestEntry {
+                file: normalize_path(&file),
+                title: value(title_column),
+                description: value(description_column),
+                category: value(category_column),
+                tags,
+            };
+
+            if manifest.by_file.contains_key(&entry.file) {
+                return Err(Error::InvalidFile {
+                    error: format!("duplicate file `{file}` in row {line}"),
+                });
+            }
+
+            manifest.by_file.insert(entry.file.clone(), manifest.entries.len());
+            manifest.entries.push(entry);
+        }
+
+        Ok(manifest)
+    }
+
+    /// The entry for the torrent file. The path relative to the import
+    /// directory takes precedence over the file name.
+    #[must_use]
+    pub fn entry_for(&self, relative_path: &Utf8Path) -> Option<&ManifestEntry> {
+        self.by_file
+            .get(&normalize_path(relative_path.as_str()))
+            .or_else(|| relative_path.file_name().and_then(|file_name| self.by_file.get(file_name)))
+            .map(|index| &self.entries[*index])
+    }
+
+    #[must_use]
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+}
+
+fn normalize_path(path: &str) -> String {
+    path.replace('\\', "/").trim_start_matches("./").to_string()
+}
+
+/// It parses a CSV document ([RFC 4180](https://www.rfc-editor.org/rfc/rfc4180)).
+/// Fields can be quoted, and quotes inside quoted fields are escaped by
+/// doubling them.
+fn parse_csv(csv: &str) -> Result<Vec<Vec<String>>, Error> {
+    let mut records = vec![];
+    let mut record = vec![];
+    let mut field = String::new();
+    let mut in_quotes = false;
+    let mut chars = csv.chars().peekable();
+
+    while let Some(char) = chars.next() {
+        if in_quotes {
+            match char {
+                '"' if chars.peek() == Some(&'"') => {
+                    chars.next();
+                    field.push('"');
+                }
+                '"' => in_quotes = false,
+                _ => field.push(char),
+            }
+            continue;
+        }
+
+        match char {
+            '"' if field.is_empty() => in_quotes = true,
+            ',' => record.push(std::mem::take(&mut field)),
+            '\r' if chars.peek() == Some(&'\n') => {}
+            '\n' => {
+                record.push(std::mem::take(&mut field));
+                records.push(std::mem::take(&mut record));
+            }
+            _ => field.push(char),
+        }
+    }
+
+    if in_quotes {
+        return Err(Error::InvalidFile {
+            error: "unterminated quoted field".to_string(),
+        });
+    }
+
+    if !field.is_empty() || !record.is_empty() {
+        record.push(field);
+        records.push(record);
+    }
+
+    Ok(records)
+}
+
+#[cfg(test)]
+mod tests {
+    use camino::Utf8Path;
+
+    use super::{Manifest, ManifestEntry};
+
+    #[test]
+    fn it_should_parse_a_manifest_with_quoted_fields() {
+        let manifest = Manifest::parse(
+            "file,title,description,category,tags\r\n\
+             linux/ubuntu.torrent,\"Ubuntu, \"\"LTS\"\"\",,Apps,linux; ubuntu\r\n",
+        )
+        .unwrap();
+
+        assert_eq!(
+            manifest.entry_for(Utf8Path::new("linux/ubuntu.torrent")),
+            Some(&ManifestEntry {
+                file: "linux/ubuntu.torrent".to_string(),
+                title: Some("Ubuntu, \"LTS\"".to_string()),
+                description: None,
+                category: Some("Apps".to_string()),
+                tags: vec!["linux".to_string(), "ubuntu".to_string()],
+            })
+        );
+    }
+
+    #[test]
+    fn it_should_match_entries_by_file_name_when_the_relative_path_is_not_listed() {
+        let manifest = Manifest::parse("title,file\nDebian,debian.torrent\n").unwrap();
+
+        let entry = manifest.entry_for(Utf8Path::new("isos/debian.torrent")).unwrap();
+
+        assert_eq!(entry.title, Some("Debian".to_string()));
+        assert_eq!(manifest.entry_for(Utf8Path::new("fedora.torrent")), None);
+    }
+
+    #[test]
+    fn it_should_require_the_file_column() {
+        assert!(Manifest::parse("title,category\nDebian,Apps\n").is_err());
+    }
+
+    #[test]
+    fn it_should_reject_duplicate_files() {
+        assert!(Manifest::parse("file\ndebian.torrent\ndebian.torrent\n").is_err());
+    }
+
+    #[test]
+    fn it_should_reject_unterminated_quoted_fields() {
+        assert!(Manifest::parse("file,title\ndebian.torrent,\"Debian\n").is_err());
+    }
+}
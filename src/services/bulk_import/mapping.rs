@@ -0,0 +1,338 @@
+//! Categories and tags mapping for bulk imports.
+//!
+//! The mapping file translates the categories and tags used in the source
+//! site into local ones. It's a TOML file like this:
+//!
+//! ```toml
+//! # Category used when the source category is not mapped (optional).
+//! default_category = "other"
+//!
+//! # Create the local categories and tags used as mapping targets when they
+//! # do not exist yet. Default to `false`.
+//! create_missing_targets = true
+//!
+//! [categories]
+//! "Movies/HD" = "movies"
+//! "Movies/SD" = "movies"
+//! "Apps" = "software"
+//!
+//! [tags]
+//! "x264" = "h264"
+//! "ubuntu" = "linux"
+//! ```
+//!
+//! Source values are matched ignoring case and surrounding whitespace.
+//!
+//! Source values without a mapping are kept as they are. Every value that
+//! could not be resolved to a local category or tag is collected in the
+//! [`Report`], so the administrator can complete the mapping file and run the
+//! import again.
+use std::collections::{BTreeMap, BTreeSet, HashMap};
+use std::sync::Arc;
+
+use camino::Utf8Path;
+use derive_more::{Display, Error};
+use serde::{Deserialize, Serialize};
+
+use crate::databases::database;
+use crate::models::category::CategoryId;
+use crate::models::torrent_tag::TagId;
+use crate::services::category::DbCategoryRepository;
+use crate::services::tag::DbTagRepository;
+
+#[derive(Debug, Display, PartialEq, Eq, Error)]
+pub enum Error {
+    #[display("Can't read the mapping file: {error}.")]
+    CannotReadFile { error: String },
+
+    #[display("Invalid mapping file: {error}.")]
+    InvalidFile { error: String },
+
+    #[display("Database error while resolving the mapping.")]
+    Database,
+}
+
+/// The mapping from the source site taxonomy to the local one.
+#[derive(Debug, Default, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Mapping {
+    /// Local category used when the source category has no mapping.
+    #[serde(default)]
+    pub default_category: Option<String>,
+
+    /// Whether missing local categories and tags are created.
+    #[serde(default)]
+    pub create_missing_targets: bool,
+
+    /// Source category -> local category.
+    #[serde(default)]
+    pub categories: HashMap<String, String>,
+
+    /// Source tag -> local tag.
+    #[serde(default)]
+    pub tags: HashMap<String, String>,
+}
+
+impl Mapping {
+    /// It loads the mapping from a TOML file.
+    ///
+    /// # Errors
+    ///
+    /// It returns an error if the file can't be read or it's not valid.
+    pub fn load_from_file(path: &Utf8Path) -> Result<Self, Error> {
+        let contents = std::fs::read_to_string(path).map_err(|e| Error::CannotReadFile { error: e.to_string() })?;
+
+        Self::load_from_toml(&contents)
+    }
+
+    /// It loads the mapping from a TOML string.
+    ///
+    /// # Errors
+    ///
+    /// It returns an error if the TOML is not a valid mapping.
+    pub fn load_from_toml(toml: &str) -> Result<Self, Error> {
+        let mapping: Mapping = toml::from_str(toml).map_err(|e| Error::InvalidFile { error: e.to_string() })?;
+
+        Ok(mapping.normalized())
+    }
+
+    /// The local category for the source category, if it's mapped.
+    #[must_use]
+    pub fn category_for(&self, source_category: &str) -> Option<&str> {
+        self.categories.get(&normalize(source_category)).map(String::as_str)
+    }
+
+    /// The local tag for the source tag, if it's mapped.
+    #[must_use]
+    pub fn tag_for(&self, source_tag: &str) -> Option<&str> {
+        self.tags.get(&normalize(source_tag)).map(String::as_str)
+    }
+
+    /// It returns the mapping with the source keys normalized.
+    #[must_use]
+    pub fn normalized(self) -> Self {
+        Self {
+            default_category: self.default_category.map(|c| c.trim().to_string()),
+            create_missing_targets: self.create_missing_targets,
+            categories: normalize_keys(self.categories),
+            tags: normalize_keys(self.tags),
+        }
+    }
+}
+
+/// Summary of the mapping resolution during an import.
+#[derive(Debug, Default, Clone, PartialEq, Eq, Serialize)]
+pub struct Report {
+    /// Source categories without a mapping, with the number of torrents.
+    pub unmapped_categories: BTreeMap<String, usize>,
+    /// Source tags without a mapping, with the number of torrents.
+    pub unmapped_tags: BTreeMap<String, usize>,
+    /// Local categories that do not exist and could not be created.
+    pub missing_categories: BTreeSet<String>,
+    /// Local tags that do not exist and could not be created.
+    pub missing_tags: BTreeSet<String>,
+    /// Local categories created during the import.
+    pub created_categories: BTreeSet<String>,
+    /// Local tags created during the import.
+    pub created_tags: BTreeSet<String>,
+}
+
+/// It resolves source categories and tags into local IDs, using the mapping
+/// and creating missing targets if it's enabled.
+pub struct Mapper {
+    mapping: Mapping,
+    category_repository: Arc<DbCategoryRepository>,
+    tag_repository: Arc<DbTagRepository>,
+    category_ids: HashMap<String, Option<CategoryId>>,
+    tag_ids: HashMap<String, Option<TagId>>,
+    report: Report,
+}
+
+impl Mapper {
+    #[must_use]
+    pub fn new(mapping: Mapping, category_repository: Arc<DbCategoryRepository>, tag_repository: Arc<DbTagRepository>) -> Self {
+        Self {
+            mapping,
+            category_repository,
+            tag_repository,
+            category_ids: HashMap::new(),
+            tag_ids: HashMap::new(),
+            report: Report::default(),
+        }
+    }
+
+    /// It returns the local category name and ID for the source category.
+    ///
+    /// It returns `None` if the source category can't be resolved. The
+    /// value is added to the report.
+    ///
+    /// # Errors
+    ///
+    /// It returns an error if there is a database error.
+    pub async fn resolve_category(&mut self, source_category: &str) -> Result<Option<(String, CategoryId)>, Error> {
+        let local_category = match self.mapping.category_for(source_category) {
+            Some(local_category) => local_category.to_string(),
+            None => {
+                *self
+                    .report
+                    .unmapped_categories
+                    .entry(source_category.trim().to_string())
+                    .or_default() += 1;
+
+                match &self.mapping.default_category {
+                    Some(default_category) => default_category.clone(),
+                    None => source_category.trim().to_string(),
+                }
+            }
+        };
+
+        self.resolve_local_category(local_category).await
+    }
+
+    /// It returns the default local category name and ID, used for torrents
+    /// without a source category.
+    ///
+    /// It returns `None` if there is no default category or it can't be
+    /// resolved.
+    ///
+    /// # Errors
+    ///
+    /// It returns an error if there is a database error.
+    pub async fn resolve_default_category(&mut self) -> Result<Option<(String, CategoryId)>, Error> {
+        match self.mapping.default_category.clone() {
+            Some(default_category) => self.resolve_local_category(default_category).await,
+            None => Ok(None),
+        }
+    }
+
+    async fn resolve_local_category(&mut self, local_category: String) -> Result<Option<(String, CategoryId)>, Error> {
+        if let Some(category_id) = self.category_ids.get(&local_category) {
+            return Ok(category_id.map(|id| (local_category, id)));
+        }
+
+        let category_id = match self.category_repository.get_by_name(&local_category).await {
+            Ok(category) => Some(category.category_id),
+            Err(database::Error::CategoryNotFound) if self.mapping.create_missing_targets && !local_category.is_empty() => {
+                let category_id = self
+                    .category_repository
+                    .add(&local_category)
+                    .await
+                    .map_err(|_| Error::Database)?;
+                self.report.created_categories.insert(local_category.clone());
+                Some(category_id)
+            }
+            Err(database::Error::CategoryNotFound) => {
+                self.report.missing_categories.insert(local_category.clone());
+                None
+            }
+            Err(_) => return Err(Error::Database),
+        };
+
+        self.category_ids.insert(local_category.clone(), category_id);
+
+        Ok(category_id.map(|id| (local_category, id)))
+    }
+
+    /// It returns the local tag IDs for the source tags. Tags that can't be
+    /// resolved are skipped and added to the report.
+    ///
+    /// # Errors
+    ///
+    /// It returns an error if there is a database error.
+    pub async fn resolve_tags(&mut self, source_tags: &[String]) -> Result<Vec<TagId>, Error> {
+        let mut tag_ids = Vec::with_capacity(source_tags.len());
+
+        for source_tag in source_tags.iter().filter(|t| !t.trim().is_empty()) {
+            let local_tag = match self.mapping.tag_for(source_tag) {
+                Some(local_tag) => local_tag.to_string(),
+                None => {
+                    *self.report.unmapped_tags.entry(source_tag.trim().to_string()).or_default() += 1;
+                    source_tag.trim().to_string()
+                }
+            };
+
+            if let Some(tag_id) = self.resolve_tag(&local_tag).await? {
+                if !tag_ids.contains(&tag_id) {
+                    tag_ids.push(tag_id);
+                }
+            }
+        }
+
+        Ok(tag_ids)
+    }
+
+    async fn resolve_tag(&mut self, local_tag: &str) -> Result<Option<TagId>, Error> {
+        if let Some(tag_id) = self.tag_ids.get(local_tag) {
+            return Ok(*tag_id);
+        }
+
+        let tag_id = match self.tag_repository.get_by_name(local_tag).await {
+            Ok(tag) => Some(tag.tag_id),
+            Err(database::Error::TagNotFound) if self.mapping.create_missing_targets => {
+                let tag_id = self.tag_repository.add(local_tag).await.map_err(|_| Error::Database)?;
+                self.report.created_tags.insert(local_tag.to_string());
+                Some(tag_id)
+            }
+            Err(database::Error::TagNotFound) => {
+                self.report.missing_tags.insert(local_tag.to_string());
+                None
+            }
+            Err(_) => return Err(Error::Database),
+        };
+
+        self.tag_ids.insert(local_tag.to_string(), tag_id);
+
+        Ok(tag_id)
+    }
+
+    /// The summary of the resolved values so far.
+    #[must_use]
+    pub fn report(&self) -> &Report {
+        &self.report
+    }
+}
+
+fn normalize(value: &str) -> String {
+    value.trim().to_lowercase()
+}
+
+fn normalize_keys(map: HashMap<String, String>) -> HashMap<String, String> {
+    map.into_iter()
+        .map(|(source, target)| (normalize(&source), target.trim().to_string()))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Mapping;
+
+    #[test]
+    fn it_should_map_source_values_ignoring_case_and_whitespace() {
+        let mapping = Mapping::load_from_toml(
+            r#"
+            [categories]
+            "Movies/HD" = "movies"
+
+            [tags]
+            "x264" = "h264"
+            "#,
+        )
+        .unwrap();
+
+        assert_eq!(mapping.category_for(" movies/hd "), Some("movies"));
+        assert_eq!(mapping.tag_for("X264"), Some("h264"));
+        assert_eq!(mapping.category_for("Apps"), None);
+    }
+
+    #[test]
+    fn it_should_not_create_missing_targets_by_default() {
+        let mapping = Mapping::load_from_toml("").unwrap();
+
+        assert!(!mapping.create_missing_targets);
+        assert_eq!(mapping.default_category, None);
+    }
+
+    #[test]
+    fn it_should_fail_loading_an_invalid_mapping_file() {
+        assert!(Mapping::load_from_toml("[categories]\nmovies = 1").is_err());
+    }
+}
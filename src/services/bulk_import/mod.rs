@@ -0,0 +1,303 @@
+//! Bulk torrent import.
+//!
+//! Site migrations ingest torrents exported from another index. The source
+//! site usually has its own categories and tags, so the import can use a
+//! [`mapping`] file to translate them into the local taxonomy.
+//!
+//! The import walks a directory looking for `.torrent` files (including
+//! subdirectories). The title, description, category and tags of each
+//! torrent are taken from an optional CSV [`manifest`]. Every torrent is
+//! uploaded like a regular upload by the importing administrator, so the
+//! same validations apply.
+//!
+//! The import can be run with the `import_torrents` console command or with
+//! the admin API endpoint. In both cases the paths are paths in the server.
+//! Remote peers push the torrents through the API in import [`session`]s.
+pub mod manifest;
+pub mod mapping;
+pub mod session;
+
+use std::collections::BTreeMap;
+use std::sync::Arc;
+
+use camino::{Utf8Path, Utf8PathBuf};
+use serde::{Deserialize, Serialize};
+use tracing::{debug, info};
+use utoipa::ToSchema;
+
+use self::manifest::Manifest;
+use self::mapping::{Mapper, Mapping, Report};
+use super::authorization::{self, ACTION};
+use super::category::DbCategoryRepository;
+use super::tag::DbTagRepository;
+use super::torrent::{self, AddTorrentRequest};
+use crate::errors::ServiceError;
+use crate::models::torrent::TorrentId;
+use crate::models::user::UserId;
+
+const TORRENT_FILE_EXTENSION: &str = "torrent";
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+pub struct ImportRequest {
+    /// Directory containing the `.torrent` files.
+    #[schema(value_type = String)]
+    pub directory: Utf8PathBuf,
+    /// CSV file with the metadata of the torrents.
+    #[schema(value_type = Option<String>)]
+    pub manifest: Option<Utf8PathBuf>,
+    /// TOML file with the categories and tags mapping.
+    #[schema(value_type = Option<String>)]
+    pub mapping: Option<Utf8PathBuf>,
+}
+
+/// Summary of an import.
+#[derive(Debug, Default, Clone, PartialEq, Eq, Serialize)]
+pub struct ImportSummary {
+    /// Number of torrent files found.
+    pub total: usize,
+    pub imported: Vec<ImportedTorrent>,
+    /// Torrents already in the index.
+    pub duplicates: Vec<SkippedTorrent>,
+    pub failures: Vec<SkippedTorrent>,
+    /// Categories and tags that could not be mapped.
+    pub mapping: Report,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct ImportedTorrent {
+    /// Path relative to the import directory.
+    pub file: String,
+    pub torrent_id: TorrentId,
+    pub info_hash: String,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct SkippedTorrent {
+    /// Path relative to the import directory.
+    pub file: String,
+    pub reason: String,
+}
+
+pub struct Service {
+    torrent_index: Arc<torrent::Index>,
+    category_repository: Arc<DbCategoryRepository>,
+    tag_repository: Arc<DbTagRepository>,
+    authorization_service: Arc<authorization::Service>,
+}
+
+impl Service {
+    #[must_use]
+    pub fn new(
+        torrent_index: Arc<torrent::Index>,
+        category_repository: Arc<DbCategoryRepository>,
+        tag_repository: Arc<DbTagRepository>,
+        authorization_service: Arc<authorization::Service>,
+    ) -> Service {
+        Service {
+            torrent_index,
+            category_repository,
+            tag_repository,
+            authorization_service,
+        }
+    }
+
+    /// It imports all the torrent files in the directory.
+    ///
+    /// Torrents that can't be imported are skipped. They are included in the
+    /// summary with the reason.
+    ///
+    /// # Errors
+    ///
+    /// It returns an error if:
+    ///
+    /// * The user does not have permissions to import torrents.
+    /// * The directory, the manifest or the mapping file can't be read.
+    /// * There is a database error resolving the mapping.
+    pub async fn import(&self, request: &ImportRequest, maybe_user_id: Option<UserId>) -> Result<ImportSummary, ServiceError> {
+        self.authorization_service
+            .authorize(ACTION::ImportTorrents, maybe_user_id)
+            .await?;
+
+        let manifest = match &request.manifest {
+            Some(path) => Manifest::load_from_file(path).map_err(|_| ServiceError::InvalidImportManifest)?,
+            None => Manifest::default(),
+        };
+
+        let mapping = match &request.mapping {
+            Some(path) => Mapping::load_from_file(path).map_err(|_| ServiceError::InvalidImportMapping)?,
+            None => Mapping::default(),
+        };
+
+        let files = find_torrent_files(&request.directory).map_err(|_| ServiceError::InvalidImportDirectory)?;
+
+        info!(target: "bulk-import", "Importing {} torrent files from {}", files.len(), request.directory);
+
+        let mut mapper = Mapper::new(mapping, self.category_repository.clone(), self.tag_repository.clone());
+
+        let mut summary = ImportSummary {
+            total: files.len(),
+            ..ImportSummary::default()
+        };
+
+        for relative_path in files {
+            let file = relative_path.to_string();
+
+            match self
+                .import_file(&request.directory, &relative_path, &manifest, &mut mapper, maybe_user_id)
+                .await?
+            {
+                Ok(imported) => summary.imported.push(imported),
+                Err(error) => {
+                    debug!(target: "bulk-import", "Skipped {file}: {error}");
+
+                    let skipped = SkippedTorrent {
+                        file,
+                        reason: error.to_string(),
+                    };
+
+                    if is_duplicate(&error) {
+                        summary.duplicates.push(skipped);
+                    } else {
+                        summary.failures.push(skipped);
+                    }
+                }
+            }
+        }
+
+        summary.mapping = mapper.report().clone();
+
+        info!(
+            target: "bulk-import",
+            "Import finished: {} imported, {} duplicates, {} failures",
+            summary.imported.len(),
+            summary.duplicates.len(),
+            summary.failures.len()
+        );
+
+        Ok(summary)
+    }
+
+    /// It imports one torrent file. The outer error aborts the whole import,
+    /// the inner one only skips the file.
+    async fn import_file(
+        &self,
+        directory: &Utf8Path,
+        relative_path: &Utf8Path,
+        manifest: &Manifest,
+        mapper: &mut Mapper,
+        maybe_user_id: Option<UserId>,
+    ) -> Result<Result<ImportedTorrent, ServiceError>, ServiceError> {
+        let entry = manifest.entry_for(relative_path).cloned().unwrap_or_default();
+
+        let category = match &entry.category {
+            Some(source_category) => mapper.resolve_category(source_category).await,
+            None => mapper.resolve_default_category().await,
+        }
+        .map_err(|_| ServiceError::DatabaseError)?;
+
+        let Some((category_name, _category_id)) = category else {
+            return Ok(Err(ServiceError::InvalidCategory));
+        };
+
+        let tags = mapper
+            .resolve_tags(&entry.tags)
+            .await
+            .map_err(|_| ServiceError::DatabaseError)?;
+
+        let Ok(torrent_buffer) = std::fs::read(directory.join(relative_path)) else {
+            return Ok(Err(ServiceError::InvalidTorrentFile));
+        };
+
+        let title = entry
+            .title
+            .unwrap_or_else(|| relative_path.file_stem().unwrap_or_default().to_string());
+
+        let add_torrent_request = AddTorrentRequest {
+            title,
+            description: entry.description.unwrap_or_default(),
+            category_name,
+            tags,
+            torrent_buffer,
+            publish_at: None,
+            preserve_announce_urls: None,
+            fields: BTreeMap::new(),
+        };
+
+        Ok(self
+            .torrent_index
+            .add_torrent(add_torrent_request, maybe_user_id)
+            .await
+            .map(|response| ImportedTorrent {
+                file: relative_path.to_string(),
+                torrent_id: response.torrent_id,
+                info_hash: response.info_hash,
+            }))
+    }
+}
+
+/// Torrents rejected because they are already in the index.
+fn is_duplicate(error: &ServiceError) -> bool {
+    matches!(
+        error,
+        ServiceError::InfoHashAlreadyExists
+            | ServiceError::OriginalInfoHashAlreadyExists
+            | ServiceError::CanonicalInfoHashAlreadyExists
+    )
+}
+
+/// It returns the paths, relative to the directory, of all the `.torrent`
+/// files in the directory and its subdirectories, sorted.
+///
+/// # Errors
+///
+/// It returns an error if a directory can't be read.
+fn find_torrent_files(directory: &Utf8Path) -> std::io::Result<Vec<Utf8PathBuf>> {
+    let mut files = vec![];
+    let mut pending = vec![directory.to_path_buf()];
+
+    while let Some(current) = pending.pop() {
+        for entry in current.read_dir_utf8()? {
+            let entry = entry?;
+            let path = entry.path();
+
+            if entry.file_type()?.is_dir() {
+                pending.push(path.to_path_buf());
+            } else if path.extension() == Some(TORRENT_FILE_EXTENSION) {
+                if let Ok(relative_path) = path.strip_prefix(directory) {
+                    files.push(relative_path.to_path_buf());
+                }
+            }
+        }
+    }
+
+    files.sort();
+
+    Ok(files)
+}
+
+#[cfg(test)]
+mod tests {
+    use camino::Utf8PathBuf;
+
+    use super::find_torrent_files;
+
+    #[test]
+    fn it_should_find_the_torrent_files_in_the_directory_and_its_subdirectories() {
+        let directory = std::env::temp_dir().join(format!("torrust-bulk-import-{}", uuid::Uuid::new_v4()));
+        let directory = Utf8PathBuf::try_from(directory).unwrap();
+
+        std::fs::create_dir_all(directory.join("linux")).unwrap();
+        std::fs::write(directory.join("b.torrent"), b"").unwrap();
+        std::fs::write(directory.join("notes.txt"), b"").unwrap();
+        std::fs::write(directory.join("linux").join("a.torrent"), b"").unwrap();
+
+        let files = find_torrent_files(&directory).unwrap();
+
+        std::fs::remove_dir_all(&directory).unwrap();
+
+        assert_eq!(
+            files,
+            vec![Utf8PathBuf::from("b.torrent"), Utf8PathBuf::from("linux/a.torrent")]
+        );
+    }
+}
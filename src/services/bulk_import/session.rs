@@ -0,0 +1,560 @@
+//! Chunked and resumable imports for remote peers.
+//!
+//! The directory import only works with files in the server. Trusted peers
+//! and migration tools push the torrents through the API instead:
+//!
+//! 1. They create an import session with the name of the source, the
+//!    [`ConflictPolicy`] and, optionally, a categories and tags
+//!    [`Mapping`].
+//! 2. They push the torrents in batches of up to [`MAX_BATCH_SIZE`]
+//!    torrents. Each batch has an ID chosen by the peer. Pushing a batch
+//!    again returns the summary of the first time without importing it
+//!    again, so batches can be retried safely.
+//! 3. They mark the session as completed.
+//!
+//! The status of the session includes the IDs of the processed batches, so
+//! an interrupted import can be resumed by pushing the missing ones.
+//!
+//! Every torrent is uploaded like a regular upload by the importing user, so
+//! the same validations apply. When a batch fails because of a database
+//! error, nothing is recorded for the batch and it can be pushed again. The
+//! torrents imported before the error are then reported as duplicates.
+use std::collections::BTreeMap;
+use std::sync::Arc;
+
+use base64::engine::general_purpose::STANDARD;
+use base64::Engine;
+use serde::{Deserialize, Serialize};
+use tracing::{debug, info};
+use utoipa::ToSchema;
+
+use super::is_duplicate;
+use super::mapping::{Mapper, Mapping};
+use crate::databases::database::{Database, Error};
+use crate::errors::ServiceError;
+use crate::models::import_session::{ConflictPolicy, ImportBatch, ImportSession, ImportSessionId};
+use crate::models::torrent::TorrentId;
+use crate::models::user::UserId;
+use crate::services::authorization::{self, ACTION};
+use crate::services::category::DbCategoryRepository;
+use crate::services::tag::DbTagRepository;
+use crate::services::torrent::{self, AddTorrentRequest};
+use crate::utils::parse_torrent::decode_and_validate_torrent_file;
+
+/// Maximum number of torrents in a batch.
+pub const MAX_BATCH_SIZE: usize = 50;
+
+/// Maximum length of the batch IDs.
+const MAX_BATCH_ID_LENGTH: usize = 64;
+
+/// Maximum length of the source name.
+const MAX_SOURCE_LENGTH: usize = 100;
+
+/// Request to create an import session.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+pub struct CreateImportSessionRequest {
+    /// Name of the source site or tool.
+    pub source: String,
+    #[serde(default)]
+    pub conflict_policy: ConflictPolicy,
+    /// Categories and tags mapping. It has the same fields as the mapping
+    /// file of the directory import.
+    #[serde(default)]
+    #[schema(value_type = Option<Object>)]
+    pub mapping: Option<Mapping>,
+}
+
+/// A batch of torrents.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+pub struct ImportBatchRequest {
+    pub torrents: Vec<BatchTorrent>,
+}
+
+/// A torrent in a batch.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+pub struct BatchTorrent {
+    /// ID of the torrent in the source. It's used to identify the torrent in
+    /// the batch summary. Default to its position in the batch.
+    #[serde(default)]
+    pub source_id: Option<String>,
+    /// The torrent file encoded in Base64.
+    pub torrent: String,
+    pub title: String,
+    #[serde(default)]
+    pub description: String,
+    /// Source category. The mapping default category is used when missing.
+    #[serde(default)]
+    pub category: Option<String>,
+    /// Source tags.
+    #[serde(default)]
+    pub tags: Vec<String>,
+}
+
+/// Summary of a batch.
+#[derive(Debug, Default, Clone, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+pub struct BatchSummary {
+    pub batch_id: String,
+    /// Whether the batch was already processed. The summary is the one of
+    /// the first time.
+    pub replayed: bool,
+    pub imported: Vec<BatchTorrentResult>,
+    /// Torrents already in the index updated with the `update` conflict
+    /// policy.
+    pub updated: Vec<BatchTorrentResult>,
+    /// Torrents already in the index skipped with the `skip` conflict
+    /// policy.
+    pub duplicates: Vec<SkippedBatchTorrent>,
+    pub failures: Vec<SkippedBatchTorrent>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+pub struct BatchTorrentResult {
+    /// The source ID of the torrent.
+    pub source_id: String,
+    pub torrent_id: TorrentId,
+    pub info_hash: String,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+pub struct SkippedBatchTorrent {
+    /// The source ID of the torrent.
+    pub source_id: String,
+    pub reason: String,
+}
+
+pub struct Service {
+    import_session_repository: Arc<DbImportSessionRepository>,
+    torrent_index: Arc<torrent::Index>,
+    category_repository: Arc<DbCategoryRepository>,
+    tag_repository: Arc<DbTagRepository>,
+    authorization_service: Arc<authorization::Service>,
+}
+
+impl Service {
+    #[must_use]
+    pub fn new(
+        import_session_repository: Arc<DbImportSessionRepository>,
+        torrent_index: Arc<torrent::Index>,
+        category_repository: Arc<DbCategoryRepository>,
+        tag_repository: Arc<DbTagRepository>,
+        authorization_service: Arc<authorization::Service>,
+    ) -> Service {
+        Service {
+            import_session_repository,
+            torrent_index,
+            category_repository,
+            tag_repository,
+            authorization_service,
+        }
+    }
+
+    /// It creates a new import session.
+    ///
+    /// # Errors
+    ///
+    /// It returns an error if:
+    ///
+    /// * The user does not have permissions to import torrents.
+    /// * The source name is empty or too long.
+    /// * There is a database error.
+    pub async fn create_session(
+        &self,
+        request: &CreateImportSessionRequest,
+        maybe_user_id: Option<UserId>,
+    ) -> Result<ImportSession, ServiceError> {
+        let user_id = self.authorize(maybe_user_id).await?;
+
+        let source = request.source.trim();
+
+        if source.is_empty() || source.chars().count() > MAX_SOURCE_LENGTH {
+            return Err(ServiceError::InvalidImportSession);
+        }
+
+        let mapping = match &request.mapping {
+            Some(mapping) => Some(serde_json::to_string(mapping).map_err(|_| ServiceError::InvalidImportMapping)?),
+            None => None,
+        };
+
+        let import_id = self
+            .import_session_repository
+            .add(user_id, source, request.conflict_policy, mapping.as_deref())
+            .await?;
+
+        info!(target: "bulk-import", "Import session #{import_id} created for {source}");
+
+        self.get(import_id).await
+    }
+
+    /// It returns the status of an import session.
+    ///
+    /// # Errors
+    ///
+    /// It returns an error if:
+    ///
+    /// * The user does not have permissions to import torrents.
+    /// * The session does not exist.
+    /// * There is a database error.
+    pub async fn get_session(
+        &self,
+        import_id: ImportSessionId,
+        maybe_user_id: Option<UserId>,
+    ) -> Result<ImportSession, ServiceError> {
+        self.authorize(maybe_user_id).await?;
+
+        self.get(import_id).await
+    }
+
+    /// It marks an import session as completed. New batches are rejected
+    /// after that, but processed batches can still be pushed again.
+    ///
+    /// # Errors
+    ///
+    /// It returns an error if:
+    ///
+    /// * The user does not have permissions to import torrents.
+    /// * The session does not exist.
+    /// * There is a database error.
+    pub async fn complete_session(
+        &self,
+        import_id: ImportSessionId,
+        maybe_user_id: Option<UserId>,
+    ) -> Result<ImportSession, ServiceError> {
+        self.authorize(maybe_user_id).await?;
+
+        self.import_session_repository.get(import_id).await?;
+
+        self.import_session_repository.complete(import_id).await?;
+
+        info!(target: "bulk-import", "Import session #{import_id} completed");
+
+        self.get(import_id).await
+    }
+
+    /// It imports a batch of torrents. When the batch was already processed,
+    /// it returns the summary of the first time.
+    ///
+    /// Torrents that can't be imported are skipped. They are included in the
+    /// summary with the reason.
+    ///
+    /// # Errors
+    ///
+    /// It returns an error if:
+    ///
+    /// * The user does not have permissions to import torrents.
+    /// * The batch ID is not valid, or the batch is empty or too big.
+    /// * The session does not exist or it's completed.
+    /// * There is a database error.
+    pub async fn import_batch(
+        &self,
+        import_id: ImportSessionId,
+        batch_id: &str,
+        request: &ImportBatchRequest,
+        maybe_user_id: Option<UserId>,
+    ) -> Result<BatchSummary, ServiceError> {
+        let user_id = self.authorize(maybe_user_id).await?;
+
+        validate_batch(batch_id, request)?;
+
+        let session = self.import_session_repository.get(import_id).await?;
+
+        if let Some(summary) = self.import_session_repository.get_batch_summary(import_id, batch_id).await? {
+            debug!(target: "bulk-import", "Batch {batch_id} of import session #{import_id} already processed");
+
+            let mut summary: BatchSummary = serde_json::from_str(&summary).map_err(|_| ServiceError::InternalServerError)?;
+            summary.replayed = true;
+
+            return Ok(summary);
+        }
+
+        if !session.is_open() {
+            return Err(ServiceError::ImportSessionCompleted);
+        }
+
+        let mapping = match &session.mapping {
+            Some(mapping) => serde_json::from_str::<Mapping>(mapping)
+                .map_err(|_| ServiceError::InvalidImportMapping)?
+                .normalized(),
+            None => Mapping::default(),
+        };
+
+        let conflict_policy = if session.conflict_policy == ConflictPolicy::Update.to_string() {
+            ConflictPolicy::Update
+        } else {
+            ConflictPolicy::Skip
+        };
+
+        let mut mapper = Mapper::new(mapping, self.category_repository.clone(), self.tag_repository.clone());
+
+        let mut summary = BatchSummary {
+            batch_id: batch_id.to_string(),
+            ..BatchSummary::default()
+        };
+
+        for (position, batch_torrent) in request.torrents.iter().enumerate() {
+            let source_id = batch_torrent.source_id.clone().unwrap_or_else(|| format!("#{position}"));
+
+            self.import_torrent(batch_torrent, source_id, conflict_policy, &mut mapper, user_id, &mut summary)
+                .await?;
+        }
+
+        let batch = ImportBatch {
+            batch_id: batch_id.to_string(),
+            imported: i64::try_from(summary.imported.len()).unwrap_or_default(),
+            updated: i64::try_from(summary.updated.len()).unwrap_or_default(),
+            duplicates: i64::try_from(summary.duplicates.len()).unwrap_or_default(),
+            failures: i64::try_from(summary.failures.len()).unwrap_or_default(),
+            summary: serde_json::to_string(&summary).map_err(|_| ServiceError::InternalServerError)?,
+        };
+
+        self.import_session_repository.add_batch(import_id, &batch).await?;
+
+        info!(
+            target: "bulk-import",
+            "Batch {batch_id} of import session #{import_id}: {} imported, {} updated, {} duplicates, {} failures",
+            batch.imported,
+            batch.updated,
+            batch.duplicates,
+            batch.failures
+        );
+
+        Ok(summary)
+    }
+
+    /// It imports one torrent of a batch and adds the result to the summary.
+    /// The error aborts the whole batch.
+    async fn import_torrent(
+        &self,
+        batch_torrent: &BatchTorrent,
+        source_id: String,
+        conflict_policy: ConflictPolicy,
+        mapper: &mut Mapper,
+        user_id: UserId,
+        summary: &mut BatchSummary,
+    ) -> Result<(), ServiceError> {
+        let category = match &batch_torrent.category {
+            Some(source_category) => mapper.resolve_category(source_category).await,
+            None => mapper.resolve_default_category().await,
+        }
+        .map_err(|_| ServiceError::DatabaseError)?;
+
+        let Some((category_name, category_id)) = category else {
+            summary.failures.push(skipped(source_id, &ServiceError::InvalidCategory));
+            return Ok(());
+        };
+
+        let tags = mapper
+            .resolve_tags(&batch_torrent.tags)
+            .await
+            .map_err(|_| ServiceError::DatabaseError)?;
+
+        let Ok(torrent_buffer) = STANDARD.decode(batch_torrent.torrent.trim()) else {
+            summary.failures.push(skipped(source_id, &ServiceError::InvalidTorrentFile));
+            return Ok(());
+        };
+
+        let add_torrent_request = AddTorrentRequest {
+            title: batch_torrent.title.clone(),
+            description: batch_torrent.description.clone(),
+            category_name,
+            tags: tags.clone(),
+            torrent_buffer: torrent_buffer.clone(),
+            publish_at: None,
+            preserve_announce_urls: None,
+            fields: BTreeMap::new(),
+        };
+
+        match self.torrent_index.add_torrent(add_torrent_request, Some(user_id)).await {
+            Ok(response) => summary.imported.push(BatchTorrentResult {
+                source_id,
+                torrent_id: response.torrent_id,
+                info_hash: response.info_hash,
+            }),
+            Err(error) if is_duplicate(&error) && conflict_policy == ConflictPolicy::Update => {
+                let Ok((torrent, _original_info_hash)) = decode_and_validate_torrent_file(&torrent_buffer) else {
+                    summary.failures.push(skipped(source_id, &ServiceError::InvalidTorrentFile));
+                    return Ok(());
+                };
+
+                match self
+                    .torrent_index
+                    .update_torrent_info(
+                        &torrent.canonical_info_hash(),
+                        &Some(batch_torrent.title.clone()),
+                        &Some(batch_torrent.description.clone()),
+                        &Some(category_id),
+                        &Some(tags),
+                        &user_id,
+                    )
+                    .await
+                {
+                    Ok(response) => summary.updated.push(BatchTorrentResult {
+                        source_id,
+                        torrent_id: response.torrent_id,
+                        info_hash: response.info_hash,
+                    }),
+                    Err(error) => summary.failures.push(skipped(source_id, &error)),
+                }
+            }
+            Err(error) if is_duplicate(&error) => summary.duplicates.push(skipped(source_id, &error)),
+            Err(error) => summary.failures.push(skipped(source_id, &error)),
+        }
+
+        Ok(())
+    }
+
+    async fn authorize(&self, maybe_user_id: Option<UserId>) -> Result<UserId, ServiceError> {
+        self.authorization_service
+            .authorize(ACTION::ImportTorrents, maybe_user_id)
+            .await?;
+
+        maybe_user_id.ok_or(ServiceError::UnauthorizedActionForGuests)
+    }
+
+    async fn get(&self, import_id: ImportSessionId) -> Result<ImportSession, ServiceError> {
+        let mut session = self.import_session_repository.get(import_id).await?;
+
+        session.batch_ids = self.import_session_repository.get_batch_ids(import_id).await?;
+
+        Ok(session)
+    }
+}
+
+fn skipped(source_id: String, error: &ServiceError) -> SkippedBatchTorrent {
+    debug!(target: "bulk-import", "Skipped {source_id}: {error}");
+
+    SkippedBatchTorrent {
+        source_id,
+        reason: error.to_string(),
+    }
+}
+
+/// It checks the batch ID and the number of torrents in the batch. Batch IDs
+/// can only contain ASCII letters, digits, `-` and `_`.
+fn validate_batch(batch_id: &str, request: &ImportBatchRequest) -> Result<(), ServiceError> {
+    let is_valid_batch_id = !batch_id.is_empty()
+        && batch_id.len() <= MAX_BATCH_ID_LENGTH
+        && batch_id.chars().all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_');
+
+    if !is_valid_batch_id || request.torrents.is_empty() || request.torrents.len() > MAX_BATCH_SIZE {
+        return Err(ServiceError::InvalidImportBatch);
+    }
+
+    Ok(())
+}
+
+pub struct DbImportSessionRepository {
+    database: Arc<Box<dyn Database>>,
+}
+
+impl DbImportSessionRepository {
+    #[must_use]
+    pub fn new(database: Arc<Box<dyn Database>>) -> Self {
+        Self { database }
+    }
+
+    /// It adds a new import session.
+    ///
+    /// # Errors
+    ///
+    /// It returns an error if there is a database error.
+    pub async fn add(
+        &self,
+        user_id: UserId,
+        source: &str,
+        conflict_policy: ConflictPolicy,
+        mapping: Option<&str>,
+    ) -> Result<ImportSessionId, Error> {
+        self.database
+            .insert_import_session(user_id, source, &conflict_policy.to_string(), mapping)
+            .await
+    }
+
+    /// It returns an import session.
+    ///
+    /// # Errors
+    ///
+    /// It returns an error if the session does not exist or there is a
+    /// database error.
+    pub async fn get(&self, import_id: ImportSessionId) -> Result<ImportSession, Error> {
+        self.database.get_import_session(import_id).await
+    }
+
+    /// It marks an import session as completed.
+    ///
+    /// # Errors
+    ///
+    /// It returns an error if there is a database error.
+    pub async fn complete(&self, import_id: ImportSessionId) -> Result<(), Error> {
+        self.database.complete_import_session(import_id).await
+    }
+
+    /// It returns the IDs of the processed batches of an import session.
+    ///
+    /// # Errors
+    ///
+    /// It returns an error if there is a database error.
+    pub async fn get_batch_ids(&self, import_id: ImportSessionId) -> Result<Vec<String>, Error> {
+        self.database.get_import_batch_ids(import_id).await
+    }
+
+    /// It returns the summary of a processed batch.
+    ///
+    /// # Errors
+    ///
+    /// It returns an error if there is a database error.
+    pub async fn get_batch_summary(&self, import_id: ImportSessionId, batch_id: &str) -> Result<Option<String>, Error> {
+        self.database.get_import_batch_summary(import_id, batch_id).await
+    }
+
+    /// It adds a processed batch.
+    ///
+    /// # Errors
+    ///
+    /// It returns an error if there is a database error.
+    pub async fn add_batch(&self, import_id: ImportSessionId, batch: &ImportBatch) -> Result<bool, Error> {
+        self.database.insert_import_batch(import_id, batch).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{validate_batch, BatchTorrent, ImportBatchRequest, MAX_BATCH_SIZE};
+    use crate::errors::ServiceError;
+
+    fn batch(torrents: usize) -> ImportBatchRequest {
+        ImportBatchRequest {
+            torrents: vec![
+                BatchTorrent {
+                    source_id: None,
+                    torrent: String::new(),
+                    title: "Ubuntu".to_string(),
+                    description: String::new(),
+                    category: None,
+                    tags: vec![],
+                };
+                torrents
+            ],
+        }
+    }
+
+    #[test]
+    fn it_should_accept_batches_with_a_valid_id_and_size() {
+        assert_eq!(validate_batch("batch-0001_a", &batch(1)), Ok(()));
+        assert_eq!(validate_batch("b", &batch(MAX_BATCH_SIZE)), Ok(()));
+    }
+
+    #[test]
+    fn it_should_reject_invalid_batch_ids_and_sizes() {
+        assert_eq!(validate_batch("", &batch(1)), Err(ServiceError::InvalidImportBatch));
+        assert_eq!(validate_batch("batch/1", &batch(1)), Err(ServiceError::InvalidImportBatch));
+        assert_eq!(
+            validate_batch(&"a".repeat(65), &batch(1)),
+            Err(ServiceError::InvalidImportBatch)
+        );
+        assert_eq!(validate_batch("batch-1", &batch(0)), Err(ServiceError::InvalidImportBatch));
+        assert_eq!(
+            validate_batch("batch-1", &batch(MAX_BATCH_SIZE + 1)),
+            Err(ServiceError::InvalidImportBatch)
+        );
+    }
+}
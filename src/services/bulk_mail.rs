@@ -0,0 +1,263 @@
+//! Bulk email service.
+//!
+//! Administrators can send an announcement to a segment of the users: all
+//! users, users active in the last days or users with a role. Only users with
+//! a verified email receive bulk emails.
+//!
+//! The subject and the body are [Tera](https://keats.github.io/tera/)
+//! templates rendered for each recipient with these variables:
+//!
+//! - `username`: the recipient's username.
+//!
+//! Emails are not sent directly. They are added to the outbound email queue
+//! and sent in batches by the [`mail_queue`](crate::console::cronjobs::mail_queue)
+//! cronjob.
+//!
+//! Announcements are non-essential by default: they include a link to
+//! unsubscribe and users who unsubscribed don't receive them. Essential
+//! emails, like changes in the terms of service, are sent to all the users in
+//! the segment.
+use std::sync::Arc;
+
+use jsonwebtoken::{decode, Algorithm, DecodingKey, Validation};
+use serde::{Deserialize, Serialize};
+use tera::{Context, Tera};
+use tracing::info;
+use utoipa::ToSchema;
+
+use super::authorization::{self, ACTION};
+use crate::config::Configuration;
+use crate::databases::database::{Database, Error};
+use crate::errors::ServiceError;
+use crate::mailer::{self, VerifyClaims, EMAIL_UNSUBSCRIBE_ISSUER};
+use crate::models::email::{EmailId, QueuedEmail, Recipient, Segment};
+use crate::models::user::UserId;
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+pub struct BulkMailRequest {
+    /// Template for the subject.
+    pub subject: String,
+    /// Template for the plain text body.
+    pub body: String,
+    pub segment: Segment,
+    /// Essential emails are also sent to users who unsubscribed.
+    #[serde(default)]
+    pub essential: bool,
+}
+
+/// Summary of a bulk email.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct BulkMailSummary {
+    /// Number of emails added to the outbound email queue.
+    pub queued: usize,
+}
+
+pub struct Service {
+    configuration: Arc<Configuration>,
+    mailer: Arc<mailer::Service>,
+    email_queue_repository: Arc<DbEmailQueueRepository>,
+    authorization_service: Arc<authorization::Service>,
+}
+
+impl Service {
+    #[must_use]
+    pub fn new(
+        configuration: Arc<Configuration>,
+        mailer: Arc<mailer::Service>,
+        email_queue_repository: Arc<DbEmailQueueRepository>,
+        authorization_service: Arc<authorization::Service>,
+    ) -> Service {
+        Service {
+            configuration,
+            mailer,
+            email_queue_repository,
+            authorization_service,
+        }
+    }
+
+    /// It renders the email for each recipient in the segment and adds the
+    /// emails to the outbound email queue.
+    ///
+    /// # Errors
+    ///
+    /// It returns an error if:
+    ///
+    /// * The user does not have permissions to send bulk emails.
+    /// * The subject or the body are empty.
+    /// * The subject or the body are not valid templates.
+    /// * There is a database error.
+    pub async fn send(
+        &self,
+        request: &BulkMailRequest,
+        base_url: &str,
+        maybe_user_id: Option<UserId>,
+    ) -> Result<BulkMailSummary, ServiceError> {
+        self.authorization_service
+            .authorize(ACTION::SendBulkMail, maybe_user_id)
+            .await?;
+
+        if request.subject.trim().is_empty() || request.body.trim().is_empty() {
+            return Err(ServiceError::MissingBulkMailFields);
+        }
+
+        // Check the templates before queueing any email.
+        render(&request.subject, "username")?;
+        render(&request.body, "username")?;
+
+        let recipients = self
+            .email_queue_repository
+            .get_recipients(&request.segment, request.essential)
+            .await?;
+
+        for recipient in &recipients {
+            let subject = render(&request.subject, &recipient.username)?;
+            let mut body = render(&request.body, &recipient.username)?;
+
+            if !request.essential {
+                let unsubscribe_url = self.mailer.get_unsubscribe_url(recipient.user_id, base_url).await;
+                body.push_str(&unsubscribe_footer(&unsubscribe_url));
+            }
+
+            self.email_queue_repository
+                .enqueue(&recipient.email, subject.trim(), &body)
+                .await?;
+        }
+
+        info!(target: "mailer", "Queued {} bulk emails for the segment {:?}", recipients.len(), request.segment);
+
+        Ok(BulkMailSummary {
+            queued: recipients.len(),
+        })
+    }
+
+    /// It unsubscribes the user in the token from non-essential emails.
+    ///
+    /// It returns `false` if the token is not valid.
+    ///
+    /// # Errors
+    ///
+    /// It returns a `ServiceError::DatabaseError` if there is a database
+    /// error.
+    pub async fn unsubscribe(&self, token: &str) -> Result<bool, ServiceError> {
+        let settings = self.configuration.settings.read().await;
+
+        let Ok(token_data) = decode::<VerifyClaims>(
+            token,
+            &DecodingKey::from_secret(settings.auth.user_claim_token_pepper.as_bytes()),
+            &Validation::new(Algorithm::HS256),
+        ) else {
+            return Ok(false);
+        };
+
+        drop(settings);
+
+        if token_data.claims.iss != EMAIL_UNSUBSCRIBE_ISSUER {
+            return Ok(false);
+        }
+
+        self.email_queue_repository
+            .unsubscribe(token_data.claims.sub)
+            .await
+            .map_err(|_| ServiceError::DatabaseError)?;
+
+        Ok(true)
+    }
+}
+
+/// It renders a template for a recipient.
+fn render(template: &str, username: &str) -> Result<String, ServiceError> {
+    let mut context = Context::new();
+    context.insert("username", username);
+
+    Tera::one_off(template, &context, false).map_err(|_| ServiceError::InvalidBulkMailTemplate)
+}
+
+fn unsubscribe_footer(unsubscribe_url: &str) -> String {
+    format!("\n\n--\nYou are receiving this email because you have an account in this index.\nUnsubscribe: {unsubscribe_url}\n")
+}
+
+pub struct DbEmailQueueRepository {
+    database: Arc<Box<dyn Database>>,
+}
+
+impl DbEmailQueueRepository {
+    #[must_use]
+    pub fn new(database: Arc<Box<dyn Database>>) -> Self {
+        Self { database }
+    }
+
+    /// It returns the users in the segment with a verified email.
+    ///
+    /// # Errors
+    ///
+    /// It returns an error if there is a database error.
+    pub async fn get_recipients(&self, segment: &Segment, include_unsubscribed: bool) -> Result<Vec<Recipient>, Error> {
+        self.database.get_email_recipients(segment, include_unsubscribed).await
+    }
+
+    /// It unsubscribes a user from non-essential emails.
+    ///
+    /// # Errors
+    ///
+    /// It returns an error if there is a database error.
+    pub async fn unsubscribe(&self, user_id: UserId) -> Result<(), Error> {
+        self.database.insert_email_unsubscribe(user_id).await
+    }
+
+    /// It adds an email to the queue.
+    ///
+    /// # Errors
+    ///
+    /// It returns an error if there is a database error.
+    pub async fn enqueue(&self, recipient: &str, subject: &str, body: &str) -> Result<EmailId, Error> {
+        self.database.insert_queued_email(recipient, subject, body).await
+    }
+
+    /// It returns the oldest emails in the queue.
+    ///
+    /// # Errors
+    ///
+    /// It returns an error if there is a database error.
+    pub async fn get_batch(&self, limit: u32) -> Result<Vec<QueuedEmail>, Error> {
+        self.database.get_queued_emails(limit).await
+    }
+
+    /// It records a failed delivery attempt.
+    ///
+    /// # Errors
+    ///
+    /// It returns an error if there is a database error.
+    pub async fn increase_attempts(&self, email_id: EmailId) -> Result<(), Error> {
+        self.database.increase_queued_email_attempts(email_id).await
+    }
+
+    /// It removes an email from the queue.
+    ///
+    /// # Errors
+    ///
+    /// It returns an error if there is a database error.
+    pub async fn delete(&self, email_id: EmailId) -> Result<(), Error> {
+        self.database.delete_queued_email(email_id).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::render;
+    use crate::errors::ServiceError;
+
+    #[test]
+    fn it_should_render_the_template_for_each_recipient() {
+        assert_eq!(render("Hi {{ username }}!", "alice").unwrap(), "Hi alice!");
+    }
+
+    #[test]
+    fn it_should_not_escape_html_in_plain_text_emails() {
+        assert_eq!(render("{{ username }} & co", "<alice>").unwrap(), "<alice> & co");
+    }
+
+    #[test]
+    fn it_should_reject_invalid_templates() {
+        assert_eq!(render("Hi {{ username", "alice"), Err(ServiceError::InvalidBulkMailTemplate));
+    }
+}
@@ -1,23 +1,35 @@
 //! Category service.
 use std::sync::Arc;
 
+use super::audit;
 use super::authorization::{self, ACTION};
+use crate::cache::query::{self, QueryCache};
 use crate::databases::database::{Category, Database, Error as DatabaseError};
 use crate::errors::ServiceError;
-use crate::models::category::CategoryId;
+use crate::models::audit::AuditAction;
+use crate::models::category::{CategoryId, CategoryPolicy};
 use crate::models::user::UserId;
 
 pub struct Service {
     category_repository: Arc<DbCategoryRepository>,
+    category_moderator_repository: Arc<DbCategoryModeratorRepository>,
     authorization_service: Arc<authorization::Service>,
+    audit_service: Arc<audit::Service>,
 }
 
 impl Service {
     #[must_use]
-    pub fn new(category_repository: Arc<DbCategoryRepository>, authorization_service: Arc<authorization::Service>) -> Service {
+    pub fn new(
+        category_repository: Arc<DbCategoryRepository>,
+        category_moderator_repository: Arc<DbCategoryModeratorRepository>,
+        authorization_service: Arc<authorization::Service>,
+        audit_service: Arc<audit::Service>,
+    ) -> Service {
         Service {
             category_repository,
+            category_moderator_repository,
             authorization_service,
+            audit_service,
         }
     }
 
@@ -49,7 +61,13 @@ impl Service {
             Err(e) => match e {
                 // Otherwise try to create it
                 DatabaseError::CategoryNotFound => match self.category_repository.add(trimmed_name).await {
-                    Ok(id) => Ok(id),
+                    Ok(id) => {
+                        self.audit_service
+                            .record(maybe_user_id, AuditAction::CategoryAdd, trimmed_name, None)
+                            .await;
+
+                        Ok(id)
+                    }
                     Err(_) => Err(ServiceError::DatabaseError),
                 },
                 _ => Err(ServiceError::DatabaseError),
@@ -71,7 +89,13 @@ impl Service {
             .await?;
 
         match self.category_repository.delete(category_name).await {
-            Ok(()) => Ok(()),
+            Ok(()) => {
+                self.audit_service
+                    .record(maybe_user_id, AuditAction::CategoryDelete, category_name, None)
+                    .await;
+
+                Ok(())
+            }
             Err(e) => match e {
                 DatabaseError::CategoryNotFound => Err(ServiceError::CategoryNotFound),
                 _ => Err(ServiceError::DatabaseError),
@@ -97,25 +121,197 @@ impl Service {
             .await
             .map_err(|_| ServiceError::DatabaseError)
     }
+
+    /// Returns the usernames of the moderators of a category.
+    ///
+    /// # Errors
+    ///
+    /// It returns an error if:
+    ///
+    /// * The user does not have the required permissions.
+    /// * The category does not exist.
+    /// * There is a database error.
+    pub async fn get_moderators(&self, category_name: &str, maybe_user_id: Option<UserId>) -> Result<Vec<String>, ServiceError> {
+        self.authorization_service
+            .authorize(ACTION::ManageCategoryModerators, maybe_user_id)
+            .await?;
+
+        let category = self.get_category(category_name).await?;
+
+        Ok(self
+            .category_moderator_repository
+            .get_moderators(category.category_id)
+            .await?)
+    }
+
+    /// Makes a user a moderator of a category. Category moderators can
+    /// approve, reject, edit and delete the torrents in the category.
+    ///
+    /// # Errors
+    ///
+    /// It returns an error if:
+    ///
+    /// * The user does not have the required permissions.
+    /// * The category or the user does not exist.
+    /// * There is a database error.
+    pub async fn add_moderator(
+        &self,
+        category_name: &str,
+        username: &str,
+        maybe_user_id: Option<UserId>,
+    ) -> Result<(), ServiceError> {
+        self.authorization_service
+            .authorize(ACTION::ManageCategoryModerators, maybe_user_id)
+            .await?;
+
+        let category = self.get_category(category_name).await?;
+
+        let user_id = self.category_moderator_repository.get_user_id(username).await?;
+
+        self.category_moderator_repository.add(category.category_id, user_id).await?;
+
+        self.audit_service
+            .record(
+                maybe_user_id,
+                AuditAction::CategoryModeratorAdd,
+                category_name,
+                Some(username),
+            )
+            .await;
+
+        Ok(())
+    }
+
+    /// Removes a moderator from a category.
+    ///
+    /// # Errors
+    ///
+    /// It returns an error if:
+    ///
+    /// * The user does not have the required permissions.
+    /// * The category or the user does not exist.
+    /// * There is a database error.
+    pub async fn remove_moderator(
+        &self,
+        category_name: &str,
+        username: &str,
+        maybe_user_id: Option<UserId>,
+    ) -> Result<(), ServiceError> {
+        self.authorization_service
+            .authorize(ACTION::ManageCategoryModerators, maybe_user_id)
+            .await?;
+
+        let category = self.get_category(category_name).await?;
+
+        let user_id = self.category_moderator_repository.get_user_id(username).await?;
+
+        self.category_moderator_repository
+            .remove(category.category_id, user_id)
+            .await?;
+
+        self.audit_service
+            .record(
+                maybe_user_id,
+                AuditAction::CategoryModeratorRemove,
+                category_name,
+                Some(username),
+            )
+            .await;
+
+        Ok(())
+    }
+
+    /// Returns the moderation policy of a category.
+    ///
+    /// # Errors
+    ///
+    /// It returns an error if:
+    ///
+    /// * The user does not have the required permissions.
+    /// * The category does not exist.
+    /// * There is a database error.
+    pub async fn get_policy(&self, category_name: &str, maybe_user_id: Option<UserId>) -> Result<CategoryPolicy, ServiceError> {
+        self.authorization_service
+            .authorize(ACTION::ManageCategoryPolicies, maybe_user_id)
+            .await?;
+
+        let category = self.get_category(category_name).await?;
+
+        Ok(self.category_repository.get_policy(category.category_id).await?)
+    }
+
+    /// Sets the moderation policy of a category. It only applies to the
+    /// torrents approved after the change.
+    ///
+    /// # Errors
+    ///
+    /// It returns an error if:
+    ///
+    /// * The user does not have the required permissions.
+    /// * The category does not exist.
+    /// * There is a database error.
+    pub async fn set_policy(
+        &self,
+        category_name: &str,
+        policy: &CategoryPolicy,
+        maybe_user_id: Option<UserId>,
+    ) -> Result<(), ServiceError> {
+        self.authorization_service
+            .authorize(ACTION::ManageCategoryPolicies, maybe_user_id)
+            .await?;
+
+        let category = self.get_category(category_name).await?;
+
+        self.category_repository.set_policy(category.category_id, policy).await?;
+
+        self.audit_service
+            .record(
+                maybe_user_id,
+                AuditAction::CategoryPolicyChange,
+                category_name,
+                Some(&format!("co_signing: {}", policy.co_signing)),
+            )
+            .await;
+
+        Ok(())
+    }
+
+    async fn get_category(&self, category_name: &str) -> Result<Category, ServiceError> {
+        self.category_repository
+            .get_by_name(category_name)
+            .await
+            .map_err(|e| match e {
+                DatabaseError::CategoryNotFound => ServiceError::CategoryNotFound,
+                _ => ServiceError::DatabaseError,
+            })
+    }
 }
 
 pub struct DbCategoryRepository {
     database: Arc<Box<dyn Database>>,
+    query_cache: Arc<QueryCache>,
 }
 
 impl DbCategoryRepository {
     #[must_use]
-    pub fn new(database: Arc<Box<dyn Database>>) -> Self {
-        Self { database }
+    pub fn new(database: Arc<Box<dyn Database>>, query_cache: Arc<QueryCache>) -> Self {
+        Self { database, query_cache }
     }
 
     /// It returns the categories.
     ///
+    /// The cached categories are keyed by the torrent listing version, so the
+    /// number of torrents is updated when torrents are added or removed.
+    ///
     /// # Errors
     ///
     /// It returns an error if there is a database error.
     pub async fn get_all(&self) -> Result<Vec<Category>, DatabaseError> {
-        self.database.get_categories().await
+        let version = self.database.get_torrent_listing_version().await?;
+
+        self.query_cache
+            .get_or_load(query::CATEGORIES, &version, || self.database.get_categories())
+            .await
     }
 
     /// Adds a new category.
@@ -124,7 +320,11 @@ impl DbCategoryRepository {
     ///
     /// It returns an error if there is a database error.
     pub async fn add(&self, category_name: &str) -> Result<CategoryId, DatabaseError> {
-        self.database.insert_category_and_get_id(category_name).await
+        let result = self.database.insert_category_and_get_id(category_name).await;
+
+        self.invalidate_cache().await;
+
+        result
     }
 
     /// Deletes a new category.
@@ -133,7 +333,36 @@ impl DbCategoryRepository {
     ///
     /// It returns an error if there is a database error.
     pub async fn delete(&self, category_name: &str) -> Result<(), DatabaseError> {
-        self.database.delete_category(category_name).await
+        let result = self.database.delete_category(category_name).await;
+
+        self.invalidate_cache().await;
+
+        result
+    }
+
+    /// It removes the cached categories. It must be called after changes not
+    /// reflected in the torrent listing version, like moving a torrent to
+    /// another category.
+    pub async fn invalidate_cache(&self) {
+        self.query_cache.invalidate(query::CATEGORIES).await;
+    }
+
+    /// It returns the moderation policy of a category.
+    ///
+    /// # Errors
+    ///
+    /// It returns an error if there is a database error.
+    pub async fn get_policy(&self, category_id: CategoryId) -> Result<CategoryPolicy, DatabaseError> {
+        self.database.get_category_policy(category_id).await
+    }
+
+    /// It sets the moderation policy of a category.
+    ///
+    /// # Errors
+    ///
+    /// It returns an error if there is a database error.
+    pub async fn set_policy(&self, category_id: CategoryId, policy: &CategoryPolicy) -> Result<(), DatabaseError> {
+        self.database.upsert_category_policy(category_id, policy).await
     }
 
     /// It finds a category by name
@@ -154,3 +383,71 @@ impl DbCategoryRepository {
         self.database.get_category_from_id(*category_id).await
     }
 }
+
+pub struct DbCategoryModeratorRepository {
+    database: Arc<Box<dyn Database>>,
+}
+
+impl DbCategoryModeratorRepository {
+    #[must_use]
+    pub fn new(database: Arc<Box<dyn Database>>) -> Self {
+        Self { database }
+    }
+
+    /// It returns the ID of a user.
+    ///
+    /// # Errors
+    ///
+    /// It returns an error if the user does not exist.
+    pub async fn get_user_id(&self, username: &str) -> Result<UserId, DatabaseError> {
+        self.database
+            .get_user_profile_from_username(username)
+            .await
+            .map(|user_profile| user_profile.user_id)
+    }
+
+    /// It makes the user a moderator of the category.
+    ///
+    /// # Errors
+    ///
+    /// It returns an error if there is a database error.
+    pub async fn add(&self, category_id: CategoryId, user_id: UserId) -> Result<(), DatabaseError> {
+        self.database.insert_category_moderator(category_id, user_id).await
+    }
+
+    /// It removes the user from the moderators of the category.
+    ///
+    /// # Errors
+    ///
+    /// It returns an error if there is a database error.
+    pub async fn remove(&self, category_id: CategoryId, user_id: UserId) -> Result<(), DatabaseError> {
+        self.database.delete_category_moderator(category_id, user_id).await
+    }
+
+    /// It returns the usernames of the moderators of the category.
+    ///
+    /// # Errors
+    ///
+    /// It returns an error if there is a database error.
+    pub async fn get_moderators(&self, category_id: CategoryId) -> Result<Vec<String>, DatabaseError> {
+        self.database.get_category_moderators(category_id).await
+    }
+
+    /// It returns the categories moderated by the user.
+    ///
+    /// # Errors
+    ///
+    /// It returns an error if there is a database error.
+    pub async fn get_moderated_categories(&self, user_id: UserId) -> Result<Vec<Category>, DatabaseError> {
+        self.database.get_moderated_categories(user_id).await
+    }
+
+    /// It checks if the user is a moderator of the category.
+    ///
+    /// # Errors
+    ///
+    /// It returns an error if there is a database error.
+    pub async fn is_moderator(&self, category_id: CategoryId, user_id: UserId) -> Result<bool, DatabaseError> {
+        self.database.is_category_moderator(category_id, user_id).await
+    }
+}
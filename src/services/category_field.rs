@@ -0,0 +1,429 @@
+//! Custom fields of the torrents in each category.
+//!
+//! Admins can define structured fields for the torrents of a category, like
+//! the `resolution` of the videos or the `bitrate` of the music. Uploads to
+//! the category fill the fields, and the required ones can't be left empty.
+//! The values are shown in the torrent details and the listing can be
+//! filtered by them:
+//!
+//! ```text
+//! /v1/torrents?categories=movies&fields=resolution:1080p,codec:x265
+//! ```
+//!
+//! Fields have one of these types:
+//!
+//! - `text`: free text, up to 255 characters.
+//! - `integer`: a whole number.
+//! - `boolean`: `true` or `false`.
+//! - `choice`: one of the `options` of the field.
+use std::collections::BTreeMap;
+use std::sync::Arc;
+
+use super::audit;
+use super::authorization::{self, ACTION};
+use super::category::DbCategoryRepository;
+use crate::databases::database::{Category, Database, Error as DatabaseError};
+use crate::errors::ServiceError;
+use crate::models::audit::AuditAction;
+use crate::models::category::CategoryId;
+use crate::models::category_field::{CategoryField, CategoryFieldId, FieldType, TorrentFieldValue};
+use crate::models::torrent::TorrentId;
+use crate::models::user::UserId;
+
+/// Max number of fields of a category.
+const MAX_FIELDS_PER_CATEGORY: usize = 20;
+
+/// Max length of the field names.
+const MAX_FIELD_NAME_LENGTH: usize = 64;
+
+/// Max length of the field labels.
+const MAX_FIELD_LABEL_LENGTH: usize = 128;
+
+/// Max length of the text values and the options of `choice` fields.
+const MAX_FIELD_VALUE_LENGTH: usize = 255;
+
+/// User request to add a field to a category.
+pub struct AddCategoryFieldRequest {
+    pub name: String,
+    pub label: String,
+    pub field_type: FieldType,
+    pub required: bool,
+    pub options: Vec<String>,
+}
+
+pub struct Service {
+    category_repository: Arc<DbCategoryRepository>,
+    category_field_repository: Arc<DbCategoryFieldRepository>,
+    authorization_service: Arc<authorization::Service>,
+    audit_service: Arc<audit::Service>,
+}
+
+impl Service {
+    #[must_use]
+    pub fn new(
+        category_repository: Arc<DbCategoryRepository>,
+        category_field_repository: Arc<DbCategoryFieldRepository>,
+        authorization_service: Arc<authorization::Service>,
+        audit_service: Arc<audit::Service>,
+    ) -> Service {
+        Service {
+            category_repository,
+            category_field_repository,
+            authorization_service,
+            audit_service,
+        }
+    }
+
+    /// Adds a field to the torrents of a category. Existing torrents don't
+    /// have a value for the new field, even if it's required.
+    ///
+    /// # Errors
+    ///
+    /// It returns an error if:
+    ///
+    /// * The user does not have the required permissions.
+    /// * The category does not exist.
+    /// * The field is not valid or the category has already the max number
+    ///   of fields.
+    /// * The category has already a field with the same name.
+    /// * There is a database error.
+    pub async fn add_field(
+        &self,
+        category_name: &str,
+        request: &AddCategoryFieldRequest,
+        maybe_user_id: Option<UserId>,
+    ) -> Result<CategoryField, ServiceError> {
+        self.authorization_service
+            .authorize(ACTION::ManageCategoryFields, maybe_user_id)
+            .await?;
+
+        let category = self.get_category(category_name).await?;
+
+        let field = normalize_field(category.category_id, request)?;
+
+        if self.category_field_repository.get_all(category.category_id).await?.len() >= MAX_FIELDS_PER_CATEGORY {
+            return Err(ServiceError::InvalidCategoryField);
+        }
+
+        let field_id = self.category_field_repository.add(&field).await?;
+
+        self.audit_service
+            .record(
+                maybe_user_id,
+                AuditAction::CategoryFieldAdd,
+                category_name,
+                Some(&format!("field: {}, type: {}", field.name, field.field_type.as_str())),
+            )
+            .await;
+
+        Ok(CategoryField { field_id, ..field })
+    }
+
+    /// Returns the fields of a category.
+    ///
+    /// # Errors
+    ///
+    /// It returns an error if:
+    ///
+    /// * The user does not have the required permissions.
+    /// * The category does not exist.
+    /// * There is a database error.
+    pub async fn get_fields(
+        &self,
+        category_name: &str,
+        maybe_user_id: Option<UserId>,
+    ) -> Result<Vec<CategoryField>, ServiceError> {
+        self.authorization_service
+            .authorize(ACTION::GetCategories, maybe_user_id)
+            .await?;
+
+        let category = self.get_category(category_name).await?;
+
+        Ok(self.category_field_repository.get_all(category.category_id).await?)
+    }
+
+    /// Removes a field from a category, with the values of all the torrents.
+    ///
+    /// # Errors
+    ///
+    /// It returns an error if:
+    ///
+    /// * The user does not have the required permissions.
+    /// * The category or the field do not exist.
+    /// * There is a database error.
+    pub async fn delete_field(
+        &self,
+        category_name: &str,
+        field_name: &str,
+        maybe_user_id: Option<UserId>,
+    ) -> Result<(), ServiceError> {
+        self.authorization_service
+            .authorize(ACTION::ManageCategoryFields, maybe_user_id)
+            .await?;
+
+        let category = self.get_category(category_name).await?;
+
+        self.category_field_repository
+            .delete(category.category_id, field_name)
+            .await?;
+
+        self.audit_service
+            .record(
+                maybe_user_id,
+                AuditAction::CategoryFieldDelete,
+                category_name,
+                Some(&format!("field: {field_name}")),
+            )
+            .await;
+
+        Ok(())
+    }
+
+    async fn get_category(&self, category_name: &str) -> Result<Category, ServiceError> {
+        self.category_repository
+            .get_by_name(category_name)
+            .await
+            .map_err(|e| match e {
+                DatabaseError::CategoryNotFound => ServiceError::CategoryNotFound,
+                _ => ServiceError::DatabaseError,
+            })
+    }
+}
+
+/// It validates a new field and it trims its name, label and options.
+fn normalize_field(category_id: CategoryId, request: &AddCategoryFieldRequest) -> Result<CategoryField, ServiceError> {
+    let name = request.name.trim().to_string();
+    let label = request.label.trim().to_string();
+
+    // The names are used in the listing filter, so they can't have `:` or `,`
+    let is_valid_name = !name.is_empty()
+        && name.len() <= MAX_FIELD_NAME_LENGTH
+        && name.chars().all(|c| c.is_ascii_lowercase() || c.is_ascii_digit() || c == '_');
+
+    if !is_valid_name || label.is_empty() || label.chars().count() > MAX_FIELD_LABEL_LENGTH {
+        return Err(ServiceError::InvalidCategoryField);
+    }
+
+    let options: Vec<String> = request.options.iter().map(|option| option.trim().to_string()).collect();
+
+    match request.field_type {
+        FieldType::Choice => {
+            let has_invalid_options = options.iter().enumerate().any(|(i, option)| {
+                option.is_empty() || option.chars().count() > MAX_FIELD_VALUE_LENGTH || options[..i].contains(option)
+            });
+
+            if options.is_empty() || has_invalid_options {
+                return Err(ServiceError::InvalidCategoryField);
+            }
+        }
+        FieldType::Text | FieldType::Integer | FieldType::Boolean => {
+            if !options.is_empty() {
+                return Err(ServiceError::InvalidCategoryField);
+            }
+        }
+    }
+
+    Ok(CategoryField {
+        field_id: 0,
+        category_id,
+        name,
+        label,
+        field_type: request.field_type,
+        required: request.required,
+        options,
+    })
+}
+
+/// It validates the values of the category fields in an upload, by field
+/// name. It returns the normalized values by field ID.
+///
+/// # Errors
+///
+/// It returns an error if a value is not valid for the field type, there is
+/// no field with the name or a required field is empty.
+pub fn validate_values(
+    fields: &[CategoryField],
+    values: &BTreeMap<String, String>,
+) -> Result<Vec<(CategoryFieldId, String)>, ServiceError> {
+    if values.keys().any(|name| !fields.iter().any(|field| &field.name == name)) {
+        return Err(ServiceError::InvalidCategoryFieldValue);
+    }
+
+    let mut validated = vec![];
+
+    for field in fields {
+        let value = values.get(&field.name).map(|value| value.trim()).unwrap_or_default();
+
+        if value.is_empty() {
+            if field.required {
+                return Err(ServiceError::MissingCategoryFieldValue);
+            }
+            continue;
+        }
+
+        let value = match field.field_type {
+            FieldType::Text if value.chars().count() <= MAX_FIELD_VALUE_LENGTH => value.to_string(),
+            FieldType::Integer => value
+                .parse::<i64>()
+                .map_err(|_| ServiceError::InvalidCategoryFieldValue)?
+                .to_string(),
+            FieldType::Boolean if value == "true" || value == "false" => value.to_string(),
+            FieldType::Choice if field.options.iter().any(|option| option == value) => value.to_string(),
+            _ => return Err(ServiceError::InvalidCategoryFieldValue),
+        };
+
+        validated.push((field.field_id, value));
+    }
+
+    Ok(validated)
+}
+
+pub struct DbCategoryFieldRepository {
+    database: Arc<Box<dyn Database>>,
+}
+
+impl DbCategoryFieldRepository {
+    #[must_use]
+    pub fn new(database: Arc<Box<dyn Database>>) -> Self {
+        Self { database }
+    }
+
+    /// It adds a field to a category.
+    ///
+    /// # Errors
+    ///
+    /// It returns an error if the category has already a field with the same
+    /// name or there is a database error.
+    pub async fn add(&self, field: &CategoryField) -> Result<CategoryFieldId, DatabaseError> {
+        self.database
+            .insert_category_field(
+                field.category_id,
+                &field.name,
+                &field.label,
+                field.field_type,
+                field.required,
+                &field.options,
+            )
+            .await
+    }
+
+    /// It returns the fields of a category.
+    ///
+    /// # Errors
+    ///
+    /// It returns an error if there is a database error.
+    pub async fn get_all(&self, category_id: CategoryId) -> Result<Vec<CategoryField>, DatabaseError> {
+        self.database.get_category_fields(category_id).await
+    }
+
+    /// It removes a field from a category.
+    ///
+    /// # Errors
+    ///
+    /// It returns an error if the field does not exist or there is a
+    /// database error.
+    pub async fn delete(&self, category_id: CategoryId, name: &str) -> Result<(), DatabaseError> {
+        self.database.delete_category_field(category_id, name).await
+    }
+
+    /// It validates the values of the fields of the category in an upload.
+    ///
+    /// # Errors
+    ///
+    /// It returns an error if the values are not valid or there is a
+    /// database error.
+    pub async fn validate_values(
+        &self,
+        category_id: CategoryId,
+        values: &BTreeMap<String, String>,
+    ) -> Result<Vec<(CategoryFieldId, String)>, ServiceError> {
+        let fields = self.get_all(category_id).await?;
+
+        validate_values(&fields, values)
+    }
+
+    /// It replaces the values of the category fields of a torrent.
+    ///
+    /// # Errors
+    ///
+    /// It returns an error if there is a database error.
+    pub async fn set_values(&self, torrent_id: TorrentId, values: &[(CategoryFieldId, String)]) -> Result<(), DatabaseError> {
+        self.database.set_torrent_field_values(torrent_id, values).await
+    }
+
+    /// It returns the values of the category fields of a torrent.
+    ///
+    /// # Errors
+    ///
+    /// It returns an error if there is a database error.
+    pub async fn get_values(&self, torrent_id: TorrentId) -> Result<Vec<TorrentFieldValue>, DatabaseError> {
+        self.database.get_torrent_field_values(torrent_id).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::BTreeMap;
+
+    use super::validate_values;
+    use crate::errors::ServiceError;
+    use crate::models::category_field::{CategoryField, FieldType};
+
+    fn field(field_id: i64, name: &str, field_type: FieldType, required: bool, options: &[&str]) -> CategoryField {
+        CategoryField {
+            field_id,
+            category_id: 1,
+            name: name.to_string(),
+            label: name.to_string(),
+            field_type,
+            required,
+            options: options.iter().map(ToString::to_string).collect(),
+        }
+    }
+
+    fn values(pairs: &[(&str, &str)]) -> BTreeMap<String, String> {
+        pairs
+            .iter()
+            .map(|(name, value)| ((*name).to_string(), (*value).to_string()))
+            .collect()
+    }
+
+    fn fields() -> Vec<CategoryField> {
+        vec![
+            field(1, "resolution", FieldType::Choice, true, &["720p", "1080p"]),
+            field(2, "runtime", FieldType::Integer, false, &[]),
+            field(3, "hdr", FieldType::Boolean, false, &[]),
+        ]
+    }
+
+    #[test]
+    fn it_should_normalize_the_values_of_the_category_fields() {
+        assert_eq!(
+            validate_values(&fields(), &values(&[("resolution", "1080p"), ("runtime", " +136 ")])),
+            Ok(vec![(1, "1080p".to_string()), (2, "136".to_string())])
+        );
+    }
+
+    #[test]
+    fn it_should_require_the_required_fields() {
+        assert_eq!(
+            validate_values(&fields(), &values(&[("runtime", "136")])),
+            Err(ServiceError::MissingCategoryFieldValue)
+        );
+    }
+
+    #[test]
+    fn it_should_reject_values_not_valid_for_the_field_type() {
+        for invalid in [
+            values(&[("resolution", "4k")]),
+            values(&[("resolution", "1080p"), ("runtime", "long")]),
+            values(&[("resolution", "1080p"), ("hdr", "yes")]),
+            values(&[("resolution", "1080p"), ("codec", "x265")]),
+        ] {
+            assert_eq!(
+                validate_values(&fields(), &invalid),
+                Err(ServiceError::InvalidCategoryFieldValue)
+            );
+        }
+    }
+}
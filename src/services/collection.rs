@@ -0,0 +1,668 @@
+//! Torrent collections service.
+//!
+//! Users can group torrents in named collections, for example all the
+//! releases of a series. Collections are public: anyone can see a collection
+//! with the aggregate size and peer counts of its torrents. Only the owner
+//! can change a collection, and moderators can delete it.
+//!
+//! # Smart collections
+//!
+//! Administrators can define smart collections with a [`SmartFilter`]
+//! (category, tags and upload date range) instead of adding the torrents one
+//! by one. The matching torrents are materialized as regular collection items
+//! when the filter is set and periodically by the `smart_collections`
+//! cronjob, so smart collections are listed as fast as the other ones and
+//! stay up to date with the new uploads. Their torrents can't be added or
+//! removed manually.
+use std::sync::Arc;
+
+use bittorrent_primitives::info_hash::InfoHash;
+use chrono::{NaiveDate, TimeDelta, Utc};
+use serde_derive::{Deserialize, Serialize};
+use utoipa::{IntoParams, ToSchema};
+
+use super::authorization::{self, ACTION};
+use super::torrent::{DbTorrentListingGenerator, ListingSpecification};
+use crate::config::Configuration;
+use crate::databases::database::{Database, Error, Sorting};
+use crate::errors::ServiceError;
+use crate::models::collection::{Collection, CollectionId, SmartFilter};
+use crate::models::response::TorrentsResponse;
+use crate::models::torrent::{ListingFilter, TorrentId, TorrentStatus};
+use crate::models::user::UserId;
+use crate::utils::clock::DATETIME_FORMAT;
+
+/// Maximum number of characters in a collection name.
+const MAX_NAME_LENGTH: usize = 100;
+
+/// Maximum number of torrents in a smart collection. Only the newest matching
+/// torrents are included.
+pub const MAX_SMART_COLLECTION_SIZE: u32 = 1000;
+
+/// User request to get a page of the torrents in a collection.
+#[derive(Debug, Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
+pub struct ListingRequest {
+    pub page_size: Option<u8>,
+    pub page: Option<u32>,
+}
+
+/// The public page of a collection: the collection with its aggregate stats
+/// and a page of its torrents, from the newest item to the oldest.
+#[derive(Debug, Serialize, ToSchema)]
+pub struct CollectionPage {
+    pub collection: Collection,
+    /// The filter of a smart collection.
+    pub filter: Option<SmartFilter>,
+    pub torrents: TorrentsResponse,
+}
+
+pub struct Service {
+    configuration: Arc<Configuration>,
+    collection_repository: Arc<DbCollectionRepository>,
+    torrent_listing_generator: Arc<DbTorrentListingGenerator>,
+    authorization_service: Arc<authorization::Service>,
+}
+
+impl Service {
+    #[must_use]
+    pub fn new(
+        configuration: Arc<Configuration>,
+        collection_repository: Arc<DbCollectionRepository>,
+        torrent_listing_generator: Arc<DbTorrentListingGenerator>,
+        authorization_service: Arc<authorization::Service>,
+    ) -> Service {
+        Service {
+            configuration,
+            collection_repository,
+            torrent_listing_generator,
+            authorization_service,
+        }
+    }
+
+    /// Creates a collection for the logged-in user. It's empty unless a
+    /// filter is given, which makes it a smart collection.
+    ///
+    /// # Errors
+    ///
+    /// It returns an error if:
+    ///
+    /// * The user does not have the required permissions.
+    /// * The name is empty or too long.
+    /// * The filter is not valid.
+    /// * There is a database error.
+    pub async fn create_collection(
+        &self,
+        name: &str,
+        description: Option<&str>,
+        filter: Option<&SmartFilter>,
+        maybe_user_id: Option<UserId>,
+    ) -> Result<Collection, ServiceError> {
+        let user_id = self.authorize(maybe_user_id).await?;
+
+        let name = validate_name(name)?;
+
+        let filter = match filter {
+            Some(filter) => Some(self.validate_smart_filter(filter, maybe_user_id).await?),
+            None => None,
+        };
+
+        let collection_id = self
+            .collection_repository
+            .add(user_id, name, normalize_description(description))
+            .await?;
+
+        if let Some(filter) = filter {
+            self.set_filter(collection_id, &filter).await?;
+        }
+
+        Ok(self.collection_repository.get(collection_id).await?)
+    }
+
+    /// Returns the public page of a collection.
+    ///
+    /// # Errors
+    ///
+    /// It returns an error if:
+    ///
+    /// * The user does not have the required permissions.
+    /// * The collection does not exist.
+    /// * There is a database error.
+    pub async fn get_collection(
+        &self,
+        collection_id: CollectionId,
+        request: &ListingRequest,
+        maybe_user_id: Option<UserId>,
+    ) -> Result<CollectionPage, ServiceError> {
+        self.authorization_service
+            .authorize(ACTION::GetCollection, maybe_user_id)
+            .await?;
+
+        let collection = self.collection_repository.get(collection_id).await?;
+
+        let settings = self.configuration.settings.read().await;
+        let default_torrent_page_size = settings.api.default_torrent_page_size;
+        let max_torrent_page_size = settings.api.max_torrent_page_size;
+        drop(settings);
+
+        let page_size = request
+            .page_size
+            .unwrap_or(default_torrent_page_size)
+            .min(max_torrent_page_size);
+        let offset = u64::from(request.page.unwrap_or(0)) * u64::from(page_size);
+
+        let torrent_ids = self.collection_repository.get_torrent_ids(collection_id).await?;
+
+        let specification = ListingSpecification {
+            search: None,
+            categories: None,
+            tags: None,
+            torrent_ids: Some(torrent_ids),
+            status: TorrentStatus::Approved,
+            filter: ListingFilter::default(),
+            sort: Sorting::Relevance,
+            then_sort: None,
+            offset,
+            page_size,
+        };
+
+        let torrents = self.torrent_listing_generator.generate_listing(&specification).await?;
+
+        let filter = if collection.smart {
+            self.collection_repository.get_filter(collection_id).await?
+        } else {
+            None
+        };
+
+        Ok(CollectionPage {
+            collection,
+            filter,
+            torrents,
+        })
+    }
+
+    /// Returns the collections of the logged-in user.
+    ///
+    /// # Errors
+    ///
+    /// It returns an error if:
+    ///
+    /// * The user does not have the required permissions.
+    /// * There is a database error.
+    pub async fn get_user_collections(&self, maybe_user_id: Option<UserId>) -> Result<Vec<Collection>, ServiceError> {
+        let user_id = self.authorize(maybe_user_id).await?;
+
+        Ok(self.collection_repository.get_by_user(user_id).await?)
+    }
+
+    /// Changes the name and description of a collection. If a filter is
+    /// given, it replaces the filter of a smart collection, or it turns a
+    /// regular collection into a smart one, replacing its torrents.
+    ///
+    /// # Errors
+    ///
+    /// It returns an error if:
+    ///
+    /// * The user does not have the required permissions.
+    /// * The collection does not exist or it belongs to another user.
+    /// * The name is empty or too long.
+    /// * The filter is not valid.
+    /// * There is a database error.
+    pub async fn update_collection(
+        &self,
+        collection_id: CollectionId,
+        name: &str,
+        description: Option<&str>,
+        filter: Option<&SmartFilter>,
+        maybe_user_id: Option<UserId>,
+    ) -> Result<Collection, ServiceError> {
+        self.owned_collection(collection_id, maybe_user_id).await?;
+
+        let name = validate_name(name)?;
+
+        let filter = match filter {
+            Some(filter) => Some(self.validate_smart_filter(filter, maybe_user_id).await?),
+            None => None,
+        };
+
+        self.collection_repository
+            .update(collection_id, name, normalize_description(description))
+            .await?;
+
+        if let Some(filter) = filter {
+            self.set_filter(collection_id, &filter).await?;
+        }
+
+        Ok(self.collection_repository.get(collection_id).await?)
+    }
+
+    /// Deletes a collection. Collections can be deleted by their owner or by
+    /// a moderator.
+    ///
+    /// # Errors
+    ///
+    /// It returns an error if:
+    ///
+    /// * The user is neither the owner nor a moderator.
+    /// * The collection does not exist.
+    /// * There is a database error.
+    pub async fn delete_collection(
+        &self,
+        collection_id: CollectionId,
+        maybe_user_id: Option<UserId>,
+    ) -> Result<CollectionId, ServiceError> {
+        let user_id = self.authorize(maybe_user_id).await?;
+
+        let collection = self.collection_repository.get(collection_id).await?;
+
+        if collection.user_id != user_id {
+            self.authorization_service
+                .authorize(ACTION::ModerateCollections, maybe_user_id)
+                .await?;
+        }
+
+        self.collection_repository.delete(collection_id).await?;
+
+        Ok(collection_id)
+    }
+
+    /// Adds a torrent to a collection. Adding a torrent twice has no effect.
+    ///
+    /// # Errors
+    ///
+    /// It returns an error if:
+    ///
+    /// * The user does not have the required permissions.
+    /// * The collection does not exist or it belongs to another user.
+    /// * The collection is a smart collection.
+    /// * The torrent does not exist.
+    /// * There is a database error.
+    pub async fn add_torrent(
+        &self,
+        collection_id: CollectionId,
+        info_hash: &InfoHash,
+        maybe_user_id: Option<UserId>,
+    ) -> Result<Collection, ServiceError> {
+        let collection = self.owned_collection(collection_id, maybe_user_id).await?;
+
+        if collection.smart {
+            return Err(ServiceError::SmartCollectionNotEditable);
+        }
+
+        let torrent_id = self.torrent_id(info_hash).await?;
+
+        self.collection_repository.add_torrent(collection_id, torrent_id).await?;
+
+        Ok(self.collection_repository.get(collection_id).await?)
+    }
+
+    /// Removes a torrent from a collection.
+    ///
+    /// # Errors
+    ///
+    /// It returns an error if:
+    ///
+    /// * The user does not have the required permissions.
+    /// * The collection does not exist or it belongs to another user.
+    /// * The collection is a smart collection.
+    /// * The torrent does not exist or it's not in the collection.
+    /// * There is a database error.
+    pub async fn remove_torrent(
+        &self,
+        collection_id: CollectionId,
+        info_hash: &InfoHash,
+        maybe_user_id: Option<UserId>,
+    ) -> Result<Collection, ServiceError> {
+        let collection = self.owned_collection(collection_id, maybe_user_id).await?;
+
+        if collection.smart {
+            return Err(ServiceError::SmartCollectionNotEditable);
+        }
+
+        let torrent_id = self.torrent_id(info_hash).await?;
+
+        self.collection_repository.remove_torrent(collection_id, torrent_id).await?;
+
+        Ok(self.collection_repository.get(collection_id).await?)
+    }
+
+    /// It updates the torrents of all the smart collections. It returns the
+    /// number of collections updated.
+    ///
+    /// # Errors
+    ///
+    /// It returns an error if there is a database error.
+    pub async fn materialize_smart_collections(&self) -> Result<usize, ServiceError> {
+        let filters = self.collection_repository.get_filters().await?;
+
+        for (collection_id, filter) in &filters {
+            self.collection_repository.materialize(*collection_id, filter).await?;
+        }
+
+        Ok(filters.len())
+    }
+
+    async fn set_filter(&self, collection_id: CollectionId, filter: &SmartFilter) -> Result<(), ServiceError> {
+        self.collection_repository.set_filter(collection_id, filter).await?;
+
+        self.collection_repository.materialize(collection_id, filter).await?;
+
+        Ok(())
+    }
+
+    /// Only administrators can define smart collections.
+    async fn validate_smart_filter(
+        &self,
+        filter: &SmartFilter,
+        maybe_user_id: Option<UserId>,
+    ) -> Result<SmartFilter, ServiceError> {
+        self.authorization_service
+            .authorize(ACTION::ManageSmartCollections, maybe_user_id)
+            .await?;
+
+        normalize_filter(filter)
+    }
+
+    async fn authorize(&self, maybe_user_id: Option<UserId>) -> Result<UserId, ServiceError> {
+        self.authorization_service
+            .authorize(ACTION::ManageCollections, maybe_user_id)
+            .await?;
+
+        maybe_user_id.ok_or(ServiceError::UnauthorizedActionForGuests)
+    }
+
+    /// It returns the collection if it belongs to the logged-in user.
+    async fn owned_collection(
+        &self,
+        collection_id: CollectionId,
+        maybe_user_id: Option<UserId>,
+    ) -> Result<Collection, ServiceError> {
+        let user_id = self.authorize(maybe_user_id).await?;
+
+        let collection = self.collection_repository.get(collection_id).await?;
+
+        if collection.user_id != user_id {
+            return Err(ServiceError::CollectionNotFound);
+        }
+
+        Ok(collection)
+    }
+
+    async fn torrent_id(&self, info_hash: &InfoHash) -> Result<TorrentId, ServiceError> {
+        let torrent_listing = self.torrent_listing_generator.one_torrent_by_info_hash(info_hash).await?;
+
+        Ok(torrent_listing.torrent_id)
+    }
+}
+
+fn validate_name(name: &str) -> Result<&str, ServiceError> {
+    let name = name.trim();
+
+    if name.is_empty() || name.chars().count() > MAX_NAME_LENGTH {
+        return Err(ServiceError::InvalidCollectionName);
+    }
+
+    Ok(name)
+}
+
+fn normalize_description(description: Option<&str>) -> Option<&str> {
+    description.map(str::trim).filter(|description| !description.is_empty())
+}
+
+/// It trims the filter values and checks the filter has at least one
+/// criterion and a valid date range.
+fn normalize_filter(filter: &SmartFilter) -> Result<SmartFilter, ServiceError> {
+    let category = filter
+        .category
+        .as_deref()
+        .map(str::trim)
+        .filter(|category| !category.is_empty())
+        .map(ToString::to_string);
+
+    let mut tags: Vec<String> = filter
+        .tags
+        .iter()
+        .map(|tag| tag.trim().to_string())
+        .filter(|tag| !tag.is_empty())
+        .collect();
+
+    if tags.iter().any(|tag| tag.contains(',')) {
+        return Err(ServiceError::InvalidSmartCollectionFilter);
+    }
+
+    tags.sort();
+    tags.dedup();
+
+    let uploaded_after = parse_day(filter.uploaded_after.as_deref())?;
+    let uploaded_before = parse_day(filter.uploaded_before.as_deref())?;
+
+    if let (Some(uploaded_after), Some(uploaded_before)) = (uploaded_after, uploaded_before) {
+        if uploaded_after >= uploaded_before {
+            return Err(ServiceError::InvalidSmartCollectionFilter);
+        }
+    }
+
+    let max_age_days = filter.max_age_days.filter(|days| *days > 0);
+
+    if category.is_none() && tags.is_empty() && uploaded_after.is_none() && uploaded_before.is_none() && max_age_days.is_none() {
+        return Err(ServiceError::InvalidSmartCollectionFilter);
+    }
+
+    Ok(SmartFilter {
+        category,
+        tags,
+        uploaded_after: uploaded_after.map(|day| day.format("%Y-%m-%d").to_string()),
+        uploaded_before: uploaded_before.map(|day| day.format("%Y-%m-%d").to_string()),
+        max_age_days,
+    })
+}
+
+fn parse_day(day: Option<&str>) -> Result<Option<NaiveDate>, ServiceError> {
+    day.map(|day| NaiveDate::parse_from_str(day.trim(), "%Y-%m-%d").map_err(|_| ServiceError::InvalidSmartCollectionFilter))
+        .transpose()
+}
+
+pub struct DbCollectionRepository {
+    database: Arc<Box<dyn Database>>,
+}
+
+impl DbCollectionRepository {
+    #[must_use]
+    pub fn new(database: Arc<Box<dyn Database>>) -> Self {
+        Self { database }
+    }
+
+    /// It adds a new collection and returns its ID.
+    ///
+    /// # Errors
+    ///
+    /// It returns an error if there is a database error.
+    pub async fn add(&self, user_id: UserId, name: &str, description: Option<&str>) -> Result<CollectionId, Error> {
+        self.database.insert_collection(user_id, name, description).await
+    }
+
+    /// It returns a collection.
+    ///
+    /// # Errors
+    ///
+    /// It returns an error if the collection does not exist or there is a
+    /// database error.
+    pub async fn get(&self, collection_id: CollectionId) -> Result<Collection, Error> {
+        self.database.get_collection(collection_id).await
+    }
+
+    /// It returns the collections of a user.
+    ///
+    /// # Errors
+    ///
+    /// It returns an error if there is a database error.
+    pub async fn get_by_user(&self, user_id: UserId) -> Result<Vec<Collection>, Error> {
+        self.database.get_user_collections(user_id).await
+    }
+
+    /// It changes the collection name and description.
+    ///
+    /// # Errors
+    ///
+    /// It returns an error if there is a database error.
+    pub async fn update(&self, collection_id: CollectionId, name: &str, description: Option<&str>) -> Result<(), Error> {
+        self.database.update_collection(collection_id, name, description).await
+    }
+
+    /// It deletes the collection.
+    ///
+    /// # Errors
+    ///
+    /// It returns an error if the collection does not exist or there is a
+    /// database error.
+    pub async fn delete(&self, collection_id: CollectionId) -> Result<(), Error> {
+        self.database.delete_collection(collection_id).await
+    }
+
+    /// It adds the torrent to the collection.
+    ///
+    /// # Errors
+    ///
+    /// It returns an error if there is a database error.
+    pub async fn add_torrent(&self, collection_id: CollectionId, torrent_id: TorrentId) -> Result<(), Error> {
+        self.database.insert_collection_item(collection_id, torrent_id).await
+    }
+
+    /// It removes the torrent from the collection.
+    ///
+    /// # Errors
+    ///
+    /// It returns an error if the torrent is not in the collection or there
+    /// is a database error.
+    pub async fn remove_torrent(&self, collection_id: CollectionId, torrent_id: TorrentId) -> Result<(), Error> {
+        self.database.delete_collection_item(collection_id, torrent_id).await
+    }
+
+    /// It returns the IDs of the torrents in the collection, from the newest
+    /// item to the oldest.
+    ///
+    /// # Errors
+    ///
+    /// It returns an error if there is a database error.
+    pub async fn get_torrent_ids(&self, collection_id: CollectionId) -> Result<Vec<TorrentId>, Error> {
+        self.database.get_collection_torrent_ids(collection_id).await
+    }
+
+    /// It sets the filter of a smart collection.
+    ///
+    /// # Errors
+    ///
+    /// It returns an error if there is a database error.
+    pub async fn set_filter(&self, collection_id: CollectionId, filter: &SmartFilter) -> Result<(), Error> {
+        self.database.upsert_smart_collection_filter(collection_id, filter).await
+    }
+
+    /// It returns the filter of a smart collection.
+    ///
+    /// # Errors
+    ///
+    /// It returns an error if there is a database error.
+    pub async fn get_filter(&self, collection_id: CollectionId) -> Result<Option<SmartFilter>, Error> {
+        self.database.get_smart_collection_filter(collection_id).await
+    }
+
+    /// It returns the filters of all the smart collections.
+    ///
+    /// # Errors
+    ///
+    /// It returns an error if there is a database error.
+    pub async fn get_filters(&self) -> Result<Vec<(CollectionId, SmartFilter)>, Error> {
+        self.database.get_smart_collection_filters().await
+    }
+
+    /// It replaces the torrents of a smart collection with the ones matching
+    /// its filter now. It returns the number of torrents.
+    ///
+    /// # Errors
+    ///
+    /// It returns an error if there is a database error.
+    pub async fn materialize(&self, collection_id: CollectionId, filter: &SmartFilter) -> Result<u64, Error> {
+        let uploaded_since = filter.max_age_days.map(|days| {
+            (Utc::now() - TimeDelta::days(i64::from(days)))
+                .format(DATETIME_FORMAT)
+                .to_string()
+        });
+
+        self.database
+            .materialize_smart_collection(collection_id, filter, uploaded_since.as_deref(), MAX_SMART_COLLECTION_SIZE)
+            .await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{normalize_description, normalize_filter, validate_name};
+    use crate::errors::ServiceError;
+    use crate::models::collection::SmartFilter;
+
+    #[test]
+    fn it_should_trim_the_collection_name() {
+        assert_eq!(validate_name("  Ubuntu releases ").unwrap(), "Ubuntu releases");
+    }
+
+    #[test]
+    fn it_should_reject_empty_or_too_long_collection_names() {
+        assert!(matches!(validate_name("   "), Err(ServiceError::InvalidCollectionName)));
+        assert!(matches!(
+            validate_name(&"a".repeat(101)),
+            Err(ServiceError::InvalidCollectionName)
+        ));
+    }
+
+    #[test]
+    fn it_should_ignore_blank_descriptions() {
+        assert_eq!(normalize_description(Some("  ")), None);
+        assert_eq!(
+            normalize_description(Some(" All the LTS releases ")),
+            Some("All the LTS releases")
+        );
+    }
+
+    #[test]
+    fn it_should_normalize_the_smart_collection_filter() {
+        let filter = SmartFilter {
+            category: Some(" movies ".to_string()),
+            tags: vec!["hd".to_string(), " 4k".to_string(), "hd".to_string(), " ".to_string()],
+            uploaded_after: Some("2024-01-01".to_string()),
+            uploaded_before: None,
+            max_age_days: Some(0),
+        };
+
+        assert_eq!(
+            normalize_filter(&filter).unwrap(),
+            SmartFilter {
+                category: Some("movies".to_string()),
+                tags: vec!["4k".to_string(), "hd".to_string()],
+                uploaded_after: Some("2024-01-01".to_string()),
+                uploaded_before: None,
+                max_age_days: None,
+            }
+        );
+    }
+
+    #[test]
+    fn it_should_reject_empty_smart_collection_filters_and_invalid_date_ranges() {
+        assert!(matches!(
+            normalize_filter(&SmartFilter::default()),
+            Err(ServiceError::InvalidSmartCollectionFilter)
+        ));
+
+        for (uploaded_after, uploaded_before) in [("2024-02-01", "2024-01-01"), ("2024-13-01", "2024-12-31")] {
+            let filter = SmartFilter {
+                uploaded_after: Some(uploaded_after.to_string()),
+                uploaded_before: Some(uploaded_before.to_string()),
+                ..SmartFilter::default()
+            };
+
+            assert!(matches!(
+                normalize_filter(&filter),
+                Err(ServiceError::InvalidSmartCollectionFilter)
+            ));
+        }
+    }
+}
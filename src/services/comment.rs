@@ -0,0 +1,375 @@
+//! Torrent comment service.
+use std::sync::Arc;
+
+use bittorrent_primitives::info_hash::InfoHash;
+use serde_derive::Deserialize;
+use utoipa::IntoParams;
+
+use super::audit;
+use super::authorization::{self, ACTION};
+use super::torrent::DbTorrentListingGenerator;
+use crate::databases::database::{Database, Error};
+use crate::errors::ServiceError;
+use crate::models::audit::AuditAction;
+use crate::models::comment::{Comment, CommentId};
+use crate::models::response::CommentsResponse;
+use crate::models::torrent::TorrentId;
+use crate::models::user::UserId;
+
+/// Default number of comments per page.
+const DEFAULT_PAGE_SIZE: u8 = 20;
+
+/// Maximum number of comments per page.
+const MAX_PAGE_SIZE: u8 = 100;
+
+/// Maximum number of characters in a comment.
+const MAX_COMMENT_LENGTH: usize = 10_000;
+
+/// User request to get a page of comments.
+#[derive(Debug, Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
+pub struct ListingRequest {
+    pub page_size: Option<u8>,
+    pub page: Option<u32>,
+}
+
+pub struct Service {
+    comment_repository: Arc<DbCommentRepository>,
+    torrent_listing_generator: Arc<DbTorrentListingGenerator>,
+    authorization_service: Arc<authorization::Service>,
+    audit_service: Arc<audit::Service>,
+}
+
+impl Service {
+    #[must_use]
+    pub fn new(
+        comment_repository: Arc<DbCommentRepository>,
+        torrent_listing_generator: Arc<DbTorrentListingGenerator>,
+        authorization_service: Arc<authorization::Service>,
+        audit_service: Arc<audit::Service>,
+    ) -> Service {
+        Service {
+            comment_repository,
+            torrent_listing_generator,
+            authorization_service,
+            audit_service,
+        }
+    }
+
+    /// Returns a page of the torrent comments.
+    ///
+    /// Hidden comments are only included for moderators.
+    ///
+    /// # Errors
+    ///
+    /// It returns an error if:
+    ///
+    /// * The user does not have the required permissions.
+    /// * The torrent does not exist.
+    /// * There is a database error.
+    pub async fn get_comments(
+        &self,
+        info_hash: &InfoHash,
+        request: &ListingRequest,
+        maybe_user_id: Option<UserId>,
+    ) -> Result<CommentsResponse, ServiceError> {
+        self.authorization_service
+            .authorize(ACTION::GetComments, maybe_user_id)
+            .await?;
+
+        let torrent_id = self.torrent_id(info_hash).await?;
+
+        let include_hidden = self
+            .authorization_service
+            .authorize(ACTION::ModerateComment, maybe_user_id)
+            .await
+            .is_ok();
+
+        let page_size = request.page_size.unwrap_or(DEFAULT_PAGE_SIZE).min(MAX_PAGE_SIZE);
+        let offset = u64::from(request.page.unwrap_or(0)) * u64::from(page_size);
+
+        let comments = self
+            .comment_repository
+            .get_page(torrent_id, include_hidden, offset, page_size)
+            .await?;
+
+        Ok(comments)
+    }
+
+    /// Adds a new comment to a torrent.
+    ///
+    /// # Errors
+    ///
+    /// It returns an error if:
+    ///
+    /// * The user does not have the required permissions.
+    /// * The comment text is empty or too long.
+    /// * The torrent does not exist.
+    /// * There is a database error.
+    pub async fn add_comment(
+        &self,
+        info_hash: &InfoHash,
+        text: &str,
+        maybe_user_id: Option<UserId>,
+    ) -> Result<Comment, ServiceError> {
+        self.authorization_service
+            .authorize(ACTION::AddComment, maybe_user_id)
+            .await?;
+
+        let Some(user_id) = maybe_user_id else {
+            return Err(ServiceError::UnauthorizedActionForGuests);
+        };
+
+        let text = validate_text(text)?;
+
+        let torrent_id = self.torrent_id(info_hash).await?;
+
+        let comment_id = self.comment_repository.add(torrent_id, user_id, text).await?;
+
+        Ok(self.comment_repository.get(comment_id).await?)
+    }
+
+    /// Edits the text of a comment. Only the author can edit a comment.
+    ///
+    /// # Errors
+    ///
+    /// It returns an error if:
+    ///
+    /// * The user is not the author of the comment.
+    /// * The comment text is empty or too long.
+    /// * The comment does not exist.
+    /// * There is a database error.
+    pub async fn edit_comment(
+        &self,
+        info_hash: &InfoHash,
+        comment_id: CommentId,
+        text: &str,
+        user_id: &UserId,
+    ) -> Result<Comment, ServiceError> {
+        let comment = self.comment_of_torrent(info_hash, comment_id).await?;
+
+        if comment.author_id != *user_id {
+            return Err(ServiceError::UnauthorizedAction);
+        }
+
+        let text = validate_text(text)?;
+
+        self.comment_repository.update_text(comment_id, text).await?;
+
+        Ok(self.comment_repository.get(comment_id).await?)
+    }
+
+    /// Deletes a comment. Comments can be deleted by their author or by a
+    /// moderator.
+    ///
+    /// # Errors
+    ///
+    /// It returns an error if:
+    ///
+    /// * The user is neither the author nor a moderator.
+    /// * The comment does not exist.
+    /// * There is a database error.
+    pub async fn delete_comment(
+        &self,
+        info_hash: &InfoHash,
+        comment_id: CommentId,
+        maybe_user_id: Option<UserId>,
+    ) -> Result<(), ServiceError> {
+        let Some(user_id) = maybe_user_id else {
+            return Err(ServiceError::UnauthorizedActionForGuests);
+        };
+
+        let comment = self.comment_of_torrent(info_hash, comment_id).await?;
+
+        let deleted_by_moderator = comment.author_id != user_id;
+
+        if deleted_by_moderator {
+            self.authorization_service
+                .authorize(ACTION::ModerateComment, maybe_user_id)
+                .await?;
+        }
+
+        self.comment_repository.delete(comment_id).await?;
+
+        if deleted_by_moderator {
+            self.audit_service
+                .record(
+                    maybe_user_id,
+                    AuditAction::CommentModerate,
+                    &comment_id.to_string(),
+                    Some("deleted"),
+                )
+                .await;
+        }
+
+        Ok(())
+    }
+
+    /// Hides or shows a comment. Hidden comments are only listed for
+    /// moderators.
+    ///
+    /// # Errors
+    ///
+    /// It returns an error if:
+    ///
+    /// * The user does not have the required permissions.
+    /// * The comment does not exist.
+    /// * There is a database error.
+    pub async fn moderate_comment(
+        &self,
+        info_hash: &InfoHash,
+        comment_id: CommentId,
+        hidden: bool,
+        maybe_user_id: Option<UserId>,
+    ) -> Result<Comment, ServiceError> {
+        self.authorization_service
+            .authorize(ACTION::ModerateComment, maybe_user_id)
+            .await?;
+
+        self.comment_of_torrent(info_hash, comment_id).await?;
+
+        self.comment_repository.update_hidden(comment_id, hidden).await?;
+
+        self.audit_service
+            .record(
+                maybe_user_id,
+                AuditAction::CommentModerate,
+                &comment_id.to_string(),
+                Some(if hidden { "hidden" } else { "visible" }),
+            )
+            .await;
+
+        Ok(self.comment_repository.get(comment_id).await?)
+    }
+
+    async fn torrent_id(&self, info_hash: &InfoHash) -> Result<TorrentId, ServiceError> {
+        let torrent_listing = self.torrent_listing_generator.one_torrent_by_info_hash(info_hash).await?;
+
+        Ok(torrent_listing.torrent_id)
+    }
+
+    /// It returns the comment only if it belongs to the torrent.
+    async fn comment_of_torrent(&self, info_hash: &InfoHash, comment_id: CommentId) -> Result<Comment, ServiceError> {
+        let torrent_id = self.torrent_id(info_hash).await?;
+
+        let comment = self.comment_repository.get(comment_id).await?;
+
+        if comment.torrent_id != torrent_id {
+            return Err(ServiceError::CommentNotFound);
+        }
+
+        Ok(comment)
+    }
+}
+
+/// It returns the trimmed comment text.
+fn validate_text(text: &str) -> Result<&str, ServiceError> {
+    let text = text.trim();
+
+    if text.is_empty() || text.chars().count() > MAX_COMMENT_LENGTH {
+        return Err(ServiceError::InvalidCommentLength);
+    }
+
+    Ok(text)
+}
+
+pub struct DbCommentRepository {
+    database: Arc<Box<dyn Database>>,
+}
+
+impl DbCommentRepository {
+    #[must_use]
+    pub fn new(database: Arc<Box<dyn Database>>) -> Self {
+        Self { database }
+    }
+
+    /// It adds a new comment and returns its ID.
+    ///
+    /// # Errors
+    ///
+    /// It returns an error if there is a database error.
+    pub async fn add(&self, torrent_id: TorrentId, author_id: UserId, text: &str) -> Result<CommentId, Error> {
+        self.database.insert_comment(torrent_id, author_id, text).await
+    }
+
+    /// It returns a comment.
+    ///
+    /// # Errors
+    ///
+    /// It returns an error if the comment does not exist or there is a
+    /// database error.
+    pub async fn get(&self, comment_id: CommentId) -> Result<Comment, Error> {
+        self.database.get_comment(comment_id).await
+    }
+
+    /// It returns a page of the torrent comments.
+    ///
+    /// # Errors
+    ///
+    /// It returns an error if there is a database error.
+    pub async fn get_page(
+        &self,
+        torrent_id: TorrentId,
+        include_hidden: bool,
+        offset: u64,
+        page_size: u8,
+    ) -> Result<CommentsResponse, Error> {
+        self.database
+            .get_torrent_comments_paginated(torrent_id, include_hidden, offset, page_size)
+            .await
+    }
+
+    /// It updates the comment text.
+    ///
+    /// # Errors
+    ///
+    /// It returns an error if the comment does not exist or there is a
+    /// database error.
+    pub async fn update_text(&self, comment_id: CommentId, text: &str) -> Result<(), Error> {
+        self.database.update_comment_text(comment_id, text).await
+    }
+
+    /// It hides or shows the comment.
+    ///
+    /// # Errors
+    ///
+    /// It returns an error if the comment does not exist or there is a
+    /// database error.
+    pub async fn update_hidden(&self, comment_id: CommentId, hidden: bool) -> Result<(), Error> {
+        self.database.update_comment_hidden(comment_id, hidden).await
+    }
+
+    /// It deletes the comment.
+    ///
+    /// # Errors
+    ///
+    /// It returns an error if the comment does not exist or there is a
+    /// database error.
+    pub async fn delete(&self, comment_id: CommentId) -> Result<(), Error> {
+        self.database.delete_comment(comment_id).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{validate_text, MAX_COMMENT_LENGTH};
+    use crate::errors::ServiceError;
+
+    #[test]
+    fn it_should_trim_the_comment_text() {
+        assert_eq!(validate_text("  Thanks for sharing!\n"), Ok("Thanks for sharing!"));
+    }
+
+    #[test]
+    fn it_should_reject_empty_comments() {
+        assert_eq!(validate_text(" \n "), Err(ServiceError::InvalidCommentLength));
+    }
+
+    #[test]
+    fn it_should_reject_too_long_comments() {
+        assert_eq!(
+            validate_text(&"a".repeat(MAX_COMMENT_LENGTH + 1)),
+            Err(ServiceError::InvalidCommentLength)
+        );
+    }
+}
@@ -0,0 +1,64 @@
+//! Domain event outbox.
+//!
+//! Services publish domain events by appending them to the outbox table in
+//! the same database as the rest of the data. Consumers (for example, the
+//! announcer) process the events asynchronously and keep a cursor with the
+//! last event they have processed, so no event is lost if the application is
+//! restarted.
+use std::sync::Arc;
+
+use crate::databases::database::{Database, Error};
+use crate::models::domain_event::{DbDomainEvent, DomainEvent, EventId};
+
+pub struct DbDomainEventRepository {
+    database: Arc<Box<dyn Database>>,
+}
+
+impl DbDomainEventRepository {
+    #[must_use]
+    pub fn new(database: Arc<Box<dyn Database>>) -> Self {
+        Self { database }
+    }
+
+    /// It appends a new event to the outbox.
+    ///
+    /// # Errors
+    ///
+    /// It returns an error if there is a database error.
+    ///
+    /// # Panics
+    ///
+    /// It panics if the event can't be serialized.
+    pub async fn publish(&self, event: &DomainEvent) -> Result<EventId, Error> {
+        let payload = serde_json::to_string(event).expect("domain events should be serializable");
+
+        self.database.insert_domain_event(event.event_type(), &payload).await
+    }
+
+    /// It returns up to `limit` events published after the event `event_id`.
+    ///
+    /// # Errors
+    ///
+    /// It returns an error if there is a database error.
+    pub async fn fetch_after(&self, event_id: EventId, limit: u32) -> Result<Vec<DbDomainEvent>, Error> {
+        self.database.get_domain_events_after(event_id, limit).await
+    }
+
+    /// It returns the last event processed by the consumer.
+    ///
+    /// # Errors
+    ///
+    /// It returns an error if there is a database error.
+    pub async fn get_cursor(&self, consumer: &str) -> Result<EventId, Error> {
+        self.database.get_domain_event_consumer_cursor(consumer).await
+    }
+
+    /// It saves the last event processed by the consumer.
+    ///
+    /// # Errors
+    ///
+    /// It returns an error if there is a database error.
+    pub async fn save_cursor(&self, consumer: &str, event_id: EventId) -> Result<(), Error> {
+        self.database.update_domain_event_consumer_cursor(consumer, event_id).await
+    }
+}
@@ -0,0 +1,295 @@
+//! Download statistics service.
+//!
+//! When enabled in the configuration, it counts the `.torrent` downloads by
+//! client family, so operators can see which `BitTorrent` clients and
+//! automation tools consume the index. Only daily counters are stored.
+use std::sync::Arc;
+
+use chrono::{Days, Utc};
+use serde::{Deserialize, Serialize};
+use tracing::error;
+use utoipa::IntoParams;
+
+use super::authorization::{self, ACTION};
+use crate::config::Configuration;
+use crate::databases::database::{Database, DownloadCount, Error};
+use crate::errors::ServiceError;
+use crate::models::user::UserId;
+
+/// Family for requests without a `User-Agent` header.
+pub const UNKNOWN_CLIENT: &str = "Unknown";
+
+/// Family for user agents that do not match any known client.
+pub const OTHER_CLIENT: &str = "Other";
+
+/// Known clients. Each entry contains a lowercase substring of the user agent
+/// and the client family. The first match wins, so more specific substrings
+/// go first (for example, Edge user agents also contain `Chrome`).
+const CLIENT_FAMILIES: &[(&str, &str)] = &[
+    ("qbittorrent", "qBittorrent"),
+    ("transmission", "Transmission"),
+    ("deluge", "Deluge"),
+    ("rtorrent", "rTorrent"),
+    ("libtorrent", "libtorrent"),
+    ("utorrent", "µTorrent"),
+    ("biglybt", "BiglyBT"),
+    ("azureus", "Vuze"),
+    ("vuze", "Vuze"),
+    ("tixati", "Tixati"),
+    ("bittorrent", "BitTorrent"),
+    ("aria2", "aria2"),
+    ("sonarr", "Sonarr"),
+    ("radarr", "Radarr"),
+    ("lidarr", "Lidarr"),
+    ("readarr", "Readarr"),
+    ("prowlarr", "Prowlarr"),
+    ("jackett", "Jackett"),
+    ("flexget", "FlexGet"),
+    ("curl/", "curl"),
+    ("wget/", "Wget"),
+    ("python-requests", "Python"),
+    ("python-urllib", "Python"),
+    ("aiohttp", "Python"),
+    ("go-http-client", "Go"),
+    ("okhttp", "OkHttp"),
+    ("node-fetch", "Node.js"),
+    ("axios", "Node.js"),
+    ("reqwest", "reqwest"),
+    ("edg/", "Edge"),
+    ("firefox/", "Firefox"),
+    ("chrome/", "Chrome"),
+    ("chromium/", "Chrome"),
+    ("safari/", "Safari"),
+];
+
+/// Max length of the raw user agents stored in the database.
+const MAX_USER_AGENT_LENGTH: usize = 255;
+
+/// Max number of raw user agents included in the statistics.
+const MAX_USER_AGENTS: u32 = 50;
+
+const DEFAULT_DAYS: u32 = 30;
+
+const MAX_DAYS: u32 = 365;
+
+/// Admin request to get the download statistics.
+#[derive(Debug, Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
+pub struct StatisticsRequest {
+    /// Number of days included, counting today. Default to `30`.
+    pub days: Option<u32>,
+}
+
+/// Number of `.torrent` downloads by client.
+#[derive(Debug, Serialize, Deserialize, PartialEq, Eq)]
+pub struct DownloadStatistics {
+    /// Whether downloads are being recorded or not.
+    pub enabled: bool,
+    /// Number of days included, counting today.
+    pub days: u32,
+    /// Total number of downloads in the period.
+    pub total: i64,
+    /// Downloads by client family, from the most used to the least used.
+    pub clients: Vec<DownloadCount>,
+    /// Downloads by raw user agent. It's only present when raw user agents
+    /// are stored.
+    pub user_agents: Option<Vec<DownloadCount>>,
+}
+
+/// Returns the client family for a `User-Agent` header value.
+#[must_use]
+pub fn client_family(user_agent: Option<&str>) -> &'static str {
+    let Some(user_agent) = user_agent.map(str::trim).filter(|user_agent| !user_agent.is_empty()) else {
+        return UNKNOWN_CLIENT;
+    };
+
+    let user_agent = user_agent.to_lowercase();
+
+    CLIENT_FAMILIES
+        .iter()
+        .find(|(needle, _)| user_agent.contains(needle))
+        .map_or(OTHER_CLIENT, |(_, family)| *family)
+}
+
+pub struct Service {
+    configuration: Arc<Configuration>,
+    download_statistics_repository: Arc<DbDownloadStatisticsRepository>,
+    authorization_service: Arc<authorization::Service>,
+}
+
+impl Service {
+    #[must_use]
+    pub fn new(
+        configuration: Arc<Configuration>,
+        download_statistics_repository: Arc<DbDownloadStatisticsRepository>,
+        authorization_service: Arc<authorization::Service>,
+    ) -> Service {
+        Service {
+            configuration,
+            download_statistics_repository,
+            authorization_service,
+        }
+    }
+
+    /// Counts a `.torrent` download. It does nothing when download statistics
+    /// are disabled.
+    ///
+    /// Errors are only logged. Statistics must never make a download fail.
+    pub async fn record_download(&self, user_agent: Option<&str>) {
+        let Some(download_statistics) = self.configuration.settings.read().await.download_statistics.clone() else {
+            return;
+        };
+
+        let family = client_family(user_agent);
+
+        let raw_user_agent = if download_statistics.store_raw_user_agents {
+            user_agent
+                .map(str::trim)
+                .unwrap_or_default()
+                .chars()
+                .take(MAX_USER_AGENT_LENGTH)
+                .collect()
+        } else {
+            String::new()
+        };
+
+        if let Err(err) = self.download_statistics_repository.increment(family, &raw_user_agent).await {
+            error!("Failed to record torrent download for client {family}: {err:?}");
+        }
+    }
+
+    /// Returns the number of downloads by client for the last days.
+    ///
+    /// # Errors
+    ///
+    /// It returns an error if:
+    ///
+    /// * The user does not have the required permissions.
+    /// * There is a database error.
+    pub async fn get_statistics(
+        &self,
+        request: &StatisticsRequest,
+        maybe_user_id: Option<UserId>,
+    ) -> Result<DownloadStatistics, ServiceError> {
+        self.authorization_service
+            .authorize(ACTION::GetDownloadStatistics, maybe_user_id)
+            .await?;
+
+        let settings = self.configuration.settings.read().await;
+        let enabled = settings.download_statistics.is_some();
+        let store_raw_user_agents = settings
+            .download_statistics
+            .as_ref()
+            .is_some_and(|download_statistics| download_statistics.store_raw_user_agents);
+        drop(settings);
+
+        let days = request.days.unwrap_or(DEFAULT_DAYS).clamp(1, MAX_DAYS);
+
+        let clients = self.download_statistics_repository.get_client_families(days).await?;
+
+        let user_agents = if store_raw_user_agents {
+            Some(self.download_statistics_repository.get_user_agents(days).await?)
+        } else {
+            None
+        };
+
+        Ok(DownloadStatistics {
+            enabled,
+            days,
+            total: clients.iter().map(|client| client.downloads).sum(),
+            clients,
+            user_agents,
+        })
+    }
+}
+
+pub struct DbDownloadStatisticsRepository {
+    database: Arc<Box<dyn Database>>,
+}
+
+impl DbDownloadStatisticsRepository {
+    #[must_use]
+    pub fn new(database: Arc<Box<dyn Database>>) -> Self {
+        Self { database }
+    }
+
+    /// It adds one download to today's counter.
+    ///
+    /// # Errors
+    ///
+    /// It returns an error if there is a database error.
+    pub async fn increment(&self, client_family: &str, user_agent: &str) -> Result<(), Error> {
+        self.database
+            .increment_download_client_stats(&today(), client_family, user_agent)
+            .await
+    }
+
+    /// It returns the downloads by client family for the last days.
+    ///
+    /// # Errors
+    ///
+    /// It returns an error if there is a database error.
+    pub async fn get_client_families(&self, days: u32) -> Result<Vec<DownloadCount>, Error> {
+        self.database.get_download_client_family_stats(&first_day(days)).await
+    }
+
+    /// It returns the most used raw user agents for the last days.
+    ///
+    /// # Errors
+    ///
+    /// It returns an error if there is a database error.
+    pub async fn get_user_agents(&self, days: u32) -> Result<Vec<DownloadCount>, Error> {
+        self.database
+            .get_download_user_agent_stats(&first_day(days), MAX_USER_AGENTS)
+            .await
+    }
+}
+
+fn today() -> String {
+    Utc::now().format("%Y-%m-%d").to_string()
+}
+
+/// First day of a period of `days` days ending today.
+fn first_day(days: u32) -> String {
+    Utc::now()
+        .checked_sub_days(Days::new(u64::from(days.saturating_sub(1))))
+        .unwrap_or_else(Utc::now)
+        .format("%Y-%m-%d")
+        .to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{client_family, OTHER_CLIENT, UNKNOWN_CLIENT};
+
+    #[test]
+    fn it_should_classify_bittorrent_clients() {
+        assert_eq!(client_family(Some("qBittorrent/4.6.2")), "qBittorrent");
+        assert_eq!(client_family(Some("Transmission/4.0.5")), "Transmission");
+        assert_eq!(client_family(Some("rtorrent/0.9.8/0.13.8")), "rTorrent");
+        assert_eq!(client_family(Some("Deluge 2.1.1")), "Deluge");
+    }
+
+    #[test]
+    fn it_should_classify_automation_tools() {
+        assert_eq!(client_family(Some("curl/8.5.0")), "curl");
+        assert_eq!(client_family(Some("python-requests/2.31.0")), "Python");
+        assert_eq!(client_family(Some("Sonarr/4.0.0.0 (ubuntu 22.04)")), "Sonarr");
+    }
+
+    #[test]
+    fn it_should_prefer_the_most_specific_browser() {
+        let edge = "Mozilla/5.0 (Windows NT 10.0; Win64; x64) AppleWebKit/537.36 (KHTML, like Gecko) Chrome/120.0.0.0 Safari/537.36 Edg/120.0.0.0";
+        let chrome = "Mozilla/5.0 (X11; Linux x86_64) AppleWebKit/537.36 (KHTML, like Gecko) Chrome/120.0.0.0 Safari/537.36";
+
+        assert_eq!(client_family(Some(edge)), "Edge");
+        assert_eq!(client_family(Some(chrome)), "Chrome");
+    }
+
+    #[test]
+    fn it_should_use_a_fallback_family_for_missing_or_unknown_user_agents() {
+        assert_eq!(client_family(None), UNKNOWN_CLIENT);
+        assert_eq!(client_family(Some("  ")), UNKNOWN_CLIENT);
+        assert_eq!(client_family(Some("MyCustomScraper")), OTHER_CLIENT);
+    }
+}
@@ -0,0 +1,464 @@
+//! Metadata enrichment of the torrents.
+//!
+//! Uploaders can fetch the poster, the synopsis and the canonical metadata of
+//! their torrents from external providers, using an external ID or the
+//! title. The metadata is stored with the torrent, it's included in the
+//! torrent details and torrents can be searched by the canonical title.
+//!
+//! There are two providers:
+//!
+//! - [`tmdb`]: [TMDB](https://www.themoviedb.org/), for movies and TV shows.
+//!   It also resolves IMDb IDs.
+//! - [`musicbrainz`]: [`MusicBrainz`](https://musicbrainz.org/), for music
+//!   releases, with the covers from the Cover Art Archive.
+//!
+//! The external IDs have the provider as prefix:
+//!
+//! - `imdb:tt0133093`
+//! - `tmdb:movie/603` or `tmdb:tv/1399`
+//! - `musicbrainz:b1392450-e666-3926-a536-22c65f834433` (a release group)
+//!
+//! Enrichment is disabled unless the `[enrichment]` section is present, with
+//! at least one provider:
+//!
+//! ```toml
+//! [enrichment.tmdb]
+//! api_key = "..."
+//!
+//! [enrichment.musicbrainz]
+//! ```
+pub mod musicbrainz;
+pub mod tmdb;
+
+use std::fmt;
+use std::str::FromStr;
+use std::sync::Arc;
+
+use async_trait::async_trait;
+use bittorrent_primitives::info_hash::InfoHash;
+use derive_more::{Display, Error};
+use tracing::error;
+
+use super::torrent::DbTorrentListingGenerator;
+use crate::config::{Configuration, Enrichment};
+use crate::databases::database::{self, Database};
+use crate::errors::ServiceError;
+use crate::models::torrent::{TorrentId, TorrentListing};
+use crate::models::torrent_metadata::TorrentMetadata;
+use crate::models::user::UserId;
+use crate::services::authorization::{self, ACTION};
+use crate::services::search;
+use crate::services::user::Repository;
+
+#[derive(Debug, Display, PartialEq, Eq, Error)]
+pub enum Error {
+    #[display("Metadata provider request error: {error}.")]
+    Request { error: String },
+
+    #[display("Unexpected metadata provider response.")]
+    UnexpectedResponse,
+}
+
+/// The ID of a title in an external provider.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ExternalId {
+    /// An IMDb title, like `tt0133093`.
+    Imdb(String),
+    /// A TMDB movie.
+    TmdbMovie(u64),
+    /// A TMDB TV show.
+    TmdbTv(u64),
+    /// A `MusicBrainz` release group.
+    MusicBrainz(String),
+}
+
+impl FromStr for ExternalId {
+    type Err = ();
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let (prefix, id) = s.trim().split_once(':').ok_or(())?;
+
+        match prefix {
+            "imdb" if id.len() > 2 && id.starts_with("tt") && id[2..].chars().all(|c| c.is_ascii_digit()) => {
+                Ok(ExternalId::Imdb(id.to_string()))
+            }
+            "tmdb" => match id.split_once('/') {
+                Some(("movie", id)) => id.parse().map(ExternalId::TmdbMovie).map_err(|_| ()),
+                Some(("tv", id)) => id.parse().map(ExternalId::TmdbTv).map_err(|_| ()),
+                _ => Err(()),
+            },
+            "musicbrainz" => uuid::Uuid::parse_str(id)
+                .map(|mbid| ExternalId::MusicBrainz(mbid.hyphenated().to_string()))
+                .map_err(|_| ()),
+            _ => Err(()),
+        }
+    }
+}
+
+impl fmt::Display for ExternalId {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ExternalId::Imdb(id) => write!(f, "imdb:{id}"),
+            ExternalId::TmdbMovie(id) => write!(f, "tmdb:movie/{id}"),
+            ExternalId::TmdbTv(id) => write!(f, "tmdb:tv/{id}"),
+            ExternalId::MusicBrainz(id) => write!(f, "musicbrainz:{id}"),
+        }
+    }
+}
+
+/// The metadata of a title as it's returned by a provider.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FetchedMetadata {
+    /// The canonical ID in the provider. IMDb IDs are resolved to TMDB IDs.
+    pub external_id: ExternalId,
+    pub title: String,
+    pub year: Option<i64>,
+    pub synopsis: Option<String>,
+    pub poster_url: Option<String>,
+    pub external_url: Option<String>,
+}
+
+/// A metadata provider.
+#[async_trait]
+pub trait Provider: Sync + Send {
+    /// The provider name, as it's named in the configuration.
+    fn name(&self) -> &'static str;
+
+    /// Whether the provider can look up the external ID.
+    fn supports(&self, external_id: &ExternalId) -> bool;
+
+    /// It returns the metadata of the title with the external ID, if it
+    /// exists.
+    async fn lookup(&self, external_id: &ExternalId) -> Result<Option<FetchedMetadata>, Error>;
+
+    /// It returns the metadata of the best match for the title, if any.
+    async fn search(&self, title: &str) -> Result<Option<FetchedMetadata>, Error>;
+}
+
+/// User request to fetch the metadata of a torrent. The external ID takes
+/// precedence over the title.
+pub struct EnrichMetadataRequest {
+    pub external_id: Option<String>,
+    pub title: Option<String>,
+    /// The provider used to search the title. Default to all the configured
+    /// providers, in order, until one finds it.
+    pub provider: Option<String>,
+}
+
+pub struct Service {
+    configuration: Arc<Configuration>,
+    client: reqwest::Client,
+    user_repository: Arc<Box<dyn Repository>>,
+    torrent_listing_generator: Arc<DbTorrentListingGenerator>,
+    metadata_repository: Arc<DbTorrentMetadataRepository>,
+    search_service: Arc<search::Service>,
+    authorization_service: Arc<authorization::Service>,
+}
+
+impl Service {
+    #[must_use]
+    pub fn new(
+        configuration: Arc<Configuration>,
+        user_repository: Arc<Box<dyn Repository>>,
+        torrent_listing_generator: Arc<DbTorrentListingGenerator>,
+        metadata_repository: Arc<DbTorrentMetadataRepository>,
+        search_service: Arc<search::Service>,
+        authorization_service: Arc<authorization::Service>,
+    ) -> Service {
+        Service {
+            configuration,
+            client: reqwest::Client::new(),
+            user_repository,
+            torrent_listing_generator,
+            metadata_repository,
+            search_service,
+            authorization_service,
+        }
+    }
+
+    /// It fetches the metadata of a torrent from the providers and it
+    /// replaces the stored one. Owners can always fetch the metadata of their
+    /// torrents. Other users need permission to edit the torrent.
+    ///
+    /// # Errors
+    ///
+    /// It returns an error if:
+    ///
+    /// * Enrichment is not enabled.
+    /// * The user does not have permission to edit the torrent.
+    /// * The torrent does not exist.
+    /// * The external ID is not valid, or there is neither an external ID
+    ///   nor a title.
+    /// * There is no configured provider for the external ID.
+    /// * The providers don't find the title or they fail.
+    /// * There is a database error.
+    pub async fn enrich(
+        &self,
+        info_hash: &InfoHash,
+        request: &EnrichMetadataRequest,
+        maybe_user_id: Option<UserId>,
+    ) -> Result<TorrentMetadata, ServiceError> {
+        let enrichment = self.enrichment_settings().await?;
+
+        let (_user_id, torrent_listing) = self.authorize_edit(info_hash, maybe_user_id).await?;
+
+        let providers = self.providers(&enrichment);
+
+        let fetched = match (&request.external_id, &request.title) {
+            (Some(external_id), _) => {
+                let external_id = ExternalId::from_str(external_id).map_err(|()| ServiceError::InvalidExternalId)?;
+
+                let provider = providers
+                    .iter()
+                    .find(|provider| provider.supports(&external_id))
+                    .ok_or(ServiceError::MetadataProviderNotConfigured)?;
+
+                provider.lookup(&external_id).await.map_err(provider_error)?
+            }
+            (None, Some(title)) if !title.trim().is_empty() => {
+                search_title(&providers, title.trim(), request.provider.as_deref()).await?
+            }
+            _ => return Err(ServiceError::InvalidExternalId),
+        };
+
+        let fetched = fetched.ok_or(ServiceError::TorrentMetadataNotFound)?;
+
+        let provider = match fetched.external_id {
+            ExternalId::Imdb(_) | ExternalId::TmdbMovie(_) | ExternalId::TmdbTv(_) => tmdb::NAME,
+            ExternalId::MusicBrainz(_) => musicbrainz::NAME,
+        };
+
+        self.metadata_repository
+            .save(&TorrentMetadata {
+                torrent_id: torrent_listing.torrent_id,
+                provider: provider.to_string(),
+                external_id: fetched.external_id.to_string(),
+                title: fetched.title,
+                year: fetched.year,
+                synopsis: fetched.synopsis,
+                poster_url: fetched.poster_url,
+                external_url: fetched.external_url,
+                date_updated: String::new(),
+            })
+            .await?;
+
+        self.metadata_changed(torrent_listing.torrent_id).await;
+
+        self.metadata_repository
+            .get(torrent_listing.torrent_id)
+            .await?
+            .ok_or(ServiceError::TorrentMetadataNotFound)
+    }
+
+    /// It returns the stored metadata of a torrent.
+    ///
+    /// # Errors
+    ///
+    /// It returns an error if:
+    ///
+    /// * The user does not have permission to get the torrent info.
+    /// * The torrent does not exist or it does not have metadata.
+    /// * There is a database error.
+    pub async fn get_metadata(
+        &self,
+        info_hash: &InfoHash,
+        maybe_user_id: Option<UserId>,
+    ) -> Result<TorrentMetadata, ServiceError> {
+        self.authorization_service
+            .authorize(ACTION::GetTorrentInfo, maybe_user_id)
+            .await?;
+
+        let torrent_listing = self.torrent_listing_generator.one_torrent_by_info_hash(info_hash).await?;
+
+        self.metadata_repository
+            .get(torrent_listing.torrent_id)
+            .await?
+            .ok_or(ServiceError::TorrentMetadataNotFound)
+    }
+
+    /// It removes the metadata of a torrent. Owners can always remove the
+    /// metadata of their torrents. Other users need permission to edit the
+    /// torrent.
+    ///
+    /// # Errors
+    ///
+    /// It returns an error if:
+    ///
+    /// * The user does not have permission to edit the torrent.
+    /// * The torrent does not exist or it does not have metadata.
+    /// * There is a database error.
+    pub async fn delete_metadata(&self, info_hash: &InfoHash, maybe_user_id: Option<UserId>) -> Result<(), ServiceError> {
+        let (_user_id, torrent_listing) = self.authorize_edit(info_hash, maybe_user_id).await?;
+
+        self.metadata_repository.delete(torrent_listing.torrent_id).await?;
+
+        self.metadata_changed(torrent_listing.torrent_id).await;
+
+        Ok(())
+    }
+
+    /// The canonical title is searchable, so the torrent is indexed again.
+    async fn metadata_changed(&self, torrent_id: TorrentId) {
+        self.search_service.index_torrent(torrent_id).await;
+
+        self.torrent_listing_generator.invalidate();
+    }
+
+    fn providers(&self, enrichment: &Enrichment) -> Vec<Box<dyn Provider>> {
+        let mut providers: Vec<Box<dyn Provider>> = vec![];
+
+        if let Some(tmdb) = &enrichment.tmdb {
+            providers.push(Box::new(tmdb::TmdbProvider::new(self.client.clone(), tmdb)));
+        }
+
+        if let Some(musicbrainz) = &enrichment.musicbrainz {
+            providers.push(Box::new(musicbrainz::MusicBrainzProvider::new(
+                self.client.clone(),
+                musicbrainz,
+            )));
+        }
+
+        providers
+    }
+
+    async fn enrichment_settings(&self) -> Result<Enrichment, ServiceError> {
+        self.configuration
+            .settings
+            .read()
+            .await
+            .enrichment
+            .clone()
+            .ok_or(ServiceError::MetadataEnrichmentDisabled)
+    }
+
+    async fn authorize_edit(
+        &self,
+        info_hash: &InfoHash,
+        maybe_user_id: Option<UserId>,
+    ) -> Result<(UserId, TorrentListing), ServiceError> {
+        let user_id = maybe_user_id.ok_or(ServiceError::UnauthorizedActionForGuests)?;
+
+        let user = self.user_repository.get_compact(&user_id).await?;
+
+        let torrent_listing = self.torrent_listing_generator.one_torrent_by_info_hash(info_hash).await?;
+
+        if torrent_listing.uploader != user.username {
+            self.authorization_service
+                .authorize_in_category(ACTION::EditTorrentInfo, Some(user_id), torrent_listing.category_id)
+                .await?;
+        }
+
+        Ok((user_id, torrent_listing))
+    }
+}
+
+/// It searches the title in the providers, in order, until one finds it.
+async fn search_title(
+    providers: &[Box<dyn Provider>],
+    title: &str,
+    provider_name: Option<&str>,
+) -> Result<Option<FetchedMetadata>, ServiceError> {
+    let providers: Vec<&dyn Provider> = providers
+        .iter()
+        .map(AsRef::as_ref)
+        .filter(|provider| provider_name.map_or(true, |name| provider.name() == name))
+        .collect();
+
+    if providers.is_empty() {
+        return Err(ServiceError::MetadataProviderNotConfigured);
+    }
+
+    for provider in providers {
+        if let Some(fetched) = provider.search(title).await.map_err(provider_error)? {
+            return Ok(Some(fetched));
+        }
+    }
+
+    Ok(None)
+}
+
+fn provider_error(e: Error) -> ServiceError {
+    error!(target: "enrichment", "Failed to fetch the torrent metadata: {e}");
+
+    ServiceError::MetadataProviderFailed
+}
+
+/// It returns the year of a `YYYY-MM-DD` date, or a partial date.
+fn year_of(date: Option<&str>) -> Option<i64> {
+    date.and_then(|date| date.get(..4)).and_then(|year| year.parse().ok())
+}
+
+pub struct DbTorrentMetadataRepository {
+    database: Arc<Box<dyn Database>>,
+}
+
+impl DbTorrentMetadataRepository {
+    #[must_use]
+    pub fn new(database: Arc<Box<dyn Database>>) -> Self {
+        Self { database }
+    }
+
+    /// It adds or replaces the metadata of a torrent.
+    ///
+    /// # Errors
+    ///
+    /// It returns an error if there is a database error.
+    pub async fn save(&self, metadata: &TorrentMetadata) -> Result<(), database::Error> {
+        self.database.upsert_torrent_metadata(metadata).await
+    }
+
+    /// It returns the metadata of a torrent, if it has.
+    ///
+    /// # Errors
+    ///
+    /// It returns an error if there is a database error.
+    pub async fn get(&self, torrent_id: TorrentId) -> Result<Option<TorrentMetadata>, database::Error> {
+        self.database.get_torrent_metadata(torrent_id).await
+    }
+
+    /// It removes the metadata of a torrent.
+    ///
+    /// # Errors
+    ///
+    /// It returns an error if the torrent does not have metadata or there is
+    /// a database error.
+    pub async fn delete(&self, torrent_id: TorrentId) -> Result<(), database::Error> {
+        self.database.delete_torrent_metadata(torrent_id).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::str::FromStr;
+
+    use super::{year_of, ExternalId};
+
+    #[test]
+    fn it_should_parse_the_external_ids_of_all_the_providers() {
+        assert_eq!(
+            ExternalId::from_str("imdb:tt0133093"),
+            Ok(ExternalId::Imdb("tt0133093".to_string()))
+        );
+        assert_eq!(ExternalId::from_str("tmdb:movie/603"), Ok(ExternalId::TmdbMovie(603)));
+        assert_eq!(ExternalId::from_str("tmdb:tv/1399"), Ok(ExternalId::TmdbTv(1399)));
+        assert_eq!(
+            ExternalId::from_str("musicbrainz:B1392450-E666-3926-A536-22C65F834433"),
+            Ok(ExternalId::MusicBrainz("b1392450-e666-3926-a536-22c65f834433".to_string()))
+        );
+    }
+
+    #[test]
+    fn it_should_reject_invalid_external_ids() {
+        assert!(ExternalId::from_str("tt0133093").is_err());
+        assert!(ExternalId::from_str("imdb:0133093").is_err());
+        assert!(ExternalId::from_str("tmdb:person/1").is_err());
+        assert!(ExternalId::from_str("musicbrainz:radiohead").is_err());
+    }
+
+    #[test]
+    fn it_should_keep_the_external_ids_as_they_are_parsed() {
+        assert_eq!(ExternalId::TmdbTv(1399).to_string(), "tmdb:tv/1399");
+        assert_eq!(year_of(Some("1999-03-30")), Some(1999));
+        assert_eq!(year_of(Some("")), None);
+    }
+}
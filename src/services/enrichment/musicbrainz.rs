@@ -0,0 +1,158 @@
+//! Metadata provider backed by the [`MusicBrainz`](https://musicbrainz.org/doc/MusicBrainz_API)
+//! API, for music releases.
+//!
+//! Torrents are matched with release groups: the album, single or EP,
+//! whatever its edition. The covers are the front images of the release
+//! groups in the [Cover Art Archive](https://coverartarchive.org/).
+use async_trait::async_trait;
+use reqwest::header::USER_AGENT;
+use reqwest::Client;
+use serde::Deserialize;
+use url::Url;
+
+use super::{year_of, Error, ExternalId, FetchedMetadata, Provider};
+use crate::config;
+
+pub const NAME: &str = "musicbrainz";
+
+pub struct MusicBrainzProvider {
+    client: Client,
+    api_url: Url,
+    cover_art_url: Url,
+    user_agent: String,
+}
+
+#[derive(Deserialize)]
+struct ReleaseGroup {
+    id: String,
+    title: String,
+    #[serde(rename = "first-release-date")]
+    first_release_date: Option<String>,
+    #[serde(rename = "primary-type")]
+    primary_type: Option<String>,
+    #[serde(rename = "artist-credit", default)]
+    artist_credit: Vec<ArtistCredit>,
+}
+
+#[derive(Deserialize)]
+struct ArtistCredit {
+    name: String,
+    #[serde(default)]
+    joinphrase: String,
+}
+
+#[derive(Deserialize)]
+struct SearchResponse {
+    #[serde(rename = "release-groups")]
+    release_groups: Vec<ReleaseGroup>,
+}
+
+impl MusicBrainzProvider {
+    #[must_use]
+    pub fn new(client: Client, config: &config::MusicBrainzProvider) -> Self {
+        Self {
+            client,
+            api_url: config.api_url.clone(),
+            cover_art_url: config.cover_art_url.clone(),
+            user_agent: config.user_agent.clone(),
+        }
+    }
+
+    /// It returns `None` when the release group does not exist.
+    async fn get(&self, path: &str, query: &[(&str, &str)]) -> Result<Option<reqwest::Response>, Error> {
+        let url = self.api_url.join(path).map_err(|e| Error::Request { error: e.to_string() })?;
+
+        let response = self
+            .client
+            .get(url)
+            .header(USER_AGENT, &self.user_agent)
+            .query(&[("fmt", "json")])
+            .query(query)
+            .send()
+            .await
+            .map_err(|e| Error::Request { error: e.to_string() })?;
+
+        if response.status() == reqwest::StatusCode::NOT_FOUND {
+            return Ok(None);
+        }
+
+        if !response.status().is_success() {
+            return Err(Error::UnexpectedResponse);
+        }
+
+        Ok(Some(response))
+    }
+
+    fn to_metadata(&self, release_group: ReleaseGroup) -> FetchedMetadata {
+        let artists: String = release_group
+            .artist_credit
+            .iter()
+            .map(|credit| format!("{}{}", credit.name, credit.joinphrase))
+            .collect();
+
+        let synopsis = match (release_group.primary_type, artists.is_empty()) {
+            (Some(primary_type), false) => Some(format!("{primary_type} by {artists}")),
+            (Some(primary_type), true) => Some(primary_type),
+            (None, false) => Some(format!("By {artists}")),
+            (None, true) => None,
+        };
+
+        FetchedMetadata {
+            external_id: ExternalId::MusicBrainz(release_group.id.clone()),
+            title: release_group.title,
+            year: year_of(release_group.first_release_date.as_deref()),
+            synopsis,
+            poster_url: self
+                .cover_art_url
+                .join(&format!("release-group/{}/front", release_group.id))
+                .ok()
+                .map(|url| url.to_string()),
+            external_url: Some(format!("https://musicbrainz.org/release-group/{}", release_group.id)),
+        }
+    }
+}
+
+#[async_trait]
+impl Provider for MusicBrainzProvider {
+    fn name(&self) -> &'static str {
+        NAME
+    }
+
+    fn supports(&self, external_id: &ExternalId) -> bool {
+        matches!(external_id, ExternalId::MusicBrainz(_))
+    }
+
+    async fn lookup(&self, external_id: &ExternalId) -> Result<Option<FetchedMetadata>, Error> {
+        let ExternalId::MusicBrainz(mbid) = external_id else {
+            return Ok(None);
+        };
+
+        let Some(response) = self
+            .get(&format!("release-group/{mbid}"), &[("inc", "artist-credits")])
+            .await?
+        else {
+            return Ok(None);
+        };
+
+        let release_group = response.json::<ReleaseGroup>().await.map_err(|_| Error::UnexpectedResponse)?;
+
+        Ok(Some(self.to_metadata(release_group)))
+    }
+
+    async fn search(&self, title: &str) -> Result<Option<FetchedMetadata>, Error> {
+        let Some(response) = self.get("release-group", &[("query", title), ("limit", "1")]).await? else {
+            return Ok(None);
+        };
+
+        let found = response
+            .json::<SearchResponse>()
+            .await
+            .map_err(|_| Error::UnexpectedResponse)?;
+
+        Ok(found
+            .release_groups
+            .into_iter()
+            .next()
+            .map(|release_group| self.to_metadata(release_group)))
+    }
+}
@@ -0,0 +1,166 @@
+//! Metadata provider backed by the [TMDB](https://developer.themoviedb.org/docs)
+//! API, for movies and TV shows.
+//!
+//! IMDb IDs are resolved with the TMDB `find` endpoint, so they are stored as
+//! TMDB IDs.
+use async_trait::async_trait;
+use reqwest::Client;
+use serde::de::DeserializeOwned;
+use serde::Deserialize;
+use url::Url;
+
+use super::{year_of, Error, ExternalId, FetchedMetadata, Provider};
+use crate::config;
+
+pub const NAME: &str = "tmdb";
+
+pub struct TmdbProvider {
+    client: Client,
+    api_url: Url,
+    api_key: String,
+    language: String,
+    image_url: Url,
+}
+
+/// A movie or a TV show. Movies have a `title` and a `release_date`, TV
+/// shows have a `name` and a `first_air_date`.
+#[derive(Deserialize)]
+struct Title {
+    id: u64,
+    title: Option<String>,
+    name: Option<String>,
+    release_date: Option<String>,
+    first_air_date: Option<String>,
+    overview: Option<String>,
+    poster_path: Option<String>,
+    /// Only in the `search/multi` results.
+    media_type: Option<String>,
+}
+
+#[derive(Deserialize)]
+struct FindResponse {
+    movie_results: Vec<Title>,
+    tv_results: Vec<Title>,
+}
+
+#[derive(Deserialize)]
+struct SearchResponse {
+    results: Vec<Title>,
+}
+
+impl TmdbProvider {
+    #[must_use]
+    pub fn new(client: Client, config: &config::TmdbProvider) -> Self {
+        Self {
+            client,
+            api_url: config.api_url.clone(),
+            api_key: config.api_key.clone(),
+            language: config.language.clone(),
+            image_url: config.image_url.clone(),
+        }
+    }
+
+    /// It returns `None` when the title does not exist.
+    async fn get<T: DeserializeOwned>(&self, path: &str, query: &[(&str, &str)]) -> Result<Option<T>, Error> {
+        let url = self.api_url.join(path).map_err(|e| Error::Request { error: e.to_string() })?;
+
+        let response = self
+            .client
+            .get(url)
+            .query(&[("api_key", self.api_key.as_str()), ("language", self.language.as_str())])
+            .query(query)
+            .send()
+            .await
+            .map_err(|e| Error::Request { error: e.to_string() })?;
+
+        if response.status() == reqwest::StatusCode::NOT_FOUND {
+            return Ok(None);
+        }
+
+        if !response.status().is_success() {
+            return Err(Error::UnexpectedResponse);
+        }
+
+        response.json::<T>().await.map(Some).map_err(|_| Error::UnexpectedResponse)
+    }
+
+    fn to_metadata(&self, title: Title, is_tv: bool) -> FetchedMetadata {
+        let (external_id, kind) = if is_tv {
+            (ExternalId::TmdbTv(title.id), "tv")
+        } else {
+            (ExternalId::TmdbMovie(title.id), "movie")
+        };
+
+        let date = if is_tv { title.first_air_date } else { title.release_date };
+
+        FetchedMetadata {
+            external_id,
+            title: title.title.or(title.name).unwrap_or_default(),
+            year: year_of(date.as_deref()),
+            synopsis: title.overview.filter(|overview| !overview.is_empty()),
+            poster_url: title
+                .poster_path
+                .and_then(|path| self.image_url.join(path.trim_start_matches('/')).ok())
+                .map(|url| url.to_string()),
+            external_url: Some(format!("https://www.themoviedb.org/{kind}/{}", title.id)),
+        }
+    }
+}
+
+#[async_trait]
+impl Provider for TmdbProvider {
+    fn name(&self) -> &'static str {
+        NAME
+    }
+
+    fn supports(&self, external_id: &ExternalId) -> bool {
+        matches!(
+            external_id,
+            ExternalId::Imdb(_) | ExternalId::TmdbMovie(_) | ExternalId::TmdbTv(_)
+        )
+    }
+
+    async fn lookup(&self, external_id: &ExternalId) -> Result<Option<FetchedMetadata>, Error> {
+        match external_id {
+            ExternalId::TmdbMovie(id) => Ok(self
+                .get::<Title>(&format!("movie/{id}"), &[])
+                .await?
+                .map(|title| self.to_metadata(title, false))),
+            ExternalId::TmdbTv(id) => Ok(self
+                .get::<Title>(&format!("tv/{id}"), &[])
+                .await?
+                .map(|title| self.to_metadata(title, true))),
+            ExternalId::Imdb(id) => {
+                let Some(found) = self
+                    .get::<FindResponse>(&format!("find/{id}"), &[("external_source", "imdb_id")])
+                    .await?
+                else {
+                    return Ok(None);
+                };
+
+                if let Some(movie) = found.movie_results.into_iter().next() {
+                    return Ok(Some(self.to_metadata(movie, false)));
+                }
+
+                Ok(found.tv_results.into_iter().next().map(|tv| self.to_metadata(tv, true)))
+            }
+            ExternalId::MusicBrainz(_) => Ok(None),
+        }
+    }
+
+    async fn search(&self, title: &str) -> Result<Option<FetchedMetadata>, Error> {
+        let Some(found) = self.get::<SearchResponse>("search/multi", &[("query", title)]).await? else {
+            return Ok(None);
+        };
+
+        // The results include people too
+        Ok(found
+            .results
+            .into_iter()
+            .find(|result| matches!(result.media_type.as_deref(), Some("movie" | "tv")))
+            .map(|result| {
+                let is_tv = result.media_type.as_deref() == Some("tv");
+                self.to_metadata(result, is_tv)
+            }))
+    }
+}
@@ -0,0 +1,180 @@
+//! Health of the application for container orchestrators and uptime
+//! monitors.
+//!
+//! - Liveness (`GET /health/live`): the application is running and it can
+//!   handle requests. The services it depends on are not checked, so it does
+//!   not fail, and the container is not restarted, when one of them is down.
+//! - Readiness (`GET /health/ready`): the services the application depends
+//!   on are available: the database, the tracker API and the SMTP server,
+//!   when it's configured.
+//!
+//! Both endpoints return `200` when the application is up and `503`
+//! otherwise, with the status of each component:
+//!
+//! ```json
+//! {
+//!   "status": "down",
+//!   "components": [
+//!     { "name": "database", "status": "up", "latency_ms": 1 },
+//!     { "name": "tracker_api", "status": "down", "latency_ms": 5003, "error": "..." },
+//!     { "name": "smtp", "status": "disabled", "latency_ms": 0 }
+//!   ]
+//! }
+//! ```
+use std::future::Future;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+use serde::Serialize;
+use tokio::time::timeout;
+
+use crate::config::validator::{check_smtp_server, check_tracker_api};
+use crate::config::Configuration;
+use crate::databases::database::Database;
+
+/// Maximum time to wait for each component.
+const CHECK_TIMEOUT: Duration = Duration::from_secs(5);
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Status {
+    Up,
+    Down,
+    /// The component is not configured, so it's not checked.
+    Disabled,
+}
+
+/// The status of a service the application depends on.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct ComponentHealth {
+    pub name: &'static str,
+    pub status: Status,
+    /// How long the check took.
+    pub latency_ms: u64,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct HealthReport {
+    /// `up` when all the components are up or disabled.
+    pub status: Status,
+    pub components: Vec<ComponentHealth>,
+}
+
+impl HealthReport {
+    #[must_use]
+    pub fn new(components: Vec<ComponentHealth>) -> Self {
+        let status = if components.iter().any(|component| component.status == Status::Down) {
+            Status::Down
+        } else {
+            Status::Up
+        };
+
+        Self { status, components }
+    }
+
+    #[must_use]
+    pub fn is_up(&self) -> bool {
+        self.status == Status::Up
+    }
+}
+
+pub struct Service {
+    configuration: Arc<Configuration>,
+    database: Arc<Box<dyn Database>>,
+}
+
+impl Service {
+    #[must_use]
+    pub fn new(configuration: Arc<Configuration>, database: Arc<Box<dyn Database>>) -> Service {
+        Service { configuration, database }
+    }
+
+    /// It checks the services the application depends on, at the same time.
+    pub async fn ready(&self) -> HealthReport {
+        let settings = self.configuration.get_all().await;
+
+        let smtp = async {
+            if settings.mail.smtp.server.is_empty() {
+                return ComponentHealth {
+                    name: "smtp",
+                    status: Status::Disabled,
+                    latency_ms: 0,
+                    error: None,
+                };
+            }
+
+            check("smtp", async {
+                check_smtp_server(&settings).await.map_err(|e| e.to_string())
+            })
+            .await
+        };
+
+        let (database, tracker_api, smtp) = tokio::join!(
+            check("database", async { self.database.ping().await.map_err(|e| e.to_string()) }),
+            check("tracker_api", async {
+                check_tracker_api(&settings).await.map_err(|e| e.to_string())
+            }),
+            smtp,
+        );
+
+        HealthReport::new(vec![database, tracker_api, smtp])
+    }
+}
+
+/// It runs the check of a component, with a timeout.
+async fn check<F>(name: &'static str, component_check: F) -> ComponentHealth
+where
+    F: Future<Output = Result<(), String>>,
+{
+    let start = Instant::now();
+
+    let result = timeout(CHECK_TIMEOUT, component_check)
+        .await
+        .unwrap_or_else(|_| Err("timeout".to_string()));
+
+    let latency_ms = u64::try_from(start.elapsed().as_millis()).unwrap_or(u64::MAX);
+
+    match result {
+        Ok(()) => ComponentHealth {
+            name,
+            status: Status::Up,
+            latency_ms,
+            error: None,
+        },
+        Err(error) => ComponentHealth {
+            name,
+            status: Status::Down,
+            latency_ms,
+            error: Some(error),
+        },
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{ComponentHealth, HealthReport, Status};
+
+    fn component(status: Status) -> ComponentHealth {
+        ComponentHealth {
+            name: "database",
+            status,
+            latency_ms: 0,
+            error: None,
+        }
+    }
+
+    #[test]
+    fn the_application_should_be_up_when_no_component_is_down() {
+        assert!(HealthReport::new(vec![]).is_up());
+        assert!(HealthReport::new(vec![component(Status::Up), component(Status::Disabled)]).is_up());
+    }
+
+    #[test]
+    fn the_application_should_be_down_when_any_component_is_down() {
+        let report = HealthReport::new(vec![component(Status::Up), component(Status::Down)]);
+
+        assert_eq!(report.status, Status::Down);
+    }
+}
@@ -0,0 +1,322 @@
+//! Direct image uploads to the S3 storage backend.
+//!
+//! Large images don't go through the index. The upload has two steps:
+//!
+//! 1. The user asks for a pre-signed `PUT` URL for an image with a given
+//!    kind, content type and size. The URL is valid for a few minutes and
+//!    only for that content type.
+//! 2. After uploading the image to the storage, the user confirms the upload.
+//!    The index reads the beginning of the object to check it's an image of
+//!    the expected type and size, and registers it. Invalid objects are
+//!    deleted from the bucket.
+//!
+//! Confirmed avatars replace the user's avatar. Confirmed screenshots get a
+//! public URL the user can link from torrent descriptions.
+//!
+//! Refer to the `[storage.s3]` section in the configuration.
+use std::sync::Arc;
+
+use chrono::Utc;
+use rand::distributions::Alphanumeric;
+use rand::Rng;
+use serde::{Deserialize, Serialize};
+use tracing::{error, warn};
+use utoipa::ToSchema;
+
+use super::authorization::{self, ACTION};
+use crate::databases::database::{Database, Error};
+use crate::errors::ServiceError;
+use crate::models::image::{ImageId, ImageKind, UploadedImage};
+use crate::models::user::UserId;
+use crate::storage::s3::{self, Bucket};
+
+/// Image types accepted: content type and file extension.
+const IMAGE_TYPES: &[(&str, &str)] = &[
+    ("image/png", "png"),
+    ("image/jpeg", "jpg"),
+    ("image/gif", "gif"),
+    ("image/webp", "webp"),
+];
+
+/// Number of bytes read to detect the image type.
+const MAGIC_BYTES_LENGTH: u64 = 16;
+
+/// User request to get a pre-signed upload URL.
+#[derive(Debug, Deserialize, ToSchema)]
+pub struct PresignRequest {
+    pub kind: ImageKind,
+    /// The image content type: `image/png`, `image/jpeg`, `image/gif` or
+    /// `image/webp`.
+    pub content_type: String,
+    /// The image size in bytes.
+    pub size: u64,
+}
+
+/// A pre-signed upload URL.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct PresignedUpload {
+    /// The key of the object in the bucket. It's needed to confirm the
+    /// upload.
+    pub object_key: String,
+    /// The URL to upload the image with a `PUT` request.
+    pub upload_url: String,
+    /// The `Content-Type` header the upload request must include.
+    pub content_type: String,
+    pub expires_in_seconds: u64,
+}
+
+/// User request to confirm an upload.
+#[derive(Debug, Deserialize, ToSchema)]
+pub struct ConfirmRequest {
+    pub object_key: String,
+}
+
+pub struct Service {
+    bucket: Option<Bucket>,
+    image_repository: Arc<DbImageRepository>,
+    authorization_service: Arc<authorization::Service>,
+}
+
+impl Service {
+    #[must_use]
+    pub fn new(
+        bucket: Option<Bucket>,
+        image_repository: Arc<DbImageRepository>,
+        authorization_service: Arc<authorization::Service>,
+    ) -> Service {
+        Service {
+            bucket,
+            image_repository,
+            authorization_service,
+        }
+    }
+
+    /// It returns a pre-signed URL to upload an image to the storage.
+    ///
+    /// # Errors
+    ///
+    /// It returns an error if:
+    ///
+    /// * The S3 storage backend is not enabled.
+    /// * The user does not have permissions to upload images.
+    /// * The image type is not supported or the image is too big.
+    pub async fn presign(
+        &self,
+        request: &PresignRequest,
+        maybe_user_id: Option<UserId>,
+    ) -> Result<PresignedUpload, ServiceError> {
+        let bucket = self.bucket.as_ref().ok_or(ServiceError::ImageStorageDisabled)?;
+
+        self.authorization_service
+            .authorize(ACTION::UploadImage, maybe_user_id)
+            .await?;
+
+        let user_id = maybe_user_id.ok_or(ServiceError::UnauthorizedActionForGuests)?;
+
+        let extension = extension_for(&request.content_type).ok_or(ServiceError::UnsupportedImageType)?;
+
+        if request.size == 0 || request.size > bucket.config().max_image_size {
+            return Err(ServiceError::InvalidImageSize);
+        }
+
+        let name: String = rand::thread_rng()
+            .sample_iter(&Alphanumeric)
+            .take(32)
+            .map(char::from)
+            .collect();
+
+        let object_key = format!("{}/{user_id}/{name}.{extension}", request.kind.as_str());
+
+        Ok(PresignedUpload {
+            upload_url: bucket
+                .presigned_put_url(&object_key, &request.content_type, Utc::now())
+                .to_string(),
+            object_key,
+            content_type: request.content_type.clone(),
+            expires_in_seconds: bucket.config().presigned_url_expiration_seconds,
+        })
+    }
+
+    /// It validates an uploaded image and registers it.
+    ///
+    /// # Errors
+    ///
+    /// It returns an error if:
+    ///
+    /// * The S3 storage backend is not enabled.
+    /// * The user does not have permissions to upload images.
+    /// * The object key was not issued for the user.
+    /// * The object does not exist, it's not an image of the expected type
+    ///   or it's too big.
+    /// * The image was already registered.
+    /// * There is a database error.
+    pub async fn confirm(&self, request: &ConfirmRequest, maybe_user_id: Option<UserId>) -> Result<UploadedImage, ServiceError> {
+        let bucket = self.bucket.as_ref().ok_or(ServiceError::ImageStorageDisabled)?;
+
+        self.authorization_service
+            .authorize(ACTION::UploadImage, maybe_user_id)
+            .await?;
+
+        let user_id = maybe_user_id.ok_or(ServiceError::UnauthorizedActionForGuests)?;
+
+        let (kind, content_type) = parse_object_key(&request.object_key, user_id).ok_or(ServiceError::InvalidImageUpload)?;
+
+        let head = bucket
+            .read_head(&request.object_key, MAGIC_BYTES_LENGTH)
+            .await
+            .map_err(|e| match e {
+                s3::Error::ObjectNotFound => ServiceError::ImageUploadNotFound,
+                s3::Error::RequestFailed(e) => {
+                    error!(target: "storage", "Failed to read the uploaded image {}: {e}", request.object_key);
+                    ServiceError::InternalServerError
+                }
+            })?;
+
+        if head.size > bucket.config().max_image_size || detect_content_type(&head.bytes) != Some(content_type) {
+            if let Err(e) = bucket.delete(&request.object_key).await {
+                warn!(target: "storage", "Failed to delete the invalid image {}: {e:?}", request.object_key);
+            }
+
+            return Err(ServiceError::InvalidImageUpload);
+        }
+
+        let url = bucket.public_url(&request.object_key).to_string();
+
+        let image_id = self
+            .image_repository
+            .add(user_id, kind, &request.object_key, &url, content_type, head.size)
+            .await?;
+
+        if kind == ImageKind::Avatar {
+            self.image_repository.set_avatar(user_id, &url).await?;
+        }
+
+        Ok(UploadedImage {
+            image_id,
+            kind,
+            url,
+            content_type: content_type.to_string(),
+            size: head.size,
+        })
+    }
+}
+
+fn extension_for(content_type: &str) -> Option<&'static str> {
+    IMAGE_TYPES
+        .iter()
+        .find(|(image_type, _)| *image_type == content_type)
+        .map(|(_, extension)| *extension)
+}
+
+/// It checks the object key was issued for the user and returns the image
+/// kind and content type.
+fn parse_object_key(object_key: &str, user_id: UserId) -> Option<(ImageKind, &'static str)> {
+    let mut parts = object_key.split('/');
+
+    let kind = match parts.next()? {
+        "avatar" => ImageKind::Avatar,
+        "screenshot" => ImageKind::Screenshot,
+        _ => return None,
+    };
+
+    if parts.next()?.parse::<UserId>().ok()? != user_id {
+        return None;
+    }
+
+    let (name, extension) = parts.next()?.rsplit_once('.')?;
+
+    if parts.next().is_some() || name.is_empty() || !name.chars().all(|c| c.is_ascii_alphanumeric()) {
+        return None;
+    }
+
+    IMAGE_TYPES
+        .iter()
+        .find(|(_, image_extension)| *image_extension == extension)
+        .map(|(content_type, _)| (kind, *content_type))
+}
+
+/// It detects the image type from the first bytes of the file.
+pub(crate) fn detect_content_type(bytes: &[u8]) -> Option<&'static str> {
+    if bytes.starts_with(b"\x89PNG\r\n\x1a\n") {
+        Some("image/png")
+    } else if bytes.starts_with(b"\xff\xd8\xff") {
+        Some("image/jpeg")
+    } else if bytes.starts_with(b"GIF87a") || bytes.starts_with(b"GIF89a") {
+        Some("image/gif")
+    } else if bytes.len() >= 12 && &bytes[0..4] == b"RIFF" && &bytes[8..12] == b"WEBP" {
+        Some("image/webp")
+    } else {
+        None
+    }
+}
+
+pub struct DbImageRepository {
+    database: Arc<Box<dyn Database>>,
+}
+
+impl DbImageRepository {
+    #[must_use]
+    pub fn new(database: Arc<Box<dyn Database>>) -> Self {
+        Self { database }
+    }
+
+    /// It registers an uploaded image.
+    ///
+    /// # Errors
+    ///
+    /// It returns an error if the image was already registered or there is a
+    /// database error.
+    pub async fn add(
+        &self,
+        user_id: UserId,
+        kind: ImageKind,
+        object_key: &str,
+        url: &str,
+        content_type: &str,
+        size: u64,
+    ) -> Result<ImageId, Error> {
+        self.database
+            .insert_image(user_id, kind, object_key, url, content_type, size)
+            .await
+    }
+
+    /// It changes the user's avatar.
+    ///
+    /// # Errors
+    ///
+    /// It returns an error if there is a database error.
+    pub async fn set_avatar(&self, user_id: UserId, url: &str) -> Result<(), Error> {
+        self.database.update_user_avatar(user_id, url).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{detect_content_type, parse_object_key};
+    use crate::models::image::ImageKind;
+
+    #[test]
+    fn it_should_only_accept_object_keys_issued_for_the_user() {
+        assert_eq!(
+            parse_object_key("avatar/1/abc123.png", 1),
+            Some((ImageKind::Avatar, "image/png"))
+        );
+        assert_eq!(
+            parse_object_key("screenshot/1/abc123.jpg", 1),
+            Some((ImageKind::Screenshot, "image/jpeg"))
+        );
+        assert_eq!(parse_object_key("avatar/2/abc123.png", 1), None);
+        assert_eq!(parse_object_key("avatar/1/../2/abc123.png", 1), None);
+        assert_eq!(parse_object_key("avatar/1/abc123.exe", 1), None);
+        assert_eq!(parse_object_key("banner/1/abc123.png", 1), None);
+    }
+
+    #[test]
+    fn it_should_detect_the_image_type_from_the_magic_bytes() {
+        assert_eq!(detect_content_type(b"\x89PNG\r\n\x1a\n\0\0\0\rIHDR"), Some("image/png"));
+        assert_eq!(detect_content_type(b"\xff\xd8\xff\xe0\0\x10JFIF"), Some("image/jpeg"));
+        assert_eq!(detect_content_type(b"GIF89a\x01\0\x01\0"), Some("image/gif"));
+        assert_eq!(detect_content_type(b"RIFF\x24\0\0\0WEBPVP8 "), Some("image/webp"));
+        assert_eq!(detect_content_type(b"<html></html>"), None);
+    }
+}
@@ -0,0 +1,528 @@
+//! Account inactivity policy.
+//!
+//! Users who don't log in for a while are warned by email and, if they still
+//! don't log in, their accounts are disabled or deleted. Refer to the
+//! [`Inactivity`](crate::config::Inactivity) configuration for the periods.
+//!
+//! The last activity of a user is the date of the last login or, if the user
+//! never logged in, the registration date. Logging in clears the warning.
+//!
+//! The policy is applied by the [`inactivity`](crate::console::cronjobs::inactivity)
+//! cronjob. Administrators can get a report of the upcoming actions and exempt
+//! users from the policy. Exempting a disabled user enables the account
+//! again.
+//!
+//! Users are always warned before their account is disabled or deleted, and
+//! they get at least the time between the warning and the action periods to
+//! log in. Users without email are "warned" without sending any email.
+use std::sync::Arc;
+
+use chrono::{NaiveDateTime, TimeDelta, Utc};
+use serde::{Deserialize, Serialize};
+use tracing::{error, info};
+use utoipa::IntoParams;
+
+use super::authorization::{self, ACTION};
+use super::bulk_mail::DbEmailQueueRepository;
+use crate::config::{Configuration, Inactivity, InactivityAction};
+use crate::databases::database::{Database, Error};
+use crate::errors::ServiceError;
+use crate::models::user::{InactiveUser, UserId};
+use crate::utils::clock::DATETIME_FORMAT;
+
+/// Number of days in a month for the inactivity periods.
+const DAYS_PER_MONTH: i64 = 30;
+
+/// Default number of days included in the report.
+const DEFAULT_REPORT_DAYS: u32 = 30;
+
+/// Maximum number of days included in the report.
+const MAX_REPORT_DAYS: u32 = 365;
+
+/// Admin request to get the upcoming actions.
+#[derive(Debug, Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
+pub struct ReportRequest {
+    /// Number of days ahead included in the report. Default to `30`.
+    pub days: Option<u32>,
+}
+
+/// The upcoming actions of the inactivity policy.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct InactivityReport {
+    /// Whether the inactivity policy is enabled or not.
+    pub enabled: bool,
+    /// Number of days ahead included in the report.
+    pub days: u32,
+    /// Actions due in the next `days` days, including overdue actions that
+    /// will be applied in the next check.
+    pub upcoming: Vec<UpcomingAction>,
+    /// Usernames of the users exempted from the policy.
+    pub exemptions: Vec<String>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct UpcomingAction {
+    pub username: String,
+    pub last_activity: String,
+    pub action: PlannedAction,
+    /// Date when the action is due.
+    pub date: String,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum PlannedAction {
+    Warn,
+    Disable,
+    Delete,
+}
+
+impl PlannedAction {
+    fn past_participle(self) -> &'static str {
+        match self {
+            PlannedAction::Warn => "warned",
+            PlannedAction::Disable => "disabled",
+            PlannedAction::Delete => "deleted",
+        }
+    }
+}
+
+/// Number of accounts affected by a check.
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct CheckSummary {
+    pub warned: usize,
+    pub disabled: usize,
+    pub deleted: usize,
+}
+
+pub struct Service {
+    configuration: Arc<Configuration>,
+    inactivity_repository: Arc<DbInactivityRepository>,
+    email_queue_repository: Arc<DbEmailQueueRepository>,
+    authorization_service: Arc<authorization::Service>,
+}
+
+impl Service {
+    #[must_use]
+    pub fn new(
+        configuration: Arc<Configuration>,
+        inactivity_repository: Arc<DbInactivityRepository>,
+        email_queue_repository: Arc<DbEmailQueueRepository>,
+        authorization_service: Arc<authorization::Service>,
+    ) -> Service {
+        Service {
+            configuration,
+            inactivity_repository,
+            email_queue_repository,
+            authorization_service,
+        }
+    }
+
+    /// It returns the actions due in the next days.
+    ///
+    /// # Errors
+    ///
+    /// It returns an error if:
+    ///
+    /// * The user does not have permissions to manage the inactivity policy.
+    /// * There is a database error.
+    pub async fn get_report(
+        &self,
+        request: &ReportRequest,
+        maybe_user_id: Option<UserId>,
+    ) -> Result<InactivityReport, ServiceError> {
+        self.authorization_service
+            .authorize(ACTION::ManageInactivity, maybe_user_id)
+            .await?;
+
+        let days = request.days.unwrap_or(DEFAULT_REPORT_DAYS).clamp(1, MAX_REPORT_DAYS);
+
+        let exemptions = self.inactivity_repository.get_exemptions().await?;
+
+        let Some(policy) = self.policy().await else {
+            return Ok(InactivityReport {
+                enabled: false,
+                days,
+                upcoming: vec![],
+                exemptions,
+            });
+        };
+
+        let until = Utc::now().naive_utc() + TimeDelta::days(i64::from(days));
+
+        let users = self
+            .inactivity_repository
+            .get_inactive_users(until - months(policy.warn_after_months))
+            .await?;
+
+        let upcoming = users
+            .iter()
+            .filter_map(|user| {
+                let (action, date) = next_action(user, &policy, Utc::now().naive_utc())?;
+
+                (date <= until).then(|| UpcomingAction {
+                    username: user.username.clone(),
+                    last_activity: user.last_activity.clone(),
+                    action,
+                    date: date.format(DATETIME_FORMAT).to_string(),
+                })
+            })
+            .collect();
+
+        Ok(InactivityReport {
+            enabled: true,
+            days,
+            upcoming,
+            exemptions,
+        })
+    }
+
+    /// It exempts a user from the inactivity policy. If the account was
+    /// disabled, it's enabled again.
+    ///
+    /// # Errors
+    ///
+    /// It returns an error if:
+    ///
+    /// * The user does not have permissions to manage the inactivity policy.
+    /// * The user to exempt does not exist.
+    /// * There is a database error.
+    pub async fn add_exemption(&self, username: &str, maybe_user_id: Option<UserId>) -> Result<(), ServiceError> {
+        self.authorization_service
+            .authorize(ACTION::ManageInactivity, maybe_user_id)
+            .await?;
+
+        let user_id = self.inactivity_repository.get_user_id(username).await?;
+
+        self.inactivity_repository.add_exemption(user_id).await?;
+        self.inactivity_repository.set_disabled(user_id, false).await?;
+
+        Ok(())
+    }
+
+    /// It removes the inactivity policy exemption of a user.
+    ///
+    /// # Errors
+    ///
+    /// It returns an error if:
+    ///
+    /// * The user does not have permissions to manage the inactivity policy.
+    /// * The user does not exist.
+    /// * There is a database error.
+    pub async fn remove_exemption(&self, username: &str, maybe_user_id: Option<UserId>) -> Result<(), ServiceError> {
+        self.authorization_service
+            .authorize(ACTION::ManageInactivity, maybe_user_id)
+            .await?;
+
+        let user_id = self.inactivity_repository.get_user_id(username).await?;
+
+        Ok(self.inactivity_repository.remove_exemption(user_id).await?)
+    }
+
+    /// It applies the actions that are due. Errors applying the action to
+    /// one user are only logged.
+    ///
+    /// # Errors
+    ///
+    /// It returns an error if the inactive users can't be loaded.
+    pub async fn check(&self) -> Result<CheckSummary, Error> {
+        let mut summary = CheckSummary::default();
+
+        let Some(policy) = self.policy().await else {
+            return Ok(summary);
+        };
+
+        let now = Utc::now().naive_utc();
+
+        let users = self
+            .inactivity_repository
+            .get_inactive_users(now - months(policy.warn_after_months))
+            .await?;
+
+        for user in users {
+            let Some((action, date)) = next_action(&user, &policy, now) else {
+                continue;
+            };
+
+            if date > now {
+                continue;
+            }
+
+            if let Err(e) = self.apply(&user, action, &policy, now).await {
+                error!(target: "inactivity", "Failed to apply the inactivity policy to user {}: {e:?}", user.username);
+                continue;
+            }
+
+            info!(target: "inactivity", "User {} {} after being inactive since {}", user.username, action.past_participle(), user.last_activity);
+
+            match action {
+                PlannedAction::Warn => summary.warned += 1,
+                PlannedAction::Disable => summary.disabled += 1,
+                PlannedAction::Delete => summary.deleted += 1,
+            }
+        }
+
+        Ok(summary)
+    }
+
+    async fn apply(
+        &self,
+        user: &InactiveUser,
+        action: PlannedAction,
+        policy: &Inactivity,
+        now: NaiveDateTime,
+    ) -> Result<(), Error> {
+        match action {
+            PlannedAction::Warn => {
+                if !user.email.is_empty() {
+                    let site_name = self.configuration.settings.read().await.website.name.clone();
+
+                    let warned = InactiveUser {
+                        date_inactivity_warning: Some(now.format(DATETIME_FORMAT).to_string()),
+                        ..user.clone()
+                    };
+
+                    if let Some((final_action, date)) = next_action(&warned, policy, now) {
+                        let body = warning_body(&site_name, user, final_action, date);
+
+                        self.email_queue_repository
+                            .enqueue(&user.email, &format!("{site_name} - Inactive account"), &body)
+                            .await?;
+                    }
+                }
+
+                self.inactivity_repository.set_warned(user.user_id).await
+            }
+            PlannedAction::Disable => self.inactivity_repository.set_disabled(user.user_id, true).await,
+            PlannedAction::Delete => self.inactivity_repository.delete_user(user.user_id).await,
+        }
+    }
+
+    async fn policy(&self) -> Option<Inactivity> {
+        self.configuration.settings.read().await.inactivity.clone()
+    }
+}
+
+fn months(months: u32) -> TimeDelta {
+    TimeDelta::days(i64::from(months) * DAYS_PER_MONTH)
+}
+
+fn parse_date(date: &str) -> Option<NaiveDateTime> {
+    NaiveDateTime::parse_from_str(date, DATETIME_FORMAT).ok()
+}
+
+/// It returns the next action for an inactive user and the date when it's
+/// due. Users are warned first. The final action is due at least the time
+/// between the warning and the action periods after the warning.
+fn next_action(user: &InactiveUser, policy: &Inactivity, now: NaiveDateTime) -> Option<(PlannedAction, NaiveDateTime)> {
+    let last_activity = parse_date(&user.last_activity)?;
+
+    let Some(date_warning) = user.date_inactivity_warning.as_deref() else {
+        return Some((PlannedAction::Warn, last_activity + months(policy.warn_after_months)));
+    };
+
+    let date_warning = parse_date(date_warning).unwrap_or(now);
+
+    let grace_period = months(policy.action_after_months.saturating_sub(policy.warn_after_months));
+
+    let date = (last_activity + months(policy.action_after_months)).max(date_warning + grace_period);
+
+    let action = match policy.action {
+        InactivityAction::Delete if !(policy.preserve_uploads && user.uploads > 0) => PlannedAction::Delete,
+        InactivityAction::Delete | InactivityAction::Disable => PlannedAction::Disable,
+    };
+
+    Some((action, date))
+}
+
+fn warning_body(site_name: &str, user: &InactiveUser, action: PlannedAction, date: NaiveDateTime) -> String {
+    format!(
+        "Hi {},\n\nYou have not logged in to {site_name} since {}. If you don't log in before {} (UTC), your account will be {}.\n",
+        user.username,
+        user.last_activity,
+        date.format(DATETIME_FORMAT),
+        action.past_participle()
+    )
+}
+
+pub struct DbInactivityRepository {
+    database: Arc<Box<dyn Database>>,
+}
+
+impl DbInactivityRepository {
+    #[must_use]
+    pub fn new(database: Arc<Box<dyn Database>>) -> Self {
+        Self { database }
+    }
+
+    /// It returns the users inactive since the date.
+    ///
+    /// # Errors
+    ///
+    /// It returns an error if there is a database error.
+    pub async fn get_inactive_users(&self, since: NaiveDateTime) -> Result<Vec<InactiveUser>, Error> {
+        self.database
+            .get_inactive_users(&since.format(DATETIME_FORMAT).to_string())
+            .await
+    }
+
+    /// It returns the ID of a user.
+    ///
+    /// # Errors
+    ///
+    /// It returns an error if the user does not exist.
+    pub async fn get_user_id(&self, username: &str) -> Result<UserId, Error> {
+        self.database
+            .get_user_profile_from_username(username)
+            .await
+            .map(|user_profile| user_profile.user_id)
+    }
+
+    /// It records that the user was warned.
+    ///
+    /// # Errors
+    ///
+    /// It returns an error if there is a database error.
+    pub async fn set_warned(&self, user_id: UserId) -> Result<(), Error> {
+        self.database.update_user_inactivity_warning(user_id).await
+    }
+
+    /// It disables or enables the user account.
+    ///
+    /// # Errors
+    ///
+    /// It returns an error if there is a database error.
+    pub async fn set_disabled(&self, user_id: UserId, disabled: bool) -> Result<(), Error> {
+        self.database.update_user_disabled(user_id, disabled).await
+    }
+
+    /// It deletes the user.
+    ///
+    /// # Errors
+    ///
+    /// It returns an error if there is a database error.
+    pub async fn delete_user(&self, user_id: UserId) -> Result<(), Error> {
+        self.database.delete_user(user_id).await
+    }
+
+    /// It returns the usernames of the exempted users.
+    ///
+    /// # Errors
+    ///
+    /// It returns an error if there is a database error.
+    pub async fn get_exemptions(&self) -> Result<Vec<String>, Error> {
+        self.database.get_inactivity_exemptions().await
+    }
+
+    /// It exempts a user from the inactivity policy.
+    ///
+    /// # Errors
+    ///
+    /// It returns an error if there is a database error.
+    pub async fn add_exemption(&self, user_id: UserId) -> Result<(), Error> {
+        self.database.insert_inactivity_exemption(user_id).await
+    }
+
+    /// It removes the exemption of a user.
+    ///
+    /// # Errors
+    ///
+    /// It returns an error if there is a database error.
+    pub async fn remove_exemption(&self, user_id: UserId) -> Result<(), Error> {
+        self.database.delete_inactivity_exemption(user_id).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::NaiveDateTime;
+
+    use super::{next_action, parse_date, PlannedAction};
+    use crate::config::{Inactivity, InactivityAction};
+    use crate::models::user::InactiveUser;
+
+    fn user(last_activity: &str, date_inactivity_warning: Option<&str>, uploads: i64) -> InactiveUser {
+        InactiveUser {
+            user_id: 1,
+            username: "alice".to_string(),
+            email: "alice@example.com".to_string(),
+            email_verified: true,
+            last_activity: last_activity.to_string(),
+            date_inactivity_warning: date_inactivity_warning.map(ToString::to_string),
+            uploads,
+        }
+    }
+
+    fn date(date: &str) -> NaiveDateTime {
+        parse_date(date).unwrap()
+    }
+
+    fn policy(action: InactivityAction) -> Inactivity {
+        Inactivity {
+            warn_after_months: 11,
+            action_after_months: 12,
+            action,
+            ..Inactivity::default()
+        }
+    }
+
+    #[test]
+    fn it_should_warn_users_first() {
+        let now = date("2025-01-01 00:00:00");
+
+        assert_eq!(
+            next_action(&user("2023-01-01 00:00:00", None, 0), &policy(InactivityAction::Delete), now),
+            Some((PlannedAction::Warn, date("2023-11-27 00:00:00")))
+        );
+    }
+
+    #[test]
+    fn it_should_give_warned_users_time_to_log_in() {
+        let now = date("2025-01-01 00:00:00");
+
+        assert_eq!(
+            next_action(
+                &user("2023-01-01 00:00:00", Some("2024-12-31 00:00:00"), 0),
+                &policy(InactivityAction::Delete),
+                now
+            ),
+            Some((PlannedAction::Delete, date("2025-01-30 00:00:00")))
+        );
+    }
+
+    #[test]
+    fn it_should_apply_the_action_after_the_action_period() {
+        let now = date("2025-01-01 00:00:00");
+
+        assert_eq!(
+            next_action(
+                &user("2024-01-01 00:00:00", Some("2024-11-26 00:00:00"), 0),
+                &policy(InactivityAction::Disable),
+                now
+            ),
+            Some((PlannedAction::Disable, date("2024-12-26 00:00:00")))
+        );
+    }
+
+    #[test]
+    fn it_should_disable_instead_of_delete_users_with_uploads_when_uploads_are_preserved() {
+        let now = date("2025-01-01 00:00:00");
+        let warned_user = user("2023-01-01 00:00:00", Some("2024-01-01 00:00:00"), 3);
+
+        assert_eq!(
+            next_action(&warned_user, &policy(InactivityAction::Delete), now).map(|(action, _)| action),
+            Some(PlannedAction::Disable)
+        );
+
+        let policy = Inactivity {
+            preserve_uploads: false,
+            ..policy(InactivityAction::Delete)
+        };
+
+        assert_eq!(
+            next_action(&warned_user, &policy, now).map(|(action, _)| action),
+            Some(PlannedAction::Delete)
+        );
+    }
+}
@@ -0,0 +1,237 @@
+//! Torrent listing snapshots for anonymous traffic.
+//!
+//! When the `[listing_snapshots]` configuration section is present, the first
+//! pages of the default torrent listing, and of the listing for the categories
+//! with the most torrents, are rendered as JSON by a background task every
+//! few seconds. Requests from guests matching one of those listings are
+//! served from the snapshot without querying the database.
+//!
+//! Logged-in users always get a fresh listing.
+use std::collections::HashMap;
+use std::sync::{Arc, RwLock};
+
+use tracing::{debug, error};
+
+use super::category::DbCategoryRepository;
+use super::torrent::{self, ListingRequest};
+use crate::config::{Configuration, ListingSnapshots};
+use crate::databases::database::Sorting;
+
+/// The listing category (`None` for all categories) and the page number.
+type SnapshotKey = (Option<String>, u32);
+
+#[derive(Default)]
+struct Snapshots {
+    /// The page size used to render the snapshots.
+    page_size: u8,
+    /// The JSON encoded [`TorrentsResponse`](crate::models::response::TorrentsResponse)
+    /// for each listing page.
+    listings: HashMap<SnapshotKey, Arc<String>>,
+}
+
+pub struct Service {
+    configuration: Arc<Configuration>,
+    config: Option<ListingSnapshots>,
+    torrent_service: Arc<torrent::Index>,
+    category_repository: Arc<DbCategoryRepository>,
+    snapshots: RwLock<Snapshots>,
+}
+
+impl Service {
+    pub async fn new(
+        configuration: Arc<Configuration>,
+        torrent_service: Arc<torrent::Index>,
+        category_repository: Arc<DbCategoryRepository>,
+    ) -> Service {
+        let config = configuration.settings.read().await.listing_snapshots.clone();
+
+        Service {
+            configuration,
+            config,
+            torrent_service,
+            category_repository,
+            snapshots: RwLock::new(Snapshots::default()),
+        }
+    }
+
+    /// Whether the listing snapshots are enabled or not.
+    #[must_use]
+    pub fn is_enabled(&self) -> bool {
+        self.config.is_some()
+    }
+
+    /// It returns the JSON encoded listing for a guest request if there is a
+    /// snapshot for it.
+    ///
+    /// # Panics
+    ///
+    /// Will panic if the snapshots lock is poisoned.
+    #[must_use]
+    pub fn get(&self, request: &ListingRequest) -> Option<Arc<String>> {
+        if !self.is_enabled() {
+            return None;
+        }
+
+        let snapshots = self.snapshots.read().expect("listing snapshots lock should not be poisoned");
+
+        let key = snapshot_key(request, snapshots.page_size)?;
+
+        snapshots.listings.get(&key).cloned()
+    }
+
+    /// It renders again all the snapshots.
+    ///
+    /// Listings that can't be rendered are not cached, so those requests are
+    /// served from the database until the next refresh.
+    ///
+    /// # Panics
+    ///
+    /// Will panic if the snapshots lock is poisoned.
+    pub async fn refresh(&self) {
+        let Some(config) = &self.config else {
+            return;
+        };
+
+        let page_size = self.configuration.settings.read().await.api.default_torrent_page_size;
+
+        let mut categories = match self.category_repository.get_all().await {
+            Ok(categories) => categories,
+            Err(e) => {
+                error!(target: "listing snapshots", "Failed to get the categories: {e:?}");
+                Vec::new()
+            }
+        };
+        categories.sort_by(|a, b| b.num_torrents.cmp(&a.num_torrents));
+
+        let listing_categories = std::iter::once(None).chain(
+            categories
+                .into_iter()
+                .take(config.top_categories)
+                .map(|category| Some(category.name)),
+        );
+
+        let mut listings = HashMap::new();
+
+        for category in listing_categories {
+            for page in 0..config.pages {
+                let request = ListingRequest {
+                    page: Some(page),
+                    categories: category.clone(),
+                    ..ListingRequest::default()
+                };
+
+                let torrents_response = match self.torrent_service.generate_torrent_info_listing(&request, None).await {
+                    Ok(torrents_response) => torrents_response,
+                    Err(e) => {
+                        error!(target: "listing snapshots", "Failed to render the listing snapshot {category:?} page {page}: {e}");
+                        break;
+                    }
+                };
+
+                let is_last_page = torrents_response.results.len() < usize::from(page_size);
+
+                match serde_json::to_string(&torrents_response) {
+                    Ok(json) => {
+                        listings.insert((category.clone(), page), Arc::new(json));
+                    }
+                    Err(e) => error!(target: "listing snapshots", "Failed to encode the listing snapshot: {e}"),
+                }
+
+                if is_last_page {
+                    break;
+                }
+            }
+        }
+
+        debug!(target: "listing snapshots", "{} listing snapshots rendered", listings.len());
+
+        *self.snapshots.write().expect("listing snapshots lock should not be poisoned") = Snapshots { page_size, listings };
+    }
+}
+
+/// It returns the snapshot key for the request, or `None` if the request is
+/// not for one of the default listings.
+fn snapshot_key(request: &ListingRequest, page_size: u8) -> Option<SnapshotKey> {
+    let is_default_listing = request.search.is_none()
+        && request.tags.as_deref().unwrap_or_default().is_empty()
+        && matches!(request.sort, None | Some(Sorting::UploadedDesc))
+        && request.then_sort.is_none()
+        && !request.has_filters()
+        && request.page_size.unwrap_or(page_size) == page_size;
+
+    if !is_default_listing {
+        return None;
+    }
+
+    let category = match request.categories.as_deref().map(str::trim) {
+        None | Some("") => None,
+        Some(category) if category.contains(',') => return None,
+        Some(category) => Some(category.to_string()),
+    };
+
+    Some((category, request.page.unwrap_or(0)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::snapshot_key;
+    use crate::databases::database::Sorting;
+    use crate::services::torrent::ListingRequest;
+
+    fn default_request() -> ListingRequest {
+        ListingRequest::default()
+    }
+
+    #[test]
+    fn it_should_use_the_snapshot_for_the_default_listing() {
+        assert_eq!(snapshot_key(&default_request(), 10), Some((None, 0)));
+
+        let request = ListingRequest {
+            page_size: Some(10),
+            page: Some(2),
+            sort: Some(Sorting::UploadedDesc),
+            categories: Some("movies".to_string()),
+            ..default_request()
+        };
+
+        assert_eq!(snapshot_key(&request, 10), Some((Some("movies".to_string()), 2)));
+    }
+
+    #[test]
+    fn it_should_not_use_the_snapshot_for_custom_listings() {
+        let requests = [
+            ListingRequest {
+                search: Some("ubuntu".to_string()),
+                ..default_request()
+            },
+            ListingRequest {
+                tags: Some("linux".to_string()),
+                ..default_request()
+            },
+            ListingRequest {
+                sort: Some(Sorting::SeedersDesc),
+                ..default_request()
+            },
+            ListingRequest {
+                page_size: Some(50),
+                ..default_request()
+            },
+            ListingRequest {
+                categories: Some("movies,music".to_string()),
+                ..default_request()
+            },
+            ListingRequest {
+                then_sort: Some(Sorting::SeedersDesc),
+                ..default_request()
+            },
+            ListingRequest {
+                min_seeders: Some(1),
+                ..default_request()
+            },
+        ];
+
+        for request in requests {
+            assert_eq!(snapshot_key(&request, 10), None);
+        }
+    }
+}
@@ -0,0 +1,234 @@
+//! Live events for connected frontends.
+//!
+//! Services publish events to an in-memory broadcast channel and the
+//! `/v1/events` endpoint streams them to the connected clients with
+//! Server-Sent Events. Unlike the [domain events](super::domain_events),
+//! live events are not persisted: clients only receive the events published
+//! while they are connected, and slow clients may miss some of them.
+//!
+//! Everybody receives the public events (new torrents in the public listing).
+//! Moderation events are only sent to moderators.
+use std::sync::Arc;
+
+use serde::Serialize;
+use tokio::sync::broadcast;
+use tokio::sync::broadcast::error::RecvError;
+use tracing::debug;
+
+use super::authorization::{self, ACTION};
+use crate::models::domain_event::TorrentUploaded;
+use crate::models::report::ReportId;
+use crate::models::torrent::TorrentId;
+use crate::models::user::UserId;
+
+/// Number of events kept for slow subscribers.
+const CHANNEL_CAPACITY: usize = 256;
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum LiveEvent {
+    /// A torrent was added to the public listing.
+    TorrentUploaded {
+        torrent_id: TorrentId,
+        info_hash: String,
+        title: String,
+        category: String,
+        uploader: String,
+    },
+    /// A torrent is waiting for moderation.
+    TorrentPending {
+        torrent_id: TorrentId,
+        info_hash: String,
+        title: String,
+    },
+    TorrentApproved {
+        torrent_id: TorrentId,
+        info_hash: String,
+        title: String,
+    },
+    TorrentRejected {
+        torrent_id: TorrentId,
+        info_hash: String,
+        title: String,
+    },
+    ReportCreated {
+        report_id: ReportId,
+        torrent_id: TorrentId,
+        title: String,
+        reason: String,
+    },
+    ReportResolved {
+        report_id: ReportId,
+        torrent_id: TorrentId,
+        title: String,
+        resolution: String,
+    },
+}
+
+impl LiveEvent {
+    #[must_use]
+    pub fn event_type(&self) -> &'static str {
+        match self {
+            LiveEvent::TorrentUploaded { .. } => "torrent_uploaded",
+            LiveEvent::TorrentPending { .. } => "torrent_pending",
+            LiveEvent::TorrentApproved { .. } => "torrent_approved",
+            LiveEvent::TorrentRejected { .. } => "torrent_rejected",
+            LiveEvent::ReportCreated { .. } => "report_created",
+            LiveEvent::ReportResolved { .. } => "report_resolved",
+        }
+    }
+
+    /// Public events are sent to everybody. The rest only to moderators.
+    #[must_use]
+    pub fn is_public(&self) -> bool {
+        matches!(self, LiveEvent::TorrentUploaded { .. })
+    }
+}
+
+impl From<&TorrentUploaded> for LiveEvent {
+    fn from(event: &TorrentUploaded) -> Self {
+        LiveEvent::TorrentUploaded {
+            torrent_id: event.torrent_id,
+            info_hash: event.info_hash.clone(),
+            title: event.title.clone(),
+            category: event.category.clone(),
+            uploader: event.uploader.clone(),
+        }
+    }
+}
+
+/// The broadcast channel the services publish the live events to.
+pub struct Broadcaster {
+    sender: broadcast::Sender<LiveEvent>,
+}
+
+impl Default for Broadcaster {
+    fn default() -> Self {
+        let (sender, _) = broadcast::channel(CHANNEL_CAPACITY);
+
+        Self { sender }
+    }
+}
+
+impl Broadcaster {
+    /// It sends the event to the connected subscribers, if any.
+    pub fn publish(&self, event: LiveEvent) {
+        // It only fails when there are no subscribers.
+        drop(self.sender.send(event));
+    }
+
+    #[must_use]
+    pub fn subscribe(&self) -> broadcast::Receiver<LiveEvent> {
+        self.sender.subscribe()
+    }
+}
+
+pub struct Service {
+    broadcaster: Arc<Broadcaster>,
+    authorization_service: Arc<authorization::Service>,
+}
+
+impl Service {
+    #[must_use]
+    pub fn new(broadcaster: Arc<Broadcaster>, authorization_service: Arc<authorization::Service>) -> Service {
+        Service {
+            broadcaster,
+            authorization_service,
+        }
+    }
+
+    /// It subscribes to the live events. Moderators also receive the
+    /// moderation events.
+    pub async fn subscribe(&self, maybe_user_id: Option<UserId>) -> Subscription {
+        let moderator = self
+            .authorization_service
+            .authorize(ACTION::ModerateTorrent, maybe_user_id)
+            .await
+            .is_ok();
+
+        Subscription {
+            receiver: self.broadcaster.subscribe(),
+            moderator,
+        }
+    }
+}
+
+pub struct Subscription {
+    receiver: broadcast::Receiver<LiveEvent>,
+    moderator: bool,
+}
+
+impl Subscription {
+    /// It waits for the next event the subscriber can receive. It returns
+    /// `None` when the channel is closed.
+    pub async fn next(&mut self) -> Option<LiveEvent> {
+        loop {
+            match self.receiver.recv().await {
+                Ok(event) if self.moderator || event.is_public() => return Some(event),
+                Ok(_) => {}
+                Err(RecvError::Lagged(skipped)) => debug!("Live events subscriber lagged behind: {skipped} events skipped"),
+                Err(RecvError::Closed) => return None,
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Broadcaster, LiveEvent, Subscription};
+
+    fn pending_torrent() -> LiveEvent {
+        LiveEvent::TorrentPending {
+            torrent_id: 1,
+            info_hash: "5452869be36f9f3350ccee6b4544e7e76caaadab".to_string(),
+            title: "MandelbrotSet".to_string(),
+        }
+    }
+
+    fn uploaded_torrent() -> LiveEvent {
+        LiveEvent::TorrentUploaded {
+            torrent_id: 2,
+            info_hash: "6452869be36f9f3350ccee6b4544e7e76caaadab".to_string(),
+            title: "JuliaSet".to_string(),
+            category: "software".to_string(),
+            uploader: "indexadmin".to_string(),
+        }
+    }
+
+    #[tokio::test]
+    async fn it_should_only_send_public_events_to_non_moderators() {
+        let broadcaster = Broadcaster::default();
+
+        let mut subscription = Subscription {
+            receiver: broadcaster.subscribe(),
+            moderator: false,
+        };
+
+        broadcaster.publish(pending_torrent());
+        broadcaster.publish(uploaded_torrent());
+
+        assert_eq!(subscription.next().await, Some(uploaded_torrent()));
+    }
+
+    #[tokio::test]
+    async fn it_should_send_moderation_events_to_moderators() {
+        let broadcaster = Broadcaster::default();
+
+        let mut subscription = Subscription {
+            receiver: broadcaster.subscribe(),
+            moderator: true,
+        };
+
+        broadcaster.publish(pending_torrent());
+
+        assert_eq!(subscription.next().await, Some(pending_torrent()));
+    }
+
+    #[test]
+    fn it_should_serialize_the_event_type_as_a_field() {
+        let json = serde_json::to_value(pending_torrent()).unwrap();
+
+        assert_eq!(json["type"], "torrent_pending");
+        assert_eq!(json["title"], "MandelbrotSet");
+    }
+}
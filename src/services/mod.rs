@@ -1,12 +1,49 @@
 //! App services.
 pub mod about;
+pub mod announce_key;
+pub mod antispam;
+pub mod api_usage;
+pub mod audit;
 pub mod authentication;
 pub mod authorization;
+pub mod bookmark;
+pub mod bulk_import;
+pub mod bulk_mail;
 pub mod category;
+pub mod category_field;
+pub mod collection;
+pub mod comment;
+pub mod domain_events;
+pub mod download_statistics;
+pub mod enrichment;
 pub mod hasher;
+pub mod health;
+pub mod image_upload;
+pub mod inactivity;
+pub mod listing_snapshot;
+pub mod live_events;
 pub mod proxy;
+pub mod ratio;
+pub mod report;
+pub mod reputation;
+pub mod retention;
+pub mod saved_search;
+pub mod search;
+pub mod seeding_rewards;
+pub mod session;
 pub mod settings;
+pub mod signed_upload;
+pub mod statistics_importer;
+pub mod subscription;
+pub mod sync;
 pub mod tag;
+#[cfg(test)]
+pub mod testing;
 pub mod torrent;
 pub mod torrent_file;
+pub mod torrent_rules;
+pub mod torrent_structure;
+pub mod trending_tags;
+pub mod upload_policy;
 pub mod user;
+pub mod widget;
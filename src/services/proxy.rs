@@ -6,12 +6,17 @@
 //! Sample URL:
 //!
 //! <http://0.0.0.0:3001/v1/proxy/image/https%3A%2F%2Fupload.wikimedia.org%2Fwikipedia%2Fcommons%2Fthumb%2F2%2F21%2FMandel_zoom_00_mandelbrot_set.jpg%2F1280px-Mandel_zoom_00_mandelbrot_set.jpg>
+//!
+//! Resized and converted variants of the image can be requested with the
+//! `width` and `format` query parameters. See
+//! [`thumbnail`](crate::cache::image::thumbnail).
 use std::sync::Arc;
 
 use bytes::Bytes;
 
 use super::authorization::{self, ACTION};
 use crate::cache::image::manager::{Error, ImageCacheService};
+use crate::cache::image::thumbnail::VariantRequest;
 use crate::models::user::UserId;
 
 pub struct Service {
@@ -50,4 +55,28 @@ impl Service {
 
         self.image_cache_service.get_image_by_url(url, user_id).await
     }
+
+    /// It gets a resized or converted variant of the image by URL. It
+    /// returns the image and its content type.
+    ///
+    /// # Errors
+    ///
+    /// It returns the same errors as [`get_image_by_url`](Self::get_image_by_url).
+    pub async fn get_image_variant(
+        &self,
+        url: &str,
+        maybe_user_id: Option<UserId>,
+        request: &VariantRequest,
+    ) -> Result<(Bytes, &'static str), Error> {
+        let Some(user_id) = maybe_user_id else {
+            return Err(Error::Unauthenticated);
+        };
+
+        self.authorization_service
+            .authorize(ACTION::GetImageByUrl, maybe_user_id)
+            .await
+            .map_err(|_| Error::Unauthenticated)?;
+
+        self.image_cache_service.get_image_variant(url, user_id, request).await
+    }
 }
@@ -0,0 +1,299 @@
+//! Ratio accounting in private mode.
+//!
+//! In private mode, every user announces with their own tracker key, so the
+//! tracker knows the bytes each user uploads and downloads. The index
+//! periodically imports the counters of every key from the tracker API and
+//! keeps the totals of each user:
+//!
+//! ```text
+//! ratio = uploaded / downloaded
+//! ```
+//!
+//! The ratio is shown in the user profiles. When a minimum ratio is set in
+//! the `[ratio]` section, users below it can't download torrent files, once
+//! they have downloaded enough bytes to have a meaningful ratio.
+//!
+//! The tracker has to report the counters of each key since it was issued in
+//! the `GET /api/v1/keys/stats` endpoint:
+//!
+//! ```json
+//! [
+//!   {
+//!     "key": "mCGfCr8nvixxA0h8B4iz0sT8V3FIQLi7",
+//!     "uploaded": 1073741824,
+//!     "downloaded": 536870912
+//!   }
+//! ]
+//! ```
+//!
+//! Refer to [`Ratio`](crate::config::Ratio) for the configuration.
+use std::sync::Arc;
+
+use crate::config::{Configuration, Ratio};
+use crate::databases::database::{Database, Error};
+use crate::errors::ServiceError;
+use crate::models::transfer_stats::{TransferStats, UserTransferStats};
+use crate::models::user::UserId;
+use crate::tracker;
+
+pub struct Service {
+    configuration: Arc<Configuration>,
+    tracker_service: Arc<tracker::service::Service>,
+    transfer_stats_repository: Arc<DbTransferStatsRepository>,
+}
+
+impl Service {
+    #[must_use]
+    pub fn new(
+        configuration: Arc<Configuration>,
+        tracker_service: Arc<tracker::service::Service>,
+        transfer_stats_repository: Arc<DbTransferStatsRepository>,
+    ) -> Service {
+        Service {
+            configuration,
+            tracker_service,
+            transfer_stats_repository,
+        }
+    }
+
+    /// It imports the bytes uploaded and downloaded with each key from the
+    /// tracker. It returns the number of keys imported. Keys not issued by
+    /// the index are ignored.
+    ///
+    /// # Errors
+    ///
+    /// It returns an error if:
+    ///
+    /// * The tracker API request fails.
+    /// * There is a database error.
+    pub async fn import_transfer_stats(&self) -> Result<u64, ServiceError> {
+        if self.ratio_settings().await.is_none() {
+            return Ok(0);
+        }
+
+        let keys_transfer_stats = self.tracker_service.get_keys_transfer_stats().await?;
+
+        let mut imported = 0;
+
+        for key_transfer_stats in keys_transfer_stats {
+            if self
+                .transfer_stats_repository
+                .import(
+                    &key_transfer_stats.key,
+                    key_transfer_stats.uploaded,
+                    key_transfer_stats.downloaded,
+                )
+                .await?
+            {
+                imported += 1;
+            }
+        }
+
+        Ok(imported)
+    }
+
+    /// It returns the transfer stats of the user, or `None` when the ratio
+    /// accounting is not enabled.
+    ///
+    /// # Errors
+    ///
+    /// It returns an error if there is a database error.
+    pub async fn get_transfer_stats(&self, user_id: UserId) -> Result<Option<UserTransferStats>, ServiceError> {
+        if self.ratio_settings().await.is_none() {
+            return Ok(None);
+        }
+
+        let stats = self.transfer_stats_repository.get(user_id).await?;
+
+        Ok(Some(stats.into()))
+    }
+
+    /// It checks the user can download torrent files with their current
+    /// ratio.
+    ///
+    /// # Errors
+    ///
+    /// It returns an error if:
+    ///
+    /// * The user's ratio is below the minimum.
+    /// * There is a database error.
+    pub async fn authorize_download(&self, user_id: UserId) -> Result<(), ServiceError> {
+        let Some(ratio_settings) = self.ratio_settings().await else {
+            return Ok(());
+        };
+
+        if ratio_settings.min_download_ratio.is_none() {
+            return Ok(());
+        }
+
+        let stats = self.transfer_stats_repository.get(user_id).await?;
+
+        if !may_download(&stats, &ratio_settings) {
+            return Err(ServiceError::RatioTooLow);
+        }
+
+        Ok(())
+    }
+
+    /// The `[ratio]` settings, when the ratio accounting is enabled. It's
+    /// only enabled in private mode.
+    async fn ratio_settings(&self) -> Option<Ratio> {
+        let settings = self.configuration.settings.read().await;
+
+        if !settings.tracker.private {
+            return None;
+        }
+
+        settings.ratio.clone()
+    }
+}
+
+/// It checks the stats against the minimum ratio. Users who have not
+/// downloaded the minimum number of bytes can always download.
+fn may_download(stats: &TransferStats, ratio_settings: &Ratio) -> bool {
+    let Some(min_download_ratio) = ratio_settings.min_download_ratio else {
+        return true;
+    };
+
+    if stats.downloaded < ratio_settings.min_downloaded_bytes {
+        return true;
+    }
+
+    stats.ratio().map_or(true, |ratio| ratio >= min_download_ratio)
+}
+
+pub struct DbTransferStatsRepository {
+    database: Arc<Box<dyn Database>>,
+}
+
+impl DbTransferStatsRepository {
+    #[must_use]
+    pub fn new(database: Arc<Box<dyn Database>>) -> Self {
+        Self { database }
+    }
+
+    /// It saves the bytes transferred with a tracker key. It returns `false`
+    /// when the key was not issued by the index.
+    ///
+    /// # Errors
+    ///
+    /// It returns an error if there is a database error.
+    pub async fn import(&self, tracker_key: &str, uploaded: u64, downloaded: u64) -> Result<bool, Error> {
+        self.database
+            .import_key_transfer_stats(tracker_key, uploaded, downloaded)
+            .await
+    }
+
+    /// It returns the bytes transferred with all the keys of the user.
+    ///
+    /// # Errors
+    ///
+    /// It returns an error if there is a database error.
+    pub async fn get(&self, user_id: UserId) -> Result<TransferStats, Error> {
+        self.database.get_user_transfer_stats(user_id).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use super::may_download;
+    use crate::config::{Configuration, Ratio, Settings};
+    use crate::databases::database::Database;
+    use crate::databases::testing::{add_admin, add_category, add_torrent, add_user, new_sqlite_database};
+    use crate::errors::ServiceError;
+    use crate::models::torrent::TorrentStatus;
+    use crate::models::tracker_key::TrackerKey;
+    use crate::models::transfer_stats::TransferStats;
+    use crate::models::user::UserId;
+    use crate::services::testing::services;
+
+    const GIB: u64 = 1_073_741_824;
+
+    fn ratio_settings(min_download_ratio: Option<f64>) -> Ratio {
+        Ratio {
+            min_download_ratio,
+            ..Ratio::default()
+        }
+    }
+
+    fn stats(uploaded: u64, downloaded: u64) -> TransferStats {
+        TransferStats { uploaded, downloaded }
+    }
+
+    #[test]
+    fn it_should_compute_the_ratio_of_the_transferred_bytes() {
+        assert_eq!(stats(3 * GIB, 2 * GIB).ratio(), Some(1.5));
+        assert_eq!(stats(0, GIB).ratio(), Some(0.0));
+        assert_eq!(stats(GIB, 0).ratio(), None);
+    }
+
+    #[test]
+    fn it_should_block_the_downloads_below_the_minimum_ratio() {
+        let ratio_settings = ratio_settings(Some(0.5));
+
+        assert!(may_download(&stats(5 * GIB, 10 * GIB), &ratio_settings));
+        assert!(!may_download(&stats(4 * GIB, 10 * GIB), &ratio_settings));
+    }
+
+    #[test]
+    fn it_should_allow_the_downloads_until_the_user_has_downloaded_the_minimum_bytes() {
+        let ratio_settings = ratio_settings(Some(0.5));
+
+        assert!(may_download(&stats(0, 4 * GIB), &ratio_settings));
+        assert!(!may_download(&stats(0, 5 * GIB), &ratio_settings));
+    }
+
+    #[test]
+    fn it_should_allow_all_the_downloads_without_a_minimum_ratio() {
+        assert!(may_download(&stats(0, 100 * GIB), &ratio_settings(None)));
+    }
+
+    async fn add_transfer_stats(database: &dyn Database, user_id: UserId, uploaded: u64, downloaded: u64) {
+        let key = format!("key-of-user-{user_id}");
+
+        database
+            .add_tracker_key(
+                user_id,
+                &TrackerKey {
+                    key: key.clone(),
+                    valid_until: i64::MAX,
+                },
+            )
+            .await
+            .unwrap();
+
+        assert!(database.import_key_transfer_stats(&key, uploaded, downloaded).await.unwrap());
+    }
+
+    #[tokio::test]
+    async fn it_should_refuse_the_torrent_downloads_of_the_users_below_the_minimum_ratio() {
+        let mut settings = Settings::default();
+        settings.tracker.private = true;
+        settings.ratio = Some(Ratio {
+            min_download_ratio: Some(0.5),
+            min_downloaded_bytes: GIB,
+            ..Ratio::default()
+        });
+
+        let (_temp_dir, database) = new_sqlite_database().await;
+        let services = services(Arc::new(Configuration::new(settings)), database.clone()).await;
+
+        let admin_id = add_admin(database.as_ref(), "admin").await;
+        let category_id = add_category(database.as_ref(), "ratio").await;
+        let (_torrent_id, info_hash) = add_torrent(database.as_ref(), admin_id, category_id, &TorrentStatus::Approved).await;
+
+        let leecher_id = add_user(database.as_ref(), "leecher").await;
+        add_transfer_stats(database.as_ref(), leecher_id, GIB, 4 * GIB).await;
+
+        let seeder_id = add_user(database.as_ref(), "seeder").await;
+        add_transfer_stats(database.as_ref(), seeder_id, 2 * GIB, 4 * GIB).await;
+
+        assert!(matches!(
+            services.torrent_index.get_torrent(&info_hash, Some(leecher_id)).await,
+            Err(ServiceError::RatioTooLow)
+        ));
+        assert!(services.ratio_service.authorize_download(seeder_id).await.is_ok());
+    }
+}
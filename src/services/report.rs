@@ -0,0 +1,300 @@
+//! Torrent report service.
+//!
+//! Users can report torrents that infringe copyright, contain malware, etc.
+//! Moderators review the open reports and resolve them, optionally hiding
+//! the torrent or banning the uploader.
+use std::sync::Arc;
+
+use bittorrent_primitives::info_hash::InfoHash;
+use serde_derive::Deserialize;
+use utoipa::IntoParams;
+
+use super::audit;
+use super::authorization::{self, ACTION};
+use super::live_events::{Broadcaster, LiveEvent};
+use super::torrent::{self, DbTorrentListingGenerator};
+use super::user::BanService;
+use crate::databases::database::{Database, Error};
+use crate::errors::ServiceError;
+use crate::models::audit::AuditAction;
+use crate::models::report::{Report, ReportAction, ReportId, ReportReason};
+use crate::models::response::ReportsResponse;
+use crate::models::torrent::TorrentId;
+use crate::models::user::UserId;
+
+/// Default number of reports per page.
+const DEFAULT_PAGE_SIZE: u8 = 20;
+
+/// Maximum number of reports per page.
+const MAX_PAGE_SIZE: u8 = 100;
+
+/// Maximum number of characters in the report details.
+const MAX_DETAILS_LENGTH: usize = 2_000;
+
+/// Moderator request to get a page of reports.
+#[derive(Debug, Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
+pub struct ListingRequest {
+    pub page_size: Option<u8>,
+    pub page: Option<u32>,
+    /// Only include resolved (`true`) or open (`false`) reports.
+    pub resolved: Option<bool>,
+}
+
+pub struct Service {
+    report_repository: Arc<DbReportRepository>,
+    torrent_listing_generator: Arc<DbTorrentListingGenerator>,
+    torrent_service: Arc<torrent::Index>,
+    ban_service: Arc<BanService>,
+    authorization_service: Arc<authorization::Service>,
+    live_events: Arc<Broadcaster>,
+    audit_service: Arc<audit::Service>,
+}
+
+impl Service {
+    #[must_use]
+    pub fn new(
+        report_repository: Arc<DbReportRepository>,
+        torrent_listing_generator: Arc<DbTorrentListingGenerator>,
+        torrent_service: Arc<torrent::Index>,
+        ban_service: Arc<BanService>,
+        authorization_service: Arc<authorization::Service>,
+        live_events: Arc<Broadcaster>,
+        audit_service: Arc<audit::Service>,
+    ) -> Service {
+        Service {
+            report_repository,
+            torrent_listing_generator,
+            torrent_service,
+            ban_service,
+            authorization_service,
+            live_events,
+            audit_service,
+        }
+    }
+
+    /// Reports a torrent.
+    ///
+    /// # Errors
+    ///
+    /// It returns an error if:
+    ///
+    /// * The user does not have the required permissions.
+    /// * The report details are too long.
+    /// * The torrent does not exist.
+    /// * There is a database error.
+    pub async fn report_torrent(
+        &self,
+        info_hash: &InfoHash,
+        reason: &ReportReason,
+        details: Option<&str>,
+        maybe_user_id: Option<UserId>,
+    ) -> Result<Report, ServiceError> {
+        self.authorization_service
+            .authorize(ACTION::ReportTorrent, maybe_user_id)
+            .await?;
+
+        let Some(user_id) = maybe_user_id else {
+            return Err(ServiceError::UnauthorizedActionForGuests);
+        };
+
+        let details = validate_details(details)?;
+
+        let torrent_listing = self.torrent_listing_generator.one_torrent_by_info_hash(info_hash).await?;
+
+        let report_id = self
+            .report_repository
+            .add(torrent_listing.torrent_id, user_id, reason, details)
+            .await?;
+
+        let report = self.report_repository.get(report_id).await?;
+
+        self.live_events.publish(LiveEvent::ReportCreated {
+            report_id,
+            torrent_id: report.torrent_id,
+            title: report.title.clone(),
+            reason: report.reason.clone(),
+        });
+
+        Ok(report)
+    }
+
+    /// Returns a page of reports.
+    ///
+    /// # Errors
+    ///
+    /// It returns an error if:
+    ///
+    /// * The user does not have the required permissions.
+    /// * There is a database error.
+    pub async fn get_reports(
+        &self,
+        request: &ListingRequest,
+        maybe_user_id: Option<UserId>,
+    ) -> Result<ReportsResponse, ServiceError> {
+        self.authorization_service
+            .authorize(ACTION::ModerateReports, maybe_user_id)
+            .await?;
+
+        let page_size = request.page_size.unwrap_or(DEFAULT_PAGE_SIZE).min(MAX_PAGE_SIZE);
+        let offset = u64::from(request.page.unwrap_or(0)) * u64::from(page_size);
+
+        Ok(self.report_repository.get_page(request.resolved, offset, page_size).await?)
+    }
+
+    /// Resolves a report taking the given action.
+    ///
+    /// # Errors
+    ///
+    /// It returns an error if:
+    ///
+    /// * The user does not have the required permissions.
+    /// * The report does not exist or it's already resolved.
+    /// * The action can't be taken.
+    /// * There is a database error.
+    pub async fn resolve_report(
+        &self,
+        report_id: ReportId,
+        action: &ReportAction,
+        maybe_user_id: Option<UserId>,
+    ) -> Result<Report, ServiceError> {
+        self.authorization_service
+            .authorize(ACTION::ModerateReports, maybe_user_id)
+            .await?;
+
+        let Some(user_id) = maybe_user_id else {
+            return Err(ServiceError::UnauthorizedActionForGuests);
+        };
+
+        let report = self.report_repository.get(report_id).await?;
+
+        if report.resolved {
+            return Err(ServiceError::ReportAlreadyResolved);
+        }
+
+        match action {
+            ReportAction::Dismiss => {}
+            ReportAction::HideTorrent => {
+                self.torrent_service.reject_torrent(report.torrent_id, maybe_user_id).await?;
+            }
+            ReportAction::BanUploader => {
+                self.ban_service.ban_user(&report.uploader, maybe_user_id).await?;
+            }
+        }
+
+        self.report_repository.resolve(report_id, user_id, action).await?;
+
+        self.audit_service
+            .record(
+                maybe_user_id,
+                AuditAction::ReportResolve,
+                &report_id.to_string(),
+                Some(&action.to_string()),
+            )
+            .await;
+
+        let report = self.report_repository.get(report_id).await?;
+
+        self.live_events.publish(LiveEvent::ReportResolved {
+            report_id,
+            torrent_id: report.torrent_id,
+            title: report.title.clone(),
+            resolution: report.resolution.clone().unwrap_or_default(),
+        });
+
+        Ok(report)
+    }
+}
+
+/// It returns the trimmed report details, or `None` if they are empty.
+fn validate_details(details: Option<&str>) -> Result<Option<&str>, ServiceError> {
+    let Some(details) = details.map(str::trim).filter(|details| !details.is_empty()) else {
+        return Ok(None);
+    };
+
+    if details.chars().count() > MAX_DETAILS_LENGTH {
+        return Err(ServiceError::InvalidReportDetailsLength);
+    }
+
+    Ok(Some(details))
+}
+
+pub struct DbReportRepository {
+    database: Arc<Box<dyn Database>>,
+}
+
+impl DbReportRepository {
+    #[must_use]
+    pub fn new(database: Arc<Box<dyn Database>>) -> Self {
+        Self { database }
+    }
+
+    /// It adds a new report and returns its ID.
+    ///
+    /// # Errors
+    ///
+    /// It returns an error if there is a database error.
+    pub async fn add(
+        &self,
+        torrent_id: TorrentId,
+        reporter_id: UserId,
+        reason: &ReportReason,
+        details: Option<&str>,
+    ) -> Result<ReportId, Error> {
+        self.database.insert_report(torrent_id, reporter_id, reason, details).await
+    }
+
+    /// It returns a report.
+    ///
+    /// # Errors
+    ///
+    /// It returns an error if the report does not exist or there is a
+    /// database error.
+    pub async fn get(&self, report_id: ReportId) -> Result<Report, Error> {
+        self.database.get_report(report_id).await
+    }
+
+    /// It returns a page of reports.
+    ///
+    /// # Errors
+    ///
+    /// It returns an error if there is a database error.
+    pub async fn get_page(&self, resolved: Option<bool>, offset: u64, page_size: u8) -> Result<ReportsResponse, Error> {
+        self.database.get_reports_paginated(resolved, offset, page_size).await
+    }
+
+    /// It marks the report as resolved.
+    ///
+    /// # Errors
+    ///
+    /// It returns an error if the report does not exist or there is a
+    /// database error.
+    pub async fn resolve(&self, report_id: ReportId, resolved_by: UserId, resolution: &ReportAction) -> Result<(), Error> {
+        self.database.resolve_report(report_id, resolved_by, resolution).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{validate_details, MAX_DETAILS_LENGTH};
+    use crate::errors::ServiceError;
+
+    #[test]
+    fn it_should_ignore_empty_report_details() {
+        assert_eq!(validate_details(Some("  \n ")), Ok(None));
+        assert_eq!(validate_details(None), Ok(None));
+    }
+
+    #[test]
+    fn it_should_trim_the_report_details() {
+        assert_eq!(validate_details(Some(" Fake file \n")), Ok(Some("Fake file")));
+    }
+
+    #[test]
+    fn it_should_reject_too_long_report_details() {
+        assert_eq!(
+            validate_details(Some(&"a".repeat(MAX_DETAILS_LENGTH + 1))),
+            Err(ServiceError::InvalidReportDetailsLength)
+        );
+    }
+}
@@ -0,0 +1,295 @@
+//! Reputation of the uploaders.
+//!
+//! The index keeps track of the uploads of each user and the reports against
+//! their torrents. Users meeting all the thresholds in the `[reputation]`
+//! section are trusted uploaders:
+//!
+//! - Their uploads skip the moderation queue, unless the category requires
+//!   co-signing or the upload looks like spam.
+//! - They have a badge in the torrent details and their profile.
+//!
+//! ```text
+//! approved uploads >= min_approved_uploads
+//! approved / (approved + rejected) >= min_approved_ratio
+//! upheld reports <= max_upheld_reports
+//! ```
+//!
+//! Administrators can trust or distrust any user regardless of the
+//! thresholds, and remove the override so the thresholds apply again.
+//!
+//! Refer to [`Reputation`](crate::config::Reputation) for the configuration.
+use std::sync::Arc;
+
+use super::audit;
+use super::authorization::{self, ACTION};
+use crate::config::{Configuration, Reputation};
+use crate::databases::database::{Database, Error};
+use crate::errors::ServiceError;
+use crate::models::audit::AuditAction;
+use crate::models::reputation::{ReputationStats, UserReputation};
+use crate::models::user::UserId;
+
+pub struct Service {
+    configuration: Arc<Configuration>,
+    reputation_repository: Arc<DbReputationRepository>,
+    authorization_service: Arc<authorization::Service>,
+    audit_service: Arc<audit::Service>,
+}
+
+impl Service {
+    #[must_use]
+    pub fn new(
+        configuration: Arc<Configuration>,
+        reputation_repository: Arc<DbReputationRepository>,
+        authorization_service: Arc<authorization::Service>,
+        audit_service: Arc<audit::Service>,
+    ) -> Service {
+        Service {
+            configuration,
+            reputation_repository,
+            authorization_service,
+            audit_service,
+        }
+    }
+
+    /// It returns whether the user is a trusted uploader.
+    ///
+    /// # Errors
+    ///
+    /// It returns an error if there is a database error.
+    pub async fn is_trusted(&self, user_id: UserId) -> Result<bool, ServiceError> {
+        let (_stats, trusted, _overridden) = self.reputation_of(user_id).await?;
+
+        Ok(trusted)
+    }
+
+    /// It returns whether the user with the username is a trusted uploader.
+    /// Users that don't exist anymore are not trusted.
+    ///
+    /// # Errors
+    ///
+    /// It returns an error if there is a database error.
+    pub async fn is_trusted_username(&self, username: &str) -> Result<bool, ServiceError> {
+        match self.reputation_repository.get_user_id(username).await {
+            Ok(user_id) => self.is_trusted(user_id).await,
+            Err(Error::UserNotFound) => Ok(false),
+            Err(err) => Err(err.into()),
+        }
+    }
+
+    /// It returns the reputation of a user.
+    ///
+    /// # Errors
+    ///
+    /// It returns an error if:
+    ///
+    /// * The user does not have permissions to manage the reputation.
+    /// * The user does not exist.
+    /// * There is a database error.
+    pub async fn get_reputation(&self, username: &str, maybe_user_id: Option<UserId>) -> Result<UserReputation, ServiceError> {
+        self.authorization_service
+            .authorize(ACTION::ManageReputation, maybe_user_id)
+            .await?;
+
+        let user_id = self.reputation_repository.get_user_id(username).await?;
+
+        let (stats, trusted, overridden) = self.reputation_of(user_id).await?;
+
+        Ok(UserReputation {
+            username: username.to_string(),
+            stats,
+            approved_ratio: stats.approved_ratio(),
+            trusted,
+            overridden,
+        })
+    }
+
+    /// It trusts or distrusts a user regardless of the `[reputation]`
+    /// thresholds.
+    ///
+    /// # Errors
+    ///
+    /// It returns an error if:
+    ///
+    /// * The user does not have permissions to manage the reputation.
+    /// * The user does not exist.
+    /// * There is a database error.
+    pub async fn set_trusted(&self, username: &str, trusted: bool, maybe_user_id: Option<UserId>) -> Result<(), ServiceError> {
+        self.authorization_service
+            .authorize(ACTION::ManageReputation, maybe_user_id)
+            .await?;
+
+        let user_id = self.reputation_repository.get_user_id(username).await?;
+
+        self.reputation_repository.set_override(user_id, trusted).await?;
+
+        self.audit_service
+            .record(
+                maybe_user_id,
+                AuditAction::UserTrustChange,
+                username,
+                Some(&format!("trusted: {trusted}")),
+            )
+            .await;
+
+        Ok(())
+    }
+
+    /// It removes the trusted status set for a user. The `[reputation]`
+    /// thresholds apply again.
+    ///
+    /// # Errors
+    ///
+    /// It returns an error if:
+    ///
+    /// * The user does not have permissions to manage the reputation.
+    /// * The user does not exist.
+    /// * There is a database error.
+    pub async fn remove_override(&self, username: &str, maybe_user_id: Option<UserId>) -> Result<(), ServiceError> {
+        self.authorization_service
+            .authorize(ACTION::ManageReputation, maybe_user_id)
+            .await?;
+
+        let user_id = self.reputation_repository.get_user_id(username).await?;
+
+        self.reputation_repository.remove_override(user_id).await?;
+
+        self.audit_service
+            .record(
+                maybe_user_id,
+                AuditAction::UserTrustChange,
+                username,
+                Some("override removed"),
+            )
+            .await;
+
+        Ok(())
+    }
+
+    /// It returns the stats of the user, whether it's trusted and whether
+    /// the status was set by an administrator.
+    async fn reputation_of(&self, user_id: UserId) -> Result<(ReputationStats, bool, bool), ServiceError> {
+        let stats = self.reputation_repository.get_stats(user_id).await?;
+
+        if let Some(trusted) = self.reputation_repository.get_override(user_id).await? {
+            return Ok((stats, trusted, true));
+        }
+
+        let thresholds = self.configuration.settings.read().await.reputation.clone();
+
+        Ok((stats, meets_thresholds(&stats, thresholds.as_ref()), false))
+    }
+}
+
+/// It checks the stats against the thresholds. Without thresholds, nobody is
+/// trusted.
+fn meets_thresholds(stats: &ReputationStats, thresholds: Option<&Reputation>) -> bool {
+    let Some(thresholds) = thresholds else {
+        return false;
+    };
+
+    stats.approved_uploads >= thresholds.min_approved_uploads
+        && stats.approved_ratio() >= thresholds.min_approved_ratio
+        && stats.upheld_reports <= thresholds.max_upheld_reports
+}
+
+pub struct DbReputationRepository {
+    database: Arc<Box<dyn Database>>,
+}
+
+impl DbReputationRepository {
+    #[must_use]
+    pub fn new(database: Arc<Box<dyn Database>>) -> Self {
+        Self { database }
+    }
+
+    /// It returns the ID of a user.
+    ///
+    /// # Errors
+    ///
+    /// It returns an error if the user does not exist.
+    pub async fn get_user_id(&self, username: &str) -> Result<UserId, Error> {
+        self.database
+            .get_user_profile_from_username(username)
+            .await
+            .map(|user_profile| user_profile.user_id)
+    }
+
+    /// It returns the uploads of the user and the reports against them.
+    ///
+    /// # Errors
+    ///
+    /// It returns an error if there is a database error.
+    pub async fn get_stats(&self, user_id: UserId) -> Result<ReputationStats, Error> {
+        self.database.get_user_reputation_stats(user_id).await
+    }
+
+    /// It returns the trusted status set for the user, if any.
+    ///
+    /// # Errors
+    ///
+    /// It returns an error if there is a database error.
+    pub async fn get_override(&self, user_id: UserId) -> Result<Option<bool>, Error> {
+        self.database.get_user_trusted_override(user_id).await
+    }
+
+    /// It sets the trusted status of the user.
+    ///
+    /// # Errors
+    ///
+    /// It returns an error if there is a database error.
+    pub async fn set_override(&self, user_id: UserId, trusted: bool) -> Result<(), Error> {
+        self.database.upsert_user_trusted_override(user_id, trusted).await
+    }
+
+    /// It removes the trusted status set for the user.
+    ///
+    /// # Errors
+    ///
+    /// It returns an error if there is a database error.
+    pub async fn remove_override(&self, user_id: UserId) -> Result<(), Error> {
+        self.database.delete_user_trusted_override(user_id).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::meets_thresholds;
+    use crate::config::Reputation;
+    use crate::models::reputation::ReputationStats;
+
+    fn stats(approved_uploads: u64, rejected_uploads: u64, upheld_reports: u64) -> ReputationStats {
+        ReputationStats {
+            uploads: approved_uploads + rejected_uploads,
+            approved_uploads,
+            rejected_uploads,
+            reports: upheld_reports,
+            upheld_reports,
+        }
+    }
+
+    #[test]
+    fn it_should_trust_users_meeting_all_the_thresholds() {
+        let thresholds = Reputation::default();
+
+        assert!(meets_thresholds(&stats(10, 0, 0), Some(&thresholds)));
+        assert!(meets_thresholds(&stats(18, 2, 0), Some(&thresholds)));
+    }
+
+    #[test]
+    fn it_should_not_trust_users_missing_any_threshold() {
+        let thresholds = Reputation::default();
+
+        // Not enough approved uploads
+        assert!(!meets_thresholds(&stats(9, 0, 0), Some(&thresholds)));
+        // Too many rejected uploads
+        assert!(!meets_thresholds(&stats(10, 5, 0), Some(&thresholds)));
+        // Upheld reports
+        assert!(!meets_thresholds(&stats(10, 0, 1), Some(&thresholds)));
+    }
+
+    #[test]
+    fn it_should_not_trust_anybody_without_thresholds() {
+        assert!(!meets_thresholds(&stats(1000, 0, 0), None));
+    }
+}
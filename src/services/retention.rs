@@ -0,0 +1,118 @@
+//! Data retention service.
+//!
+//! It deletes the rows older than the retention period configured for their
+//! kind of data in the `[retention]` section. It's run periodically by the
+//! [`gc`](crate::console::cronjobs::gc) cronjob.
+//!
+//! Kind of data | Tables
+//! ---|---
+//! Access logs | `torrust_user_api_usage`
+//! Security events | `torrust_audit_log`
+//! Download history | `torrust_download_client_stats`
+//! Stats | `torrust_torrent_tracker_stats`
+//!
+//! Other tables in [`RETAINED_TABLES`] are only cleaned when they have a
+//! per-table period in the `[retention.tables]` section.
+use std::sync::Arc;
+
+use chrono::{TimeDelta, Utc};
+use tracing::info;
+
+use crate::config::{Configuration, RetentionDataKind};
+use crate::databases::database::{Database, Error};
+use crate::utils::clock::DATETIME_FORMAT;
+
+/// A table with rows that can be deleted after a retention period.
+pub struct RetainedTable {
+    pub table: &'static str,
+    /// The column with the date used to compute the age of the rows.
+    pub date_column: &'static str,
+    pub kind: Option<RetentionDataKind>,
+}
+
+/// The tables the garbage collection job cleans.
+pub const RETAINED_TABLES: &[RetainedTable] = &[
+    RetainedTable {
+        table: "torrust_download_client_stats",
+        date_column: "date",
+        kind: Some(RetentionDataKind::DownloadHistory),
+    },
+    RetainedTable {
+        table: "torrust_torrent_tracker_stats",
+        date_column: "updated_at",
+        kind: Some(RetentionDataKind::Stats),
+    },
+    RetainedTable {
+        table: "torrust_domain_events",
+        date_column: "date_created",
+        kind: None,
+    },
+    RetainedTable {
+        table: "torrust_user_api_usage",
+        date_column: "date",
+        kind: Some(RetentionDataKind::AccessLogs),
+    },
+    RetainedTable {
+        table: "torrust_audit_log",
+        date_column: "date_created",
+        kind: Some(RetentionDataKind::SecurityEvents),
+    },
+];
+
+/// Number of rows deleted from a table.
+#[derive(Debug, PartialEq, Eq)]
+pub struct Purge {
+    pub table: &'static str,
+    pub deleted: u64,
+}
+
+pub struct Service {
+    configuration: Arc<Configuration>,
+    database: Arc<Box<dyn Database>>,
+}
+
+impl Service {
+    #[must_use]
+    pub fn new(configuration: Arc<Configuration>, database: Arc<Box<dyn Database>>) -> Self {
+        Self { configuration, database }
+    }
+
+    /// It deletes the rows older than their retention period.
+    ///
+    /// # Errors
+    ///
+    /// It returns an error if there is a database error.
+    pub async fn collect_garbage(&self) -> Result<Vec<Purge>, Error> {
+        let Some(retention) = self.configuration.settings.read().await.retention.clone() else {
+            return Ok(vec![]);
+        };
+
+        let mut purges = vec![];
+
+        for retained_table in RETAINED_TABLES {
+            let Some(days) = retention.days_for(retained_table.table, retained_table.kind) else {
+                continue;
+            };
+
+            let cutoff = (Utc::now() - TimeDelta::days(i64::from(days)))
+                .format(DATETIME_FORMAT)
+                .to_string();
+
+            let deleted = self
+                .database
+                .delete_rows_older_than(retained_table.table, retained_table.date_column, &cutoff)
+                .await?;
+
+            if deleted > 0 {
+                info!(target: "retention", "Deleted {deleted} rows older than {cutoff} from {}", retained_table.table);
+            }
+
+            purges.push(Purge {
+                table: retained_table.table,
+                deleted,
+            });
+        }
+
+        Ok(purges)
+    }
+}
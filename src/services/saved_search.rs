@@ -0,0 +1,552 @@
+//! Saved searches service.
+//!
+//! Users can save a search: words in the title, a category and tags. The
+//! [`saved_searches`](crate::console::cronjobs::saved_searches) cronjob
+//! matches the new uploads against the saved searches and adds the matching
+//! torrents to the results of each search. Users with a verified email who
+//! enabled the alerts receive one email with the new results of all their
+//! searches.
+//!
+//! Only the torrents uploaded after the search was saved are matched. Use the
+//! torrent listing to search the existing torrents.
+use std::collections::BTreeMap;
+use std::fmt::Write;
+use std::sync::Arc;
+
+use serde_derive::{Deserialize, Serialize};
+use tracing::{debug, error};
+use utoipa::{IntoParams, ToSchema};
+
+use super::authorization::{self, ACTION};
+use super::bulk_mail::DbEmailQueueRepository;
+use super::torrent::{DbTorrentListingGenerator, DbTorrentTagRepository, ListingSpecification};
+use super::user::DbUserProfileRepository;
+use crate::config::Configuration;
+use crate::databases::database::{Database, Error, Sorting};
+use crate::errors::ServiceError;
+use crate::models::domain_event::TorrentUploaded;
+use crate::models::response::TorrentsResponse;
+use crate::models::saved_search::{SavedSearch, SavedSearchId};
+use crate::models::torrent::{ListingFilter, TorrentId, TorrentStatus};
+use crate::models::user::UserId;
+
+/// Maximum number of characters in the name and the query of a saved search.
+const MAX_LENGTH: usize = 100;
+
+/// Maximum number of saved searches per user.
+pub(crate) const MAX_SAVED_SEARCHES_PER_USER: usize = 20;
+
+/// Maximum number of torrents in an alert email.
+const MAX_TORRENTS_PER_ALERT: usize = 50;
+
+/// User request to save a search.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+pub struct AddSavedSearchRequest {
+    pub name: String,
+    /// Words the torrent title must contain.
+    #[serde(default)]
+    pub query: String,
+    pub category: Option<String>,
+    #[serde(default)]
+    pub tags: Vec<String>,
+    /// Whether to send an email with the new matching torrents. Default to
+    /// `true`.
+    pub email_alerts: Option<bool>,
+}
+
+/// User request to get a page of the results of a saved search.
+#[derive(Debug, Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
+pub struct ResultsRequest {
+    pub page_size: Option<u8>,
+    pub page: Option<u32>,
+}
+
+pub struct Service {
+    configuration: Arc<Configuration>,
+    saved_search_repository: Arc<DbSavedSearchRepository>,
+    torrent_tag_repository: Arc<DbTorrentTagRepository>,
+    torrent_listing_generator: Arc<DbTorrentListingGenerator>,
+    user_profile_repository: Arc<DbUserProfileRepository>,
+    email_queue_repository: Arc<DbEmailQueueRepository>,
+    authorization_service: Arc<authorization::Service>,
+}
+
+impl Service {
+    #[must_use]
+    pub fn new(
+        configuration: Arc<Configuration>,
+        saved_search_repository: Arc<DbSavedSearchRepository>,
+        torrent_tag_repository: Arc<DbTorrentTagRepository>,
+        torrent_listing_generator: Arc<DbTorrentListingGenerator>,
+        user_profile_repository: Arc<DbUserProfileRepository>,
+        email_queue_repository: Arc<DbEmailQueueRepository>,
+        authorization_service: Arc<authorization::Service>,
+    ) -> Service {
+        Service {
+            configuration,
+            saved_search_repository,
+            torrent_tag_repository,
+            torrent_listing_generator,
+            user_profile_repository,
+            email_queue_repository,
+            authorization_service,
+        }
+    }
+
+    /// Returns the saved searches of the logged-in user.
+    ///
+    /// # Errors
+    ///
+    /// It returns an error if:
+    ///
+    /// * The user does not have the required permissions.
+    /// * There is a database error.
+    pub async fn get_saved_searches(&self, maybe_user_id: Option<UserId>) -> Result<Vec<SavedSearch>, ServiceError> {
+        let user_id = self.authorize(maybe_user_id).await?;
+
+        Ok(self.saved_search_repository.get_by_user(user_id).await?)
+    }
+
+    /// Saves a search for the logged-in user.
+    ///
+    /// # Errors
+    ///
+    /// It returns an error if:
+    ///
+    /// * The user does not have the required permissions.
+    /// * The search has no name or no criteria.
+    /// * The user has too many saved searches.
+    /// * There is a database error.
+    pub async fn add_saved_search(
+        &self,
+        request: &AddSavedSearchRequest,
+        maybe_user_id: Option<UserId>,
+    ) -> Result<SavedSearch, ServiceError> {
+        let user_id = self.authorize(maybe_user_id).await?;
+
+        let request = normalize(request)?;
+
+        if self.saved_search_repository.get_by_user(user_id).await?.len() >= MAX_SAVED_SEARCHES_PER_USER {
+            return Err(ServiceError::TooManySavedSearches);
+        }
+
+        let saved_search_id = self.saved_search_repository.add(user_id, &request).await?;
+
+        Ok(self.saved_search_repository.get(saved_search_id).await?)
+    }
+
+    /// Deletes a saved search of the logged-in user.
+    ///
+    /// # Errors
+    ///
+    /// It returns an error if:
+    ///
+    /// * The user does not have the required permissions.
+    /// * The saved search does not exist or it belongs to another user.
+    /// * There is a database error.
+    pub async fn delete_saved_search(
+        &self,
+        saved_search_id: SavedSearchId,
+        maybe_user_id: Option<UserId>,
+    ) -> Result<SavedSearchId, ServiceError> {
+        self.owned_saved_search(saved_search_id, maybe_user_id).await?;
+
+        self.saved_search_repository.delete(saved_search_id).await?;
+
+        Ok(saved_search_id)
+    }
+
+    /// Returns a page of the results of a saved search of the logged-in
+    /// user, the newest match first. Only approved torrents are included.
+    ///
+    /// # Errors
+    ///
+    /// It returns an error if:
+    ///
+    /// * The user does not have the required permissions.
+    /// * The saved search does not exist or it belongs to another user.
+    /// * There is a database error.
+    pub async fn get_results(
+        &self,
+        saved_search_id: SavedSearchId,
+        request: &ResultsRequest,
+        maybe_user_id: Option<UserId>,
+    ) -> Result<TorrentsResponse, ServiceError> {
+        self.owned_saved_search(saved_search_id, maybe_user_id).await?;
+
+        let settings = self.configuration.settings.read().await;
+        let default_torrent_page_size = settings.api.default_torrent_page_size;
+        let max_torrent_page_size = settings.api.max_torrent_page_size;
+        drop(settings);
+
+        let page_size = request
+            .page_size
+            .unwrap_or(default_torrent_page_size)
+            .min(max_torrent_page_size);
+        let offset = u64::from(request.page.unwrap_or(0)) * u64::from(page_size);
+
+        let torrent_ids = self.saved_search_repository.get_result_ids(saved_search_id).await?;
+
+        let specification = ListingSpecification {
+            search: None,
+            categories: None,
+            tags: None,
+            torrent_ids: Some(torrent_ids),
+            status: TorrentStatus::Approved,
+            filter: ListingFilter::default(),
+            sort: Sorting::Relevance,
+            then_sort: None,
+            offset,
+            page_size,
+        };
+
+        Ok(self.torrent_listing_generator.generate_listing(&specification).await?)
+    }
+
+    /// It matches the new torrents against the saved searches, adds them to
+    /// the results and queues the alert emails. It returns the number of
+    /// matches.
+    ///
+    /// Errors getting the tags of a torrent or queueing an email are only
+    /// logged.
+    ///
+    /// # Errors
+    ///
+    /// It returns an error if the saved searches can't be loaded or the
+    /// results can't be saved.
+    pub async fn match_new_torrents(&self, torrents: &[TorrentUploaded]) -> Result<usize, Error> {
+        if torrents.is_empty() {
+            return Ok(0);
+        }
+
+        let saved_searches = self.saved_search_repository.get_all().await?;
+
+        if saved_searches.is_empty() {
+            return Ok(0);
+        }
+
+        let mut matches = 0;
+        let mut alerts: BTreeMap<String, Vec<(&SavedSearch, &TorrentUploaded)>> = BTreeMap::new();
+
+        for torrent in torrents {
+            let tags: Vec<String> = match self.torrent_tag_repository.get_tags_for_torrent(&torrent.torrent_id).await {
+                Ok(tags) => tags.into_iter().map(|tag| tag.name).collect(),
+                Err(e) => {
+                    error!(target: "saved_searches", "Failed to get the tags for torrent #{}: {e:?}", torrent.torrent_id);
+                    Vec::new()
+                }
+            };
+
+            for saved_search in saved_searches
+                .iter()
+                .filter(|saved_search| is_match(saved_search, torrent, &tags))
+            {
+                self.saved_search_repository
+                    .add_result(saved_search.saved_search_id, torrent.torrent_id)
+                    .await?;
+
+                matches += 1;
+
+                if saved_search.email_alerts {
+                    alerts
+                        .entry(saved_search.username.clone())
+                        .or_default()
+                        .push((saved_search, torrent));
+                }
+            }
+        }
+
+        let site_name = self.configuration.settings.read().await.website.name.clone();
+
+        for (username, results) in alerts {
+            let user_profile = match self.user_profile_repository.get_user_profile_from_username(&username).await {
+                Ok(user_profile) => user_profile,
+                Err(e) => {
+                    error!(target: "saved_searches", "Failed to get the profile for user {username}: {e:?}");
+                    continue;
+                }
+            };
+
+            if user_profile.email.is_empty() || !user_profile.email_verified {
+                continue;
+            }
+
+            debug!(target: "saved_searches", "Sending alert with {} results to user {username} ...", results.len());
+
+            if let Err(e) = self
+                .email_queue_repository
+                .enqueue(
+                    &user_profile.email,
+                    &format!("{site_name} - New torrents for your saved searches"),
+                    &alert_body(&username, &results),
+                )
+                .await
+            {
+                error!(target: "saved_searches", "Failed to queue the alert for user {username}: {e:?}");
+            }
+        }
+
+        Ok(matches)
+    }
+
+    async fn authorize(&self, maybe_user_id: Option<UserId>) -> Result<UserId, ServiceError> {
+        self.authorization_service
+            .authorize(ACTION::ManageSavedSearches, maybe_user_id)
+            .await?;
+
+        maybe_user_id.ok_or(ServiceError::UnauthorizedActionForGuests)
+    }
+
+    /// It returns the saved search if it belongs to the logged-in user.
+    async fn owned_saved_search(
+        &self,
+        saved_search_id: SavedSearchId,
+        maybe_user_id: Option<UserId>,
+    ) -> Result<SavedSearch, ServiceError> {
+        let user_id = self.authorize(maybe_user_id).await?;
+
+        let saved_search = self.saved_search_repository.get(saved_search_id).await?;
+
+        if saved_search.user_id != user_id {
+            return Err(ServiceError::SavedSearchNotFound);
+        }
+
+        Ok(saved_search)
+    }
+}
+
+/// It trims the request values and checks the search has a name and at
+/// least one criterion.
+pub(crate) fn normalize(request: &AddSavedSearchRequest) -> Result<AddSavedSearchRequest, ServiceError> {
+    let name = request.name.trim().to_string();
+    let query = request.query.split_whitespace().collect::<Vec<_>>().join(" ");
+
+    let category = request
+        .category
+        .as_deref()
+        .map(str::trim)
+        .filter(|category| !category.is_empty())
+        .map(ToString::to_string);
+
+    let mut tags: Vec<String> = request
+        .tags
+        .iter()
+        .map(|tag| tag.trim().to_string())
+        .filter(|tag| !tag.is_empty())
+        .collect();
+
+    tags.sort();
+    tags.dedup();
+
+    if name.is_empty()
+        || name.chars().count() > MAX_LENGTH
+        || query.chars().count() > MAX_LENGTH
+        || tags.iter().any(|tag| tag.contains(','))
+        || (query.is_empty() && category.is_none() && tags.is_empty())
+    {
+        return Err(ServiceError::InvalidSavedSearch);
+    }
+
+    Ok(AddSavedSearchRequest {
+        name,
+        query,
+        category,
+        tags,
+        email_alerts: Some(request.email_alerts.unwrap_or(true)),
+    })
+}
+
+/// It checks whether a new torrent matches a saved search. The title must
+/// contain all the words in the query, the torrent must be in the category
+/// and it must have all the tags. Comparisons are not case-sensitive.
+fn is_match(saved_search: &SavedSearch, torrent: &TorrentUploaded, tags: &[String]) -> bool {
+    let title = torrent.title.to_lowercase();
+
+    saved_search
+        .query
+        .split_whitespace()
+        .all(|word| title.contains(&word.to_lowercase()))
+        && saved_search
+            .category
+            .as_ref()
+            .map_or(true, |category| category.eq_ignore_ascii_case(&torrent.category))
+        && saved_search
+            .tags
+            .iter()
+            .all(|tag| tags.iter().any(|torrent_tag| torrent_tag.eq_ignore_ascii_case(tag)))
+}
+
+fn alert_body(username: &str, results: &[(&SavedSearch, &TorrentUploaded)]) -> String {
+    let mut body = format!("Hi {username},\n\nThese are the new torrents matching your saved searches:\n\n");
+
+    for (saved_search, torrent) in results.iter().take(MAX_TORRENTS_PER_ALERT) {
+        let _ = writeln!(
+            body,
+            "- [{}] {} uploaded by {} (info-hash: {})",
+            saved_search.name, torrent.title, torrent.uploader, torrent.info_hash
+        );
+    }
+
+    if results.len() > MAX_TORRENTS_PER_ALERT {
+        let _ = writeln!(body, "- ... and {} more", results.len() - MAX_TORRENTS_PER_ALERT);
+    }
+
+    body
+}
+
+pub struct DbSavedSearchRepository {
+    database: Arc<Box<dyn Database>>,
+}
+
+impl DbSavedSearchRepository {
+    #[must_use]
+    pub fn new(database: Arc<Box<dyn Database>>) -> Self {
+        Self { database }
+    }
+
+    /// It adds a new saved search and returns its ID.
+    ///
+    /// # Errors
+    ///
+    /// It returns an error if there is a database error.
+    pub async fn add(&self, user_id: UserId, request: &AddSavedSearchRequest) -> Result<SavedSearchId, Error> {
+        self.database
+            .insert_saved_search(
+                user_id,
+                &request.name,
+                &request.query,
+                request.category.as_deref(),
+                &request.tags,
+                request.email_alerts.unwrap_or(true),
+            )
+            .await
+    }
+
+    /// It returns a saved search.
+    ///
+    /// # Errors
+    ///
+    /// It returns an error if the saved search does not exist or there is a
+    /// database error.
+    pub async fn get(&self, saved_search_id: SavedSearchId) -> Result<SavedSearch, Error> {
+        self.database.get_saved_search(saved_search_id).await
+    }
+
+    /// It returns the saved searches of a user.
+    ///
+    /// # Errors
+    ///
+    /// It returns an error if there is a database error.
+    pub async fn get_by_user(&self, user_id: UserId) -> Result<Vec<SavedSearch>, Error> {
+        self.database.get_user_saved_searches(user_id).await
+    }
+
+    /// It returns the saved searches of all users.
+    ///
+    /// # Errors
+    ///
+    /// It returns an error if there is a database error.
+    pub async fn get_all(&self) -> Result<Vec<SavedSearch>, Error> {
+        self.database.get_all_saved_searches().await
+    }
+
+    /// It deletes the saved search and its results.
+    ///
+    /// # Errors
+    ///
+    /// It returns an error if the saved search does not exist or there is a
+    /// database error.
+    pub async fn delete(&self, saved_search_id: SavedSearchId) -> Result<(), Error> {
+        self.database.delete_saved_search(saved_search_id).await
+    }
+
+    /// It adds a torrent to the results of the saved search.
+    ///
+    /// # Errors
+    ///
+    /// It returns an error if there is a database error.
+    pub async fn add_result(&self, saved_search_id: SavedSearchId, torrent_id: TorrentId) -> Result<(), Error> {
+        self.database.insert_saved_search_result(saved_search_id, torrent_id).await
+    }
+
+    /// It returns the torrents in the results of the saved search, the newest
+    /// match first.
+    ///
+    /// # Errors
+    ///
+    /// It returns an error if there is a database error.
+    pub async fn get_result_ids(&self, saved_search_id: SavedSearchId) -> Result<Vec<TorrentId>, Error> {
+        self.database.get_saved_search_result_ids(saved_search_id).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{is_match, normalize, AddSavedSearchRequest};
+    use crate::errors::ServiceError;
+    use crate::models::domain_event::TorrentUploaded;
+    use crate::models::saved_search::SavedSearch;
+
+    fn saved_search(query: &str, category: Option<&str>, tags: &[&str]) -> SavedSearch {
+        SavedSearch {
+            saved_search_id: 1,
+            user_id: 1,
+            username: "alice".to_string(),
+            name: "Linux".to_string(),
+            query: query.to_string(),
+            category: category.map(ToString::to_string),
+            tags: tags.iter().map(ToString::to_string).collect(),
+            email_alerts: true,
+            date_created: "2024-10-17 10:00:00".to_string(),
+        }
+    }
+
+    fn torrent(title: &str, category: &str) -> TorrentUploaded {
+        TorrentUploaded {
+            torrent_id: 1,
+            info_hash: "443c7602b4fde83d1154d6d9da48808418b181b6".to_string(),
+            title: title.to_string(),
+            category: category.to_string(),
+            uploader: "bob".to_string(),
+        }
+    }
+
+    #[test]
+    fn it_should_match_torrents_with_all_the_words_category_and_tags() {
+        let torrent = torrent("Ubuntu 24.04 Desktop amd64", "software");
+        let tags = vec!["Linux".to_string(), "ISO".to_string()];
+
+        assert!(is_match(&saved_search("ubuntu AMD64", None, &[]), &torrent, &tags));
+        assert!(is_match(
+            &saved_search("", Some("Software"), &["linux", "iso"]),
+            &torrent,
+            &tags
+        ));
+        assert!(!is_match(&saved_search("ubuntu server", None, &[]), &torrent, &tags));
+        assert!(!is_match(&saved_search("ubuntu", Some("movies"), &[]), &torrent, &tags));
+        assert!(!is_match(&saved_search("ubuntu", None, &["linux", "arm"]), &torrent, &tags));
+    }
+
+    #[test]
+    fn it_should_reject_saved_searches_without_name_or_criteria() {
+        let request = |name: &str, query: &str| AddSavedSearchRequest {
+            name: name.to_string(),
+            query: query.to_string(),
+            category: Some(" ".to_string()),
+            tags: vec![" ".to_string()],
+            email_alerts: None,
+        };
+
+        assert_eq!(normalize(&request(" ", "ubuntu")), Err(ServiceError::InvalidSavedSearch));
+        assert_eq!(normalize(&request("Linux", "  ")), Err(ServiceError::InvalidSavedSearch));
+
+        let normalized = normalize(&request(" Linux ", " ubuntu   desktop ")).unwrap();
+
+        assert_eq!(normalized.name, "Linux");
+        assert_eq!(normalized.query, "ubuntu desktop");
+        assert_eq!(normalized.category, None);
+        assert!(normalized.tags.is_empty());
+        assert_eq!(normalized.email_alerts, Some(true));
+    }
+}
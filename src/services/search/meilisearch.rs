@@ -0,0 +1,112 @@
+//! Search index backed by an external [Meilisearch](https://www.meilisearch.com/) server.
+//!
+//! It uses the Meilisearch REST API directly. Documents are stored in the
+//! configured index using the torrent ID as primary key.
+use async_trait::async_trait;
+use reqwest::{Client, RequestBuilder};
+use serde::Deserialize;
+use serde_json::json;
+use url::Url;
+
+use super::{Document, Error, SearchIndex};
+use crate::config::Meilisearch;
+use crate::models::torrent::TorrentId;
+
+pub struct MeilisearchIndex {
+    client: Client,
+    url: Url,
+    api_key: Option<String>,
+    index: String,
+}
+
+#[derive(Deserialize)]
+struct SearchResponse {
+    hits: Vec<Hit>,
+}
+
+#[derive(Deserialize)]
+struct Hit {
+    torrent_id: TorrentId,
+}
+
+impl MeilisearchIndex {
+    #[must_use]
+    pub fn new(config: &Meilisearch) -> Self {
+        Self {
+            client: Client::new(),
+            url: config.url.clone(),
+            api_key: config.api_key.clone(),
+            index: config.index.clone(),
+        }
+    }
+
+    fn endpoint(&self, path: &str) -> String {
+        format!("{}/indexes/{}/{path}", self.url.as_str().trim_end_matches('/'), self.index)
+    }
+
+    fn authorized(&self, request_builder: RequestBuilder) -> RequestBuilder {
+        match &self.api_key {
+            Some(api_key) => request_builder.bearer_auth(api_key),
+            None => request_builder,
+        }
+    }
+
+    async fn send(&self, request_builder: RequestBuilder) -> Result<reqwest::Response, Error> {
+        let response = self
+            .authorized(request_builder)
+            .send()
+            .await
+            .map_err(|e| Error::Request { error: e.to_string() })?;
+
+        if !response.status().is_success() {
+            return Err(Error::UnexpectedResponse);
+        }
+
+        Ok(response)
+    }
+}
+
+#[async_trait]
+impl SearchIndex for MeilisearchIndex {
+    async fn index(&self, documents: &[Document]) -> Result<(), Error> {
+        if documents.is_empty() {
+            return Ok(());
+        }
+
+        self.send(
+            self.client
+                .post(self.endpoint("documents?primaryKey=torrent_id"))
+                .json(documents),
+        )
+        .await?;
+
+        Ok(())
+    }
+
+    async fn remove(&self, torrent_id: TorrentId) -> Result<(), Error> {
+        self.send(self.client.delete(self.endpoint(&format!("documents/{torrent_id}"))))
+            .await?;
+
+        Ok(())
+    }
+
+    async fn clear(&self) -> Result<(), Error> {
+        self.send(self.client.delete(self.endpoint("documents"))).await?;
+
+        Ok(())
+    }
+
+    async fn search(&self, query: &str, limit: usize) -> Result<Vec<TorrentId>, Error> {
+        let response = self
+            .send(self.client.post(self.endpoint("search")).json(&json!({
+                "q": query,
+                "limit": limit,
+                "attributesToRetrieve": ["torrent_id"],
+            })))
+            .await?;
+
+        let search_response: SearchResponse = response.json().await.map_err(|_| Error::UnexpectedResponse)?;
+
+        Ok(search_response.hits.into_iter().map(|hit| hit.torrent_id).collect())
+    }
+}
@@ -0,0 +1,232 @@
+//! Full-text search service.
+//!
+//! By default the torrent listing uses a simple SQL `LIKE` on the torrent
+//! title. When the `[search]` configuration section is present, torrents are
+//! indexed in a dedicated search index and the `search` param in the
+//! listing endpoint is resolved using the index relevance ranking.
+//!
+//! There are two backends:
+//!
+//! - [`tantivy_index`]: a built-in index using [Tantivy](https://github.com/quickwit-oss/tantivy).
+//! - [`meilisearch`]: a client for an external [Meilisearch](https://www.meilisearch.com/) server.
+//!
+//! The indexed fields are the torrent title, the description and the file
+//! names.
+pub mod meilisearch;
+pub mod tantivy_index;
+
+use std::sync::Arc;
+
+use async_trait::async_trait;
+use derive_more::{Display, Error};
+use serde::{Deserialize, Serialize};
+use tracing::{error, info};
+
+use crate::config::{Configuration, SearchBackend};
+use crate::databases::database::{self, Database};
+use crate::models::torrent::TorrentId;
+
+/// Number of torrents indexed at once when the index is rebuilt.
+const REBUILD_BATCH_SIZE: usize = 500;
+
+/// A torrent as it is stored in the search index.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Document {
+    pub torrent_id: TorrentId,
+    pub title: String,
+    pub description: String,
+    pub files: Vec<String>,
+}
+
+#[derive(Debug, Display, PartialEq, Eq, Error)]
+pub enum Error {
+    #[display("Search index error: {error}.")]
+    Index { error: String },
+
+    #[display("Search backend request error: {error}.")]
+    Request { error: String },
+
+    #[display("Unexpected search backend response.")]
+    UnexpectedResponse,
+}
+
+/// A search index backend.
+#[async_trait]
+pub trait SearchIndex: Sync + Send {
+    /// It adds or replaces torrents in the index.
+    async fn index(&self, documents: &[Document]) -> Result<(), Error>;
+
+    /// It removes a torrent from the index.
+    async fn remove(&self, torrent_id: TorrentId) -> Result<(), Error>;
+
+    /// It removes all torrents from the index.
+    async fn clear(&self) -> Result<(), Error>;
+
+    /// It returns the IDs of the torrents matching the query ordered by
+    /// relevance.
+    async fn search(&self, query: &str, limit: usize) -> Result<Vec<TorrentId>, Error>;
+}
+
+pub struct Service {
+    database: Arc<Box<dyn Database>>,
+    search_index: Option<Box<dyn SearchIndex>>,
+    max_results: usize,
+}
+
+impl Service {
+    /// # Panics
+    ///
+    /// Will panic if the search index can't be opened.
+    pub async fn new(cfg: Arc<Configuration>, database: Arc<Box<dyn Database>>) -> Service {
+        let settings = cfg.settings.read().await;
+        let search = settings.search.clone();
+        drop(settings);
+
+        let Some(search) = search else {
+            return Service {
+                database,
+                search_index: None,
+                max_results: 0,
+            };
+        };
+
+        let search_index: Box<dyn SearchIndex> = match search.backend {
+            SearchBackend::Tantivy => {
+                Box::new(tantivy_index::TantivyIndex::new(&search.tantivy).expect("the search index should be opened"))
+            }
+            SearchBackend::Meilisearch => Box::new(meilisearch::MeilisearchIndex::new(
+                &search.meilisearch.expect("the Meilisearch configuration should be validated"),
+            )),
+        };
+
+        Service {
+            database,
+            search_index: Some(search_index),
+            max_results: search.max_results,
+        }
+    }
+
+    /// Whether the search is backed by a search index or not.
+    #[must_use]
+    pub fn is_enabled(&self) -> bool {
+        self.search_index.is_some()
+    }
+
+    /// It returns the IDs of the torrents matching the query ordered by
+    /// relevance, or `None` if there is no search index.
+    ///
+    /// # Errors
+    ///
+    /// It returns an error if the search index query fails.
+    pub async fn search(&self, query: &str) -> Result<Option<Vec<TorrentId>>, Error> {
+        match &self.search_index {
+            Some(search_index) => Ok(Some(search_index.search(query, self.max_results).await?)),
+            None => Ok(None),
+        }
+    }
+
+    /// It adds or updates a torrent in the search index.
+    ///
+    /// Errors are only logged because the search index can always be rebuilt
+    /// from the database.
+    pub async fn index_torrent(&self, torrent_id: TorrentId) {
+        let Some(search_index) = &self.search_index else {
+            return;
+        };
+
+        let result = match self.build_document(torrent_id).await {
+            Ok(document) => search_index.index(&[document]).await,
+            Err(e) => Err(Error::Index { error: format!("{e:?}") }),
+        };
+
+        if let Err(e) = result {
+            error!(target: "search", "Failed to index torrent #{torrent_id}: {e}");
+        }
+    }
+
+    /// It removes a torrent from the search index.
+    pub async fn remove_torrent(&self, torrent_id: TorrentId) {
+        let Some(search_index) = &self.search_index else {
+            return;
+        };
+
+        if let Err(e) = search_index.remove(torrent_id).await {
+            error!(target: "search", "Failed to remove torrent #{torrent_id} from the search index: {e}");
+        }
+    }
+
+    /// It rebuilds the whole search index from the database.
+    ///
+    /// # Errors
+    ///
+    /// It returns an error if the index can't be cleared or there is a
+    /// database error.
+    pub async fn rebuild(&self) -> Result<(), Error> {
+        let Some(search_index) = &self.search_index else {
+            return Ok(());
+        };
+
+        let torrents = self
+            .database
+            .get_all_torrents_compact()
+            .await
+            .map_err(|e| Error::Index { error: format!("{e:?}") })?;
+
+        info!(target: "search", "Rebuilding the search index with {} torrents ...", torrents.len());
+
+        search_index.clear().await?;
+
+        for batch in torrents.chunks(REBUILD_BATCH_SIZE) {
+            let mut documents = Vec::with_capacity(batch.len());
+
+            for torrent in batch {
+                match self.build_document(torrent.torrent_id).await {
+                    Ok(document) => documents.push(document),
+                    Err(e) => error!(target: "search", "Failed to index torrent #{}: {e:?}", torrent.torrent_id),
+                }
+            }
+
+            search_index.index(&documents).await?;
+        }
+
+        info!(target: "search", "Search index rebuilt");
+
+        Ok(())
+    }
+
+    async fn build_document(&self, torrent_id: TorrentId) -> Result<Document, database::Error> {
+        let torrent_listing = self.database.get_torrent_listing_from_id(torrent_id).await?;
+
+        let mut files: Vec<String> = self
+            .database
+            .get_torrent_files_from_id(torrent_id)
+            .await?
+            .into_iter()
+            .map(|file| file.path.join("/"))
+            .filter(|path| !path.is_empty())
+            .collect();
+
+        files.push(torrent_listing.name);
+
+        let mut description = torrent_listing.description.unwrap_or_default();
+
+        // The canonical title and synopsis from the metadata providers are
+        // searchable too
+        if let Some(metadata) = self.database.get_torrent_metadata(torrent_id).await? {
+            description.push('\n');
+            description.push_str(&metadata.title);
+
+            if let Some(synopsis) = metadata.synopsis {
+                description.push('\n');
+                description.push_str(&synopsis);
+            }
+        }
+
+        Ok(Document {
+            torrent_id,
+            title: torrent_listing.title,
+            description,
+            files,
+        })
+    }
+}
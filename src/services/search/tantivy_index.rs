@@ -0,0 +1,336 @@
+//! Built-in search index using [Tantivy](https://github.com/quickwit-oss/tantivy).
+//!
+//! Tantivy is a blocking library, so the index writer and the searches run
+//! on the blocking thread pool. Updates are not committed one by one:
+//! changes are staged in the index writer and committed together after
+//! [`COMMIT_DELAY`], so they become searchable within that delay.
+//! Uncommitted changes are lost if the index is shut down, but the index can
+//! always be rebuilt from the database.
+use std::fmt::Display;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use async_trait::async_trait;
+use tantivy::collector::TopDocs;
+use tantivy::directory::MmapDirectory;
+use tantivy::query::QueryParser;
+use tantivy::schema::{Field, Schema, Value, INDEXED, STORED, TEXT};
+use tantivy::{Index, IndexReader, IndexWriter, ReloadPolicy, TantivyDocument, Term};
+use tracing::error;
+
+use super::{Document, Error, SearchIndex};
+use crate::config::Tantivy;
+use crate::models::torrent::TorrentId;
+
+/// Number of indexing threads. Index updates are small and infrequent.
+const WRITER_NUM_THREADS: usize = 1;
+
+/// Memory used by the index writer before flushing to segments.
+const WRITER_MEMORY_BUDGET_IN_BYTES: usize = 50_000_000;
+
+/// Matches in the title are more relevant than in the description or files.
+const TITLE_BOOST: f32 = 3.0;
+
+/// Time the staged changes wait before being committed, so the changes made
+/// in the meantime are committed together.
+pub const COMMIT_DELAY: Duration = Duration::from_secs(1);
+
+struct Fields {
+    torrent_id: Field,
+    title: Field,
+    description: Field,
+    files: Field,
+}
+
+pub struct TantivyIndex {
+    inner: Arc<Inner>,
+}
+
+struct Inner {
+    index: Index,
+    reader: IndexReader,
+    writer: Mutex<IndexWriter>,
+    fields: Fields,
+    commit_scheduled: AtomicBool,
+}
+
+impl TantivyIndex {
+    /// It opens (or creates) the index.
+    ///
+    /// # Errors
+    ///
+    /// It returns an error if the index directory can't be created or opened.
+    pub fn new(config: &Tantivy) -> Result<Self, Error> {
+        let (schema, fields) = build_schema();
+
+        let index = match &config.index_path {
+            Some(path) => {
+                std::fs::create_dir_all(path).map_err(index_error)?;
+                let directory = MmapDirectory::open(path.as_std_path()).map_err(index_error)?;
+                Index::open_or_create(directory, schema).map_err(index_error)?
+            }
+            None => Index::create_in_ram(schema),
+        };
+
+        let reader = index
+            .reader_builder()
+            .reload_policy(ReloadPolicy::Manual)
+            .try_into()
+            .map_err(index_error)?;
+
+        let writer = index
+            .writer_with_num_threads(WRITER_NUM_THREADS, WRITER_MEMORY_BUDGET_IN_BYTES)
+            .map_err(index_error)?;
+
+        Ok(Self {
+            inner: Arc::new(Inner {
+                index,
+                reader,
+                writer: Mutex::new(writer),
+                fields,
+                commit_scheduled: AtomicBool::new(false),
+            }),
+        })
+    }
+
+    /// It commits the staged changes right away, without waiting for the
+    /// scheduled commit.
+    ///
+    /// # Errors
+    ///
+    /// It returns an error if the commit fails.
+    pub async fn commit(&self) -> Result<(), Error> {
+        let inner = self.inner.clone();
+
+        run_blocking(move || inner.commit()).await
+    }
+
+    /// It stages changes in the index writer and schedules a commit.
+    async fn update<F>(&self, stage: F) -> Result<(), Error>
+    where
+        F: FnOnce(&Inner, &mut IndexWriter) -> Result<(), Error> + Send + 'static,
+    {
+        let inner = self.inner.clone();
+
+        run_blocking(move || {
+            let mut writer = inner.writer.lock().map_err(index_error)?;
+            stage(&inner, &mut writer)
+        })
+        .await?;
+
+        self.schedule_commit();
+
+        Ok(())
+    }
+
+    /// It commits the staged changes after [`COMMIT_DELAY`], unless there
+    /// is already a commit scheduled.
+    fn schedule_commit(&self) {
+        if self.inner.commit_scheduled.swap(true, Ordering::AcqRel) {
+            return;
+        }
+
+        let inner = self.inner.clone();
+
+        tokio::spawn(async move {
+            tokio::time::sleep(COMMIT_DELAY).await;
+
+            // Changes staged from now on need another commit
+            inner.commit_scheduled.store(false, Ordering::Release);
+
+            if let Err(e) = run_blocking(move || inner.commit()).await {
+                error!(target: "search", "Failed to commit the search index: {e}");
+            }
+        });
+    }
+}
+
+impl Inner {
+    fn commit(&self) -> Result<(), Error> {
+        let mut writer = self.writer.lock().map_err(index_error)?;
+
+        writer.commit().map_err(index_error)?;
+
+        drop(writer);
+
+        self.reader.reload().map_err(index_error)
+    }
+
+    fn stage_documents(&self, writer: &mut IndexWriter, documents: &[Document]) -> Result<(), Error> {
+        for document in documents {
+            writer.delete_term(Term::from_field_i64(self.fields.torrent_id, document.torrent_id));
+
+            let mut tantivy_document = TantivyDocument::default();
+            tantivy_document.add_i64(self.fields.torrent_id, document.torrent_id);
+            tantivy_document.add_text(self.fields.title, &document.title);
+            tantivy_document.add_text(self.fields.description, &document.description);
+            for file in &document.files {
+                tantivy_document.add_text(self.fields.files, file);
+            }
+
+            writer.add_document(tantivy_document).map_err(index_error)?;
+        }
+
+        Ok(())
+    }
+
+    fn search(&self, query: &str, limit: usize) -> Result<Vec<TorrentId>, Error> {
+        let mut query_parser = QueryParser::for_index(
+            &self.index,
+            vec![self.fields.title, self.fields.description, self.fields.files],
+        );
+        query_parser.set_conjunction_by_default();
+        query_parser.set_field_boost(self.fields.title, TITLE_BOOST);
+
+        // Invalid query syntax is ignored so users can search for any text.
+        let (query, _errors) = query_parser.parse_query_lenient(query);
+
+        let searcher = self.reader.searcher();
+
+        let top_docs = searcher
+            .search(&query, &TopDocs::with_limit(limit.max(1)))
+            .map_err(index_error)?;
+
+        let mut torrent_ids = Vec::with_capacity(top_docs.len());
+
+        for (_score, doc_address) in top_docs {
+            let tantivy_document: TantivyDocument = searcher.doc(doc_address).map_err(index_error)?;
+
+            if let Some(torrent_id) = tantivy_document.get_first(self.fields.torrent_id).and_then(Value::as_i64) {
+                torrent_ids.push(torrent_id);
+            }
+        }
+
+        Ok(torrent_ids)
+    }
+}
+
+#[async_trait]
+impl SearchIndex for TantivyIndex {
+    async fn index(&self, documents: &[Document]) -> Result<(), Error> {
+        let documents = documents.to_vec();
+
+        self.update(move |inner, writer| inner.stage_documents(writer, &documents))
+            .await
+    }
+
+    async fn remove(&self, torrent_id: TorrentId) -> Result<(), Error> {
+        self.update(move |inner, writer| {
+            writer.delete_term(Term::from_field_i64(inner.fields.torrent_id, torrent_id));
+            Ok(())
+        })
+        .await
+    }
+
+    async fn clear(&self) -> Result<(), Error> {
+        self.update(|_inner, writer| writer.delete_all_documents().map(|_| ()).map_err(index_error))
+            .await
+    }
+
+    async fn search(&self, query: &str, limit: usize) -> Result<Vec<TorrentId>, Error> {
+        let inner = self.inner.clone();
+        let query = query.to_string();
+
+        run_blocking(move || inner.search(&query, limit)).await
+    }
+}
+
+fn build_schema() -> (Schema, Fields) {
+    let mut schema_builder = Schema::builder();
+
+    let fields = Fields {
+        torrent_id: schema_builder.add_i64_field("torrent_id", INDEXED | STORED),
+        title: schema_builder.add_text_field("title", TEXT),
+        description: schema_builder.add_text_field("description", TEXT),
+        files: schema_builder.add_text_field("files", TEXT),
+    };
+
+    (schema_builder.build(), fields)
+}
+
+fn index_error<E: Display>(e: E) -> Error {
+    Error::Index { error: e.to_string() }
+}
+
+async fn run_blocking<T, F>(f: F) -> Result<T, Error>
+where
+    T: Send + 'static,
+    F: FnOnce() -> Result<T, Error> + Send + 'static,
+{
+    tokio::task::spawn_blocking(f).await.map_err(index_error)?
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{TantivyIndex, COMMIT_DELAY};
+    use crate::config::Tantivy;
+    use crate::services::search::{Document, SearchIndex};
+
+    fn document(torrent_id: i64, title: &str, description: &str, files: &[&str]) -> Document {
+        Document {
+            torrent_id,
+            title: title.to_string(),
+            description: description.to_string(),
+            files: files.iter().map(ToString::to_string).collect(),
+        }
+    }
+
+    #[tokio::test]
+    async fn it_should_rank_title_matches_first() {
+        let index = TantivyIndex::new(&Tantivy::default()).unwrap();
+
+        index
+            .index(&[
+                document(1, "Big Buck Bunny", "An animated film", &["bunny.mp4"]),
+                document(2, "Sintel", "A film with a bunny in the description", &["sintel.mp4"]),
+                document(3, "Ubuntu", "Linux distribution", &["ubuntu.iso"]),
+            ])
+            .await
+            .unwrap();
+        index.commit().await.unwrap();
+
+        assert_eq!(index.search("bunny", 10).await.unwrap(), vec![1, 2]);
+    }
+
+    #[tokio::test]
+    async fn it_should_search_in_file_names() {
+        let index = TantivyIndex::new(&Tantivy::default()).unwrap();
+
+        index
+            .index(&[document(1, "Linux", "", &["ubuntu-24.04-desktop-amd64.iso"])])
+            .await
+            .unwrap();
+        index.commit().await.unwrap();
+
+        assert_eq!(index.search("ubuntu", 10).await.unwrap(), vec![1]);
+    }
+
+    #[tokio::test]
+    async fn it_should_remove_torrents_from_the_index() {
+        let index = TantivyIndex::new(&Tantivy::default()).unwrap();
+
+        index.index(&[document(1, "Big Buck Bunny", "", &[])]).await.unwrap();
+        index.remove(1).await.unwrap();
+        index.commit().await.unwrap();
+
+        assert!(index.search("bunny", 10).await.unwrap().is_empty());
+    }
+
+    #[tokio::test]
+    async fn it_should_commit_the_staged_changes_together_after_the_commit_delay() {
+        let index = TantivyIndex::new(&Tantivy::default()).unwrap();
+
+        index.index(&[document(1, "Big Buck Bunny", "", &[])]).await.unwrap();
+        index.index(&[document(2, "Bunny", "", &[])]).await.unwrap();
+
+        assert!(index.search("bunny", 10).await.unwrap().is_empty());
+
+        tokio::time::sleep(COMMIT_DELAY * 3).await;
+
+        let mut torrent_ids = index.search("bunny", 10).await.unwrap();
+        torrent_ids.sort_unstable();
+
+        assert_eq!(torrent_ids, vec![1, 2]);
+    }
+}
@@ -0,0 +1,443 @@
+//! Seeding reward hooks for external bonus systems.
+//!
+//! The index does not implement a bonus economy. Instead, it periodically
+//! publishes a [`SeedingSummary`] domain event for every user with seeded
+//! uploads, built from the tracker stats. External bonus systems consume the
+//! summaries in two ways:
+//!
+//! - Pulling them from the admin API, keeping the ID of the last summary
+//!   they have processed.
+//! - Receiving them in a webhook. Each summary is posted as a JSON object.
+//!   When a secret is configured, the `X-Torrust-Signature` header contains
+//!   the lowercase hex-encoded HMAC-SHA256 of the request body.
+//!
+//! The external system writes back its decisions as adjustments to the
+//! user's local points balance. Adjustments can have a reference, unique for
+//! the user, so retried requests are only applied once.
+//!
+//! The integration is configured in the `[integrations.seeding_rewards]`
+//! section. Refer to [`SeedingRewards`](crate::config::SeedingRewards).
+use std::sync::Arc;
+
+use hmac::{Hmac, Mac};
+use reqwest::header::CONTENT_TYPE;
+use serde::{Deserialize, Serialize};
+use sha2::Sha256;
+use tracing::{debug, error};
+use url::Url;
+use utoipa::{IntoParams, ToSchema};
+
+use super::authorization::{self, ACTION};
+use crate::config::SigningSecret;
+use crate::databases::database::{Database, Error};
+use crate::errors::ServiceError;
+use crate::models::domain_event::{DbDomainEvent, DomainEvent, EventId, SeedingSummary, SEEDING_SUMMARY_EVENT_TYPE};
+use crate::models::points::{PointsAdjustment, PointsBalance, SeedingSummaryEvent, UploaderSeedingStats};
+use crate::models::user::UserId;
+use crate::services::domain_events::DbDomainEventRepository;
+use crate::utils::clock::datetime_now;
+use crate::utils::trace_context::PropagateTraceContext;
+
+type HmacSha256 = Hmac<Sha256>;
+
+/// The header with the webhook request signature.
+pub const SIGNATURE_HEADER: &str = "X-Torrust-Signature";
+
+/// Default number of summaries returned by the API.
+const DEFAULT_SUMMARIES_LIMIT: u32 = 100;
+
+/// Maximum number of summaries returned by the API.
+const MAX_SUMMARIES_LIMIT: u32 = 1000;
+
+/// Number of adjustments included in the points balance.
+const LATEST_ADJUSTMENTS: u32 = 20;
+
+/// Maximum length of the adjustment reasons.
+const MAX_REASON_LENGTH: usize = 200;
+
+/// Maximum length of the adjustment references.
+const MAX_REFERENCE_LENGTH: usize = 100;
+
+/// Request to get the seeding summaries published after a summary.
+#[derive(Debug, Default, Deserialize, IntoParams)]
+pub struct SummariesRequest {
+    /// ID of the last summary processed by the consumer. Default to `0`.
+    pub after: Option<EventId>,
+    /// Max number of summaries. Default to `100`, max `1000`.
+    pub limit: Option<u32>,
+}
+
+/// Request to adjust the points balance of a user.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+pub struct AdjustPointsRequest {
+    /// Points added to the balance. Negative amounts subtract points.
+    pub amount: i64,
+    pub reason: String,
+    /// Unique reference of the adjustment in the external system. Repeated
+    /// adjustments with the same reference are ignored.
+    #[serde(default)]
+    pub reference: Option<String>,
+}
+
+pub struct Service {
+    seeding_rewards_repository: Arc<DbSeedingRewardsRepository>,
+    domain_event_repository: Arc<DbDomainEventRepository>,
+    authorization_service: Arc<authorization::Service>,
+}
+
+impl Service {
+    #[must_use]
+    pub fn new(
+        seeding_rewards_repository: Arc<DbSeedingRewardsRepository>,
+        domain_event_repository: Arc<DbDomainEventRepository>,
+        authorization_service: Arc<authorization::Service>,
+    ) -> Service {
+        Service {
+            seeding_rewards_repository,
+            domain_event_repository,
+            authorization_service,
+        }
+    }
+
+    /// It publishes the seeding summary of every user with seeded uploads.
+    /// It returns the number of published summaries.
+    ///
+    /// # Errors
+    ///
+    /// It returns an error if there is a database error.
+    pub async fn publish_summaries(&self, period_seconds: u64) -> Result<usize, Error> {
+        let date = datetime_now();
+
+        let stats = self.seeding_rewards_repository.get_uploader_seeding_stats().await?;
+
+        for uploader_stats in &stats {
+            let event = DomainEvent::SeedingSummary(seeding_summary(uploader_stats, &date, period_seconds));
+
+            self.domain_event_repository.publish(&event).await?;
+        }
+
+        Ok(stats.len())
+    }
+
+    /// It returns the seeding summaries published after a summary, oldest
+    /// first.
+    ///
+    /// # Errors
+    ///
+    /// It returns an error if:
+    ///
+    /// * The user does not have permissions to manage the points.
+    /// * There is a database error.
+    pub async fn get_summaries(
+        &self,
+        request: &SummariesRequest,
+        maybe_user_id: Option<UserId>,
+    ) -> Result<Vec<SeedingSummaryEvent>, ServiceError> {
+        self.authorization_service
+            .authorize(ACTION::ManagePoints, maybe_user_id)
+            .await?;
+
+        let limit = request.limit.unwrap_or(DEFAULT_SUMMARIES_LIMIT).clamp(1, MAX_SUMMARIES_LIMIT);
+
+        let events = self
+            .seeding_rewards_repository
+            .get_summaries_after(request.after.unwrap_or_default(), limit)
+            .await?;
+
+        Ok(events.iter().filter_map(seeding_summary_event).collect())
+    }
+
+    /// It returns the points balance of a user.
+    ///
+    /// # Errors
+    ///
+    /// It returns an error if:
+    ///
+    /// * The user does not have permissions to manage the points.
+    /// * The user does not exist.
+    /// * There is a database error.
+    pub async fn get_points(&self, username: &str, maybe_user_id: Option<UserId>) -> Result<PointsBalance, ServiceError> {
+        self.authorization_service
+            .authorize(ACTION::ManagePoints, maybe_user_id)
+            .await?;
+
+        let user_id = self.seeding_rewards_repository.get_user_id(username).await?;
+
+        self.points_balance(username, user_id).await
+    }
+
+    /// It adjusts the points balance of a user. Adjustments with a reference
+    /// already used for the user are ignored.
+    ///
+    /// # Errors
+    ///
+    /// It returns an error if:
+    ///
+    /// * The user does not have permissions to manage the points.
+    /// * The amount is zero, or the reason or the reference are not valid.
+    /// * The user does not exist.
+    /// * There is a database error.
+    pub async fn adjust_points(
+        &self,
+        username: &str,
+        request: &AdjustPointsRequest,
+        maybe_user_id: Option<UserId>,
+    ) -> Result<PointsBalance, ServiceError> {
+        self.authorization_service
+            .authorize(ACTION::ManagePoints, maybe_user_id)
+            .await?;
+
+        validate_adjustment(request)?;
+
+        let user_id = self.seeding_rewards_repository.get_user_id(username).await?;
+
+        let reference = request.reference.as_deref().map(str::trim);
+
+        let applied = self
+            .seeding_rewards_repository
+            .add_adjustment(user_id, request.amount, request.reason.trim(), reference)
+            .await?;
+
+        if !applied {
+            debug!(target: "seeding rewards", "Ignoring repeated points adjustment for user #{user_id}");
+        }
+
+        self.points_balance(username, user_id).await
+    }
+
+    async fn points_balance(&self, username: &str, user_id: UserId) -> Result<PointsBalance, ServiceError> {
+        Ok(PointsBalance {
+            username: username.to_string(),
+            balance: self.seeding_rewards_repository.get_balance(user_id).await?,
+            adjustments: self
+                .seeding_rewards_repository
+                .get_adjustments(user_id, LATEST_ADJUSTMENTS)
+                .await?,
+        })
+    }
+}
+
+fn seeding_summary(stats: &UploaderSeedingStats, date: &str, period_seconds: u64) -> SeedingSummary {
+    SeedingSummary {
+        user_id: stats.user_id,
+        username: stats.username.clone(),
+        date: date.to_string(),
+        period_seconds,
+        torrents: stats.torrents,
+        seeded_torrents: stats.seeded_torrents,
+        seeders: stats.seeders,
+        leechers: stats.leechers,
+        seeded_bytes: stats.seeded_bytes,
+    }
+}
+
+/// It decodes a seeding summary domain event. Other events are ignored.
+#[must_use]
+pub fn seeding_summary_event(event: &DbDomainEvent) -> Option<SeedingSummaryEvent> {
+    match serde_json::from_str::<DomainEvent>(&event.payload) {
+        Ok(DomainEvent::SeedingSummary(summary)) => Some(SeedingSummaryEvent {
+            event_id: event.event_id,
+            summary,
+        }),
+        Ok(_) => None,
+        Err(e) => {
+            error!(target: "seeding rewards", "Skipping invalid domain event #{}: {e}", event.event_id);
+            None
+        }
+    }
+}
+
+fn validate_adjustment(request: &AdjustPointsRequest) -> Result<(), ServiceError> {
+    let reason = request.reason.trim();
+
+    let is_valid_reference = request.reference.as_deref().map_or(true, |reference| {
+        !reference.trim().is_empty() && reference.len() <= MAX_REFERENCE_LENGTH
+    });
+
+    if request.amount == 0 || reason.is_empty() || reason.chars().count() > MAX_REASON_LENGTH || !is_valid_reference {
+        return Err(ServiceError::InvalidPointsAdjustment);
+    }
+
+    Ok(())
+}
+
+/// It signs a webhook request body with the shared secret.
+#[must_use]
+pub fn sign(secret: &SigningSecret, body: &[u8]) -> String {
+    let mut mac = HmacSha256::new_from_slice(secret.as_bytes()).expect("HMAC can take a key of any size");
+
+    mac.update(body);
+
+    hex::encode(mac.finalize().into_bytes())
+}
+
+/// It posts the seeding summaries to the webhook of the external bonus
+/// system.
+pub struct Webhook {
+    client: reqwest::Client,
+    url: Url,
+    secret: Option<SigningSecret>,
+}
+
+impl Webhook {
+    #[must_use]
+    pub fn new(url: Url, secret: Option<SigningSecret>) -> Self {
+        Self {
+            client: reqwest::Client::new(),
+            url,
+            secret,
+        }
+    }
+
+    /// It posts a seeding summary.
+    ///
+    /// # Errors
+    ///
+    /// It returns an error if the request fails or the webhook does not
+    /// accept it.
+    pub async fn send(&self, summary: &SeedingSummaryEvent) -> Result<(), reqwest::Error> {
+        let body = serde_json::to_vec(summary).expect("seeding summaries should be serializable");
+
+        let mut request = self
+            .client
+            .post(self.url.clone())
+            .with_trace_context()
+            .header(CONTENT_TYPE, "application/json");
+
+        if let Some(secret) = &self.secret {
+            request = request.header(SIGNATURE_HEADER, sign(secret, &body));
+        }
+
+        request.body(body).send().await?.error_for_status()?;
+
+        Ok(())
+    }
+}
+
+pub struct DbSeedingRewardsRepository {
+    database: Arc<Box<dyn Database>>,
+}
+
+impl DbSeedingRewardsRepository {
+    #[must_use]
+    pub fn new(database: Arc<Box<dyn Database>>) -> Self {
+        Self { database }
+    }
+
+    /// It returns the seeding stats of the uploaders with seeded torrents.
+    ///
+    /// # Errors
+    ///
+    /// It returns an error if there is a database error.
+    pub async fn get_uploader_seeding_stats(&self) -> Result<Vec<UploaderSeedingStats>, Error> {
+        self.database.get_uploader_seeding_stats().await
+    }
+
+    /// It returns the seeding summary events published after an event.
+    ///
+    /// # Errors
+    ///
+    /// It returns an error if there is a database error.
+    pub async fn get_summaries_after(&self, event_id: EventId, limit: u32) -> Result<Vec<DbDomainEvent>, Error> {
+        self.database
+            .get_domain_events_of_type_after(SEEDING_SUMMARY_EVENT_TYPE, event_id, limit)
+            .await
+    }
+
+    /// It returns the ID of a user.
+    ///
+    /// # Errors
+    ///
+    /// It returns an error if the user does not exist or there is a database
+    /// error.
+    pub async fn get_user_id(&self, username: &str) -> Result<UserId, Error> {
+        self.database
+            .get_user_profile_from_username(username)
+            .await
+            .map(|user_profile| user_profile.user_id)
+    }
+
+    /// It adds a points adjustment. It returns `false` if the reference was
+    /// already used for the user.
+    ///
+    /// # Errors
+    ///
+    /// It returns an error if there is a database error.
+    pub async fn add_adjustment(
+        &self,
+        user_id: UserId,
+        amount: i64,
+        reason: &str,
+        reference: Option<&str>,
+    ) -> Result<bool, Error> {
+        self.database
+            .insert_points_adjustment(user_id, amount, reason, reference)
+            .await
+    }
+
+    /// It returns the points balance of a user.
+    ///
+    /// # Errors
+    ///
+    /// It returns an error if there is a database error.
+    pub async fn get_balance(&self, user_id: UserId) -> Result<i64, Error> {
+        self.database.get_points_balance(user_id).await
+    }
+
+    /// It returns the latest points adjustments of a user.
+    ///
+    /// # Errors
+    ///
+    /// It returns an error if there is a database error.
+    pub async fn get_adjustments(&self, user_id: UserId, limit: u32) -> Result<Vec<PointsAdjustment>, Error> {
+        self.database.get_points_adjustments(user_id, limit).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{sign, validate_adjustment, AdjustPointsRequest};
+    use crate::config::SigningSecret;
+    use crate::errors::ServiceError;
+
+    fn adjustment(amount: i64, reason: &str, reference: Option<&str>) -> AdjustPointsRequest {
+        AdjustPointsRequest {
+            amount,
+            reason: reason.to_string(),
+            reference: reference.map(ToString::to_string),
+        }
+    }
+
+    #[test]
+    fn it_should_validate_the_points_adjustments() {
+        assert_eq!(
+            validate_adjustment(&adjustment(10, "Seeding bonus", Some("2024-10-17"))),
+            Ok(())
+        );
+        assert_eq!(validate_adjustment(&adjustment(-5, "Penalty", None)), Ok(()));
+
+        assert_eq!(
+            validate_adjustment(&adjustment(0, "Seeding bonus", None)),
+            Err(ServiceError::InvalidPointsAdjustment)
+        );
+        assert_eq!(
+            validate_adjustment(&adjustment(10, " ", None)),
+            Err(ServiceError::InvalidPointsAdjustment)
+        );
+        assert_eq!(
+            validate_adjustment(&adjustment(10, "Seeding bonus", Some(""))),
+            Err(ServiceError::InvalidPointsAdjustment)
+        );
+    }
+
+    #[test]
+    fn it_should_sign_the_webhook_body_with_hmac_sha256() {
+        // RFC 4231, test case 2.
+        let secret = SigningSecret::new("Jefe");
+
+        assert_eq!(
+            sign(&secret, b"what do ya want for nothing?"),
+            "5bdcc146bf60754e6a042426089575c75a003f089d2739839dec58b964ec3843"
+        );
+    }
+}
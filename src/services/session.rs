@@ -0,0 +1,291 @@
+//! User sessions.
+//!
+//! A session is started each time a user logs in. The session ID is the `jti`
+//! claim of the token, so renewed tokens keep the session of the original
+//! token. Users can list their active sessions and revoke them. Tokens of a
+//! revoked session are rejected even if they have not expired yet.
+//!
+//! Tokens issued before sessions were introduced don't have the `jti` claim.
+//! They are valid until they expire, but they can't be revoked.
+use std::sync::Arc;
+
+use chrono::{DateTime, NaiveDateTime, Utc};
+use tracing::error;
+
+use super::authorization::{self, ACTION};
+use crate::databases::database::{Database, Error};
+use crate::errors::ServiceError;
+use crate::models::user::UserId;
+use crate::models::user_session::{SessionId, UserSession};
+use crate::utils::clock::{datetime_now, seconds_ago_utc, DATETIME_FORMAT};
+
+/// Max length of the user agent stored with a session.
+const MAX_USER_AGENT_LENGTH: usize = 255;
+
+/// The last time a session was used is only updated when it's older than
+/// this, so it's not updated in every request.
+const LAST_SEEN_RESOLUTION_SECONDS: i64 = 60;
+
+/// The client used to log in.
+#[derive(Debug, Clone, Default)]
+pub struct Client {
+    pub user_agent: Option<String>,
+    pub ip: Option<String>,
+}
+
+pub struct Service {
+    session_repository: Arc<DbUserSessionRepository>,
+    authorization_service: Arc<authorization::Service>,
+}
+
+impl Service {
+    #[must_use]
+    pub fn new(session_repository: Arc<DbUserSessionRepository>, authorization_service: Arc<authorization::Service>) -> Service {
+        Service {
+            session_repository,
+            authorization_service,
+        }
+    }
+
+    /// It starts a new session for a user who has just logged in. The
+    /// expired sessions of all users are deleted.
+    ///
+    /// # Errors
+    ///
+    /// It returns an error if there is a database error.
+    pub async fn start(&self, user_id: UserId, client: &Client, expires_at: u64) -> Result<SessionId, ServiceError> {
+        if let Err(e) = self.session_repository.delete_expired(&datetime_now()).await {
+            error!(target: "sessions", "Failed to delete the expired sessions: {e:?}");
+        }
+
+        let session_id = uuid::Uuid::new_v4().simple().to_string();
+
+        let user_agent = client
+            .user_agent
+            .as_deref()
+            .map(|user_agent| user_agent.chars().take(MAX_USER_AGENT_LENGTH).collect::<String>());
+
+        self.session_repository
+            .add(
+                &session_id,
+                user_id,
+                user_agent.as_deref(),
+                client.ip.as_deref(),
+                &format_timestamp(expires_at),
+            )
+            .await?;
+
+        Ok(session_id)
+    }
+
+    /// It checks that the session of a token was not revoked, and it records
+    /// that the session was used.
+    ///
+    /// # Errors
+    ///
+    /// It returns an error if:
+    ///
+    /// * The session was revoked.
+    /// * There is a database error.
+    pub async fn check(&self, session_id: &str) -> Result<(), ServiceError> {
+        let session = match self.session_repository.get(session_id).await {
+            Ok(session) => session,
+            Err(Error::UserSessionNotFound) => return Err(ServiceError::SessionRevoked),
+            Err(e) => return Err(e.into()),
+        };
+
+        if is_seen_before(&session.last_seen, seconds_ago_utc(LAST_SEEN_RESOLUTION_SECONDS)) {
+            if let Err(e) = self.session_repository.update_last_seen(session_id, &datetime_now()).await {
+                error!(target: "sessions", "Failed to update the last use of session {session_id}: {e:?}");
+            }
+        }
+
+        Ok(())
+    }
+
+    /// It extends a session when its token is renewed.
+    ///
+    /// # Errors
+    ///
+    /// It returns an error if there is a database error.
+    pub async fn extend(&self, session_id: &str, expires_at: u64) -> Result<(), ServiceError> {
+        Ok(self
+            .session_repository
+            .update_expiration(session_id, &format_timestamp(expires_at))
+            .await?)
+    }
+
+    /// It returns the active sessions of the logged-in user, from the most
+    /// recently used. The `current_session_id` is the session of the token
+    /// used in the request, if any.
+    ///
+    /// # Errors
+    ///
+    /// It returns an error if:
+    ///
+    /// * The user is not logged in.
+    /// * There is a database error.
+    pub async fn get_sessions(
+        &self,
+        maybe_user_id: Option<UserId>,
+        current_session_id: Option<&str>,
+    ) -> Result<Vec<UserSession>, ServiceError> {
+        let Some(user_id) = maybe_user_id else {
+            return Err(ServiceError::UnauthorizedActionForGuests);
+        };
+
+        self.authorization_service
+            .authorize(ACTION::ManageSessions, maybe_user_id)
+            .await?;
+
+        let mut sessions = self.session_repository.get_all(user_id, &datetime_now()).await?;
+
+        for session in &mut sessions {
+            session.current = current_session_id == Some(session.session_id.as_str());
+        }
+
+        Ok(sessions)
+    }
+
+    /// It revokes a session of the logged-in user. The tokens of the session
+    /// are rejected from now on.
+    ///
+    /// # Errors
+    ///
+    /// It returns an error if:
+    ///
+    /// * The user is not logged in.
+    /// * The session does not exist or belongs to another user.
+    /// * There is a database error.
+    pub async fn revoke(&self, session_id: &str, maybe_user_id: Option<UserId>) -> Result<(), ServiceError> {
+        let Some(user_id) = maybe_user_id else {
+            return Err(ServiceError::UnauthorizedActionForGuests);
+        };
+
+        self.authorization_service
+            .authorize(ACTION::ManageSessions, maybe_user_id)
+            .await?;
+
+        Ok(self.session_repository.delete(session_id, user_id).await?)
+    }
+}
+
+/// It formats a timestamp in seconds in database format.
+fn format_timestamp(timestamp: u64) -> String {
+    DateTime::from_timestamp(i64::try_from(timestamp).unwrap_or(i64::MAX), 0)
+        .unwrap_or(DateTime::<Utc>::MAX_UTC)
+        .format(DATETIME_FORMAT)
+        .to_string()
+}
+
+/// Whether the last use of a session is older than the given date. Dates
+/// that can't be parsed are always older.
+fn is_seen_before(last_seen: &str, date: DateTime<Utc>) -> bool {
+    NaiveDateTime::parse_from_str(last_seen, DATETIME_FORMAT).map_or(true, |last_seen| last_seen.and_utc() < date)
+}
+
+pub struct DbUserSessionRepository {
+    database: Arc<Box<dyn Database>>,
+}
+
+impl DbUserSessionRepository {
+    #[must_use]
+    pub fn new(database: Arc<Box<dyn Database>>) -> Self {
+        Self { database }
+    }
+
+    /// It adds a session.
+    ///
+    /// # Errors
+    ///
+    /// It returns an error if there is a database error.
+    pub async fn add(
+        &self,
+        session_id: &str,
+        user_id: UserId,
+        user_agent: Option<&str>,
+        ip: Option<&str>,
+        expires_at: &str,
+    ) -> Result<(), Error> {
+        self.database
+            .insert_user_session(session_id, user_id, user_agent, ip, expires_at)
+            .await
+    }
+
+    /// It returns a session.
+    ///
+    /// # Errors
+    ///
+    /// It returns an error if the session does not exist or there is a
+    /// database error.
+    pub async fn get(&self, session_id: &str) -> Result<UserSession, Error> {
+        self.database.get_user_session(session_id).await
+    }
+
+    /// It returns the sessions of a user which expire after the given date.
+    ///
+    /// # Errors
+    ///
+    /// It returns an error if there is a database error.
+    pub async fn get_all(&self, user_id: UserId, expires_after: &str) -> Result<Vec<UserSession>, Error> {
+        self.database.get_user_sessions(user_id, expires_after).await
+    }
+
+    /// It records the last time a session was used.
+    ///
+    /// # Errors
+    ///
+    /// It returns an error if there is a database error.
+    pub async fn update_last_seen(&self, session_id: &str, last_seen: &str) -> Result<(), Error> {
+        self.database.update_user_session_last_seen(session_id, last_seen).await
+    }
+
+    /// It changes the expiration date of a session.
+    ///
+    /// # Errors
+    ///
+    /// It returns an error if there is a database error.
+    pub async fn update_expiration(&self, session_id: &str, expires_at: &str) -> Result<(), Error> {
+        self.database.update_user_session_expiration(session_id, expires_at).await
+    }
+
+    /// It deletes a session of a user.
+    ///
+    /// # Errors
+    ///
+    /// It returns an error if the session does not exist or there is a
+    /// database error.
+    pub async fn delete(&self, session_id: &str, user_id: UserId) -> Result<(), Error> {
+        self.database.delete_user_session(session_id, user_id).await
+    }
+
+    /// It deletes the sessions which expired before the given date.
+    ///
+    /// # Errors
+    ///
+    /// It returns an error if there is a database error.
+    pub async fn delete_expired(&self, expires_before: &str) -> Result<u64, Error> {
+        self.database.delete_expired_user_sessions(expires_before).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::{TimeZone, Utc};
+
+    use super::{format_timestamp, is_seen_before};
+
+    #[test]
+    fn timestamps_should_be_formatted_in_database_format() {
+        assert_eq!(format_timestamp(1_729_216_800), "2024-10-18 02:00:00");
+    }
+
+    #[test]
+    fn it_should_compare_the_last_use_of_a_session_with_a_date() {
+        let date = Utc.with_ymd_and_hms(2024, 10, 18, 2, 0, 0).unwrap();
+
+        assert!(is_seen_before("2024-10-18 01:59:00", date));
+        assert!(!is_seen_before("2024-10-18 02:00:30", date));
+        assert!(is_seen_before("not a date", date));
+    }
+}
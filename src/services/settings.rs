@@ -4,24 +4,34 @@ use std::str::FromStr;
 use std::sync::Arc;
 
 use serde::{Deserialize, Serialize};
+use tracing::{error, info};
 use url::Url;
 
+use super::audit;
 use super::authorization::{self, ACTION};
+use crate::config::changes::SettingsChanges;
 use crate::config::{self, Configuration, Settings};
 use crate::errors::ServiceError;
+use crate::models::audit::AuditAction;
 use crate::models::user::UserId;
 
 pub struct Service {
     configuration: Arc<Configuration>,
     authorization_service: Arc<authorization::Service>,
+    audit_service: Arc<audit::Service>,
 }
 
 impl Service {
     #[must_use]
-    pub fn new(configuration: Arc<Configuration>, authorization_service: Arc<authorization::Service>) -> Service {
+    pub fn new(
+        configuration: Arc<Configuration>,
+        authorization_service: Arc<authorization::Service>,
+        audit_service: Arc<audit::Service>,
+    ) -> Service {
         Service {
             configuration,
             authorization_service,
+            audit_service,
         }
     }
 
@@ -57,6 +67,77 @@ impl Service {
         Ok(torrust_index_configuration)
     }
 
+    /// It gets the options that changed since the application started,
+    /// grouped by whether they took effect or they require a restart.
+    ///
+    /// # Errors
+    ///
+    /// It returns an error if the user does not have the required permissions.
+    pub async fn get_changes(&self, maybe_user_id: Option<UserId>) -> Result<SettingsChanges, ServiceError> {
+        self.authorization_service
+            .authorize(ACTION::GetSettingsSecret, maybe_user_id)
+            .await?;
+
+        Ok(self.configuration.get_changes_since_startup().await)
+    }
+
+    /// It reloads the settings from the configuration file or the
+    /// environment variable. It returns the options that changed, grouped by
+    /// whether they took effect or they require a restart.
+    ///
+    /// # Errors
+    ///
+    /// It returns an error if:
+    ///
+    /// * The user is not an administrator.
+    /// * The new settings can't be loaded or they are not valid. The running
+    ///   settings are not changed.
+    pub async fn reload(&self, maybe_user_id: Option<UserId>) -> Result<SettingsChanges, ServiceError> {
+        self.authorization_service
+            .authorize(ACTION::ReloadSettings, maybe_user_id)
+            .await?;
+
+        self.reload_as(maybe_user_id).await
+    }
+
+    /// It reloads the settings when the application receives the `SIGHUP`
+    /// signal. Errors are only logged.
+    pub async fn reload_on_signal(&self) {
+        drop(self.reload_as(None).await);
+    }
+
+    async fn reload_as(&self, actor_id: Option<UserId>) -> Result<SettingsChanges, ServiceError> {
+        let changes = match self.configuration.reload().await {
+            Ok(changes) => changes,
+            Err(e) => {
+                error!(target: "settings", "Failed to reload the settings: {e}");
+                return Err(ServiceError::SettingsReloadFailed);
+            }
+        };
+
+        info!(
+            target: "settings",
+            "Settings reloaded. Applied: {:?}. Pending restart: {:?}",
+            changes.applied, changes.pending_restart
+        );
+
+        if !changes.is_empty() {
+            let options = changes
+                .applied
+                .iter()
+                .chain(&changes.pending_restart)
+                .cloned()
+                .collect::<Vec<String>>()
+                .join(", ");
+
+            self.audit_service
+                .record(actor_id, AuditAction::SettingsUpdate, "settings", Some(&options))
+                .await;
+        }
+
+        Ok(changes)
+    }
+
     /// It gets only the public settings.
     ///
     /// # Errors
@@ -0,0 +1,417 @@
+//! Signed upload service.
+//!
+//! It allows trusted external systems (for example, an automated release
+//! pipeline) to upload torrents on behalf of a user without storing the
+//! user's long-lived token. Requests are signed with a shared secret
+//! configured in the `[integrations.signed_uploads]` section.
+//!
+//! The signature is the lowercase hex-encoded HMAC-SHA256 of the following
+//! string (fields separated by a new line `\n`):
+//!
+//! ```text
+//! <timestamp>
+//! <nonce>
+//! <user_id>
+//! <title>
+//! <category>
+//! <sha256 of the torrent file, lowercase hex>
+//! <sha256 of the description, lowercase hex>
+//! <tag IDs sorted in ascending order, separated by commas>
+//! <publish date in RFC 3339 format in UTC, like 2024-06-01T12:00:00Z, or empty>
+//! <preserve_announce_urls: true, false or empty>
+//! <sha256 of the category fields, lowercase hex>
+//! ```
+//!
+//! The hash of the category fields is computed over the fields as a JSON
+//! object without whitespace and with the keys in ascending order, like
+//! `{"resolution":"1080p","source":"web"}`, or `{}` when there are none.
+//!
+//! The timestamp is the number of seconds since the Unix epoch. Requests
+//! outside the allowed clock skew are rejected and every nonce can only be
+//! used once while the timestamp is still valid. Nonces have up to
+//! [`MAX_NONCE_LENGTH`] characters.
+//!
+//! The used nonces are saved in the database, so they can't be replayed
+//! after a restart or against another instance sharing the database.
+use std::sync::Arc;
+
+use chrono::SecondsFormat;
+use hmac::{Hmac, Mac};
+use sha2::{Digest, Sha256};
+
+use super::torrent::AddTorrentRequest;
+use crate::config::Configuration;
+use crate::databases::database::{Database, Error};
+use crate::errors::ServiceError;
+use crate::models::user::UserId;
+use crate::utils::clock;
+
+type HmacSha256 = Hmac<Sha256>;
+
+/// Maximum length of the nonces.
+pub const MAX_NONCE_LENGTH: usize = 64;
+
+/// The signature data sent by the external system along with the upload.
+pub struct UploadSignature {
+    pub user_id: UserId,
+    pub timestamp: u64,
+    pub nonce: String,
+    pub signature: String,
+}
+
+pub struct Service {
+    cfg: Arc<Configuration>,
+    upload_nonce_repository: Arc<DbUploadNonceRepository>,
+}
+
+impl Service {
+    #[must_use]
+    pub fn new(cfg: Arc<Configuration>, upload_nonce_repository: Arc<DbUploadNonceRepository>) -> Service {
+        Service {
+            cfg,
+            upload_nonce_repository,
+        }
+    }
+
+    /// It verifies the signature of a delegated upload request.
+    ///
+    /// # Errors
+    ///
+    /// It returns an error if:
+    ///
+    /// * Signed uploads are not enabled.
+    /// * The timestamp is outside the allowed clock skew.
+    /// * The nonce has already been used.
+    /// * The signature does not match the request.
+    /// * There is a database error.
+    pub async fn verify(
+        &self,
+        upload_signature: &UploadSignature,
+        add_torrent_req: &AddTorrentRequest,
+    ) -> Result<(), ServiceError> {
+        let settings = self.cfg.settings.read().await;
+
+        let Some(signed_uploads) = settings.integrations.as_ref().and_then(|i| i.signed_uploads.clone()) else {
+            return Err(ServiceError::SignedUploadsDisabled);
+        };
+
+        drop(settings);
+
+        let now = clock::now();
+
+        if now.abs_diff(upload_signature.timestamp) > signed_uploads.max_clock_skew_seconds {
+            return Err(ServiceError::ExpiredUploadSignature);
+        }
+
+        if upload_signature.nonce.is_empty() || upload_signature.nonce.len() > MAX_NONCE_LENGTH {
+            return Err(ServiceError::InvalidUploadSignature);
+        }
+
+        let message = string_to_sign(upload_signature, add_torrent_req);
+
+        let Ok(signature) = hex::decode(&upload_signature.signature) else {
+            return Err(ServiceError::InvalidUploadSignature);
+        };
+
+        let mut mac =
+            HmacSha256::new_from_slice(signed_uploads.secret.as_bytes()).map_err(|_| ServiceError::InternalServerError)?;
+
+        mac.update(message.as_bytes());
+
+        mac.verify_slice(&signature)
+            .map_err(|_| ServiceError::InvalidUploadSignature)?;
+
+        // Nonces for expired timestamps can not be replayed anymore.
+        self.upload_nonce_repository.delete_expired(now).await?;
+
+        let date_expiry = upload_signature.timestamp + signed_uploads.max_clock_skew_seconds;
+
+        if !self.upload_nonce_repository.add(&upload_signature.nonce, date_expiry).await? {
+            return Err(ServiceError::UploadSignatureAlreadyUsed);
+        }
+
+        Ok(())
+    }
+}
+
+/// It builds the string the external system has to sign.
+///
+/// # Panics
+///
+/// It panics if the category fields can't be serialized, which can't happen
+/// with string keys and values.
+#[must_use]
+pub fn string_to_sign(upload_signature: &UploadSignature, add_torrent_req: &AddTorrentRequest) -> String {
+    let mut tags = add_torrent_req.tags.clone();
+
+    tags.sort_unstable();
+
+    // The keys of a `BTreeMap` are serialized in ascending order
+    let fields = serde_json::to_string(&add_torrent_req.fields).expect("the category fields should be serialized");
+
+    format!(
+        "{}\n{}\n{}\n{}\n{}\n{}\n{}\n{}\n{}\n{}\n{}",
+        upload_signature.timestamp,
+        upload_signature.nonce,
+        upload_signature.user_id,
+        add_torrent_req.title,
+        add_torrent_req.category_name,
+        hex::encode(Sha256::digest(&add_torrent_req.torrent_buffer)),
+        hex::encode(Sha256::digest(add_torrent_req.description.as_bytes())),
+        tags.iter().map(ToString::to_string).collect::<Vec<_>>().join(","),
+        add_torrent_req
+            .publish_at
+            .map(|publish_at| publish_at.to_rfc3339_opts(SecondsFormat::AutoSi, true))
+            .unwrap_or_default(),
+        add_torrent_req
+            .preserve_announce_urls
+            .as_ref()
+            .map(ToString::to_string)
+            .unwrap_or_default(),
+        hex::encode(Sha256::digest(fields.as_bytes()))
+    )
+}
+
+pub struct DbUploadNonceRepository {
+    database: Arc<Box<dyn Database>>,
+}
+
+impl DbUploadNonceRepository {
+    #[must_use]
+    pub fn new(database: Arc<Box<dyn Database>>) -> Self {
+        Self { database }
+    }
+
+    /// It saves a nonce until it expires. It returns `false` when the nonce
+    /// was already used.
+    ///
+    /// # Errors
+    ///
+    /// It returns an error if there is a database error.
+    pub async fn add(&self, nonce: &str, date_expiry: u64) -> Result<bool, Error> {
+        self.database.add_upload_nonce(nonce, date_expiry).await
+    }
+
+    /// It deletes the nonces expired before `now`.
+    ///
+    /// # Errors
+    ///
+    /// It returns an error if there is a database error.
+    pub async fn delete_expired(&self, now: u64) -> Result<(), Error> {
+        self.database.delete_expired_upload_nonces(now).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::BTreeMap;
+    use std::sync::Arc;
+
+    use chrono::{TimeZone, Utc};
+    use hmac::Mac;
+    use tempfile::TempDir;
+
+    use sha2::{Digest, Sha256};
+
+    use super::{string_to_sign, DbUploadNonceRepository, HmacSha256, Service, UploadSignature};
+    use crate::config::{Configuration, Integrations, Settings, SignedUploads, SigningSecret};
+    use crate::databases::testing::new_sqlite_database;
+    use crate::errors::ServiceError;
+    use crate::services::torrent::AddTorrentRequest;
+    use crate::utils::clock;
+
+    fn configuration_with_signed_uploads() -> Arc<Configuration> {
+        let mut settings = Settings::default();
+
+        settings.integrations = Some(Integrations {
+            signed_uploads: Some(SignedUploads {
+                secret: SigningSecret::new("secret"),
+                max_clock_skew_seconds: 300,
+            }),
+            ..Integrations::default()
+        });
+
+        Arc::new(Configuration::new(settings))
+    }
+
+    async fn service() -> (TempDir, Service) {
+        let (temp_dir, database) = new_sqlite_database().await;
+
+        let service = Service::new(
+            configuration_with_signed_uploads(),
+            Arc::new(DbUploadNonceRepository::new(database)),
+        );
+
+        (temp_dir, service)
+    }
+
+    fn add_torrent_request() -> AddTorrentRequest {
+        AddTorrentRequest {
+            title: "title".to_string(),
+            description: "description".to_string(),
+            category_name: "software".to_string(),
+            tags: vec![2, 1],
+            torrent_buffer: b"torrent".to_vec(),
+            publish_at: None,
+            preserve_announce_urls: None,
+            fields: BTreeMap::new(),
+        }
+    }
+
+    fn signed(timestamp: u64, nonce: &str, add_torrent_req: &AddTorrentRequest) -> UploadSignature {
+        let mut upload_signature = UploadSignature {
+            user_id: 1,
+            timestamp,
+            nonce: nonce.to_string(),
+            signature: String::new(),
+        };
+
+        let mut mac = HmacSha256::new_from_slice(b"secret").unwrap();
+        mac.update(string_to_sign(&upload_signature, add_torrent_req).as_bytes());
+        upload_signature.signature = hex::encode(mac.finalize().into_bytes());
+
+        upload_signature
+    }
+
+    #[test]
+    fn it_should_sign_the_description_hash_and_the_sorted_tags() {
+        let upload_signature = UploadSignature {
+            user_id: 1,
+            timestamp: 1_700_000_000,
+            nonce: "nonce".to_string(),
+            signature: String::new(),
+        };
+
+        let string_to_sign = string_to_sign(&upload_signature, &add_torrent_request());
+
+        let lines: Vec<&str> = string_to_sign.split('\n').collect();
+
+        assert_eq!(lines.len(), 11);
+        assert_eq!(lines[..5], ["1700000000", "nonce", "1", "title", "software"]);
+        // sha256("description")
+        assert_eq!(lines[6], "c9046f7a37ad0ea7cee73355984fa5428982f8b37c8f7bcec91f7ac71a7cd104");
+        assert_eq!(lines[7], "1,2");
+        assert_eq!(lines[8], "");
+        assert_eq!(lines[9], "");
+        // sha256("{}")
+        assert_eq!(lines[10], "44136fa355b3678a1146ad16f7e8649e94fb4fc21fe77e8310c060f61caaff8a");
+    }
+
+    #[test]
+    fn it_should_sign_the_publish_date_the_announce_urls_option_and_the_category_fields() {
+        let upload_signature = UploadSignature {
+            user_id: 1,
+            timestamp: 1_700_000_000,
+            nonce: "nonce".to_string(),
+            signature: String::new(),
+        };
+
+        let mut add_torrent_req = add_torrent_request();
+        add_torrent_req.publish_at = Some(Utc.with_ymd_and_hms(2024, 6, 1, 12, 0, 0).unwrap());
+        add_torrent_req.preserve_announce_urls = Some(false);
+        add_torrent_req.fields.insert("source".to_string(), "web".to_string());
+        add_torrent_req.fields.insert("resolution".to_string(), "1080p".to_string());
+
+        let string_to_sign = string_to_sign(&upload_signature, &add_torrent_req);
+
+        let lines: Vec<&str> = string_to_sign.split('\n').collect();
+
+        assert_eq!(lines[8], "2024-06-01T12:00:00Z");
+        assert_eq!(lines[9], "false");
+        assert_eq!(
+            lines[10],
+            hex::encode(Sha256::digest(br#"{"resolution":"1080p","source":"web"}"#))
+        );
+    }
+
+    #[tokio::test]
+    async fn it_should_accept_a_valid_signature() {
+        let (_temp_dir, service) = service().await;
+        let add_torrent_req = add_torrent_request();
+
+        let upload_signature = signed(clock::now(), "nonce", &add_torrent_req);
+
+        assert_eq!(service.verify(&upload_signature, &add_torrent_req).await, Ok(()));
+    }
+
+    #[tokio::test]
+    async fn it_should_reject_a_replayed_nonce() {
+        let (_temp_dir, service) = service().await;
+        let add_torrent_req = add_torrent_request();
+
+        let upload_signature = signed(clock::now(), "nonce", &add_torrent_req);
+
+        service.verify(&upload_signature, &add_torrent_req).await.unwrap();
+
+        assert_eq!(
+            service.verify(&upload_signature, &add_torrent_req).await,
+            Err(ServiceError::UploadSignatureAlreadyUsed)
+        );
+    }
+
+    #[tokio::test]
+    async fn it_should_reject_a_nonce_replayed_against_another_instance() {
+        let (_temp_dir, database) = new_sqlite_database().await;
+        let add_torrent_req = add_torrent_request();
+
+        let first_instance = Service::new(
+            configuration_with_signed_uploads(),
+            Arc::new(DbUploadNonceRepository::new(database.clone())),
+        );
+        let second_instance = Service::new(
+            configuration_with_signed_uploads(),
+            Arc::new(DbUploadNonceRepository::new(database)),
+        );
+
+        let upload_signature = signed(clock::now(), "nonce", &add_torrent_req);
+
+        first_instance.verify(&upload_signature, &add_torrent_req).await.unwrap();
+
+        assert_eq!(
+            second_instance.verify(&upload_signature, &add_torrent_req).await,
+            Err(ServiceError::UploadSignatureAlreadyUsed)
+        );
+    }
+
+    #[tokio::test]
+    async fn it_should_reject_an_expired_timestamp() {
+        let (_temp_dir, service) = service().await;
+        let add_torrent_req = add_torrent_request();
+
+        let upload_signature = signed(clock::now() - 3600, "nonce", &add_torrent_req);
+
+        assert_eq!(
+            service.verify(&upload_signature, &add_torrent_req).await,
+            Err(ServiceError::ExpiredUploadSignature)
+        );
+    }
+
+    #[tokio::test]
+    async fn it_should_reject_a_tampered_request() {
+        let (_temp_dir, service) = service().await;
+
+        let tamper: [fn(&mut AddTorrentRequest); 6] = [
+            |add_torrent_req| add_torrent_req.title = "another title".to_string(),
+            |add_torrent_req| add_torrent_req.description = "another description".to_string(),
+            |add_torrent_req| add_torrent_req.tags.push(3),
+            |add_torrent_req| add_torrent_req.publish_at = Some(Utc.with_ymd_and_hms(2030, 1, 1, 0, 0, 0).unwrap()),
+            |add_torrent_req| add_torrent_req.preserve_announce_urls = Some(true),
+            |add_torrent_req| {
+                add_torrent_req.fields.insert("source".to_string(), "bluray".to_string());
+            },
+        ];
+
+        for (nonce, tamper) in tamper.iter().enumerate() {
+            let mut add_torrent_req = add_torrent_request();
+
+            let upload_signature = signed(clock::now(), &nonce.to_string(), &add_torrent_req);
+
+            tamper(&mut add_torrent_req);
+
+            assert_eq!(
+                service.verify(&upload_signature, &add_torrent_req).await,
+                Err(ServiceError::InvalidUploadSignature)
+            );
+        }
+    }
+}
@@ -0,0 +1,38 @@
+//! Tracker statistics importer status service.
+use std::sync::Arc;
+
+use super::authorization::{self, ACTION};
+use crate::errors::ServiceError;
+use crate::models::user::UserId;
+use crate::tracker::statistics_importer::{ImporterStatus, StatisticsImporter};
+
+pub struct Service {
+    tracker_statistics_importer: Arc<StatisticsImporter>,
+    authorization_service: Arc<authorization::Service>,
+}
+
+impl Service {
+    #[must_use]
+    pub fn new(
+        tracker_statistics_importer: Arc<StatisticsImporter>,
+        authorization_service: Arc<authorization::Service>,
+    ) -> Service {
+        Service {
+            tracker_statistics_importer,
+            authorization_service,
+        }
+    }
+
+    /// Returns the tracker statistics importer status.
+    ///
+    /// # Errors
+    ///
+    /// It returns an error if the user does not have the required permissions.
+    pub async fn get_status(&self, maybe_user_id: Option<UserId>) -> Result<ImporterStatus, ServiceError> {
+        self.authorization_service
+            .authorize(ACTION::GetImporterStatus, maybe_user_id)
+            .await?;
+
+        Ok(self.tracker_statistics_importer.status())
+    }
+}
@@ -0,0 +1,201 @@
+//! User subscriptions service.
+//!
+//! Users can subscribe to categories and tags to receive a digest email with
+//! the new torrents. Refer to the [`digest`](crate::mailer::digest) module for
+//! more information about the emails.
+use std::sync::Arc;
+
+use super::authorization::{self, ACTION};
+use super::category::DbCategoryRepository;
+use super::tag::DbTagRepository;
+use crate::databases::database::{Database, Error};
+use crate::errors::ServiceError;
+use crate::models::category::CategoryId;
+use crate::models::subscription::{Subscription, SubscriptionId};
+use crate::models::torrent_tag::TagId;
+use crate::models::user::UserId;
+
+pub struct Service {
+    subscription_repository: Arc<DbSubscriptionRepository>,
+    category_repository: Arc<DbCategoryRepository>,
+    tag_repository: Arc<DbTagRepository>,
+    authorization_service: Arc<authorization::Service>,
+}
+
+impl Service {
+    #[must_use]
+    pub fn new(
+        subscription_repository: Arc<DbSubscriptionRepository>,
+        category_repository: Arc<DbCategoryRepository>,
+        tag_repository: Arc<DbTagRepository>,
+        authorization_service: Arc<authorization::Service>,
+    ) -> Service {
+        Service {
+            subscription_repository,
+            category_repository,
+            tag_repository,
+            authorization_service,
+        }
+    }
+
+    /// Returns the subscriptions of the logged-in user.
+    ///
+    /// # Errors
+    ///
+    /// It returns an error if:
+    ///
+    /// * The user does not have the required permissions.
+    /// * There is a database error.
+    pub async fn get_subscriptions(&self, maybe_user_id: Option<UserId>) -> Result<Vec<Subscription>, ServiceError> {
+        let user_id = self.authorize(maybe_user_id).await?;
+
+        Ok(self.subscription_repository.get_by_user(user_id).await?)
+    }
+
+    /// Subscribes the logged-in user to a category or a tag.
+    ///
+    /// # Errors
+    ///
+    /// It returns an error if:
+    ///
+    /// * The user does not have the required permissions.
+    /// * Neither or both the category and the tag are provided.
+    /// * The category or the tag does not exist.
+    /// * The user is already subscribed.
+    /// * There is a database error.
+    pub async fn add_subscription(
+        &self,
+        category_id: Option<CategoryId>,
+        tag_id: Option<TagId>,
+        maybe_user_id: Option<UserId>,
+    ) -> Result<Subscription, ServiceError> {
+        let user_id = self.authorize(maybe_user_id).await?;
+
+        match (category_id, tag_id) {
+            (Some(category_id), None) => {
+                self.category_repository
+                    .get_by_id(&category_id)
+                    .await
+                    .map_err(|_| ServiceError::InvalidCategory)?;
+            }
+            (None, Some(tag_id)) => {
+                let tags = self.tag_repository.get_all().await?;
+                if !tags.iter().any(|tag| tag.tag_id == tag_id) {
+                    return Err(ServiceError::InvalidTag);
+                }
+            }
+            _ => return Err(ServiceError::InvalidSubscription),
+        }
+
+        let subscriptions = self.subscription_repository.get_by_user(user_id).await?;
+
+        if subscriptions
+            .iter()
+            .any(|subscription| subscription.category_id == category_id && subscription.tag_id == tag_id)
+        {
+            return Err(ServiceError::SubscriptionAlreadyExists);
+        }
+
+        let subscription_id = self.subscription_repository.add(user_id, category_id, tag_id).await?;
+
+        Ok(self.subscription_repository.get(subscription_id).await?)
+    }
+
+    /// Deletes a subscription of the logged-in user.
+    ///
+    /// # Errors
+    ///
+    /// It returns an error if:
+    ///
+    /// * The user does not have the required permissions.
+    /// * The subscription does not exist or it belongs to another user.
+    /// * There is a database error.
+    pub async fn delete_subscription(
+        &self,
+        subscription_id: SubscriptionId,
+        maybe_user_id: Option<UserId>,
+    ) -> Result<SubscriptionId, ServiceError> {
+        let user_id = self.authorize(maybe_user_id).await?;
+
+        let subscription = self.subscription_repository.get(subscription_id).await?;
+
+        if subscription.user_id != user_id {
+            return Err(ServiceError::SubscriptionNotFound);
+        }
+
+        self.subscription_repository.delete(subscription_id).await?;
+
+        Ok(subscription_id)
+    }
+
+    async fn authorize(&self, maybe_user_id: Option<UserId>) -> Result<UserId, ServiceError> {
+        self.authorization_service
+            .authorize(ACTION::ManageSubscriptions, maybe_user_id)
+            .await?;
+
+        maybe_user_id.ok_or(ServiceError::UnauthorizedActionForGuests)
+    }
+}
+
+pub struct DbSubscriptionRepository {
+    database: Arc<Box<dyn Database>>,
+}
+
+impl DbSubscriptionRepository {
+    #[must_use]
+    pub fn new(database: Arc<Box<dyn Database>>) -> Self {
+        Self { database }
+    }
+
+    /// It adds a new subscription and returns its ID.
+    ///
+    /// # Errors
+    ///
+    /// It returns an error if there is a database error.
+    pub async fn add(
+        &self,
+        user_id: UserId,
+        category_id: Option<CategoryId>,
+        tag_id: Option<TagId>,
+    ) -> Result<SubscriptionId, Error> {
+        self.database.insert_subscription(user_id, category_id, tag_id).await
+    }
+
+    /// It returns a subscription.
+    ///
+    /// # Errors
+    ///
+    /// It returns an error if the subscription does not exist or there is a
+    /// database error.
+    pub async fn get(&self, subscription_id: SubscriptionId) -> Result<Subscription, Error> {
+        self.database.get_subscription(subscription_id).await
+    }
+
+    /// It returns the subscriptions of a user.
+    ///
+    /// # Errors
+    ///
+    /// It returns an error if there is a database error.
+    pub async fn get_by_user(&self, user_id: UserId) -> Result<Vec<Subscription>, Error> {
+        self.database.get_user_subscriptions(user_id).await
+    }
+
+    /// It returns the subscriptions of all users.
+    ///
+    /// # Errors
+    ///
+    /// It returns an error if there is a database error.
+    pub async fn get_all(&self) -> Result<Vec<Subscription>, Error> {
+        self.database.get_all_subscriptions().await
+    }
+
+    /// It deletes the subscription.
+    ///
+    /// # Errors
+    ///
+    /// It returns an error if the subscription does not exist or there is a
+    /// database error.
+    pub async fn delete(&self, subscription_id: SubscriptionId) -> Result<(), Error> {
+        self.database.delete_subscription(subscription_id).await
+    }
+}
@@ -0,0 +1,414 @@
+//! Delta sync for mirrors.
+//!
+//! Mirrors keep a copy of the approved torrents without downloading a full
+//! dump periodically. The torrents are grouped in partitions of
+//! [`PARTITION_SIZE`] consecutive torrent IDs.
+//!
+//! A background job compares a hash of each torrent with the hash stored in
+//! the last run. Every torrent added, changed or removed from the index (or
+//! not approved anymore) gets a new sequence number. Sequence numbers only
+//! grow, so mirrors:
+//!
+//! 1. Get the manifest with the version of each partition.
+//! 2. Request the partitions whose version changed, with the last sequence
+//!    they have for the partition, and get only the records changed after it.
+//!
+//! The tracker stats are not included because they change too often. The
+//! changes are visible after the next run of the job. Refer to
+//! [`MirrorSync`](crate::config::MirrorSync) for the configuration.
+use std::collections::{HashMap, HashSet};
+use std::sync::Arc;
+
+use serde::Deserialize;
+use sha2::{Digest, Sha256};
+use utoipa::IntoParams;
+
+use super::authorization::{self, ACTION};
+use crate::config::Configuration;
+use crate::databases::database::{Database, Error};
+use crate::errors::ServiceError;
+use crate::models::sync::{
+    PartitionChanges, PartitionId, Sequence, SyncManifest, SyncPartition, SyncPartitionSummary, SyncRecord, SyncState,
+    SyncTorrent,
+};
+use crate::models::torrent::TorrentId;
+use crate::models::user::UserId;
+
+/// Number of consecutive torrent IDs in a partition.
+pub const PARTITION_SIZE: u32 = 1000;
+
+/// Number of torrents read from the database at once.
+const PAGE_SIZE: u32 = 1000;
+
+/// Mirror request to get the changes in a partition.
+#[derive(Debug, Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
+pub struct PartitionRequest {
+    /// Only changes after this sequence. Default to `0`, all the records.
+    pub since: Option<Sequence>,
+}
+
+pub struct Service {
+    configuration: Arc<Configuration>,
+    sync_repository: Arc<DbSyncRepository>,
+    authorization_service: Arc<authorization::Service>,
+}
+
+impl Service {
+    #[must_use]
+    pub fn new(
+        configuration: Arc<Configuration>,
+        sync_repository: Arc<DbSyncRepository>,
+        authorization_service: Arc<authorization::Service>,
+    ) -> Service {
+        Service {
+            configuration,
+            sync_repository,
+            authorization_service,
+        }
+    }
+
+    /// It detects the torrents added, changed or removed since the last run
+    /// and gives them a new sequence number. It returns the number of
+    /// changes.
+    ///
+    /// # Errors
+    ///
+    /// It returns an error if there is a database error.
+    pub async fn refresh(&self) -> Result<usize, ServiceError> {
+        let mut current = Vec::new();
+        let mut after_torrent_id = 0;
+
+        loop {
+            let torrents = self.sync_repository.get_torrents(after_torrent_id, PAGE_SIZE).await?;
+
+            let Some(last) = torrents.last() else {
+                break;
+            };
+
+            after_torrent_id = last.torrent_id;
+
+            current.extend(torrents.into_iter().map(|torrent| {
+                let record = SyncRecord::from(torrent);
+                (record.torrent_id, record_hash(&record))
+            }));
+        }
+
+        let stored = self.sync_repository.get_states().await?;
+
+        let next_sequence = self.sync_repository.get_last_sequence().await? + 1;
+
+        let changes = diff(&current, &stored, next_sequence);
+
+        if !changes.is_empty() {
+            self.sync_repository.save_states(&changes).await?;
+        }
+
+        Ok(changes.len())
+    }
+
+    /// It returns the manifest with the version of each partition.
+    ///
+    /// # Errors
+    ///
+    /// It returns an error if:
+    ///
+    /// * The delta sync is disabled.
+    /// * The user does not have permissions to list the torrents.
+    /// * There is a database error.
+    pub async fn get_manifest(&self, maybe_user_id: Option<UserId>) -> Result<SyncManifest, ServiceError> {
+        self.check_enabled().await?;
+
+        self.authorization_service
+            .authorize(ACTION::GenerateTorrentInfoListing, maybe_user_id)
+            .await?;
+
+        let partitions: Vec<SyncPartition> = self
+            .sync_repository
+            .get_partitions()
+            .await?
+            .into_iter()
+            .map(|summary| SyncPartition {
+                partition_id: summary.partition_id,
+                version: partition_version(summary.partition_id, summary.last_sequence),
+                records: summary.records,
+                last_sequence: summary.last_sequence,
+            })
+            .collect();
+
+        Ok(SyncManifest {
+            partition_size: i64::from(PARTITION_SIZE),
+            last_sequence: partitions.iter().map(|partition| partition.last_sequence).max().unwrap_or(0),
+            partitions,
+        })
+    }
+
+    /// It returns the records of a partition changed after a sequence.
+    ///
+    /// # Errors
+    ///
+    /// It returns an error if:
+    ///
+    /// * The delta sync is disabled.
+    /// * The user does not have permissions to list the torrents.
+    /// * There is a database error.
+    pub async fn get_partition_changes(
+        &self,
+        partition_id: PartitionId,
+        request: &PartitionRequest,
+        maybe_user_id: Option<UserId>,
+    ) -> Result<PartitionChanges, ServiceError> {
+        self.check_enabled().await?;
+
+        self.authorization_service
+            .authorize(ACTION::GenerateTorrentInfoListing, maybe_user_id)
+            .await?;
+
+        let since = request.since.unwrap_or(0);
+
+        let states = self.sync_repository.get_partition_changes(partition_id, since).await?;
+
+        let last_sequence = states.iter().map(|state| state.sequence).max().unwrap_or(since);
+
+        let mut deleted: Vec<TorrentId> = states
+            .iter()
+            .filter(|state| state.deleted)
+            .map(|state| state.torrent_id)
+            .collect();
+
+        let changed: HashSet<TorrentId> = states
+            .iter()
+            .filter(|state| !state.deleted)
+            .map(|state| state.torrent_id)
+            .collect();
+
+        let mut records = Vec::new();
+
+        if !changed.is_empty() {
+            let first_torrent_id = partition_id * i64::from(PARTITION_SIZE);
+
+            let torrents = self
+                .sync_repository
+                .get_torrents(first_torrent_id - 1, PARTITION_SIZE)
+                .await?;
+
+            records = torrents
+                .into_iter()
+                .filter(|torrent| changed.contains(&torrent.torrent_id))
+                .map(SyncRecord::from)
+                .collect();
+        }
+
+        // Torrents removed after the last run of the job
+        let found: HashSet<TorrentId> = records.iter().map(|record| record.torrent_id).collect();
+        deleted.extend(changed.difference(&found));
+        deleted.sort_unstable();
+
+        Ok(PartitionChanges {
+            partition_id,
+            version: partition_version(partition_id, last_sequence),
+            last_sequence,
+            records,
+            deleted,
+        })
+    }
+
+    async fn check_enabled(&self) -> Result<(), ServiceError> {
+        if self.configuration.settings.read().await.mirror_sync.is_none() {
+            return Err(ServiceError::MirrorSyncDisabled);
+        }
+
+        Ok(())
+    }
+}
+
+#[must_use]
+pub fn partition_of(torrent_id: TorrentId) -> PartitionId {
+    torrent_id / i64::from(PARTITION_SIZE)
+}
+
+/// The hash of the record mirrors use to detect changes.
+///
+/// # Panics
+///
+/// It panics if the record can't be serialized.
+#[must_use]
+pub fn record_hash(record: &SyncRecord) -> String {
+    let json = serde_json::to_string(record).expect("sync records should be serializable");
+
+    hex::encode(Sha256::digest(json.as_bytes()))
+}
+
+/// The version of a partition. Sequence numbers are never reused, so the
+/// last sequence in the partition identifies its content.
+fn partition_version(partition_id: PartitionId, last_sequence: Sequence) -> String {
+    hex::encode(&Sha256::digest(format!("{partition_id}:{last_sequence}").as_bytes())[..8])
+}
+
+/// It compares the current hash of the torrents, sorted by ID, with the
+/// stored states. It returns the new states of the torrents that were added,
+/// changed or removed, with consecutive sequence numbers starting at
+/// `next_sequence`.
+fn diff(current: &[(TorrentId, String)], stored: &[SyncState], next_sequence: Sequence) -> Vec<SyncState> {
+    let stored: HashMap<TorrentId, &SyncState> = stored.iter().map(|state| (state.torrent_id, state)).collect();
+
+    let mut changes = Vec::new();
+    let mut sequence = next_sequence;
+
+    for (torrent_id, hash) in current {
+        let unchanged = stored
+            .get(torrent_id)
+            .is_some_and(|state| !state.deleted && state.record_hash == *hash);
+
+        if !unchanged {
+            changes.push(SyncState {
+                torrent_id: *torrent_id,
+                partition_id: partition_of(*torrent_id),
+                record_hash: hash.clone(),
+                sequence,
+                deleted: false,
+            });
+            sequence += 1;
+        }
+    }
+
+    let current: HashSet<TorrentId> = current.iter().map(|(torrent_id, _)| *torrent_id).collect();
+
+    let mut removed: Vec<TorrentId> = stored
+        .values()
+        .filter(|state| !state.deleted && !current.contains(&state.torrent_id))
+        .map(|state| state.torrent_id)
+        .collect();
+
+    removed.sort_unstable();
+
+    for torrent_id in removed {
+        changes.push(SyncState {
+            torrent_id,
+            partition_id: partition_of(torrent_id),
+            record_hash: String::new(),
+            sequence,
+            deleted: true,
+        });
+        sequence += 1;
+    }
+
+    changes
+}
+
+pub struct DbSyncRepository {
+    database: Arc<Box<dyn Database>>,
+}
+
+impl DbSyncRepository {
+    #[must_use]
+    pub fn new(database: Arc<Box<dyn Database>>) -> Self {
+        Self { database }
+    }
+
+    /// It returns up to `limit` approved torrents after a torrent ID.
+    ///
+    /// # Errors
+    ///
+    /// It returns an error if there is a database error.
+    pub async fn get_torrents(&self, after_torrent_id: TorrentId, limit: u32) -> Result<Vec<SyncTorrent>, Error> {
+        self.database.get_sync_torrents(after_torrent_id, limit).await
+    }
+
+    /// It returns the last synced state of all torrents.
+    ///
+    /// # Errors
+    ///
+    /// It returns an error if there is a database error.
+    pub async fn get_states(&self) -> Result<Vec<SyncState>, Error> {
+        self.database.get_sync_states().await
+    }
+
+    /// It returns the sequence of the last change.
+    ///
+    /// # Errors
+    ///
+    /// It returns an error if there is a database error.
+    pub async fn get_last_sequence(&self) -> Result<Sequence, Error> {
+        self.database.get_last_sync_sequence().await
+    }
+
+    /// It saves the new synced states.
+    ///
+    /// # Errors
+    ///
+    /// It returns an error if there is a database error.
+    pub async fn save_states(&self, states: &[SyncState]) -> Result<(), Error> {
+        self.database.upsert_sync_states(states).await
+    }
+
+    /// It returns the summary of all the partitions.
+    ///
+    /// # Errors
+    ///
+    /// It returns an error if there is a database error.
+    pub async fn get_partitions(&self) -> Result<Vec<SyncPartitionSummary>, Error> {
+        self.database.get_sync_partitions().await
+    }
+
+    /// It returns the states of a partition changed after a sequence.
+    ///
+    /// # Errors
+    ///
+    /// It returns an error if there is a database error.
+    pub async fn get_partition_changes(&self, partition_id: PartitionId, since: Sequence) -> Result<Vec<SyncState>, Error> {
+        self.database.get_sync_partition_changes(partition_id, since).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{diff, partition_of};
+    use crate::models::sync::SyncState;
+
+    fn state(torrent_id: i64, record_hash: &str, sequence: i64, deleted: bool) -> SyncState {
+        SyncState {
+            torrent_id,
+            partition_id: partition_of(torrent_id),
+            record_hash: record_hash.to_string(),
+            sequence,
+            deleted,
+        }
+    }
+
+    #[test]
+    fn it_should_group_consecutive_torrent_ids_in_partitions() {
+        assert_eq!(partition_of(1), 0);
+        assert_eq!(partition_of(999), 0);
+        assert_eq!(partition_of(1000), 1);
+    }
+
+    #[test]
+    fn it_should_give_new_sequences_to_added_changed_and_removed_torrents() {
+        let current = vec![(1, "a".to_string()), (2, "b2".to_string()), (4, "d".to_string())];
+        let stored = vec![state(1, "a", 1, false), state(2, "b", 2, false), state(3, "c", 3, false)];
+
+        let changes = diff(&current, &stored, 4);
+
+        assert_eq!(
+            changes,
+            vec![state(2, "b2", 4, false), state(4, "d", 5, false), state(3, "", 6, true)]
+        );
+    }
+
+    #[test]
+    fn it_should_not_change_anything_when_the_torrents_did_not_change() {
+        let current = vec![(1, "a".to_string())];
+        let stored = vec![state(1, "a", 1, false), state(2, "", 2, true)];
+
+        assert!(diff(&current, &stored, 3).is_empty());
+    }
+
+    #[test]
+    fn it_should_add_again_torrents_that_were_removed() {
+        let current = vec![(2, "b".to_string())];
+        let stored = vec![state(2, "", 2, true)];
+
+        assert_eq!(diff(&current, &stored, 3), vec![state(2, "b", 3, false)]);
+    }
+}
@@ -1,23 +1,36 @@
 //! Tag service.
 use std::sync::Arc;
 
+use super::audit;
 use super::authorization::{self, ACTION};
+use crate::cache::query::{self, QueryCache};
 use crate::databases::database::{Database, Error as DatabaseError, Error};
 use crate::errors::ServiceError;
-use crate::models::torrent_tag::{TagId, TorrentTag};
+use crate::models::audit::AuditAction;
+use crate::models::torrent_tag::{TagId, TagSynonym, TorrentTag};
 use crate::models::user::UserId;
+use crate::services::torrent::DbTorrentTagRepository;
 
 pub struct Service {
     tag_repository: Arc<DbTagRepository>,
+    torrent_tag_repository: Arc<DbTorrentTagRepository>,
     authorization_service: Arc<authorization::Service>,
+    audit_service: Arc<audit::Service>,
 }
 
 impl Service {
     #[must_use]
-    pub fn new(tag_repository: Arc<DbTagRepository>, authorization_service: Arc<authorization::Service>) -> Service {
+    pub fn new(
+        tag_repository: Arc<DbTagRepository>,
+        torrent_tag_repository: Arc<DbTorrentTagRepository>,
+        authorization_service: Arc<authorization::Service>,
+        audit_service: Arc<audit::Service>,
+    ) -> Service {
         Service {
             tag_repository,
+            torrent_tag_repository,
             authorization_service,
+            audit_service,
         }
     }
 
@@ -38,8 +51,18 @@ impl Service {
             return Err(ServiceError::TagNameEmpty);
         }
 
+        if self.torrent_tag_repository.get_tag_for_synonym(trimmed_name).await?.is_some() {
+            return Err(ServiceError::TagAlreadyExists);
+        }
+
         match self.tag_repository.add(trimmed_name).await {
-            Ok(id) => Ok(id),
+            Ok(id) => {
+                self.audit_service
+                    .record(maybe_user_id, AuditAction::TagAdd, trimmed_name, None)
+                    .await;
+
+                Ok(id)
+            }
             Err(e) => match e {
                 DatabaseError::TagAlreadyExists => Err(ServiceError::TagAlreadyExists),
                 _ => Err(ServiceError::DatabaseError),
@@ -59,7 +82,13 @@ impl Service {
         self.authorization_service.authorize(ACTION::DeleteTag, maybe_user_id).await?;
 
         match self.tag_repository.delete(tag_id).await {
-            Ok(()) => Ok(()),
+            Ok(()) => {
+                self.audit_service
+                    .record(maybe_user_id, AuditAction::TagDelete, &tag_id.to_string(), None)
+                    .await;
+
+                Ok(())
+            }
             Err(e) => match e {
                 DatabaseError::TagNotFound => Err(ServiceError::TagNotFound),
                 _ => Err(ServiceError::DatabaseError),
@@ -80,16 +109,142 @@ impl Service {
 
         self.tag_repository.get_all().await.map_err(|_| ServiceError::DatabaseError)
     }
+
+    /// Merges the `source` tag into the `target` tag. The torrents with the
+    /// `source` tag get the `target` tag, and the `source` tag is deleted and
+    /// its name kept as a synonym of the `target` tag.
+    ///
+    /// # Errors
+    ///
+    /// It returns an error if:
+    ///
+    /// * The user does not have the required permissions.
+    /// * Both tags are the same or any of them does not exist.
+    /// * There is a database error.
+    pub async fn merge_tags(
+        &self,
+        source_tag_id: TagId,
+        target_tag_id: TagId,
+        maybe_user_id: Option<UserId>,
+    ) -> Result<(), ServiceError> {
+        self.authorization_service.authorize(ACTION::MergeTags, maybe_user_id).await?;
+
+        if source_tag_id == target_tag_id {
+            return Err(ServiceError::CannotMergeTagIntoItself);
+        }
+
+        match self.torrent_tag_repository.merge_tags(source_tag_id, target_tag_id).await {
+            Ok(()) => {
+                self.audit_service
+                    .record(
+                        maybe_user_id,
+                        AuditAction::TagMerge,
+                        &source_tag_id.to_string(),
+                        Some(&format!("merged into tag {target_tag_id}")),
+                    )
+                    .await;
+
+                Ok(())
+            }
+            Err(DatabaseError::TagNotFound) => Err(ServiceError::TagNotFound),
+            Err(_) => Err(ServiceError::DatabaseError),
+        }
+    }
+
+    /// Adds a synonym for a tag. Searches for the synonym find the torrents
+    /// with the tag.
+    ///
+    /// # Errors
+    ///
+    /// It returns an error if:
+    ///
+    /// * The user does not have the required permissions.
+    /// * The synonym is empty or it's already a tag or a synonym.
+    /// * The tag does not exist.
+    /// * There is a database error.
+    pub async fn add_synonym(&self, name: &str, tag_id: TagId, maybe_user_id: Option<UserId>) -> Result<(), ServiceError> {
+        self.authorization_service
+            .authorize(ACTION::ManageTagSynonyms, maybe_user_id)
+            .await?;
+
+        let trimmed_name = name.trim();
+
+        if trimmed_name.is_empty() {
+            return Err(ServiceError::TagNameEmpty);
+        }
+
+        match self.tag_repository.get_by_name(trimmed_name).await {
+            Ok(_) => return Err(ServiceError::TagAlreadyExists),
+            Err(DatabaseError::TagNotFound) => {}
+            Err(_) => return Err(ServiceError::DatabaseError),
+        }
+
+        match self.torrent_tag_repository.add_synonym(trimmed_name, tag_id).await {
+            Ok(()) => {
+                self.audit_service
+                    .record(
+                        maybe_user_id,
+                        AuditAction::TagSynonymAdd,
+                        trimmed_name,
+                        Some(&format!("synonym of tag {tag_id}")),
+                    )
+                    .await;
+
+                Ok(())
+            }
+            Err(DatabaseError::TagAlreadyExists) => Err(ServiceError::TagAlreadyExists),
+            Err(DatabaseError::TagNotFound) => Err(ServiceError::TagNotFound),
+            Err(_) => Err(ServiceError::DatabaseError),
+        }
+    }
+
+    /// Deletes a tag synonym.
+    ///
+    /// # Errors
+    ///
+    /// It returns an error if:
+    ///
+    /// * The user does not have the required permissions.
+    /// * The synonym does not exist.
+    /// * There is a database error.
+    pub async fn delete_synonym(&self, name: &str, maybe_user_id: Option<UserId>) -> Result<(), ServiceError> {
+        self.authorization_service
+            .authorize(ACTION::ManageTagSynonyms, maybe_user_id)
+            .await?;
+
+        self.torrent_tag_repository.delete_synonym(name.trim()).await?;
+
+        self.audit_service
+            .record(maybe_user_id, AuditAction::TagSynonymDelete, name.trim(), None)
+            .await;
+
+        Ok(())
+    }
+
+    /// Returns all the tag synonyms.
+    ///
+    /// # Errors
+    ///
+    /// It returns an error if:
+    ///
+    /// * The user does not have the required permissions.
+    /// * There is a database error.
+    pub async fn get_synonyms(&self, maybe_user_id: Option<UserId>) -> Result<Vec<TagSynonym>, ServiceError> {
+        self.authorization_service.authorize(ACTION::GetTags, maybe_user_id).await?;
+
+        Ok(self.torrent_tag_repository.get_synonyms().await?)
+    }
 }
 
 pub struct DbTagRepository {
     database: Arc<Box<dyn Database>>,
+    query_cache: Arc<QueryCache>,
 }
 
 impl DbTagRepository {
     #[must_use]
-    pub fn new(database: Arc<Box<dyn Database>>) -> Self {
-        Self { database }
+    pub fn new(database: Arc<Box<dyn Database>>, query_cache: Arc<QueryCache>) -> Self {
+        Self { database, query_cache }
     }
 
     /// It adds a new tag and returns the newly created tag.
@@ -98,7 +253,21 @@ impl DbTagRepository {
     ///
     /// It returns an error if there is a database error.
     pub async fn add(&self, tag_name: &str) -> Result<TagId, Error> {
-        self.database.insert_tag_and_get_id(tag_name).await
+        let result = self.database.insert_tag_and_get_id(tag_name).await;
+
+        self.query_cache.invalidate(query::TAGS).await;
+
+        result
+    }
+
+    /// It finds a tag by name.
+    ///
+    /// # Errors
+    ///
+    /// It returns an error if the tag does not exist or there is a database
+    /// error.
+    pub async fn get_by_name(&self, tag_name: &str) -> Result<TorrentTag, Error> {
+        self.database.get_tag_from_name(tag_name).await
     }
 
     /// It returns all the tags.
@@ -107,7 +276,9 @@ impl DbTagRepository {
     ///
     /// It returns an error if there is a database error.
     pub async fn get_all(&self) -> Result<Vec<TorrentTag>, Error> {
-        self.database.get_tags().await
+        self.query_cache
+            .get_or_load(query::TAGS, "all", || self.database.get_tags())
+            .await
     }
 
     /// It removes a tag and returns it.
@@ -116,6 +287,10 @@ impl DbTagRepository {
     ///
     /// It returns an error if there is a database error.
     pub async fn delete(&self, tag_id: &TagId) -> Result<(), Error> {
-        self.database.delete_tag(*tag_id).await
+        let result = self.database.delete_tag(*tag_id).await;
+
+        self.query_cache.invalidate(query::TAGS).await;
+
+        result
     }
 }
@@ -0,0 +1,216 @@
+//! Services for the unit tests.
+use std::sync::{Arc, Mutex};
+
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::net::TcpListener;
+use url::Url;
+
+use super::antispam::{self, DbBannedPatternRepository};
+use super::audit::{self, DbAuditLogRepository};
+use super::authorization::{self, CasbinEnforcer};
+use super::bookmark::{self, DbBookmarkRepository};
+use super::category::{DbCategoryModeratorRepository, DbCategoryRepository};
+use super::category_field::DbCategoryFieldRepository;
+use super::domain_events::DbDomainEventRepository;
+use super::enrichment::DbTorrentMetadataRepository;
+use super::live_events::Broadcaster;
+use super::ratio::{self, DbTransferStatsRepository};
+use super::reputation::{self, DbReputationRepository};
+use super::saved_search::DbSavedSearchRepository;
+use super::search;
+use super::torrent::deduplication::{self, DbTorrentDuplicateRepository};
+use super::torrent::file_group::DbTorrentFileGroupRepository;
+use super::torrent::preview::DbTorrentPreviewRepository;
+use super::torrent::quota::{self, DbUploadQuotaRepository};
+use super::torrent::trash::{self, DbTorrentTrashRepository};
+use super::torrent::{
+    self, DbCanonicalInfoHashGroupRepository, DbMagnetUploadRepository, DbTorrentAnnounceUrlRepository, DbTorrentFileRepository,
+    DbTorrentInfoRepository, DbTorrentListingGenerator, DbTorrentRepository, DbTorrentTagRepository,
+};
+use super::user::{CachedUserRepository, DbUserRepository, Repository};
+use crate::cache::query::QueryCache;
+use crate::config::Configuration;
+use crate::databases::database::Database;
+use crate::storage::torrent_file;
+use crate::tracker;
+use crate::tracker::statistics_importer::StatisticsImporter;
+
+/// The services wired like in the application.
+pub struct Services {
+    pub torrent_index: torrent::Index,
+    pub bookmark_service: bookmark::Service,
+    pub trash_service: trash::Service,
+    pub reputation_service: Arc<reputation::Service>,
+    pub ratio_service: Arc<ratio::Service>,
+}
+
+/// It builds the services using the database. External services, like the
+/// tracker, are the ones in the configuration.
+pub async fn services(configuration: Arc<Configuration>, database: Arc<Box<dyn Database>>) -> Services {
+    let storage = configuration.settings.read().await.storage.clone().unwrap_or_default();
+
+    let query_cache = Arc::new(QueryCache::new(None));
+    let torrent_file_store = torrent_file::from_config(&storage, database.clone());
+
+    let user_repository: Arc<Box<dyn Repository>> = Arc::new(Box::new(CachedUserRepository::new(Box::new(
+        DbUserRepository::new(database.clone()),
+    ))));
+    let category_repository = Arc::new(DbCategoryRepository::new(database.clone(), query_cache.clone()));
+    let torrent_repository = Arc::new(DbTorrentRepository::new(database.clone(), torrent_file_store.clone()));
+    let torrent_listing_generator = Arc::new(DbTorrentListingGenerator::new(database.clone(), query_cache));
+
+    let authorization_service = Arc::new(authorization::Service::new(
+        user_repository.clone(),
+        Arc::new(DbCategoryModeratorRepository::new(database.clone())),
+        Arc::new(CasbinEnforcer::with_default_configuration().await),
+    ));
+    let audit_service = Arc::new(audit::Service::new(
+        Arc::new(DbAuditLogRepository::new(database.clone())),
+        authorization_service.clone(),
+    ));
+    let tracker_service = Arc::new(tracker::service::Service::new(configuration.clone(), database.clone()).await);
+    let search_service = Arc::new(search::Service::new(configuration.clone(), database.clone()).await);
+    let reputation_service = Arc::new(reputation::Service::new(
+        configuration.clone(),
+        Arc::new(DbReputationRepository::new(database.clone())),
+        authorization_service.clone(),
+        audit_service.clone(),
+    ));
+    let ratio_service = Arc::new(ratio::Service::new(
+        configuration.clone(),
+        tracker_service.clone(),
+        Arc::new(DbTransferStatsRepository::new(database.clone())),
+    ));
+
+    let bookmark_service = bookmark::Service::new(
+        configuration.clone(),
+        Arc::new(DbBookmarkRepository::new(database.clone())),
+        Arc::new(DbSavedSearchRepository::new(database.clone())),
+        torrent_listing_generator.clone(),
+        authorization_service.clone(),
+    );
+
+    let torrent_index = torrent::Index::new(
+        configuration.clone(),
+        Arc::new(StatisticsImporter::new(configuration.clone(), tracker_service.clone(), database.clone()).await),
+        tracker_service.clone(),
+        user_repository,
+        category_repository,
+        torrent_repository.clone(),
+        Arc::new(DbCanonicalInfoHashGroupRepository::new(database.clone())),
+        Arc::new(DbTorrentInfoRepository::new(database.clone())),
+        Arc::new(DbTorrentFileRepository::new(database.clone())),
+        Arc::new(DbTorrentAnnounceUrlRepository::new(database.clone())),
+        Arc::new(DbTorrentTagRepository::new(database.clone())),
+        torrent_listing_generator.clone(),
+        authorization_service.clone(),
+        search_service.clone(),
+        Arc::new(DbDomainEventRepository::new(database.clone())),
+        Arc::new(DbBookmarkRepository::new(database.clone())),
+        Arc::new(DbMagnetUploadRepository::new(database.clone())),
+        Arc::new(DbTorrentPreviewRepository::new(database.clone())),
+        Arc::new(DbTorrentFileGroupRepository::new(database.clone())),
+        Arc::new(quota::Service::new(
+            configuration.clone(),
+            Arc::new(DbUploadQuotaRepository::new(database.clone())),
+            authorization_service.clone(),
+        )),
+        Arc::new(Broadcaster::default()),
+        audit_service.clone(),
+        Arc::new(deduplication::Service::new(
+            configuration.clone(),
+            Arc::new(DbTorrentDuplicateRepository::new(database.clone(), torrent_file_store)),
+            authorization_service.clone(),
+        )),
+        Arc::new(DbTorrentTrashRepository::new(database.clone())),
+        Arc::new(antispam::Service::new(
+            configuration.clone(),
+            Arc::new(DbBannedPatternRepository::new(database.clone())),
+            authorization_service.clone(),
+            audit_service.clone(),
+        )),
+        Arc::new(DbTorrentMetadataRepository::new(database.clone())),
+        Arc::new(DbCategoryFieldRepository::new(database.clone())),
+        reputation_service.clone(),
+        ratio_service.clone(),
+    );
+
+    let trash_service = trash::Service::new(
+        configuration,
+        Arc::new(DbTorrentTrashRepository::new(database)),
+        torrent_repository,
+        torrent_listing_generator,
+        tracker_service,
+        search_service,
+        authorization_service,
+        audit_service,
+    );
+
+    Services {
+        torrent_index,
+        bookmark_service,
+        trash_service,
+        reputation_service,
+        ratio_service,
+    }
+}
+
+/// A fake tracker API that accepts all the requests and records them.
+pub struct FakeTrackerApi {
+    pub url: Url,
+    requests: Arc<Mutex<Vec<String>>>,
+}
+
+impl FakeTrackerApi {
+    /// It starts the fake tracker API on a random port.
+    ///
+    /// # Panics
+    ///
+    /// It panics if the port can't be bound.
+    pub async fn start() -> Self {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let url = Url::parse(&format!("http://{}", listener.local_addr().unwrap())).unwrap();
+        let requests = Arc::new(Mutex::new(vec![]));
+
+        let recorded_requests = requests.clone();
+
+        tokio::spawn(async move {
+            loop {
+                let Ok((mut stream, _)) = listener.accept().await else {
+                    return;
+                };
+
+                let mut request = [0; 4096];
+                let Ok(request_length) = stream.read(&mut request).await else {
+                    continue;
+                };
+
+                // Only the method and the path, without the token in the query
+                let request_line = String::from_utf8_lossy(&request[..request_length])
+                    .split(['?', ' '])
+                    .take(2)
+                    .collect::<Vec<_>>()
+                    .join(" ");
+
+                recorded_requests.lock().unwrap().push(request_line);
+
+                let _ = stream
+                    .write_all(b"HTTP/1.1 200 OK\r\ncontent-length: 0\r\nconnection: close\r\n\r\n")
+                    .await;
+            }
+        });
+
+        Self { url, requests }
+    }
+
+    /// The method and path of the requests received, like
+    /// `DELETE /api/v1/whitelist/<info-hash>`.
+    ///
+    /// # Panics
+    ///
+    /// It panics if the lock is poisoned.
+    #[must_use]
+    pub fn requests(&self) -> Vec<String> {
+        self.requests.lock().unwrap().clone()
+    }
+}
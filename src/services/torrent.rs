@@ -1,25 +1,71 @@
 //! Torrent service.
+pub mod attachment;
+pub mod contents;
+pub mod deduplication;
+pub mod description_image;
+pub mod file_group;
+pub mod health;
+pub mod magnet;
+pub mod preview;
+pub mod quota;
+pub mod similar_titles;
+pub mod trash;
+pub mod web_seed;
+
+use std::collections::{BTreeMap, HashSet};
+use std::net::{IpAddr, SocketAddr};
+use std::str::FromStr;
+use std::sync::atomic::{AtomicU64, Ordering};
 use std::sync::Arc;
+use std::time::Duration;
 
 use bittorrent_primitives::info_hash::InfoHash;
+use chrono::{DateTime, NaiveDate, NaiveTime, Utc};
 use serde_derive::{Deserialize, Serialize};
-use tracing::debug;
+use sha2::{Digest, Sha256};
+use tracing::{debug, error, info};
 use url::Url;
-
+use utoipa::{IntoParams, ToSchema};
+
+use self::file_group::DbTorrentFileGroupRepository;
+use self::magnet::{torrent_file_from_info_dictionary, MagnetLink, MetadataFetcher};
+use self::preview::DbTorrentPreviewRepository;
+use self::trash::DbTorrentTrashRepository;
+use super::antispam::{self, Verdict};
+use super::audit;
 use super::authorization::{self, ACTION};
+use super::bookmark::DbBookmarkRepository;
 use super::category::DbCategoryRepository;
-use crate::config::Configuration;
+use super::category_field::DbCategoryFieldRepository;
+use super::domain_events::DbDomainEventRepository;
+use super::enrichment::DbTorrentMetadataRepository;
+use super::live_events::{Broadcaster, LiveEvent};
+use super::{ratio, reputation, search, torrent_structure, upload_policy};
+use crate::cache::query::{self, QueryCache};
+use crate::config::{Configuration, TorrentDownload, TorrentFilesBackend};
 use crate::databases::database::{Database, Error, Sorting};
 use crate::errors::ServiceError;
+use crate::models::audit::AuditAction;
 use crate::models::category::CategoryId;
-use crate::models::response::{DeletedTorrentResponse, TorrentResponse, TorrentsResponse};
-use crate::models::torrent::{Metadata, TorrentId, TorrentListing};
+use crate::models::domain_event::{DomainEvent, TorrentUploaded};
+use crate::models::magnet_upload::{MagnetUpload, MagnetUploadId, MagnetUploadStatus};
+use crate::models::response::{
+    DeletedTorrentResponse, MagnetLinkResponse, TorrentPeer, TorrentPeersResponse, TorrentResponse, TorrentsResponse,
+};
+use crate::models::torrent::{
+    BulkAction, ListingFilter, Metadata, ScheduledRelease, SimilarTorrent, TorrentId, TorrentListing, TorrentStatus,
+};
 use crate::models::torrent_file::{DbTorrent, Torrent, TorrentFile};
-use crate::models::torrent_tag::{TagId, TorrentTag};
+use crate::models::torrent_tag::{TagId, TagSynonym, TorrentTag};
 use crate::models::user::UserId;
 use crate::services::user::Repository;
+use crate::storage::torrent_file::{self, TorrentFileStore};
+use crate::tracker::service::TrackerAPIError;
 use crate::tracker::statistics_importer::StatisticsImporter;
+use crate::utils::clock::{datetime_now, DATETIME_FORMAT};
 use crate::utils::parse_torrent::decode_and_validate_torrent_file;
+use crate::utils::qr_code;
+use crate::utils::redaction::REDACTED;
 use crate::{tracker, AsCSV};
 
 pub struct Index {
@@ -36,24 +82,129 @@ pub struct Index {
     torrent_tag_repository: Arc<DbTorrentTagRepository>,
     torrent_listing_generator: Arc<DbTorrentListingGenerator>,
     authorization_service: Arc<authorization::Service>,
+    search_service: Arc<search::Service>,
+    domain_event_repository: Arc<DbDomainEventRepository>,
+    bookmark_repository: Arc<DbBookmarkRepository>,
+    magnet_upload_repository: Arc<DbMagnetUploadRepository>,
+    torrent_preview_repository: Arc<DbTorrentPreviewRepository>,
+    torrent_file_group_repository: Arc<DbTorrentFileGroupRepository>,
+    upload_quota_service: Arc<quota::Service>,
+    live_events: Arc<Broadcaster>,
+    audit_service: Arc<audit::Service>,
+    duplicate_service: Arc<deduplication::Service>,
+    torrent_trash_repository: Arc<DbTorrentTrashRepository>,
+    antispam_service: Arc<antispam::Service>,
+    torrent_metadata_repository: Arc<DbTorrentMetadataRepository>,
+    category_field_repository: Arc<DbCategoryFieldRepository>,
+    reputation_service: Arc<reputation::Service>,
+    ratio_service: Arc<ratio::Service>,
 }
 
+/// Max number of magnet uploads resolved in each run of the resolver.
+const MAX_MAGNET_UPLOADS_PER_RUN: u32 = 10;
+
+/// Max number of scheduled torrents released in each run of the scheduler.
+const MAX_SCHEDULED_RELEASES_PER_RUN: u32 = 100;
+
+/// Max number of torrents with a similar size compared with an uploaded
+/// torrent looking for similar titles.
+const MAX_TORRENTS_COMPARED_BY_TITLE: u32 = 500;
+
+/// Number of staff members who must approve a torrent in a category that
+/// requires co-signing.
+pub const REQUIRED_CO_SIGNATURES: u64 = 2;
+
+/// Max number of torrents in a bulk action.
+pub const MAX_BULK_ACTION_TORRENTS: usize = 1000;
+
 pub struct AddTorrentRequest {
     pub title: String,
     pub description: String,
     pub category_name: String,
     pub tags: Vec<TagId>,
     pub torrent_buffer: Vec<u8>,
+    /// When set, the torrent is hidden until this date.
+    pub publish_at: Option<DateTime<Utc>>,
+    /// Keep the original trackers after the index tracker, so the torrent can
+    /// be cross-seeded. Default to the `torrent.announce_urls.preserve`
+    /// option.
+    pub preserve_announce_urls: Option<bool>,
+    /// The values of the category fields, by field name.
+    pub fields: BTreeMap<String, String>,
 }
 
 pub struct AddTorrentResponse {
     pub torrent_id: TorrentId,
     pub canonical_info_hash: String,
     pub info_hash: String,
+    /// Existing torrents that could be the same release.
+    pub similar_torrents: Vec<SimilarTorrent>,
+}
+
+pub struct AddMagnetRequest {
+    pub magnet_uri: String,
+    /// When empty, the display name (`dn`) in the magnet link is used.
+    pub title: String,
+    pub description: String,
+    pub category_name: String,
+    pub tags: Vec<TagId>,
+}
+
+/// Moderation actions that can be applied to many torrents at once.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum BulkActionKind {
+    /// Move the torrents to the trash bin.
+    Delete,
+    /// Move the torrents to the `category_id`.
+    Recategorize,
+    /// Add the `tag_id` to the torrents.
+    AddTag,
+    /// Remove the `tag_id` from the torrents.
+    RemoveTag,
+    Approve,
+}
+
+/// Request to apply a moderation action to many torrents.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+pub struct BulkActionRequest {
+    pub action: BulkActionKind,
+    pub info_hashes: Vec<String>,
+    /// The new category for the `recategorize` action.
+    pub category_id: Option<CategoryId>,
+    /// The tag for the `add_tag` and `remove_tag` actions.
+    pub tag_id: Option<TagId>,
+    /// When `true`, it only returns the torrents the action would be applied
+    /// to.
+    #[serde(default)]
+    pub dry_run: bool,
+}
+
+/// The result of a bulk action.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct BulkActionSummary {
+    pub action: BulkActionKind,
+    pub dry_run: bool,
+    /// The torrents the action was applied to, or would be in a dry run.
+    pub torrents: Vec<BulkActionTorrent>,
+    /// Info-hashes of the torrents the action does not change, like an
+    /// approved torrent for the `approve` action, or that can't be changed
+    /// in bulk, like the torrents that must be co-signed.
+    pub skipped: Vec<String>,
+    /// Info-hashes that are not valid or don't belong to any torrent.
+    pub not_found: Vec<String>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct BulkActionTorrent {
+    pub torrent_id: TorrentId,
+    pub info_hash: String,
+    pub title: String,
 }
 
 /// User request to generate a torrent listing.
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Default, Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
 pub struct ListingRequest {
     pub page_size: Option<u8>,
     pub page: Option<u32>,
@@ -63,6 +214,39 @@ pub struct ListingRequest {
     /// Expects comma separated string, eg: "?tags=Linux,Ubuntu"
     pub tags: Option<String>,
     pub search: Option<String>,
+    /// Secondary sorting for the torrents with the same value in the
+    /// `sort` field, eg: "?sort=SeedersDesc&then_sort=UploadedDesc"
+    pub then_sort: Option<Sorting>,
+    /// Minimum total size in bytes.
+    pub min_size: Option<i64>,
+    /// Maximum total size in bytes.
+    pub max_size: Option<i64>,
+    /// Expects a date, eg: "?uploaded_after=2024-10-01". The day is included.
+    pub uploaded_after: Option<String>,
+    /// Expects a date, eg: "?uploaded_before=2024-10-31". The day is not
+    /// included.
+    pub uploaded_before: Option<String>,
+    pub min_seeders: Option<i64>,
+    /// Expects an extension with or without the dot, eg: "?file_ext=mkv"
+    pub file_ext: Option<String>,
+    /// Expects comma separated `name:value` pairs of category fields, eg:
+    /// "?fields=resolution:1080p,codec:x265"
+    pub fields: Option<String>,
+}
+
+impl ListingRequest {
+    /// Whether the request has any of the size, date, seeders or file
+    /// extension filters.
+    #[must_use]
+    pub fn has_filters(&self) -> bool {
+        self.min_size.is_some()
+            || self.max_size.is_some()
+            || self.uploaded_after.is_some()
+            || self.uploaded_before.is_some()
+            || self.min_seeders.is_some()
+            || self.file_ext.is_some()
+            || self.fields.is_some()
+    }
 }
 
 /// Internal specification for torrent listings.
@@ -71,7 +255,13 @@ pub struct ListingSpecification {
     pub search: Option<String>,
     pub categories: Option<Vec<String>>,
     pub tags: Option<Vec<String>>,
+    /// Only these torrents are listed, for example the ones found in the
+    /// search index. The `Relevance` sorting keeps this order.
+    pub torrent_ids: Option<Vec<TorrentId>>,
+    pub status: TorrentStatus,
+    pub filter: ListingFilter,
     pub sort: Sorting,
+    pub then_sort: Option<Sorting>,
     pub offset: u64,
     pub page_size: u8,
 }
@@ -93,6 +283,22 @@ impl Index {
         torrent_tag_repository: Arc<DbTorrentTagRepository>,
         torrent_listing_repository: Arc<DbTorrentListingGenerator>,
         authorization_service: Arc<authorization::Service>,
+        search_service: Arc<search::Service>,
+        domain_event_repository: Arc<DbDomainEventRepository>,
+        bookmark_repository: Arc<DbBookmarkRepository>,
+        magnet_upload_repository: Arc<DbMagnetUploadRepository>,
+        torrent_preview_repository: Arc<DbTorrentPreviewRepository>,
+        torrent_file_group_repository: Arc<DbTorrentFileGroupRepository>,
+        upload_quota_service: Arc<quota::Service>,
+        live_events: Arc<Broadcaster>,
+        audit_service: Arc<audit::Service>,
+        duplicate_service: Arc<deduplication::Service>,
+        torrent_trash_repository: Arc<DbTorrentTrashRepository>,
+        antispam_service: Arc<antispam::Service>,
+        torrent_metadata_repository: Arc<DbTorrentMetadataRepository>,
+        category_field_repository: Arc<DbCategoryFieldRepository>,
+        reputation_service: Arc<reputation::Service>,
+        ratio_service: Arc<ratio::Service>,
     ) -> Self {
         Self {
             configuration,
@@ -108,6 +314,22 @@ impl Index {
             torrent_tag_repository,
             torrent_listing_generator: torrent_listing_repository,
             authorization_service,
+            search_service,
+            domain_event_repository,
+            bookmark_repository,
+            magnet_upload_repository,
+            torrent_preview_repository,
+            torrent_file_group_repository,
+            upload_quota_service,
+            live_events,
+            audit_service,
+            duplicate_service,
+            torrent_trash_repository,
+            antispam_service,
+            torrent_metadata_repository,
+            category_field_repository,
+            reputation_service,
+            ratio_service,
         }
     }
 
@@ -123,6 +345,14 @@ impl Index {
     /// * Unable to insert the torrent into the database.
     /// * Unable to add the torrent to the whitelist.
     /// * Torrent title is too short.
+    /// * The torrent does not comply with the `[torrent.structure]` policies.
+    /// * The torrent does not comply with the `[upload_policy]` policies.
+    /// * The user has exceeded their upload quota.
+    /// * The torrent contents are already in another torrent and duplicates
+    ///   are rejected.
+    /// * The category field values are not valid or a required one is empty.
+    /// * The title or the description match a banned pattern which rejects
+    ///   uploads.
     ///
     /// # Panics
     ///
@@ -144,18 +374,74 @@ impl Index {
 
         let metadata = self.validate_and_build_metadata(&add_torrent_req).await?;
 
+        let field_values = self
+            .category_field_repository
+            .validate_values(metadata.category_id, &add_torrent_req.fields)
+            .await?;
+
+        let verdict = self
+            .antispam_service
+            .check_upload(&metadata.title, &metadata.description)
+            .await?;
+
+        if add_torrent_req.publish_at.is_some_and(|publish_at| publish_at <= Utc::now()) {
+            return Err(ServiceError::PublishDateInThePast);
+        }
+
         let (mut torrent, original_info_hash) = decode_and_validate_torrent_file(&add_torrent_req.torrent_buffer)?;
 
-        self.customize_announcement_info_for(&mut torrent).await;
+        self.validate_upload_policy(&torrent).await?;
+
+        self.upload_quota_service
+            .check_upload(user_id, Some(u64::try_from(torrent.file_size()).unwrap_or_default()))
+            .await?;
+
+        self.validate_torrent_structure(&torrent, &add_torrent_req.category_name)
+            .await?;
+
+        self.customize_announcement_info_for(&mut torrent, add_torrent_req.preserve_announce_urls)
+            .await;
 
         self.canonical_info_hash_group_checks(&original_info_hash, &torrent.canonical_info_hash())
             .await?;
 
+        let duplicates = self.duplicate_service.check_upload(&torrent).await?;
+
+        let status = match verdict {
+            Verdict::Suspicious(reason) => {
+                info!("Upload \"{}\" held for moderation: {reason}", metadata.title);
+                TorrentStatus::Pending
+            }
+            Verdict::Clean => self.initial_status_for_upload(maybe_user_id, metadata.category_id).await,
+        };
+
+        // Scheduled torrents get their initial status when they are released
+        let is_scheduled = add_torrent_req.publish_at.is_some();
+        let upload_status = if is_scheduled { TorrentStatus::Scheduled } else { status };
+
         let torrent_id = self
             .torrent_repository
-            .add(&original_info_hash, &torrent, &metadata, user_id)
+            .add(&original_info_hash, &torrent, &metadata, user_id, &upload_status)
             .await?;
 
+        if let Some(publish_at) = add_torrent_req.publish_at {
+            if let Err(e) = self
+                .torrent_repository
+                .schedule_release(&torrent_id, &publish_at.format(DATETIME_FORMAT).to_string(), &status)
+                .await
+            {
+                drop(self.torrent_repository.delete(&torrent_id).await);
+                return Err(e.into());
+            }
+        }
+
+        if !field_values.is_empty() {
+            if let Err(e) = self.category_field_repository.set_values(torrent_id, &field_values).await {
+                drop(self.torrent_repository.delete(&torrent_id).await);
+                return Err(e.into());
+            }
+        }
+
         // Synchronous secondary tasks
 
         // code-review: consider moving this to a background task
@@ -168,25 +454,490 @@ impl Index {
         //
         // code-review: maybe we should consider adding a new feature to
         // whitelist  all torrents from the admin panel if that change happens.
-        if let Err(e) = self
-            .tracker_service
-            .whitelist_info_hash(torrent.canonical_info_hash_hex())
-            .await
-        {
-            // If the torrent can't be whitelisted somehow, remove the torrent from database
-            drop(self.torrent_repository.delete(&torrent_id).await);
-            return Err(e.into());
+        //
+        // Scheduled torrents are whitelisted when they are released.
+        if !is_scheduled {
+            if let Err(e) = self
+                .tracker_service
+                .whitelist_info_hash(torrent.canonical_info_hash_hex())
+                .await
+            {
+                // If the torrent can't be whitelisted somehow, remove the torrent from database
+                drop(self.torrent_repository.delete(&torrent_id).await);
+                return Err(e.into());
+            }
+
+            self.search_service.index_torrent(torrent_id).await;
+        }
+
+        self.duplicate_service.flag(torrent_id, &duplicates).await;
+
+        // Pending torrents are announced when they are approved, and
+        // scheduled torrents when they are released
+        if let Some(publish_at) = add_torrent_req.publish_at {
+            info!("Torrent #{torrent_id} scheduled for release at {publish_at}");
+        } else if status == TorrentStatus::Approved {
+            self.publish_torrent_uploaded(torrent_id).await;
+        } else {
+            self.live_events.publish(LiveEvent::TorrentPending {
+                torrent_id,
+                info_hash: torrent.canonical_info_hash_hex(),
+                title: metadata.title.clone(),
+            });
         }
 
+        let similar_torrents = self
+            .find_similar_torrents(torrent_id, &metadata.title, torrent.file_size())
+            .await;
+
         // Build response
 
         Ok(AddTorrentResponse {
             torrent_id,
             canonical_info_hash: torrent.canonical_info_hash_hex(),
             info_hash: original_info_hash.to_string(),
+            similar_torrents,
         })
     }
 
+    /// Adds a torrent from a magnet link.
+    ///
+    /// The torrent is not added to the index immediately. The magnet upload
+    /// gets the `resolving` status until the info dictionary is fetched from
+    /// peers by the magnet resolver cronjob.
+    ///
+    /// # Errors
+    ///
+    /// This function will return an error if:
+    ///
+    /// * Magnet uploads are not enabled.
+    /// * The user is not allowed to upload torrents.
+    /// * The magnet link does not contain a `BitTorrent` v1 info-hash.
+    /// * The title, description, category or tags are not valid.
+    /// * The category has required fields.
+    /// * The title or the description match a banned pattern which rejects
+    ///   uploads.
+    /// * The torrent is already in the index or being resolved.
+    /// * There is a database error.
+    pub async fn add_magnet_upload(
+        &self,
+        add_magnet_req: AddMagnetRequest,
+        maybe_user_id: Option<UserId>,
+    ) -> Result<MagnetUpload, ServiceError> {
+        let Some(user_id) = maybe_user_id else {
+            return Err(ServiceError::UnauthorizedActionForGuests);
+        };
+
+        if self.magnet_uploads_settings().await.is_none() {
+            return Err(ServiceError::MagnetUploadsDisabled);
+        }
+
+        self.authorization_service
+            .authorize(ACTION::AddTorrent, maybe_user_id)
+            .await?;
+
+        self.upload_quota_service.check_upload(user_id, None).await?;
+
+        let magnet_link = MagnetLink::parse(&add_magnet_req.magnet_uri).map_err(|_| ServiceError::InvalidMagnetLink)?;
+
+        let title = if add_magnet_req.title.trim().is_empty() {
+            magnet_link.display_name.clone().unwrap_or_default()
+        } else {
+            add_magnet_req.title
+        };
+
+        let metadata = self
+            .validate_and_build_metadata(&AddTorrentRequest {
+                title,
+                description: add_magnet_req.description,
+                category_name: add_magnet_req.category_name,
+                tags: add_magnet_req.tags,
+                torrent_buffer: vec![],
+                publish_at: None,
+                preserve_announce_urls: None,
+                fields: BTreeMap::new(),
+            })
+            .await?;
+
+        // Magnet uploads can't fill the category fields, so they are rejected
+        // in categories with required fields
+        self.category_field_repository
+            .validate_values(metadata.category_id, &BTreeMap::new())
+            .await?;
+
+        // Suspicious uploads are held for moderation when the magnet link is
+        // resolved
+        self.antispam_service
+            .check_upload(&metadata.title, &metadata.description)
+            .await?;
+
+        if self.torrent_repository.get_by_info_hash(&magnet_link.info_hash).await.is_ok() {
+            return Err(ServiceError::OriginalInfoHashAlreadyExists);
+        }
+
+        if self.magnet_upload_repository.is_resolving(&magnet_link.info_hash).await? {
+            return Err(ServiceError::MagnetUploadAlreadyResolving);
+        }
+
+        let magnet_upload_id = self
+            .magnet_upload_repository
+            .add(user_id, &magnet_link.info_hash, &add_magnet_req.magnet_uri, &metadata)
+            .await?;
+
+        Ok(self.magnet_upload_repository.get(magnet_upload_id).await?)
+    }
+
+    /// Returns a magnet upload. Only the uploader and moderators can see it.
+    ///
+    /// # Errors
+    ///
+    /// This function will return an error if:
+    ///
+    /// * The magnet upload does not exist.
+    /// * The user is not the uploader and is not allowed to moderate torrents.
+    pub async fn get_magnet_upload(
+        &self,
+        magnet_upload_id: MagnetUploadId,
+        maybe_user_id: Option<UserId>,
+    ) -> Result<MagnetUpload, ServiceError> {
+        let magnet_upload = self.magnet_upload_repository.get(magnet_upload_id).await?;
+
+        if maybe_user_id != Some(magnet_upload.user_id) {
+            self.authorization_service
+                .authorize_in_category(ACTION::ModerateTorrent, maybe_user_id, Some(magnet_upload.category_id))
+                .await?;
+        }
+
+        Ok(magnet_upload)
+    }
+
+    /// Tries to fetch the info dictionary of the magnet uploads being
+    /// resolved. Resolved magnet uploads are added to the index as if the
+    /// uploader had uploaded the torrent file.
+    ///
+    /// Errors are only logged. Uploads that could not be resolved are
+    /// retried in the next run, up to the max number of attempts.
+    pub async fn resolve_magnet_uploads(&self) {
+        let Some(settings) = self.magnet_uploads_settings().await else {
+            return;
+        };
+
+        let magnet_uploads = match self.magnet_upload_repository.get_resolving(MAX_MAGNET_UPLOADS_PER_RUN).await {
+            Ok(magnet_uploads) => magnet_uploads,
+            Err(e) => {
+                error!("Failed to get the magnet uploads being resolved: {e:?}");
+                return;
+            }
+        };
+
+        let fetcher = MetadataFetcher::new(
+            Duration::from_secs(settings.timeout_seconds),
+            settings.max_peers,
+            settings.max_metadata_size,
+        );
+
+        for magnet_upload in magnet_uploads {
+            self.resolve_magnet_upload(&fetcher, &magnet_upload, settings.max_attempts)
+                .await;
+        }
+    }
+
+    async fn resolve_magnet_upload(&self, fetcher: &MetadataFetcher, magnet_upload: &MagnetUpload, max_attempts: u32) {
+        let magnet_upload_id = magnet_upload.magnet_upload_id;
+
+        let Ok(magnet_link) = MagnetLink::parse(&magnet_upload.magnet_uri) else {
+            self.update_magnet_upload(
+                magnet_upload,
+                MagnetUploadStatus::Failed,
+                None,
+                magnet_upload.attempts,
+                Some(&ServiceError::InvalidMagnetLink.to_string()),
+            )
+            .await;
+            return;
+        };
+
+        let mut peers = self.tracker_peers(&magnet_link.info_hash).await;
+
+        for peer in fetcher.find_peers(&magnet_link.info_hash, &magnet_link.trackers).await {
+            if !peers.contains(&peer) {
+                peers.push(peer);
+            }
+        }
+
+        let info_dictionary = match fetcher.fetch_info_dictionary(&magnet_link.info_hash, &peers).await {
+            Ok(info_dictionary) => info_dictionary,
+            Err(e) => {
+                let attempts = magnet_upload.attempts + 1;
+
+                let status = if attempts >= i64::from(max_attempts) {
+                    MagnetUploadStatus::Failed
+                } else {
+                    MagnetUploadStatus::Resolving
+                };
+
+                debug!("Magnet upload #{magnet_upload_id} not resolved (attempt {attempts}): {e}");
+
+                self.update_magnet_upload(magnet_upload, status, None, attempts, Some(&e.to_string()))
+                    .await;
+                return;
+            }
+        };
+
+        let result = match self.category_repository.get_by_id(&magnet_upload.category_id).await {
+            Ok(category) => {
+                self.add_torrent(
+                    AddTorrentRequest {
+                        title: magnet_upload.title.clone(),
+                        description: magnet_upload.description.clone(),
+                        category_name: category.name,
+                        tags: magnet_upload.tags(),
+                        torrent_buffer: torrent_file_from_info_dictionary(&info_dictionary, &magnet_link.trackers),
+                        publish_at: None,
+                        preserve_announce_urls: None,
+                        fields: BTreeMap::new(),
+                    },
+                    Some(magnet_upload.user_id),
+                )
+                .await
+            }
+            Err(_) => Err(ServiceError::InvalidCategory),
+        };
+
+        match result {
+            Ok(response) => {
+                info!(
+                    "Magnet upload #{magnet_upload_id} resolved as torrent #{}",
+                    response.torrent_id
+                );
+
+                self.update_magnet_upload(
+                    magnet_upload,
+                    MagnetUploadStatus::Resolved,
+                    Some(response.torrent_id),
+                    magnet_upload.attempts,
+                    None,
+                )
+                .await;
+            }
+            Err(e) => {
+                self.update_magnet_upload(
+                    magnet_upload,
+                    MagnetUploadStatus::Failed,
+                    None,
+                    magnet_upload.attempts,
+                    Some(&e.to_string()),
+                )
+                .await;
+            }
+        }
+    }
+
+    async fn update_magnet_upload(
+        &self,
+        magnet_upload: &MagnetUpload,
+        status: MagnetUploadStatus,
+        torrent_id: Option<TorrentId>,
+        attempts: i64,
+        error: Option<&str>,
+    ) {
+        if let Err(e) = self
+            .magnet_upload_repository
+            .update(magnet_upload.magnet_upload_id, &status, torrent_id, attempts, error)
+            .await
+        {
+            error!(
+                "Failed to update the status of magnet upload #{}: {e:?}",
+                magnet_upload.magnet_upload_id
+            );
+        }
+    }
+
+    /// Peers known by the index tracker. The uploader is probably seeding
+    /// the torrent.
+    async fn tracker_peers(&self, info_hash: &InfoHash) -> Vec<SocketAddr> {
+        match self.tracker_service.get_torrent_info(&info_hash.to_hex_string()).await {
+            Ok(torrent_info) => torrent_info
+                .peers
+                .iter()
+                .filter_map(|peer| peer.peer_addr.as_ref()?.parse().ok())
+                .collect(),
+            Err(_) => vec![],
+        }
+    }
+
+    /// It returns the existing torrents with a similar title and size, when
+    /// the `[torrent.similar_titles]` detection is enabled.
+    ///
+    /// Errors are only logged because the torrent has already been added.
+    async fn find_similar_torrents(&self, torrent_id: TorrentId, title: &str, file_size: i64) -> Vec<SimilarTorrent> {
+        let settings = self.configuration.settings.read().await;
+        let opt_similar_titles = settings.torrent.as_ref().and_then(|torrent| torrent.similar_titles.clone());
+        drop(settings);
+
+        let Some(similar_titles) = opt_similar_titles else {
+            return vec![];
+        };
+
+        let (min_size, max_size) = similar_titles::size_range(file_size, similar_titles.size_tolerance);
+
+        let candidates = match self
+            .torrent_repository
+            .get_by_size_range(min_size, max_size, MAX_TORRENTS_COMPARED_BY_TITLE)
+            .await
+        {
+            Ok(candidates) => candidates,
+            Err(e) => {
+                error!("Failed to find torrents similar to torrent #{torrent_id}: {e:?}");
+                return vec![];
+            }
+        };
+
+        let candidates = candidates
+            .into_iter()
+            .filter(|candidate| candidate.torrent_id != torrent_id)
+            .collect();
+
+        similar_titles::most_similar(
+            title,
+            candidates,
+            similar_titles.max_title_distance,
+            similar_titles.max_candidates,
+        )
+    }
+
+    async fn magnet_uploads_settings(&self) -> Option<crate::config::MagnetUploads> {
+        let settings = self.configuration.settings.read().await;
+
+        settings.torrent.as_ref().and_then(|torrent| torrent.magnet_uploads.clone())
+    }
+
+    /// Releases the scheduled torrents whose publish date has passed. They are
+    /// whitelisted in the tracker, indexed for searching and announced as if
+    /// they had just been uploaded. Torrents waiting for moderation are
+    /// announced when they are approved.
+    ///
+    /// Errors are only logged. Torrents that could not be released are
+    /// retried in the next run.
+    pub async fn publish_scheduled_torrents(&self) {
+        let releases = match self
+            .torrent_repository
+            .get_due_releases(&datetime_now(), MAX_SCHEDULED_RELEASES_PER_RUN)
+            .await
+        {
+            Ok(releases) => releases,
+            Err(e) => {
+                error!("Failed to get the scheduled torrents: {e:?}");
+                return;
+            }
+        };
+
+        for release in releases {
+            self.publish_scheduled_torrent(&release).await;
+        }
+    }
+
+    async fn publish_scheduled_torrent(&self, release: &ScheduledRelease) {
+        let torrent_id = release.torrent_id;
+
+        if let Err(e) = self.tracker_service.whitelist_info_hash(release.info_hash.clone()).await {
+            error!("Failed to whitelist the scheduled torrent #{torrent_id}: {e:?}");
+            return;
+        }
+
+        if let Err(e) = self.torrent_repository.publish_release(&torrent_id).await {
+            error!("Failed to release the scheduled torrent #{torrent_id}: {e:?}");
+            return;
+        }
+
+        info!("Scheduled torrent #{torrent_id} released");
+
+        self.torrent_listing_generator.invalidate();
+
+        self.search_service.index_torrent(torrent_id).await;
+
+        if release.status_on_publish == TorrentStatus::Approved.to_string() {
+            self.publish_torrent_uploaded(torrent_id).await;
+        } else {
+            self.live_events.publish(LiveEvent::TorrentPending {
+                torrent_id,
+                info_hash: release.info_hash.clone(),
+                title: release.title.clone(),
+            });
+        }
+    }
+
+    /// It publishes the `TorrentUploaded` domain event.
+    ///
+    /// Errors are only logged because the torrent has already been added.
+    async fn publish_torrent_uploaded(&self, torrent_id: TorrentId) {
+        let torrent_listing = match self.torrent_listing_generator.one_torrent_by_torrent_id(&torrent_id).await {
+            Ok(torrent_listing) => torrent_listing,
+            Err(e) => {
+                error!("Failed to get the listing of torrent #{torrent_id}: {e:?}");
+                return;
+            }
+        };
+
+        let category = match torrent_listing.category_id {
+            Some(category_id) => self
+                .category_repository
+                .get_by_id(&category_id)
+                .await
+                .map(|category| category.name)
+                .unwrap_or_default(),
+            None => String::new(),
+        };
+
+        let torrent_uploaded = TorrentUploaded {
+            torrent_id,
+            info_hash: torrent_listing.info_hash,
+            title: torrent_listing.title,
+            category,
+            uploader: torrent_listing.uploader,
+        };
+
+        self.live_events.publish(LiveEvent::from(&torrent_uploaded));
+
+        let event = DomainEvent::TorrentUploaded(torrent_uploaded);
+
+        if let Err(e) = self.domain_event_repository.publish(&event).await {
+            error!("Failed to publish the domain event for torrent #{torrent_id}: {e:?}");
+        }
+    }
+
+    /// New uploads are pending when uploads are moderated, unless the uploader
+    /// is a moderator or a trusted uploader. Uploads in categories that
+    /// require co-signing are always pending.
+    async fn initial_status_for_upload(&self, maybe_user_id: Option<UserId>, category_id: CategoryId) -> TorrentStatus {
+        // When the policy can't be loaded, the upload is held for moderation
+        if self.requires_co_signing(Some(category_id)).await.unwrap_or(true) {
+            return TorrentStatus::Pending;
+        }
+
+        let moderated_uploads = self.configuration.settings.read().await.website.moderated_uploads;
+
+        if !moderated_uploads {
+            return TorrentStatus::Approved;
+        }
+
+        if self
+            .authorization_service
+            .authorize_in_category(ACTION::ModerateTorrent, maybe_user_id, Some(category_id))
+            .await
+            .is_ok()
+        {
+            return TorrentStatus::Approved;
+        }
+
+        // When the reputation can't be loaded, the upload is held for
+        // moderation
+        match maybe_user_id {
+            Some(user_id) if self.reputation_service.is_trusted(user_id).await.unwrap_or(false) => TorrentStatus::Approved,
+            _ => TorrentStatus::Pending,
+        }
+    }
+
     async fn validate_and_build_metadata(&self, add_torrent_req: &AddTorrentRequest) -> Result<Metadata, ServiceError> {
         if add_torrent_req.category_name.is_empty() {
             return Err(ServiceError::MissingMandatoryMetadataFields);
@@ -208,6 +959,30 @@ impl Index {
         Ok(metadata)
     }
 
+    /// It applies the `[torrent.structure]` upload policies, if any.
+    async fn validate_torrent_structure(&self, torrent: &Torrent, category_name: &str) -> Result<(), ServiceError> {
+        let settings = self.configuration.settings.read().await;
+        let policy = settings.torrent.as_ref().and_then(|torrent| torrent.structure.clone());
+        drop(settings);
+
+        match policy {
+            Some(policy) => torrent_structure::validate(torrent, category_name, &policy),
+            None => Ok(()),
+        }
+    }
+
+    /// It applies the `[upload_policy]` content policies, if any.
+    async fn validate_upload_policy(&self, torrent: &Torrent) -> Result<(), ServiceError> {
+        let settings = self.configuration.settings.read().await;
+        let policy = settings.upload_policy.clone();
+        drop(settings);
+
+        match policy {
+            Some(policy) => upload_policy::validate(torrent, &policy),
+            None => Ok(()),
+        }
+    }
+
     async fn canonical_info_hash_group_checks(
         &self,
         original_info_hash: &InfoHash,
@@ -245,11 +1020,23 @@ impl Index {
         Ok(())
     }
 
-    async fn customize_announcement_info_for(&self, torrent: &mut Torrent) {
+    async fn customize_announcement_info_for(&self, torrent: &mut Torrent, preserve_announce_urls: Option<bool>) {
         let settings = self.configuration.settings.read().await;
         let tracker_url = settings.tracker.url.clone();
-        torrent.set_announce_to(&tracker_url);
-        torrent.reset_announce_list_if_private();
+
+        let preserve_announce_urls = preserve_announce_urls.unwrap_or_else(|| {
+            settings
+                .torrent
+                .as_ref()
+                .is_some_and(|torrent| torrent.announce_urls.preserve)
+        });
+
+        if preserve_announce_urls {
+            torrent.preserve_original_trackers(&tracker_url);
+        } else {
+            torrent.set_announce_to(&tracker_url);
+            torrent.reset_announce_list_if_private();
+        }
     }
 
     async fn import_torrent_statistics_from_tracker(&self, torrent_id: TorrentId, canonical_info_hash: &InfoHash) {
@@ -265,14 +1052,29 @@ impl Index {
     /// # Errors
     ///
     /// This function will return an error if unable to get the torrent from the
-    /// database.
+    /// database, or if the user's upload ratio is below the minimum in
+    /// private mode.
     pub async fn get_torrent(&self, info_hash: &InfoHash, maybe_user_id: Option<UserId>) -> Result<Torrent, ServiceError> {
         self.authorization_service
             .authorize(ACTION::GetTorrent, maybe_user_id)
             .await?;
 
+        if let Some(user_id) = maybe_user_id {
+            self.ratio_service.authorize_download(user_id).await?;
+        }
+
         let mut torrent = self.torrent_repository.get_by_info_hash(info_hash).await?;
 
+        if let Some(download) = self.download_settings().await {
+            rewrite_for_download(&mut torrent, &download);
+
+            let rewritten_info_hash = torrent.canonical_info_hash();
+
+            if rewritten_info_hash != *info_hash {
+                self.register_rewritten_info_hash(&rewritten_info_hash, info_hash).await?;
+            }
+        }
+
         let tracker_url = self.get_tracker_url().await;
         let tracker_is_private = self.tracker_is_private().await;
 
@@ -288,37 +1090,51 @@ impl Index {
             torrent.include_url_as_main_tracker(&tracker_url);
         }
 
+        torrent.include_urls_as_backup_trackers(&self.get_backup_tracker_urls().await);
+
         Ok(torrent)
     }
 
     /// Delete a Torrent from the Index
     ///
+    /// The torrent is moved to the [trash bin](trash). It stays whitelisted
+    /// in the tracker until it's purged.
+    ///
     /// # Errors
     ///
     /// This function will return an error if:
     ///
     /// * Unable to get the user who is deleting the torrent (logged-in user).
-    /// * The user does not have permission to delete the torrent.
+    /// * The user does not have permission to delete the torrent. Category
+    ///   moderators can delete the torrents in their categories.
     /// * Unable to get the torrent listing from it's ID.
-    /// * Unable to delete the torrent from the database.
+    /// * Unable to move the torrent to the trash bin.
     pub async fn delete_torrent(
         &self,
         info_hash: &InfoHash,
         maybe_user_id: Option<UserId>,
     ) -> Result<DeletedTorrentResponse, ServiceError> {
+        let torrent_listing = self.torrent_listing_generator.one_torrent_by_info_hash(info_hash).await?;
+
         self.authorization_service
-            .authorize(ACTION::DeleteTorrent, maybe_user_id)
+            .authorize_in_category(ACTION::DeleteTorrent, maybe_user_id, torrent_listing.category_id)
             .await?;
 
-        let torrent_listing = self.torrent_listing_generator.one_torrent_by_info_hash(info_hash).await?;
+        self.torrent_trash_repository
+            .add(torrent_listing.torrent_id, maybe_user_id)
+            .await?;
 
-        self.torrent_repository.delete(&torrent_listing.torrent_id).await?;
+        self.torrent_listing_generator.invalidate();
 
-        // Remove info-hash from tracker whitelist
-        // todo: handle the error when the tracker is offline or not well configured.
-        let _unused = self
-            .tracker_service
-            .remove_info_hash_from_whitelist(info_hash.to_string())
+        self.search_service.remove_torrent(torrent_listing.torrent_id).await;
+
+        self.audit_service
+            .record(
+                maybe_user_id,
+                AuditAction::TorrentDelete,
+                &torrent_listing.info_hash,
+                Some(&torrent_listing.title),
+            )
             .await;
 
         Ok(DeletedTorrentResponse {
@@ -327,8 +1143,236 @@ impl Index {
         })
     }
 
+    /// It applies a moderation action to many torrents in a single
+    /// transaction, so either all of them are changed or none. In a dry run,
+    /// it only returns the torrents that would be changed.
+    ///
+    /// Torrents in categories that require co-signing are not approved in
+    /// bulk.
+    ///
+    /// # Errors
+    ///
+    /// This function will return an error if:
+    ///
+    /// * The user is not an administrator.
+    /// * There are no info-hashes or too many of them.
+    /// * The category or tag of the action is missing or does not exist.
+    /// * There is a database error.
+    pub async fn apply_bulk_action(
+        &self,
+        request: &BulkActionRequest,
+        maybe_user_id: Option<UserId>,
+    ) -> Result<BulkActionSummary, ServiceError> {
+        self.authorization_service
+            .authorize(ACTION::BulkModerateTorrents, maybe_user_id)
+            .await?;
+
+        if request.info_hashes.is_empty() || request.info_hashes.len() > MAX_BULK_ACTION_TORRENTS {
+            return Err(ServiceError::InvalidBulkAction);
+        }
+
+        let (action, detail) = self.bulk_action_from(request, maybe_user_id).await?;
+
+        let mut summary = BulkActionSummary {
+            action: request.action,
+            dry_run: request.dry_run,
+            torrents: vec![],
+            skipped: vec![],
+            not_found: vec![],
+        };
+
+        let mut torrent_listings = vec![];
+        let mut seen = HashSet::new();
+
+        for info_hash in &request.info_hashes {
+            let Ok(parsed_info_hash) = InfoHash::from_str(&info_hash.to_lowercase()) else {
+                summary.not_found.push(info_hash.clone());
+                continue;
+            };
+
+            if !seen.insert(parsed_info_hash) {
+                continue;
+            }
+
+            let Ok(torrent_listing) = self
+                .torrent_listing_generator
+                .one_torrent_by_info_hash(&parsed_info_hash)
+                .await
+            else {
+                summary.not_found.push(info_hash.clone());
+                continue;
+            };
+
+            if self.is_changed_by_bulk_action(&torrent_listing, &action).await? {
+                summary.torrents.push(BulkActionTorrent {
+                    torrent_id: torrent_listing.torrent_id,
+                    info_hash: torrent_listing.info_hash.clone(),
+                    title: torrent_listing.title.clone(),
+                });
+                torrent_listings.push(torrent_listing);
+            } else {
+                summary.skipped.push(torrent_listing.info_hash);
+            }
+        }
+
+        if request.dry_run || torrent_listings.is_empty() {
+            return Ok(summary);
+        }
+
+        let torrent_ids: Vec<TorrentId> = torrent_listings
+            .iter()
+            .map(|torrent_listing| torrent_listing.torrent_id)
+            .collect();
+
+        self.torrent_repository.apply_bulk_action(&torrent_ids, &action).await?;
+
+        self.torrent_listing_generator.invalidate();
+
+        for torrent_listing in torrent_listings {
+            self.after_bulk_action(&torrent_listing, &action, detail.as_deref(), maybe_user_id)
+                .await;
+        }
+
+        Ok(summary)
+    }
+
+    /// It validates the request and returns the action to apply and the
+    /// detail for the audit log.
+    async fn bulk_action_from(
+        &self,
+        request: &BulkActionRequest,
+        maybe_user_id: Option<UserId>,
+    ) -> Result<(BulkAction, Option<String>), ServiceError> {
+        match request.action {
+            BulkActionKind::Delete => Ok((
+                BulkAction::Delete {
+                    deleted_by: maybe_user_id,
+                },
+                None,
+            )),
+            BulkActionKind::Approve => Ok((BulkAction::Approve, None)),
+            BulkActionKind::Recategorize => {
+                let category_id = request.category_id.ok_or(ServiceError::InvalidBulkAction)?;
+
+                let category = self.category_repository.get_by_id(&category_id).await?;
+
+                Ok((
+                    BulkAction::Recategorize(category_id),
+                    Some(format!("category: {}", category.name)),
+                ))
+            }
+            BulkActionKind::AddTag | BulkActionKind::RemoveTag => {
+                let tag_id = request.tag_id.ok_or(ServiceError::InvalidBulkAction)?;
+
+                let tag = self
+                    .torrent_tag_repository
+                    .get_all()
+                    .await?
+                    .into_iter()
+                    .find(|tag| tag.tag_id == tag_id)
+                    .ok_or(ServiceError::TagNotFound)?;
+
+                if request.action == BulkActionKind::AddTag {
+                    Ok((BulkAction::AddTag(tag_id), Some(format!("added tag: {}", tag.name))))
+                } else {
+                    Ok((BulkAction::RemoveTag(tag_id), Some(format!("removed tag: {}", tag.name))))
+                }
+            }
+        }
+    }
+
+    /// Whether the bulk action changes the torrent or not.
+    async fn is_changed_by_bulk_action(
+        &self,
+        torrent_listing: &TorrentListing,
+        action: &BulkAction,
+    ) -> Result<bool, ServiceError> {
+        match action {
+            BulkAction::Delete { .. } => Ok(true),
+            BulkAction::Recategorize(category_id) => Ok(torrent_listing.category_id != Some(*category_id)),
+            BulkAction::AddTag(tag_id) | BulkAction::RemoveTag(tag_id) => {
+                let has_tag = self
+                    .torrent_tag_repository
+                    .get_tags_for_torrent(&torrent_listing.torrent_id)
+                    .await?
+                    .iter()
+                    .any(|tag| tag.tag_id == *tag_id);
+
+                Ok(has_tag == matches!(action, BulkAction::RemoveTag(_)))
+            }
+            BulkAction::Approve => {
+                // Scheduled torrents are approved when they are released
+                if torrent_listing.status == TorrentStatus::Approved.to_string()
+                    || torrent_listing.status == TorrentStatus::Scheduled.to_string()
+                {
+                    return Ok(false);
+                }
+
+                Ok(!self.requires_co_signing(torrent_listing.category_id).await?)
+            }
+        }
+    }
+
+    /// It updates the search index, the audit log and the events of a
+    /// torrent changed by a bulk action.
+    async fn after_bulk_action(
+        &self,
+        torrent_listing: &TorrentListing,
+        action: &BulkAction,
+        detail: Option<&str>,
+        maybe_user_id: Option<UserId>,
+    ) {
+        let torrent_id = torrent_listing.torrent_id;
+
+        let audit_action = match action {
+            BulkAction::Delete { .. } => {
+                self.search_service.remove_torrent(torrent_id).await;
+
+                AuditAction::TorrentDelete
+            }
+            BulkAction::Recategorize(_) => {
+                self.search_service.index_torrent(torrent_id).await;
+
+                AuditAction::TorrentRecategorize
+            }
+            BulkAction::AddTag(_) | BulkAction::RemoveTag(_) => {
+                self.search_service.index_torrent(torrent_id).await;
+
+                AuditAction::TorrentRetag
+            }
+            BulkAction::Approve => {
+                self.search_service.index_torrent(torrent_id).await;
+
+                self.live_events.publish(LiveEvent::TorrentApproved {
+                    torrent_id,
+                    info_hash: torrent_listing.info_hash.clone(),
+                    title: torrent_listing.title.clone(),
+                });
+
+                // Pending torrents are announced when they are approved
+                if torrent_listing.status == TorrentStatus::Pending.to_string() {
+                    self.publish_torrent_uploaded(torrent_id).await;
+                }
+
+                AuditAction::TorrentApprove
+            }
+        };
+
+        let detail = match detail {
+            Some(detail) => format!("{} ({detail})", torrent_listing.title),
+            None => torrent_listing.title.clone(),
+        };
+
+        self.audit_service
+            .record(maybe_user_id, audit_action, &torrent_listing.info_hash, Some(&detail))
+            .await;
+    }
+
     /// Get torrent info from the Index
     ///
+    /// The seeders and leechers are scraped from the trackers when they are
+    /// older than the `on_demand_scrape_threshold`, if it's enabled.
+    ///
     /// # Errors
     ///
     /// This function will return an error if:
@@ -338,6 +1382,8 @@ impl Index {
     /// * Unable to get torrent files from id.
     /// * Unable to get torrent info from id.
     /// * Unable to get torrent announce url(s) from id.
+    /// * Unable to get torrent previews from id.
+    /// * Unable to get torrent file groups from id.
     pub async fn get_torrent_info(
         &self,
         info_hash: &InfoHash,
@@ -349,10 +1395,40 @@ impl Index {
 
         let torrent_listing = self.torrent_listing_generator.one_torrent_by_info_hash(info_hash).await?;
 
-        let torrent_response = self
+        let torrent_id = torrent_listing.torrent_id;
+
+        let mut torrent_response = self
             .build_full_torrent_response(torrent_listing, info_hash, maybe_user_id)
             .await?;
 
+        if let Some((seeders, leechers)) = self
+            .tracker_service
+            .scrape_on_demand(torrent_id, &info_hash.to_hex_string())
+            .await
+        {
+            torrent_response.seeders = seeders;
+            torrent_response.leechers = leechers;
+        }
+
+        if let Some(user_id) = maybe_user_id {
+            torrent_response.bookmarked = self.bookmark_repository.is_bookmarked(user_id, torrent_id).await?;
+        }
+
+        torrent_response.previews = self.torrent_preview_repository.get_all(torrent_id).await?;
+
+        let file_groups = self.torrent_file_group_repository.get_all(torrent_id).await?;
+
+        torrent_response.file_groups = file_group::summarize(&file_groups, &torrent_response.files);
+
+        torrent_response.metadata = self.torrent_metadata_repository.get(torrent_id).await?;
+
+        torrent_response.fields = self.category_field_repository.get_values(torrent_id).await?;
+
+        torrent_response.uploader_trusted = self
+            .reputation_service
+            .is_trusted_username(&torrent_response.uploader)
+            .await?;
+
         Ok(torrent_response)
     }
 
@@ -370,7 +1446,78 @@ impl Index {
             .authorize(ACTION::GenerateTorrentInfoListing, maybe_user_id)
             .await?;
 
-        let torrent_listing_specification = self.listing_specification_from_user_request(request).await;
+        let mut torrent_listing_specification = self.listing_specification_from_user_request(request).await?;
+
+        // Resolve the search using the search index if there is one
+        if let Some(search) = &torrent_listing_specification.search {
+            if let Some(torrent_ids) = self.search_service.search(search).await? {
+                torrent_listing_specification.search = None;
+                torrent_listing_specification.torrent_ids = Some(torrent_ids);
+                if request.sort.is_none() {
+                    torrent_listing_specification.sort = Sorting::Relevance;
+                }
+            }
+        }
+
+        let torrents_response = self
+            .torrent_listing_generator
+            .generate_listing(&torrent_listing_specification)
+            .await?;
+
+        Ok(torrents_response)
+    }
+
+    /// It returns the version of the data behind the torrent listing. It's
+    /// used to build the listing `ETag`.
+    ///
+    /// # Errors
+    ///
+    /// Returns a `ServiceError::DatabaseError` if the database query fails.
+    pub async fn torrent_listing_version(&self) -> Result<String, ServiceError> {
+        Ok(self.torrent_listing_generator.data_version().await?)
+    }
+
+    /// It returns the list of torrents waiting for moderation.
+    ///
+    /// Category moderators only get the torrents in the categories they
+    /// moderate.
+    ///
+    /// # Errors
+    ///
+    /// It returns an error if:
+    ///
+    /// * The user does not have the required permissions.
+    /// * There is a database error.
+    pub async fn generate_pending_torrent_listing(
+        &self,
+        request: &ListingRequest,
+        maybe_user_id: Option<UserId>,
+    ) -> Result<TorrentsResponse, ServiceError> {
+        let moderated_categories = self
+            .authorization_service
+            .authorized_categories(ACTION::ModerateTorrent, maybe_user_id)
+            .await?;
+
+        let mut torrent_listing_specification = self.listing_specification_from_user_request(request).await?;
+
+        torrent_listing_specification.status = TorrentStatus::Pending;
+
+        if let Some(moderated_categories) = moderated_categories {
+            let categories = match torrent_listing_specification.categories.take() {
+                Some(requested) => requested
+                    .into_iter()
+                    .filter(|category| moderated_categories.contains(category))
+                    .collect(),
+                None => moderated_categories,
+            };
+
+            if categories.is_empty() {
+                // None of the requested categories is moderated by the user
+                torrent_listing_specification.torrent_ids = Some(vec![]);
+            }
+
+            torrent_listing_specification.categories = Some(categories);
+        }
 
         let torrents_response = self
             .torrent_listing_generator
@@ -380,9 +1527,201 @@ impl Index {
         Ok(torrents_response)
     }
 
+    /// It approves a torrent so that it's included in the public listing.
+    ///
+    /// In categories that require co-signing, the torrent is only approved
+    /// when [`REQUIRED_CO_SIGNATURES`] staff members, other than the uploader,
+    /// have approved it. Until then, it stays pending.
+    ///
+    /// The `TorrentUploaded` event is published when a pending torrent is
+    /// approved.
+    ///
+    /// # Errors
+    ///
+    /// It returns an error if:
+    ///
+    /// * The user does not have the required permissions.
+    /// * The torrent does not exist.
+    /// * The user already approved the torrent or uploaded it, in categories
+    ///   that require co-signing.
+    /// * There is a database error.
+    pub async fn approve_torrent(
+        &self,
+        torrent_id: TorrentId,
+        maybe_user_id: Option<UserId>,
+    ) -> Result<TorrentListing, ServiceError> {
+        let torrent_listing = self.torrent_listing_generator.one_torrent_by_torrent_id(&torrent_id).await?;
+
+        if torrent_listing.status != TorrentStatus::Approved.to_string()
+            && self.requires_co_signing(torrent_listing.category_id).await?
+        {
+            let approvals = self.co_sign_torrent(&torrent_listing, maybe_user_id).await?;
+
+            if approvals < REQUIRED_CO_SIGNATURES {
+                return Ok(torrent_listing);
+            }
+        }
+
+        let torrent_listing = self
+            .change_torrent_status(torrent_id, TorrentStatus::Approved, maybe_user_id)
+            .await?;
+
+        if torrent_listing.status == TorrentStatus::Pending.to_string() {
+            self.publish_torrent_uploaded(torrent_id).await;
+        }
+
+        Ok(self.torrent_listing_generator.one_torrent_by_torrent_id(&torrent_id).await?)
+    }
+
+    /// It rejects a torrent so that it's excluded from the public listing.
+    ///
+    /// # Errors
+    ///
+    /// It returns an error if:
+    ///
+    /// * The user does not have the required permissions.
+    /// * The torrent does not exist.
+    /// * There is a database error.
+    pub async fn reject_torrent(
+        &self,
+        torrent_id: TorrentId,
+        maybe_user_id: Option<UserId>,
+    ) -> Result<TorrentListing, ServiceError> {
+        self.change_torrent_status(torrent_id, TorrentStatus::Rejected, maybe_user_id)
+            .await?;
+
+        // A rejected torrent must be co-signed again from scratch
+        self.torrent_repository.delete_approvals(&torrent_id).await?;
+
+        Ok(self.torrent_listing_generator.one_torrent_by_torrent_id(&torrent_id).await?)
+    }
+
+    /// It checks whether the torrents in the category must be co-signed.
+    async fn requires_co_signing(&self, category_id: Option<CategoryId>) -> Result<bool, ServiceError> {
+        let Some(category_id) = category_id else {
+            return Ok(false);
+        };
+
+        Ok(self.category_repository.get_policy(category_id).await?.co_signing)
+    }
+
+    /// It records the approval of a staff member for a torrent in a category
+    /// that requires co-signing, and returns the number of approvals.
+    async fn co_sign_torrent(
+        &self,
+        torrent_listing: &TorrentListing,
+        maybe_user_id: Option<UserId>,
+    ) -> Result<u64, ServiceError> {
+        self.authorization_service
+            .authorize_in_category(ACTION::ModerateTorrent, maybe_user_id, torrent_listing.category_id)
+            .await?;
+
+        let Some(user_id) = maybe_user_id else {
+            return Err(ServiceError::UnauthorizedActionForGuests);
+        };
+
+        let approver = self.user_repository.get_compact(&user_id).await?;
+
+        if approver.username == torrent_listing.uploader {
+            return Err(ServiceError::CannotCoSignOwnTorrent);
+        }
+
+        if !self
+            .torrent_repository
+            .add_approval(&torrent_listing.torrent_id, user_id)
+            .await?
+        {
+            return Err(ServiceError::TorrentAlreadyCoSigned);
+        }
+
+        let approvals = self.torrent_repository.count_approvals(&torrent_listing.torrent_id).await?;
+
+        self.audit_service
+            .record(
+                maybe_user_id,
+                AuditAction::TorrentCoSign,
+                &torrent_listing.info_hash,
+                Some(&format!("approval {approvals} of {REQUIRED_CO_SIGNATURES}")),
+            )
+            .await;
+
+        Ok(approvals)
+    }
+
+    /// It changes the moderation status and returns the torrent listing
+    /// before the change.
+    async fn change_torrent_status(
+        &self,
+        torrent_id: TorrentId,
+        status: TorrentStatus,
+        maybe_user_id: Option<UserId>,
+    ) -> Result<TorrentListing, ServiceError> {
+        let torrent_listing = self.torrent_listing_generator.one_torrent_by_torrent_id(&torrent_id).await?;
+
+        self.authorization_service
+            .authorize_in_category(ACTION::ModerateTorrent, maybe_user_id, torrent_listing.category_id)
+            .await?;
+
+        if torrent_listing.status != status.to_string() {
+            self.torrent_repository.update_status(&torrent_id, &status).await?;
+
+            self.torrent_listing_generator.invalidate();
+
+            let audit_action = match status {
+                TorrentStatus::Approved => Some(AuditAction::TorrentApprove),
+                TorrentStatus::Rejected => Some(AuditAction::TorrentReject),
+                TorrentStatus::Pending
+                | TorrentStatus::Archived
+                | TorrentStatus::Delisted
+                | TorrentStatus::Deleted
+                | TorrentStatus::Scheduled => None,
+            };
+
+            if let Some(audit_action) = audit_action {
+                self.audit_service
+                    .record(
+                        maybe_user_id,
+                        audit_action,
+                        &torrent_listing.info_hash,
+                        Some(&torrent_listing.title),
+                    )
+                    .await;
+            }
+
+            let (info_hash, title) = (torrent_listing.info_hash.clone(), torrent_listing.title.clone());
+
+            match status {
+                TorrentStatus::Approved => self.live_events.publish(LiveEvent::TorrentApproved {
+                    torrent_id,
+                    info_hash,
+                    title,
+                }),
+                TorrentStatus::Rejected => self.live_events.publish(LiveEvent::TorrentRejected {
+                    torrent_id,
+                    info_hash,
+                    title,
+                }),
+                TorrentStatus::Pending
+                | TorrentStatus::Archived
+                | TorrentStatus::Delisted
+                | TorrentStatus::Deleted
+                | TorrentStatus::Scheduled => {}
+            }
+        }
+
+        Ok(torrent_listing)
+    }
+
     /// It converts the user listing request into an internal listing
     /// specification.
-    async fn listing_specification_from_user_request(&self, request: &ListingRequest) -> ListingSpecification {
+    ///
+    /// # Errors
+    ///
+    /// It returns an error if the filters are not valid.
+    async fn listing_specification_from_user_request(
+        &self,
+        request: &ListingRequest,
+    ) -> Result<ListingSpecification, ServiceError> {
         let settings = self.configuration.settings.read().await;
         let default_torrent_page_size = settings.api.default_torrent_page_size;
         let max_torrent_page_size = settings.api.max_torrent_page_size;
@@ -403,16 +1742,24 @@ impl Index {
 
         let categories = request.categories.as_csv::<String>().unwrap_or(None);
 
-        let tags = request.tags.as_csv::<String>().unwrap_or(None);
+        // Searches for a tag synonym find the torrents with the tag.
+        let tags = match request.tags.as_csv::<String>().unwrap_or(None) {
+            Some(tags) => Some(self.torrent_tag_repository.resolve_synonyms(&tags).await.unwrap_or(tags)),
+            None => None,
+        };
 
-        ListingSpecification {
+        Ok(ListingSpecification {
             search: request.search.clone(),
             categories,
             tags,
+            torrent_ids: None,
+            status: TorrentStatus::Approved,
+            filter: listing_filter_from(request)?,
             sort,
+            then_sort: request.then_sort,
             offset,
             page_size,
-        }
+        })
     }
 
     /// Update the torrent info on the Index.
@@ -438,16 +1785,33 @@ impl Index {
 
         let torrent_listing = self.torrent_listing_generator.one_torrent_by_info_hash(info_hash).await?;
 
-        // Check if user is owner or administrator
-        // todo: move this to an authorization service.
-        if !(torrent_listing.uploader == updater.username || updater.administrator) {
-            return Err(ServiceError::UnauthorizedAction);
+        // Owners can always edit their torrents. Other users need permission
+        // to edit the torrents in the current category and, when moving the
+        // torrent, in the new one.
+        if torrent_listing.uploader != updater.username {
+            self.authorization_service
+                .authorize_in_category(ACTION::EditTorrentInfo, Some(*user_id), torrent_listing.category_id)
+                .await?;
+
+            if category_id.is_some() && *category_id != torrent_listing.category_id {
+                self.authorization_service
+                    .authorize_in_category(ACTION::EditTorrentInfo, Some(*user_id), *category_id)
+                    .await?;
+            }
         }
 
         self.torrent_info_repository
             .update(&torrent_listing.torrent_id, title, description, category_id, tags)
             .await?;
 
+        self.torrent_listing_generator.invalidate();
+
+        if category_id.is_some() && *category_id != torrent_listing.category_id {
+            self.category_repository.invalidate_cache().await;
+        }
+
+        self.search_service.index_torrent(torrent_listing.torrent_id).await;
+
         let torrent_listing = self
             .torrent_listing_generator
             .one_torrent_by_torrent_id(&torrent_listing.torrent_id)
@@ -458,16 +1822,88 @@ impl Index {
         Ok(torrent_response)
     }
 
+    /// It replaces the torrent trackers with the given `announce-list` tiers.
+    ///
+    /// Empty tiers and duplicate trackers are removed. The regenerated torrent
+    /// file keeps the tiers, after the index tracker.
+    ///
+    /// # Errors
+    ///
+    /// This function will return an error if:
+    ///
+    /// * The user does not have the required permissions.
+    /// * There are no trackers or any of them is not a valid URL.
+    /// * The torrent does not exist.
+    /// * There is a database error.
+    pub async fn update_torrent_trackers(
+        &self,
+        info_hash: &InfoHash,
+        tiers: &[Vec<String>],
+        maybe_user_id: Option<UserId>,
+    ) -> Result<Vec<Vec<String>>, ServiceError> {
+        let torrent_listing = self.torrent_listing_generator.one_torrent_by_info_hash(info_hash).await?;
+
+        self.authorization_service
+            .authorize_in_category(ACTION::EditTorrentTrackers, maybe_user_id, torrent_listing.category_id)
+            .await?;
+
+        let tiers = normalize_tracker_tiers(tiers)?;
+
+        self.torrent_announce_url_repository
+            .update_tiers(&torrent_listing.torrent_id, &tiers)
+            .await?;
+
+        Ok(tiers)
+    }
+
     async fn get_tracker_url(&self) -> Url {
         let settings = self.configuration.settings.read().await;
         settings.tracker.url.clone()
     }
 
+    async fn get_backup_tracker_urls(&self) -> Vec<Url> {
+        let settings = self.configuration.settings.read().await;
+        settings.tracker.backups.iter().map(|backup| backup.url.clone()).collect()
+    }
+
     async fn tracker_is_private(&self) -> bool {
         let settings = self.configuration.settings.read().await;
         settings.tracker.private
     }
 
+    async fn download_settings(&self) -> Option<TorrentDownload> {
+        let settings = self.configuration.settings.read().await;
+        settings.torrent.as_ref().and_then(|torrent| torrent.download.clone())
+    }
+
+    /// The info-hash of a rewritten torrent is added to the group of the
+    /// uploaded torrent, so it's found by it, and whitelisted in the tracker.
+    /// It's only done the first time the torrent is downloaded.
+    async fn register_rewritten_info_hash(
+        &self,
+        rewritten_info_hash: &InfoHash,
+        canonical_info_hash: &InfoHash,
+    ) -> Result<(), ServiceError> {
+        let group = self
+            .torrent_info_hash_repository
+            .get_canonical_info_hash_group(canonical_info_hash)
+            .await?;
+
+        if group.original_info_hashes.contains(rewritten_info_hash) {
+            return Ok(());
+        }
+
+        self.tracker_service
+            .whitelist_info_hash(rewritten_info_hash.to_hex_string())
+            .await?;
+
+        self.torrent_info_hash_repository
+            .add_info_hash_to_canonical_info_hash_group(rewritten_info_hash, canonical_info_hash)
+            .await?;
+
+        Ok(())
+    }
+
     async fn build_short_torrent_response(
         &self,
         torrent_listing: TorrentListing,
@@ -513,13 +1949,20 @@ impl Index {
                 .for_each(|v| v.path = vec![torrent_info.name.to_string()]);
         }
 
+        torrent_response.contents = contents::build(&torrent_response.files);
+
         // Add trackers
 
         // code-review: duplicate logic. We have to check the same in the
         // download torrent file endpoint. Here he have only one list of tracker
         // like the `announce_list` in the torrent file.
 
-        torrent_response.trackers = self.torrent_announce_url_repository.get_by_torrent_id(&torrent_id).await?;
+        torrent_response.tracker_tiers = self
+            .torrent_announce_url_repository
+            .get_tiers_by_torrent_id(&torrent_id)
+            .await?;
+
+        torrent_response.trackers = torrent_response.tracker_tiers.iter().flatten().cloned().collect();
 
         let tracker_url = self.get_tracker_url().await;
 
@@ -539,22 +1982,17 @@ impl Index {
             torrent_response.include_url_as_main_tracker(&tracker_url);
         }
 
+        torrent_response.include_urls_as_backup_trackers(&self.get_backup_tracker_urls().await);
+
         // Add magnet link
 
-        // todo: extract a struct or function to build the magnet links
-        let mut magnet = format!(
-            "magnet:?xt=urn:btih:{}&dn={}",
-            torrent_response.info_hash,
-            urlencoding::encode(&torrent_response.title)
+        torrent_response.magnet_link = magnet_link(
+            &torrent_response.info_hash,
+            &torrent_response.title,
+            &torrent_response.trackers,
+            &[],
         );
 
-        // Add trackers from torrent file to magnet link
-        for tracker in &torrent_response.trackers {
-            magnet.push_str(&format!("&tr={}", urlencoding::encode(tracker)));
-        }
-
-        torrent_response.magnet_link = magnet;
-
         // Get realtime seeders and leechers
         if let Ok(torrent_info) = self
             .tracker_statistics_importer
@@ -589,51 +2027,525 @@ impl Index {
             .await
             .map_err(|_| ServiceError::DatabaseError)
     }
+
+    /// Returns the URL the short URL of a torrent redirects to: the torrent
+    /// page in the web application, if it's configured, or the torrent
+    /// details in the API.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the user is not authorized, the torrent does not
+    /// exist or there is a problem with the database.
+    pub async fn get_torrent_page_url(
+        &self,
+        info_hash: &InfoHash,
+        maybe_user_id: Option<UserId>,
+    ) -> Result<String, ServiceError> {
+        let canonical_info_hash = self
+            .get_canonical_info_hash(info_hash, maybe_user_id)
+            .await?
+            .ok_or(ServiceError::TorrentNotFound)?
+            .to_hex_string();
+
+        let settings = self.configuration.settings.read().await;
+
+        Ok(match &settings.website.torrent_page_url {
+            Some(torrent_page_url) => torrent_page_url.replace("{info_hash}", &canonical_info_hash),
+            None => format!("/v1/torrent/{canonical_info_hash}"),
+        })
+    }
+
+    /// Returns the magnet link of the torrent, with the same trackers and web
+    /// seeds as the downloaded torrent file: the user's personal announce URL
+    /// in private mode, the backup trackers and the `[torrent.download]`
+    /// changes.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the user is not authorized, the torrent does not
+    /// exist or there is a problem with the database.
+    pub async fn get_magnet_link(
+        &self,
+        info_hash: &InfoHash,
+        maybe_user_id: Option<UserId>,
+    ) -> Result<MagnetLinkResponse, ServiceError> {
+        let canonical_info_hash = self
+            .torrent_info_hash_repository
+            .find_canonical_info_hash_for(info_hash)
+            .await?
+            .ok_or(ServiceError::TorrentNotFound)?;
+
+        let torrent = self.get_torrent(&canonical_info_hash, maybe_user_id).await?;
+
+        let mut trackers: Vec<String> = vec![];
+
+        for tracker in torrent
+            .announce
+            .iter()
+            .chain(torrent.announce_list.iter().flatten().flatten())
+        {
+            if !trackers.contains(tracker) {
+                trackers.push(tracker.clone());
+            }
+        }
+
+        let info_hash = torrent.canonical_info_hash_hex();
+
+        Ok(MagnetLinkResponse {
+            magnet_uri: magnet_link(
+                &info_hash,
+                &torrent.info.name,
+                &trackers,
+                torrent.url_list.as_deref().unwrap_or_default(),
+            ),
+            info_hash,
+        })
+    }
+
+    /// Returns the QR code of the torrent magnet link as a PNG image.
+    ///
+    /// When the magnet link is too long for a QR code because of the
+    /// trackers, the QR code only contains the info-hash.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the user is not authorized, the torrent does not
+    /// exist or there is a problem with the database.
+    pub async fn get_magnet_link_qr_code(
+        &self,
+        info_hash: &InfoHash,
+        maybe_user_id: Option<UserId>,
+    ) -> Result<Vec<u8>, ServiceError> {
+        let torrent_response = self.get_torrent_info(info_hash, maybe_user_id).await?;
+
+        let qr_code = match qr_code::png(torrent_response.magnet_link.as_bytes()) {
+            Err(qr_code::Error::DataTooLong) => {
+                qr_code::png(format!("magnet:?xt=urn:btih:{}", torrent_response.info_hash).as_bytes())
+            }
+            qr_code => qr_code,
+        };
+
+        qr_code.map_err(|e| {
+            error!(
+                "Failed to render the magnet link QR code for {}: {e}",
+                torrent_response.info_hash
+            );
+            ServiceError::InternalServerError
+        })
+    }
+
+    /// Returns the current peers of the torrent in the main tracker, so that
+    /// moderators can check the torrent is actually seeded.
+    ///
+    /// The peer addresses are masked, and the peer IDs removed, unless the
+    /// user can see the peer addresses (only administrators by default).
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the user is not authorized, the torrent does not
+    /// exist or the tracker API request fails.
+    pub async fn get_torrent_peers(
+        &self,
+        info_hash: &InfoHash,
+        maybe_user_id: Option<UserId>,
+    ) -> Result<TorrentPeersResponse, ServiceError> {
+        let torrent_listing = self.torrent_listing_generator.one_torrent_by_info_hash(info_hash).await?;
+
+        self.authorization_service
+            .authorize_in_category(ACTION::GetTorrentPeers, maybe_user_id, torrent_listing.category_id)
+            .await?;
+
+        let masked = self
+            .authorization_service
+            .authorize(ACTION::GetPeerAddresses, maybe_user_id)
+            .await
+            .is_err();
+
+        let torrent_info = match self.tracker_service.get_torrent_info(&info_hash.to_hex_string()).await {
+            Ok(torrent_info) => torrent_info,
+            // The tracker does not know the torrents without peers.
+            Err(TrackerAPIError::TorrentNotFound) => tracker::service::TorrentInfo {
+                info_hash: info_hash.to_hex_string(),
+                seeders: 0,
+                completed: 0,
+                leechers: 0,
+                peers: vec![],
+            },
+            Err(err) => return Err(err.into()),
+        };
+
+        Ok(TorrentPeersResponse {
+            info_hash: torrent_info.info_hash,
+            seeders: torrent_info.seeders,
+            completed: torrent_info.completed,
+            leechers: torrent_info.leechers,
+            masked,
+            peers: torrent_info
+                .peers
+                .into_iter()
+                .map(|peer| torrent_peer(peer, masked))
+                .collect(),
+        })
+    }
+}
+
+/// It converts a peer from the tracker API, masking its address and removing
+/// its ID if required.
+fn torrent_peer(peer: tracker::service::Peer, masked: bool) -> TorrentPeer {
+    let (peer_id, client) = peer.peer_id.map_or((None, None), |peer_id| (peer_id.id, peer_id.client));
+
+    TorrentPeer {
+        peer_id: if masked { None } else { peer_id },
+        client,
+        address: if masked {
+            peer.peer_addr.as_deref().map(mask_peer_address)
+        } else {
+            peer.peer_addr
+        },
+        updated: peer.updated,
+        uploaded: peer.uploaded,
+        downloaded: peer.downloaded,
+        seeder: peer.left == Some(0),
+        left: peer.left,
+        event: peer.event,
+    }
+}
+
+/// It replaces the peer address with its network: the `/16` network for IPv4
+/// addresses and the `/48` one for IPv6 addresses. The port is removed.
+fn mask_peer_address(address: &str) -> String {
+    let Ok(ip) = address
+        .parse::<SocketAddr>()
+        .map(|socket_addr| socket_addr.ip())
+        .or_else(|_| address.parse::<IpAddr>())
+    else {
+        return REDACTED.to_string();
+    };
+
+    let ip = match ip {
+        IpAddr::V6(ip) => ip.to_ipv4_mapped().map_or(IpAddr::V6(ip), IpAddr::V4),
+        ip @ IpAddr::V4(_) => ip,
+    };
+
+    match ip {
+        IpAddr::V4(ip) => {
+            let [a, b, _, _] = ip.octets();
+            format!("{a}.{b}.0.0/16")
+        }
+        IpAddr::V6(ip) => {
+            let [a, b, c, ..] = ip.segments();
+            format!("{a:x}:{b:x}:{c:x}::/48")
+        }
+    }
+}
+
+/// It builds a magnet link (BEP 9) with the trackers (`tr`) and the web seeds
+/// (`ws`).
+fn magnet_link(info_hash: &str, display_name: &str, trackers: &[String], web_seeds: &[String]) -> String {
+    let mut magnet = format!("magnet:?xt=urn:btih:{info_hash}&dn={}", urlencoding::encode(display_name));
+
+    for tracker in trackers {
+        magnet.push_str(&format!("&tr={}", urlencoding::encode(tracker)));
+    }
+
+    for web_seed in web_seeds {
+        magnet.push_str(&format!("&ws={}", urlencoding::encode(web_seed)));
+    }
+
+    magnet
+}
+
+/// It applies the `[torrent.download]` changes to a torrent file.
+fn rewrite_for_download(torrent: &mut Torrent, download: &TorrentDownload) {
+    if download.set_private {
+        torrent.info.private = Some(1);
+    }
+
+    if let Some(source) = &download.source {
+        torrent.info.source = Some(source.clone());
+    }
+
+    if download.strip_web_seeds {
+        torrent.httpseeds = None;
+        torrent.url_list = None;
+    }
+}
+
+pub struct DbTorrentRepository {
+    database: Arc<Box<dyn Database>>,
+    torrent_file_store: Arc<dyn TorrentFileStore>,
+}
+
+impl DbTorrentRepository {
+    #[must_use]
+    pub fn new(database: Arc<Box<dyn Database>>, torrent_file_store: Arc<dyn TorrentFileStore>) -> Self {
+        Self {
+            database,
+            torrent_file_store,
+        }
+    }
+
+    /// It finds the torrent by info-hash.
+    ///
+    /// # Errors
+    ///
+    /// This function will return an error there is a database error or the
+    /// pieces can't be loaded from the torrent file store.
+    pub async fn get_by_info_hash(&self, info_hash: &InfoHash) -> Result<Torrent, Error> {
+        let mut torrent = self.database.get_torrent_from_info_hash(info_hash).await?;
+
+        torrent_file::load_pieces(self.torrent_file_store.as_ref(), info_hash, &mut torrent)
+            .await
+            .map_err(|e| {
+                error!(target: "torrent_files", "Failed to load the pieces of torrent {info_hash}: {e:?}");
+                Error::Error
+            })?;
+
+        Ok(torrent)
+    }
+
+    /// Inserts the entire torrent in the database. The pieces are moved to
+    /// the torrent file store, when they are not kept in the database.
+    ///
+    /// # Errors
+    ///
+    /// This function will return an error there is a database error.
+    pub async fn add(
+        &self,
+        original_info_hash: &InfoHash,
+        torrent: &Torrent,
+        metadata: &Metadata,
+        user_id: UserId,
+        status: &TorrentStatus,
+    ) -> Result<TorrentId, Error> {
+        let torrent_id = self
+            .database
+            .insert_torrent_and_get_id(original_info_hash, torrent, user_id, metadata, status)
+            .await?;
+
+        let info_hash = torrent.canonical_info_hash();
+
+        // The pieces are kept in the database when they can't be stored, so
+        // they can be moved later.
+        if let Err(e) = torrent_file::move_out_of_database(
+            self.torrent_file_store.as_ref(),
+            self.database.as_ref().as_ref(),
+            &info_hash,
+            torrent,
+        )
+        .await
+        {
+            error!(target: "torrent_files", "Failed to store the pieces of torrent {info_hash}: {e:?}");
+        }
+
+        Ok(torrent_id)
+    }
+
+    /// It returns the most recent torrents whose size is in the range.
+    ///
+    /// # Errors
+    ///
+    /// This function will return an error there is a database error.
+    pub async fn get_by_size_range(&self, min_size: i64, max_size: i64, limit: u32) -> Result<Vec<SimilarTorrent>, Error> {
+        self.database.get_torrents_by_size_range(min_size, max_size, limit).await
+    }
+
+    /// It updates the moderation status of the torrent.
+    ///
+    /// # Errors
+    ///
+    /// This function will return an error there is a database error.
+    pub async fn update_status(&self, torrent_id: &TorrentId, status: &TorrentStatus) -> Result<(), Error> {
+        self.database.update_torrent_status(*torrent_id, status).await
+    }
+
+    /// It applies a moderation action to many torrents in a single
+    /// transaction.
+    ///
+    /// # Errors
+    ///
+    /// This function will return an error there is a database error.
+    pub async fn apply_bulk_action(&self, torrent_ids: &[TorrentId], action: &BulkAction) -> Result<(), Error> {
+        self.database.apply_bulk_action(torrent_ids, action).await
+    }
+
+    /// It schedules the release of a torrent.
+    ///
+    /// # Errors
+    ///
+    /// This function will return an error there is a database error.
+    pub async fn schedule_release(
+        &self,
+        torrent_id: &TorrentId,
+        publish_at: &str,
+        status_on_publish: &TorrentStatus,
+    ) -> Result<(), Error> {
+        self.database
+            .insert_torrent_schedule(*torrent_id, publish_at, status_on_publish)
+            .await
+    }
+
+    /// It returns the scheduled torrents that should have been released at
+    /// the given date.
+    ///
+    /// # Errors
+    ///
+    /// This function will return an error there is a database error.
+    pub async fn get_due_releases(&self, date: &str, limit: u32) -> Result<Vec<ScheduledRelease>, Error> {
+        self.database.get_due_torrent_schedules(date, limit).await
+    }
+
+    /// It releases a scheduled torrent.
+    ///
+    /// # Errors
+    ///
+    /// This function will return an error if the torrent is not scheduled or
+    /// there is a database error.
+    pub async fn publish_release(&self, torrent_id: &TorrentId) -> Result<(), Error> {
+        self.database.publish_scheduled_torrent(*torrent_id).await
+    }
+
+    /// It adds the approval of a staff member to a torrent in a category
+    /// that requires co-signing. It returns `false` if the user had already
+    /// approved it.
+    ///
+    /// # Errors
+    ///
+    /// This function will return an error there is a database error.
+    pub async fn add_approval(&self, torrent_id: &TorrentId, user_id: UserId) -> Result<bool, Error> {
+        self.database.insert_torrent_approval(*torrent_id, user_id).await
+    }
+
+    /// It returns the number of staff members who approved the torrent.
+    ///
+    /// # Errors
+    ///
+    /// This function will return an error there is a database error.
+    pub async fn count_approvals(&self, torrent_id: &TorrentId) -> Result<u64, Error> {
+        self.database.count_torrent_approvals(*torrent_id).await
+    }
+
+    /// It removes all the approvals of the torrent.
+    ///
+    /// # Errors
+    ///
+    /// This function will return an error there is a database error.
+    pub async fn delete_approvals(&self, torrent_id: &TorrentId) -> Result<(), Error> {
+        self.database.delete_torrent_approvals(*torrent_id).await
+    }
+
+    /// Deletes the entire torrent in the database, and its pieces in the
+    /// torrent file store.
+    ///
+    /// # Errors
+    ///
+    /// This function will return an error there is a database error.
+    pub async fn delete(&self, torrent_id: &TorrentId) -> Result<(), Error> {
+        if self.torrent_file_store.backend() == TorrentFilesBackend::Database {
+            return self.database.delete_torrent(*torrent_id).await;
+        }
+
+        let db_torrent = self.database.get_torrent_info_from_id(*torrent_id).await?;
+
+        self.database.delete_torrent(*torrent_id).await?;
+
+        if let Ok(info_hash) = InfoHash::from_str(&db_torrent.info_hash) {
+            if let Err(e) = self.torrent_file_store.delete(&info_hash).await {
+                error!(target: "torrent_files", "Failed to delete the pieces of torrent {info_hash}: {e:?}");
+            }
+        }
+
+        Ok(())
+    }
 }
 
-pub struct DbTorrentRepository {
+pub struct DbMagnetUploadRepository {
     database: Arc<Box<dyn Database>>,
 }
 
-impl DbTorrentRepository {
+impl DbMagnetUploadRepository {
     #[must_use]
     pub fn new(database: Arc<Box<dyn Database>>) -> Self {
         Self { database }
     }
 
-    /// It finds the torrent by info-hash.
-    ///
-    /// # Errors
-    ///
-    /// This function will return an error there is a database error.
-    pub async fn get_by_info_hash(&self, info_hash: &InfoHash) -> Result<Torrent, Error> {
-        self.database.get_torrent_from_info_hash(info_hash).await
-    }
-
-    /// Inserts the entire torrent in the database.
+    /// It adds a magnet upload with the `resolving` status.
     ///
     /// # Errors
     ///
-    /// This function will return an error there is a database error.
+    /// It returns an error if there is a database error.
     pub async fn add(
         &self,
-        original_info_hash: &InfoHash,
-        torrent: &Torrent,
-        metadata: &Metadata,
         user_id: UserId,
-    ) -> Result<TorrentId, Error> {
+        info_hash: &InfoHash,
+        magnet_uri: &str,
+        metadata: &Metadata,
+    ) -> Result<MagnetUploadId, Error> {
+        let tag_ids = metadata
+            .tags
+            .iter()
+            .map(ToString::to_string)
+            .collect::<Vec<String>>()
+            .join(",");
+
         self.database
-            .insert_torrent_and_get_id(original_info_hash, torrent, user_id, metadata)
+            .insert_magnet_upload(
+                user_id,
+                &info_hash.to_hex_string(),
+                magnet_uri,
+                &metadata.title,
+                &metadata.description,
+                metadata.category_id,
+                &tag_ids,
+            )
             .await
     }
 
-    /// Deletes the entire torrent in the database.
+    /// It finds the magnet upload by ID.
     ///
     /// # Errors
     ///
-    /// This function will return an error there is a database error.
-    pub async fn delete(&self, torrent_id: &TorrentId) -> Result<(), Error> {
-        self.database.delete_torrent(*torrent_id).await
+    /// It returns an error if the magnet upload does not exist or there is a
+    /// database error.
+    pub async fn get(&self, magnet_upload_id: MagnetUploadId) -> Result<MagnetUpload, Error> {
+        self.database.get_magnet_upload(magnet_upload_id).await
+    }
+
+    /// Whether the torrent is being resolved from another magnet upload.
+    ///
+    /// # Errors
+    ///
+    /// It returns an error if there is a database error.
+    pub async fn is_resolving(&self, info_hash: &InfoHash) -> Result<bool, Error> {
+        self.database.is_magnet_upload_resolving(&info_hash.to_hex_string()).await
+    }
+
+    /// It returns the magnet uploads being resolved, starting with the ones
+    /// not attempted for the longest time.
+    ///
+    /// # Errors
+    ///
+    /// It returns an error if there is a database error.
+    pub async fn get_resolving(&self, limit: u32) -> Result<Vec<MagnetUpload>, Error> {
+        self.database.get_resolving_magnet_uploads(limit).await
+    }
+
+    /// It updates the status of the magnet upload.
+    ///
+    /// # Errors
+    ///
+    /// It returns an error if there is a database error.
+    pub async fn update(
+        &self,
+        magnet_upload_id: MagnetUploadId,
+        status: &MagnetUploadStatus,
+        torrent_id: Option<TorrentId>,
+        attempts: i64,
+        error: Option<&str>,
+    ) -> Result<(), Error> {
+        self.database
+            .update_magnet_upload(magnet_upload_id, status, torrent_id, attempts, error)
+            .await
     }
 }
 
@@ -833,6 +2745,25 @@ impl DbTorrentAnnounceUrlRepository {
             .await
             .map(|v| v.into_iter().flatten().collect())
     }
+
+    /// It finds the announce URLs by torrent id grouped in `announce-list`
+    /// tiers.
+    ///
+    /// # Errors
+    ///
+    /// It returns an error if there is a database error.
+    pub async fn get_tiers_by_torrent_id(&self, torrent_id: &TorrentId) -> Result<Vec<Vec<String>>, Error> {
+        self.database.get_torrent_announce_urls_from_id(*torrent_id).await
+    }
+
+    /// It replaces the announce URLs of a torrent.
+    ///
+    /// # Errors
+    ///
+    /// It returns an error if there is a database error.
+    pub async fn update_tiers(&self, torrent_id: &TorrentId, tiers: &[Vec<String>]) -> Result<(), Error> {
+        self.database.update_torrent_announce_urls(*torrent_id, tiers).await
+    }
 }
 
 pub struct DbTorrentTagRepository {
@@ -863,6 +2794,15 @@ impl DbTorrentTagRepository {
         self.database.add_torrent_tag_links(*torrent_id, tag_ids).await
     }
 
+    /// It returns all the tags.
+    ///
+    /// # Errors
+    ///
+    /// It returns an error if there is a database error.
+    pub async fn get_all(&self) -> Result<Vec<TorrentTag>, Error> {
+        self.database.get_tags().await
+    }
+
     /// It returns all the tags linked to a certain torrent ID.
     ///
     /// # Errors
@@ -889,16 +2829,116 @@ impl DbTorrentTagRepository {
     pub async fn unlink_all_tags_for_torrent(&self, torrent_id: &TorrentId) -> Result<(), Error> {
         self.database.delete_all_torrent_tag_links(*torrent_id).await
     }
+
+    /// It merges the `source` tag into the `target` tag. The torrents with
+    /// the `source` tag are re-pointed to the `target` tag in one transaction
+    /// and the `source` name becomes a synonym of the `target` tag.
+    ///
+    /// # Errors
+    ///
+    /// It returns an error if any of the tags does not exist or there is a
+    /// database error.
+    pub async fn merge_tags(&self, source_tag_id: TagId, target_tag_id: TagId) -> Result<(), Error> {
+        self.database.merge_tags(source_tag_id, target_tag_id).await
+    }
+
+    /// It adds a synonym for a tag.
+    ///
+    /// # Errors
+    ///
+    /// It returns an error if the synonym already exists or there is a
+    /// database error.
+    pub async fn add_synonym(&self, name: &str, tag_id: TagId) -> Result<(), Error> {
+        self.database.insert_tag_synonym(name, tag_id).await
+    }
+
+    /// It removes a tag synonym.
+    ///
+    /// # Errors
+    ///
+    /// It returns an error if the synonym does not exist or there is a
+    /// database error.
+    pub async fn delete_synonym(&self, name: &str) -> Result<(), Error> {
+        self.database.delete_tag_synonym(name).await
+    }
+
+    /// It returns all the tag synonyms.
+    ///
+    /// # Errors
+    ///
+    /// It returns an error if there is a database error.
+    pub async fn get_synonyms(&self) -> Result<Vec<TagSynonym>, Error> {
+        self.database.get_tag_synonyms().await
+    }
+
+    /// It returns the tag a synonym points to, if any.
+    ///
+    /// # Errors
+    ///
+    /// It returns an error if there is a database error.
+    pub async fn get_tag_for_synonym(&self, name: &str) -> Result<Option<TorrentTag>, Error> {
+        match self.database.get_tag_from_synonym(name).await {
+            Ok(tag) => Ok(Some(tag)),
+            Err(Error::TagNotFound) => Ok(None),
+            Err(err) => Err(err),
+        }
+    }
+
+    /// It replaces the synonyms in a list of tag names with the names of the
+    /// tags they point to. Unknown names are kept.
+    ///
+    /// # Errors
+    ///
+    /// It returns an error if there is a database error.
+    pub async fn resolve_synonyms(&self, tag_names: &[String]) -> Result<Vec<String>, Error> {
+        let mut resolved = Vec::with_capacity(tag_names.len());
+
+        for tag_name in tag_names {
+            match self.get_tag_for_synonym(tag_name).await? {
+                Some(tag) => resolved.push(tag.name),
+                None => resolved.push(tag_name.clone()),
+            }
+        }
+
+        Ok(resolved)
+    }
 }
 
 pub struct DbTorrentListingGenerator {
     database: Arc<Box<dyn Database>>,
+    /// Changes not tracked by the database version, like edits or
+    /// moderation. It starts at a random value so versions are not reused
+    /// after a restart.
+    generation: AtomicU64,
+    query_cache: Arc<QueryCache>,
 }
 
 impl DbTorrentListingGenerator {
     #[must_use]
-    pub fn new(database: Arc<Box<dyn Database>>) -> Self {
-        Self { database }
+    pub fn new(database: Arc<Box<dyn Database>>, query_cache: Arc<QueryCache>) -> Self {
+        Self {
+            database,
+            generation: AtomicU64::new(rand::random()),
+            query_cache,
+        }
+    }
+
+    /// It returns a cheap value that changes whenever the torrent listing
+    /// may have changed.
+    ///
+    /// # Errors
+    ///
+    /// It returns an error if there is a database error.
+    pub async fn data_version(&self) -> Result<String, Error> {
+        let database_version = self.database.get_torrent_listing_version().await?;
+
+        Ok(format!("{database_version}-{}", self.generation.load(Ordering::Relaxed)))
+    }
+
+    /// It changes the data version after a change that is not reflected in
+    /// the database version.
+    pub fn invalidate(&self) {
+        self.generation.fetch_add(1, Ordering::Relaxed);
     }
 
     /// It finds the torrent listing by info-hash
@@ -925,15 +2965,583 @@ impl DbTorrentListingGenerator {
     ///
     /// It returns an error if there is a database error.
     pub async fn generate_listing(&self, specification: &ListingSpecification) -> Result<TorrentsResponse, Error> {
+        // Search results are too diverse to be worth caching
+        if specification.search.is_some() || specification.torrent_ids.is_some() {
+            return self.query_listing(specification).await;
+        }
+
+        // The data version is part of the key, so changes to the listing
+        // never return cached pages.
+        let key = hex::encode(Sha256::digest(format!("{}:{specification:?}", self.data_version().await?)));
+
+        self.query_cache
+            .get_or_load(query::TORRENT_LISTINGS, &key, || self.query_listing(specification))
+            .await
+    }
+
+    async fn query_listing(&self, specification: &ListingSpecification) -> Result<TorrentsResponse, Error> {
         self.database
             .get_torrents_search_sorted_paginated(
                 &specification.search,
                 &specification.categories,
                 &specification.tags,
+                &specification.torrent_ids,
+                &specification.status,
+                &specification.filter,
                 &specification.sort,
+                &specification.then_sort,
                 specification.offset,
                 specification.page_size,
             )
             .await
     }
 }
+
+/// Maximum length of the file extension filter.
+const MAX_FILE_EXTENSION_LENGTH: usize = 10;
+
+/// It builds the listing filter from the user request.
+fn listing_filter_from(request: &ListingRequest) -> Result<ListingFilter, ServiceError> {
+    let uploaded_after = parse_listing_day(request.uploaded_after.as_deref())?;
+    let uploaded_before = parse_listing_day(request.uploaded_before.as_deref())?;
+
+    if request.min_size.is_some_and(i64::is_negative)
+        || request.max_size.is_some_and(i64::is_negative)
+        || request.min_seeders.is_some_and(i64::is_negative)
+    {
+        return Err(ServiceError::InvalidListingFilter);
+    }
+
+    if let (Some(min_size), Some(max_size)) = (request.min_size, request.max_size) {
+        if min_size > max_size {
+            return Err(ServiceError::InvalidListingFilter);
+        }
+    }
+
+    if let (Some(after), Some(before)) = (uploaded_after, uploaded_before) {
+        if after >= before {
+            return Err(ServiceError::InvalidListingFilter);
+        }
+    }
+
+    let file_extension = match request.file_ext.as_deref().map(|ext| ext.trim().trim_start_matches('.')) {
+        None | Some("") => None,
+        Some(ext) if ext.len() <= MAX_FILE_EXTENSION_LENGTH && ext.chars().all(|c| c.is_ascii_alphanumeric()) => {
+            Some(ext.to_ascii_lowercase())
+        }
+        Some(_) => return Err(ServiceError::InvalidListingFilter),
+    };
+
+    let fields = match request.fields.as_deref() {
+        None => vec![],
+        Some(fields) => parse_listing_fields(fields)?,
+    };
+
+    let start_of = |day: NaiveDate| day.and_time(NaiveTime::MIN).format("%Y-%m-%d %H:%M:%S").to_string();
+
+    Ok(ListingFilter {
+        min_size: request.min_size,
+        max_size: request.max_size,
+        uploaded_since: uploaded_after.map(start_of),
+        uploaded_until: uploaded_before.map(start_of),
+        min_seeders: request.min_seeders,
+        file_extension,
+        fields,
+    })
+}
+
+/// It parses the `name:value` pairs of the category fields filter.
+fn parse_listing_fields(fields: &str) -> Result<Vec<(String, String)>, ServiceError> {
+    fields
+        .split(',')
+        .map(str::trim)
+        .filter(|pair| !pair.is_empty())
+        .map(|pair| match pair.split_once(':') {
+            Some((name, value)) if !name.trim().is_empty() && !value.trim().is_empty() => {
+                Ok((name.trim().to_string(), value.trim().to_string()))
+            }
+            _ => Err(ServiceError::InvalidListingFilter),
+        })
+        .collect()
+}
+
+fn parse_listing_day(day: Option<&str>) -> Result<Option<NaiveDate>, ServiceError> {
+    day.map(str::trim)
+        .filter(|day| !day.is_empty())
+        .map(|day| NaiveDate::parse_from_str(day, "%Y-%m-%d").map_err(|_| ServiceError::InvalidListingFilter))
+        .transpose()
+}
+
+/// Tracker URL schemes allowed in the `announce-list`.
+const TRACKER_URL_SCHEMES: [&str; 3] = ["http", "https", "udp"];
+
+/// It trims the tracker URLs and removes empty tiers and duplicate trackers.
+fn normalize_tracker_tiers(tiers: &[Vec<String>]) -> Result<Vec<Vec<String>>, ServiceError> {
+    let mut seen: Vec<String> = vec![];
+    let mut normalized_tiers = vec![];
+
+    for tier in tiers {
+        let mut normalized_tier = vec![];
+
+        for tracker_url in tier {
+            let tracker_url = tracker_url.trim().to_string();
+
+            let Ok(url) = Url::parse(&tracker_url) else {
+                return Err(ServiceError::InvalidTrackerUrl);
+            };
+
+            if !TRACKER_URL_SCHEMES.contains(&url.scheme()) {
+                return Err(ServiceError::InvalidTrackerUrl);
+            }
+
+            if !seen.contains(&tracker_url) {
+                seen.push(tracker_url.clone());
+                normalized_tier.push(tracker_url);
+            }
+        }
+
+        if !normalized_tier.is_empty() {
+            normalized_tiers.push(normalized_tier);
+        }
+    }
+
+    if normalized_tiers.is_empty() {
+        return Err(ServiceError::MissingTrackers);
+    }
+
+    Ok(normalized_tiers)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use chrono::{TimeDelta, Utc};
+
+    use super::{
+        listing_filter_from, magnet_link, mask_peer_address, normalize_tracker_tiers, rewrite_for_download, BulkActionKind,
+        BulkActionRequest, ListingRequest,
+    };
+    use crate::config::{Configuration, Settings, TorrentDownload};
+    use crate::databases::database::Database;
+    use crate::databases::testing::{add_admin, add_category, add_torrent, add_user, new_sqlite_database};
+    use crate::errors::ServiceError;
+    use crate::models::category::CategoryPolicy;
+    use crate::models::torrent::{BulkAction, TorrentId, TorrentStatus};
+    use crate::models::torrent_file::{Torrent, TorrentFile, TorrentInfoDictionary};
+    use crate::services::testing::{services, FakeTrackerApi};
+    use crate::utils::clock::DATETIME_FORMAT;
+
+    fn tiers(tiers: &[&[&str]]) -> Vec<Vec<String>> {
+        tiers
+            .iter()
+            .map(|tier| tier.iter().map(ToString::to_string).collect())
+            .collect()
+    }
+
+    #[test]
+    fn it_should_remove_empty_tiers_and_duplicate_trackers() {
+        assert_eq!(
+            normalize_tracker_tiers(&tiers(&[
+                &[" udp://tracker-a:6969 ", "udp://tracker-b:6969"],
+                &[],
+                &["udp://tracker-a:6969", "https://tracker-c/announce"],
+            ])),
+            Ok(tiers(&[
+                &["udp://tracker-a:6969", "udp://tracker-b:6969"],
+                &["https://tracker-c/announce"],
+            ]))
+        );
+    }
+
+    #[test]
+    fn it_should_reject_invalid_tracker_urls() {
+        assert_eq!(
+            normalize_tracker_tiers(&tiers(&[&["not a url"]])),
+            Err(ServiceError::InvalidTrackerUrl)
+        );
+        assert_eq!(
+            normalize_tracker_tiers(&tiers(&[&["ftp://tracker-a/announce"]])),
+            Err(ServiceError::InvalidTrackerUrl)
+        );
+    }
+
+    #[test]
+    fn it_should_require_at_least_one_tracker() {
+        assert_eq!(normalize_tracker_tiers(&tiers(&[&[]])), Err(ServiceError::MissingTrackers));
+    }
+
+    #[test]
+    fn it_should_mask_peer_addresses_keeping_only_their_network() {
+        assert_eq!(mask_peer_address("203.0.113.42:6881"), "203.0.0.0/16");
+        assert_eq!(
+            mask_peer_address("[2001:db8:85a3:8d3:1319:8a2e:370:7348]:6881"),
+            "2001:db8:85a3::/48"
+        );
+        assert_eq!(mask_peer_address("[::ffff:203.0.113.42]:6881"), "203.0.0.0/16");
+        assert_eq!(mask_peer_address("not an address"), "***");
+    }
+
+    #[test]
+    fn it_should_build_the_listing_filter_from_the_request() {
+        let request = ListingRequest {
+            min_size: Some(1024),
+            max_size: Some(2048),
+            uploaded_after: Some("2024-10-01".to_string()),
+            uploaded_before: Some(" 2024-10-31 ".to_string()),
+            min_seeders: Some(1),
+            file_ext: Some(".MKV".to_string()),
+            fields: Some("resolution:1080p, codec: x265".to_string()),
+            ..ListingRequest::default()
+        };
+
+        let filter = listing_filter_from(&request).unwrap();
+
+        assert_eq!(filter.min_size, Some(1024));
+        assert_eq!(filter.max_size, Some(2048));
+        assert_eq!(filter.uploaded_since, Some("2024-10-01 00:00:00".to_string()));
+        assert_eq!(filter.uploaded_until, Some("2024-10-31 00:00:00".to_string()));
+        assert_eq!(filter.min_seeders, Some(1));
+        assert_eq!(filter.file_extension, Some("mkv".to_string()));
+        assert_eq!(
+            filter.fields,
+            vec![
+                ("resolution".to_string(), "1080p".to_string()),
+                ("codec".to_string(), "x265".to_string())
+            ]
+        );
+    }
+
+    #[test]
+    fn it_should_reject_invalid_listing_filters() {
+        let requests = [
+            ListingRequest {
+                min_size: Some(2048),
+                max_size: Some(1024),
+                ..ListingRequest::default()
+            },
+            ListingRequest {
+                min_seeders: Some(-1),
+                ..ListingRequest::default()
+            },
+            ListingRequest {
+                uploaded_after: Some("2024-10-31".to_string()),
+                uploaded_before: Some("2024-10-01".to_string()),
+                ..ListingRequest::default()
+            },
+            ListingRequest {
+                uploaded_after: Some("yesterday".to_string()),
+                ..ListingRequest::default()
+            },
+            ListingRequest {
+                file_ext: Some("mkv%".to_string()),
+                ..ListingRequest::default()
+            },
+            ListingRequest {
+                fields: Some("resolution".to_string()),
+                ..ListingRequest::default()
+            },
+        ];
+
+        for request in requests {
+            assert_eq!(listing_filter_from(&request), Err(ServiceError::InvalidListingFilter));
+        }
+    }
+
+    fn torrent() -> Torrent {
+        Torrent {
+            info: TorrentInfoDictionary::with(
+                "file.txt",
+                16384,
+                None,
+                0,
+                "3E6A1CB0D5BCA1B2D5AB8E6A0B2D9C1C5C2E7F10",
+                &[TorrentFile {
+                    path: vec!["file.txt".to_string()],
+                    length: 11,
+                    md5sum: None,
+                }],
+            ),
+            announce: None,
+            nodes: None,
+            encoding: None,
+            httpseeds: Some(vec!["https://seed.example.com/file.txt".to_string()]),
+            url_list: Some(vec!["https://mirror.example.com/file.txt".to_string()]),
+            announce_list: None,
+            creation_date: None,
+            comment: None,
+            created_by: None,
+        }
+    }
+
+    #[test]
+    fn it_should_build_magnet_links_with_the_trackers_and_the_web_seeds() {
+        assert_eq!(
+            magnet_link(
+                "5452869be36f9f3350ccee6b4544e7e76caaadab",
+                "file 1.txt",
+                &["udp://tracker:6969".to_string()],
+                &["https://seed.example.com/file.txt".to_string()],
+            ),
+            "magnet:?xt=urn:btih:5452869be36f9f3350ccee6b4544e7e76caaadab&dn=file%201.txt&tr=udp%3A%2F%2Ftracker%3A6969\
+             &ws=https%3A%2F%2Fseed.example.com%2Ffile.txt"
+        );
+    }
+
+    #[test]
+    fn it_should_not_change_the_torrent_with_the_default_download_settings() {
+        let mut rewritten = torrent();
+
+        rewrite_for_download(&mut rewritten, &TorrentDownload::default());
+
+        assert_eq!(rewritten, torrent());
+    }
+
+    #[test]
+    fn it_should_set_the_private_flag_and_the_source_and_strip_the_web_seeds() {
+        let mut rewritten = torrent();
+
+        rewrite_for_download(
+            &mut rewritten,
+            &TorrentDownload {
+                set_private: true,
+                source: Some("Torrust".to_string()),
+                strip_web_seeds: true,
+            },
+        );
+
+        assert_eq!(rewritten.info.private, Some(1));
+        assert_eq!(rewritten.info.source, Some("Torrust".to_string()));
+        assert_eq!(rewritten.httpseeds, None);
+        assert_eq!(rewritten.url_list, None);
+        assert_ne!(rewritten.canonical_info_hash(), torrent().canonical_info_hash());
+    }
+
+    fn bulk_approve(info_hashes: &[String], dry_run: bool) -> BulkActionRequest {
+        BulkActionRequest {
+            action: BulkActionKind::Approve,
+            info_hashes: info_hashes.to_vec(),
+            category_id: None,
+            tag_id: None,
+            dry_run,
+        }
+    }
+
+    async fn status(database: &dyn Database, torrent_id: TorrentId) -> String {
+        database.get_torrent_listing_from_id(torrent_id).await.unwrap().status
+    }
+
+    #[tokio::test]
+    async fn it_should_only_list_the_torrents_in_a_bulk_action_dry_run() {
+        let (_temp_dir, database) = new_sqlite_database().await;
+        let torrent_index = services(Arc::new(Configuration::default()), database.clone())
+            .await
+            .torrent_index;
+
+        let admin_id = add_admin(database.as_ref(), "admin").await;
+        let category_id = add_category(database.as_ref(), "bulk actions").await;
+        let (torrent_id, info_hash) = add_torrent(database.as_ref(), admin_id, category_id, &TorrentStatus::Pending).await;
+        let info_hashes = [info_hash.to_hex_string()];
+
+        let summary = torrent_index
+            .apply_bulk_action(&bulk_approve(&info_hashes, true), Some(admin_id))
+            .await
+            .unwrap();
+
+        assert!(summary.dry_run);
+        assert_eq!(summary.torrents.len(), 1);
+        assert_eq!(summary.torrents[0].torrent_id, torrent_id);
+        assert_eq!(
+            status(database.as_ref(), torrent_id).await,
+            TorrentStatus::Pending.to_string()
+        );
+
+        let summary = torrent_index
+            .apply_bulk_action(&bulk_approve(&info_hashes, false), Some(admin_id))
+            .await
+            .unwrap();
+
+        assert!(!summary.dry_run);
+        assert_eq!(summary.torrents.len(), 1);
+        assert_eq!(summary.torrents[0].torrent_id, torrent_id);
+        assert_eq!(
+            status(database.as_ref(), torrent_id).await,
+            TorrentStatus::Approved.to_string()
+        );
+    }
+
+    #[tokio::test]
+    async fn it_should_report_the_invalid_and_unknown_info_hashes_and_ignore_the_duplicates_in_a_bulk_action() {
+        let (_temp_dir, database) = new_sqlite_database().await;
+        let torrent_index = services(Arc::new(Configuration::default()), database.clone())
+            .await
+            .torrent_index;
+
+        let admin_id = add_admin(database.as_ref(), "admin").await;
+        let category_id = add_category(database.as_ref(), "bulk actions").await;
+        let (torrent_id, info_hash) = add_torrent(database.as_ref(), admin_id, category_id, &TorrentStatus::Pending).await;
+        let unknown_info_hash = "0".repeat(40);
+
+        let summary = torrent_index
+            .apply_bulk_action(
+                &bulk_approve(
+                    &[
+                        info_hash.to_hex_string(),
+                        info_hash.to_hex_string().to_uppercase(),
+                        "not-an-info-hash".to_string(),
+                        unknown_info_hash.clone(),
+                    ],
+                    false,
+                ),
+                Some(admin_id),
+            )
+            .await
+            .unwrap();
+
+        assert_eq!(summary.torrents.len(), 1);
+        assert_eq!(summary.torrents[0].torrent_id, torrent_id);
+        assert!(summary.skipped.is_empty());
+        assert_eq!(summary.not_found, vec!["not-an-info-hash".to_string(), unknown_info_hash]);
+
+        assert!(matches!(
+            torrent_index
+                .apply_bulk_action(&bulk_approve(&[], false), Some(admin_id))
+                .await,
+            Err(ServiceError::InvalidBulkAction)
+        ));
+    }
+
+    #[tokio::test]
+    async fn it_should_skip_the_torrents_that_must_be_co_signed_in_a_bulk_approval() {
+        let (_temp_dir, database) = new_sqlite_database().await;
+        let torrent_index = services(Arc::new(Configuration::default()), database.clone())
+            .await
+            .torrent_index;
+
+        let admin_id = add_admin(database.as_ref(), "admin").await;
+        let category_id = add_category(database.as_ref(), "bulk actions").await;
+        database
+            .upsert_category_policy(category_id, &CategoryPolicy { co_signing: true })
+            .await
+            .unwrap();
+        let (torrent_id, info_hash) = add_torrent(database.as_ref(), admin_id, category_id, &TorrentStatus::Pending).await;
+
+        let summary = torrent_index
+            .apply_bulk_action(&bulk_approve(&[info_hash.to_hex_string()], false), Some(admin_id))
+            .await
+            .unwrap();
+
+        assert!(summary.torrents.is_empty());
+        assert_eq!(summary.skipped, vec![info_hash.to_hex_string()]);
+        assert_eq!(
+            status(database.as_ref(), torrent_id).await,
+            TorrentStatus::Pending.to_string()
+        );
+    }
+
+    #[tokio::test]
+    async fn it_should_roll_back_a_bulk_action_when_it_fails_for_one_torrent() {
+        let (_temp_dir, database) = new_sqlite_database().await;
+
+        let admin_id = add_admin(database.as_ref(), "admin").await;
+        let category_id = add_category(database.as_ref(), "bulk actions").await;
+        let (torrent_id, _info_hash) = add_torrent(database.as_ref(), admin_id, category_id, &TorrentStatus::Approved).await;
+        let tag_id = database.insert_tag_and_get_id("bulk").await.unwrap();
+
+        // The tag can't be added to a torrent that does not exist
+        let result = database
+            .apply_bulk_action(&[torrent_id, torrent_id + 1], &BulkAction::AddTag(tag_id))
+            .await;
+
+        assert!(result.is_err());
+        assert!(database.get_tags_for_torrent_id(torrent_id).await.unwrap().is_empty());
+    }
+
+    #[tokio::test]
+    async fn it_should_release_the_scheduled_torrents_when_their_publish_date_has_passed() {
+        let tracker_api = FakeTrackerApi::start().await;
+        let mut settings = Settings::default();
+        settings.tracker.api_url = tracker_api.url.clone();
+
+        let (_temp_dir, database) = new_sqlite_database().await;
+        let torrent_index = services(Arc::new(Configuration::new(settings)), database.clone())
+            .await
+            .torrent_index;
+
+        let admin_id = add_admin(database.as_ref(), "admin").await;
+        let category_id = add_category(database.as_ref(), "scheduled releases").await;
+        let (due_torrent_id, due_info_hash) =
+            add_torrent(database.as_ref(), admin_id, category_id, &TorrentStatus::Scheduled).await;
+        let (future_torrent_id, _future_info_hash) =
+            add_torrent(database.as_ref(), admin_id, category_id, &TorrentStatus::Scheduled).await;
+
+        for (torrent_id, publish_at) in [
+            (due_torrent_id, Utc::now() - TimeDelta::minutes(1)),
+            (future_torrent_id, Utc::now() + TimeDelta::days(1)),
+        ] {
+            database
+                .insert_torrent_schedule(
+                    torrent_id,
+                    &publish_at.format(DATETIME_FORMAT).to_string(),
+                    &TorrentStatus::Approved,
+                )
+                .await
+                .unwrap();
+        }
+
+        torrent_index.publish_scheduled_torrents().await;
+
+        assert_eq!(
+            status(database.as_ref(), due_torrent_id).await,
+            TorrentStatus::Approved.to_string()
+        );
+        assert_eq!(
+            status(database.as_ref(), future_torrent_id).await,
+            TorrentStatus::Scheduled.to_string()
+        );
+        assert_eq!(
+            tracker_api.requests(),
+            vec![format!("POST /api/v1/whitelist/{}", due_info_hash.to_hex_string())]
+        );
+    }
+
+    #[tokio::test]
+    async fn it_should_not_hold_the_uploads_of_the_trusted_uploaders_for_moderation() {
+        let mut settings = Settings::default();
+        settings.website.moderated_uploads = true;
+
+        let (_temp_dir, database) = new_sqlite_database().await;
+        let torrent_index = services(Arc::new(Configuration::new(settings)), database.clone())
+            .await
+            .torrent_index;
+
+        let category_id = add_category(database.as_ref(), "moderated").await;
+        let co_signed_category_id = add_category(database.as_ref(), "co-signed").await;
+        database
+            .upsert_category_policy(co_signed_category_id, &CategoryPolicy { co_signing: true })
+            .await
+            .unwrap();
+
+        let user_id = add_user(database.as_ref(), "user").await;
+        let trusted_user_id = add_user(database.as_ref(), "trusted").await;
+        database.upsert_user_trusted_override(trusted_user_id, true).await.unwrap();
+
+        assert_eq!(
+            torrent_index.initial_status_for_upload(Some(user_id), category_id).await,
+            TorrentStatus::Pending
+        );
+        assert_eq!(
+            torrent_index
+                .initial_status_for_upload(Some(trusted_user_id), category_id)
+                .await,
+            TorrentStatus::Approved
+        );
+        // Co-signing can't be skipped
+        assert_eq!(
+            torrent_index
+                .initial_status_for_upload(Some(trusted_user_id), co_signed_category_id)
+                .await,
+            TorrentStatus::Pending
+        );
+    }
+}
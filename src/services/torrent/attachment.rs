@@ -0,0 +1,389 @@
+//! Auxiliary files attached to the torrents.
+//!
+//! Uploaders can attach the NFO, the text file with the release information,
+//! and sample screenshots to their torrents. They are shown in the torrent
+//! details. Unlike [previews](super::preview), they don't need to be in the
+//! torrent contents.
+//!
+//! A torrent has at most one NFO. Attaching a new NFO replaces the previous
+//! one. NFO files are usually encoded with the CP437 code page, so they are
+//! not required to be UTF-8. They are served as they were uploaded.
+//!
+//! Attachments are disabled unless the `[torrent.attachments]` section is
+//! present:
+//!
+//! ```toml
+//! [torrent.attachments]
+//! max_nfo_size = 65536
+//! max_screenshot_size = 2097152
+//! max_screenshots_per_torrent = 10
+//! ```
+use std::sync::Arc;
+
+use bittorrent_primitives::info_hash::InfoHash;
+
+use super::DbTorrentListingGenerator;
+use crate::config::{Configuration, TorrentAttachments};
+use crate::databases::database::{Database, Error};
+use crate::errors::ServiceError;
+use crate::models::torrent::{TorrentId, TorrentListing};
+use crate::models::torrent_attachment::{AttachmentId, AttachmentKind, TorrentAttachment};
+use crate::models::user::UserId;
+use crate::services::authorization::{self, ACTION};
+use crate::services::image_upload::detect_content_type;
+use crate::services::user::Repository;
+
+/// The content type of the NFO files.
+const NFO_CONTENT_TYPE: &str = "text/plain";
+
+/// Max length in characters of the stored file names.
+const MAX_FILE_NAME_LENGTH: usize = 255;
+
+/// User request to attach a file to a torrent.
+pub struct AddAttachmentRequest {
+    pub kind: AttachmentKind,
+    /// The name of the uploaded file.
+    pub file_name: String,
+    pub content: Vec<u8>,
+}
+
+pub struct Service {
+    configuration: Arc<Configuration>,
+    user_repository: Arc<Box<dyn Repository>>,
+    torrent_listing_generator: Arc<DbTorrentListingGenerator>,
+    attachment_repository: Arc<DbTorrentAttachmentRepository>,
+    authorization_service: Arc<authorization::Service>,
+}
+
+impl Service {
+    #[must_use]
+    pub fn new(
+        configuration: Arc<Configuration>,
+        user_repository: Arc<Box<dyn Repository>>,
+        torrent_listing_generator: Arc<DbTorrentListingGenerator>,
+        attachment_repository: Arc<DbTorrentAttachmentRepository>,
+        authorization_service: Arc<authorization::Service>,
+    ) -> Service {
+        Service {
+            configuration,
+            user_repository,
+            torrent_listing_generator,
+            attachment_repository,
+            authorization_service,
+        }
+    }
+
+    /// It attaches a file to a torrent. Owners can always attach files to
+    /// their torrents. Other users need permission to edit the torrent.
+    ///
+    /// # Errors
+    ///
+    /// It returns an error if:
+    ///
+    /// * Attachments are not enabled.
+    /// * The user does not have permission to edit the torrent.
+    /// * The torrent does not exist.
+    /// * The file is empty or too big for its type.
+    /// * The NFO is not a text file or the screenshot is not an image.
+    /// * The torrent has already the max number of screenshots.
+    /// * There is a database error.
+    pub async fn add_attachment(
+        &self,
+        info_hash: &InfoHash,
+        request: &AddAttachmentRequest,
+        maybe_user_id: Option<UserId>,
+    ) -> Result<TorrentAttachment, ServiceError> {
+        let attachments = self.attachments_settings().await?;
+
+        let (user_id, torrent_listing) = self.authorize_edit(info_hash, maybe_user_id).await?;
+
+        let size = u64::try_from(request.content.len()).unwrap_or(u64::MAX);
+
+        let max_size = match request.kind {
+            AttachmentKind::Nfo => attachments.max_nfo_size,
+            AttachmentKind::Screenshot => attachments.max_screenshot_size,
+        };
+
+        if size == 0 || size > max_size {
+            return Err(ServiceError::InvalidAttachmentSize);
+        }
+
+        let content_type =
+            attachment_content_type(request.kind, &request.content).ok_or(ServiceError::UnsupportedAttachmentType)?;
+
+        let existing_attachments = self.attachment_repository.get_all(torrent_listing.torrent_id).await?;
+
+        let replaced_attachments: Vec<AttachmentId> = match request.kind {
+            AttachmentKind::Nfo => existing_attachments
+                .iter()
+                .filter(|attachment| attachment.kind == AttachmentKind::Nfo.as_str())
+                .map(|attachment| attachment.attachment_id)
+                .collect(),
+            AttachmentKind::Screenshot => {
+                let screenshots = existing_attachments
+                    .iter()
+                    .filter(|attachment| attachment.kind == AttachmentKind::Screenshot.as_str())
+                    .count();
+
+                if screenshots >= attachments.max_screenshots_per_torrent {
+                    return Err(ServiceError::TooManyScreenshots);
+                }
+
+                vec![]
+            }
+        };
+
+        let file_name = sanitize_file_name(&request.file_name, request.kind);
+
+        let attachment_id = self
+            .attachment_repository
+            .add(
+                torrent_listing.torrent_id,
+                user_id,
+                request.kind,
+                &file_name,
+                content_type,
+                &request.content,
+            )
+            .await?;
+
+        // The previous NFO is removed after adding the new one, so the torrent
+        // keeps it if the new one can't be added
+        for replaced_attachment_id in replaced_attachments {
+            self.attachment_repository
+                .delete(torrent_listing.torrent_id, replaced_attachment_id)
+                .await?;
+        }
+
+        Ok(TorrentAttachment {
+            attachment_id,
+            torrent_id: torrent_listing.torrent_id,
+            kind: request.kind.as_str().to_string(),
+            file_name,
+            content_type: content_type.to_string(),
+            size: i64::try_from(size).unwrap_or(i64::MAX),
+        })
+    }
+
+    /// It returns the files attached to a torrent, without their contents.
+    ///
+    /// # Errors
+    ///
+    /// It returns an error if:
+    ///
+    /// * The user does not have permission to get the torrent info.
+    /// * The torrent does not exist.
+    /// * There is a database error.
+    pub async fn get_attachments(
+        &self,
+        info_hash: &InfoHash,
+        maybe_user_id: Option<UserId>,
+    ) -> Result<Vec<TorrentAttachment>, ServiceError> {
+        self.authorization_service
+            .authorize(ACTION::GetTorrentInfo, maybe_user_id)
+            .await?;
+
+        let torrent_listing = self.torrent_listing_generator.one_torrent_by_info_hash(info_hash).await?;
+
+        Ok(self.attachment_repository.get_all(torrent_listing.torrent_id).await?)
+    }
+
+    /// It returns the content type and the contents of a file attached to a
+    /// torrent.
+    ///
+    /// # Errors
+    ///
+    /// It returns an error if:
+    ///
+    /// * The user does not have permission to get the torrent info.
+    /// * The torrent or the attachment do not exist.
+    /// * There is a database error.
+    pub async fn get_attachment_content(
+        &self,
+        info_hash: &InfoHash,
+        attachment_id: AttachmentId,
+        maybe_user_id: Option<UserId>,
+    ) -> Result<(String, Vec<u8>), ServiceError> {
+        self.authorization_service
+            .authorize(ACTION::GetTorrentInfo, maybe_user_id)
+            .await?;
+
+        let torrent_listing = self.torrent_listing_generator.one_torrent_by_info_hash(info_hash).await?;
+
+        Ok(self
+            .attachment_repository
+            .get_content(torrent_listing.torrent_id, attachment_id)
+            .await?)
+    }
+
+    /// It removes a file attached to a torrent. Owners can always remove the
+    /// files attached to their torrents. Other users need permission to edit
+    /// the torrent.
+    ///
+    /// # Errors
+    ///
+    /// It returns an error if:
+    ///
+    /// * The user does not have permission to edit the torrent.
+    /// * The torrent or the attachment do not exist.
+    /// * There is a database error.
+    pub async fn delete_attachment(
+        &self,
+        info_hash: &InfoHash,
+        attachment_id: AttachmentId,
+        maybe_user_id: Option<UserId>,
+    ) -> Result<(), ServiceError> {
+        let (_user_id, torrent_listing) = self.authorize_edit(info_hash, maybe_user_id).await?;
+
+        Ok(self
+            .attachment_repository
+            .delete(torrent_listing.torrent_id, attachment_id)
+            .await?)
+    }
+
+    async fn attachments_settings(&self) -> Result<TorrentAttachments, ServiceError> {
+        self.configuration
+            .settings
+            .read()
+            .await
+            .torrent
+            .as_ref()
+            .and_then(|torrent| torrent.attachments.clone())
+            .ok_or(ServiceError::TorrentAttachmentsDisabled)
+    }
+
+    async fn authorize_edit(
+        &self,
+        info_hash: &InfoHash,
+        maybe_user_id: Option<UserId>,
+    ) -> Result<(UserId, TorrentListing), ServiceError> {
+        let user_id = maybe_user_id.ok_or(ServiceError::UnauthorizedActionForGuests)?;
+
+        let user = self.user_repository.get_compact(&user_id).await?;
+
+        let torrent_listing = self.torrent_listing_generator.one_torrent_by_info_hash(info_hash).await?;
+
+        if torrent_listing.uploader != user.username {
+            self.authorization_service
+                .authorize_in_category(ACTION::EditTorrentInfo, Some(user_id), torrent_listing.category_id)
+                .await?;
+        }
+
+        Ok((user_id, torrent_listing))
+    }
+}
+
+/// It returns the content type of the attachment. Screenshots are detected
+/// from their contents. NFO files can be in any encoding, but they can't
+/// contain null bytes.
+fn attachment_content_type(kind: AttachmentKind, content: &[u8]) -> Option<&'static str> {
+    match kind {
+        AttachmentKind::Nfo => (!content.contains(&0) && detect_content_type(content).is_none()).then_some(NFO_CONTENT_TYPE),
+        AttachmentKind::Screenshot => detect_content_type(content),
+    }
+}
+
+/// It removes the directories from the file name and limits its length.
+fn sanitize_file_name(file_name: &str, kind: AttachmentKind) -> String {
+    let file_name = file_name.rsplit(['/', '\\']).next().unwrap_or_default().trim();
+
+    if file_name.is_empty() {
+        return kind.as_str().to_string();
+    }
+
+    file_name.chars().take(MAX_FILE_NAME_LENGTH).collect()
+}
+
+pub struct DbTorrentAttachmentRepository {
+    database: Arc<Box<dyn Database>>,
+}
+
+impl DbTorrentAttachmentRepository {
+    #[must_use]
+    pub fn new(database: Arc<Box<dyn Database>>) -> Self {
+        Self { database }
+    }
+
+    /// It attaches a file to a torrent.
+    ///
+    /// # Errors
+    ///
+    /// It returns an error if there is a database error.
+    pub async fn add(
+        &self,
+        torrent_id: TorrentId,
+        uploader_id: UserId,
+        kind: AttachmentKind,
+        file_name: &str,
+        content_type: &str,
+        content: &[u8],
+    ) -> Result<AttachmentId, Error> {
+        self.database
+            .insert_torrent_attachment(torrent_id, uploader_id, kind, file_name, content_type, content)
+            .await
+    }
+
+    /// It returns the files attached to a torrent, without their contents.
+    ///
+    /// # Errors
+    ///
+    /// It returns an error if there is a database error.
+    pub async fn get_all(&self, torrent_id: TorrentId) -> Result<Vec<TorrentAttachment>, Error> {
+        self.database.get_torrent_attachments(torrent_id).await
+    }
+
+    /// It returns the content type and the contents of an attachment.
+    ///
+    /// # Errors
+    ///
+    /// It returns an error if the attachment does not exist or there is a
+    /// database error.
+    pub async fn get_content(&self, torrent_id: TorrentId, attachment_id: AttachmentId) -> Result<(String, Vec<u8>), Error> {
+        self.database.get_torrent_attachment_content(torrent_id, attachment_id).await
+    }
+
+    /// It removes an attachment.
+    ///
+    /// # Errors
+    ///
+    /// It returns an error if the attachment does not exist or there is a
+    /// database error.
+    pub async fn delete(&self, torrent_id: TorrentId, attachment_id: AttachmentId) -> Result<(), Error> {
+        self.database.delete_torrent_attachment(torrent_id, attachment_id).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{attachment_content_type, sanitize_file_name};
+    use crate::models::torrent_attachment::AttachmentKind;
+
+    #[test]
+    fn it_should_accept_nfo_files_in_any_text_encoding() {
+        // "═══" in CP437
+        assert_eq!(
+            attachment_content_type(AttachmentKind::Nfo, b"\xcd\xcd\xcd Release \xcd\xcd\xcd"),
+            Some("text/plain")
+        );
+        assert_eq!(attachment_content_type(AttachmentKind::Nfo, b"binary\0file"), None);
+        assert_eq!(
+            attachment_content_type(AttachmentKind::Nfo, b"\x89PNG\r\n\x1a\n\0\0\0\rIHDR"),
+            None
+        );
+    }
+
+    #[test]
+    fn it_should_only_accept_images_as_screenshots() {
+        assert_eq!(
+            attachment_content_type(AttachmentKind::Screenshot, b"\x89PNG\r\n\x1a\n\0\0\0\rIHDR"),
+            Some("image/png")
+        );
+        assert_eq!(attachment_content_type(AttachmentKind::Screenshot, b"Release notes"), None);
+    }
+
+    #[test]
+    fn it_should_remove_the_directories_from_the_file_name() {
+        assert_eq!(sanitize_file_name("release/info.nfo", AttachmentKind::Nfo), "info.nfo");
+        assert_eq!(sanitize_file_name("", AttachmentKind::Screenshot), "screenshot");
+    }
+}
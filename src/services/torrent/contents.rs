@@ -0,0 +1,251 @@
+//! File tree and file kinds of the torrent contents.
+//!
+//! The torrent details include the files as a tree of folders, instead of a
+//! flat list of paths, so clients can display them like a file browser. Each
+//! file is classified by its extension, with its MIME type when it's known:
+//!
+//! ```text
+//! Movie/
+//!   Movie.mkv           -> video (video/x-matroska)
+//!   Subs/Movie.en.srt   -> subtitle (application/x-subrip)
+//!   Extras.zip          -> archive (application/zip)
+//! ```
+//!
+//! The aggregate stats, like the number of videos or the total size of the
+//! archives, let clients tell at a glance what the torrent contains.
+use std::collections::BTreeMap;
+
+use crate::models::torrent_contents::{FileKind, FileNode, FileStats, TorrentContents};
+use crate::models::torrent_file::TorrentFile;
+
+/// Known file types: extension, MIME type and kind.
+const FILE_TYPES: &[(&str, &str, FileKind)] = &[
+    ("mkv", "video/x-matroska", FileKind::Video),
+    ("mp4", "video/mp4", FileKind::Video),
+    ("m4v", "video/x-m4v", FileKind::Video),
+    ("avi", "video/x-msvideo", FileKind::Video),
+    ("mov", "video/quicktime", FileKind::Video),
+    ("wmv", "video/x-ms-wmv", FileKind::Video),
+    ("webm", "video/webm", FileKind::Video),
+    ("mpg", "video/mpeg", FileKind::Video),
+    ("mpeg", "video/mpeg", FileKind::Video),
+    ("ts", "video/mp2t", FileKind::Video),
+    ("m2ts", "video/mp2t", FileKind::Video),
+    ("flac", "audio/flac", FileKind::Audio),
+    ("mp3", "audio/mpeg", FileKind::Audio),
+    ("m4a", "audio/mp4", FileKind::Audio),
+    ("aac", "audio/aac", FileKind::Audio),
+    ("ogg", "audio/ogg", FileKind::Audio),
+    ("opus", "audio/opus", FileKind::Audio),
+    ("wav", "audio/wav", FileKind::Audio),
+    ("jpg", "image/jpeg", FileKind::Image),
+    ("jpeg", "image/jpeg", FileKind::Image),
+    ("png", "image/png", FileKind::Image),
+    ("gif", "image/gif", FileKind::Image),
+    ("webp", "image/webp", FileKind::Image),
+    ("bmp", "image/bmp", FileKind::Image),
+    ("srt", "application/x-subrip", FileKind::Subtitle),
+    ("ass", "text/x-ssa", FileKind::Subtitle),
+    ("ssa", "text/x-ssa", FileKind::Subtitle),
+    ("vtt", "text/vtt", FileKind::Subtitle),
+    ("sub", "text/plain", FileKind::Subtitle),
+    ("idx", "application/octet-stream", FileKind::Subtitle),
+    ("txt", "text/plain", FileKind::Document),
+    ("nfo", "text/plain", FileKind::Document),
+    ("md", "text/markdown", FileKind::Document),
+    ("pdf", "application/pdf", FileKind::Document),
+    ("epub", "application/epub+zip", FileKind::Document),
+    ("mobi", "application/x-mobipocket-ebook", FileKind::Document),
+    ("cbz", "application/vnd.comicbook+zip", FileKind::Document),
+    ("cbr", "application/vnd.comicbook-rar", FileKind::Document),
+    ("zip", "application/zip", FileKind::Archive),
+    ("rar", "application/vnd.rar", FileKind::Archive),
+    ("7z", "application/x-7z-compressed", FileKind::Archive),
+    ("tar", "application/x-tar", FileKind::Archive),
+    ("gz", "application/gzip", FileKind::Archive),
+    ("bz2", "application/x-bzip2", FileKind::Archive),
+    ("xz", "application/x-xz", FileKind::Archive),
+    ("zst", "application/zstd", FileKind::Archive),
+    ("iso", "application/x-iso9660-image", FileKind::DiskImage),
+    ("img", "application/octet-stream", FileKind::DiskImage),
+    ("dmg", "application/x-apple-diskimage", FileKind::DiskImage),
+    ("exe", "application/vnd.microsoft.portable-executable", FileKind::Executable),
+    ("msi", "application/x-msi", FileKind::Executable),
+    ("apk", "application/vnd.android.package-archive", FileKind::Executable),
+    ("deb", "application/vnd.debian.binary-package", FileKind::Executable),
+    ("rpm", "application/x-rpm", FileKind::Executable),
+    ("appimage", "application/x-executable", FileKind::Executable),
+];
+
+/// It guesses the kind and the MIME type of a file from the extension of its
+/// name. Files without a known extension are `other`.
+#[must_use]
+pub fn classify(file_name: &str) -> (FileKind, Option<&'static str>) {
+    let Some((_, extension)) = file_name.rsplit_once('.') else {
+        return (FileKind::Other, None);
+    };
+
+    let extension = extension.to_lowercase();
+
+    FILE_TYPES
+        .iter()
+        .find(|(known_extension, _, _)| *known_extension == extension)
+        .map_or((FileKind::Other, None), |(_, mime_type, kind)| (*kind, Some(*mime_type)))
+}
+
+/// It builds the file tree of a torrent and the aggregate stats of its
+/// files. File paths are relative to the torrent root folder.
+#[must_use]
+pub fn build(files: &[TorrentFile]) -> TorrentContents {
+    let mut root = Folder::default();
+    let mut stats = FileStats::default();
+
+    for file in files {
+        let Some((file_name, folders)) = file.path.split_last() else {
+            continue;
+        };
+
+        let (kind, mime_type) = classify(file_name);
+
+        add_to_stats(&mut stats, kind, file.length);
+
+        let mut folder = &mut root;
+
+        for folder_name in folders {
+            folder = folder.folders.entry(folder_name.clone()).or_default();
+            folder.length += file.length;
+        }
+
+        folder.files.push(FileNode {
+            name: file_name.clone(),
+            length: file.length,
+            kind: Some(kind),
+            mime_type: mime_type.map(ToString::to_string),
+            children: vec![],
+        });
+    }
+
+    TorrentContents {
+        tree: root.into_nodes(),
+        stats,
+    }
+}
+
+fn add_to_stats(stats: &mut FileStats, kind: FileKind, length: i64) {
+    stats.file_count += 1;
+
+    match kind {
+        FileKind::Video => {
+            stats.video_count += 1;
+            stats.video_size += length;
+        }
+        FileKind::Audio => {
+            stats.audio_count += 1;
+            stats.audio_size += length;
+        }
+        FileKind::Image => stats.image_count += 1,
+        FileKind::Archive => {
+            stats.archive_count += 1;
+            stats.archive_size += length;
+        }
+        FileKind::Subtitle | FileKind::Document | FileKind::DiskImage | FileKind::Executable | FileKind::Other => {}
+    }
+}
+
+/// A folder while the tree is built. Sub-folders are kept by name, so huge
+/// packs don't need a linear search for each file.
+#[derive(Default)]
+struct Folder {
+    length: i64,
+    folders: BTreeMap<String, Folder>,
+    files: Vec<FileNode>,
+}
+
+impl Folder {
+    fn into_nodes(self) -> Vec<FileNode> {
+        let mut files = self.files;
+
+        files.sort_by(|a, b| a.name.cmp(&b.name));
+
+        self.folders
+            .into_iter()
+            .map(|(name, folder)| FileNode {
+                name,
+                length: folder.length,
+                kind: None,
+                mime_type: None,
+                children: folder.into_nodes(),
+            })
+            .chain(files)
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{build, classify};
+    use crate::models::torrent_contents::FileKind;
+    use crate::models::torrent_file::TorrentFile;
+
+    fn file(path: &str, length: i64) -> TorrentFile {
+        TorrentFile {
+            path: path.split('/').map(ToString::to_string).collect(),
+            length,
+            md5sum: None,
+        }
+    }
+
+    #[test]
+    fn it_should_classify_the_files_by_extension() {
+        assert_eq!(classify("Movie.MKV"), (FileKind::Video, Some("video/x-matroska")));
+        assert_eq!(classify("archive.tar.gz"), (FileKind::Archive, Some("application/gzip")));
+        assert_eq!(classify("Movie.en.srt"), (FileKind::Subtitle, Some("application/x-subrip")));
+        assert_eq!(classify("README"), (FileKind::Other, None));
+        assert_eq!(classify("data.xyz"), (FileKind::Other, None));
+    }
+
+    #[test]
+    fn it_should_build_the_file_tree_with_the_folders_first() {
+        let contents = build(&[
+            file("README.txt", 1),
+            file("Season 2/S02E01.mkv", 20),
+            file("Season 1/S01E02.mkv", 10),
+            file("Season 1/S01E01.mkv", 10),
+            file("Season 1/Subs/S01E01.srt", 2),
+        ]);
+
+        let names: Vec<&str> = contents.tree.iter().map(|node| node.name.as_str()).collect();
+
+        assert_eq!(names, vec!["Season 1", "Season 2", "README.txt"]);
+
+        let season_1 = &contents.tree[0];
+
+        assert!(season_1.is_folder());
+        assert_eq!(season_1.length, 22);
+
+        let names: Vec<&str> = season_1.children.iter().map(|node| node.name.as_str()).collect();
+
+        assert_eq!(names, vec!["Subs", "S01E01.mkv", "S01E02.mkv"]);
+        assert_eq!(season_1.children[1].kind, Some(FileKind::Video));
+        assert_eq!(season_1.children[0].children[0].kind, Some(FileKind::Subtitle));
+    }
+
+    #[test]
+    fn it_should_compute_the_aggregate_stats_of_the_files() {
+        let contents = build(&[
+            file("Movie/Movie.mkv", 700),
+            file("Movie/Sample.mp4", 30),
+            file("Movie/Extras.zip", 100),
+            file("Movie/Extras.part2.rar", 50),
+            file("Movie/Cover.jpg", 1),
+            file("Movie/Movie.nfo", 1),
+        ]);
+
+        assert_eq!(contents.stats.file_count, 6);
+        assert_eq!(contents.stats.video_count, 2);
+        assert_eq!(contents.stats.video_size, 730);
+        assert_eq!(contents.stats.archive_count, 2);
+        assert_eq!(contents.stats.archive_size, 150);
+        assert_eq!(contents.stats.image_count, 1);
+    }
+}
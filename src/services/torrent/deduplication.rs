@@ -0,0 +1,498 @@
+//! Detection of duplicate uploads by content.
+//!
+//! Torrents with a different info-hash can share most of their contents, for
+//! example when a release is packed again or when a season pack contains
+//! episodes that were already uploaded. When a torrent is uploaded, the index
+//! compares it with the existing torrents that have files with the same
+//! sizes:
+//!
+//! - The files with the same size, weighted by their size. Only the files
+//!   larger than `min_file_size` are compared, and padding files are ignored.
+//! - The piece hashes, when both torrents have the same piece length.
+//!
+//! The overlap is the fraction of the contents of the smallest torrent that
+//! is also in the other one, the greatest of both comparisons. When it
+//! reaches the threshold, the upload is rejected or flagged. Administrators
+//! can list the flagged torrents grouped in clusters of torrents connected by
+//! their contents.
+//!
+//! Deduplication is disabled unless the `[torrent.deduplication]` section is
+//! present:
+//!
+//! ```toml
+//! [torrent.deduplication]
+//! threshold = 0.9
+//! action = "flag"
+//! min_file_size = 1048576
+//! max_candidates = 50
+//! ```
+use std::collections::{HashMap, HashSet};
+use std::sync::Arc;
+
+use tracing::error;
+
+use crate::config::{Configuration, DuplicateAction, TorrentDeduplication};
+use crate::databases::database::{Database, Error};
+use crate::errors::ServiceError;
+use crate::models::torrent::{SimilarTorrent, TorrentId};
+use crate::models::torrent_duplicate::{DuplicateCluster, DuplicatePair};
+use crate::models::torrent_file::{Torrent, TorrentFile};
+use crate::models::user::UserId;
+use crate::services::authorization::{self, ACTION};
+use crate::services::torrent_structure::is_padding_file;
+use crate::storage::torrent_file::{self, TorrentFileStore};
+
+/// Max number of file sizes of the uploaded torrent used to look for
+/// candidates. The largest files are used.
+const MAX_FILE_LENGTHS_COMPARED: usize = 100;
+
+/// Length in hex characters of a piece hash (SHA-1).
+const PIECE_HASH_HEX_LENGTH: usize = 40;
+
+/// An existing torrent whose contents overlap with an uploaded torrent.
+#[derive(Debug, Clone, PartialEq)]
+pub struct DuplicateMatch {
+    pub torrent_id: TorrentId,
+    pub overlap: f64,
+}
+
+pub struct Service {
+    configuration: Arc<Configuration>,
+    duplicate_repository: Arc<DbTorrentDuplicateRepository>,
+    authorization_service: Arc<authorization::Service>,
+}
+
+impl Service {
+    #[must_use]
+    pub fn new(
+        configuration: Arc<Configuration>,
+        duplicate_repository: Arc<DbTorrentDuplicateRepository>,
+        authorization_service: Arc<authorization::Service>,
+    ) -> Service {
+        Service {
+            configuration,
+            duplicate_repository,
+            authorization_service,
+        }
+    }
+
+    /// It compares the contents of an uploaded torrent with the existing
+    /// torrents. It returns the duplicates, from the greatest overlap to the
+    /// smallest. When deduplication is disabled, it returns no duplicates.
+    ///
+    /// # Errors
+    ///
+    /// It returns an error if:
+    ///
+    /// * The torrent is a duplicate and the action is `reject`.
+    /// * There is a database error.
+    pub async fn check_upload(&self, torrent: &Torrent) -> Result<Vec<DuplicateMatch>, ServiceError> {
+        let Some(deduplication) = self.deduplication_settings().await else {
+            return Ok(vec![]);
+        };
+
+        let fingerprint = ContentFingerprint::from_torrent(torrent, deduplication.min_file_size);
+
+        let mut lengths: Vec<i64> = fingerprint.file_lengths.clone();
+        lengths.dedup();
+        lengths.truncate(MAX_FILE_LENGTHS_COMPARED);
+
+        let candidates = self
+            .duplicate_repository
+            .get_candidates(&lengths, deduplication.max_candidates)
+            .await?;
+
+        let mut duplicates = vec![];
+
+        for torrent_id in candidates {
+            let candidate = self
+                .duplicate_repository
+                .get_fingerprint(torrent_id, deduplication.min_file_size)
+                .await?;
+
+            let overlap = fingerprint.overlap(&candidate);
+
+            if overlap >= deduplication.threshold {
+                duplicates.push(DuplicateMatch { torrent_id, overlap });
+            }
+        }
+
+        if !duplicates.is_empty() && deduplication.action == DuplicateAction::Reject {
+            return Err(ServiceError::DuplicateTorrentContent);
+        }
+
+        duplicates.sort_by(|a, b| b.overlap.total_cmp(&a.overlap));
+
+        Ok(duplicates)
+    }
+
+    /// It records the duplicates of an uploaded torrent so that they are
+    /// listed for the administrators. Errors are logged, they don't make the
+    /// upload fail.
+    pub async fn flag(&self, torrent_id: TorrentId, duplicates: &[DuplicateMatch]) {
+        for duplicate in duplicates {
+            if let Err(e) = self
+                .duplicate_repository
+                .add(torrent_id, duplicate.torrent_id, duplicate.overlap)
+                .await
+            {
+                error!(
+                    "Failed to flag torrent #{torrent_id} as a duplicate of torrent #{}: {e:?}",
+                    duplicate.torrent_id
+                );
+            }
+        }
+    }
+
+    /// It returns the detected duplicates grouped in clusters of torrents
+    /// connected by their contents, from the most recently detected.
+    ///
+    /// # Errors
+    ///
+    /// It returns an error if:
+    ///
+    /// * The user is not an administrator.
+    /// * There is a database error.
+    pub async fn get_clusters(&self, maybe_user_id: Option<UserId>) -> Result<Vec<DuplicateCluster>, ServiceError> {
+        self.authorization_service
+            .authorize(ACTION::GetDuplicateTorrents, maybe_user_id)
+            .await?;
+
+        let pairs = self.duplicate_repository.get_all().await?;
+
+        let torrents: HashMap<TorrentId, SimilarTorrent> = self
+            .duplicate_repository
+            .get_duplicated_torrents()
+            .await?
+            .into_iter()
+            .map(|torrent| (torrent.torrent_id, torrent))
+            .collect();
+
+        Ok(group_in_clusters(pairs)
+            .into_iter()
+            .map(|(torrent_ids, pairs)| DuplicateCluster {
+                torrents: torrent_ids.iter().filter_map(|id| torrents.get(id).cloned()).collect(),
+                pairs,
+            })
+            .collect())
+    }
+
+    async fn deduplication_settings(&self) -> Option<TorrentDeduplication> {
+        let settings = self.configuration.settings.read().await;
+
+        settings.torrent.as_ref().and_then(|torrent| torrent.deduplication.clone())
+    }
+}
+
+/// The parts of a torrent compared to detect duplicates.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ContentFingerprint {
+    piece_length: i64,
+    piece_hashes: HashSet<String>,
+    /// The sizes of the compared files, from the largest to the smallest.
+    file_lengths: Vec<i64>,
+}
+
+impl ContentFingerprint {
+    /// It builds the fingerprint from the pieces in hex and the files of a
+    /// torrent. Padding files and files smaller than the min size are not
+    /// compared.
+    #[must_use]
+    pub fn new(piece_length: i64, pieces: &str, files: &[TorrentFile], min_file_size: u64) -> Self {
+        let piece_hashes = pieces
+            .as_bytes()
+            .chunks(PIECE_HASH_HEX_LENGTH)
+            .map(|hash| String::from_utf8_lossy(hash).to_lowercase())
+            .collect();
+
+        let mut file_lengths: Vec<i64> = files
+            .iter()
+            .filter(|file| !is_padding_file(file))
+            .map(|file| file.length)
+            .filter(|length| u64::try_from(*length).is_ok_and(|length| length >= min_file_size))
+            .collect();
+
+        file_lengths.sort_unstable_by(|a, b| b.cmp(a));
+
+        Self {
+            piece_length,
+            piece_hashes,
+            file_lengths,
+        }
+    }
+
+    /// It builds the fingerprint of an uploaded torrent.
+    #[must_use]
+    pub fn from_torrent(torrent: &Torrent, min_file_size: u64) -> Self {
+        let files = match &torrent.info.files {
+            Some(files) => files.clone(),
+            None => vec![TorrentFile {
+                path: vec![torrent.info.name.clone()],
+                length: torrent.info.length.unwrap_or_default(),
+                md5sum: None,
+            }],
+        };
+
+        Self::new(
+            torrent.info.piece_length,
+            &torrent.info.get_pieces_as_string(),
+            &files,
+            min_file_size,
+        )
+    }
+
+    /// Fraction of the contents of the smallest torrent that is also in the
+    /// other one, from `0.0` to `1.0`.
+    #[must_use]
+    pub fn overlap(&self, other: &ContentFingerprint) -> f64 {
+        self.file_overlap(other).max(self.piece_overlap(other))
+    }
+
+    /// Fraction of the bytes in the compared files of the smallest torrent
+    /// that are in files with the same size in the other one.
+    #[allow(clippy::cast_precision_loss)]
+    fn file_overlap(&self, other: &ContentFingerprint) -> f64 {
+        let total = self
+            .file_lengths
+            .iter()
+            .sum::<i64>()
+            .min(other.file_lengths.iter().sum::<i64>());
+
+        if total == 0 {
+            return 0.0;
+        }
+
+        let mut other_lengths: HashMap<i64, usize> = HashMap::new();
+
+        for length in &other.file_lengths {
+            *other_lengths.entry(*length).or_default() += 1;
+        }
+
+        let mut matched = 0;
+
+        for length in &self.file_lengths {
+            if let Some(count) = other_lengths.get_mut(length).filter(|count| **count > 0) {
+                *count -= 1;
+                matched += length;
+            }
+        }
+
+        (matched as f64 / total as f64).min(1.0)
+    }
+
+    /// Fraction of the pieces of the smallest torrent that are in the other
+    /// one. Pieces are only comparable when both torrents have the same piece
+    /// length.
+    #[allow(clippy::cast_precision_loss)]
+    fn piece_overlap(&self, other: &ContentFingerprint) -> f64 {
+        let total = self.piece_hashes.len().min(other.piece_hashes.len());
+
+        if self.piece_length != other.piece_length || total == 0 {
+            return 0.0;
+        }
+
+        let matched = self.piece_hashes.intersection(&other.piece_hashes).count();
+
+        matched as f64 / total as f64
+    }
+}
+
+/// It groups the duplicates in clusters of connected torrents. Clusters
+/// keep the order of the pairs, so the one with the first pair goes first.
+fn group_in_clusters(pairs: Vec<DuplicatePair>) -> Vec<(Vec<TorrentId>, Vec<DuplicatePair>)> {
+    let mut parents: HashMap<TorrentId, TorrentId> = HashMap::new();
+
+    for pair in &pairs {
+        let a = root(&mut parents, pair.torrent_id);
+        let b = root(&mut parents, pair.duplicate_of);
+
+        if a != b {
+            parents.insert(a, b);
+        }
+    }
+
+    let mut clusters: Vec<(Vec<TorrentId>, Vec<DuplicatePair>)> = vec![];
+    let mut cluster_positions: HashMap<TorrentId, usize> = HashMap::new();
+
+    for pair in pairs {
+        let root_id = root(&mut parents, pair.torrent_id);
+
+        let position = *cluster_positions.entry(root_id).or_insert_with(|| {
+            clusters.push((vec![], vec![]));
+            clusters.len() - 1
+        });
+
+        let (torrent_ids, cluster_pairs) = &mut clusters[position];
+
+        for torrent_id in [pair.torrent_id, pair.duplicate_of] {
+            if !torrent_ids.contains(&torrent_id) {
+                torrent_ids.push(torrent_id);
+            }
+        }
+
+        cluster_pairs.push(pair);
+    }
+
+    clusters
+}
+
+/// It returns the torrent that represents the cluster of the given torrent.
+fn root(parents: &mut HashMap<TorrentId, TorrentId>, torrent_id: TorrentId) -> TorrentId {
+    let parent = *parents.entry(torrent_id).or_insert(torrent_id);
+
+    if parent == torrent_id {
+        return torrent_id;
+    }
+
+    let root_id = root(parents, parent);
+    parents.insert(torrent_id, root_id);
+    root_id
+}
+
+pub struct DbTorrentDuplicateRepository {
+    database: Arc<Box<dyn Database>>,
+    torrent_file_store: Arc<dyn TorrentFileStore>,
+}
+
+impl DbTorrentDuplicateRepository {
+    #[must_use]
+    pub fn new(database: Arc<Box<dyn Database>>, torrent_file_store: Arc<dyn TorrentFileStore>) -> Self {
+        Self {
+            database,
+            torrent_file_store,
+        }
+    }
+
+    /// It returns the torrents with files of the given sizes, from the one
+    /// with the most matching files.
+    ///
+    /// # Errors
+    ///
+    /// It returns an error if there is a database error.
+    pub async fn get_candidates(&self, lengths: &[i64], limit: u32) -> Result<Vec<TorrentId>, Error> {
+        self.database.get_torrents_with_file_lengths(lengths, limit).await
+    }
+
+    /// It returns the fingerprint of an existing torrent.
+    ///
+    /// # Errors
+    ///
+    /// It returns an error if there is a database error or the pieces can't
+    /// be loaded from the torrent file store.
+    pub async fn get_fingerprint(&self, torrent_id: TorrentId, min_file_size: u64) -> Result<ContentFingerprint, Error> {
+        let mut db_torrent = self.database.get_torrent_info_from_id(torrent_id).await?;
+
+        torrent_file::load_db_pieces(self.torrent_file_store.as_ref(), &mut db_torrent)
+            .await
+            .map_err(|e| {
+                error!(target: "torrent_files", "Failed to load the pieces of torrent #{torrent_id}: {e:?}");
+                Error::Error
+            })?;
+
+        let files = self.database.get_torrent_files_from_id(torrent_id).await?;
+
+        Ok(ContentFingerprint::new(
+            db_torrent.piece_length,
+            &db_torrent.pieces.unwrap_or_default(),
+            &files,
+            min_file_size,
+        ))
+    }
+
+    /// It records that an uploaded torrent is a duplicate of an existing one.
+    ///
+    /// # Errors
+    ///
+    /// It returns an error if there is a database error.
+    pub async fn add(&self, torrent_id: TorrentId, duplicate_of: TorrentId, overlap: f64) -> Result<(), Error> {
+        self.database
+            .insert_torrent_duplicate(torrent_id, duplicate_of, overlap)
+            .await
+    }
+
+    /// It returns all the detected duplicates, from the most recent.
+    ///
+    /// # Errors
+    ///
+    /// It returns an error if there is a database error.
+    pub async fn get_all(&self) -> Result<Vec<DuplicatePair>, Error> {
+        self.database.get_torrent_duplicates().await
+    }
+
+    /// It returns the torrents in any detected duplicate.
+    ///
+    /// # Errors
+    ///
+    /// It returns an error if there is a database error.
+    pub async fn get_duplicated_torrents(&self) -> Result<Vec<SimilarTorrent>, Error> {
+        self.database.get_duplicated_torrents().await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{group_in_clusters, ContentFingerprint};
+    use crate::models::torrent_duplicate::DuplicatePair;
+    use crate::models::torrent_file::TorrentFile;
+
+    const MIB: i64 = 1024 * 1024;
+
+    fn file(name: &str, length: i64) -> TorrentFile {
+        TorrentFile {
+            path: vec![name.to_string()],
+            length,
+            md5sum: None,
+        }
+    }
+
+    fn pair(torrent_id: i64, duplicate_of: i64) -> DuplicatePair {
+        DuplicatePair {
+            torrent_id,
+            duplicate_of,
+            overlap: 1.0,
+            date_detected: "2024-10-17 22:00:00".to_string(),
+        }
+    }
+
+    #[test]
+    fn it_should_compare_the_files_by_size_ignoring_the_small_files() {
+        let episode = ContentFingerprint::new(16384, "", &[file("e01.mkv", 700 * MIB), file("e01.nfo", 10)], 1024 * 1024);
+
+        let season = ContentFingerprint::new(
+            32768,
+            "",
+            &[file("e01.mkv", 700 * MIB), file("e02.mkv", 650 * MIB), file("season.nfo", 20)],
+            1024 * 1024,
+        );
+
+        let other = ContentFingerprint::new(16384, "", &[file("movie.mkv", 1400 * MIB)], 1024 * 1024);
+
+        assert!((episode.overlap(&season) - 1.0).abs() < f64::EPSILON);
+        assert!((season.overlap(&episode) - 1.0).abs() < f64::EPSILON);
+        assert!(episode.overlap(&other).abs() < f64::EPSILON);
+    }
+
+    #[test]
+    fn it_should_compare_the_piece_hashes_when_the_piece_length_is_the_same() {
+        let a = "a".repeat(40);
+        let b = "b".repeat(40);
+        let c = "c".repeat(40);
+
+        let original = ContentFingerprint::new(16384, &format!("{a}{b}"), &[], 1024 * 1024);
+        let repacked = ContentFingerprint::new(16384, &format!("{a}{c}"), &[], 1024 * 1024);
+        let other_piece_length = ContentFingerprint::new(32768, &format!("{a}{b}"), &[], 1024 * 1024);
+
+        assert!((original.overlap(&repacked) - 0.5).abs() < f64::EPSILON);
+        assert!(original.overlap(&other_piece_length).abs() < f64::EPSILON);
+    }
+
+    #[test]
+    fn it_should_group_the_connected_duplicates_in_clusters() {
+        let clusters = group_in_clusters(vec![pair(3, 2), pair(5, 4), pair(2, 1)]);
+
+        assert_eq!(clusters.len(), 2);
+        assert_eq!(clusters[0].0, vec![3, 2, 1]);
+        assert_eq!(clusters[0].1, vec![pair(3, 2), pair(2, 1)]);
+        assert_eq!(clusters[1].0, vec![5, 4]);
+    }
+}
@@ -0,0 +1,405 @@
+//! Images uploaded for the torrent descriptions.
+//!
+//! Besides linking remote images, which are fetched through the image proxy,
+//! uploaders can upload images to be embedded in the description of their
+//! torrents. The images are validated and stored with the image cache
+//! backend, and served by the index. The upload response contains the
+//! markdown to embed the image, so clients can insert it in the description.
+//!
+//! Uploads are disabled unless the `[torrent.description_images]` section
+//! is present:
+//!
+//! ```toml
+//! [image_cache]
+//! backend = "filesystem"
+//!
+//! [torrent.description_images]
+//! max_image_size = 2097152
+//! max_images_per_torrent = 20
+//! ```
+//!
+//! The memory image cache backend can't be used, because the images would be
+//! lost when the application restarts.
+use std::sync::Arc;
+
+use bittorrent_primitives::info_hash::InfoHash;
+use bytes::Bytes;
+use serde::Serialize;
+use sha2::{Digest, Sha256};
+use tracing::error;
+use utoipa::ToSchema;
+
+use super::DbTorrentListingGenerator;
+use crate::cache::image::manager::ImageCacheService;
+use crate::cache::image::store;
+use crate::config::Configuration;
+use crate::databases::database::{Database, Error};
+use crate::errors::ServiceError;
+use crate::models::image::{DescriptionImage, ImageId};
+use crate::models::torrent::TorrentId;
+use crate::models::user::UserId;
+use crate::services::authorization::{self, ACTION};
+use crate::services::image_upload::detect_content_type;
+use crate::services::user::Repository;
+
+/// Max length in characters of the stored file names.
+const MAX_FILE_NAME_LENGTH: usize = 255;
+
+/// User request to upload an image for a torrent description.
+pub struct UploadImageRequest {
+    /// The name of the uploaded file.
+    pub file_name: String,
+    pub bytes: Bytes,
+}
+
+/// An image uploaded for a torrent description, with the URL and the
+/// markdown to embed it.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, ToSchema)]
+pub struct UploadedDescriptionImage {
+    #[serde(flatten)]
+    pub image: DescriptionImage,
+    /// The absolute URL of the image.
+    pub url: String,
+    /// The markdown to embed the image in the description. For example:
+    /// `![screenshot](https://index.example.com/v1/torrent/<info_hash>/images/1)`.
+    pub markdown: String,
+}
+
+pub struct Service {
+    configuration: Arc<Configuration>,
+    user_repository: Arc<Box<dyn Repository>>,
+    torrent_listing_generator: Arc<DbTorrentListingGenerator>,
+    description_image_repository: Arc<DbDescriptionImageRepository>,
+    image_cache_service: Arc<ImageCacheService>,
+    authorization_service: Arc<authorization::Service>,
+}
+
+impl Service {
+    #[must_use]
+    pub fn new(
+        configuration: Arc<Configuration>,
+        user_repository: Arc<Box<dyn Repository>>,
+        torrent_listing_generator: Arc<DbTorrentListingGenerator>,
+        description_image_repository: Arc<DbDescriptionImageRepository>,
+        image_cache_service: Arc<ImageCacheService>,
+        authorization_service: Arc<authorization::Service>,
+    ) -> Service {
+        Service {
+            configuration,
+            user_repository,
+            torrent_listing_generator,
+            description_image_repository,
+            image_cache_service,
+            authorization_service,
+        }
+    }
+
+    /// It uploads an image for the description of a torrent. Owners can
+    /// always upload images for their torrents. Other users need permission
+    /// to edit the torrent.
+    ///
+    /// # Errors
+    ///
+    /// It returns an error if:
+    ///
+    /// * Description image uploads are not enabled.
+    /// * The user does not have permission to edit the torrent.
+    /// * The torrent does not exist.
+    /// * The image is empty or too big, or the image type is not supported.
+    /// * The torrent has already the max number of images, or the same image.
+    /// * The image can't be stored.
+    /// * There is a database error.
+    pub async fn upload_image(
+        &self,
+        info_hash: &InfoHash,
+        request: UploadImageRequest,
+        api_base_url: &str,
+        maybe_user_id: Option<UserId>,
+    ) -> Result<UploadedDescriptionImage, ServiceError> {
+        let Some(description_images) = self
+            .configuration
+            .settings
+            .read()
+            .await
+            .torrent
+            .as_ref()
+            .and_then(|torrent| torrent.description_images.clone())
+        else {
+            return Err(ServiceError::DescriptionImagesDisabled);
+        };
+
+        if !self.image_cache_service.is_persistent() {
+            return Err(ServiceError::DescriptionImagesDisabled);
+        }
+
+        let user_id = maybe_user_id.ok_or(ServiceError::UnauthorizedActionForGuests)?;
+
+        let user = self.user_repository.get_compact(&user_id).await?;
+
+        let torrent_listing = self.torrent_listing_generator.one_torrent_by_info_hash(info_hash).await?;
+
+        if torrent_listing.uploader != user.username {
+            self.authorization_service
+                .authorize_in_category(ACTION::EditTorrentInfo, Some(user_id), torrent_listing.category_id)
+                .await?;
+        }
+
+        let size = u64::try_from(request.bytes.len()).unwrap_or(u64::MAX);
+
+        if size == 0 || size > description_images.max_image_size {
+            return Err(ServiceError::InvalidImageSize);
+        }
+
+        let content_type = detect_content_type(&request.bytes).ok_or(ServiceError::UnsupportedImageType)?;
+
+        let existing_images = self.description_image_repository.get_all(torrent_listing.torrent_id).await?;
+
+        if existing_images.len() >= description_images.max_images_per_torrent {
+            return Err(ServiceError::TooManyDescriptionImages);
+        }
+
+        let cache_key = uploaded_image_key(&request.bytes);
+
+        self.image_cache_service
+            .put_uploaded_image(&cache_key, request.bytes.clone())
+            .await
+            .map_err(|e| match e {
+                store::Error::ImageTooBig => ServiceError::InvalidImageSize,
+                store::Error::StorageFailed(e) => {
+                    error!(target: "image cache", "Failed to store the uploaded image {cache_key}: {e}");
+                    ServiceError::InternalServerError
+                }
+            })?;
+
+        let file_name = sanitize_file_name(&request.file_name);
+        let size = i64::try_from(size).unwrap_or(i64::MAX);
+
+        let image_id = self
+            .description_image_repository
+            .add(
+                torrent_listing.torrent_id,
+                user_id,
+                &cache_key,
+                &file_name,
+                content_type,
+                size,
+            )
+            .await?;
+
+        Ok(uploaded_description_image(
+            DescriptionImage {
+                image_id,
+                torrent_id: torrent_listing.torrent_id,
+                file_name,
+                content_type: content_type.to_string(),
+                size,
+            },
+            info_hash,
+            api_base_url,
+        ))
+    }
+
+    /// It returns the images uploaded for the description of a torrent.
+    ///
+    /// # Errors
+    ///
+    /// It returns an error if:
+    ///
+    /// * The user does not have permission to get the torrent info.
+    /// * The torrent does not exist.
+    /// * There is a database error.
+    pub async fn get_images(
+        &self,
+        info_hash: &InfoHash,
+        api_base_url: &str,
+        maybe_user_id: Option<UserId>,
+    ) -> Result<Vec<UploadedDescriptionImage>, ServiceError> {
+        self.authorization_service
+            .authorize(ACTION::GetTorrentInfo, maybe_user_id)
+            .await?;
+
+        let torrent_listing = self.torrent_listing_generator.one_torrent_by_info_hash(info_hash).await?;
+
+        Ok(self
+            .description_image_repository
+            .get_all(torrent_listing.torrent_id)
+            .await?
+            .into_iter()
+            .map(|image| uploaded_description_image(image, info_hash, api_base_url))
+            .collect())
+    }
+
+    /// It returns the content type and the contents of an image uploaded for
+    /// the description of a torrent.
+    ///
+    /// # Errors
+    ///
+    /// It returns an error if:
+    ///
+    /// * The user does not have permission to get the torrent info.
+    /// * The torrent or the image do not exist.
+    /// * There is a database or a storage error.
+    pub async fn get_image_content(
+        &self,
+        info_hash: &InfoHash,
+        image_id: ImageId,
+        maybe_user_id: Option<UserId>,
+    ) -> Result<(String, Bytes), ServiceError> {
+        self.authorization_service
+            .authorize(ACTION::GetTorrentInfo, maybe_user_id)
+            .await?;
+
+        let torrent_listing = self.torrent_listing_generator.one_torrent_by_info_hash(info_hash).await?;
+
+        let (content_type, cache_key) = self
+            .description_image_repository
+            .get_key(torrent_listing.torrent_id, image_id)
+            .await?;
+
+        match self.image_cache_service.get_uploaded_image(&cache_key).await {
+            Ok(Some(bytes)) => Ok((content_type, bytes)),
+            Ok(None) => Err(ServiceError::DescriptionImageNotFound),
+            Err(e) => {
+                error!(target: "image cache", "Failed to read the uploaded image {cache_key}: {e:?}");
+                Err(ServiceError::InternalServerError)
+            }
+        }
+    }
+}
+
+/// The image cache key of an uploaded image. Images are stored by the
+/// SHA-256 of their contents, with a suffix so they never collide with the
+/// proxied images.
+fn uploaded_image_key(bytes: &[u8]) -> String {
+    format!("{}.upload", hex::encode(Sha256::digest(bytes)))
+}
+
+/// It removes the directories from the file name and limits its length.
+fn sanitize_file_name(file_name: &str) -> String {
+    let file_name = file_name.rsplit(['/', '\\']).next().unwrap_or_default().trim();
+
+    if file_name.is_empty() {
+        return "image".to_string();
+    }
+
+    file_name.chars().take(MAX_FILE_NAME_LENGTH).collect()
+}
+
+fn uploaded_description_image(image: DescriptionImage, info_hash: &InfoHash, api_base_url: &str) -> UploadedDescriptionImage {
+    let url = format!(
+        "{}/v1/torrent/{}/images/{}",
+        api_base_url.trim_end_matches('/'),
+        info_hash.to_hex_string(),
+        image.image_id
+    );
+
+    let alt_text = image
+        .file_name
+        .rsplit_once('.')
+        .map_or(image.file_name.as_str(), |(name, _)| name);
+
+    UploadedDescriptionImage {
+        markdown: markdown_image(alt_text, &url),
+        url,
+        image,
+    }
+}
+
+/// The markdown to embed an image. Characters with a meaning in the alt text
+/// are escaped.
+fn markdown_image(alt_text: &str, url: &str) -> String {
+    let mut escaped = String::with_capacity(alt_text.len());
+
+    for c in alt_text.chars() {
+        if matches!(c, '\\' | '[' | ']') {
+            escaped.push('\\');
+        }
+        if !c.is_control() {
+            escaped.push(c);
+        }
+    }
+
+    format!("![{escaped}]({url})")
+}
+
+pub struct DbDescriptionImageRepository {
+    database: Arc<Box<dyn Database>>,
+}
+
+impl DbDescriptionImageRepository {
+    #[must_use]
+    pub fn new(database: Arc<Box<dyn Database>>) -> Self {
+        Self { database }
+    }
+
+    /// It adds an image to the description of a torrent.
+    ///
+    /// # Errors
+    ///
+    /// It returns an error if the torrent has already the same image or
+    /// there is a database error.
+    pub async fn add(
+        &self,
+        torrent_id: TorrentId,
+        uploader_id: UserId,
+        cache_key: &str,
+        file_name: &str,
+        content_type: &str,
+        size: i64,
+    ) -> Result<ImageId, Error> {
+        self.database
+            .insert_torrent_description_image(torrent_id, uploader_id, cache_key, file_name, content_type, size)
+            .await
+    }
+
+    /// It returns the images uploaded for the description of a torrent.
+    ///
+    /// # Errors
+    ///
+    /// It returns an error if there is a database error.
+    pub async fn get_all(&self, torrent_id: TorrentId) -> Result<Vec<DescriptionImage>, Error> {
+        self.database.get_torrent_description_images(torrent_id).await
+    }
+
+    /// It returns the content type and the image cache key of an image.
+    ///
+    /// # Errors
+    ///
+    /// It returns an error if the image does not exist or there is a
+    /// database error.
+    pub async fn get_key(&self, torrent_id: TorrentId, image_id: ImageId) -> Result<(String, String), Error> {
+        self.database.get_torrent_description_image_key(torrent_id, image_id).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{markdown_image, sanitize_file_name, uploaded_image_key};
+
+    #[test]
+    fn it_should_escape_the_alt_text_in_the_markdown() {
+        assert_eq!(
+            markdown_image("screenshot [1]", "http://localhost/1"),
+            "![screenshot \\[1\\]](http://localhost/1)"
+        );
+        assert_eq!(
+            markdown_image("line\nbreak", "http://localhost/1"),
+            "![linebreak](http://localhost/1)"
+        );
+    }
+
+    #[test]
+    fn it_should_remove_the_directories_from_the_file_name() {
+        assert_eq!(sanitize_file_name("../../etc/cover.png"), "cover.png");
+        assert_eq!(sanitize_file_name("C:\\Users\\me\\cover.png"), "cover.png");
+        assert_eq!(sanitize_file_name(" "), "image");
+    }
+
+    #[test]
+    fn it_should_not_store_uploaded_images_with_the_keys_of_proxied_images() {
+        let key = uploaded_image_key(b"image");
+
+        assert!(key.ends_with(".upload"));
+        assert_eq!(key.len(), 64 + ".upload".len());
+    }
+}
@@ -0,0 +1,419 @@
+//! File groups of torrent packs.
+//!
+//! Huge packs, like a whole TV series, can have thousands of files. Uploaders
+//! can declare logical sub-groups of the file list, like the folder of each
+//! season, so that clients can display and filter the files within the pack.
+//!
+//! A group has a name and a path prefix relative to the torrent root folder.
+//! A file belongs to the first group whose prefix matches its path. Files not
+//! matching any group are ungrouped.
+//!
+//! ```text
+//! Season 1/S01E01.mkv -> "Season 1" (Season 1/)
+//! Season 2/S02E01.mkv -> "Season 2" (Season 2/)
+//! README.txt          -> no group
+//! ```
+use std::collections::HashSet;
+use std::sync::Arc;
+
+use bittorrent_primitives::info_hash::InfoHash;
+use serde_derive::Deserialize;
+use utoipa::IntoParams;
+
+use super::{DbTorrentFileRepository, DbTorrentListingGenerator};
+use crate::databases::database::{Database, Error};
+use crate::errors::ServiceError;
+use crate::models::torrent::TorrentId;
+use crate::models::torrent_file::TorrentFile;
+use crate::models::torrent_file_group::{PackFile, TorrentFileGroup, TorrentFileGroupSummary};
+use crate::models::user::UserId;
+use crate::services::authorization::{self, ACTION};
+use crate::services::user::Repository;
+
+/// Maximum number of groups in a torrent.
+const MAX_GROUPS_PER_TORRENT: usize = 100;
+
+/// Maximum length of a group name, in characters.
+const MAX_GROUP_NAME_LENGTH: usize = 100;
+
+/// User request to search the files of a torrent pack.
+#[derive(Debug, Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
+pub struct FileSearchRequest {
+    /// Only the files whose path contains this text, ignoring case. Eg: `?search=E01`
+    pub search: Option<String>,
+    /// Only the files in this group. Eg: `?group=Season 1`
+    pub group: Option<String>,
+}
+
+pub struct Service {
+    user_repository: Arc<Box<dyn Repository>>,
+    torrent_listing_generator: Arc<DbTorrentListingGenerator>,
+    torrent_file_repository: Arc<DbTorrentFileRepository>,
+    file_group_repository: Arc<DbTorrentFileGroupRepository>,
+    authorization_service: Arc<authorization::Service>,
+}
+
+impl Service {
+    #[must_use]
+    pub fn new(
+        user_repository: Arc<Box<dyn Repository>>,
+        torrent_listing_generator: Arc<DbTorrentListingGenerator>,
+        torrent_file_repository: Arc<DbTorrentFileRepository>,
+        file_group_repository: Arc<DbTorrentFileGroupRepository>,
+        authorization_service: Arc<authorization::Service>,
+    ) -> Service {
+        Service {
+            user_repository,
+            torrent_listing_generator,
+            torrent_file_repository,
+            file_group_repository,
+            authorization_service,
+        }
+    }
+
+    /// It replaces the file groups of a torrent. An empty list removes all
+    /// the groups. Owners can always group the files of their torrents.
+    /// Other users need permission to edit the torrent.
+    ///
+    /// # Errors
+    ///
+    /// It returns an error if:
+    ///
+    /// * The user does not have permission to edit the torrent.
+    /// * The torrent does not exist.
+    /// * There are too many groups.
+    /// * A group name is empty, too long or repeated, or a group path does
+    ///   not match any file in the torrent.
+    /// * There is a database error.
+    pub async fn update_file_groups(
+        &self,
+        info_hash: &InfoHash,
+        groups: &[TorrentFileGroup],
+        maybe_user_id: Option<UserId>,
+    ) -> Result<Vec<TorrentFileGroupSummary>, ServiceError> {
+        let user_id = maybe_user_id.ok_or(ServiceError::UnauthorizedActionForGuests)?;
+
+        let user = self.user_repository.get_compact(&user_id).await?;
+
+        let torrent_listing = self.torrent_listing_generator.one_torrent_by_info_hash(info_hash).await?;
+
+        if torrent_listing.uploader != user.username {
+            self.authorization_service
+                .authorize_in_category(ACTION::EditTorrentInfo, Some(user_id), torrent_listing.category_id)
+                .await?;
+        }
+
+        let files = self
+            .torrent_file_repository
+            .get_by_torrent_id(&torrent_listing.torrent_id)
+            .await?;
+
+        let groups = validate_groups(groups, &files)?;
+
+        self.file_group_repository.update(torrent_listing.torrent_id, &groups).await?;
+
+        Ok(summarize(&groups, &files))
+    }
+
+    /// It returns the file groups of a torrent with the number of files in
+    /// each group and their total size.
+    ///
+    /// # Errors
+    ///
+    /// It returns an error if:
+    ///
+    /// * The user does not have permission to get the torrent info.
+    /// * The torrent does not exist.
+    /// * There is a database error.
+    pub async fn get_file_groups(
+        &self,
+        info_hash: &InfoHash,
+        maybe_user_id: Option<UserId>,
+    ) -> Result<Vec<TorrentFileGroupSummary>, ServiceError> {
+        self.authorization_service
+            .authorize(ACTION::GetTorrentInfo, maybe_user_id)
+            .await?;
+
+        let torrent_listing = self.torrent_listing_generator.one_torrent_by_info_hash(info_hash).await?;
+
+        let groups = self.file_group_repository.get_all(torrent_listing.torrent_id).await?;
+
+        let files = self
+            .torrent_file_repository
+            .get_by_torrent_id(&torrent_listing.torrent_id)
+            .await?;
+
+        Ok(summarize(&groups, &files))
+    }
+
+    /// It returns the files of a torrent matching the search, with the group
+    /// each file belongs to.
+    ///
+    /// # Errors
+    ///
+    /// It returns an error if:
+    ///
+    /// * The user does not have permission to get the torrent info.
+    /// * The torrent or the group do not exist.
+    /// * There is a database error.
+    pub async fn search_files(
+        &self,
+        info_hash: &InfoHash,
+        request: &FileSearchRequest,
+        maybe_user_id: Option<UserId>,
+    ) -> Result<Vec<PackFile>, ServiceError> {
+        self.authorization_service
+            .authorize(ACTION::GetTorrentInfo, maybe_user_id)
+            .await?;
+
+        let torrent_listing = self.torrent_listing_generator.one_torrent_by_info_hash(info_hash).await?;
+
+        let groups = self.file_group_repository.get_all(torrent_listing.torrent_id).await?;
+
+        if let Some(group) = &request.group {
+            if !groups.iter().any(|existing_group| existing_group.name == *group) {
+                return Err(ServiceError::TorrentFileGroupNotFound);
+            }
+        }
+
+        let files = self
+            .torrent_file_repository
+            .get_by_torrent_id(&torrent_listing.torrent_id)
+            .await?;
+
+        Ok(search(&groups, &files, request))
+    }
+}
+
+/// It normalizes the groups and checks they are valid for the torrent files.
+/// Group paths always end with `/`, so `Season 1` does not match the files in
+/// `Season 10`.
+fn validate_groups(groups: &[TorrentFileGroup], files: &[TorrentFile]) -> Result<Vec<TorrentFileGroup>, ServiceError> {
+    if groups.len() > MAX_GROUPS_PER_TORRENT {
+        return Err(ServiceError::TooManyTorrentFileGroups);
+    }
+
+    let mut names = HashSet::new();
+
+    groups
+        .iter()
+        .map(|group| {
+            let name = group.name.trim();
+            let path = group.path.trim().trim_matches('/');
+
+            if name.is_empty() || name.chars().count() > MAX_GROUP_NAME_LENGTH || !names.insert(name) || path.is_empty() {
+                return Err(ServiceError::InvalidTorrentFileGroup);
+            }
+
+            let group = TorrentFileGroup {
+                name: name.to_string(),
+                path: format!("{path}/"),
+            };
+
+            if !files.iter().any(|file| file_path(file).starts_with(&group.path)) {
+                return Err(ServiceError::InvalidTorrentFileGroup);
+            }
+
+            Ok(group)
+        })
+        .collect()
+}
+
+/// It returns the group the file belongs to, if any.
+fn group_of<'a>(groups: &'a [TorrentFileGroup], path: &str) -> Option<&'a TorrentFileGroup> {
+    groups.iter().find(|group| path.starts_with(&group.path))
+}
+
+/// It returns the file path relative to the torrent root folder.
+fn file_path(file: &TorrentFile) -> String {
+    file.path.join("/")
+}
+
+/// It counts the files in each group and adds up their sizes.
+#[must_use]
+pub fn summarize(groups: &[TorrentFileGroup], files: &[TorrentFile]) -> Vec<TorrentFileGroupSummary> {
+    let mut summaries: Vec<TorrentFileGroupSummary> = groups
+        .iter()
+        .map(|group| TorrentFileGroupSummary {
+            name: group.name.clone(),
+            path: group.path.clone(),
+            files: 0,
+            size: 0,
+        })
+        .collect();
+
+    for file in files {
+        let path = file_path(file);
+
+        if let Some(position) = groups.iter().position(|group| path.starts_with(&group.path)) {
+            summaries[position].files += 1;
+            summaries[position].size += file.length;
+        }
+    }
+
+    summaries
+}
+
+/// It returns the files matching the search with the group they belong to.
+fn search(groups: &[TorrentFileGroup], files: &[TorrentFile], request: &FileSearchRequest) -> Vec<PackFile> {
+    let text = request
+        .search
+        .as_deref()
+        .map(str::trim)
+        .filter(|text| !text.is_empty())
+        .map(str::to_lowercase);
+
+    files
+        .iter()
+        .filter_map(|file| {
+            let path = file_path(file);
+
+            let group = group_of(groups, &path).map(|group| group.name.clone());
+
+            if request.group.is_some() && group != request.group {
+                return None;
+            }
+
+            if let Some(text) = &text {
+                if !path.to_lowercase().contains(text) {
+                    return None;
+                }
+            }
+
+            Some(PackFile {
+                path,
+                length: file.length,
+                group,
+            })
+        })
+        .collect()
+}
+
+pub struct DbTorrentFileGroupRepository {
+    database: Arc<Box<dyn Database>>,
+}
+
+impl DbTorrentFileGroupRepository {
+    #[must_use]
+    pub fn new(database: Arc<Box<dyn Database>>) -> Self {
+        Self { database }
+    }
+
+    /// It replaces the file groups of a torrent.
+    ///
+    /// # Errors
+    ///
+    /// It returns an error if there is a database error.
+    pub async fn update(&self, torrent_id: TorrentId, groups: &[TorrentFileGroup]) -> Result<(), Error> {
+        self.database.update_torrent_file_groups(torrent_id, groups).await
+    }
+
+    /// It returns the file groups of a torrent in the order they were
+    /// declared.
+    ///
+    /// # Errors
+    ///
+    /// It returns an error if there is a database error.
+    pub async fn get_all(&self, torrent_id: TorrentId) -> Result<Vec<TorrentFileGroup>, Error> {
+        self.database.get_torrent_file_groups(torrent_id).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{search, summarize, validate_groups, FileSearchRequest};
+    use crate::errors::ServiceError;
+    use crate::models::torrent_file::TorrentFile;
+    use crate::models::torrent_file_group::TorrentFileGroup;
+
+    fn file(path: &str, length: i64) -> TorrentFile {
+        TorrentFile {
+            path: path.split('/').map(ToString::to_string).collect(),
+            length,
+            md5sum: None,
+        }
+    }
+
+    fn files() -> Vec<TorrentFile> {
+        vec![
+            file("Season 1/S01E01.mkv", 10),
+            file("Season 1/S01E02.mkv", 20),
+            file("Season 10/S10E01.mkv", 30),
+            file("README.txt", 1),
+        ]
+    }
+
+    fn group(name: &str, path: &str) -> TorrentFileGroup {
+        TorrentFileGroup {
+            name: name.to_string(),
+            path: path.to_string(),
+        }
+    }
+
+    #[test]
+    fn it_should_normalize_the_group_names_and_paths() {
+        assert_eq!(
+            validate_groups(&[group(" Season 1 ", "/Season 1")], &files()),
+            Ok(vec![group("Season 1", "Season 1/")])
+        );
+    }
+
+    #[test]
+    fn it_should_reject_invalid_groups() {
+        let files = files();
+
+        assert_eq!(
+            validate_groups(&[group("", "Season 1")], &files),
+            Err(ServiceError::InvalidTorrentFileGroup)
+        );
+        assert_eq!(
+            validate_groups(&[group("Season 2", "Season 2")], &files),
+            Err(ServiceError::InvalidTorrentFileGroup)
+        );
+        assert_eq!(
+            validate_groups(&[group("Season", "Season 1"), group("Season", "Season 10")], &files),
+            Err(ServiceError::InvalidTorrentFileGroup)
+        );
+    }
+
+    #[test]
+    fn it_should_count_the_files_in_each_group() {
+        let summaries = summarize(&[group("Season 1", "Season 1/"), group("Season 10", "Season 10/")], &files());
+
+        assert_eq!(
+            summaries
+                .iter()
+                .map(|summary| (summary.files, summary.size))
+                .collect::<Vec<_>>(),
+            vec![(2, 30), (1, 30)]
+        );
+    }
+
+    #[test]
+    fn it_should_attribute_the_matching_files_to_their_groups() {
+        let groups = [group("Season 1", "Season 1/")];
+
+        let request = FileSearchRequest {
+            search: Some("e01".to_string()),
+            group: None,
+        };
+
+        let matches = search(&groups, &files(), &request);
+
+        assert_eq!(
+            matches
+                .iter()
+                .map(|file| (file.path.as_str(), file.group.as_deref()))
+                .collect::<Vec<_>>(),
+            vec![("Season 1/S01E01.mkv", Some("Season 1")), ("Season 10/S10E01.mkv", None)]
+        );
+
+        let request = FileSearchRequest {
+            search: None,
+            group: Some("Season 1".to_string()),
+        };
+
+        assert_eq!(search(&groups, &files(), &request).len(), 2);
+    }
+}
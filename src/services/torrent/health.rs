@@ -0,0 +1,335 @@
+//! Health score of the torrents and cleanup of the dead ones.
+//!
+//! The [`torrent_health`](crate::console::cronjobs::torrent_health) cronjob
+//! samples the seeders imported from the tracker and updates the health
+//! score of the approved torrents. The score is a moving average of the
+//! seeders, from `0.0` (no seeders) to `1.0` (`healthy_seeders` or more), so
+//! it reflects the seeders over time and not only the last sample.
+//!
+//! Torrents without seeders for `dead_after_days` are dead. Depending on the
+//! `action`, they are flagged for the administrators or archived. Dead
+//! torrents that are seeded again are not dead anymore and their flag is
+//! cleared. Archived torrents start from scratch if they are approved again.
+//! Administrators can get the list of dead torrents before any action is
+//! taken.
+//!
+//! ```toml
+//! [torrent.health]
+//! check_interval_seconds = 3600
+//! healthy_seeders = 10
+//! dead_after_days = 30
+//! action = "flag"
+//! ```
+use std::sync::Arc;
+
+use chrono::{TimeDelta, Utc};
+use serde::Serialize;
+use tracing::error;
+
+use super::{DbTorrentListingGenerator, DbTorrentRepository};
+use crate::config::{Configuration, DeadTorrentAction, TorrentHealth};
+use crate::databases::database::{Database, Error};
+use crate::errors::ServiceError;
+use crate::models::audit::AuditAction;
+use crate::models::torrent::{TorrentId, TorrentStatus};
+use crate::models::torrent_health::{DeadTorrent, TorrentHealthSample};
+use crate::models::user::UserId;
+use crate::services::audit;
+use crate::services::authorization::{self, ACTION};
+use crate::utils::clock::DATETIME_FORMAT;
+
+/// Weight of the last sample in the health score.
+const SAMPLE_WEIGHT: f64 = 0.1;
+
+/// The dead torrents and what will be done with them.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct DeadTorrentsReport {
+    /// Whether the health of the torrents is tracked or not.
+    pub enabled: bool,
+    /// What is done with the dead torrents. `None` when it's not enabled.
+    pub action: Option<DeadTorrentAction>,
+    pub torrents: Vec<DeadTorrent>,
+}
+
+/// Number of torrents affected by a check.
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct CheckSummary {
+    pub updated: usize,
+    pub flagged: usize,
+    pub archived: usize,
+}
+
+pub struct Service {
+    configuration: Arc<Configuration>,
+    health_repository: Arc<DbTorrentHealthRepository>,
+    torrent_repository: Arc<DbTorrentRepository>,
+    torrent_listing_generator: Arc<DbTorrentListingGenerator>,
+    authorization_service: Arc<authorization::Service>,
+    audit_service: Arc<audit::Service>,
+}
+
+impl Service {
+    #[must_use]
+    pub fn new(
+        configuration: Arc<Configuration>,
+        health_repository: Arc<DbTorrentHealthRepository>,
+        torrent_repository: Arc<DbTorrentRepository>,
+        torrent_listing_generator: Arc<DbTorrentListingGenerator>,
+        authorization_service: Arc<authorization::Service>,
+        audit_service: Arc<audit::Service>,
+    ) -> Service {
+        Service {
+            configuration,
+            health_repository,
+            torrent_repository,
+            torrent_listing_generator,
+            authorization_service,
+            audit_service,
+        }
+    }
+
+    /// It returns the dead torrents, from the oldest without seeders. No
+    /// action is taken.
+    ///
+    /// # Errors
+    ///
+    /// It returns an error if:
+    ///
+    /// * The user is not an administrator.
+    /// * There is a database error.
+    pub async fn get_report(&self, maybe_user_id: Option<UserId>) -> Result<DeadTorrentsReport, ServiceError> {
+        self.authorization_service
+            .authorize(ACTION::GetDeadTorrents, maybe_user_id)
+            .await?;
+
+        let Some(health) = self.health_settings().await else {
+            return Ok(DeadTorrentsReport {
+                enabled: false,
+                action: None,
+                torrents: vec![],
+            });
+        };
+
+        Ok(DeadTorrentsReport {
+            enabled: true,
+            action: Some(health.action),
+            torrents: self.dead_torrents(&health).await?,
+        })
+    }
+
+    /// It updates the health score of the approved torrents with their
+    /// current seeders, and then it flags or archives the dead torrents.
+    /// Errors updating one torrent are only logged.
+    ///
+    /// # Errors
+    ///
+    /// It returns an error if the torrents can't be loaded.
+    pub async fn check(&self) -> Result<CheckSummary, Error> {
+        let mut summary = CheckSummary::default();
+
+        let Some(health) = self.health_settings().await else {
+            return Ok(summary);
+        };
+
+        let now = Utc::now().format(DATETIME_FORMAT).to_string();
+
+        for sample in self.health_repository.get_samples().await? {
+            let health_score = next_health_score(sample.health_score, sample.seeders, health.healthy_seeders);
+            let zero_seeders_since = next_zero_seeders_since(&sample, &now);
+
+            if let Err(e) = self
+                .health_repository
+                .update(sample.torrent_id, health_score, zero_seeders_since.as_deref())
+                .await
+            {
+                error!(target: "torrent_health", "Failed to update the health of torrent #{}: {e:?}", sample.torrent_id);
+                continue;
+            }
+
+            summary.updated += 1;
+        }
+
+        for dead_torrent in self.dead_torrents(&health).await? {
+            if let Err(e) = self.apply(health.action, &dead_torrent).await {
+                error!(target: "torrent_health", "Failed to clean up the dead torrent {}: {e:?}", dead_torrent.info_hash);
+                continue;
+            }
+
+            match health.action {
+                DeadTorrentAction::Flag if dead_torrent.date_flagged.is_none() => summary.flagged += 1,
+                DeadTorrentAction::Flag => {}
+                DeadTorrentAction::Archive => summary.archived += 1,
+            }
+        }
+
+        if summary.archived > 0 {
+            self.torrent_listing_generator.invalidate();
+        }
+
+        Ok(summary)
+    }
+
+    async fn dead_torrents(&self, health: &TorrentHealth) -> Result<Vec<DeadTorrent>, Error> {
+        let zero_seeders_before = (Utc::now() - TimeDelta::days(i64::from(health.dead_after_days)))
+            .format(DATETIME_FORMAT)
+            .to_string();
+
+        self.health_repository.get_dead(&zero_seeders_before).await
+    }
+
+    async fn apply(&self, action: DeadTorrentAction, dead_torrent: &DeadTorrent) -> Result<(), Error> {
+        match action {
+            DeadTorrentAction::Flag => {
+                if dead_torrent.date_flagged.is_none() {
+                    self.health_repository.flag(dead_torrent.torrent_id).await?;
+                }
+            }
+            DeadTorrentAction::Archive => {
+                self.torrent_repository
+                    .update_status(&dead_torrent.torrent_id, &TorrentStatus::Archived)
+                    .await?;
+
+                self.health_repository.delete(dead_torrent.torrent_id).await?;
+
+                self.audit_service
+                    .record(
+                        None,
+                        AuditAction::TorrentArchive,
+                        &dead_torrent.info_hash,
+                        Some(&format!("{} (dead torrent)", dead_torrent.title)),
+                    )
+                    .await;
+            }
+        }
+
+        Ok(())
+    }
+
+    async fn health_settings(&self) -> Option<TorrentHealth> {
+        let settings = self.configuration.settings.read().await;
+
+        settings.torrent.as_ref().and_then(|torrent| torrent.health.clone())
+    }
+}
+
+/// It returns the health score after a new sample of the seeders. The first
+/// sample is the initial score.
+fn next_health_score(previous_score: Option<f64>, seeders: i64, healthy_seeders: u32) -> f64 {
+    #[allow(clippy::cast_precision_loss)]
+    let sample = seeders.clamp(0, i64::from(healthy_seeders)) as f64 / f64::from(healthy_seeders);
+
+    match previous_score {
+        Some(previous_score) => previous_score + (sample - previous_score) * SAMPLE_WEIGHT,
+        None => sample,
+    }
+}
+
+/// It returns since when the torrent has no seeders, or `None` if it has
+/// seeders now.
+fn next_zero_seeders_since(sample: &TorrentHealthSample, now: &str) -> Option<String> {
+    if sample.seeders > 0 {
+        return None;
+    }
+
+    Some(sample.zero_seeders_since.clone().unwrap_or_else(|| now.to_string()))
+}
+
+pub struct DbTorrentHealthRepository {
+    database: Arc<Box<dyn Database>>,
+}
+
+impl DbTorrentHealthRepository {
+    #[must_use]
+    pub fn new(database: Arc<Box<dyn Database>>) -> Self {
+        Self { database }
+    }
+
+    /// It returns the current seeders and the health of the approved
+    /// torrents.
+    ///
+    /// # Errors
+    ///
+    /// It returns an error if there is a database error.
+    pub async fn get_samples(&self) -> Result<Vec<TorrentHealthSample>, Error> {
+        self.database.get_torrent_health_samples().await
+    }
+
+    /// It saves the health of a torrent.
+    ///
+    /// # Errors
+    ///
+    /// It returns an error if there is a database error.
+    pub async fn update(&self, torrent_id: TorrentId, health_score: f64, zero_seeders_since: Option<&str>) -> Result<(), Error> {
+        self.database
+            .upsert_torrent_health(torrent_id, health_score, zero_seeders_since)
+            .await
+    }
+
+    /// It returns the torrents without seeders since before the given date.
+    ///
+    /// # Errors
+    ///
+    /// It returns an error if there is a database error.
+    pub async fn get_dead(&self, zero_seeders_before: &str) -> Result<Vec<DeadTorrent>, Error> {
+        self.database.get_dead_torrents(zero_seeders_before).await
+    }
+
+    /// It flags a torrent as dead.
+    ///
+    /// # Errors
+    ///
+    /// It returns an error if there is a database error.
+    pub async fn flag(&self, torrent_id: TorrentId) -> Result<(), Error> {
+        self.database.flag_dead_torrent(torrent_id).await
+    }
+
+    /// It deletes the health of a torrent.
+    ///
+    /// # Errors
+    ///
+    /// It returns an error if there is a database error.
+    pub async fn delete(&self, torrent_id: TorrentId) -> Result<(), Error> {
+        self.database.delete_torrent_health(torrent_id).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{next_health_score, next_zero_seeders_since};
+    use crate::models::torrent_health::TorrentHealthSample;
+
+    fn sample(seeders: i64, zero_seeders_since: Option<&str>) -> TorrentHealthSample {
+        TorrentHealthSample {
+            torrent_id: 1,
+            seeders,
+            health_score: None,
+            zero_seeders_since: zero_seeders_since.map(ToString::to_string),
+        }
+    }
+
+    #[test]
+    fn the_first_sample_should_be_the_initial_health_score() {
+        assert!((next_health_score(None, 5, 10) - 0.5).abs() < f64::EPSILON);
+        assert!((next_health_score(None, 20, 10) - 1.0).abs() < f64::EPSILON);
+        assert!(next_health_score(None, 0, 10).abs() < f64::EPSILON);
+    }
+
+    #[test]
+    fn the_health_score_should_move_slowly_towards_the_last_sample() {
+        let health_score = next_health_score(Some(1.0), 0, 10);
+
+        assert!((health_score - 0.9).abs() < 1e-9);
+    }
+
+    #[test]
+    fn it_should_keep_the_date_since_the_torrent_has_no_seeders() {
+        let now = "2024-10-18 00:00:00";
+
+        assert_eq!(next_zero_seeders_since(&sample(0, None), now), Some(now.to_string()));
+        assert_eq!(
+            next_zero_seeders_since(&sample(0, Some("2024-09-01 00:00:00")), now),
+            Some("2024-09-01 00:00:00".to_string())
+        );
+        assert_eq!(next_zero_seeders_since(&sample(3, Some("2024-09-01 00:00:00")), now), None);
+    }
+}
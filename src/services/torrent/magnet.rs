@@ -0,0 +1,720 @@
+//! Magnet links and the torrent metadata fetcher.
+//!
+//! Magnet links do not contain the info dictionary of the torrent, only its
+//! info-hash. The [`MetadataFetcher`] gets the peers from the trackers
+//! (HTTP and UDP) and downloads the info dictionary from them using the
+//! extension protocol ([BEP 10](https://www.bittorrent.org/beps/bep_0010.html))
+//! and the metadata extension ([BEP 9](https://www.bittorrent.org/beps/bep_0009.html)).
+use std::collections::HashMap;
+use std::net::{IpAddr, Ipv4Addr, Ipv6Addr, SocketAddr};
+use std::time::Duration;
+
+use bittorrent_primitives::info_hash::InfoHash;
+use derive_more::{Display, Error};
+use serde_bencode::value::Value;
+use sha1::{Digest, Sha1};
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::net::{lookup_host, TcpStream, UdpSocket};
+use tokio::time::timeout;
+use tracing::debug;
+use url::Url;
+
+use crate::utils::base32;
+use crate::utils::redaction::redact;
+
+/// Size of the metadata pieces exchanged with the `ut_metadata` extension.
+const METADATA_PIECE_SIZE: usize = 16 * 1024;
+
+/// Max length of a peer wire message. Larger messages are not needed to get
+/// the metadata.
+const MAX_MESSAGE_LENGTH: usize = 1024 * 1024;
+
+/// ID used by the index for the `ut_metadata` extension messages.
+const UT_METADATA_ID: u8 = 1;
+
+const PROTOCOL: &[u8] = b"BitTorrent protocol";
+
+/// The port announced to trackers. The index never accepts connections from
+/// peers.
+const ANNOUNCED_PORT: u16 = 6881;
+
+const UDP_TRACKER_PROTOCOL_ID: u64 = 0x0417_2710_1980;
+
+/// A parsed magnet link.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MagnetLink {
+    pub info_hash: InfoHash,
+    /// The `dn` param.
+    pub display_name: Option<String>,
+    /// The `tr` params.
+    pub trackers: Vec<String>,
+}
+
+impl MagnetLink {
+    /// Parses a magnet URI. Only `BitTorrent` v1 info-hashes are supported,
+    /// in hex or base32 format.
+    ///
+    /// # Errors
+    ///
+    /// It returns an error if the URI is not a magnet link or it does not
+    /// contain a `BitTorrent` v1 info-hash.
+    pub fn parse(uri: &str) -> Result<Self, InvalidMagnetLink> {
+        let url = Url::parse(uri.trim()).map_err(|_| InvalidMagnetLink)?;
+
+        if url.scheme() != "magnet" {
+            return Err(InvalidMagnetLink);
+        }
+
+        let mut info_hash = None;
+        let mut display_name = None;
+        let mut trackers: Vec<String> = vec![];
+
+        for (name, value) in url.query_pairs() {
+            match name.as_ref() {
+                "xt" => {
+                    if let Some(encoded) = value.strip_prefix("urn:btih:") {
+                        info_hash = Some(decode_info_hash(encoded).ok_or(InvalidMagnetLink)?);
+                    }
+                }
+                "dn" => display_name = Some(value.to_string()),
+                "tr" => {
+                    if !trackers.iter().any(|tracker| *tracker == value) {
+                        trackers.push(value.to_string());
+                    }
+                }
+                _ => {}
+            }
+        }
+
+        Ok(Self {
+            info_hash: info_hash.ok_or(InvalidMagnetLink)?,
+            display_name,
+            trackers,
+        })
+    }
+}
+
+#[derive(Debug, Display, PartialEq, Eq, Error)]
+#[display("Invalid magnet link.")]
+pub struct InvalidMagnetLink;
+
+/// Decodes a 40-char hex or a 32-char base32 info-hash.
+fn decode_info_hash(encoded: &str) -> Option<InfoHash> {
+    let bytes = match encoded.len() {
+        40 => hex::decode(encoded).ok()?,
+        32 => base32::decode(encoded)?,
+        _ => return None,
+    };
+
+    Some(InfoHash::from_bytes(&bytes))
+}
+
+/// Builds a torrent file from the info dictionary. Each tracker from the
+/// magnet link goes into its own tier.
+#[must_use]
+pub fn torrent_file_from_info_dictionary(info_dictionary: &[u8], trackers: &[String]) -> Vec<u8> {
+    fn bencode_string(value: &[u8]) -> Vec<u8> {
+        [value.len().to_string().as_bytes(), b":", value].concat()
+    }
+
+    let mut torrent = b"d".to_vec();
+
+    if let Some(announce) = trackers.first() {
+        torrent.extend(bencode_string(b"announce"));
+        torrent.extend(bencode_string(announce.as_bytes()));
+        torrent.extend(bencode_string(b"announce-list"));
+        torrent.push(b'l');
+        for tracker in trackers {
+            torrent.push(b'l');
+            torrent.extend(bencode_string(tracker.as_bytes()));
+            torrent.push(b'e');
+        }
+        torrent.push(b'e');
+    }
+
+    torrent.extend(bencode_string(b"info"));
+    torrent.extend(info_dictionary);
+    torrent.push(b'e');
+
+    torrent
+}
+
+#[derive(Debug, Display, PartialEq, Eq, Error)]
+pub enum FetchError {
+    #[display("No peers found for the torrent.")]
+    NoPeers,
+
+    #[display("None of the peers sent the torrent metadata.")]
+    MetadataNotFound,
+}
+
+#[derive(Debug, Display)]
+enum PeerError {
+    #[display("{_0}")]
+    Io(std::io::Error),
+    #[display("timeout")]
+    Timeout,
+    #[display("protocol error: {_0}")]
+    Protocol(&'static str),
+}
+
+impl From<std::io::Error> for PeerError {
+    fn from(err: std::io::Error) -> Self {
+        PeerError::Io(err)
+    }
+}
+
+/// Downloads the info dictionary of torrents from peers.
+pub struct MetadataFetcher {
+    peer_id: [u8; 20],
+    timeout: Duration,
+    max_peers: usize,
+    max_metadata_size: usize,
+}
+
+impl MetadataFetcher {
+    #[must_use]
+    pub fn new(timeout: Duration, max_peers: usize, max_metadata_size: usize) -> Self {
+        let mut peer_id = *b"-TI3000-000000000000";
+        for byte in &mut peer_id[8..] {
+            *byte = b'0' + rand::random::<u8>() % 10;
+        }
+
+        Self {
+            peer_id,
+            timeout,
+            max_peers,
+            max_metadata_size,
+        }
+    }
+
+    /// Announces the torrent to the trackers and returns the peers, up to
+    /// the max number of peers. Trackers that fail are ignored.
+    pub async fn find_peers(&self, info_hash: &InfoHash, trackers: &[String]) -> Vec<SocketAddr> {
+        let mut peers: Vec<SocketAddr> = vec![];
+
+        for tracker in trackers {
+            if peers.len() >= self.max_peers {
+                break;
+            }
+
+            let result = match Url::parse(tracker) {
+                Ok(url) if url.scheme() == "udp" => timeout(self.timeout, self.announce_udp(&url, info_hash)).await,
+                Ok(url) if url.scheme() == "http" || url.scheme() == "https" => {
+                    timeout(self.timeout, self.announce_http(&url, info_hash)).await
+                }
+                _ => continue,
+            };
+
+            match result {
+                Ok(Ok(tracker_peers)) => {
+                    for peer in tracker_peers {
+                        if !peers.contains(&peer) {
+                            peers.push(peer);
+                        }
+                    }
+                }
+                Ok(Err(err)) => debug!(
+                    "Failed to announce {} to {}: {}",
+                    info_hash.to_hex_string(),
+                    redact(tracker),
+                    redact(&err.to_string())
+                ),
+                Err(_) => debug!("Timeout announcing {} to {}", info_hash.to_hex_string(), redact(tracker)),
+            }
+        }
+
+        peers.truncate(self.max_peers);
+
+        peers
+    }
+
+    /// Downloads the info dictionary from the first peer that sends it.
+    ///
+    /// # Errors
+    ///
+    /// It returns an error if there are no peers, or none of them sent a
+    /// valid info dictionary.
+    pub async fn fetch_info_dictionary(&self, info_hash: &InfoHash, peers: &[SocketAddr]) -> Result<Vec<u8>, FetchError> {
+        if peers.is_empty() {
+            return Err(FetchError::NoPeers);
+        }
+
+        for peer in peers.iter().take(self.max_peers) {
+            match self.fetch_from_peer(info_hash, *peer).await {
+                Ok(info_dictionary) => return Ok(info_dictionary),
+                Err(err) => debug!(
+                    "Failed to get the metadata of {} from {peer}: {err}",
+                    info_hash.to_hex_string()
+                ),
+            }
+        }
+
+        Err(FetchError::MetadataNotFound)
+    }
+
+    async fn announce_http(&self, url: &Url, info_hash: &InfoHash) -> Result<Vec<SocketAddr>, PeerError> {
+        let info_hash_bytes = info_hash_bytes(info_hash);
+
+        let params = format!(
+            "info_hash={}&peer_id={}&port={ANNOUNCED_PORT}&uploaded=0&downloaded=0&left={METADATA_PIECE_SIZE}&compact=1&numwant=50&event=started",
+            urlencoding::encode_binary(&info_hash_bytes),
+            urlencoding::encode_binary(&self.peer_id),
+        );
+
+        let separator = if url.query().is_some() { '&' } else { '?' };
+
+        let body = reqwest::get(format!("{url}{separator}{params}"))
+            .await
+            .map_err(|_| PeerError::Protocol("tracker request failed"))?
+            .bytes()
+            .await
+            .map_err(|_| PeerError::Protocol("tracker response without body"))?;
+
+        let Ok(Value::Dict(response)) = serde_bencode::from_bytes::<Value>(&body) else {
+            return Err(PeerError::Protocol("invalid tracker response"));
+        };
+
+        let mut peers = vec![];
+
+        match response.get(b"peers".as_slice()) {
+            Some(Value::Bytes(compact)) => peers.extend(compact_peers(compact, 4)),
+            Some(Value::List(list)) => {
+                for peer in list {
+                    let Value::Dict(peer) = peer else { continue };
+                    let (Some(Value::Bytes(ip)), Some(Value::Int(port))) =
+                        (peer.get(b"ip".as_slice()), peer.get(b"port".as_slice()))
+                    else {
+                        continue;
+                    };
+                    let (Some(ip), Ok(port)) = (String::from_utf8_lossy(ip).parse::<IpAddr>().ok(), u16::try_from(*port)) else {
+                        continue;
+                    };
+                    peers.push(SocketAddr::new(ip, port));
+                }
+            }
+            _ => {}
+        }
+
+        if let Some(Value::Bytes(compact)) = response.get(b"peers6".as_slice()) {
+            peers.extend(compact_peers(compact, 16));
+        }
+
+        Ok(peers)
+    }
+
+    async fn announce_udp(&self, url: &Url, info_hash: &InfoHash) -> Result<Vec<SocketAddr>, PeerError> {
+        let (Some(host), Some(port)) = (url.host_str(), url.port()) else {
+            return Err(PeerError::Protocol("invalid tracker URL"));
+        };
+
+        let tracker = lookup_host((host.trim_matches(['[', ']']), port))
+            .await?
+            .next()
+            .ok_or(PeerError::Protocol("unknown tracker host"))?;
+
+        let bind_address: SocketAddr = if tracker.is_ipv4() {
+            (Ipv4Addr::UNSPECIFIED, 0).into()
+        } else {
+            (Ipv6Addr::UNSPECIFIED, 0).into()
+        };
+
+        let socket = UdpSocket::bind(bind_address).await?;
+        socket.connect(tracker).await?;
+
+        let mut buffer = vec![0u8; 2048];
+
+        // Connect
+        let transaction_id: u32 = rand::random();
+        let mut request = Vec::with_capacity(16);
+        request.extend(UDP_TRACKER_PROTOCOL_ID.to_be_bytes());
+        request.extend(0u32.to_be_bytes());
+        request.extend(transaction_id.to_be_bytes());
+        socket.send(&request).await?;
+
+        let length = socket.recv(&mut buffer).await?;
+        if length < 16 || buffer[0..4] != 0u32.to_be_bytes() || buffer[4..8] != transaction_id.to_be_bytes() {
+            return Err(PeerError::Protocol("invalid UDP tracker connect response"));
+        }
+        let connection_id = buffer[8..16].to_vec();
+
+        // Announce
+        let transaction_id: u32 = rand::random();
+        let mut request = Vec::with_capacity(98);
+        request.extend(&connection_id);
+        request.extend(1u32.to_be_bytes());
+        request.extend(transaction_id.to_be_bytes());
+        request.extend(info_hash_bytes(info_hash));
+        request.extend(self.peer_id);
+        request.extend(0u64.to_be_bytes()); // downloaded
+        request.extend((METADATA_PIECE_SIZE as u64).to_be_bytes()); // left
+        request.extend(0u64.to_be_bytes()); // uploaded
+        request.extend(2u32.to_be_bytes()); // event: started
+        request.extend(0u32.to_be_bytes()); // IP address: default
+        request.extend(rand::random::<u32>().to_be_bytes()); // key
+        request.extend((-1i32).to_be_bytes()); // num want: default
+        request.extend(ANNOUNCED_PORT.to_be_bytes());
+        socket.send(&request).await?;
+
+        let length = socket.recv(&mut buffer).await?;
+        if length < 20 || buffer[0..4] != 1u32.to_be_bytes() || buffer[4..8] != transaction_id.to_be_bytes() {
+            return Err(PeerError::Protocol("invalid UDP tracker announce response"));
+        }
+
+        let ip_length = if tracker.is_ipv4() { 4 } else { 16 };
+
+        Ok(compact_peers(&buffer[20..length], ip_length))
+    }
+
+    async fn fetch_from_peer(&self, info_hash: &InfoHash, peer: SocketAddr) -> Result<Vec<u8>, PeerError> {
+        let mut stream = timeout(self.timeout, TcpStream::connect(peer))
+            .await
+            .map_err(|_| PeerError::Timeout)??;
+
+        let info_hash_bytes = info_hash_bytes(info_hash);
+
+        // Handshake, announcing support for the extension protocol
+        let mut reserved = [0u8; 8];
+        reserved[5] |= 0x10;
+
+        let handshake = [
+            &[u8::try_from(PROTOCOL.len()).expect("protocol name length should fit in a byte")],
+            PROTOCOL,
+            &reserved,
+            &info_hash_bytes,
+            &self.peer_id,
+        ]
+        .concat();
+        stream.write_all(&handshake).await?;
+
+        let mut response = [0u8; 68];
+        self.read_exact(&mut stream, &mut response).await?;
+
+        if response[1..20] != *PROTOCOL || response[28..48] != info_hash_bytes {
+            return Err(PeerError::Protocol("invalid handshake"));
+        }
+        if response[25] & 0x10 == 0 {
+            return Err(PeerError::Protocol("the peer does not support the extension protocol"));
+        }
+
+        send_extended(&mut stream, 0, format!("d1:md11:ut_metadatai{UT_METADATA_ID}eee").as_bytes()).await?;
+
+        let mut peer_ut_metadata_id = None;
+        let mut pieces: Vec<Option<Vec<u8>>> = vec![];
+        let mut metadata_size = 0;
+
+        loop {
+            let message = self.read_message(&mut stream).await?;
+
+            // Only extended messages are used. Other messages are ignored.
+            let [20, extended_id, payload @ ..] = message.as_slice() else {
+                continue;
+            };
+
+            if *extended_id == 0 {
+                if peer_ut_metadata_id.is_some() {
+                    continue;
+                }
+
+                let (id, size) = parse_extended_handshake(payload)?;
+
+                if size == 0 || size > self.max_metadata_size {
+                    return Err(PeerError::Protocol("invalid metadata size"));
+                }
+
+                metadata_size = size;
+                pieces = vec![None; (size + METADATA_PIECE_SIZE - 1) / METADATA_PIECE_SIZE];
+                peer_ut_metadata_id = Some(id);
+
+                for piece in 0..pieces.len() {
+                    send_extended(&mut stream, id, format!("d8:msg_typei0e5:piecei{piece}ee").as_bytes()).await?;
+                }
+            } else if *extended_id == UT_METADATA_ID && peer_ut_metadata_id.is_some() {
+                let (piece, data) = parse_metadata_message(payload)?;
+
+                let slot = pieces.get_mut(piece).ok_or(PeerError::Protocol("invalid metadata piece"))?;
+                *slot = Some(data.to_vec());
+
+                if pieces.iter().all(Option::is_some) {
+                    let info_dictionary: Vec<u8> = pieces.into_iter().flatten().flatten().collect();
+
+                    if info_dictionary.len() != metadata_size || Sha1::digest(&info_dictionary).as_slice() != info_hash_bytes {
+                        return Err(PeerError::Protocol("the metadata does not match the info-hash"));
+                    }
+
+                    return Ok(info_dictionary);
+                }
+            }
+        }
+    }
+
+    async fn read_exact(&self, stream: &mut TcpStream, buffer: &mut [u8]) -> Result<(), PeerError> {
+        timeout(self.timeout, stream.read_exact(buffer))
+            .await
+            .map_err(|_| PeerError::Timeout)??;
+        Ok(())
+    }
+
+    /// Reads the next peer wire message, skipping keep-alives.
+    async fn read_message(&self, stream: &mut TcpStream) -> Result<Vec<u8>, PeerError> {
+        loop {
+            let mut length = [0u8; 4];
+            self.read_exact(stream, &mut length).await?;
+
+            let length = usize::try_from(u32::from_be_bytes(length)).map_err(|_| PeerError::Protocol("message too long"))?;
+
+            if length == 0 {
+                continue;
+            }
+            if length > MAX_MESSAGE_LENGTH {
+                return Err(PeerError::Protocol("message too long"));
+            }
+
+            let mut message = vec![0u8; length];
+            self.read_exact(stream, &mut message).await?;
+
+            return Ok(message);
+        }
+    }
+}
+
+fn info_hash_bytes(info_hash: &InfoHash) -> Vec<u8> {
+    hex::decode(info_hash.to_hex_string()).expect("info-hash should be a valid hex string")
+}
+
+/// Parses peers in the compact format: the IP address followed by the port.
+fn compact_peers(compact: &[u8], ip_length: usize) -> Vec<SocketAddr> {
+    compact
+        .chunks_exact(ip_length + 2)
+        .filter_map(|peer| {
+            let ip = match ip_length {
+                4 => IpAddr::from(<[u8; 4]>::try_from(&peer[..4]).ok()?),
+                16 => IpAddr::from(<[u8; 16]>::try_from(&peer[..16]).ok()?),
+                _ => return None,
+            };
+            let port = u16::from_be_bytes([peer[ip_length], peer[ip_length + 1]]);
+
+            (port != 0).then_some(SocketAddr::new(ip, port))
+        })
+        .collect()
+}
+
+async fn send_extended(stream: &mut TcpStream, extended_id: u8, payload: &[u8]) -> Result<(), PeerError> {
+    let length = u32::try_from(payload.len() + 2).map_err(|_| PeerError::Protocol("message too long"))?;
+
+    let message = [&length.to_be_bytes(), &[20, extended_id][..], payload].concat();
+
+    stream.write_all(&message).await?;
+
+    Ok(())
+}
+
+/// Returns the peer ID for `ut_metadata` messages and the metadata size.
+fn parse_extended_handshake(payload: &[u8]) -> Result<(u8, usize), PeerError> {
+    let Ok(Value::Dict(handshake)) = serde_bencode::from_bytes::<Value>(payload) else {
+        return Err(PeerError::Protocol("invalid extended handshake"));
+    };
+
+    let id = match handshake.get(b"m".as_slice()) {
+        Some(Value::Dict(extensions)) => match extensions.get(b"ut_metadata".as_slice()) {
+            Some(Value::Int(id)) => u8::try_from(*id).ok().filter(|id| *id != 0),
+            _ => None,
+        },
+        _ => None,
+    }
+    .ok_or(PeerError::Protocol("the peer does not support the metadata extension"))?;
+
+    let size = match handshake.get(b"metadata_size".as_slice()) {
+        Some(Value::Int(size)) => usize::try_from(*size).ok(),
+        _ => None,
+    }
+    .ok_or(PeerError::Protocol("missing metadata size"))?;
+
+    Ok((id, size))
+}
+
+/// Parses a `ut_metadata` data message. The payload is a bencoded dictionary
+/// followed by the piece data.
+fn parse_metadata_message(payload: &[u8]) -> Result<(usize, &[u8]), PeerError> {
+    let dictionary_length = bencoded_length(payload, 0).ok_or(PeerError::Protocol("invalid metadata message"))?;
+
+    let Ok(Value::Dict(dictionary)) = serde_bencode::from_bytes::<Value>(&payload[..dictionary_length]) else {
+        return Err(PeerError::Protocol("invalid metadata message"));
+    };
+
+    let int = |dictionary: &HashMap<Vec<u8>, Value>, key: &[u8]| match dictionary.get(key) {
+        Some(Value::Int(value)) => Some(*value),
+        _ => None,
+    };
+
+    match int(&dictionary, b"msg_type") {
+        Some(1) => {}
+        Some(2) => return Err(PeerError::Protocol("the peer rejected the metadata request")),
+        _ => return Err(PeerError::Protocol("unexpected metadata message")),
+    }
+
+    let piece = int(&dictionary, b"piece")
+        .and_then(|piece| usize::try_from(piece).ok())
+        .ok_or(PeerError::Protocol("invalid metadata piece"))?;
+
+    Ok((piece, &payload[dictionary_length..]))
+}
+
+/// Returns the length of the bencoded value at the beginning of the bytes.
+fn bencoded_length(bytes: &[u8], depth: usize) -> Option<usize> {
+    if depth > 64 {
+        return None;
+    }
+
+    match bytes.first()? {
+        b'i' => bytes.iter().position(|byte| *byte == b'e').map(|end| end + 1),
+        b'l' | b'd' => {
+            let mut position = 1;
+            while *bytes.get(position)? != b'e' {
+                position += bencoded_length(&bytes[position..], depth + 1)?;
+            }
+            Some(position + 1)
+        }
+        b'0'..=b'9' => {
+            let colon = bytes.iter().position(|byte| *byte == b':')?;
+            let length: usize = std::str::from_utf8(&bytes[..colon]).ok()?.parse().ok()?;
+            let end = colon + 1 + length;
+            (end <= bytes.len()).then_some(end)
+        }
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::str::FromStr;
+    use std::time::Duration;
+
+    use bittorrent_primitives::info_hash::InfoHash;
+    use sha1::{Digest, Sha1};
+    use tokio::io::{AsyncReadExt, AsyncWriteExt};
+    use tokio::net::TcpListener;
+
+    use super::{bencoded_length, torrent_file_from_info_dictionary, MagnetLink, MetadataFetcher};
+    use crate::utils::parse_torrent::decode_and_validate_torrent_file;
+
+    const INFO_DICTIONARY: &[u8] = b"d6:lengthi11e4:name8:file.txt12:piece lengthi16384e6:pieces20:aaaaaaaaaaaaaaaaaaaae";
+
+    fn info_hash() -> InfoHash {
+        InfoHash::from_bytes(&Sha1::digest(INFO_DICTIONARY))
+    }
+
+    #[test]
+    fn it_should_parse_magnet_links_with_hex_info_hashes() {
+        let magnet_link = MagnetLink::parse(
+            "magnet:?xt=urn:btih:5452869BE36F9F3350CCEE6B4544E7E76CAAADAB&dn=MandelbrotSet&tr=udp%3A%2F%2Ftracker.example.com%3A6969&tr=https%3A%2F%2Ftracker.example.org%2Fannounce",
+        )
+        .unwrap();
+
+        assert_eq!(
+            magnet_link,
+            MagnetLink {
+                info_hash: InfoHash::from_str("5452869be36f9f3350ccee6b4544e7e76caaadab").unwrap(),
+                display_name: Some("MandelbrotSet".to_string()),
+                trackers: vec![
+                    "udp://tracker.example.com:6969".to_string(),
+                    "https://tracker.example.org/announce".to_string()
+                ],
+            }
+        );
+    }
+
+    #[test]
+    fn it_should_parse_magnet_links_with_base32_info_hashes() {
+        let magnet_link = MagnetLink::parse("magnet:?xt=urn:btih:KRJING7DN6PTGUGM5ZVUKRHH45WKVLNL").unwrap();
+
+        assert_eq!(
+            magnet_link.info_hash,
+            InfoHash::from_str("5452869be36f9f3350ccee6b4544e7e76caaadab").unwrap()
+        );
+    }
+
+    #[test]
+    fn it_should_reject_uris_without_a_bittorrent_v1_info_hash() {
+        assert!(MagnetLink::parse("https://example.com/?xt=urn:btih:5452869be36f9f3350ccee6b4544e7e76caaadab").is_err());
+        assert!(MagnetLink::parse("magnet:?dn=MandelbrotSet").is_err());
+        assert!(MagnetLink::parse("magnet:?xt=urn:btih:5452869be36f9f3350").is_err());
+        assert!(
+            MagnetLink::parse("magnet:?xt=urn:btmh:1220caf1e1c30e81cb361b9ee167c4aa64228a7fa4fa9f6105232b28ad099f3a302e")
+                .is_err()
+        );
+    }
+
+    #[test]
+    fn it_should_get_the_length_of_bencoded_values() {
+        assert_eq!(bencoded_length(b"d8:msg_typei1e5:piecei0eeDATA", 0), Some(25));
+        assert_eq!(bencoded_length(b"li1e3:abce", 0), Some(10));
+        assert_eq!(bencoded_length(b"5:ab", 0), None);
+    }
+
+    #[test]
+    fn it_should_build_a_torrent_file_with_the_same_info_hash() {
+        let torrent_file = torrent_file_from_info_dictionary(INFO_DICTIONARY, &["udp://tracker.example.com:6969".to_string()]);
+
+        let (torrent, original_info_hash) = decode_and_validate_torrent_file(&torrent_file).unwrap();
+
+        assert_eq!(original_info_hash, info_hash());
+        assert_eq!(torrent.announce, Some("udp://tracker.example.com:6969".to_string()));
+        assert_eq!(
+            torrent.announce_list,
+            Some(vec![vec!["udp://tracker.example.com:6969".to_string()]])
+        );
+    }
+
+    /// A peer serving the metadata in a single piece.
+    async fn serve_metadata(listener: TcpListener) {
+        let (mut stream, _) = listener.accept().await.unwrap();
+
+        let mut handshake = [0u8; 68];
+        stream.read_exact(&mut handshake).await.unwrap();
+        handshake[40..60].copy_from_slice(b"-XX0001-000000000000");
+        stream.write_all(&handshake).await.unwrap();
+
+        let send = |payload: Vec<u8>| {
+            let mut message = u32::try_from(payload.len()).unwrap().to_be_bytes().to_vec();
+            message.extend(payload);
+            message
+        };
+
+        let extended_handshake = format!("d1:md11:ut_metadatai3ee13:metadata_sizei{}ee", INFO_DICTIONARY.len());
+        stream
+            .write_all(&send([&[20, 0], extended_handshake.as_bytes()].concat()))
+            .await
+            .unwrap();
+
+        loop {
+            let mut length = [0u8; 4];
+            if stream.read_exact(&mut length).await.is_err() {
+                return;
+            }
+            let mut message = vec![0u8; u32::from_be_bytes(length) as usize];
+            stream.read_exact(&mut message).await.unwrap();
+
+            if message[..2] == [20, 3] {
+                let header = format!("d8:msg_typei1e5:piecei0e10:total_sizei{}ee", INFO_DICTIONARY.len());
+                stream
+                    .write_all(&send([&[20, 1], header.as_bytes(), INFO_DICTIONARY].concat()))
+                    .await
+                    .unwrap();
+            }
+        }
+    }
+
+    #[tokio::test]
+    async fn it_should_fetch_the_info_dictionary_from_a_peer() {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let peer = listener.local_addr().unwrap();
+        tokio::spawn(serve_metadata(listener));
+
+        let fetcher = MetadataFetcher::new(Duration::from_secs(5), 10, 1024 * 1024);
+
+        let info_dictionary = fetcher.fetch_info_dictionary(&info_hash(), &[peer]).await.unwrap();
+
+        assert_eq!(info_dictionary, INFO_DICTIONARY);
+    }
+}
@@ -0,0 +1,451 @@
+//! Previews of the torrent contents.
+//!
+//! Uploaders can attach images and small text files in their torrents as
+//! previews, which are shown in the torrent details. The client extracts the
+//! pieces containing the file from the torrent contents and sends them with
+//! the index of the file in the torrent. The index verifies the pieces
+//! against the piece hashes in the torrent and stores only the file, so
+//! previews always match the torrent contents.
+//!
+//! The pieces at the beginning and the end of a file can contain bytes from
+//! the previous and next files. They are needed to verify the hashes but they
+//! are not stored.
+//!
+//! Previews are disabled unless the `[torrent.previews]` section is present:
+//!
+//! ```toml
+//! [torrent.previews]
+//! max_file_size = 1048576
+//! max_previews_per_torrent = 5
+//! ```
+//!
+//! Torrents without piece hashes (BEP 30) can't have previews.
+use std::sync::Arc;
+
+use bittorrent_primitives::info_hash::InfoHash;
+use sha1::{Digest, Sha1};
+
+use super::{DbTorrentListingGenerator, DbTorrentRepository};
+use crate::config::Configuration;
+use crate::databases::database::{Database, Error};
+use crate::errors::ServiceError;
+use crate::models::torrent::TorrentId;
+use crate::models::torrent_file::Torrent;
+use crate::models::torrent_preview::{PreviewId, TorrentPreview};
+use crate::models::user::UserId;
+use crate::services::authorization::{self, ACTION};
+use crate::services::image_upload::detect_content_type;
+use crate::services::user::Repository;
+
+/// Length in bytes of a SHA-1 piece hash.
+const PIECE_HASH_LENGTH: usize = 20;
+
+/// Extensions of the text files accepted as previews.
+const TEXT_EXTENSIONS: &[&str] = &["txt", "nfo", "diz", "md", "log"];
+
+/// User request to attach a file in the torrent as a preview.
+pub struct AddPreviewRequest {
+    /// Position of the file in the torrent. It's always `0` in single-file
+    /// torrents.
+    pub file_index: usize,
+    /// The contents of all the pieces the file is in, from the first to the
+    /// last one.
+    pub pieces: Vec<u8>,
+}
+
+/// A file in the torrent contents.
+#[derive(Debug, PartialEq, Eq)]
+struct FileSpan {
+    /// The file path, including the torrent name in multi-file torrents.
+    path: String,
+    /// Position of the first byte of the file in the torrent contents.
+    offset: u64,
+    length: u64,
+}
+
+pub struct Service {
+    configuration: Arc<Configuration>,
+    user_repository: Arc<Box<dyn Repository>>,
+    torrent_repository: Arc<DbTorrentRepository>,
+    torrent_listing_generator: Arc<DbTorrentListingGenerator>,
+    preview_repository: Arc<DbTorrentPreviewRepository>,
+    authorization_service: Arc<authorization::Service>,
+}
+
+impl Service {
+    #[must_use]
+    pub fn new(
+        configuration: Arc<Configuration>,
+        user_repository: Arc<Box<dyn Repository>>,
+        torrent_repository: Arc<DbTorrentRepository>,
+        torrent_listing_generator: Arc<DbTorrentListingGenerator>,
+        preview_repository: Arc<DbTorrentPreviewRepository>,
+        authorization_service: Arc<authorization::Service>,
+    ) -> Service {
+        Service {
+            configuration,
+            user_repository,
+            torrent_repository,
+            torrent_listing_generator,
+            preview_repository,
+            authorization_service,
+        }
+    }
+
+    /// It attaches a file in the torrent as a preview. Owners can always
+    /// attach previews to their torrents. Other users need permission to
+    /// edit the torrent.
+    ///
+    /// # Errors
+    ///
+    /// It returns an error if:
+    ///
+    /// * Previews are not enabled.
+    /// * The user does not have permission to edit the torrent.
+    /// * The torrent or the file do not exist.
+    /// * The file is empty or too big, or it's not an image or a text file.
+    /// * The pieces do not match the piece hashes in the torrent.
+    /// * The torrent has already the max number of previews, or a preview of
+    ///   the file.
+    /// * There is a database error.
+    pub async fn add_preview(
+        &self,
+        info_hash: &InfoHash,
+        request: &AddPreviewRequest,
+        maybe_user_id: Option<UserId>,
+    ) -> Result<TorrentPreview, ServiceError> {
+        let Some(previews) = self
+            .configuration
+            .settings
+            .read()
+            .await
+            .torrent
+            .as_ref()
+            .and_then(|torrent| torrent.previews.clone())
+        else {
+            return Err(ServiceError::TorrentPreviewsDisabled);
+        };
+
+        let user_id = maybe_user_id.ok_or(ServiceError::UnauthorizedActionForGuests)?;
+
+        let user = self.user_repository.get_compact(&user_id).await?;
+
+        let torrent_listing = self.torrent_listing_generator.one_torrent_by_info_hash(info_hash).await?;
+
+        if torrent_listing.uploader != user.username {
+            self.authorization_service
+                .authorize_in_category(ACTION::EditTorrentInfo, Some(user_id), torrent_listing.category_id)
+                .await?;
+        }
+
+        let torrent = self.torrent_repository.get_by_info_hash(info_hash).await?;
+
+        let file = file_span(&torrent, request.file_index).ok_or(ServiceError::PreviewFileNotFound)?;
+
+        if file.length == 0 || file.length > previews.max_file_size {
+            return Err(ServiceError::InvalidPreviewFileSize);
+        }
+
+        let piece_hashes = torrent.info.pieces.as_ref().ok_or(ServiceError::InvalidPreviewPieces)?;
+        let piece_length = u64::try_from(torrent.info.piece_length).map_err(|_| ServiceError::InvalidPreviewPieces)?;
+        let total_length = u64::try_from(torrent.file_size()).map_err(|_| ServiceError::InvalidPreviewPieces)?;
+
+        let content = extract_verified_file(piece_hashes, piece_length, total_length, &file, &request.pieces)?;
+
+        let content_type = preview_content_type(&file.path, &content).ok_or(ServiceError::UnsupportedPreviewType)?;
+
+        let existing_previews = self.preview_repository.get_all(torrent_listing.torrent_id).await?;
+
+        if existing_previews.len() >= previews.max_previews_per_torrent {
+            return Err(ServiceError::TooManyPreviews);
+        }
+
+        let file_index = i64::try_from(request.file_index).map_err(|_| ServiceError::PreviewFileNotFound)?;
+
+        let preview_id = self
+            .preview_repository
+            .add(torrent_listing.torrent_id, file_index, &file.path, content_type, &content)
+            .await?;
+
+        Ok(TorrentPreview {
+            preview_id,
+            torrent_id: torrent_listing.torrent_id,
+            file_index,
+            path: file.path,
+            content_type: content_type.to_string(),
+            size: i64::try_from(content.len()).unwrap_or(i64::MAX),
+        })
+    }
+
+    /// It returns the previews of a torrent, without their contents.
+    ///
+    /// # Errors
+    ///
+    /// It returns an error if:
+    ///
+    /// * The user does not have permission to get the torrent info.
+    /// * The torrent does not exist.
+    /// * There is a database error.
+    pub async fn get_previews(
+        &self,
+        info_hash: &InfoHash,
+        maybe_user_id: Option<UserId>,
+    ) -> Result<Vec<TorrentPreview>, ServiceError> {
+        self.authorization_service
+            .authorize(ACTION::GetTorrentInfo, maybe_user_id)
+            .await?;
+
+        let torrent_listing = self.torrent_listing_generator.one_torrent_by_info_hash(info_hash).await?;
+
+        Ok(self.preview_repository.get_all(torrent_listing.torrent_id).await?)
+    }
+
+    /// It returns the content type and the contents of a torrent preview.
+    ///
+    /// # Errors
+    ///
+    /// It returns an error if:
+    ///
+    /// * The user does not have permission to get the torrent info.
+    /// * The torrent or the preview do not exist.
+    /// * There is a database error.
+    pub async fn get_preview_content(
+        &self,
+        info_hash: &InfoHash,
+        preview_id: PreviewId,
+        maybe_user_id: Option<UserId>,
+    ) -> Result<(String, Vec<u8>), ServiceError> {
+        self.authorization_service
+            .authorize(ACTION::GetTorrentInfo, maybe_user_id)
+            .await?;
+
+        let torrent_listing = self.torrent_listing_generator.one_torrent_by_info_hash(info_hash).await?;
+
+        Ok(self
+            .preview_repository
+            .get_content(torrent_listing.torrent_id, preview_id)
+            .await?)
+    }
+}
+
+/// It returns the path and the position of a file in the torrent contents.
+fn file_span(torrent: &Torrent, file_index: usize) -> Option<FileSpan> {
+    if torrent.is_a_single_file_torrent() {
+        if file_index != 0 {
+            return None;
+        }
+
+        return Some(FileSpan {
+            path: torrent.info.name.clone(),
+            offset: 0,
+            length: u64::try_from(torrent.info.length?).ok()?,
+        });
+    }
+
+    let files = torrent.info.files.as_deref()?;
+
+    let file = files.get(file_index)?;
+
+    let offset = files[..file_index]
+        .iter()
+        .map(|file| u64::try_from(file.length).unwrap_or_default())
+        .sum();
+
+    let mut path = vec![torrent.info.name.as_str()];
+    path.extend(file.path.iter().map(String::as_str));
+
+    Some(FileSpan {
+        path: path.join("/"),
+        offset,
+        length: u64::try_from(file.length).ok()?,
+    })
+}
+
+/// It verifies the pieces containing a file against the piece hashes and
+/// returns the file contents. `pieces` must contain all the pieces the file
+/// is in, from the first to the last one.
+fn extract_verified_file(
+    piece_hashes: &[u8],
+    piece_length: u64,
+    total_length: u64,
+    file: &FileSpan,
+    pieces: &[u8],
+) -> Result<Vec<u8>, ServiceError> {
+    if piece_length == 0 || file.length == 0 || file.offset + file.length > total_length {
+        return Err(ServiceError::InvalidPreviewPieces);
+    }
+
+    let first_piece = file.offset / piece_length;
+    let last_piece = (file.offset + file.length - 1) / piece_length;
+
+    let start = first_piece * piece_length;
+    let end = ((last_piece + 1) * piece_length).min(total_length);
+
+    if u64::try_from(pieces.len()).ok() != Some(end - start) {
+        return Err(ServiceError::InvalidPreviewPieces);
+    }
+
+    let chunk_size = usize::try_from(piece_length).map_err(|_| ServiceError::InvalidPreviewPieces)?;
+
+    for (piece_index, piece) in (first_piece..=last_piece).zip(pieces.chunks(chunk_size)) {
+        let hash_start = usize::try_from(piece_index)
+            .ok()
+            .and_then(|piece_index| piece_index.checked_mul(PIECE_HASH_LENGTH))
+            .ok_or(ServiceError::InvalidPreviewPieces)?;
+
+        let expected_hash = piece_hashes
+            .get(hash_start..hash_start + PIECE_HASH_LENGTH)
+            .ok_or(ServiceError::InvalidPreviewPieces)?;
+
+        if Sha1::digest(piece).as_slice() != expected_hash {
+            return Err(ServiceError::InvalidPreviewPieces);
+        }
+    }
+
+    let file_start = usize::try_from(file.offset - start).map_err(|_| ServiceError::InvalidPreviewPieces)?;
+    let file_end = file_start + usize::try_from(file.length).map_err(|_| ServiceError::InvalidPreviewPieces)?;
+
+    Ok(pieces[file_start..file_end].to_vec())
+}
+
+/// It returns the content type of the preview. Images are detected from their
+/// contents. Text files must have one of the [`TEXT_EXTENSIONS`] and can't
+/// contain null bytes.
+fn preview_content_type(path: &str, content: &[u8]) -> Option<&'static str> {
+    if let Some(content_type) = detect_content_type(content) {
+        return Some(content_type);
+    }
+
+    let (_, extension) = path.rsplit_once('.')?;
+
+    if !TEXT_EXTENSIONS.contains(&extension.to_ascii_lowercase().as_str()) || content.contains(&0) {
+        return None;
+    }
+
+    if std::str::from_utf8(content).is_ok() {
+        Some("text/plain; charset=utf-8")
+    } else {
+        Some("text/plain")
+    }
+}
+
+pub struct DbTorrentPreviewRepository {
+    database: Arc<Box<dyn Database>>,
+}
+
+impl DbTorrentPreviewRepository {
+    #[must_use]
+    pub fn new(database: Arc<Box<dyn Database>>) -> Self {
+        Self { database }
+    }
+
+    /// It adds a preview to a torrent.
+    ///
+    /// # Errors
+    ///
+    /// It returns an error if the torrent has already a preview of the file
+    /// or there is a database error.
+    pub async fn add(
+        &self,
+        torrent_id: TorrentId,
+        file_index: i64,
+        path: &str,
+        content_type: &str,
+        content: &[u8],
+    ) -> Result<PreviewId, Error> {
+        self.database
+            .insert_torrent_preview(torrent_id, file_index, path, content_type, content)
+            .await
+    }
+
+    /// It returns the previews of a torrent, without their contents.
+    ///
+    /// # Errors
+    ///
+    /// It returns an error if there is a database error.
+    pub async fn get_all(&self, torrent_id: TorrentId) -> Result<Vec<TorrentPreview>, Error> {
+        self.database.get_torrent_previews(torrent_id).await
+    }
+
+    /// It returns the content type and the contents of a preview.
+    ///
+    /// # Errors
+    ///
+    /// It returns an error if the preview does not exist or there is a
+    /// database error.
+    pub async fn get_content(&self, torrent_id: TorrentId, preview_id: PreviewId) -> Result<(String, Vec<u8>), Error> {
+        self.database.get_torrent_preview_content(torrent_id, preview_id).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use sha1::{Digest, Sha1};
+
+    use super::{extract_verified_file, preview_content_type, FileSpan};
+    use crate::errors::ServiceError;
+
+    const PIECE_LENGTH: u64 = 4;
+
+    /// The contents of a torrent with three files: `aaaaaa`, `bbbbb` and
+    /// `cc`. The second file is in the second and third pieces.
+    const CONTENTS: &[u8] = b"aaaaaabbbbbcc";
+
+    fn piece_hashes(contents: &[u8]) -> Vec<u8> {
+        contents.chunks(4).flat_map(|piece| Sha1::digest(piece).to_vec()).collect()
+    }
+
+    fn second_file() -> FileSpan {
+        FileSpan {
+            path: "pack/b.txt".to_string(),
+            offset: 6,
+            length: 5,
+        }
+    }
+
+    #[test]
+    fn it_should_extract_the_file_from_the_verified_pieces() {
+        assert_eq!(
+            extract_verified_file(&piece_hashes(CONTENTS), PIECE_LENGTH, 13, &second_file(), &CONTENTS[4..12]),
+            Ok(b"bbbbb".to_vec())
+        );
+    }
+
+    #[test]
+    fn it_should_reject_pieces_not_matching_the_piece_hashes() {
+        let mut pieces = CONTENTS[4..12].to_vec();
+        pieces[0] = b'x';
+
+        assert_eq!(
+            extract_verified_file(&piece_hashes(CONTENTS), PIECE_LENGTH, 13, &second_file(), &pieces),
+            Err(ServiceError::InvalidPreviewPieces)
+        );
+    }
+
+    #[test]
+    fn it_should_reject_incomplete_pieces() {
+        assert_eq!(
+            extract_verified_file(&piece_hashes(CONTENTS), PIECE_LENGTH, 13, &second_file(), &CONTENTS[4..11]),
+            Err(ServiceError::InvalidPreviewPieces)
+        );
+        assert_eq!(
+            extract_verified_file(&piece_hashes(CONTENTS), PIECE_LENGTH, 13, &second_file(), &CONTENTS[6..11]),
+            Err(ServiceError::InvalidPreviewPieces)
+        );
+    }
+
+    #[test]
+    fn it_should_only_accept_images_and_text_files_as_previews() {
+        assert_eq!(
+            preview_content_type("pack/cover.jpg", b"\xff\xd8\xff\xe0\0\x10JFIF"),
+            Some("image/jpeg")
+        );
+        assert_eq!(
+            preview_content_type("pack/README.NFO", "Ubuntu 24.04 ✓".as_bytes()),
+            Some("text/plain; charset=utf-8")
+        );
+        assert_eq!(preview_content_type("pack/release.nfo", b"\xdb\xdb\xb0"), Some("text/plain"));
+        assert_eq!(preview_content_type("pack/setup.exe", b"MZ\x90\0"), None);
+        assert_eq!(preview_content_type("pack/notes.txt", b"text\0with null bytes"), None);
+    }
+}
@@ -0,0 +1,291 @@
+//! Per-user upload quotas.
+//!
+//! Users can upload a limited number of torrents per day, and the contents of
+//! all their torrents can't exceed a total size. The limits come from the
+//! `[limits]` section in the configuration unless an administrator sets other
+//! limits for the user. Administrators are never limited.
+//!
+//! Magnet uploads count towards the uploads per day when they are submitted.
+//! Their size is unknown until they are resolved, so they are not checked
+//! against the total size quota.
+//!
+//! Refer to [`Limits`](crate::config::Limits) for the configuration.
+use std::sync::Arc;
+
+use chrono::{TimeDelta, Utc};
+
+use crate::config::Configuration;
+use crate::databases::database::{Database, Error};
+use crate::errors::ServiceError;
+use crate::models::upload_quota::{UploadLimits, UploadQuota, UploadUsage};
+use crate::models::user::UserId;
+use crate::services::authorization::{self, ACTION};
+use crate::utils::clock::DATETIME_FORMAT;
+
+pub struct Service {
+    configuration: Arc<Configuration>,
+    quota_repository: Arc<DbUploadQuotaRepository>,
+    authorization_service: Arc<authorization::Service>,
+}
+
+impl Service {
+    #[must_use]
+    pub fn new(
+        configuration: Arc<Configuration>,
+        quota_repository: Arc<DbUploadQuotaRepository>,
+        authorization_service: Arc<authorization::Service>,
+    ) -> Service {
+        Service {
+            configuration,
+            quota_repository,
+            authorization_service,
+        }
+    }
+
+    /// It checks the user can upload a new torrent. The size of the torrent
+    /// contents is only checked when it's known.
+    ///
+    /// # Errors
+    ///
+    /// It returns an error if:
+    ///
+    /// * The user has reached the max number of uploads per day.
+    /// * The upload would exceed the user's total upload size quota.
+    /// * There is a database error.
+    pub async fn check_upload(&self, user_id: UserId, size: Option<u64>) -> Result<(), ServiceError> {
+        if self
+            .authorization_service
+            .authorize(ACTION::ManageUploadQuotas, Some(user_id))
+            .await
+            .is_ok()
+        {
+            return Ok(());
+        }
+
+        let (limits, _overridden) = self.limits_for(user_id).await?;
+
+        if limits == UploadLimits::default() {
+            return Ok(());
+        }
+
+        let usage = self.quota_repository.get_usage(user_id).await?;
+
+        check(&limits, &usage, size)
+    }
+
+    /// It returns the upload quota of a user.
+    ///
+    /// # Errors
+    ///
+    /// It returns an error if:
+    ///
+    /// * The user does not have permissions to manage the upload quotas.
+    /// * The user does not exist.
+    /// * There is a database error.
+    pub async fn get_quota(&self, username: &str, maybe_user_id: Option<UserId>) -> Result<UploadQuota, ServiceError> {
+        self.authorization_service
+            .authorize(ACTION::ManageUploadQuotas, maybe_user_id)
+            .await?;
+
+        let user_id = self.quota_repository.get_user_id(username).await?;
+
+        let (limits, overridden) = self.limits_for(user_id).await?;
+
+        let usage = self.quota_repository.get_usage(user_id).await?;
+
+        Ok(UploadQuota {
+            username: username.to_string(),
+            limits,
+            overridden,
+            usage,
+        })
+    }
+
+    /// It sets the upload limits of a user. They replace the limits in the
+    /// configuration.
+    ///
+    /// # Errors
+    ///
+    /// It returns an error if:
+    ///
+    /// * The user does not have permissions to manage the upload quotas.
+    /// * The user does not exist.
+    /// * There is a database error.
+    pub async fn set_limits(
+        &self,
+        username: &str,
+        limits: &UploadLimits,
+        maybe_user_id: Option<UserId>,
+    ) -> Result<(), ServiceError> {
+        self.authorization_service
+            .authorize(ACTION::ManageUploadQuotas, maybe_user_id)
+            .await?;
+
+        let user_id = self.quota_repository.get_user_id(username).await?;
+
+        Ok(self.quota_repository.set_limits(user_id, limits).await?)
+    }
+
+    /// It removes the upload limits set for a user. The limits in the
+    /// configuration apply again.
+    ///
+    /// # Errors
+    ///
+    /// It returns an error if:
+    ///
+    /// * The user does not have permissions to manage the upload quotas.
+    /// * The user does not exist.
+    /// * There is a database error.
+    pub async fn remove_limits(&self, username: &str, maybe_user_id: Option<UserId>) -> Result<(), ServiceError> {
+        self.authorization_service
+            .authorize(ACTION::ManageUploadQuotas, maybe_user_id)
+            .await?;
+
+        let user_id = self.quota_repository.get_user_id(username).await?;
+
+        Ok(self.quota_repository.remove_limits(user_id).await?)
+    }
+
+    /// It returns the limits that apply to the user and whether they were
+    /// set for the user.
+    async fn limits_for(&self, user_id: UserId) -> Result<(UploadLimits, bool), ServiceError> {
+        if let Some(limits) = self.quota_repository.get_limits(user_id).await? {
+            return Ok((limits, true));
+        }
+
+        let settings = self.configuration.settings.read().await;
+
+        let limits = settings
+            .limits
+            .as_ref()
+            .map_or_else(UploadLimits::default, |limits| UploadLimits {
+                max_uploads_per_day: limits.max_uploads_per_day,
+                max_total_upload_size: limits.max_total_upload_size,
+            });
+
+        Ok((limits, false))
+    }
+}
+
+/// It checks a new upload against the user's limits and current usage.
+fn check(limits: &UploadLimits, usage: &UploadUsage, size: Option<u64>) -> Result<(), ServiceError> {
+    if let Some(max_uploads_per_day) = limits.max_uploads_per_day {
+        if usage.uploads_last_day >= u64::from(max_uploads_per_day) {
+            return Err(ServiceError::DailyUploadLimitReached);
+        }
+    }
+
+    if let (Some(max_total_upload_size), Some(size)) = (limits.max_total_upload_size, size) {
+        if usage.total_upload_size.saturating_add(size) > max_total_upload_size {
+            return Err(ServiceError::UploadSizeQuotaExceeded);
+        }
+    }
+
+    Ok(())
+}
+
+pub struct DbUploadQuotaRepository {
+    database: Arc<Box<dyn Database>>,
+}
+
+impl DbUploadQuotaRepository {
+    #[must_use]
+    pub fn new(database: Arc<Box<dyn Database>>) -> Self {
+        Self { database }
+    }
+
+    /// It returns the ID of a user.
+    ///
+    /// # Errors
+    ///
+    /// It returns an error if the user does not exist.
+    pub async fn get_user_id(&self, username: &str) -> Result<UserId, Error> {
+        self.database
+            .get_user_profile_from_username(username)
+            .await
+            .map(|user_profile| user_profile.user_id)
+    }
+
+    /// It returns the uploads of the user counted by the quotas.
+    ///
+    /// # Errors
+    ///
+    /// It returns an error if there is a database error.
+    pub async fn get_usage(&self, user_id: UserId) -> Result<UploadUsage, Error> {
+        let since = (Utc::now() - TimeDelta::days(1)).format(DATETIME_FORMAT).to_string();
+
+        self.database.get_user_upload_usage(user_id, &since).await
+    }
+
+    /// It returns the limits set for the user, if any.
+    ///
+    /// # Errors
+    ///
+    /// It returns an error if there is a database error.
+    pub async fn get_limits(&self, user_id: UserId) -> Result<Option<UploadLimits>, Error> {
+        self.database.get_user_upload_limits(user_id).await
+    }
+
+    /// It sets the limits of the user.
+    ///
+    /// # Errors
+    ///
+    /// It returns an error if there is a database error.
+    pub async fn set_limits(&self, user_id: UserId, limits: &UploadLimits) -> Result<(), Error> {
+        self.database.upsert_user_upload_limits(user_id, limits).await
+    }
+
+    /// It removes the limits set for the user.
+    ///
+    /// # Errors
+    ///
+    /// It returns an error if there is a database error.
+    pub async fn remove_limits(&self, user_id: UserId) -> Result<(), Error> {
+        self.database.delete_user_upload_limits(user_id).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::check;
+    use crate::errors::ServiceError;
+    use crate::models::upload_quota::{UploadLimits, UploadUsage};
+
+    fn usage(uploads_last_day: u64, total_upload_size: u64) -> UploadUsage {
+        UploadUsage {
+            uploads_last_day,
+            total_upload_size,
+        }
+    }
+
+    #[test]
+    fn it_should_allow_any_upload_without_limits() {
+        assert_eq!(check(&UploadLimits::default(), &usage(1000, u64::MAX), Some(1)), Ok(()));
+    }
+
+    #[test]
+    fn it_should_reject_uploads_over_the_daily_limit() {
+        let limits = UploadLimits {
+            max_uploads_per_day: Some(3),
+            max_total_upload_size: None,
+        };
+
+        assert_eq!(check(&limits, &usage(2, 0), None), Ok(()));
+        assert_eq!(check(&limits, &usage(3, 0), None), Err(ServiceError::DailyUploadLimitReached));
+    }
+
+    #[test]
+    fn it_should_reject_uploads_over_the_total_size_quota() {
+        let limits = UploadLimits {
+            max_uploads_per_day: None,
+            max_total_upload_size: Some(1000),
+        };
+
+        assert_eq!(check(&limits, &usage(0, 600), Some(400)), Ok(()));
+        assert_eq!(
+            check(&limits, &usage(0, 600), Some(401)),
+            Err(ServiceError::UploadSizeQuotaExceeded)
+        );
+        assert_eq!(check(&limits, &usage(0, 2000), None), Ok(()));
+    }
+}
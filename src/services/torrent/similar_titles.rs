@@ -0,0 +1,151 @@
+//! Detection of possible duplicate uploads by title and size.
+//!
+//! Refer to [`SimilarTitles`](crate::config::SimilarTitles) for the
+//! configuration.
+use crate::models::torrent::SimilarTorrent;
+
+/// It normalizes a title for comparison: lowercase alphanumeric words
+/// separated by one space. Separators like `.`, `_` and `-`, common in
+/// release names, are ignored.
+#[must_use]
+pub fn normalize_title(title: &str) -> String {
+    title
+        .to_lowercase()
+        .split(|char: char| !char.is_alphanumeric())
+        .filter(|word| !word.is_empty())
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+/// Distance between two normalized titles, from `0.0` (identical) to `1.0`
+/// (completely different). It's the Levenshtein distance divided by the
+/// length of the longest title.
+#[must_use]
+#[allow(clippy::cast_precision_loss)]
+pub fn title_distance(a: &str, b: &str) -> f64 {
+    let a: Vec<char> = a.chars().collect();
+    let b: Vec<char> = b.chars().collect();
+
+    let max_len = a.len().max(b.len());
+
+    if max_len == 0 {
+        return 0.0;
+    }
+
+    levenshtein(&a, &b) as f64 / max_len as f64
+}
+
+fn levenshtein(a: &[char], b: &[char]) -> usize {
+    let mut previous: Vec<usize> = (0..=b.len()).collect();
+    let mut current = vec![0; b.len() + 1];
+
+    for (i, char_a) in a.iter().enumerate() {
+        current[0] = i + 1;
+
+        for (j, char_b) in b.iter().enumerate() {
+            let substitution = previous[j] + usize::from(char_a != char_b);
+            current[j + 1] = substitution.min(previous[j + 1] + 1).min(current[j] + 1);
+        }
+
+        std::mem::swap(&mut previous, &mut current);
+    }
+
+    previous[b.len()]
+}
+
+/// The size range (inclusive) of the torrents compared with a torrent of the
+/// given size.
+#[must_use]
+#[allow(clippy::cast_possible_truncation)]
+#[allow(clippy::cast_precision_loss)]
+pub fn size_range(size: i64, tolerance: f64) -> (i64, i64) {
+    let margin = (size as f64 * tolerance).round() as i64;
+
+    ((size - margin).max(0), size.saturating_add(margin))
+}
+
+/// It returns the candidates whose normalized title is within the max
+/// distance, from the most similar to the least similar.
+#[must_use]
+pub fn most_similar(
+    title: &str,
+    candidates: Vec<SimilarTorrent>,
+    max_title_distance: f64,
+    max_candidates: usize,
+) -> Vec<SimilarTorrent> {
+    let title = normalize_title(title);
+
+    let mut similar: Vec<(f64, SimilarTorrent)> = candidates
+        .into_iter()
+        .map(|candidate| (title_distance(&title, &normalize_title(&candidate.title)), candidate))
+        .filter(|(distance, _)| *distance <= max_title_distance)
+        .collect();
+
+    similar.sort_by(|(a, _), (b, _)| a.total_cmp(b));
+
+    similar
+        .into_iter()
+        .take(max_candidates)
+        .map(|(_, candidate)| candidate)
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{most_similar, normalize_title, size_range, title_distance};
+    use crate::models::torrent::SimilarTorrent;
+
+    fn torrent(torrent_id: i64, title: &str) -> SimilarTorrent {
+        SimilarTorrent {
+            torrent_id,
+            info_hash: format!("{torrent_id:040}"),
+            title: title.to_string(),
+            file_size: 1000,
+        }
+    }
+
+    #[test]
+    fn it_should_ignore_case_and_separators_when_normalizing_titles() {
+        assert_eq!(
+            normalize_title("Ubuntu.24.04_Desktop - AMD64 [ISO]"),
+            "ubuntu 24 04 desktop amd64 iso"
+        );
+    }
+
+    #[test]
+    fn it_should_measure_the_distance_relative_to_the_longest_title() {
+        assert!(title_distance("ubuntu", "ubuntu").abs() < f64::EPSILON);
+        assert!((title_distance("ubuntu 24 04", "ubuntu 24 10") - 2.0 / 12.0).abs() < f64::EPSILON);
+        assert!((title_distance("abc", "xyz") - 1.0).abs() < f64::EPSILON);
+        assert!(title_distance("", "").abs() < f64::EPSILON);
+    }
+
+    #[test]
+    fn it_should_compare_torrents_with_a_size_within_the_tolerance() {
+        assert_eq!(size_range(1000, 0.01), (990, 1010));
+        assert_eq!(size_range(0, 0.5), (0, 0));
+    }
+
+    #[test]
+    fn it_should_return_the_most_similar_torrents_first() {
+        let candidates = vec![
+            torrent(1, "Debian 12"),
+            torrent(2, "Ubuntu 24.04 Desktop amd64"),
+            torrent(3, "ubuntu-24.04-desktop-amd64"),
+        ];
+
+        let similar = most_similar("Ubuntu.24.04.Desktop.AMD64", candidates, 0.2, 5);
+
+        assert_eq!(
+            similar.iter().map(|torrent| torrent.torrent_id).collect::<Vec<_>>(),
+            vec![2, 3]
+        );
+    }
+
+    #[test]
+    fn it_should_limit_the_number_of_similar_torrents() {
+        let candidates = vec![torrent(1, "Ubuntu 24.04"), torrent(2, "Ubuntu 24.04")];
+
+        assert_eq!(most_similar("Ubuntu 24.04", candidates, 0.2, 1).len(), 1);
+    }
+}
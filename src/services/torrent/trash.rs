@@ -0,0 +1,388 @@
+//! Trash bin for the deleted torrents.
+//!
+//! Deleting a torrent moves it to the trash bin. It's hidden from the
+//! listing and from the torrent endpoints, but it's kept, and whitelisted in
+//! the tracker, so that an administrator can restore it if it was deleted by
+//! mistake.
+//!
+//! Torrents are purged from the trash bin after the retention period by the
+//! [`trash_purge`](crate::console::cronjobs::trash_purge) cronjob, or before
+//! by an administrator. Purged torrents are removed from the database and
+//! from the tracker whitelist.
+//!
+//! ```toml
+//! [torrent.trash]
+//! retention_days = 30
+//! purge_interval_seconds = 3600
+//! ```
+use std::sync::Arc;
+
+use chrono::{TimeDelta, Utc};
+use tracing::{error, info};
+
+use super::{DbTorrentListingGenerator, DbTorrentRepository};
+use crate::config::{Configuration, TorrentTrash};
+use crate::databases::database::{Database, Error};
+use crate::errors::ServiceError;
+use crate::models::audit::AuditAction;
+use crate::models::response::DeletedTorrentResponse;
+use crate::models::torrent::{TorrentId, TrashedTorrent};
+use crate::models::user::UserId;
+use crate::services::authorization::{self, ACTION};
+use crate::services::{audit, search};
+use crate::tracker;
+use crate::utils::clock::DATETIME_FORMAT;
+
+/// Max number of torrents purged in each run of the purge job.
+const MAX_TORRENTS_PURGED_PER_RUN: u32 = 100;
+
+pub struct Service {
+    configuration: Arc<Configuration>,
+    trash_repository: Arc<DbTorrentTrashRepository>,
+    torrent_repository: Arc<DbTorrentRepository>,
+    torrent_listing_generator: Arc<DbTorrentListingGenerator>,
+    tracker_service: Arc<tracker::service::Service>,
+    search_service: Arc<search::Service>,
+    authorization_service: Arc<authorization::Service>,
+    audit_service: Arc<audit::Service>,
+}
+
+impl Service {
+    #[allow(clippy::too_many_arguments)]
+    #[must_use]
+    pub fn new(
+        configuration: Arc<Configuration>,
+        trash_repository: Arc<DbTorrentTrashRepository>,
+        torrent_repository: Arc<DbTorrentRepository>,
+        torrent_listing_generator: Arc<DbTorrentListingGenerator>,
+        tracker_service: Arc<tracker::service::Service>,
+        search_service: Arc<search::Service>,
+        authorization_service: Arc<authorization::Service>,
+        audit_service: Arc<audit::Service>,
+    ) -> Service {
+        Service {
+            configuration,
+            trash_repository,
+            torrent_repository,
+            torrent_listing_generator,
+            tracker_service,
+            search_service,
+            authorization_service,
+            audit_service,
+        }
+    }
+
+    /// It returns the torrents in the trash bin, from the most recently
+    /// deleted.
+    ///
+    /// # Errors
+    ///
+    /// It returns an error if:
+    ///
+    /// * The user is not an administrator.
+    /// * There is a database error.
+    pub async fn get_trashed_torrents(&self, maybe_user_id: Option<UserId>) -> Result<Vec<TrashedTorrent>, ServiceError> {
+        self.authorization_service
+            .authorize(ACTION::ManageTrash, maybe_user_id)
+            .await?;
+
+        Ok(self.trash_repository.get_all().await?)
+    }
+
+    /// It restores a torrent from the trash bin with the moderation status it
+    /// had before it was deleted.
+    ///
+    /// # Errors
+    ///
+    /// It returns an error if:
+    ///
+    /// * The user is not an administrator.
+    /// * The torrent is not in the trash bin.
+    /// * There is a database error.
+    pub async fn restore_torrent(
+        &self,
+        torrent_id: TorrentId,
+        maybe_user_id: Option<UserId>,
+    ) -> Result<TrashedTorrent, ServiceError> {
+        self.authorization_service
+            .authorize(ACTION::ManageTrash, maybe_user_id)
+            .await?;
+
+        let trashed_torrent = self.trash_repository.get(torrent_id).await?;
+
+        self.trash_repository.restore(torrent_id).await?;
+
+        self.torrent_listing_generator.invalidate();
+
+        self.search_service.index_torrent(torrent_id).await;
+
+        self.audit_service
+            .record(
+                maybe_user_id,
+                AuditAction::TorrentRestore,
+                &trashed_torrent.info_hash,
+                Some(&trashed_torrent.title),
+            )
+            .await;
+
+        Ok(trashed_torrent)
+    }
+
+    /// It removes a torrent in the trash bin for good, before the end of the
+    /// retention period.
+    ///
+    /// # Errors
+    ///
+    /// It returns an error if:
+    ///
+    /// * The user is not an administrator.
+    /// * The torrent is not in the trash bin.
+    /// * There is a database error.
+    pub async fn purge_torrent(
+        &self,
+        torrent_id: TorrentId,
+        maybe_user_id: Option<UserId>,
+    ) -> Result<DeletedTorrentResponse, ServiceError> {
+        self.authorization_service
+            .authorize(ACTION::ManageTrash, maybe_user_id)
+            .await?;
+
+        let trashed_torrent = self.trash_repository.get(torrent_id).await?;
+
+        self.purge(&trashed_torrent).await?;
+
+        self.audit_service
+            .record(
+                maybe_user_id,
+                AuditAction::TorrentPurge,
+                &trashed_torrent.info_hash,
+                Some(&trashed_torrent.title),
+            )
+            .await;
+
+        Ok(DeletedTorrentResponse {
+            torrent_id: trashed_torrent.torrent_id,
+            info_hash: trashed_torrent.info_hash,
+        })
+    }
+
+    /// It purges the torrents deleted before the retention period. It
+    /// returns the number of purged torrents.
+    ///
+    /// # Errors
+    ///
+    /// It returns an error if there is a database error.
+    pub async fn purge_expired_torrents(&self) -> Result<usize, ServiceError> {
+        let trash = self.trash_settings().await;
+
+        let deleted_before = (Utc::now() - TimeDelta::days(i64::from(trash.retention_days)))
+            .format(DATETIME_FORMAT)
+            .to_string();
+
+        let expired_torrents = self
+            .trash_repository
+            .get_deleted_before(&deleted_before, MAX_TORRENTS_PURGED_PER_RUN)
+            .await?;
+
+        for trashed_torrent in &expired_torrents {
+            self.purge(trashed_torrent).await?;
+
+            self.audit_service
+                .record(
+                    None,
+                    AuditAction::TorrentPurge,
+                    &trashed_torrent.info_hash,
+                    Some(&trashed_torrent.title),
+                )
+                .await;
+        }
+
+        if !expired_torrents.is_empty() {
+            info!(target: "trash", "Purged {} torrents from the trash bin", expired_torrents.len());
+        }
+
+        Ok(expired_torrents.len())
+    }
+
+    async fn purge(&self, trashed_torrent: &TrashedTorrent) -> Result<(), ServiceError> {
+        self.torrent_repository.delete(&trashed_torrent.torrent_id).await?;
+
+        // todo: handle the error when the tracker is offline or not well configured.
+        if let Err(e) = self
+            .tracker_service
+            .remove_info_hash_from_whitelist(trashed_torrent.info_hash.clone())
+            .await
+        {
+            error!(
+                target: "trash",
+                "Failed to remove torrent {} from the tracker whitelist: {e:?}", trashed_torrent.info_hash
+            );
+        }
+
+        Ok(())
+    }
+
+    async fn trash_settings(&self) -> TorrentTrash {
+        let settings = self.configuration.settings.read().await;
+
+        settings
+            .torrent
+            .as_ref()
+            .map(|torrent| torrent.trash.clone())
+            .unwrap_or_default()
+    }
+}
+
+pub struct DbTorrentTrashRepository {
+    database: Arc<Box<dyn Database>>,
+}
+
+impl DbTorrentTrashRepository {
+    #[must_use]
+    pub fn new(database: Arc<Box<dyn Database>>) -> Self {
+        Self { database }
+    }
+
+    /// It moves a torrent to the trash bin.
+    ///
+    /// # Errors
+    ///
+    /// It returns an error if the torrent does not exist, it's already in the
+    /// trash bin or there is a database error.
+    pub async fn add(&self, torrent_id: TorrentId, deleted_by: Option<UserId>) -> Result<(), Error> {
+        self.database.move_torrent_to_trash(torrent_id, deleted_by).await
+    }
+
+    /// It restores a torrent from the trash bin.
+    ///
+    /// # Errors
+    ///
+    /// It returns an error if the torrent is not in the trash bin or there is
+    /// a database error.
+    pub async fn restore(&self, torrent_id: TorrentId) -> Result<(), Error> {
+        self.database.restore_torrent_from_trash(torrent_id).await
+    }
+
+    /// It returns a torrent in the trash bin.
+    ///
+    /// # Errors
+    ///
+    /// It returns an error if the torrent is not in the trash bin or there is
+    /// a database error.
+    pub async fn get(&self, torrent_id: TorrentId) -> Result<TrashedTorrent, Error> {
+        self.database.get_trashed_torrent(torrent_id).await
+    }
+
+    /// It returns the torrents in the trash bin.
+    ///
+    /// # Errors
+    ///
+    /// It returns an error if there is a database error.
+    pub async fn get_all(&self) -> Result<Vec<TrashedTorrent>, Error> {
+        self.database.get_trashed_torrents().await
+    }
+
+    /// It returns the torrents deleted before the given date.
+    ///
+    /// # Errors
+    ///
+    /// It returns an error if there is a database error.
+    pub async fn get_deleted_before(&self, date: &str, limit: u32) -> Result<Vec<TrashedTorrent>, Error> {
+        self.database.get_torrents_trashed_before(date, limit).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use chrono::{TimeDelta, Utc};
+
+    use crate::config::{Configuration, Settings};
+    use crate::databases::testing::{add_admin, add_category, add_torrent, execute, new_sqlite_database};
+    use crate::models::torrent::TorrentStatus;
+    use crate::services::testing::{services, FakeTrackerApi};
+    use crate::utils::clock::DATETIME_FORMAT;
+
+    fn configuration(tracker_api: &FakeTrackerApi) -> Arc<Configuration> {
+        let mut settings = Settings::default();
+        settings.tracker.api_url = tracker_api.url.clone();
+
+        Arc::new(Configuration::new(settings))
+    }
+
+    fn days_ago(days: i64) -> String {
+        (Utc::now() - TimeDelta::days(days)).format(DATETIME_FORMAT).to_string()
+    }
+
+    #[tokio::test]
+    async fn it_should_purge_the_torrents_deleted_before_the_retention_period() {
+        let tracker_api = FakeTrackerApi::start().await;
+        let (temp_dir, database) = new_sqlite_database().await;
+        let services = services(configuration(&tracker_api), database.clone()).await;
+
+        let admin_id = add_admin(database.as_ref(), "admin").await;
+        let category_id = add_category(database.as_ref(), "trash").await;
+        let (expired_torrent_id, expired_info_hash) =
+            add_torrent(database.as_ref(), admin_id, category_id, &TorrentStatus::Approved).await;
+        let (kept_torrent_id, kept_info_hash) =
+            add_torrent(database.as_ref(), admin_id, category_id, &TorrentStatus::Approved).await;
+
+        for info_hash in [&expired_info_hash, &kept_info_hash] {
+            services
+                .torrent_index
+                .delete_torrent(info_hash, Some(admin_id))
+                .await
+                .unwrap();
+        }
+
+        // The default retention period is 30 days
+        for (torrent_id, date_deleted) in [(expired_torrent_id, days_ago(31)), (kept_torrent_id, days_ago(29))] {
+            execute(
+                &temp_dir,
+                &format!("UPDATE torrust_torrent_trash SET date_deleted = '{date_deleted}' WHERE torrent_id = {torrent_id}"),
+            )
+            .await;
+        }
+
+        assert_eq!(services.trash_service.purge_expired_torrents().await.unwrap(), 1);
+
+        assert!(database.get_trashed_torrent(expired_torrent_id).await.is_err());
+        assert!(database.get_torrent_info_from_id(expired_torrent_id).await.is_err());
+        assert!(database.get_trashed_torrent(kept_torrent_id).await.is_ok());
+        assert_eq!(
+            tracker_api.requests(),
+            vec![format!("DELETE /api/v1/whitelist/{}", expired_info_hash.to_hex_string())]
+        );
+    }
+
+    #[tokio::test]
+    async fn it_should_only_remove_the_torrent_from_the_tracker_whitelist_when_it_is_purged() {
+        let tracker_api = FakeTrackerApi::start().await;
+        let (_temp_dir, database) = new_sqlite_database().await;
+        let services = services(configuration(&tracker_api), database.clone()).await;
+
+        let admin_id = add_admin(database.as_ref(), "admin").await;
+        let category_id = add_category(database.as_ref(), "trash").await;
+        let (torrent_id, info_hash) = add_torrent(database.as_ref(), admin_id, category_id, &TorrentStatus::Approved).await;
+
+        services
+            .torrent_index
+            .delete_torrent(&info_hash, Some(admin_id))
+            .await
+            .unwrap();
+
+        assert!(tracker_api.requests().is_empty());
+
+        services
+            .trash_service
+            .purge_torrent(torrent_id, Some(admin_id))
+            .await
+            .unwrap();
+
+        assert_eq!(
+            tracker_api.requests(),
+            vec![format!("DELETE /api/v1/whitelist/{}", info_hash.to_hex_string())]
+        );
+    }
+}
@@ -0,0 +1,238 @@
+//! HTTP web seeds of the torrents.
+//!
+//! Uploaders can add HTTP web seeds (BEP 19) to their torrents: URLs of
+//! servers hosting the torrent contents, so clients can download them when
+//! there are no peers. They are added to the `url-list` of the downloaded
+//! torrent files and to the magnet links. They are outside the info
+//! dictionary, so they don't change the info-hash.
+//!
+//! For single-file torrents the URL is the file itself. For multi-file
+//! torrents it's the directory containing the torrent directory, ending with
+//! a `/`.
+use std::sync::Arc;
+
+use bittorrent_primitives::info_hash::InfoHash;
+use url::Url;
+
+use super::DbTorrentListingGenerator;
+use crate::databases::database::{Database, Error};
+use crate::errors::ServiceError;
+use crate::models::torrent::{TorrentId, TorrentListing};
+use crate::models::torrent_web_seed::{TorrentWebSeed, WebSeedId};
+use crate::models::user::UserId;
+use crate::services::authorization::{self, ACTION};
+use crate::services::user::Repository;
+use crate::utils::clock::datetime_now;
+
+/// Max number of web seeds of a torrent.
+const MAX_WEB_SEEDS_PER_TORRENT: usize = 10;
+
+/// Max length of the web seed URLs.
+const MAX_WEB_SEED_URL_LENGTH: usize = 512;
+
+pub struct Service {
+    user_repository: Arc<Box<dyn Repository>>,
+    torrent_listing_generator: Arc<DbTorrentListingGenerator>,
+    web_seed_repository: Arc<DbTorrentWebSeedRepository>,
+    authorization_service: Arc<authorization::Service>,
+}
+
+impl Service {
+    #[must_use]
+    pub fn new(
+        user_repository: Arc<Box<dyn Repository>>,
+        torrent_listing_generator: Arc<DbTorrentListingGenerator>,
+        web_seed_repository: Arc<DbTorrentWebSeedRepository>,
+        authorization_service: Arc<authorization::Service>,
+    ) -> Service {
+        Service {
+            user_repository,
+            torrent_listing_generator,
+            web_seed_repository,
+            authorization_service,
+        }
+    }
+
+    /// It adds a web seed to a torrent. Owners can always add web seeds to
+    /// their torrents. Other users need permission to edit the torrent.
+    ///
+    /// # Errors
+    ///
+    /// It returns an error if:
+    ///
+    /// * The user does not have permission to edit the torrent.
+    /// * The torrent does not exist.
+    /// * The URL is not a valid HTTP or HTTPS URL.
+    /// * The torrent has already the web seed or the max number of web seeds.
+    /// * There is a database error.
+    pub async fn add_web_seed(
+        &self,
+        info_hash: &InfoHash,
+        url: &str,
+        maybe_user_id: Option<UserId>,
+    ) -> Result<TorrentWebSeed, ServiceError> {
+        let (user_id, torrent_listing) = self.authorize_edit(info_hash, maybe_user_id).await?;
+
+        let url = parse_web_seed_url(url).ok_or(ServiceError::InvalidWebSeedUrl)?;
+
+        let web_seeds = self.web_seed_repository.get_all(torrent_listing.torrent_id).await?;
+
+        if web_seeds.len() >= MAX_WEB_SEEDS_PER_TORRENT {
+            return Err(ServiceError::TooManyWebSeeds);
+        }
+
+        let web_seed_id = self
+            .web_seed_repository
+            .add(torrent_listing.torrent_id, &url, user_id)
+            .await?;
+
+        Ok(TorrentWebSeed {
+            web_seed_id,
+            torrent_id: torrent_listing.torrent_id,
+            url,
+            date_created: datetime_now(),
+        })
+    }
+
+    /// It returns the web seeds of a torrent.
+    ///
+    /// # Errors
+    ///
+    /// It returns an error if:
+    ///
+    /// * The user does not have permission to get the torrent info.
+    /// * The torrent does not exist.
+    /// * There is a database error.
+    pub async fn get_web_seeds(
+        &self,
+        info_hash: &InfoHash,
+        maybe_user_id: Option<UserId>,
+    ) -> Result<Vec<TorrentWebSeed>, ServiceError> {
+        self.authorization_service
+            .authorize(ACTION::GetTorrentInfo, maybe_user_id)
+            .await?;
+
+        let torrent_listing = self.torrent_listing_generator.one_torrent_by_info_hash(info_hash).await?;
+
+        Ok(self.web_seed_repository.get_all(torrent_listing.torrent_id).await?)
+    }
+
+    /// It removes a web seed from a torrent. Owners can always remove the web
+    /// seeds of their torrents. Other users need permission to edit the
+    /// torrent.
+    ///
+    /// # Errors
+    ///
+    /// It returns an error if:
+    ///
+    /// * The user does not have permission to edit the torrent.
+    /// * The torrent or the web seed do not exist.
+    /// * There is a database error.
+    pub async fn delete_web_seed(
+        &self,
+        info_hash: &InfoHash,
+        web_seed_id: WebSeedId,
+        maybe_user_id: Option<UserId>,
+    ) -> Result<(), ServiceError> {
+        let (_user_id, torrent_listing) = self.authorize_edit(info_hash, maybe_user_id).await?;
+
+        Ok(self
+            .web_seed_repository
+            .delete(torrent_listing.torrent_id, web_seed_id)
+            .await?)
+    }
+
+    async fn authorize_edit(
+        &self,
+        info_hash: &InfoHash,
+        maybe_user_id: Option<UserId>,
+    ) -> Result<(UserId, TorrentListing), ServiceError> {
+        let user_id = maybe_user_id.ok_or(ServiceError::UnauthorizedActionForGuests)?;
+
+        let user = self.user_repository.get_compact(&user_id).await?;
+
+        let torrent_listing = self.torrent_listing_generator.one_torrent_by_info_hash(info_hash).await?;
+
+        if torrent_listing.uploader != user.username {
+            self.authorization_service
+                .authorize_in_category(ACTION::EditTorrentInfo, Some(user_id), torrent_listing.category_id)
+                .await?;
+        }
+
+        Ok((user_id, torrent_listing))
+    }
+}
+
+/// It returns the normalized web seed URL, or `None` if it's not an HTTP or
+/// HTTPS URL with a host.
+fn parse_web_seed_url(url: &str) -> Option<String> {
+    let url = Url::parse(url.trim()).ok()?;
+
+    if !matches!(url.scheme(), "http" | "https") || url.host_str().is_none() || url.fragment().is_some() {
+        return None;
+    }
+
+    let url = url.to_string();
+
+    (url.len() <= MAX_WEB_SEED_URL_LENGTH).then_some(url)
+}
+
+pub struct DbTorrentWebSeedRepository {
+    database: Arc<Box<dyn Database>>,
+}
+
+impl DbTorrentWebSeedRepository {
+    #[must_use]
+    pub fn new(database: Arc<Box<dyn Database>>) -> Self {
+        Self { database }
+    }
+
+    /// It adds a web seed to a torrent.
+    ///
+    /// # Errors
+    ///
+    /// It returns an error if the torrent has already the web seed or there
+    /// is a database error.
+    pub async fn add(&self, torrent_id: TorrentId, url: &str, added_by: UserId) -> Result<WebSeedId, Error> {
+        self.database.insert_torrent_web_seed(torrent_id, url, added_by).await
+    }
+
+    /// It returns the web seeds of a torrent.
+    ///
+    /// # Errors
+    ///
+    /// It returns an error if there is a database error.
+    pub async fn get_all(&self, torrent_id: TorrentId) -> Result<Vec<TorrentWebSeed>, Error> {
+        self.database.get_torrent_web_seeds(torrent_id).await
+    }
+
+    /// It removes a web seed.
+    ///
+    /// # Errors
+    ///
+    /// It returns an error if the web seed does not exist or there is a
+    /// database error.
+    pub async fn delete(&self, torrent_id: TorrentId, web_seed_id: WebSeedId) -> Result<(), Error> {
+        self.database.delete_torrent_web_seed(torrent_id, web_seed_id).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::parse_web_seed_url;
+
+    #[test]
+    fn it_should_only_accept_http_and_https_web_seeds() {
+        assert_eq!(
+            parse_web_seed_url(" https://mirror.example.com/files/ "),
+            Some("https://mirror.example.com/files/".to_string())
+        );
+        assert_eq!(
+            parse_web_seed_url("http://mirror.example.com/file.txt"),
+            Some("http://mirror.example.com/file.txt".to_string())
+        );
+        assert_eq!(parse_web_seed_url("ftp://mirror.example.com/file.txt"), None);
+        assert_eq!(parse_web_seed_url("file:///srv/file.txt"), None);
+        assert_eq!(parse_web_seed_url("mirror.example.com/file.txt"), None);
+    }
+}
@@ -41,6 +41,7 @@ impl CreateTorrentRequest {
             nodes: None,
             encoding: self.encoding.clone(),
             httpseeds: None,
+            url_list: None,
             announce_list: Some(self.announce_urls.clone()),
             creation_date: self.creation_date,
             comment: self.comment.clone(),
@@ -142,6 +143,7 @@ mod tests {
             nodes: None,
             encoding: None,
             httpseeds: None,
+            url_list: None,
         };
 
         assert_eq!(torrent, expected_torrent);
@@ -0,0 +1,418 @@
+//! Torrent rules.
+//!
+//! Operators define rules in the `[torrent_rules]` section to archive, delist
+//! or notify the uploader of the approved torrents matching some conditions:
+//! category, tags, age and seeders. Refer to the
+//! [`TorrentRules`](crate::config::TorrentRules) configuration.
+//!
+//! Rules are evaluated in order by the [`torrent_rules`](crate::console::cronjobs::torrent_rules)
+//! cronjob. Archived and delisted torrents are not in the public listing, so
+//! they are not matched by the following rules. Each rule is applied only
+//! once to each torrent, even if a moderator approves the torrent again.
+//!
+//! Administrators can get a dry-run report with the torrents each rule would
+//! be applied to in the next evaluation.
+use std::collections::HashSet;
+use std::sync::Arc;
+
+use chrono::{NaiveDateTime, TimeDelta, Utc};
+use serde::Serialize;
+use tracing::{error, info, warn};
+
+use super::audit;
+use super::authorization::{self, ACTION};
+use super::bulk_mail::DbEmailQueueRepository;
+use super::tag::DbTagRepository;
+use super::torrent::{DbTorrentListingGenerator, DbTorrentRepository};
+use crate::config::{Configuration, TorrentRule, TorrentRuleAction};
+use crate::databases::database::{Database, Error};
+use crate::errors::ServiceError;
+use crate::models::audit::AuditAction;
+use crate::models::torrent::{TorrentId, TorrentStatus};
+use crate::models::torrent_rule::TorrentRuleCandidate;
+use crate::models::torrent_tag::{TagId, TorrentTag};
+use crate::models::user::UserId;
+use crate::utils::clock::DATETIME_FORMAT;
+
+/// The torrents each rule would be applied to in the next evaluation.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct TorrentRulesReport {
+    /// Whether the torrent rules are enabled or not.
+    pub enabled: bool,
+    pub rules: Vec<RuleReport>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct RuleReport {
+    pub name: String,
+    pub action: TorrentRuleAction,
+    pub torrents: Vec<RuleMatch>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct RuleMatch {
+    pub torrent_id: TorrentId,
+    pub info_hash: String,
+    pub title: String,
+    pub date_uploaded: String,
+    pub seeders: i64,
+}
+
+/// Number of torrents affected by an evaluation.
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct CheckSummary {
+    pub archived: usize,
+    pub delisted: usize,
+    pub notified: usize,
+}
+
+pub struct Service {
+    configuration: Arc<Configuration>,
+    torrent_rule_repository: Arc<DbTorrentRuleRepository>,
+    torrent_repository: Arc<DbTorrentRepository>,
+    torrent_listing_generator: Arc<DbTorrentListingGenerator>,
+    tag_repository: Arc<DbTagRepository>,
+    email_queue_repository: Arc<DbEmailQueueRepository>,
+    audit_service: Arc<audit::Service>,
+    authorization_service: Arc<authorization::Service>,
+}
+
+impl Service {
+    #[allow(clippy::too_many_arguments)]
+    #[must_use]
+    pub fn new(
+        configuration: Arc<Configuration>,
+        torrent_rule_repository: Arc<DbTorrentRuleRepository>,
+        torrent_repository: Arc<DbTorrentRepository>,
+        torrent_listing_generator: Arc<DbTorrentListingGenerator>,
+        tag_repository: Arc<DbTagRepository>,
+        email_queue_repository: Arc<DbEmailQueueRepository>,
+        audit_service: Arc<audit::Service>,
+        authorization_service: Arc<authorization::Service>,
+    ) -> Service {
+        Service {
+            configuration,
+            torrent_rule_repository,
+            torrent_repository,
+            torrent_listing_generator,
+            tag_repository,
+            email_queue_repository,
+            audit_service,
+            authorization_service,
+        }
+    }
+
+    /// It returns the torrents each rule would be applied to, without
+    /// applying the rules.
+    ///
+    /// # Errors
+    ///
+    /// It returns an error if:
+    ///
+    /// * The user does not have permissions to manage the torrent rules.
+    /// * There is a database error.
+    pub async fn get_report(&self, maybe_user_id: Option<UserId>) -> Result<TorrentRulesReport, ServiceError> {
+        self.authorization_service
+            .authorize(ACTION::ManageTorrentRules, maybe_user_id)
+            .await?;
+
+        let Some(rules) = self.rules().await else {
+            return Ok(TorrentRulesReport {
+                enabled: false,
+                rules: vec![],
+            });
+        };
+
+        let plan = self.plan(&rules, Utc::now().naive_utc()).await?;
+
+        Ok(TorrentRulesReport {
+            enabled: true,
+            rules: plan
+                .into_iter()
+                .map(|(rule, candidates)| RuleReport {
+                    name: rule.name.clone(),
+                    action: rule.action,
+                    torrents: candidates
+                        .into_iter()
+                        .map(|candidate| RuleMatch {
+                            torrent_id: candidate.torrent_id,
+                            info_hash: candidate.info_hash,
+                            title: candidate.title,
+                            date_uploaded: candidate.date_uploaded,
+                            seeders: candidate.seeders,
+                        })
+                        .collect(),
+                })
+                .collect(),
+        })
+    }
+
+    /// It applies the rules. Errors applying a rule to one torrent are only
+    /// logged.
+    ///
+    /// # Errors
+    ///
+    /// It returns an error if the matching torrents can't be loaded.
+    pub async fn check(&self) -> Result<CheckSummary, Error> {
+        let mut summary = CheckSummary::default();
+
+        let Some(rules) = self.rules().await else {
+            return Ok(summary);
+        };
+
+        let plan = self.plan(&rules, Utc::now().naive_utc()).await?;
+
+        for (rule, candidates) in plan {
+            for candidate in candidates {
+                if let Err(e) = self.apply(rule, &candidate).await {
+                    error!(target: "torrent_rules", "Failed to apply rule {} to torrent {}: {e:?}", rule.name, candidate.info_hash);
+                    continue;
+                }
+
+                info!(target: "torrent_rules", "Rule {} applied to torrent {}", rule.name, candidate.info_hash);
+
+                match rule.action {
+                    TorrentRuleAction::Archive => summary.archived += 1,
+                    TorrentRuleAction::Delist => summary.delisted += 1,
+                    TorrentRuleAction::Notify => summary.notified += 1,
+                }
+            }
+        }
+
+        if summary.archived + summary.delisted > 0 {
+            self.torrent_listing_generator.invalidate();
+        }
+
+        Ok(summary)
+    }
+
+    /// It returns the torrents each rule matches, in evaluation order.
+    async fn plan<'a>(
+        &self,
+        rules: &'a [TorrentRule],
+        now: NaiveDateTime,
+    ) -> Result<Vec<(&'a TorrentRule, Vec<TorrentRuleCandidate>)>, Error> {
+        let tags = self.tag_repository.get_all().await?;
+
+        // Torrents archived or delisted by a previous rule
+        let mut removed: HashSet<TorrentId> = HashSet::new();
+
+        let mut plan = vec![];
+
+        for rule in rules {
+            let Some(tag_ids) = resolve_tags(&rule.tags, &tags) else {
+                warn!(target: "torrent_rules", "Rule {} has tags that don't exist, it won't match any torrent", rule.name);
+                plan.push((rule, vec![]));
+                continue;
+            };
+
+            let uploaded_before = now - TimeDelta::days(i64::from(rule.min_age_days.unwrap_or(0)));
+
+            let candidates: Vec<TorrentRuleCandidate> = self
+                .torrent_rule_repository
+                .get_candidates(&rule.name, uploaded_before)
+                .await?
+                .into_iter()
+                .filter(|candidate| !removed.contains(&candidate.torrent_id) && matches(rule, &tag_ids, candidate))
+                .collect();
+
+            if rule.action != TorrentRuleAction::Notify {
+                removed.extend(candidates.iter().map(|candidate| candidate.torrent_id));
+            }
+
+            plan.push((rule, candidates));
+        }
+
+        Ok(plan)
+    }
+
+    async fn apply(&self, rule: &TorrentRule, candidate: &TorrentRuleCandidate) -> Result<(), Error> {
+        let status_and_audit_action = match rule.action {
+            TorrentRuleAction::Archive => Some((TorrentStatus::Archived, AuditAction::TorrentArchive)),
+            TorrentRuleAction::Delist => Some((TorrentStatus::Delisted, AuditAction::TorrentDelist)),
+            TorrentRuleAction::Notify => None,
+        };
+
+        if let Some((status, audit_action)) = status_and_audit_action {
+            self.torrent_repository.update_status(&candidate.torrent_id, &status).await?;
+
+            self.audit_service
+                .record(
+                    None,
+                    audit_action,
+                    &candidate.info_hash,
+                    Some(&format!("{} (rule: {})", candidate.title, rule.name)),
+                )
+                .await;
+        } else if !candidate.uploader_email.is_empty() {
+            let site_name = self.configuration.settings.read().await.website.name.clone();
+
+            self.email_queue_repository
+                .enqueue(
+                    &candidate.uploader_email,
+                    &format!("{site_name} - {}", candidate.title),
+                    &notification_body(&site_name, rule, candidate),
+                )
+                .await?;
+        }
+
+        self.torrent_rule_repository
+            .add_application(candidate.torrent_id, &rule.name)
+            .await
+    }
+
+    async fn rules(&self) -> Option<Vec<TorrentRule>> {
+        self.configuration
+            .settings
+            .read()
+            .await
+            .torrent_rules
+            .as_ref()
+            .map(|torrent_rules| torrent_rules.rules.clone())
+    }
+}
+
+/// It returns the IDs of the tags with the names, or `None` if any of them
+/// does not exist. Tag names are not case-sensitive.
+fn resolve_tags(names: &[String], tags: &[TorrentTag]) -> Option<Vec<TagId>> {
+    names
+        .iter()
+        .map(|name| {
+            tags.iter()
+                .find(|tag| tag.name.eq_ignore_ascii_case(name.trim()))
+                .map(|tag| tag.tag_id)
+        })
+        .collect()
+}
+
+/// It checks the conditions of the rule, except the age, which is checked
+/// when the candidates are loaded.
+fn matches(rule: &TorrentRule, tag_ids: &[TagId], candidate: &TorrentRuleCandidate) -> bool {
+    let category_matches = rule.category.as_ref().map_or(true, |category| {
+        candidate
+            .category
+            .as_ref()
+            .is_some_and(|candidate_category| candidate_category.eq_ignore_ascii_case(category.trim()))
+    });
+
+    let candidate_tag_ids = candidate.tag_ids();
+    let tags_match = tag_ids.iter().all(|tag_id| candidate_tag_ids.contains(tag_id));
+
+    let seeders_match = rule.max_seeders.map_or(true, |max_seeders| candidate.seeders <= max_seeders);
+
+    category_matches && tags_match && seeders_match
+}
+
+fn notification_body(site_name: &str, rule: &TorrentRule, candidate: &TorrentRuleCandidate) -> String {
+    let message = rule
+        .message
+        .clone()
+        .unwrap_or_else(|| format!("Your torrent needs your attention. It has {} seeders.", candidate.seeders));
+
+    format!(
+        "Hi {},\n\n{message}\n\nTorrent: {} ({})\n\n-- \n{site_name}\n",
+        candidate.uploader, candidate.title, candidate.info_hash
+    )
+}
+
+pub struct DbTorrentRuleRepository {
+    database: Arc<Box<dyn Database>>,
+}
+
+impl DbTorrentRuleRepository {
+    #[must_use]
+    pub fn new(database: Arc<Box<dyn Database>>) -> Self {
+        Self { database }
+    }
+
+    /// It returns the approved torrents uploaded before the date that the
+    /// rule has not been applied to.
+    ///
+    /// # Errors
+    ///
+    /// It returns an error if there is a database error.
+    pub async fn get_candidates(&self, rule: &str, uploaded_before: NaiveDateTime) -> Result<Vec<TorrentRuleCandidate>, Error> {
+        self.database
+            .get_torrent_rule_candidates(rule, &uploaded_before.format(DATETIME_FORMAT).to_string())
+            .await
+    }
+
+    /// It records that the rule was applied to the torrent.
+    ///
+    /// # Errors
+    ///
+    /// It returns an error if there is a database error.
+    pub async fn add_application(&self, torrent_id: TorrentId, rule: &str) -> Result<(), Error> {
+        self.database.insert_torrent_rule_application(torrent_id, rule).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{matches, resolve_tags};
+    use crate::config::{TorrentRule, TorrentRuleAction};
+    use crate::models::torrent_rule::TorrentRuleCandidate;
+    use crate::models::torrent_tag::TorrentTag;
+
+    fn rule() -> TorrentRule {
+        TorrentRule {
+            name: "archive-dead-torrents".to_string(),
+            category: None,
+            tags: vec![],
+            min_age_days: Some(365),
+            max_seeders: Some(0),
+            action: TorrentRuleAction::Archive,
+            message: None,
+        }
+    }
+
+    fn candidate(category: Option<&str>, tag_ids: Option<&str>, seeders: i64) -> TorrentRuleCandidate {
+        TorrentRuleCandidate {
+            torrent_id: 1,
+            info_hash: "443c7602b4fde83d1154d6d9da48808418b181b6".to_string(),
+            title: "Ubuntu".to_string(),
+            category: category.map(ToString::to_string),
+            tag_ids: tag_ids.map(ToString::to_string),
+            date_uploaded: "2023-01-01 00:00:00".to_string(),
+            seeders,
+            uploader: "alice".to_string(),
+            uploader_email: String::new(),
+        }
+    }
+
+    #[test]
+    fn it_should_match_torrents_meeting_all_the_conditions() {
+        let rule = TorrentRule {
+            category: Some("Software".to_string()),
+            ..rule()
+        };
+
+        assert!(matches(&rule, &[2], &candidate(Some("software"), Some("1,2"), 0)));
+        assert!(!matches(&rule, &[2], &candidate(Some("software"), Some("1,2"), 1)));
+        assert!(!matches(&rule, &[2], &candidate(Some("movies"), Some("1,2"), 0)));
+        assert!(!matches(&rule, &[2], &candidate(None, Some("1,2"), 0)));
+        assert!(!matches(&rule, &[2, 3], &candidate(Some("software"), Some("1,2"), 0)));
+        assert!(!matches(&rule, &[2], &candidate(Some("software"), None, 0)));
+    }
+
+    #[test]
+    fn it_should_not_resolve_the_rule_tags_when_any_of_them_does_not_exist() {
+        let tags = vec![
+            TorrentTag {
+                tag_id: 1,
+                name: "linux".to_string(),
+            },
+            TorrentTag {
+                tag_id: 2,
+                name: "iso".to_string(),
+            },
+        ];
+
+        assert_eq!(
+            resolve_tags(&["ISO".to_string(), "linux".to_string()], &tags),
+            Some(vec![2, 1])
+        );
+        assert_eq!(resolve_tags(&["linux".to_string(), "bsd".to_string()], &tags), None);
+        assert_eq!(resolve_tags(&[], &tags), Some(vec![]));
+    }
+}
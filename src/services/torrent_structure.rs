@@ -0,0 +1,266 @@
+//! Upload policies on the torrent metainfo structure.
+//!
+//! They are configured in the `[torrent.structure]` section:
+//!
+//! ```toml
+//! [torrent.structure]
+//! min_piece_length = 16384
+//! max_piece_length = 67108864
+//! reject_padding_abuse = true
+//! pack_only_categories = ["tv-packs"]
+//! max_path_depth = 16
+//! max_path_length = 1024
+//! ```
+//!
+//! Each rule has its own error so uploaders know what to fix.
+use crate::config::TorrentStructure;
+use crate::errors::ServiceError;
+use crate::models::torrent_file::{Torrent, TorrentFile};
+
+/// Directory used for padding files (BEP 47).
+const PADDING_DIRECTORY: &str = ".pad";
+
+/// File name prefix used for padding files by some clients.
+const LEGACY_PADDING_FILE_PREFIX: &str = "_____padding_file_";
+
+/// It validates the torrent against the structure policies.
+///
+/// # Errors
+///
+/// It returns the error for the first rule the torrent does not comply with.
+pub fn validate(torrent: &Torrent, category_name: &str, policy: &TorrentStructure) -> Result<(), ServiceError> {
+    validate_piece_length(torrent, policy)?;
+
+    if torrent.is_a_single_file_torrent() {
+        if policy.pack_only_categories.iter().any(|category| category == category_name) {
+            return Err(ServiceError::SingleFileTorrentNotAllowedInCategory);
+        }
+
+        return validate_path(&[torrent.info.name.as_str()], policy);
+    }
+
+    let files = torrent.info.files.as_deref().unwrap_or_default();
+
+    if policy.reject_padding_abuse {
+        validate_padding_files(files, torrent.info.piece_length)?;
+    }
+
+    for file in files {
+        let mut path = vec![torrent.info.name.as_str()];
+        path.extend(file.path.iter().map(String::as_str));
+
+        validate_path(&path, policy)?;
+    }
+
+    Ok(())
+}
+
+fn validate_piece_length(torrent: &Torrent, policy: &TorrentStructure) -> Result<(), ServiceError> {
+    let piece_length = u64::try_from(torrent.info.piece_length).map_err(|_| ServiceError::TorrentPieceLengthNotAllowed)?;
+
+    if piece_length < policy.min_piece_length || piece_length > policy.max_piece_length {
+        return Err(ServiceError::TorrentPieceLengthNotAllowed);
+    }
+
+    Ok(())
+}
+
+/// Padding files are only used to align the next file to a piece boundary,
+/// so they must be shorter than a piece, they can't be the first file and
+/// they can't follow another padding file.
+fn validate_padding_files(files: &[TorrentFile], piece_length: i64) -> Result<(), ServiceError> {
+    let mut previous_is_padding = true;
+
+    for file in files {
+        let is_padding = is_padding_file(file);
+
+        if is_padding && (previous_is_padding || file.length >= piece_length) {
+            return Err(ServiceError::TorrentPaddingFilesNotAllowed);
+        }
+
+        previous_is_padding = is_padding;
+    }
+
+    Ok(())
+}
+
+pub(crate) fn is_padding_file(file: &TorrentFile) -> bool {
+    match file.path.as_slice() {
+        [directory, _] if directory == PADDING_DIRECTORY => true,
+        [.., name] => name.starts_with(LEGACY_PADDING_FILE_PREFIX),
+        [] => false,
+    }
+}
+
+fn validate_path(path: &[&str], policy: &TorrentStructure) -> Result<(), ServiceError> {
+    if path.len() > policy.max_path_depth {
+        return Err(ServiceError::TorrentPathTooDeep);
+    }
+
+    // Components plus separators
+    let length = path.iter().map(|component| component.len()).sum::<usize>() + path.len() - 1;
+
+    if length > policy.max_path_length {
+        return Err(ServiceError::TorrentPathTooLong);
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::validate;
+    use crate::config::TorrentStructure;
+    use crate::errors::ServiceError;
+    use crate::models::torrent_file::{Torrent, TorrentFile, TorrentInfoDictionary};
+
+    const PIECE_LENGTH: i64 = 16 * 1024;
+
+    fn single_file_torrent(piece_length: i64) -> Torrent {
+        torrent(TorrentInfoDictionary {
+            name: "file.txt".to_string(),
+            pieces: None,
+            piece_length,
+            md5sum: None,
+            length: Some(1024),
+            files: None,
+            private: None,
+            path: None,
+            root_hash: None,
+            source: None,
+        })
+    }
+
+    fn multi_file_torrent(files: &[(&[&str], i64)]) -> Torrent {
+        torrent(TorrentInfoDictionary {
+            name: "pack".to_string(),
+            pieces: None,
+            piece_length: PIECE_LENGTH,
+            md5sum: None,
+            length: None,
+            files: Some(
+                files
+                    .iter()
+                    .map(|(path, length)| TorrentFile {
+                        path: path.iter().map(ToString::to_string).collect(),
+                        length: *length,
+                        md5sum: None,
+                    })
+                    .collect(),
+            ),
+            private: None,
+            path: None,
+            root_hash: None,
+            source: None,
+        })
+    }
+
+    fn torrent(info: TorrentInfoDictionary) -> Torrent {
+        Torrent {
+            info,
+            announce: None,
+            nodes: None,
+            encoding: None,
+            httpseeds: None,
+            url_list: None,
+            announce_list: None,
+            creation_date: None,
+            comment: None,
+            created_by: None,
+        }
+    }
+
+    #[test]
+    fn it_should_accept_torrents_complying_with_the_default_policies() {
+        let policy = TorrentStructure::default();
+
+        assert_eq!(validate(&single_file_torrent(PIECE_LENGTH), "software", &policy), Ok(()));
+        assert_eq!(
+            validate(
+                &multi_file_torrent(&[(&["a.mkv"], 100), (&[".pad", "16284"], 16284), (&["b.mkv"], 100)]),
+                "software",
+                &policy
+            ),
+            Ok(())
+        );
+    }
+
+    #[test]
+    fn it_should_reject_piece_lengths_outside_the_allowed_range() {
+        let policy = TorrentStructure::default();
+
+        assert_eq!(
+            validate(&single_file_torrent(8 * 1024), "software", &policy),
+            Err(ServiceError::TorrentPieceLengthNotAllowed)
+        );
+        assert_eq!(
+            validate(&single_file_torrent(128 * 1024 * 1024), "software", &policy),
+            Err(ServiceError::TorrentPieceLengthNotAllowed)
+        );
+    }
+
+    #[test]
+    fn it_should_reject_padding_files_not_used_for_alignment() {
+        let policy = TorrentStructure::default();
+
+        // Longer than a piece
+        assert_eq!(
+            validate(
+                &multi_file_torrent(&[(&["a.mkv"], 100), (&[".pad", "big"], PIECE_LENGTH)]),
+                "software",
+                &policy
+            ),
+            Err(ServiceError::TorrentPaddingFilesNotAllowed)
+        );
+
+        // Consecutive padding files
+        assert_eq!(
+            validate(
+                &multi_file_torrent(&[
+                    (&["a.mkv"], 100),
+                    (&["_____padding_file_0_"], 10),
+                    (&["_____padding_file_1_"], 10)
+                ]),
+                "software",
+                &policy
+            ),
+            Err(ServiceError::TorrentPaddingFilesNotAllowed)
+        );
+    }
+
+    #[test]
+    fn it_should_reject_single_file_torrents_in_pack_only_categories() {
+        let policy = TorrentStructure {
+            pack_only_categories: vec!["tv-packs".to_string()],
+            ..Default::default()
+        };
+
+        assert_eq!(
+            validate(&single_file_torrent(PIECE_LENGTH), "tv-packs", &policy),
+            Err(ServiceError::SingleFileTorrentNotAllowedInCategory)
+        );
+        assert_eq!(validate(&single_file_torrent(PIECE_LENGTH), "software", &policy), Ok(()));
+    }
+
+    #[test]
+    fn it_should_reject_too_deep_or_too_long_paths() {
+        let policy = TorrentStructure {
+            max_path_depth: 3,
+            max_path_length: 20,
+            ..Default::default()
+        };
+
+        assert_eq!(
+            validate(&multi_file_torrent(&[(&["a", "b", "c.txt"], 100)]), "software", &policy),
+            Err(ServiceError::TorrentPathTooDeep)
+        );
+        assert_eq!(
+            validate(
+                &multi_file_torrent(&[(&["a-very-long-file-name.txt"], 100)]),
+                "software",
+                &policy
+            ),
+            Err(ServiceError::TorrentPathTooLong)
+        );
+    }
+}
@@ -0,0 +1,246 @@
+//! Trending tags.
+//!
+//! When the `[trending_tags]` configuration section is present, the index
+//! records the `.torrent` downloads of each torrent per day, and a background
+//! task ranks the tags by the growth of their activity, approved uploads plus
+//! downloads, in the last window compared to the previous one:
+//!
+//! ```toml
+//! [trending_tags]
+//! window_days = 7
+//! refresh_interval_seconds = 3600
+//! max_tags = 20
+//! ```
+//!
+//! Only tags with a positive growth are included. Requests are served from
+//! the last ranking without querying the database.
+use std::collections::HashMap;
+use std::sync::{Arc, RwLock};
+
+use chrono::{Days, NaiveDate, Utc};
+use tracing::error;
+
+use super::authorization::{self, ACTION};
+use crate::config::{Configuration, TrendingTags};
+use crate::databases::database::{Database, Error, TagActivity};
+use crate::errors::ServiceError;
+use crate::models::torrent_tag::TrendingTag;
+use crate::models::user::UserId;
+
+pub struct Service {
+    config: Option<TrendingTags>,
+    tag_activity_repository: Arc<DbTagActivityRepository>,
+    authorization_service: Arc<authorization::Service>,
+    ranking: RwLock<Vec<TrendingTag>>,
+}
+
+impl Service {
+    pub async fn new(
+        configuration: Arc<Configuration>,
+        tag_activity_repository: Arc<DbTagActivityRepository>,
+        authorization_service: Arc<authorization::Service>,
+    ) -> Service {
+        let config = configuration.settings.read().await.trending_tags.clone();
+
+        Service {
+            config,
+            tag_activity_repository,
+            authorization_service,
+            ranking: RwLock::new(Vec::new()),
+        }
+    }
+
+    /// Whether the trending tags are enabled or not.
+    #[must_use]
+    pub fn is_enabled(&self) -> bool {
+        self.config.is_some()
+    }
+
+    /// It counts a `.torrent` download for the trending tags. Errors are only
+    /// logged.
+    pub async fn record_download(&self, info_hash: &str) {
+        if !self.is_enabled() {
+            return;
+        }
+
+        if let Err(err) = self.tag_activity_repository.increment_downloads(info_hash).await {
+            error!(target: "trending tags", "Failed to record download of torrent {info_hash}: {err:?}");
+        }
+    }
+
+    /// It returns the last ranking of trending tags.
+    ///
+    /// # Errors
+    ///
+    /// It returns an error if:
+    ///
+    /// * The trending tags are not enabled.
+    /// * The user does not have permission to get the tags.
+    ///
+    /// # Panics
+    ///
+    /// Will panic if the ranking lock is poisoned.
+    pub async fn get_trending_tags(&self, maybe_user_id: Option<UserId>) -> Result<Vec<TrendingTag>, ServiceError> {
+        if !self.is_enabled() {
+            return Err(ServiceError::TrendingTagsDisabled);
+        }
+
+        self.authorization_service.authorize(ACTION::GetTags, maybe_user_id).await?;
+
+        Ok(self
+            .ranking
+            .read()
+            .expect("trending tags lock should not be poisoned")
+            .clone())
+    }
+
+    /// It ranks the tags again. The last window ends today, so the activity
+    /// of the current day is included.
+    ///
+    /// The previous ranking is kept if the activity can't be loaded.
+    ///
+    /// # Panics
+    ///
+    /// Will panic if the ranking lock is poisoned.
+    pub async fn refresh(&self) {
+        let Some(config) = &self.config else {
+            return;
+        };
+
+        let window = Days::new(u64::from(config.window_days.max(1)));
+
+        let tomorrow = Utc::now().date_naive() + Days::new(1);
+        let window_start = tomorrow - window;
+        let previous_window_start = window_start - window;
+
+        let day = |date: NaiveDate| date.format("%Y-%m-%d").to_string();
+
+        let current = self.tag_activity_repository.get(&day(window_start), &day(tomorrow)).await;
+
+        let previous = self
+            .tag_activity_repository
+            .get(&day(previous_window_start), &day(window_start))
+            .await;
+
+        match (current, previous) {
+            (Ok(current), Ok(previous)) => {
+                let ranking = rank(current, &previous, config.max_tags);
+
+                *self.ranking.write().expect("trending tags lock should not be poisoned") = ranking;
+            }
+            (Err(err), _) | (_, Err(err)) => {
+                error!(target: "trending tags", "Failed to get the tag activity: {err:?}");
+            }
+        }
+    }
+}
+
+/// It ranks the tags by the growth of their activity. Ties are broken by the
+/// activity in the current window and then by the tag name.
+fn rank(current: Vec<TagActivity>, previous: &[TagActivity], max_tags: usize) -> Vec<TrendingTag> {
+    let previous_activity: HashMap<_, _> = previous
+        .iter()
+        .map(|activity| (activity.tag_id, activity.uploads + activity.downloads))
+        .collect();
+
+    let mut ranking: Vec<TrendingTag> = current
+        .into_iter()
+        .map(|activity| TrendingTag {
+            growth: activity.uploads + activity.downloads - previous_activity.get(&activity.tag_id).copied().unwrap_or_default(),
+            tag_id: activity.tag_id,
+            name: activity.name,
+            uploads: activity.uploads,
+            downloads: activity.downloads,
+        })
+        .filter(|tag| tag.growth > 0)
+        .collect();
+
+    ranking.sort_by(|a, b| {
+        b.growth
+            .cmp(&a.growth)
+            .then((b.uploads + b.downloads).cmp(&(a.uploads + a.downloads)))
+            .then(a.name.cmp(&b.name))
+    });
+
+    ranking.truncate(max_tags);
+
+    ranking
+}
+
+pub struct DbTagActivityRepository {
+    database: Arc<Box<dyn Database>>,
+}
+
+impl DbTagActivityRepository {
+    #[must_use]
+    pub fn new(database: Arc<Box<dyn Database>>) -> Self {
+        Self { database }
+    }
+
+    /// It increments today's downloads of a torrent.
+    ///
+    /// # Errors
+    ///
+    /// It returns an error if there is a database error.
+    pub async fn increment_downloads(&self, info_hash: &str) -> Result<(), Error> {
+        self.database
+            .increment_torrent_download_stats(info_hash, &Utc::now().format("%Y-%m-%d").to_string())
+            .await
+    }
+
+    /// It returns the activity of each tag from the `since` day (included)
+    /// to the `until` day (excluded).
+    ///
+    /// # Errors
+    ///
+    /// It returns an error if there is a database error.
+    pub async fn get(&self, since: &str, until: &str) -> Result<Vec<TagActivity>, Error> {
+        self.database.get_tag_activity(since, until).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::rank;
+    use crate::databases::database::TagActivity;
+
+    fn activity(tag_id: i64, name: &str, uploads: i64, downloads: i64) -> TagActivity {
+        TagActivity {
+            tag_id,
+            name: name.to_string(),
+            uploads,
+            downloads,
+        }
+    }
+
+    #[test]
+    fn it_should_rank_the_tags_by_the_growth_of_their_activity() {
+        let current = vec![
+            activity(1, "anime", 10, 100),
+            activity(2, "linux", 2, 20),
+            activity(3, "music", 1, 5),
+        ];
+        let previous = vec![activity(1, "anime", 10, 95), activity(2, "linux", 0, 0)];
+
+        let ranking = rank(current, &previous, 10);
+
+        assert_eq!(
+            ranking.iter().map(|tag| (tag.name.as_str(), tag.growth)).collect::<Vec<_>>(),
+            vec![("linux", 22), ("music", 6), ("anime", 5)]
+        );
+    }
+
+    #[test]
+    fn it_should_only_include_growing_tags_up_to_the_max_number_of_tags() {
+        let current = vec![
+            activity(1, "anime", 1, 1),
+            activity(2, "linux", 0, 3),
+            activity(3, "music", 0, 1),
+        ];
+        let previous = vec![activity(3, "music", 0, 4)];
+
+        let ranking = rank(current, &previous, 1);
+
+        assert_eq!(ranking.iter().map(|tag| tag.name.as_str()).collect::<Vec<_>>(), vec!["linux"]);
+    }
+}
@@ -0,0 +1,184 @@
+//! Content policies of the uploaded torrents.
+//!
+//! They are configured in the `[upload_policy]` section:
+//!
+//! ```toml
+//! [upload_policy]
+//! banned_extensions = ["exe", "scr", "lnk"]
+//! min_total_size = 1048576
+//! max_total_size = 107374182400
+//! min_piece_length = 262144
+//! max_piece_length = 16777216
+//! ```
+//!
+//! Unlike the [`torrent_structure`](crate::services::torrent_structure)
+//! policies, which protect the index and the clients from malformed
+//! metainfo, these policies are about what the site accepts. Each rule has
+//! its own error so uploaders know what to fix.
+use crate::config::UploadPolicy;
+use crate::errors::ServiceError;
+use crate::models::torrent_file::Torrent;
+use crate::services::torrent_structure::is_padding_file;
+
+/// It validates the torrent against the content policies.
+///
+/// # Errors
+///
+/// It returns the error for the first rule the torrent does not comply with.
+pub fn validate(torrent: &Torrent, policy: &UploadPolicy) -> Result<(), ServiceError> {
+    let total_size = u64::try_from(torrent.file_size()).unwrap_or_default();
+
+    if policy.min_total_size.is_some_and(|min| total_size < min) {
+        return Err(ServiceError::TorrentTooSmall);
+    }
+
+    if policy.max_total_size.is_some_and(|max| total_size > max) {
+        return Err(ServiceError::TorrentTooLarge);
+    }
+
+    let piece_length = u64::try_from(torrent.info.piece_length).unwrap_or_default();
+
+    if policy.min_piece_length.is_some_and(|min| piece_length < min) {
+        return Err(ServiceError::TorrentPieceLengthTooSmall);
+    }
+
+    if policy.max_piece_length.is_some_and(|max| piece_length > max) {
+        return Err(ServiceError::TorrentPieceLengthTooLarge);
+    }
+
+    if policy.banned_extensions.is_empty() {
+        return Ok(());
+    }
+
+    let has_banned_files = match &torrent.info.files {
+        None => is_banned(&torrent.info.name, &policy.banned_extensions),
+        Some(files) => files
+            .iter()
+            .filter(|file| !is_padding_file(file))
+            .filter_map(|file| file.path.last())
+            .any(|file_name| is_banned(file_name, &policy.banned_extensions)),
+    };
+
+    if has_banned_files {
+        return Err(ServiceError::TorrentFileExtensionNotAllowed);
+    }
+
+    Ok(())
+}
+
+fn is_banned(file_name: &str, banned_extensions: &[String]) -> bool {
+    let Some((_, extension)) = file_name.rsplit_once('.') else {
+        return false;
+    };
+
+    banned_extensions
+        .iter()
+        .any(|banned| banned.trim().trim_start_matches('.').eq_ignore_ascii_case(extension))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::validate;
+    use crate::config::UploadPolicy;
+    use crate::errors::ServiceError;
+    use crate::models::torrent_file::{Torrent, TorrentFile, TorrentInfoDictionary};
+
+    const PIECE_LENGTH: i64 = 256 * 1024;
+
+    fn torrent(files: &[(&str, i64)], piece_length: i64) -> Torrent {
+        Torrent {
+            info: TorrentInfoDictionary {
+                name: "pack".to_string(),
+                pieces: None,
+                piece_length,
+                md5sum: None,
+                length: None,
+                files: Some(
+                    files
+                        .iter()
+                        .map(|(path, length)| TorrentFile {
+                            path: path.split('/').map(ToString::to_string).collect(),
+                            length: *length,
+                            md5sum: None,
+                        })
+                        .collect(),
+                ),
+                private: None,
+                path: None,
+                root_hash: None,
+                source: None,
+            },
+            announce: None,
+            nodes: None,
+            encoding: None,
+            httpseeds: None,
+            url_list: None,
+            announce_list: None,
+            creation_date: None,
+            comment: None,
+            created_by: None,
+        }
+    }
+
+    #[test]
+    fn it_should_reject_torrents_with_files_with_a_banned_extension() {
+        let policy = UploadPolicy {
+            banned_extensions: vec!["exe".to_string(), ".LNK".to_string()],
+            ..Default::default()
+        };
+
+        assert_eq!(
+            validate(
+                &torrent(&[("movie.mkv", 100), ("bonus/setup.EXE", 10)], PIECE_LENGTH),
+                &policy
+            ),
+            Err(ServiceError::TorrentFileExtensionNotAllowed)
+        );
+        assert_eq!(
+            validate(&torrent(&[("movie.lnk", 100)], PIECE_LENGTH), &policy),
+            Err(ServiceError::TorrentFileExtensionNotAllowed)
+        );
+        assert_eq!(
+            validate(&torrent(&[("movie.mkv", 100), ("exe", 10)], PIECE_LENGTH), &policy),
+            Ok(())
+        );
+    }
+
+    #[test]
+    fn it_should_reject_torrents_outside_the_total_size_bounds() {
+        let policy = UploadPolicy {
+            min_total_size: Some(100),
+            max_total_size: Some(1000),
+            ..Default::default()
+        };
+
+        assert_eq!(
+            validate(&torrent(&[("a.mkv", 50), ("b.mkv", 49)], PIECE_LENGTH), &policy),
+            Err(ServiceError::TorrentTooSmall)
+        );
+        assert_eq!(
+            validate(&torrent(&[("a.mkv", 1001)], PIECE_LENGTH), &policy),
+            Err(ServiceError::TorrentTooLarge)
+        );
+        assert_eq!(validate(&torrent(&[("a.mkv", 500)], PIECE_LENGTH), &policy), Ok(()));
+    }
+
+    #[test]
+    fn it_should_reject_piece_lengths_outside_the_bounds() {
+        let policy = UploadPolicy {
+            min_piece_length: Some(256 * 1024),
+            max_piece_length: Some(1024 * 1024),
+            ..Default::default()
+        };
+
+        assert_eq!(
+            validate(&torrent(&[("a.mkv", 100)], PIECE_LENGTH / 2), &policy),
+            Err(ServiceError::TorrentPieceLengthTooSmall)
+        );
+        assert_eq!(
+            validate(&torrent(&[("a.mkv", 100)], PIECE_LENGTH * 8), &policy),
+            Err(ServiceError::TorrentPieceLengthTooLarge)
+        );
+        assert_eq!(validate(&torrent(&[("a.mkv", 100)], PIECE_LENGTH), &policy), Ok(()));
+    }
+}
@@ -1,26 +1,37 @@
 //! User services.
-use std::sync::Arc;
+use std::collections::HashMap;
+use std::sync::{Arc, RwLock};
+use std::time::{Duration, Instant};
 
-use argon2::password_hash::SaltString;
-use argon2::{Argon2, PasswordHasher};
 use async_trait::async_trait;
 use jsonwebtoken::{decode, Algorithm, DecodingKey, Validation};
 #[cfg(test)]
 use mockall::automock;
-use pbkdf2::password_hash::rand_core::OsRng;
+use serde::{Deserialize, Serialize};
 use tracing::{debug, info};
+use url::Url;
+use utoipa::{IntoParams, ToSchema};
 
+use super::audit;
 use super::authentication::DbUserAuthenticationRepository;
 use super::authorization::{self, ACTION};
+use super::torrent::{DbTorrentListingGenerator, ListingSpecification};
+use super::{ratio, reputation};
 use crate::config::{Configuration, PasswordConstraints};
-use crate::databases::database::{Database, Error};
+use crate::databases::database::{Database, Error, Sorting};
 use crate::errors::ServiceError;
 use crate::mailer;
-use crate::mailer::VerifyClaims;
-use crate::models::user::{UserCompact, UserId, UserProfile, Username};
-use crate::services::authentication::verify_password;
+use crate::mailer::{ChangeEmailClaims, VerifyClaims, EMAIL_CHANGE_ISSUER, EMAIL_VERIFICATION_ISSUER};
+use crate::models::audit::AuditAction;
+use crate::models::response::TorrentsResponse;
+use crate::models::torrent::{ListingFilter, TorrentId, TorrentStatus};
+use crate::models::transfer_stats::UserTransferStats;
+use crate::models::user::{PublicProfile, UserCompact, UserId, UserProfile, Username};
+use crate::services::authentication::{hash_password, verify_password};
 use crate::utils::validation::validate_email_address;
-use crate::web::api::server::v1::contexts::user::forms::{ChangePasswordForm, RegistrationForm};
+use crate::web::api::server::v1::contexts::user::forms::{
+    ChangeEmailForm, ChangePasswordForm, LoginForm, RegistrationForm, UpdateProfileForm,
+};
 
 /// Since user email could be optional, we need a way to represent "no email"
 /// in the database. This function returns the string that should be used for
@@ -29,6 +40,34 @@ fn no_email() -> String {
     String::new()
 }
 
+/// Max number of characters in the user's bio.
+pub const MAX_BIO_LENGTH: usize = 1000;
+
+/// User request to get a page of the torrents uploaded by a user.
+#[derive(Debug, Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
+pub struct ListingRequest {
+    pub page_size: Option<u8>,
+    pub page: Option<u32>,
+}
+
+/// The public profile page of a user: the profile and a page of the torrents
+/// uploaded by the user, from the newest to the oldest.
+#[derive(Debug, Serialize, ToSchema)]
+pub struct ProfilePage {
+    pub profile: PublicProfile,
+    /// Whether the user is a trusted uploader.
+    pub trusted: bool,
+    /// The bytes uploaded and downloaded by the user and their upload
+    /// ratio. It's only included in private mode, when the ratio accounting
+    /// is enabled.
+    pub transfer: Option<UserTransferStats>,
+    /// The path to get the avatar through the index image proxy, which
+    /// caches the image.
+    pub avatar_proxy_url: Option<String>,
+    pub torrents: TorrentsResponse,
+}
+
 pub struct RegistrationService {
     configuration: Arc<Configuration>,
     mailer: Arc<mailer::Service>,
@@ -173,7 +212,7 @@ impl RegistrationService {
             &Validation::new(Algorithm::HS256),
         ) {
             Ok(token_data) => {
-                if !token_data.claims.iss.eq("email-verification") {
+                if !token_data.claims.iss.eq(EMAIL_VERIFICATION_ISSUER) {
                     return Ok(false);
                 }
 
@@ -196,22 +235,286 @@ impl RegistrationService {
 
 pub struct ProfileService {
     configuration: Arc<Configuration>,
+    mailer: Arc<mailer::Service>,
+    user_repository: Arc<Box<dyn Repository>>,
     user_authentication_repository: Arc<DbUserAuthenticationRepository>,
+    user_profile_repository: Arc<DbUserProfileRepository>,
+    torrent_listing_generator: Arc<DbTorrentListingGenerator>,
     authorization_service: Arc<authorization::Service>,
+    reputation_service: Arc<reputation::Service>,
+    ratio_service: Arc<ratio::Service>,
 }
 
 impl ProfileService {
+    #[allow(clippy::too_many_arguments)]
     #[must_use]
     pub fn new(
         configuration: Arc<Configuration>,
-        user_repository: Arc<DbUserAuthenticationRepository>,
+        mailer: Arc<mailer::Service>,
+        user_repository: Arc<Box<dyn Repository>>,
+        user_authentication_repository: Arc<DbUserAuthenticationRepository>,
+        user_profile_repository: Arc<DbUserProfileRepository>,
+        torrent_listing_generator: Arc<DbTorrentListingGenerator>,
         authorization_service: Arc<authorization::Service>,
+        reputation_service: Arc<reputation::Service>,
+        ratio_service: Arc<ratio::Service>,
     ) -> Self {
         Self {
             configuration,
-            user_authentication_repository: user_repository,
+            mailer,
+            user_repository,
+            user_authentication_repository,
+            user_profile_repository,
+            torrent_listing_generator,
             authorization_service,
+            reputation_service,
+            ratio_service,
+        }
+    }
+
+    /// It sends the email verification link again.
+    ///
+    /// Users with an unverified email can't log in when the verification is
+    /// required, so they are identified with their username and password.
+    ///
+    /// # Errors
+    ///
+    /// It returns an error if:
+    ///
+    /// * The username or the password are wrong.
+    /// * The user does not have an email or it's already verified.
+    /// * Unable to send the email.
+    pub async fn resend_verification_email(&self, login_form: &LoginForm, api_base_url: &str) -> Result<(), ServiceError> {
+        let user_profile = self
+            .user_profile_repository
+            .get_user_profile_from_username(&login_form.login)
+            .await
+            .map_err(|_| ServiceError::WrongPasswordOrUsername)?;
+
+        let user_authentication = self
+            .user_authentication_repository
+            .get_user_authentication_from_id(&user_profile.user_id)
+            .await
+            .map_err(|_| ServiceError::InternalServerError)?;
+
+        verify_password(login_form.password.as_bytes(), &user_authentication)
+            .map_err(|_| ServiceError::WrongPasswordOrUsername)?;
+
+        if user_profile.email.is_empty() {
+            return Err(ServiceError::EmailMissing);
         }
+
+        if user_profile.email_verified {
+            return Err(ServiceError::EmailAlreadyVerified);
+        }
+
+        info!("resending the verification email for user ID: {}", user_profile.user_id);
+
+        self.mailer
+            .send_verification_mail(
+                &user_profile.email,
+                &user_profile.username,
+                user_profile.user_id,
+                api_base_url,
+            )
+            .await
+    }
+
+    /// It starts the change of the user's email. The email is only changed
+    /// when the user opens the confirmation link sent to the new address.
+    ///
+    /// # Errors
+    ///
+    /// It returns an error if:
+    ///
+    /// * The user is not logged in or does not have permissions to change
+    ///   the email.
+    /// * The current password is wrong.
+    /// * The new email is not valid.
+    /// * Unable to send the confirmation email.
+    pub async fn request_email_change(
+        &self,
+        maybe_user_id: Option<UserId>,
+        change_email_form: &ChangeEmailForm,
+        api_base_url: &str,
+    ) -> Result<(), ServiceError> {
+        let Some(user_id) = maybe_user_id else {
+            return Err(ServiceError::UnauthorizedActionForGuests);
+        };
+
+        self.authorization_service
+            .authorize(ACTION::ChangeEmail, maybe_user_id)
+            .await?;
+
+        let user_authentication = self
+            .user_authentication_repository
+            .get_user_authentication_from_id(&user_id)
+            .await?;
+
+        verify_password(change_email_form.current_password.as_bytes(), &user_authentication)?;
+
+        let email = change_email_form.email.trim();
+
+        if !validate_email_address(email) {
+            return Err(ServiceError::EmailInvalid);
+        }
+
+        let user = self.user_repository.get_compact(&user_id).await?;
+
+        info!("sending the email change confirmation for user ID: {}", user_id);
+
+        self.mailer
+            .send_email_change_mail(email, &user.username, user_id, api_base_url)
+            .await
+    }
+
+    /// It changes the user's email via the token sent to the new address.
+    /// The new email is verified.
+    ///
+    /// It returns `false` if the token is not valid or it has expired.
+    ///
+    /// # Errors
+    ///
+    /// It returns an error if the email is used by another user or there is a
+    /// database error.
+    pub async fn confirm_email_change(&self, token: &str) -> Result<bool, ServiceError> {
+        let settings = self.configuration.settings.read().await;
+
+        let claims = match decode::<ChangeEmailClaims>(
+            token,
+            &DecodingKey::from_secret(settings.auth.user_claim_token_pepper.as_bytes()),
+            &Validation::new(Algorithm::HS256),
+        ) {
+            Ok(token_data) if token_data.claims.iss == EMAIL_CHANGE_ISSUER => token_data.claims,
+            _ => return Ok(false),
+        };
+
+        drop(settings);
+
+        self.user_profile_repository.change_email(claims.sub, &claims.email).await?;
+
+        info!("email changed for user ID: {}", claims.sub);
+
+        Ok(true)
+    }
+
+    /// It returns the public profile page of a user.
+    ///
+    /// # Errors
+    ///
+    /// It returns an error if:
+    ///
+    /// * The user does not have permissions to see user profiles.
+    /// * The user does not exist.
+    /// * There is a database error.
+    pub async fn get_profile_page(
+        &self,
+        username: &str,
+        request: &ListingRequest,
+        maybe_user_id: Option<UserId>,
+    ) -> Result<ProfilePage, ServiceError> {
+        self.authorization_service
+            .authorize(ACTION::GetUserProfile, maybe_user_id)
+            .await?;
+
+        let profile = self.user_profile_repository.get_public_profile(username).await?;
+
+        let settings = self.configuration.settings.read().await;
+        let default_torrent_page_size = settings.api.default_torrent_page_size;
+        let max_torrent_page_size = settings.api.max_torrent_page_size;
+        drop(settings);
+
+        let page_size = request
+            .page_size
+            .unwrap_or(default_torrent_page_size)
+            .min(max_torrent_page_size);
+        let offset = u64::from(request.page.unwrap_or(0)) * u64::from(page_size);
+
+        let user_id = self
+            .user_profile_repository
+            .get_user_profile_from_username(username)
+            .await?
+            .user_id;
+
+        let torrent_ids = self.user_profile_repository.get_uploaded_torrent_ids(user_id).await?;
+
+        let specification = ListingSpecification {
+            search: None,
+            categories: None,
+            tags: None,
+            torrent_ids: Some(torrent_ids),
+            status: TorrentStatus::Approved,
+            filter: ListingFilter::default(),
+            sort: Sorting::Relevance,
+            then_sort: None,
+            offset,
+            page_size,
+        };
+
+        let torrents = self.torrent_listing_generator.generate_listing(&specification).await?;
+
+        let trusted = self.reputation_service.is_trusted(user_id).await?;
+
+        let transfer = self.ratio_service.get_transfer_stats(user_id).await?;
+
+        Ok(ProfilePage {
+            trusted,
+            transfer,
+            avatar_proxy_url: profile.avatar.as_deref().map(avatar_proxy_url),
+            profile,
+            torrents,
+        })
+    }
+
+    /// It updates the bio and the avatar of the logged-in user. Fields
+    /// missing in the form are not changed and empty fields are cleared.
+    ///
+    /// Avatars uploaded to the index storage are set with the
+    /// [`image_upload`](crate::services::image_upload) service. This method
+    /// can also set an external avatar URL.
+    ///
+    /// # Errors
+    ///
+    /// It returns an error if:
+    ///
+    /// * The user is not logged in or it's not the owner of the profile.
+    /// * The bio is too long.
+    /// * The avatar is not an HTTP or HTTPS URL.
+    /// * There is a database error.
+    pub async fn update_profile(
+        &self,
+        username: &str,
+        form: &UpdateProfileForm,
+        maybe_user_id: Option<UserId>,
+    ) -> Result<PublicProfile, ServiceError> {
+        let Some(user_id) = maybe_user_id else {
+            return Err(ServiceError::UnauthorizedActionForGuests);
+        };
+
+        self.authorization_service
+            .authorize(ACTION::EditUserProfile, maybe_user_id)
+            .await?;
+
+        let user_profile = self.user_profile_repository.get_user_profile_from_username(username).await?;
+
+        if user_profile.user_id != user_id {
+            return Err(ServiceError::UnauthorizedAction);
+        }
+
+        let bio = non_empty(form.bio.as_deref().unwrap_or(&user_profile.bio));
+        let avatar = non_empty(form.avatar.as_deref().unwrap_or(&user_profile.avatar));
+
+        if bio.is_some_and(|bio| bio.chars().count() > MAX_BIO_LENGTH) {
+            return Err(ServiceError::BioTooLong);
+        }
+
+        if let Some(avatar) = avatar {
+            validate_avatar_url(avatar)?;
+        }
+
+        self.user_profile_repository.update_profile(user_id, bio, avatar).await?;
+
+        Ok(self.user_profile_repository.get_public_profile(username).await?)
     }
 
     /// It registers a new user.
@@ -268,27 +571,35 @@ impl ProfileService {
             .change_password(user_id, &password_hash)
             .await?;
 
+        self.user_repository.invalidate(&user_id);
+
         Ok(())
     }
 }
 
 pub struct BanService {
+    user_repository: Arc<Box<dyn Repository>>,
     user_profile_repository: Arc<DbUserProfileRepository>,
     banned_user_list: Arc<DbBannedUserList>,
     authorization_service: Arc<authorization::Service>,
+    audit_service: Arc<audit::Service>,
 }
 
 impl BanService {
     #[must_use]
     pub fn new(
+        user_repository: Arc<Box<dyn Repository>>,
         user_profile_repository: Arc<DbUserProfileRepository>,
         banned_user_list: Arc<DbBannedUserList>,
         authorization_service: Arc<authorization::Service>,
+        audit_service: Arc<audit::Service>,
     ) -> Self {
         Self {
+            user_repository,
             user_profile_repository,
             banned_user_list,
             authorization_service,
+            audit_service,
         }
     }
 
@@ -317,6 +628,12 @@ impl BanService {
 
         self.banned_user_list.add(&user_profile.user_id).await?;
 
+        self.user_repository.invalidate(&user_profile.user_id);
+
+        self.audit_service
+            .record(maybe_user_id, AuditAction::UserBan, &user_profile.username, None)
+            .await;
+
         Ok(())
     }
 }
@@ -328,6 +645,10 @@ pub trait Repository: Sync + Send {
     async fn grant_admin_role(&self, user_id: &UserId) -> Result<(), Error>;
     async fn delete(&self, user_id: &UserId) -> Result<(), Error>;
     async fn add(&self, username: &str, email: &str, password_hash: &str) -> Result<UserId, Error>;
+
+    /// It forgets the cached data for the user, if any. It must be called
+    /// after changing the role or the account state of the user.
+    fn invalidate(&self, _user_id: &UserId) {}
 }
 
 pub struct DbUserRepository {
@@ -385,6 +706,125 @@ impl Repository for DbUserRepository {
     }
 }
 
+/// Time the compact users are kept in the [`CachedUserRepository`].
+const USER_CACHE_TTL: Duration = Duration::from_secs(30);
+
+/// Maximum number of users in the [`CachedUserRepository`].
+const MAX_CACHED_USERS: usize = 10_000;
+
+/// A read-through cache for the compact users.
+///
+/// The compact user is loaded to get the role of the user in every
+/// authorized request. Entries expire after a few seconds, so changes made
+/// directly in the database are eventually seen, and they are removed when
+/// the role changes, the user is banned or the password changes.
+pub struct CachedUserRepository {
+    repository: Box<dyn Repository>,
+    ttl: Duration,
+    users: RwLock<HashMap<UserId, (UserCompact, Instant)>>,
+}
+
+impl CachedUserRepository {
+    #[must_use]
+    pub fn new(repository: Box<dyn Repository>) -> Self {
+        Self::with_ttl(repository, USER_CACHE_TTL)
+    }
+
+    #[must_use]
+    pub fn with_ttl(repository: Box<dyn Repository>, ttl: Duration) -> Self {
+        Self {
+            repository,
+            ttl,
+            users: RwLock::new(HashMap::new()),
+        }
+    }
+
+    fn cached(&self, user_id: &UserId) -> Option<UserCompact> {
+        let users = self.users.read().expect("user cache lock should not be poisoned");
+
+        users
+            .get(user_id)
+            .filter(|(_, cached_at)| cached_at.elapsed() < self.ttl)
+            .map(|(user, _)| user.clone())
+    }
+
+    fn cache(&self, user: &UserCompact) {
+        let mut users = self.users.write().expect("user cache lock should not be poisoned");
+
+        if users.len() >= MAX_CACHED_USERS {
+            users.retain(|_, (_, cached_at)| cached_at.elapsed() < self.ttl);
+
+            if users.len() >= MAX_CACHED_USERS {
+                users.clear();
+            }
+        }
+
+        users.insert(user.user_id, (user.clone(), Instant::now()));
+    }
+}
+
+#[async_trait]
+impl Repository for CachedUserRepository {
+    /// It returns the compact user, from the cache when possible.
+    ///
+    /// # Errors
+    ///
+    /// It returns an error if there is a database error.
+    async fn get_compact(&self, user_id: &UserId) -> Result<UserCompact, ServiceError> {
+        if let Some(user) = self.cached(user_id) {
+            return Ok(user);
+        }
+
+        let user = self.repository.get_compact(user_id).await?;
+
+        self.cache(&user);
+
+        Ok(user)
+    }
+
+    /// It grants the admin role to the user.
+    ///
+    /// # Errors
+    ///
+    /// It returns an error if there is a database error.
+    async fn grant_admin_role(&self, user_id: &UserId) -> Result<(), Error> {
+        let result = self.repository.grant_admin_role(user_id).await;
+
+        self.invalidate(user_id);
+
+        result
+    }
+
+    /// It deletes the user.
+    ///
+    /// # Errors
+    ///
+    /// It returns an error if there is a database error.
+    async fn delete(&self, user_id: &UserId) -> Result<(), Error> {
+        let result = self.repository.delete(user_id).await;
+
+        self.invalidate(user_id);
+
+        result
+    }
+
+    /// It adds a new user.
+    ///
+    /// # Errors
+    ///
+    /// It returns an error if there is a database error.
+    async fn add(&self, username: &str, email: &str, password_hash: &str) -> Result<UserId, Error> {
+        self.repository.add(username, email, password_hash).await
+    }
+
+    fn invalidate(&self, user_id: &UserId) {
+        self.users
+            .write()
+            .expect("user cache lock should not be poisoned")
+            .remove(user_id);
+    }
+}
+
 pub struct DbUserProfileRepository {
     database: Arc<Box<dyn Database>>,
 }
@@ -412,6 +852,46 @@ impl DbUserProfileRepository {
     pub async fn get_user_profile_from_username(&self, username: &str) -> Result<UserProfile, Error> {
         self.database.get_user_profile_from_username(username).await
     }
+
+    /// It changes the user's email to a verified address.
+    ///
+    /// # Errors
+    ///
+    /// It returns an error if the email is used by another user or there is a
+    /// database error.
+    pub async fn change_email(&self, user_id: UserId, email: &str) -> Result<(), Error> {
+        self.database.update_user_email(user_id, email).await?;
+
+        self.database.verify_email(user_id).await
+    }
+
+    /// It gets the public profile of a user from the username.
+    ///
+    /// # Errors
+    ///
+    /// It returns an error if the user does not exist or there is a database
+    /// error.
+    pub async fn get_public_profile(&self, username: &str) -> Result<PublicProfile, Error> {
+        self.database.get_public_user_profile(username).await
+    }
+
+    /// It updates the user's bio and avatar.
+    ///
+    /// # Errors
+    ///
+    /// It returns an error if there is a database error.
+    pub async fn update_profile(&self, user_id: UserId, bio: Option<&str>, avatar: Option<&str>) -> Result<(), Error> {
+        self.database.update_user_profile(user_id, bio, avatar).await
+    }
+
+    /// It gets the IDs of the approved torrents uploaded by the user.
+    ///
+    /// # Errors
+    ///
+    /// It returns an error if there is a database error.
+    pub async fn get_uploaded_torrent_ids(&self, user_id: UserId) -> Result<Vec<TorrentId>, Error> {
+        self.database.get_user_uploaded_torrent_ids(user_id).await
+    }
 }
 
 pub struct DbBannedUserList {
@@ -450,6 +930,29 @@ impl DbBannedUserList {
     }
 }
 
+/// It returns `None` for blank profile fields.
+fn non_empty(value: &str) -> Option<&str> {
+    let value = value.trim();
+
+    if value.is_empty() {
+        None
+    } else {
+        Some(value)
+    }
+}
+
+fn validate_avatar_url(avatar: &str) -> Result<(), ServiceError> {
+    match Url::parse(avatar) {
+        Ok(url) if url.scheme() == "http" || url.scheme() == "https" => Ok(()),
+        _ => Err(ServiceError::NotAUrl),
+    }
+}
+
+/// The path to get an avatar through the image proxy.
+fn avatar_proxy_url(avatar: &str) -> String {
+    format!("/v1/proxy/image/{}", urlencoding::encode(avatar))
+}
+
 fn validate_password_constraints(
     password: &str,
     confirm_password: &str,
@@ -472,14 +975,44 @@ fn validate_password_constraints(
     Ok(())
 }
 
-fn hash_password(password: &str) -> Result<String, ServiceError> {
-    let salt = SaltString::generate(&mut OsRng);
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use super::{CachedUserRepository, MockRepository, Repository};
+    use crate::models::user::UserCompact;
+
+    fn user() -> UserCompact {
+        UserCompact {
+            user_id: 1,
+            username: "alice".to_string(),
+            administrator: false,
+        }
+    }
 
-    // Argon2 with default params (Argon2id v19)
-    let argon2 = Argon2::default();
+    #[tokio::test]
+    async fn it_should_only_load_the_user_again_after_it_was_invalidated() {
+        let mut repository = MockRepository::new();
+        repository.expect_get_compact().times(2).returning(|_| Ok(user()));
 
-    // Hash password to PHC string ($argon2id$v=19$...)
-    let password_hash = argon2.hash_password(password.as_bytes(), &salt)?.to_string();
+        let cached_user_repository = CachedUserRepository::new(Box::new(repository));
 
-    Ok(password_hash)
+        assert_eq!(cached_user_repository.get_compact(&1).await.unwrap().username, "alice");
+        assert_eq!(cached_user_repository.get_compact(&1).await.unwrap().username, "alice");
+
+        cached_user_repository.invalidate(&1);
+
+        assert_eq!(cached_user_repository.get_compact(&1).await.unwrap().username, "alice");
+    }
+
+    #[tokio::test]
+    async fn it_should_load_the_user_again_when_the_cached_user_expired() {
+        let mut repository = MockRepository::new();
+        repository.expect_get_compact().times(2).returning(|_| Ok(user()));
+
+        let cached_user_repository = CachedUserRepository::with_ttl(Box::new(repository), Duration::ZERO);
+
+        assert!(cached_user_repository.get_compact(&1).await.is_ok());
+        assert!(cached_user_repository.get_compact(&1).await.is_ok());
+    }
 }
@@ -0,0 +1,237 @@
+//! Embeddable widgets.
+//!
+//! Community sites can embed the latest torrents of the index, optionally
+//! from one category, as a minimal HTML page in an `<iframe>` or as JSON
+//! (also JSONP for sites that can't make cross-origin requests).
+//!
+//! Widgets only show what guests can see. They can be disabled with the
+//! `api.widgets` option, for example, in private instances:
+//!
+//! ```toml
+//! [api]
+//! widgets = false
+//! ```
+use std::str::FromStr;
+use std::sync::Arc;
+
+use bittorrent_primitives::info_hash::InfoHash;
+use serde::{Deserialize, Serialize};
+use utoipa::{IntoParams, ToSchema};
+
+use super::authorization::{self, ACTION};
+use super::torrent::{DbTorrentListingGenerator, ListingSpecification};
+use crate::config::Configuration;
+use crate::databases::database::Sorting;
+use crate::errors::ServiceError;
+use crate::models::torrent::{ListingFilter, TorrentListing, TorrentStatus};
+use crate::utils::base32::encode_info_hash;
+
+/// Number of torrents in a widget when the request does not set it.
+pub const DEFAULT_WIDGET_SIZE: u8 = 10;
+
+/// Max number of torrents in a widget.
+pub const MAX_WIDGET_SIZE: u8 = 50;
+
+/// Max length of the JSONP callback name.
+const MAX_CALLBACK_LENGTH: usize = 64;
+
+/// Request for a widget with the latest torrents.
+#[derive(Debug, Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
+pub struct WidgetRequest {
+    /// Only torrents in this category. Eg: `?category=movies`
+    pub category: Option<String>,
+    /// Number of torrents. Default to 10, max 50.
+    pub limit: Option<u8>,
+    /// Name of the JavaScript function the JSON is passed to (JSONP). Only
+    /// for the JSON widget.
+    pub callback: Option<String>,
+}
+
+/// A torrent shown in a widget.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, ToSchema)]
+pub struct WidgetTorrent {
+    pub title: String,
+    pub info_hash: String,
+    pub file_size: i64,
+    pub seeders: i64,
+    pub leechers: i64,
+    pub date_uploaded: String,
+    /// The short URL of the torrent in the index.
+    pub url: String,
+}
+
+pub struct Service {
+    configuration: Arc<Configuration>,
+    torrent_listing_generator: Arc<DbTorrentListingGenerator>,
+    authorization_service: Arc<authorization::Service>,
+}
+
+impl Service {
+    #[must_use]
+    pub fn new(
+        configuration: Arc<Configuration>,
+        torrent_listing_generator: Arc<DbTorrentListingGenerator>,
+        authorization_service: Arc<authorization::Service>,
+    ) -> Service {
+        Service {
+            configuration,
+            torrent_listing_generator,
+            authorization_service,
+        }
+    }
+
+    /// It returns the latest approved torrents for a widget. `base_url` is
+    /// used to build the links to the torrents.
+    ///
+    /// # Errors
+    ///
+    /// It returns an error if:
+    ///
+    /// * The widgets are disabled.
+    /// * Guests can't list the torrents.
+    /// * There is a database error.
+    pub async fn get_latest_torrents(&self, request: &WidgetRequest, base_url: &str) -> Result<Vec<WidgetTorrent>, ServiceError> {
+        if !self.configuration.settings.read().await.api.widgets {
+            return Err(ServiceError::WidgetsDisabled);
+        }
+
+        // Widgets are public, so they only include what guests can see
+        self.authorization_service
+            .authorize(ACTION::GenerateTorrentInfoListing, None)
+            .await?;
+
+        let specification = ListingSpecification {
+            search: None,
+            categories: request.category.clone().map(|category| vec![category]),
+            tags: None,
+            torrent_ids: None,
+            status: TorrentStatus::Approved,
+            filter: ListingFilter::default(),
+            sort: Sorting::UploadedDesc,
+            then_sort: None,
+            offset: 0,
+            page_size: request.limit.unwrap_or(DEFAULT_WIDGET_SIZE).clamp(1, MAX_WIDGET_SIZE),
+        };
+
+        let torrents_response = self.torrent_listing_generator.generate_listing(&specification).await?;
+
+        Ok(torrents_response
+            .results
+            .into_iter()
+            .map(|torrent| widget_torrent(torrent, base_url))
+            .collect())
+    }
+}
+
+fn widget_torrent(torrent: TorrentListing, base_url: &str) -> WidgetTorrent {
+    let short_info_hash = InfoHash::from_str(&torrent.info_hash)
+        .map_or_else(|_| torrent.info_hash.clone(), |info_hash| encode_info_hash(&info_hash));
+
+    WidgetTorrent {
+        url: format!("{base_url}/t/{short_info_hash}"),
+        title: torrent.title,
+        info_hash: torrent.info_hash,
+        file_size: torrent.file_size,
+        seeders: torrent.seeders,
+        leechers: torrent.leechers,
+        date_uploaded: torrent.date_uploaded,
+    }
+}
+
+/// It checks the JSONP callback is a plain JavaScript identifier, optionally
+/// with dots, like `renderTorrents` or `widgets.torrents`.
+#[must_use]
+pub fn is_valid_callback(callback: &str) -> bool {
+    !callback.is_empty()
+        && callback.len() <= MAX_CALLBACK_LENGTH
+        && !callback.starts_with(|c: char| c.is_ascii_digit() || c == '.')
+        && !callback.ends_with('.')
+        && callback
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '_' || c == '$' || c == '.')
+}
+
+/// It renders the widget as a minimal HTML page. Links open in a new tab so
+/// they work inside an `<iframe>`.
+#[must_use]
+pub fn render_html(torrents: &[WidgetTorrent]) -> String {
+    let items: String = torrents
+        .iter()
+        .map(|torrent| {
+            format!(
+                r#"<li><a href="{}" target="_blank" rel="noopener">{}</a> <small>{} seeders, {} leechers</small></li>"#,
+                escape_html(&torrent.url),
+                escape_html(&torrent.title),
+                torrent.seeders,
+                torrent.leechers
+            )
+        })
+        .collect();
+
+    format!(
+        r#"<!DOCTYPE html>
+<html>
+    <head>
+        <meta charset="utf-8">
+        <title>Latest torrents</title>
+    </head>
+    <body style="margin: 0;font-family: sans-serif;font-size: 0.9em;">
+        <ul style="margin: 0;padding-left: 1.25em;">{items}</ul>
+    </body>
+</html>
+"#
+    )
+}
+
+fn escape_html(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+
+    for c in text.chars() {
+        match c {
+            '&' => escaped.push_str("&amp;"),
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            '"' => escaped.push_str("&quot;"),
+            '\'' => escaped.push_str("&#39;"),
+            _ => escaped.push(c),
+        }
+    }
+
+    escaped
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{is_valid_callback, render_html, WidgetTorrent};
+
+    #[test]
+    fn it_should_only_accept_plain_javascript_identifiers_as_callbacks() {
+        assert!(is_valid_callback("renderTorrents"));
+        assert!(is_valid_callback("widgets.torrents_1"));
+        assert!(is_valid_callback("$"));
+
+        assert!(!is_valid_callback(""));
+        assert!(!is_valid_callback("1callback"));
+        assert!(!is_valid_callback("alert(1)//"));
+        assert!(!is_valid_callback("widgets."));
+        assert!(!is_valid_callback(&"a".repeat(65)));
+    }
+
+    #[test]
+    fn it_should_escape_the_torrent_titles_in_the_html_widget() {
+        let html = render_html(&[WidgetTorrent {
+            title: "<script>alert(\"x\")</script>".to_string(),
+            info_hash: "5452869be36f9f3350ccee6b4544e7e76caaadab".to_string(),
+            file_size: 1,
+            seeders: 2,
+            leechers: 3,
+            date_uploaded: "2024-10-17 00:00:00".to_string(),
+            url: "http://localhost/t/krjing7dn6ptgugm5zvukrhh45wkvlnl".to_string(),
+        }]);
+
+        assert!(html.contains("&lt;script&gt;alert(&quot;x&quot;)&lt;/script&gt;"));
+        assert!(!html.contains("<script>"));
+        assert!(html.contains("2 seeders, 3 leechers"));
+    }
+}
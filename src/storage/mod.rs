@@ -0,0 +1,5 @@
+//! External storage backends.
+//!
+//! Refer to the `[storage]` section in the configuration.
+pub mod s3;
+pub mod torrent_file;
@@ -0,0 +1,345 @@
+//! Storage backends for the torrent files.
+//!
+//! Uploaded torrents are decomposed into database tables. Most of the size of
+//! a torrent file is the `pieces` key of the info dictionary: the SHA-1 hashes
+//! of all the pieces. They can be kept out of the database, so it stays
+//! small and its backups are fast:
+//!
+//! - [`DatabaseStore`]: the pieces are stored in the `pieces` column of the
+//!   `torrust_torrents` table, in hex. This is the default.
+//! - [`FilesystemStore`]: the pieces are stored as files in a local
+//!   directory.
+//! - [`S3Store`]: the pieces are stored as objects in the S3-compatible
+//!   bucket of the `[storage.s3]` section.
+//!
+//! The pieces are stored by info-hash. With the filesystem and S3 backends,
+//! the `pieces` column is left empty. Torrents whose pieces are still in the
+//! database are read from it, so the existing torrents can be moved to
+//! another backend at any time with the `move_torrent_files` command.
+//!
+//! BEP 30 torrents have a root hash instead of pieces and they are always
+//! kept in the database.
+use std::sync::Arc;
+
+use async_trait::async_trait;
+use bittorrent_primitives::info_hash::InfoHash;
+use bytes::Bytes;
+use camino::{Utf8Path, Utf8PathBuf};
+use serde_bytes::ByteBuf;
+
+use crate::config::{Storage, TorrentFilesBackend};
+use crate::databases::database::{self, Database};
+use crate::models::torrent_file::{DbTorrent, Torrent};
+use crate::storage::s3::Bucket;
+use crate::utils::hex::{from_bytes, into_bytes};
+
+#[derive(Debug)]
+pub enum Error {
+    /// The pieces are not in the database nor in the configured store.
+    PiecesNotFound,
+    /// The pieces stored in the database are not valid hex.
+    InvalidPieces,
+    /// The database failed.
+    Database(database::Error),
+    /// The storage failed.
+    StorageFailed(String),
+}
+
+impl From<database::Error> for Error {
+    fn from(err: database::Error) -> Self {
+        Error::Database(err)
+    }
+}
+
+/// A torrent file storage backend. The pieces are stored by info-hash.
+#[async_trait]
+pub trait TorrentFileStore: Sync + Send {
+    /// The backend, as it's named in the configuration.
+    fn backend(&self) -> TorrentFilesBackend;
+
+    /// It returns the pieces of the torrent, if they are stored.
+    async fn get(&self, info_hash: &InfoHash) -> Result<Option<Bytes>, Error>;
+
+    /// It stores the pieces of the torrent.
+    async fn put(&self, info_hash: &InfoHash, pieces: Bytes) -> Result<(), Error>;
+
+    /// It removes the pieces of the torrent. Removing pieces that are not
+    /// stored is not an error.
+    async fn delete(&self, info_hash: &InfoHash) -> Result<(), Error>;
+}
+
+/// It builds the store for the configured backend.
+///
+/// # Panics
+///
+/// Will panic if the backend is `s3` and there is no bucket. The
+/// configuration validation rejects it.
+#[must_use]
+pub fn from_config(storage: &Storage, database: Arc<Box<dyn Database>>) -> Arc<dyn TorrentFileStore> {
+    build(storage.torrent_files.backend, storage, database)
+}
+
+/// It builds the store for a backend, with the options in the configuration.
+///
+/// # Panics
+///
+/// Will panic if the backend is `s3` and there is no bucket.
+#[must_use]
+pub fn build(backend: TorrentFilesBackend, storage: &Storage, database: Arc<Box<dyn Database>>) -> Arc<dyn TorrentFileStore> {
+    match backend {
+        TorrentFilesBackend::Database => Arc::new(DatabaseStore::new(database)),
+        TorrentFilesBackend::Filesystem => Arc::new(FilesystemStore::new(&storage.torrent_files.path)),
+        TorrentFilesBackend::S3 => Arc::new(S3Store::new(
+            Bucket::new(storage.s3.as_ref().expect("the torrent files bucket should be validated")),
+            &storage.torrent_files.s3_key_prefix,
+        )),
+    }
+}
+
+/// It stores the pieces of a new torrent in the store, when they are not
+/// kept in the database, and it clears them from the database.
+///
+/// # Errors
+///
+/// It returns an error if the pieces can't be stored. They are kept in the
+/// database in that case.
+pub async fn move_out_of_database(
+    store: &dyn TorrentFileStore,
+    database: &dyn Database,
+    info_hash: &InfoHash,
+    torrent: &Torrent,
+) -> Result<(), Error> {
+    if store.backend() == TorrentFilesBackend::Database {
+        return Ok(());
+    }
+
+    let Some(pieces) = &torrent.info.pieces else {
+        return Ok(());
+    };
+
+    store.put(info_hash, Bytes::copy_from_slice(pieces.as_ref())).await?;
+
+    database.update_torrent_pieces(info_hash, "").await?;
+
+    Ok(())
+}
+
+/// It fills in the pieces of a torrent loaded from the database, when they
+/// are not kept in the database.
+///
+/// # Errors
+///
+/// It returns an error if the pieces can't be loaded from the store, or
+/// they are not in it.
+pub async fn load_pieces(store: &dyn TorrentFileStore, info_hash: &InfoHash, torrent: &mut Torrent) -> Result<(), Error> {
+    if !torrent.info.pieces.as_ref().is_some_and(|pieces| pieces.is_empty()) {
+        return Ok(());
+    }
+
+    let pieces = store.get(info_hash).await?.ok_or(Error::PiecesNotFound)?;
+
+    torrent.info.pieces = Some(ByteBuf::from(pieces.to_vec()));
+
+    Ok(())
+}
+
+/// It fills in the pieces of a torrent row, in hex, when they are not kept in
+/// the database.
+///
+/// # Errors
+///
+/// It returns an error if the pieces can't be loaded from the store, or
+/// they are not in it.
+pub async fn load_db_pieces(store: &dyn TorrentFileStore, db_torrent: &mut DbTorrent) -> Result<(), Error> {
+    if db_torrent.is_bep_30 != 0 || !db_torrent.pieces.as_deref().is_some_and(str::is_empty) {
+        return Ok(());
+    }
+
+    let info_hash = db_torrent
+        .info_hash
+        .parse::<InfoHash>()
+        .map_err(|_| Error::StorageFailed(format!("invalid info-hash: {}", db_torrent.info_hash)))?;
+
+    let pieces = store.get(&info_hash).await?.ok_or(Error::PiecesNotFound)?;
+
+    db_torrent.pieces = Some(from_bytes(&pieces));
+
+    Ok(())
+}
+
+/// The pieces are kept in the `pieces` column, in hex. An empty column means
+/// they are in another store.
+pub struct DatabaseStore {
+    database: Arc<Box<dyn Database>>,
+}
+
+impl DatabaseStore {
+    #[must_use]
+    pub fn new(database: Arc<Box<dyn Database>>) -> Self {
+        Self { database }
+    }
+}
+
+#[async_trait]
+impl TorrentFileStore for DatabaseStore {
+    fn backend(&self) -> TorrentFilesBackend {
+        TorrentFilesBackend::Database
+    }
+
+    async fn get(&self, info_hash: &InfoHash) -> Result<Option<Bytes>, Error> {
+        match self.database.get_torrent_pieces(info_hash).await? {
+            Some(pieces) if !pieces.is_empty() => Ok(Some(Bytes::from(into_bytes(&pieces).map_err(|_| Error::InvalidPieces)?))),
+            _ => Ok(None),
+        }
+    }
+
+    async fn put(&self, info_hash: &InfoHash, pieces: Bytes) -> Result<(), Error> {
+        Ok(self.database.update_torrent_pieces(info_hash, &from_bytes(&pieces)).await?)
+    }
+
+    async fn delete(&self, info_hash: &InfoHash) -> Result<(), Error> {
+        Ok(self.database.update_torrent_pieces(info_hash, "").await?)
+    }
+}
+
+pub struct FilesystemStore {
+    path: Utf8PathBuf,
+}
+
+impl FilesystemStore {
+    #[must_use]
+    pub fn new(path: &Utf8Path) -> Self {
+        Self { path: path.to_owned() }
+    }
+
+    /// Files are grouped in subdirectories by the first two characters of
+    /// the info-hash, to avoid directories with too many files.
+    fn pieces_path(&self, info_hash: &InfoHash) -> Utf8PathBuf {
+        let key = object_key(info_hash);
+
+        self.path.join(&key[..2]).join(key)
+    }
+}
+
+#[async_trait]
+impl TorrentFileStore for FilesystemStore {
+    fn backend(&self) -> TorrentFilesBackend {
+        TorrentFilesBackend::Filesystem
+    }
+
+    async fn get(&self, info_hash: &InfoHash) -> Result<Option<Bytes>, Error> {
+        match tokio::fs::read(self.pieces_path(info_hash)).await {
+            Ok(bytes) => Ok(Some(Bytes::from(bytes))),
+            Err(err) if err.kind() == std::io::ErrorKind::NotFound => Ok(None),
+            Err(err) => Err(Error::StorageFailed(err.to_string())),
+        }
+    }
+
+    async fn put(&self, info_hash: &InfoHash, pieces: Bytes) -> Result<(), Error> {
+        let pieces_path = self.pieces_path(info_hash);
+
+        if let Some(directory) = pieces_path.parent() {
+            tokio::fs::create_dir_all(directory)
+                .await
+                .map_err(|err| Error::StorageFailed(err.to_string()))?;
+        }
+
+        // Write to a temporary file first, so readers never get partial pieces
+        let temporary_path = pieces_path.with_extension("tmp");
+
+        tokio::fs::write(&temporary_path, &pieces)
+            .await
+            .map_err(|err| Error::StorageFailed(err.to_string()))?;
+
+        tokio::fs::rename(&temporary_path, &pieces_path)
+            .await
+            .map_err(|err| Error::StorageFailed(err.to_string()))
+    }
+
+    async fn delete(&self, info_hash: &InfoHash) -> Result<(), Error> {
+        match tokio::fs::remove_file(self.pieces_path(info_hash)).await {
+            Ok(()) => Ok(()),
+            Err(err) if err.kind() == std::io::ErrorKind::NotFound => Ok(()),
+            Err(err) => Err(Error::StorageFailed(err.to_string())),
+        }
+    }
+}
+
+pub struct S3Store {
+    bucket: Bucket,
+    key_prefix: String,
+}
+
+impl S3Store {
+    #[must_use]
+    pub fn new(bucket: Bucket, key_prefix: &str) -> Self {
+        Self {
+            bucket,
+            key_prefix: key_prefix.to_string(),
+        }
+    }
+
+    fn object_key(&self, info_hash: &InfoHash) -> String {
+        format!("{}{}", self.key_prefix, object_key(info_hash))
+    }
+}
+
+#[async_trait]
+impl TorrentFileStore for S3Store {
+    fn backend(&self) -> TorrentFilesBackend {
+        TorrentFilesBackend::S3
+    }
+
+    async fn get(&self, info_hash: &InfoHash) -> Result<Option<Bytes>, Error> {
+        self.bucket
+            .get(&self.object_key(info_hash))
+            .await
+            .map_err(|err| Error::StorageFailed(format!("{err:?}")))
+    }
+
+    async fn put(&self, info_hash: &InfoHash, pieces: Bytes) -> Result<(), Error> {
+        self.bucket
+            .put(&self.object_key(info_hash), "application/octet-stream", pieces)
+            .await
+            .map_err(|err| Error::StorageFailed(format!("{err:?}")))
+    }
+
+    async fn delete(&self, info_hash: &InfoHash) -> Result<(), Error> {
+        self.bucket
+            .delete(&self.object_key(info_hash))
+            .await
+            .map_err(|err| Error::StorageFailed(format!("{err:?}")))
+    }
+}
+
+fn object_key(info_hash: &InfoHash) -> String {
+    info_hash.to_hex_string().to_lowercase()
+}
+
+#[cfg(test)]
+mod tests {
+    use bittorrent_primitives::info_hash::InfoHash;
+    use bytes::Bytes;
+    use camino::Utf8PathBuf;
+
+    use super::{FilesystemStore, TorrentFileStore};
+
+    #[tokio::test]
+    async fn the_filesystem_store_should_keep_the_pieces_by_info_hash() {
+        let directory = tempfile::tempdir().unwrap();
+        let store = FilesystemStore::new(&Utf8PathBuf::from_path_buf(directory.path().to_path_buf()).unwrap());
+
+        let info_hash = "6C690018C5786DBBB00161F62B0712D69296DF97".parse::<InfoHash>().unwrap();
+
+        assert_eq!(store.get(&info_hash).await.unwrap(), None);
+
+        store.put(&info_hash, Bytes::from_static(b"pieces")).await.unwrap();
+
+        assert_eq!(store.get(&info_hash).await.unwrap(), Some(Bytes::from_static(b"pieces")));
+
+        store.delete(&info_hash).await.unwrap();
+        store.delete(&info_hash).await.unwrap();
+
+        assert_eq!(store.get(&info_hash).await.unwrap(), None);
+    }
+}
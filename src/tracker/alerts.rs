@@ -0,0 +1,101 @@
+//! Alerts for the tracker statistics importer.
+//!
+//! When the `[tracker_statistics_importer.alerts]` section is present, an
+//! alert is sent by email and/or to a webhook once the importer has failed
+//! the configured number of consecutive runs. No more alerts are sent until
+//! the importer recovers and fails again.
+use std::sync::Arc;
+
+use serde_json::json;
+use tracing::{error, info};
+
+use super::statistics_importer::ImporterStatus;
+use crate::config::ImporterAlerts;
+use crate::mailer;
+use crate::utils::trace_context::PropagateTraceContext;
+
+pub struct Alerter {
+    config: ImporterAlerts,
+    mailer: Arc<mailer::Service>,
+    client: reqwest::Client,
+}
+
+impl Alerter {
+    #[must_use]
+    pub fn new(config: ImporterAlerts, mailer: Arc<mailer::Service>) -> Self {
+        Self {
+            config,
+            mailer,
+            client: reqwest::Client::new(),
+        }
+    }
+
+    /// It sends the alert if the importer has just reached the configured
+    /// number of consecutive failures.
+    ///
+    /// Errors sending the alert are only logged.
+    pub async fn check(&self, status: &ImporterStatus) {
+        if !should_alert(status, self.config.consecutive_failures) {
+            return;
+        }
+
+        let message = alert_message(status);
+
+        info!(target: "importer alerts", "Sending tracker statistics importer alert: {message}");
+
+        if let Some(email) = &self.config.email {
+            if let Err(e) = self
+                .mailer
+                .send_plain_mail(email, "Torrust - Tracker statistics importer failing", &message)
+                .await
+            {
+                error!(target: "importer alerts", "Failed to send importer alert email: {e}");
+            }
+        }
+
+        if let Some(webhook_url) = &self.config.webhook_url {
+            let result = self
+                .client
+                .post(webhook_url.clone())
+                .with_trace_context()
+                .json(&json!({ "text": message, "status": status }))
+                .send()
+                .await
+                .and_then(reqwest::Response::error_for_status);
+
+            if let Err(e) = result {
+                error!(target: "importer alerts", "Failed to send importer alert webhook: {e}");
+            }
+        }
+    }
+}
+
+fn should_alert(status: &ImporterStatus, threshold: u32) -> bool {
+    threshold > 0 && status.consecutive_failures == threshold
+}
+
+fn alert_message(status: &ImporterStatus) -> String {
+    format!(
+        "The tracker statistics importer has failed {} consecutive runs. Last error: {}",
+        status.consecutive_failures,
+        status.last_error.as_deref().unwrap_or("unknown")
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::should_alert;
+    use crate::tracker::statistics_importer::ImporterStatus;
+
+    #[test]
+    fn it_should_only_alert_when_the_threshold_is_reached() {
+        let status = |consecutive_failures| ImporterStatus {
+            consecutive_failures,
+            ..Default::default()
+        };
+
+        assert!(!should_alert(&status(2), 3));
+        assert!(should_alert(&status(3), 3));
+        assert!(!should_alert(&status(4), 3));
+    }
+}
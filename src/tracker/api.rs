@@ -2,6 +2,8 @@ use std::time::Duration;
 
 use reqwest::{Error, Response};
 use url::Url;
+
+use crate::utils::trace_context::PropagateTraceContext;
 pub struct ConnectionInfo {
     /// The URL of the tracker API. Eg: <https://tracker:1212>.
     pub url: Url,
@@ -58,7 +60,12 @@ impl Client {
     pub async fn whitelist_torrent(&self, info_hash: &str) -> Result<Response, Error> {
         let request_url = format!("{}/whitelist/{}", self.api_base_url, info_hash);
 
-        self.client.post(request_url).query(&self.token_param).send().await
+        self.client
+            .post(request_url)
+            .with_trace_context()
+            .query(&self.token_param)
+            .send()
+            .await
     }
 
     /// Remove a torrent from the tracker whitelist.
@@ -69,7 +76,12 @@ impl Client {
     pub async fn remove_torrent_from_whitelist(&self, info_hash: &str) -> Result<Response, Error> {
         let request_url = format!("{}/whitelist/{}", self.api_base_url, info_hash);
 
-        self.client.delete(request_url).query(&self.token_param).send().await
+        self.client
+            .delete(request_url)
+            .with_trace_context()
+            .query(&self.token_param)
+            .send()
+            .await
     }
 
     /// Retrieve a new tracker key.
@@ -80,7 +92,38 @@ impl Client {
     pub async fn retrieve_new_tracker_key(&self, token_valid_seconds: u64) -> Result<Response, Error> {
         let request_url = format!("{}/key/{}", self.api_base_url, token_valid_seconds);
 
-        self.client.post(request_url).query(&self.token_param).send().await
+        self.client
+            .post(request_url)
+            .with_trace_context()
+            .query(&self.token_param)
+            .send()
+            .await
+    }
+
+    /// Revoke a tracker key.
+    ///
+    /// # Errors
+    ///
+    /// Will return an error if the HTTP request fails.
+    pub async fn delete_tracker_key(&self, key: &str) -> Result<Response, Error> {
+        let request_url = format!("{}/key/{}", self.api_base_url, key);
+
+        self.client
+            .delete(request_url)
+            .with_trace_context()
+            .query(&self.token_param)
+            .send()
+            .await
+    }
+
+    /// Send an announce request to the tracker, not to the tracker API. It's
+    /// used to check the tracker accepts a user's key.
+    ///
+    /// # Errors
+    ///
+    /// Will return an error if the HTTP request fails.
+    pub async fn announce(&self, announce_url: &str) -> Result<Response, Error> {
+        self.client.get(announce_url).with_trace_context().send().await
     }
 
     /// Retrieve the info for one torrent.
@@ -91,7 +134,12 @@ impl Client {
     pub async fn get_torrent_info(&self, info_hash: &str) -> Result<Response, Error> {
         let request_url = format!("{}/torrent/{}", self.api_base_url, info_hash);
 
-        self.client.get(request_url).query(&self.token_param).send().await
+        self.client
+            .get(request_url)
+            .with_trace_context()
+            .query(&self.token_param)
+            .send()
+            .await
     }
 
     /// Retrieve the info for multiple torrents at the same time.
@@ -110,6 +158,28 @@ impl Client {
             query_params.push(("info_hash".to_string(), info_hash.clone()));
         }
 
-        self.client.get(request_url).query(&query_params).send().await
+        self.client
+            .get(request_url)
+            .with_trace_context()
+            .query(&query_params)
+            .send()
+            .await
+    }
+
+    /// Retrieve the bytes uploaded and downloaded with each key. The
+    /// counters are the totals since the key was issued.
+    ///
+    /// # Errors
+    ///
+    /// Will return an error if the HTTP request fails.
+    pub async fn get_keys_transfer_stats(&self) -> Result<Response, Error> {
+        let request_url = format!("{}/keys/stats", self.api_base_url);
+
+        self.client
+            .get(request_url)
+            .with_trace_context()
+            .query(&self.token_param)
+            .send()
+            .await
     }
 }
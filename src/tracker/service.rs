@@ -1,20 +1,27 @@
-use std::sync::Arc;
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
 
 use derive_more::{Display, Error};
 use hyper::StatusCode;
 use serde::{Deserialize, Serialize};
+use serde_bencode::value::Value;
 use tracing::{debug, error};
 use url::Url;
 
 use super::api::{Client, ConnectionInfo};
+use super::statistics_importer::aggregate_statistics;
 use crate::config::Configuration;
 use crate::databases::database::Database;
+use crate::models::torrent::TorrentId;
 use crate::models::tracker_key::TrackerKey;
 use crate::models::user::UserId;
+use crate::utils::redaction::redact;
 
 #[derive(Debug, Display, PartialEq, Eq, Error)]
 #[allow(dead_code)]
 pub enum TrackerAPIError {
+    /// The error is redacted. It does not include the tracker API token.
     #[display("Error with tracker request: {error}.")]
     TrackerOffline { error: String },
 
@@ -39,6 +46,7 @@ pub enum TrackerAPIError {
     #[display("Expected body in tracker response, received empty body.")]
     MissingResponseBody,
 
+    /// The body is redacted. It does not include the tracker keys.
     #[display("Expected body in tracker response, received empty body.")]
     FailedToParseTrackerResponse { body: String },
 }
@@ -77,11 +85,32 @@ pub struct PeerId {
     pub client: Option<String>,
 }
 
+/// Bytes uploaded and downloaded with a key, reported by the tracker.
+#[derive(Debug, Serialize, Deserialize, PartialEq)]
+pub struct KeyTransferStats {
+    pub key: String,
+    pub uploaded: u64,
+    pub downloaded: u64,
+}
+
+/// Peer ID of the announce requests to check the users' keys.
+const KEY_CHECK_PEER_ID: &str = "-TI0000-000000000000";
+
+/// Statistics of a torrent scraped on demand.
+struct ScrapedStatistics {
+    scraped_at: Instant,
+    /// The seeders and leechers, or `None` if the main tracker failed.
+    statistics: Option<(i64, i64)>,
+}
+
 pub struct Service {
     database: Arc<Box<dyn Database>>,
     api_client: Client,
+    backup_api_clients: Vec<Client>,
     token_valid_seconds: u64,
     tracker_url: Url,
+    on_demand_scrape_threshold: Option<Duration>,
+    scrape_cache: Mutex<HashMap<String, ScrapedStatistics>>,
 }
 
 impl Service {
@@ -95,29 +124,59 @@ impl Service {
             settings.tracker.token.clone().to_string(),
         ))
         .expect("a reqwest client should be provided");
+        let backup_api_clients = settings
+            .tracker
+            .backups
+            .iter()
+            .map(|backup| {
+                Client::new(ConnectionInfo::new(backup.api_url.clone(), backup.token.to_string()))
+                    .expect("a reqwest client should be provided")
+            })
+            .collect();
         let token_valid_seconds = settings.tracker.token_valid_seconds;
         let tracker_url = settings.tracker.url.clone();
+        let on_demand_scrape_threshold = settings
+            .tracker_statistics_importer
+            .on_demand_scrape_threshold
+            .map(Duration::from_secs);
         drop(settings);
         Service {
             database,
             api_client,
+            backup_api_clients,
             token_valid_seconds,
             tracker_url,
+            on_demand_scrape_threshold,
+            scrape_cache: Mutex::new(HashMap::new()),
         }
     }
 
-    /// Add a torrent to the tracker whitelist.
+    /// Add a torrent to the whitelist of the main tracker and the backup
+    /// trackers.
     ///
     /// # Errors
     ///
-    /// Will return an error if the HTTP request failed (for example if the
-    /// tracker API is offline) or if the tracker API returned an error.
+    /// Will return an error if the HTTP request to the main tracker failed
+    /// (for example if the tracker API is offline) or if the tracker API
+    /// returned an error. Errors from the backup trackers are only logged.
     pub async fn whitelist_info_hash(&self, info_hash: String) -> Result<(), TrackerAPIError> {
+        Self::whitelist_info_hash_in(&self.api_client, &info_hash).await?;
+
+        for api_client in &self.backup_api_clients {
+            if let Err(err) = Self::whitelist_info_hash_in(api_client, &info_hash).await {
+                error!(target: "tracker-service", "add to whitelist in backup tracker {} failed: {err}", api_client.connection_info.url);
+            }
+        }
+
+        Ok(())
+    }
+
+    async fn whitelist_info_hash_in(api_client: &Client, info_hash: &str) -> Result<(), TrackerAPIError> {
         debug!(target: "tracker-service", "add to whitelist: {info_hash}");
 
-        let maybe_response = self.api_client.whitelist_torrent(&info_hash).await;
+        let maybe_response = api_client.whitelist_torrent(info_hash).await;
 
-        debug!(target: "tracker-service", "add to whitelist response result: {:?}", maybe_response);
+        debug!(target: "tracker-service", "add to whitelist response result: {}", redact(&format!("{maybe_response:?}")));
 
         match maybe_response {
             Ok(response) => {
@@ -134,32 +193,48 @@ impl Service {
                         if body == "Unhandled rejection: Err { reason: \"token not valid\" }" {
                             Err(TrackerAPIError::InvalidToken)
                         } else {
-                            error!(target: "tracker-service", "add to whitelist 500 response: status {status}, body: {body}");
+                            error!(target: "tracker-service", "add to whitelist 500 response: status {status}, body: {}", redact(&body));
                             Err(TrackerAPIError::InternalServerError)
                         }
                     }
                     _ => {
-                        error!(target: "tracker-service", "add to whitelist unexpected response: status {status}, body: {body}");
+                        error!(target: "tracker-service", "add to whitelist unexpected response: status {status}, body: {}", redact(&body));
                         Err(TrackerAPIError::UnexpectedResponseStatus)
                     }
                 }
             }
-            Err(err) => Err(TrackerAPIError::TrackerOffline { error: err.to_string() }),
+            Err(err) => Err(TrackerAPIError::TrackerOffline {
+                error: redact(&err.to_string()),
+            }),
         }
     }
 
-    /// Remove a torrent from the tracker whitelist.
+    /// Remove a torrent from the whitelist of the main tracker and the backup
+    /// trackers.
     ///
     /// # Errors
     ///
-    /// Will return an error if the HTTP request failed (for example if the
-    /// tracker API is offline) or if the tracker API returned an error.
+    /// Will return an error if the HTTP request to the main tracker failed
+    /// (for example if the tracker API is offline) or if the tracker API
+    /// returned an error. Errors from the backup trackers are only logged.
     pub async fn remove_info_hash_from_whitelist(&self, info_hash: String) -> Result<(), TrackerAPIError> {
+        Self::remove_info_hash_from_whitelist_in(&self.api_client, &info_hash).await?;
+
+        for api_client in &self.backup_api_clients {
+            if let Err(err) = Self::remove_info_hash_from_whitelist_in(api_client, &info_hash).await {
+                error!(target: "tracker-service", "remove from whitelist in backup tracker {} failed: {err}", api_client.connection_info.url);
+            }
+        }
+
+        Ok(())
+    }
+
+    async fn remove_info_hash_from_whitelist_in(api_client: &Client, info_hash: &str) -> Result<(), TrackerAPIError> {
         debug!(target: "tracker-service", "remove from whitelist: {info_hash}");
 
-        let maybe_response = self.api_client.remove_torrent_from_whitelist(&info_hash).await;
+        let maybe_response = api_client.remove_torrent_from_whitelist(info_hash).await;
 
-        debug!(target: "tracker-service", "remove from whitelist response result: {:?}", maybe_response);
+        debug!(target: "tracker-service", "remove from whitelist response result: {}", redact(&format!("{maybe_response:?}")));
 
         match maybe_response {
             Ok(response) => {
@@ -176,17 +251,19 @@ impl Service {
                         if body == Self::invalid_token_body() {
                             Err(TrackerAPIError::InvalidToken)
                         } else {
-                            error!(target: "tracker-service", "remove from whitelist 500 response: status {status}, body: {body}");
+                            error!(target: "tracker-service", "remove from whitelist 500 response: status {status}, body: {}", redact(&body));
                             Err(TrackerAPIError::InternalServerError)
                         }
                     }
                     _ => {
-                        error!(target: "tracker-service", "remove from whitelist unexpected response: status {status}, body: {body}");
+                        error!(target: "tracker-service", "remove from whitelist unexpected response: status {status}, body: {}", redact(&body));
                         Err(TrackerAPIError::UnexpectedResponseStatus)
                     }
                 }
             }
-            Err(err) => Err(TrackerAPIError::TrackerOffline { error: err.to_string() }),
+            Err(err) => Err(TrackerAPIError::TrackerOffline {
+                error: redact(&err.to_string()),
+            }),
         }
     }
 
@@ -226,7 +303,7 @@ impl Service {
 
         let maybe_response = self.api_client.get_torrent_info(info_hash).await;
 
-        debug!(target: "tracker-service", "get torrent info response result: {:?}", maybe_response);
+        debug!(target: "tracker-service", "get torrent info response result: {}", redact(&format!("{maybe_response:?}")));
 
         match maybe_response {
             Ok(response) => {
@@ -248,41 +325,137 @@ impl Service {
                         serde_json::from_str(&body).map_err(|e| {
                             error!(
                                 target: "tracker-service", "Failed to parse torrent info from tracker response. Body: {}, Error: {}",
-                                body, e
+                                redact(&body),
+                            e
                             );
-                            TrackerAPIError::FailedToParseTrackerResponse { body }
+                            TrackerAPIError::FailedToParseTrackerResponse { body: redact(&body) }
                         })
                     }
                     StatusCode::INTERNAL_SERVER_ERROR => {
                         if body == Self::invalid_token_body() {
                             Err(TrackerAPIError::InvalidToken)
                         } else {
-                            error!(target: "tracker-service", "get torrent info 500 response: status {status}, body: {body}");
+                            error!(target: "tracker-service", "get torrent info 500 response: status {status}, body: {}", redact(&body));
                             Err(TrackerAPIError::InternalServerError)
                         }
                     }
                     _ => {
-                        error!(target: "tracker-service", "get torrent info unhandled response: status {status}, body: {body}");
+                        error!(target: "tracker-service", "get torrent info unhandled response: status {status}, body: {}", redact(&body));
                         Err(TrackerAPIError::UnexpectedResponseStatus)
                     }
                 }
             }
-            Err(err) => Err(TrackerAPIError::TrackerOffline { error: err.to_string() }),
+            Err(err) => Err(TrackerAPIError::TrackerOffline {
+                error: redact(&err.to_string()),
+            }),
         }
     }
 
-    /// Get torrent info from tracker in batches.
+    /// Get torrent info from the main tracker in batches.
     ///
     /// # Errors
     ///
     /// Will return an error if the HTTP request to get torrent info fails or
     /// if the response cannot be parsed.
     pub async fn get_torrents_info(&self, info_hashes: &[String]) -> Result<Vec<TorrentBasicInfo>, TrackerAPIError> {
+        Self::get_torrents_info_from(&self.api_client, info_hashes).await
+    }
+
+    /// Get torrent info from each backup tracker in batches. There is one
+    /// list for each backup tracker that responded. Errors are only logged.
+    pub async fn get_backup_torrents_info(&self, info_hashes: &[String]) -> Vec<Vec<TorrentBasicInfo>> {
+        let mut torrents_info = vec![];
+
+        for api_client in &self.backup_api_clients {
+            match Self::get_torrents_info_from(api_client, info_hashes).await {
+                Ok(backup_torrents_info) => torrents_info.push(backup_torrents_info),
+                Err(err) => {
+                    error!(target: "tracker-service", "get torrents info from backup tracker {} failed: {err}", api_client.connection_info.url);
+                }
+            }
+        }
+
+        torrents_info
+    }
+
+    /// It scrapes the seeders and leechers of a torrent on demand, so that
+    /// the torrent details show live statistics between importer runs. The
+    /// statistics are aggregated with the backup trackers and saved in the
+    /// database.
+    ///
+    /// The result is cached for the `on_demand_scrape_threshold` period, so
+    /// the trackers are scraped at most once per period for each torrent.
+    /// It returns `None` if on-demand scrape is disabled or the main tracker
+    /// failed. Errors are only logged.
+    pub async fn scrape_on_demand(&self, torrent_id: TorrentId, info_hash: &str) -> Option<(i64, i64)> {
+        let threshold = self.on_demand_scrape_threshold?;
+
+        if let Some(cached) = self
+            .scrape_cache
+            .lock()
+            .expect("scrape cache lock should not be poisoned")
+            .get(info_hash)
+        {
+            if cached.scraped_at.elapsed() < threshold {
+                return cached.statistics;
+            }
+        }
+
+        let statistics = self.scrape(torrent_id, info_hash).await;
+
+        let mut scrape_cache = self.scrape_cache.lock().expect("scrape cache lock should not be poisoned");
+
+        scrape_cache.retain(|_, cached| cached.scraped_at.elapsed() < threshold);
+        scrape_cache.insert(
+            info_hash.to_string(),
+            ScrapedStatistics {
+                scraped_at: Instant::now(),
+                statistics,
+            },
+        );
+
+        statistics
+    }
+
+    async fn scrape(&self, torrent_id: TorrentId, info_hash: &str) -> Option<(i64, i64)> {
+        let info_hashes = [info_hash.to_string()];
+
+        let torrents_info = match self.get_torrents_info(&info_hashes).await {
+            Ok(torrents_info) => torrents_info,
+            Err(err) => {
+                error!(target: "tracker-service", "scrape on demand {info_hash} failed: {err}");
+                return None;
+            }
+        };
+
+        // No stats for this torrent in the main tracker
+        let statistics = torrents_info
+            .iter()
+            .find(|torrent_info| torrent_info.info_hash == info_hash)
+            .map_or((0, 0), |torrent_info| (torrent_info.seeders, torrent_info.leechers));
+
+        let backup_torrents_info = self.get_backup_torrents_info(&info_hashes).await;
+
+        let (seeders, leechers) = aggregate_statistics(info_hash, statistics, &backup_torrents_info);
+
+        drop(
+            self.database
+                .update_tracker_info(torrent_id, &self.tracker_url, seeders, leechers)
+                .await,
+        );
+
+        Some((seeders, leechers))
+    }
+
+    async fn get_torrents_info_from(
+        api_client: &Client,
+        info_hashes: &[String],
+    ) -> Result<Vec<TorrentBasicInfo>, TrackerAPIError> {
         debug!(target: "tracker-service", "get torrents info");
 
-        let maybe_response = self.api_client.get_torrents_info(info_hashes).await;
+        let maybe_response = api_client.get_torrents_info(info_hashes).await;
 
-        debug!(target: "tracker-service", "get torrents info response result: {:?}", maybe_response);
+        debug!(target: "tracker-service", "get torrents info response result: {}", redact(&format!("{maybe_response:?}")));
 
         match maybe_response {
             Ok(response) => {
@@ -298,29 +471,184 @@ impl Service {
                     StatusCode::OK => serde_json::from_str(&body).map_err(|e| {
                         error!(
                             target: "tracker-service", "Failed to parse torrents info from tracker response. Body: {}, Error: {}",
-                            body, e
+                            redact(&body),
+                            e
                         );
-                        TrackerAPIError::FailedToParseTrackerResponse { body }
+                        TrackerAPIError::FailedToParseTrackerResponse { body: redact(&body) }
                     }),
                     StatusCode::INTERNAL_SERVER_ERROR => {
                         if body == Self::invalid_token_body() {
                             Err(TrackerAPIError::InvalidToken)
                         } else {
-                            error!(target: "tracker-service", "get torrents info 500 response: status {status}, body: {body}");
+                            error!(target: "tracker-service", "get torrents info 500 response: status {status}, body: {}", redact(&body));
                             Err(TrackerAPIError::InternalServerError)
                         }
                     }
                     StatusCode::NOT_FOUND => {
-                        error!(target: "tracker-service", "get torrents info 404 response: url {url}");
+                        error!(target: "tracker-service", "get torrents info 404 response: url {}", redact(url.as_str()));
                         Err(TrackerAPIError::NotFound)
                     }
                     _ => {
-                        error!(target: "tracker-service", "get torrents info unhandled response: status {status}, body: {body}");
+                        error!(target: "tracker-service", "get torrents info unhandled response: status {status}, body: {}", redact(&body));
                         Err(TrackerAPIError::UnexpectedResponseStatus)
                     }
                 }
             }
-            Err(err) => Err(TrackerAPIError::TrackerOffline { error: err.to_string() }),
+            Err(err) => Err(TrackerAPIError::TrackerOffline {
+                error: redact(&err.to_string()),
+            }),
+        }
+    }
+
+    /// Get the bytes uploaded and downloaded with each key from the main
+    /// tracker. Only the main tracker issues keys.
+    ///
+    /// # Errors
+    ///
+    /// Will return an error if the HTTP request fails or if the response
+    /// cannot be parsed.
+    pub async fn get_keys_transfer_stats(&self) -> Result<Vec<KeyTransferStats>, TrackerAPIError> {
+        debug!(target: "tracker-service", "get keys transfer stats");
+
+        let maybe_response = self.api_client.get_keys_transfer_stats().await;
+
+        debug!(target: "tracker-service", "get keys transfer stats response result: {}", redact(&format!("{maybe_response:?}")));
+
+        match maybe_response {
+            Ok(response) => {
+                let status: StatusCode = map_status_code(response.status());
+
+                let body = response.text().await.map_err(|_| {
+                    error!(target: "tracker-service", "response without body");
+                    TrackerAPIError::MissingResponseBody
+                })?;
+
+                match status {
+                    StatusCode::OK => serde_json::from_str(&body).map_err(|e| {
+                        error!(
+                            target: "tracker-service", "Failed to parse keys transfer stats from tracker response. Body: {}, Error: {}",
+                            redact(&body),
+                            e
+                        );
+                        TrackerAPIError::FailedToParseTrackerResponse { body: redact(&body) }
+                    }),
+                    StatusCode::INTERNAL_SERVER_ERROR => {
+                        if body == Self::invalid_token_body() {
+                            Err(TrackerAPIError::InvalidToken)
+                        } else {
+                            error!(target: "tracker-service", "get keys transfer stats 500 response: status {status}, body: {}", redact(&body));
+                            Err(TrackerAPIError::InternalServerError)
+                        }
+                    }
+                    StatusCode::NOT_FOUND => {
+                        error!(target: "tracker-service", "get keys transfer stats 404 response: the tracker does not support the transfer stats");
+                        Err(TrackerAPIError::NotFound)
+                    }
+                    _ => {
+                        error!(target: "tracker-service", "get keys transfer stats unhandled response: status {status}, body: {}", redact(&body));
+                        Err(TrackerAPIError::UnexpectedResponseStatus)
+                    }
+                }
+            }
+            Err(err) => Err(TrackerAPIError::TrackerOffline {
+                error: redact(&err.to_string()),
+            }),
+        }
+    }
+
+    /// It returns the user's tracker key valid for at least one more hour, if
+    /// any.
+    pub async fn get_user_tracker_key(&self, user_id: UserId) -> Option<TrackerKey> {
+        self.database.get_user_tracker_key(user_id).await
+    }
+
+    /// It checks whether the tracker accepts the key with an announce request
+    /// for an unknown torrent. The tracker authenticates the key before
+    /// checking the torrent, so the request only fails because of the key if
+    /// the key is not valid.
+    ///
+    /// # Errors
+    ///
+    /// Will return an error if the announce request fails.
+    pub async fn is_tracker_key_accepted(&self, tracker_key: &TrackerKey) -> Result<bool, TrackerAPIError> {
+        let announce_url = format!(
+            "{}?info_hash={}&peer_id={KEY_CHECK_PEER_ID}&port=6881&uploaded=0&downloaded=0&left=0&event=stopped&compact=1&numwant=0",
+            self.announce_url_with_key(tracker_key),
+            "%00".repeat(20)
+        );
+
+        let response = self
+            .api_client
+            .announce(&announce_url)
+            .await
+            .map_err(|err| TrackerAPIError::TrackerOffline {
+                error: redact(&err.to_string()),
+            })?;
+
+        let status: StatusCode = map_status_code(response.status());
+
+        let body = response.bytes().await.map_err(|_| {
+            error!(target: "tracker-service", "response without body");
+            TrackerAPIError::MissingResponseBody
+        })?;
+
+        match status {
+            StatusCode::OK => Ok(announce_accepts_key(&body)),
+            _ => {
+                error!(target: "tracker-service", "announce unexpected response: status {status}");
+                Err(TrackerAPIError::UnexpectedResponseStatus)
+            }
+        }
+    }
+
+    /// It revokes the user's tracker keys and issues a new one. The announce
+    /// URLs in the torrent files downloaded from now on include the new key.
+    ///
+    /// # Errors
+    ///
+    /// Will return an error if the tracker API requests fail or the keys can't
+    /// be saved in the database.
+    pub async fn regenerate_tracker_key(&self, user_id: UserId) -> Result<TrackerKey, TrackerAPIError> {
+        if let Some(tracker_key) = self.database.get_user_tracker_key(user_id).await {
+            Self::revoke_tracker_key_in(&self.api_client, &tracker_key).await?;
+        }
+
+        self.database
+            .delete_user_tracker_keys(user_id)
+            .await
+            .map_err(|_| TrackerAPIError::CannotSaveUserKey)?;
+
+        self.retrieve_new_tracker_key(user_id).await
+    }
+
+    /// It removes the key from the tracker. Keys the tracker does not know
+    /// are ignored.
+    async fn revoke_tracker_key_in(api_client: &Client, tracker_key: &TrackerKey) -> Result<(), TrackerAPIError> {
+        let response = api_client
+            .delete_tracker_key(&tracker_key.key)
+            .await
+            .map_err(|err| TrackerAPIError::TrackerOffline {
+                error: redact(&err.to_string()),
+            })?;
+
+        let status: StatusCode = map_status_code(response.status());
+
+        let body = response.text().await.unwrap_or_default();
+
+        match status {
+            StatusCode::OK | StatusCode::NOT_FOUND => Ok(()),
+            StatusCode::INTERNAL_SERVER_ERROR => {
+                if body == Self::invalid_token_body() {
+                    Err(TrackerAPIError::InvalidToken)
+                } else {
+                    error!(target: "tracker-service", "revoke key 500 response: status {status}, body: {}", redact(&body));
+                    Err(TrackerAPIError::InternalServerError)
+                }
+            }
+            _ => {
+                error!(target: "tracker-service", "revoke key unexpected response: status {status}, body: {}", redact(&body));
+                Err(TrackerAPIError::UnexpectedResponseStatus)
+            }
         }
     }
 
@@ -330,7 +658,7 @@ impl Service {
 
         let maybe_response = self.api_client.retrieve_new_tracker_key(self.token_valid_seconds).await;
 
-        debug!(target: "tracker-service", "retrieve key response result: {:?}", maybe_response);
+        debug!(target: "tracker-service", "retrieve key response result: {}", redact(&format!("{maybe_response:?}")));
 
         match maybe_response {
             Ok(response) => {
@@ -344,8 +672,8 @@ impl Service {
                 match status {
                     StatusCode::OK => {
                         // Parse tracker key from response
-                        let tracker_key =
-                            serde_json::from_str(&body).map_err(|_| TrackerAPIError::FailedToParseTrackerResponse { body })?;
+                        let tracker_key = serde_json::from_str(&body)
+                            .map_err(|_| TrackerAPIError::FailedToParseTrackerResponse { body: redact(&body) })?;
 
                         // Add tracker key to database (tied to a user)
                         self.database
@@ -359,23 +687,30 @@ impl Service {
                         if body == Self::invalid_token_body() {
                             Err(TrackerAPIError::InvalidToken)
                         } else {
-                            error!(target: "tracker-service", "retrieve key 500 response: status {status}, body: {body}");
+                            error!(target: "tracker-service", "retrieve key 500 response: status {status}, body: {}", redact(&body));
                             Err(TrackerAPIError::InternalServerError)
                         }
                     }
                     _ => {
-                        error!(target: "tracker-service", " retrieve key unexpected response: status {status}, body: {body}");
+                        error!(target: "tracker-service", " retrieve key unexpected response: status {status}, body: {}", redact(&body));
                         Err(TrackerAPIError::UnexpectedResponseStatus)
                     }
                 }
             }
-            Err(err) => Err(TrackerAPIError::TrackerOffline { error: err.to_string() }),
+            Err(err) => Err(TrackerAPIError::TrackerOffline {
+                error: redact(&err.to_string()),
+            }),
         }
     }
 
     /// It builds the announce url appending the user tracker key.
     /// Eg: <https://tracker:7070/USER_TRACKER_KEY>
-    fn announce_url_with_key(&self, tracker_key: &TrackerKey) -> Url {
+    ///
+    /// # Panics
+    ///
+    /// Will panic if the key can't be added to the tracker URL.
+    #[must_use]
+    pub fn announce_url_with_key(&self, tracker_key: &TrackerKey) -> Url {
         self.tracker_url
             .join(&tracker_key.key)
             .expect("a tracker key should be added to the tracker base URL")
@@ -390,8 +725,160 @@ impl Service {
     }
 }
 
+/// The beginning of the failure reasons the tracker returns when it rejects
+/// the key in an announce request:
+///
+/// - The key is missing or malformed: `Authentication error: ...`.
+/// - The key is unknown or expired: `Tracker error: The supplied key: ...`.
+const KEY_REJECTED_FAILURE_REASONS: [&[u8]; 2] = [b"Authentication error: ", b"Tracker error: The supplied key: "];
+
+/// It checks the tracker did not reject the key in the announce response.
+/// The announce is for an unknown torrent, so other failures, like the
+/// torrent not being whitelisted, are expected.
+fn announce_accepts_key(body: &[u8]) -> bool {
+    let Ok(Value::Dict(response)) = serde_bencode::from_bytes::<Value>(body) else {
+        return false;
+    };
+
+    match response.get(b"failure reason".as_slice()) {
+        Some(Value::Bytes(reason)) => !KEY_REJECTED_FAILURE_REASONS
+            .iter()
+            .any(|rejected| reason.starts_with(rejected)),
+        _ => true,
+    }
+}
+
 /// Temporary patch to map `StatusCode` from crate `http` 0.2.11 to `http` v1.0.0
 /// until `reqwest` upgrades to hyper 1.0. See <https://github.com/seanmonstar/reqwest/issues/2039>
 fn map_status_code(status: reqwest::StatusCode) -> hyper::StatusCode {
     StatusCode::from_u16(status.as_u16()).unwrap()
 }
+
+#[cfg(test)]
+mod tests {
+    use tokio::io::{AsyncReadExt, AsyncWriteExt};
+    use tokio::net::TcpListener;
+    use url::Url;
+
+    use super::{announce_accepts_key, Service, TrackerAPIError};
+    use crate::models::tracker_key::TrackerKey;
+    use crate::tracker::api::{Client, ConnectionInfo};
+
+    const TOKEN: &str = "MyAccessToken";
+
+    const TRACKER_KEY: &str = "mCGfCr8nvixxA0h8B4iz0sT8V3FIQLi7";
+
+    fn client(url: &str) -> Client {
+        Client::new(ConnectionInfo::new(Url::parse(url).unwrap(), TOKEN.to_string())).unwrap()
+    }
+
+    fn tracker_key() -> TrackerKey {
+        TrackerKey {
+            key: TRACKER_KEY.to_string(),
+            valid_until: 0,
+        }
+    }
+
+    /// It starts a fake tracker API that answers one request with the given
+    /// body.
+    async fn fake_tracker_api(body: &'static str) -> String {
+        fake_tracker_api_with_status("200 OK", body).await
+    }
+
+    /// It starts a fake tracker API that answers one request with the given
+    /// status and body.
+    async fn fake_tracker_api_with_status(status: &'static str, body: &'static str) -> String {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let url = format!("http://{}", listener.local_addr().unwrap());
+
+        tokio::spawn(async move {
+            let (mut stream, _) = listener.accept().await.unwrap();
+            let mut request = [0; 1024];
+            let _request_length = stream.read(&mut request).await.unwrap();
+            let response = format!("HTTP/1.1 {status}\r\ncontent-length: {}\r\n\r\n{body}", body.len());
+            stream.write_all(response.as_bytes()).await.unwrap();
+        });
+
+        url
+    }
+
+    #[tokio::test]
+    async fn it_should_not_include_the_tracker_api_token_in_errors_when_the_tracker_is_offline() {
+        let api_client = client("http://127.0.0.1:1");
+
+        let whitelist_error = Service::whitelist_info_hash_in(&api_client, "443c7602b4fde83d1154d6d9da48808418b181b6")
+            .await
+            .unwrap_err();
+
+        let torrents_info_error =
+            Service::get_torrents_info_from(&api_client, &["443c7602b4fde83d1154d6d9da48808418b181b6".to_string()])
+                .await
+                .unwrap_err();
+
+        for error in [whitelist_error, torrents_info_error] {
+            assert!(matches!(error, TrackerAPIError::TrackerOffline { .. }));
+            assert!(!error.to_string().contains(TOKEN));
+            assert!(!format!("{error:?}").contains(TOKEN));
+        }
+    }
+
+    #[tokio::test]
+    async fn it_should_not_include_the_tracker_keys_in_errors_when_the_tracker_response_can_not_be_parsed() {
+        let api_client = client(&fake_tracker_api(r#"{"key": "mCGfCr8nvixxA0h8B4iz0sT8V3FIQLi7", "#).await);
+
+        let error = Service::get_torrents_info_from(&api_client, &["443c7602b4fde83d1154d6d9da48808418b181b6".to_string()])
+            .await
+            .unwrap_err();
+
+        assert!(matches!(error, TrackerAPIError::FailedToParseTrackerResponse { .. }));
+        assert!(!format!("{error:?}").contains(TRACKER_KEY));
+    }
+
+    #[test]
+    fn the_key_should_be_accepted_unless_the_tracker_rejects_it() {
+        assert!(announce_accepts_key(
+            b"d8:completei0e10:incompletei0e8:intervali120e5:peers0:e"
+        ));
+        assert!(announce_accepts_key(
+            b"d14:failure reason49:The torrent: 0000000000000000, is not whitelistede"
+        ));
+        assert!(!announce_accepts_key(
+            b"d14:failure reason38:Authentication error: invalid key: xyze"
+        ));
+        assert!(!announce_accepts_key(
+            b"d14:failure reason74:Tracker error: The supplied key: Key(\"xyz\"), is not valid: Key has expirede"
+        ));
+        assert!(!announce_accepts_key(b"not bencoded"));
+    }
+
+    #[test]
+    fn the_key_should_be_accepted_when_other_failure_reasons_mention_keys() {
+        assert!(announce_accepts_key(
+            b"d14:failure reason83:Tracker error: The torrent: 0000000000000000, is not whitelisted, src/core/key.rs:1e"
+        ));
+    }
+
+    #[tokio::test]
+    async fn it_should_ignore_the_keys_the_tracker_does_not_know_when_revoking_them() {
+        let api_client = client(&fake_tracker_api_with_status("404 Not Found", "").await);
+
+        assert!(Service::revoke_tracker_key_in(&api_client, &tracker_key()).await.is_ok());
+    }
+
+    #[tokio::test]
+    async fn it_should_fail_revoking_a_key_when_the_tracker_response_is_unexpected() {
+        let api_client = client(&fake_tracker_api_with_status("500 Internal Server Error", "failed to delete key").await);
+
+        assert_eq!(
+            Service::revoke_tracker_key_in(&api_client, &tracker_key()).await.unwrap_err(),
+            TrackerAPIError::InternalServerError
+        );
+
+        let api_client = client(&fake_tracker_api_with_status("400 Bad Request", "").await);
+
+        assert_eq!(
+            Service::revoke_tracker_key_in(&api_client, &tracker_key()).await.unwrap_err(),
+            TrackerAPIError::UnexpectedResponseStatus
+        );
+    }
+}
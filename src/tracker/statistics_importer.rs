@@ -1,44 +1,253 @@
-use std::sync::Arc;
+use std::collections::BTreeMap;
+use std::fmt::Write;
+use std::sync::{Arc, Mutex};
 use std::time::Instant;
 
 use chrono::{DateTime, Utc};
+use futures_util::future::join_all;
+use serde::{Serialize, Serializer};
 use text_colorizer::Colorize;
 use tracing::{debug, error, info};
 use url::Url;
 
-use super::service::{Service, TorrentInfo, TrackerAPIError};
+use super::service::{Service, TorrentBasicInfo, TorrentInfo, TrackerAPIError};
 use crate::config::Configuration;
-use crate::databases::database::{self, Database};
+use crate::databases::database::{self, Database, TorrentCompact};
+use crate::models::torrent::TorrentId;
 
 const LOG_TARGET: &str = "Tracker Stats Importer";
 
+/// Name of the persisted cursor of the incremental importer.
+const IMPORTER_CURSOR: &str = "tracker_statistics";
+
+/// It returns the cursor for the next run: the last torrent imported, or the
+/// first torrent when the pass is complete, that is, when there were fewer
+/// torrents left than requested.
+fn next_cursor(torrents: &[TorrentCompact], limit: usize) -> TorrentId {
+    if torrents.len() < limit {
+        return 0;
+    }
+
+    torrents.last().map_or(0, |torrent| torrent.torrent_id)
+}
+
 pub struct StatisticsImporter {
     database: Arc<Box<dyn Database>>,
     tracker_service: Arc<Service>,
     tracker_url: Url,
+    /// Number of torrents requested to the tracker API at once.
+    batch_size: usize,
+    /// Number of batches requested to the tracker API at the same time.
+    concurrency: usize,
+    status: Mutex<ImporterStatus>,
+}
+
+/// Health of the importer since the application started.
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+pub struct ImporterStatus {
+    /// When the importer ran for the last time.
+    #[serde(serialize_with = "serialize_optional_datetime")]
+    pub last_run_at: Option<DateTime<Utc>>,
+    /// When the importer ran for the last time without errors.
+    #[serde(serialize_with = "serialize_optional_datetime")]
+    pub last_successful_run_at: Option<DateTime<Utc>>,
+    pub runs: u64,
+    pub failed_runs: u64,
+    /// Number of failed runs since the last successful run.
+    pub consecutive_failures: u32,
+    /// Number of torrents whose statistics have been updated.
+    pub torrents_updated: u64,
+    /// Number of errors by error type, for example `tracker_offline`.
+    pub failures_by_error: BTreeMap<String, u64>,
+    pub last_error: Option<String>,
+}
+
+impl ImporterStatus {
+    fn record_success(&mut self, torrents_updated: u64) {
+        let now = Utc::now();
+        self.last_run_at = Some(now);
+        self.last_successful_run_at = Some(now);
+        self.runs += 1;
+        self.consecutive_failures = 0;
+        self.torrents_updated += torrents_updated;
+    }
+
+    fn record_failure(&mut self, error_type: &str, error: String) {
+        self.last_run_at = Some(Utc::now());
+        self.runs += 1;
+        self.failed_runs += 1;
+        self.consecutive_failures += 1;
+        self.record_error(error_type, error);
+    }
+
+    /// It records an error that does not make the whole run fail.
+    fn record_error(&mut self, error_type: &str, error: String) {
+        *self.failures_by_error.entry(error_type.to_string()).or_insert(0) += 1;
+        self.last_error = Some(error);
+    }
+
+    /// It renders the status in the Prometheus text exposition format.
+    #[must_use]
+    pub fn to_prometheus(&self) -> String {
+        let mut metrics = String::new();
+
+        let mut add = |name: &str, metric_type: &str, help: &str, samples: Vec<(String, String)>| {
+            let _ = writeln!(metrics, "# HELP {name} {help}");
+            let _ = writeln!(metrics, "# TYPE {name} {metric_type}");
+            for (labels, value) in samples {
+                let _ = writeln!(metrics, "{name}{labels} {value}");
+            }
+        };
+
+        let timestamp = |datetime: Option<DateTime<Utc>>| datetime.map_or(0, |datetime| datetime.timestamp()).to_string();
+
+        add(
+            "torrust_index_stats_importer_runs_total",
+            "counter",
+            "Number of tracker statistics importer runs.",
+            vec![(String::new(), self.runs.to_string())],
+        );
+        add(
+            "torrust_index_stats_importer_failed_runs_total",
+            "counter",
+            "Number of failed tracker statistics importer runs.",
+            vec![(String::new(), self.failed_runs.to_string())],
+        );
+        add(
+            "torrust_index_stats_importer_consecutive_failures",
+            "gauge",
+            "Number of failed runs since the last successful run.",
+            vec![(String::new(), self.consecutive_failures.to_string())],
+        );
+        add(
+            "torrust_index_stats_importer_torrents_updated_total",
+            "counter",
+            "Number of torrents whose statistics have been updated.",
+            vec![(String::new(), self.torrents_updated.to_string())],
+        );
+        add(
+            "torrust_index_stats_importer_last_run_timestamp_seconds",
+            "gauge",
+            "Unix timestamp of the last run.",
+            vec![(String::new(), timestamp(self.last_run_at))],
+        );
+        add(
+            "torrust_index_stats_importer_last_success_timestamp_seconds",
+            "gauge",
+            "Unix timestamp of the last successful run.",
+            vec![(String::new(), timestamp(self.last_successful_run_at))],
+        );
+        add(
+            "torrust_index_stats_importer_errors_total",
+            "counter",
+            "Number of importer errors by error type.",
+            self.failures_by_error
+                .iter()
+                .map(|(error_type, count)| (format!("{{error=\"{error_type}\"}}"), count.to_string()))
+                .collect(),
+        );
+
+        metrics
+    }
+}
+
+#[allow(clippy::ref_option)]
+fn serialize_optional_datetime<S: Serializer>(datetime: &Option<DateTime<Utc>>, serializer: S) -> Result<S::Ok, S::Error> {
+    match datetime {
+        Some(datetime) => serializer.serialize_some(&datetime.to_rfc3339()),
+        None => serializer.serialize_none(),
+    }
+}
+
+/// It returns the error type label used in the importer status.
+fn error_type(error: &TrackerAPIError) -> &'static str {
+    match error {
+        TrackerAPIError::TrackerOffline { .. } => "tracker_offline",
+        TrackerAPIError::InvalidToken => "invalid_token",
+        TrackerAPIError::InternalServerError => "internal_server_error",
+        TrackerAPIError::NotFound => "not_found",
+        TrackerAPIError::UnexpectedResponseStatus => "unexpected_response_status",
+        TrackerAPIError::CannotSaveUserKey => "cannot_save_user_key",
+        TrackerAPIError::TorrentNotFound => "torrent_not_found",
+        TrackerAPIError::MissingResponseBody => "missing_response_body",
+        TrackerAPIError::FailedToParseTrackerResponse { .. } => "failed_to_parse_tracker_response",
+    }
+}
+
+/// Error type label for database errors.
+const DATABASE_ERROR: &str = "database";
+
+/// It aggregates the seeders and leechers of a torrent in the main tracker
+/// with the ones in the backup trackers. Peers usually announce to all the
+/// trackers in the torrent, so it takes the highest counts instead of the
+/// sum, which would count the same peers more than once.
+pub(crate) fn aggregate_statistics(
+    info_hash: &str,
+    statistics: (i64, i64),
+    backup_torrents_info: &[Vec<TorrentBasicInfo>],
+) -> (i64, i64) {
+    backup_torrents_info
+        .iter()
+        .flatten()
+        .filter(|torrent_info| torrent_info.info_hash == info_hash)
+        .fold(statistics, |(seeders, leechers), torrent_info| {
+            (seeders.max(torrent_info.seeders), leechers.max(torrent_info.leechers))
+        })
 }
 
 impl StatisticsImporter {
     pub async fn new(cfg: Arc<Configuration>, tracker_service: Arc<Service>, database: Arc<Box<dyn Database>>) -> Self {
         let settings = cfg.settings.read().await;
         let tracker_url = settings.tracker.url.clone();
+        let batch_size = settings.tracker_statistics_importer.batch_size.max(1) as usize;
+        let concurrency = usize::from(settings.tracker_statistics_importer.concurrency.max(1));
         drop(settings);
         Self {
             database,
             tracker_service,
             tracker_url,
+            batch_size,
+            concurrency,
+            status: Mutex::new(ImporterStatus::default()),
         }
     }
 
+    /// It returns the current importer status.
+    ///
+    /// # Panics
+    ///
+    /// Will panic if the status lock is poisoned.
+    #[must_use]
+    pub fn status(&self) -> ImporterStatus {
+        self.status
+            .lock()
+            .expect("importer status lock should not be poisoned")
+            .clone()
+    }
+
+    fn update_status<F: FnOnce(&mut ImporterStatus)>(&self, update: F) {
+        update(&mut self.status.lock().expect("importer status lock should not be poisoned"));
+    }
+
     /// Import torrents statistics from tracker and update them in database.
     ///
+    /// The torrents are requested to the tracker API in batches of
+    /// `batch_size` torrents.
+    ///
     /// # Errors
     ///
     /// Will return an error if the database query failed.
     pub async fn import_all_torrents_statistics(&self) -> Result<(), database::Error> {
-        let torrents = self.database.get_all_torrents_compact().await?;
+        let torrents = match self.database.get_all_torrents_compact().await {
+            Ok(torrents) => torrents,
+            Err(err) => {
+                self.update_status(|status| status.record_failure(DATABASE_ERROR, format!("{err:?}")));
+                return Err(err);
+            }
+        };
 
         if torrents.is_empty() {
+            self.update_status(|status| status.record_success(0));
             return Ok(());
         }
 
@@ -47,23 +256,24 @@ impl StatisticsImporter {
         // Start the timer before the loop
         let start_time = Instant::now();
 
-        for torrent in torrents {
-            info!(target: LOG_TARGET, "Importing torrent #{} statistics ...", torrent.torrent_id.to_string().yellow());
+        let mut torrents_updated = 0;
 
-            let ret = self.import_torrent_statistics(torrent.torrent_id, &torrent.info_hash).await;
+        for batch in torrents.chunks(self.batch_size) {
+            info!(target: LOG_TARGET, "Importing statistics of torrents #{} to #{} ...", batch[0].torrent_id.to_string().yellow(), batch[batch.len() - 1].torrent_id.to_string().yellow());
 
-            if let Some(err) = ret.err() {
-                if err != TrackerAPIError::TorrentNotFound {
-                    let message = format!(
-                        "Error updating torrent tracker stats for torrent. Torrent: id {}; infohash {}. Error: {:?}",
-                        torrent.torrent_id, torrent.info_hash, err
-                    );
+            match self.import_batch(batch).await {
+                Ok(updated) => torrents_updated += updated,
+                Err(err) => {
+                    let message = format!("Error getting torrents tracker stats. Error: {err:?}");
                     error!(target: "statistics_importer", "{}", message);
+                    self.update_status(|status| status.record_error(error_type(&err), message));
                     // todo: return a service error that can be a tracker API error or a database error.
                 }
             }
         }
 
+        self.update_status(|status| status.record_success(torrents_updated));
+
         let elapsed_time = start_time.elapsed();
 
         info!(target: LOG_TARGET, "Statistics import completed in {:.2?}", elapsed_time);
@@ -71,83 +281,133 @@ impl StatisticsImporter {
         Ok(())
     }
 
-    /// Import torrents statistics not updated recently..
+    /// Import the statistics of the next torrents not updated recently.
+    ///
+    /// The importer walks the torrents by ID. A persisted cursor keeps the
+    /// last torrent imported, so each run continues where the previous one
+    /// stopped, even after a restart. Torrents whose statistics were updated
+    /// since `datetime`, for example, when they were uploaded, are skipped.
+    /// When there are no more torrents, the cursor goes back to the first
+    /// torrent for the next pass.
+    ///
+    /// Each run imports up to `concurrency` batches of `batch_size` torrents
+    /// at the same time. The cursor does not move if a batch fails, so the
+    /// same torrents are imported again in the next run.
     ///
     /// # Errors
     ///
     /// Will return an error if the database query failed.
-    pub async fn import_torrents_statistics_not_updated_since(
-        &self,
-        datetime: DateTime<Utc>,
-        limit: i64,
-    ) -> Result<(), database::Error> {
-        debug!(target: LOG_TARGET, "Importing torrents statistics not updated since {} limited to a maximum of {} torrents ...", datetime.to_string().yellow(), limit.to_string().yellow());
-
-        let torrents = self
+    pub async fn import_next_batches(&self, datetime: DateTime<Utc>) -> Result<(), database::Error> {
+        let cursor = match self.database.get_importer_cursor(IMPORTER_CURSOR).await {
+            Ok(cursor) => cursor,
+            Err(err) => {
+                self.update_status(|status| status.record_failure(DATABASE_ERROR, format!("{err:?}")));
+                return Err(err);
+            }
+        };
+
+        let limit = self.batch_size * self.concurrency;
+
+        debug!(target: LOG_TARGET, "Importing torrents statistics not updated since {} after torrent #{} limited to a maximum of {} torrents ...", datetime.to_string().yellow(), cursor.to_string().yellow(), limit.to_string().yellow());
+
+        let torrents = match self
             .database
-            .get_torrents_with_stats_not_updated_since(datetime, limit)
-            .await?;
+            .get_torrents_with_stats_not_updated_since(cursor, datetime, i64::try_from(limit).unwrap_or(i64::MAX))
+            .await
+        {
+            Ok(torrents) => torrents,
+            Err(err) => {
+                self.update_status(|status| status.record_failure(DATABASE_ERROR, format!("{err:?}")));
+                return Err(err);
+            }
+        };
+
+        let next_cursor = next_cursor(&torrents, limit);
 
         if torrents.is_empty() {
+            if cursor != 0 {
+                self.database.update_importer_cursor(IMPORTER_CURSOR, next_cursor).await?;
+            }
+            self.update_status(|status| status.record_success(0));
             return Ok(());
         }
 
         info!(target: LOG_TARGET, "Importing {} torrents statistics from tracker {} ...", torrents.len().to_string().yellow(), self.tracker_url.to_string().yellow());
 
-        // Import stats for all torrents in one request
+        let results = join_all(torrents.chunks(self.batch_size).map(|batch| self.import_batch(batch))).await;
+
+        let torrents_updated: u64 = results.iter().filter_map(|result| result.as_ref().ok()).sum();
 
+        if let Some(err) = results.into_iter().find_map(Result::err) {
+            let message = format!("Error getting torrents tracker stats. Error: {err:?}");
+            error!(target: LOG_TARGET, "{}", message);
+            self.update_status(|status| {
+                status.torrents_updated += torrents_updated;
+                status.record_failure(error_type(&err), message);
+            });
+            // todo: return a service error that can be a tracker API error or a database error.
+            return Ok(());
+        }
+
+        self.database.update_importer_cursor(IMPORTER_CURSOR, next_cursor).await?;
+
+        self.update_status(|status| status.record_success(torrents_updated));
+
+        Ok(())
+    }
+
+    /// Import the statistics of a batch of torrents in one request to each
+    /// tracker. It returns the number of torrents updated.
+    async fn import_batch(&self, torrents: &[TorrentCompact]) -> Result<u64, TrackerAPIError> {
         let info_hashes: Vec<String> = torrents.iter().map(|t| t.info_hash.clone()).collect();
 
-        let torrent_info_vec = match self.tracker_service.get_torrents_info(&info_hashes).await {
-            Ok(torrents_info) => torrents_info,
-            Err(err) => {
-                let message = format!("Error getting torrents tracker stats. Error: {err:?}");
-                error!(target: LOG_TARGET, "{}", message);
-                // todo: return a service error that can be a tracker API error or a database error.
-                return Ok(());
-            }
-        };
+        let torrent_info_vec = self.tracker_service.get_torrents_info(&info_hashes).await?;
+
+        let backup_torrents_info = self.tracker_service.get_backup_torrents_info(&info_hashes).await;
 
-        // Update stats for all torrents
+        let mut torrents_updated = 0;
 
         for torrent in torrents {
-            match torrent_info_vec.iter().find(|t| t.info_hash == torrent.info_hash) {
-                None => {
-                    // No stats for this torrent in the tracker
-                    drop(
-                        self.database
-                            .update_tracker_info(torrent.torrent_id, &self.tracker_url, 0, 0)
-                            .await,
-                    );
-                }
-                Some(torrent_info) => {
-                    // Update torrent stats for this tracker
-                    drop(
-                        self.database
-                            .update_tracker_info(
-                                torrent.torrent_id,
-                                &self.tracker_url,
-                                torrent_info.seeders,
-                                torrent_info.leechers,
-                            )
-                            .await,
-                    );
-                }
+            // No stats for this torrent in the main tracker
+            let statistics = torrent_info_vec
+                .iter()
+                .find(|t| t.info_hash == torrent.info_hash)
+                .map_or((0, 0), |torrent_info| (torrent_info.seeders, torrent_info.leechers));
+
+            let (seeders, leechers) = aggregate_statistics(&torrent.info_hash, statistics, &backup_torrents_info);
+
+            let result = self
+                .database
+                .update_tracker_info(torrent.torrent_id, &self.tracker_url, seeders, leechers)
+                .await;
+
+            if result.is_ok() {
+                torrents_updated += 1;
             }
         }
 
-        Ok(())
+        Ok(torrents_updated)
     }
 
     /// Import torrent statistics from tracker and update them in database.
+    /// The seeders and leechers are aggregated with the ones in the backup
+    /// trackers, if any.
     ///
     /// # Errors
     ///
-    /// Will return an error if the HTTP request failed or the torrent is not
-    /// found.
+    /// Will return an error if the HTTP request to the main tracker failed or
+    /// the torrent is not found.
     pub async fn import_torrent_statistics(&self, torrent_id: i64, info_hash: &str) -> Result<TorrentInfo, TrackerAPIError> {
         match self.tracker_service.get_torrent_info(info_hash).await {
-            Ok(torrent_info) => {
+            Ok(mut torrent_info) => {
+                let backup_torrents_info = self.tracker_service.get_backup_torrents_info(&[info_hash.to_string()]).await;
+
+                (torrent_info.seeders, torrent_info.leechers) = aggregate_statistics(
+                    info_hash,
+                    (torrent_info.seeders, torrent_info.leechers),
+                    &backup_torrents_info,
+                );
+
                 drop(
                     self.database
                         .update_tracker_info(torrent_id, &self.tracker_url, torrent_info.seeders, torrent_info.leechers)
@@ -162,3 +422,81 @@ impl StatisticsImporter {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::{aggregate_statistics, next_cursor, ImporterStatus};
+    use crate::databases::database::TorrentCompact;
+    use crate::tracker::service::TorrentBasicInfo;
+
+    fn torrent_info(info_hash: &str, seeders: i64, leechers: i64) -> TorrentBasicInfo {
+        TorrentBasicInfo {
+            info_hash: info_hash.to_string(),
+            seeders,
+            completed: 0,
+            leechers,
+        }
+    }
+
+    #[test]
+    fn it_should_aggregate_the_statistics_of_the_backup_trackers_with_the_highest_counts() {
+        let info_hash = "443c7602b4fde83d1154d6d9da48808418b181b6";
+
+        let backup_torrents_info = vec![
+            vec![torrent_info(info_hash, 7, 1), torrent_info("other", 100, 100)],
+            vec![torrent_info(info_hash, 2, 4)],
+        ];
+
+        assert_eq!(aggregate_statistics(info_hash, (5, 3), &backup_torrents_info), (7, 4));
+        assert_eq!(aggregate_statistics(info_hash, (5, 3), &[]), (5, 3));
+    }
+
+    #[test]
+    fn it_should_move_the_cursor_to_the_last_torrent_imported_until_the_pass_is_complete() {
+        let torrents: Vec<TorrentCompact> = [3, 7, 8]
+            .into_iter()
+            .map(|torrent_id| TorrentCompact {
+                torrent_id,
+                info_hash: format!("{torrent_id:040}"),
+            })
+            .collect();
+
+        assert_eq!(next_cursor(&torrents, 3), 8);
+        assert_eq!(next_cursor(&torrents, 4), 0);
+        assert_eq!(next_cursor(&[], 3), 0);
+    }
+
+    #[test]
+    fn it_should_reset_the_consecutive_failures_after_a_successful_run() {
+        let mut status = ImporterStatus::default();
+
+        status.record_failure("tracker_offline", "Tracker offline".to_string());
+        status.record_failure("tracker_offline", "Tracker offline".to_string());
+
+        assert_eq!(status.consecutive_failures, 2);
+        assert_eq!(status.failures_by_error.get("tracker_offline"), Some(&2));
+        assert!(status.last_successful_run_at.is_none());
+
+        status.record_success(10);
+
+        assert_eq!(status.consecutive_failures, 0);
+        assert_eq!(status.runs, 3);
+        assert_eq!(status.failed_runs, 2);
+        assert_eq!(status.torrents_updated, 10);
+        assert!(status.last_successful_run_at.is_some());
+    }
+
+    #[test]
+    fn it_should_render_the_status_as_prometheus_metrics() {
+        let mut status = ImporterStatus::default();
+
+        status.record_failure("invalid_token", "Invalid token".to_string());
+
+        let metrics = status.to_prometheus();
+
+        assert!(metrics
+            .contains("# TYPE torrust_index_stats_importer_runs_total counter\ntorrust_index_stats_importer_runs_total 1\n"));
+        assert!(metrics.contains("torrust_index_stats_importer_errors_total{error=\"invalid_token\"} 1\n"));
+        assert!(metrics.contains("torrust_index_stats_importer_last_success_timestamp_seconds 0\n"));
+    }
+}
@@ -0,0 +1,108 @@
+//! Lowercase [RFC 4648](https://datatracker.ietf.org/doc/html/rfc4648#section-6)
+//! base32 encoding without padding.
+//!
+//! It's used for the short torrent URLs: a 20-byte info-hash is 32
+//! characters long in base32 instead of 40 in hex.
+use bittorrent_primitives::info_hash::InfoHash;
+
+const ALPHABET: &[u8; 32] = b"abcdefghijklmnopqrstuvwxyz234567";
+
+#[must_use]
+pub fn encode(bytes: &[u8]) -> String {
+    let mut encoded = String::with_capacity((bytes.len() * 8 + 4) / 5);
+
+    let mut buffer: u16 = 0;
+    let mut bits = 0;
+
+    for byte in bytes {
+        buffer = (buffer << 8) | u16::from(*byte);
+        bits += 8;
+
+        while bits >= 5 {
+            bits -= 5;
+            encoded.push(char::from(ALPHABET[usize::from((buffer >> bits) & 0x1f)]));
+        }
+    }
+
+    if bits > 0 {
+        encoded.push(char::from(ALPHABET[usize::from((buffer << (5 - bits)) & 0x1f)]));
+    }
+
+    encoded
+}
+
+/// It decodes a base32 string. It's case-insensitive.
+///
+/// It returns `None` if the string contains characters outside the alphabet.
+#[must_use]
+pub fn decode(encoded: &str) -> Option<Vec<u8>> {
+    let mut decoded = Vec::with_capacity(encoded.len() * 5 / 8);
+
+    let mut buffer: u16 = 0;
+    let mut bits = 0;
+
+    for c in encoded.bytes() {
+        let value = ALPHABET.iter().position(|a| *a == c.to_ascii_lowercase())?;
+
+        buffer = (buffer << 5) | u16::try_from(value).ok()?;
+        bits += 5;
+
+        if bits >= 8 {
+            bits -= 8;
+            decoded.push(u8::try_from((buffer >> bits) & 0xff).ok()?);
+        }
+    }
+
+    Some(decoded)
+}
+
+/// The short (base32) form of an info-hash.
+#[must_use]
+pub fn encode_info_hash(info_hash: &InfoHash) -> String {
+    encode(&info_hash.bytes())
+}
+
+/// It parses the short (base32) form of an info-hash.
+#[must_use]
+pub fn decode_info_hash(encoded: &str) -> Option<InfoHash> {
+    let bytes: [u8; 20] = decode(encoded)?.try_into().ok()?;
+
+    Some(InfoHash::from_bytes(&bytes))
+}
+
+#[cfg(test)]
+mod tests {
+    use std::str::FromStr;
+
+    use bittorrent_primitives::info_hash::InfoHash;
+
+    use super::{decode, decode_info_hash, encode, encode_info_hash};
+
+    #[test]
+    fn it_should_encode_the_rfc_4648_test_vectors() {
+        assert_eq!(encode(b""), "");
+        assert_eq!(encode(b"f"), "my");
+        assert_eq!(encode(b"fo"), "mzxq");
+        assert_eq!(encode(b"foo"), "mzxw6");
+        assert_eq!(encode(b"foob"), "mzxw6yq");
+        assert_eq!(encode(b"fooba"), "mzxw6ytb");
+        assert_eq!(encode(b"foobar"), "mzxw6ytboi");
+    }
+
+    #[test]
+    fn it_should_decode_case_insensitively() {
+        assert_eq!(decode("MZXW6YTBOI"), Some(b"foobar".to_vec()));
+        assert_eq!(decode("mzxw6!"), None);
+    }
+
+    #[test]
+    fn it_should_encode_info_hashes_in_32_characters() {
+        let info_hash = InfoHash::from_str("5452869be36f9f3350ccee6b4544e7e76caaadab").unwrap();
+
+        let encoded = encode_info_hash(&info_hash);
+
+        assert_eq!(encoded, "krjing7dn6ptgugm5zvukrhh45wkvlnl");
+        assert_eq!(decode_info_hash(&encoded), Some(info_hash));
+        assert_eq!(decode_info_hash(&encoded[..31]), None);
+    }
+}
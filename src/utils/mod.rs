@@ -1,4 +1,8 @@
+pub mod base32;
 pub mod clock;
 pub mod hex;
 pub mod parse_torrent;
+pub mod qr_code;
+pub mod redaction;
+pub mod trace_context;
 pub mod validation;
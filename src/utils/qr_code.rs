@@ -0,0 +1,65 @@
+//! QR code images.
+use std::io::Cursor;
+
+use image::{ImageFormat, Luma};
+use qrcode::types::QrError;
+use qrcode::QrCode;
+use thiserror::Error;
+
+/// Minimum width and height of the images in pixels.
+const MIN_IMAGE_SIZE: u32 = 256;
+
+#[derive(Error, Debug, PartialEq, Eq)]
+pub enum Error {
+    #[error("the data is too long to fit in a QR code")]
+    DataTooLong,
+
+    #[error("unable to encode the QR code: {0}")]
+    Encoding(String),
+
+    #[error("unable to write the QR code image: {0}")]
+    Image(String),
+}
+
+/// It renders the data as a QR code in a PNG image.
+///
+/// # Errors
+///
+/// It returns an error if the data does not fit in a QR code or the image
+/// can't be encoded.
+pub fn png(data: &[u8]) -> Result<Vec<u8>, Error> {
+    let code = QrCode::new(data).map_err(|e| match e {
+        QrError::DataTooLong => Error::DataTooLong,
+        e => Error::Encoding(e.to_string()),
+    })?;
+
+    let image = code
+        .render::<Luma<u8>>()
+        .min_dimensions(MIN_IMAGE_SIZE, MIN_IMAGE_SIZE)
+        .build();
+
+    let mut png = Cursor::new(Vec::new());
+
+    image
+        .write_to(&mut png, ImageFormat::Png)
+        .map_err(|e| Error::Image(e.to_string()))?;
+
+    Ok(png.into_inner())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{png, Error};
+
+    #[test]
+    fn it_should_render_the_qr_code_as_a_png_image() {
+        let image = png(b"magnet:?xt=urn:btih:5452869be36f9f3350ccee6b4544e7e76caaadab").unwrap();
+
+        assert!(image.starts_with(b"\x89PNG\r\n\x1a\n"));
+    }
+
+    #[test]
+    fn it_should_fail_when_the_data_does_not_fit_in_a_qr_code() {
+        assert_eq!(png(&[b'a'; 8000]), Err(Error::DataTooLong));
+    }
+}
@@ -0,0 +1,90 @@
+//! Redaction of secrets in logs and error messages.
+//!
+//! The tracker API token, the users' tracker keys (passkeys) and the
+//! personalized announce URLs that contain them must never be written to the
+//! logs or returned in API error bodies. Errors from the HTTP client, for
+//! example, include the request URL with the `token` query parameter.
+//!
+//! The secrets are replaced with [`REDACTED`]:
+//!
+//! ```text
+//! http://tracker:1212/api/v1/torrents?token=MyAccessToken -> http://tracker:1212/api/v1/torrents?token=***
+//! udp://tracker:6969/mCGfCr8nvixxA0h8B4iz0sT8V3FIQLi7     -> udp://tracker:6969/***
+//! {"key":"mCGfCr8nvixxA0h8B4iz0sT8V3FIQLi7"}               -> {"key":"***"}
+//! ```
+use lazy_static::lazy_static;
+use regex::Regex;
+
+/// The text secrets are replaced with.
+pub const REDACTED: &str = "***";
+
+lazy_static! {
+    /// Query params with secrets. Eg: `?token=MyAccessToken`.
+    static ref QUERY_PARAM_REGEX: Regex =
+        Regex::new(r"(?i)\b(?P<name>token|key|passkey)=[^&\s'\x22<>)]+").expect("query param regex should be valid");
+
+    /// JSON fields with secrets. Eg: `"key": "mCGfCr8nvixxA0h8B4iz0sT8V3FIQLi7"`.
+    static ref JSON_FIELD_REGEX: Regex = Regex::new(r#"(?i)"(?P<name>token|key|passkey)"(?P<separator>\s*:\s*)"[^"]*""#)
+        .expect("JSON field regex should be valid");
+
+    /// Tracker keys in the path of personalized announce URLs. Tracker keys
+    /// are 32 alphanumeric characters long. Eg: `udp://tracker:6969/mCGfCr8nvixxA0h8B4iz0sT8V3FIQLi7`.
+    static ref ANNOUNCE_KEY_REGEX: Regex =
+        Regex::new(r"(?P<url>[a-z][a-z0-9+.-]*://[^\s/'\x22<>]+/(?:[^\s/?#'\x22<>]+/)*)[A-Za-z0-9]{32}\b")
+            .expect("announce key regex should be valid");
+}
+
+/// It replaces the tracker tokens and keys in a text, usually a log or error
+/// message, with [`REDACTED`].
+#[must_use]
+pub fn redact(text: &str) -> String {
+    let text = QUERY_PARAM_REGEX.replace_all(text, format!("${{name}}={REDACTED}"));
+    let text = JSON_FIELD_REGEX.replace_all(&text, format!("\"${{name}}\"${{separator}}\"{REDACTED}\""));
+    let text = ANNOUNCE_KEY_REGEX.replace_all(&text, format!("${{url}}{REDACTED}"));
+
+    text.into_owned()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::redact;
+
+    #[test]
+    fn it_should_redact_the_tracker_api_token_in_urls() {
+        assert_eq!(
+            redact("error sending request for url (http://127.0.0.1:1212/api/v1/torrents?token=MyAccessToken&info_hash=443c7602b4fde83d1154d6d9da48808418b181b6)"),
+            "error sending request for url (http://127.0.0.1:1212/api/v1/torrents?token=***&info_hash=443c7602b4fde83d1154d6d9da48808418b181b6)"
+        );
+    }
+
+    #[test]
+    fn it_should_redact_the_tracker_keys_in_personalized_announce_urls() {
+        assert_eq!(
+            redact("announce url: udp://tracker:6969/mCGfCr8nvixxA0h8B4iz0sT8V3FIQLi7"),
+            "announce url: udp://tracker:6969/***"
+        );
+        assert_eq!(
+            redact("http://tracker:7070/announce/mCGfCr8nvixxA0h8B4iz0sT8V3FIQLi7?info_hash=x"),
+            "http://tracker:7070/announce/***?info_hash=x"
+        );
+        assert_eq!(
+            redact("http://tracker:7070/announce?passkey=mCGfCr8nvixxA0h8B4iz0sT8V3FIQLi7"),
+            "http://tracker:7070/announce?passkey=***"
+        );
+    }
+
+    #[test]
+    fn it_should_redact_the_tracker_keys_in_json_bodies() {
+        assert_eq!(
+            redact(r#"{"key": "mCGfCr8nvixxA0h8B4iz0sT8V3FIQLi7", "valid_until": 1702652181}"#),
+            r#"{"key": "***", "valid_until": 1702652181}"#
+        );
+    }
+
+    #[test]
+    fn it_should_not_change_texts_without_secrets() {
+        let text = "get torrent info from http://tracker:1212/api/v1/torrent/443c7602b4fde83d1154d6d9da48808418b181b6";
+
+        assert_eq!(redact(text), text);
+    }
+}
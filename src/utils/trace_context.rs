@@ -0,0 +1,234 @@
+//! [W3C Trace Context](https://www.w3.org/TR/trace-context/) propagation.
+//!
+//! The API accepts the `traceparent` and `tracestate` headers. The trace
+//! context of the request is available for the whole request handling, so
+//! outbound calls (tracker API, webhooks) can forward it:
+//!
+//! ```text
+//! traceparent: 00-4bf92f3577b34da6a3ce929d0e0e4736-00f067aa0ba902b7-01
+//! ```
+//!
+//! When the request does not contain a valid `traceparent` header a new
+//! trace is started.
+use std::future::Future;
+
+use rand::Rng;
+
+pub const TRACEPARENT: &str = "traceparent";
+pub const TRACESTATE: &str = "tracestate";
+
+/// Max length of the `tracestate` header accepted and propagated.
+const MAX_TRACESTATE_LENGTH: usize = 512;
+
+const SUPPORTED_VERSION: &str = "00";
+
+const SAMPLED_FLAG: u8 = 0x01;
+
+tokio::task_local! {
+    static CURRENT: TraceContext;
+}
+
+/// The trace context of the span handled by the index.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TraceContext {
+    pub trace_id: [u8; 16],
+    /// The ID of the index span.
+    pub span_id: [u8; 8],
+    /// The ID of the caller span, if the trace was started by the caller.
+    pub parent_span_id: Option<[u8; 8]>,
+    pub flags: u8,
+    /// Vendor-specific data. It's propagated without changes.
+    pub tracestate: Option<String>,
+}
+
+impl TraceContext {
+    /// Starts a new trace.
+    #[must_use]
+    pub fn new_root() -> Self {
+        Self {
+            trace_id: random_id(),
+            span_id: random_id(),
+            parent_span_id: None,
+            flags: SAMPLED_FLAG,
+            tracestate: None,
+        }
+    }
+
+    /// Continues the trace from the caller headers. It returns `None` if the
+    /// `traceparent` header is not valid.
+    #[must_use]
+    pub fn from_headers(traceparent: &str, tracestate: Option<&str>) -> Option<Self> {
+        let mut parts = traceparent.trim().split('-');
+
+        let version = parts.next()?;
+        let trace_id = parts.next()?;
+        let parent_span_id = parts.next()?;
+        let flags = parts.next()?;
+
+        // Future versions can append fields, but version `00` can't.
+        if version.len() != 2 || version == "ff" || (version == SUPPORTED_VERSION && parts.next().is_some()) {
+            return None;
+        }
+        if ![version, trace_id, parent_span_id, flags]
+            .iter()
+            .all(|field| is_lowercase_hex(field))
+        {
+            return None;
+        }
+
+        let trace_id: [u8; 16] = hex::decode(trace_id).ok()?.try_into().ok()?;
+        let parent_span_id: [u8; 8] = hex::decode(parent_span_id).ok()?.try_into().ok()?;
+        let flags = *hex::decode(flags).ok()?.first().filter(|_| flags.len() == 2)?;
+
+        if trace_id == [0; 16] || parent_span_id == [0; 8] {
+            return None;
+        }
+
+        Some(Self {
+            trace_id,
+            span_id: random_id(),
+            parent_span_id: Some(parent_span_id),
+            flags,
+            tracestate: tracestate
+                .map(str::trim)
+                .filter(|tracestate| !tracestate.is_empty() && tracestate.len() <= MAX_TRACESTATE_LENGTH)
+                .map(ToString::to_string),
+        })
+    }
+
+    /// The context of the request being handled, if any.
+    #[must_use]
+    pub fn current() -> Option<Self> {
+        CURRENT.try_with(Clone::clone).ok()
+    }
+
+    /// Runs the future with this context as the current one.
+    pub async fn scope<F: Future>(self, future: F) -> F::Output {
+        CURRENT.scope(self, future).await
+    }
+
+    #[must_use]
+    pub fn trace_id_hex(&self) -> String {
+        hex::encode(self.trace_id)
+    }
+
+    #[must_use]
+    pub fn span_id_hex(&self) -> String {
+        hex::encode(self.span_id)
+    }
+
+    #[must_use]
+    pub fn parent_span_id_hex(&self) -> String {
+        self.parent_span_id.map(hex::encode).unwrap_or_default()
+    }
+
+    /// The `traceparent` header for outbound calls. The index span is the
+    /// parent of the callee span.
+    #[must_use]
+    pub fn traceparent(&self) -> String {
+        format!(
+            "{SUPPORTED_VERSION}-{}-{}-{:02x}",
+            self.trace_id_hex(),
+            self.span_id_hex(),
+            self.flags
+        )
+    }
+}
+
+/// A random trace ID (16 bytes) or span ID (8 bytes). IDs can't be all zeros.
+#[must_use]
+pub fn random_id<const N: usize>() -> [u8; N] {
+    let mut id = [0u8; N];
+    while id == [0u8; N] {
+        rand::thread_rng().fill(&mut id[..]);
+    }
+    id
+}
+
+fn is_lowercase_hex(value: &str) -> bool {
+    value.chars().all(|char| matches!(char, '0'..='9' | 'a'..='f'))
+}
+
+/// Adds the trace context headers of the current request to outbound HTTP
+/// requests.
+pub trait PropagateTraceContext {
+    #[must_use]
+    fn with_trace_context(self) -> Self;
+}
+
+impl PropagateTraceContext for reqwest::RequestBuilder {
+    fn with_trace_context(self) -> Self {
+        let Some(trace_context) = TraceContext::current() else {
+            return self;
+        };
+
+        let request = self.header(TRACEPARENT, trace_context.traceparent());
+
+        match &trace_context.tracestate {
+            Some(tracestate) => request.header(TRACESTATE, tracestate),
+            None => request,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::TraceContext;
+
+    const TRACEPARENT: &str = "00-4bf92f3577b34da6a3ce929d0e0e4736-00f067aa0ba902b7-01";
+
+    #[test]
+    fn it_should_continue_the_trace_from_a_valid_traceparent_header() {
+        let trace_context = TraceContext::from_headers(TRACEPARENT, Some("vendor=value")).unwrap();
+
+        assert_eq!(trace_context.trace_id_hex(), "4bf92f3577b34da6a3ce929d0e0e4736");
+        assert_eq!(trace_context.parent_span_id_hex(), "00f067aa0ba902b7");
+        assert_ne!(trace_context.span_id_hex(), "00f067aa0ba902b7");
+        assert_eq!(trace_context.flags, 1);
+        assert_eq!(trace_context.tracestate, Some("vendor=value".to_string()));
+    }
+
+    #[test]
+    fn it_should_reject_invalid_traceparent_headers() {
+        for traceparent in [
+            "",
+            "00-4bf92f3577b34da6a3ce929d0e0e4736-00f067aa0ba902b7",
+            "ff-4bf92f3577b34da6a3ce929d0e0e4736-00f067aa0ba902b7-01",
+            "00-4BF92F3577B34DA6A3CE929D0E0E4736-00f067aa0ba902b7-01",
+            "00-00000000000000000000000000000000-00f067aa0ba902b7-01",
+            "00-4bf92f3577b34da6a3ce929d0e0e4736-0000000000000000-01",
+            "00-4bf92f3577b34da6a3ce929d0e0e4736-00f067aa0ba902b7-01-extra",
+            "00-4bf92f3577b34da6a3ce929d0e0e47-00f067aa0ba902b7-01",
+        ] {
+            assert_eq!(TraceContext::from_headers(traceparent, None), None, "{traceparent}");
+        }
+    }
+
+    #[test]
+    fn it_should_accept_future_versions_with_extra_fields() {
+        let traceparent = "01-4bf92f3577b34da6a3ce929d0e0e4736-00f067aa0ba902b7-01-extra";
+
+        assert!(TraceContext::from_headers(traceparent, None).is_some());
+    }
+
+    #[test]
+    fn it_should_use_the_index_span_as_parent_for_outbound_calls() {
+        let trace_context = TraceContext::from_headers(TRACEPARENT, None).unwrap();
+
+        assert_eq!(
+            trace_context.traceparent(),
+            format!("00-4bf92f3577b34da6a3ce929d0e0e4736-{}-01", trace_context.span_id_hex())
+        );
+    }
+
+    #[tokio::test]
+    async fn it_should_make_the_context_available_while_handling_the_request() {
+        let trace_context = TraceContext::new_root();
+
+        assert_eq!(TraceContext::current(), None);
+
+        let current = trace_context.clone().scope(async { TraceContext::current() }).await;
+
+        assert_eq!(current, Some(trace_context));
+    }
+}
@@ -1,15 +1,20 @@
 pub mod custom_axum;
+pub mod rate_limit;
 pub mod signals;
+pub mod tls;
+pub mod trace_context;
 pub mod v1;
 
 use std::net::SocketAddr;
 use std::panic::Location;
 use std::sync::Arc;
+use std::time::Duration;
 
 use axum_server::tls_rustls::RustlsConfig;
 use axum_server::Handle;
 use thiserror::Error;
 use tokio::sync::oneshot::{Receiver, Sender};
+use tokio::task::JoinHandle;
 use torrust_index_located_error::LocatedError;
 use tracing::{error, info};
 use v1::routes::router;
@@ -33,6 +38,8 @@ pub async fn start(app_data: Arc<AppData>, config_bind_address: SocketAddr, opt_
         .await
         .map(|tls| tls.expect("it should have a valid net tls configuration"));
 
+    let opt_cert_reloader = start_cert_reloader(opt_tsl.as_ref(), opt_rust_tls_config.as_ref());
+
     let (tx_start, rx) = tokio::sync::oneshot::channel::<Started>();
     let (tx_halt, rx_halt) = tokio::sync::oneshot::channel::<Halted>();
 
@@ -42,6 +49,10 @@ pub async fn start(app_data: Arc<AppData>, config_bind_address: SocketAddr, opt_
 
         start_server(config_bind_address, app_data.clone(), tx_start, rx_halt, opt_rust_tls_config).await;
 
+        if let Some(cert_reloader) = opt_cert_reloader {
+            cert_reloader.abort();
+        }
+
         info!("API server stopped");
 
         Ok(())
@@ -64,6 +75,25 @@ pub async fn start(app_data: Arc<AppData>, config_bind_address: SocketAddr, opt_
     }
 }
 
+/// It starts the task that reloads the TLS certificate when it changes. Refer
+/// to the [`tls`] module.
+fn start_cert_reloader(opt_tsl: Option<&Tsl>, opt_rust_tls_config: Option<&RustlsConfig>) -> Option<JoinHandle<()>> {
+    let (tsl, rust_tls_config) = (opt_tsl?, opt_rust_tls_config?);
+
+    if tsl.cert_reload_interval_seconds == 0 {
+        return None;
+    }
+
+    let (cert, key) = (tsl.ssl_cert_path.clone()?, tsl.ssl_key_path.clone()?);
+
+    Some(tls::watch(
+        rust_tls_config.clone(),
+        cert,
+        key,
+        Duration::from_secs(tsl.cert_reload_interval_seconds),
+    ))
+}
+
 async fn start_server(
     config_socket_addr: SocketAddr,
     app_data: Arc<AppData>,
@@ -71,7 +101,10 @@ async fn start_server(
     rx_halt: Receiver<Halted>,
     rust_tls_config: Option<RustlsConfig>,
 ) {
-    let router = router(app_data);
+    let compression = app_data.cfg.settings.read().await.api.compression.clone();
+    let shutdown_timeout = Duration::from_secs(app_data.cfg.settings.read().await.net.shutdown_timeout_seconds);
+
+    let router = router(app_data, &compression);
     let socket = std::net::TcpListener::bind(config_socket_addr).expect("Could not bind tcp_listener to address.");
     let address = socket.local_addr().expect("Could not get local_addr from tcp_listener.");
 
@@ -81,6 +114,7 @@ async fn start_server(
         handle.clone(),
         rx_halt,
         format!("Shutting down API server on socket address: {address}"),
+        shutdown_timeout,
     ));
 
     let tls = rust_tls_config.clone();
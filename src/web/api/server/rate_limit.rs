@@ -0,0 +1,717 @@
+//! Rate limiting middleware for the API.
+//!
+//! It limits the endpoints that are commonly abused: login, registration,
+//! the endpoints sending emails, torrent upload and searches in the torrent
+//! listing. Each client gets a
+//! [token bucket](https://en.wikipedia.org/wiki/Token_bucket) per group of
+//! endpoints. Logged-in users are identified by their user ID and guests by
+//! their IP address.
+//!
+//! Limits are configured in the `[net.rate_limits]` section. When the section
+//! is not present, requests are not rate limited.
+//!
+//! ```toml
+//! [net.rate_limits]
+//! login = { burst = 5, per_minute = 10 }
+//! registration = { burst = 3, per_minute = 2 }
+//! email = { burst = 3, per_minute = 1 }
+//! upload = { burst = 10, per_minute = 10 }
+//! search = { burst = 30, per_minute = 120 }
+//! soft_threshold = 80
+//! ```
+//!
+//! Responses to the rate limited endpoints include these headers so API
+//! clients can slow down before they are rejected:
+//!
+//! Header | Description
+//! ---|---
+//! `X-RateLimit-Limit` | Max number of requests accepted in a burst
+//! `X-RateLimit-Remaining` | Requests the client can make now
+//! `X-RateLimit-Reset` | Seconds until the bucket is full again
+//! `X-RateLimit-Warning` | Only when the client is over the soft threshold
+//!
+//! The soft threshold (`soft_threshold`, a percentage of the burst, default
+//! to 80) is a warning: requests are still accepted, but the response
+//! includes the `X-RateLimit-Warning` header and a warning is logged, which
+//! helps to find the clients that will be limited.
+//!
+//! Rejected requests get a `429 Too Many Requests` response with a
+//! `Retry-After` header containing the number of seconds to wait.
+//!
+//! Administrators can inspect the current buckets with the
+//! [rate limits endpoint](crate::web::api::server::v1::contexts::admin#get-the-rate-limit-counters).
+//!
+//! The middleware also counts the requests made by logged-in users with each
+//! credential, even when rate limits are not configured. The counters are
+//! kept in memory and stored periodically by the
+//! [`api_usage`](crate::services::api_usage) service.
+use std::collections::HashMap;
+use std::fmt;
+use std::net::{IpAddr, SocketAddr};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+use axum::extract::{ConnectInfo, FromRequestParts, Request, State};
+use axum::http::header::AUTHORIZATION;
+use axum::http::header::RETRY_AFTER;
+use axum::http::{HeaderMap, HeaderName, HeaderValue, Method};
+use axum::middleware::Next;
+use axum::response::{IntoResponse, Response};
+use chrono::{DateTime, Utc};
+
+use super::v1::extractors::bearer_token;
+use super::v1::extractors::optional_user_id::ExtractOptionalLoggedInUser;
+use super::v1::routes::API_VERSION_URL_PREFIX;
+use crate::common::AppData;
+use crate::config::{RateLimit, RateLimits};
+use crate::errors::ServiceError;
+use crate::models::api_usage::{ApiUsageRecord, RateLimitCounter};
+use crate::models::user::UserId;
+use crate::services::api_usage::credential_id;
+use crate::utils::clock::DATETIME_FORMAT;
+
+/// Number of buckets kept in memory before removing the ones that are full
+/// again, which behave exactly like new buckets.
+const MAX_BUCKETS_BEFORE_CLEANUP: usize = 10_000;
+
+pub const X_RATELIMIT_LIMIT: &str = "x-ratelimit-limit";
+pub const X_RATELIMIT_REMAINING: &str = "x-ratelimit-remaining";
+pub const X_RATELIMIT_RESET: &str = "x-ratelimit-reset";
+pub const X_RATELIMIT_WARNING: &str = "x-ratelimit-warning";
+
+/// Groups of endpoints sharing the same limit.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Group {
+    Login,
+    Registration,
+    Email,
+    Upload,
+    Search,
+}
+
+impl Group {
+    /// Returns the group for the request, or `None` if the endpoint is not
+    /// rate limited.
+    #[must_use]
+    pub fn of(method: &Method, path: &str, query: Option<&str>) -> Option<Group> {
+        let path = path.strip_prefix(&format!("/{API_VERSION_URL_PREFIX}"))?;
+
+        match (method, path.trim_end_matches('/')) {
+            (&Method::POST, "/user/login") => Some(Group::Login),
+            (&Method::POST, "/user/register") => Some(Group::Registration),
+            (&Method::POST, "/user/email/resend-verification" | "/user/email/change") => Some(Group::Email),
+            (&Method::POST, "/torrent/upload" | "/torrent/upload/signed") => Some(Group::Upload),
+            (&Method::GET, "/torrents") if query.is_some_and(has_search) => Some(Group::Search),
+            _ => None,
+        }
+    }
+
+    fn limit(self, limits: &RateLimits) -> RateLimit {
+        match self {
+            Group::Login => limits.login,
+            Group::Registration => limits.registration,
+            Group::Email => limits.email,
+            Group::Upload => limits.upload,
+            Group::Search => limits.search,
+        }
+    }
+}
+
+impl fmt::Display for Group {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = match self {
+            Group::Login => "login",
+            Group::Registration => "registration",
+            Group::Email => "email",
+            Group::Upload => "upload",
+            Group::Search => "search",
+        };
+
+        write!(f, "{name}")
+    }
+}
+
+/// Whether the query string contains a non-empty `search` param.
+fn has_search(query: &str) -> bool {
+    query
+        .split('&')
+        .filter_map(|pair| pair.split_once('='))
+        .any(|(name, value)| name == "search" && !value.is_empty())
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+enum Client {
+    User(UserId),
+    Ip(IpAddr),
+}
+
+impl fmt::Display for Client {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Client::User(user_id) => write!(f, "user #{user_id}"),
+            Client::Ip(ip) => write!(f, "IP {ip}"),
+        }
+    }
+}
+
+/// The state of the client bucket after an accepted request.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct Status {
+    limit: u32,
+    remaining: u32,
+    reset_after: Duration,
+    soft_limited: bool,
+}
+
+impl Status {
+    fn insert_headers(&self, headers: &mut HeaderMap) {
+        headers.insert(HeaderName::from_static(X_RATELIMIT_LIMIT), HeaderValue::from(self.limit));
+        headers.insert(
+            HeaderName::from_static(X_RATELIMIT_REMAINING),
+            HeaderValue::from(self.remaining),
+        );
+        headers.insert(
+            HeaderName::from_static(X_RATELIMIT_RESET),
+            HeaderValue::from(whole_seconds(self.reset_after)),
+        );
+
+        if self.soft_limited {
+            headers.insert(
+                HeaderName::from_static(X_RATELIMIT_WARNING),
+                HeaderValue::from_static("Close to the rate limit, requests will be rejected soon"),
+            );
+        }
+    }
+}
+
+#[derive(Debug)]
+struct Bucket {
+    tokens: f64,
+    updated_at: Instant,
+}
+
+impl Bucket {
+    fn full(limit: RateLimit, now: Instant) -> Self {
+        Self {
+            tokens: f64::from(limit.burst),
+            updated_at: now,
+        }
+    }
+
+    fn refill(&mut self, limit: RateLimit, now: Instant) {
+        let elapsed = now.saturating_duration_since(self.updated_at).as_secs_f64();
+
+        self.tokens = (self.tokens + elapsed * tokens_per_second(limit)).min(f64::from(limit.burst));
+        self.updated_at = now;
+    }
+
+    /// Takes one token from the bucket. If the bucket is empty, it returns
+    /// the time until the next token is available.
+    fn take(&mut self, limit: RateLimit, now: Instant) -> Result<(), Duration> {
+        self.refill(limit, now);
+
+        if self.tokens >= 1.0 {
+            self.tokens -= 1.0;
+            return Ok(());
+        }
+
+        let rate = tokens_per_second(limit);
+
+        if rate > 0.0 {
+            Err(Duration::from_secs_f64((1.0 - self.tokens) / rate))
+        } else {
+            Err(Duration::from_secs(60))
+        }
+    }
+
+    fn is_full(&self, limit: RateLimit, now: Instant) -> bool {
+        let elapsed = now.saturating_duration_since(self.updated_at).as_secs_f64();
+
+        self.tokens + elapsed * tokens_per_second(limit) >= f64::from(limit.burst)
+    }
+
+    /// Number of whole tokens in the bucket.
+    #[allow(clippy::cast_possible_truncation)]
+    #[allow(clippy::cast_sign_loss)]
+    fn remaining(&self) -> u32 {
+        // Tokens are always between 0 and the burst, which is a `u32`
+        self.tokens.floor() as u32
+    }
+
+    fn status(&self, limit: RateLimit, soft_threshold: u8) -> Status {
+        let remaining = self.remaining();
+
+        Status {
+            limit: limit.burst,
+            remaining,
+            reset_after: time_to_refill(limit, self.tokens),
+            soft_limited: is_over_soft_threshold(limit, remaining, soft_threshold),
+        }
+    }
+}
+
+fn tokens_per_second(limit: RateLimit) -> f64 {
+    f64::from(limit.per_minute) / 60.0
+}
+
+/// Time until a bucket with the given tokens is full again.
+fn time_to_refill(limit: RateLimit, tokens: f64) -> Duration {
+    let missing = f64::from(limit.burst) - tokens;
+    let rate = tokens_per_second(limit);
+
+    if missing <= 0.0 {
+        Duration::ZERO
+    } else if rate > 0.0 {
+        Duration::from_secs_f64(missing / rate)
+    } else {
+        Duration::from_secs(60)
+    }
+}
+
+/// Whether the client has used at least the `soft_threshold` percentage of
+/// the burst.
+fn is_over_soft_threshold(limit: RateLimit, remaining: u32, soft_threshold: u8) -> bool {
+    let used = u64::from(limit.burst.saturating_sub(remaining));
+
+    limit.burst > 0 && used * 100 >= u64::from(limit.burst) * u64::from(soft_threshold.min(100))
+}
+
+/// Rounds up a duration to whole seconds.
+fn whole_seconds(duration: Duration) -> u64 {
+    duration.as_secs() + u64::from(duration.subsec_nanos() > 0)
+}
+
+/// In-memory token buckets for all the clients.
+pub struct RateLimiter {
+    limits: Option<RateLimits>,
+    buckets: Mutex<HashMap<(Group, Client), Bucket>>,
+    /// API usage counters by user, credential and day that have not been
+    /// stored yet.
+    usage: Mutex<HashMap<(UserId, String, String), ApiUsageRecord>>,
+}
+
+impl RateLimiter {
+    #[must_use]
+    pub fn new(limits: Option<RateLimits>) -> Self {
+        Self {
+            limits,
+            buckets: Mutex::new(HashMap::new()),
+            usage: Mutex::new(HashMap::new()),
+        }
+    }
+
+    #[must_use]
+    pub fn is_enabled(&self) -> bool {
+        self.limits.is_some()
+    }
+
+    /// Takes one token from the client bucket for the group. It returns the
+    /// state of the bucket after taking the token, or `None` if rate limits
+    /// are not configured.
+    ///
+    /// # Errors
+    ///
+    /// It returns the time the client has to wait if the limit was exceeded.
+    ///
+    /// # Panics
+    ///
+    /// It panics if the buckets lock is poisoned.
+    fn check(&self, group: Group, client: Client, now: Instant) -> Result<Option<Status>, Duration> {
+        let Some(limits) = &self.limits else {
+            return Ok(None);
+        };
+
+        let mut buckets = self.buckets.lock().expect("it should get the rate limit buckets lock");
+
+        if buckets.len() >= MAX_BUCKETS_BEFORE_CLEANUP {
+            buckets.retain(|(group, _), bucket| !bucket.is_full(group.limit(limits), now));
+        }
+
+        let limit = group.limit(limits);
+
+        let bucket = buckets.entry((group, client)).or_insert_with(|| Bucket::full(limit, now));
+
+        bucket.take(limit, now)?;
+
+        Ok(Some(bucket.status(limit, limits.soft_threshold)))
+    }
+
+    /// It returns the limit configured for the group, if any.
+    fn limit(&self, group: Group) -> Option<RateLimit> {
+        self.limits.as_ref().map(|limits| group.limit(limits))
+    }
+
+    /// It returns the current state of the buckets, optionally only the ones
+    /// of a user or an IP address. Buckets that are full again are only
+    /// included until they are cleaned up.
+    ///
+    /// # Panics
+    ///
+    /// It panics if the buckets lock is poisoned.
+    #[must_use]
+    pub fn counters(&self, user_id: Option<UserId>, ip: Option<IpAddr>, now: Instant) -> Vec<RateLimitCounter> {
+        let Some(limits) = &self.limits else {
+            return vec![];
+        };
+
+        let mut buckets = self.buckets.lock().expect("it should get the rate limit buckets lock");
+
+        let mut counters: Vec<RateLimitCounter> = buckets
+            .iter_mut()
+            .filter(|((_, client), _)| match client {
+                Client::User(id) => ip.is_none() && user_id.map_or(true, |user_id| user_id == *id),
+                Client::Ip(client_ip) => user_id.is_none() && ip.map_or(true, |ip| ip == *client_ip),
+            })
+            .map(|((group, client), bucket)| {
+                let limit = group.limit(limits);
+
+                bucket.refill(limit, now);
+
+                let status = bucket.status(limit, limits.soft_threshold);
+
+                RateLimitCounter {
+                    group: group.to_string(),
+                    user_id: match client {
+                        Client::User(user_id) => Some(*user_id),
+                        Client::Ip(_) => None,
+                    },
+                    ip: match client {
+                        Client::User(_) => None,
+                        Client::Ip(ip) => Some(ip.to_string()),
+                    },
+                    limit: status.limit,
+                    remaining: status.remaining,
+                    reset_after_seconds: whole_seconds(status.reset_after),
+                    soft_limited: status.soft_limited,
+                }
+            })
+            .collect();
+
+        counters.sort_by(|a, b| (&a.group, a.user_id, &a.ip).cmp(&(&b.group, b.user_id, &b.ip)));
+
+        counters
+    }
+
+    /// Counts a request made by a logged-in user with a credential.
+    ///
+    /// # Panics
+    ///
+    /// It panics if the usage lock is poisoned.
+    pub fn record_usage(&self, user_id: UserId, credential: &str, ip: Option<IpAddr>, rate_limited: bool, now: DateTime<Utc>) {
+        let date = now.format("%Y-%m-%d").to_string();
+        let last_used = now.format(DATETIME_FORMAT).to_string();
+
+        let mut usage = self.usage.lock().expect("it should get the API usage lock");
+
+        let record = usage
+            .entry((user_id, credential.to_string(), date.clone()))
+            .or_insert_with(|| ApiUsageRecord {
+                user_id,
+                credential: credential.to_string(),
+                date,
+                requests: 0,
+                rate_limit_hits: 0,
+                last_ip: None,
+                last_used: last_used.clone(),
+            });
+
+        record.requests += 1;
+
+        if rate_limited {
+            record.rate_limit_hits += 1;
+        }
+
+        if let Some(ip) = ip {
+            record.last_ip = Some(ip.to_string());
+        }
+
+        record.last_used = last_used;
+    }
+
+    /// It takes the usage counters recorded since the last call.
+    ///
+    /// # Panics
+    ///
+    /// It panics if the usage lock is poisoned.
+    #[must_use]
+    pub fn take_usage(&self) -> Vec<ApiUsageRecord> {
+        let mut usage = self.usage.lock().expect("it should get the API usage lock");
+
+        std::mem::take(&mut *usage).into_values().collect()
+    }
+
+    /// It returns the usage counters of a user that have not been taken yet.
+    ///
+    /// # Panics
+    ///
+    /// It panics if the usage lock is poisoned.
+    #[must_use]
+    pub fn pending_usage(&self, user_id: UserId) -> Vec<ApiUsageRecord> {
+        let usage = self.usage.lock().expect("it should get the API usage lock");
+
+        usage.values().filter(|record| record.user_id == user_id).cloned().collect()
+    }
+}
+
+/// Middleware that rejects requests exceeding the configured rate limits and
+/// counts the requests made by logged-in users.
+pub async fn rate_limit_middleware(State(app_data): State<Arc<AppData>>, request: Request, next: Next) -> Response {
+    let maybe_group = if app_data.rate_limiter.is_enabled() {
+        Group::of(request.method(), request.uri().path(), request.uri().query())
+    } else {
+        None
+    };
+
+    if maybe_group.is_none() && !request.headers().contains_key(AUTHORIZATION) {
+        return next.run(request).await;
+    }
+
+    let (mut parts, body) = request.into_parts();
+
+    let maybe_bearer_token = match bearer_token::Extract::from_request_parts(&mut parts, &app_data).await {
+        Ok(bearer_token::Extract(maybe_bearer_token)) => maybe_bearer_token,
+        Err(_) => None,
+    };
+
+    let maybe_user_id = match ExtractOptionalLoggedInUser::from_request_parts(&mut parts, &app_data).await {
+        Ok(ExtractOptionalLoggedInUser(maybe_user_id)) => maybe_user_id,
+        Err(_) => None,
+    };
+
+    let maybe_ip = parts
+        .extensions
+        .get::<ConnectInfo<SocketAddr>>()
+        .map(|ConnectInfo(socket_addr)| socket_addr.ip());
+
+    let maybe_client = match (maybe_user_id, maybe_ip) {
+        (Some(user_id), _) => Some(Client::User(user_id)),
+        (None, Some(ip)) => Some(Client::Ip(ip)),
+        (None, None) => None,
+    };
+
+    let result = match (maybe_group, maybe_client) {
+        (Some(group), Some(client)) => app_data.rate_limiter.check(group, client, Instant::now()),
+        _ => Ok(None),
+    };
+
+    if let (Ok(Some(status)), Some(group), Some(client)) = (&result, maybe_group, maybe_client) {
+        if status.soft_limited {
+            tracing::warn!(
+                "Client {client} is close to the {group} rate limit: {} of {} requests left",
+                status.remaining,
+                status.limit
+            );
+        }
+    }
+
+    if let (Some(user_id), Some(bearer_token)) = (maybe_user_id, maybe_bearer_token) {
+        app_data.rate_limiter.record_usage(
+            user_id,
+            &credential_id(&bearer_token.value()),
+            maybe_ip,
+            result.is_err(),
+            Utc::now(),
+        );
+    }
+
+    match result {
+        Ok(maybe_status) => {
+            let mut response = next.run(Request::from_parts(parts, body)).await;
+
+            if let Some(status) = maybe_status {
+                status.insert_headers(response.headers_mut());
+            }
+
+            response
+        }
+        Err(retry_after) => {
+            let mut response = ServiceError::TooManyRequests.into_response();
+
+            response
+                .headers_mut()
+                .insert(RETRY_AFTER, HeaderValue::from(whole_seconds(retry_after).max(1)));
+
+            if let Some(limit) = maybe_group.and_then(|group| app_data.rate_limiter.limit(group)) {
+                Status {
+                    limit: limit.burst,
+                    remaining: 0,
+                    reset_after: time_to_refill(limit, 0.0),
+                    soft_limited: true,
+                }
+                .insert_headers(response.headers_mut());
+            }
+
+            response
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::net::{IpAddr, Ipv4Addr};
+    use std::time::{Duration, Instant};
+
+    use axum::http::Method;
+    use chrono::{TimeZone, Utc};
+
+    use super::{Client, Group, RateLimiter};
+    use crate::config::{RateLimit, RateLimits};
+    use crate::models::api_usage::{ApiUsageRecord, RateLimitCounter};
+
+    fn limiter(burst: u32, per_minute: u32) -> RateLimiter {
+        RateLimiter::new(Some(RateLimits {
+            login: RateLimit::new(burst, per_minute),
+            ..RateLimits::default()
+        }))
+    }
+
+    fn guest() -> Client {
+        Client::Ip(IpAddr::V4(Ipv4Addr::new(126, 0, 0, 1)))
+    }
+
+    #[test]
+    fn it_should_only_limit_the_configured_endpoints() {
+        assert_eq!(Group::of(&Method::POST, "/v1/user/login", None), Some(Group::Login));
+        assert_eq!(Group::of(&Method::POST, "/v1/user/register", None), Some(Group::Registration));
+        assert_eq!(
+            Group::of(&Method::POST, "/v1/user/email/resend-verification", None),
+            Some(Group::Email)
+        );
+        assert_eq!(Group::of(&Method::POST, "/v1/user/email/change", None), Some(Group::Email));
+        assert_eq!(Group::of(&Method::POST, "/v1/torrent/upload", None), Some(Group::Upload));
+        assert_eq!(
+            Group::of(&Method::GET, "/v1/torrents", Some("page=0&search=ubuntu")),
+            Some(Group::Search)
+        );
+
+        assert_eq!(Group::of(&Method::GET, "/v1/torrents", Some("page=0&search=")), None);
+        assert_eq!(Group::of(&Method::GET, "/v1/torrents", None), None);
+        assert_eq!(Group::of(&Method::GET, "/v1/user/login", None), None);
+        assert_eq!(Group::of(&Method::GET, "/health_check", None), None);
+    }
+
+    #[test]
+    fn it_should_allow_bursts_up_to_the_bucket_capacity() {
+        let limiter = limiter(3, 60);
+        let now = Instant::now();
+
+        for _ in 0..3 {
+            assert!(limiter.check(Group::Login, guest(), now).is_ok());
+        }
+
+        assert_eq!(limiter.check(Group::Login, guest(), now), Err(Duration::from_secs(1)));
+    }
+
+    #[test]
+    fn it_should_refill_the_bucket_over_time() {
+        let limiter = limiter(1, 60);
+        let now = Instant::now();
+
+        assert!(limiter.check(Group::Login, guest(), now).is_ok());
+        assert!(limiter.check(Group::Login, guest(), now).is_err());
+
+        assert!(limiter.check(Group::Login, guest(), now + Duration::from_secs(1)).is_ok());
+    }
+
+    #[test]
+    fn it_should_keep_separate_buckets_for_each_client_and_group() {
+        let limiter = limiter(1, 60);
+        let now = Instant::now();
+
+        assert!(limiter.check(Group::Login, guest(), now).is_ok());
+        assert!(limiter.check(Group::Login, guest(), now).is_err());
+
+        assert!(limiter.check(Group::Login, Client::User(1), now).is_ok());
+        assert!(limiter.check(Group::Registration, guest(), now).is_ok());
+    }
+
+    #[test]
+    fn it_should_return_the_remaining_requests_and_warn_over_the_soft_threshold() {
+        let limiter = limiter(5, 60);
+        let now = Instant::now();
+
+        let status = limiter.check(Group::Login, guest(), now).unwrap().unwrap();
+
+        assert_eq!(status.limit, 5);
+        assert_eq!(status.remaining, 4);
+        assert_eq!(status.reset_after, Duration::from_secs(1));
+        assert!(!status.soft_limited);
+
+        for _ in 0..2 {
+            assert!(!limiter.check(Group::Login, guest(), now).unwrap().unwrap().soft_limited);
+        }
+
+        // 4 of 5 requests is the default 80% soft threshold
+        let status = limiter.check(Group::Login, guest(), now).unwrap().unwrap();
+
+        assert_eq!(status.remaining, 1);
+        assert!(status.soft_limited);
+    }
+
+    #[test]
+    fn it_should_return_the_counters_of_a_client() {
+        let limiter = limiter(5, 60);
+        let now = Instant::now();
+
+        assert!(limiter.check(Group::Login, guest(), now).is_ok());
+        assert!(limiter.check(Group::Login, Client::User(1), now).is_ok());
+        assert!(limiter.check(Group::Search, Client::User(1), now).is_ok());
+
+        assert_eq!(limiter.counters(None, None, now).len(), 3);
+        assert_eq!(
+            limiter.counters(None, Some(IpAddr::V4(Ipv4Addr::new(126, 0, 0, 1))), now),
+            vec![RateLimitCounter {
+                group: "login".to_string(),
+                user_id: None,
+                ip: Some("126.0.0.1".to_string()),
+                limit: 5,
+                remaining: 4,
+                reset_after_seconds: 1,
+                soft_limited: false,
+            }]
+        );
+
+        let counters = limiter.counters(Some(1), None, now + Duration::from_secs(1));
+
+        assert_eq!(counters.len(), 2);
+        assert_eq!(counters[0].group, "login");
+        assert_eq!(counters[0].remaining, 5);
+    }
+
+    #[test]
+    fn it_should_count_the_requests_by_user_credential_and_day() {
+        let limiter = RateLimiter::new(None);
+        let now = Utc.with_ymd_and_hms(2024, 10, 17, 10, 0, 0).unwrap();
+        let ip = IpAddr::V4(Ipv4Addr::new(126, 0, 0, 1));
+
+        limiter.record_usage(1, "token-a", None, false, now);
+        limiter.record_usage(1, "token-a", Some(ip), true, now);
+        limiter.record_usage(2, "token-b", None, false, now);
+
+        assert_eq!(
+            limiter.pending_usage(1),
+            vec![ApiUsageRecord {
+                user_id: 1,
+                credential: "token-a".to_string(),
+                date: "2024-10-17".to_string(),
+                requests: 2,
+                rate_limit_hits: 1,
+                last_ip: Some("126.0.0.1".to_string()),
+                last_used: "2024-10-17 10:00:00".to_string(),
+            }]
+        );
+
+        assert_eq!(limiter.take_usage().len(), 2);
+        assert!(limiter.take_usage().is_empty());
+    }
+
+    #[test]
+    fn it_should_not_limit_requests_when_rate_limits_are_not_configured() {
+        let limiter = RateLimiter::new(None);
+        let now = Instant::now();
+
+        for _ in 0..100 {
+            assert!(limiter.check(Group::Login, guest(), now).is_ok());
+        }
+    }
+}
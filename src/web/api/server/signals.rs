@@ -19,13 +19,20 @@ pub enum Halted {
     Normal,
 }
 
-pub async fn graceful_shutdown(handle: axum_server::Handle, rx_halt: tokio::sync::oneshot::Receiver<Halted>, message: String) {
+/// It stops accepting new connections when the shutdown signal is received,
+/// and it waits up to `timeout` for the in-flight requests to finish.
+pub async fn graceful_shutdown(
+    handle: axum_server::Handle,
+    rx_halt: tokio::sync::oneshot::Receiver<Halted>,
+    message: String,
+    timeout: Duration,
+) {
     shutdown_signal_with_message(rx_halt, message).await;
 
     info!("Sending graceful shutdown signal");
-    handle.graceful_shutdown(Some(Duration::from_secs(90)));
+    handle.graceful_shutdown(Some(timeout));
 
-    println!("!! shuting down in 90 seconds !!");
+    println!("!! shuting down in {} seconds !!", timeout.as_secs());
 
     loop {
         sleep(Duration::from_secs(1)).await;
@@ -0,0 +1,117 @@
+//! Reload of the TLS certificate without restarting the API server.
+//!
+//! Certificates are renewed often, every 60 to 90 days with services like
+//! Let's Encrypt. The certificate and key files are checked periodically and
+//! they are loaded again when they change. If the new files are not valid,
+//! the server keeps using the previous certificate.
+//!
+//! ```toml
+//! [net.tsl]
+//! ssl_cert_path = "./storage/index/lib/tls/localhost.crt"
+//! ssl_key_path = "./storage/index/lib/tls/localhost.key"
+//! cert_reload_interval_seconds = 60
+//! ```
+use std::time::{Duration, SystemTime};
+
+use axum_server::tls_rustls::RustlsConfig;
+use camino::Utf8PathBuf;
+use tokio::task::JoinHandle;
+use tracing::{error, info};
+
+/// It starts a task that reloads the certificate when the certificate or the
+/// key files change.
+#[must_use]
+pub fn watch(tls: RustlsConfig, cert: Utf8PathBuf, key: Utf8PathBuf, interval: Duration) -> JoinHandle<()> {
+    tokio::spawn(async move {
+        let mut execution_interval = tokio::time::interval(interval);
+
+        let mut last_modified = modified(&cert, &key).await;
+
+        info!(
+            "Checking the TLS certificate for changes every {} seconds ...",
+            interval.as_secs()
+        );
+
+        loop {
+            execution_interval.tick().await;
+
+            let modified = modified(&cert, &key).await;
+
+            if modified.is_none() || modified == last_modified {
+                continue;
+            }
+
+            last_modified = modified;
+
+            match tls.reload_from_pem_file(&cert, &key).await {
+                Ok(()) => info!("TLS certificate reloaded. Cert path: {cert}."),
+                Err(e) => {
+                    error!("Failed to reload the TLS certificate, the previous one is still used. Cert path: {cert}. Error: {e}")
+                }
+            }
+        }
+    })
+}
+
+/// It returns the last modification times of the certificate and the key, or
+/// `None` if any of them can't be read.
+async fn modified(cert: &Utf8PathBuf, key: &Utf8PathBuf) -> Option<(SystemTime, SystemTime)> {
+    let cert_modified = tokio::fs::metadata(cert)
+        .await
+        .and_then(|metadata| metadata.modified())
+        .ok()?;
+    let key_modified = tokio::fs::metadata(key).await.and_then(|metadata| metadata.modified()).ok()?;
+
+    Some((cert_modified, key_modified))
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+    use std::time::Duration;
+
+    use axum_server::tls_rustls::RustlsConfig;
+    use camino::Utf8PathBuf;
+    use tempfile::TempDir;
+
+    use super::watch;
+
+    const INTERVAL: Duration = Duration::from_millis(10);
+
+    #[tokio::test]
+    async fn it_should_reload_the_certificate_when_the_files_change() {
+        let temp_dir = TempDir::new().unwrap();
+        let cert = Utf8PathBuf::from_path_buf(temp_dir.path().join("localhost.crt")).unwrap();
+        let key = Utf8PathBuf::from_path_buf(temp_dir.path().join("localhost.key")).unwrap();
+
+        std::fs::copy("./share/tls/localhost.crt", &cert).unwrap();
+        std::fs::copy("./share/tls/localhost.key", &key).unwrap();
+
+        let tls = RustlsConfig::from_pem_file(&cert, &key).await.unwrap();
+        let loaded = tls.get_inner();
+
+        let watcher = watch(tls.clone(), cert.clone(), key.clone(), INTERVAL);
+
+        // Some filesystems only keep the modification time in seconds
+        tokio::time::sleep(Duration::from_millis(1100)).await;
+
+        assert!(Arc::ptr_eq(&loaded, &tls.get_inner()));
+
+        std::fs::write(&cert, std::fs::read(&cert).unwrap()).unwrap();
+
+        let mut reloaded = false;
+
+        for _ in 0..100 {
+            tokio::time::sleep(INTERVAL).await;
+
+            if !Arc::ptr_eq(&loaded, &tls.get_inner()) {
+                reloaded = true;
+                break;
+            }
+        }
+
+        watcher.abort();
+
+        assert!(reloaded);
+    }
+}
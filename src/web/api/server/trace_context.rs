@@ -0,0 +1,28 @@
+//! Middleware to accept the [W3C Trace Context](https://www.w3.org/TR/trace-context/)
+//! headers.
+//!
+//! It continues the trace from the `traceparent` and `tracestate` request
+//! headers, or starts a new one. The context is added to the request
+//! extensions, so the request span includes the trace and span IDs, and it's
+//! kept as the current context while the request is handled, so outbound
+//! calls can propagate it.
+//!
+//! Refer to [`trace_context`](crate::utils::trace_context) for more
+//! information.
+use axum::extract::Request;
+use axum::middleware::Next;
+use axum::response::Response;
+
+use crate::utils::trace_context::{TraceContext, TRACEPARENT, TRACESTATE};
+
+pub async fn trace_context_middleware(mut request: Request, next: Next) -> Response {
+    let header = |name: &str| request.headers().get(name).and_then(|value| value.to_str().ok());
+
+    let trace_context = header(TRACEPARENT)
+        .and_then(|traceparent| TraceContext::from_headers(traceparent, header(TRACESTATE)))
+        .unwrap_or_else(TraceContext::new_root);
+
+    request.extensions_mut().insert(trace_context.clone());
+
+    trace_context.scope(next.run(request)).await
+}
@@ -85,31 +85,53 @@ use hyper::http::HeaderValue;
 use crate::common::AppData;
 use crate::errors::ServiceError;
 use crate::models::user::{UserClaims, UserCompact, UserId};
+use crate::models::user_session::SessionId;
 use crate::services::authentication::JsonWebToken;
+use crate::services::session;
 use crate::web::api::server::v1::extractors::bearer_token::BearerToken;
 
 pub struct Authentication {
     json_web_token: Arc<JsonWebToken>,
+    session_service: Arc<session::Service>,
 }
 
 impl Authentication {
     #[must_use]
-    pub fn new(json_web_token: Arc<JsonWebToken>) -> Self {
-        Self { json_web_token }
+    pub fn new(json_web_token: Arc<JsonWebToken>, session_service: Arc<session::Service>) -> Self {
+        Self {
+            json_web_token,
+            session_service,
+        }
     }
 
     /// Create Json Web Token
     pub async fn sign_jwt(&self, user: UserCompact) -> String {
-        self.json_web_token.sign(user).await
+        self.json_web_token.sign(user, None).await
     }
 
     /// Verify Json Web Token
     ///
     /// # Errors
     ///
-    /// This function will return an error if the JWT is not good or expired.
+    /// This function will return an error if the JWT is not good or expired,
+    /// or if its session was revoked.
     pub async fn verify_jwt(&self, token: &str) -> Result<UserClaims, ServiceError> {
-        self.json_web_token.verify(token).await
+        let claims = self.json_web_token.verify(token).await?;
+
+        if let Some(session_id) = &claims.jti {
+            self.session_service.check(session_id).await?;
+        }
+
+        Ok(claims)
+    }
+
+    /// Get the session ID from bearer token, if the token is valid and it
+    /// has a session.
+    pub async fn get_session_id_from_bearer_token(&self, maybe_token: Option<BearerToken>) -> Option<SessionId> {
+        self.get_claims_from_bearer_token(maybe_token)
+            .await
+            .ok()
+            .and_then(|claims| claims.jti)
     }
 
     /// Get logged-in user ID from bearer token
@@ -0,0 +1,97 @@
+//! `Cache-Control` headers configured by route.
+//!
+//! Deployments behind a CDN can cache some responses, like the proxied images
+//! or the torrent listings, without a custom reverse proxy configuration. The
+//! rules are configured in the `api.cache_control` section:
+//!
+//! ```toml
+//! [[api.cache_control]]
+//! path = "/v1/proxy/image/:url"
+//! value = "public, max-age=86400"
+//!
+//! [[api.cache_control]]
+//! path = "/v1/torrents"
+//! value = "public, max-age=60"
+//! ```
+//!
+//! The header is only added to successful (or `304 Not Modified`) responses
+//! to `GET` and `HEAD` requests, and only when the handler did not set it.
+//! Those responses also include `Vary: Authorization`, because some responses
+//! depend on the logged-in user, so shared caches must not serve a response
+//! to a different user.
+use std::sync::Arc;
+
+use axum::extract::{Request, State};
+use axum::http::header::{AUTHORIZATION, CACHE_CONTROL, VARY};
+use axum::http::{HeaderValue, Method, StatusCode};
+use axum::middleware::Next;
+use axum::response::Response;
+
+use crate::common::AppData;
+use crate::config::CacheControlRule;
+use crate::web::api::server::v1::deprecation::path_matches;
+
+/// It returns the `Cache-Control` value of the first rule matching the path.
+#[must_use]
+pub fn cache_control_for<'a>(rules: &'a [CacheControlRule], path: &str) -> Option<&'a str> {
+    rules
+        .iter()
+        .find(|rule| path_matches(&rule.path, path))
+        .map(|rule| rule.value.as_str())
+}
+
+/// Middleware that adds the configured `Cache-Control` header to the
+/// responses.
+pub async fn cache_control_middleware(State(app_data): State<Arc<AppData>>, request: Request, next: Next) -> Response {
+    if request.method() != Method::GET && request.method() != Method::HEAD {
+        return next.run(request).await;
+    }
+
+    let value = cache_control_for(&app_data.cfg.settings.read().await.api.cache_control, request.uri().path())
+        .and_then(|value| HeaderValue::from_str(value).ok());
+
+    let mut response = next.run(request).await;
+
+    let Some(value) = value else {
+        return response;
+    };
+
+    let cacheable = response.status().is_success() || response.status() == StatusCode::NOT_MODIFIED;
+
+    if cacheable && !response.headers().contains_key(CACHE_CONTROL) {
+        response.headers_mut().insert(CACHE_CONTROL, value);
+        response.headers_mut().append(VARY, HeaderValue::from_name(AUTHORIZATION));
+    }
+
+    response
+}
+
+#[cfg(test)]
+mod tests {
+    use super::cache_control_for;
+    use crate::config::CacheControlRule;
+
+    fn rule(path: &str, value: &str) -> CacheControlRule {
+        CacheControlRule {
+            path: path.to_string(),
+            value: value.to_string(),
+        }
+    }
+
+    #[test]
+    fn it_should_use_the_first_rule_matching_the_path() {
+        let rules = vec![
+            rule("/v1/proxy/image/:url", "public, max-age=86400"),
+            rule("/v1/torrents", "public, max-age=60"),
+            rule("/v1/*", "no-cache"),
+        ];
+
+        assert_eq!(
+            cache_control_for(&rules, "/v1/proxy/image/https%3A%2F%2Fexample.com%2Flogo.png"),
+            Some("public, max-age=86400")
+        );
+        assert_eq!(cache_control_for(&rules, "/v1/torrents"), Some("public, max-age=60"));
+        assert_eq!(cache_control_for(&rules, "/v1/category/tags"), Some("no-cache"));
+        assert_eq!(cache_control_for(&rules, "/health_check"), None);
+    }
+}
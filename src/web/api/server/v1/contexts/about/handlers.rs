@@ -9,6 +9,15 @@ use axum::response::{IntoResponse, Response};
 use crate::common::AppData;
 use crate::web::api::server::v1::extractors::optional_user_id::ExtractOptionalLoggedInUser;
 
+#[utoipa::path(
+    get,
+    path = "/v1/about",
+    tag = "about",
+    responses(
+        (status = 200, description = "The about page", content_type = "text/html"),
+    ),
+    security((), ("bearer_auth" = []))
+)]
 #[allow(clippy::unused_async)]
 pub async fn about_page_handler(
     State(app_data): State<Arc<AppData>>,
@@ -20,6 +29,15 @@ pub async fn about_page_handler(
     }
 }
 
+#[utoipa::path(
+    get,
+    path = "/v1/about/license",
+    tag = "about",
+    responses(
+        (status = 200, description = "The license page", content_type = "text/html"),
+    ),
+    security((), ("bearer_auth" = []))
+)]
 #[allow(clippy::unused_async)]
 pub async fn license_page_handler(
     State(app_data): State<Arc<AppData>>,
@@ -0,0 +1,24 @@
+//! API forms for the the [`admin`](crate::web::api::server::v1::contexts::admin) API
+//! context.
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+
+use crate::models::report::ReportAction;
+
+#[derive(Serialize, Deserialize, Debug, ToSchema)]
+pub struct ResolveReportForm {
+    pub action: ReportAction,
+}
+
+/// Upload limits of a user. Missing limits are unlimited.
+#[derive(Serialize, Deserialize, Debug, ToSchema)]
+pub struct UploadLimitsForm {
+    pub max_uploads_per_day: Option<u32>,
+    pub max_total_upload_size: Option<u64>,
+}
+
+/// Trusted status of a user, set by an administrator.
+#[derive(Serialize, Deserialize, Debug, ToSchema)]
+pub struct TrustedStatusForm {
+    pub trusted: bool,
+}
@@ -0,0 +1,1490 @@
+//! API handlers for the [`admin`](crate::web::api::server::v1::contexts::admin) API
+//! context.
+use std::sync::Arc;
+
+use axum::extract::{self, Host, Path, Query, State};
+use axum::response::{IntoResponse, Json, Response};
+
+use super::forms::{ResolveReportForm, TrustedStatusForm, UploadLimitsForm};
+use super::responses::moderated_torrent;
+use crate::common::AppData;
+use crate::models::antispam::{BannedPattern, PatternId};
+use crate::models::api_usage::RateLimitCounter;
+use crate::models::import_session::ImportSessionId;
+use crate::models::points::{PointsBalance, SeedingSummaryEvent};
+use crate::models::report::ReportId;
+use crate::models::reputation::UserReputation;
+use crate::models::response::AuditLogResponse;
+use crate::models::torrent::{TorrentId, TrashedTorrent};
+use crate::models::upload_quota::{UploadLimits, UploadQuota};
+use crate::services::antispam::AddBannedPatternRequest;
+use crate::services::api_usage::RateLimitCountersRequest;
+use crate::services::audit::AuditLogRequest;
+use crate::services::bulk_import::session::{CreateImportSessionRequest, ImportBatchRequest};
+use crate::services::bulk_import::ImportRequest;
+use crate::services::bulk_mail::BulkMailRequest;
+use crate::services::download_statistics::StatisticsRequest;
+use crate::services::inactivity::ReportRequest as InactivityReportRequest;
+use crate::services::report::ListingRequest as ReportListingRequest;
+use crate::services::seeding_rewards::{AdjustPointsRequest, SummariesRequest};
+use crate::services::torrent::{BulkActionRequest, ListingRequest};
+use crate::web::api::server::v1::contexts::report::responses::report;
+use crate::web::api::server::v1::contexts::user::handlers::{api_base_url, UsernameParam};
+use crate::web::api::server::v1::extractors::optional_user_id::ExtractOptionalLoggedInUser;
+use crate::web::api::server::v1::responses::{
+    DeletedTorrentResponseData, ErrorResponseData, MessageResponseData, OkResponseData, TorrentsResponseData,
+};
+
+/// It handles the request to get the torrents waiting for moderation.
+///
+/// It returns:
+///
+/// - `200` response with a json containing the torrent list [`TorrentsResponse`](crate::models::response::TorrentsResponse).
+/// - Other error status codes if there is a database error.
+///
+/// # Errors
+///
+/// It returns an error if:
+///
+/// - The user does not have permissions to moderate torrents.
+/// - There is a database error.
+#[utoipa::path(
+    get,
+    path = "/v1/admin/torrents/pending",
+    tag = "admin",
+    params(
+        ListingRequest,
+    ),
+    responses(
+        (status = 200, description = "The torrents waiting for moderation", body = TorrentsResponseData),
+        (status = 401, description = "The user is not logged in", body = ErrorResponseData),
+        (status = 403, description = "The user does not have permissions to perform the action", body = ErrorResponseData),
+    ),
+    security(("bearer_auth" = []))
+)]
+#[allow(clippy::unused_async)]
+pub async fn get_pending_torrents_handler(
+    State(app_data): State<Arc<AppData>>,
+    ExtractOptionalLoggedInUser(maybe_user_id): ExtractOptionalLoggedInUser,
+    Query(criteria): Query<ListingRequest>,
+) -> Response {
+    match app_data
+        .torrent_service
+        .generate_pending_torrent_listing(&criteria, maybe_user_id)
+        .await
+    {
+        Ok(torrents_response) => Json(OkResponseData { data: torrents_response }).into_response(),
+        Err(error) => error.into_response(),
+    }
+}
+
+/// It approves a torrent.
+///
+/// # Errors
+///
+/// It returns an error if:
+///
+/// - The user does not have permissions to moderate torrents.
+/// - The torrent does not exist.
+/// - There is a database error.
+#[utoipa::path(
+    post,
+    path = "/v1/admin/torrent/{torrent_id}/approve",
+    tag = "admin",
+    params(
+        ("torrent_id" = i64, Path, description = "The torrent ID"),
+    ),
+    responses(
+        (status = 200, description = "The approved torrent"),
+        (status = 401, description = "The user is not logged in", body = ErrorResponseData),
+        (status = 403, description = "The user does not have permissions to perform the action", body = ErrorResponseData),
+        (status = 404, description = "The resource does not exist", body = ErrorResponseData),
+    ),
+    security(("bearer_auth" = []))
+)]
+#[allow(clippy::unused_async)]
+pub async fn approve_torrent_handler(
+    State(app_data): State<Arc<AppData>>,
+    ExtractOptionalLoggedInUser(maybe_user_id): ExtractOptionalLoggedInUser,
+    Path(torrent_id): Path<TorrentId>,
+) -> Response {
+    match app_data.torrent_service.approve_torrent(torrent_id, maybe_user_id).await {
+        Ok(torrent_listing) => moderated_torrent(torrent_listing).into_response(),
+        Err(error) => error.into_response(),
+    }
+}
+
+/// It applies a moderation action to many torrents at once.
+///
+/// It returns:
+///
+/// - `200` response with a json containing the summary [`BulkActionSummary`](crate::services::torrent::BulkActionSummary).
+///
+/// # Errors
+///
+/// It returns an error if:
+///
+/// - The user is not an administrator.
+/// - There are no info-hashes or too many of them.
+/// - The category or tag of the action is missing or does not exist.
+/// - There is a database error.
+#[utoipa::path(
+    post,
+    path = "/v1/admin/torrents/bulk",
+    tag = "admin",
+    request_body = BulkActionRequest,
+    responses(
+        (status = 200, description = "The torrents changed by the action"),
+        (status = 400, description = "The request is not valid", body = ErrorResponseData),
+        (status = 401, description = "The user is not logged in", body = ErrorResponseData),
+        (status = 403, description = "The user does not have permissions to perform the action", body = ErrorResponseData),
+        (status = 404, description = "The tag does not exist", body = ErrorResponseData),
+    ),
+    security(("bearer_auth" = []))
+)]
+#[allow(clippy::unused_async)]
+pub async fn bulk_action_handler(
+    State(app_data): State<Arc<AppData>>,
+    ExtractOptionalLoggedInUser(maybe_user_id): ExtractOptionalLoggedInUser,
+    extract::Json(request): extract::Json<BulkActionRequest>,
+) -> Response {
+    match app_data.torrent_service.apply_bulk_action(&request, maybe_user_id).await {
+        Ok(summary) => Json(OkResponseData { data: summary }).into_response(),
+        Err(error) => error.into_response(),
+    }
+}
+
+/// It rejects a torrent.
+///
+/// # Errors
+///
+/// It returns an error if:
+///
+/// - The user does not have permissions to moderate torrents.
+/// - The torrent does not exist.
+/// - There is a database error.
+#[utoipa::path(
+    post,
+    path = "/v1/admin/torrent/{torrent_id}/reject",
+    tag = "admin",
+    params(
+        ("torrent_id" = i64, Path, description = "The torrent ID"),
+    ),
+    responses(
+        (status = 200, description = "The rejected torrent"),
+        (status = 401, description = "The user is not logged in", body = ErrorResponseData),
+        (status = 403, description = "The user does not have permissions to perform the action", body = ErrorResponseData),
+        (status = 404, description = "The resource does not exist", body = ErrorResponseData),
+    ),
+    security(("bearer_auth" = []))
+)]
+#[allow(clippy::unused_async)]
+pub async fn reject_torrent_handler(
+    State(app_data): State<Arc<AppData>>,
+    ExtractOptionalLoggedInUser(maybe_user_id): ExtractOptionalLoggedInUser,
+    Path(torrent_id): Path<TorrentId>,
+) -> Response {
+    match app_data.torrent_service.reject_torrent(torrent_id, maybe_user_id).await {
+        Ok(torrent_listing) => moderated_torrent(torrent_listing).into_response(),
+        Err(error) => error.into_response(),
+    }
+}
+
+/// It returns the torrents in the trash bin, from the most recently deleted.
+///
+/// # Errors
+///
+/// It returns an error if:
+///
+/// - The user is not an administrator.
+/// - There is a database error.
+#[utoipa::path(
+    get,
+    path = "/v1/admin/torrents/trash",
+    tag = "admin",
+    responses(
+        (status = 200, description = "The torrents in the trash bin", body = [TrashedTorrent]),
+        (status = 401, description = "The user is not logged in", body = ErrorResponseData),
+        (status = 403, description = "The user does not have permissions to perform the action", body = ErrorResponseData),
+    ),
+    security(("bearer_auth" = []))
+)]
+#[allow(clippy::unused_async)]
+pub async fn get_trashed_torrents_handler(
+    State(app_data): State<Arc<AppData>>,
+    ExtractOptionalLoggedInUser(maybe_user_id): ExtractOptionalLoggedInUser,
+) -> Response {
+    match app_data.torrent_trash_service.get_trashed_torrents(maybe_user_id).await {
+        Ok(trashed_torrents) => Json(OkResponseData { data: trashed_torrents }).into_response(),
+        Err(error) => error.into_response(),
+    }
+}
+
+/// It restores a torrent from the trash bin.
+///
+/// # Errors
+///
+/// It returns an error if:
+///
+/// - The user is not an administrator.
+/// - The torrent is not in the trash bin.
+/// - There is a database error.
+#[utoipa::path(
+    post,
+    path = "/v1/admin/torrent/{torrent_id}/restore",
+    tag = "admin",
+    params(
+        ("torrent_id" = i64, Path, description = "The torrent ID"),
+    ),
+    responses(
+        (status = 200, description = "The restored torrent, as it was in the trash bin", body = TrashedTorrent),
+        (status = 401, description = "The user is not logged in", body = ErrorResponseData),
+        (status = 403, description = "The user does not have permissions to perform the action", body = ErrorResponseData),
+        (status = 404, description = "The resource does not exist", body = ErrorResponseData),
+    ),
+    security(("bearer_auth" = []))
+)]
+#[allow(clippy::unused_async)]
+pub async fn restore_torrent_handler(
+    State(app_data): State<Arc<AppData>>,
+    ExtractOptionalLoggedInUser(maybe_user_id): ExtractOptionalLoggedInUser,
+    Path(torrent_id): Path<TorrentId>,
+) -> Response {
+    match app_data
+        .torrent_trash_service
+        .restore_torrent(torrent_id, maybe_user_id)
+        .await
+    {
+        Ok(trashed_torrent) => Json(OkResponseData { data: trashed_torrent }).into_response(),
+        Err(error) => error.into_response(),
+    }
+}
+
+/// It removes a torrent in the trash bin for good, and from the tracker
+/// whitelist.
+///
+/// # Errors
+///
+/// It returns an error if:
+///
+/// - The user is not an administrator.
+/// - The torrent is not in the trash bin.
+/// - There is a database error.
+#[utoipa::path(
+    post,
+    path = "/v1/admin/torrent/{torrent_id}/purge",
+    tag = "admin",
+    params(
+        ("torrent_id" = i64, Path, description = "The torrent ID"),
+    ),
+    responses(
+        (status = 200, description = "The purged torrent", body = DeletedTorrentResponseData),
+        (status = 401, description = "The user is not logged in", body = ErrorResponseData),
+        (status = 403, description = "The user does not have permissions to perform the action", body = ErrorResponseData),
+        (status = 404, description = "The resource does not exist", body = ErrorResponseData),
+    ),
+    security(("bearer_auth" = []))
+)]
+#[allow(clippy::unused_async)]
+pub async fn purge_torrent_handler(
+    State(app_data): State<Arc<AppData>>,
+    ExtractOptionalLoggedInUser(maybe_user_id): ExtractOptionalLoggedInUser,
+    Path(torrent_id): Path<TorrentId>,
+) -> Response {
+    match app_data.torrent_trash_service.purge_torrent(torrent_id, maybe_user_id).await {
+        Ok(deleted_torrent_response) => Json(OkResponseData {
+            data: deleted_torrent_response,
+        })
+        .into_response(),
+        Err(error) => error.into_response(),
+    }
+}
+
+/// It handles the request to get a page of the torrent reports.
+///
+/// It returns:
+///
+/// - `200` response with a json containing the report list [`ReportsResponse`](crate::models::response::ReportsResponse).
+/// - Other error status codes if there is a database error.
+///
+/// # Errors
+///
+/// It returns an error if:
+///
+/// - The user does not have permissions to moderate reports.
+/// - There is a database error.
+#[utoipa::path(
+    get,
+    path = "/v1/admin/reports",
+    tag = "admin",
+    params(
+        ReportListingRequest,
+    ),
+    responses(
+        (status = 200, description = "The torrent reports"),
+        (status = 401, description = "The user is not logged in", body = ErrorResponseData),
+        (status = 403, description = "The user does not have permissions to perform the action", body = ErrorResponseData),
+    ),
+    security(("bearer_auth" = []))
+)]
+#[allow(clippy::unused_async)]
+pub async fn get_reports_handler(
+    State(app_data): State<Arc<AppData>>,
+    ExtractOptionalLoggedInUser(maybe_user_id): ExtractOptionalLoggedInUser,
+    Query(criteria): Query<ReportListingRequest>,
+) -> Response {
+    match app_data.report_service.get_reports(&criteria, maybe_user_id).await {
+        Ok(reports_response) => Json(OkResponseData { data: reports_response }).into_response(),
+        Err(error) => error.into_response(),
+    }
+}
+
+/// It resolves a torrent report.
+///
+/// # Errors
+///
+/// It returns an error if:
+///
+/// - The user does not have permissions to moderate reports.
+/// - The report does not exist or it's already resolved.
+/// - There is a database error.
+#[utoipa::path(
+    post,
+    path = "/v1/admin/report/{report_id}/resolve",
+    tag = "admin",
+    params(
+        ("report_id" = i64, Path, description = "The report ID"),
+    ),
+    request_body = ResolveReportForm,
+    responses(
+        (status = 200, description = "The resolved report"),
+        (status = 400, description = "The request is not valid", body = ErrorResponseData),
+        (status = 401, description = "The user is not logged in", body = ErrorResponseData),
+        (status = 403, description = "The user does not have permissions to perform the action", body = ErrorResponseData),
+        (status = 404, description = "The resource does not exist", body = ErrorResponseData),
+    ),
+    security(("bearer_auth" = []))
+)]
+#[allow(clippy::unused_async)]
+pub async fn resolve_report_handler(
+    State(app_data): State<Arc<AppData>>,
+    ExtractOptionalLoggedInUser(maybe_user_id): ExtractOptionalLoggedInUser,
+    Path(report_id): Path<ReportId>,
+    extract::Json(resolve_report_form): extract::Json<ResolveReportForm>,
+) -> Response {
+    match app_data
+        .report_service
+        .resolve_report(report_id, &resolve_report_form.action, maybe_user_id)
+        .await
+    {
+        Ok(resolved_report) => report(resolved_report).into_response(),
+        Err(error) => error.into_response(),
+    }
+}
+
+/// It returns the tracker statistics importer status.
+///
+/// It returns:
+///
+/// - `200` response with a json containing the importer status [`ImporterStatus`](crate::tracker::statistics_importer::ImporterStatus).
+///
+/// # Errors
+///
+/// It returns an error if the user does not have permissions to get the
+/// importer status.
+#[utoipa::path(
+    get,
+    path = "/v1/admin/stats-importer/status",
+    tag = "admin",
+    responses(
+        (status = 200, description = "The status of the tracker statistics importer"),
+        (status = 401, description = "The user is not logged in", body = ErrorResponseData),
+        (status = 403, description = "The user does not have permissions to perform the action", body = ErrorResponseData),
+    ),
+    security(("bearer_auth" = []))
+)]
+#[allow(clippy::unused_async)]
+pub async fn get_stats_importer_status_handler(
+    State(app_data): State<Arc<AppData>>,
+    ExtractOptionalLoggedInUser(maybe_user_id): ExtractOptionalLoggedInUser,
+) -> Response {
+    match app_data.statistics_importer_service.get_status(maybe_user_id).await {
+        Ok(status) => Json(OkResponseData { data: status }).into_response(),
+        Err(error) => error.into_response(),
+    }
+}
+
+/// It returns the number of `.torrent` downloads by client family.
+///
+/// It returns:
+///
+/// - `200` response with a json containing the statistics [`DownloadStatistics`](crate::services::download_statistics::DownloadStatistics).
+///
+/// # Errors
+///
+/// It returns an error if:
+///
+/// - The user does not have permissions to get the download statistics.
+/// - There is a database error.
+#[utoipa::path(
+    get,
+    path = "/v1/admin/stats/downloads",
+    tag = "admin",
+    params(
+        StatisticsRequest,
+    ),
+    responses(
+        (status = 200, description = "The download statistics"),
+        (status = 400, description = "The request is not valid", body = ErrorResponseData),
+        (status = 401, description = "The user is not logged in", body = ErrorResponseData),
+        (status = 403, description = "The user does not have permissions to perform the action", body = ErrorResponseData),
+    ),
+    security(("bearer_auth" = []))
+)]
+#[allow(clippy::unused_async)]
+pub async fn get_download_statistics_handler(
+    State(app_data): State<Arc<AppData>>,
+    ExtractOptionalLoggedInUser(maybe_user_id): ExtractOptionalLoggedInUser,
+    Query(request): Query<StatisticsRequest>,
+) -> Response {
+    match app_data
+        .download_statistics_service
+        .get_statistics(&request, maybe_user_id)
+        .await
+    {
+        Ok(statistics) => Json(OkResponseData { data: statistics }).into_response(),
+        Err(error) => error.into_response(),
+    }
+}
+
+/// It imports all the torrent files in a directory of the server.
+///
+/// It returns:
+///
+/// - `200` response with a json containing the import summary [`ImportSummary`](crate::services::bulk_import::ImportSummary).
+///
+/// # Errors
+///
+/// It returns an error if:
+///
+/// - The user does not have permissions to import torrents.
+/// - The directory, the manifest or the mapping file can't be read.
+/// - There is a database error.
+#[utoipa::path(
+    post,
+    path = "/v1/admin/torrents/import",
+    tag = "admin",
+    request_body = ImportRequest,
+    responses(
+        (status = 200, description = "The import summary"),
+        (status = 400, description = "The request is not valid", body = ErrorResponseData),
+        (status = 401, description = "The user is not logged in", body = ErrorResponseData),
+        (status = 403, description = "The user does not have permissions to perform the action", body = ErrorResponseData),
+    ),
+    security(("bearer_auth" = []))
+)]
+#[allow(clippy::unused_async)]
+pub async fn import_torrents_handler(
+    State(app_data): State<Arc<AppData>>,
+    ExtractOptionalLoggedInUser(maybe_user_id): ExtractOptionalLoggedInUser,
+    extract::Json(request): extract::Json<ImportRequest>,
+) -> Response {
+    match app_data.bulk_import_service.import(&request, maybe_user_id).await {
+        Ok(summary) => Json(OkResponseData { data: summary }).into_response(),
+        Err(error) => error.into_response(),
+    }
+}
+
+/// It creates an import session to push torrents from a remote peer in
+/// batches.
+///
+/// It returns:
+///
+/// - `200` response with a json containing the import session [`ImportSession`](crate::models::import_session::ImportSession).
+///
+/// # Errors
+///
+/// It returns an error if:
+///
+/// - The user does not have permissions to import torrents.
+/// - The source name is not valid.
+/// - There is a database error.
+#[utoipa::path(
+    post,
+    path = "/v1/admin/imports",
+    tag = "admin",
+    request_body = CreateImportSessionRequest,
+    responses(
+        (status = 200, description = "The new import session"),
+        (status = 400, description = "The request is not valid", body = ErrorResponseData),
+        (status = 401, description = "The user is not logged in", body = ErrorResponseData),
+        (status = 403, description = "The user does not have permissions to perform the action", body = ErrorResponseData),
+    ),
+    security(("bearer_auth" = []))
+)]
+#[allow(clippy::unused_async)]
+pub async fn create_import_session_handler(
+    State(app_data): State<Arc<AppData>>,
+    ExtractOptionalLoggedInUser(maybe_user_id): ExtractOptionalLoggedInUser,
+    extract::Json(request): extract::Json<CreateImportSessionRequest>,
+) -> Response {
+    match app_data.import_session_service.create_session(&request, maybe_user_id).await {
+        Ok(session) => Json(OkResponseData { data: session }).into_response(),
+        Err(error) => error.into_response(),
+    }
+}
+
+/// It returns the status of an import session, including the IDs of the
+/// processed batches.
+///
+/// It returns:
+///
+/// - `200` response with a json containing the import session [`ImportSession`](crate::models::import_session::ImportSession).
+///
+/// # Errors
+///
+/// It returns an error if:
+///
+/// - The user does not have permissions to import torrents.
+/// - The import session does not exist.
+/// - There is a database error.
+#[utoipa::path(
+    get,
+    path = "/v1/admin/imports/{import_id}",
+    tag = "admin",
+    params(
+        ("import_id" = i64, Path, description = "The import session ID"),
+    ),
+    responses(
+        (status = 200, description = "The import session"),
+        (status = 401, description = "The user is not logged in", body = ErrorResponseData),
+        (status = 403, description = "The user does not have permissions to perform the action", body = ErrorResponseData),
+        (status = 404, description = "The resource does not exist", body = ErrorResponseData),
+    ),
+    security(("bearer_auth" = []))
+)]
+#[allow(clippy::unused_async)]
+pub async fn get_import_session_handler(
+    State(app_data): State<Arc<AppData>>,
+    ExtractOptionalLoggedInUser(maybe_user_id): ExtractOptionalLoggedInUser,
+    Path(import_id): Path<ImportSessionId>,
+) -> Response {
+    match app_data.import_session_service.get_session(import_id, maybe_user_id).await {
+        Ok(session) => Json(OkResponseData { data: session }).into_response(),
+        Err(error) => error.into_response(),
+    }
+}
+
+/// It imports a batch of torrents into an import session. Pushing a batch
+/// that was already processed returns the summary of the first time.
+///
+/// It returns:
+///
+/// - `200` response with a json containing the batch summary [`BatchSummary`](crate::services::bulk_import::session::BatchSummary).
+///
+/// # Errors
+///
+/// It returns an error if:
+///
+/// - The user does not have permissions to import torrents.
+/// - The batch ID or the batch size is not valid.
+/// - The import session does not exist or it's completed.
+/// - There is a database error.
+#[utoipa::path(
+    put,
+    path = "/v1/admin/imports/{import_id}/batches/{batch_id}",
+    tag = "admin",
+    params(
+        ("import_id" = i64, Path, description = "The import session ID"),
+        ("batch_id" = String, Path, description = "The batch ID chosen by the peer"),
+    ),
+    request_body = ImportBatchRequest,
+    responses(
+        (status = 200, description = "The batch summary"),
+        (status = 400, description = "The request is not valid", body = ErrorResponseData),
+        (status = 401, description = "The user is not logged in", body = ErrorResponseData),
+        (status = 403, description = "The user does not have permissions to perform the action", body = ErrorResponseData),
+        (status = 404, description = "The resource does not exist", body = ErrorResponseData),
+        (status = 409, description = "The import session is already completed", body = ErrorResponseData),
+    ),
+    security(("bearer_auth" = []))
+)]
+#[allow(clippy::unused_async)]
+pub async fn import_batch_handler(
+    State(app_data): State<Arc<AppData>>,
+    ExtractOptionalLoggedInUser(maybe_user_id): ExtractOptionalLoggedInUser,
+    Path((import_id, batch_id)): Path<(ImportSessionId, String)>,
+    extract::Json(request): extract::Json<ImportBatchRequest>,
+) -> Response {
+    match app_data
+        .import_session_service
+        .import_batch(import_id, &batch_id, &request, maybe_user_id)
+        .await
+    {
+        Ok(summary) => Json(OkResponseData { data: summary }).into_response(),
+        Err(error) => error.into_response(),
+    }
+}
+
+/// It marks an import session as completed.
+///
+/// It returns:
+///
+/// - `200` response with a json containing the import session [`ImportSession`](crate::models::import_session::ImportSession).
+///
+/// # Errors
+///
+/// It returns an error if:
+///
+/// - The user does not have permissions to import torrents.
+/// - The import session does not exist.
+/// - There is a database error.
+#[utoipa::path(
+    post,
+    path = "/v1/admin/imports/{import_id}/complete",
+    tag = "admin",
+    params(
+        ("import_id" = i64, Path, description = "The import session ID"),
+    ),
+    responses(
+        (status = 200, description = "The completed import session"),
+        (status = 401, description = "The user is not logged in", body = ErrorResponseData),
+        (status = 403, description = "The user does not have permissions to perform the action", body = ErrorResponseData),
+        (status = 404, description = "The resource does not exist", body = ErrorResponseData),
+    ),
+    security(("bearer_auth" = []))
+)]
+#[allow(clippy::unused_async)]
+pub async fn complete_import_session_handler(
+    State(app_data): State<Arc<AppData>>,
+    ExtractOptionalLoggedInUser(maybe_user_id): ExtractOptionalLoggedInUser,
+    Path(import_id): Path<ImportSessionId>,
+) -> Response {
+    match app_data
+        .import_session_service
+        .complete_session(import_id, maybe_user_id)
+        .await
+    {
+        Ok(session) => Json(OkResponseData { data: session }).into_response(),
+        Err(error) => error.into_response(),
+    }
+}
+
+/// It sends a templated email to a segment of the users. The emails are added
+/// to the outbound email queue.
+///
+/// It returns:
+///
+/// - `200` response with a json containing the summary [`BulkMailSummary`](crate::services::bulk_mail::BulkMailSummary).
+///
+/// # Errors
+///
+/// It returns an error if:
+///
+/// - The user does not have permissions to send bulk emails.
+/// - The subject or the body are missing or they are not valid templates.
+/// - There is a database error.
+#[utoipa::path(
+    post,
+    path = "/v1/admin/mail",
+    tag = "admin",
+    request_body = BulkMailRequest,
+    responses(
+        (status = 200, description = "The number of queued emails"),
+        (status = 400, description = "The request is not valid", body = ErrorResponseData),
+        (status = 401, description = "The user is not logged in", body = ErrorResponseData),
+        (status = 403, description = "The user does not have permissions to perform the action", body = ErrorResponseData),
+    ),
+    security(("bearer_auth" = []))
+)]
+#[allow(clippy::unused_async)]
+pub async fn send_bulk_mail_handler(
+    State(app_data): State<Arc<AppData>>,
+    Host(host_from_header): Host,
+    ExtractOptionalLoggedInUser(maybe_user_id): ExtractOptionalLoggedInUser,
+    extract::Json(request): extract::Json<BulkMailRequest>,
+) -> Response {
+    let api_base_url = app_data
+        .cfg
+        .get_api_base_url()
+        .await
+        .unwrap_or(api_base_url(&host_from_header));
+
+    match app_data.bulk_mail_service.send(&request, &api_base_url, maybe_user_id).await {
+        Ok(summary) => Json(OkResponseData { data: summary }).into_response(),
+        Err(error) => error.into_response(),
+    }
+}
+
+/// It handles the request to get the upcoming actions of the account
+/// inactivity policy.
+///
+/// It returns:
+///
+/// - `200` response with a json containing the report [`InactivityReport`](crate::services::inactivity::InactivityReport).
+///
+/// # Errors
+///
+/// It returns an error if:
+///
+/// - The user does not have permissions to manage the inactivity policy.
+/// - There is a database error.
+#[utoipa::path(
+    get,
+    path = "/v1/admin/inactivity",
+    tag = "admin",
+    params(
+        InactivityReportRequest,
+    ),
+    responses(
+        (status = 200, description = "The inactive accounts"),
+        (status = 401, description = "The user is not logged in", body = ErrorResponseData),
+        (status = 403, description = "The user does not have permissions to perform the action", body = ErrorResponseData),
+    ),
+    security(("bearer_auth" = []))
+)]
+#[allow(clippy::unused_async)]
+pub async fn get_inactivity_report_handler(
+    State(app_data): State<Arc<AppData>>,
+    ExtractOptionalLoggedInUser(maybe_user_id): ExtractOptionalLoggedInUser,
+    Query(request): Query<InactivityReportRequest>,
+) -> Response {
+    match app_data.inactivity_service.get_report(&request, maybe_user_id).await {
+        Ok(report) => Json(OkResponseData { data: report }).into_response(),
+        Err(error) => error.into_response(),
+    }
+}
+
+/// It exempts a user from the account inactivity policy.
+///
+/// # Errors
+///
+/// It returns an error if:
+///
+/// - The user does not have permissions to manage the inactivity policy.
+/// - The user does not exist.
+/// - There is a database error.
+#[utoipa::path(
+    post,
+    path = "/v1/admin/inactivity/exemption/{username}",
+    tag = "admin",
+    params(
+        ("username" = String, Path, description = "The username"),
+    ),
+    responses(
+        (status = 200, description = "The username", body = MessageResponseData),
+        (status = 401, description = "The user is not logged in", body = ErrorResponseData),
+        (status = 403, description = "The user does not have permissions to perform the action", body = ErrorResponseData),
+        (status = 404, description = "The resource does not exist", body = ErrorResponseData),
+    ),
+    security(("bearer_auth" = []))
+)]
+#[allow(clippy::unused_async)]
+pub async fn add_inactivity_exemption_handler(
+    State(app_data): State<Arc<AppData>>,
+    Path(username): Path<UsernameParam>,
+    ExtractOptionalLoggedInUser(maybe_user_id): ExtractOptionalLoggedInUser,
+) -> Response {
+    match app_data.inactivity_service.add_exemption(&username.0, maybe_user_id).await {
+        Ok(()) => Json(OkResponseData {
+            data: format!("Exempted user: {}", username.0),
+        })
+        .into_response(),
+        Err(error) => error.into_response(),
+    }
+}
+
+/// It removes the account inactivity policy exemption of a user.
+///
+/// # Errors
+///
+/// It returns an error if:
+///
+/// - The user does not have permissions to manage the inactivity policy.
+/// - The user does not exist.
+/// - There is a database error.
+#[utoipa::path(
+    delete,
+    path = "/v1/admin/inactivity/exemption/{username}",
+    tag = "admin",
+    params(
+        ("username" = String, Path, description = "The username"),
+    ),
+    responses(
+        (status = 200, description = "The username", body = MessageResponseData),
+        (status = 401, description = "The user is not logged in", body = ErrorResponseData),
+        (status = 403, description = "The user does not have permissions to perform the action", body = ErrorResponseData),
+        (status = 404, description = "The resource does not exist", body = ErrorResponseData),
+    ),
+    security(("bearer_auth" = []))
+)]
+#[allow(clippy::unused_async)]
+pub async fn remove_inactivity_exemption_handler(
+    State(app_data): State<Arc<AppData>>,
+    Path(username): Path<UsernameParam>,
+    ExtractOptionalLoggedInUser(maybe_user_id): ExtractOptionalLoggedInUser,
+) -> Response {
+    match app_data.inactivity_service.remove_exemption(&username.0, maybe_user_id).await {
+        Ok(()) => Json(OkResponseData {
+            data: format!("Removed the exemption of user: {}", username.0),
+        })
+        .into_response(),
+        Err(error) => error.into_response(),
+    }
+}
+
+/// It handles the request to get the torrents each torrent rule would be
+/// applied to in the next evaluation. The rules are not applied.
+///
+/// It returns:
+///
+/// - `200` response with a json containing the report [`TorrentRulesReport`](crate::services::torrent_rules::TorrentRulesReport).
+///
+/// # Errors
+///
+/// It returns an error if:
+///
+/// - The user does not have permissions to manage the torrent rules.
+/// - There is a database error.
+#[utoipa::path(
+    get,
+    path = "/v1/admin/torrent-rules",
+    tag = "admin",
+    responses(
+        (status = 200, description = "The torrents matching each rule"),
+        (status = 401, description = "The user is not logged in", body = ErrorResponseData),
+        (status = 403, description = "The user does not have permissions to perform the action", body = ErrorResponseData),
+    ),
+    security(("bearer_auth" = []))
+)]
+#[allow(clippy::unused_async)]
+pub async fn get_torrent_rules_report_handler(
+    State(app_data): State<Arc<AppData>>,
+    ExtractOptionalLoggedInUser(maybe_user_id): ExtractOptionalLoggedInUser,
+) -> Response {
+    match app_data.torrent_rules_service.get_report(maybe_user_id).await {
+        Ok(report) => Json(OkResponseData { data: report }).into_response(),
+        Err(error) => error.into_response(),
+    }
+}
+
+/// It handles the request to get the torrents without seeders for longer
+/// than the `[torrent.health]` period. No action is taken.
+///
+/// It returns:
+///
+/// - `200` response with a json containing the report [`DeadTorrentsReport`](crate::services::torrent::health::DeadTorrentsReport).
+///
+/// # Errors
+///
+/// It returns an error if:
+///
+/// - The user is not an administrator.
+/// - There is a database error.
+#[utoipa::path(
+    get,
+    path = "/v1/admin/torrents/dead",
+    tag = "admin",
+    responses(
+        (status = 200, description = "The dead torrents"),
+        (status = 401, description = "The user is not logged in", body = ErrorResponseData),
+        (status = 403, description = "The user does not have permissions to perform the action", body = ErrorResponseData),
+    ),
+    security(("bearer_auth" = []))
+)]
+#[allow(clippy::unused_async)]
+pub async fn get_dead_torrents_report_handler(
+    State(app_data): State<Arc<AppData>>,
+    ExtractOptionalLoggedInUser(maybe_user_id): ExtractOptionalLoggedInUser,
+) -> Response {
+    match app_data.torrent_health_service.get_report(maybe_user_id).await {
+        Ok(report) => Json(OkResponseData { data: report }).into_response(),
+        Err(error) => error.into_response(),
+    }
+}
+
+/// It handles the request to get the banned patterns checked on uploads.
+///
+/// It returns:
+///
+/// - `200` response with a json containing the list of [`BannedPattern`].
+///
+/// # Errors
+///
+/// It returns an error if:
+///
+/// - The user is not an administrator.
+/// - There is a database error.
+#[utoipa::path(
+    get,
+    path = "/v1/admin/antispam/patterns",
+    tag = "admin",
+    responses(
+        (status = 200, description = "The banned patterns", body = [BannedPattern]),
+        (status = 401, description = "The user is not logged in", body = ErrorResponseData),
+        (status = 403, description = "The user does not have permissions to perform the action", body = ErrorResponseData),
+    ),
+    security(("bearer_auth" = []))
+)]
+#[allow(clippy::unused_async)]
+pub async fn get_banned_patterns_handler(
+    State(app_data): State<Arc<AppData>>,
+    ExtractOptionalLoggedInUser(maybe_user_id): ExtractOptionalLoggedInUser,
+) -> Response {
+    match app_data.antispam_service.get_patterns(maybe_user_id).await {
+        Ok(patterns) => Json(OkResponseData { data: patterns }).into_response(),
+        Err(error) => error.into_response(),
+    }
+}
+
+/// It adds a banned pattern.
+///
+/// It returns:
+///
+/// - `200` response with a json containing the new pattern ID.
+///
+/// # Errors
+///
+/// It returns an error if:
+///
+/// - The user is not an administrator.
+/// - The pattern is not a valid regular expression.
+/// - The pattern already exists.
+/// - There is a database error.
+#[utoipa::path(
+    post,
+    path = "/v1/admin/antispam/patterns",
+    tag = "admin",
+    request_body = AddBannedPatternRequest,
+    responses(
+        (status = 200, description = "The pattern ID"),
+        (status = 400, description = "The pattern is not valid", body = ErrorResponseData),
+        (status = 401, description = "The user is not logged in", body = ErrorResponseData),
+        (status = 403, description = "The user does not have permissions to perform the action", body = ErrorResponseData),
+        (status = 409, description = "The pattern already exists", body = ErrorResponseData),
+    ),
+    security(("bearer_auth" = []))
+)]
+#[allow(clippy::unused_async)]
+pub async fn add_banned_pattern_handler(
+    State(app_data): State<Arc<AppData>>,
+    ExtractOptionalLoggedInUser(maybe_user_id): ExtractOptionalLoggedInUser,
+    extract::Json(request): extract::Json<AddBannedPatternRequest>,
+) -> Response {
+    match app_data.antispam_service.add_pattern(&request, maybe_user_id).await {
+        Ok(pattern_id) => Json(OkResponseData { data: pattern_id }).into_response(),
+        Err(error) => error.into_response(),
+    }
+}
+
+/// It deletes a banned pattern.
+///
+/// # Errors
+///
+/// It returns an error if:
+///
+/// - The user is not an administrator.
+/// - The pattern does not exist.
+/// - There is a database error.
+#[utoipa::path(
+    delete,
+    path = "/v1/admin/antispam/patterns/{pattern_id}",
+    tag = "admin",
+    params(
+        ("pattern_id" = i64, Path, description = "The pattern ID"),
+    ),
+    responses(
+        (status = 200, description = "The pattern ID"),
+        (status = 401, description = "The user is not logged in", body = ErrorResponseData),
+        (status = 403, description = "The user does not have permissions to perform the action", body = ErrorResponseData),
+        (status = 404, description = "The pattern does not exist", body = ErrorResponseData),
+    ),
+    security(("bearer_auth" = []))
+)]
+#[allow(clippy::unused_async)]
+pub async fn delete_banned_pattern_handler(
+    State(app_data): State<Arc<AppData>>,
+    ExtractOptionalLoggedInUser(maybe_user_id): ExtractOptionalLoggedInUser,
+    Path(pattern_id): Path<PatternId>,
+) -> Response {
+    match app_data.antispam_service.delete_pattern(pattern_id, maybe_user_id).await {
+        Ok(()) => Json(OkResponseData { data: pattern_id }).into_response(),
+        Err(error) => error.into_response(),
+    }
+}
+
+/// It returns the upload quota of a user: the limits that apply and the
+/// current usage.
+///
+/// # Errors
+///
+/// It returns an error if:
+///
+/// - The user does not have permissions to manage the upload quotas.
+/// - The user does not exist.
+/// - There is a database error.
+#[utoipa::path(
+    get,
+    path = "/v1/admin/quota/{username}",
+    tag = "admin",
+    params(
+        ("username" = String, Path, description = "The username"),
+    ),
+    responses(
+        (status = 200, description = "The upload quota", body = UploadQuota),
+        (status = 401, description = "The user is not logged in", body = ErrorResponseData),
+        (status = 403, description = "The user does not have permissions to perform the action", body = ErrorResponseData),
+        (status = 404, description = "The resource does not exist", body = ErrorResponseData),
+    ),
+    security(("bearer_auth" = []))
+)]
+#[allow(clippy::unused_async)]
+pub async fn get_upload_quota_handler(
+    State(app_data): State<Arc<AppData>>,
+    Path(username): Path<UsernameParam>,
+    ExtractOptionalLoggedInUser(maybe_user_id): ExtractOptionalLoggedInUser,
+) -> Response {
+    match app_data.upload_quota_service.get_quota(&username.0, maybe_user_id).await {
+        Ok(quota) => Json(OkResponseData { data: quota }).into_response(),
+        Err(error) => error.into_response(),
+    }
+}
+
+/// It sets the upload limits of a user. They override the `[limits]`
+/// configuration for that user.
+///
+/// # Errors
+///
+/// It returns an error if:
+///
+/// - The user does not have permissions to manage the upload quotas.
+/// - The user does not exist.
+/// - There is a database error.
+#[utoipa::path(
+    put,
+    path = "/v1/admin/quota/{username}",
+    tag = "admin",
+    params(
+        ("username" = String, Path, description = "The username"),
+    ),
+    request_body = UploadLimitsForm,
+    responses(
+        (status = 200, description = "The username", body = MessageResponseData),
+        (status = 401, description = "The user is not logged in", body = ErrorResponseData),
+        (status = 403, description = "The user does not have permissions to perform the action", body = ErrorResponseData),
+        (status = 404, description = "The resource does not exist", body = ErrorResponseData),
+    ),
+    security(("bearer_auth" = []))
+)]
+#[allow(clippy::unused_async)]
+pub async fn set_upload_limits_handler(
+    State(app_data): State<Arc<AppData>>,
+    Path(username): Path<UsernameParam>,
+    ExtractOptionalLoggedInUser(maybe_user_id): ExtractOptionalLoggedInUser,
+    extract::Json(form): extract::Json<UploadLimitsForm>,
+) -> Response {
+    let limits = UploadLimits {
+        max_uploads_per_day: form.max_uploads_per_day,
+        max_total_upload_size: form.max_total_upload_size,
+    };
+
+    match app_data
+        .upload_quota_service
+        .set_limits(&username.0, &limits, maybe_user_id)
+        .await
+    {
+        Ok(()) => Json(OkResponseData {
+            data: format!("Set the upload limits of user: {}", username.0),
+        })
+        .into_response(),
+        Err(error) => error.into_response(),
+    }
+}
+
+/// It removes the upload limits set for a user. The `[limits]` configuration
+/// applies again.
+///
+/// # Errors
+///
+/// It returns an error if:
+///
+/// - The user does not have permissions to manage the upload quotas.
+/// - The user does not exist.
+/// - There is a database error.
+#[utoipa::path(
+    delete,
+    path = "/v1/admin/quota/{username}",
+    tag = "admin",
+    params(
+        ("username" = String, Path, description = "The username"),
+    ),
+    responses(
+        (status = 200, description = "The username", body = MessageResponseData),
+        (status = 401, description = "The user is not logged in", body = ErrorResponseData),
+        (status = 403, description = "The user does not have permissions to perform the action", body = ErrorResponseData),
+        (status = 404, description = "The resource does not exist", body = ErrorResponseData),
+    ),
+    security(("bearer_auth" = []))
+)]
+#[allow(clippy::unused_async)]
+pub async fn remove_upload_limits_handler(
+    State(app_data): State<Arc<AppData>>,
+    Path(username): Path<UsernameParam>,
+    ExtractOptionalLoggedInUser(maybe_user_id): ExtractOptionalLoggedInUser,
+) -> Response {
+    match app_data.upload_quota_service.remove_limits(&username.0, maybe_user_id).await {
+        Ok(()) => Json(OkResponseData {
+            data: format!("Removed the upload limits of user: {}", username.0),
+        })
+        .into_response(),
+        Err(error) => error.into_response(),
+    }
+}
+
+/// It returns the reputation of a user: the uploads and reports counted and
+/// whether the user is a trusted uploader.
+///
+/// # Errors
+///
+/// It returns an error if:
+///
+/// - The user does not have permissions to manage the reputation.
+/// - The user does not exist.
+/// - There is a database error.
+#[utoipa::path(
+    get,
+    path = "/v1/admin/reputation/{username}",
+    tag = "admin",
+    params(
+        ("username" = String, Path, description = "The username"),
+    ),
+    responses(
+        (status = 200, description = "The user reputation", body = UserReputation),
+        (status = 401, description = "The user is not logged in", body = ErrorResponseData),
+        (status = 403, description = "The user does not have permissions to perform the action", body = ErrorResponseData),
+        (status = 404, description = "The resource does not exist", body = ErrorResponseData),
+    ),
+    security(("bearer_auth" = []))
+)]
+#[allow(clippy::unused_async)]
+pub async fn get_reputation_handler(
+    State(app_data): State<Arc<AppData>>,
+    Path(username): Path<UsernameParam>,
+    ExtractOptionalLoggedInUser(maybe_user_id): ExtractOptionalLoggedInUser,
+) -> Response {
+    match app_data.reputation_service.get_reputation(&username.0, maybe_user_id).await {
+        Ok(reputation) => Json(OkResponseData { data: reputation }).into_response(),
+        Err(error) => error.into_response(),
+    }
+}
+
+/// It trusts or distrusts a user regardless of the `[reputation]`
+/// thresholds.
+///
+/// # Errors
+///
+/// It returns an error if:
+///
+/// - The user does not have permissions to manage the reputation.
+/// - The user does not exist.
+/// - There is a database error.
+#[utoipa::path(
+    put,
+    path = "/v1/admin/reputation/{username}",
+    tag = "admin",
+    params(
+        ("username" = String, Path, description = "The username"),
+    ),
+    request_body = TrustedStatusForm,
+    responses(
+        (status = 200, description = "The username", body = MessageResponseData),
+        (status = 401, description = "The user is not logged in", body = ErrorResponseData),
+        (status = 403, description = "The user does not have permissions to perform the action", body = ErrorResponseData),
+        (status = 404, description = "The resource does not exist", body = ErrorResponseData),
+    ),
+    security(("bearer_auth" = []))
+)]
+#[allow(clippy::unused_async)]
+pub async fn set_trusted_status_handler(
+    State(app_data): State<Arc<AppData>>,
+    Path(username): Path<UsernameParam>,
+    ExtractOptionalLoggedInUser(maybe_user_id): ExtractOptionalLoggedInUser,
+    extract::Json(form): extract::Json<TrustedStatusForm>,
+) -> Response {
+    match app_data
+        .reputation_service
+        .set_trusted(&username.0, form.trusted, maybe_user_id)
+        .await
+    {
+        Ok(()) => Json(OkResponseData {
+            data: format!("Set the trusted status of user: {}", username.0),
+        })
+        .into_response(),
+        Err(error) => error.into_response(),
+    }
+}
+
+/// It removes the trusted status set for a user. The `[reputation]`
+/// thresholds apply again.
+///
+/// # Errors
+///
+/// It returns an error if:
+///
+/// - The user does not have permissions to manage the reputation.
+/// - The user does not exist.
+/// - There is a database error.
+#[utoipa::path(
+    delete,
+    path = "/v1/admin/reputation/{username}",
+    tag = "admin",
+    params(
+        ("username" = String, Path, description = "The username"),
+    ),
+    responses(
+        (status = 200, description = "The username", body = MessageResponseData),
+        (status = 401, description = "The user is not logged in", body = ErrorResponseData),
+        (status = 403, description = "The user does not have permissions to perform the action", body = ErrorResponseData),
+        (status = 404, description = "The resource does not exist", body = ErrorResponseData),
+    ),
+    security(("bearer_auth" = []))
+)]
+#[allow(clippy::unused_async)]
+pub async fn remove_trusted_status_handler(
+    State(app_data): State<Arc<AppData>>,
+    Path(username): Path<UsernameParam>,
+    ExtractOptionalLoggedInUser(maybe_user_id): ExtractOptionalLoggedInUser,
+) -> Response {
+    match app_data.reputation_service.remove_override(&username.0, maybe_user_id).await {
+        Ok(()) => Json(OkResponseData {
+            data: format!("Removed the trusted status of user: {}", username.0),
+        })
+        .into_response(),
+        Err(error) => error.into_response(),
+    }
+}
+
+/// It returns the current state of the rate limit buckets, optionally only
+/// the ones of a user or a guest IP address.
+///
+/// # Errors
+///
+/// It returns an error if:
+///
+/// - The user does not have permissions to inspect the rate limits.
+/// - The user in the filter does not exist.
+#[utoipa::path(
+    get,
+    path = "/v1/admin/rate-limits",
+    tag = "admin",
+    params(
+        RateLimitCountersRequest,
+    ),
+    responses(
+        (status = 200, description = "The rate limit counters", body = [RateLimitCounter]),
+        (status = 401, description = "The user is not logged in", body = ErrorResponseData),
+        (status = 403, description = "The user does not have permissions to perform the action", body = ErrorResponseData),
+        (status = 404, description = "The resource does not exist", body = ErrorResponseData),
+    ),
+    security(("bearer_auth" = []))
+)]
+#[allow(clippy::unused_async)]
+pub async fn get_rate_limit_counters_handler(
+    State(app_data): State<Arc<AppData>>,
+    ExtractOptionalLoggedInUser(maybe_user_id): ExtractOptionalLoggedInUser,
+    Query(request): Query<RateLimitCountersRequest>,
+) -> Response {
+    match app_data
+        .api_usage_service
+        .get_rate_limit_counters(&request, maybe_user_id)
+        .await
+    {
+        Ok(counters) => Json(OkResponseData { data: counters }).into_response(),
+        Err(error) => error.into_response(),
+    }
+}
+
+/// It returns a page of the audit log with the actions taken by
+/// administrators and moderators, from the newest to the oldest.
+///
+/// # Errors
+///
+/// It returns an error if:
+///
+/// - The user does not have permissions to read the audit log.
+/// - The date range is not valid.
+/// - There is a database error.
+#[utoipa::path(
+    get,
+    path = "/v1/admin/audit-log",
+    tag = "admin",
+    params(
+        AuditLogRequest,
+    ),
+    responses(
+        (status = 200, description = "A page of the audit log", body = AuditLogResponse),
+        (status = 400, description = "Invalid date range", body = ErrorResponseData),
+        (status = 401, description = "The user is not logged in", body = ErrorResponseData),
+        (status = 403, description = "The user does not have permissions to perform the action", body = ErrorResponseData),
+    ),
+    security(("bearer_auth" = []))
+)]
+#[allow(clippy::unused_async)]
+pub async fn get_audit_log_handler(
+    State(app_data): State<Arc<AppData>>,
+    ExtractOptionalLoggedInUser(maybe_user_id): ExtractOptionalLoggedInUser,
+    Query(request): Query<AuditLogRequest>,
+) -> Response {
+    match app_data.audit_service.get_log(&request, maybe_user_id).await {
+        Ok(audit_log) => Json(OkResponseData { data: audit_log }).into_response(),
+        Err(error) => error.into_response(),
+    }
+}
+
+/// It returns the seeding summaries published after a summary, oldest
+/// first. External bonus systems use it to pull the summaries.
+///
+/// # Errors
+///
+/// It returns an error if:
+///
+/// - The user does not have permissions to manage the points.
+/// - There is a database error.
+#[utoipa::path(
+    get,
+    path = "/v1/admin/seeding-summaries",
+    tag = "admin",
+    params(
+        SummariesRequest,
+    ),
+    responses(
+        (status = 200, description = "The seeding summaries", body = [SeedingSummaryEvent]),
+        (status = 401, description = "The user is not logged in", body = ErrorResponseData),
+        (status = 403, description = "The user does not have permissions to perform the action", body = ErrorResponseData),
+    ),
+    security(("bearer_auth" = []))
+)]
+#[allow(clippy::unused_async)]
+pub async fn get_seeding_summaries_handler(
+    State(app_data): State<Arc<AppData>>,
+    ExtractOptionalLoggedInUser(maybe_user_id): ExtractOptionalLoggedInUser,
+    Query(request): Query<SummariesRequest>,
+) -> Response {
+    match app_data.seeding_rewards_service.get_summaries(&request, maybe_user_id).await {
+        Ok(summaries) => Json(OkResponseData { data: summaries }).into_response(),
+        Err(error) => error.into_response(),
+    }
+}
+
+/// It returns the points balance of a user with the latest adjustments.
+///
+/// # Errors
+///
+/// It returns an error if:
+///
+/// - The user does not have permissions to manage the points.
+/// - The user does not exist.
+/// - There is a database error.
+#[utoipa::path(
+    get,
+    path = "/v1/admin/points/{username}",
+    tag = "admin",
+    params(
+        ("username" = String, Path, description = "The username"),
+    ),
+    responses(
+        (status = 200, description = "The points balance", body = PointsBalance),
+        (status = 401, description = "The user is not logged in", body = ErrorResponseData),
+        (status = 403, description = "The user does not have permissions to perform the action", body = ErrorResponseData),
+        (status = 404, description = "The resource does not exist", body = ErrorResponseData),
+    ),
+    security(("bearer_auth" = []))
+)]
+#[allow(clippy::unused_async)]
+pub async fn get_points_handler(
+    State(app_data): State<Arc<AppData>>,
+    Path(username): Path<UsernameParam>,
+    ExtractOptionalLoggedInUser(maybe_user_id): ExtractOptionalLoggedInUser,
+) -> Response {
+    match app_data.seeding_rewards_service.get_points(&username.0, maybe_user_id).await {
+        Ok(points_balance) => Json(OkResponseData { data: points_balance }).into_response(),
+        Err(error) => error.into_response(),
+    }
+}
+
+/// It adjusts the points balance of a user. Adjustments with a reference
+/// already used for the user are ignored, so external systems can retry
+/// them safely.
+///
+/// # Errors
+///
+/// It returns an error if:
+///
+/// - The user does not have permissions to manage the points.
+/// - The adjustment is not valid.
+/// - The user does not exist.
+/// - There is a database error.
+#[utoipa::path(
+    post,
+    path = "/v1/admin/points/{username}",
+    tag = "admin",
+    params(
+        ("username" = String, Path, description = "The username"),
+    ),
+    request_body = AdjustPointsRequest,
+    responses(
+        (status = 200, description = "The updated points balance", body = PointsBalance),
+        (status = 400, description = "The request is not valid", body = ErrorResponseData),
+        (status = 401, description = "The user is not logged in", body = ErrorResponseData),
+        (status = 403, description = "The user does not have permissions to perform the action", body = ErrorResponseData),
+        (status = 404, description = "The resource does not exist", body = ErrorResponseData),
+    ),
+    security(("bearer_auth" = []))
+)]
+#[allow(clippy::unused_async)]
+pub async fn adjust_points_handler(
+    State(app_data): State<Arc<AppData>>,
+    Path(username): Path<UsernameParam>,
+    ExtractOptionalLoggedInUser(maybe_user_id): ExtractOptionalLoggedInUser,
+    extract::Json(request): extract::Json<AdjustPointsRequest>,
+) -> Response {
+    match app_data
+        .seeding_rewards_service
+        .adjust_points(&username.0, &request, maybe_user_id)
+        .await
+    {
+        Ok(points_balance) => Json(OkResponseData { data: points_balance }).into_response(),
+        Err(error) => error.into_response(),
+    }
+}
+
+/// It reloads the settings without restarting the application.
+///
+/// # Errors
+///
+/// It returns an error if:
+///
+/// - The user does not have permissions to reload the settings.
+/// - The new settings can't be loaded or they are not valid.
+#[utoipa::path(
+    post,
+    path = "/v1/admin/settings/reload",
+    tag = "admin",
+    responses(
+        (status = 200, description = "The options that took effect and the ones that require a restart"),
+        (status = 401, description = "The user is not logged in", body = ErrorResponseData),
+        (status = 403, description = "The user does not have permissions to perform the action", body = ErrorResponseData),
+        (status = 500, description = "The new settings can't be loaded or they are not valid", body = ErrorResponseData),
+    ),
+    security(("bearer_auth" = []))
+)]
+#[allow(clippy::unused_async)]
+pub async fn reload_settings_handler(
+    State(app_data): State<Arc<AppData>>,
+    ExtractOptionalLoggedInUser(maybe_user_id): ExtractOptionalLoggedInUser,
+) -> Response {
+    match app_data.settings_service.reload(maybe_user_id).await {
+        Ok(changes) => Json(OkResponseData { data: changes }).into_response(),
+        Err(error) => error.into_response(),
+    }
+}
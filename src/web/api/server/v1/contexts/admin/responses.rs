@@ -0,0 +1,11 @@
+//! API responses for the [`admin`](crate::web::api::server::v1::contexts::admin) API
+//! context.
+use axum::Json;
+
+use crate::models::torrent::TorrentListing;
+use crate::web::api::server::v1::responses::OkResponseData;
+
+/// Response after successfully approving or rejecting a torrent.
+pub fn moderated_torrent(torrent_listing: TorrentListing) -> Json<OkResponseData<TorrentListing>> {
+    Json(OkResponseData { data: torrent_listing })
+}
@@ -0,0 +1,140 @@
+//! API routes for the [`admin`](crate::web::api::server::v1::contexts::admin) API context.
+//!
+//! Refer to the [API endpoint documentation](crate::web::api::server::v1::contexts::admin).
+use std::sync::Arc;
+
+use axum::routing::{delete, get, post, put};
+use axum::Router;
+
+use super::handlers::{
+    add_banned_pattern_handler, add_inactivity_exemption_handler, adjust_points_handler, approve_torrent_handler,
+    bulk_action_handler, complete_import_session_handler, create_import_session_handler, delete_banned_pattern_handler,
+    get_audit_log_handler, get_banned_patterns_handler, get_dead_torrents_report_handler, get_download_statistics_handler,
+    get_import_session_handler, get_inactivity_report_handler, get_pending_torrents_handler, get_points_handler,
+    get_rate_limit_counters_handler, get_reports_handler, get_reputation_handler, get_seeding_summaries_handler,
+    get_stats_importer_status_handler, get_torrent_rules_report_handler, get_trashed_torrents_handler, get_upload_quota_handler,
+    import_batch_handler, import_torrents_handler, purge_torrent_handler, reject_torrent_handler, reload_settings_handler,
+    remove_inactivity_exemption_handler, remove_trusted_status_handler, remove_upload_limits_handler, resolve_report_handler,
+    restore_torrent_handler, send_bulk_mail_handler, set_trusted_status_handler, set_upload_limits_handler,
+};
+use crate::common::AppData;
+
+/// Routes for the [`admin`](crate::web::api::server::v1::contexts::admin) API context.
+pub fn router(app_data: Arc<AppData>) -> Router {
+    Router::new()
+        .route(
+            "/torrents/pending",
+            get(get_pending_torrents_handler).with_state(app_data.clone()),
+        )
+        .route(
+            "/torrent/:torrent_id/approve",
+            post(approve_torrent_handler).with_state(app_data.clone()),
+        )
+        .route(
+            "/torrent/:torrent_id/reject",
+            post(reject_torrent_handler).with_state(app_data.clone()),
+        )
+        .route("/torrents/bulk", post(bulk_action_handler).with_state(app_data.clone()))
+        .route(
+            "/torrents/trash",
+            get(get_trashed_torrents_handler).with_state(app_data.clone()),
+        )
+        .route(
+            "/torrent/:torrent_id/restore",
+            post(restore_torrent_handler).with_state(app_data.clone()),
+        )
+        .route(
+            "/torrent/:torrent_id/purge",
+            post(purge_torrent_handler).with_state(app_data.clone()),
+        )
+        .route("/reports", get(get_reports_handler).with_state(app_data.clone()))
+        .route(
+            "/report/:report_id/resolve",
+            post(resolve_report_handler).with_state(app_data.clone()),
+        )
+        .route(
+            "/stats-importer/status",
+            get(get_stats_importer_status_handler).with_state(app_data.clone()),
+        )
+        .route(
+            "/stats/downloads",
+            get(get_download_statistics_handler).with_state(app_data.clone()),
+        )
+        .route("/torrents/import", post(import_torrents_handler).with_state(app_data.clone()))
+        .route("/imports", post(create_import_session_handler).with_state(app_data.clone()))
+        .route(
+            "/imports/:import_id",
+            get(get_import_session_handler).with_state(app_data.clone()),
+        )
+        .route(
+            "/imports/:import_id/batches/:batch_id",
+            put(import_batch_handler).with_state(app_data.clone()),
+        )
+        .route(
+            "/imports/:import_id/complete",
+            post(complete_import_session_handler).with_state(app_data.clone()),
+        )
+        .route("/mail", post(send_bulk_mail_handler).with_state(app_data.clone()))
+        .route("/inactivity", get(get_inactivity_report_handler).with_state(app_data.clone()))
+        .route(
+            "/inactivity/exemption/:username",
+            post(add_inactivity_exemption_handler)
+                .with_state(app_data.clone())
+                .delete(remove_inactivity_exemption_handler)
+                .with_state(app_data.clone()),
+        )
+        .route(
+            "/torrent-rules",
+            get(get_torrent_rules_report_handler).with_state(app_data.clone()),
+        )
+        .route(
+            "/torrents/dead",
+            get(get_dead_torrents_report_handler).with_state(app_data.clone()),
+        )
+        .route(
+            "/antispam/patterns",
+            get(get_banned_patterns_handler)
+                .with_state(app_data.clone())
+                .post(add_banned_pattern_handler)
+                .with_state(app_data.clone()),
+        )
+        .route(
+            "/antispam/patterns/:pattern_id",
+            delete(delete_banned_pattern_handler).with_state(app_data.clone()),
+        )
+        .route(
+            "/quota/:username",
+            get(get_upload_quota_handler)
+                .with_state(app_data.clone())
+                .put(set_upload_limits_handler)
+                .with_state(app_data.clone())
+                .delete(remove_upload_limits_handler)
+                .with_state(app_data.clone()),
+        )
+        .route(
+            "/reputation/:username",
+            get(get_reputation_handler)
+                .with_state(app_data.clone())
+                .put(set_trusted_status_handler)
+                .with_state(app_data.clone())
+                .delete(remove_trusted_status_handler)
+                .with_state(app_data.clone()),
+        )
+        .route(
+            "/rate-limits",
+            get(get_rate_limit_counters_handler).with_state(app_data.clone()),
+        )
+        .route("/audit-log", get(get_audit_log_handler).with_state(app_data.clone()))
+        .route("/settings/reload", post(reload_settings_handler).with_state(app_data.clone()))
+        .route(
+            "/seeding-summaries",
+            get(get_seeding_summaries_handler).with_state(app_data.clone()),
+        )
+        .route(
+            "/points/:username",
+            get(get_points_handler)
+                .with_state(app_data.clone())
+                .post(adjust_points_handler)
+                .with_state(app_data),
+        )
+}
@@ -0,0 +1,202 @@
+//! API handlers for the [`bookmark`](crate::web::api::server::v1::contexts::bookmark) API
+//! context.
+use std::str::FromStr;
+use std::sync::Arc;
+
+use axum::extract::{Path, Query, State};
+use axum::response::{IntoResponse, Json, Response};
+use bittorrent_primitives::info_hash::InfoHash;
+
+use super::responses::{bookmark, bookmarks_file};
+use crate::common::AppData;
+use crate::services::bookmark::{BookmarksFile, ListingRequest};
+use crate::web::api::server::v1::contexts::torrent::errors;
+use crate::web::api::server::v1::extractors::optional_user_id::ExtractOptionalLoggedInUser;
+use crate::web::api::server::v1::responses::{ErrorResponseData, OkResponseData};
+
+/// It bookmarks a torrent for the logged-in user.
+///
+/// # Errors
+///
+/// It returns an error if:
+///
+/// - The user is not logged in.
+/// - The info-hash is not valid or the torrent does not exist.
+/// - There is a database error.
+#[utoipa::path(
+    post,
+    path = "/v1/torrent/{info_hash}/bookmark",
+    tag = "bookmark",
+    params(
+        ("info_hash" = String, Path, description = "The torrent info-hash"),
+    ),
+    responses(
+        (status = 200, description = "The bookmark"),
+        (status = 400, description = "The request is not valid", body = ErrorResponseData),
+        (status = 401, description = "The user is not logged in", body = ErrorResponseData),
+        (status = 404, description = "The resource does not exist", body = ErrorResponseData),
+    ),
+    security(("bearer_auth" = []))
+)]
+#[allow(clippy::unused_async)]
+pub async fn add_bookmark_handler(
+    State(app_data): State<Arc<AppData>>,
+    ExtractOptionalLoggedInUser(maybe_user_id): ExtractOptionalLoggedInUser,
+    Path(info_hash): Path<String>,
+) -> Response {
+    let Ok(info_hash) = InfoHash::from_str(&info_hash.to_lowercase()) else {
+        return errors::Request::InvalidInfoHashParam.into_response();
+    };
+
+    match app_data.bookmark_service.add_bookmark(&info_hash, maybe_user_id).await {
+        Ok(torrent_id) => bookmark(torrent_id, true).into_response(),
+        Err(error) => error.into_response(),
+    }
+}
+
+/// It removes a torrent from the logged-in user bookmarks.
+///
+/// # Errors
+///
+/// It returns an error if:
+///
+/// - The user is not logged in.
+/// - The info-hash is not valid or the torrent is not bookmarked.
+/// - There is a database error.
+#[utoipa::path(
+    delete,
+    path = "/v1/torrent/{info_hash}/bookmark",
+    tag = "bookmark",
+    params(
+        ("info_hash" = String, Path, description = "The torrent info-hash"),
+    ),
+    responses(
+        (status = 200, description = "The deleted bookmark"),
+        (status = 400, description = "The request is not valid", body = ErrorResponseData),
+        (status = 401, description = "The user is not logged in", body = ErrorResponseData),
+        (status = 404, description = "The resource does not exist", body = ErrorResponseData),
+    ),
+    security(("bearer_auth" = []))
+)]
+#[allow(clippy::unused_async)]
+pub async fn delete_bookmark_handler(
+    State(app_data): State<Arc<AppData>>,
+    ExtractOptionalLoggedInUser(maybe_user_id): ExtractOptionalLoggedInUser,
+    Path(info_hash): Path<String>,
+) -> Response {
+    let Ok(info_hash) = InfoHash::from_str(&info_hash.to_lowercase()) else {
+        return errors::Request::InvalidInfoHashParam.into_response();
+    };
+
+    match app_data.bookmark_service.delete_bookmark(&info_hash, maybe_user_id).await {
+        Ok(torrent_id) => bookmark(torrent_id, false).into_response(),
+        Err(error) => error.into_response(),
+    }
+}
+
+/// It returns a page of the torrents bookmarked by the logged-in user.
+///
+/// It returns:
+///
+/// - `200` response with a json containing the torrent list [`TorrentsResponse`](crate::models::response::TorrentsResponse).
+///
+/// # Errors
+///
+/// It returns an error if:
+///
+/// - The user is not logged in.
+/// - There is a database error.
+#[utoipa::path(
+    get,
+    path = "/v1/user/bookmarks",
+    tag = "bookmark",
+    params(
+        ListingRequest,
+    ),
+    responses(
+        (status = 200, description = "The user's bookmarks"),
+        (status = 401, description = "The user is not logged in", body = ErrorResponseData),
+    ),
+    security(("bearer_auth" = []))
+)]
+#[allow(clippy::unused_async)]
+pub async fn get_bookmarks_handler(
+    State(app_data): State<Arc<AppData>>,
+    ExtractOptionalLoggedInUser(maybe_user_id): ExtractOptionalLoggedInUser,
+    Query(criteria): Query<ListingRequest>,
+) -> Response {
+    match app_data.bookmark_service.get_bookmarks(&criteria, maybe_user_id).await {
+        Ok(torrents_response) => Json(OkResponseData { data: torrents_response }).into_response(),
+        Err(error) => error.into_response(),
+    }
+}
+
+/// It exports all the logged-in user bookmarks to a portable JSON file.
+///
+/// It returns:
+///
+/// - `200` response with the [`BookmarksFile`] as an attachment.
+///
+/// # Errors
+///
+/// It returns an error if:
+///
+/// - The user is not logged in.
+/// - There is a database error.
+#[utoipa::path(
+    get,
+    path = "/v1/user/bookmarks/export",
+    tag = "bookmark",
+    responses(
+        (status = 200, description = "The bookmarks file", body = BookmarksFile),
+        (status = 401, description = "The user is not logged in", body = ErrorResponseData),
+    ),
+    security(("bearer_auth" = []))
+)]
+#[allow(clippy::unused_async)]
+pub async fn export_bookmarks_handler(
+    State(app_data): State<Arc<AppData>>,
+    ExtractOptionalLoggedInUser(maybe_user_id): ExtractOptionalLoggedInUser,
+) -> Response {
+    match app_data.bookmark_service.export_bookmarks(maybe_user_id).await {
+        Ok(file) => bookmarks_file(file),
+        Err(error) => error.into_response(),
+    }
+}
+
+/// It imports the bookmarks in a bookmarks file for the logged-in user.
+///
+/// It returns:
+///
+/// - `200` response with the [`ImportSummary`](crate::services::bookmark::ImportSummary).
+///
+/// # Errors
+///
+/// It returns an error if:
+///
+/// - The user is not logged in.
+/// - The file format or version is not supported.
+/// - There is a database error.
+#[utoipa::path(
+    post,
+    path = "/v1/user/bookmarks/import",
+    tag = "bookmark",
+    request_body = BookmarksFile,
+    responses(
+        (status = 200, description = "The import summary"),
+        (status = 400, description = "The request is not valid", body = ErrorResponseData),
+        (status = 401, description = "The user is not logged in", body = ErrorResponseData),
+    ),
+    security(("bearer_auth" = []))
+)]
+#[allow(clippy::unused_async)]
+pub async fn import_bookmarks_handler(
+    State(app_data): State<Arc<AppData>>,
+    ExtractOptionalLoggedInUser(maybe_user_id): ExtractOptionalLoggedInUser,
+    Json(file): Json<BookmarksFile>,
+) -> Response {
+    match app_data.bookmark_service.import_bookmarks(&file, maybe_user_id).await {
+        Ok(summary) => Json(OkResponseData { data: summary }).into_response(),
+        Err(error) => error.into_response(),
+    }
+}
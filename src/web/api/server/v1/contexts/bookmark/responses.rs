@@ -0,0 +1,38 @@
+//! API responses for the [`bookmark`](crate::web::api::server::v1::contexts::bookmark) API
+//! context.
+use axum::http::{header, HeaderMap, StatusCode};
+use axum::response::{IntoResponse, Response};
+use axum::Json;
+use serde::{Deserialize, Serialize};
+
+use crate::models::torrent::TorrentId;
+use crate::services::bookmark::BookmarksFile;
+use crate::web::api::server::v1::responses::OkResponseData;
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct BookmarkResponseData {
+    pub torrent_id: TorrentId,
+    pub bookmarked: bool,
+}
+
+/// Response after successfully adding or removing a bookmark.
+pub fn bookmark(torrent_id: TorrentId, bookmarked: bool) -> Json<OkResponseData<BookmarkResponseData>> {
+    Json(OkResponseData {
+        data: BookmarkResponseData { torrent_id, bookmarked },
+    })
+}
+
+/// Response with the exported bookmarks file. The file is sent as an
+/// attachment and without the `data` envelope, so it can be imported again
+/// as it is.
+pub fn bookmarks_file(bookmarks_file: BookmarksFile) -> Response {
+    let mut headers = HeaderMap::new();
+    headers.insert(
+        header::CONTENT_DISPOSITION,
+        "attachment; filename=bookmarks.json"
+            .parse()
+            .expect("the bookmarks filename should be a valid header value"),
+    );
+
+    (StatusCode::OK, headers, Json(bookmarks_file)).into_response()
+}
@@ -0,0 +1,35 @@
+//! API routes for the [`bookmark`](crate::web::api::server::v1::contexts::bookmark) API context.
+//!
+//! Refer to the [API endpoint documentation](crate::web::api::server::v1::contexts::bookmark).
+use std::sync::Arc;
+
+use axum::routing::{get, post};
+use axum::Router;
+
+use super::handlers::{
+    add_bookmark_handler, delete_bookmark_handler, export_bookmarks_handler, get_bookmarks_handler, import_bookmarks_handler,
+};
+use crate::common::AppData;
+
+/// Routes for a torrent bookmark.
+///
+/// They are nested under the torrent resource: `/torrent/:info_hash/bookmark`.
+pub fn router_for_torrent(app_data: Arc<AppData>) -> Router {
+    Router::new().route(
+        "/",
+        post(add_bookmark_handler)
+            .with_state(app_data.clone())
+            .delete(delete_bookmark_handler)
+            .with_state(app_data),
+    )
+}
+
+/// Routes for the user bookmarks.
+///
+/// They are nested under the user resource: `/user/bookmarks`.
+pub fn router_for_user(app_data: Arc<AppData>) -> Router {
+    Router::new()
+        .route("/", get(get_bookmarks_handler).with_state(app_data.clone()))
+        .route("/export", get(export_bookmarks_handler).with_state(app_data.clone()))
+        .route("/import", post(import_bookmarks_handler).with_state(app_data))
+}
@@ -1,9 +1,33 @@
 use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
 
-#[derive(Serialize, Deserialize, Debug)]
+use crate::models::category_field::FieldType;
+
+#[derive(Serialize, Deserialize, Debug, ToSchema)]
 pub struct AddCategoryForm {
     pub name: String,
     pub icon: Option<String>,
 }
 
 pub type DeleteCategoryForm = AddCategoryForm;
+
+#[derive(Serialize, Deserialize, Debug, ToSchema)]
+pub struct CategoryPolicyForm {
+    /// Whether new torrents must be approved by two different staff members.
+    pub co_signing: bool,
+}
+
+#[derive(Serialize, Deserialize, Debug, ToSchema)]
+pub struct AddCategoryFieldForm {
+    /// The field key in uploads and listing filters, like `resolution`.
+    pub name: String,
+    /// The name shown to users, like `Resolution`.
+    pub label: String,
+    pub field_type: FieldType,
+    /// Whether uploads to the category must fill the field.
+    #[serde(default)]
+    pub required: bool,
+    /// The allowed values of `choice` fields.
+    #[serde(default)]
+    pub options: Vec<String>,
+}
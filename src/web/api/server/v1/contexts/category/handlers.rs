@@ -2,14 +2,19 @@
 //! context.
 use std::sync::Arc;
 
-use axum::extract::{self, State};
+use axum::extract::{self, Path, State};
 use axum::response::{IntoResponse, Json, Response};
 
-use super::forms::{AddCategoryForm, DeleteCategoryForm};
+use super::forms::{AddCategoryFieldForm, AddCategoryForm, CategoryPolicyForm, DeleteCategoryForm};
 use super::responses::{added_category, deleted_category, Category};
 use crate::common::AppData;
+use crate::models::category::CategoryPolicy;
+use crate::models::category_field::CategoryField;
+use crate::services::category_field::AddCategoryFieldRequest;
 use crate::web::api::server::v1::extractors::optional_user_id::ExtractOptionalLoggedInUser;
-use crate::web::api::server::v1::responses::{self};
+use crate::web::api::server::v1::responses::{
+    self, CategoriesResponseData, ErrorResponseData, MessageResponseData, OkResponseData,
+};
 
 /// It handles the request to get all the categories.
 ///
@@ -24,6 +29,15 @@ use crate::web::api::server::v1::responses::{self};
 /// # Errors
 ///
 /// It returns an error if there is a database error.
+#[utoipa::path(
+    get,
+    path = "/v1/category",
+    tag = "category",
+    responses(
+        (status = 200, description = "The categories", body = CategoriesResponseData),
+    ),
+    security((), ("bearer_auth" = []))
+)]
 #[allow(clippy::unused_async)]
 pub async fn get_all_handler(
     State(app_data): State<Arc<AppData>>,
@@ -46,6 +60,20 @@ pub async fn get_all_handler(
 ///
 /// - The user does not have permissions to create a new category.
 /// - There is a database error.
+#[utoipa::path(
+    post,
+    path = "/v1/category",
+    tag = "category",
+    request_body = AddCategoryForm,
+    responses(
+        (status = 200, description = "The category name", body = MessageResponseData),
+        (status = 400, description = "The request is not valid", body = ErrorResponseData),
+        (status = 401, description = "The user is not logged in", body = ErrorResponseData),
+        (status = 403, description = "The user does not have permissions to perform the action", body = ErrorResponseData),
+        (status = 409, description = "The resource already exists", body = ErrorResponseData),
+    ),
+    security(("bearer_auth" = []))
+)]
 #[allow(clippy::unused_async)]
 pub async fn add_handler(
     State(app_data): State<Arc<AppData>>,
@@ -70,6 +98,19 @@ pub async fn add_handler(
 ///
 /// - The user does not have permissions to delete category.
 /// - There is a database error.
+#[utoipa::path(
+    delete,
+    path = "/v1/category",
+    tag = "category",
+    request_body = DeleteCategoryForm,
+    responses(
+        (status = 200, description = "The category name", body = MessageResponseData),
+        (status = 401, description = "The user is not logged in", body = ErrorResponseData),
+        (status = 403, description = "The user does not have permissions to perform the action", body = ErrorResponseData),
+        (status = 404, description = "The resource does not exist", body = ErrorResponseData),
+    ),
+    security(("bearer_auth" = []))
+)]
 #[allow(clippy::unused_async)]
 pub async fn delete_handler(
     State(app_data): State<Arc<AppData>>,
@@ -89,3 +130,336 @@ pub async fn delete_handler(
         Err(error) => error.into_response(),
     }
 }
+
+/// It returns the usernames of the moderators of a category.
+///
+/// # Errors
+///
+/// It returns an error if:
+///
+/// - The user does not have permissions to manage the category moderators.
+/// - The category does not exist.
+/// - There is a database error.
+#[utoipa::path(
+    get,
+    path = "/v1/category/{category}/moderators",
+    tag = "category",
+    params(
+        ("category" = String, Path, description = "The category name"),
+    ),
+    responses(
+        (status = 200, description = "The usernames of the moderators", body = Vec<String>),
+        (status = 401, description = "The user is not logged in", body = ErrorResponseData),
+        (status = 403, description = "The user does not have permissions to perform the action", body = ErrorResponseData),
+        (status = 404, description = "The resource does not exist", body = ErrorResponseData),
+    ),
+    security(("bearer_auth" = []))
+)]
+#[allow(clippy::unused_async)]
+pub async fn get_moderators_handler(
+    State(app_data): State<Arc<AppData>>,
+    Path(category_name): Path<String>,
+    ExtractOptionalLoggedInUser(maybe_user_id): ExtractOptionalLoggedInUser,
+) -> Response {
+    match app_data.category_service.get_moderators(&category_name, maybe_user_id).await {
+        Ok(moderators) => Json(OkResponseData { data: moderators }).into_response(),
+        Err(error) => error.into_response(),
+    }
+}
+
+/// It makes a user a moderator of a category.
+///
+/// # Errors
+///
+/// It returns an error if:
+///
+/// - The user does not have permissions to manage the category moderators.
+/// - The category or the user does not exist.
+/// - There is a database error.
+#[utoipa::path(
+    post,
+    path = "/v1/category/{category}/moderator/{username}",
+    tag = "category",
+    params(
+        ("category" = String, Path, description = "The category name"),
+        ("username" = String, Path, description = "The username"),
+    ),
+    responses(
+        (status = 200, description = "The username", body = MessageResponseData),
+        (status = 401, description = "The user is not logged in", body = ErrorResponseData),
+        (status = 403, description = "The user does not have permissions to perform the action", body = ErrorResponseData),
+        (status = 404, description = "The resource does not exist", body = ErrorResponseData),
+    ),
+    security(("bearer_auth" = []))
+)]
+#[allow(clippy::unused_async)]
+pub async fn add_moderator_handler(
+    State(app_data): State<Arc<AppData>>,
+    Path((category_name, username)): Path<(String, String)>,
+    ExtractOptionalLoggedInUser(maybe_user_id): ExtractOptionalLoggedInUser,
+) -> Response {
+    match app_data
+        .category_service
+        .add_moderator(&category_name, &username, maybe_user_id)
+        .await
+    {
+        Ok(()) => Json(OkResponseData { data: username }).into_response(),
+        Err(error) => error.into_response(),
+    }
+}
+
+/// It removes a moderator from a category.
+///
+/// # Errors
+///
+/// It returns an error if:
+///
+/// - The user does not have permissions to manage the category moderators.
+/// - The category or the user does not exist.
+/// - There is a database error.
+#[utoipa::path(
+    delete,
+    path = "/v1/category/{category}/moderator/{username}",
+    tag = "category",
+    params(
+        ("category" = String, Path, description = "The category name"),
+        ("username" = String, Path, description = "The username"),
+    ),
+    responses(
+        (status = 200, description = "The username", body = MessageResponseData),
+        (status = 401, description = "The user is not logged in", body = ErrorResponseData),
+        (status = 403, description = "The user does not have permissions to perform the action", body = ErrorResponseData),
+        (status = 404, description = "The resource does not exist", body = ErrorResponseData),
+    ),
+    security(("bearer_auth" = []))
+)]
+#[allow(clippy::unused_async)]
+pub async fn remove_moderator_handler(
+    State(app_data): State<Arc<AppData>>,
+    Path((category_name, username)): Path<(String, String)>,
+    ExtractOptionalLoggedInUser(maybe_user_id): ExtractOptionalLoggedInUser,
+) -> Response {
+    match app_data
+        .category_service
+        .remove_moderator(&category_name, &username, maybe_user_id)
+        .await
+    {
+        Ok(()) => Json(OkResponseData { data: username }).into_response(),
+        Err(error) => error.into_response(),
+    }
+}
+
+/// It returns the moderation policy of a category.
+///
+/// # Errors
+///
+/// It returns an error if:
+///
+/// - The user does not have permissions to manage the category policies.
+/// - The category does not exist.
+/// - There is a database error.
+#[utoipa::path(
+    get,
+    path = "/v1/category/{category}/policy",
+    tag = "category",
+    params(
+        ("category" = String, Path, description = "The category name"),
+    ),
+    responses(
+        (status = 200, description = "The moderation policy", body = CategoryPolicy),
+        (status = 401, description = "The user is not logged in", body = ErrorResponseData),
+        (status = 403, description = "The user does not have permissions to perform the action", body = ErrorResponseData),
+        (status = 404, description = "The resource does not exist", body = ErrorResponseData),
+    ),
+    security(("bearer_auth" = []))
+)]
+#[allow(clippy::unused_async)]
+pub async fn get_policy_handler(
+    State(app_data): State<Arc<AppData>>,
+    Path(category_name): Path<String>,
+    ExtractOptionalLoggedInUser(maybe_user_id): ExtractOptionalLoggedInUser,
+) -> Response {
+    match app_data.category_service.get_policy(&category_name, maybe_user_id).await {
+        Ok(policy) => Json(OkResponseData { data: policy }).into_response(),
+        Err(error) => error.into_response(),
+    }
+}
+
+/// It sets the moderation policy of a category.
+///
+/// # Errors
+///
+/// It returns an error if:
+///
+/// - The user does not have permissions to manage the category policies.
+/// - The category does not exist.
+/// - There is a database error.
+#[utoipa::path(
+    put,
+    path = "/v1/category/{category}/policy",
+    tag = "category",
+    params(
+        ("category" = String, Path, description = "The category name"),
+    ),
+    request_body = CategoryPolicyForm,
+    responses(
+        (status = 200, description = "The new moderation policy", body = CategoryPolicy),
+        (status = 401, description = "The user is not logged in", body = ErrorResponseData),
+        (status = 403, description = "The user does not have permissions to perform the action", body = ErrorResponseData),
+        (status = 404, description = "The resource does not exist", body = ErrorResponseData),
+    ),
+    security(("bearer_auth" = []))
+)]
+#[allow(clippy::unused_async)]
+pub async fn set_policy_handler(
+    State(app_data): State<Arc<AppData>>,
+    Path(category_name): Path<String>,
+    ExtractOptionalLoggedInUser(maybe_user_id): ExtractOptionalLoggedInUser,
+    extract::Json(policy_form): extract::Json<CategoryPolicyForm>,
+) -> Response {
+    let policy = CategoryPolicy {
+        co_signing: policy_form.co_signing,
+    };
+
+    match app_data
+        .category_service
+        .set_policy(&category_name, &policy, maybe_user_id)
+        .await
+    {
+        Ok(()) => Json(OkResponseData { data: policy }).into_response(),
+        Err(error) => error.into_response(),
+    }
+}
+
+/// It returns the custom fields of the torrents in a category.
+///
+/// # Errors
+///
+/// It returns an error if:
+///
+/// - The category does not exist.
+/// - There is a database error.
+#[utoipa::path(
+    get,
+    path = "/v1/category/{category}/fields",
+    tag = "category",
+    params(
+        ("category" = String, Path, description = "The category name"),
+    ),
+    responses(
+        (status = 200, description = "The category fields", body = [CategoryField]),
+        (status = 404, description = "The resource does not exist", body = ErrorResponseData),
+    ),
+    security((), ("bearer_auth" = []))
+)]
+#[allow(clippy::unused_async)]
+pub async fn get_fields_handler(
+    State(app_data): State<Arc<AppData>>,
+    Path(category_name): Path<String>,
+    ExtractOptionalLoggedInUser(maybe_user_id): ExtractOptionalLoggedInUser,
+) -> Response {
+    match app_data
+        .category_field_service
+        .get_fields(&category_name, maybe_user_id)
+        .await
+    {
+        Ok(fields) => Json(OkResponseData { data: fields }).into_response(),
+        Err(error) => error.into_response(),
+    }
+}
+
+/// It adds a custom field to the torrents in a category.
+///
+/// # Errors
+///
+/// It returns an error if:
+///
+/// - The user does not have permissions to manage the category fields.
+/// - The category does not exist.
+/// - The field is not valid or the category has already a field with the
+///   same name.
+/// - There is a database error.
+#[utoipa::path(
+    post,
+    path = "/v1/category/{category}/fields",
+    tag = "category",
+    params(
+        ("category" = String, Path, description = "The category name"),
+    ),
+    request_body = AddCategoryFieldForm,
+    responses(
+        (status = 200, description = "The new category field", body = CategoryField),
+        (status = 400, description = "The field is not valid", body = ErrorResponseData),
+        (status = 401, description = "The user is not logged in", body = ErrorResponseData),
+        (status = 403, description = "The user does not have permissions to perform the action", body = ErrorResponseData),
+        (status = 404, description = "The resource does not exist", body = ErrorResponseData),
+        (status = 409, description = "The category has already a field with the same name", body = ErrorResponseData),
+    ),
+    security(("bearer_auth" = []))
+)]
+#[allow(clippy::unused_async)]
+pub async fn add_field_handler(
+    State(app_data): State<Arc<AppData>>,
+    Path(category_name): Path<String>,
+    ExtractOptionalLoggedInUser(maybe_user_id): ExtractOptionalLoggedInUser,
+    extract::Json(field_form): extract::Json<AddCategoryFieldForm>,
+) -> Response {
+    let request = AddCategoryFieldRequest {
+        name: field_form.name,
+        label: field_form.label,
+        field_type: field_form.field_type,
+        required: field_form.required,
+        options: field_form.options,
+    };
+
+    match app_data
+        .category_field_service
+        .add_field(&category_name, &request, maybe_user_id)
+        .await
+    {
+        Ok(field) => Json(OkResponseData { data: field }).into_response(),
+        Err(error) => error.into_response(),
+    }
+}
+
+/// It removes a custom field from a category, with the values of all the
+/// torrents.
+///
+/// # Errors
+///
+/// It returns an error if:
+///
+/// - The user does not have permissions to manage the category fields.
+/// - The category or the field do not exist.
+/// - There is a database error.
+#[utoipa::path(
+    delete,
+    path = "/v1/category/{category}/fields/{field}",
+    tag = "category",
+    params(
+        ("category" = String, Path, description = "The category name"),
+        ("field" = String, Path, description = "The field name"),
+    ),
+    responses(
+        (status = 200, description = "The field was removed", body = MessageResponseData),
+        (status = 401, description = "The user is not logged in", body = ErrorResponseData),
+        (status = 403, description = "The user does not have permissions to perform the action", body = ErrorResponseData),
+        (status = 404, description = "The resource does not exist", body = ErrorResponseData),
+    ),
+    security(("bearer_auth" = []))
+)]
+#[allow(clippy::unused_async)]
+pub async fn delete_field_handler(
+    State(app_data): State<Arc<AppData>>,
+    Path((category_name, field_name)): Path<(String, String)>,
+    ExtractOptionalLoggedInUser(maybe_user_id): ExtractOptionalLoggedInUser,
+) -> Response {
+    match app_data
+        .category_field_service
+        .delete_field(&category_name, &field_name, maybe_user_id)
+        .await
+    {
+        Ok(()) => Json(OkResponseData { data: field_name }).into_response(),
+        Err(error) => error.into_response(),
+    }
+}
@@ -2,11 +2,12 @@
 //! context.
 use axum::Json;
 use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
 
 use crate::databases::database::Category as DatabaseCategory;
 use crate::web::api::server::v1::responses::OkResponseData;
 
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
 pub struct Category {
     pub id: i64,
     /// Deprecated. Use `id`.
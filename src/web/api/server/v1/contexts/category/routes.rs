@@ -6,7 +6,10 @@ use std::sync::Arc;
 use axum::routing::{delete, get, post};
 use axum::Router;
 
-use super::handlers::{add_handler, delete_handler, get_all_handler};
+use super::handlers::{
+    add_field_handler, add_handler, add_moderator_handler, delete_field_handler, delete_handler, get_all_handler,
+    get_fields_handler, get_moderators_handler, get_policy_handler, remove_moderator_handler, set_policy_handler,
+};
 use crate::common::AppData;
 
 /// Routes for the [`category`](crate::web::api::server::v1::contexts::category) API context.
@@ -14,5 +17,31 @@ pub fn router(app_data: Arc<AppData>) -> Router {
     Router::new()
         .route("/", get(get_all_handler).with_state(app_data.clone()))
         .route("/", post(add_handler).with_state(app_data.clone()))
-        .route("/", delete(delete_handler).with_state(app_data))
+        .route("/", delete(delete_handler).with_state(app_data.clone()))
+        .route(
+            "/:category/moderators",
+            get(get_moderators_handler).with_state(app_data.clone()),
+        )
+        .route(
+            "/:category/moderator/:username",
+            post(add_moderator_handler)
+                .with_state(app_data.clone())
+                .delete(remove_moderator_handler)
+                .with_state(app_data.clone()),
+        )
+        .route(
+            "/:category/policy",
+            get(get_policy_handler)
+                .with_state(app_data.clone())
+                .put(set_policy_handler)
+                .with_state(app_data.clone()),
+        )
+        .route(
+            "/:category/fields",
+            get(get_fields_handler)
+                .with_state(app_data.clone())
+                .post(add_field_handler)
+                .with_state(app_data.clone()),
+        )
+        .route("/:category/fields/:field", delete(delete_field_handler).with_state(app_data))
 }
@@ -0,0 +1,14 @@
+//! API forms for the the [`collection`](crate::web::api::server::v1::contexts::collection) API
+//! context.
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+
+use crate::models::collection::SmartFilter;
+
+#[derive(Serialize, Deserialize, Debug, ToSchema)]
+pub struct CollectionForm {
+    pub name: String,
+    pub description: Option<String>,
+    /// Only for administrators. It makes the collection a smart collection.
+    pub filter: Option<SmartFilter>,
+}
@@ -0,0 +1,312 @@
+//! API handlers for the [`collection`](crate::web::api::server::v1::contexts::collection) API
+//! context.
+use std::str::FromStr;
+use std::sync::Arc;
+
+use axum::extract::{self, Path, Query, State};
+use axum::response::{IntoResponse, Json, Response};
+use bittorrent_primitives::info_hash::InfoHash;
+
+use super::forms::CollectionForm;
+use super::responses::{collection, deleted_collection};
+use crate::common::AppData;
+use crate::models::collection::{Collection, CollectionId};
+use crate::services::collection::{CollectionPage, ListingRequest};
+use crate::web::api::server::v1::contexts::torrent::errors;
+use crate::web::api::server::v1::extractors::optional_user_id::ExtractOptionalLoggedInUser;
+use crate::web::api::server::v1::responses::{ErrorResponseData, OkResponseData};
+
+/// It creates a collection for the logged-in user.
+///
+/// # Errors
+///
+/// It returns an error if:
+///
+/// - The user is not logged in.
+/// - The collection name is not valid.
+/// - There is a database error.
+#[utoipa::path(
+    post,
+    path = "/v1/collection",
+    tag = "collection",
+    request_body = CollectionForm,
+    responses(
+        (status = 200, description = "The new collection", body = Collection),
+        (status = 400, description = "The request is not valid", body = ErrorResponseData),
+        (status = 401, description = "The user is not logged in", body = ErrorResponseData),
+    ),
+    security(("bearer_auth" = []))
+)]
+#[allow(clippy::unused_async)]
+pub async fn create_collection_handler(
+    State(app_data): State<Arc<AppData>>,
+    ExtractOptionalLoggedInUser(maybe_user_id): ExtractOptionalLoggedInUser,
+    extract::Json(collection_form): extract::Json<CollectionForm>,
+) -> Response {
+    match app_data
+        .collection_service
+        .create_collection(
+            &collection_form.name,
+            collection_form.description.as_deref(),
+            collection_form.filter.as_ref(),
+            maybe_user_id,
+        )
+        .await
+    {
+        Ok(new_collection) => collection(new_collection).into_response(),
+        Err(error) => error.into_response(),
+    }
+}
+
+/// It returns the public page of a collection: the collection with the
+/// aggregate size and peer counts of its torrents, and a page of its
+/// torrents.
+///
+/// # Errors
+///
+/// It returns an error if:
+///
+/// - The collection does not exist.
+/// - There is a database error.
+#[utoipa::path(
+    get,
+    path = "/v1/collection/{collection_id}",
+    tag = "collection",
+    params(
+        ("collection_id" = i64, Path, description = "The collection ID"),
+        ListingRequest,
+    ),
+    responses(
+        (status = 200, description = "The collection page", body = CollectionPage),
+        (status = 404, description = "The resource does not exist", body = ErrorResponseData),
+    ),
+    security((), ("bearer_auth" = []))
+)]
+#[allow(clippy::unused_async)]
+pub async fn get_collection_handler(
+    State(app_data): State<Arc<AppData>>,
+    ExtractOptionalLoggedInUser(maybe_user_id): ExtractOptionalLoggedInUser,
+    Path(collection_id): Path<CollectionId>,
+    Query(criteria): Query<ListingRequest>,
+) -> Response {
+    match app_data
+        .collection_service
+        .get_collection(collection_id, &criteria, maybe_user_id)
+        .await
+    {
+        Ok(collection_page) => Json(OkResponseData { data: collection_page }).into_response(),
+        Err(error) => error.into_response(),
+    }
+}
+
+/// It returns the collections of the logged-in user.
+///
+/// # Errors
+///
+/// It returns an error if:
+///
+/// - The user is not logged in.
+/// - There is a database error.
+#[utoipa::path(
+    get,
+    path = "/v1/user/collections",
+    tag = "collection",
+    responses(
+        (status = 200, description = "The user's collections", body = [Collection]),
+        (status = 401, description = "The user is not logged in", body = ErrorResponseData),
+    ),
+    security(("bearer_auth" = []))
+)]
+#[allow(clippy::unused_async)]
+pub async fn get_user_collections_handler(
+    State(app_data): State<Arc<AppData>>,
+    ExtractOptionalLoggedInUser(maybe_user_id): ExtractOptionalLoggedInUser,
+) -> Response {
+    match app_data.collection_service.get_user_collections(maybe_user_id).await {
+        Ok(collections) => Json(OkResponseData { data: collections }).into_response(),
+        Err(error) => error.into_response(),
+    }
+}
+
+/// It changes the name and description of a collection of the logged-in
+/// user.
+///
+/// # Errors
+///
+/// It returns an error if:
+///
+/// - The user is not logged in.
+/// - The collection does not exist or it belongs to another user.
+/// - The collection name is not valid.
+/// - There is a database error.
+#[utoipa::path(
+    put,
+    path = "/v1/collection/{collection_id}",
+    tag = "collection",
+    params(
+        ("collection_id" = i64, Path, description = "The collection ID"),
+    ),
+    request_body = CollectionForm,
+    responses(
+        (status = 200, description = "The updated collection", body = Collection),
+        (status = 400, description = "The request is not valid", body = ErrorResponseData),
+        (status = 401, description = "The user is not logged in", body = ErrorResponseData),
+        (status = 404, description = "The resource does not exist", body = ErrorResponseData),
+    ),
+    security(("bearer_auth" = []))
+)]
+#[allow(clippy::unused_async)]
+pub async fn update_collection_handler(
+    State(app_data): State<Arc<AppData>>,
+    ExtractOptionalLoggedInUser(maybe_user_id): ExtractOptionalLoggedInUser,
+    Path(collection_id): Path<CollectionId>,
+    extract::Json(collection_form): extract::Json<CollectionForm>,
+) -> Response {
+    match app_data
+        .collection_service
+        .update_collection(
+            collection_id,
+            &collection_form.name,
+            collection_form.description.as_deref(),
+            collection_form.filter.as_ref(),
+            maybe_user_id,
+        )
+        .await
+    {
+        Ok(updated_collection) => collection(updated_collection).into_response(),
+        Err(error) => error.into_response(),
+    }
+}
+
+/// It deletes a collection. Moderators can delete any collection.
+///
+/// # Errors
+///
+/// It returns an error if:
+///
+/// - The user is not logged in.
+/// - The user is neither the owner nor a moderator.
+/// - The collection does not exist.
+/// - There is a database error.
+#[utoipa::path(
+    delete,
+    path = "/v1/collection/{collection_id}",
+    tag = "collection",
+    params(
+        ("collection_id" = i64, Path, description = "The collection ID"),
+    ),
+    responses(
+        (status = 200, description = "The deleted collection ID"),
+        (status = 401, description = "The user is not logged in", body = ErrorResponseData),
+        (status = 403, description = "The user does not have permission", body = ErrorResponseData),
+        (status = 404, description = "The resource does not exist", body = ErrorResponseData),
+    ),
+    security(("bearer_auth" = []))
+)]
+#[allow(clippy::unused_async)]
+pub async fn delete_collection_handler(
+    State(app_data): State<Arc<AppData>>,
+    ExtractOptionalLoggedInUser(maybe_user_id): ExtractOptionalLoggedInUser,
+    Path(collection_id): Path<CollectionId>,
+) -> Response {
+    match app_data
+        .collection_service
+        .delete_collection(collection_id, maybe_user_id)
+        .await
+    {
+        Ok(collection_id) => deleted_collection(collection_id).into_response(),
+        Err(error) => error.into_response(),
+    }
+}
+
+/// It adds a torrent to a collection of the logged-in user.
+///
+/// # Errors
+///
+/// It returns an error if:
+///
+/// - The user is not logged in.
+/// - The collection does not exist or it belongs to another user.
+/// - The info-hash is not valid or the torrent does not exist.
+/// - There is a database error.
+#[utoipa::path(
+    post,
+    path = "/v1/collection/{collection_id}/torrent/{info_hash}",
+    tag = "collection",
+    params(
+        ("collection_id" = i64, Path, description = "The collection ID"),
+        ("info_hash" = String, Path, description = "The torrent info-hash"),
+    ),
+    responses(
+        (status = 200, description = "The updated collection", body = Collection),
+        (status = 400, description = "The request is not valid", body = ErrorResponseData),
+        (status = 401, description = "The user is not logged in", body = ErrorResponseData),
+        (status = 404, description = "The resource does not exist", body = ErrorResponseData),
+    ),
+    security(("bearer_auth" = []))
+)]
+#[allow(clippy::unused_async)]
+pub async fn add_torrent_handler(
+    State(app_data): State<Arc<AppData>>,
+    ExtractOptionalLoggedInUser(maybe_user_id): ExtractOptionalLoggedInUser,
+    Path((collection_id, info_hash)): Path<(CollectionId, String)>,
+) -> Response {
+    let Ok(info_hash) = InfoHash::from_str(&info_hash.to_lowercase()) else {
+        return errors::Request::InvalidInfoHashParam.into_response();
+    };
+
+    match app_data
+        .collection_service
+        .add_torrent(collection_id, &info_hash, maybe_user_id)
+        .await
+    {
+        Ok(updated_collection) => collection(updated_collection).into_response(),
+        Err(error) => error.into_response(),
+    }
+}
+
+/// It removes a torrent from a collection of the logged-in user.
+///
+/// # Errors
+///
+/// It returns an error if:
+///
+/// - The user is not logged in.
+/// - The collection does not exist or it belongs to another user.
+/// - The info-hash is not valid or the torrent is not in the collection.
+/// - There is a database error.
+#[utoipa::path(
+    delete,
+    path = "/v1/collection/{collection_id}/torrent/{info_hash}",
+    tag = "collection",
+    params(
+        ("collection_id" = i64, Path, description = "The collection ID"),
+        ("info_hash" = String, Path, description = "The torrent info-hash"),
+    ),
+    responses(
+        (status = 200, description = "The updated collection", body = Collection),
+        (status = 400, description = "The request is not valid", body = ErrorResponseData),
+        (status = 401, description = "The user is not logged in", body = ErrorResponseData),
+        (status = 404, description = "The resource does not exist", body = ErrorResponseData),
+    ),
+    security(("bearer_auth" = []))
+)]
+#[allow(clippy::unused_async)]
+pub async fn remove_torrent_handler(
+    State(app_data): State<Arc<AppData>>,
+    ExtractOptionalLoggedInUser(maybe_user_id): ExtractOptionalLoggedInUser,
+    Path((collection_id, info_hash)): Path<(CollectionId, String)>,
+) -> Response {
+    let Ok(info_hash) = InfoHash::from_str(&info_hash.to_lowercase()) else {
+        return errors::Request::InvalidInfoHashParam.into_response();
+    };
+
+    match app_data
+        .collection_service
+        .remove_torrent(collection_id, &info_hash, maybe_user_id)
+        .await
+    {
+        Ok(updated_collection) => collection(updated_collection).into_response(),
+        Err(error) => error.into_response(),
+    }
+}
@@ -0,0 +1,16 @@
+//! API responses for the [`collection`](crate::web::api::server::v1::contexts::collection) API
+//! context.
+use axum::Json;
+
+use crate::models::collection::{Collection, CollectionId};
+use crate::web::api::server::v1::responses::OkResponseData;
+
+/// Response after successfully creating or changing a collection.
+pub fn collection(collection: Collection) -> Json<OkResponseData<Collection>> {
+    Json(OkResponseData { data: collection })
+}
+
+/// Response after successfully deleting a collection.
+pub fn deleted_collection(collection_id: CollectionId) -> Json<OkResponseData<CollectionId>> {
+    Json(OkResponseData { data: collection_id })
+}
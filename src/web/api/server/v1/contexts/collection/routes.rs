@@ -0,0 +1,42 @@
+//! API routes for the [`collection`](crate::web::api::server::v1::contexts::collection) API context.
+//!
+//! Refer to the [API endpoint documentation](crate::web::api::server::v1::contexts::collection).
+use std::sync::Arc;
+
+use axum::routing::{get, post};
+use axum::Router;
+
+use super::handlers::{
+    add_torrent_handler, create_collection_handler, delete_collection_handler, get_collection_handler,
+    get_user_collections_handler, remove_torrent_handler, update_collection_handler,
+};
+use crate::common::AppData;
+
+/// Routes for the [`collection`](crate::web::api::server::v1::contexts::collection) API context.
+pub fn router(app_data: Arc<AppData>) -> Router {
+    Router::new()
+        .route("/", post(create_collection_handler).with_state(app_data.clone()))
+        .route(
+            "/:collection_id",
+            get(get_collection_handler)
+                .with_state(app_data.clone())
+                .put(update_collection_handler)
+                .with_state(app_data.clone())
+                .delete(delete_collection_handler)
+                .with_state(app_data.clone()),
+        )
+        .route(
+            "/:collection_id/torrent/:info_hash",
+            post(add_torrent_handler)
+                .with_state(app_data.clone())
+                .delete(remove_torrent_handler)
+                .with_state(app_data),
+        )
+}
+
+/// Routes for the user collections.
+///
+/// They are nested under the user resource: `/user/collections`.
+pub fn router_for_user(app_data: Arc<AppData>) -> Router {
+    Router::new().route("/", get(get_user_collections_handler).with_state(app_data))
+}
@@ -0,0 +1,19 @@
+//! API forms for the the [`comment`](crate::web::api::server::v1::contexts::comment) API
+//! context.
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+
+#[derive(Serialize, Deserialize, Debug, ToSchema)]
+pub struct AddCommentForm {
+    pub text: String,
+}
+
+#[derive(Serialize, Deserialize, Debug, ToSchema)]
+pub struct EditCommentForm {
+    pub text: String,
+}
+
+#[derive(Serialize, Deserialize, Debug, ToSchema)]
+pub struct ModerateCommentForm {
+    pub hidden: bool,
+}
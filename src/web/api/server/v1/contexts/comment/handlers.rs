@@ -0,0 +1,259 @@
+//! API handlers for the [`comment`](crate::web::api::server::v1::contexts::comment) API
+//! context.
+use std::str::FromStr;
+use std::sync::Arc;
+
+use axum::extract::{self, Path, Query, State};
+use axum::response::{IntoResponse, Json, Response};
+use bittorrent_primitives::info_hash::InfoHash;
+
+use super::forms::{AddCommentForm, EditCommentForm, ModerateCommentForm};
+use super::responses::{comment, deleted_comment};
+use crate::common::AppData;
+use crate::models::comment::CommentId;
+use crate::services::comment::ListingRequest;
+use crate::web::api::server::v1::contexts::torrent::errors;
+use crate::web::api::server::v1::extractors::optional_user_id::ExtractOptionalLoggedInUser;
+use crate::web::api::server::v1::extractors::user_id::ExtractLoggedInUser;
+use crate::web::api::server::v1::responses::{ErrorResponseData, OkResponseData};
+
+/// It handles the request to get a page of the torrent comments.
+///
+/// It returns:
+///
+/// - `200` response with a json containing the comment list [`CommentsResponse`](crate::models::response::CommentsResponse).
+/// - Other error status codes if there is a database error.
+///
+/// # Errors
+///
+/// It returns an error if:
+///
+/// - The info-hash is not valid or the torrent does not exist.
+/// - There is a database error.
+#[utoipa::path(
+    get,
+    path = "/v1/torrent/{info_hash}/comments",
+    tag = "comment",
+    params(
+        ("info_hash" = String, Path, description = "The torrent info-hash"),
+        ListingRequest,
+    ),
+    responses(
+        (status = 200, description = "The torrent comments"),
+        (status = 400, description = "The request is not valid", body = ErrorResponseData),
+        (status = 404, description = "The resource does not exist", body = ErrorResponseData),
+    ),
+    security((), ("bearer_auth" = []))
+)]
+#[allow(clippy::unused_async)]
+pub async fn get_all_handler(
+    State(app_data): State<Arc<AppData>>,
+    ExtractOptionalLoggedInUser(maybe_user_id): ExtractOptionalLoggedInUser,
+    Path(info_hash): Path<String>,
+    Query(criteria): Query<ListingRequest>,
+) -> Response {
+    let Ok(info_hash) = InfoHash::from_str(&info_hash.to_lowercase()) else {
+        return errors::Request::InvalidInfoHashParam.into_response();
+    };
+
+    match app_data
+        .comment_service
+        .get_comments(&info_hash, &criteria, maybe_user_id)
+        .await
+    {
+        Ok(comments) => Json(OkResponseData { data: comments }).into_response(),
+        Err(error) => error.into_response(),
+    }
+}
+
+/// It adds a new comment to the torrent.
+///
+/// # Errors
+///
+/// It returns an error if:
+///
+/// - The user does not have permissions to comment.
+/// - The comment text is empty or too long.
+/// - The info-hash is not valid or the torrent does not exist.
+/// - There is a database error.
+#[utoipa::path(
+    post,
+    path = "/v1/torrent/{info_hash}/comments",
+    tag = "comment",
+    params(
+        ("info_hash" = String, Path, description = "The torrent info-hash"),
+    ),
+    request_body = AddCommentForm,
+    responses(
+        (status = 200, description = "The new comment"),
+        (status = 400, description = "The request is not valid", body = ErrorResponseData),
+        (status = 401, description = "The user is not logged in", body = ErrorResponseData),
+        (status = 403, description = "The user does not have permissions to perform the action", body = ErrorResponseData),
+        (status = 404, description = "The resource does not exist", body = ErrorResponseData),
+    ),
+    security(("bearer_auth" = []))
+)]
+#[allow(clippy::unused_async)]
+pub async fn add_handler(
+    State(app_data): State<Arc<AppData>>,
+    ExtractOptionalLoggedInUser(maybe_user_id): ExtractOptionalLoggedInUser,
+    Path(info_hash): Path<String>,
+    extract::Json(add_comment_form): extract::Json<AddCommentForm>,
+) -> Response {
+    let Ok(info_hash) = InfoHash::from_str(&info_hash.to_lowercase()) else {
+        return errors::Request::InvalidInfoHashParam.into_response();
+    };
+
+    match app_data
+        .comment_service
+        .add_comment(&info_hash, &add_comment_form.text, maybe_user_id)
+        .await
+    {
+        Ok(added_comment) => comment(added_comment).into_response(),
+        Err(error) => error.into_response(),
+    }
+}
+
+/// It edits the text of a comment. Only the author can edit the comment.
+///
+/// # Errors
+///
+/// It returns an error if:
+///
+/// - The user is not the author of the comment.
+/// - The comment text is empty or too long.
+/// - The comment does not exist.
+/// - There is a database error.
+#[utoipa::path(
+    put,
+    path = "/v1/torrent/{info_hash}/comments/{comment_id}",
+    tag = "comment",
+    params(
+        ("info_hash" = String, Path, description = "The torrent info-hash"),
+        ("comment_id" = i64, Path, description = "The comment ID"),
+    ),
+    request_body = EditCommentForm,
+    responses(
+        (status = 200, description = "The edited comment"),
+        (status = 400, description = "The request is not valid", body = ErrorResponseData),
+        (status = 401, description = "The user is not logged in", body = ErrorResponseData),
+        (status = 403, description = "The user does not have permissions to perform the action", body = ErrorResponseData),
+        (status = 404, description = "The resource does not exist", body = ErrorResponseData),
+    ),
+    security(("bearer_auth" = []))
+)]
+#[allow(clippy::unused_async)]
+pub async fn edit_handler(
+    State(app_data): State<Arc<AppData>>,
+    ExtractLoggedInUser(user_id): ExtractLoggedInUser,
+    Path((info_hash, comment_id)): Path<(String, CommentId)>,
+    extract::Json(edit_comment_form): extract::Json<EditCommentForm>,
+) -> Response {
+    let Ok(info_hash) = InfoHash::from_str(&info_hash.to_lowercase()) else {
+        return errors::Request::InvalidInfoHashParam.into_response();
+    };
+
+    match app_data
+        .comment_service
+        .edit_comment(&info_hash, comment_id, &edit_comment_form.text, &user_id)
+        .await
+    {
+        Ok(edited_comment) => comment(edited_comment).into_response(),
+        Err(error) => error.into_response(),
+    }
+}
+
+/// It deletes a comment. Comments can be deleted by their author or by a
+/// moderator.
+///
+/// # Errors
+///
+/// It returns an error if:
+///
+/// - The user is neither the author nor a moderator.
+/// - The comment does not exist.
+/// - There is a database error.
+#[utoipa::path(
+    delete,
+    path = "/v1/torrent/{info_hash}/comments/{comment_id}",
+    tag = "comment",
+    params(
+        ("info_hash" = String, Path, description = "The torrent info-hash"),
+        ("comment_id" = i64, Path, description = "The comment ID"),
+    ),
+    responses(
+        (status = 200, description = "The deleted comment ID"),
+        (status = 400, description = "The request is not valid", body = ErrorResponseData),
+        (status = 401, description = "The user is not logged in", body = ErrorResponseData),
+        (status = 403, description = "The user does not have permissions to perform the action", body = ErrorResponseData),
+        (status = 404, description = "The resource does not exist", body = ErrorResponseData),
+    ),
+    security(("bearer_auth" = []))
+)]
+#[allow(clippy::unused_async)]
+pub async fn delete_handler(
+    State(app_data): State<Arc<AppData>>,
+    ExtractOptionalLoggedInUser(maybe_user_id): ExtractOptionalLoggedInUser,
+    Path((info_hash, comment_id)): Path<(String, CommentId)>,
+) -> Response {
+    let Ok(info_hash) = InfoHash::from_str(&info_hash.to_lowercase()) else {
+        return errors::Request::InvalidInfoHashParam.into_response();
+    };
+
+    match app_data
+        .comment_service
+        .delete_comment(&info_hash, comment_id, maybe_user_id)
+        .await
+    {
+        Ok(()) => deleted_comment(comment_id).into_response(),
+        Err(error) => error.into_response(),
+    }
+}
+
+/// It hides or shows a comment.
+///
+/// # Errors
+///
+/// It returns an error if:
+///
+/// - The user does not have permissions to moderate comments.
+/// - The comment does not exist.
+/// - There is a database error.
+#[utoipa::path(
+    put,
+    path = "/v1/torrent/{info_hash}/comments/{comment_id}/moderation",
+    tag = "comment",
+    params(
+        ("info_hash" = String, Path, description = "The torrent info-hash"),
+        ("comment_id" = i64, Path, description = "The comment ID"),
+    ),
+    request_body = ModerateCommentForm,
+    responses(
+        (status = 200, description = "The moderated comment"),
+        (status = 400, description = "The request is not valid", body = ErrorResponseData),
+        (status = 401, description = "The user is not logged in", body = ErrorResponseData),
+        (status = 403, description = "The user does not have permissions to perform the action", body = ErrorResponseData),
+        (status = 404, description = "The resource does not exist", body = ErrorResponseData),
+    ),
+    security(("bearer_auth" = []))
+)]
+#[allow(clippy::unused_async)]
+pub async fn moderate_handler(
+    State(app_data): State<Arc<AppData>>,
+    ExtractOptionalLoggedInUser(maybe_user_id): ExtractOptionalLoggedInUser,
+    Path((info_hash, comment_id)): Path<(String, CommentId)>,
+    extract::Json(moderate_comment_form): extract::Json<ModerateCommentForm>,
+) -> Response {
+    let Ok(info_hash) = InfoHash::from_str(&info_hash.to_lowercase()) else {
+        return errors::Request::InvalidInfoHashParam.into_response();
+    };
+
+    match app_data
+        .comment_service
+        .moderate_comment(&info_hash, comment_id, moderate_comment_form.hidden, maybe_user_id)
+        .await
+    {
+        Ok(moderated_comment) => comment(moderated_comment).into_response(),
+        Err(error) => error.into_response(),
+    }
+}
@@ -0,0 +1,16 @@
+//! API responses for the [`comment`](crate::web::api::server::v1::contexts::comment) API
+//! context.
+use axum::Json;
+
+use crate::models::comment::{Comment, CommentId};
+use crate::web::api::server::v1::responses::OkResponseData;
+
+/// Response after successfully creating, editing or moderating a comment.
+pub fn comment(comment: Comment) -> Json<OkResponseData<Comment>> {
+    Json(OkResponseData { data: comment })
+}
+
+/// Response after successfully deleting a comment.
+pub fn deleted_comment(comment_id: CommentId) -> Json<OkResponseData<CommentId>> {
+    Json(OkResponseData { data: comment_id })
+}
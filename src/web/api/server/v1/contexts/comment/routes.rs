@@ -0,0 +1,22 @@
+//! API routes for the [`comment`](crate::web::api::server::v1::contexts::comment) API context.
+//!
+//! Refer to the [API endpoint documentation](crate::web::api::server::v1::contexts::comment).
+use std::sync::Arc;
+
+use axum::routing::{delete, get, post, put};
+use axum::Router;
+
+use super::handlers::{add_handler, delete_handler, edit_handler, get_all_handler, moderate_handler};
+use crate::common::AppData;
+
+/// Routes for the [`comment`](crate::web::api::server::v1::contexts::comment) API context.
+///
+/// They are nested under the torrent resource: `/torrent/:info_hash/comments`.
+pub fn router(app_data: Arc<AppData>) -> Router {
+    Router::new()
+        .route("/", get(get_all_handler).with_state(app_data.clone()))
+        .route("/", post(add_handler).with_state(app_data.clone()))
+        .route("/:comment_id", put(edit_handler).with_state(app_data.clone()))
+        .route("/:comment_id", delete(delete_handler).with_state(app_data.clone()))
+        .route("/:comment_id/moderation", put(moderate_handler).with_state(app_data))
+}
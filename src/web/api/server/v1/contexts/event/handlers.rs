@@ -0,0 +1,48 @@
+//! API handlers for the [`event`](crate::web::api::server::v1::contexts::event) API
+//! context.
+use std::convert::Infallible;
+use std::sync::Arc;
+
+use axum::extract::State;
+use axum::response::sse::{Event, KeepAlive, Sse};
+use axum::response::{IntoResponse, Response};
+use futures_util::stream;
+
+use crate::common::AppData;
+use crate::services::live_events::LiveEvent;
+use crate::web::api::server::v1::extractors::optional_user_id::ExtractOptionalLoggedInUser;
+
+/// It streams the live events to the client until it disconnects.
+#[utoipa::path(
+    get,
+    path = "/v1/events",
+    tag = "event",
+    responses(
+        (status = 200, description = "The stream of live events", content_type = "text/event-stream"),
+    ),
+    security((), ("bearer_auth" = []))
+)]
+#[allow(clippy::unused_async)]
+pub async fn subscribe_handler(
+    State(app_data): State<Arc<AppData>>,
+    ExtractOptionalLoggedInUser(maybe_user_id): ExtractOptionalLoggedInUser,
+) -> Response {
+    let subscription = app_data.live_events_service.subscribe(maybe_user_id).await;
+
+    let events = stream::unfold(subscription, |mut subscription| async move {
+        let event = subscription.next().await?;
+
+        Some((Ok::<_, Infallible>(sse_event(&event)), subscription))
+    });
+
+    Sse::new(events).keep_alive(KeepAlive::default()).into_response()
+}
+
+/// # Panics
+///
+/// Will panic if the event can't be serialized.
+fn sse_event(event: &LiveEvent) -> Event {
+    Event::default()
+        .event(event.event_type())
+        .data(serde_json::to_string(event).expect("live events should be serializable"))
+}
@@ -0,0 +1,49 @@
+//! API context: `event`.
+//!
+//! This API context streams live events to the connected frontends with
+//! [Server-Sent Events](https://developer.mozilla.org/en-US/docs/Web/API/Server-sent_events).
+//! Refer to the [`live_events`](crate::services::live_events) service for
+//! more information.
+//!
+//! # Endpoints
+//!
+//! - [Subscribe to the live events](#subscribe-to-the-live-events)
+//!
+//! # Subscribe to the live events
+//!
+//! `GET /v1/events`
+//!
+//! Guests and users receive the public events. Moderators also receive the
+//! moderation events. Since the browser `EventSource` API can't send the
+//! `Authorization` header, moderator frontends have to use a `fetch`-based
+//! client to receive them.
+//!
+//! Event | Audience | Description
+//! ---|---|---
+//! `torrent_uploaded` | Everybody | A torrent was added to the public listing
+//! `torrent_pending` | Moderators | A torrent is waiting for moderation
+//! `torrent_approved` | Moderators | A torrent was approved
+//! `torrent_rejected` | Moderators | A torrent was rejected
+//! `report_created` | Moderators | A torrent was reported
+//! `report_resolved` | Moderators | A report was resolved
+//!
+//! **Example request**
+//!
+//! ```bash
+//! curl --no-buffer http://127.0.0.1:3001/v1/events
+//! ```
+//!
+//! **Example response** `200`
+//!
+//! ```text
+//! event: torrent_uploaded
+//! data: {"type":"torrent_uploaded","torrent_id":1,"info_hash":"5452869be36f9f3350ccee6b4544e7e76caaadab","title":"MandelbrotSet","category":"software","uploader":"indexadmin"}
+//!
+//! ```
+//!
+//! **Resource**
+//!
+//! Refer to the [`LiveEvent`](crate::services::live_events::LiveEvent) enum
+//! for more information about the event attributes.
+pub mod handlers;
+pub mod routes;
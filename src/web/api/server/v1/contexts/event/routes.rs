@@ -0,0 +1,15 @@
+//! API routes for the [`event`](crate::web::api::server::v1::contexts::event) API context.
+//!
+//! Refer to the [API endpoint documentation](crate::web::api::server::v1::contexts::event).
+use std::sync::Arc;
+
+use axum::routing::get;
+use axum::Router;
+
+use super::handlers::subscribe_handler;
+use crate::common::AppData;
+
+/// Routes for the [`event`](crate::web::api::server::v1::contexts::event) API context.
+pub fn router(app_data: Arc<AppData>) -> Router {
+    Router::new().route("/", get(subscribe_handler).with_state(app_data))
+}
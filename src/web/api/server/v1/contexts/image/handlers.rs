@@ -0,0 +1,84 @@
+//! API handlers for the [`image`](crate::web::api::server::v1::contexts::image) API
+//! context.
+use std::sync::Arc;
+
+use axum::extract::{self, State};
+use axum::response::{IntoResponse, Json, Response};
+
+use crate::common::AppData;
+use crate::services::image_upload::{ConfirmRequest, PresignRequest};
+use crate::web::api::server::v1::extractors::optional_user_id::ExtractOptionalLoggedInUser;
+use crate::web::api::server::v1::responses::{ErrorResponseData, OkResponseData};
+
+/// It returns a pre-signed URL to upload an image directly to the storage.
+///
+/// # Errors
+///
+/// It returns an error if:
+///
+/// - The S3 storage backend is not enabled.
+/// - The user is not logged in.
+/// - The image type is not supported or the image is too big.
+#[utoipa::path(
+    post,
+    path = "/v1/image/upload",
+    tag = "image",
+    request_body = PresignRequest,
+    responses(
+        (status = 200, description = "The pre-signed upload URL"),
+        (status = 400, description = "The request is not valid", body = ErrorResponseData),
+        (status = 401, description = "The user is not logged in", body = ErrorResponseData),
+        (status = 403, description = "The user does not have permissions to perform the action", body = ErrorResponseData),
+        (status = 503, description = "The feature is not enabled", body = ErrorResponseData),
+    ),
+    security(("bearer_auth" = []))
+)]
+#[allow(clippy::unused_async)]
+pub async fn presign_upload_handler(
+    State(app_data): State<Arc<AppData>>,
+    ExtractOptionalLoggedInUser(maybe_user_id): ExtractOptionalLoggedInUser,
+    extract::Json(request): extract::Json<PresignRequest>,
+) -> Response {
+    match app_data.image_upload_service.presign(&request, maybe_user_id).await {
+        Ok(presigned_upload) => Json(OkResponseData { data: presigned_upload }).into_response(),
+        Err(error) => error.into_response(),
+    }
+}
+
+/// It validates and registers an image uploaded with a pre-signed URL.
+///
+/// # Errors
+///
+/// It returns an error if:
+///
+/// - The S3 storage backend is not enabled.
+/// - The user is not logged in.
+/// - The uploaded object does not exist or it's not a valid image.
+/// - There is a database error.
+#[utoipa::path(
+    post,
+    path = "/v1/image/upload/confirm",
+    tag = "image",
+    request_body = ConfirmRequest,
+    responses(
+        (status = 200, description = "The uploaded image"),
+        (status = 400, description = "The request is not valid", body = ErrorResponseData),
+        (status = 401, description = "The user is not logged in", body = ErrorResponseData),
+        (status = 403, description = "The user does not have permissions to perform the action", body = ErrorResponseData),
+        (status = 404, description = "The resource does not exist", body = ErrorResponseData),
+        (status = 409, description = "The resource already exists", body = ErrorResponseData),
+        (status = 503, description = "The feature is not enabled", body = ErrorResponseData),
+    ),
+    security(("bearer_auth" = []))
+)]
+#[allow(clippy::unused_async)]
+pub async fn confirm_upload_handler(
+    State(app_data): State<Arc<AppData>>,
+    ExtractOptionalLoggedInUser(maybe_user_id): ExtractOptionalLoggedInUser,
+    extract::Json(request): extract::Json<ConfirmRequest>,
+) -> Response {
+    match app_data.image_upload_service.confirm(&request, maybe_user_id).await {
+        Ok(image) => Json(OkResponseData { data: image }).into_response(),
+        Err(error) => error.into_response(),
+    }
+}
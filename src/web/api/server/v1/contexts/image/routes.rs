@@ -0,0 +1,17 @@
+//! API routes for the [`image`](crate::web::api::server::v1::contexts::image) API context.
+//!
+//! Refer to the [API endpoint documentation](crate::web::api::server::v1::contexts::image).
+use std::sync::Arc;
+
+use axum::routing::post;
+use axum::Router;
+
+use super::handlers::{confirm_upload_handler, presign_upload_handler};
+use crate::common::AppData;
+
+/// Routes for the [`image`](crate::web::api::server::v1::contexts::image) API context.
+pub fn router(app_data: Arc<AppData>) -> Router {
+    Router::new()
+        .route("/upload", post(presign_upload_handler).with_state(app_data.clone()))
+        .route("/upload/confirm", post(confirm_upload_handler).with_state(app_data))
+}
@@ -3,17 +3,39 @@
 //! Context | Description | Version
 //! ---|---|---
 //! `About` | Metadata about the API | [`v1`](crate::web::api::server::v1::contexts::about)
+//! `Admin` | Moderation | [`v1`](crate::web::api::server::v1::contexts::admin)
+//! `Bookmark` | User bookmarks | [`v1`](crate::web::api::server::v1::contexts::bookmark)
 //! `Category` | Torrent categories | [`v1`](crate::web::api::server::v1::contexts::category)
+//! `Collection` | Torrent collections | [`v1`](crate::web::api::server::v1::contexts::collection)
+//! `Comment` | Torrent comments | [`v1`](crate::web::api::server::v1::contexts::comment)
+//! `Event` | Live events | [`v1`](crate::web::api::server::v1::contexts::event)
+//! `Image` | Images uploaded to the storage | [`v1`](crate::web::api::server::v1::contexts::image)
 //! `Proxy` | Image proxy cache | [`v1`](crate::web::api::server::v1::contexts::proxy)
+//! `Report` | Torrent reports | [`v1`](crate::web::api::server::v1::contexts::report)
+//! `SavedSearch` | User saved searches | [`v1`](crate::web::api::server::v1::contexts::saved_search)
 //! `Settings` | Index settings | [`v1`](crate::web::api::server::v1::contexts::settings)
+//! `Subscription` | User subscriptions | [`v1`](crate::web::api::server::v1::contexts::subscription)
+//! `Sync` | Delta sync for mirrors | [`v1`](crate::web::api::server::v1::contexts::sync)
 //! `Tag` | Torrent tags | [`v1`](crate::web::api::server::v1::contexts::tag)
 //! `Torrent` | Indexed torrents | [`v1`](crate::web::api::server::v1::contexts::torrent)
 //! `User` | Users | [`v1`](crate::web::api::server::v1::contexts::user)
+//! `Widget` | Embeddable widgets | [`v1`](crate::web::api::server::v1::contexts::widget)
 //!
 pub mod about;
+pub mod admin;
+pub mod bookmark;
 pub mod category;
+pub mod collection;
+pub mod comment;
+pub mod event;
+pub mod image;
 pub mod proxy;
+pub mod report;
+pub mod saved_search;
 pub mod settings;
+pub mod subscription;
+pub mod sync;
 pub mod tag;
 pub mod torrent;
 pub mod user;
+pub mod widget;
@@ -2,20 +2,35 @@
 //! context.
 use std::sync::Arc;
 
-use axum::extract::{Path, State};
+use axum::extract::{Path, Query, State};
 use axum::response::Response;
 
-use super::responses::png_image;
+use super::responses::{image, png_image};
+use crate::cache::image::thumbnail::VariantRequest;
 use crate::common::AppData;
 use crate::ui::proxy::map_error_to_image;
 use crate::web::api::server::v1::extractors::optional_user_id::ExtractOptionalLoggedInUser;
 
 /// Get the remote image. It uses the cached image if available.
+#[utoipa::path(
+    get,
+    path = "/v1/proxy/image/{url}",
+    tag = "proxy",
+    params(
+        ("url" = String, Path, description = "The URL-encoded image URL"),
+        VariantRequest,
+    ),
+    responses(
+        (status = 200, description = "The image, or an image with the error message", content_type = ["image/png", "image/webp", "image/avif"]),
+    ),
+    security((), ("bearer_auth" = []))
+)]
 #[allow(clippy::unused_async)]
 pub async fn get_proxy_image_handler(
     State(app_data): State<Arc<AppData>>,
     ExtractOptionalLoggedInUser(maybe_user_id): ExtractOptionalLoggedInUser,
     Path(url): Path<String>,
+    Query(variant): Query<VariantRequest>,
 ) -> Response {
     // code-review: Handling status codes in the index-gui other tan OK is quite a pain.
     // Return OK for now.
@@ -27,6 +42,17 @@ pub async fn get_proxy_image_handler(
     // Get image URL from URL path parameter.
     let image_url = urlencoding::decode(&url).unwrap_or_default().into_owned();
 
+    if !variant.is_original() {
+        return match app_data
+            .proxy_service
+            .get_image_variant(&image_url, maybe_user_id, &variant)
+            .await
+        {
+            Ok((image_bytes, content_type)) => image(image_bytes, content_type),
+            Err(e) => png_image(map_error_to_image(&e)),
+        };
+    }
+
     match app_data.proxy_service.get_image_by_url(&image_url, maybe_user_id).await {
         Ok(image_bytes) => {
             // Returns the cached image.
@@ -12,6 +12,16 @@
 //!
 //! **NOTICE:** For now, it only supports PNG images.
 //!
+//! Clients can request a resized variant of the image with the `width` query
+//! parameter, and a WebP or AVIF variant with the `format` query parameter:
+//!
+//! ```text
+//! /v1/proxy/image/{url}?width=320&format=webp
+//! ```
+//!
+//! The width is rounded up to one of the `image_cache.thumbnail_widths`.
+//! Variants are cached alongside the original image.
+//!
 //! **NOTICE:** The proxy service is not intended to be used as a general
 //! purpose proxy. It is only intended to be used for the images in the
 //! torrent descriptions.
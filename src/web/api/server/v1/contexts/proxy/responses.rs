@@ -4,5 +4,10 @@ use hyper::{header, StatusCode};
 
 #[must_use]
 pub fn png_image(bytes: Bytes) -> Response {
-    (StatusCode::OK, [(header::CONTENT_TYPE, "image/png")], bytes).into_response()
+    image(bytes, "image/png")
+}
+
+#[must_use]
+pub fn image(bytes: Bytes, content_type: &'static str) -> Response {
+    (StatusCode::OK, [(header::CONTENT_TYPE, content_type)], bytes).into_response()
 }
@@ -0,0 +1,12 @@
+//! API forms for the the [`report`](crate::web::api::server::v1::contexts::report) API
+//! context.
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+
+use crate::models::report::ReportReason;
+
+#[derive(Serialize, Deserialize, Debug, ToSchema)]
+pub struct ReportTorrentForm {
+    pub reason: ReportReason,
+    pub details: Option<String>,
+}
@@ -0,0 +1,68 @@
+//! API handlers for the [`report`](crate::web::api::server::v1::contexts::report) API
+//! context.
+use std::str::FromStr;
+use std::sync::Arc;
+
+use axum::extract::{self, Path, State};
+use axum::response::{IntoResponse, Response};
+use bittorrent_primitives::info_hash::InfoHash;
+
+use super::forms::ReportTorrentForm;
+use super::responses::report;
+use crate::common::AppData;
+use crate::web::api::server::v1::contexts::torrent::errors;
+use crate::web::api::server::v1::extractors::optional_user_id::ExtractOptionalLoggedInUser;
+use crate::web::api::server::v1::responses::ErrorResponseData;
+
+/// It reports a torrent.
+///
+/// # Errors
+///
+/// It returns an error if:
+///
+/// - The user does not have permissions to report torrents.
+/// - The report details are too long.
+/// - The info-hash is not valid or the torrent does not exist.
+/// - There is a database error.
+#[utoipa::path(
+    post,
+    path = "/v1/torrent/{info_hash}/report",
+    tag = "report",
+    params(
+        ("info_hash" = String, Path, description = "The torrent info-hash"),
+    ),
+    request_body = ReportTorrentForm,
+    responses(
+        (status = 200, description = "The new report"),
+        (status = 400, description = "The request is not valid", body = ErrorResponseData),
+        (status = 401, description = "The user is not logged in", body = ErrorResponseData),
+        (status = 404, description = "The resource does not exist", body = ErrorResponseData),
+        (status = 409, description = "The resource already exists", body = ErrorResponseData),
+    ),
+    security(("bearer_auth" = []))
+)]
+#[allow(clippy::unused_async)]
+pub async fn report_torrent_handler(
+    State(app_data): State<Arc<AppData>>,
+    ExtractOptionalLoggedInUser(maybe_user_id): ExtractOptionalLoggedInUser,
+    Path(info_hash): Path<String>,
+    extract::Json(report_torrent_form): extract::Json<ReportTorrentForm>,
+) -> Response {
+    let Ok(info_hash) = InfoHash::from_str(&info_hash.to_lowercase()) else {
+        return errors::Request::InvalidInfoHashParam.into_response();
+    };
+
+    match app_data
+        .report_service
+        .report_torrent(
+            &info_hash,
+            &report_torrent_form.reason,
+            report_torrent_form.details.as_deref(),
+            maybe_user_id,
+        )
+        .await
+    {
+        Ok(new_report) => report(new_report).into_response(),
+        Err(error) => error.into_response(),
+    }
+}
@@ -0,0 +1,11 @@
+//! API responses for the [`report`](crate::web::api::server::v1::contexts::report) API
+//! context.
+use axum::Json;
+
+use crate::models::report::Report;
+use crate::web::api::server::v1::responses::OkResponseData;
+
+/// Response after successfully creating or resolving a report.
+pub fn report(report: Report) -> Json<OkResponseData<Report>> {
+    Json(OkResponseData { data: report })
+}
@@ -0,0 +1,17 @@
+//! API routes for the [`report`](crate::web::api::server::v1::contexts::report) API context.
+//!
+//! Refer to the [API endpoint documentation](crate::web::api::server::v1::contexts::report).
+use std::sync::Arc;
+
+use axum::routing::post;
+use axum::Router;
+
+use super::handlers::report_torrent_handler;
+use crate::common::AppData;
+
+/// Routes for the [`report`](crate::web::api::server::v1::contexts::report) API context.
+///
+/// They are nested under the torrent resource: `/torrent/:info_hash/report`.
+pub fn router(app_data: Arc<AppData>) -> Router {
+    Router::new().route("/", post(report_torrent_handler).with_state(app_data))
+}
@@ -0,0 +1,160 @@
+//! API handlers for the [`saved_search`](crate::web::api::server::v1::contexts::saved_search) API
+//! context.
+use std::sync::Arc;
+
+use axum::extract::{self, Path, Query, State};
+use axum::response::{IntoResponse, Json, Response};
+
+use super::responses::{deleted_saved_search, saved_search};
+use crate::common::AppData;
+use crate::models::saved_search::SavedSearchId;
+use crate::services::saved_search::{AddSavedSearchRequest, ResultsRequest};
+use crate::web::api::server::v1::extractors::optional_user_id::ExtractOptionalLoggedInUser;
+use crate::web::api::server::v1::responses::{ErrorResponseData, OkResponseData, TorrentsResponseData};
+
+/// It returns the saved searches of the logged-in user.
+///
+/// # Errors
+///
+/// It returns an error if:
+///
+/// - The user is not logged in.
+/// - There is a database error.
+#[utoipa::path(
+    get,
+    path = "/v1/user/saved-searches",
+    tag = "saved_search",
+    responses(
+        (status = 200, description = "The user's saved searches"),
+        (status = 401, description = "The user is not logged in", body = ErrorResponseData),
+    ),
+    security(("bearer_auth" = []))
+)]
+#[allow(clippy::unused_async)]
+pub async fn get_saved_searches_handler(
+    State(app_data): State<Arc<AppData>>,
+    ExtractOptionalLoggedInUser(maybe_user_id): ExtractOptionalLoggedInUser,
+) -> Response {
+    match app_data.saved_search_service.get_saved_searches(maybe_user_id).await {
+        Ok(saved_searches) => Json(OkResponseData { data: saved_searches }).into_response(),
+        Err(error) => error.into_response(),
+    }
+}
+
+/// It saves a search for the logged-in user.
+///
+/// # Errors
+///
+/// It returns an error if:
+///
+/// - The user is not logged in.
+/// - The saved search is not valid or the user has too many.
+/// - There is a database error.
+#[utoipa::path(
+    post,
+    path = "/v1/user/saved-searches",
+    tag = "saved_search",
+    request_body = AddSavedSearchRequest,
+    responses(
+        (status = 200, description = "The new saved search"),
+        (status = 400, description = "The request is not valid", body = ErrorResponseData),
+        (status = 401, description = "The user is not logged in", body = ErrorResponseData),
+    ),
+    security(("bearer_auth" = []))
+)]
+#[allow(clippy::unused_async)]
+pub async fn add_saved_search_handler(
+    State(app_data): State<Arc<AppData>>,
+    ExtractOptionalLoggedInUser(maybe_user_id): ExtractOptionalLoggedInUser,
+    extract::Json(add_saved_search_request): extract::Json<AddSavedSearchRequest>,
+) -> Response {
+    match app_data
+        .saved_search_service
+        .add_saved_search(&add_saved_search_request, maybe_user_id)
+        .await
+    {
+        Ok(new_saved_search) => saved_search(new_saved_search).into_response(),
+        Err(error) => error.into_response(),
+    }
+}
+
+/// It deletes a saved search of the logged-in user.
+///
+/// # Errors
+///
+/// It returns an error if:
+///
+/// - The user is not logged in.
+/// - The saved search does not exist.
+/// - There is a database error.
+#[utoipa::path(
+    delete,
+    path = "/v1/user/saved-searches/{saved_search_id}",
+    tag = "saved_search",
+    params(
+        ("saved_search_id" = i64, Path, description = "The saved search ID"),
+    ),
+    responses(
+        (status = 200, description = "The deleted saved search ID"),
+        (status = 401, description = "The user is not logged in", body = ErrorResponseData),
+        (status = 404, description = "The resource does not exist", body = ErrorResponseData),
+    ),
+    security(("bearer_auth" = []))
+)]
+#[allow(clippy::unused_async)]
+pub async fn delete_saved_search_handler(
+    State(app_data): State<Arc<AppData>>,
+    ExtractOptionalLoggedInUser(maybe_user_id): ExtractOptionalLoggedInUser,
+    Path(saved_search_id): Path<SavedSearchId>,
+) -> Response {
+    match app_data
+        .saved_search_service
+        .delete_saved_search(saved_search_id, maybe_user_id)
+        .await
+    {
+        Ok(saved_search_id) => deleted_saved_search(saved_search_id).into_response(),
+        Err(error) => error.into_response(),
+    }
+}
+
+/// It returns a page of the torrents matched by a saved search of the
+/// logged-in user.
+///
+/// # Errors
+///
+/// It returns an error if:
+///
+/// - The user is not logged in.
+/// - The saved search does not exist.
+/// - There is a database error.
+#[utoipa::path(
+    get,
+    path = "/v1/user/saved-searches/{saved_search_id}/results",
+    tag = "saved_search",
+    params(
+        ("saved_search_id" = i64, Path, description = "The saved search ID"),
+        ResultsRequest,
+    ),
+    responses(
+        (status = 200, description = "The matched torrents", body = TorrentsResponseData),
+        (status = 401, description = "The user is not logged in", body = ErrorResponseData),
+        (status = 404, description = "The resource does not exist", body = ErrorResponseData),
+    ),
+    security(("bearer_auth" = []))
+)]
+#[allow(clippy::unused_async)]
+pub async fn get_results_handler(
+    State(app_data): State<Arc<AppData>>,
+    ExtractOptionalLoggedInUser(maybe_user_id): ExtractOptionalLoggedInUser,
+    Path(saved_search_id): Path<SavedSearchId>,
+    Query(request): Query<ResultsRequest>,
+) -> Response {
+    match app_data
+        .saved_search_service
+        .get_results(saved_search_id, &request, maybe_user_id)
+        .await
+    {
+        Ok(torrents_response) => Json(OkResponseData { data: torrents_response }).into_response(),
+        Err(error) => error.into_response(),
+    }
+}
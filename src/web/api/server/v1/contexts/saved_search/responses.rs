@@ -0,0 +1,16 @@
+//! API responses for the [`saved_search`](crate::web::api::server::v1::contexts::saved_search) API
+//! context.
+use axum::Json;
+
+use crate::models::saved_search::{SavedSearch, SavedSearchId};
+use crate::web::api::server::v1::responses::OkResponseData;
+
+/// Response after successfully adding a saved search.
+pub fn saved_search(saved_search: SavedSearch) -> Json<OkResponseData<SavedSearch>> {
+    Json(OkResponseData { data: saved_search })
+}
+
+/// Response after successfully deleting a saved search.
+pub fn deleted_saved_search(saved_search_id: SavedSearchId) -> Json<OkResponseData<SavedSearchId>> {
+    Json(OkResponseData { data: saved_search_id })
+}
@@ -0,0 +1,29 @@
+//! API routes for the [`saved_search`](crate::web::api::server::v1::contexts::saved_search) API context.
+//!
+//! Refer to the [API endpoint documentation](crate::web::api::server::v1::contexts::saved_search).
+use std::sync::Arc;
+
+use axum::routing::{delete, get};
+use axum::Router;
+
+use super::handlers::{add_saved_search_handler, delete_saved_search_handler, get_results_handler, get_saved_searches_handler};
+use crate::common::AppData;
+
+/// Routes for the user saved searches.
+///
+/// They are nested under the user resource: `/user/saved-searches`.
+pub fn router_for_user(app_data: Arc<AppData>) -> Router {
+    Router::new()
+        .route(
+            "/",
+            get(get_saved_searches_handler)
+                .with_state(app_data.clone())
+                .post(add_saved_search_handler)
+                .with_state(app_data.clone()),
+        )
+        .route(
+            "/:saved_search_id",
+            delete(delete_saved_search_handler).with_state(app_data.clone()),
+        )
+        .route("/:saved_search_id/results", get(get_results_handler).with_state(app_data))
+}
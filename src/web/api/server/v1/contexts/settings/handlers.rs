@@ -7,7 +7,7 @@ use axum::response::{IntoResponse, Json, Response};
 
 use crate::common::AppData;
 use crate::web::api::server::v1::extractors::optional_user_id::ExtractOptionalLoggedInUser;
-use crate::web::api::server::v1::responses;
+use crate::web::api::server::v1::responses::{self, ErrorResponseData, MessageResponseData};
 
 /// Get all settings.
 ///
@@ -15,6 +15,17 @@ use crate::web::api::server::v1::responses;
 ///
 /// This function will return an error if the user does not have permission to
 /// view all the settings.
+#[utoipa::path(
+    get,
+    path = "/v1/settings",
+    tag = "settings",
+    responses(
+        (status = 200, description = "All the settings, with the secrets masked"),
+        (status = 401, description = "The user is not logged in", body = ErrorResponseData),
+        (status = 403, description = "The user does not have permissions to perform the action", body = ErrorResponseData),
+    ),
+    security(("bearer_auth" = []))
+)]
 #[allow(clippy::unused_async)]
 pub async fn get_all_handler(
     State(app_data): State<Arc<AppData>>,
@@ -29,6 +40,15 @@ pub async fn get_all_handler(
 }
 
 /// Get public Settings.
+#[utoipa::path(
+    get,
+    path = "/v1/settings/public",
+    tag = "settings",
+    responses(
+        (status = 200, description = "The public settings"),
+    ),
+    security((), ("bearer_auth" = []))
+)]
 #[allow(clippy::unused_async)]
 pub async fn get_public_handler(
     State(app_data): State<Arc<AppData>>,
@@ -41,6 +61,15 @@ pub async fn get_public_handler(
 }
 
 /// Get website name.
+#[utoipa::path(
+    get,
+    path = "/v1/settings/name",
+    tag = "settings",
+    responses(
+        (status = 200, description = "The website name", body = MessageResponseData),
+    ),
+    security((), ("bearer_auth" = []))
+)]
 #[allow(clippy::unused_async)]
 pub async fn get_site_name_handler(
     State(app_data): State<Arc<AppData>>,
@@ -51,3 +80,31 @@ pub async fn get_site_name_handler(
         Err(error) => error.into_response(),
     }
 }
+
+/// Get the settings that changed since the application started.
+///
+/// # Errors
+///
+/// This function will return an error if the user does not have permission to
+/// view all the settings.
+#[utoipa::path(
+    get,
+    path = "/v1/settings/changes",
+    tag = "settings",
+    responses(
+        (status = 200, description = "The options that took effect and the ones that require a restart"),
+        (status = 401, description = "The user is not logged in", body = ErrorResponseData),
+        (status = 403, description = "The user does not have permissions to perform the action", body = ErrorResponseData),
+    ),
+    security(("bearer_auth" = []))
+)]
+#[allow(clippy::unused_async)]
+pub async fn get_changes_handler(
+    State(app_data): State<Arc<AppData>>,
+    ExtractOptionalLoggedInUser(maybe_user_id): ExtractOptionalLoggedInUser,
+) -> Response {
+    match app_data.settings_service.get_changes(maybe_user_id).await {
+        Ok(changes) => Json(responses::OkResponseData { data: changes }).into_response(),
+        Err(error) => error.into_response(),
+    }
+}
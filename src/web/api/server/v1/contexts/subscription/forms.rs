@@ -0,0 +1,13 @@
+//! API forms for the the [`subscription`](crate::web::api::server::v1::contexts::subscription) API
+//! context.
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+
+use crate::models::category::CategoryId;
+use crate::models::torrent_tag::TagId;
+
+#[derive(Serialize, Deserialize, Debug, ToSchema)]
+pub struct AddSubscriptionForm {
+    pub category_id: Option<CategoryId>,
+    pub tag_id: Option<TagId>,
+}
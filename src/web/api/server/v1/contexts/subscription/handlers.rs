@@ -0,0 +1,119 @@
+//! API handlers for the [`subscription`](crate::web::api::server::v1::contexts::subscription) API
+//! context.
+use std::sync::Arc;
+
+use axum::extract::{self, Path, State};
+use axum::response::{IntoResponse, Json, Response};
+
+use super::forms::AddSubscriptionForm;
+use super::responses::{deleted_subscription, subscription};
+use crate::common::AppData;
+use crate::models::subscription::SubscriptionId;
+use crate::web::api::server::v1::extractors::optional_user_id::ExtractOptionalLoggedInUser;
+use crate::web::api::server::v1::responses::{ErrorResponseData, OkResponseData};
+
+/// It returns the subscriptions of the logged-in user.
+///
+/// # Errors
+///
+/// It returns an error if:
+///
+/// - The user is not logged in.
+/// - There is a database error.
+#[utoipa::path(
+    get,
+    path = "/v1/user/subscriptions",
+    tag = "subscription",
+    responses(
+        (status = 200, description = "The user's subscriptions"),
+        (status = 401, description = "The user is not logged in", body = ErrorResponseData),
+    ),
+    security(("bearer_auth" = []))
+)]
+#[allow(clippy::unused_async)]
+pub async fn get_subscriptions_handler(
+    State(app_data): State<Arc<AppData>>,
+    ExtractOptionalLoggedInUser(maybe_user_id): ExtractOptionalLoggedInUser,
+) -> Response {
+    match app_data.subscription_service.get_subscriptions(maybe_user_id).await {
+        Ok(subscriptions) => Json(OkResponseData { data: subscriptions }).into_response(),
+        Err(error) => error.into_response(),
+    }
+}
+
+/// It subscribes the logged-in user to a category or a tag.
+///
+/// # Errors
+///
+/// It returns an error if:
+///
+/// - The user is not logged in.
+/// - The subscription is not valid or it already exists.
+/// - There is a database error.
+#[utoipa::path(
+    post,
+    path = "/v1/user/subscriptions",
+    tag = "subscription",
+    request_body = AddSubscriptionForm,
+    responses(
+        (status = 200, description = "The new subscription"),
+        (status = 400, description = "The request is not valid", body = ErrorResponseData),
+        (status = 401, description = "The user is not logged in", body = ErrorResponseData),
+        (status = 409, description = "The resource already exists", body = ErrorResponseData),
+    ),
+    security(("bearer_auth" = []))
+)]
+#[allow(clippy::unused_async)]
+pub async fn add_subscription_handler(
+    State(app_data): State<Arc<AppData>>,
+    ExtractOptionalLoggedInUser(maybe_user_id): ExtractOptionalLoggedInUser,
+    extract::Json(add_subscription_form): extract::Json<AddSubscriptionForm>,
+) -> Response {
+    match app_data
+        .subscription_service
+        .add_subscription(add_subscription_form.category_id, add_subscription_form.tag_id, maybe_user_id)
+        .await
+    {
+        Ok(new_subscription) => subscription(new_subscription).into_response(),
+        Err(error) => error.into_response(),
+    }
+}
+
+/// It deletes a subscription of the logged-in user.
+///
+/// # Errors
+///
+/// It returns an error if:
+///
+/// - The user is not logged in.
+/// - The subscription does not exist.
+/// - There is a database error.
+#[utoipa::path(
+    delete,
+    path = "/v1/user/subscriptions/{subscription_id}",
+    tag = "subscription",
+    params(
+        ("subscription_id" = i64, Path, description = "The subscription ID"),
+    ),
+    responses(
+        (status = 200, description = "The deleted subscription ID"),
+        (status = 401, description = "The user is not logged in", body = ErrorResponseData),
+        (status = 404, description = "The resource does not exist", body = ErrorResponseData),
+    ),
+    security(("bearer_auth" = []))
+)]
+#[allow(clippy::unused_async)]
+pub async fn delete_subscription_handler(
+    State(app_data): State<Arc<AppData>>,
+    ExtractOptionalLoggedInUser(maybe_user_id): ExtractOptionalLoggedInUser,
+    Path(subscription_id): Path<SubscriptionId>,
+) -> Response {
+    match app_data
+        .subscription_service
+        .delete_subscription(subscription_id, maybe_user_id)
+        .await
+    {
+        Ok(subscription_id) => deleted_subscription(subscription_id).into_response(),
+        Err(error) => error.into_response(),
+    }
+}
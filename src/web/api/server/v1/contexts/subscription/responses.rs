@@ -0,0 +1,16 @@
+//! API responses for the [`subscription`](crate::web::api::server::v1::contexts::subscription) API
+//! context.
+use axum::Json;
+
+use crate::models::subscription::{Subscription, SubscriptionId};
+use crate::web::api::server::v1::responses::OkResponseData;
+
+/// Response after successfully adding a subscription.
+pub fn subscription(subscription: Subscription) -> Json<OkResponseData<Subscription>> {
+    Json(OkResponseData { data: subscription })
+}
+
+/// Response after successfully deleting a subscription.
+pub fn deleted_subscription(subscription_id: SubscriptionId) -> Json<OkResponseData<SubscriptionId>> {
+    Json(OkResponseData { data: subscription_id })
+}
@@ -0,0 +1,25 @@
+//! API routes for the [`subscription`](crate::web::api::server::v1::contexts::subscription) API context.
+//!
+//! Refer to the [API endpoint documentation](crate::web::api::server::v1::contexts::subscription).
+use std::sync::Arc;
+
+use axum::routing::{delete, get};
+use axum::Router;
+
+use super::handlers::{add_subscription_handler, delete_subscription_handler, get_subscriptions_handler};
+use crate::common::AppData;
+
+/// Routes for the user subscriptions.
+///
+/// They are nested under the user resource: `/user/subscriptions`.
+pub fn router_for_user(app_data: Arc<AppData>) -> Router {
+    Router::new()
+        .route(
+            "/",
+            get(get_subscriptions_handler)
+                .with_state(app_data.clone())
+                .post(add_subscription_handler)
+                .with_state(app_data.clone()),
+        )
+        .route("/:subscription_id", delete(delete_subscription_handler).with_state(app_data))
+}
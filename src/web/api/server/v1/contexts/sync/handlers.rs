@@ -0,0 +1,82 @@
+//! API handlers for the [`sync`](crate::web::api::server::v1::contexts::sync) API
+//! context.
+use std::sync::Arc;
+
+use axum::extract::{Path, Query, State};
+use axum::response::{IntoResponse, Json, Response};
+
+use crate::common::AppData;
+use crate::models::sync::{PartitionChanges, PartitionId, SyncManifest};
+use crate::services::sync::PartitionRequest;
+use crate::web::api::server::v1::extractors::optional_user_id::ExtractOptionalLoggedInUser;
+use crate::web::api::server::v1::responses::{ErrorResponseData, OkResponseData};
+
+/// It returns the manifest with the version of each partition.
+///
+/// # Errors
+///
+/// It returns an error if:
+///
+/// - The delta sync is disabled.
+/// - The user does not have permissions to list the torrents.
+/// - There is a database error.
+#[utoipa::path(
+    get,
+    path = "/v1/sync/manifest",
+    tag = "sync",
+    responses(
+        (status = 200, description = "The sync manifest", body = SyncManifest),
+        (status = 403, description = "The delta sync is disabled", body = ErrorResponseData),
+    ),
+    security((), ("bearer_auth" = []))
+)]
+#[allow(clippy::unused_async)]
+pub async fn get_manifest_handler(
+    State(app_data): State<Arc<AppData>>,
+    ExtractOptionalLoggedInUser(maybe_user_id): ExtractOptionalLoggedInUser,
+) -> Response {
+    match app_data.sync_service.get_manifest(maybe_user_id).await {
+        Ok(manifest) => Json(OkResponseData { data: manifest }).into_response(),
+        Err(error) => error.into_response(),
+    }
+}
+
+/// It returns the records of a partition changed after a sequence.
+///
+/// # Errors
+///
+/// It returns an error if:
+///
+/// - The delta sync is disabled.
+/// - The user does not have permissions to list the torrents.
+/// - There is a database error.
+#[utoipa::path(
+    get,
+    path = "/v1/sync/partition/{partition_id}",
+    tag = "sync",
+    params(
+        ("partition_id" = i64, Path, description = "The partition ID"),
+        PartitionRequest,
+    ),
+    responses(
+        (status = 200, description = "The changes in the partition", body = PartitionChanges),
+        (status = 403, description = "The delta sync is disabled", body = ErrorResponseData),
+    ),
+    security((), ("bearer_auth" = []))
+)]
+#[allow(clippy::unused_async)]
+pub async fn get_partition_handler(
+    State(app_data): State<Arc<AppData>>,
+    ExtractOptionalLoggedInUser(maybe_user_id): ExtractOptionalLoggedInUser,
+    Path(partition_id): Path<PartitionId>,
+    Query(request): Query<PartitionRequest>,
+) -> Response {
+    match app_data
+        .sync_service
+        .get_partition_changes(partition_id, &request, maybe_user_id)
+        .await
+    {
+        Ok(changes) => Json(OkResponseData { data: changes }).into_response(),
+        Err(error) => error.into_response(),
+    }
+}
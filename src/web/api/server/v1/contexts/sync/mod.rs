@@ -0,0 +1,103 @@
+//! API context: `sync`.
+//!
+//! This API context is used by mirrors to keep a copy of the approved
+//! torrents up to date, downloading only what changed. Refer to the
+//! [`sync`](crate::services::sync) service for more information.
+//!
+//! The endpoints are disabled unless the `[mirror_sync]` section is present
+//! in the configuration:
+//!
+//! ```toml
+//! [mirror_sync]
+//! refresh_interval_seconds = 300
+//! ```
+//!
+//! A mirror stores the `version` and `last_sequence` of each partition. To
+//! update its copy, it gets the manifest and, for each partition with a
+//! different version, it requests the changes after the `last_sequence` it
+//! has for the partition.
+//!
+//! # Endpoints
+//!
+//! - [Get the manifest](#get-the-manifest)
+//! - [Get the changes in a partition](#get-the-changes-in-a-partition)
+//!
+//! # Get the manifest
+//!
+//! `GET /v1/sync/manifest`
+//!
+//! It returns the list of partitions with their versions.
+//!
+//! **Example request**
+//!
+//! ```bash
+//! curl "http://127.0.0.1:3001/v1/sync/manifest"
+//! ```
+//!
+//! **Example response** `200`
+//!
+//! ```json
+//! {
+//!   "data": {
+//!     "partition_size": 1000,
+//!     "last_sequence": 1532,
+//!     "partitions": [
+//!       {
+//!         "partition_id": 0,
+//!         "version": "9f86d081884c7d65",
+//!         "records": 998,
+//!         "last_sequence": 1532
+//!       }
+//!     ]
+//!   }
+//! }
+//! ```
+//!
+//! # Get the changes in a partition
+//!
+//! `GET /v1/sync/partition/{partition_id}`
+//!
+//! It returns the torrents in the partition added or changed after a
+//! sequence, and the IDs of the torrents removed. Removed torrents include
+//! the ones that are not approved anymore.
+//!
+//! **Query parameters**
+//!
+//! Name | Type | Description | Required | Example
+//! ---|---|---|---|---
+//! `since` | `i64` | Only changes after this sequence. Default to `0`, all the records | No | `1500`
+//!
+//! **Example request**
+//!
+//! ```bash
+//! curl "http://127.0.0.1:3001/v1/sync/partition/0?since=1500"
+//! ```
+//!
+//! **Example response** `200`
+//!
+//! ```json
+//! {
+//!   "data": {
+//!     "partition_id": 0,
+//!     "version": "9f86d081884c7d65",
+//!     "last_sequence": 1532,
+//!     "records": [
+//!       {
+//!         "torrent_id": 12,
+//!         "info_hash": "5452869be36f9f3350ccee6b4544e7e76caaadab",
+//!         "title": "Big Buck Bunny",
+//!         "description": "A short film",
+//!         "category": "movies",
+//!         "tags": ["animation"],
+//!         "name": "big-buck-bunny",
+//!         "file_size": 276134947,
+//!         "date_uploaded": "2024-10-17 10:20:00",
+//!         "uploader": "indexadmin"
+//!       }
+//!     ],
+//!     "deleted": [7]
+//!   }
+//! }
+//! ```
+pub mod handlers;
+pub mod routes;
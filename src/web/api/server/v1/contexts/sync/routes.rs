@@ -0,0 +1,17 @@
+//! API routes for the [`sync`](crate::web::api::server::v1::contexts::sync) API context.
+//!
+//! Refer to the [API endpoint documentation](crate::web::api::server::v1::contexts::sync).
+use std::sync::Arc;
+
+use axum::routing::get;
+use axum::Router;
+
+use super::handlers::{get_manifest_handler, get_partition_handler};
+use crate::common::AppData;
+
+/// Routes for the [`sync`](crate::web::api::server::v1::contexts::sync) API context.
+pub fn router(app_data: Arc<AppData>) -> Router {
+    Router::new()
+        .route("/manifest", get(get_manifest_handler).with_state(app_data.clone()))
+        .route("/partition/:partition_id", get(get_partition_handler).with_state(app_data))
+}
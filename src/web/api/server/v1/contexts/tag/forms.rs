@@ -1,15 +1,36 @@
 //! API forms for the the [`tag`](crate::web::api::server::v1::contexts::tag) API
 //! context.
 use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
 
 use crate::models::torrent_tag::TagId;
 
-#[derive(Serialize, Deserialize, Debug)]
+#[derive(Serialize, Deserialize, Debug, ToSchema)]
 pub struct AddTagForm {
     pub name: String,
 }
 
-#[derive(Serialize, Deserialize, Debug)]
+#[derive(Serialize, Deserialize, Debug, ToSchema)]
 pub struct DeleteTagForm {
     pub tag_id: TagId,
 }
+
+#[derive(Serialize, Deserialize, Debug, ToSchema)]
+pub struct MergeTagsForm {
+    /// The tag to merge. It's deleted after the merge.
+    pub source_tag_id: TagId,
+    /// The tag that remains.
+    pub target_tag_id: TagId,
+}
+
+#[derive(Serialize, Deserialize, Debug, ToSchema)]
+pub struct AddTagSynonymForm {
+    pub name: String,
+    /// The tag the synonym points to.
+    pub tag_id: TagId,
+}
+
+#[derive(Serialize, Deserialize, Debug, ToSchema)]
+pub struct DeleteTagSynonymForm {
+    pub name: String,
+}
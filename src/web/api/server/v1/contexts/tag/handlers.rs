@@ -5,11 +5,12 @@ use std::sync::Arc;
 use axum::extract::{self, State};
 use axum::response::{IntoResponse, Json, Response};
 
-use super::forms::{AddTagForm, DeleteTagForm};
-use super::responses::{added_tag, deleted_tag};
+use super::forms::{AddTagForm, AddTagSynonymForm, DeleteTagForm, DeleteTagSynonymForm, MergeTagsForm};
+use super::responses::{added_tag, deleted_tag, merged_tag, tag_synonym};
 use crate::common::AppData;
+use crate::models::torrent_tag::{TagSynonym, TrendingTag};
 use crate::web::api::server::v1::extractors::optional_user_id::ExtractOptionalLoggedInUser;
-use crate::web::api::server::v1::responses::{self};
+use crate::web::api::server::v1::responses::{self, ErrorResponseData, IdResponseData, MessageResponseData, TagsResponseData};
 
 /// It handles the request to get all the tags.
 ///
@@ -27,6 +28,15 @@ use crate::web::api::server::v1::responses::{self};
 /// There is a database error
 /// There is a problem authorizing the action.
 /// The user is not authorized to perform the action
+#[utoipa::path(
+    get,
+    path = "/v1/tags",
+    tag = "tag",
+    responses(
+        (status = 200, description = "The tags", body = TagsResponseData),
+    ),
+    security((), ("bearer_auth" = []))
+)]
 #[allow(clippy::unused_async)]
 pub async fn get_all_handler(
     State(app_data): State<Arc<AppData>>,
@@ -38,6 +48,39 @@ pub async fn get_all_handler(
     }
 }
 
+/// It returns the tags with the largest growth in uploads and downloads in
+/// the last window.
+///
+/// Refer to the [`trending_tags`](crate::services::trending_tags) service for
+/// more information.
+///
+/// # Errors
+///
+/// It returns an error if:
+///
+/// - The trending tags are not enabled.
+/// - The user is not authorized to get the tags.
+#[utoipa::path(
+    get,
+    path = "/v1/tags/trending",
+    tag = "tag",
+    responses(
+        (status = 200, description = "The trending tags", body = [TrendingTag]),
+        (status = 403, description = "The trending tags are not enabled", body = ErrorResponseData),
+    ),
+    security((), ("bearer_auth" = []))
+)]
+#[allow(clippy::unused_async)]
+pub async fn get_trending_handler(
+    State(app_data): State<Arc<AppData>>,
+    ExtractOptionalLoggedInUser(maybe_user_id): ExtractOptionalLoggedInUser,
+) -> Response {
+    match app_data.trending_tags_service.get_trending_tags(maybe_user_id).await {
+        Ok(tags) => Json(responses::OkResponseData { data: tags }).into_response(),
+        Err(error) => error.into_response(),
+    }
+}
+
 /// It adds a new tag.
 ///
 /// # Errors
@@ -46,6 +89,20 @@ pub async fn get_all_handler(
 ///
 /// - The user does not have permissions to create a new tag.
 /// - There is a database error.
+#[utoipa::path(
+    post,
+    path = "/v1/tag",
+    tag = "tag",
+    request_body = AddTagForm,
+    responses(
+        (status = 200, description = "The tag name", body = MessageResponseData),
+        (status = 400, description = "The request is not valid", body = ErrorResponseData),
+        (status = 401, description = "The user is not logged in", body = ErrorResponseData),
+        (status = 403, description = "The user does not have permissions to perform the action", body = ErrorResponseData),
+        (status = 409, description = "The resource already exists", body = ErrorResponseData),
+    ),
+    security(("bearer_auth" = []))
+)]
 #[allow(clippy::unused_async)]
 pub async fn add_handler(
     State(app_data): State<Arc<AppData>>,
@@ -66,6 +123,19 @@ pub async fn add_handler(
 ///
 /// - The user does not have permissions to delete tags.
 /// - There is a database error.
+#[utoipa::path(
+    delete,
+    path = "/v1/tag",
+    tag = "tag",
+    request_body = DeleteTagForm,
+    responses(
+        (status = 200, description = "The deleted tag ID", body = IdResponseData),
+        (status = 401, description = "The user is not logged in", body = ErrorResponseData),
+        (status = 403, description = "The user does not have permissions to perform the action", body = ErrorResponseData),
+        (status = 404, description = "The resource does not exist", body = ErrorResponseData),
+    ),
+    security(("bearer_auth" = []))
+)]
 #[allow(clippy::unused_async)]
 pub async fn delete_handler(
     State(app_data): State<Arc<AppData>>,
@@ -77,3 +147,149 @@ pub async fn delete_handler(
         Err(error) => error.into_response(),
     }
 }
+
+/// It merges a tag into another tag. The torrents with the merged tag get the
+/// other tag, and the name of the merged tag becomes a synonym.
+///
+/// # Errors
+///
+/// It returns an error if:
+///
+/// - The user does not have permissions to merge tags.
+/// - Both tags are the same or any of them does not exist.
+/// - There is a database error.
+#[utoipa::path(
+    post,
+    path = "/v1/tag/merge",
+    tag = "tag",
+    request_body = MergeTagsForm,
+    responses(
+        (status = 200, description = "The ID of the tag that remains", body = IdResponseData),
+        (status = 400, description = "The request is not valid", body = ErrorResponseData),
+        (status = 401, description = "The user is not logged in", body = ErrorResponseData),
+        (status = 403, description = "The user does not have permissions to perform the action", body = ErrorResponseData),
+        (status = 404, description = "The resource does not exist", body = ErrorResponseData),
+    ),
+    security(("bearer_auth" = []))
+)]
+#[allow(clippy::unused_async)]
+pub async fn merge_handler(
+    State(app_data): State<Arc<AppData>>,
+    ExtractOptionalLoggedInUser(maybe_user_id): ExtractOptionalLoggedInUser,
+    extract::Json(merge_tags_form): extract::Json<MergeTagsForm>,
+) -> Response {
+    match app_data
+        .tag_service
+        .merge_tags(merge_tags_form.source_tag_id, merge_tags_form.target_tag_id, maybe_user_id)
+        .await
+    {
+        Ok(()) => merged_tag(merge_tags_form.target_tag_id).into_response(),
+        Err(error) => error.into_response(),
+    }
+}
+
+/// It returns all the tag synonyms.
+///
+/// # Errors
+///
+/// It returns an error if:
+///
+/// - The user is not authorized to get the tags.
+/// - There is a database error.
+#[utoipa::path(
+    get,
+    path = "/v1/tags/synonyms",
+    tag = "tag",
+    responses(
+        (status = 200, description = "The tag synonyms", body = [TagSynonym]),
+    ),
+    security((), ("bearer_auth" = []))
+)]
+#[allow(clippy::unused_async)]
+pub async fn get_synonyms_handler(
+    State(app_data): State<Arc<AppData>>,
+    ExtractOptionalLoggedInUser(maybe_user_id): ExtractOptionalLoggedInUser,
+) -> Response {
+    match app_data.tag_service.get_synonyms(maybe_user_id).await {
+        Ok(synonyms) => Json(responses::OkResponseData { data: synonyms }).into_response(),
+        Err(error) => error.into_response(),
+    }
+}
+
+/// It adds a synonym for a tag.
+///
+/// # Errors
+///
+/// It returns an error if:
+///
+/// - The user does not have permissions to manage the tag synonyms.
+/// - The synonym is already a tag or a synonym.
+/// - The tag does not exist.
+/// - There is a database error.
+#[utoipa::path(
+    post,
+    path = "/v1/tag/synonym",
+    tag = "tag",
+    request_body = AddTagSynonymForm,
+    responses(
+        (status = 200, description = "The synonym", body = MessageResponseData),
+        (status = 400, description = "The request is not valid", body = ErrorResponseData),
+        (status = 401, description = "The user is not logged in", body = ErrorResponseData),
+        (status = 403, description = "The user does not have permissions to perform the action", body = ErrorResponseData),
+        (status = 404, description = "The resource does not exist", body = ErrorResponseData),
+    ),
+    security(("bearer_auth" = []))
+)]
+#[allow(clippy::unused_async)]
+pub async fn add_synonym_handler(
+    State(app_data): State<Arc<AppData>>,
+    ExtractOptionalLoggedInUser(maybe_user_id): ExtractOptionalLoggedInUser,
+    extract::Json(add_tag_synonym_form): extract::Json<AddTagSynonymForm>,
+) -> Response {
+    match app_data
+        .tag_service
+        .add_synonym(&add_tag_synonym_form.name, add_tag_synonym_form.tag_id, maybe_user_id)
+        .await
+    {
+        Ok(()) => tag_synonym(add_tag_synonym_form.name.trim()).into_response(),
+        Err(error) => error.into_response(),
+    }
+}
+
+/// It deletes a tag synonym.
+///
+/// # Errors
+///
+/// It returns an error if:
+///
+/// - The user does not have permissions to manage the tag synonyms.
+/// - The synonym does not exist.
+/// - There is a database error.
+#[utoipa::path(
+    delete,
+    path = "/v1/tag/synonym",
+    tag = "tag",
+    request_body = DeleteTagSynonymForm,
+    responses(
+        (status = 200, description = "The deleted synonym", body = MessageResponseData),
+        (status = 401, description = "The user is not logged in", body = ErrorResponseData),
+        (status = 403, description = "The user does not have permissions to perform the action", body = ErrorResponseData),
+        (status = 404, description = "The resource does not exist", body = ErrorResponseData),
+    ),
+    security(("bearer_auth" = []))
+)]
+#[allow(clippy::unused_async)]
+pub async fn delete_synonym_handler(
+    State(app_data): State<Arc<AppData>>,
+    ExtractOptionalLoggedInUser(maybe_user_id): ExtractOptionalLoggedInUser,
+    extract::Json(delete_tag_synonym_form): extract::Json<DeleteTagSynonymForm>,
+) -> Response {
+    match app_data
+        .tag_service
+        .delete_synonym(&delete_tag_synonym_form.name, maybe_user_id)
+        .await
+    {
+        Ok(()) => tag_synonym(delete_tag_synonym_form.name.trim()).into_response(),
+        Err(error) => error.into_response(),
+    }
+}
@@ -16,3 +16,13 @@ pub fn added_tag(tag_name: &str) -> Json<OkResponseData<String>> {
 pub fn deleted_tag(tag_id: TagId) -> Json<OkResponseData<TagId>> {
     Json(OkResponseData { data: tag_id })
 }
+
+/// Response after successfully merging a tag into another tag.
+pub fn merged_tag(target_tag_id: TagId) -> Json<OkResponseData<TagId>> {
+    Json(OkResponseData { data: target_tag_id })
+}
+
+/// Response after successfully adding or deleting a tag synonym.
+pub fn tag_synonym(name: &str) -> Json<OkResponseData<String>> {
+    Json(OkResponseData { data: name.to_string() })
+}
@@ -6,7 +6,10 @@ use std::sync::Arc;
 use axum::routing::{delete, get, post};
 use axum::Router;
 
-use super::handlers::{add_handler, delete_handler, get_all_handler};
+use super::handlers::{
+    add_handler, add_synonym_handler, delete_handler, delete_synonym_handler, get_all_handler, get_synonyms_handler,
+    get_trending_handler, merge_handler,
+};
 use crate::common::AppData;
 
 // code-review: should we use `tags` also for single resources?
@@ -15,10 +18,16 @@ use crate::common::AppData;
 pub fn router_for_single_resources(app_data: Arc<AppData>) -> Router {
     Router::new()
         .route("/", post(add_handler).with_state(app_data.clone()))
-        .route("/", delete(delete_handler).with_state(app_data))
+        .route("/", delete(delete_handler).with_state(app_data.clone()))
+        .route("/merge", post(merge_handler).with_state(app_data.clone()))
+        .route("/synonym", post(add_synonym_handler).with_state(app_data.clone()))
+        .route("/synonym", delete(delete_synonym_handler).with_state(app_data))
 }
 
 /// Routes for the [`tag`](crate::web::api::server::v1::contexts::tag) API context.
 pub fn router_for_multiple_resources(app_data: Arc<AppData>) -> Router {
-    Router::new().route("/", get(get_all_handler).with_state(app_data))
+    Router::new()
+        .route("/", get(get_all_handler).with_state(app_data.clone()))
+        .route("/trending", get(get_trending_handler).with_state(app_data.clone()))
+        .route("/synonyms", get(get_synonyms_handler).with_state(app_data))
 }
@@ -32,6 +32,33 @@ pub enum Request {
 
     #[display("provided path param for Info-hash is not valid.")]
     InvalidInfoHashParam,
+
+    #[display("signed upload request headers are missing or not valid.")]
+    InvalidUploadSignatureHeaders,
+
+    #[display("the multipart form is not valid.")]
+    InvalidMultipartForm,
+
+    #[display("preview file index is not a valid number.")]
+    InvalidPreviewFileIndex,
+
+    #[display("the multipart form must contain an `image` file.")]
+    MissingImageFile,
+
+    #[display("attachment kind should be `nfo` or `screenshot`.")]
+    InvalidAttachmentKind,
+
+    #[display("the multipart form must contain a `file`.")]
+    MissingAttachmentFile,
+
+    #[display("publish date should be a RFC 3339 date, like `2024-10-18T12:00:00Z`.")]
+    InvalidPublishDate,
+
+    #[display("preserve_announce_urls should be `true` or `false`.")]
+    InvalidPreserveAnnounceUrls,
+
+    #[display("torrent fields should be a JSON object with the values by field name.")]
+    InvalidCategoryFields,
 }
 
 impl IntoResponse for Request {
@@ -54,6 +81,15 @@ pub fn http_status_code_for_handler_error(error: &Request) -> StatusCode {
         Request::TagsArrayIsNotValidJson => StatusCode::BAD_REQUEST,
         Request::InvalidFileType => StatusCode::BAD_REQUEST,
         Request::InvalidInfoHashParam => StatusCode::BAD_REQUEST,
+        Request::InvalidUploadSignatureHeaders => StatusCode::BAD_REQUEST,
+        Request::InvalidMultipartForm => StatusCode::BAD_REQUEST,
+        Request::InvalidPreviewFileIndex => StatusCode::BAD_REQUEST,
+        Request::MissingImageFile => StatusCode::BAD_REQUEST,
+        Request::InvalidAttachmentKind => StatusCode::BAD_REQUEST,
+        Request::MissingAttachmentFile => StatusCode::BAD_REQUEST,
+        Request::InvalidPublishDate => StatusCode::BAD_REQUEST,
+        Request::InvalidPreserveAnnounceUrls => StatusCode::BAD_REQUEST,
+        Request::InvalidCategoryFields => StatusCode::BAD_REQUEST,
         // Internal errors processing the request
         Request::CannotWriteChunkFromUploadedBinary => StatusCode::INTERNAL_SERVER_ERROR,
         Request::CannotReadChunkFromUploadedBinary => StatusCode::INTERNAL_SERVER_ERROR,
@@ -1,12 +1,126 @@
 use serde::Deserialize;
+use utoipa::ToSchema;
 
 use crate::models::category::CategoryId;
+use crate::models::torrent_file_group::TorrentFileGroup;
 use crate::models::torrent_tag::TagId;
 
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Deserialize, ToSchema)]
 pub struct UpdateTorrentInfoForm {
     pub title: Option<String>,
     pub description: Option<String>,
     pub category: Option<CategoryId>,
     pub tags: Option<Vec<TagId>>,
 }
+
+#[derive(Debug, Deserialize, ToSchema)]
+pub struct UpdateTorrentTrackersForm {
+    /// The trackers grouped in `announce-list` tiers.
+    pub tiers: Vec<Vec<String>>,
+}
+
+#[derive(Debug, Deserialize, ToSchema)]
+pub struct UpdateTorrentFileGroupsForm {
+    /// The new file groups. An empty list removes all the groups.
+    pub groups: Vec<TorrentFileGroup>,
+}
+
+#[derive(Debug, Deserialize, ToSchema)]
+pub struct AddMagnetForm {
+    pub magnet_uri: String,
+    /// Default to the display name (`dn`) in the magnet link.
+    pub title: Option<String>,
+    pub description: Option<String>,
+    /// The category name, like in torrent file uploads.
+    pub category: String,
+    pub tags: Option<Vec<TagId>>,
+}
+
+#[derive(Debug, Deserialize, ToSchema)]
+pub struct AddWebSeedForm {
+    /// The HTTP or HTTPS URL of the web seed.
+    pub url: String,
+}
+
+#[derive(Debug, Deserialize, ToSchema)]
+pub struct EnrichMetadataForm {
+    /// The ID of the title in the provider: `imdb:<id>`, `tmdb:movie/<id>`,
+    /// `tmdb:tv/<id>` or `musicbrainz:<release group id>`. It takes
+    /// precedence over the title.
+    pub external_id: Option<String>,
+    /// The title to search in the providers.
+    pub title: Option<String>,
+    /// The provider to search the title in: `tmdb` or `musicbrainz`. Default
+    /// to all the configured providers.
+    pub provider: Option<String>,
+}
+
+/// The multipart form to upload a torrent file.
+///
+/// The handler reads the fields from the multipart payload. This type only
+/// documents them in the `OpenAPI` specification.
+#[allow(dead_code)]
+#[derive(ToSchema)]
+pub struct UploadTorrentMultipartForm {
+    pub title: String,
+    pub description: String,
+    /// The category name.
+    pub category: String,
+    /// A JSON array with the tag IDs. For example: `[1,2]`.
+    pub tags: Option<String>,
+    /// An optional RFC 3339 release date, like `2024-10-18T12:00:00Z`. The
+    /// torrent is hidden until then.
+    pub publish_at: Option<String>,
+    /// `true` to keep the original trackers after the index tracker, so the
+    /// torrent can be cross-seeded. Default to the instance configuration.
+    pub preserve_announce_urls: Option<String>,
+    /// A JSON object with the values of the category fields, by field name.
+    /// For example: `{"resolution":"1080p","runtime":136}`.
+    pub fields: Option<String>,
+    /// The torrent file, with the `application/x-bittorrent` content type.
+    #[schema(value_type = String, format = Binary)]
+    pub torrent: Vec<u8>,
+}
+
+/// The multipart form to attach a file in the torrent as a preview.
+///
+/// The handler reads the fields from the multipart payload. This type only
+/// documents them in the `OpenAPI` specification.
+#[allow(dead_code)]
+#[derive(ToSchema)]
+pub struct UploadPreviewMultipartForm {
+    /// The position of the file in the torrent. It's `0` in single-file
+    /// torrents.
+    pub file_index: usize,
+    /// The contents of all the pieces the file is in, from the first to the
+    /// last one.
+    #[schema(value_type = String, format = Binary)]
+    pub pieces: Vec<u8>,
+}
+
+/// The multipart form to upload an image for a torrent description.
+///
+/// The handler reads the fields from the multipart payload. This type only
+/// documents them in the `OpenAPI` specification.
+#[allow(dead_code)]
+#[derive(ToSchema)]
+pub struct UploadDescriptionImageMultipartForm {
+    /// The image file: PNG, JPEG, GIF or WebP. The file name is used as the
+    /// alt text in the markdown.
+    #[schema(value_type = String, format = Binary)]
+    pub image: Vec<u8>,
+}
+
+/// The multipart form to attach a file to a torrent.
+///
+/// The handler reads the fields from the multipart payload. This type only
+/// documents them in the `OpenAPI` specification.
+#[allow(dead_code)]
+#[derive(ToSchema)]
+pub struct UploadAttachmentMultipartForm {
+    /// `nfo` or `screenshot`.
+    pub kind: String,
+    /// The NFO text file or the screenshot image.
+    #[schema(value_type = String, format = Binary)]
+    pub file: Vec<u8>,
+}
@@ -1,29 +1,61 @@
 //! API handlers for the [`torrent`](crate::web::api::server::v1::contexts::torrent) API
 //! context.
+use std::collections::BTreeMap;
 use std::io::{Cursor, Write};
 use std::str::FromStr;
 use std::sync::Arc;
 
-use axum::extract::{self, Multipart, Path, Query, State};
+use axum::extract::{self, Host, Multipart, Path, Query, RawQuery, State};
+use axum::http::header::{self, USER_AGENT};
+use axum::http::HeaderMap;
 use axum::response::{IntoResponse, Redirect, Response};
 use axum::Json;
 use bittorrent_primitives::info_hash::InfoHash;
+use bytes::Bytes;
+use chrono::{DateTime, Utc};
 use serde::Deserialize;
 use tracing::debug;
 use uuid::Uuid;
 
 use super::errors;
-use super::forms::UpdateTorrentInfoForm;
-use super::responses::{new_torrent_response, torrent_file_response};
+use super::forms::{
+    AddMagnetForm, AddWebSeedForm, EnrichMetadataForm, UpdateTorrentFileGroupsForm, UpdateTorrentInfoForm,
+    UpdateTorrentTrackersForm, UploadAttachmentMultipartForm, UploadDescriptionImageMultipartForm, UploadPreviewMultipartForm,
+    UploadTorrentMultipartForm,
+};
+use super::responses::{
+    description_image_response, new_torrent_response, torrent_file_response, torrent_preview_response, torrents_snapshot_response,
+};
 use crate::common::AppData;
 use crate::errors::ServiceError;
+use crate::models::image::ImageId;
+use crate::models::magnet_upload::MagnetUploadId;
+use crate::models::response::{MagnetLinkResponse, TorrentPeersResponse};
+use crate::models::torrent_attachment::{AttachmentId, AttachmentKind, TorrentAttachment};
+use crate::models::torrent_duplicate::DuplicateCluster;
+use crate::models::torrent_file_group::{PackFile, TorrentFileGroupSummary};
+use crate::models::torrent_metadata::TorrentMetadata;
+use crate::models::torrent_preview::{PreviewId, TorrentPreview};
 use crate::models::torrent_tag::TagId;
-use crate::services::torrent::{AddTorrentRequest, ListingRequest};
+use crate::models::torrent_web_seed::{TorrentWebSeed, WebSeedId};
+use crate::services::enrichment::EnrichMetadataRequest;
+use crate::services::signed_upload::UploadSignature;
+use crate::services::torrent::attachment::AddAttachmentRequest;
+use crate::services::torrent::description_image::{UploadImageRequest, UploadedDescriptionImage};
+use crate::services::torrent::file_group::FileSearchRequest;
+use crate::services::torrent::preview::AddPreviewRequest;
+use crate::services::torrent::{AddMagnetRequest, AddTorrentRequest, ListingRequest};
 use crate::services::torrent_file::generate_random_torrent;
-use crate::utils::parse_torrent;
+use crate::utils::{base32, parse_torrent};
+use crate::web::api::server::v1::contexts::proxy::responses::png_image;
+use crate::web::api::server::v1::contexts::user::handlers::api_base_url;
+use crate::web::api::server::v1::etag::{is_not_modified, not_modified, weak_etag, with_validators};
 use crate::web::api::server::v1::extractors::optional_user_id::ExtractOptionalLoggedInUser;
 use crate::web::api::server::v1::extractors::user_id::ExtractLoggedInUser;
-use crate::web::api::server::v1::responses::OkResponseData;
+use crate::web::api::server::v1::responses::{
+    DeletedTorrentResponseData, ErrorResponseData, MessageResponseData, OkResponseData, TorrentResponseData,
+    TorrentsResponseData, UploadedTorrentResponseData,
+};
 use crate::web::api::server::v1::routes::API_VERSION_URL_PREFIX;
 
 /// Upload a new torrent file to the Index
@@ -34,6 +66,20 @@ use crate::web::api::server::v1::routes::API_VERSION_URL_PREFIX;
 ///
 /// - The user does not have permission to upload the torrent file.
 /// - The submitted torrent file is not a valid torrent file.
+#[utoipa::path(
+    post,
+    path = "/v1/torrent/upload",
+    tag = "torrent",
+    request_body = (content = UploadTorrentMultipartForm, content_type = "multipart/form-data"),
+    responses(
+        (status = 200, description = "The uploaded torrent", body = UploadedTorrentResponseData),
+        (status = 400, description = "The request is not valid", body = ErrorResponseData),
+        (status = 401, description = "The user is not logged in", body = ErrorResponseData),
+        (status = 403, description = "The user does not have permissions to perform the action", body = ErrorResponseData),
+        (status = 409, description = "The resource already exists", body = ErrorResponseData),
+    ),
+    security(("bearer_auth" = []))
+)]
 #[allow(clippy::unused_async)]
 pub async fn upload_torrent_handler(
     State(app_data): State<Arc<AppData>>,
@@ -51,6 +97,91 @@ pub async fn upload_torrent_handler(
     }
 }
 
+/// Header with the ID of the user the torrent is uploaded on behalf of.
+pub const UPLOAD_USER_ID_HEADER: &str = "x-upload-user-id";
+/// Header with the signature timestamp (seconds since the Unix epoch).
+pub const UPLOAD_TIMESTAMP_HEADER: &str = "x-upload-timestamp";
+/// Header with the single-use signature nonce.
+pub const UPLOAD_NONCE_HEADER: &str = "x-upload-nonce";
+/// Header with the hex-encoded HMAC-SHA256 signature.
+pub const UPLOAD_SIGNATURE_HEADER: &str = "x-upload-signature";
+
+/// Upload a new torrent file to the Index on behalf of a user.
+///
+/// The request is not authenticated with the user's token but signed by a
+/// trusted external system. See [`signed_upload`](crate::services::signed_upload).
+///
+/// # Errors
+///
+/// This function will return an error if
+///
+/// - The signature headers are missing or the signature is not valid.
+/// - The user does not have permission to upload the torrent file.
+/// - The submitted torrent file is not a valid torrent file.
+#[utoipa::path(
+    post,
+    path = "/v1/torrent/upload/signed",
+    tag = "torrent",
+    params(
+        ("x-upload-user-id" = i64, Header, description = "The ID of the user the torrent is uploaded on behalf of"),
+        ("x-upload-timestamp" = i64, Header, description = "The signature timestamp (seconds since the Unix epoch)"),
+        ("x-upload-nonce" = String, Header, description = "The single-use signature nonce"),
+        ("x-upload-signature" = String, Header, description = "The hex-encoded HMAC-SHA256 signature"),
+    ),
+    request_body = (content = UploadTorrentMultipartForm, content_type = "multipart/form-data"),
+    responses(
+        (status = 200, description = "The uploaded torrent", body = UploadedTorrentResponseData),
+        (status = 400, description = "The request is not valid", body = ErrorResponseData),
+        (status = 401, description = "The user is not logged in", body = ErrorResponseData),
+        (status = 403, description = "The user does not have permissions to perform the action", body = ErrorResponseData),
+        (status = 409, description = "The resource already exists", body = ErrorResponseData),
+    ),
+    security(())
+)]
+#[allow(clippy::unused_async)]
+pub async fn signed_upload_torrent_handler(
+    State(app_data): State<Arc<AppData>>,
+    headers: HeaderMap,
+    multipart: Multipart,
+) -> Response {
+    let Some(upload_signature) = upload_signature_from_headers(&headers) else {
+        return errors::Request::InvalidUploadSignatureHeaders.into_response();
+    };
+
+    let add_torrent_form = match build_add_torrent_request_from_payload(multipart).await {
+        Ok(torrent_request) => torrent_request,
+        Err(error) => return error.into_response(),
+    };
+
+    if let Err(error) = app_data
+        .signed_upload_service
+        .verify(&upload_signature, &add_torrent_form)
+        .await
+    {
+        return error.into_response();
+    }
+
+    match app_data
+        .torrent_service
+        .add_torrent(add_torrent_form, Some(upload_signature.user_id))
+        .await
+    {
+        Ok(response) => new_torrent_response(&response).into_response(),
+        Err(error) => error.into_response(),
+    }
+}
+
+fn upload_signature_from_headers(headers: &HeaderMap) -> Option<UploadSignature> {
+    let header = |name: &str| headers.get(name).and_then(|value| value.to_str().ok());
+
+    Some(UploadSignature {
+        user_id: header(UPLOAD_USER_ID_HEADER)?.parse().ok()?,
+        timestamp: header(UPLOAD_TIMESTAMP_HEADER)?.parse().ok()?,
+        nonce: header(UPLOAD_NONCE_HEADER)?.to_string(),
+        signature: header(UPLOAD_SIGNATURE_HEADER)?.to_lowercase(),
+    })
+}
+
 #[derive(Deserialize)]
 pub struct InfoHashParam(pub String);
 
@@ -62,14 +193,33 @@ impl InfoHashParam {
 
 /// Returns the torrent as a byte stream `application/x-bittorrent`.
 ///
+/// Successful downloads are counted by client family when download
+/// statistics are enabled.
+///
 /// # Errors
 ///
 /// Returns an error if the torrent info-hash is invalid.
+#[utoipa::path(
+    get,
+    path = "/v1/torrent/download/{info_hash}",
+    tag = "torrent",
+    params(
+        ("info_hash" = String, Path, description = "The torrent info-hash"),
+    ),
+    responses(
+        (status = 200, description = "The torrent file", content_type = "application/x-bittorrent"),
+        (status = 400, description = "The request is not valid", body = ErrorResponseData),
+        (status = 403, description = "The user's upload ratio is below the minimum", body = ErrorResponseData),
+        (status = 404, description = "The resource does not exist", body = ErrorResponseData),
+    ),
+    security((), ("bearer_auth" = []))
+)]
 #[allow(clippy::unused_async)]
 pub async fn download_torrent_handler(
     State(app_data): State<Arc<AppData>>,
     ExtractOptionalLoggedInUser(maybe_user_id): ExtractOptionalLoggedInUser,
     Path(info_hash): Path<InfoHashParam>,
+    headers: HeaderMap,
 ) -> Response {
     let Ok(info_hash) = InfoHash::from_str(&info_hash.lowercase()) else {
         return errors::Request::InvalidInfoHashParam.into_response();
@@ -92,10 +242,41 @@ pub async fn download_torrent_handler(
             return ServiceError::InternalServerError.into_response();
         };
 
-        torrent_file_response(
-            bytes,
-            &format!("{}.torrent", torrent.info.name),
-            &torrent.canonical_info_hash_hex(),
+        // The file can include the user tracker key, so the tag is derived
+        // from the file. Clients revalidating their copy are not counted as
+        // downloads.
+        let etag = weak_etag(&[bytes.as_slice()]);
+        let last_modified = app_data.modification_times.last_modified(
+            &format!(
+                "torrent/download/{}#{}",
+                info_hash.to_hex_string(),
+                user_resource(maybe_user_id)
+            ),
+            &etag,
+        );
+
+        if is_not_modified(&headers, &etag, &last_modified) {
+            return not_modified(&etag, &last_modified);
+        }
+
+        app_data
+            .download_statistics_service
+            .record_download(headers.get(USER_AGENT).and_then(|value| value.to_str().ok()))
+            .await;
+
+        app_data
+            .trending_tags_service
+            .record_download(&torrent.canonical_info_hash_hex())
+            .await;
+
+        with_validators(
+            torrent_file_response(
+                bytes,
+                &format!("{}.torrent", torrent.info.name),
+                &torrent.canonical_info_hash_hex(),
+            ),
+            &etag,
+            &last_modified,
         )
     }
 }
@@ -131,25 +312,118 @@ async fn redirect_to_download_url_using_canonical_info_hash_if_needed(
 ///
 /// Eg: `/torrents?categories=music,other,movie&search=bunny&sort=size_DESC`
 ///
+/// The response includes a weak `ETag` and a `Last-Modified` date. It
+/// returns `304 Not Modified` when the `If-None-Match` or the
+/// `If-Modified-Since` header shows the client copy is still valid.
+///
 /// # Errors
 ///
 /// It returns an error if the database query fails.
+#[utoipa::path(
+    get,
+    path = "/v1/torrents",
+    tag = "torrent",
+    params(
+        ListingRequest,
+    ),
+    responses(
+        (status = 200, description = "The torrents", body = TorrentsResponseData),
+        (status = 400, description = "The request is not valid", body = ErrorResponseData),
+    ),
+    security((), ("bearer_auth" = []))
+)]
 #[allow(clippy::unused_async)]
 pub async fn get_torrents_handler(
     State(app_data): State<Arc<AppData>>,
+    headers: HeaderMap,
+    RawQuery(raw_query): RawQuery,
     Query(criteria): Query<ListingRequest>,
     ExtractOptionalLoggedInUser(maybe_user_id): ExtractOptionalLoggedInUser,
 ) -> Response {
+    // Guests are served from the listing snapshots when possible. The
+    // snapshot can be older than the database, so its tag is derived from
+    // its content.
+    let raw_query = raw_query.unwrap_or_default();
+
+    let user = user_resource(maybe_user_id);
+
+    let resource = format!("torrents?{raw_query}#{user}");
+
+    if maybe_user_id.is_none() {
+        if let Some(snapshot) = app_data.listing_snapshot_service.get(&criteria) {
+            let etag = weak_etag(&[snapshot.as_bytes()]);
+            let last_modified = app_data.modification_times.last_modified(&resource, &etag);
+
+            if is_not_modified(&headers, &etag, &last_modified) {
+                return not_modified(&etag, &last_modified);
+            }
+
+            return with_validators(torrents_snapshot_response(&snapshot), &etag, &last_modified);
+        }
+    }
+
+    let data_version = match app_data.torrent_service.torrent_listing_version().await {
+        Ok(data_version) => data_version,
+        Err(error) => return error.into_response(),
+    };
+
+    let etag = weak_etag(&[data_version.as_bytes(), raw_query.as_bytes(), user.as_bytes()]);
+    let last_modified = app_data.modification_times.last_modified(&resource, &etag);
+
+    if is_not_modified(&headers, &etag, &last_modified) {
+        return not_modified(&etag, &last_modified);
+    }
+
     match app_data
         .torrent_service
         .generate_torrent_info_listing(&criteria, maybe_user_id)
         .await
     {
-        Ok(torrents_response) => Json(OkResponseData { data: torrents_response }).into_response(),
+        Ok(torrents_response) => with_validators(
+            Json(OkResponseData { data: torrents_response }).into_response(),
+            &etag,
+            &last_modified,
+        ),
+        Err(error) => error.into_response(),
+    }
+}
+
+/// It returns the torrents detected as duplicates by content, grouped in
+/// clusters of torrents connected by their contents. Only administrators can
+/// list them.
+///
+/// # Errors
+///
+/// It returns an error if the user is not an administrator or there is a
+/// database error.
+#[utoipa::path(
+    get,
+    path = "/v1/torrents/duplicates",
+    tag = "torrent",
+    responses(
+        (status = 200, description = "The clusters of duplicate torrents", body = [DuplicateCluster]),
+        (status = 401, description = "The user is not logged in", body = ErrorResponseData),
+        (status = 403, description = "The user does not have permissions to perform the action", body = ErrorResponseData),
+    ),
+    security(("bearer_auth" = []))
+)]
+#[allow(clippy::unused_async)]
+pub async fn get_duplicate_torrents_handler(
+    State(app_data): State<Arc<AppData>>,
+    ExtractOptionalLoggedInUser(maybe_user_id): ExtractOptionalLoggedInUser,
+) -> Response {
+    match app_data.torrent_duplicate_service.get_clusters(maybe_user_id).await {
+        Ok(clusters) => Json(OkResponseData { data: clusters }).into_response(),
         Err(error) => error.into_response(),
     }
 }
 
+/// The part of the conditional request resource names that identifies the
+/// user. Responses can be different for each user.
+fn user_resource(maybe_user_id: Option<i64>) -> String {
+    maybe_user_id.map(|user_id| user_id.to_string()).unwrap_or_default()
+}
+
 /// Get Torrent from the Index
 ///
 /// # Errors
@@ -158,11 +432,26 @@ pub async fn get_torrents_handler(
 ///
 /// - The info-hash is not valid.
 /// - Ot there was a problem getting the torrent info from the database.
+#[utoipa::path(
+    get,
+    path = "/v1/torrent/{info_hash}",
+    tag = "torrent",
+    params(
+        ("info_hash" = String, Path, description = "The torrent info-hash"),
+    ),
+    responses(
+        (status = 200, description = "The torrent details", body = TorrentResponseData),
+        (status = 400, description = "The request is not valid", body = ErrorResponseData),
+        (status = 404, description = "The resource does not exist", body = ErrorResponseData),
+    ),
+    security((), ("bearer_auth" = []))
+)]
 #[allow(clippy::unused_async)]
 pub async fn get_torrent_info_handler(
     State(app_data): State<Arc<AppData>>,
     ExtractOptionalLoggedInUser(maybe_user_id): ExtractOptionalLoggedInUser,
     Path(info_hash): Path<InfoHashParam>,
+    headers: HeaderMap,
 ) -> Response {
     let Ok(info_hash) = InfoHash::from_str(&info_hash.lowercase()) else {
         return errors::Request::InvalidInfoHashParam.into_response();
@@ -173,10 +462,32 @@ pub async fn get_torrent_info_handler(
     {
         redirect_response
     } else {
-        match app_data.torrent_service.get_torrent_info(&info_hash, maybe_user_id).await {
-            Ok(torrent_response) => Json(OkResponseData { data: torrent_response }).into_response(),
-            Err(error) => error.into_response(),
+        let torrent_response = match app_data.torrent_service.get_torrent_info(&info_hash, maybe_user_id).await {
+            Ok(torrent_response) => torrent_response,
+            Err(error) => return error.into_response(),
+        };
+
+        // The details include the tracker stats and the user bookmark, so the
+        // tag is derived from the body.
+        let Ok(body) = serde_json::to_string(&OkResponseData { data: torrent_response }) else {
+            return ServiceError::InternalServerError.into_response();
+        };
+
+        let etag = weak_etag(&[body.as_bytes()]);
+        let last_modified = app_data.modification_times.last_modified(
+            &format!("torrent/{}#{}", info_hash.to_hex_string(), user_resource(maybe_user_id)),
+            &etag,
+        );
+
+        if is_not_modified(&headers, &etag, &last_modified) {
+            return not_modified(&etag, &last_modified);
         }
+
+        with_validators(
+            ([(header::CONTENT_TYPE, "application/json")], body).into_response(),
+            &etag,
+            &last_modified,
+        )
     }
 }
 
@@ -216,6 +527,23 @@ async fn redirect_to_details_url_using_canonical_info_hash_if_needed(
 /// * Get the user id from the request.
 /// * Get the torrent info-hash from the request.
 /// * Update the torrent info.
+#[utoipa::path(
+    put,
+    path = "/v1/torrent/{info_hash}",
+    tag = "torrent",
+    params(
+        ("info_hash" = String, Path, description = "The torrent info-hash"),
+    ),
+    request_body = UpdateTorrentInfoForm,
+    responses(
+        (status = 200, description = "The updated torrent", body = TorrentResponseData),
+        (status = 400, description = "The request is not valid", body = ErrorResponseData),
+        (status = 401, description = "The user is not logged in", body = ErrorResponseData),
+        (status = 403, description = "The user does not have permissions to perform the action", body = ErrorResponseData),
+        (status = 404, description = "The resource does not exist", body = ErrorResponseData),
+    ),
+    security(("bearer_auth" = []))
+)]
 #[allow(clippy::unused_async)]
 pub async fn update_torrent_info_handler(
     State(app_data): State<Arc<AppData>>,
@@ -244,6 +572,141 @@ pub async fn update_torrent_info_handler(
     }
 }
 
+/// Replace the torrent trackers keeping the `announce-list` tiers.
+///
+/// # Errors
+///
+/// This function will return an error if:
+///
+/// * The user does not have permission to edit the trackers.
+/// * The info-hash or any of the tracker URLs is not valid.
+/// * The torrent does not exist.
+#[utoipa::path(
+    put,
+    path = "/v1/torrent/{info_hash}/trackers",
+    tag = "torrent",
+    params(
+        ("info_hash" = String, Path, description = "The torrent info-hash"),
+    ),
+    request_body = UpdateTorrentTrackersForm,
+    responses(
+        (status = 200, description = "The new tracker tiers"),
+        (status = 400, description = "The request is not valid", body = ErrorResponseData),
+        (status = 401, description = "The user is not logged in", body = ErrorResponseData),
+        (status = 403, description = "The user does not have permissions to perform the action", body = ErrorResponseData),
+        (status = 404, description = "The resource does not exist", body = ErrorResponseData),
+    ),
+    security(("bearer_auth" = []))
+)]
+#[allow(clippy::unused_async)]
+pub async fn update_torrent_trackers_handler(
+    State(app_data): State<Arc<AppData>>,
+    ExtractOptionalLoggedInUser(maybe_user_id): ExtractOptionalLoggedInUser,
+    Path(info_hash): Path<InfoHashParam>,
+    extract::Json(update_torrent_trackers_form): extract::Json<UpdateTorrentTrackersForm>,
+) -> Response {
+    let Ok(info_hash) = InfoHash::from_str(&info_hash.lowercase()) else {
+        return errors::Request::InvalidInfoHashParam.into_response();
+    };
+
+    match app_data
+        .torrent_service
+        .update_torrent_trackers(&info_hash, &update_torrent_trackers_form.tiers, maybe_user_id)
+        .await
+    {
+        Ok(tiers) => Json(OkResponseData { data: tiers }).into_response(),
+        Err(error) => error.into_response(),
+    }
+}
+
+/// Add a torrent from a magnet link.
+///
+/// The torrent is added to the index in the background, once its metadata
+/// is fetched from peers. The response contains the magnet upload with the
+/// `resolving` status.
+///
+/// # Errors
+///
+/// It returns an error if:
+///
+/// * Magnet uploads are not enabled.
+/// * The user does not have permission to upload torrents.
+/// * The magnet link or the torrent metadata are not valid.
+/// * The torrent is already in the index or being resolved.
+#[utoipa::path(
+    post,
+    path = "/v1/torrent/magnet",
+    tag = "torrent",
+    request_body = AddMagnetForm,
+    responses(
+        (status = 200, description = "The pending magnet upload"),
+        (status = 400, description = "The request is not valid", body = ErrorResponseData),
+        (status = 401, description = "The user is not logged in", body = ErrorResponseData),
+        (status = 403, description = "The user does not have permissions to perform the action", body = ErrorResponseData),
+        (status = 409, description = "The resource already exists", body = ErrorResponseData),
+    ),
+    security(("bearer_auth" = []))
+)]
+#[allow(clippy::unused_async)]
+pub async fn add_magnet_handler(
+    State(app_data): State<Arc<AppData>>,
+    ExtractOptionalLoggedInUser(maybe_user_id): ExtractOptionalLoggedInUser,
+    extract::Json(add_magnet_form): extract::Json<AddMagnetForm>,
+) -> Response {
+    let add_magnet_req = AddMagnetRequest {
+        magnet_uri: add_magnet_form.magnet_uri,
+        title: add_magnet_form.title.unwrap_or_default(),
+        description: add_magnet_form.description.unwrap_or_default(),
+        category_name: add_magnet_form.category,
+        tags: add_magnet_form.tags.unwrap_or_default(),
+    };
+
+    match app_data
+        .torrent_service
+        .add_magnet_upload(add_magnet_req, maybe_user_id)
+        .await
+    {
+        Ok(magnet_upload) => Json(OkResponseData { data: magnet_upload }).into_response(),
+        Err(error) => error.into_response(),
+    }
+}
+
+/// Get the status of a torrent uploaded as a magnet link.
+///
+/// # Errors
+///
+/// It returns an error if the magnet upload does not exist or the user is
+/// not the uploader or a moderator.
+#[utoipa::path(
+    get,
+    path = "/v1/torrent/magnet/{magnet_upload_id}",
+    tag = "torrent",
+    params(
+        ("magnet_upload_id" = i64, Path, description = "The magnet upload ID"),
+    ),
+    responses(
+        (status = 200, description = "The magnet upload"),
+        (status = 401, description = "The user is not logged in", body = ErrorResponseData),
+        (status = 404, description = "The resource does not exist", body = ErrorResponseData),
+    ),
+    security(("bearer_auth" = []))
+)]
+#[allow(clippy::unused_async)]
+pub async fn get_magnet_upload_handler(
+    State(app_data): State<Arc<AppData>>,
+    ExtractOptionalLoggedInUser(maybe_user_id): ExtractOptionalLoggedInUser,
+    Path(magnet_upload_id): Path<MagnetUploadId>,
+) -> Response {
+    match app_data
+        .torrent_service
+        .get_magnet_upload(magnet_upload_id, maybe_user_id)
+        .await
+    {
+        Ok(magnet_upload) => Json(OkResponseData { data: magnet_upload }).into_response(),
+        Err(error) => error.into_response(),
+    }
+}
+
 /// Delete a torrent.
 ///
 /// # Errors
@@ -253,6 +716,22 @@ pub async fn update_torrent_info_handler(
 /// * Get the user ID from the request.
 /// * Get the torrent info-hash from the request.
 /// * Delete the torrent.
+#[utoipa::path(
+    delete,
+    path = "/v1/torrent/{info_hash}",
+    tag = "torrent",
+    params(
+        ("info_hash" = String, Path, description = "The torrent info-hash"),
+    ),
+    responses(
+        (status = 200, description = "The deleted torrent", body = DeletedTorrentResponseData),
+        (status = 400, description = "The request is not valid", body = ErrorResponseData),
+        (status = 401, description = "The user is not logged in", body = ErrorResponseData),
+        (status = 403, description = "The user does not have permissions to perform the action", body = ErrorResponseData),
+        (status = 404, description = "The resource does not exist", body = ErrorResponseData),
+    ),
+    security(("bearer_auth" = []))
+)]
 #[allow(clippy::unused_async)]
 pub async fn delete_torrent_handler(
     State(app_data): State<Arc<AppData>>,
@@ -272,95 +751,1215 @@ pub async fn delete_torrent_handler(
     }
 }
 
-#[derive(Debug, Deserialize)]
-pub struct UuidParam(pub String);
+/// Returns the magnet link of the torrent, with the trackers and web seeds.
+///
+/// # Errors
+///
+/// This function will return an error if:
+///
+/// - The info-hash is not valid.
+/// - The torrent does not exist.
+#[utoipa::path(
+    get,
+    path = "/v1/torrent/{info_hash}/magnet",
+    tag = "torrent",
+    params(
+        ("info_hash" = String, Path, description = "The torrent info-hash"),
+    ),
+    responses(
+        (status = 200, description = "The magnet link", body = MagnetLinkResponse),
+        (status = 400, description = "The request is not valid", body = ErrorResponseData),
+        (status = 404, description = "The resource does not exist", body = ErrorResponseData),
+    ),
+    security((), ("bearer_auth" = []))
+)]
+#[allow(clippy::unused_async)]
+pub async fn get_magnet_link_handler(
+    State(app_data): State<Arc<AppData>>,
+    ExtractOptionalLoggedInUser(maybe_user_id): ExtractOptionalLoggedInUser,
+    Path(info_hash): Path<InfoHashParam>,
+) -> Response {
+    let Ok(info_hash) = InfoHash::from_str(&info_hash.lowercase()) else {
+        return errors::Request::InvalidInfoHashParam.into_response();
+    };
 
-impl UuidParam {
-    fn value(&self) -> String {
-        self.0.to_lowercase()
+    match app_data.torrent_service.get_magnet_link(&info_hash, maybe_user_id).await {
+        Ok(magnet_link) => Json(OkResponseData { data: magnet_link }).into_response(),
+        Err(error) => error.into_response(),
     }
 }
 
-/// Returns a random torrent file as a byte stream `application/x-bittorrent`.
-///
-/// This is useful for testing purposes.
+/// Returns the QR code of the torrent magnet link as a PNG image.
 ///
 /// # Errors
 ///
-/// Returns an error if the torrent info-hash is invalid.
+/// This function will return an error if:
+///
+/// - The info-hash is not valid.
+/// - The torrent does not exist.
+#[utoipa::path(
+    get,
+    path = "/v1/torrent/{info_hash}/qr",
+    tag = "torrent",
+    params(
+        ("info_hash" = String, Path, description = "The torrent info-hash"),
+    ),
+    responses(
+        (status = 200, description = "The QR code of the magnet link", content_type = "image/png"),
+        (status = 400, description = "The request is not valid", body = ErrorResponseData),
+        (status = 404, description = "The resource does not exist", body = ErrorResponseData),
+    ),
+    security((), ("bearer_auth" = []))
+)]
 #[allow(clippy::unused_async)]
-pub async fn create_random_torrent_handler(State(_app_data): State<Arc<AppData>>, Path(uuid): Path<UuidParam>) -> Response {
-    let Ok(uuid) = Uuid::parse_str(&uuid.value()) else {
+pub async fn get_magnet_link_qr_code_handler(
+    State(app_data): State<Arc<AppData>>,
+    ExtractOptionalLoggedInUser(maybe_user_id): ExtractOptionalLoggedInUser,
+    Path(info_hash): Path<InfoHashParam>,
+) -> Response {
+    let Ok(info_hash) = InfoHash::from_str(&info_hash.lowercase()) else {
         return errors::Request::InvalidInfoHashParam.into_response();
     };
 
-    let torrent = generate_random_torrent(uuid);
+    match app_data
+        .torrent_service
+        .get_magnet_link_qr_code(&info_hash, maybe_user_id)
+        .await
+    {
+        Ok(qr_code) => png_image(Bytes::from(qr_code)),
+        Err(error) => error.into_response(),
+    }
+}
 
-    let Ok(bytes) = parse_torrent::encode_torrent(&torrent) else {
-        return ServiceError::InternalServerError.into_response();
+/// Returns the current peers of the torrent in the tracker.
+///
+/// # Errors
+///
+/// This function will return an error if:
+///
+/// - The user does not have permission to get the torrent peers.
+/// - The info-hash is not valid.
+/// - The torrent does not exist.
+/// - The tracker API request fails.
+#[utoipa::path(
+    get,
+    path = "/v1/torrent/{info_hash}/peers",
+    tag = "torrent",
+    params(
+        ("info_hash" = String, Path, description = "The torrent info-hash"),
+    ),
+    responses(
+        (status = 200, description = "The torrent peers", body = TorrentPeersResponse),
+        (status = 400, description = "The request is not valid", body = ErrorResponseData),
+        (status = 401, description = "The user is not logged in", body = ErrorResponseData),
+        (status = 403, description = "The user does not have permissions to perform the action", body = ErrorResponseData),
+        (status = 404, description = "The resource does not exist", body = ErrorResponseData),
+        (status = 503, description = "The tracker is not available", body = ErrorResponseData),
+    ),
+    security(("bearer_auth" = []))
+)]
+#[allow(clippy::unused_async)]
+pub async fn get_torrent_peers_handler(
+    State(app_data): State<Arc<AppData>>,
+    ExtractOptionalLoggedInUser(maybe_user_id): ExtractOptionalLoggedInUser,
+    Path(info_hash): Path<InfoHashParam>,
+) -> Response {
+    let Ok(info_hash) = InfoHash::from_str(&info_hash.lowercase()) else {
+        return errors::Request::InvalidInfoHashParam.into_response();
     };
 
-    torrent_file_response(
-        bytes,
-        &format!("{}.torrent", torrent.info.name),
-        &torrent.canonical_info_hash_hex(),
-    )
+    match app_data.torrent_service.get_torrent_peers(&info_hash, maybe_user_id).await {
+        Ok(torrent_peers) => Json(OkResponseData { data: torrent_peers }).into_response(),
+        Err(error) => error.into_response(),
+    }
 }
 
-/// Extracts the [`TorrentRequest`] from the multipart form payload.
+/// Attaches a file in the torrent as a preview.
+///
+/// The request contains the index of the file in the torrent and the
+/// contents of the pieces the file is in. Refer to the
+/// [`preview`](crate::services::torrent::preview) service.
 ///
 /// # Errors
 ///
-/// It will return an error if:
+/// This function will return an error if:
 ///
-/// - The text fields do not contain a valid UTF8 string.
-/// - The torrent file data is not valid because:
-///    - The content type is not `application/x-bittorrent` or `application/octet-stream`.
-///    - The multipart content is invalid.
-///    - The torrent file pieces key has a length that is not a multiple of 20.
-///    - The binary data cannot be decoded as a torrent file.
-async fn build_add_torrent_request_from_payload(mut payload: Multipart) -> Result<AddTorrentRequest, errors::Request> {
-    let torrent_buffer = vec![0u8];
-    let mut torrent_cursor = Cursor::new(torrent_buffer);
+/// - Previews are not enabled.
+/// - The user does not have permission to edit the torrent.
+/// - The file is not a valid preview or the pieces do not match the torrent.
+#[utoipa::path(
+    post,
+    path = "/v1/torrent/{info_hash}/previews",
+    tag = "torrent",
+    params(
+        ("info_hash" = String, Path, description = "The torrent info-hash"),
+    ),
+    request_body = (content = UploadPreviewMultipartForm, content_type = "multipart/form-data"),
+    responses(
+        (status = 200, description = "The new preview", body = TorrentPreview),
+        (status = 400, description = "The request is not valid", body = ErrorResponseData),
+        (status = 401, description = "The user is not logged in", body = ErrorResponseData),
+        (status = 403, description = "The user does not have permissions to perform the action", body = ErrorResponseData),
+        (status = 404, description = "The resource does not exist", body = ErrorResponseData),
+        (status = 409, description = "The resource already exists", body = ErrorResponseData),
+    ),
+    security(("bearer_auth" = []))
+)]
+#[allow(clippy::unused_async)]
+pub async fn add_torrent_preview_handler(
+    State(app_data): State<Arc<AppData>>,
+    ExtractOptionalLoggedInUser(maybe_user_id): ExtractOptionalLoggedInUser,
+    Path(info_hash): Path<InfoHashParam>,
+    multipart: Multipart,
+) -> Response {
+    let Ok(info_hash) = InfoHash::from_str(&info_hash.lowercase()) else {
+        return errors::Request::InvalidInfoHashParam.into_response();
+    };
 
-    let mut title = String::new();
-    let mut description = String::new();
-    let mut category = String::new();
-    let mut tags: Vec<TagId> = vec![];
+    let add_preview_request = match build_add_preview_request_from_payload(multipart).await {
+        Ok(add_preview_request) => add_preview_request,
+        Err(error) => return error.into_response(),
+    };
 
-    while let Some(mut field) = payload.next_field().await.unwrap() {
-        let name = field.name().unwrap();
+    match app_data
+        .torrent_preview_service
+        .add_preview(&info_hash, &add_preview_request, maybe_user_id)
+        .await
+    {
+        Ok(preview) => Json(OkResponseData { data: preview }).into_response(),
+        Err(error) => error.into_response(),
+    }
+}
 
-        match name {
-            "title" => {
-                let data = field.bytes().await.unwrap();
-                if data.is_empty() {
-                    continue;
-                };
-                title = String::from_utf8(data.to_vec()).map_err(|_| errors::Request::TitleIsNotValidUtf8)?;
-            }
-            "description" => {
-                let data = field.bytes().await.unwrap();
-                if data.is_empty() {
-                    continue;
-                };
-                description = String::from_utf8(data.to_vec()).map_err(|_| errors::Request::DescriptionIsNotValidUtf8)?;
-            }
-            "category" => {
-                let data = field.bytes().await.unwrap();
-                if data.is_empty() {
-                    continue;
-                };
-                category = String::from_utf8(data.to_vec()).map_err(|_| errors::Request::CategoryIsNotValidUtf8)?;
-            }
-            "tags" => {
-                let data = field.bytes().await.unwrap();
-                if data.is_empty() {
-                    continue;
+/// Returns the previews of a torrent, without their contents.
+///
+/// # Errors
+///
+/// This function will return an error if:
+///
+/// - The info-hash is not valid.
+/// - The torrent does not exist.
+#[utoipa::path(
+    get,
+    path = "/v1/torrent/{info_hash}/previews",
+    tag = "torrent",
+    params(
+        ("info_hash" = String, Path, description = "The torrent info-hash"),
+    ),
+    responses(
+        (status = 200, description = "The torrent previews", body = [TorrentPreview]),
+        (status = 400, description = "The request is not valid", body = ErrorResponseData),
+        (status = 404, description = "The resource does not exist", body = ErrorResponseData),
+    ),
+    security((), ("bearer_auth" = []))
+)]
+#[allow(clippy::unused_async)]
+pub async fn get_torrent_previews_handler(
+    State(app_data): State<Arc<AppData>>,
+    ExtractOptionalLoggedInUser(maybe_user_id): ExtractOptionalLoggedInUser,
+    Path(info_hash): Path<InfoHashParam>,
+) -> Response {
+    let Ok(info_hash) = InfoHash::from_str(&info_hash.lowercase()) else {
+        return errors::Request::InvalidInfoHashParam.into_response();
+    };
+
+    match app_data.torrent_preview_service.get_previews(&info_hash, maybe_user_id).await {
+        Ok(previews) => Json(OkResponseData { data: previews }).into_response(),
+        Err(error) => error.into_response(),
+    }
+}
+
+/// Returns the contents of a torrent preview.
+///
+/// # Errors
+///
+/// This function will return an error if:
+///
+/// - The info-hash is not valid.
+/// - The torrent or the preview do not exist.
+#[utoipa::path(
+    get,
+    path = "/v1/torrent/{info_hash}/previews/{preview_id}",
+    tag = "torrent",
+    params(
+        ("info_hash" = String, Path, description = "The torrent info-hash"),
+        ("preview_id" = i64, Path, description = "The preview ID"),
+    ),
+    responses(
+        (status = 200, description = "The preview contents", content_type = "application/octet-stream"),
+        (status = 400, description = "The request is not valid", body = ErrorResponseData),
+        (status = 404, description = "The resource does not exist", body = ErrorResponseData),
+    ),
+    security((), ("bearer_auth" = []))
+)]
+#[allow(clippy::unused_async)]
+pub async fn get_torrent_preview_content_handler(
+    State(app_data): State<Arc<AppData>>,
+    ExtractOptionalLoggedInUser(maybe_user_id): ExtractOptionalLoggedInUser,
+    Path((info_hash, preview_id)): Path<(String, PreviewId)>,
+) -> Response {
+    let Ok(info_hash) = InfoHash::from_str(&info_hash.to_lowercase()) else {
+        return errors::Request::InvalidInfoHashParam.into_response();
+    };
+
+    match app_data
+        .torrent_preview_service
+        .get_preview_content(&info_hash, preview_id, maybe_user_id)
+        .await
+    {
+        Ok((content_type, content)) => torrent_preview_response(content, &content_type),
+        Err(error) => error.into_response(),
+    }
+}
+
+/// Uploads an image to be embedded in the torrent description.
+///
+/// The response contains the image URL and the markdown to embed it. Refer
+/// to the [`description_image`](crate::services::torrent::description_image)
+/// service.
+///
+/// # Errors
+///
+/// This function will return an error if:
+///
+/// - Description image uploads are not enabled.
+/// - The user does not have permission to edit the torrent.
+/// - The image is empty, too big or not a supported image type.
+#[utoipa::path(
+    post,
+    path = "/v1/torrent/{info_hash}/images",
+    tag = "torrent",
+    params(
+        ("info_hash" = String, Path, description = "The torrent info-hash"),
+    ),
+    request_body = (content = UploadDescriptionImageMultipartForm, content_type = "multipart/form-data"),
+    responses(
+        (status = 200, description = "The uploaded image", body = UploadedDescriptionImage),
+        (status = 400, description = "The request is not valid", body = ErrorResponseData),
+        (status = 401, description = "The user is not logged in", body = ErrorResponseData),
+        (status = 403, description = "The user does not have permissions to perform the action", body = ErrorResponseData),
+        (status = 404, description = "The resource does not exist", body = ErrorResponseData),
+        (status = 409, description = "The resource already exists", body = ErrorResponseData),
+    ),
+    security(("bearer_auth" = []))
+)]
+#[allow(clippy::unused_async)]
+pub async fn upload_description_image_handler(
+    State(app_data): State<Arc<AppData>>,
+    Host(host_from_header): Host,
+    ExtractOptionalLoggedInUser(maybe_user_id): ExtractOptionalLoggedInUser,
+    Path(info_hash): Path<InfoHashParam>,
+    multipart: Multipart,
+) -> Response {
+    let Ok(info_hash) = InfoHash::from_str(&info_hash.lowercase()) else {
+        return errors::Request::InvalidInfoHashParam.into_response();
+    };
+
+    let upload_image_request = match build_upload_image_request_from_payload(multipart).await {
+        Ok(upload_image_request) => upload_image_request,
+        Err(error) => return error.into_response(),
+    };
+
+    let api_base_url = app_data
+        .cfg
+        .get_api_base_url()
+        .await
+        .unwrap_or(api_base_url(&host_from_header));
+
+    match app_data
+        .description_image_service
+        .upload_image(&info_hash, upload_image_request, &api_base_url, maybe_user_id)
+        .await
+    {
+        Ok(image) => Json(OkResponseData { data: image }).into_response(),
+        Err(error) => error.into_response(),
+    }
+}
+
+/// Returns the images uploaded for the torrent description.
+///
+/// # Errors
+///
+/// This function will return an error if:
+///
+/// - The info-hash is not valid.
+/// - The torrent does not exist.
+#[utoipa::path(
+    get,
+    path = "/v1/torrent/{info_hash}/images",
+    tag = "torrent",
+    params(
+        ("info_hash" = String, Path, description = "The torrent info-hash"),
+    ),
+    responses(
+        (status = 200, description = "The uploaded images", body = [UploadedDescriptionImage]),
+        (status = 400, description = "The request is not valid", body = ErrorResponseData),
+        (status = 404, description = "The resource does not exist", body = ErrorResponseData),
+    ),
+    security((), ("bearer_auth" = []))
+)]
+#[allow(clippy::unused_async)]
+pub async fn get_description_images_handler(
+    State(app_data): State<Arc<AppData>>,
+    Host(host_from_header): Host,
+    ExtractOptionalLoggedInUser(maybe_user_id): ExtractOptionalLoggedInUser,
+    Path(info_hash): Path<InfoHashParam>,
+) -> Response {
+    let Ok(info_hash) = InfoHash::from_str(&info_hash.lowercase()) else {
+        return errors::Request::InvalidInfoHashParam.into_response();
+    };
+
+    let api_base_url = app_data
+        .cfg
+        .get_api_base_url()
+        .await
+        .unwrap_or(api_base_url(&host_from_header));
+
+    match app_data
+        .description_image_service
+        .get_images(&info_hash, &api_base_url, maybe_user_id)
+        .await
+    {
+        Ok(images) => Json(OkResponseData { data: images }).into_response(),
+        Err(error) => error.into_response(),
+    }
+}
+
+/// Returns an image uploaded for the torrent description.
+///
+/// # Errors
+///
+/// This function will return an error if:
+///
+/// - The info-hash is not valid.
+/// - The torrent or the image do not exist.
+#[utoipa::path(
+    get,
+    path = "/v1/torrent/{info_hash}/images/{image_id}",
+    tag = "torrent",
+    params(
+        ("info_hash" = String, Path, description = "The torrent info-hash"),
+        ("image_id" = i64, Path, description = "The image ID"),
+    ),
+    responses(
+        (status = 200, description = "The image", content_type = "application/octet-stream"),
+        (status = 400, description = "The request is not valid", body = ErrorResponseData),
+        (status = 404, description = "The resource does not exist", body = ErrorResponseData),
+    ),
+    security((), ("bearer_auth" = []))
+)]
+#[allow(clippy::unused_async)]
+pub async fn get_description_image_handler(
+    State(app_data): State<Arc<AppData>>,
+    ExtractOptionalLoggedInUser(maybe_user_id): ExtractOptionalLoggedInUser,
+    Path((info_hash, image_id)): Path<(String, ImageId)>,
+) -> Response {
+    let Ok(info_hash) = InfoHash::from_str(&info_hash.to_lowercase()) else {
+        return errors::Request::InvalidInfoHashParam.into_response();
+    };
+
+    match app_data
+        .description_image_service
+        .get_image_content(&info_hash, image_id, maybe_user_id)
+        .await
+    {
+        Ok((content_type, bytes)) => description_image_response(bytes, &content_type),
+        Err(error) => error.into_response(),
+    }
+}
+
+/// Attaches an auxiliary file to a torrent: the NFO or a screenshot.
+///
+/// Refer to the [`attachment`](crate::services::torrent::attachment) service.
+///
+/// # Errors
+///
+/// This function will return an error if:
+///
+/// - Attachments are not enabled.
+/// - The user does not have permission to edit the torrent.
+/// - The file is empty, too big or not valid for its kind.
+#[utoipa::path(
+    post,
+    path = "/v1/torrent/{info_hash}/attachments",
+    tag = "torrent",
+    params(
+        ("info_hash" = String, Path, description = "The torrent info-hash"),
+    ),
+    request_body = (content = UploadAttachmentMultipartForm, content_type = "multipart/form-data"),
+    responses(
+        (status = 200, description = "The new attachment", body = TorrentAttachment),
+        (status = 400, description = "The request is not valid", body = ErrorResponseData),
+        (status = 401, description = "The user is not logged in", body = ErrorResponseData),
+        (status = 403, description = "The user does not have permissions to perform the action", body = ErrorResponseData),
+        (status = 404, description = "The resource does not exist", body = ErrorResponseData),
+        (status = 409, description = "The torrent has already the max number of screenshots", body = ErrorResponseData),
+    ),
+    security(("bearer_auth" = []))
+)]
+#[allow(clippy::unused_async)]
+pub async fn add_torrent_attachment_handler(
+    State(app_data): State<Arc<AppData>>,
+    ExtractOptionalLoggedInUser(maybe_user_id): ExtractOptionalLoggedInUser,
+    Path(info_hash): Path<InfoHashParam>,
+    multipart: Multipart,
+) -> Response {
+    let Ok(info_hash) = InfoHash::from_str(&info_hash.lowercase()) else {
+        return errors::Request::InvalidInfoHashParam.into_response();
+    };
+
+    let add_attachment_request = match build_add_attachment_request_from_payload(multipart).await {
+        Ok(add_attachment_request) => add_attachment_request,
+        Err(error) => return error.into_response(),
+    };
+
+    match app_data
+        .torrent_attachment_service
+        .add_attachment(&info_hash, &add_attachment_request, maybe_user_id)
+        .await
+    {
+        Ok(attachment) => Json(OkResponseData { data: attachment }).into_response(),
+        Err(error) => error.into_response(),
+    }
+}
+
+/// Returns the files attached to a torrent, without their contents.
+///
+/// # Errors
+///
+/// This function will return an error if:
+///
+/// - The info-hash is not valid.
+/// - The torrent does not exist.
+#[utoipa::path(
+    get,
+    path = "/v1/torrent/{info_hash}/attachments",
+    tag = "torrent",
+    params(
+        ("info_hash" = String, Path, description = "The torrent info-hash"),
+    ),
+    responses(
+        (status = 200, description = "The torrent attachments", body = [TorrentAttachment]),
+        (status = 400, description = "The request is not valid", body = ErrorResponseData),
+        (status = 404, description = "The resource does not exist", body = ErrorResponseData),
+    ),
+    security((), ("bearer_auth" = []))
+)]
+#[allow(clippy::unused_async)]
+pub async fn get_torrent_attachments_handler(
+    State(app_data): State<Arc<AppData>>,
+    ExtractOptionalLoggedInUser(maybe_user_id): ExtractOptionalLoggedInUser,
+    Path(info_hash): Path<InfoHashParam>,
+) -> Response {
+    let Ok(info_hash) = InfoHash::from_str(&info_hash.lowercase()) else {
+        return errors::Request::InvalidInfoHashParam.into_response();
+    };
+
+    match app_data
+        .torrent_attachment_service
+        .get_attachments(&info_hash, maybe_user_id)
+        .await
+    {
+        Ok(attachments) => Json(OkResponseData { data: attachments }).into_response(),
+        Err(error) => error.into_response(),
+    }
+}
+
+/// Returns the contents of a file attached to a torrent.
+///
+/// # Errors
+///
+/// This function will return an error if:
+///
+/// - The info-hash is not valid.
+/// - The torrent or the attachment do not exist.
+#[utoipa::path(
+    get,
+    path = "/v1/torrent/{info_hash}/attachments/{attachment_id}",
+    tag = "torrent",
+    params(
+        ("info_hash" = String, Path, description = "The torrent info-hash"),
+        ("attachment_id" = i64, Path, description = "The attachment ID"),
+    ),
+    responses(
+        (status = 200, description = "The attachment contents", content_type = "application/octet-stream"),
+        (status = 400, description = "The request is not valid", body = ErrorResponseData),
+        (status = 404, description = "The resource does not exist", body = ErrorResponseData),
+    ),
+    security((), ("bearer_auth" = []))
+)]
+#[allow(clippy::unused_async)]
+pub async fn get_torrent_attachment_content_handler(
+    State(app_data): State<Arc<AppData>>,
+    ExtractOptionalLoggedInUser(maybe_user_id): ExtractOptionalLoggedInUser,
+    Path((info_hash, attachment_id)): Path<(String, AttachmentId)>,
+) -> Response {
+    let Ok(info_hash) = InfoHash::from_str(&info_hash.to_lowercase()) else {
+        return errors::Request::InvalidInfoHashParam.into_response();
+    };
+
+    match app_data
+        .torrent_attachment_service
+        .get_attachment_content(&info_hash, attachment_id, maybe_user_id)
+        .await
+    {
+        Ok((content_type, content)) => torrent_preview_response(content, &content_type),
+        Err(error) => error.into_response(),
+    }
+}
+
+/// Removes a file attached to a torrent.
+///
+/// # Errors
+///
+/// This function will return an error if:
+///
+/// - The user does not have permission to edit the torrent.
+/// - The torrent or the attachment do not exist.
+#[utoipa::path(
+    delete,
+    path = "/v1/torrent/{info_hash}/attachments/{attachment_id}",
+    tag = "torrent",
+    params(
+        ("info_hash" = String, Path, description = "The torrent info-hash"),
+        ("attachment_id" = i64, Path, description = "The attachment ID"),
+    ),
+    responses(
+        (status = 200, description = "The attachment was removed", body = MessageResponseData),
+        (status = 400, description = "The request is not valid", body = ErrorResponseData),
+        (status = 401, description = "The user is not logged in", body = ErrorResponseData),
+        (status = 403, description = "The user does not have permissions to perform the action", body = ErrorResponseData),
+        (status = 404, description = "The resource does not exist", body = ErrorResponseData),
+    ),
+    security(("bearer_auth" = []))
+)]
+#[allow(clippy::unused_async)]
+pub async fn delete_torrent_attachment_handler(
+    State(app_data): State<Arc<AppData>>,
+    ExtractOptionalLoggedInUser(maybe_user_id): ExtractOptionalLoggedInUser,
+    Path((info_hash, attachment_id)): Path<(String, AttachmentId)>,
+) -> Response {
+    let Ok(info_hash) = InfoHash::from_str(&info_hash.to_lowercase()) else {
+        return errors::Request::InvalidInfoHashParam.into_response();
+    };
+
+    match app_data
+        .torrent_attachment_service
+        .delete_attachment(&info_hash, attachment_id, maybe_user_id)
+        .await
+    {
+        Ok(()) => Json(OkResponseData {
+            data: format!("Attachment {attachment_id} removed"),
+        })
+        .into_response(),
+        Err(error) => error.into_response(),
+    }
+}
+
+/// Adds an HTTP web seed (BEP 19) to a torrent.
+///
+/// Refer to the [`web_seed`](crate::services::torrent::web_seed) service.
+///
+/// # Errors
+///
+/// This function will return an error if:
+///
+/// - The user does not have permission to edit the torrent.
+/// - The URL is not a valid HTTP or HTTPS URL.
+/// - The torrent has already the web seed or the max number of web seeds.
+#[utoipa::path(
+    post,
+    path = "/v1/torrent/{info_hash}/webseeds",
+    tag = "torrent",
+    params(
+        ("info_hash" = String, Path, description = "The torrent info-hash"),
+    ),
+    request_body = AddWebSeedForm,
+    responses(
+        (status = 200, description = "The new web seed", body = TorrentWebSeed),
+        (status = 400, description = "The request is not valid", body = ErrorResponseData),
+        (status = 401, description = "The user is not logged in", body = ErrorResponseData),
+        (status = 403, description = "The user does not have permissions to perform the action", body = ErrorResponseData),
+        (status = 404, description = "The resource does not exist", body = ErrorResponseData),
+        (status = 409, description = "The torrent has already the web seed or the max number of web seeds", body = ErrorResponseData),
+    ),
+    security(("bearer_auth" = []))
+)]
+#[allow(clippy::unused_async)]
+pub async fn add_torrent_web_seed_handler(
+    State(app_data): State<Arc<AppData>>,
+    ExtractOptionalLoggedInUser(maybe_user_id): ExtractOptionalLoggedInUser,
+    Path(info_hash): Path<InfoHashParam>,
+    extract::Json(add_web_seed_form): extract::Json<AddWebSeedForm>,
+) -> Response {
+    let Ok(info_hash) = InfoHash::from_str(&info_hash.lowercase()) else {
+        return errors::Request::InvalidInfoHashParam.into_response();
+    };
+
+    match app_data
+        .torrent_web_seed_service
+        .add_web_seed(&info_hash, &add_web_seed_form.url, maybe_user_id)
+        .await
+    {
+        Ok(web_seed) => Json(OkResponseData { data: web_seed }).into_response(),
+        Err(error) => error.into_response(),
+    }
+}
+
+/// Returns the HTTP web seeds of a torrent.
+///
+/// # Errors
+///
+/// This function will return an error if:
+///
+/// - The info-hash is not valid.
+/// - The torrent does not exist.
+#[utoipa::path(
+    get,
+    path = "/v1/torrent/{info_hash}/webseeds",
+    tag = "torrent",
+    params(
+        ("info_hash" = String, Path, description = "The torrent info-hash"),
+    ),
+    responses(
+        (status = 200, description = "The torrent web seeds", body = [TorrentWebSeed]),
+        (status = 400, description = "The request is not valid", body = ErrorResponseData),
+        (status = 404, description = "The resource does not exist", body = ErrorResponseData),
+    ),
+    security((), ("bearer_auth" = []))
+)]
+#[allow(clippy::unused_async)]
+pub async fn get_torrent_web_seeds_handler(
+    State(app_data): State<Arc<AppData>>,
+    ExtractOptionalLoggedInUser(maybe_user_id): ExtractOptionalLoggedInUser,
+    Path(info_hash): Path<InfoHashParam>,
+) -> Response {
+    let Ok(info_hash) = InfoHash::from_str(&info_hash.lowercase()) else {
+        return errors::Request::InvalidInfoHashParam.into_response();
+    };
+
+    match app_data
+        .torrent_web_seed_service
+        .get_web_seeds(&info_hash, maybe_user_id)
+        .await
+    {
+        Ok(web_seeds) => Json(OkResponseData { data: web_seeds }).into_response(),
+        Err(error) => error.into_response(),
+    }
+}
+
+/// Removes an HTTP web seed from a torrent.
+///
+/// # Errors
+///
+/// This function will return an error if:
+///
+/// - The user does not have permission to edit the torrent.
+/// - The torrent or the web seed do not exist.
+#[utoipa::path(
+    delete,
+    path = "/v1/torrent/{info_hash}/webseeds/{web_seed_id}",
+    tag = "torrent",
+    params(
+        ("info_hash" = String, Path, description = "The torrent info-hash"),
+        ("web_seed_id" = i64, Path, description = "The web seed ID"),
+    ),
+    responses(
+        (status = 200, description = "The web seed was removed", body = MessageResponseData),
+        (status = 400, description = "The request is not valid", body = ErrorResponseData),
+        (status = 401, description = "The user is not logged in", body = ErrorResponseData),
+        (status = 403, description = "The user does not have permissions to perform the action", body = ErrorResponseData),
+        (status = 404, description = "The resource does not exist", body = ErrorResponseData),
+    ),
+    security(("bearer_auth" = []))
+)]
+#[allow(clippy::unused_async)]
+pub async fn delete_torrent_web_seed_handler(
+    State(app_data): State<Arc<AppData>>,
+    ExtractOptionalLoggedInUser(maybe_user_id): ExtractOptionalLoggedInUser,
+    Path((info_hash, web_seed_id)): Path<(String, WebSeedId)>,
+) -> Response {
+    let Ok(info_hash) = InfoHash::from_str(&info_hash.to_lowercase()) else {
+        return errors::Request::InvalidInfoHashParam.into_response();
+    };
+
+    match app_data
+        .torrent_web_seed_service
+        .delete_web_seed(&info_hash, web_seed_id, maybe_user_id)
+        .await
+    {
+        Ok(()) => Json(OkResponseData {
+            data: format!("Web seed {web_seed_id} removed"),
+        })
+        .into_response(),
+        Err(error) => error.into_response(),
+    }
+}
+
+/// Fetches the metadata of a torrent from the configured providers, with an
+/// external ID or the title, and replaces the stored one.
+///
+/// Refer to the [`enrichment`](crate::services::enrichment) service.
+///
+/// # Errors
+///
+/// This function will return an error if:
+///
+/// - Enrichment is not enabled.
+/// - The user does not have permission to edit the torrent.
+/// - The external ID is not valid or there is no provider for it.
+/// - The providers don't find the title or they fail.
+#[utoipa::path(
+    post,
+    path = "/v1/torrent/{info_hash}/metadata",
+    tag = "torrent",
+    params(
+        ("info_hash" = String, Path, description = "The torrent info-hash"),
+    ),
+    request_body = EnrichMetadataForm,
+    responses(
+        (status = 200, description = "The new torrent metadata", body = TorrentMetadata),
+        (status = 400, description = "The request is not valid", body = ErrorResponseData),
+        (status = 401, description = "The user is not logged in", body = ErrorResponseData),
+        (status = 403, description = "The user does not have permissions to perform the action or enrichment is disabled", body = ErrorResponseData),
+        (status = 404, description = "The torrent does not exist or the providers don't find the title", body = ErrorResponseData),
+        (status = 503, description = "The provider failed", body = ErrorResponseData),
+    ),
+    security(("bearer_auth" = []))
+)]
+#[allow(clippy::unused_async)]
+pub async fn enrich_torrent_metadata_handler(
+    State(app_data): State<Arc<AppData>>,
+    ExtractOptionalLoggedInUser(maybe_user_id): ExtractOptionalLoggedInUser,
+    Path(info_hash): Path<InfoHashParam>,
+    extract::Json(enrich_metadata_form): extract::Json<EnrichMetadataForm>,
+) -> Response {
+    let Ok(info_hash) = InfoHash::from_str(&info_hash.lowercase()) else {
+        return errors::Request::InvalidInfoHashParam.into_response();
+    };
+
+    let request = EnrichMetadataRequest {
+        external_id: enrich_metadata_form.external_id,
+        title: enrich_metadata_form.title,
+        provider: enrich_metadata_form.provider,
+    };
+
+    match app_data
+        .torrent_metadata_service
+        .enrich(&info_hash, &request, maybe_user_id)
+        .await
+    {
+        Ok(metadata) => Json(OkResponseData { data: metadata }).into_response(),
+        Err(error) => error.into_response(),
+    }
+}
+
+/// Returns the metadata of a torrent fetched from an external provider.
+///
+/// # Errors
+///
+/// This function will return an error if:
+///
+/// - The info-hash is not valid.
+/// - The torrent does not exist or it does not have metadata.
+#[utoipa::path(
+    get,
+    path = "/v1/torrent/{info_hash}/metadata",
+    tag = "torrent",
+    params(
+        ("info_hash" = String, Path, description = "The torrent info-hash"),
+    ),
+    responses(
+        (status = 200, description = "The torrent metadata", body = TorrentMetadata),
+        (status = 400, description = "The request is not valid", body = ErrorResponseData),
+        (status = 404, description = "The resource does not exist", body = ErrorResponseData),
+    ),
+    security((), ("bearer_auth" = []))
+)]
+#[allow(clippy::unused_async)]
+pub async fn get_torrent_metadata_handler(
+    State(app_data): State<Arc<AppData>>,
+    ExtractOptionalLoggedInUser(maybe_user_id): ExtractOptionalLoggedInUser,
+    Path(info_hash): Path<InfoHashParam>,
+) -> Response {
+    let Ok(info_hash) = InfoHash::from_str(&info_hash.lowercase()) else {
+        return errors::Request::InvalidInfoHashParam.into_response();
+    };
+
+    match app_data
+        .torrent_metadata_service
+        .get_metadata(&info_hash, maybe_user_id)
+        .await
+    {
+        Ok(metadata) => Json(OkResponseData { data: metadata }).into_response(),
+        Err(error) => error.into_response(),
+    }
+}
+
+/// Removes the metadata of a torrent.
+///
+/// # Errors
+///
+/// This function will return an error if:
+///
+/// - The user does not have permission to edit the torrent.
+/// - The torrent does not exist or it does not have metadata.
+#[utoipa::path(
+    delete,
+    path = "/v1/torrent/{info_hash}/metadata",
+    tag = "torrent",
+    params(
+        ("info_hash" = String, Path, description = "The torrent info-hash"),
+    ),
+    responses(
+        (status = 200, description = "The metadata was removed", body = MessageResponseData),
+        (status = 400, description = "The request is not valid", body = ErrorResponseData),
+        (status = 401, description = "The user is not logged in", body = ErrorResponseData),
+        (status = 403, description = "The user does not have permissions to perform the action", body = ErrorResponseData),
+        (status = 404, description = "The resource does not exist", body = ErrorResponseData),
+    ),
+    security(("bearer_auth" = []))
+)]
+#[allow(clippy::unused_async)]
+pub async fn delete_torrent_metadata_handler(
+    State(app_data): State<Arc<AppData>>,
+    ExtractOptionalLoggedInUser(maybe_user_id): ExtractOptionalLoggedInUser,
+    Path(info_hash): Path<InfoHashParam>,
+) -> Response {
+    let Ok(info_hash) = InfoHash::from_str(&info_hash.lowercase()) else {
+        return errors::Request::InvalidInfoHashParam.into_response();
+    };
+
+    match app_data
+        .torrent_metadata_service
+        .delete_metadata(&info_hash, maybe_user_id)
+        .await
+    {
+        Ok(()) => Json(OkResponseData {
+            data: format!("Metadata of torrent {info_hash} removed"),
+        })
+        .into_response(),
+        Err(error) => error.into_response(),
+    }
+}
+
+/// Replaces the file groups of a torrent pack.
+///
+/// # Errors
+///
+/// This function will return an error if:
+///
+/// - The user does not have permission to edit the torrent.
+/// - The info-hash or any of the groups is not valid.
+/// - The torrent does not exist.
+#[utoipa::path(
+    put,
+    path = "/v1/torrent/{info_hash}/file-groups",
+    tag = "torrent",
+    params(
+        ("info_hash" = String, Path, description = "The torrent info-hash"),
+    ),
+    request_body = UpdateTorrentFileGroupsForm,
+    responses(
+        (status = 200, description = "The new file groups", body = [TorrentFileGroupSummary]),
+        (status = 400, description = "The request is not valid", body = ErrorResponseData),
+        (status = 401, description = "The user is not logged in", body = ErrorResponseData),
+        (status = 403, description = "The user does not have permissions to perform the action", body = ErrorResponseData),
+        (status = 404, description = "The resource does not exist", body = ErrorResponseData),
+    ),
+    security(("bearer_auth" = []))
+)]
+#[allow(clippy::unused_async)]
+pub async fn update_torrent_file_groups_handler(
+    State(app_data): State<Arc<AppData>>,
+    ExtractLoggedInUser(user_id): ExtractLoggedInUser,
+    Path(info_hash): Path<InfoHashParam>,
+    extract::Json(update_torrent_file_groups_form): extract::Json<UpdateTorrentFileGroupsForm>,
+) -> Response {
+    let Ok(info_hash) = InfoHash::from_str(&info_hash.lowercase()) else {
+        return errors::Request::InvalidInfoHashParam.into_response();
+    };
+
+    match app_data
+        .torrent_file_group_service
+        .update_file_groups(&info_hash, &update_torrent_file_groups_form.groups, Some(user_id))
+        .await
+    {
+        Ok(file_groups) => Json(OkResponseData { data: file_groups }).into_response(),
+        Err(error) => error.into_response(),
+    }
+}
+
+/// Returns the file groups of a torrent pack.
+///
+/// # Errors
+///
+/// This function will return an error if:
+///
+/// - The info-hash is not valid.
+/// - The torrent does not exist.
+#[utoipa::path(
+    get,
+    path = "/v1/torrent/{info_hash}/file-groups",
+    tag = "torrent",
+    params(
+        ("info_hash" = String, Path, description = "The torrent info-hash"),
+    ),
+    responses(
+        (status = 200, description = "The torrent file groups", body = [TorrentFileGroupSummary]),
+        (status = 400, description = "The request is not valid", body = ErrorResponseData),
+        (status = 404, description = "The resource does not exist", body = ErrorResponseData),
+    ),
+    security((), ("bearer_auth" = []))
+)]
+#[allow(clippy::unused_async)]
+pub async fn get_torrent_file_groups_handler(
+    State(app_data): State<Arc<AppData>>,
+    ExtractOptionalLoggedInUser(maybe_user_id): ExtractOptionalLoggedInUser,
+    Path(info_hash): Path<InfoHashParam>,
+) -> Response {
+    let Ok(info_hash) = InfoHash::from_str(&info_hash.lowercase()) else {
+        return errors::Request::InvalidInfoHashParam.into_response();
+    };
+
+    match app_data
+        .torrent_file_group_service
+        .get_file_groups(&info_hash, maybe_user_id)
+        .await
+    {
+        Ok(file_groups) => Json(OkResponseData { data: file_groups }).into_response(),
+        Err(error) => error.into_response(),
+    }
+}
+
+/// Searches the files of a torrent pack. Each file includes the group it
+/// belongs to.
+///
+/// # Errors
+///
+/// This function will return an error if:
+///
+/// - The info-hash is not valid.
+/// - The torrent or the group do not exist.
+#[utoipa::path(
+    get,
+    path = "/v1/torrent/{info_hash}/files",
+    tag = "torrent",
+    params(
+        ("info_hash" = String, Path, description = "The torrent info-hash"),
+        FileSearchRequest,
+    ),
+    responses(
+        (status = 200, description = "The matching files", body = [PackFile]),
+        (status = 400, description = "The request is not valid", body = ErrorResponseData),
+        (status = 404, description = "The resource does not exist", body = ErrorResponseData),
+    ),
+    security((), ("bearer_auth" = []))
+)]
+#[allow(clippy::unused_async)]
+pub async fn search_torrent_files_handler(
+    State(app_data): State<Arc<AppData>>,
+    ExtractOptionalLoggedInUser(maybe_user_id): ExtractOptionalLoggedInUser,
+    Path(info_hash): Path<InfoHashParam>,
+    Query(request): Query<FileSearchRequest>,
+) -> Response {
+    let Ok(info_hash) = InfoHash::from_str(&info_hash.lowercase()) else {
+        return errors::Request::InvalidInfoHashParam.into_response();
+    };
+
+    match app_data
+        .torrent_file_group_service
+        .search_files(&info_hash, &request, maybe_user_id)
+        .await
+    {
+        Ok(files) => Json(OkResponseData { data: files }).into_response(),
+        Err(error) => error.into_response(),
+    }
+}
+
+/// Redirects the short URL of a torrent (`/t/{short_info_hash}`) to the
+/// torrent page. The short info-hash is the info-hash in base32.
+///
+/// # Errors
+///
+/// This function will return an error if:
+///
+/// - The short info-hash is not valid.
+/// - The torrent does not exist.
+#[utoipa::path(
+    get,
+    path = "/t/{short_info_hash}",
+    tag = "torrent",
+    params(
+        ("short_info_hash" = String, Path, description = "The torrent info-hash in base32"),
+    ),
+    responses(
+        (status = 307, description = "Redirect to the torrent page"),
+        (status = 400, description = "The request is not valid", body = ErrorResponseData),
+        (status = 404, description = "The resource does not exist", body = ErrorResponseData),
+    ),
+    security((), ("bearer_auth" = []))
+)]
+#[allow(clippy::unused_async)]
+pub async fn short_url_handler(
+    State(app_data): State<Arc<AppData>>,
+    ExtractOptionalLoggedInUser(maybe_user_id): ExtractOptionalLoggedInUser,
+    Path(short_info_hash): Path<String>,
+) -> Response {
+    let Some(info_hash) = base32::decode_info_hash(&short_info_hash) else {
+        return errors::Request::InvalidInfoHashParam.into_response();
+    };
+
+    match app_data.torrent_service.get_torrent_page_url(&info_hash, maybe_user_id).await {
+        Ok(torrent_page_url) => Redirect::temporary(&torrent_page_url).into_response(),
+        Err(error) => error.into_response(),
+    }
+}
+
+#[derive(Debug, Deserialize)]
+pub struct UuidParam(pub String);
+
+impl UuidParam {
+    fn value(&self) -> String {
+        self.0.to_lowercase()
+    }
+}
+
+/// Returns a random torrent file as a byte stream `application/x-bittorrent`.
+///
+/// This is useful for testing purposes.
+///
+/// # Errors
+///
+/// Returns an error if the torrent info-hash is invalid.
+#[utoipa::path(
+    get,
+    path = "/v1/torrent/meta-info/random/{uuid}",
+    tag = "torrent",
+    params(
+        ("uuid" = String, Path, description = "The UUID used to generate the torrent"),
+    ),
+    responses(
+        (status = 200, description = "A random torrent file, for testing", content_type = "application/x-bittorrent"),
+        (status = 400, description = "The request is not valid", body = ErrorResponseData),
+    ),
+    security(())
+)]
+#[allow(clippy::unused_async)]
+pub async fn create_random_torrent_handler(State(_app_data): State<Arc<AppData>>, Path(uuid): Path<UuidParam>) -> Response {
+    let Ok(uuid) = Uuid::parse_str(&uuid.value()) else {
+        return errors::Request::InvalidInfoHashParam.into_response();
+    };
+
+    let torrent = generate_random_torrent(uuid);
+
+    let Ok(bytes) = parse_torrent::encode_torrent(&torrent) else {
+        return ServiceError::InternalServerError.into_response();
+    };
+
+    torrent_file_response(
+        bytes,
+        &format!("{}.torrent", torrent.info.name),
+        &torrent.canonical_info_hash_hex(),
+    )
+}
+
+/// Extracts the [`TorrentRequest`] from the multipart form payload.
+///
+/// # Errors
+///
+/// It will return an error if:
+///
+/// - The multipart form is malformed.
+/// - The text fields do not contain a valid UTF8 string.
+/// - The publish date is not a valid RFC 3339 date.
+/// - The option to preserve the announce URLs is not `true` or `false`.
+/// - The category fields are not a JSON object with string, number or
+///   boolean values.
+/// - The torrent file data is not valid because:
+///    - The content type is not `application/x-bittorrent` or `application/octet-stream`.
+///    - The multipart content is invalid.
+///    - The torrent file pieces key has a length that is not a multiple of 20.
+///    - The binary data cannot be decoded as a torrent file.
+async fn build_add_torrent_request_from_payload(mut payload: Multipart) -> Result<AddTorrentRequest, errors::Request> {
+    let torrent_buffer = vec![0u8];
+    let mut torrent_cursor = Cursor::new(torrent_buffer);
+
+    let mut title = String::new();
+    let mut description = String::new();
+    let mut category = String::new();
+    let mut tags: Vec<TagId> = vec![];
+    let mut publish_at = None;
+    let mut preserve_announce_urls = None;
+    let mut fields = BTreeMap::new();
+
+    while let Some(mut field) = payload
+        .next_field()
+        .await
+        .map_err(|_| errors::Request::InvalidMultipartForm)?
+    {
+        let name = field.name().ok_or(errors::Request::InvalidMultipartForm)?;
+
+        match name {
+            "title" => {
+                let data = field.bytes().await.unwrap();
+                if data.is_empty() {
+                    continue;
+                };
+                title = String::from_utf8(data.to_vec()).map_err(|_| errors::Request::TitleIsNotValidUtf8)?;
+            }
+            "description" => {
+                let data = field.bytes().await.unwrap();
+                if data.is_empty() {
+                    continue;
+                };
+                description = String::from_utf8(data.to_vec()).map_err(|_| errors::Request::DescriptionIsNotValidUtf8)?;
+            }
+            "category" => {
+                let data = field.bytes().await.unwrap();
+                if data.is_empty() {
+                    continue;
+                };
+                category = String::from_utf8(data.to_vec()).map_err(|_| errors::Request::CategoryIsNotValidUtf8)?;
+            }
+            "tags" => {
+                let data = field.bytes().await.unwrap();
+                if data.is_empty() {
+                    continue;
                 };
                 let string_data = String::from_utf8(data.to_vec()).map_err(|_| errors::Request::TagsArrayIsNotValidUtf8)?;
                 tags = serde_json::from_str(&string_data).map_err(|_| errors::Request::TagsArrayIsNotValidJson)?;
             }
+            "publish_at" => {
+                let data = field.bytes().await.map_err(|_| errors::Request::InvalidMultipartForm)?;
+                if data.is_empty() {
+                    continue;
+                };
+                let string_data = String::from_utf8(data.to_vec()).map_err(|_| errors::Request::InvalidPublishDate)?;
+                publish_at = Some(
+                    DateTime::parse_from_rfc3339(string_data.trim())
+                        .map_err(|_| errors::Request::InvalidPublishDate)?
+                        .with_timezone(&Utc),
+                );
+            }
+            "preserve_announce_urls" => {
+                let data = field.bytes().await.map_err(|_| errors::Request::InvalidMultipartForm)?;
+                if data.is_empty() {
+                    continue;
+                };
+                preserve_announce_urls = match data.as_ref() {
+                    b"true" => Some(true),
+                    b"false" => Some(false),
+                    _ => return Err(errors::Request::InvalidPreserveAnnounceUrls),
+                };
+            }
+            "fields" => {
+                let data = field.bytes().await.map_err(|_| errors::Request::InvalidMultipartForm)?;
+                if data.is_empty() {
+                    continue;
+                };
+                fields = parse_category_fields(&data)?;
+            }
             "torrent" => {
                 let content_type = field.content_type().unwrap();
 
@@ -388,5 +1987,266 @@ async fn build_add_torrent_request_from_payload(mut payload: Multipart) -> Resul
         category_name: category,
         tags,
         torrent_buffer: torrent_cursor.into_inner(),
+        publish_at,
+        preserve_announce_urls,
+        fields,
+    })
+}
+
+/// It parses the JSON object with the values of the category fields. Numbers
+/// and booleans are accepted as well as strings.
+fn parse_category_fields(data: &[u8]) -> Result<BTreeMap<String, String>, errors::Request> {
+    let values: BTreeMap<String, serde_json::Value> =
+        serde_json::from_slice(data).map_err(|_| errors::Request::InvalidCategoryFields)?;
+
+    values
+        .into_iter()
+        .map(|(name, value)| match value {
+            serde_json::Value::String(value) => Ok((name, value)),
+            serde_json::Value::Number(_) | serde_json::Value::Bool(_) => Ok((name, value.to_string())),
+            _ => Err(errors::Request::InvalidCategoryFields),
+        })
+        .collect()
+}
+
+/// Extracts the request to attach a preview from the multipart form payload.
+async fn build_add_preview_request_from_payload(mut payload: Multipart) -> Result<AddPreviewRequest, errors::Request> {
+    let mut file_index = None;
+    let mut pieces_cursor = Cursor::new(vec![]);
+
+    while let Some(mut field) = payload
+        .next_field()
+        .await
+        .map_err(|_| errors::Request::InvalidMultipartForm)?
+    {
+        let name = field.name().ok_or(errors::Request::InvalidMultipartForm)?;
+
+        match name {
+            "file_index" => {
+                let data = field.bytes().await.map_err(|_| errors::Request::InvalidMultipartForm)?;
+                let string_data = String::from_utf8(data.to_vec()).map_err(|_| errors::Request::InvalidPreviewFileIndex)?;
+                file_index = Some(
+                    string_data
+                        .trim()
+                        .parse::<usize>()
+                        .map_err(|_| errors::Request::InvalidPreviewFileIndex)?,
+                );
+            }
+            "pieces" => {
+                while let Some(chunk) = field
+                    .chunk()
+                    .await
+                    .map_err(|_| (errors::Request::CannotReadChunkFromUploadedBinary))?
+                {
+                    pieces_cursor
+                        .write_all(&chunk)
+                        .map_err(|_| (errors::Request::CannotWriteChunkFromUploadedBinary))?;
+                }
+            }
+            _ => {}
+        }
+    }
+
+    Ok(AddPreviewRequest {
+        file_index: file_index.ok_or(errors::Request::InvalidPreviewFileIndex)?,
+        pieces: pieces_cursor.into_inner(),
+    })
+}
+
+/// Extracts the [`UploadImageRequest`] from the multipart form payload.
+async fn build_upload_image_request_from_payload(mut payload: Multipart) -> Result<UploadImageRequest, errors::Request> {
+    while let Some(mut field) = payload
+        .next_field()
+        .await
+        .map_err(|_| errors::Request::InvalidMultipartForm)?
+    {
+        if field.name() != Some("image") {
+            continue;
+        }
+
+        let file_name = field.file_name().unwrap_or_default().to_string();
+
+        let mut image_cursor = Cursor::new(vec![]);
+
+        while let Some(chunk) = field
+            .chunk()
+            .await
+            .map_err(|_| (errors::Request::CannotReadChunkFromUploadedBinary))?
+        {
+            image_cursor
+                .write_all(&chunk)
+                .map_err(|_| (errors::Request::CannotWriteChunkFromUploadedBinary))?;
+        }
+
+        return Ok(UploadImageRequest {
+            file_name,
+            bytes: Bytes::from(image_cursor.into_inner()),
+        });
+    }
+
+    Err(errors::Request::MissingImageFile)
+}
+
+/// Extracts the [`AddAttachmentRequest`] from the multipart form payload.
+async fn build_add_attachment_request_from_payload(mut payload: Multipart) -> Result<AddAttachmentRequest, errors::Request> {
+    let mut kind = None;
+    let mut file = None;
+
+    while let Some(mut field) = payload
+        .next_field()
+        .await
+        .map_err(|_| errors::Request::InvalidMultipartForm)?
+    {
+        let name = field.name().ok_or(errors::Request::InvalidMultipartForm)?;
+
+        match name {
+            "kind" => {
+                let data = field.bytes().await.map_err(|_| errors::Request::InvalidMultipartForm)?;
+                kind = Some(match data.as_ref() {
+                    b"nfo" => AttachmentKind::Nfo,
+                    b"screenshot" => AttachmentKind::Screenshot,
+                    _ => return Err(errors::Request::InvalidAttachmentKind),
+                });
+            }
+            "file" => {
+                let file_name = field.file_name().unwrap_or_default().to_string();
+
+                let mut file_cursor = Cursor::new(vec![]);
+
+                while let Some(chunk) = field
+                    .chunk()
+                    .await
+                    .map_err(|_| (errors::Request::CannotReadChunkFromUploadedBinary))?
+                {
+                    file_cursor
+                        .write_all(&chunk)
+                        .map_err(|_| (errors::Request::CannotWriteChunkFromUploadedBinary))?;
+                }
+
+                file = Some((file_name, file_cursor.into_inner()));
+            }
+            _ => {}
+        }
+    }
+
+    let (file_name, content) = file.ok_or(errors::Request::MissingAttachmentFile)?;
+
+    Ok(AddAttachmentRequest {
+        kind: kind.ok_or(errors::Request::InvalidAttachmentKind)?,
+        file_name,
+        content,
     })
 }
+
+#[cfg(test)]
+mod tests {
+    use axum::body::Body;
+    use axum::extract::{FromRequest, Multipart};
+    use axum::http::Request;
+    use hyper::StatusCode;
+
+    use super::build_add_torrent_request_from_payload;
+    use super::errors::{self, http_status_code_for_handler_error};
+
+    const BOUNDARY: &str = "torrust-index-boundary";
+
+    async fn multipart(body: Vec<u8>) -> Multipart {
+        let request = Request::builder()
+            .header("content-type", format!("multipart/form-data; boundary={BOUNDARY}"))
+            .body(Body::from(body))
+            .unwrap();
+
+        Multipart::from_request(request, &()).await.unwrap()
+    }
+
+    /// A form with a text part for each `(name, value)` pair.
+    fn form(parts: &[(&str, &[u8])]) -> Vec<u8> {
+        let mut body = vec![];
+
+        for (name, value) in parts {
+            body.extend_from_slice(format!("--{BOUNDARY}\r\nContent-Disposition: form-data; name=\"{name}\"\r\n\r\n").as_bytes());
+            body.extend_from_slice(value);
+            body.extend_from_slice(b"\r\n");
+        }
+
+        body.extend_from_slice(format!("--{BOUNDARY}--\r\n").as_bytes());
+
+        body
+    }
+
+    /// A form whose only part is cut off before its end.
+    fn truncated_form(name: &str) -> Vec<u8> {
+        format!("--{BOUNDARY}\r\nContent-Disposition: form-data; name=\"{name}\"\r\n\r\nunterminated").into_bytes()
+    }
+
+    #[tokio::test]
+    async fn it_should_reject_a_malformed_publish_date_part() {
+        let result = build_add_torrent_request_from_payload(multipart(truncated_form("publish_at")).await).await;
+
+        assert_eq!(result.err(), Some(errors::Request::InvalidMultipartForm));
+    }
+
+    #[tokio::test]
+    async fn it_should_reject_a_publish_date_that_is_not_valid_utf8() {
+        let result =
+            build_add_torrent_request_from_payload(multipart(form(&[("publish_at", b"\xff\xfe".as_slice())])).await).await;
+
+        assert_eq!(result.err(), Some(errors::Request::InvalidPublishDate));
+    }
+
+    #[tokio::test]
+    async fn it_should_reject_a_malformed_preserve_announce_urls_part() {
+        let result = build_add_torrent_request_from_payload(multipart(truncated_form("preserve_announce_urls")).await).await;
+
+        assert_eq!(result.err(), Some(errors::Request::InvalidMultipartForm));
+    }
+
+    #[tokio::test]
+    async fn it_should_reject_a_preserve_announce_urls_value_that_is_not_a_boolean() {
+        let result =
+            build_add_torrent_request_from_payload(multipart(form(&[("preserve_announce_urls", b"yes".as_slice())])).await).await;
+
+        assert_eq!(result.err(), Some(errors::Request::InvalidPreserveAnnounceUrls));
+    }
+
+    #[tokio::test]
+    async fn it_should_reject_a_malformed_category_fields_part() {
+        let result = build_add_torrent_request_from_payload(multipart(truncated_form("fields")).await).await;
+
+        assert_eq!(result.err(), Some(errors::Request::InvalidMultipartForm));
+    }
+
+    #[tokio::test]
+    async fn it_should_reject_invalid_category_fields() {
+        for payload in [b"{\"year\":".as_slice(), b"[\"1999\"]", b"\xff\xfe", b"{\"year\":[1999]}"] {
+            let result = build_add_torrent_request_from_payload(multipart(form(&[("fields", payload)])).await).await;
+
+            let error = result.err().unwrap();
+
+            assert_eq!(error, errors::Request::InvalidCategoryFields);
+            assert_eq!(http_status_code_for_handler_error(&error), StatusCode::BAD_REQUEST);
+        }
+    }
+
+    #[tokio::test]
+    async fn it_should_accept_category_fields_with_string_number_and_boolean_values() {
+        let result = build_add_torrent_request_from_payload(
+            multipart(form(&[(
+                "fields",
+                br#"{"year":1999,"codec":"x264","remastered":true}"#.as_slice(),
+            )]))
+            .await,
+        )
+        .await;
+
+        let Ok(request) = result else {
+            panic!("the category fields should be accepted");
+        };
+
+        let fields = request.fields;
+
+        assert_eq!(fields.get("year").map(String::as_str), Some("1999"));
+        assert_eq!(fields.get("codec").map(String::as_str), Some("x264"));
+        assert_eq!(fields.get("remastered").map(String::as_str), Some("true"));
+    }
+}
@@ -1,18 +1,22 @@
 use axum::response::{IntoResponse, Response};
 use axum::Json;
+use bytes::Bytes;
 use hyper::{header, HeaderMap, StatusCode};
 use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
 
-use crate::models::torrent::TorrentId;
+use crate::models::torrent::{SimilarTorrent, TorrentId};
 use crate::services::torrent::AddTorrentResponse;
 use crate::web::api::server::v1::responses::OkResponseData;
 
 #[allow(clippy::module_name_repetitions)]
-#[derive(Serialize, Deserialize, Debug)]
+#[derive(Serialize, Deserialize, Debug, ToSchema)]
+#[schema(as = UploadedTorrent)]
 pub struct NewTorrentResponseData {
     pub torrent_id: TorrentId,
     pub canonical_info_hash: String,
     pub info_hash: String,
+    pub similar_torrents: Vec<SimilarTorrent>,
 }
 
 /// Response after successfully uploading a new torrent.
@@ -22,6 +26,7 @@ pub fn new_torrent_response(add_torrent_response: &AddTorrentResponse) -> Json<O
             torrent_id: add_torrent_response.torrent_id,
             canonical_info_hash: add_torrent_response.canonical_info_hash.clone(),
             info_hash: add_torrent_response.info_hash.clone(),
+            similar_torrents: add_torrent_response.similar_torrents.clone(),
         },
     })
 }
@@ -56,3 +61,66 @@ pub fn torrent_file_response(bytes: Vec<u8>, filename: &str, info_hash: &str) ->
 
     (StatusCode::OK, headers, bytes).into_response()
 }
+
+/// Builds the response for a torrent listing snapshot.
+///
+/// The snapshot is the JSON encoded [`TorrentsResponse`](crate::models::response::TorrentsResponse).
+#[must_use]
+pub fn torrents_snapshot_response(snapshot: &str) -> Response {
+    (
+        StatusCode::OK,
+        [(header::CONTENT_TYPE, "application/json")],
+        format!(r#"{{"data":{snapshot}}}"#),
+    )
+        .into_response()
+}
+
+/// Builds the binary response for a torrent preview.
+///
+/// Browsers must not guess the content type of the preview, since it comes
+/// from the torrent contents.
+///
+/// # Panics
+///
+/// Panics if the content type is not a valid header value.
+#[must_use]
+pub fn torrent_preview_response(bytes: Vec<u8>, content_type: &str) -> Response {
+    let mut headers = HeaderMap::new();
+    headers.insert(
+        header::CONTENT_TYPE,
+        content_type
+            .parse()
+            .expect("Preview content type should be a valid header value"),
+    );
+    headers.insert(
+        header::X_CONTENT_TYPE_OPTIONS,
+        "nosniff".parse().expect("HTTP header value should be valid"),
+    );
+
+    (StatusCode::OK, headers, bytes).into_response()
+}
+
+/// Response with an image uploaded for a torrent description. The images are
+/// immutable, so they can be cached by the clients.
+#[must_use]
+pub fn description_image_response(bytes: Bytes, content_type: &str) -> Response {
+    let mut headers = HeaderMap::new();
+    headers.insert(
+        header::CONTENT_TYPE,
+        content_type
+            .parse()
+            .expect("Image content type should be a valid header value"),
+    );
+    headers.insert(
+        header::X_CONTENT_TYPE_OPTIONS,
+        "nosniff".parse().expect("HTTP header value should be valid"),
+    );
+    headers.insert(
+        header::CACHE_CONTROL,
+        "public, max-age=31536000, immutable"
+            .parse()
+            .expect("HTTP header value should be valid"),
+    );
+
+    (StatusCode::OK, headers, bytes).into_response()
+}
@@ -7,32 +7,110 @@ use axum::routing::{delete, get, post, put};
 use axum::Router;
 
 use super::handlers::{
-    create_random_torrent_handler, delete_torrent_handler, download_torrent_handler, get_torrent_info_handler,
-    get_torrents_handler, update_torrent_info_handler, upload_torrent_handler,
+    add_magnet_handler, add_torrent_attachment_handler, add_torrent_preview_handler, add_torrent_web_seed_handler,
+    create_random_torrent_handler, delete_torrent_attachment_handler, delete_torrent_handler, delete_torrent_metadata_handler,
+    delete_torrent_web_seed_handler, download_torrent_handler, enrich_torrent_metadata_handler, get_description_image_handler,
+    get_description_images_handler, get_duplicate_torrents_handler, get_magnet_link_handler, get_magnet_link_qr_code_handler,
+    get_magnet_upload_handler, get_torrent_attachment_content_handler, get_torrent_attachments_handler,
+    get_torrent_file_groups_handler, get_torrent_info_handler, get_torrent_metadata_handler, get_torrent_peers_handler,
+    get_torrent_preview_content_handler, get_torrent_previews_handler, get_torrent_web_seeds_handler, get_torrents_handler,
+    search_torrent_files_handler, signed_upload_torrent_handler, update_torrent_file_groups_handler, update_torrent_info_handler,
+    update_torrent_trackers_handler, upload_description_image_handler, upload_torrent_handler,
 };
 use crate::common::AppData;
+use crate::web::api::server::v1::contexts::{bookmark, comment, report};
 
 /// Routes for the [`torrent`](crate::web::api::server::v1::contexts::torrent) API context for single resources.
 pub fn router_for_single_resources(app_data: Arc<AppData>) -> Router {
     let torrent_info_routes = Router::new()
         .route("/", get(get_torrent_info_handler).with_state(app_data.clone()))
         .route("/", put(update_torrent_info_handler).with_state(app_data.clone()))
-        .route("/", delete(delete_torrent_handler).with_state(app_data.clone()));
+        .route("/", delete(delete_torrent_handler).with_state(app_data.clone()))
+        .route("/trackers", put(update_torrent_trackers_handler).with_state(app_data.clone()))
+        .route("/magnet", get(get_magnet_link_handler).with_state(app_data.clone()))
+        .route("/qr", get(get_magnet_link_qr_code_handler).with_state(app_data.clone()))
+        .route("/peers", get(get_torrent_peers_handler).with_state(app_data.clone()))
+        .route("/previews", get(get_torrent_previews_handler).with_state(app_data.clone()))
+        .route("/previews", post(add_torrent_preview_handler).with_state(app_data.clone()))
+        .route(
+            "/previews/:preview_id",
+            get(get_torrent_preview_content_handler).with_state(app_data.clone()),
+        )
+        .route("/images", get(get_description_images_handler).with_state(app_data.clone()))
+        .route("/images", post(upload_description_image_handler).with_state(app_data.clone()))
+        .route(
+            "/images/:image_id",
+            get(get_description_image_handler).with_state(app_data.clone()),
+        )
+        .route(
+            "/attachments",
+            get(get_torrent_attachments_handler).with_state(app_data.clone()),
+        )
+        .route(
+            "/attachments",
+            post(add_torrent_attachment_handler).with_state(app_data.clone()),
+        )
+        .route(
+            "/attachments/:attachment_id",
+            get(get_torrent_attachment_content_handler).with_state(app_data.clone()),
+        )
+        .route(
+            "/attachments/:attachment_id",
+            delete(delete_torrent_attachment_handler).with_state(app_data.clone()),
+        )
+        .route("/webseeds", get(get_torrent_web_seeds_handler).with_state(app_data.clone()))
+        .route("/webseeds", post(add_torrent_web_seed_handler).with_state(app_data.clone()))
+        .route(
+            "/webseeds/:web_seed_id",
+            delete(delete_torrent_web_seed_handler).with_state(app_data.clone()),
+        )
+        .route("/metadata", get(get_torrent_metadata_handler).with_state(app_data.clone()))
+        .route(
+            "/metadata",
+            post(enrich_torrent_metadata_handler).with_state(app_data.clone()),
+        )
+        .route(
+            "/metadata",
+            delete(delete_torrent_metadata_handler).with_state(app_data.clone()),
+        )
+        .route(
+            "/file-groups",
+            get(get_torrent_file_groups_handler).with_state(app_data.clone()),
+        )
+        .route(
+            "/file-groups",
+            put(update_torrent_file_groups_handler).with_state(app_data.clone()),
+        )
+        .route("/files", get(search_torrent_files_handler).with_state(app_data.clone()));
 
     Router::new()
         .route("/upload", post(upload_torrent_handler).with_state(app_data.clone()))
+        .route(
+            "/upload/signed",
+            post(signed_upload_torrent_handler).with_state(app_data.clone()),
+        )
+        .route("/magnet", post(add_magnet_handler).with_state(app_data.clone()))
+        .route(
+            "/magnet/:magnet_upload_id",
+            get(get_magnet_upload_handler).with_state(app_data.clone()),
+        )
         .route(
             "/download/:info_hash",
             get(download_torrent_handler).with_state(app_data.clone()),
         )
         .route(
             "/meta-info/random/:uuid",
-            get(create_random_torrent_handler).with_state(app_data),
+            get(create_random_torrent_handler).with_state(app_data.clone()),
         )
         .nest("/:info_hash", torrent_info_routes)
+        .nest("/:info_hash/comments", comment::routes::router(app_data.clone()))
+        .nest("/:info_hash/report", report::routes::router(app_data.clone()))
+        .nest("/:info_hash/bookmark", bookmark::routes::router_for_torrent(app_data))
 }
 
 /// Routes for the [`torrent`](crate::web::api::server::v1::contexts::torrent) API context for multiple resources.
 pub fn router_for_multiple_resources(app_data: Arc<AppData>) -> Router {
-    Router::new().route("/", get(get_torrents_handler).with_state(app_data))
+    Router::new()
+        .route("/", get(get_torrents_handler).with_state(app_data.clone()))
+        .route("/duplicates", get(get_duplicate_torrents_handler).with_state(app_data))
 }
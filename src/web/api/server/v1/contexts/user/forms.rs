@@ -1,8 +1,9 @@
 use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
 
 // Registration
 
-#[derive(Clone, Debug, Deserialize, Serialize)]
+#[derive(Clone, Debug, Deserialize, Serialize, ToSchema)]
 pub struct RegistrationForm {
     pub username: String,
     pub email: Option<String>,
@@ -12,22 +13,37 @@ pub struct RegistrationForm {
 
 // Authentication
 
-#[derive(Clone, Debug, Deserialize, Serialize)]
+#[derive(Clone, Debug, Deserialize, Serialize, ToSchema)]
 pub struct LoginForm {
     pub login: String, // todo: rename to `username`
     pub password: String,
 }
 
-#[derive(Clone, Debug, Deserialize, Serialize)]
+#[derive(Clone, Debug, Deserialize, Serialize, ToSchema)]
 pub struct JsonWebToken {
     pub token: String, // // todo: rename to `encoded` or `value`
 }
 
 // Profile
 
-#[derive(Clone, Debug, Deserialize, Serialize)]
+#[derive(Clone, Debug, Deserialize, Serialize, ToSchema)]
 pub struct ChangePasswordForm {
     pub current_password: String,
     pub password: String,
     pub confirm_password: String,
 }
+
+#[derive(Clone, Debug, Deserialize, Serialize, ToSchema)]
+pub struct ChangeEmailForm {
+    /// The new email address.
+    pub email: String,
+    pub current_password: String,
+}
+
+/// Missing fields are not changed. Empty fields are cleared.
+#[derive(Clone, Debug, Deserialize, Serialize, ToSchema)]
+pub struct UpdateProfileForm {
+    pub bio: Option<String>,
+    /// An HTTP or HTTPS image URL.
+    pub avatar: Option<String>,
+}
@@ -1,17 +1,28 @@
 //! API handlers for the the [`user`](crate::web::api::server::v1::contexts::user) API
 //! context.
+use std::net::SocketAddr;
 use std::sync::Arc;
 
-use axum::extract::{self, Host, Path, State};
+use axum::extract::{self, ConnectInfo, Host, Path, Query, State};
+use axum::http::header::USER_AGENT;
+use axum::http::HeaderMap;
 use axum::response::{IntoResponse, Response};
 use axum::Json;
 use serde::Deserialize;
 
-use super::forms::{ChangePasswordForm, JsonWebToken, LoginForm, RegistrationForm};
+use super::forms::{ChangeEmailForm, ChangePasswordForm, JsonWebToken, LoginForm, RegistrationForm, UpdateProfileForm};
 use super::responses::{self};
 use crate::common::AppData;
+use crate::models::api_usage::ApiUsageSummary;
+use crate::models::tracker_key::{RegeneratedTrackerKey, TrackerKeyStatus};
+use crate::models::user::PublicProfile;
+use crate::models::user_session::{SessionId, UserSession};
+use crate::services::api_usage::credential_id;
+use crate::services::session::Client;
+use crate::services::user::{ListingRequest, ProfilePage};
+use crate::web::api::server::v1::extractors::bearer_token;
 use crate::web::api::server::v1::extractors::optional_user_id::ExtractOptionalLoggedInUser;
-use crate::web::api::server::v1::responses::OkResponseData;
+use crate::web::api::server::v1::responses::{ErrorResponseData, MessageResponseData, OkResponseData, TokenResponseData};
 
 // Registration
 
@@ -20,6 +31,18 @@ use crate::web::api::server::v1::responses::OkResponseData;
 /// # Errors
 ///
 /// It returns an error if the user could not be registered.
+#[utoipa::path(
+    post,
+    path = "/v1/user/register",
+    tag = "user",
+    request_body = RegistrationForm,
+    responses(
+        (status = 200, description = "The new user"),
+        (status = 400, description = "The request is not valid", body = ErrorResponseData),
+        (status = 409, description = "The resource already exists", body = ErrorResponseData),
+    ),
+    security(())
+)]
 #[allow(clippy::unused_async)]
 pub async fn registration_handler(
     State(app_data): State<Arc<AppData>>,
@@ -46,6 +69,18 @@ pub async fn registration_handler(
 pub struct TokenParam(String);
 
 /// It handles the verification of the email verification token.
+#[utoipa::path(
+    get,
+    path = "/v1/user/email/verify/{token}",
+    tag = "user",
+    params(
+        ("token" = String, Path, description = "The token sent by email"),
+    ),
+    responses(
+        (status = 200, description = "A message with the result of the verification", content_type = "text/plain"),
+    ),
+    security(())
+)]
 #[allow(clippy::unused_async)]
 pub async fn email_verification_handler(State(app_data): State<Arc<AppData>>, Path(token): Path<TokenParam>) -> String {
     match app_data.registration_service.verify_email(&token.0).await {
@@ -54,6 +89,150 @@ pub async fn email_verification_handler(State(app_data): State<Arc<AppData>>, Pa
     }
 }
 
+/// It handles the request to unsubscribe from non-essential emails, like
+/// announcements. The link is included in the emails.
+#[utoipa::path(
+    get,
+    path = "/v1/user/email/unsubscribe/{token}",
+    tag = "user",
+    params(
+        ("token" = String, Path, description = "The token sent by email"),
+    ),
+    responses(
+        (status = 200, description = "A message with the result of the unsubscription", content_type = "text/plain"),
+    ),
+    security(())
+)]
+#[allow(clippy::unused_async)]
+pub async fn email_unsubscribe_handler(State(app_data): State<Arc<AppData>>, Path(token): Path<TokenParam>) -> String {
+    match app_data.bulk_mail_service.unsubscribe(&token.0).await {
+        Ok(true) => String::from("You have been unsubscribed, you can close this page."),
+        Ok(false) => String::from("Invalid unsubscribe link."),
+        Err(error) => error.to_string(),
+    }
+}
+
+/// It sends the email verification link again. The user is identified with
+/// the username and password because users with an unverified email can't
+/// log in.
+///
+/// # Errors
+///
+/// It returns an error if:
+///
+/// - The username or the password are wrong.
+/// - The user does not have an email or it's already verified.
+/// - Unable to send the email.
+#[utoipa::path(
+    post,
+    path = "/v1/user/email/resend-verification",
+    tag = "user",
+    request_body = LoginForm,
+    responses(
+        (status = 200, description = "The verification email was sent", body = MessageResponseData),
+        (status = 400, description = "The request is not valid", body = ErrorResponseData),
+        (status = 403, description = "Wrong username or password", body = ErrorResponseData),
+        (status = 429, description = "Too many requests", body = ErrorResponseData),
+    ),
+    security(())
+)]
+#[allow(clippy::unused_async)]
+pub async fn resend_verification_email_handler(
+    State(app_data): State<Arc<AppData>>,
+    Host(host_from_header): Host,
+    extract::Json(login_form): extract::Json<LoginForm>,
+) -> Response {
+    let api_base_url = app_data
+        .cfg
+        .get_api_base_url()
+        .await
+        .unwrap_or(api_base_url(&host_from_header));
+
+    match app_data
+        .profile_service
+        .resend_verification_email(&login_form, &api_base_url)
+        .await
+    {
+        Ok(()) => Json(OkResponseData {
+            data: "Verification email sent".to_string(),
+        })
+        .into_response(),
+        Err(error) => error.into_response(),
+    }
+}
+
+/// It starts the change of the logged-in user's email. A confirmation link is
+/// sent to the new address and the email is changed when the user opens it.
+///
+/// # Errors
+///
+/// It returns an error if:
+///
+/// - The user is not logged in.
+/// - The current password is wrong or the new email is not valid.
+/// - Unable to send the email.
+#[utoipa::path(
+    post,
+    path = "/v1/user/email/change",
+    tag = "user",
+    request_body = ChangeEmailForm,
+    responses(
+        (status = 200, description = "The confirmation email was sent", body = MessageResponseData),
+        (status = 400, description = "The request is not valid", body = ErrorResponseData),
+        (status = 401, description = "The user is not logged in", body = ErrorResponseData),
+        (status = 403, description = "The user does not have permissions to perform the action", body = ErrorResponseData),
+        (status = 429, description = "Too many requests", body = ErrorResponseData),
+    ),
+    security(("bearer_auth" = []))
+)]
+#[allow(clippy::unused_async)]
+pub async fn change_email_handler(
+    State(app_data): State<Arc<AppData>>,
+    Host(host_from_header): Host,
+    ExtractOptionalLoggedInUser(maybe_user_id): ExtractOptionalLoggedInUser,
+    extract::Json(change_email_form): extract::Json<ChangeEmailForm>,
+) -> Response {
+    let api_base_url = app_data
+        .cfg
+        .get_api_base_url()
+        .await
+        .unwrap_or(api_base_url(&host_from_header));
+
+    match app_data
+        .profile_service
+        .request_email_change(maybe_user_id, &change_email_form, &api_base_url)
+        .await
+    {
+        Ok(()) => Json(OkResponseData {
+            data: "Confirmation email sent to the new address".to_string(),
+        })
+        .into_response(),
+        Err(error) => error.into_response(),
+    }
+}
+
+/// It handles the confirmation of the email change token.
+#[utoipa::path(
+    get,
+    path = "/v1/user/email/change/{token}",
+    tag = "user",
+    params(
+        ("token" = String, Path, description = "The token sent by email"),
+    ),
+    responses(
+        (status = 200, description = "A message with the result of the email change", content_type = "text/plain"),
+    ),
+    security(())
+)]
+#[allow(clippy::unused_async)]
+pub async fn email_change_confirmation_handler(State(app_data): State<Arc<AppData>>, Path(token): Path<TokenParam>) -> String {
+    match app_data.profile_service.confirm_email_change(&token.0).await {
+        Ok(true) => String::from("Email changed, you can close this page."),
+        Ok(false) => String::from("Invalid or expired email change link."),
+        Err(error) => error.to_string(),
+    }
+}
+
 // Authentication
 
 /// It handles the user login.
@@ -64,14 +243,37 @@ pub async fn email_verification_handler(State(app_data): State<Arc<AppData>>, Pa
 ///
 /// - Unable to verify the supplied payload as a valid JWT.
 /// - The JWT is not invalid or expired.
+#[utoipa::path(
+    post,
+    path = "/v1/user/login",
+    tag = "user",
+    request_body = LoginForm,
+    responses(
+        (status = 200, description = "The user's token", body = TokenResponseData),
+        (status = 400, description = "The request is not valid", body = ErrorResponseData),
+        (status = 401, description = "The user is not logged in", body = ErrorResponseData),
+        (status = 403, description = "The user does not have permissions to perform the action", body = ErrorResponseData),
+    ),
+    security(())
+)]
 #[allow(clippy::unused_async)]
 pub async fn login_handler(
     State(app_data): State<Arc<AppData>>,
+    headers: HeaderMap,
+    maybe_connect_info: Option<ConnectInfo<SocketAddr>>,
     extract::Json(login_form): extract::Json<LoginForm>,
 ) -> Response {
+    let client = Client {
+        user_agent: headers
+            .get(USER_AGENT)
+            .and_then(|value| value.to_str().ok())
+            .map(ToString::to_string),
+        ip: maybe_connect_info.map(|ConnectInfo(socket_addr)| socket_addr.ip().to_string()),
+    };
+
     match app_data
         .authentication_service
-        .login(&login_form.login, &login_form.password)
+        .login(&login_form.login, &login_form.password, &client)
         .await
     {
         Ok((token, user_compact)) => responses::logged_in_user(token, user_compact).into_response(),
@@ -87,12 +289,23 @@ pub async fn login_handler(
 ///
 /// - Unable to verify the supplied payload as a valid JWT.
 /// - The JWT is not invalid or expired.
+#[utoipa::path(
+    post,
+    path = "/v1/user/token/verify",
+    tag = "user",
+    request_body = JsonWebToken,
+    responses(
+        (status = 200, description = "The token is valid", body = MessageResponseData),
+        (status = 401, description = "The user is not logged in", body = ErrorResponseData),
+    ),
+    security(())
+)]
 #[allow(clippy::unused_async)]
 pub async fn verify_token_handler(
     State(app_data): State<Arc<AppData>>,
     extract::Json(token): extract::Json<JsonWebToken>,
 ) -> Response {
-    match app_data.json_web_token.verify(&token.token).await {
+    match app_data.auth.verify_jwt(&token.token).await {
         Ok(_) => axum::Json(OkResponseData {
             data: "Token is valid.".to_string(),
         })
@@ -112,6 +325,17 @@ pub struct UsernameParam(pub String);
 ///
 /// - Unable to parse the supplied payload as a valid JWT.
 /// - The JWT is not invalid or expired.
+#[utoipa::path(
+    post,
+    path = "/v1/user/token/renew",
+    tag = "user",
+    request_body = JsonWebToken,
+    responses(
+        (status = 200, description = "The renewed token", body = TokenResponseData),
+        (status = 401, description = "The user is not logged in", body = ErrorResponseData),
+    ),
+    security(())
+)]
 #[allow(clippy::unused_async)]
 pub async fn renew_token_handler(
     State(app_data): State<Arc<AppData>>,
@@ -130,6 +354,22 @@ pub async fn renew_token_handler(
 /// It returns an error if:
 ///
 /// - The user account is not found.
+#[utoipa::path(
+    post,
+    path = "/v1/user/{user}/change-password",
+    tag = "user",
+    params(
+        ("user" = String, Path, description = "The username"),
+    ),
+    request_body = ChangePasswordForm,
+    responses(
+        (status = 200, description = "The password was changed", body = MessageResponseData),
+        (status = 400, description = "The request is not valid", body = ErrorResponseData),
+        (status = 401, description = "The user is not logged in", body = ErrorResponseData),
+        (status = 403, description = "The user does not have permissions to perform the action", body = ErrorResponseData),
+    ),
+    security(("bearer_auth" = []))
+)]
 #[allow(clippy::unused_async)]
 #[allow(clippy::missing_panics_doc)]
 pub async fn change_password_handler(
@@ -150,6 +390,266 @@ pub async fn change_password_handler(
     }
 }
 
+/// It returns the public profile page of a user: the profile and a page of
+/// the torrents uploaded by the user.
+///
+/// # Errors
+///
+/// It returns an error if:
+///
+/// - The user does not exist.
+/// - There is a database error.
+#[utoipa::path(
+    get,
+    path = "/v1/user/{user}/profile",
+    tag = "user",
+    params(
+        ("user" = String, Path, description = "The username"),
+        ListingRequest,
+    ),
+    responses(
+        (status = 200, description = "The profile page", body = ProfilePage),
+        (status = 404, description = "The resource does not exist", body = ErrorResponseData),
+    ),
+    security((), ("bearer_auth" = []))
+)]
+#[allow(clippy::unused_async)]
+pub async fn get_profile_handler(
+    State(app_data): State<Arc<AppData>>,
+    ExtractOptionalLoggedInUser(maybe_user_id): ExtractOptionalLoggedInUser,
+    Path(username): Path<UsernameParam>,
+    Query(criteria): Query<ListingRequest>,
+) -> Response {
+    match app_data
+        .profile_service
+        .get_profile_page(&username.0, &criteria, maybe_user_id)
+        .await
+    {
+        Ok(profile_page) => Json(OkResponseData { data: profile_page }).into_response(),
+        Err(error) => error.into_response(),
+    }
+}
+
+/// It updates the bio and the avatar of the logged-in user.
+///
+/// # Errors
+///
+/// It returns an error if:
+///
+/// - The user is not logged in or it's not the owner of the profile.
+/// - The bio is too long or the avatar is not a valid URL.
+/// - There is a database error.
+#[utoipa::path(
+    put,
+    path = "/v1/user/{user}/profile",
+    tag = "user",
+    params(
+        ("user" = String, Path, description = "The username"),
+    ),
+    request_body = UpdateProfileForm,
+    responses(
+        (status = 200, description = "The updated profile", body = PublicProfile),
+        (status = 400, description = "The request is not valid", body = ErrorResponseData),
+        (status = 401, description = "The user is not logged in", body = ErrorResponseData),
+        (status = 403, description = "The user does not have permissions to perform the action", body = ErrorResponseData),
+        (status = 404, description = "The resource does not exist", body = ErrorResponseData),
+    ),
+    security(("bearer_auth" = []))
+)]
+#[allow(clippy::unused_async)]
+pub async fn update_profile_handler(
+    State(app_data): State<Arc<AppData>>,
+    ExtractOptionalLoggedInUser(maybe_user_id): ExtractOptionalLoggedInUser,
+    Path(username): Path<UsernameParam>,
+    extract::Json(update_profile_form): extract::Json<UpdateProfileForm>,
+) -> Response {
+    match app_data
+        .profile_service
+        .update_profile(&username.0, &update_profile_form, maybe_user_id)
+        .await
+    {
+        Ok(profile) => Json(OkResponseData { data: profile }).into_response(),
+        Err(error) => error.into_response(),
+    }
+}
+
+/// It returns the API usage of the logged-in user: requests per day and the
+/// usage of each credential.
+///
+/// # Errors
+///
+/// It returns an error if:
+///
+/// - The user is not logged in.
+/// - There is a database error.
+#[utoipa::path(
+    get,
+    path = "/v1/user/api-usage",
+    tag = "user",
+    responses(
+        (status = 200, description = "The API usage summary", body = ApiUsageSummary),
+        (status = 401, description = "The user is not logged in", body = ErrorResponseData),
+    ),
+    security(("bearer_auth" = []))
+)]
+#[allow(clippy::unused_async)]
+pub async fn get_api_usage_handler(
+    State(app_data): State<Arc<AppData>>,
+    ExtractOptionalLoggedInUser(maybe_user_id): ExtractOptionalLoggedInUser,
+    bearer_token::Extract(maybe_bearer_token): bearer_token::Extract,
+) -> Response {
+    let current_credential = maybe_bearer_token.map(|bearer_token| credential_id(&bearer_token.value()));
+
+    match app_data
+        .api_usage_service
+        .get_summary(maybe_user_id, current_credential.as_deref())
+        .await
+    {
+        Ok(summary) => Json(OkResponseData { data: summary }).into_response(),
+        Err(error) => error.into_response(),
+    }
+}
+
+/// It returns the active sessions of the logged-in user, from the most
+/// recently used. The session of the token used in the request is flagged as
+/// `current`.
+///
+/// # Errors
+///
+/// It returns an error if:
+///
+/// - The user is not logged in.
+/// - There is a database error.
+#[utoipa::path(
+    get,
+    path = "/v1/user/sessions",
+    tag = "user",
+    responses(
+        (status = 200, description = "The active sessions", body = [UserSession]),
+        (status = 401, description = "The user is not logged in", body = ErrorResponseData),
+    ),
+    security(("bearer_auth" = []))
+)]
+#[allow(clippy::unused_async)]
+pub async fn get_sessions_handler(
+    State(app_data): State<Arc<AppData>>,
+    ExtractOptionalLoggedInUser(maybe_user_id): ExtractOptionalLoggedInUser,
+    bearer_token::Extract(maybe_bearer_token): bearer_token::Extract,
+) -> Response {
+    let current_session_id = app_data.auth.get_session_id_from_bearer_token(maybe_bearer_token).await;
+
+    match app_data
+        .session_service
+        .get_sessions(maybe_user_id, current_session_id.as_deref())
+        .await
+    {
+        Ok(sessions) => Json(OkResponseData { data: sessions }).into_response(),
+        Err(error) => error.into_response(),
+    }
+}
+
+/// It revokes a session of the logged-in user. The tokens of the session are
+/// rejected from now on.
+///
+/// # Errors
+///
+/// It returns an error if:
+///
+/// - The user is not logged in.
+/// - The session does not exist or it belongs to another user.
+/// - There is a database error.
+#[utoipa::path(
+    delete,
+    path = "/v1/user/sessions/{session_id}",
+    tag = "user",
+    params(
+        ("session_id" = String, Path, description = "The session ID"),
+    ),
+    responses(
+        (status = 200, description = "The session ID", body = MessageResponseData),
+        (status = 401, description = "The user is not logged in", body = ErrorResponseData),
+        (status = 404, description = "The session does not exist", body = ErrorResponseData),
+    ),
+    security(("bearer_auth" = []))
+)]
+#[allow(clippy::unused_async)]
+pub async fn revoke_session_handler(
+    State(app_data): State<Arc<AppData>>,
+    ExtractOptionalLoggedInUser(maybe_user_id): ExtractOptionalLoggedInUser,
+    Path(session_id): Path<SessionId>,
+) -> Response {
+    match app_data.session_service.revoke(&session_id, maybe_user_id).await {
+        Ok(()) => Json(OkResponseData { data: session_id }).into_response(),
+        Err(error) => error.into_response(),
+    }
+}
+
+/// It returns the status of the logged-in user's announce key, checking the
+/// tracker accepts it.
+///
+/// # Errors
+///
+/// It returns an error if:
+///
+/// - The user is not logged in.
+/// - The tracker is not private.
+/// - The announce request to check the key fails.
+#[utoipa::path(
+    get,
+    path = "/v1/user/announce-key/status",
+    tag = "user",
+    responses(
+        (status = 200, description = "The announce key status", body = TrackerKeyStatus),
+        (status = 400, description = "The tracker is not private", body = ErrorResponseData),
+        (status = 401, description = "The user is not logged in", body = ErrorResponseData),
+        (status = 503, description = "The tracker is not available", body = ErrorResponseData),
+    ),
+    security(("bearer_auth" = []))
+)]
+#[allow(clippy::unused_async)]
+pub async fn get_announce_key_status_handler(
+    State(app_data): State<Arc<AppData>>,
+    ExtractOptionalLoggedInUser(maybe_user_id): ExtractOptionalLoggedInUser,
+) -> Response {
+    match app_data.announce_key_service.get_status(maybe_user_id).await {
+        Ok(status) => Json(OkResponseData { data: status }).into_response(),
+        Err(error) => error.into_response(),
+    }
+}
+
+/// It revokes the logged-in user's announce key and issues a new one. The
+/// torrent files downloaded from now on include the new key.
+///
+/// # Errors
+///
+/// It returns an error if:
+///
+/// - The user is not logged in.
+/// - The tracker is not private.
+/// - The tracker API requests fail.
+#[utoipa::path(
+    post,
+    path = "/v1/user/announce-key/regenerate",
+    tag = "user",
+    responses(
+        (status = 200, description = "The new announce key", body = RegeneratedTrackerKey),
+        (status = 400, description = "The tracker is not private", body = ErrorResponseData),
+        (status = 401, description = "The user is not logged in", body = ErrorResponseData),
+        (status = 503, description = "The tracker is not available", body = ErrorResponseData),
+    ),
+    security(("bearer_auth" = []))
+)]
+#[allow(clippy::unused_async)]
+pub async fn regenerate_announce_key_handler(
+    State(app_data): State<Arc<AppData>>,
+    ExtractOptionalLoggedInUser(maybe_user_id): ExtractOptionalLoggedInUser,
+) -> Response {
+    match app_data.announce_key_service.regenerate(maybe_user_id).await {
+        Ok(tracker_key) => Json(OkResponseData { data: tracker_key }).into_response(),
+        Err(error) => error.into_response(),
+    }
+}
+
 /// It bans a user from the index.
 ///
 /// # Errors
@@ -158,6 +658,21 @@ pub async fn change_password_handler(
 ///
 /// - The JWT provided by the banning authority was not valid.
 /// - The user could not be banned: it does not exist, etcetera.
+#[utoipa::path(
+    delete,
+    path = "/v1/user/ban/{user}",
+    tag = "user",
+    params(
+        ("user" = String, Path, description = "The username"),
+    ),
+    responses(
+        (status = 200, description = "The user was banned", body = MessageResponseData),
+        (status = 401, description = "The user is not logged in", body = ErrorResponseData),
+        (status = 403, description = "The user does not have permissions to perform the action", body = ErrorResponseData),
+        (status = 404, description = "The resource does not exist", body = ErrorResponseData),
+    ),
+    security(("bearer_auth" = []))
+)]
 #[allow(clippy::unused_async)]
 pub async fn ban_handler(
     State(app_data): State<Arc<AppData>>,
@@ -176,7 +691,7 @@ pub async fn ban_handler(
 }
 
 /// It returns the base API URL without the port. For example: `http://localhost`.
-fn api_base_url(host: &str) -> String {
+pub(crate) fn api_base_url(host: &str) -> String {
     // HTTPS is not supported yet.
     // See https://github.com/torrust/torrust-index/issues/131
     format!("http://{host}")
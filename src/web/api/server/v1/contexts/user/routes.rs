@@ -7,10 +7,13 @@ use axum::routing::{delete, get, post};
 use axum::Router;
 
 use super::handlers::{
-    ban_handler, change_password_handler, email_verification_handler, login_handler, registration_handler, renew_token_handler,
-    verify_token_handler,
+    ban_handler, change_email_handler, change_password_handler, email_change_confirmation_handler, email_unsubscribe_handler,
+    email_verification_handler, get_announce_key_status_handler, get_api_usage_handler, get_profile_handler,
+    get_sessions_handler, login_handler, regenerate_announce_key_handler, registration_handler, renew_token_handler,
+    resend_verification_email_handler, revoke_session_handler, update_profile_handler, verify_token_handler,
 };
 use crate::common::AppData;
+use crate::web::api::server::v1::contexts::{bookmark, collection, saved_search, subscription};
 
 /// Routes for the [`user`](crate::web::api::server::v1::contexts::user) API context.
 pub fn router(app_data: Arc<AppData>) -> Router {
@@ -25,16 +28,61 @@ pub fn router(app_data: Arc<AppData>) -> Router {
             "/email/verify/:token",
             get(email_verification_handler).with_state(app_data.clone()),
         )
+        .route(
+            "/email/unsubscribe/:token",
+            get(email_unsubscribe_handler).with_state(app_data.clone()),
+        )
+        .route(
+            "/email/resend-verification",
+            post(resend_verification_email_handler).with_state(app_data.clone()),
+        )
+        .route("/email/change", post(change_email_handler).with_state(app_data.clone()))
+        .route(
+            "/email/change/:token",
+            get(email_change_confirmation_handler).with_state(app_data.clone()),
+        )
         // Authentication
         .route("/login", post(login_handler).with_state(app_data.clone()))
         .route("/token/verify", post(verify_token_handler).with_state(app_data.clone()))
         .route("/token/renew", post(renew_token_handler).with_state(app_data.clone()))
+        // Sessions
+        .route("/sessions", get(get_sessions_handler).with_state(app_data.clone()))
+        .route(
+            "/sessions/:session_id",
+            delete(revoke_session_handler).with_state(app_data.clone()),
+        )
         // Profile
         .route(
             "/:user/change-password",
             post(change_password_handler).with_state(app_data.clone()),
         )
+        .route(
+            "/:user/profile",
+            get(get_profile_handler)
+                .with_state(app_data.clone())
+                .put(update_profile_handler)
+                .with_state(app_data.clone()),
+        )
+        // API usage
+        .route("/api-usage", get(get_api_usage_handler).with_state(app_data.clone()))
+        // Announce key
+        .route(
+            "/announce-key/status",
+            get(get_announce_key_status_handler).with_state(app_data.clone()),
+        )
+        .route(
+            "/announce-key/regenerate",
+            post(regenerate_announce_key_handler).with_state(app_data.clone()),
+        )
         // User ban
         // code-review: should not this be a POST method? We add the user to the blacklist. We do not delete the user.
-        .route("/ban/:user", delete(ban_handler).with_state(app_data))
+        .route("/ban/:user", delete(ban_handler).with_state(app_data.clone()))
+        // Bookmarks
+        .nest("/bookmarks", bookmark::routes::router_for_user(app_data.clone()))
+        // Subscriptions
+        .nest("/subscriptions", subscription::routes::router_for_user(app_data.clone()))
+        // Saved searches
+        .nest("/saved-searches", saved_search::routes::router_for_user(app_data.clone()))
+        // Collections
+        .nest("/collections", collection::routes::router_for_user(app_data))
 }
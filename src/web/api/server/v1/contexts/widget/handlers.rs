@@ -0,0 +1,130 @@
+//! API handlers for the the [`widget`](crate::web::api::server::v1::contexts::widget) API
+//! context.
+use std::sync::Arc;
+
+use axum::extract::{Host, Query, State};
+use axum::http::header;
+use axum::response::{IntoResponse, Response};
+use axum::Json;
+
+use crate::common::AppData;
+use crate::errors::ServiceError;
+use crate::services::widget::{is_valid_callback, render_html, WidgetRequest, WidgetTorrent};
+use crate::web::api::server::v1::contexts::user::handlers::api_base_url;
+use crate::web::api::server::v1::responses::{ErrorResponseData, OkResponseData};
+
+/// The widget pages only load inline styles and can be framed by any site.
+const HTML_CONTENT_SECURITY_POLICY: &str = "default-src 'none'; style-src 'unsafe-inline'; frame-ancestors *";
+
+/// It returns the latest torrents as JSON, or as JSONP when the request
+/// includes a `callback`.
+///
+/// # Errors
+///
+/// It returns an error if:
+///
+/// - The widgets are disabled.
+/// - The callback is not a valid function name.
+/// - There is a database error.
+#[utoipa::path(
+    get,
+    path = "/v1/widget/torrents",
+    tag = "widget",
+    params(WidgetRequest),
+    responses(
+        (status = 200, description = "The latest torrents", body = [WidgetTorrent]),
+        (status = 400, description = "The request is not valid", body = ErrorResponseData),
+        (status = 403, description = "The widgets are disabled", body = ErrorResponseData),
+    ),
+    security(())
+)]
+#[allow(clippy::unused_async)]
+#[allow(clippy::missing_panics_doc)]
+pub async fn get_latest_torrents_handler(
+    State(app_data): State<Arc<AppData>>,
+    Host(host_from_header): Host,
+    Query(request): Query<WidgetRequest>,
+) -> Response {
+    if let Some(callback) = &request.callback {
+        if !is_valid_callback(callback) {
+            return ServiceError::InvalidJsonpCallback.into_response();
+        }
+    }
+
+    let base_url = app_data
+        .cfg
+        .get_api_base_url()
+        .await
+        .unwrap_or(api_base_url(&host_from_header));
+
+    let torrents = match app_data.widget_service.get_latest_torrents(&request, &base_url).await {
+        Ok(torrents) => torrents,
+        Err(error) => return error.into_response(),
+    };
+
+    let cors = (header::ACCESS_CONTROL_ALLOW_ORIGIN, "*");
+
+    match request.callback {
+        Some(callback) => {
+            let json = serde_json::to_string(&OkResponseData { data: torrents }).expect("widget torrents should be serializable");
+
+            (
+                [
+                    cors,
+                    (header::CONTENT_TYPE, "application/javascript; charset=utf-8"),
+                    (header::X_CONTENT_TYPE_OPTIONS, "nosniff"),
+                ],
+                // The comment prevents some content sniffing attacks
+                format!("/**/{callback}({json});"),
+            )
+                .into_response()
+        }
+        None => ([cors], Json(OkResponseData { data: torrents })).into_response(),
+    }
+}
+
+/// It returns the latest torrents as a minimal HTML page that can be
+/// embedded in an `<iframe>`.
+///
+/// # Errors
+///
+/// It returns an error if:
+///
+/// - The widgets are disabled.
+/// - There is a database error.
+#[utoipa::path(
+    get,
+    path = "/v1/widget/torrents/html",
+    tag = "widget",
+    params(WidgetRequest),
+    responses(
+        (status = 200, description = "The latest torrents", content_type = "text/html"),
+        (status = 403, description = "The widgets are disabled", body = ErrorResponseData),
+    ),
+    security(())
+)]
+#[allow(clippy::unused_async)]
+pub async fn get_latest_torrents_html_handler(
+    State(app_data): State<Arc<AppData>>,
+    Host(host_from_header): Host,
+    Query(request): Query<WidgetRequest>,
+) -> Response {
+    let base_url = app_data
+        .cfg
+        .get_api_base_url()
+        .await
+        .unwrap_or(api_base_url(&host_from_header));
+
+    match app_data.widget_service.get_latest_torrents(&request, &base_url).await {
+        Ok(torrents) => (
+            [
+                (header::ACCESS_CONTROL_ALLOW_ORIGIN, "*"),
+                (header::CONTENT_TYPE, "text/html; charset=utf-8"),
+                (header::CONTENT_SECURITY_POLICY, HTML_CONTENT_SECURITY_POLICY),
+            ],
+            render_html(&torrents),
+        )
+            .into_response(),
+        Err(error) => error.into_response(),
+    }
+}
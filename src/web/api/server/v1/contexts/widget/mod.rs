@@ -0,0 +1,73 @@
+//! API context: `widget`.
+//!
+//! This API context serves the widgets community sites can embed to show
+//! the latest torrents of the index. Refer to the
+//! [`widget`](crate::services::widget) service for more information.
+//!
+//! Widget responses include the `Access-Control-Allow-Origin: *` header, so
+//! they can be requested from any site. The widgets can be disabled with
+//! the `api.widgets` option.
+//!
+//! # Endpoints
+//!
+//! - [Latest torrents as JSON](#latest-torrents-as-json)
+//! - [Latest torrents as HTML](#latest-torrents-as-html)
+//!
+//! # Latest torrents as JSON
+//!
+//! `GET /v1/widget/torrents`
+//!
+//! It returns the latest approved torrents.
+//!
+//! **Query parameters**
+//!
+//! Name | Type | Description | Required | Example
+//! ---|---|---|---|---
+//! `category` | `String` | Only torrents in this category | No | `movies`
+//! `limit` | `u8` | Number of torrents. Default to 10, max 50 | No | `5`
+//! `callback` | `String` | JavaScript function to call with the JSON (JSONP) | No | `renderTorrents`
+//!
+//! **Example request**
+//!
+//! ```bash
+//! curl "http://127.0.0.1:3001/v1/widget/torrents?category=movies&limit=1"
+//! ```
+//!
+//! **Example response** `200`
+//!
+//! ```json
+//! {
+//!   "data": [
+//!     {
+//!       "title": "Big Buck Bunny",
+//!       "info_hash": "5452869be36f9f3350ccee6b4544e7e76caaadab",
+//!       "file_size": 276134947,
+//!       "seeders": 12,
+//!       "leechers": 1,
+//!       "date_uploaded": "2024-10-17 10:20:00",
+//!       "url": "http://127.0.0.1:3001/t/krjing7dn6ptgugm5zvukrhh45wkvlnl"
+//!     }
+//!   ]
+//! }
+//! ```
+//!
+//! With a `callback` the response is JavaScript that calls the function with
+//! the same JSON:
+//!
+//! ```javascript
+//! /**/renderTorrents({"data":[...]});
+//! ```
+//!
+//! # Latest torrents as HTML
+//!
+//! `GET /v1/widget/torrents/html`
+//!
+//! It returns a minimal HTML page with the latest approved torrents. It
+//! accepts the same `category` and `limit` parameters. The page can be
+//! embedded in an `<iframe>`:
+//!
+//! ```html
+//! <iframe src="http://127.0.0.1:3001/v1/widget/torrents/html?category=movies&limit=5"></iframe>
+//! ```
+pub mod handlers;
+pub mod routes;
@@ -0,0 +1,17 @@
+//! API routes for the [`widget`](crate::web::api::server::v1::contexts::widget) API context.
+//!
+//! Refer to the [API endpoint documentation](crate::web::api::server::v1::contexts::widget).
+use std::sync::Arc;
+
+use axum::routing::get;
+use axum::Router;
+
+use super::handlers::{get_latest_torrents_handler, get_latest_torrents_html_handler};
+use crate::common::AppData;
+
+/// Routes for the [`widget`](crate::web::api::server::v1::contexts::widget) API context.
+pub fn router(app_data: Arc<AppData>) -> Router {
+    Router::new()
+        .route("/torrents", get(get_latest_torrents_handler).with_state(app_data.clone()))
+        .route("/torrents/html", get(get_latest_torrents_html_handler).with_state(app_data))
+}
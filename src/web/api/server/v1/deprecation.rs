@@ -0,0 +1,317 @@
+//! Deprecation of API endpoints and query parameters.
+//!
+//! Deprecated surfaces are declared in [`DEPRECATIONS`]. Responses to
+//! requests using them include the headers:
+//!
+//! - `Deprecation`: the date since the surface is deprecated ([RFC 9745](https://www.rfc-editor.org/rfc/rfc9745)).
+//! - `Sunset`: the date after which the surface may be removed ([RFC 8594](https://www.rfc-editor.org/rfc/rfc8594)).
+//! - `Link`: a link, with the `deprecation` relation type, to the migration
+//!   guide.
+//!
+//! For example:
+//!
+//! ```text
+//! Deprecation: @1729036800
+//! Sunset: Wed, 01 Jan 2025 00:00:00 GMT
+//! Link: <https://example.com/migration>; rel="deprecation"
+//! ```
+//!
+//! The number of requests to each deprecated surface is exposed in the
+//! `/metrics` endpoint as `torrust_index_deprecated_requests_total`, so
+//! maintainers can check nobody uses a surface anymore before removing it.
+use std::fmt::Write;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Arc;
+
+use axum::extract::{Request, State};
+use axum::http::header::LINK;
+use axum::http::{HeaderName, HeaderValue, Method};
+use axum::middleware::Next;
+use axum::response::Response;
+use chrono::{NaiveDate, NaiveTime};
+
+use crate::common::AppData;
+
+/// Deprecated API surfaces.
+///
+/// To deprecate an endpoint or a query parameter add it here. It should only
+/// be removed from the API, and from this list, after the sunset date.
+pub const DEPRECATIONS: &[Deprecation] = &[];
+
+/// A deprecated endpoint, or a deprecated query parameter of an endpoint.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Deprecation {
+    pub method: Method,
+    /// Path pattern using the router syntax, for example
+    /// `/v1/torrent/:info_hash`.
+    pub path: &'static str,
+    /// The deprecated query parameter. When `None`, the whole endpoint is
+    /// deprecated.
+    pub param: Option<&'static str>,
+    /// Date (`YYYY-MM-DD`) since the surface is deprecated.
+    pub since: &'static str,
+    /// Date (`YYYY-MM-DD`) after which the surface may be removed.
+    pub sunset: Option<&'static str>,
+    /// URL of the documentation explaining how to migrate.
+    pub link: Option<&'static str>,
+}
+
+impl Deprecation {
+    /// Name of the surface used in the metrics. For example:
+    /// `GET /v1/torrents?tags`.
+    #[must_use]
+    pub fn surface(&self) -> String {
+        match self.param {
+            Some(param) => format!("{} {}?{param}", self.method, self.path),
+            None => format!("{} {}", self.method, self.path),
+        }
+    }
+
+    fn matches(&self, method: &Method, path: &str, query: Option<&str>) -> bool {
+        if *method != self.method || !path_matches(self.path, path) {
+            return false;
+        }
+
+        match self.param {
+            Some(param) => query.is_some_and(|query| has_param(query, param)),
+            None => true,
+        }
+    }
+
+    fn headers(&self) -> Vec<(HeaderName, HeaderValue)> {
+        let mut headers = vec![];
+
+        if let Some(since) = timestamp(self.since) {
+            if let Ok(value) = HeaderValue::from_str(&format!("@{since}")) {
+                headers.push((HeaderName::from_static("deprecation"), value));
+            }
+        }
+
+        if let Some(sunset) = self.sunset.and_then(http_date) {
+            if let Ok(value) = HeaderValue::from_str(&sunset) {
+                headers.push((HeaderName::from_static("sunset"), value));
+            }
+        }
+
+        if let Some(link) = self.link {
+            if let Ok(value) = HeaderValue::from_str(&format!("<{link}>; rel=\"deprecation\"")) {
+                headers.push((LINK, value));
+            }
+        }
+
+        headers
+    }
+}
+
+/// Whether the request path matches the path pattern. Segments starting with
+/// `:` match any value and a trailing `*` matches any remaining segments.
+pub(crate) fn path_matches(pattern: &str, path: &str) -> bool {
+    let (pattern, any_suffix) = match pattern.strip_suffix("/*") {
+        Some(prefix) => (prefix, true),
+        None => (pattern, false),
+    };
+
+    let pattern = pattern.trim_end_matches('/').split('/');
+    let path = path.trim_end_matches('/').split('/');
+
+    let (pattern_segments, path_segments) = (pattern.clone().count(), path.clone().count());
+
+    let length_matches = if any_suffix {
+        path_segments >= pattern_segments
+    } else {
+        path_segments == pattern_segments
+    };
+
+    length_matches
+        && pattern
+            .zip(path)
+            .all(|(expected, actual)| expected.starts_with(':') || expected == actual)
+}
+
+fn has_param(query: &str, param: &str) -> bool {
+    query
+        .split('&')
+        .map(|pair| pair.split_once('=').map_or(pair, |(name, _)| name))
+        .any(|name| name == param)
+}
+
+fn midnight(date: &str) -> Option<chrono::NaiveDateTime> {
+    NaiveDate::parse_from_str(date, "%Y-%m-%d")
+        .ok()
+        .map(|date| date.and_time(NaiveTime::MIN))
+}
+
+fn timestamp(date: &str) -> Option<i64> {
+    midnight(date).map(|datetime| datetime.and_utc().timestamp())
+}
+
+fn http_date(date: &str) -> Option<String> {
+    midnight(date).map(|datetime| datetime.format("%a, %d %b %Y %H:%M:%S GMT").to_string())
+}
+
+/// The deprecated surfaces and their usage since the application started.
+pub struct Deprecations {
+    deprecations: &'static [Deprecation],
+    usage: Vec<AtomicU64>,
+}
+
+impl Default for Deprecations {
+    fn default() -> Self {
+        Self::new(DEPRECATIONS)
+    }
+}
+
+impl Deprecations {
+    #[must_use]
+    pub fn new(deprecations: &'static [Deprecation]) -> Self {
+        Self {
+            deprecations,
+            usage: deprecations.iter().map(|_| AtomicU64::new(0)).collect(),
+        }
+    }
+
+    fn find(&self, method: &Method, path: &str, query: Option<&str>) -> Option<usize> {
+        self.deprecations
+            .iter()
+            .position(|deprecation| deprecation.matches(method, path, query))
+    }
+
+    /// Number of requests to each deprecated surface.
+    #[must_use]
+    pub fn usage(&self) -> Vec<(String, u64)> {
+        self.deprecations
+            .iter()
+            .zip(&self.usage)
+            .map(|(deprecation, counter)| (deprecation.surface(), counter.load(Ordering::Relaxed)))
+            .collect()
+    }
+
+    /// Usage counters in the Prometheus text format.
+    #[must_use]
+    pub fn to_prometheus(&self) -> String {
+        let mut metrics = String::new();
+
+        let name = "torrust_index_deprecated_requests_total";
+
+        let _ = writeln!(metrics, "# HELP {name} Number of requests to deprecated API surfaces.");
+        let _ = writeln!(metrics, "# TYPE {name} counter");
+        for (surface, requests) in self.usage() {
+            let surface = surface.replace('\\', "\\\\").replace('"', "\\\"");
+            let _ = writeln!(metrics, "{name}{{surface=\"{surface}\"}} {requests}");
+        }
+
+        metrics
+    }
+}
+
+/// Middleware that adds the deprecation headers to responses for deprecated
+/// surfaces and counts their usage.
+pub async fn deprecation_middleware(State(app_data): State<Arc<AppData>>, request: Request, next: Next) -> Response {
+    let deprecations = &app_data.deprecations;
+
+    let Some(index) = deprecations.find(request.method(), request.uri().path(), request.uri().query()) else {
+        return next.run(request).await;
+    };
+
+    deprecations.usage[index].fetch_add(1, Ordering::Relaxed);
+
+    let mut response = next.run(request).await;
+
+    for (name, value) in deprecations.deprecations[index].headers() {
+        response.headers_mut().append(name, value);
+    }
+
+    response
+}
+
+#[cfg(test)]
+mod tests {
+    use axum::http::Method;
+
+    use super::{Deprecation, Deprecations};
+
+    const TAGS_PARAM: Deprecation = Deprecation {
+        method: Method::GET,
+        path: "/v1/torrents",
+        param: Some("tags"),
+        since: "2024-10-16",
+        sunset: Some("2025-01-01"),
+        link: Some("https://example.com/migration"),
+    };
+
+    const TORRENT_ENDPOINT: Deprecation = Deprecation {
+        method: Method::DELETE,
+        path: "/v1/torrent/:info_hash",
+        param: None,
+        since: "2024-10-16",
+        sunset: None,
+        link: None,
+    };
+
+    static DEPRECATIONS: &[Deprecation] = &[TAGS_PARAM, TORRENT_ENDPOINT];
+
+    #[test]
+    fn it_should_match_deprecated_query_params() {
+        let deprecations = Deprecations::new(DEPRECATIONS);
+
+        assert_eq!(
+            deprecations.find(&Method::GET, "/v1/torrents", Some("page=0&tags=linux")),
+            Some(0)
+        );
+        assert_eq!(deprecations.find(&Method::GET, "/v1/torrents", Some("page=0")), None);
+        assert_eq!(deprecations.find(&Method::GET, "/v1/torrents", None), None);
+    }
+
+    #[test]
+    fn it_should_match_deprecated_endpoints_with_path_params() {
+        let deprecations = Deprecations::new(DEPRECATIONS);
+
+        assert_eq!(
+            deprecations.find(&Method::DELETE, "/v1/torrent/5452869be36f9f3350ccee6b4544e7e76caaadab", None),
+            Some(1)
+        );
+        assert_eq!(
+            deprecations.find(&Method::GET, "/v1/torrent/5452869be36f9f3350ccee6b4544e7e76caaadab", None),
+            None
+        );
+        assert_eq!(
+            deprecations.find(
+                &Method::DELETE,
+                "/v1/torrent/5452869be36f9f3350ccee6b4544e7e76caaadab/comments",
+                None
+            ),
+            None
+        );
+    }
+
+    #[test]
+    fn it_should_build_the_deprecation_headers() {
+        let headers: Vec<(String, String)> = TAGS_PARAM
+            .headers()
+            .into_iter()
+            .map(|(name, value)| (name.to_string(), value.to_str().unwrap().to_string()))
+            .collect();
+
+        assert_eq!(
+            headers,
+            vec![
+                ("deprecation".to_string(), "@1729036800".to_string()),
+                ("sunset".to_string(), "Wed, 01 Jan 2025 00:00:00 GMT".to_string()),
+                (
+                    "link".to_string(),
+                    "<https://example.com/migration>; rel=\"deprecation\"".to_string()
+                ),
+            ]
+        );
+    }
+
+    #[test]
+    fn it_should_export_the_usage_of_each_surface_in_the_prometheus_format() {
+        let deprecations = Deprecations::new(DEPRECATIONS);
+
+        let metrics = deprecations.to_prometheus();
+
+        assert!(metrics.contains("torrust_index_deprecated_requests_total{surface=\"GET /v1/torrents?tags\"} 0"));
+        assert!(metrics.contains("torrust_index_deprecated_requests_total{surface=\"DELETE /v1/torrent/:info_hash\"} 0"));
+    }
+}
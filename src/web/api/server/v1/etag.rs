@@ -0,0 +1,265 @@
+//! Conditional requests support.
+//!
+//! Responses that are polled by the frontends, like the torrent listing, the
+//! torrent details and the torrent files, include a weak `ETag` and a
+//! `Last-Modified` header. Clients can send them back in the `If-None-Match`
+//! or `If-Modified-Since` headers to get an empty `304 Not Modified` response
+//! when the data has not changed.
+//!
+//! The listing tag is derived from a cheap data version and the request, not
+//! from the response body, so the response does not need to be generated to
+//! answer a conditional request. The other tags are derived from the body.
+//!
+//! Torrents do not have a modification date, so the `Last-Modified` date is
+//! the time this process first served the current `ETag` of the resource.
+//! See [`ModificationTimes`].
+use std::collections::HashMap;
+use std::sync::Mutex;
+
+use axum::response::{IntoResponse, Response};
+use chrono::{DateTime, Utc};
+use hyper::{header, HeaderMap, StatusCode};
+use sha2::{Digest, Sha256};
+
+/// Number of bytes of the hash included in the tag.
+const TAG_LENGTH: usize = 16;
+
+/// Maximum number of resources with a tracked modification time.
+const MAX_TRACKED_RESOURCES: usize = 10_000;
+
+/// Format of the HTTP dates (IMF-fixdate).
+const HTTP_DATE_FORMAT: &str = "%a, %d %b %Y %H:%M:%S GMT";
+
+/// It builds a weak `ETag` from the values the response depends on.
+#[must_use]
+pub fn weak_etag(parts: &[&[u8]]) -> String {
+    let mut hasher = Sha256::new();
+
+    for part in parts {
+        // The length prefix avoids collisions between different splits.
+        hasher.update((part.len() as u64).to_be_bytes());
+        hasher.update(part);
+    }
+
+    format!("W/\"{}\"", hex::encode(&hasher.finalize()[..TAG_LENGTH]))
+}
+
+/// The time each resource got its current `ETag`.
+///
+/// The times are kept in memory. When there are too many resources, they are
+/// all forgotten and their times restart, which only makes clients download
+/// the resources again.
+#[derive(Default)]
+pub struct ModificationTimes {
+    resources: Mutex<HashMap<String, (String, DateTime<Utc>)>>,
+}
+
+impl ModificationTimes {
+    /// It returns the time the resource got the `ETag`. It's the current time
+    /// the first time the `ETag` is seen for the resource.
+    ///
+    /// # Panics
+    ///
+    /// It panics if the lock is poisoned.
+    pub fn last_modified(&self, resource: &str, etag: &str) -> DateTime<Utc> {
+        let mut resources = self.resources.lock().expect("modification times lock should not be poisoned");
+
+        if let Some((current_etag, last_modified)) = resources.get(resource) {
+            if current_etag == etag {
+                return *last_modified;
+            }
+        }
+
+        if resources.len() >= MAX_TRACKED_RESOURCES && !resources.contains_key(resource) {
+            resources.clear();
+        }
+
+        let last_modified = Utc::now();
+
+        resources.insert(resource.to_string(), (etag.to_string(), last_modified));
+
+        last_modified
+    }
+}
+
+/// Whether the client copy of the resource is still valid, so the request
+/// can be answered with `304 Not Modified`.
+///
+/// As required by RFC 9110, `If-Modified-Since` is ignored when the request
+/// includes `If-None-Match`.
+#[must_use]
+pub fn is_not_modified(headers: &HeaderMap, etag: &str, last_modified: &DateTime<Utc>) -> bool {
+    if headers.contains_key(header::IF_NONE_MATCH) {
+        return if_none_match(headers, etag);
+    }
+
+    if_modified_since(headers, last_modified)
+}
+
+/// Whether the `If-None-Match` request header matches the `ETag`.
+///
+/// It uses the weak comparison function, as required for `If-None-Match`.
+#[must_use]
+pub fn if_none_match(headers: &HeaderMap, etag: &str) -> bool {
+    let Some(value) = headers.get(header::IF_NONE_MATCH).and_then(|value| value.to_str().ok()) else {
+        return false;
+    };
+
+    let opaque_tag = |tag: &str| tag.trim().trim_start_matches("W/").to_string();
+
+    let etag = opaque_tag(etag);
+
+    value.split(',').any(|tag| tag.trim() == "*" || opaque_tag(tag) == etag)
+}
+
+/// Whether the resource was not modified after the `If-Modified-Since`
+/// request header date. Invalid dates are ignored.
+#[must_use]
+pub fn if_modified_since(headers: &HeaderMap, last_modified: &DateTime<Utc>) -> bool {
+    let Some(since) = headers
+        .get(header::IF_MODIFIED_SINCE)
+        .and_then(|value| value.to_str().ok())
+        .and_then(|value| DateTime::parse_from_rfc2822(value).ok())
+    else {
+        return false;
+    };
+
+    // HTTP dates do not have sub-second precision.
+    last_modified.timestamp() <= since.timestamp()
+}
+
+/// It formats a date as an HTTP date.
+#[must_use]
+pub fn http_date(date: &DateTime<Utc>) -> String {
+    date.format(HTTP_DATE_FORMAT).to_string()
+}
+
+/// It returns the `304 Not Modified` response.
+#[must_use]
+pub fn not_modified(etag: &str, last_modified: &DateTime<Utc>) -> Response {
+    (
+        StatusCode::NOT_MODIFIED,
+        [
+            (header::ETAG, etag.to_string()),
+            (header::LAST_MODIFIED, http_date(last_modified)),
+        ],
+    )
+        .into_response()
+}
+
+/// It adds the `ETag` and `Last-Modified` headers to a successful response.
+#[must_use]
+pub fn with_validators(mut response: Response, etag: &str, last_modified: &DateTime<Utc>) -> Response {
+    if response.status().is_success() {
+        if let Ok(value) = etag.parse() {
+            response.headers_mut().insert(header::ETAG, value);
+        }
+
+        if let Ok(value) = http_date(last_modified).parse() {
+            response.headers_mut().insert(header::LAST_MODIFIED, value);
+        }
+    }
+
+    response
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::{TimeDelta, TimeZone, Utc};
+    use hyper::{header, HeaderMap};
+
+    use super::{http_date, if_none_match, is_not_modified, weak_etag, ModificationTimes};
+
+    fn headers_with_if_none_match(value: &str) -> HeaderMap {
+        let mut headers = HeaderMap::new();
+        headers.insert(header::IF_NONE_MATCH, value.parse().unwrap());
+        headers
+    }
+
+    fn headers_with_if_modified_since(value: &str) -> HeaderMap {
+        let mut headers = HeaderMap::new();
+        headers.insert(header::IF_MODIFIED_SINCE, value.parse().unwrap());
+        headers
+    }
+
+    #[test]
+    fn it_should_build_different_tags_for_different_values() {
+        assert_eq!(weak_etag(&[b"1", b"page=0"]), weak_etag(&[b"1", b"page=0"]));
+        assert_ne!(weak_etag(&[b"1", b"page=0"]), weak_etag(&[b"2", b"page=0"]));
+        assert_ne!(weak_etag(&[b"1", b"2"]), weak_etag(&[b"12", b""]));
+    }
+
+    #[test]
+    fn it_should_build_weak_tags() {
+        assert!(weak_etag(&[b"1"]).starts_with("W/\""));
+    }
+
+    #[test]
+    fn it_should_match_the_if_none_match_header_using_the_weak_comparison() {
+        let etag = weak_etag(&[b"1"]);
+        let strong_etag = etag.trim_start_matches("W/");
+
+        assert!(if_none_match(&headers_with_if_none_match(&etag), &etag));
+        assert!(if_none_match(&headers_with_if_none_match(strong_etag), &etag));
+        assert!(if_none_match(
+            &headers_with_if_none_match(&format!("\"other\", {etag}")),
+            &etag
+        ));
+        assert!(if_none_match(&headers_with_if_none_match("*"), &etag));
+    }
+
+    #[test]
+    fn it_should_not_match_a_different_tag_or_a_missing_header() {
+        let etag = weak_etag(&[b"1"]);
+
+        assert!(!if_none_match(&headers_with_if_none_match(&weak_etag(&[b"2"])), &etag));
+        assert!(!if_none_match(&HeaderMap::new(), &etag));
+    }
+    #[test]
+    fn it_should_format_http_dates() {
+        let date = Utc.with_ymd_and_hms(2024, 10, 17, 8, 5, 3).unwrap();
+
+        assert_eq!(http_date(&date), "Thu, 17 Oct 2024 08:05:03 GMT");
+    }
+
+    #[test]
+    fn it_should_use_if_modified_since_only_without_if_none_match() {
+        let etag = weak_etag(&[b"1"]);
+        let last_modified = Utc.with_ymd_and_hms(2024, 10, 17, 8, 5, 3).unwrap();
+
+        assert!(is_not_modified(
+            &headers_with_if_modified_since(&http_date(&last_modified)),
+            &etag,
+            &last_modified
+        ));
+        assert!(!is_not_modified(
+            &headers_with_if_modified_since(&http_date(&(last_modified - TimeDelta::seconds(1)))),
+            &etag,
+            &last_modified
+        ));
+        assert!(!is_not_modified(
+            &headers_with_if_modified_since("yesterday"),
+            &etag,
+            &last_modified
+        ));
+
+        let mut headers = headers_with_if_modified_since(&http_date(&last_modified));
+        headers.insert(header::IF_NONE_MATCH, weak_etag(&[b"2"]).parse().unwrap());
+
+        assert!(!is_not_modified(&headers, &etag, &last_modified));
+    }
+
+    #[test]
+    fn it_should_keep_the_modification_time_while_the_etag_does_not_change() {
+        let modification_times = ModificationTimes::default();
+
+        let first = modification_times.last_modified("torrent/1", "W/\"a\"");
+
+        assert_eq!(modification_times.last_modified("torrent/1", "W/\"a\""), first);
+        assert!(modification_times.last_modified("torrent/1", "W/\"b\"") >= first);
+        assert_ne!(
+            modification_times.resources.lock().unwrap().get("torrent/1").unwrap().0,
+            "W/\"a\""
+        );
+    }
+}
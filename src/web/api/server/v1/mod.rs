@@ -4,8 +4,29 @@
 //!
 //! Refer to the [`contexts`] module for more
 //! information.
+//!
+//! Deprecated endpoints and query parameters are announced with the
+//! `Deprecation` and `Sunset` response headers. Refer to the [`deprecation`]
+//! module for more information.
+//!
+//! Clients can ask for bare responses without the `{"data": ...}` envelope
+//! or `camelCase` field names. Refer to the [`response_format`] module for
+//! more information.
+//!
+//! The index can serve an `OpenAPI` specification generated from the handlers
+//! and a Swagger UI. Refer to the [`openapi`] module for more information.
+//!
+//! Requests can include the W3C `traceparent` and `tracestate` headers. The
+//! index continues the trace and propagates it to the tracker API and the
+//! webhooks. Refer to the [`trace_context`](crate::utils::trace_context)
+//! module for more information.
 pub mod auth;
+pub mod cache_control;
 pub mod contexts;
+pub mod deprecation;
+pub mod etag;
 pub mod extractors;
+pub mod openapi;
+pub mod response_format;
 pub mod responses;
 pub mod routes;
@@ -0,0 +1,467 @@
+//! `OpenAPI` specification of the v1 API.
+//!
+//! The specification is generated from the annotations on the handlers, so
+//! it uses the same forms, query parameters and response types the handlers
+//! use. When the `api.openapi` option is enabled, the index serves it at
+//! `/v1/openapi.json` and a Swagger UI at `/v1/docs`:
+//!
+//! ```toml
+//! [api]
+//! openapi = true
+//! ```
+//!
+//! The specification can be used to generate API clients.
+//!
+//! > **NOTICE**: only the responses with the default format are documented.
+//! > Refer to the [`response_format`](crate::web::api::server::v1::response_format)
+//! > module for the other formats.
+use std::sync::Arc;
+
+use axum::extract::{Request, State};
+use axum::http::StatusCode;
+use axum::middleware::{self, Next};
+use axum::response::{IntoResponse, Response};
+use axum::Router;
+use utoipa::openapi::security::{HttpAuthScheme, HttpBuilder, SecurityScheme};
+use utoipa::{Modify, OpenApi};
+use utoipa_swagger_ui::SwaggerUi;
+
+use super::contexts::{
+    about, admin, bookmark, category, collection, comment, event, image, proxy, report, saved_search, settings, subscription,
+    sync, tag, torrent, user, widget,
+};
+use super::responses::{
+    CategoriesResponseData, DeletedTorrentResponseData, ErrorResponseData, IdResponseData, MessageResponseData, TagsResponseData,
+    TokenResponseData, TorrentResponseData, TorrentsResponseData, UploadedTorrentResponseData,
+};
+use crate::cache::image::thumbnail::Format as ImageFormat;
+use crate::common::AppData;
+use crate::databases::database::Sorting;
+use crate::models::antispam::{BannedPattern, PatternAction};
+use crate::models::api_usage::{ApiUsageSummary, CredentialUsage, DailyApiUsage, RateLimitCounter};
+use crate::models::audit::{AuditAction, AuditEntry};
+use crate::models::bookmark::Bookmark;
+use crate::models::category::{Category as TorrentCategory, CategoryPolicy};
+use crate::models::category_field::{CategoryField, FieldType, TorrentFieldValue};
+use crate::models::collection::{Collection, SmartFilter};
+use crate::models::domain_event::SeedingSummary;
+use crate::models::email::{Role, Segment};
+use crate::models::image::{DescriptionImage, ImageKind};
+use crate::models::import_session::{ConflictPolicy, ImportSession};
+use crate::models::points::{PointsAdjustment, PointsBalance, SeedingSummaryEvent};
+use crate::models::report::{ReportAction, ReportReason};
+use crate::models::reputation::{ReputationStats, UserReputation};
+use crate::models::response::{
+    AuditLogResponse, DeletedTorrentResponse, MagnetLinkResponse, TokenResponse, TorrentPeer, TorrentPeersResponse,
+    TorrentResponse, TorrentsResponse,
+};
+use crate::models::saved_search::SavedSearch;
+use crate::models::sync::{PartitionChanges, SyncManifest, SyncPartition, SyncRecord};
+use crate::models::torrent::{SimilarTorrent, TorrentListing, TrashedTorrent};
+use crate::models::torrent_attachment::{AttachmentKind, TorrentAttachment};
+use crate::models::torrent_contents::{FileKind, FileNode, FileStats, TorrentContents};
+use crate::models::torrent_duplicate::{DuplicateCluster, DuplicatePair};
+use crate::models::torrent_file::TorrentFile;
+use crate::models::torrent_file_group::{PackFile, TorrentFileGroup, TorrentFileGroupSummary};
+use crate::models::torrent_metadata::TorrentMetadata;
+use crate::models::torrent_preview::TorrentPreview;
+use crate::models::torrent_tag::{TagSynonym, TorrentTag, TrendingTag};
+use crate::models::torrent_web_seed::TorrentWebSeed;
+use crate::models::tracker_key::{RegeneratedTrackerKey, TrackerKeyStatus};
+use crate::models::transfer_stats::{TransferStats, UserTransferStats};
+use crate::models::upload_quota::{UploadLimits, UploadQuota, UploadUsage};
+use crate::models::user::PublicProfile;
+use crate::models::user_session::UserSession;
+use crate::services::antispam::AddBannedPatternRequest;
+use crate::services::bookmark::{BookmarksFile, ImportSummary};
+use crate::services::bulk_import::session::{
+    BatchSummary, BatchTorrent, BatchTorrentResult, CreateImportSessionRequest, ImportBatchRequest, SkippedBatchTorrent,
+};
+use crate::services::bulk_import::ImportRequest;
+use crate::services::bulk_mail::BulkMailRequest;
+use crate::services::collection::CollectionPage;
+use crate::services::image_upload::{ConfirmRequest, PresignRequest};
+use crate::services::saved_search::AddSavedSearchRequest;
+use crate::services::seeding_rewards::AdjustPointsRequest;
+use crate::services::torrent::description_image::UploadedDescriptionImage;
+use crate::services::torrent::{BulkActionKind, BulkActionRequest};
+use crate::services::user::ProfilePage;
+use crate::services::widget::WidgetTorrent;
+
+pub const OPENAPI_JSON_URL: &str = "/v1/openapi.json";
+pub const SWAGGER_UI_URL: &str = "/v1/docs";
+
+#[derive(OpenApi)]
+#[openapi(
+    info(title = "Torrust Index API", description = "The REST API of the Torrust Index."),
+    paths(
+        about::handlers::about_page_handler,
+        about::handlers::license_page_handler,
+        admin::handlers::get_pending_torrents_handler,
+        admin::handlers::approve_torrent_handler,
+        admin::handlers::bulk_action_handler,
+        admin::handlers::reject_torrent_handler,
+        admin::handlers::get_trashed_torrents_handler,
+        admin::handlers::restore_torrent_handler,
+        admin::handlers::purge_torrent_handler,
+        admin::handlers::get_reports_handler,
+        admin::handlers::resolve_report_handler,
+        admin::handlers::get_stats_importer_status_handler,
+        admin::handlers::get_download_statistics_handler,
+        admin::handlers::import_torrents_handler,
+        admin::handlers::create_import_session_handler,
+        admin::handlers::get_import_session_handler,
+        admin::handlers::import_batch_handler,
+        admin::handlers::complete_import_session_handler,
+        admin::handlers::send_bulk_mail_handler,
+        admin::handlers::get_inactivity_report_handler,
+        admin::handlers::add_inactivity_exemption_handler,
+        admin::handlers::remove_inactivity_exemption_handler,
+        admin::handlers::get_torrent_rules_report_handler,
+        admin::handlers::get_dead_torrents_report_handler,
+        admin::handlers::get_banned_patterns_handler,
+        admin::handlers::add_banned_pattern_handler,
+        admin::handlers::delete_banned_pattern_handler,
+        admin::handlers::get_upload_quota_handler,
+        admin::handlers::set_upload_limits_handler,
+        admin::handlers::remove_upload_limits_handler,
+        admin::handlers::get_reputation_handler,
+        admin::handlers::set_trusted_status_handler,
+        admin::handlers::remove_trusted_status_handler,
+        admin::handlers::get_rate_limit_counters_handler,
+        admin::handlers::get_audit_log_handler,
+        admin::handlers::get_seeding_summaries_handler,
+        admin::handlers::get_points_handler,
+        admin::handlers::adjust_points_handler,
+        admin::handlers::reload_settings_handler,
+        bookmark::handlers::add_bookmark_handler,
+        bookmark::handlers::delete_bookmark_handler,
+        bookmark::handlers::get_bookmarks_handler,
+        bookmark::handlers::export_bookmarks_handler,
+        bookmark::handlers::import_bookmarks_handler,
+        category::handlers::get_all_handler,
+        category::handlers::add_handler,
+        category::handlers::delete_handler,
+        category::handlers::get_moderators_handler,
+        category::handlers::add_moderator_handler,
+        category::handlers::remove_moderator_handler,
+        category::handlers::get_policy_handler,
+        category::handlers::set_policy_handler,
+        category::handlers::get_fields_handler,
+        category::handlers::add_field_handler,
+        category::handlers::delete_field_handler,
+        collection::handlers::create_collection_handler,
+        collection::handlers::get_collection_handler,
+        collection::handlers::get_user_collections_handler,
+        collection::handlers::update_collection_handler,
+        collection::handlers::delete_collection_handler,
+        collection::handlers::add_torrent_handler,
+        collection::handlers::remove_torrent_handler,
+        comment::handlers::get_all_handler,
+        comment::handlers::add_handler,
+        comment::handlers::edit_handler,
+        comment::handlers::delete_handler,
+        comment::handlers::moderate_handler,
+        event::handlers::subscribe_handler,
+        image::handlers::presign_upload_handler,
+        image::handlers::confirm_upload_handler,
+        proxy::handlers::get_proxy_image_handler,
+        report::handlers::report_torrent_handler,
+        saved_search::handlers::get_saved_searches_handler,
+        saved_search::handlers::add_saved_search_handler,
+        saved_search::handlers::delete_saved_search_handler,
+        saved_search::handlers::get_results_handler,
+        settings::handlers::get_all_handler,
+        settings::handlers::get_changes_handler,
+        settings::handlers::get_public_handler,
+        settings::handlers::get_site_name_handler,
+        subscription::handlers::get_subscriptions_handler,
+        subscription::handlers::add_subscription_handler,
+        subscription::handlers::delete_subscription_handler,
+        sync::handlers::get_manifest_handler,
+        sync::handlers::get_partition_handler,
+        tag::handlers::get_all_handler,
+        tag::handlers::get_trending_handler,
+        tag::handlers::add_handler,
+        tag::handlers::delete_handler,
+        tag::handlers::merge_handler,
+        tag::handlers::get_synonyms_handler,
+        tag::handlers::add_synonym_handler,
+        tag::handlers::delete_synonym_handler,
+        torrent::handlers::upload_torrent_handler,
+        torrent::handlers::signed_upload_torrent_handler,
+        torrent::handlers::download_torrent_handler,
+        torrent::handlers::get_torrents_handler,
+        torrent::handlers::get_duplicate_torrents_handler,
+        torrent::handlers::get_torrent_info_handler,
+        torrent::handlers::update_torrent_info_handler,
+        torrent::handlers::update_torrent_trackers_handler,
+        torrent::handlers::add_magnet_handler,
+        torrent::handlers::get_magnet_upload_handler,
+        torrent::handlers::delete_torrent_handler,
+        torrent::handlers::create_random_torrent_handler,
+        torrent::handlers::get_magnet_link_handler,
+        torrent::handlers::get_magnet_link_qr_code_handler,
+        torrent::handlers::get_torrent_peers_handler,
+        torrent::handlers::add_torrent_preview_handler,
+        torrent::handlers::get_torrent_previews_handler,
+        torrent::handlers::get_torrent_preview_content_handler,
+        torrent::handlers::upload_description_image_handler,
+        torrent::handlers::get_description_images_handler,
+        torrent::handlers::get_description_image_handler,
+        torrent::handlers::add_torrent_attachment_handler,
+        torrent::handlers::get_torrent_attachments_handler,
+        torrent::handlers::get_torrent_attachment_content_handler,
+        torrent::handlers::delete_torrent_attachment_handler,
+        torrent::handlers::add_torrent_web_seed_handler,
+        torrent::handlers::get_torrent_web_seeds_handler,
+        torrent::handlers::delete_torrent_web_seed_handler,
+        torrent::handlers::enrich_torrent_metadata_handler,
+        torrent::handlers::get_torrent_metadata_handler,
+        torrent::handlers::delete_torrent_metadata_handler,
+        torrent::handlers::get_torrent_file_groups_handler,
+        torrent::handlers::update_torrent_file_groups_handler,
+        torrent::handlers::search_torrent_files_handler,
+        torrent::handlers::short_url_handler,
+        user::handlers::registration_handler,
+        user::handlers::email_verification_handler,
+        user::handlers::email_unsubscribe_handler,
+        user::handlers::resend_verification_email_handler,
+        user::handlers::change_email_handler,
+        user::handlers::email_change_confirmation_handler,
+        user::handlers::login_handler,
+        user::handlers::verify_token_handler,
+        user::handlers::renew_token_handler,
+        user::handlers::change_password_handler,
+        user::handlers::get_profile_handler,
+        user::handlers::update_profile_handler,
+        user::handlers::get_api_usage_handler,
+        user::handlers::get_sessions_handler,
+        user::handlers::revoke_session_handler,
+        user::handlers::get_announce_key_status_handler,
+        user::handlers::regenerate_announce_key_handler,
+        user::handlers::ban_handler,
+        widget::handlers::get_latest_torrents_handler,
+        widget::handlers::get_latest_torrents_html_handler,
+    ),
+    components(schemas(
+        MessageResponseData,
+        IdResponseData,
+        TokenResponseData,
+        CategoriesResponseData,
+        TagsResponseData,
+        TorrentResponseData,
+        TorrentsResponseData,
+        UploadedTorrentResponseData,
+        DeletedTorrentResponseData,
+        ErrorResponseData,
+        admin::forms::ResolveReportForm,
+        admin::forms::TrustedStatusForm,
+        admin::forms::UploadLimitsForm,
+        category::forms::AddCategoryFieldForm,
+        category::forms::AddCategoryForm,
+        category::forms::CategoryPolicyForm,
+        category::responses::Category,
+        collection::forms::CollectionForm,
+        comment::forms::AddCommentForm,
+        comment::forms::EditCommentForm,
+        comment::forms::ModerateCommentForm,
+        report::forms::ReportTorrentForm,
+        subscription::forms::AddSubscriptionForm,
+        tag::forms::AddTagForm,
+        tag::forms::DeleteTagForm,
+        tag::forms::MergeTagsForm,
+        tag::forms::AddTagSynonymForm,
+        tag::forms::DeleteTagSynonymForm,
+        torrent::forms::UpdateTorrentInfoForm,
+        torrent::forms::UpdateTorrentTrackersForm,
+        torrent::forms::UpdateTorrentFileGroupsForm,
+        torrent::forms::AddMagnetForm,
+        torrent::forms::AddWebSeedForm,
+        torrent::forms::EnrichMetadataForm,
+        torrent::forms::UploadTorrentMultipartForm,
+        torrent::forms::UploadPreviewMultipartForm,
+        torrent::forms::UploadDescriptionImageMultipartForm,
+        torrent::forms::UploadAttachmentMultipartForm,
+        torrent::responses::NewTorrentResponseData,
+        user::forms::RegistrationForm,
+        user::forms::LoginForm,
+        user::forms::JsonWebToken,
+        user::forms::ChangePasswordForm,
+        user::forms::ChangeEmailForm,
+        user::forms::UpdateProfileForm,
+        AddBannedPatternRequest,
+        AddSavedSearchRequest,
+        AdjustPointsRequest,
+        ApiUsageSummary,
+        AuditAction,
+        AuditEntry,
+        AttachmentKind,
+        AuditLogResponse,
+        BatchSummary,
+        BatchTorrent,
+        BannedPattern,
+        BatchTorrentResult,
+        Bookmark,
+        BookmarksFile,
+        BulkActionKind,
+        BulkActionRequest,
+        CategoryField,
+        CategoryPolicy,
+        BulkMailRequest,
+        Collection,
+        CollectionPage,
+        ConfirmRequest,
+        ConflictPolicy,
+        CreateImportSessionRequest,
+        CredentialUsage,
+        DailyApiUsage,
+        DeletedTorrentResponse,
+        DescriptionImage,
+        DuplicateCluster,
+        DuplicatePair,
+        FieldType,
+        FileKind,
+        FileNode,
+        FileStats,
+        ImageFormat,
+        ImageKind,
+        ImportBatchRequest,
+        ImportRequest,
+        ImportSession,
+        ImportSummary,
+        MagnetLinkResponse,
+        PackFile,
+        PartitionChanges,
+        PatternAction,
+        PointsAdjustment,
+        PointsBalance,
+        PresignRequest,
+        ProfilePage,
+        PublicProfile,
+        RateLimitCounter,
+        ReportAction,
+        RegeneratedTrackerKey,
+        ReportReason,
+        ReputationStats,
+        Role,
+        SavedSearch,
+        SeedingSummary,
+        SeedingSummaryEvent,
+        Segment,
+        SimilarTorrent,
+        SkippedBatchTorrent,
+        SmartFilter,
+        Sorting,
+        SyncManifest,
+        SyncPartition,
+        SyncRecord,
+        TagSynonym,
+        TokenResponse,
+        TorrentAttachment,
+        TorrentCategory,
+        TorrentContents,
+        TorrentFieldValue,
+        TorrentFile,
+        TorrentFileGroup,
+        TorrentFileGroupSummary,
+        TorrentListing,
+        TorrentMetadata,
+        TorrentPeer,
+        TorrentPeersResponse,
+        TorrentPreview,
+        TorrentResponse,
+        TorrentsResponse,
+        TorrentTag,
+        TorrentWebSeed,
+        TrackerKeyStatus,
+        TransferStats,
+        TrashedTorrent,
+        TrendingTag,
+        UploadLimits,
+        UploadQuota,
+        UploadUsage,
+        UserReputation,
+        UserSession,
+        UserTransferStats,
+        UploadedDescriptionImage,
+        WidgetTorrent,
+    )),
+    modifiers(&SecurityAddon),
+    tags(
+        (name = "about", description = "About and license pages"),
+        (name = "admin", description = "Moderation and administration"),
+        (name = "bookmark", description = "Torrent bookmarks"),
+        (name = "category", description = "Torrent categories"),
+        (name = "collection", description = "Torrent collections"),
+        (name = "comment", description = "Torrent comments"),
+        (name = "event", description = "Live events"),
+        (name = "image", description = "Direct image uploads"),
+        (name = "proxy", description = "Image proxy"),
+        (name = "report", description = "Torrent reports"),
+        (name = "saved_search", description = "Saved searches"),
+        (name = "settings", description = "Index settings"),
+        (name = "subscription", description = "Email subscriptions"),
+        (name = "sync", description = "Delta sync for mirrors"),
+        (name = "tag", description = "Torrent tags"),
+        (name = "torrent", description = "Torrents"),
+        (name = "user", description = "Users and authentication"),
+        (name = "widget", description = "Embeddable widgets"),
+    )
+)]
+pub struct ApiDoc;
+
+/// It adds the JSON Web Token authentication scheme used by the handlers.
+struct SecurityAddon;
+
+impl Modify for SecurityAddon {
+    fn modify(&self, openapi: &mut utoipa::openapi::OpenApi) {
+        if let Some(components) = openapi.components.as_mut() {
+            components.add_security_scheme(
+                "bearer_auth",
+                SecurityScheme::Http(HttpBuilder::new().scheme(HttpAuthScheme::Bearer).bearer_format("JWT").build()),
+            );
+        }
+    }
+}
+
+/// Routes for the `OpenAPI` specification and the Swagger UI.
+///
+/// They return a `404` response unless the `api.openapi` option is enabled.
+#[allow(clippy::needless_pass_by_value)]
+pub fn router(app_data: Arc<AppData>) -> Router {
+    Router::from(SwaggerUi::new(SWAGGER_UI_URL).url(OPENAPI_JSON_URL, ApiDoc::openapi()))
+        .layer(middleware::from_fn_with_state(app_data, openapi_enabled_middleware))
+}
+
+async fn openapi_enabled_middleware(State(app_data): State<Arc<AppData>>, request: Request, next: Next) -> Response {
+    if app_data.cfg.settings.read().await.api.openapi {
+        next.run(request).await
+    } else {
+        StatusCode::NOT_FOUND.into_response()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use utoipa::OpenApi;
+
+    use super::ApiDoc;
+
+    #[test]
+    fn it_should_document_the_api_endpoints() {
+        let openapi = ApiDoc::openapi();
+
+        for path in ["/v1/torrents", "/v1/torrent/{info_hash}", "/v1/user/login", "/v1/category"] {
+            assert!(openapi.paths.paths.contains_key(path), "missing path {path}");
+        }
+    }
+
+    #[test]
+    fn it_should_include_the_bearer_authentication_scheme() {
+        let json = serde_json::to_value(ApiDoc::openapi()).unwrap();
+
+        assert_eq!(json["components"]["securitySchemes"]["bearer_auth"]["scheme"], "bearer");
+        assert!(json["components"]["schemas"]["TorrentsResponseData"].is_object());
+    }
+}
@@ -0,0 +1,269 @@
+//! Negotiation of the shape of the JSON responses.
+//!
+//! The handlers wrap successful responses in a `{"data": ...}` envelope and
+//! use `snake_case` field names. Some API consumers prefer bare objects or
+//! `camelCase` field names. They can ask for them with a comma-separated list
+//! of profiles in the `format` query parameter or in the `Accept-Profile`
+//! header:
+//!
+//! - `envelope`: wrap the successful responses in a `{"data": ...}` envelope.
+//! - `bare`: return the successful responses without the envelope.
+//! - `snake_case`: use `snake_case` field names.
+//! - `camel_case`: use `camelCase` field names.
+//!
+//! For example:
+//!
+//! ```text
+//! curl "http://127.0.0.1:3001/v1/settings/public?format=bare,camel_case"
+//! ```
+//!
+//! ```json
+//! {
+//!   "websiteName": "Torrust",
+//!   "trackerUrl": "udp://localhost:6969",
+//!   ...
+//! }
+//! ```
+//!
+//! The query parameter takes precedence over the header, and both over the
+//! `[api.response_format]` section in the configuration. Unknown profiles
+//! are ignored. Error responses (`{"error": ...}`) never have an envelope.
+//!
+//! Reshaped responses include the `Content-Profile` header with the profiles
+//! used.
+//!
+//! > **NOTICE**: the `camel_case` profile renames all the object keys,
+//! > including the keys of maps whose keys are data.
+use std::sync::Arc;
+
+use axum::body::{to_bytes, Body};
+use axum::extract::{Request, State};
+use axum::http::header::{CONTENT_LENGTH, CONTENT_TYPE, VARY};
+use axum::http::{HeaderMap, HeaderName, HeaderValue, StatusCode};
+use axum::middleware::Next;
+use axum::response::{IntoResponse, Response};
+use serde_json::{Map, Value};
+
+use crate::common::AppData;
+use crate::config::{FieldNaming, ResponseFormat};
+
+const ACCEPT_PROFILE: &str = "accept-profile";
+const CONTENT_PROFILE: &str = "content-profile";
+
+/// Middleware that reshapes the JSON responses to the format the client asked
+/// for.
+pub async fn response_format_middleware(State(app_data): State<Arc<AppData>>, request: Request, next: Next) -> Response {
+    let default_format = app_data.cfg.settings.read().await.api.response_format;
+
+    let format = negotiate(default_format, request.uri().query(), request.headers());
+
+    let mut response = next.run(request).await;
+
+    response.headers_mut().append(VARY, HeaderValue::from_static(ACCEPT_PROFILE));
+
+    if format == ResponseFormat::default() || !is_json(response.headers()) {
+        return response;
+    }
+
+    let (mut parts, body) = response.into_parts();
+
+    let Ok(bytes) = to_bytes(body, usize::MAX).await else {
+        return StatusCode::INTERNAL_SERVER_ERROR.into_response();
+    };
+
+    let Ok(value) = serde_json::from_slice::<Value>(&bytes) else {
+        return Response::from_parts(parts, Body::from(bytes));
+    };
+
+    let Ok(body) = serde_json::to_vec(&reshape(value, format)) else {
+        return StatusCode::INTERNAL_SERVER_ERROR.into_response();
+    };
+
+    parts.headers.remove(CONTENT_LENGTH);
+
+    if let Ok(profile) = HeaderValue::from_str(&profile(format)) {
+        parts.headers.insert(HeaderName::from_static(CONTENT_PROFILE), profile);
+    }
+
+    Response::from_parts(parts, Body::from(body))
+}
+
+/// It returns the format requested with the `format` query parameter or the
+/// `Accept-Profile` header.
+fn negotiate(default_format: ResponseFormat, query: Option<&str>, headers: &HeaderMap) -> ResponseFormat {
+    let from_query = query.and_then(|query| {
+        query
+            .split('&')
+            .filter_map(|pair| pair.split_once('='))
+            .find(|(name, _)| *name == "format")
+            .map(|(_, value)| urlencoding::decode(value).map_or_else(|_| value.to_string(), |value| value.into_owned()))
+    });
+
+    let from_header = || {
+        headers
+            .get(ACCEPT_PROFILE)
+            .and_then(|value| value.to_str().ok())
+            .map(ToString::to_string)
+    };
+
+    match from_query.or_else(from_header) {
+        Some(profiles) => apply_profiles(default_format, &profiles),
+        None => default_format,
+    }
+}
+
+fn apply_profiles(mut format: ResponseFormat, profiles: &str) -> ResponseFormat {
+    for profile in profiles.split(',') {
+        match profile.trim().trim_matches(|c| c == '"' || c == '<' || c == '>') {
+            "envelope" => format.envelope = true,
+            "bare" => format.envelope = false,
+            "snake_case" => format.field_naming = FieldNaming::SnakeCase,
+            "camel_case" => format.field_naming = FieldNaming::CamelCase,
+            _ => {}
+        }
+    }
+
+    format
+}
+
+fn profile(format: ResponseFormat) -> String {
+    let envelope = if format.envelope { "envelope" } else { "bare" };
+
+    let field_naming = match format.field_naming {
+        FieldNaming::SnakeCase => "snake_case",
+        FieldNaming::CamelCase => "camel_case",
+    };
+
+    format!("{envelope}, {field_naming}")
+}
+
+fn is_json(headers: &HeaderMap) -> bool {
+    headers
+        .get(CONTENT_TYPE)
+        .and_then(|value| value.to_str().ok())
+        .is_some_and(|content_type| content_type.starts_with("application/json"))
+}
+
+fn reshape(value: Value, format: ResponseFormat) -> Value {
+    let value = if format.envelope { value } else { remove_envelope(value) };
+
+    match format.field_naming {
+        FieldNaming::SnakeCase => value,
+        FieldNaming::CamelCase => rename_keys(value),
+    }
+}
+
+fn remove_envelope(value: Value) -> Value {
+    match value {
+        Value::Object(mut object) if object.len() == 1 && object.contains_key("data") => {
+            object.remove("data").unwrap_or(Value::Null)
+        }
+        value => value,
+    }
+}
+
+fn rename_keys(value: Value) -> Value {
+    match value {
+        Value::Object(object) => Value::Object(
+            object
+                .into_iter()
+                .map(|(key, value)| (camel_case(&key), rename_keys(value)))
+                .collect::<Map<String, Value>>(),
+        ),
+        Value::Array(values) => Value::Array(values.into_iter().map(rename_keys).collect()),
+        value => value,
+    }
+}
+
+fn camel_case(key: &str) -> String {
+    let mut words = key.split('_').filter(|word| !word.is_empty());
+
+    let mut camel_case = words.next().unwrap_or_default().to_string();
+
+    for word in words {
+        let mut chars = word.chars();
+        if let Some(first) = chars.next() {
+            camel_case.extend(first.to_uppercase());
+            camel_case.push_str(chars.as_str());
+        }
+    }
+
+    camel_case
+}
+
+#[cfg(test)]
+mod tests {
+    use axum::http::{HeaderMap, HeaderValue};
+    use serde_json::json;
+
+    use super::{camel_case, negotiate, reshape};
+    use crate::config::{FieldNaming, ResponseFormat};
+
+    fn bare_camel_case() -> ResponseFormat {
+        ResponseFormat {
+            envelope: false,
+            field_naming: FieldNaming::CamelCase,
+        }
+    }
+
+    #[test]
+    fn it_should_use_the_default_format_when_the_client_does_not_ask_for_one() {
+        assert_eq!(
+            negotiate(ResponseFormat::default(), Some("page=1"), &HeaderMap::new()),
+            ResponseFormat::default()
+        );
+    }
+
+    #[test]
+    fn it_should_prefer_the_query_parameter_over_the_accept_profile_header() {
+        let mut headers = HeaderMap::new();
+        headers.insert("accept-profile", HeaderValue::from_static("snake_case"));
+
+        assert_eq!(
+            negotiate(ResponseFormat::default(), Some("format=bare%2Ccamel_case"), &headers),
+            bare_camel_case()
+        );
+        assert_eq!(
+            negotiate(ResponseFormat::default(), None, &headers),
+            ResponseFormat::default()
+        );
+    }
+
+    #[test]
+    fn it_should_only_override_the_requested_options_of_the_default_format() {
+        let mut headers = HeaderMap::new();
+        headers.insert("accept-profile", HeaderValue::from_static("envelope"));
+
+        assert_eq!(
+            negotiate(bare_camel_case(), None, &headers),
+            ResponseFormat {
+                envelope: true,
+                field_naming: FieldNaming::CamelCase,
+            }
+        );
+    }
+
+    #[test]
+    fn it_should_remove_the_envelope_and_rename_the_fields() {
+        let response = json!({ "data": { "website_name": "Torrust", "tags": [{ "tag_id": 1 }] } });
+
+        assert_eq!(
+            reshape(response, bare_camel_case()),
+            json!({ "websiteName": "Torrust", "tags": [{ "tagId": 1 }] })
+        );
+    }
+
+    #[test]
+    fn it_should_not_change_the_error_responses_shape() {
+        let response = json!({ "error": "Torrent not found." });
+
+        assert_eq!(reshape(response.clone(), bare_camel_case()), response);
+    }
+
+    #[test]
+    fn it_should_convert_snake_case_keys_to_camel_case() {
+        assert_eq!(camel_case("info_hash"), "infoHash");
+        assert_eq!(camel_case("date_last_login"), "dateLastLogin");
+        assert_eq!(camel_case("name"), "name");
+    }
+}
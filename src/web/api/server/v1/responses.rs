@@ -3,16 +3,35 @@ use axum::response::{IntoResponse, Response};
 use hyper::{header, StatusCode};
 use serde::{Deserialize, Serialize};
 use serde_json::json;
+use utoipa::ToSchema;
 
 use crate::databases::database;
 use crate::errors::{http_status_code_for_service_error, map_database_error_to_service_error, ServiceError};
+use crate::models::response::{DeletedTorrentResponse, TokenResponse, TorrentResponse, TorrentsResponse};
+use crate::models::torrent_tag::TorrentTag;
+use crate::web::api::server::v1::contexts::category::responses::Category;
+use crate::web::api::server::v1::contexts::torrent::responses::NewTorrentResponseData;
 
-#[derive(Serialize, Deserialize, Debug)]
+/// The envelope of the successful responses.
+///
+/// The aliases are the envelopes used in the `OpenAPI` specification.
+#[derive(Serialize, Deserialize, Debug, ToSchema)]
+#[aliases(
+    MessageResponseData = OkResponseData<String>,
+    IdResponseData = OkResponseData<i64>,
+    TokenResponseData = OkResponseData<TokenResponse>,
+    CategoriesResponseData = OkResponseData<Vec<Category>>,
+    TagsResponseData = OkResponseData<Vec<TorrentTag>>,
+    TorrentResponseData = OkResponseData<TorrentResponse>,
+    TorrentsResponseData = OkResponseData<TorrentsResponse>,
+    UploadedTorrentResponseData = OkResponseData<NewTorrentResponseData>,
+    DeletedTorrentResponseData = OkResponseData<DeletedTorrentResponse>
+)]
 pub struct OkResponseData<T> {
     pub data: T,
 }
 
-#[derive(Serialize, Deserialize, Debug)]
+#[derive(Serialize, Deserialize, Debug, ToSchema)]
 pub struct ErrorResponseData {
     pub error: String,
 }
@@ -3,29 +3,39 @@ use std::env;
 use std::sync::Arc;
 use std::time::Duration;
 
-use axum::extract::DefaultBodyLimit;
-use axum::http::HeaderName;
-use axum::response::{Redirect, Response};
+use axum::extract::{DefaultBodyLimit, State};
+use axum::http::{header, HeaderName, StatusCode};
+use axum::response::{IntoResponse, Redirect, Response};
 use axum::routing::get;
-use axum::{Json, Router};
+use axum::{middleware, Json, Router};
 use hyper::Request;
 use serde_json::{json, Value};
+use tower_http::compression::predicate::{NotForContentType, Predicate, SizeAbove};
 use tower_http::compression::CompressionLayer;
 use tower_http::cors::CorsLayer;
 use tower_http::propagate_header::PropagateHeaderLayer;
 use tower_http::request_id::{MakeRequestUuid, SetRequestIdLayer};
-use tower_http::trace::{DefaultMakeSpan, TraceLayer};
+use tower_http::trace::TraceLayer;
 use tracing::{Level, Span};
 
-use super::contexts::{about, category, proxy, settings, tag, torrent, user};
+use super::contexts::{about, admin, category, collection, event, image, proxy, settings, sync, tag, torrent, user, widget};
 use crate::bootstrap::config::ENV_VAR_CORS_PERMISSIVE;
 use crate::common::AppData;
+use crate::config::Compression;
+use crate::services::health::HealthReport;
+use crate::utils::trace_context::TraceContext;
+use crate::web::api::server::rate_limit::rate_limit_middleware;
+use crate::web::api::server::trace_context::trace_context_middleware;
+use crate::web::api::server::v1::cache_control::cache_control_middleware;
+use crate::web::api::server::v1::deprecation::deprecation_middleware;
+use crate::web::api::server::v1::openapi;
+use crate::web::api::server::v1::response_format::response_format_middleware;
 
 pub const API_VERSION_URL_PREFIX: &str = "v1";
 
 /// Add all API routes to the router.
 #[allow(clippy::needless_pass_by_value)]
-pub fn router(app_data: Arc<AppData>) -> Router {
+pub fn router(app_data: Arc<AppData>, compression: &Compression) -> Router {
     // code-review: should we use plural for the resource prefix: `users`, `categories`, `tags`?
     // Some endpoint are using plural (for instance, `get_categories`) and some singular.
     // See: https://stackoverflow.com/questions/6845772/should-i-use-singular-or-plural-name-convention-for-rest-resources
@@ -35,17 +45,31 @@ pub fn router(app_data: Arc<AppData>) -> Router {
         .nest("/user", user::routes::router(app_data.clone()))
         .nest("/about", about::routes::router(app_data.clone()))
         .nest("/category", category::routes::router(app_data.clone()))
+        .nest("/collection", collection::routes::router(app_data.clone()))
         .nest("/tag", tag::routes::router_for_single_resources(app_data.clone()))
         .nest("/tags", tag::routes::router_for_multiple_resources(app_data.clone()))
         .nest("/settings", settings::routes::router(app_data.clone()))
         .nest("/torrent", torrent::routes::router_for_single_resources(app_data.clone()))
         .nest("/torrents", torrent::routes::router_for_multiple_resources(app_data.clone()))
-        .nest("/proxy", proxy::routes::router(app_data.clone()));
+        .nest("/proxy", proxy::routes::router(app_data.clone()))
+        .nest("/image", image::routes::router(app_data.clone()))
+        .nest("/events", event::routes::router(app_data.clone()))
+        .nest("/widget", widget::routes::router(app_data.clone()))
+        .nest("/sync", sync::routes::router(app_data.clone()))
+        .nest("/admin", admin::routes::router(app_data.clone()));
 
     let router = Router::new()
         .route("/", get(redirect_to_about))
         .route("/health_check", get(health_check_handler).with_state(app_data.clone()))
-        .nest(&format!("/{API_VERSION_URL_PREFIX}"), v1_api_routes);
+        .route("/health/live", get(health_live_handler))
+        .route("/health/ready", get(health_ready_handler).with_state(app_data.clone()))
+        .route("/metrics", get(metrics_handler).with_state(app_data.clone()))
+        .route(
+            "/t/:short_info_hash",
+            get(torrent::handlers::short_url_handler).with_state(app_data.clone()),
+        )
+        .nest(&format!("/{API_VERSION_URL_PREFIX}"), v1_api_routes)
+        .merge(openapi::router(app_data.clone()));
 
     let router = if env::var(ENV_VAR_CORS_PERMISSIVE).is_ok() {
         router.layer(CorsLayer::permissive())
@@ -54,13 +78,34 @@ pub fn router(app_data: Arc<AppData>) -> Router {
     };
 
     router
+        .layer(middleware::from_fn_with_state(app_data.clone(), cache_control_middleware))
+        .layer(middleware::from_fn_with_state(app_data.clone(), response_format_middleware))
+        .layer(middleware::from_fn_with_state(app_data.clone(), deprecation_middleware))
+        .layer(middleware::from_fn_with_state(app_data, rate_limit_middleware))
         .layer(DefaultBodyLimit::max(10_485_760))
-        .layer(CompressionLayer::new())
+        .layer(compression_layer(compression))
         .layer(SetRequestIdLayer::x_request_id(MakeRequestUuid))
         .layer(PropagateHeaderLayer::new(HeaderName::from_static("x-request-id")))
         .layer(
             TraceLayer::new_for_http()
-                .make_span_with(DefaultMakeSpan::new().level(Level::INFO))
+                .make_span_with(|request: &Request<axum::body::Body>| {
+                    let trace_context = request
+                        .extensions()
+                        .get::<TraceContext>()
+                        .cloned()
+                        .unwrap_or_else(TraceContext::new_root);
+
+                    tracing::span!(
+                        Level::INFO,
+                        "request",
+                        method = %request.method(),
+                        uri = %request.uri(),
+                        version = ?request.version(),
+                        trace_id = %trace_context.trace_id_hex(),
+                        span_id = %trace_context.span_id_hex(),
+                        parent_span_id = %trace_context.parent_span_id_hex(),
+                    )
+                })
                 .on_request(|request: &Request<axum::body::Body>, _span: &Span| {
                     let method = request.method().to_string();
                     let uri = request.uri().to_string();
@@ -88,14 +133,63 @@ pub fn router(app_data: Arc<AppData>) -> Router {
                         tracing::Level::INFO, "response", latency = %latency_ms, status = %status_code, request_id = %request_id);
                 }),
         )
+        .layer(middleware::from_fn(trace_context_middleware))
         .layer(SetRequestIdLayer::x_request_id(MakeRequestUuid))
 }
 
+/// It builds the compression layer with the enabled encodings.
+fn compression_layer(compression: &Compression) -> CompressionLayer<impl Predicate> {
+    CompressionLayer::new()
+        .gzip(compression.enabled && compression.gzip)
+        .br(compression.enabled && compression.br)
+        .deflate(compression.enabled && compression.deflate)
+        .zstd(compression.enabled && compression.zstd)
+        .compress_when(
+            SizeAbove::new(compression.min_size_bytes)
+                .and(NotForContentType::GRPC)
+                .and(NotForContentType::IMAGES)
+                .and(NotForContentType::SSE),
+        )
+}
+
 /// Endpoint for container health check.
 async fn health_check_handler() -> Json<Value> {
     Json(json!({ "status": "Ok" }))
 }
 
+/// Endpoint for the liveness probe. Refer to the [`health`](crate::services::health) service.
+async fn health_live_handler() -> Response {
+    health_response(&HealthReport::new(vec![]))
+}
+
+/// Endpoint for the readiness probe. Refer to the [`health`](crate::services::health) service.
+async fn health_ready_handler(State(app_data): State<Arc<AppData>>) -> Response {
+    health_response(&app_data.health_service.ready().await)
+}
+
+fn health_response(report: &HealthReport) -> Response {
+    let status_code = if report.is_up() {
+        StatusCode::OK
+    } else {
+        StatusCode::SERVICE_UNAVAILABLE
+    };
+
+    (status_code, Json(report)).into_response()
+}
+
+/// Endpoint for Prometheus metrics.
+async fn metrics_handler(State(app_data): State<Arc<AppData>>) -> Response {
+    (
+        [(header::CONTENT_TYPE, "text/plain; version=0.0.4")],
+        format!(
+            "{}{}",
+            app_data.tracker_statistics_importer.status().to_prometheus(),
+            app_data.deprecations.to_prometheus()
+        ),
+    )
+        .into_response()
+}
+
 async fn redirect_to_about() -> Redirect {
     Redirect::permanent(&format!("/{API_VERSION_URL_PREFIX}/about"))
 }
@@ -4,14 +4,22 @@ use reqwest::{multipart, Error};
 use serde::Serialize;
 
 use super::connection_info::ConnectionInfo;
-use super::contexts::category::forms::{AddCategoryForm, DeleteCategoryForm};
-use super::contexts::tag::forms::{AddTagForm, DeleteTagForm};
-use super::contexts::torrent::forms::UpdateTorrentFrom;
+use super::contexts::admin::forms::{ImportTorrentsForm, SendBulkMailForm, UploadLimitsForm};
+use super::contexts::bookmark::responses::BookmarksFile;
+use super::contexts::category::forms::{AddCategoryForm, CategoryPolicyForm, DeleteCategoryForm};
+use super::contexts::collection::forms::CollectionForm;
+use super::contexts::comment::forms::{AddCommentForm, EditCommentForm, ModerateCommentForm};
+use super::contexts::image::forms::{ConfirmUploadForm, PresignUploadForm};
+use super::contexts::report::forms::{ReportTorrentForm, ResolveReportForm};
+use super::contexts::subscription::forms::AddSubscriptionForm;
+use super::contexts::tag::forms::{AddTagForm, DeleteTagForm, MergeTagsForm};
+use super::contexts::torrent::forms::{AddMagnetForm, UpdateTorrentFrom, UpdateTorrentTrackersForm};
 use super::contexts::torrent::requests::InfoHash;
 use super::contexts::user::forms::{
-    ChangePasswordForm, LoginForm, RegistrationForm, TokenRenewalForm, TokenVerificationForm, Username,
+    ChangeEmailForm, ChangePasswordForm, LoginForm, RegistrationForm, TokenRenewalForm, TokenVerificationForm, UpdateProfileForm,
+    Username,
 };
-use super::http::{Query, ReqwestQuery};
+use super::http::{Query, QueryParam, ReqwestQuery};
 use super::responses::{self, BinaryResponse, TextResponse};
 
 /// API Client
@@ -84,6 +92,36 @@ impl Client {
         self.http_client.delete_with_body("/category", &delete_category_form).await
     }
 
+    pub async fn get_category_moderators(&self, category: &str) -> TextResponse {
+        self.http_client
+            .get(&format!("/category/{category}/moderators"), Query::empty())
+            .await
+    }
+
+    pub async fn add_category_moderator(&self, category: &str, username: &str) -> TextResponse {
+        self.http_client
+            .post_empty(&format!("/category/{category}/moderator/{username}"))
+            .await
+    }
+
+    pub async fn remove_category_moderator(&self, category: &str, username: &str) -> TextResponse {
+        self.http_client
+            .delete(&format!("/category/{category}/moderator/{username}"))
+            .await
+    }
+
+    pub async fn get_category_policy(&self, category: &str) -> TextResponse {
+        self.http_client
+            .get(&format!("/category/{category}/policy"), Query::empty())
+            .await
+    }
+
+    pub async fn set_category_policy(&self, category: &str, category_policy_form: CategoryPolicyForm) -> TextResponse {
+        self.http_client
+            .put(&format!("/category/{category}/policy"), &category_policy_form)
+            .await
+    }
+
     // Context: tag
 
     pub async fn get_tags(&self) -> TextResponse {
@@ -100,6 +138,10 @@ impl Client {
         self.http_client.delete_with_body("/tag", &delete_tag_form).await
     }
 
+    pub async fn merge_tags(&self, merge_tags_form: MergeTagsForm) -> TextResponse {
+        self.http_client.post("/tag/merge", &merge_tags_form).await
+    }
+
     // Context: root
 
     pub async fn root(&self) -> TextResponse {
@@ -120,12 +162,37 @@ impl Client {
         self.http_client.get("/settings", Query::empty()).await
     }
 
+    pub async fn get_settings_changes(&self) -> TextResponse {
+        self.http_client.get("/settings/changes", Query::empty()).await
+    }
+
+    pub async fn get_public_settings_with_format(&self, format: &str) -> TextResponse {
+        self.http_client
+            .get(
+                "/settings/public",
+                Query::with_params(vec![QueryParam::new("format", format)]),
+            )
+            .await
+    }
+
+    pub async fn get_public_settings_with_profile(&self, profile: &str) -> TextResponse {
+        self.http_client
+            .get_with_header("/settings/public", Query::empty(), "Accept-Profile", profile)
+            .await
+    }
+
     // Context: torrent
 
     pub async fn get_torrents(&self, params: Query) -> TextResponse {
         self.http_client.get("/torrents", params).await
     }
 
+    pub async fn get_torrents_if_none_match(&self, params: Query, etag: &str) -> TextResponse {
+        self.http_client
+            .get_with_header("/torrents", params, "If-None-Match", etag)
+            .await
+    }
+
     pub async fn get_torrent(&self, info_hash: &InfoHash) -> TextResponse {
         self.http_client.get(&format!("/torrent/{info_hash}"), Query::empty()).await
     }
@@ -140,16 +207,248 @@ impl Client {
             .await
     }
 
+    pub async fn update_torrent_trackers(&self, info_hash: &InfoHash, form: UpdateTorrentTrackersForm) -> TextResponse {
+        self.http_client.put(&format!("/torrent/{info_hash}/trackers"), &form).await
+    }
+
     pub async fn upload_torrent(&self, form: multipart::Form) -> TextResponse {
         self.http_client.post_multipart("/torrent/upload", form).await
     }
 
+    pub async fn add_magnet(&self, form: AddMagnetForm) -> TextResponse {
+        self.http_client.post("/torrent/magnet", &form).await
+    }
+
     pub async fn download_torrent(&self, info_hash: &InfoHash) -> responses::BinaryResponse {
         self.http_client
             .get_binary(&format!("/torrent/download/{info_hash}"), Query::empty())
             .await
     }
 
+    pub async fn get_magnet_link_qr_code(&self, info_hash: &InfoHash) -> responses::BinaryResponse {
+        self.http_client
+            .get_binary(&format!("/torrent/{info_hash}/qr"), Query::empty())
+            .await
+    }
+
+    pub async fn get_torrent_by_short_url(&self, short_info_hash: &str) -> TextResponse {
+        self.http_client.get_from_root(&format!("/t/{short_info_hash}")).await
+    }
+
+    // Context: comment
+
+    pub async fn get_comments(&self, info_hash: &InfoHash, params: Query) -> TextResponse {
+        self.http_client.get(&format!("/torrent/{info_hash}/comments"), params).await
+    }
+
+    pub async fn add_comment(&self, info_hash: &InfoHash, add_comment_form: AddCommentForm) -> TextResponse {
+        self.http_client
+            .post(&format!("/torrent/{info_hash}/comments"), &add_comment_form)
+            .await
+    }
+
+    pub async fn edit_comment(&self, info_hash: &InfoHash, comment_id: i64, edit_comment_form: EditCommentForm) -> TextResponse {
+        self.http_client
+            .put(&format!("/torrent/{info_hash}/comments/{comment_id}"), &edit_comment_form)
+            .await
+    }
+
+    pub async fn delete_comment(&self, info_hash: &InfoHash, comment_id: i64) -> TextResponse {
+        self.http_client
+            .delete(&format!("/torrent/{info_hash}/comments/{comment_id}"))
+            .await
+    }
+
+    pub async fn moderate_comment(
+        &self,
+        info_hash: &InfoHash,
+        comment_id: i64,
+        moderate_comment_form: ModerateCommentForm,
+    ) -> TextResponse {
+        self.http_client
+            .put(
+                &format!("/torrent/{info_hash}/comments/{comment_id}/moderation"),
+                &moderate_comment_form,
+            )
+            .await
+    }
+
+    // Context: bookmark
+
+    pub async fn add_bookmark(&self, info_hash: &InfoHash) -> TextResponse {
+        self.http_client.post_empty(&format!("/torrent/{info_hash}/bookmark")).await
+    }
+
+    pub async fn delete_bookmark(&self, info_hash: &InfoHash) -> TextResponse {
+        self.http_client.delete(&format!("/torrent/{info_hash}/bookmark")).await
+    }
+
+    pub async fn get_bookmarks(&self, params: Query) -> TextResponse {
+        self.http_client.get("/user/bookmarks", params).await
+    }
+
+    pub async fn export_bookmarks(&self) -> TextResponse {
+        self.http_client.get("/user/bookmarks/export", Query::empty()).await
+    }
+
+    pub async fn import_bookmarks(&self, bookmarks_file: &BookmarksFile) -> TextResponse {
+        self.http_client.post("/user/bookmarks/import", bookmarks_file).await
+    }
+
+    // Context: collection
+
+    pub async fn create_collection(&self, collection_form: CollectionForm) -> TextResponse {
+        self.http_client.post("/collection", &collection_form).await
+    }
+
+    pub async fn get_collection(&self, collection_id: i64, params: Query) -> TextResponse {
+        self.http_client.get(&format!("/collection/{collection_id}"), params).await
+    }
+
+    pub async fn get_user_collections(&self) -> TextResponse {
+        self.http_client.get("/user/collections", Query::empty()).await
+    }
+
+    pub async fn update_collection(&self, collection_id: i64, collection_form: CollectionForm) -> TextResponse {
+        self.http_client
+            .put(&format!("/collection/{collection_id}"), &collection_form)
+            .await
+    }
+
+    pub async fn delete_collection(&self, collection_id: i64) -> TextResponse {
+        self.http_client.delete(&format!("/collection/{collection_id}")).await
+    }
+
+    pub async fn add_torrent_to_collection(&self, collection_id: i64, info_hash: &InfoHash) -> TextResponse {
+        self.http_client
+            .post_empty(&format!("/collection/{collection_id}/torrent/{info_hash}"))
+            .await
+    }
+
+    pub async fn remove_torrent_from_collection(&self, collection_id: i64, info_hash: &InfoHash) -> TextResponse {
+        self.http_client
+            .delete(&format!("/collection/{collection_id}/torrent/{info_hash}"))
+            .await
+    }
+
+    // Context: image
+
+    pub async fn presign_image_upload(&self, presign_upload_form: PresignUploadForm) -> TextResponse {
+        self.http_client.post("/image/upload", &presign_upload_form).await
+    }
+
+    pub async fn confirm_image_upload(&self, confirm_upload_form: ConfirmUploadForm) -> TextResponse {
+        self.http_client.post("/image/upload/confirm", &confirm_upload_form).await
+    }
+
+    // Context: subscription
+
+    pub async fn get_subscriptions(&self) -> TextResponse {
+        self.http_client.get("/user/subscriptions", Query::empty()).await
+    }
+
+    pub async fn add_subscription(&self, add_subscription_form: AddSubscriptionForm) -> TextResponse {
+        self.http_client.post("/user/subscriptions", &add_subscription_form).await
+    }
+
+    pub async fn delete_subscription(&self, subscription_id: i64) -> TextResponse {
+        self.http_client
+            .delete(&format!("/user/subscriptions/{subscription_id}"))
+            .await
+    }
+
+    // Context: report
+
+    pub async fn report_torrent(&self, info_hash: &InfoHash, report_torrent_form: ReportTorrentForm) -> TextResponse {
+        self.http_client
+            .post(&format!("/torrent/{info_hash}/report"), &report_torrent_form)
+            .await
+    }
+
+    // Context: admin
+
+    pub async fn get_pending_torrents(&self, params: Query) -> TextResponse {
+        self.http_client.get("/admin/torrents/pending", params).await
+    }
+
+    pub async fn approve_torrent(&self, torrent_id: i64) -> TextResponse {
+        self.http_client
+            .post_empty(&format!("/admin/torrent/{torrent_id}/approve"))
+            .await
+    }
+
+    pub async fn reject_torrent(&self, torrent_id: i64) -> TextResponse {
+        self.http_client
+            .post_empty(&format!("/admin/torrent/{torrent_id}/reject"))
+            .await
+    }
+
+    pub async fn get_reports(&self, params: Query) -> TextResponse {
+        self.http_client.get("/admin/reports", params).await
+    }
+
+    pub async fn resolve_report(&self, report_id: i64, resolve_report_form: ResolveReportForm) -> TextResponse {
+        self.http_client
+            .post(&format!("/admin/report/{report_id}/resolve"), &resolve_report_form)
+            .await
+    }
+
+    pub async fn get_stats_importer_status(&self) -> TextResponse {
+        self.http_client.get("/admin/stats-importer/status", Query::empty()).await
+    }
+
+    pub async fn get_download_statistics(&self, params: Query) -> TextResponse {
+        self.http_client.get("/admin/stats/downloads", params).await
+    }
+
+    pub async fn import_torrents(&self, import_torrents_form: ImportTorrentsForm) -> TextResponse {
+        self.http_client.post("/admin/torrents/import", &import_torrents_form).await
+    }
+
+    pub async fn send_bulk_mail(&self, send_bulk_mail_form: SendBulkMailForm) -> TextResponse {
+        self.http_client.post("/admin/mail", &send_bulk_mail_form).await
+    }
+
+    pub async fn get_inactivity_report(&self, params: Query) -> TextResponse {
+        self.http_client.get("/admin/inactivity", params).await
+    }
+
+    pub async fn add_inactivity_exemption(&self, username: &str) -> TextResponse {
+        self.http_client
+            .post_empty(&format!("/admin/inactivity/exemption/{username}"))
+            .await
+    }
+
+    pub async fn remove_inactivity_exemption(&self, username: &str) -> TextResponse {
+        self.http_client
+            .delete(&format!("/admin/inactivity/exemption/{username}"))
+            .await
+    }
+
+    pub async fn get_upload_quota(&self, username: &str) -> TextResponse {
+        self.http_client
+            .get(&format!("/admin/quota/{username}"), Query::empty())
+            .await
+    }
+
+    pub async fn set_upload_limits(&self, username: &str, upload_limits_form: UploadLimitsForm) -> TextResponse {
+        self.http_client
+            .put(&format!("/admin/quota/{username}"), &upload_limits_form)
+            .await
+    }
+
+    pub async fn remove_upload_limits(&self, username: &str) -> TextResponse {
+        self.http_client.delete(&format!("/admin/quota/{username}")).await
+    }
+
+    pub async fn get_rate_limit_counters(&self, params: Query) -> TextResponse {
+        self.http_client.get("/admin/rate-limits", params).await
+    }
+
+    pub async fn get_audit_log(&self, params: Query) -> TextResponse {
+        self.http_client.get("/admin/audit-log", params).await
+    }
+
     // Context: user
 
     pub async fn register_user(&self, registration_form: RegistrationForm) -> TextResponse {
@@ -166,6 +465,34 @@ impl Client {
             .await
     }
 
+    pub async fn resend_verification_email(&self, login_form: LoginForm) -> TextResponse {
+        self.http_client.post("/user/email/resend-verification", &login_form).await
+    }
+
+    pub async fn change_email(&self, change_email_form: ChangeEmailForm) -> TextResponse {
+        self.http_client.post("/user/email/change", &change_email_form).await
+    }
+
+    pub async fn confirm_email_change(&self, token: &str) -> TextResponse {
+        self.http_client
+            .get(&format!("/user/email/change/{token}"), Query::empty())
+            .await
+    }
+
+    pub async fn get_user_profile(&self, username: &str, params: Query) -> TextResponse {
+        self.http_client.get(&format!("/user/{username}/profile"), params).await
+    }
+
+    pub async fn update_user_profile(&self, username: &str, update_profile_form: UpdateProfileForm) -> TextResponse {
+        self.http_client
+            .put(&format!("/user/{username}/profile"), &update_profile_form)
+            .await
+    }
+
+    pub async fn get_api_usage(&self) -> TextResponse {
+        self.http_client.get("/user/api-usage", Query::empty()).await
+    }
+
     pub async fn verify_token(&self, token_verification_form: TokenVerificationForm) -> TextResponse {
         self.http_client.post("/user/token/verify", &token_verification_form).await
     }
@@ -177,6 +504,26 @@ impl Client {
     pub async fn ban_user(&self, username: Username) -> TextResponse {
         self.http_client.delete(&format!("/user/ban/{}", &username.value)).await
     }
+
+    // Context: sync
+
+    pub async fn get_sync_manifest(&self) -> TextResponse {
+        self.http_client.get("/sync/manifest", Query::empty()).await
+    }
+
+    pub async fn get_sync_partition(&self, partition_id: i64, params: Query) -> TextResponse {
+        self.http_client.get(&format!("/sync/partition/{partition_id}"), params).await
+    }
+
+    // Context: widget
+
+    pub async fn get_widget_torrents(&self, params: Query) -> TextResponse {
+        self.http_client.get("/widget/torrents", params).await
+    }
+
+    pub async fn get_widget_torrents_html(&self, params: Query) -> TextResponse {
+        self.http_client.get("/widget/torrents/html", params).await
+    }
 }
 
 /// Generic HTTP Client
@@ -220,6 +567,23 @@ impl Http {
         TextResponse::from(response).await
     }
 
+    pub async fn get_with_header(&self, path: &str, params: Query, name: &str, value: &str) -> TextResponse {
+        let request_builder = reqwest::Client::builder()
+            .timeout(self.timeout)
+            .build()
+            .unwrap()
+            .get(self.base_url(path).clone())
+            .query(&ReqwestQuery::from(params))
+            .header(name, value);
+
+        let request_builder = match &self.connection_info.token {
+            Some(token) => request_builder.bearer_auth(token),
+            None => request_builder,
+        };
+
+        TextResponse::from(request_builder.send().await.unwrap()).await
+    }
+
     pub async fn get_binary(&self, path: &str, params: Query) -> BinaryResponse {
         let response = match &self.connection_info.token {
             Some(token) => reqwest::Client::builder()
@@ -249,6 +613,20 @@ impl Http {
         BinaryResponse::from(response).await
     }
 
+    /// GET request to a path outside the API version prefix.
+    pub async fn get_from_root(&self, path: &str) -> TextResponse {
+        let response = reqwest::Client::builder()
+            .timeout(self.timeout)
+            .build()
+            .unwrap()
+            .get(format!("http://{}{path}", &self.connection_info.bind_address)) // DevSkim: ignore DS137138
+            .send()
+            .await
+            .unwrap();
+
+        TextResponse::from(response).await
+    }
+
     pub async fn inner_get(&self, path: &str) -> Result<reqwest::Response, reqwest::Error> {
         reqwest::Client::builder()
             .timeout(self.timeout)
@@ -278,6 +656,19 @@ impl Http {
         TextResponse::from(response).await
     }
 
+    pub async fn post_empty(&self, path: &str) -> TextResponse {
+        let response = match &self.connection_info.token {
+            Some(token) => reqwest::Client::new()
+                .post(self.base_url(path).clone())
+                .bearer_auth(token)
+                .send()
+                .await
+                .unwrap(),
+            None => reqwest::Client::new().post(self.base_url(path).clone()).send().await.unwrap(),
+        };
+        TextResponse::from(response).await
+    }
+
     pub async fn post_multipart(&self, path: &str, form: multipart::Form) -> TextResponse {
         let response = match &self.connection_info.token {
             Some(token) => reqwest::Client::builder()
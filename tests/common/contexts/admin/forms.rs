@@ -0,0 +1,22 @@
+use serde::Serialize;
+
+#[derive(Serialize)]
+pub struct ImportTorrentsForm {
+    pub directory: String,
+    pub manifest: Option<String>,
+    pub mapping: Option<String>,
+}
+
+#[derive(Serialize)]
+pub struct SendBulkMailForm {
+    pub subject: String,
+    pub body: String,
+    pub segment: serde_json::Value,
+    pub essential: bool,
+}
+
+#[derive(Serialize)]
+pub struct UploadLimitsForm {
+    pub max_uploads_per_day: Option<u32>,
+    pub max_total_upload_size: Option<u64>,
+}
@@ -0,0 +1,54 @@
+use serde::{Deserialize, Serialize};
+
+pub type Id = i64;
+
+#[derive(Deserialize, Debug)]
+pub struct BookmarkResponse {
+    pub data: Bookmark,
+}
+
+#[derive(Deserialize, Debug)]
+pub struct Bookmark {
+    pub torrent_id: Id,
+    pub bookmarked: bool,
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct BookmarksFile {
+    pub format: String,
+    pub version: u32,
+    pub exported_at: String,
+    pub bookmarks: Vec<ExportedBookmark>,
+    #[serde(default)]
+    pub saved_searches: Vec<ExportedSavedSearch>,
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct ExportedBookmark {
+    pub info_hash: String,
+    pub title: String,
+    pub date_created: Option<String>,
+}
+
+#[derive(Serialize, Deserialize, Debug, PartialEq)]
+pub struct ExportedSavedSearch {
+    pub name: String,
+    pub query: String,
+    pub category: Option<String>,
+    pub tags: Vec<String>,
+    pub email_alerts: Option<bool>,
+}
+
+#[derive(Deserialize, Debug)]
+pub struct ImportSummaryResponse {
+    pub data: ImportSummary,
+}
+
+#[derive(Deserialize, Debug)]
+pub struct ImportSummary {
+    pub imported: usize,
+    pub skipped: usize,
+    pub not_found: Vec<String>,
+    pub saved_searches_imported: usize,
+    pub saved_searches_skipped: usize,
+}
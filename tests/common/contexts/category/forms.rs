@@ -7,3 +7,8 @@ pub struct AddCategoryForm {
 }
 
 pub type DeleteCategoryForm = AddCategoryForm;
+
+#[derive(Serialize)]
+pub struct CategoryPolicyForm {
+    pub co_signing: bool,
+}
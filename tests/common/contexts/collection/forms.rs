@@ -0,0 +1,18 @@
+use serde::Serialize;
+
+#[derive(Serialize)]
+pub struct CollectionForm {
+    pub name: String,
+    pub description: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub filter: Option<SmartFilterForm>,
+}
+
+#[derive(Serialize, Default)]
+pub struct SmartFilterForm {
+    pub category: Option<String>,
+    pub tags: Vec<String>,
+    pub uploaded_after: Option<String>,
+    pub uploaded_before: Option<String>,
+    pub max_age_days: Option<u32>,
+}
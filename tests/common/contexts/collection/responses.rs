@@ -0,0 +1,46 @@
+use serde::Deserialize;
+
+use crate::common::contexts::torrent::responses::TorrentList;
+
+pub type Id = i64;
+
+#[derive(Deserialize, Debug)]
+pub struct CollectionResponse {
+    pub data: Collection,
+}
+
+#[derive(Deserialize, Debug)]
+pub struct CollectionListResponse {
+    pub data: Vec<Collection>,
+}
+
+#[derive(Deserialize, Debug)]
+pub struct DeletedCollectionResponse {
+    pub data: Id,
+}
+
+#[derive(Deserialize, Debug)]
+pub struct CollectionPageResponse {
+    pub data: CollectionPage,
+}
+
+#[derive(Deserialize, Debug)]
+pub struct CollectionPage {
+    pub collection: Collection,
+    pub torrents: TorrentList,
+}
+
+#[derive(Deserialize, Debug, PartialEq)]
+pub struct Collection {
+    pub collection_id: Id,
+    pub user_id: i64,
+    pub owner: String,
+    pub name: String,
+    pub description: Option<String>,
+    pub date_created: String,
+    pub torrent_count: i64,
+    pub total_size: i64,
+    pub seeders: i64,
+    pub leechers: i64,
+    pub smart: bool,
+}
@@ -0,0 +1,16 @@
+use serde::Serialize;
+
+#[derive(Serialize)]
+pub struct AddCommentForm {
+    pub text: String,
+}
+
+#[derive(Serialize)]
+pub struct EditCommentForm {
+    pub text: String,
+}
+
+#[derive(Serialize)]
+pub struct ModerateCommentForm {
+    pub hidden: bool,
+}
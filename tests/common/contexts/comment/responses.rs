@@ -0,0 +1,36 @@
+use serde::Deserialize;
+
+pub type Id = i64;
+
+#[derive(Deserialize, Debug)]
+pub struct CommentResponse {
+    pub data: Comment,
+}
+
+#[derive(Deserialize, Debug)]
+pub struct DeletedCommentResponse {
+    pub data: Id,
+}
+
+#[derive(Deserialize, Debug)]
+pub struct CommentListResponse {
+    pub data: CommentList,
+}
+
+#[derive(Deserialize, Debug)]
+pub struct CommentList {
+    pub total: u32,
+    pub results: Vec<Comment>,
+}
+
+#[derive(Deserialize, Debug, PartialEq)]
+pub struct Comment {
+    pub comment_id: Id,
+    pub torrent_id: i64,
+    pub author_id: i64,
+    pub author: String,
+    pub text: String,
+    pub hidden: bool,
+    pub date_created: String,
+    pub date_updated: Option<String>,
+}
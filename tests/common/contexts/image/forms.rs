@@ -0,0 +1,13 @@
+use serde::Serialize;
+
+#[derive(Serialize)]
+pub struct PresignUploadForm {
+    pub kind: String,
+    pub content_type: String,
+    pub size: u64,
+}
+
+#[derive(Serialize)]
+pub struct ConfirmUploadForm {
+    pub object_key: String,
+}
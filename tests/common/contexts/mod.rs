@@ -1,7 +1,14 @@
 pub mod about;
+pub mod admin;
+pub mod bookmark;
 pub mod category;
+pub mod collection;
+pub mod comment;
+pub mod image;
+pub mod report;
 pub mod root;
 pub mod settings;
+pub mod subscription;
 pub mod tag;
 pub mod torrent;
 pub mod user;
@@ -0,0 +1,12 @@
+use serde::Serialize;
+
+#[derive(Serialize)]
+pub struct ReportTorrentForm {
+    pub reason: String,
+    pub details: Option<String>,
+}
+
+#[derive(Serialize)]
+pub struct ResolveReportForm {
+    pub action: String,
+}
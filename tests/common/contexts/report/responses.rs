@@ -0,0 +1,36 @@
+use serde::Deserialize;
+
+pub type Id = i64;
+
+#[derive(Deserialize, Debug)]
+pub struct ReportResponse {
+    pub data: Report,
+}
+
+#[derive(Deserialize, Debug)]
+pub struct ReportListResponse {
+    pub data: ReportList,
+}
+
+#[derive(Deserialize, Debug)]
+pub struct ReportList {
+    pub total: u32,
+    pub results: Vec<Report>,
+}
+
+#[derive(Deserialize, Debug, PartialEq)]
+pub struct Report {
+    pub report_id: Id,
+    pub torrent_id: i64,
+    pub info_hash: String,
+    pub title: String,
+    pub uploader: String,
+    pub reporter_id: i64,
+    pub reporter: String,
+    pub reason: String,
+    pub details: Option<String>,
+    pub resolved: bool,
+    pub resolution: Option<String>,
+    pub date_created: String,
+    pub date_resolved: Option<String>,
+}
@@ -0,0 +1,7 @@
+use serde::Serialize;
+
+#[derive(Serialize)]
+pub struct AddSubscriptionForm {
+    pub category_id: Option<i64>,
+    pub tag_id: Option<i64>,
+}
@@ -0,0 +1,30 @@
+use serde::Deserialize;
+
+pub type Id = i64;
+
+#[derive(Deserialize, Debug)]
+pub struct SubscriptionResponse {
+    pub data: Subscription,
+}
+
+#[derive(Deserialize, Debug)]
+pub struct SubscriptionListResponse {
+    pub data: Vec<Subscription>,
+}
+
+#[derive(Deserialize, Debug)]
+pub struct DeletedSubscriptionResponse {
+    pub data: Id,
+}
+
+#[derive(Deserialize, Debug, PartialEq)]
+pub struct Subscription {
+    pub subscription_id: Id,
+    pub user_id: i64,
+    pub username: String,
+    pub category_id: Option<i64>,
+    pub category: Option<String>,
+    pub tag_id: Option<i64>,
+    pub tag: Option<String>,
+    pub date_created: String,
+}
@@ -9,3 +9,9 @@ pub struct AddTagForm {
 pub struct DeleteTagForm {
     pub tag_id: i64,
 }
+
+#[derive(Serialize)]
+pub struct MergeTagsForm {
+    pub source_tag_id: i64,
+    pub target_tag_id: i64,
+}
@@ -22,6 +22,7 @@ pub fn assert_expected_torrent_details(torrent: &TorrentDetails, expected_torren
         ("leechers", torrent.leechers == expected_torrent.leechers),
         ("files", torrent.files == expected_torrent.files),
         ("trackers", torrent.trackers == expected_torrent.trackers),
+        ("tracker_tiers", torrent.tracker_tiers == expected_torrent.tracker_tiers),
         ("magnet_link", torrent.magnet_link == expected_torrent.magnet_link),
         ("tags", torrent.tags == expected_torrent.tags),
         ("name", torrent.name == expected_torrent.name),
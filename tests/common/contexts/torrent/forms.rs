@@ -11,6 +11,20 @@ pub struct UpdateTorrentFrom {
     pub tags: Option<Vec<i64>>,
 }
 
+#[derive(Deserialize, Serialize)]
+pub struct UpdateTorrentTrackersForm {
+    pub tiers: Vec<Vec<String>>,
+}
+
+#[derive(Deserialize, Serialize)]
+pub struct AddMagnetForm {
+    pub magnet_uri: String,
+    pub title: Option<String>,
+    pub description: Option<String>,
+    pub category: String,
+    pub tags: Option<Vec<i64>>,
+}
+
 use reqwest::multipart::Form;
 
 pub struct UploadTorrentMultipartForm {
@@ -44,6 +44,12 @@ pub struct ListItem {
     pub creation_date: Option<i64>,
     pub created_by: Option<String>,
     pub encoding: Option<String>,
+    pub status: String,
+}
+
+#[derive(Deserialize, PartialEq, Debug)]
+pub struct ModeratedTorrentResponse {
+    pub data: ListItem,
 }
 
 #[derive(Deserialize, PartialEq, Debug)]
@@ -65,6 +71,7 @@ pub struct TorrentDetails {
     pub leechers: u64,
     pub files: Vec<File>,
     pub trackers: Vec<String>,
+    pub tracker_tiers: Vec<Vec<String>>,
     pub magnet_link: String,
     pub tags: Vec<Tag>,
     pub name: String,
@@ -73,6 +80,7 @@ pub struct TorrentDetails {
     pub created_by: Option<String>,
     pub encoding: Option<String>,
     pub canonical_info_hash_group: Vec<String>,
+    pub bookmarked: bool,
 }
 
 #[derive(Deserialize, PartialEq, Debug)]
@@ -42,3 +42,15 @@ pub struct ChangePasswordForm {
     pub password: String,
     pub confirm_password: String,
 }
+
+#[derive(Clone, Serialize)]
+pub struct UpdateProfileForm {
+    pub bio: Option<String>,
+    pub avatar: Option<String>,
+}
+
+#[derive(Serialize)]
+pub struct ChangeEmailForm {
+    pub email: String,
+    pub current_password: String,
+}
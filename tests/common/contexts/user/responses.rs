@@ -1,5 +1,7 @@
 use serde::Deserialize;
 
+use crate::common::contexts::torrent::responses::TorrentList;
+
 #[allow(dead_code)]
 #[derive(Deserialize, Debug)]
 pub struct AddedUserResponse {
@@ -45,3 +47,51 @@ pub struct TokenRenewalData {
     pub username: String,
     pub admin: bool,
 }
+
+#[derive(Deserialize, Debug)]
+pub struct ProfilePageResponse {
+    pub data: ProfilePage,
+}
+
+#[derive(Deserialize, Debug)]
+pub struct ProfilePage {
+    pub profile: PublicProfile,
+    pub avatar_proxy_url: Option<String>,
+    pub torrents: TorrentList,
+}
+
+#[derive(Deserialize, Debug)]
+pub struct ProfileResponse {
+    pub data: PublicProfile,
+}
+
+#[derive(Deserialize, PartialEq, Debug)]
+pub struct PublicProfile {
+    pub username: String,
+    pub bio: Option<String>,
+    pub avatar: Option<String>,
+    pub uploads: i64,
+}
+
+#[derive(Deserialize, Debug)]
+pub struct ApiUsageResponse {
+    pub data: ApiUsageSummary,
+}
+
+#[derive(Deserialize, Debug)]
+pub struct ApiUsageSummary {
+    pub daily: Vec<DailyApiUsage>,
+    pub credentials: Vec<CredentialUsage>,
+}
+
+#[derive(Deserialize, Debug)]
+pub struct DailyApiUsage {
+    pub requests: i64,
+}
+
+#[derive(Deserialize, Debug)]
+pub struct CredentialUsage {
+    pub credential: String,
+    pub current: bool,
+    pub requests: i64,
+}
@@ -4,6 +4,7 @@ use reqwest::Response as ReqwestResponse;
 pub struct TextResponse {
     pub status: u16,
     pub content_type: Option<String>,
+    pub etag: Option<String>,
     pub body: String,
 }
 
@@ -15,6 +16,7 @@ impl TextResponse {
                 .headers()
                 .get("content-type")
                 .map(|content_type| content_type.to_str().unwrap().to_owned()),
+            etag: response.headers().get("etag").map(|etag| etag.to_str().unwrap().to_owned()),
             body: response.text().await.unwrap(),
         }
     }
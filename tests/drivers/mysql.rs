@@ -0,0 +1,72 @@
+use std::env;
+
+use tokio::sync::{Mutex, MutexGuard};
+use torrust_index::databases::database::{self, Database};
+
+use super::scenarios;
+
+/// `SQLx` connection string of the `MySQL` database for the driver parity
+/// tests. The tests are skipped when it's not set.
+pub const ENV_VAR_MYSQL_CONNECT_URL: &str = "TORRUST_INDEX_DRIVERS_MYSQL_CONNECT_URL";
+
+/// The scenarios share the same database, so they run one at a time.
+static DATABASE_LOCK: Mutex<()> = Mutex::const_new(());
+
+/// It connects to the `MySQL` database, if any.
+async fn database() -> Option<(MutexGuard<'static, ()>, Box<dyn Database>)> {
+    let Ok(connect_url) = env::var(ENV_VAR_MYSQL_CONNECT_URL) else {
+        println!("test skipped. It requires the env var {ENV_VAR_MYSQL_CONNECT_URL}.");
+        return None;
+    };
+
+    let guard = DATABASE_LOCK.lock().await;
+
+    let database = database::connect(&connect_url).await.expect("Database error.");
+
+    Some((guard, database))
+}
+
+#[tokio::test]
+async fn it_should_paginate_like_the_other_drivers() {
+    let Some((_guard, database)) = database().await else {
+        return;
+    };
+
+    scenarios::pagination(database.as_ref()).await;
+}
+
+#[tokio::test]
+async fn it_should_compare_usernames_like_the_other_drivers() {
+    let Some((_guard, database)) = database().await else {
+        return;
+    };
+
+    scenarios::username_case_insensitivity(database.as_ref()).await;
+}
+
+#[tokio::test]
+async fn it_should_compare_accents_in_usernames_like_the_other_drivers() {
+    let Some((_guard, database)) = database().await else {
+        return;
+    };
+
+    scenarios::username_accent_sensitivity(database.as_ref()).await;
+}
+
+#[tokio::test]
+async fn it_should_roll_back_transactions_like_the_other_drivers() {
+    let Some((_guard, database)) = database().await else {
+        return;
+    };
+
+    scenarios::transaction_rollback(database.as_ref()).await;
+}
+
+#[tokio::test]
+async fn it_should_redo_the_last_migration_like_the_other_drivers() {
+    let Some((_guard, database)) = database().await else {
+        return;
+    };
+
+    scenarios::migration_redo(database.as_ref()).await;
+}
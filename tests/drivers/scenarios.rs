@@ -0,0 +1,127 @@
+//! Scenarios shared by all the database drivers.
+//!
+//! Each scenario only uses the [`Database`] trait and data created by the
+//! scenario itself, so that it can run on a database with other data.
+use torrust_index::databases::database::{Database, Error};
+use torrust_index::models::audit::{AuditAction, AuditLogFilter};
+use torrust_index::models::user::UserId;
+
+use crate::common::random;
+
+/// It returns a random username with lowercase letters only.
+fn random_username() -> String {
+    format!("parity{}", random::string(12).to_lowercase())
+}
+
+async fn add_user(database: &dyn Database, username: &str) -> UserId {
+    database
+        .insert_user_and_get_id(username, &format!("{username}@torrust.com"), "password_hash")
+        .await
+        .expect("the user should be added")
+}
+
+/// Pages have the requested size, except the last one, and the total does
+/// not depend on the page.
+pub async fn pagination(database: &dyn Database) {
+    let username = random_username();
+    let user_id = add_user(database, &username).await;
+
+    for target in 1..=5 {
+        database
+            .insert_audit_entry(Some(user_id), &AuditAction::UserBan, &target.to_string(), None)
+            .await
+            .unwrap();
+    }
+
+    let filter = AuditLogFilter {
+        actor: Some(username),
+        ..AuditLogFilter::default()
+    };
+
+    // Entries are listed from the newest to the oldest
+    for (offset, expected_targets) in [(0, vec!["5", "4"]), (2, vec!["3", "2"]), (4, vec!["1"]), (6, vec![])] {
+        let response = database.get_audit_log_paginated(&filter, offset, 2).await.unwrap();
+
+        let targets: Vec<String> = response.results.into_iter().map(|entry| entry.target).collect();
+
+        assert_eq!(response.total, 5);
+        assert_eq!(targets, expected_targets, "page at offset {offset}");
+    }
+}
+
+/// Usernames are unique regardless of case, and users are found by their
+/// username regardless of case.
+pub async fn username_case_insensitivity(database: &dyn Database) {
+    let username = random_username();
+    add_user(database, &username).await;
+
+    let result = database
+        .insert_user_and_get_id(
+            &username.to_uppercase(),
+            &format!("other{username}@torrust.com"),
+            "password_hash",
+        )
+        .await;
+
+    assert!(matches!(result, Err(Error::UsernameTaken)));
+
+    let user_profile = database
+        .get_user_profile_from_username(&username.to_uppercase())
+        .await
+        .unwrap();
+
+    assert_eq!(user_profile.username, username);
+}
+
+/// Usernames differing only by accents are different usernames.
+pub async fn username_accent_sensitivity(database: &dyn Database) {
+    let username = random_username();
+    add_user(database, &format!("{username}e")).await;
+
+    let accented_username = format!("{username}\u{e9}");
+    add_user(database, &accented_username).await;
+
+    let user_profile = database.get_user_profile_from_username(&accented_username).await.unwrap();
+
+    assert_eq!(user_profile.username, accented_username);
+}
+
+/// A failed operation in a transaction does not leave partial changes.
+pub async fn transaction_rollback(database: &dyn Database) {
+    let username = random_username();
+    add_user(database, &username).await;
+
+    let users_before = database.count_users().await.unwrap();
+
+    // The account is created before the profile, which fails because the
+    // email is taken
+    let other_username = random_username();
+    let result = database
+        .insert_user_and_get_id(&other_username, &format!("{username}@torrust.com"), "password_hash")
+        .await;
+
+    assert!(matches!(result, Err(Error::EmailTaken)));
+    assert_eq!(database.count_users().await.unwrap(), users_before);
+    assert!(matches!(
+        database.get_user_profile_from_username(&other_username).await,
+        Err(Error::UserNotFound)
+    ));
+}
+
+/// The last migration can be reverted with its down script and applied
+/// again, keeping the rows of the other tables.
+pub async fn migration_redo(database: &dyn Database) {
+    let username = random_username();
+    add_user(database, &username).await;
+
+    let last_version = database.get_migrations().await.unwrap().last().unwrap().version;
+
+    assert_eq!(database.redo_last_migration().await.unwrap(), last_version);
+    assert!(database
+        .get_migrations()
+        .await
+        .unwrap()
+        .iter()
+        .all(|migration| migration.applied));
+    assert!(database.get_user_profile_from_username(&username).await.is_ok());
+}
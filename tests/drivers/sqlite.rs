@@ -0,0 +1,105 @@
+use sqlx::sqlite::SqlitePoolOptions;
+use sqlx::{query, Sqlite};
+use tempfile::TempDir;
+use torrust_index::databases::database::{self, Database};
+
+use super::scenarios;
+
+/// It creates a new database in a temporary directory. The directory is
+/// removed when the returned `TempDir` is dropped.
+async fn new_database() -> (TempDir, Box<dyn Database>) {
+    let temp_dir = TempDir::new().expect("failed to create a temporary directory");
+
+    let connect_url = format!("sqlite://{}/data.db?mode=rwc", temp_dir.path().display());
+
+    let database = database::connect(&connect_url).await.expect("Database error.");
+
+    (temp_dir, database)
+}
+
+#[tokio::test]
+async fn it_should_paginate_like_the_other_drivers() {
+    let (_temp_dir, database) = new_database().await;
+
+    scenarios::pagination(database.as_ref()).await;
+}
+
+#[tokio::test]
+async fn it_should_compare_usernames_like_the_other_drivers() {
+    let (_temp_dir, database) = new_database().await;
+
+    scenarios::username_case_insensitivity(database.as_ref()).await;
+}
+
+#[tokio::test]
+async fn it_should_compare_accents_in_usernames_like_the_other_drivers() {
+    let (_temp_dir, database) = new_database().await;
+
+    scenarios::username_accent_sensitivity(database.as_ref()).await;
+}
+
+#[tokio::test]
+async fn it_should_roll_back_transactions_like_the_other_drivers() {
+    let (_temp_dir, database) = new_database().await;
+
+    scenarios::transaction_rollback(database.as_ref()).await;
+}
+
+#[tokio::test]
+async fn it_should_redo_the_last_migration_like_the_other_drivers() {
+    let (_temp_dir, database) = new_database().await;
+
+    scenarios::migration_redo(database.as_ref()).await;
+}
+
+/// Version of the migration making usernames case-insensitive.
+const CASE_INSENSITIVE_USERNAMES_MIGRATION: i64 = 20_241_017_080_000;
+
+#[tokio::test]
+async fn it_should_rename_the_usernames_differing_only_by_case_before_making_them_unique() {
+    let temp_dir = TempDir::new().expect("failed to create a temporary directory");
+
+    let connect_url = format!("sqlite://{}/data.db?mode=rwc", temp_dir.path().display());
+
+    // A database created before usernames were case-insensitive
+    let pool = SqlitePoolOptions::new().connect(&connect_url).await.unwrap();
+
+    let mut migrator = sqlx::migrate!("migrations/sqlite3");
+
+    migrator.migrations = migrator
+        .migrations
+        .iter()
+        .filter(|migration| migration.version < CASE_INSENSITIVE_USERNAMES_MIGRATION)
+        .cloned()
+        .collect::<Vec<_>>()
+        .into();
+
+    migrator.run(&pool).await.unwrap();
+
+    for (user_id, username) in [(1, "alice"), (2, "Alice"), (3, "ALICE"), (4, "bob")] {
+        query::<Sqlite>("INSERT INTO torrust_users (user_id, date_registered) VALUES (?, '2024-10-17 10:00:00')")
+            .bind(user_id)
+            .execute(&pool)
+            .await
+            .unwrap();
+
+        query::<Sqlite>("INSERT INTO torrust_user_profiles (user_id, username) VALUES (?, ?)")
+            .bind(user_id)
+            .bind(username)
+            .execute(&pool)
+            .await
+            .unwrap();
+    }
+
+    pool.close().await;
+
+    // The pending migrations are applied
+    let database = database::connect(&connect_url).await.expect("Database error.");
+
+    for (username, user_id) in [("alice", 1), ("Alice_2", 2), ("ALICE_3", 3), ("bob", 4)] {
+        let user_profile = database.get_user_profile_from_username(username).await.unwrap();
+
+        assert_eq!(user_profile.username, username);
+        assert_eq!(user_profile.user_id, user_id);
+    }
+}
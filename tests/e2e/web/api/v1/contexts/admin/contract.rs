@@ -0,0 +1,508 @@
+//! API contract for `admin` context.
+
+use torrust_index::web::api;
+
+use crate::common::client::Client;
+use crate::common::contexts::admin::forms::{ImportTorrentsForm, SendBulkMailForm, UploadLimitsForm};
+use crate::common::contexts::torrent::fixtures::random_torrent;
+use crate::common::contexts::torrent::forms::UploadTorrentMultipartForm;
+use crate::common::contexts::torrent::responses::{ModeratedTorrentResponse, TorrentListResponse};
+use crate::common::contexts::user::forms::Username;
+use crate::common::http::{Query, QueryParam};
+use crate::e2e::environment::TestEnv;
+use crate::e2e::web::api::v1::contexts::torrent::steps::upload_random_torrent_to_index;
+use crate::e2e::web::api::v1::contexts::user::steps::{new_logged_in_admin, new_logged_in_user};
+
+#[tokio::test]
+async fn it_should_not_allow_non_admins_to_get_the_pending_torrents() {
+    let mut env = TestEnv::new();
+    env.start(api::Version::V1).await;
+
+    let logged_in_user = new_logged_in_user(&env).await;
+    let client = Client::authenticated(&env.server_socket_addr().unwrap(), &logged_in_user.token);
+
+    let response = client.get_pending_torrents(Query::empty()).await;
+
+    assert_eq!(response.status, 403);
+}
+
+#[tokio::test]
+async fn it_should_allow_admins_to_get_the_pending_torrents() {
+    let mut env = TestEnv::new();
+    env.start(api::Version::V1).await;
+
+    let logged_in_admin = new_logged_in_admin(&env).await;
+    let client = Client::authenticated(&env.server_socket_addr().unwrap(), &logged_in_admin.token);
+
+    let response = client.get_pending_torrents(Query::empty()).await;
+
+    assert!(response.is_json_and_ok());
+}
+
+#[tokio::test]
+async fn it_should_not_allow_non_admins_to_approve_or_reject_torrents() {
+    let mut env = TestEnv::new();
+    env.start(api::Version::V1).await;
+
+    if !env.provides_a_tracker() {
+        println!("test skipped. It requires a tracker to be running.");
+        return;
+    }
+
+    let uploader = new_logged_in_user(&env).await;
+    let (_test_torrent, uploaded_torrent) = upload_random_torrent_to_index(&uploader, &env).await;
+
+    let client = Client::authenticated(&env.server_socket_addr().unwrap(), &uploader.token);
+
+    let response = client.reject_torrent(uploaded_torrent.torrent_id).await;
+    assert_eq!(response.status, 403);
+
+    let response = client.approve_torrent(uploaded_torrent.torrent_id).await;
+    assert_eq!(response.status, 403);
+}
+
+#[tokio::test]
+async fn it_should_exclude_rejected_torrents_from_the_public_listing() {
+    let mut env = TestEnv::new();
+    env.start(api::Version::V1).await;
+
+    if !env.provides_a_tracker() {
+        println!("test skipped. It requires a tracker to be running.");
+        return;
+    }
+
+    let uploader = new_logged_in_user(&env).await;
+    let (_test_torrent, uploaded_torrent) = upload_random_torrent_to_index(&uploader, &env).await;
+
+    let admin = new_logged_in_admin(&env).await;
+    let admin_client = Client::authenticated(&env.server_socket_addr().unwrap(), &admin.token);
+
+    let response = admin_client.reject_torrent(uploaded_torrent.torrent_id).await;
+
+    let res: ModeratedTorrentResponse = serde_json::from_str(&response.body).unwrap();
+    assert_eq!(res.data.status, "rejected");
+
+    let client = Client::unauthenticated(&env.server_socket_addr().unwrap());
+    let search = vec![QueryParam::new("search", &uploaded_torrent.title)];
+
+    let response = client.get_torrents(Query::with_params(search.clone())).await;
+    let torrent_list_response: TorrentListResponse = serde_json::from_str(&response.body).unwrap();
+    assert_eq!(torrent_list_response.data.total, 0);
+
+    let response = admin_client.approve_torrent(uploaded_torrent.torrent_id).await;
+
+    let res: ModeratedTorrentResponse = serde_json::from_str(&response.body).unwrap();
+    assert_eq!(res.data.status, "approved");
+
+    let response = client.get_torrents(Query::with_params(search)).await;
+    let torrent_list_response: TorrentListResponse = serde_json::from_str(&response.body).unwrap();
+    assert_eq!(torrent_list_response.data.total, 1);
+}
+
+#[tokio::test]
+async fn it_should_not_allow_non_admins_to_get_the_stats_importer_status() {
+    let mut env = TestEnv::new();
+    env.start(api::Version::V1).await;
+
+    let logged_in_user = new_logged_in_user(&env).await;
+    let client = Client::authenticated(&env.server_socket_addr().unwrap(), &logged_in_user.token);
+
+    let response = client.get_stats_importer_status().await;
+
+    assert_eq!(response.status, 403);
+}
+
+#[tokio::test]
+async fn it_should_allow_admins_to_get_the_stats_importer_status() {
+    let mut env = TestEnv::new();
+    env.start(api::Version::V1).await;
+
+    let logged_in_admin = new_logged_in_admin(&env).await;
+    let client = Client::authenticated(&env.server_socket_addr().unwrap(), &logged_in_admin.token);
+
+    let response = client.get_stats_importer_status().await;
+
+    assert!(response.is_json_and_ok());
+
+    let res: serde_json::Value = serde_json::from_str(&response.body).unwrap();
+
+    assert!(res["data"]["consecutive_failures"].is_u64());
+    assert!(res["data"]["failures_by_error"].is_object());
+}
+
+#[tokio::test]
+async fn it_should_not_allow_non_admins_to_get_the_download_statistics() {
+    let mut env = TestEnv::new();
+    env.start(api::Version::V1).await;
+
+    let logged_in_user = new_logged_in_user(&env).await;
+    let client = Client::authenticated(&env.server_socket_addr().unwrap(), &logged_in_user.token);
+
+    let response = client.get_download_statistics(Query::empty()).await;
+
+    assert_eq!(response.status, 403);
+}
+
+#[tokio::test]
+async fn it_should_allow_admins_to_get_the_download_statistics() {
+    let mut env = TestEnv::new();
+    env.start(api::Version::V1).await;
+
+    let logged_in_admin = new_logged_in_admin(&env).await;
+    let client = Client::authenticated(&env.server_socket_addr().unwrap(), &logged_in_admin.token);
+
+    let response = client
+        .get_download_statistics(Query::with_params(vec![QueryParam::new("days", "7")]))
+        .await;
+
+    assert!(response.is_json_and_ok());
+
+    let res: serde_json::Value = serde_json::from_str(&response.body).unwrap();
+
+    assert_eq!(res["data"]["days"], 7);
+    assert!(res["data"]["enabled"].is_boolean());
+    assert!(res["data"]["clients"].is_array());
+}
+
+#[tokio::test]
+async fn it_should_not_allow_guests_to_import_torrents() {
+    let mut env = TestEnv::new();
+    env.start(api::Version::V1).await;
+
+    let client = Client::unauthenticated(&env.server_socket_addr().unwrap());
+
+    let response = client
+        .import_torrents(ImportTorrentsForm {
+            directory: std::env::temp_dir().to_string_lossy().to_string(),
+            manifest: None,
+            mapping: None,
+        })
+        .await;
+
+    assert_eq!(response.status, 401);
+}
+
+#[tokio::test]
+async fn it_should_not_allow_non_admins_to_import_torrents() {
+    let mut env = TestEnv::new();
+    env.start(api::Version::V1).await;
+
+    let logged_in_user = new_logged_in_user(&env).await;
+    let client = Client::authenticated(&env.server_socket_addr().unwrap(), &logged_in_user.token);
+
+    let response = client
+        .import_torrents(ImportTorrentsForm {
+            directory: std::env::temp_dir().to_string_lossy().to_string(),
+            manifest: None,
+            mapping: None,
+        })
+        .await;
+
+    assert_eq!(response.status, 403);
+}
+
+#[tokio::test]
+async fn it_should_reject_imports_from_a_directory_that_does_not_exist() {
+    let mut env = TestEnv::new();
+    env.start(api::Version::V1).await;
+
+    let logged_in_admin = new_logged_in_admin(&env).await;
+    let client = Client::authenticated(&env.server_socket_addr().unwrap(), &logged_in_admin.token);
+
+    let response = client
+        .import_torrents(ImportTorrentsForm {
+            directory: "/path/that/does/not/exist".to_string(),
+            manifest: None,
+            mapping: None,
+        })
+        .await;
+
+    assert_eq!(response.status, 400);
+    assert!(response.body.contains("The import directory can't be read."));
+}
+
+fn announcement(subject: &str) -> SendBulkMailForm {
+    SendBulkMailForm {
+        subject: subject.to_string(),
+        body: "Hi {{ username }}, the index will be down for maintenance.".to_string(),
+        segment: serde_json::json!({ "type": "role", "role": "administrator" }),
+        essential: false,
+    }
+}
+
+#[tokio::test]
+async fn it_should_not_allow_guests_to_send_bulk_emails() {
+    let mut env = TestEnv::new();
+    env.start(api::Version::V1).await;
+
+    let client = Client::unauthenticated(&env.server_socket_addr().unwrap());
+
+    let response = client.send_bulk_mail(announcement("Maintenance")).await;
+
+    assert_eq!(response.status, 401);
+}
+
+#[tokio::test]
+async fn it_should_not_allow_non_admins_to_send_bulk_emails() {
+    let mut env = TestEnv::new();
+    env.start(api::Version::V1).await;
+
+    let logged_in_user = new_logged_in_user(&env).await;
+    let client = Client::authenticated(&env.server_socket_addr().unwrap(), &logged_in_user.token);
+
+    let response = client.send_bulk_mail(announcement("Maintenance")).await;
+
+    assert_eq!(response.status, 403);
+}
+
+#[tokio::test]
+async fn it_should_reject_bulk_emails_without_a_subject() {
+    let mut env = TestEnv::new();
+    env.start(api::Version::V1).await;
+
+    let logged_in_admin = new_logged_in_admin(&env).await;
+    let client = Client::authenticated(&env.server_socket_addr().unwrap(), &logged_in_admin.token);
+
+    let response = client.send_bulk_mail(announcement(" ")).await;
+
+    assert_eq!(response.status, 400);
+    assert!(response.body.contains("The email subject and body are required."));
+}
+
+#[tokio::test]
+async fn it_should_allow_admins_to_queue_bulk_emails() {
+    let mut env = TestEnv::new();
+    env.start(api::Version::V1).await;
+
+    let logged_in_admin = new_logged_in_admin(&env).await;
+    let client = Client::authenticated(&env.server_socket_addr().unwrap(), &logged_in_admin.token);
+
+    let response = client.send_bulk_mail(announcement("Maintenance")).await;
+
+    assert!(response.is_json_and_ok());
+
+    let res: serde_json::Value = serde_json::from_str(&response.body).unwrap();
+
+    assert!(res["data"]["queued"].is_u64());
+}
+
+#[tokio::test]
+async fn it_should_not_allow_non_admins_to_get_the_inactivity_report() {
+    let mut env = TestEnv::new();
+    env.start(api::Version::V1).await;
+
+    let logged_in_user = new_logged_in_user(&env).await;
+    let client = Client::authenticated(&env.server_socket_addr().unwrap(), &logged_in_user.token);
+
+    let response = client.get_inactivity_report(Query::empty()).await;
+
+    assert_eq!(response.status, 403);
+}
+
+#[tokio::test]
+async fn it_should_allow_admins_to_exempt_users_from_the_inactivity_policy() {
+    let mut env = TestEnv::new();
+    env.start(api::Version::V1).await;
+
+    let logged_in_admin = new_logged_in_admin(&env).await;
+    let logged_in_user = new_logged_in_user(&env).await;
+    let client = Client::authenticated(&env.server_socket_addr().unwrap(), &logged_in_admin.token);
+
+    let response = client.add_inactivity_exemption(&logged_in_user.username).await;
+
+    assert!(response.is_json_and_ok());
+
+    let response = client.get_inactivity_report(Query::empty()).await;
+
+    assert!(response.is_json_and_ok());
+
+    let res: serde_json::Value = serde_json::from_str(&response.body).unwrap();
+
+    assert!(res["data"]["exemptions"]
+        .as_array()
+        .unwrap()
+        .contains(&serde_json::Value::String(logged_in_user.username.clone())));
+
+    let response = client.remove_inactivity_exemption(&logged_in_user.username).await;
+
+    assert!(response.is_json_and_ok());
+}
+
+#[tokio::test]
+async fn it_should_not_exempt_users_that_do_not_exist() {
+    let mut env = TestEnv::new();
+    env.start(api::Version::V1).await;
+
+    let logged_in_admin = new_logged_in_admin(&env).await;
+    let client = Client::authenticated(&env.server_socket_addr().unwrap(), &logged_in_admin.token);
+
+    let response = client.add_inactivity_exemption("no-such-user").await;
+
+    assert_eq!(response.status, 404);
+}
+
+#[tokio::test]
+async fn it_should_allow_admins_to_override_the_upload_limits_of_a_user() {
+    let mut env = TestEnv::new();
+    env.start(api::Version::V1).await;
+
+    let logged_in_admin = new_logged_in_admin(&env).await;
+    let logged_in_user = new_logged_in_user(&env).await;
+    let client = Client::authenticated(&env.server_socket_addr().unwrap(), &logged_in_admin.token);
+
+    let response = client
+        .set_upload_limits(
+            &logged_in_user.username,
+            UploadLimitsForm {
+                max_uploads_per_day: Some(0),
+                max_total_upload_size: None,
+            },
+        )
+        .await;
+
+    assert!(response.is_json_and_ok());
+
+    let response = client.get_upload_quota(&logged_in_user.username).await;
+
+    assert!(response.is_json_and_ok());
+
+    let res: serde_json::Value = serde_json::from_str(&response.body).unwrap();
+
+    assert_eq!(res["data"]["overridden"], true);
+    assert_eq!(res["data"]["limits"]["max_uploads_per_day"], 0);
+
+    let user_client = Client::authenticated(&env.server_socket_addr().unwrap(), &logged_in_user.token);
+
+    let form: UploadTorrentMultipartForm = random_torrent().index_info.into();
+
+    let response = user_client.upload_torrent(form.into()).await;
+
+    assert_eq!(response.status, 429);
+
+    let response = client.remove_upload_limits(&logged_in_user.username).await;
+
+    assert!(response.is_json_and_ok());
+
+    let response = client.get_upload_quota(&logged_in_user.username).await;
+
+    let res: serde_json::Value = serde_json::from_str(&response.body).unwrap();
+
+    assert_eq!(res["data"]["overridden"], false);
+}
+
+#[tokio::test]
+async fn it_should_not_allow_non_admins_to_set_upload_limits() {
+    let mut env = TestEnv::new();
+    env.start(api::Version::V1).await;
+
+    let logged_in_user = new_logged_in_user(&env).await;
+    let client = Client::authenticated(&env.server_socket_addr().unwrap(), &logged_in_user.token);
+
+    let response = client
+        .set_upload_limits(
+            &logged_in_user.username,
+            UploadLimitsForm {
+                max_uploads_per_day: None,
+                max_total_upload_size: None,
+            },
+        )
+        .await;
+
+    assert_eq!(response.status, 403);
+}
+
+#[tokio::test]
+async fn it_should_not_allow_non_admins_to_get_the_rate_limit_counters() {
+    let mut env = TestEnv::new();
+    env.start(api::Version::V1).await;
+
+    let logged_in_user = new_logged_in_user(&env).await;
+    let client = Client::authenticated(&env.server_socket_addr().unwrap(), &logged_in_user.token);
+
+    let response = client.get_rate_limit_counters(Query::empty()).await;
+
+    assert_eq!(response.status, 403);
+}
+
+#[tokio::test]
+async fn it_should_allow_admins_to_get_the_rate_limit_counters_of_a_user() {
+    let mut env = TestEnv::new();
+    env.start(api::Version::V1).await;
+
+    let logged_in_user = new_logged_in_user(&env).await;
+    let logged_in_admin = new_logged_in_admin(&env).await;
+    let client = Client::authenticated(&env.server_socket_addr().unwrap(), &logged_in_admin.token);
+
+    let response = client
+        .get_rate_limit_counters(Query::with_params(vec![QueryParam::new(
+            "username",
+            &logged_in_user.username,
+        )]))
+        .await;
+
+    assert!(response.is_json_and_ok());
+
+    let res: serde_json::Value = serde_json::from_str(&response.body).unwrap();
+
+    assert!(res["data"].is_array());
+}
+
+#[tokio::test]
+async fn it_should_not_allow_non_admins_to_get_the_audit_log() {
+    let mut env = TestEnv::new();
+    env.start(api::Version::V1).await;
+
+    let logged_in_user = new_logged_in_user(&env).await;
+    let client = Client::authenticated(&env.server_socket_addr().unwrap(), &logged_in_user.token);
+
+    let response = client.get_audit_log(Query::empty()).await;
+
+    assert_eq!(response.status, 403);
+}
+
+#[tokio::test]
+async fn it_should_record_banning_a_user_in_the_audit_log() {
+    let mut env = TestEnv::new();
+    env.start(api::Version::V1).await;
+
+    let logged_in_user = new_logged_in_user(&env).await;
+    let logged_in_admin = new_logged_in_admin(&env).await;
+    let client = Client::authenticated(&env.server_socket_addr().unwrap(), &logged_in_admin.token);
+
+    let response = client.ban_user(Username::new(logged_in_user.username.clone())).await;
+
+    assert_eq!(response.status, 200);
+
+    let response = client
+        .get_audit_log(Query::with_params(vec![
+            QueryParam::new("actor", &logged_in_admin.username),
+            QueryParam::new("action", "user_ban"),
+        ]))
+        .await;
+
+    assert!(response.is_json_and_ok());
+
+    let res: serde_json::Value = serde_json::from_str(&response.body).unwrap();
+
+    assert_eq!(res["data"]["total"], 1);
+    assert_eq!(res["data"]["results"][0]["target"], logged_in_user.username);
+    assert_eq!(res["data"]["results"][0]["actor"], logged_in_admin.username);
+}
+
+#[tokio::test]
+async fn it_should_reject_an_invalid_audit_log_date_range() {
+    let mut env = TestEnv::new();
+    env.start(api::Version::V1).await;
+
+    let logged_in_admin = new_logged_in_admin(&env).await;
+    let client = Client::authenticated(&env.server_socket_addr().unwrap(), &logged_in_admin.token);
+
+    let response = client
+        .get_audit_log(Query::with_params(vec![
+            QueryParam::new("from", "2024-10-31"),
+            QueryParam::new("to", "2024-10-01"),
+        ]))
+        .await;
+
+    assert_eq!(response.status, 400);
+}
@@ -0,0 +1,183 @@
+//! API contract for `bookmark` context.
+
+use torrust_index::web::api;
+
+use crate::common::client::Client;
+use crate::common::contexts::bookmark::responses::{BookmarkResponse, BookmarksFile, ExportedBookmark, ImportSummaryResponse};
+use crate::common::contexts::torrent::responses::{TorrentDetailsResponse, TorrentListResponse};
+use crate::common::http::{Query, QueryParam};
+use crate::e2e::environment::TestEnv;
+use crate::e2e::web::api::v1::contexts::torrent::steps::upload_random_torrent_to_index;
+use crate::e2e::web::api::v1::contexts::user::steps::new_logged_in_user;
+
+#[tokio::test]
+async fn it_should_allow_registered_users_to_bookmark_a_torrent() {
+    let mut env = TestEnv::new();
+    env.start(api::Version::V1).await;
+
+    if !env.provides_a_tracker() {
+        println!("test skipped. It requires a tracker to be running.");
+        return;
+    }
+
+    let uploader = new_logged_in_user(&env).await;
+    let (test_torrent, uploaded_torrent) = upload_random_torrent_to_index(&uploader, &env).await;
+
+    let client = Client::authenticated(&env.server_socket_addr().unwrap(), &uploader.token);
+
+    let response = client.add_bookmark(&test_torrent.file_info_hash()).await;
+
+    let res: BookmarkResponse = serde_json::from_str(&response.body).unwrap();
+
+    assert_eq!(res.data.torrent_id, uploaded_torrent.torrent_id);
+    assert!(res.data.bookmarked);
+
+    let response = client.get_torrent(&test_torrent.file_info_hash()).await;
+
+    let res: TorrentDetailsResponse = serde_json::from_str(&response.body).unwrap();
+
+    assert!(res.data.bookmarked);
+}
+
+#[tokio::test]
+async fn it_should_allow_registered_users_to_get_their_bookmarks() {
+    let mut env = TestEnv::new();
+    env.start(api::Version::V1).await;
+
+    if !env.provides_a_tracker() {
+        println!("test skipped. It requires a tracker to be running.");
+        return;
+    }
+
+    let uploader = new_logged_in_user(&env).await;
+    let (first_torrent, _) = upload_random_torrent_to_index(&uploader, &env).await;
+    let (second_torrent, second_uploaded_torrent) = upload_random_torrent_to_index(&uploader, &env).await;
+
+    let user = new_logged_in_user(&env).await;
+    let client = Client::authenticated(&env.server_socket_addr().unwrap(), &user.token);
+
+    client.add_bookmark(&first_torrent.file_info_hash()).await;
+    client.add_bookmark(&second_torrent.file_info_hash()).await;
+
+    let response = client
+        .get_bookmarks(Query::with_params(vec![
+            QueryParam::new("page_size", "1"),
+            QueryParam::new("page", "0"),
+        ]))
+        .await;
+
+    let res: TorrentListResponse = serde_json::from_str(&response.body).unwrap();
+
+    assert_eq!(res.data.total, 2);
+    assert_eq!(res.data.results.len(), 1);
+    assert_eq!(res.data.results[0].torrent_id, second_uploaded_torrent.torrent_id);
+}
+
+#[tokio::test]
+async fn it_should_allow_registered_users_to_remove_a_bookmark() {
+    let mut env = TestEnv::new();
+    env.start(api::Version::V1).await;
+
+    if !env.provides_a_tracker() {
+        println!("test skipped. It requires a tracker to be running.");
+        return;
+    }
+
+    let uploader = new_logged_in_user(&env).await;
+    let (test_torrent, _) = upload_random_torrent_to_index(&uploader, &env).await;
+
+    let client = Client::authenticated(&env.server_socket_addr().unwrap(), &uploader.token);
+
+    client.add_bookmark(&test_torrent.file_info_hash()).await;
+
+    let response = client.delete_bookmark(&test_torrent.file_info_hash()).await;
+
+    let res: BookmarkResponse = serde_json::from_str(&response.body).unwrap();
+
+    assert!(!res.data.bookmarked);
+
+    let response = client.delete_bookmark(&test_torrent.file_info_hash()).await;
+
+    assert_eq!(response.status, 404);
+}
+
+#[tokio::test]
+async fn it_should_not_allow_guests_to_bookmark_torrents() {
+    let mut env = TestEnv::new();
+    env.start(api::Version::V1).await;
+
+    if !env.provides_a_tracker() {
+        println!("test skipped. It requires a tracker to be running.");
+        return;
+    }
+
+    let uploader = new_logged_in_user(&env).await;
+    let (test_torrent, _) = upload_random_torrent_to_index(&uploader, &env).await;
+
+    let client = Client::unauthenticated(&env.server_socket_addr().unwrap());
+
+    let response = client.add_bookmark(&test_torrent.file_info_hash()).await;
+
+    assert_eq!(response.status, 401);
+
+    let response = client.get_bookmarks(Query::empty()).await;
+
+    assert_eq!(response.status, 401);
+}
+
+#[tokio::test]
+async fn it_should_allow_registered_users_to_export_and_import_their_bookmarks() {
+    let mut env = TestEnv::new();
+    env.start(api::Version::V1).await;
+
+    if !env.provides_a_tracker() {
+        println!("test skipped. It requires a tracker to be running.");
+        return;
+    }
+
+    let uploader = new_logged_in_user(&env).await;
+    let (test_torrent, _) = upload_random_torrent_to_index(&uploader, &env).await;
+
+    let client = Client::authenticated(&env.server_socket_addr().unwrap(), &uploader.token);
+
+    client.add_bookmark(&test_torrent.file_info_hash()).await;
+
+    let response = client.export_bookmarks().await;
+
+    assert_eq!(response.status, 200);
+
+    let mut bookmarks_file: BookmarksFile = serde_json::from_str(&response.body).unwrap();
+
+    assert_eq!(bookmarks_file.format, "torrust-index-bookmarks");
+    assert_eq!(bookmarks_file.bookmarks.len(), 1);
+    assert_eq!(
+        bookmarks_file.bookmarks[0].info_hash,
+        test_torrent.file_info_hash().to_lowercase()
+    );
+
+    let missing_info_hash = "0000000000000000000000000000000000000000".to_string();
+
+    bookmarks_file.bookmarks.push(ExportedBookmark {
+        info_hash: missing_info_hash.clone(),
+        title: "missing".to_string(),
+        date_created: None,
+    });
+
+    let another_user = new_logged_in_user(&env).await;
+    let another_client = Client::authenticated(&env.server_socket_addr().unwrap(), &another_user.token);
+
+    let response = another_client.import_bookmarks(&bookmarks_file).await;
+
+    let res: ImportSummaryResponse = serde_json::from_str(&response.body).unwrap();
+
+    assert_eq!(res.data.imported, 1);
+    assert_eq!(res.data.skipped, 0);
+    assert_eq!(res.data.not_found, vec![missing_info_hash]);
+
+    let response = another_client.import_bookmarks(&bookmarks_file).await;
+
+    let res: ImportSummaryResponse = serde_json::from_str(&response.body).unwrap();
+
+    assert_eq!(res.data.imported, 0);
+    assert_eq!(res.data.skipped, 1);
+}
@@ -5,12 +5,15 @@ use torrust_index::web::api;
 use crate::common::asserts::assert_json_ok_response;
 use crate::common::client::Client;
 use crate::common::contexts::category::asserts::{assert_added_category_response, assert_deleted_category_response};
-use crate::common::contexts::category::fixtures::random_category_name;
-use crate::common::contexts::category::forms::{AddCategoryForm, DeleteCategoryForm};
+use crate::common::contexts::category::fixtures::{random_category_name, software_category_name};
+use crate::common::contexts::category::forms::{AddCategoryForm, CategoryPolicyForm, DeleteCategoryForm};
 use crate::common::contexts::category::responses::ListResponse;
+use crate::common::contexts::torrent::fixtures::random_torrent;
+use crate::common::contexts::torrent::responses::ModeratedTorrentResponse;
 use crate::e2e::environment::TestEnv;
 use crate::e2e::web::api::v1::contexts::category::steps::{add_category, add_random_category};
-use crate::e2e::web::api::v1::contexts::user::steps::new_logged_in_admin;
+use crate::e2e::web::api::v1::contexts::torrent::steps::upload_torrent;
+use crate::e2e::web::api::v1::contexts::user::steps::{new_logged_in_admin, new_logged_in_user};
 
 #[tokio::test]
 async fn it_should_return_an_empty_category_list_when_there_are_no_categories() {
@@ -122,11 +125,130 @@ async fn it_should_allow_admins_to_delete_categories() {
     assert_deleted_category_response(&response, &added_category_name);
 }
 
+#[tokio::test]
+async fn it_should_allow_admins_to_assign_category_moderators() {
+    let mut env = TestEnv::new();
+    env.start(api::Version::V1).await;
+
+    let logged_in_admin = new_logged_in_admin(&env).await;
+    let logged_in_user = new_logged_in_user(&env).await;
+    let client = Client::authenticated(&env.server_socket_addr().unwrap(), &logged_in_admin.token);
+
+    let category = software_category_name();
+
+    let response = client.add_category_moderator(&category, &logged_in_user.username).await;
+
+    assert!(response.is_json_and_ok());
+
+    let response = client.get_category_moderators(&category).await;
+
+    let res: serde_json::Value = serde_json::from_str(&response.body).unwrap();
+
+    assert!(res["data"]
+        .as_array()
+        .unwrap()
+        .contains(&serde_json::Value::String(logged_in_user.username.clone())));
+
+    let response = client.remove_category_moderator(&category, &logged_in_user.username).await;
+
+    assert!(response.is_json_and_ok());
+
+    let response = client.get_category_moderators(&category).await;
+
+    let res: serde_json::Value = serde_json::from_str(&response.body).unwrap();
+
+    assert!(!res["data"]
+        .as_array()
+        .unwrap()
+        .contains(&serde_json::Value::String(logged_in_user.username.clone())));
+}
+
+#[tokio::test]
+async fn it_should_not_assign_moderators_to_categories_that_do_not_exist() {
+    let mut env = TestEnv::new();
+    env.start(api::Version::V1).await;
+
+    let logged_in_admin = new_logged_in_admin(&env).await;
+    let client = Client::authenticated(&env.server_socket_addr().unwrap(), &logged_in_admin.token);
+
+    let response = client
+        .add_category_moderator("no-such-category", &logged_in_admin.username)
+        .await;
+
+    assert_eq!(response.status, 404);
+}
+
+#[tokio::test]
+async fn it_should_allow_admins_to_set_the_category_policy() {
+    let mut env = TestEnv::new();
+    env.start(api::Version::V1).await;
+
+    let logged_in_admin = new_logged_in_admin(&env).await;
+    let client = Client::authenticated(&env.server_socket_addr().unwrap(), &logged_in_admin.token);
+
+    let category = add_random_category(&env).await;
+
+    let response = client
+        .set_category_policy(&category, CategoryPolicyForm { co_signing: true })
+        .await;
+
+    assert!(response.is_json_and_ok());
+
+    let response = client.get_category_policy(&category).await;
+
+    let res: serde_json::Value = serde_json::from_str(&response.body).unwrap();
+
+    assert_eq!(res["data"]["co_signing"], serde_json::Value::Bool(true));
+}
+
+#[tokio::test]
+async fn it_should_require_two_different_admins_to_approve_torrents_in_co_signed_categories() {
+    let mut env = TestEnv::new();
+    env.start(api::Version::V1).await;
+
+    if !env.provides_a_tracker() {
+        println!("test skipped. It requires a tracker to be running.");
+        return;
+    }
+
+    let first_admin = new_logged_in_admin(&env).await;
+    let first_admin_client = Client::authenticated(&env.server_socket_addr().unwrap(), &first_admin.token);
+
+    let category = add_random_category(&env).await;
+
+    first_admin_client
+        .set_category_policy(&category, CategoryPolicyForm { co_signing: true })
+        .await;
+
+    let uploader = new_logged_in_user(&env).await;
+    let mut test_torrent = random_torrent();
+    test_torrent.index_info.category = category;
+    let uploaded_torrent = upload_torrent(&uploader, &test_torrent.index_info, &env).await;
+
+    let response = first_admin_client.approve_torrent(uploaded_torrent.torrent_id).await;
+
+    let res: ModeratedTorrentResponse = serde_json::from_str(&response.body).unwrap();
+    assert_eq!(res.data.status, "pending");
+
+    let response = first_admin_client.approve_torrent(uploaded_torrent.torrent_id).await;
+
+    assert_eq!(response.status, 409);
+
+    let second_admin = new_logged_in_admin(&env).await;
+    let second_admin_client = Client::authenticated(&env.server_socket_addr().unwrap(), &second_admin.token);
+
+    let response = second_admin_client.approve_torrent(uploaded_torrent.torrent_id).await;
+
+    let res: ModeratedTorrentResponse = serde_json::from_str(&response.body).unwrap();
+    assert_eq!(res.data.status, "approved");
+}
+
 mod authorization {
     use torrust_index::web::api;
 
     use crate::common::client::Client;
-    use crate::common::contexts::category::forms::DeleteCategoryForm;
+    use crate::common::contexts::category::fixtures::software_category_name;
+    use crate::common::contexts::category::forms::{CategoryPolicyForm, DeleteCategoryForm};
     use crate::e2e::environment::TestEnv;
     use crate::e2e::web::api::v1::contexts::category::steps::add_random_category;
     use crate::e2e::web::api::v1::contexts::user::steps::new_logged_in_user;
@@ -235,6 +357,37 @@ mod authorization {
         assert_eq!(response.status, 403);
     }
     #[tokio::test]
+    async fn it_should_not_allow_registered_users_to_assign_category_moderators() {
+        let mut env = TestEnv::new();
+        env.start(api::Version::V1).await;
+
+        let logged_in_non_admin = new_logged_in_user(&env).await;
+
+        let client = Client::authenticated(&env.server_socket_addr().unwrap(), &logged_in_non_admin.token);
+
+        let response = client
+            .add_category_moderator(&software_category_name(), &logged_in_non_admin.username)
+            .await;
+
+        assert_eq!(response.status, 403);
+    }
+
+    #[tokio::test]
+    async fn it_should_not_allow_registered_users_to_set_the_category_policy() {
+        let mut env = TestEnv::new();
+        env.start(api::Version::V1).await;
+
+        let logged_in_non_admin = new_logged_in_user(&env).await;
+
+        let client = Client::authenticated(&env.server_socket_addr().unwrap(), &logged_in_non_admin.token);
+
+        let response = client
+            .set_category_policy(&software_category_name(), CategoryPolicyForm { co_signing: true })
+            .await;
+
+        assert_eq!(response.status, 403);
+    }
+    #[tokio::test]
     async fn it_should_allow_registered_users_to_get_categories() {
         let mut env = TestEnv::new();
         env.start(api::Version::V1).await;
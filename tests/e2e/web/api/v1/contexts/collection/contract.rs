@@ -0,0 +1,297 @@
+//! API contract for `collection` context.
+
+use torrust_index::web::api;
+
+use crate::common::client::Client;
+use crate::common::contexts::category::fixtures::software_category_name;
+use crate::common::contexts::collection::forms::{CollectionForm, SmartFilterForm};
+use crate::common::contexts::collection::responses::{
+    CollectionListResponse, CollectionPageResponse, CollectionResponse, DeletedCollectionResponse,
+};
+use crate::common::http::Query;
+use crate::e2e::environment::TestEnv;
+use crate::e2e::web::api::v1::contexts::torrent::steps::upload_random_torrent_to_index;
+use crate::e2e::web::api::v1::contexts::user::steps::{new_logged_in_admin, new_logged_in_user};
+
+fn ubuntu_releases() -> CollectionForm {
+    CollectionForm {
+        name: "Ubuntu releases".to_string(),
+        description: Some("All the LTS releases".to_string()),
+        filter: None,
+    }
+}
+
+#[tokio::test]
+async fn it_should_allow_registered_users_to_create_a_collection() {
+    let mut env = TestEnv::new();
+    env.start(api::Version::V1).await;
+
+    let user = new_logged_in_user(&env).await;
+
+    let client = Client::authenticated(&env.server_socket_addr().unwrap(), &user.token);
+
+    let response = client.create_collection(ubuntu_releases()).await;
+
+    assert_eq!(response.status, 200);
+
+    let res: CollectionResponse = serde_json::from_str(&response.body).unwrap();
+
+    assert_eq!(res.data.owner, user.username);
+    assert_eq!(res.data.name, "Ubuntu releases");
+    assert_eq!(res.data.torrent_count, 0);
+
+    let response = client.get_user_collections().await;
+
+    let collections: CollectionListResponse = serde_json::from_str(&response.body).unwrap();
+
+    assert_eq!(collections.data, vec![res.data]);
+}
+
+#[tokio::test]
+async fn it_should_not_allow_guests_to_create_a_collection() {
+    let mut env = TestEnv::new();
+    env.start(api::Version::V1).await;
+
+    let client = Client::unauthenticated(&env.server_socket_addr().unwrap());
+
+    let response = client.create_collection(ubuntu_releases()).await;
+
+    assert_eq!(response.status, 401);
+}
+
+#[tokio::test]
+async fn it_should_not_allow_empty_collection_names() {
+    let mut env = TestEnv::new();
+    env.start(api::Version::V1).await;
+
+    let user = new_logged_in_user(&env).await;
+
+    let client = Client::authenticated(&env.server_socket_addr().unwrap(), &user.token);
+
+    let response = client
+        .create_collection(CollectionForm {
+            name: "  ".to_string(),
+            description: None,
+            filter: None,
+        })
+        .await;
+
+    assert_eq!(response.status, 400);
+}
+
+#[tokio::test]
+async fn it_should_allow_guests_to_see_a_collection_with_its_torrents() {
+    let mut env = TestEnv::new();
+    env.start(api::Version::V1).await;
+
+    if !env.provides_a_tracker() {
+        println!("test skipped. It requires a tracker to be running.");
+        return;
+    }
+
+    let user = new_logged_in_user(&env).await;
+    let (test_torrent, uploaded_torrent) = upload_random_torrent_to_index(&user, &env).await;
+
+    let client = Client::authenticated(&env.server_socket_addr().unwrap(), &user.token);
+
+    let response = client.create_collection(ubuntu_releases()).await;
+    let collection: CollectionResponse = serde_json::from_str(&response.body).unwrap();
+    let collection_id = collection.data.collection_id;
+
+    let response = client
+        .add_torrent_to_collection(collection_id, &test_torrent.file_info_hash())
+        .await;
+
+    let res: CollectionResponse = serde_json::from_str(&response.body).unwrap();
+
+    assert_eq!(res.data.torrent_count, 1);
+    assert!(res.data.total_size > 0);
+
+    let guest = Client::unauthenticated(&env.server_socket_addr().unwrap());
+
+    let response = guest.get_collection(collection_id, Query::empty()).await;
+
+    assert_eq!(response.status, 200);
+
+    let res: CollectionPageResponse = serde_json::from_str(&response.body).unwrap();
+
+    assert_eq!(res.data.collection.torrent_count, 1);
+    assert_eq!(res.data.torrents.total, 1);
+    assert_eq!(res.data.torrents.results[0].torrent_id, uploaded_torrent.torrent_id);
+}
+
+#[tokio::test]
+async fn it_should_allow_the_owner_to_remove_a_torrent_from_a_collection() {
+    let mut env = TestEnv::new();
+    env.start(api::Version::V1).await;
+
+    if !env.provides_a_tracker() {
+        println!("test skipped. It requires a tracker to be running.");
+        return;
+    }
+
+    let user = new_logged_in_user(&env).await;
+    let (test_torrent, _) = upload_random_torrent_to_index(&user, &env).await;
+
+    let client = Client::authenticated(&env.server_socket_addr().unwrap(), &user.token);
+
+    let response = client.create_collection(ubuntu_releases()).await;
+    let collection: CollectionResponse = serde_json::from_str(&response.body).unwrap();
+    let collection_id = collection.data.collection_id;
+
+    client
+        .add_torrent_to_collection(collection_id, &test_torrent.file_info_hash())
+        .await;
+
+    let response = client
+        .remove_torrent_from_collection(collection_id, &test_torrent.file_info_hash())
+        .await;
+
+    let res: CollectionResponse = serde_json::from_str(&response.body).unwrap();
+
+    assert_eq!(res.data.torrent_count, 0);
+
+    let response = client
+        .remove_torrent_from_collection(collection_id, &test_torrent.file_info_hash())
+        .await;
+
+    assert_eq!(response.status, 404);
+}
+
+#[tokio::test]
+async fn it_should_not_allow_other_users_to_change_a_collection() {
+    let mut env = TestEnv::new();
+    env.start(api::Version::V1).await;
+
+    let owner = new_logged_in_user(&env).await;
+    let client = Client::authenticated(&env.server_socket_addr().unwrap(), &owner.token);
+
+    let response = client.create_collection(ubuntu_releases()).await;
+    let collection: CollectionResponse = serde_json::from_str(&response.body).unwrap();
+    let collection_id = collection.data.collection_id;
+
+    let another_user = new_logged_in_user(&env).await;
+    let another_client = Client::authenticated(&env.server_socket_addr().unwrap(), &another_user.token);
+
+    let response = another_client.update_collection(collection_id, ubuntu_releases()).await;
+
+    assert_eq!(response.status, 404);
+
+    let response = another_client.delete_collection(collection_id).await;
+
+    assert_eq!(response.status, 403);
+}
+
+#[tokio::test]
+async fn it_should_allow_the_owner_to_update_and_delete_a_collection() {
+    let mut env = TestEnv::new();
+    env.start(api::Version::V1).await;
+
+    let user = new_logged_in_user(&env).await;
+    let client = Client::authenticated(&env.server_socket_addr().unwrap(), &user.token);
+
+    let response = client.create_collection(ubuntu_releases()).await;
+    let collection: CollectionResponse = serde_json::from_str(&response.body).unwrap();
+    let collection_id = collection.data.collection_id;
+
+    let response = client
+        .update_collection(
+            collection_id,
+            CollectionForm {
+                name: "Ubuntu LTS releases".to_string(),
+                description: None,
+                filter: None,
+            },
+        )
+        .await;
+
+    let res: CollectionResponse = serde_json::from_str(&response.body).unwrap();
+
+    assert_eq!(res.data.name, "Ubuntu LTS releases");
+    assert_eq!(res.data.description, None);
+
+    let response = client.delete_collection(collection_id).await;
+
+    let res: DeletedCollectionResponse = serde_json::from_str(&response.body).unwrap();
+
+    assert_eq!(res.data, collection_id);
+
+    let response = client.get_collection(collection_id, Query::empty()).await;
+
+    assert_eq!(response.status, 404);
+}
+
+fn recent_software() -> CollectionForm {
+    CollectionForm {
+        name: "Recent software".to_string(),
+        description: None,
+        filter: Some(SmartFilterForm {
+            category: Some(software_category_name()),
+            max_age_days: Some(1),
+            ..SmartFilterForm::default()
+        }),
+    }
+}
+
+#[tokio::test]
+async fn it_should_not_allow_non_admins_to_create_smart_collections() {
+    let mut env = TestEnv::new();
+    env.start(api::Version::V1).await;
+
+    let user = new_logged_in_user(&env).await;
+    let client = Client::authenticated(&env.server_socket_addr().unwrap(), &user.token);
+
+    let response = client.create_collection(recent_software()).await;
+
+    assert_eq!(response.status, 403);
+}
+
+#[tokio::test]
+async fn it_should_reject_smart_collections_without_filter_criteria() {
+    let mut env = TestEnv::new();
+    env.start(api::Version::V1).await;
+
+    let admin = new_logged_in_admin(&env).await;
+    let client = Client::authenticated(&env.server_socket_addr().unwrap(), &admin.token);
+
+    let response = client
+        .create_collection(CollectionForm {
+            name: "Everything".to_string(),
+            description: None,
+            filter: Some(SmartFilterForm::default()),
+        })
+        .await;
+
+    assert_eq!(response.status, 400);
+}
+
+#[tokio::test]
+async fn it_should_fill_smart_collections_with_the_torrents_matching_the_filter() {
+    let mut env = TestEnv::new();
+    env.start(api::Version::V1).await;
+
+    if !env.provides_a_tracker() {
+        println!("test skipped. It requires a tracker to be running.");
+        return;
+    }
+
+    let admin = new_logged_in_admin(&env).await;
+    let (test_torrent, _uploaded_torrent) = upload_random_torrent_to_index(&admin, &env).await;
+
+    let client = Client::authenticated(&env.server_socket_addr().unwrap(), &admin.token);
+
+    let response = client.create_collection(recent_software()).await;
+
+    assert_eq!(response.status, 200);
+
+    let res: CollectionResponse = serde_json::from_str(&response.body).unwrap();
+
+    assert!(res.data.smart);
+    assert!(res.data.torrent_count >= 1);
+
+    let response = client
+        .add_torrent_to_collection(res.data.collection_id, &test_torrent.file_info_hash())
+        .await;
+
+    assert_eq!(response.status, 400);
+}
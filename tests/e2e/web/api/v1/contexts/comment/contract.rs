@@ -0,0 +1,257 @@
+//! API contract for `comment` context.
+
+use torrust_index::web::api;
+
+use crate::common::client::Client;
+use crate::common::contexts::comment::forms::{AddCommentForm, EditCommentForm, ModerateCommentForm};
+use crate::common::contexts::comment::responses::{CommentListResponse, CommentResponse, DeletedCommentResponse};
+use crate::common::http::{Query, QueryParam};
+use crate::e2e::environment::TestEnv;
+use crate::e2e::web::api::v1::contexts::torrent::steps::upload_random_torrent_to_index;
+use crate::e2e::web::api::v1::contexts::user::steps::{new_logged_in_admin, new_logged_in_user};
+
+#[tokio::test]
+async fn it_should_allow_registered_users_to_comment_a_torrent() {
+    let mut env = TestEnv::new();
+    env.start(api::Version::V1).await;
+
+    if !env.provides_a_tracker() {
+        println!("test skipped. It requires a tracker to be running.");
+        return;
+    }
+
+    let uploader = new_logged_in_user(&env).await;
+    let (test_torrent, _torrent_listed_in_index) = upload_random_torrent_to_index(&uploader, &env).await;
+
+    let client = Client::authenticated(&env.server_socket_addr().unwrap(), &uploader.token);
+
+    let response = client
+        .add_comment(
+            &test_torrent.file_info_hash(),
+            AddCommentForm {
+                text: "Thanks for sharing!".to_string(),
+            },
+        )
+        .await;
+
+    assert_eq!(response.status, 200);
+
+    let res: CommentResponse = serde_json::from_str(&response.body).unwrap();
+
+    assert_eq!(res.data.text, "Thanks for sharing!");
+    assert_eq!(res.data.author, uploader.username);
+    assert!(!res.data.hidden);
+}
+
+#[tokio::test]
+async fn it_should_allow_guests_to_get_the_paginated_torrent_comments() {
+    let mut env = TestEnv::new();
+    env.start(api::Version::V1).await;
+
+    if !env.provides_a_tracker() {
+        println!("test skipped. It requires a tracker to be running.");
+        return;
+    }
+
+    let uploader = new_logged_in_user(&env).await;
+    let (test_torrent, _torrent_listed_in_index) = upload_random_torrent_to_index(&uploader, &env).await;
+
+    let client = Client::authenticated(&env.server_socket_addr().unwrap(), &uploader.token);
+
+    for text in ["first", "second", "third"] {
+        let response = client
+            .add_comment(&test_torrent.file_info_hash(), AddCommentForm { text: text.to_string() })
+            .await;
+        assert_eq!(response.status, 200);
+    }
+
+    let client = Client::unauthenticated(&env.server_socket_addr().unwrap());
+
+    let response = client
+        .get_comments(
+            &test_torrent.file_info_hash(),
+            Query::with_params(vec![QueryParam::new("page_size", "2"), QueryParam::new("page", "1")]),
+        )
+        .await;
+
+    let res: CommentListResponse = serde_json::from_str(&response.body).unwrap();
+
+    assert_eq!(res.data.total, 3);
+    assert_eq!(res.data.results.len(), 1);
+    assert_eq!(res.data.results[0].text, "third");
+}
+
+#[tokio::test]
+async fn it_should_only_allow_the_author_to_edit_a_comment() {
+    let mut env = TestEnv::new();
+    env.start(api::Version::V1).await;
+
+    if !env.provides_a_tracker() {
+        println!("test skipped. It requires a tracker to be running.");
+        return;
+    }
+
+    let author = new_logged_in_user(&env).await;
+    let (test_torrent, _torrent_listed_in_index) = upload_random_torrent_to_index(&author, &env).await;
+
+    let client = Client::authenticated(&env.server_socket_addr().unwrap(), &author.token);
+
+    let response = client
+        .add_comment(
+            &test_torrent.file_info_hash(),
+            AddCommentForm {
+                text: "typo".to_string(),
+            },
+        )
+        .await;
+    let comment_id = serde_json::from_str::<CommentResponse>(&response.body)
+        .unwrap()
+        .data
+        .comment_id;
+
+    let another_user = new_logged_in_user(&env).await;
+    let another_client = Client::authenticated(&env.server_socket_addr().unwrap(), &another_user.token);
+
+    let response = another_client
+        .edit_comment(
+            &test_torrent.file_info_hash(),
+            comment_id,
+            EditCommentForm {
+                text: "edited".to_string(),
+            },
+        )
+        .await;
+
+    assert_eq!(response.status, 403);
+
+    let response = client
+        .edit_comment(
+            &test_torrent.file_info_hash(),
+            comment_id,
+            EditCommentForm {
+                text: "fixed".to_string(),
+            },
+        )
+        .await;
+
+    let res: CommentResponse = serde_json::from_str(&response.body).unwrap();
+
+    assert_eq!(res.data.text, "fixed");
+    assert!(res.data.date_updated.is_some());
+}
+
+#[tokio::test]
+async fn it_should_allow_the_author_to_delete_a_comment() {
+    let mut env = TestEnv::new();
+    env.start(api::Version::V1).await;
+
+    if !env.provides_a_tracker() {
+        println!("test skipped. It requires a tracker to be running.");
+        return;
+    }
+
+    let author = new_logged_in_user(&env).await;
+    let (test_torrent, _torrent_listed_in_index) = upload_random_torrent_to_index(&author, &env).await;
+
+    let client = Client::authenticated(&env.server_socket_addr().unwrap(), &author.token);
+
+    let response = client
+        .add_comment(&test_torrent.file_info_hash(), AddCommentForm { text: "bye".to_string() })
+        .await;
+    let comment_id = serde_json::from_str::<CommentResponse>(&response.body)
+        .unwrap()
+        .data
+        .comment_id;
+
+    let response = client.delete_comment(&test_torrent.file_info_hash(), comment_id).await;
+
+    let res: DeletedCommentResponse = serde_json::from_str(&response.body).unwrap();
+
+    assert_eq!(res.data, comment_id);
+}
+
+#[tokio::test]
+async fn it_should_hide_moderated_comments_from_non_admins() {
+    let mut env = TestEnv::new();
+    env.start(api::Version::V1).await;
+
+    if !env.provides_a_tracker() {
+        println!("test skipped. It requires a tracker to be running.");
+        return;
+    }
+
+    let author = new_logged_in_user(&env).await;
+    let (test_torrent, _torrent_listed_in_index) = upload_random_torrent_to_index(&author, &env).await;
+
+    let client = Client::authenticated(&env.server_socket_addr().unwrap(), &author.token);
+
+    let response = client
+        .add_comment(
+            &test_torrent.file_info_hash(),
+            AddCommentForm {
+                text: "spam".to_string(),
+            },
+        )
+        .await;
+    let comment_id = serde_json::from_str::<CommentResponse>(&response.body)
+        .unwrap()
+        .data
+        .comment_id;
+
+    // Non admins can't moderate comments
+    let response = client
+        .moderate_comment(
+            &test_torrent.file_info_hash(),
+            comment_id,
+            ModerateCommentForm { hidden: true },
+        )
+        .await;
+
+    assert_eq!(response.status, 403);
+
+    let admin = new_logged_in_admin(&env).await;
+    let admin_client = Client::authenticated(&env.server_socket_addr().unwrap(), &admin.token);
+
+    let response = admin_client
+        .moderate_comment(
+            &test_torrent.file_info_hash(),
+            comment_id,
+            ModerateCommentForm { hidden: true },
+        )
+        .await;
+
+    assert_eq!(response.status, 200);
+
+    let response = client.get_comments(&test_torrent.file_info_hash(), Query::empty()).await;
+    let res: CommentListResponse = serde_json::from_str(&response.body).unwrap();
+    assert_eq!(res.data.total, 0);
+
+    let response = admin_client
+        .get_comments(&test_torrent.file_info_hash(), Query::empty())
+        .await;
+    let res: CommentListResponse = serde_json::from_str(&response.body).unwrap();
+    assert_eq!(res.data.total, 1);
+    assert!(res.data.results[0].hidden);
+}
+
+#[tokio::test]
+async fn it_should_not_allow_guests_to_comment() {
+    let mut env = TestEnv::new();
+    env.start(api::Version::V1).await;
+
+    if !env.provides_a_tracker() {
+        println!("test skipped. It requires a tracker to be running.");
+        return;
+    }
+
+    let uploader = new_logged_in_user(&env).await;
+    let (test_torrent, _torrent_listed_in_index) = upload_random_torrent_to_index(&uploader, &env).await;
+
+    let client = Client::unauthenticated(&env.server_socket_addr().unwrap());
+
+    let response = client
+        .add_comment(&test_torrent.file_info_hash(), AddCommentForm { text: "hi".to_string() })
+        .await;
+
+    assert_eq!(response.status, 401);
+}
@@ -0,0 +1,49 @@
+//! API contract for `image` context.
+
+use torrust_index::web::api;
+
+use crate::common::client::Client;
+use crate::common::contexts::image::forms::{ConfirmUploadForm, PresignUploadForm};
+use crate::e2e::environment::TestEnv;
+use crate::e2e::web::api::v1::contexts::user::steps::new_logged_in_user;
+
+fn screenshot() -> PresignUploadForm {
+    PresignUploadForm {
+        kind: "screenshot".to_string(),
+        content_type: "image/png".to_string(),
+        size: 1024,
+    }
+}
+
+#[tokio::test]
+async fn it_should_not_issue_upload_urls_when_the_storage_is_not_enabled() {
+    let mut env = TestEnv::new();
+    env.start(api::Version::V1).await;
+
+    let user = new_logged_in_user(&env).await;
+
+    let client = Client::authenticated(&env.server_socket_addr().unwrap(), &user.token);
+
+    let response = client.presign_image_upload(screenshot()).await;
+
+    assert_eq!(response.status, 403);
+    assert!(response.body.contains("The image storage is not enabled."));
+}
+
+#[tokio::test]
+async fn it_should_not_confirm_uploads_when_the_storage_is_not_enabled() {
+    let mut env = TestEnv::new();
+    env.start(api::Version::V1).await;
+
+    let user = new_logged_in_user(&env).await;
+
+    let client = Client::authenticated(&env.server_socket_addr().unwrap(), &user.token);
+
+    let response = client
+        .confirm_image_upload(ConfirmUploadForm {
+            object_key: "screenshot/1/abc.png".to_string(),
+        })
+        .await;
+
+    assert_eq!(response.status, 403);
+}
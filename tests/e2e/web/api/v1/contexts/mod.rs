@@ -1,8 +1,17 @@
 pub mod about;
+pub mod admin;
+pub mod bookmark;
 pub mod category;
+pub mod collection;
+pub mod comment;
+pub mod image;
 pub mod proxy;
+pub mod report;
 pub mod root;
 pub mod settings;
+pub mod subscription;
+pub mod sync;
 pub mod tag;
 pub mod torrent;
 pub mod user;
+pub mod widget;
@@ -0,0 +1,167 @@
+//! API contract for `report` context.
+
+use torrust_index::web::api;
+
+use crate::common::client::Client;
+use crate::common::contexts::report::forms::{ReportTorrentForm, ResolveReportForm};
+use crate::common::contexts::report::responses::{ReportListResponse, ReportResponse};
+use crate::common::contexts::torrent::responses::TorrentListResponse;
+use crate::common::http::{Query, QueryParam};
+use crate::e2e::environment::TestEnv;
+use crate::e2e::web::api::v1::contexts::torrent::steps::upload_random_torrent_to_index;
+use crate::e2e::web::api::v1::contexts::user::steps::{new_logged_in_admin, new_logged_in_user};
+
+fn malware_report() -> ReportTorrentForm {
+    ReportTorrentForm {
+        reason: "malware".to_string(),
+        details: Some("The file is an executable".to_string()),
+    }
+}
+
+#[tokio::test]
+async fn it_should_allow_registered_users_to_report_a_torrent() {
+    let mut env = TestEnv::new();
+    env.start(api::Version::V1).await;
+
+    if !env.provides_a_tracker() {
+        println!("test skipped. It requires a tracker to be running.");
+        return;
+    }
+
+    let uploader = new_logged_in_user(&env).await;
+    let (test_torrent, uploaded_torrent) = upload_random_torrent_to_index(&uploader, &env).await;
+
+    let reporter = new_logged_in_user(&env).await;
+    let client = Client::authenticated(&env.server_socket_addr().unwrap(), &reporter.token);
+
+    let response = client.report_torrent(&test_torrent.file_info_hash(), malware_report()).await;
+
+    assert_eq!(response.status, 200);
+
+    let res: ReportResponse = serde_json::from_str(&response.body).unwrap();
+
+    assert_eq!(res.data.torrent_id, uploaded_torrent.torrent_id);
+    assert_eq!(res.data.uploader, uploader.username);
+    assert_eq!(res.data.reporter, reporter.username);
+    assert_eq!(res.data.reason, "malware");
+    assert!(!res.data.resolved);
+}
+
+#[tokio::test]
+async fn it_should_not_allow_guests_to_report_a_torrent() {
+    let mut env = TestEnv::new();
+    env.start(api::Version::V1).await;
+
+    if !env.provides_a_tracker() {
+        println!("test skipped. It requires a tracker to be running.");
+        return;
+    }
+
+    let uploader = new_logged_in_user(&env).await;
+    let (test_torrent, _uploaded_torrent) = upload_random_torrent_to_index(&uploader, &env).await;
+
+    let client = Client::unauthenticated(&env.server_socket_addr().unwrap());
+
+    let response = client.report_torrent(&test_torrent.file_info_hash(), malware_report()).await;
+
+    assert_eq!(response.status, 401);
+}
+
+#[tokio::test]
+async fn it_should_not_allow_non_admins_to_get_the_reports() {
+    let mut env = TestEnv::new();
+    env.start(api::Version::V1).await;
+
+    let logged_in_user = new_logged_in_user(&env).await;
+    let client = Client::authenticated(&env.server_socket_addr().unwrap(), &logged_in_user.token);
+
+    let response = client.get_reports(Query::empty()).await;
+
+    assert_eq!(response.status, 403);
+}
+
+#[tokio::test]
+async fn it_should_allow_admins_to_list_the_open_reports() {
+    let mut env = TestEnv::new();
+    env.start(api::Version::V1).await;
+
+    if !env.provides_a_tracker() {
+        println!("test skipped. It requires a tracker to be running.");
+        return;
+    }
+
+    let uploader = new_logged_in_user(&env).await;
+    let (test_torrent, _uploaded_torrent) = upload_random_torrent_to_index(&uploader, &env).await;
+
+    let client = Client::authenticated(&env.server_socket_addr().unwrap(), &uploader.token);
+
+    let response = client.report_torrent(&test_torrent.file_info_hash(), malware_report()).await;
+    let report_id = serde_json::from_str::<ReportResponse>(&response.body).unwrap().data.report_id;
+
+    let admin = new_logged_in_admin(&env).await;
+    let admin_client = Client::authenticated(&env.server_socket_addr().unwrap(), &admin.token);
+
+    let response = admin_client
+        .get_reports(Query::with_params(vec![QueryParam::new("resolved", "false")]))
+        .await;
+
+    let res: ReportListResponse = serde_json::from_str(&response.body).unwrap();
+
+    assert!(res.data.results.iter().any(|report| report.report_id == report_id));
+    assert!(res.data.results.iter().all(|report| !report.resolved));
+}
+
+#[tokio::test]
+async fn it_should_allow_admins_to_hide_a_reported_torrent() {
+    let mut env = TestEnv::new();
+    env.start(api::Version::V1).await;
+
+    if !env.provides_a_tracker() {
+        println!("test skipped. It requires a tracker to be running.");
+        return;
+    }
+
+    let uploader = new_logged_in_user(&env).await;
+    let (test_torrent, uploaded_torrent) = upload_random_torrent_to_index(&uploader, &env).await;
+
+    let client = Client::authenticated(&env.server_socket_addr().unwrap(), &uploader.token);
+
+    let response = client.report_torrent(&test_torrent.file_info_hash(), malware_report()).await;
+    let report_id = serde_json::from_str::<ReportResponse>(&response.body).unwrap().data.report_id;
+
+    let admin = new_logged_in_admin(&env).await;
+    let admin_client = Client::authenticated(&env.server_socket_addr().unwrap(), &admin.token);
+
+    let response = admin_client
+        .resolve_report(
+            report_id,
+            ResolveReportForm {
+                action: "hide_torrent".to_string(),
+            },
+        )
+        .await;
+
+    let res: ReportResponse = serde_json::from_str(&response.body).unwrap();
+
+    assert!(res.data.resolved);
+    assert_eq!(res.data.resolution, Some("hide_torrent".to_string()));
+
+    // The torrent is no longer listed
+    let response = client
+        .get_torrents(Query::with_params(vec![QueryParam::new("search", &uploaded_torrent.title)]))
+        .await;
+    let torrent_list_response: TorrentListResponse = serde_json::from_str(&response.body).unwrap();
+    assert_eq!(torrent_list_response.data.total, 0);
+
+    // A report can only be resolved once
+    let response = admin_client
+        .resolve_report(
+            report_id,
+            ResolveReportForm {
+                action: "dismiss".to_string(),
+            },
+        )
+        .await;
+
+    assert_eq!(response.status, 409);
+}
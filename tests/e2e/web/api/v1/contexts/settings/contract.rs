@@ -7,7 +7,7 @@ use crate::common::asserts::assert_json_ok_response;
 use crate::common::client::Client;
 use crate::common::contexts::settings::responses::{AllSettingsResponse, Public, PublicSettingsResponse, SiteNameResponse};
 use crate::e2e::environment::TestEnv;
-use crate::e2e::web::api::v1::contexts::user::steps::new_logged_in_admin;
+use crate::e2e::web::api::v1::contexts::user::steps::{new_logged_in_admin, new_logged_in_user};
 
 #[tokio::test]
 async fn it_should_allow_guests_to_get_the_public_settings() {
@@ -81,3 +81,63 @@ async fn it_should_allow_admins_to_get_all_the_settings() {
 
     assert_json_ok_response(&response);
 }
+
+#[tokio::test]
+async fn it_should_allow_admins_to_get_the_settings_changed_since_the_application_started() {
+    let mut env = TestEnv::new();
+    env.start(api::Version::V1).await;
+
+    let logged_in_admin = new_logged_in_admin(&env).await;
+    let client = Client::authenticated(&env.server_socket_addr().unwrap(), &logged_in_admin.token);
+
+    let response = client.get_settings_changes().await;
+
+    let res: serde_json::Value = serde_json::from_str(&response.body).unwrap();
+
+    assert_eq!(res["data"], serde_json::json!({ "applied": [], "pending_restart": [] }));
+
+    assert_json_ok_response(&response);
+}
+
+#[tokio::test]
+async fn it_should_not_allow_non_admins_to_get_the_settings_changes() {
+    let mut env = TestEnv::new();
+    env.start(api::Version::V1).await;
+
+    let logged_in_user = new_logged_in_user(&env).await;
+    let client = Client::authenticated(&env.server_socket_addr().unwrap(), &logged_in_user.token);
+
+    let response = client.get_settings_changes().await;
+
+    assert_eq!(response.status, 403);
+}
+
+#[tokio::test]
+async fn it_should_return_bare_camel_case_responses_when_the_client_asks_for_them() {
+    let mut env = TestEnv::new();
+    env.start(api::Version::V1).await;
+
+    let client = Client::unauthenticated(&env.server_socket_addr().unwrap());
+
+    let response = client.get_public_settings_with_format("bare,camel_case").await;
+
+    assert_eq!(response.status, 200);
+    assert!(response.body.starts_with('{'));
+    assert!(!response.body.contains("\"data\""));
+    assert!(response.body.contains("\"websiteName\""));
+}
+
+#[tokio::test]
+async fn it_should_negotiate_the_response_format_with_the_accept_profile_header() {
+    let mut env = TestEnv::new();
+    env.start(api::Version::V1).await;
+
+    let client = Client::unauthenticated(&env.server_socket_addr().unwrap());
+
+    let response = client.get_public_settings_with_profile("camel_case").await;
+
+    assert_eq!(response.status, 200);
+    assert!(response.body.contains("\"data\""));
+    assert!(response.body.contains("\"websiteName\""));
+    assert!(!response.body.contains("\"website_name\""));
+}
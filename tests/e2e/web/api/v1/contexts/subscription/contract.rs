@@ -0,0 +1,131 @@
+//! API contract for `subscription` context.
+
+use torrust_index::web::api;
+
+use crate::common::client::Client;
+use crate::common::contexts::category::fixtures::{software_category_name, software_predefined_category_id};
+use crate::common::contexts::subscription::forms::AddSubscriptionForm;
+use crate::common::contexts::subscription::responses::{
+    DeletedSubscriptionResponse, SubscriptionListResponse, SubscriptionResponse,
+};
+use crate::e2e::environment::TestEnv;
+use crate::e2e::web::api::v1::contexts::user::steps::new_logged_in_user;
+
+fn software_subscription() -> AddSubscriptionForm {
+    AddSubscriptionForm {
+        category_id: Some(software_predefined_category_id()),
+        tag_id: None,
+    }
+}
+
+#[tokio::test]
+async fn it_should_allow_registered_users_to_subscribe_to_a_category() {
+    let mut env = TestEnv::new();
+    env.start(api::Version::V1).await;
+
+    let user = new_logged_in_user(&env).await;
+
+    let client = Client::authenticated(&env.server_socket_addr().unwrap(), &user.token);
+
+    let response = client.add_subscription(software_subscription()).await;
+
+    assert_eq!(response.status, 200);
+
+    let res: SubscriptionResponse = serde_json::from_str(&response.body).unwrap();
+
+    assert_eq!(res.data.username, user.username);
+    assert_eq!(res.data.category, Some(software_category_name()));
+    assert_eq!(res.data.tag_id, None);
+
+    let response = client.get_subscriptions().await;
+
+    let res: SubscriptionListResponse = serde_json::from_str(&response.body).unwrap();
+
+    assert_eq!(res.data.len(), 1);
+    assert_eq!(res.data[0].category_id, Some(software_predefined_category_id()));
+}
+
+#[tokio::test]
+async fn it_should_allow_registered_users_to_delete_a_subscription() {
+    let mut env = TestEnv::new();
+    env.start(api::Version::V1).await;
+
+    let user = new_logged_in_user(&env).await;
+
+    let client = Client::authenticated(&env.server_socket_addr().unwrap(), &user.token);
+
+    let response = client.add_subscription(software_subscription()).await;
+    let subscription_id = serde_json::from_str::<SubscriptionResponse>(&response.body)
+        .unwrap()
+        .data
+        .subscription_id;
+
+    // Users can only delete their own subscriptions
+    let another_user = new_logged_in_user(&env).await;
+    let another_client = Client::authenticated(&env.server_socket_addr().unwrap(), &another_user.token);
+
+    let response = another_client.delete_subscription(subscription_id).await;
+
+    assert_eq!(response.status, 404);
+
+    let response = client.delete_subscription(subscription_id).await;
+
+    let res: DeletedSubscriptionResponse = serde_json::from_str(&response.body).unwrap();
+
+    assert_eq!(res.data, subscription_id);
+
+    let response = client.get_subscriptions().await;
+    let res: SubscriptionListResponse = serde_json::from_str(&response.body).unwrap();
+    assert!(res.data.is_empty());
+}
+
+#[tokio::test]
+async fn it_should_not_allow_duplicate_subscriptions() {
+    let mut env = TestEnv::new();
+    env.start(api::Version::V1).await;
+
+    let user = new_logged_in_user(&env).await;
+
+    let client = Client::authenticated(&env.server_socket_addr().unwrap(), &user.token);
+
+    let response = client.add_subscription(software_subscription()).await;
+    assert_eq!(response.status, 200);
+
+    let response = client.add_subscription(software_subscription()).await;
+    assert_eq!(response.status, 400);
+}
+
+#[tokio::test]
+async fn it_should_require_either_a_category_or_a_tag() {
+    let mut env = TestEnv::new();
+    env.start(api::Version::V1).await;
+
+    let user = new_logged_in_user(&env).await;
+
+    let client = Client::authenticated(&env.server_socket_addr().unwrap(), &user.token);
+
+    let response = client
+        .add_subscription(AddSubscriptionForm {
+            category_id: None,
+            tag_id: None,
+        })
+        .await;
+
+    assert_eq!(response.status, 400);
+}
+
+#[tokio::test]
+async fn it_should_not_allow_guests_to_subscribe() {
+    let mut env = TestEnv::new();
+    env.start(api::Version::V1).await;
+
+    let client = Client::unauthenticated(&env.server_socket_addr().unwrap());
+
+    let response = client.add_subscription(software_subscription()).await;
+
+    assert_eq!(response.status, 401);
+
+    let response = client.get_subscriptions().await;
+
+    assert_eq!(response.status, 401);
+}
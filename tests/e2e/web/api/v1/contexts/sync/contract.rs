@@ -0,0 +1,32 @@
+//! API contract for `sync` context.
+
+use torrust_index::web::api;
+
+use crate::common::client::Client;
+use crate::common::http::Query;
+use crate::e2e::environment::TestEnv;
+
+#[tokio::test]
+async fn it_should_not_return_the_manifest_when_the_mirror_sync_is_not_enabled() {
+    let mut env = TestEnv::new();
+    env.start(api::Version::V1).await;
+
+    let client = Client::unauthenticated(&env.server_socket_addr().unwrap());
+
+    let response = client.get_sync_manifest().await;
+
+    assert_eq!(response.status, 403);
+    assert!(response.body.contains("The delta sync for mirrors is not enabled."));
+}
+
+#[tokio::test]
+async fn it_should_not_return_the_partition_changes_when_the_mirror_sync_is_not_enabled() {
+    let mut env = TestEnv::new();
+    env.start(api::Version::V1).await;
+
+    let client = Client::unauthenticated(&env.server_socket_addr().unwrap());
+
+    let response = client.get_sync_partition(0, Query::empty()).await;
+
+    assert_eq!(response.status, 403);
+}
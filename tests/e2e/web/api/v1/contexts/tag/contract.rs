@@ -6,7 +6,7 @@ use crate::common::asserts::assert_json_ok_response;
 use crate::common::client::Client;
 use crate::common::contexts::tag::asserts::{assert_added_tag_response, assert_deleted_tag_response};
 use crate::common::contexts::tag::fixtures::random_tag_name;
-use crate::common::contexts::tag::forms::{AddTagForm, DeleteTagForm};
+use crate::common::contexts::tag::forms::{AddTagForm, DeleteTagForm, MergeTagsForm};
 use crate::common::contexts::tag::responses::ListResponse;
 use crate::e2e::environment::TestEnv;
 use crate::e2e::web::api::v1::contexts::tag::steps::{add_random_tag, add_tag};
@@ -132,6 +132,33 @@ async fn it_should_allow_admins_to_delete_tags() {
     assert_deleted_tag_response(&response, tag_id);
 }
 
+#[tokio::test]
+async fn it_should_allow_admins_to_merge_tags() {
+    let mut env = TestEnv::new();
+    env.start(api::Version::V1).await;
+
+    let logged_in_admin = new_logged_in_admin(&env).await;
+    let client = Client::authenticated(&env.server_socket_addr().unwrap(), &logged_in_admin.token);
+
+    let (source_tag_id, _source_tag_name) = add_random_tag(&env).await;
+    let (target_tag_id, _target_tag_name) = add_random_tag(&env).await;
+
+    let response = client
+        .merge_tags(MergeTagsForm {
+            source_tag_id,
+            target_tag_id,
+        })
+        .await;
+
+    assert_json_ok_response(&response);
+
+    let response = client.get_tags().await;
+    let res: ListResponse = serde_json::from_str(&response.body).unwrap();
+
+    assert!(!res.data.iter().any(|tag| tag.tag_id == source_tag_id));
+    assert!(res.data.iter().any(|tag| tag.tag_id == target_tag_id));
+}
+
 mod authorization {
     mod for_guests {
         use torrust_index::web::api;
@@ -16,6 +16,8 @@ Get torrent info:
 
 mod for_guests {
 
+    use torrust_index::utils::base32;
+    use torrust_index::utils::hex::into_bytes;
     use torrust_index::utils::parse_torrent::decode_torrent;
     use torrust_index::web::api;
     use uuid::Uuid;
@@ -63,6 +65,56 @@ mod for_guests {
         assert!(response.is_json_and_ok());
     }
 
+    #[tokio::test]
+    async fn it_should_return_not_modified_when_the_torrent_listing_etag_matches() {
+        let mut env = TestEnv::new();
+        env.start(api::Version::V1).await;
+
+        let client = Client::unauthenticated(&env.server_socket_addr().unwrap());
+
+        let response = client.get_torrents(Query::empty()).await;
+
+        assert!(response.is_json_and_ok());
+
+        let etag = response.etag.expect("the torrent listing should include an ETag");
+
+        assert!(etag.starts_with("W/"));
+
+        let response = client.get_torrents_if_none_match(Query::empty(), &etag).await;
+
+        // Other tests running against the same server can change the listing
+        // in the meantime.
+        if response.status == 200 {
+            assert_ne!(response.etag, Some(etag));
+        } else {
+            assert_eq!(response.status, 304);
+            assert!(response.body.is_empty());
+        }
+    }
+
+    #[tokio::test]
+    async fn it_should_change_the_torrent_listing_etag_when_a_torrent_is_uploaded() {
+        let mut env = TestEnv::new();
+        env.start(api::Version::V1).await;
+
+        if !env.provides_a_tracker() {
+            println!("test skipped. It requires a tracker to be running.");
+            return;
+        }
+
+        let client = Client::unauthenticated(&env.server_socket_addr().unwrap());
+
+        let etag = client.get_torrents(Query::empty()).await.etag.unwrap();
+
+        let uploader = new_logged_in_user(&env).await;
+        let (_test_torrent, _indexed_torrent) = upload_random_torrent_to_index(&uploader, &env).await;
+
+        let response = client.get_torrents_if_none_match(Query::empty(), &etag).await;
+
+        assert!(response.is_json_and_ok());
+        assert_ne!(response.etag, Some(etag));
+    }
+
     #[tokio::test]
     async fn it_should_allow_to_limit_the_number_of_torrents_per_request() {
         let mut env = TestEnv::new();
@@ -173,6 +225,7 @@ mod for_guests {
                 md5sum: None, // DevSkim: ignore DS126858
             }],
             trackers: vec![tracker_url.clone().to_string()],
+            tracker_tiers: vec![vec![tracker_url.clone().to_string()]],
             magnet_link: format!(
                 // cspell:disable-next-line
                 "magnet:?xt=urn:btih:{}&dn={}&tr={}",
@@ -187,6 +240,7 @@ mod for_guests {
             created_by: test_torrent.file_info.created_by.clone(),
             encoding: test_torrent.file_info.encoding.clone(),
             canonical_info_hash_group: vec![test_torrent.file_info.info_hash.to_lowercase()],
+            bookmarked: false,
         };
 
         assert_expected_torrent_details(&torrent_details_response.data, &expected_torrent);
@@ -238,6 +292,54 @@ mod for_guests {
         );
     }
 
+    #[tokio::test]
+    async fn it_should_redirect_the_short_torrent_url_to_the_torrent_details() {
+        let mut env = TestEnv::new();
+        env.start(api::Version::V1).await;
+
+        if !env.provides_a_tracker() {
+            println!("test skipped. It requires a tracker to be running.");
+            return;
+        }
+
+        let client = Client::unauthenticated(&env.server_socket_addr().unwrap());
+
+        let uploader = new_logged_in_user(&env).await;
+        let (test_torrent, uploaded_torrent) = upload_random_torrent_to_index(&uploader, &env).await;
+
+        let short_info_hash = base32::encode(&into_bytes(&test_torrent.file_info_hash()).unwrap());
+
+        let response = client.get_torrent_by_short_url(&short_info_hash).await;
+
+        assert!(response.is_json_and_ok());
+
+        let torrent_details_response: TorrentDetailsResponse = serde_json::from_str(&response.body).unwrap();
+
+        assert_eq!(torrent_details_response.data.torrent_id, uploaded_torrent.torrent_id);
+    }
+
+    #[tokio::test]
+    async fn it_should_allow_guests_to_get_the_magnet_link_qr_code() {
+        let mut env = TestEnv::new();
+        env.start(api::Version::V1).await;
+
+        if !env.provides_a_tracker() {
+            println!("test skipped. It requires a tracker to be running.");
+            return;
+        }
+
+        let client = Client::unauthenticated(&env.server_socket_addr().unwrap());
+
+        let uploader = new_logged_in_user(&env).await;
+        let (test_torrent, _uploaded_torrent) = upload_random_torrent_to_index(&uploader, &env).await;
+
+        let response = client.get_magnet_link_qr_code(&test_torrent.file_info_hash()).await;
+
+        assert!(response.is_ok());
+        assert_eq!(response.content_type, Some("image/png".to_string()));
+        assert!(response.bytes.starts_with(b"\x89PNG"));
+    }
+
     mod it_should_allow_guests_to_download_a_torrent_file_searching_by_info_hash {
 
         use torrust_index::utils::parse_torrent::{calculate_info_hash, decode_torrent};
@@ -410,7 +512,7 @@ mod for_guests {
 
         use crate::common::client::Client;
         use crate::common::contexts::torrent::fixtures::random_torrent;
-        use crate::common::contexts::torrent::forms::{UpdateTorrentFrom, UploadTorrentMultipartForm};
+        use crate::common::contexts::torrent::forms::{AddMagnetForm, UpdateTorrentFrom, UploadTorrentMultipartForm};
         use crate::common::contexts::torrent::responses::TorrentListResponse;
         use crate::common::http::Query;
         use crate::e2e::environment::TestEnv;
@@ -433,6 +535,28 @@ mod for_guests {
             assert_eq!(response.status, 401);
         }
 
+        #[tokio::test]
+        async fn it_should_not_allow_guests_to_upload_magnet_links() {
+            let mut env = TestEnv::new();
+            env.start(api::Version::V1).await;
+
+            let client = Client::unauthenticated(&env.server_socket_addr().unwrap());
+
+            let test_torrent = random_torrent();
+
+            let response = client
+                .add_magnet(AddMagnetForm {
+                    magnet_uri: format!("magnet:?xt=urn:btih:{}", test_torrent.file_info_hash()),
+                    title: Some(test_torrent.index_info.title),
+                    description: None,
+                    category: test_torrent.index_info.category,
+                    tags: None,
+                })
+                .await;
+
+            assert_eq!(response.status, 401);
+        }
+
         #[tokio::test]
         async fn it_should_not_allow_guests_to_delete_torrents() {
             let mut env = TestEnv::new();
@@ -614,7 +738,7 @@ mod for_authenticated_users {
         use crate::common::asserts::assert_json_error_response;
         use crate::common::client::Client;
         use crate::common::contexts::torrent::fixtures::{random_torrent, TestTorrent};
-        use crate::common::contexts::torrent::forms::UploadTorrentMultipartForm;
+        use crate::common::contexts::torrent::forms::{AddMagnetForm, UploadTorrentMultipartForm};
         use crate::common::contexts::torrent::responses::UploadedTorrentResponse;
         use crate::e2e::environment::TestEnv;
         use crate::e2e::web::api::v1::contexts::user::steps::new_logged_in_user;
@@ -648,6 +772,30 @@ mod for_authenticated_users {
             assert!(response.is_json_and_ok());
         }
 
+        #[tokio::test]
+        async fn it_should_not_return_similar_torrents_when_the_detection_is_not_enabled() {
+            let mut env = TestEnv::new();
+            env.start(api::Version::V1).await;
+
+            if !env.provides_a_tracker() {
+                println!("test skipped. It requires a tracker to be running.");
+                return;
+            }
+
+            let uploader = new_logged_in_user(&env).await;
+            let client = Client::authenticated(&env.server_socket_addr().unwrap(), &uploader.token);
+
+            let form: UploadTorrentMultipartForm = random_torrent().index_info.into();
+
+            let response = client.upload_torrent(form.into()).await;
+
+            assert!(response.is_json_and_ok());
+
+            let res: serde_json::Value = serde_json::from_str(&response.body).unwrap();
+
+            assert_eq!(res["data"]["similar_torrents"], serde_json::json!([]));
+        }
+
         mod it_should_guard_that_torrent_metadata {
             use torrust_index::web::api;
 
@@ -811,6 +959,30 @@ mod for_authenticated_users {
             assert_eq!(response.status, 400);
         }
 
+        #[tokio::test]
+        async fn it_should_not_allow_uploading_magnet_links_when_magnet_uploads_are_disabled() {
+            let mut env = TestEnv::new();
+            env.start(api::Version::V1).await;
+
+            let uploader = new_logged_in_user(&env).await;
+            let client = Client::authenticated(&env.server_socket_addr().unwrap(), &uploader.token);
+
+            let test_torrent = random_torrent();
+
+            let response = client
+                .add_magnet(AddMagnetForm {
+                    magnet_uri: format!("magnet:?xt=urn:btih:{}", test_torrent.file_info_hash()),
+                    title: Some(test_torrent.index_info.title),
+                    description: None,
+                    category: test_torrent.index_info.category,
+                    tags: None,
+                })
+                .await;
+
+            assert!(response.body.contains("Magnet uploads are not enabled."));
+            assert_eq!(response.status, 403);
+        }
+
         #[tokio::test]
         async fn it_should_not_allow_uploading_a_torrent_with_a_title_that_already_exists() {
             let mut env = TestEnv::new();
@@ -1238,7 +1410,7 @@ mod and_torrent_owners {
     use torrust_index::web::api;
 
     use crate::common::client::Client;
-    use crate::common::contexts::torrent::forms::UpdateTorrentFrom;
+    use crate::common::contexts::torrent::forms::{UpdateTorrentFrom, UpdateTorrentTrackersForm};
     use crate::common::contexts::torrent::responses::UpdatedTorrentResponse;
     use crate::e2e::environment::TestEnv;
     use crate::e2e::web::api::v1::contexts::torrent::steps::upload_random_torrent_to_index;
@@ -1282,6 +1454,33 @@ mod and_torrent_owners {
         assert_eq!(torrent.description, new_description);
         assert!(response.is_json_and_ok());
     }
+
+    #[tokio::test]
+    async fn it_should_not_allow_torrent_owners_to_edit_the_announce_tiers() {
+        let mut env = TestEnv::new();
+        env.start(api::Version::V1).await;
+
+        if !env.provides_a_tracker() {
+            println!("test skipped. It requires a tracker to be running.");
+            return;
+        }
+
+        let uploader = new_logged_in_user(&env).await;
+        let (test_torrent, _uploaded_torrent) = upload_random_torrent_to_index(&uploader, &env).await;
+
+        let client = Client::authenticated(&env.server_socket_addr().unwrap(), &uploader.token);
+
+        let response = client
+            .update_torrent_trackers(
+                &test_torrent.file_info_hash(),
+                UpdateTorrentTrackersForm {
+                    tiers: vec![vec!["udp://tracker.example.com:6969/announce".to_string()]],
+                },
+            )
+            .await;
+
+        assert_eq!(response.status, 403);
+    }
 }
 
 mod and_admins {
@@ -1289,8 +1488,8 @@ mod and_admins {
     use torrust_index::web::api;
 
     use crate::common::client::Client;
-    use crate::common::contexts::torrent::forms::UpdateTorrentFrom;
-    use crate::common::contexts::torrent::responses::{DeletedTorrentResponse, UpdatedTorrentResponse};
+    use crate::common::contexts::torrent::forms::{UpdateTorrentFrom, UpdateTorrentTrackersForm};
+    use crate::common::contexts::torrent::responses::{DeletedTorrentResponse, TorrentDetailsResponse, UpdatedTorrentResponse};
     use crate::e2e::environment::TestEnv;
     use crate::e2e::web::api::v1::contexts::torrent::steps::upload_random_torrent_to_index;
     use crate::e2e::web::api::v1::contexts::user::steps::{new_logged_in_admin, new_logged_in_user};
@@ -1359,6 +1558,79 @@ mod and_admins {
         assert!(response.is_json_and_ok());
     }
 
+    #[tokio::test]
+    async fn it_should_allow_admins_to_edit_the_announce_tiers_of_a_torrent() {
+        let mut env = TestEnv::new();
+        env.start(api::Version::V1).await;
+
+        if !env.provides_a_tracker() {
+            println!("test skipped. It requires a tracker to be running.");
+            return;
+        }
+
+        let uploader = new_logged_in_user(&env).await;
+        let (test_torrent, _uploaded_torrent) = upload_random_torrent_to_index(&uploader, &env).await;
+
+        let logged_in_admin = new_logged_in_admin(&env).await;
+        let client = Client::authenticated(&env.server_socket_addr().unwrap(), &logged_in_admin.token);
+
+        let tiers = vec![
+            vec![
+                "udp://tracker-a.example.com:6969/announce".to_string(),
+                "udp://tracker-b.example.com:6969/announce".to_string(),
+            ],
+            vec!["https://backup.example.com/announce".to_string()],
+        ];
+
+        let response = client
+            .update_torrent_trackers(
+                &test_torrent.file_info_hash(),
+                UpdateTorrentTrackersForm { tiers: tiers.clone() },
+            )
+            .await;
+
+        assert!(response.is_json_and_ok());
+
+        let response = client.get_torrent(&test_torrent.file_info_hash()).await;
+
+        let torrent_details_response: TorrentDetailsResponse = serde_json::from_str(&response.body).unwrap();
+
+        let tracker_url = env.server_settings().unwrap().tracker.url.to_string();
+
+        let mut expected_tiers = vec![vec![tracker_url]];
+        expected_tiers.extend(tiers);
+
+        assert_eq!(torrent_details_response.data.tracker_tiers, expected_tiers);
+    }
+
+    #[tokio::test]
+    async fn it_should_reject_announce_tiers_with_unsupported_tracker_urls() {
+        let mut env = TestEnv::new();
+        env.start(api::Version::V1).await;
+
+        if !env.provides_a_tracker() {
+            println!("test skipped. It requires a tracker to be running.");
+            return;
+        }
+
+        let uploader = new_logged_in_user(&env).await;
+        let (test_torrent, _uploaded_torrent) = upload_random_torrent_to_index(&uploader, &env).await;
+
+        let logged_in_admin = new_logged_in_admin(&env).await;
+        let client = Client::authenticated(&env.server_socket_addr().unwrap(), &logged_in_admin.token);
+
+        let response = client
+            .update_torrent_trackers(
+                &test_torrent.file_info_hash(),
+                UpdateTorrentTrackersForm {
+                    tiers: vec![vec!["ftp://tracker.example.com/announce".to_string()]],
+                },
+            )
+            .await;
+
+        assert_eq!(response.status, 400);
+    }
+
     mod authorization {
         use torrust_index::web::api;
 
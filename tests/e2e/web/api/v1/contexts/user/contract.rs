@@ -178,6 +178,318 @@ mod authentication {
     }
 }
 
+mod profile {
+
+    use torrust_index::web::api;
+
+    use crate::common::client::Client;
+    use crate::common::contexts::user::forms::UpdateProfileForm;
+    use crate::common::contexts::user::responses::{ProfilePageResponse, ProfileResponse};
+    use crate::common::http::Query;
+    use crate::e2e::environment::TestEnv;
+    use crate::e2e::web::api::v1::contexts::torrent::steps::upload_random_torrent_to_index;
+    use crate::e2e::web::api::v1::contexts::user::steps::new_logged_in_user;
+
+    #[tokio::test]
+    async fn it_should_allow_users_to_update_their_bio_and_avatar() {
+        let mut env = TestEnv::new();
+        env.start(api::Version::V1).await;
+
+        let user = new_logged_in_user(&env).await;
+
+        let client = Client::authenticated(&env.server_socket_addr().unwrap(), &user.token);
+
+        let response = client
+            .update_user_profile(
+                &user.username,
+                UpdateProfileForm {
+                    bio: Some("Linux distributions".to_string()),
+                    avatar: Some("https://example.com/avatar.png".to_string()),
+                },
+            )
+            .await;
+
+        assert_eq!(response.status, 200);
+
+        let res: ProfileResponse = serde_json::from_str(&response.body).unwrap();
+
+        assert_eq!(res.data.bio, Some("Linux distributions".to_string()));
+        assert_eq!(res.data.avatar, Some("https://example.com/avatar.png".to_string()));
+
+        // Missing fields are not changed and empty fields are cleared
+        let response = client
+            .update_user_profile(
+                &user.username,
+                UpdateProfileForm {
+                    bio: Some(String::new()),
+                    avatar: None,
+                },
+            )
+            .await;
+
+        let res: ProfileResponse = serde_json::from_str(&response.body).unwrap();
+
+        assert_eq!(res.data.bio, None);
+        assert_eq!(res.data.avatar, Some("https://example.com/avatar.png".to_string()));
+    }
+
+    #[tokio::test]
+    async fn it_should_not_allow_users_to_update_other_users_profiles() {
+        let mut env = TestEnv::new();
+        env.start(api::Version::V1).await;
+
+        let user = new_logged_in_user(&env).await;
+        let other_user = new_logged_in_user(&env).await;
+
+        let client = Client::authenticated(&env.server_socket_addr().unwrap(), &user.token);
+
+        let response = client
+            .update_user_profile(
+                &other_user.username,
+                UpdateProfileForm {
+                    bio: Some("Hacked".to_string()),
+                    avatar: None,
+                },
+            )
+            .await;
+
+        assert_eq!(response.status, 403);
+    }
+
+    #[tokio::test]
+    async fn it_should_not_allow_avatars_that_are_not_http_urls() {
+        let mut env = TestEnv::new();
+        env.start(api::Version::V1).await;
+
+        let user = new_logged_in_user(&env).await;
+
+        let client = Client::authenticated(&env.server_socket_addr().unwrap(), &user.token);
+
+        let response = client
+            .update_user_profile(
+                &user.username,
+                UpdateProfileForm {
+                    bio: None,
+                    avatar: Some("javascript:alert(1)".to_string()),
+                },
+            )
+            .await;
+
+        assert_eq!(response.status, 400);
+    }
+
+    #[tokio::test]
+    async fn it_should_allow_guests_to_see_a_user_profile_with_the_uploaded_torrents() {
+        let mut env = TestEnv::new();
+        env.start(api::Version::V1).await;
+
+        if !env.provides_a_tracker() {
+            println!("test skipped. It requires a tracker to be running.");
+            return;
+        }
+
+        let user = new_logged_in_user(&env).await;
+        let (_test_torrent, uploaded_torrent) = upload_random_torrent_to_index(&user, &env).await;
+
+        let client = Client::authenticated(&env.server_socket_addr().unwrap(), &user.token);
+
+        client
+            .update_user_profile(
+                &user.username,
+                UpdateProfileForm {
+                    bio: None,
+                    avatar: Some("https://example.com/avatar.png".to_string()),
+                },
+            )
+            .await;
+
+        let guest = Client::unauthenticated(&env.server_socket_addr().unwrap());
+
+        let response = guest.get_user_profile(&user.username, Query::empty()).await;
+
+        assert_eq!(response.status, 200);
+
+        let res: ProfilePageResponse = serde_json::from_str(&response.body).unwrap();
+
+        assert_eq!(res.data.profile.username, user.username);
+        assert_eq!(res.data.profile.uploads, 1);
+        assert_eq!(
+            res.data.avatar_proxy_url,
+            Some("/v1/proxy/image/https%3A%2F%2Fexample.com%2Favatar.png".to_string())
+        );
+        assert_eq!(res.data.torrents.total, 1);
+        assert_eq!(res.data.torrents.results[0].torrent_id, uploaded_torrent.torrent_id);
+    }
+
+    #[tokio::test]
+    async fn it_should_return_a_not_found_response_for_unknown_users() {
+        let mut env = TestEnv::new();
+        env.start(api::Version::V1).await;
+
+        let guest = Client::unauthenticated(&env.server_socket_addr().unwrap());
+
+        let response = guest.get_user_profile("unknown-user", Query::empty()).await;
+
+        assert_eq!(response.status, 404);
+    }
+}
+
+mod email {
+
+    use torrust_index::web::api;
+
+    use crate::common::client::Client;
+    use crate::common::contexts::user::fixtures::DEFAULT_PASSWORD;
+    use crate::common::contexts::user::forms::{ChangeEmailForm, LoginForm};
+    use crate::e2e::environment::TestEnv;
+    use crate::e2e::web::api::v1::contexts::user::steps::{new_logged_in_user, new_registered_user};
+
+    #[tokio::test]
+    async fn it_should_not_resend_the_verification_email_with_a_wrong_password() {
+        let mut env = TestEnv::new();
+        env.start(api::Version::V1).await;
+
+        let client = Client::unauthenticated(&env.server_socket_addr().unwrap());
+
+        let registered_user = new_registered_user(&env).await;
+
+        let response = client
+            .resend_verification_email(LoginForm {
+                login: registered_user.username.clone(),
+                password: "wrong password".to_string(),
+            })
+            .await;
+
+        assert_eq!(response.status, 403);
+
+        let response = client
+            .resend_verification_email(LoginForm {
+                login: format!("{}_not_registered", registered_user.username),
+                password: registered_user.password,
+            })
+            .await;
+
+        assert_eq!(response.status, 403);
+    }
+
+    #[tokio::test]
+    async fn it_should_not_allow_guests_to_change_the_email() {
+        let mut env = TestEnv::new();
+        env.start(api::Version::V1).await;
+
+        let client = Client::unauthenticated(&env.server_socket_addr().unwrap());
+
+        let response = client
+            .change_email(ChangeEmailForm {
+                email: "new@email.com".to_string(),
+                current_password: DEFAULT_PASSWORD.to_string(),
+            })
+            .await;
+
+        assert_eq!(response.status, 401);
+    }
+
+    #[tokio::test]
+    async fn it_should_require_the_current_password_to_change_the_email() {
+        let mut env = TestEnv::new();
+        env.start(api::Version::V1).await;
+
+        let user = new_logged_in_user(&env).await;
+
+        let client = Client::authenticated(&env.server_socket_addr().unwrap(), &user.token);
+
+        let response = client
+            .change_email(ChangeEmailForm {
+                email: "new@email.com".to_string(),
+                current_password: "wrong password".to_string(),
+            })
+            .await;
+
+        assert_eq!(response.status, 403);
+    }
+
+    #[tokio::test]
+    async fn it_should_not_allow_changing_to_an_invalid_email() {
+        let mut env = TestEnv::new();
+        env.start(api::Version::V1).await;
+
+        let user = new_logged_in_user(&env).await;
+
+        let client = Client::authenticated(&env.server_socket_addr().unwrap(), &user.token);
+
+        let response = client
+            .change_email(ChangeEmailForm {
+                email: "not an email".to_string(),
+                current_password: DEFAULT_PASSWORD.to_string(),
+            })
+            .await;
+
+        assert_eq!(response.status, 400);
+    }
+
+    #[tokio::test]
+    async fn it_should_show_an_error_page_for_an_invalid_email_change_link() {
+        let mut env = TestEnv::new();
+        env.start(api::Version::V1).await;
+
+        let client = Client::unauthenticated(&env.server_socket_addr().unwrap());
+
+        let response = client.confirm_email_change("invalid-token").await;
+
+        assert_eq!(response.status, 200);
+        assert_eq!(response.body, "Invalid or expired email change link.");
+    }
+}
+
+mod api_usage {
+
+    use torrust_index::web::api;
+
+    use crate::common::client::Client;
+    use crate::common::contexts::user::responses::ApiUsageResponse;
+    use crate::e2e::environment::TestEnv;
+    use crate::e2e::web::api::v1::contexts::user::steps::new_logged_in_user;
+
+    #[tokio::test]
+    async fn it_should_count_the_requests_made_with_the_current_token() {
+        let mut env = TestEnv::new();
+        env.start(api::Version::V1).await;
+
+        let user = new_logged_in_user(&env).await;
+
+        let client = Client::authenticated(&env.server_socket_addr().unwrap(), &user.token);
+
+        client.get_api_usage().await;
+
+        let response = client.get_api_usage().await;
+
+        assert_eq!(response.status, 200);
+
+        let res: ApiUsageResponse = serde_json::from_str(&response.body).unwrap();
+
+        assert_eq!(res.data.credentials.len(), 1);
+        assert!(res.data.credentials[0].current);
+        assert!(res.data.credentials[0].requests >= 2);
+        assert!(!res.data.credentials[0].credential.contains(&user.token));
+        assert_eq!(
+            res.data.daily.iter().map(|day| day.requests).sum::<i64>(),
+            res.data.credentials[0].requests
+        );
+    }
+
+    #[tokio::test]
+    async fn it_should_not_allow_guests_to_get_the_api_usage() {
+        let mut env = TestEnv::new();
+        env.start(api::Version::V1).await;
+
+        let client = Client::unauthenticated(&env.server_socket_addr().unwrap());
+
+        let response = client.get_api_usage().await;
+
+        assert_eq!(response.status, 401);
+    }
+}
+
 mod banned_user_list {
 
     use torrust_index::web::api;
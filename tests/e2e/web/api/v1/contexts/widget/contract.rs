@@ -0,0 +1,86 @@
+//! API contract for `widget` context.
+
+use serde_json::Value;
+use torrust_index::web::api;
+
+use crate::common::client::Client;
+use crate::common::http::{Query, QueryParam};
+use crate::e2e::environment::TestEnv;
+use crate::e2e::web::api::v1::contexts::torrent::steps::upload_random_torrent_to_index;
+use crate::e2e::web::api::v1::contexts::user::steps::new_logged_in_user;
+
+#[tokio::test]
+async fn it_should_allow_guests_to_get_the_latest_torrents_as_json() {
+    let mut env = TestEnv::new();
+    env.start(api::Version::V1).await;
+
+    if !env.provides_a_tracker() {
+        println!("test skipped. It requires a tracker to be running.");
+        return;
+    }
+
+    let uploader = new_logged_in_user(&env).await;
+    let (_test_torrent, _uploaded_torrent) = upload_random_torrent_to_index(&uploader, &env).await;
+
+    let client = Client::unauthenticated(&env.server_socket_addr().unwrap());
+
+    let response = client
+        .get_widget_torrents(Query::with_params(vec![QueryParam::new("limit", "1")]))
+        .await;
+
+    assert!(response.is_json_and_ok());
+
+    let res: Value = serde_json::from_str(&response.body).unwrap();
+    let torrents = res["data"].as_array().unwrap();
+
+    assert_eq!(torrents.len(), 1);
+    assert!(torrents[0]["url"].as_str().unwrap().contains("/t/"));
+}
+
+#[tokio::test]
+async fn it_should_wrap_the_json_in_the_jsonp_callback() {
+    let mut env = TestEnv::new();
+    env.start(api::Version::V1).await;
+
+    let client = Client::unauthenticated(&env.server_socket_addr().unwrap());
+
+    let response = client
+        .get_widget_torrents(Query::with_params(vec![QueryParam::new("callback", "renderTorrents")]))
+        .await;
+
+    assert_eq!(response.status, 200);
+    assert_eq!(
+        response.content_type,
+        Some("application/javascript; charset=utf-8".to_string())
+    );
+    assert!(response.body.starts_with("/**/renderTorrents({"));
+    assert!(response.body.ends_with("});"));
+}
+
+#[tokio::test]
+async fn it_should_reject_jsonp_callbacks_that_are_not_function_names() {
+    let mut env = TestEnv::new();
+    env.start(api::Version::V1).await;
+
+    let client = Client::unauthenticated(&env.server_socket_addr().unwrap());
+
+    let response = client
+        .get_widget_torrents(Query::with_params(vec![QueryParam::new("callback", "alert(1)//")]))
+        .await;
+
+    assert_eq!(response.status, 400);
+}
+
+#[tokio::test]
+async fn it_should_allow_guests_to_get_the_latest_torrents_as_html() {
+    let mut env = TestEnv::new();
+    env.start(api::Version::V1).await;
+
+    let client = Client::unauthenticated(&env.server_socket_addr().unwrap());
+
+    let response = client.get_widget_torrents_html(Query::empty()).await;
+
+    assert_eq!(response.status, 200);
+    assert_eq!(response.content_type, Some("text/html; charset=utf-8".to_string()));
+    assert!(response.body.contains("<ul"));
+}
@@ -1,6 +1,6 @@
 use std::net::SocketAddr;
 
-use tokio::sync::{oneshot, RwLock};
+use tokio::sync::oneshot;
 use tokio::task::JoinHandle;
 use torrust_index::config::Configuration;
 use torrust_index::web::api::Version;
@@ -31,16 +31,14 @@ impl AppStarter {
     ///
     /// Will panic if the app was dropped after spawning it.
     pub async fn start(&mut self, api_version: Version) {
-        let configuration = Configuration {
-            settings: RwLock::new(self.configuration.clone()),
-        };
+        let configuration = Configuration::new(self.configuration.clone());
 
         // Open a channel to communicate back with this function
         let (tx, rx) = oneshot::channel::<AppStartedMessage>();
 
         // Launch the app in a separate task
         let app_handle = tokio::spawn(async move {
-            let app = app::run(configuration, &api_version).await;
+            let app = app::run(configuration, &api_version).await.expect("the app should start");
 
             info!("Application started. API server listening on {}", app.api_socket_addr);
 
@@ -1,4 +1,5 @@
 mod common;
+mod drivers;
 mod e2e;
 pub mod environments;
 mod upgrades;